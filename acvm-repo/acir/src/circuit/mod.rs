@@ -38,17 +38,91 @@ pub struct Circuit {
     // Note: This should be a BTreeMap, but serde-reflect is creating invalid
     // c++ code at the moment when it is, due to OpcodeLocation needing a comparison
     // implementation which is never generated.
-    pub assert_messages: Vec<(OpcodeLocation, String)>,
+    pub assert_messages: Vec<(OpcodeLocation, AssertionPayload)>,
 }
 
 impl Circuit {
     /// Returns the assert message associated with the provided [`OpcodeLocation`].
     /// Returns `None` if no such assert message exists.
-    pub fn get_assert_message(&self, opcode_location: OpcodeLocation) -> Option<&str> {
+    pub fn get_assert_message(&self, opcode_location: OpcodeLocation) -> Option<&AssertionPayload> {
         self.assert_messages
             .iter()
             .find(|(loc, _)| *loc == opcode_location)
-            .map(|(_, message)| message.as_str())
+            .map(|(_, payload)| payload)
+    }
+}
+
+/// The foreign call made by Brillig code right before trapping on a failed assertion whose message
+/// has values formatted into it. It is passed these values, in the order they appear in the
+/// message, and returns nothing.
+pub const ASSERT_MESSAGE_VALUES_ORACLE: &str = "__assert_message_values";
+
+/// The message of a failed assertion, along with where to find the values formatted into it.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct AssertionPayload {
+    /// The message of the assertion, which is a format string if `printable_type` is given.
+    pub message: String,
+    /// The witnesses holding the values formatted into the message, in order. Brillig code has no
+    /// witnesses to refer to, so it passes the values to the [`ASSERT_MESSAGE_VALUES_ORACLE`]
+    /// instead.
+    pub witnesses: Vec<Witness>,
+    /// The serialized type of the format string, which describes how its values are decoded.
+    /// The ACVM itself doesn't interpret it.
+    pub printable_type: Option<String>,
+}
+
+impl From<String> for AssertionPayload {
+    fn from(message: String) -> Self {
+        AssertionPayload { message, witnesses: Vec::new(), printable_type: None }
+    }
+}
+
+// Backends read the assertion messages of a circuit as plain strings, so a payload is serialized
+// as its message alone unless values are formatted into it. In that case the message is followed
+// by the type of the format string and the indices of the witnesses, each preceded by a NUL
+// character. Neither the serialized type nor the indices can contain one.
+impl Serialize for AssertionPayload {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if self.printable_type.is_none()
+            && self.witnesses.is_empty()
+            && !self.message.contains('\0')
+        {
+            return serializer.serialize_str(&self.message);
+        }
+        let witnesses: Vec<_> =
+            self.witnesses.iter().map(|witness| witness.witness_index().to_string()).collect();
+        let printable_type = self.printable_type.as_deref().unwrap_or_default();
+        serializer.serialize_str(&format!(
+            "{}\0{printable_type}\0{}",
+            self.message,
+            witnesses.join(",")
+        ))
+    }
+}
+
+impl<'de> Deserialize<'de> for AssertionPayload {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let serialized = String::deserialize(deserializer)?;
+        let Some((rest, witnesses)) = serialized.rsplit_once('\0') else {
+            return Ok(serialized.into());
+        };
+        let (message, printable_type) = rest
+            .rsplit_once('\0')
+            .ok_or_else(|| D::Error::custom("assertion payload is missing its printable type"))?;
+        let witnesses = witnesses
+            .split(',')
+            .filter(|index| !index.is_empty())
+            .map(|index| index.parse().map(Witness))
+            .collect::<Result<_, _>>()
+            .map_err(D::Error::custom)?;
+        let printable_type = (!printable_type.is_empty()).then(|| printable_type.to_owned());
+        Ok(AssertionPayload { message: message.to_owned(), witnesses, printable_type })
     }
 }
 
@@ -174,6 +248,38 @@ impl Circuit {
         let circuit = Self::deserialize_circuit(&circuit_bytes).map_err(D::Error::custom)?;
         Ok(circuit)
     }
+
+    // Serialize each circuit of a list to its base64 encoding
+    pub fn serialize_circuits_base64<S>(circuits: &[Circuit], s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let encoded_circuits: Vec<String> = circuits
+            .iter()
+            .map(|circuit| {
+                base64::engine::general_purpose::STANDARD
+                    .encode(Circuit::serialize_circuit(circuit))
+            })
+            .collect();
+        encoded_circuits.serialize(s)
+    }
+
+    // Deserialize and base64 decode each circuit of a list
+    pub fn deserialize_circuits_base64<'de, D>(deserializer: D) -> Result<Vec<Circuit>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let encoded_circuits: Vec<String> = serde::Deserialize::deserialize(deserializer)?;
+        encoded_circuits
+            .into_iter()
+            .map(|bytecode_b64| {
+                let circuit_bytes = base64::engine::general_purpose::STANDARD
+                    .decode(bytecode_b64)
+                    .map_err(D::Error::custom)?;
+                Self::deserialize_circuit(&circuit_bytes).map_err(D::Error::custom)
+            })
+            .collect()
+    }
 }
 
 impl std::fmt::Display for Circuit {
@@ -233,7 +339,7 @@ mod tests {
 
     use super::{
         opcodes::{BlackBoxFuncCall, FunctionInput},
-        Circuit, Compression, Opcode, PublicInputs,
+        AssertionPayload, Circuit, Compression, Opcode, PublicInputs,
     };
     use crate::native_types::Witness;
     use acir_field::FieldElement;
@@ -308,6 +414,18 @@ mod tests {
             ],
         })
     }
+    fn poseidon2_permutation_opcode() -> Opcode {
+        Opcode::BlackBoxFuncCall(BlackBoxFuncCall::Poseidon2Permutation {
+            inputs: vec![
+                FunctionInput { witness: Witness(1), num_bits: FieldElement::max_num_bits() },
+                FunctionInput { witness: Witness(2), num_bits: FieldElement::max_num_bits() },
+                FunctionInput { witness: Witness(3), num_bits: FieldElement::max_num_bits() },
+                FunctionInput { witness: Witness(4), num_bits: FieldElement::max_num_bits() },
+            ],
+            outputs: vec![Witness(5), Witness(6), Witness(7), Witness(8)],
+            len: 4,
+        })
+    }
 
     #[test]
     fn serialization_roundtrip() {
@@ -343,6 +461,7 @@ mod tests {
                 range_opcode(),
                 and_opcode(),
                 keccakf1600_opcode(),
+                poseidon2_permutation_opcode(),
             ],
             private_parameters: BTreeSet::new(),
             public_parameters: PublicInputs(BTreeSet::from_iter(vec![Witness(2)])),
@@ -372,4 +491,26 @@ mod tests {
         let deserialization_result = Circuit::deserialize_circuit(&zipped_bad_circuit);
         assert!(deserialization_result.is_err());
     }
+
+    #[test]
+    fn serializes_assertion_payloads_as_strings() {
+        let message = "x must be less than y".to_string();
+        let payload = AssertionPayload::from(message.clone());
+        assert_eq!(bincode::serialize(&payload).unwrap(), bincode::serialize(&message).unwrap());
+
+        let payloads = [
+            payload,
+            AssertionPayload {
+                message: "expected {x} to be less than {y}".to_string(),
+                witnesses: vec![Witness(1), Witness(12)],
+                printable_type: Some(r#"{"kind":"fmtstring"}"#.to_string()),
+            },
+            AssertionPayload::from("a message with a \0 character".to_string()),
+        ];
+        for payload in payloads {
+            let serialized = bincode::serialize(&payload).unwrap();
+            let deserialized: AssertionPayload = bincode::deserialize(&serialized).unwrap();
+            assert_eq!(deserialized, payload);
+        }
+    }
 }
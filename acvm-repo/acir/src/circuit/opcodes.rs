@@ -28,6 +28,20 @@ pub enum Opcode {
         block_id: BlockId,
         init: Vec<Witness>,
     },
+    /// Calls another ACIR function of the program, constraining the `outputs` to be the values
+    /// the function returns for the `inputs`.
+    Call {
+        /// The index of the called function among the functions called by the program, which
+        /// do not include the entry point
+        id: u32,
+        /// The witnesses passed to the parameters of the called function, in order
+        inputs: Vec<Witness>,
+        /// The witnesses receiving the return values of the called function, in order
+        outputs: Vec<Witness>,
+        /// Predicate of the call - indicates if it should be skipped, in which case the outputs
+        /// are zero
+        predicate: Option<Expression>,
+    },
 }
 
 impl std::fmt::Display for Opcode {
@@ -99,6 +113,13 @@ impl std::fmt::Display for Opcode {
                 write!(f, "INIT ")?;
                 write!(f, "(id: {}, len: {}) ", block_id.0, init.len())
             }
+            Opcode::Call { id, inputs, outputs, predicate } => {
+                write!(f, "CALL func {id}: ")?;
+                if let Some(pred) = predicate {
+                    writeln!(f, "PREDICATE = {pred}")?;
+                }
+                write!(f, "inputs: {inputs:?}, outputs: {outputs:?}")
+            }
         }
     }
 }
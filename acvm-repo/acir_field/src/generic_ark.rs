@@ -232,8 +232,12 @@ impl<F: PrimeField> FieldElement<F> {
     }
 
     pub fn to_u128(self) -> u128 {
+        // Fields smaller than 128 bits serialize to fewer than 16 bytes
         let bytes = self.to_be_bytes();
-        u128::from_be_bytes(bytes[16..32].try_into().unwrap())
+        let len = bytes.len().min(16);
+        let mut u128_bytes = [0u8; 16];
+        u128_bytes[16 - len..].copy_from_slice(&bytes[bytes.len() - len..]);
+        u128::from_be_bytes(u128_bytes)
     }
 
     pub fn try_into_u128(self) -> Option<u128> {
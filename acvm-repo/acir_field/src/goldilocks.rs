@@ -0,0 +1,28 @@
+//! The Goldilocks field of order `2^64 - 2^32 + 1`, used by proving systems such as Plonky2.
+//!
+//! Its elements can't hold every 64 bit integer, so the compiler rejects the integer types whose
+//! arithmetic could exceed the field.
+use ark_ff::{Fp64, MontBackend, MontConfig};
+
+#[derive(MontConfig)]
+#[modulus = "18446744069414584321"]
+#[generator = "7"]
+pub struct FrConfig;
+
+pub type Fr = Fp64<MontBackend<FrConfig, 1>>;
+
+#[cfg(test)]
+mod tests {
+    use crate::FieldElement;
+
+    #[test]
+    fn goldilocks_modulus() {
+        assert_eq!(FieldElement::max_num_bits(), 64);
+        assert_eq!(FieldElement::max_num_bytes(), 8);
+
+        let modulus = u64::MAX as u128 - u32::MAX as u128 + 1;
+        assert_eq!(FieldElement::modulus(), modulus.into());
+        assert_eq!(FieldElement::from(modulus + 4).to_u128(), 4);
+        assert_eq!(FieldElement::from(u64::MAX as u128).to_u128(), u32::MAX as u128 - 1);
+    }
+}
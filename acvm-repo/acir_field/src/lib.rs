@@ -16,6 +16,11 @@ cfg_if::cfg_if! {
         mod generic_ark;
         pub type FieldElement = generic_ark::FieldElement<ark_bls12_381::Fr>;
         pub const CHOSEN_FIELD : FieldOptions = FieldOptions::BLS12_381;
+    } else if #[cfg(feature = "goldilocks")] {
+        mod generic_ark;
+        pub mod goldilocks;
+        pub type FieldElement = generic_ark::FieldElement<goldilocks::Fr>;
+        pub const CHOSEN_FIELD : FieldOptions = FieldOptions::Goldilocks;
     } else {
         compile_error!("please specify a field to compile with");
     }
@@ -25,6 +30,7 @@ cfg_if::cfg_if! {
 pub enum FieldOptions {
     BN254,
     BLS12_381,
+    Goldilocks,
 }
 
 impl FieldOptions {
@@ -32,6 +38,7 @@ impl FieldOptions {
         match self {
             FieldOptions::BN254 => "bn254",
             FieldOptions::BLS12_381 => "bls12_381",
+            FieldOptions::Goldilocks => "goldilocks",
         }
     }
 
@@ -63,4 +70,4 @@ macro_rules! assert_unique_feature {
 }
 // https://internals.rust-lang.org/t/mutually-exclusive-feature-flags/8601/7
 // If another field/feature is added, we add it here too
-assert_unique_feature!("bn254", "bls12_381");
+assert_unique_feature!("bn254", "bls12_381", "goldilocks");
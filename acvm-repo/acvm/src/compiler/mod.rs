@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use acir::circuit::{Circuit, OpcodeLocation};
+use acir::circuit::{AssertionPayload, Circuit, OpcodeLocation};
 
 use crate::ExpressionWidth;
 
@@ -56,9 +56,9 @@ impl AcirTransformationMap {
 }
 
 fn transform_assert_messages(
-    assert_messages: Vec<(OpcodeLocation, String)>,
+    assert_messages: Vec<(OpcodeLocation, AssertionPayload)>,
     map: &AcirTransformationMap,
-) -> Vec<(OpcodeLocation, String)> {
+) -> Vec<(OpcodeLocation, AssertionPayload)> {
     assert_messages
         .into_iter()
         .flat_map(|(location, message)| {
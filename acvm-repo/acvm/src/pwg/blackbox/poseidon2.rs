@@ -0,0 +1,29 @@
+use acir::{
+    circuit::opcodes::FunctionInput,
+    native_types::{Witness, WitnessMap},
+};
+
+use crate::{
+    pwg::{insert_value, witness_to_value, OpcodeResolutionError},
+    BlackBoxFunctionSolver,
+};
+
+pub(super) fn poseidon2_permutation(
+    backend: &impl BlackBoxFunctionSolver,
+    initial_witness: &mut WitnessMap,
+    inputs: &[FunctionInput],
+    outputs: &[Witness],
+    len: u32,
+) -> Result<(), OpcodeResolutionError> {
+    let state: Result<Vec<_>, _> =
+        inputs.iter().map(|input| witness_to_value(initial_witness, input.witness)).collect();
+    let state: Vec<_> = state?.into_iter().cloned().collect();
+
+    let new_state = backend.poseidon2_permutation(&state, len)?;
+
+    for (output_witness, value) in outputs.iter().zip(new_state) {
+        insert_value(output_witness, value, initial_witness)?;
+    }
+
+    Ok(())
+}
@@ -142,6 +142,14 @@ impl<'b, B: BlackBoxFunctionSolver> BrilligSolver<'b, B> {
         self.vm.program_counter()
     }
 
+    pub(super) fn enable_profiling(&mut self) {
+        self.vm.enable_profiling();
+    }
+
+    pub(super) fn opcode_counts(&self) -> Option<&[usize]> {
+        self.vm.opcode_counts()
+    }
+
     fn handle_vm_status(
         &self,
         vm_status: VMStatus,
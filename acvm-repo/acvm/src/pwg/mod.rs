@@ -1,6 +1,6 @@
 // Re-usable methods that backends can use to implement their PWG
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use acir::{
     brillig::ForeignCallResult,
@@ -46,6 +46,13 @@ pub enum ACVMStatus {
     ///
     /// Once this is done, the ACVM can be restarted to solve the remaining opcodes.
     RequiresForeignCall(ForeignCallWaitInfo),
+
+    /// The ACVM has encountered a [call][Opcode::Call] to another ACIR function, which must be
+    /// executed separately. The values returned by the function must be passed back to the ACVM
+    /// using [`ACVM::resolve_pending_acir_call`].
+    ///
+    /// Once this is done, the ACVM can be restarted to solve the remaining opcodes.
+    RequiresAcirCall(AcirCallWaitInfo),
 }
 
 impl std::fmt::Display for ACVMStatus {
@@ -55,10 +62,20 @@ impl std::fmt::Display for ACVMStatus {
             ACVMStatus::InProgress => write!(f, "In progress"),
             ACVMStatus::Failure(_) => write!(f, "Execution failure"),
             ACVMStatus::RequiresForeignCall(_) => write!(f, "Waiting on foreign call"),
+            ACVMStatus::RequiresAcirCall(_) => write!(f, "Waiting on ACIR call"),
         }
     }
 }
 
+/// The function called by an [`Opcode::Call`], along with the values of its inputs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AcirCallWaitInfo {
+    /// The index of the called function among the functions called by the program
+    pub id: u32,
+    /// The values passed to the parameters of the called function
+    pub inputs: Vec<FieldElement>,
+}
+
 pub enum StepResult<'a, B: BlackBoxFunctionSolver> {
     Status(ACVMStatus),
     IntoBrillig(BrilligSolver<'a, B>),
@@ -112,6 +129,8 @@ pub enum OpcodeResolutionError {
     BlackBoxFunctionFailed(BlackBoxFunc, String),
     #[error("Failed to solve brillig function, reason: {message}")]
     BrilligFunctionFailed { message: String, call_stack: Vec<OpcodeLocation> },
+    #[error("ACIR call returned {results_size} values, but the call has {outputs_size} outputs")]
+    AcirCallOutputsMismatch { opcode_location: ErrorLocation, results_size: u32, outputs_size: u32 },
 }
 
 impl From<BlackBoxResolutionError> for OpcodeResolutionError {
@@ -140,6 +159,13 @@ pub struct ACVM<'a, B: BlackBoxFunctionSolver> {
     witness_map: WitnessMap,
 
     brillig_solver: Option<BrilligSolver<'a, B>>,
+
+    /// The number of times each opcode of each completed Brillig call was executed, keyed by the
+    /// index of the call's [`Opcode::Brillig`], if profiling is enabled.
+    brillig_opcode_counts: Option<BTreeMap<usize, Vec<usize>>>,
+
+    /// The values returned by the pending [`Opcode::Call`], once they have been resolved.
+    acir_call_results: Option<Vec<FieldElement>>,
 }
 
 impl<'a, B: BlackBoxFunctionSolver> ACVM<'a, B> {
@@ -153,9 +179,24 @@ impl<'a, B: BlackBoxFunctionSolver> ACVM<'a, B> {
             instruction_pointer: 0,
             witness_map: initial_witness,
             brillig_solver: None,
+            brillig_opcode_counts: None,
+            acir_call_results: None,
         }
     }
 
+    /// Enables counting the number of times each Brillig opcode is executed.
+    ///
+    /// The counts can be read with [`ACVM::brillig_opcode_counts`] once execution has completed.
+    pub fn enable_brillig_profiling(&mut self) {
+        self.brillig_opcode_counts = Some(BTreeMap::new());
+    }
+
+    /// Returns the number of times each opcode of each completed Brillig call was executed,
+    /// keyed by the index of the call's [`Opcode::Brillig`], if profiling is enabled.
+    pub fn brillig_opcode_counts(&self) -> Option<&BTreeMap<usize, Vec<usize>>> {
+        self.brillig_opcode_counts.as_ref()
+    }
+
     /// Returns a reference to the current state of the ACVM's [`WitnessMap`].
     ///
     /// Once execution has completed, the witness map can be extracted using [`ACVM::finalize`]
@@ -236,12 +277,43 @@ impl<'a, B: BlackBoxFunctionSolver> ACVM<'a, B> {
         self.status(ACVMStatus::InProgress);
     }
 
+    /// Sets the status of the VM to `RequiresAcirCall`.
+    /// Indicating that the VM is now waiting for an ACIR call to be resolved.
+    fn wait_for_acir_call(&mut self, acir_call: AcirCallWaitInfo) -> ACVMStatus {
+        self.status(ACVMStatus::RequiresAcirCall(acir_call))
+    }
+
+    /// Return a reference to the function and inputs of the pending ACIR call, if one exists.
+    pub fn get_pending_acir_call(&self) -> Option<&AcirCallWaitInfo> {
+        if let ACVMStatus::RequiresAcirCall(acir_call) = &self.status {
+            Some(acir_call)
+        } else {
+            None
+        }
+    }
+
+    /// Resolves an ACIR call with the values returned by the called function, which has been
+    /// executed outside of this ACVM.
+    ///
+    /// The ACVM can then be restarted to assign the results to the outputs of the call and to
+    /// solve the remaining ACIR opcodes.
+    pub fn resolve_pending_acir_call(&mut self, call_results: Vec<FieldElement>) {
+        if !matches!(self.status, ACVMStatus::RequiresAcirCall(_)) {
+            panic!("ACVM is not expecting an ACIR call response as no call was made");
+        }
+        self.acir_call_results = Some(call_results);
+
+        // Now that the ACIR call has been resolved then we can resume execution.
+        self.status(ACVMStatus::InProgress);
+    }
+
     /// Executes the ACVM's circuit until execution halts.
     ///
-    /// Execution can halt due to three reasons:
+    /// Execution can halt due to four reasons:
     /// 1. All opcodes have been executed successfully.
     /// 2. The circuit has been found to be unsatisfiable.
-    /// 2. A Brillig [foreign call][`ForeignCallWaitInfo`] has been encountered and must be resolved.
+    /// 3. A Brillig [foreign call][`ForeignCallWaitInfo`] has been encountered and must be resolved.
+    /// 4. A [call][`AcirCallWaitInfo`] to another ACIR function has been encountered and must be resolved.
     pub fn solve(&mut self) -> ACVMStatus {
         while self.status == ACVMStatus::InProgress {
             self.solve_opcode();
@@ -270,6 +342,10 @@ impl<'a, B: BlackBoxFunctionSolver> ACVM<'a, B> {
                 Ok(Some(foreign_call)) => return self.wait_for_foreign_call(foreign_call),
                 res => res.map(|_| ()),
             },
+            Opcode::Call { .. } => match self.solve_call_opcode() {
+                Ok(Some(acir_call)) => return self.wait_for_acir_call(acir_call),
+                res => res.map(|_| ()),
+            },
         };
         self.handle_opcode_resolution(resolution)
     }
@@ -298,6 +374,10 @@ impl<'a, B: BlackBoxFunctionSolver> ACVM<'a, B> {
                     }
                     | OpcodeResolutionError::UnsatisfiedConstrain {
                         opcode_location: opcode_index,
+                    }
+                    | OpcodeResolutionError::AcirCallOutputsMismatch {
+                        opcode_location: opcode_index,
+                        ..
                     } => {
                         *opcode_index = ErrorLocation::Resolved(OpcodeLocation::Acir(
                             self.instruction_pointer(),
@@ -327,7 +407,14 @@ impl<'a, B: BlackBoxFunctionSolver> ACVM<'a, B> {
         // there will be a cached `BrilligSolver` to avoid recomputation.
         let mut solver: BrilligSolver<'_, B> = match self.brillig_solver.take() {
             Some(solver) => solver,
-            None => BrilligSolver::new(witness, brillig, self.backend, self.instruction_pointer)?,
+            None => {
+                let mut solver =
+                    BrilligSolver::new(witness, brillig, self.backend, self.instruction_pointer)?;
+                if self.brillig_opcode_counts.is_some() {
+                    solver.enable_profiling();
+                }
+                solver
+            }
         };
         match solver.solve()? {
             BrilligSolverStatus::ForeignCallWait(foreign_call) => {
@@ -339,6 +426,11 @@ impl<'a, B: BlackBoxFunctionSolver> ACVM<'a, B> {
                 unreachable!("Brillig solver still in progress")
             }
             BrilligSolverStatus::Finished => {
+                if let (Some(counts), Some(opcode_counts)) =
+                    (&mut self.brillig_opcode_counts, solver.opcode_counts())
+                {
+                    counts.insert(self.instruction_pointer, opcode_counts.to_vec());
+                }
                 // Write execution outputs
                 solver.finalize(witness, brillig)?;
                 Ok(None)
@@ -346,6 +438,46 @@ impl<'a, B: BlackBoxFunctionSolver> ACVM<'a, B> {
         }
     }
 
+    fn solve_call_opcode(&mut self) -> Result<Option<AcirCallWaitInfo>, OpcodeResolutionError> {
+        let Opcode::Call { id, inputs, outputs, predicate } =
+            &self.opcodes[self.instruction_pointer]
+        else {
+            unreachable!("Not executing a Call opcode");
+        };
+
+        // If we're resuming execution after resolving the call then its results are available.
+        if let Some(call_results) = self.acir_call_results.take() {
+            if call_results.len() != outputs.len() {
+                return Err(OpcodeResolutionError::AcirCallOutputsMismatch {
+                    opcode_location: ErrorLocation::Unresolved,
+                    results_size: call_results.len() as u32,
+                    outputs_size: outputs.len() as u32,
+                });
+            }
+            for (output, value) in outputs.iter().zip(call_results) {
+                insert_value(output, value, &mut self.witness_map)?;
+            }
+            return Ok(None);
+        }
+
+        let skip = match predicate {
+            Some(predicate) => get_value(predicate, &self.witness_map)?.is_zero(),
+            None => false,
+        };
+        if skip {
+            for output in outputs {
+                insert_value(output, FieldElement::zero(), &mut self.witness_map)?;
+            }
+            return Ok(None);
+        }
+
+        let inputs = inputs
+            .iter()
+            .map(|input| witness_to_value(&self.witness_map, *input).copied())
+            .collect::<Result<_, _>>()?;
+        Ok(Some(AcirCallWaitInfo { id: *id, inputs }))
+    }
+
     pub fn step_into_brillig_opcode(&mut self) -> StepResult<'a, B> {
         let Opcode::Brillig(brillig) = &self.opcodes[self.instruction_pointer] else {
             return StepResult::Status(self.solve_opcode());
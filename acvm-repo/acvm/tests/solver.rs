@@ -609,3 +609,43 @@ fn memory_operations() {
 
     assert_eq!(witness_map[&Witness(8)], FieldElement::from(6u128));
 }
+
+#[test]
+fn acir_call_operations() {
+    let initial_witness = WitnessMap::from(BTreeMap::from_iter([
+        (Witness(1), FieldElement::from(2u128)),
+        (Witness(2), FieldElement::from(3u128)),
+        (Witness(3), FieldElement::one()),
+        (Witness(4), FieldElement::zero()),
+    ]));
+
+    let call = |predicate: Witness, output: Witness| Opcode::Call {
+        id: 0,
+        inputs: vec![Witness(1), Witness(2)],
+        outputs: vec![output],
+        predicate: Some(predicate.into()),
+    };
+    let opcodes = vec![call(Witness(3), Witness(5)), call(Witness(4), Witness(6))];
+
+    let mut acvm = ACVM::new(&StubbedBlackBoxSolver, &opcodes, initial_witness);
+    let solver_status = acvm.solve();
+    assert!(
+        matches!(solver_status, ACVMStatus::RequiresAcirCall(_)),
+        "should require ACIR call response"
+    );
+    assert_eq!(acvm.instruction_pointer(), 0, "should stall on the call");
+
+    let acir_call = acvm.get_pending_acir_call().expect("should have an ACIR call request");
+    assert_eq!(acir_call.id, 0);
+    assert_eq!(acir_call.inputs, vec![FieldElement::from(2u128), FieldElement::from(3u128)]);
+
+    acvm.resolve_pending_acir_call(vec![FieldElement::from(6u128)]);
+
+    // The second call is disabled by its predicate, so it is skipped
+    let solver_status = acvm.solve();
+    assert_eq!(solver_status, ACVMStatus::Solved, "should be fully solved");
+    let witness_map = acvm.finalize();
+
+    assert_eq!(witness_map[&Witness(5)], FieldElement::from(6u128));
+    assert_eq!(witness_map[&Witness(6)], FieldElement::zero());
+}
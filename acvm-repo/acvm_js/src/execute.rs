@@ -1,5 +1,8 @@
 use acvm::{
-    acir::circuit::Circuit,
+    acir::{
+        brillig::ForeignCallResult,
+        circuit::{Circuit, ASSERT_MESSAGE_VALUES_ORACLE},
+    },
     pwg::{ACVMStatus, ErrorLocation, OpcodeResolutionError, ACVM},
 };
 use bn254_blackbox_solver::Bn254BlackBoxSolver;
@@ -75,7 +78,7 @@ pub async fn execute_circuit_with_black_box_solver(
                 unreachable!("Execution should not stop while in `InProgress` state.")
             }
             ACVMStatus::Failure(error) => {
-                let (assert_message, call_stack) = match &error {
+                let (assert_payload, call_stack) = match &error {
                     OpcodeResolutionError::UnsatisfiedConstrain {
                         opcode_location: ErrorLocation::Resolved(opcode_location),
                     }
@@ -92,19 +95,31 @@ pub async fn execute_circuit_with_black_box_solver(
                     }
                     _ => (None, None),
                 };
-
-                let error_string = match &assert_message {
-                    Some(assert_message) => format!("Assertion failed: {}", assert_message),
+                // The values formatted into a message are decoded by nargo, which knows how the
+                // compiler lays them out, so messages are reported as written here.
+                let error_string = match assert_payload {
+                    Some(assert_payload) => format!("Assertion failed: {}", assert_payload.message),
                     None => error.to_string(),
                 };
 
                 return Err(JsExecutionError::new(error_string.into(), call_stack).into());
             }
             ACVMStatus::RequiresForeignCall(foreign_call) => {
-                let result = resolve_brillig(&foreign_call_handler, &foreign_call).await?;
+                let result = if foreign_call.function == ASSERT_MESSAGE_VALUES_ORACLE {
+                    ForeignCallResult { values: Vec::new() }
+                } else {
+                    resolve_brillig(&foreign_call_handler, &foreign_call).await?
+                };
 
                 acvm.resolve_pending_foreign_call(result);
             }
+            ACVMStatus::RequiresAcirCall(_) => {
+                return Err(JsExecutionError::new(
+                    "Executing circuits with ACIR calls is not supported".to_string(),
+                    None,
+                )
+                .into());
+            }
         }
     }
 
@@ -41,6 +41,11 @@ pub trait BlackBoxFunctionSolver {
         input_x: &FieldElement,
         input_x: &FieldElement,
     ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError>;
+    fn poseidon2_permutation(
+        &self,
+        inputs: &[FieldElement],
+        len: u32,
+    ) -> Result<Vec<FieldElement>, BlackBoxResolutionError>;
 }
 
 pub struct StubbedBlackBoxSolver;
@@ -101,4 +106,11 @@ impl BlackBoxFunctionSolver for StubbedBlackBoxSolver {
     ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
         Err(Self::fail(BlackBoxFunc::EmbeddedCurveDouble))
     }
+    fn poseidon2_permutation(
+        &self,
+        _inputs: &[FieldElement],
+        _len: u32,
+    ) -> Result<Vec<FieldElement>, BlackBoxResolutionError> {
+        Err(Self::fail(BlackBoxFunc::Poseidon2Permutation))
+    }
 }
@@ -0,0 +1,88 @@
+use ark_ec::AffineRepr;
+
+use acir::{BlackBoxFunc, FieldElement};
+
+use crate::BlackBoxResolutionError;
+
+/// Adds two points of the embedded curve, where `(0, 0)` is the point at infinity.
+pub fn embedded_curve_add(
+    input1_x: &FieldElement,
+    input1_y: &FieldElement,
+    input2_x: &FieldElement,
+    input2_y: &FieldElement,
+) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+    let point1 = create_point(input1_x, input1_y, BlackBoxFunc::EmbeddedCurveAdd)?;
+    let point2 = create_point(input2_x, input2_y, BlackBoxFunc::EmbeddedCurveAdd)?;
+    Ok(point_coordinates(grumpkin::SWAffine::from(point1 + point2)))
+}
+
+/// Doubles a point of the embedded curve, where `(0, 0)` is the point at infinity.
+pub fn embedded_curve_double(
+    input_x: &FieldElement,
+    input_y: &FieldElement,
+) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+    let point = create_point(input_x, input_y, BlackBoxFunc::EmbeddedCurveDouble)?;
+    Ok(point_coordinates(grumpkin::SWAffine::from(point + point)))
+}
+
+fn create_point(
+    x: &FieldElement,
+    y: &FieldElement,
+    black_box_func: BlackBoxFunc,
+) -> Result<grumpkin::SWAffine, BlackBoxResolutionError> {
+    if x.is_zero() && y.is_zero() {
+        return Ok(grumpkin::SWAffine::identity());
+    }
+    let point = grumpkin::SWAffine::new_unchecked(x.into_repr(), y.into_repr());
+    if point.is_on_curve() {
+        Ok(point)
+    } else {
+        Err(BlackBoxResolutionError::Failed(
+            black_box_func,
+            format!("Point ({}, {}) is not on the embedded curve", x.to_hex(), y.to_hex()),
+        ))
+    }
+}
+
+fn point_coordinates(point: grumpkin::SWAffine) -> (FieldElement, FieldElement) {
+    if let Some((x, y)) = point.xy() {
+        (FieldElement::from_repr(*x), FieldElement::from_repr(*y))
+    } else {
+        (FieldElement::zero(), FieldElement::zero())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_base_scalar_mul;
+
+    #[test]
+    fn adds_and_doubles_points() -> Result<(), BlackBoxResolutionError> {
+        let zero = FieldElement::zero();
+        let generator = fixed_base_scalar_mul(&FieldElement::one(), &zero)?;
+        let two = fixed_base_scalar_mul(&FieldElement::from(2u128), &zero)?;
+
+        assert_eq!(embedded_curve_double(&generator.0, &generator.1)?, two);
+        assert_eq!(
+            embedded_curve_add(&generator.0, &generator.1, &generator.0, &generator.1)?,
+            two
+        );
+        assert_eq!(embedded_curve_add(&generator.0, &generator.1, &zero, &zero)?, generator);
+        assert_eq!(
+            embedded_curve_add(&generator.0, &generator.1, &generator.0, &-generator.1)?,
+            (zero, zero)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_points_off_the_curve() {
+        let one = FieldElement::one();
+        let res = embedded_curve_double(&one, &one);
+        assert!(matches!(
+            res,
+            Err(BlackBoxResolutionError::Failed(BlackBoxFunc::EmbeddedCurveDouble, _))
+        ));
+    }
+}
@@ -5,10 +5,14 @@
 use acir::{BlackBoxFunc, FieldElement};
 use acvm_blackbox_solver::{BlackBoxFunctionSolver, BlackBoxResolutionError};
 
+mod embedded_curve_ops;
 mod fixed_base_scalar_mul;
+mod poseidon2;
 mod wasm;
 
+pub use embedded_curve_ops::{embedded_curve_add, embedded_curve_double};
 pub use fixed_base_scalar_mul::fixed_base_scalar_mul;
+pub use poseidon2::poseidon2_permutation;
 use wasm::Barretenberg;
 
 use self::wasm::{Pedersen, SchnorrSig};
@@ -90,19 +94,27 @@ impl BlackBoxFunctionSolver for Bn254BlackBoxSolver {
 
     fn ec_add(
         &self,
-        _input1_x: &FieldElement,
-        _input1_y: &FieldElement,
-        _input2_x: &FieldElement,
-        _input2_y: &FieldElement,
+        input1_x: &FieldElement,
+        input1_y: &FieldElement,
+        input2_x: &FieldElement,
+        input2_y: &FieldElement,
     ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
-        todo!();
+        embedded_curve_add(input1_x, input1_y, input2_x, input2_y)
     }
 
     fn ec_double(
         &self,
-        _input_x: &FieldElement,
-        _input_y: &FieldElement,
+        input_x: &FieldElement,
+        input_y: &FieldElement,
     ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
-        todo!();
+        embedded_curve_double(input_x, input_y)
+    }
+
+    fn poseidon2_permutation(
+        &self,
+        inputs: &[FieldElement],
+        len: u32,
+    ) -> Result<Vec<FieldElement>, BlackBoxResolutionError> {
+        poseidon2_permutation(inputs, len)
     }
 }
@@ -55,4 +55,6 @@ pub enum BlackBoxOp {
     },
     /// Performs point doubling over the embedded curve.
     EmbeddedCurveDouble { input1_x: RegisterIndex, input1_y: RegisterIndex, result: HeapArray },
+    /// Applies the Poseidon2 permutation to a state of `len` field elements.
+    Poseidon2Permutation { message: HeapVector, output: HeapArray, len: RegisterIndex },
 }
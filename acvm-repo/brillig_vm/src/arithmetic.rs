@@ -4,7 +4,7 @@ use num_bigint::{BigInt, BigUint};
 use num_traits::{One, ToPrimitive, Zero};
 
 /// Evaluate a binary operation on two FieldElements and return the result as a FieldElement.
-pub(crate) fn evaluate_binary_field_op(
+pub fn evaluate_binary_field_op(
     op: &BinaryFieldOp,
     a: FieldElement,
     b: FieldElement,
@@ -20,7 +20,7 @@ pub(crate) fn evaluate_binary_field_op(
 }
 
 /// Evaluate a binary operation on two unsigned big integers with a given bit size and return the result as a big integer.
-pub(crate) fn evaluate_binary_bigint_op(
+pub fn evaluate_binary_bigint_op(
     op: &BinaryIntOp,
     a: BigUint,
     b: BigUint,
@@ -200,6 +200,20 @@ pub(crate) fn evaluate_black_box<Solver: BlackBoxFunctionSolver>(
             registers.set(*output, hash.into());
             Ok(())
         }
+        BlackBoxOp::Poseidon2Permutation { message, output, len } => {
+            let input: Vec<FieldElement> =
+                read_heap_vector(memory, registers, message).iter().map(|x| x.to_field()).collect();
+            let len: u32 = registers.get(*len).to_u128().try_into().map_err(|_| {
+                BlackBoxResolutionError::Failed(
+                    BlackBoxFunc::Poseidon2Permutation,
+                    "Invalid state length".to_string(),
+                )
+            })?;
+            let state = solver.poseidon2_permutation(&input, len)?;
+            let state: Vec<Value> = state.into_iter().map(Value::from).collect();
+            memory.write_slice(registers.get(output.pointer).to_usize(), &state);
+            Ok(())
+        }
     }
 }
 
@@ -218,6 +232,7 @@ fn black_box_function_from_op(op: &BlackBoxOp) -> BlackBoxFunc {
         BlackBoxOp::FixedBaseScalarMul { .. } => BlackBoxFunc::FixedBaseScalarMul,
         BlackBoxOp::EmbeddedCurveAdd { .. } => BlackBoxFunc::EmbeddedCurveAdd,
         BlackBoxOp::EmbeddedCurveDouble { .. } => BlackBoxFunc::EmbeddedCurveDouble,
+        BlackBoxOp::Poseidon2Permutation { .. } => BlackBoxFunc::Poseidon2Permutation,
     }
 }
 
@@ -25,7 +25,7 @@ mod memory;
 mod registers;
 
 use acvm_blackbox_solver::{BlackBoxFunctionSolver, BlackBoxResolutionError};
-use arithmetic::{evaluate_binary_bigint_op, evaluate_binary_field_op};
+pub use arithmetic::{evaluate_binary_bigint_op, evaluate_binary_field_op};
 use black_box::evaluate_black_box;
 
 pub use memory::Memory;
@@ -81,6 +81,8 @@ pub struct VM<'a, B: BlackBoxFunctionSolver> {
     call_stack: Vec<Value>,
     /// The solver for blackbox functions
     black_box_solver: &'a B,
+    /// The number of times each opcode has been executed, if profiling is enabled
+    opcode_counts: Option<Vec<usize>>,
 }
 
 impl<'a, B: BlackBoxFunctionSolver> VM<'a, B> {
@@ -102,9 +104,20 @@ impl<'a, B: BlackBoxFunctionSolver> VM<'a, B> {
             memory: memory.into(),
             call_stack: Vec::new(),
             black_box_solver,
+            opcode_counts: None,
         }
     }
 
+    /// Enables counting the number of times each opcode is executed.
+    pub fn enable_profiling(&mut self) {
+        self.opcode_counts = Some(vec![0; self.bytecode.len()]);
+    }
+
+    /// Returns the number of times each opcode has been executed, if profiling is enabled.
+    pub fn opcode_counts(&self) -> Option<&[usize]> {
+        self.opcode_counts.as_deref()
+    }
+
     /// Updates the current status of the VM.
     /// Returns the given status.
     fn status(&mut self, status: VMStatus) -> VMStatus {
@@ -178,6 +191,19 @@ impl<'a, B: BlackBoxFunctionSolver> VM<'a, B> {
 
     /// Process a single opcode and modify the program counter.
     pub fn process_opcode(&mut self) -> VMStatus {
+        let program_counter = self.program_counter;
+        let status = self.execute_opcode();
+        if let Some(opcode_counts) = &mut self.opcode_counts {
+            // A foreign call which is waiting on its results is executed again once they're
+            // resolved, so it is only counted then.
+            if !matches!(status, VMStatus::ForeignCallWait { .. }) {
+                opcode_counts[program_counter] += 1;
+            }
+        }
+        status
+    }
+
+    fn execute_opcode(&mut self) -> VMStatus {
         let opcode = &self.bytecode[self.program_counter];
         match opcode {
             Opcode::BinaryFieldOp { op, lhs, rhs, destination: result } => {
@@ -467,6 +493,13 @@ impl BlackBoxFunctionSolver for DummyBlackBoxSolver {
     ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
         Ok((7_u128.into(), 8_u128.into()))
     }
+    fn poseidon2_permutation(
+        &self,
+        _inputs: &[FieldElement],
+        len: u32,
+    ) -> Result<Vec<FieldElement>, BlackBoxResolutionError> {
+        Ok(vec![0_u128.into(); len as usize])
+    }
 }
 
 #[cfg(test)]
@@ -551,6 +584,39 @@ mod tests {
         assert_eq!(status, VMStatus::Finished);
     }
 
+    #[test]
+    fn counts_executed_opcodes() {
+        // Increments register `0` until it is no longer less than register `1`
+        let input_registers =
+            Registers::load(vec![Value::from(0u128), Value::from(3u128), Value::from(1u128)]);
+        let counter = RegisterIndex::from(0);
+        let condition = RegisterIndex::from(3);
+        let opcodes = [
+            Opcode::BinaryIntOp {
+                op: BinaryIntOp::Add,
+                bit_size: 32,
+                lhs: counter,
+                rhs: RegisterIndex::from(2),
+                destination: counter,
+            },
+            Opcode::BinaryIntOp {
+                op: BinaryIntOp::LessThan,
+                bit_size: 32,
+                lhs: counter,
+                rhs: RegisterIndex::from(1),
+                destination: condition,
+            },
+            Opcode::JumpIf { condition, location: 0 },
+        ];
+
+        let mut vm = VM::new(input_registers, vec![], &opcodes, vec![], &DummyBlackBoxSolver);
+        vm.enable_profiling();
+        let status = vm.process_opcodes();
+        assert_eq!(status, VMStatus::Finished);
+
+        assert_eq!(vm.opcode_counts(), Some([3, 3, 3].as_slice()));
+    }
+
     #[test]
     fn jmpifnot_opcode() {
         let input_registers =
@@ -67,6 +67,16 @@ pub struct ContractFunction {
     )]
     pub bytecode: Circuit,
 
+    /// The circuits which `bytecode` runs through call opcodes. Each contract function is compiled
+    /// on its own, so a function called from several of them is included in each.
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "Circuit::serialize_circuits_base64",
+        deserialize_with = "Circuit::deserialize_circuits_base64"
+    )]
+    pub functions: Vec<Circuit>,
+
     pub debug: DebugInfo,
 }
 
@@ -3,27 +3,33 @@
 #![warn(unreachable_pub)]
 #![warn(clippy::semicolon_if_nothing_returned)]
 
+use acvm::acir::circuit::Circuit;
 use acvm::ExpressionWidth;
 use clap::Args;
 use fm::{FileId, FileManager};
 use iter_extended::vecmap;
 use noirc_abi::{AbiParameter, AbiType, ContractEvent};
 use noirc_errors::{CustomDiagnostic, FileDiagnostic};
-use noirc_evaluator::create_circuit;
 use noirc_evaluator::errors::RuntimeError;
+use noirc_evaluator::{
+    check_deterministic_compilation, create_circuit, OptimizationLevel, SsaEvaluatorOptions,
+    SsaPrintOptions, DEFAULT_ARRAY_MUX_THRESHOLD,
+};
 use noirc_frontend::graph::{CrateId, CrateName};
 use noirc_frontend::hir::def_map::{Contract, CrateDefMap};
 use noirc_frontend::hir::Context;
 use noirc_frontend::macros_api::MacroProcessor;
-use noirc_frontend::monomorphization::monomorphize;
+use noirc_frontend::monomorphization::monomorphize_with_fallbacks;
 use noirc_frontend::node_interner::FuncId;
-use std::path::Path;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use tracing::info;
 
 mod abi_gen;
 mod contract;
 mod debug;
 mod program;
+mod recursion;
 mod stdlib;
 
 use debug::filter_relevant_files;
@@ -60,10 +66,24 @@ pub struct CompileOptions {
     #[arg(long, hide = true)]
     pub show_brillig: bool,
 
+    /// Print the number of SSA instructions and the time taken after each SSA pass
+    #[arg(long, hide = true)]
+    pub show_pass_stats: bool,
+
+    /// Print the SSA before and after each run of the named SSA pass, e.g. `constant_folding`.
+    /// May be given several times.
+    #[arg(long, hide = true)]
+    pub ssa_trace: Vec<String>,
+
     /// Display the ACIR for compiled circuit
     #[arg(long)]
     pub print_acir: bool,
 
+    /// Write the output of `--ssa-trace` and `--print-acir` to files in this directory, named
+    /// after the module path of each compiled function, rather than to stdout
+    #[arg(long, hide = true)]
+    pub ir_output_dir: Option<PathBuf>,
+
     /// Treat all warnings as errors
     #[arg(long, conflicts_with = "silence_warnings")]
     pub deny_warnings: bool,
@@ -83,6 +103,61 @@ pub struct CompileOptions {
     /// Outputs the monomorphized IR to stdout for debugging
     #[arg(long, hide = true)]
     pub show_monomorphized: bool,
+
+    /// Compile each program twice and fail if the two compilations produce different circuits
+    #[arg(long, hide = true)]
+    pub check_determinism: bool,
+
+    /// Check that the SSA is well-formed after each SSA pass, failing on the first pass which
+    /// breaks it
+    #[arg(long, hide = true)]
+    pub verify_ssa: bool,
+
+    /// Select which optimization passes are run: 0 (required passes only), 1 (basic),
+    /// 2 (all passes) or s (all passes without growing unconstrained code)
+    #[arg(long, default_value = "1")]
+    pub optimization_level: OptimizationLevel,
+
+    /// Compile accesses at dynamic indices of arrays with at most this many values to
+    /// arithmetic over each of their values rather than to memory operations [default: 4]
+    #[arg(long)]
+    pub array_mux_threshold: Option<usize>,
+
+    /// Compile constrained functions marked `#[inline(never)]` to separate ACIR functions run
+    /// through call opcodes rather than inlining them. Only use this with backends which support
+    /// call opcodes
+    #[arg(long)]
+    pub acir_calls: bool,
+
+    /// Outline sequences of instructions repeated many times within a constrained function, such
+    /// as the rounds of a hash, into a shared ACIR function run through call opcodes. Only use this
+    /// with backends which support call opcodes
+    #[arg(long)]
+    pub outline_repeated_code: bool,
+
+    /// Comma separated list of features of the selected packages to enable
+    #[arg(long, value_delimiter = ',')]
+    pub features: Vec<String>,
+
+    /// Do not enable the `default` feature of the selected packages
+    #[arg(long)]
+    pub no_default_features: bool,
+
+    /// The name of the backend the program is compiled for, which enables the items marked with
+    /// `#[cfg(backend = "...")]` [default: the backend in use]
+    #[arg(long)]
+    pub target_backend: Option<String>,
+
+    /// Comma separated list of the black box functions supported by the backend. Calls to any
+    /// other black box function are compiled to its implementation in the standard library, if it
+    /// has one [default: the black box functions reported by the backend]
+    #[arg(long, value_delimiter = ',')]
+    pub supported_black_boxes: Option<Vec<String>>,
+
+    /// Pass the value assigned to each variable of the program to the debugger, so that variables
+    /// can be watched. Set by the `debug` and `dap` commands
+    #[arg(skip)]
+    pub instrument_debug: bool,
 }
 
 fn parse_expression_width(input: &str) -> Result<ExpressionWidth, std::io::Error> {
@@ -229,6 +304,7 @@ pub fn compile_main(
     options: &CompileOptions,
     cached_program: Option<CompiledProgram>,
 ) -> CompilationResult<CompiledProgram> {
+    context.crate_graph.backend = options.target_backend.clone();
     let (_, mut warnings) =
         check_crate(context, crate_id, options.deny_warnings, options.disable_macros)?;
 
@@ -251,8 +327,12 @@ pub fn compile_main(
     warnings.extend(compilation_warnings);
 
     if options.print_acir {
-        println!("Compiled ACIR for main (unoptimized):");
-        println!("{}", compiled_program.circuit);
+        let file_stem = ir_file_stem(context, &main);
+        print_acir(options, "main", &file_stem, &compiled_program.circuit);
+        for (id, function) in compiled_program.functions.iter().enumerate() {
+            let name = format!("func {id}");
+            print_acir(options, &name, &format!("{file_stem}_func_{id}"), function);
+        }
     }
 
     Ok((compiled_program, warnings))
@@ -264,6 +344,7 @@ pub fn compile_contract(
     crate_id: CrateId,
     options: &CompileOptions,
 ) -> CompilationResult<CompiledContract> {
+    context.crate_graph.backend = options.target_backend.clone();
     let (_, warnings) =
         check_crate(context, crate_id, options.deny_warnings, options.disable_macros)?;
 
@@ -300,11 +381,10 @@ pub fn compile_contract(
 
         if options.print_acir {
             for contract_function in &compiled_contract.functions {
-                println!(
-                    "Compiled ACIR for {}::{} (unoptimized):",
-                    compiled_contract.name, contract_function.name
-                );
-                println!("{}", contract_function.bytecode);
+                let name = format!("{}::{}", compiled_contract.name, contract_function.name);
+                // The functions of a contract are declared directly within its module.
+                let file_stem = format!("{}.{}", compiled_contract.name, contract_function.name);
+                print_acir(options, &name, &file_stem, &contract_function.bytecode);
             }
         }
         // errors here is either empty or contains only warnings
@@ -312,6 +392,38 @@ pub fn compile_contract(
     }
 }
 
+/// Prints the ACIR of the function called `name`, or writes it to the file `<file_stem>.acir` of
+/// the IR output directory if one was given.
+fn print_acir(options: &CompileOptions, name: &str, file_stem: &str, circuit: &Circuit) {
+    match ir_output_path(options, &format!("{file_stem}.acir")) {
+        Some(path) => {
+            if let Err(err) = std::fs::write(&path, circuit.to_string()) {
+                eprintln!("warning: could not write ACIR to {}: {err}", path.display());
+            }
+        }
+        None => {
+            println!("Compiled ACIR for {name} (unoptimized):");
+            println!("{circuit}");
+        }
+    }
+}
+
+/// Returns the name of the files to which the IR of `function` is written: its path within its
+/// crate with modules separated by dots, so that functions of the same name declared in different
+/// modules are written to different files.
+fn ir_file_stem(context: &Context, function: &FuncId) -> String {
+    let crate_id = context.def_interner.function_module(*function).krate;
+    context.fully_qualified_function_name(&crate_id, function).replace("::", ".")
+}
+
+/// Returns the path of `file_name` within the IR output directory, creating the directory if
+/// needed. Any error doing so is reported when the file is written.
+fn ir_output_path(options: &CompileOptions, file_name: &str) -> Option<PathBuf> {
+    let dir = options.ir_output_dir.as_ref()?;
+    let _ = std::fs::create_dir_all(dir);
+    Some(dir.join(file_name))
+}
+
 /// True if there are (non-warning) errors present and we should halt compilation
 fn has_errors(errors: &[FileDiagnostic], deny_warnings: bool) -> bool {
     if deny_warnings {
@@ -365,6 +477,7 @@ fn compile_contract_inner(
             is_internal: modifiers.is_internal.unwrap_or(false),
             abi: function.abi,
             bytecode: function.circuit,
+            functions: function.functions,
             debug: function.debug,
         });
     }
@@ -394,6 +507,23 @@ fn compile_contract_inner(
     }
 }
 
+/// Returns the implementation in the standard library of each black box function which the
+/// backend doesn't support and which has one.
+fn black_box_fallbacks(context: &Context, options: &CompileOptions) -> HashMap<String, FuncId> {
+    let Some(supported_black_boxes) = &options.supported_black_boxes else {
+        return HashMap::new();
+    };
+    let stdlib = context
+        .def_map(context.stdlib_crate_id())
+        .expect("The stdlib should be analyzed before compiling a program");
+
+    stdlib
+        .black_box_fallbacks(&context.def_interner)
+        .filter(|(name, _)| !supported_black_boxes.iter().any(|supported| supported == name))
+        .map(|(name, func_id)| (name.to_string(), func_id))
+        .collect()
+}
+
 /// Compile the current crate using `main_function` as the entrypoint.
 ///
 /// This function assumes [`check_crate`] is called beforehand.
@@ -405,9 +535,17 @@ pub fn compile_no_check(
     cached_program: Option<CompiledProgram>,
     force_compile: bool,
 ) -> Result<CompiledProgram, RuntimeError> {
-    let program = monomorphize(main_function, &context.def_interner);
-
-    let hash = fxhash::hash64(&program);
+    let black_box_fallbacks = black_box_fallbacks(context, options);
+    let program = monomorphize_with_fallbacks(
+        main_function,
+        &context.def_interner,
+        black_box_fallbacks,
+        options.instrument_debug,
+    )?;
+
+    // The optimization level affects the compiled circuit, so an artifact compiled
+    // at a different level must not be reused.
+    let hash = fxhash::hash64(&(&program, options.optimization_level));
     let hashes_match = cached_program.as_ref().map_or(false, |program| program.hash == hash);
     if options.show_monomorphized {
         println!("{program}");
@@ -415,16 +553,54 @@ pub fn compile_no_check(
 
     // If user has specified that they want to see intermediate steps printed then we should
     // force compilation even if the program hasn't changed.
-    let force_compile =
-        force_compile || options.print_acir || options.show_brillig || options.show_ssa;
+    let force_compile = force_compile
+        || options.print_acir
+        || options.show_brillig
+        || options.show_ssa
+        || options.show_pass_stats
+        || !options.ssa_trace.is_empty()
+        || options.check_determinism
+        || options.verify_ssa;
 
     if !force_compile && hashes_match {
         info!("Program matches existing artifact, returning early");
         return Ok(cached_program.expect("cache must exist for hashes to match"));
     }
+
+    // Recursive constrained functions can't be inlined, so report them before compiling to SSA.
+    recursion::check_for_recursion(&program)?;
+
     let visibility = program.return_visibility;
-    let (circuit, debug, input_witnesses, return_witnesses, warnings) =
-        create_circuit(program, options.show_ssa, options.show_brillig)?;
+    let program_copy = options.check_determinism.then(|| program.clone());
+    let ssa_evaluator_options = SsaEvaluatorOptions {
+        enable_ssa_logging: options.show_ssa,
+        enable_brillig_logging: options.show_brillig,
+        show_pass_stats: options.show_pass_stats,
+        print_options: SsaPrintOptions {
+            trace: options.ssa_trace.clone(),
+            output: if options.ssa_trace.is_empty() {
+                None
+            } else {
+                ir_output_path(options, &format!("{}.ssa", ir_file_stem(context, &main_function)))
+            },
+        },
+        verify_ssa: options.verify_ssa,
+        optimization_level: options.optimization_level,
+        array_mux_threshold: options.array_mux_threshold.unwrap_or(DEFAULT_ARRAY_MUX_THRESHOLD),
+        acir_calls: options.acir_calls,
+        outline_repeated_code: options.outline_repeated_code,
+    };
+    let (circuit, functions, debug, input_witnesses, return_witnesses, warnings) =
+        create_circuit(program, &ssa_evaluator_options)?;
+    if let Some(program) = program_copy {
+        check_deterministic_compilation(
+            program,
+            &ssa_evaluator_options,
+            &circuit,
+            &functions,
+            &debug,
+        )?;
+    }
 
     let abi =
         abi_gen::gen_abi(context, &main_function, input_witnesses, return_witnesses, visibility);
@@ -433,6 +609,7 @@ pub fn compile_no_check(
     Ok(CompiledProgram {
         hash,
         circuit,
+        functions,
         debug,
         abi,
         file_map,
@@ -23,6 +23,16 @@ pub struct CompiledProgram {
         deserialize_with = "Circuit::deserialize_circuit_base64"
     )]
     pub circuit: Circuit,
+    /// The circuits which `circuit` runs through [call opcodes][acvm::acir::circuit::Opcode::Call],
+    /// the `id` of each call being the index of its circuit. Only compiling with `--acir-calls` or
+    /// `--outline-repeated-code` produces any.
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "Circuit::serialize_circuits_base64",
+        deserialize_with = "Circuit::deserialize_circuits_base64"
+    )]
+    pub functions: Vec<Circuit>,
     pub abi: noirc_abi::Abi,
     pub debug: DebugInfo,
     pub file_map: BTreeMap<FileId, DebugFile>,
@@ -0,0 +1,155 @@
+//! Detects recursion between constrained functions.
+//!
+//! Constrained functions are always inlined into their callers, so a constrained function which
+//! can call itself would otherwise cause function inlining to recurse forever.
+use iter_extended::vecmap;
+use noirc_errors::Location;
+use noirc_evaluator::errors::RuntimeError;
+use noirc_frontend::monomorphization::ast::{
+    Definition, Expression, FuncId, Ident, LValue, Literal, Program,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VisitState {
+    Unvisited,
+    /// The function is on the current path of the search.
+    InProgress,
+    Done,
+}
+
+/// Returns an error for the first cycle of calls between constrained functions in `program`.
+///
+/// Calls to unconstrained functions are never inlined, so a cycle which passes through an
+/// unconstrained function is allowed.
+pub(crate) fn check_for_recursion(program: &Program) -> Result<(), RuntimeError> {
+    let calls = vecmap(&program.functions, |function| {
+        let mut calls = Vec::new();
+        if !function.unconstrained {
+            collect_calls(&function.body, &mut calls);
+            calls.retain(|(callee, _)| !program[*callee].unconstrained);
+        }
+        calls
+    });
+
+    let mut states = vec![VisitState::Unvisited; calls.len()];
+    for function in 0..calls.len() {
+        if states[function] != VisitState::Unvisited {
+            continue;
+        }
+
+        if let Some(cycle) = find_cycle(function, &calls, &mut states, &mut Vec::new()) {
+            let cycle_names =
+                vecmap(&cycle, |(caller, _)| program[FuncId(*caller as u32)].name.clone());
+            let call_stack = cycle.into_iter().map(|(_, location)| location).collect();
+            return Err(RuntimeError::RecursiveFunction { cycle: cycle_names, call_stack });
+        }
+    }
+    Ok(())
+}
+
+/// Searches the calls made from `function` depth first, returning each function in the first
+/// cycle found along with the location of the call it makes to the next function in the cycle.
+///
+/// `path` holds the same for each call made to reach `function`.
+fn find_cycle(
+    function: usize,
+    calls: &[Vec<(FuncId, Location)>],
+    states: &mut [VisitState],
+    path: &mut Vec<(usize, Location)>,
+) -> Option<Vec<(usize, Location)>> {
+    states[function] = VisitState::InProgress;
+
+    for (callee, location) in &calls[function] {
+        let callee = callee.0 as usize;
+        path.push((function, *location));
+
+        match states[callee] {
+            VisitState::InProgress => {
+                let start = path
+                    .iter()
+                    .position(|(caller, _)| *caller == callee)
+                    .expect("Functions being searched should be on the path");
+                return Some(path.split_off(start));
+            }
+            VisitState::Unvisited => {
+                if let Some(cycle) = find_cycle(callee, calls, states, path) {
+                    return Some(cycle);
+                }
+            }
+            VisitState::Done => (),
+        }
+
+        path.pop();
+    }
+
+    states[function] = VisitState::Done;
+    None
+}
+
+/// Collects the function and location of each direct call within `expression`.
+fn collect_calls(expression: &Expression, calls: &mut Vec<(FuncId, Location)>) {
+    match expression {
+        Expression::Ident(_) | Expression::Literal(Literal::Integer(..) | Literal::Bool(_)) => (),
+        Expression::Break | Expression::Continue => (),
+        Expression::Literal(Literal::Str(_)) => (),
+        Expression::Literal(Literal::Array(array)) => {
+            array.contents.iter().for_each(|element| collect_calls(element, calls));
+        }
+        Expression::Literal(Literal::FmtStr(_, _, captures)) => collect_calls(captures, calls),
+        Expression::Block(expressions) | Expression::Tuple(expressions) => {
+            expressions.iter().for_each(|expression| collect_calls(expression, calls));
+        }
+        Expression::Unary(unary) => collect_calls(&unary.rhs, calls),
+        Expression::Binary(binary) => {
+            collect_calls(&binary.lhs, calls);
+            collect_calls(&binary.rhs, calls);
+        }
+        Expression::Index(index) => {
+            collect_calls(&index.collection, calls);
+            collect_calls(&index.index, calls);
+        }
+        Expression::Cast(cast) => collect_calls(&cast.lhs, calls),
+        Expression::For(for_loop) => {
+            collect_calls(&for_loop.start_range, calls);
+            collect_calls(&for_loop.end_range, calls);
+            collect_calls(&for_loop.block, calls);
+        }
+        Expression::Loop(block) => collect_calls(block, calls),
+        Expression::If(if_expr) => {
+            collect_calls(&if_expr.condition, calls);
+            collect_calls(&if_expr.consequence, calls);
+            if let Some(alternative) = &if_expr.alternative {
+                collect_calls(alternative, calls);
+            }
+        }
+        Expression::ExtractTupleField(tuple, _) => collect_calls(tuple, calls),
+        Expression::Call(call) => {
+            if let Expression::Ident(Ident { definition: Definition::Function(id), .. }) =
+                call.func.as_ref()
+            {
+                calls.push((*id, call.location));
+            }
+            collect_calls(&call.func, calls);
+            call.arguments.iter().for_each(|argument| collect_calls(argument, calls));
+        }
+        Expression::Let(let_statement) => collect_calls(&let_statement.expression, calls),
+        Expression::Constrain(condition, ..) => collect_calls(condition, calls),
+        Expression::Assign(assign) => {
+            collect_lvalue_calls(&assign.lvalue, calls);
+            collect_calls(&assign.expression, calls);
+        }
+        Expression::Semi(expression) => collect_calls(expression, calls),
+    }
+}
+
+fn collect_lvalue_calls(lvalue: &LValue, calls: &mut Vec<(FuncId, Location)>) {
+    match lvalue {
+        LValue::Ident(_) => (),
+        LValue::Index { array, index, .. } => {
+            collect_lvalue_calls(array, calls);
+            collect_calls(index, calls);
+        }
+        LValue::MemberAccess { object, .. } => collect_lvalue_calls(object, calls),
+        LValue::Dereference { reference, .. } => collect_lvalue_calls(reference, calls),
+    }
+}
@@ -0,0 +1,63 @@
+use std::path::Path;
+
+use noirc_driver::{file_manager_with_stdlib, prepare_crate, CompileOptions};
+use noirc_frontend::hir::{def_map::parse_file, Context};
+
+fn compile_errors(source: &str) -> Vec<String> {
+    let root = Path::new("");
+    let file_name = Path::new("main.nr");
+    let mut file_manager = file_manager_with_stdlib(root);
+    file_manager.add_file_with_source(file_name, source.to_owned()).expect(
+        "Adding source buffer to file manager should never fail when file manager is empty",
+    );
+    let parsed_files = file_manager
+        .as_file_map()
+        .all_file_ids()
+        .map(|&file_id| (file_id, parse_file(&file_manager, file_id)))
+        .collect();
+
+    let mut context = Context::new(file_manager, parsed_files);
+    let root_crate_id = prepare_crate(&mut context, file_name);
+
+    match noirc_driver::compile_main(&mut context, root_crate_id, &CompileOptions::default(), None)
+    {
+        Ok(_) => Vec::new(),
+        Err(errors) => errors.into_iter().map(|error| error.diagnostic.message).collect(),
+    }
+}
+
+#[test]
+fn rejects_mutually_recursive_constrained_functions() {
+    let source = "
+fn main(x: u32) {
+    assert(is_even(x));
+}
+
+fn is_even(x: u32) -> bool {
+    if x == 0 { true } else { is_odd(x - 1) }
+}
+
+fn is_odd(x: u32) -> bool {
+    if x == 0 { false } else { is_even(x - 1) }
+}";
+
+    assert_eq!(
+        compile_errors(source),
+        vec!["Function `is_even` is recursive, which is only supported in unconstrained functions"
+            .to_string()]
+    );
+}
+
+#[test]
+fn allows_recursion_through_unconstrained_functions() {
+    let source = "
+fn main(x: u32) {
+    assert(count_down(x) == 0);
+}
+
+unconstrained fn count_down(x: u32) -> u32 {
+    if x == 0 { 0 } else { count_down(x - 1) }
+}";
+
+    assert_eq!(compile_errors(source), Vec::<String>::new());
+}
@@ -5,16 +5,35 @@ pub(crate) mod brillig_directive;
 pub(crate) mod brillig_fn;
 pub(crate) mod brillig_slice_ops;
 mod variable_liveness;
+mod vector_lifetimes;
 
 use self::{brillig_block::BrilligBlock, brillig_fn::FunctionContext};
 use super::brillig_ir::{artifact::BrilligArtifact, BrilligContext};
 use crate::ssa::ir::function::Function;
 
 /// Converting an SSA function into Brillig bytecode.
-pub(crate) fn convert_ssa_function(func: &Function, enable_debug_trace: bool) -> BrilligArtifact {
+///
+/// If the function has `local_memory`, none of the memory it allocates can be accessed once it
+/// returns, so all of it is freed on return.
+pub(crate) fn convert_ssa_function(
+    func: &Function,
+    local_memory: bool,
+    enable_debug_trace: bool,
+) -> BrilligArtifact {
     let mut brillig_context = BrilligContext::new(enable_debug_trace);
 
     let mut function_context = FunctionContext::new(func, &mut brillig_context);
+    function_context.local_memory = local_memory;
+
+    // The parameters of the function are received in the first registers.
+    let parameter_registers_count = func
+        .parameters()
+        .iter()
+        .map(|parameter| {
+            function_context.ssa_value_allocations[parameter].extract_registers().len()
+        })
+        .sum();
+    brillig_context.reserve_calling_convention_registers(parameter_registers_count);
 
     brillig_context.enter_context(FunctionContext::function_id_to_function_label(func.id()));
 
@@ -22,5 +41,7 @@ pub(crate) fn convert_ssa_function(func: &Function, enable_debug_trace: bool) ->
         BrilligBlock::compile(&mut function_context, &mut brillig_context, block, &func.dfg);
     }
 
+    brillig_context.allocate_registers();
+
     brillig_context.artifact()
 }
@@ -1,3 +1,4 @@
+use crate::brillig::brillig_ir::artifact::BrilligParameter;
 use crate::brillig::brillig_ir::brillig_variable::{BrilligArray, BrilligVariable, BrilligVector};
 use crate::brillig::brillig_ir::{
     BrilligBinaryOp, BrilligContext, BRILLIG_INTEGER_ARITHMETIC_BIT_SIZE,
@@ -14,7 +15,8 @@ use crate::ssa::ir::{
     value::{Value, ValueId},
 };
 use acvm::acir::brillig::{BinaryFieldOp, BinaryIntOp, RegisterIndex, RegisterOrMemory};
-use acvm::brillig_vm::brillig::HeapVector;
+use acvm::acir::circuit::AssertionPayload;
+use acvm::brillig_vm::brillig::{HeapArray, HeapVector};
 use acvm::FieldElement;
 use fxhash::{FxHashMap as HashMap, FxHashSet as HashSet};
 use iter_extended::vecmap;
@@ -162,6 +164,9 @@ impl<'block> BrilligBlock<'block> {
                         return_variable.extract_registers()
                     })
                     .collect();
+                if self.function_context.local_memory {
+                    self.brillig_context.free_function_memory_instruction();
+                }
                 self.brillig_context.return_instruction(&return_registers);
             }
         }
@@ -265,7 +270,24 @@ impl<'block> BrilligBlock<'block> {
                     condition,
                 );
 
-                self.brillig_context.constrain_instruction(condition, assert_message.clone());
+                match assert_message {
+                    Some(assert_message) => {
+                        let values = vecmap(&assert_message.values, |value| {
+                            self.convert_ssa_register_value(*value, dfg)
+                        });
+                        let assert_payload = AssertionPayload {
+                            message: assert_message.message.clone(),
+                            witnesses: Vec::new(),
+                            printable_type: assert_message.printable_type.clone(),
+                        };
+                        self.brillig_context.constrain_with_message_values_instruction(
+                            condition,
+                            Some(assert_payload),
+                            &values,
+                        );
+                    }
+                    None => self.brillig_context.constrain_instruction(condition, None),
+                }
                 self.brillig_context.deallocate_register(condition);
             }
             Instruction::Allocate => {
@@ -329,8 +351,14 @@ impl<'block> BrilligBlock<'block> {
                 Value::ForeignFunction(func_name) => {
                     let result_ids = dfg.instruction_results(instruction_id);
 
+                    let mut flattened_arrays = Vec::new();
                     let input_registers = vecmap(arguments, |value_id| {
-                        self.convert_ssa_value(*value_id, dfg).to_register_or_memory()
+                        if let Some(array) = self.flatten_foreign_call_input(*value_id, dfg) {
+                            flattened_arrays.push(array.pointer);
+                            RegisterOrMemory::HeapArray(array)
+                        } else {
+                            self.convert_ssa_value(*value_id, dfg).to_register_or_memory()
+                        }
                     });
                     let output_registers = vecmap(result_ids, |value_id| {
                         self.allocate_external_call_result(*value_id, dfg).to_register_or_memory()
@@ -340,6 +368,9 @@ impl<'block> BrilligBlock<'block> {
                         &input_registers,
                         &output_registers,
                     );
+                    for pointer in flattened_arrays {
+                        self.brillig_context.deallocate_register(pointer);
+                    }
 
                     for (i, output_register) in output_registers.iter().enumerate() {
                         if let RegisterOrMemory::HeapVector(HeapVector { size, .. }) =
@@ -443,79 +474,131 @@ impl<'block> BrilligBlock<'block> {
                 Value::Intrinsic(Intrinsic::ToRadix(endianness)) => {
                     let source = self.convert_ssa_register_value(arguments[0], dfg);
                     let radix = self.convert_ssa_register_value(arguments[1], dfg);
-                    let limb_count = self.convert_ssa_register_value(arguments[2], dfg);
 
-                    let results = dfg.instruction_results(instruction_id);
+                    let target_array = self
+                        .variables
+                        .define_variable(
+                            self.function_context,
+                            self.brillig_context,
+                            dfg.instruction_results(instruction_id)[0],
+                            dfg,
+                        )
+                        .extract_array();
+                    let target_vector = self.brillig_context.array_to_vector(&target_array);
 
-                    let target_len = self.variables.define_register_variable(
-                        self.function_context,
-                        self.brillig_context,
-                        results[0],
-                        dfg,
+                    self.brillig_context.radix_instruction(
+                        source,
+                        target_vector,
+                        radix,
+                        matches!(endianness, Endian::Big),
                     );
 
-                    let target_vector = self
+                    self.brillig_context.deallocate_register(target_vector.size);
+                }
+                Value::Intrinsic(Intrinsic::ToBits(endianness)) => {
+                    let source = self.convert_ssa_register_value(arguments[0], dfg);
+
+                    let target_array = self
                         .variables
                         .define_variable(
                             self.function_context,
                             self.brillig_context,
-                            results[1],
+                            dfg.instruction_results(instruction_id)[0],
                             dfg,
                         )
-                        .extract_vector();
+                        .extract_array();
+                    let target_vector = self.brillig_context.array_to_vector(&target_array);
 
-                    // Update the user-facing slice length
-                    self.brillig_context.mov_instruction(target_len, limb_count);
+                    let radix = self.brillig_context.make_constant(2_usize.into());
 
                     self.brillig_context.radix_instruction(
                         source,
                         target_vector,
                         radix,
-                        limb_count,
                         matches!(endianness, Endian::Big),
                     );
+
+                    self.brillig_context.deallocate_register(radix);
+                    self.brillig_context.deallocate_register(target_vector.size);
                 }
-                Value::Intrinsic(Intrinsic::ToBits(endianness)) => {
+                Value::Intrinsic(intrinsic @ (Intrinsic::CountOnes | Intrinsic::LeadingZeros)) => {
                     let source = self.convert_ssa_register_value(arguments[0], dfg);
-                    let limb_count = self.convert_ssa_register_value(arguments[1], dfg);
-
-                    let results = dfg.instruction_results(instruction_id);
-
-                    let target_len_variable = self.variables.define_variable(
+                    let bit_size =
+                        Self::get_bit_size_from_ssa_type(dfg.type_of_value(arguments[0]));
+                    let result = self.variables.define_register_variable(
                         self.function_context,
                         self.brillig_context,
-                        results[0],
+                        dfg.instruction_results(instruction_id)[0],
                         dfg,
                     );
-                    let target_len = target_len_variable.extract_register();
 
-                    let target_vector = match self.variables.define_variable(
+                    if *intrinsic == Intrinsic::CountOnes {
+                        self.brillig_context.count_ones_instruction(source, bit_size, result);
+                    } else {
+                        self.brillig_context.leading_zeros_instruction(source, bit_size, result);
+                    }
+                }
+                Value::Intrinsic(intrinsic @ (Intrinsic::RotateLeft | Intrinsic::RotateRight)) => {
+                    let source = self.convert_ssa_register_value(arguments[0], dfg);
+                    let amount = self.convert_ssa_register_value(arguments[1], dfg);
+                    let bit_size =
+                        Self::get_bit_size_from_ssa_type(dfg.type_of_value(arguments[0]));
+                    let result = self.variables.define_register_variable(
                         self.function_context,
                         self.brillig_context,
-                        results[1],
+                        dfg.instruction_results(instruction_id)[0],
                         dfg,
-                    ) {
-                        BrilligVariable::BrilligArray(array) => {
-                            self.brillig_context.array_to_vector(&array)
-                        }
-                        BrilligVariable::BrilligVector(vector) => vector,
-                        BrilligVariable::Simple(..) => unreachable!("ICE: ToBits on non-array"),
-                    };
+                    );
 
-                    let radix = self.brillig_context.make_constant(2_usize.into());
+                    let left = *intrinsic == Intrinsic::RotateLeft;
+                    self.brillig_context.rotate_instruction(source, amount, bit_size, left, result);
+                }
+                Value::Intrinsic(Intrinsic::StrEq) => {
+                    let lhs = self.convert_ssa_value(arguments[0], dfg).extract_array();
+                    let rhs = self.convert_ssa_value(arguments[1], dfg).extract_array();
+                    let result = self.variables.define_register_variable(
+                        self.function_context,
+                        self.brillig_context,
+                        dfg.instruction_results(instruction_id)[0],
+                        dfg,
+                    );
 
-                    // Update the user-facing slice length
-                    self.brillig_context.mov_instruction(target_len, limb_count);
+                    self.brillig_context.bytes_equal_instruction(lhs, rhs, result);
+                }
+                Value::Intrinsic(Intrinsic::StrSubstring) => {
+                    let source = self.convert_ssa_value(arguments[0], dfg).extract_array();
+                    let start = self.convert_ssa_register_value(arguments[1], dfg);
+                    let target = self
+                        .allocate_external_call_result(
+                            dfg.instruction_results(instruction_id)[0],
+                            dfg,
+                        )
+                        .extract_array();
 
-                    self.brillig_context.radix_instruction(
-                        source,
-                        target_vector,
-                        radix,
-                        limb_count,
-                        matches!(endianness, Endian::Big),
+                    self.brillig_context.substring_instruction(source, start, target);
+                }
+                Value::Intrinsic(Intrinsic::ArraySwap) => {
+                    let source_variable = self.convert_ssa_value(arguments[0], dfg);
+                    let first_index = self.convert_ssa_register_value(arguments[1], dfg);
+                    let second_index = self.convert_ssa_register_value(arguments[2], dfg);
+                    let destination_variable = self.variables.define_variable(
+                        self.function_context,
+                        self.brillig_context,
+                        dfg.instruction_results(instruction_id)[0],
+                        dfg,
                     );
+                    let element_size = match dfg.type_of_value(arguments[0]) {
+                        Type::Array(element_types, _) => element_types.len(),
+                        _ => unreachable!("ICE: array_swap on non-array"),
+                    };
 
-                    self.brillig_context.deallocate_register(radix);
+                    self.convert_ssa_array_swap(
+                        source_variable,
+                        destination_variable,
+                        first_index,
+                        second_index,
+                        element_size,
+                    );
                 }
                 _ => {
                     unreachable!("unsupported function call type {:?}", dfg[*func])
@@ -625,9 +708,14 @@ impl<'block> BrilligBlock<'block> {
         let dead_variables = self
             .last_uses
             .get(&instruction_id)
-            .expect("Last uses for instruction should have been computed");
+            .expect("Last uses for instruction should have been computed")
+            .clone();
 
-        for dead_variable in dead_variables {
+        for dead_variable in &dead_variables {
+            if self.function_context.vectors_freed_at_last_use.contains(dead_variable) {
+                let vector = self.convert_ssa_value(*dead_variable, dfg).extract_vector();
+                self.brillig_context.free_array_instruction(vector.pointer, vector.size);
+            }
             self.variables.remove_variable(dead_variable);
         }
         self.brillig_context.set_call_stack(CallStack::new());
@@ -751,6 +839,63 @@ impl<'block> BrilligBlock<'block> {
         index_register: RegisterIndex,
         value_variable: BrilligVariable,
     ) {
+        let destination_pointer =
+            self.prepare_array_for_mutation(source_variable, destination_variable);
+
+        // Then set the value in the newly created array
+        self.store_variable_in_array(destination_pointer, index_register, value_variable);
+    }
+
+    /// Swaps the elements at `first_index` and `second_index` of the source array, storing the
+    /// result in the destination array.
+    ///
+    /// Each element of the array takes up `element_size` slots, one for each of its fields.
+    fn convert_ssa_array_swap(
+        &mut self,
+        source_variable: BrilligVariable,
+        destination_variable: BrilligVariable,
+        first_index: RegisterIndex,
+        second_index: RegisterIndex,
+        element_size: usize,
+    ) {
+        let first_slot = self.brillig_context.allocate_register();
+        let second_slot = self.brillig_context.allocate_register();
+        self.brillig_context.usize_op(first_index, first_slot, BinaryIntOp::Mul, element_size);
+        self.brillig_context.usize_op(second_index, second_slot, BinaryIntOp::Mul, element_size);
+        self.validate_array_index(source_variable, first_slot);
+        self.validate_array_index(source_variable, second_slot);
+
+        let destination_pointer =
+            self.prepare_array_for_mutation(source_variable, destination_variable);
+
+        let first_value = self.brillig_context.allocate_register();
+        let second_value = self.brillig_context.allocate_register();
+        for field_index in 0..element_size {
+            if field_index > 0 {
+                self.brillig_context.usize_op_in_place(first_slot, BinaryIntOp::Add, 1);
+                self.brillig_context.usize_op_in_place(second_slot, BinaryIntOp::Add, 1);
+            }
+            self.brillig_context.array_get(destination_pointer, first_slot, first_value);
+            self.brillig_context.array_get(destination_pointer, second_slot, second_value);
+            self.brillig_context.array_set(destination_pointer, first_slot, second_value);
+            self.brillig_context.array_set(destination_pointer, second_slot, first_value);
+        }
+
+        self.brillig_context.deallocate_register(first_slot);
+        self.brillig_context.deallocate_register(second_slot);
+        self.brillig_context.deallocate_register(first_value);
+        self.brillig_context.deallocate_register(second_value);
+    }
+
+    /// Points the destination array at memory which can be mutated without affecting other
+    /// references to the source array, returning the pointer to that memory.
+    ///
+    /// The source array is reused if it has a reference count of one, otherwise it is copied.
+    fn prepare_array_for_mutation(
+        &mut self,
+        source_variable: BrilligVariable,
+        destination_variable: BrilligVariable,
+    ) -> RegisterIndex {
         let destination_pointer = match destination_variable {
             BrilligVariable::BrilligArray(BrilligArray { pointer, .. }) => pointer,
             BrilligVariable::BrilligVector(BrilligVector { pointer, .. }) => pointer,
@@ -793,7 +938,10 @@ impl<'block> BrilligBlock<'block> {
                 ctx.mov_instruction(destination_pointer, source_pointer);
             } else {
                 // First issue a array copy to the destination
-                ctx.allocate_array_instruction(destination_pointer, source_size_as_register);
+                ctx.allocate_reusable_array_instruction(
+                    destination_pointer,
+                    source_size_as_register,
+                );
 
                 ctx.copy_array_instruction(
                     source_pointer,
@@ -818,12 +966,11 @@ impl<'block> BrilligBlock<'block> {
             _ => unreachable!("ICE: array set on non-array"),
         }
 
-        // Then set the value in the newly created array
-        self.store_variable_in_array(destination_pointer, index_register, value_variable);
-
         self.brillig_context.deallocate_register(source_size_as_register);
         self.brillig_context.deallocate_register(one);
         self.brillig_context.deallocate_register(condition);
+
+        destination_pointer
     }
 
     pub(crate) fn store_variable_in_array_with_ctx(
@@ -1233,6 +1380,47 @@ impl<'block> BrilligBlock<'block> {
         }
     }
 
+    /// Copies an array passed to a foreign call into a flattened array if it contains nested arrays.
+    ///
+    /// Nested arrays are stored in memory as references to their contents, which can't be followed
+    /// by the caller resolving the foreign call. Flattening the array lays its contents out in the
+    /// same order as the fields would be decoded from the value's printable type or ABI.
+    fn flatten_foreign_call_input(
+        &mut self,
+        value_id: ValueId,
+        dfg: &DataFlowGraph,
+    ) -> Option<HeapArray> {
+        let typ = dfg.type_of_value(value_id);
+        let Type::Array(item_types, item_count) = &typ else {
+            return None;
+        };
+        // Slices may contain a varying number of items so can't be flattened ahead of time.
+        if !item_types.iter().any(|item_type| matches!(item_type, Type::Array(..)))
+            || typ.is_nested_slice()
+        {
+            return None;
+        }
+
+        let variable = self.convert_ssa_value(value_id, dfg);
+        let BrilligParameter::Array(item_parameters, _) = FunctionContext::ssa_type_to_parameter(&typ)
+        else {
+            unreachable!("ICE: array type should be converted to an array parameter");
+        };
+        let size =
+            *item_count * item_parameters.iter().map(BrilligContext::flattened_size).sum::<usize>();
+
+        let pointer = self.brillig_context.allocate_register();
+        self.brillig_context.allocate_fixed_length_array(pointer, size);
+        self.brillig_context.flatten_array(
+            &item_parameters,
+            *item_count,
+            pointer,
+            variable.extract_array().pointer,
+        );
+
+        Some(HeapArray { pointer, size })
+    }
+
     /// Converts an SSA `ValueId` into a `RegisterIndex`. Initializes if necessary.
     fn convert_ssa_register_value(
         &mut self,
@@ -1,5 +1,6 @@
-use acvm::acir::brillig::{
-    BinaryFieldOp, BinaryIntOp, Opcode as BrilligOpcode, RegisterIndex, Value,
+use acvm::{
+    acir::brillig::{BinaryFieldOp, BinaryIntOp, Opcode as BrilligOpcode, RegisterIndex, Value},
+    FieldElement,
 };
 
 use crate::brillig::brillig_ir::artifact::GeneratedBrillig;
@@ -10,32 +11,22 @@ pub(crate) fn directive_invert() -> GeneratedBrillig {
     // fn invert(x : Field) -> Field {
     //    1/ x
     // }
+    let mut builder = DirectiveBuilder::default();
 
     // The input argument, ie the value that will be inverted.
     // We store the result in this register too.
-    let input = RegisterIndex::from(0);
-    let one_const = RegisterIndex::from(1);
-    // Location of the stop opcode
-    let stop_location = 3;
-
-    GeneratedBrillig {
-        byte_code: vec![
-            // If the input is zero, then we jump to the stop opcode
-            BrilligOpcode::JumpIfNot { condition: input, location: stop_location },
-            // Put value one in register (1)
-            BrilligOpcode::Const { destination: one_const, value: Value::from(1_usize) },
-            // Divide 1 by the input, and set the result of the division into register (0)
-            BrilligOpcode::BinaryFieldOp {
-                op: BinaryFieldOp::Div,
-                lhs: one_const,
-                rhs: input,
-                destination: input,
-            },
-            BrilligOpcode::Stop,
-        ],
-        assert_messages: Default::default(),
-        locations: Default::default(),
-    }
+    let input = builder.input(DirectiveType::Field);
+    let stop = builder.new_label();
+
+    // If the input is zero, then we jump to the stop opcode
+    builder.jump_if_not(input, stop);
+    let one = builder.constant(DirectiveType::Field, 1_usize);
+    // Divide 1 by the input, and set the result of the division into the input register
+    builder.field_op(BinaryFieldOp::Div, one, input, input);
+    builder.set_label(stop);
+    builder.stop();
+
+    builder.finish()
 }
 
 /// Generates brillig bytecode which computes `a / b` and returns the quotient and remainder.
@@ -48,42 +39,210 @@ pub(crate) fn directive_invert() -> GeneratedBrillig {
 /// }
 /// ```
 pub(crate) fn directive_quotient(bit_size: u32) -> GeneratedBrillig {
-    // `a` is (0) (i.e register index 0)
-    // `b` is (1)
-    GeneratedBrillig {
-        byte_code: vec![
-            //q = a/b is set into register (2)
-            BrilligOpcode::BinaryIntOp {
-                op: BinaryIntOp::UnsignedDiv,
-                lhs: RegisterIndex::from(0),
-                rhs: RegisterIndex::from(1),
-                destination: RegisterIndex::from(2),
-                bit_size,
-            },
-            //(1)= q*b
-            BrilligOpcode::BinaryIntOp {
-                op: BinaryIntOp::Mul,
-                lhs: RegisterIndex::from(2),
-                rhs: RegisterIndex::from(1),
-                destination: RegisterIndex::from(1),
-                bit_size,
-            },
-            //(1) = a-q*b
-            BrilligOpcode::BinaryIntOp {
-                op: BinaryIntOp::Sub,
-                lhs: RegisterIndex::from(0),
-                rhs: RegisterIndex::from(1),
-                destination: RegisterIndex::from(1),
-                bit_size,
-            },
-            //(0) = q
-            BrilligOpcode::Mov {
-                destination: RegisterIndex::from(0),
-                source: RegisterIndex::from(2),
-            },
-            BrilligOpcode::Stop,
-        ],
-        assert_messages: Default::default(),
-        locations: Default::default(),
+    let mut builder = DirectiveBuilder::default();
+
+    let a = builder.input(DirectiveType::Integer(bit_size));
+    let b = builder.input(DirectiveType::Integer(bit_size));
+    let q = builder.register(DirectiveType::Integer(bit_size));
+
+    builder.int_op(BinaryIntOp::UnsignedDiv, a, b, q);
+    // The remainder `a - q * b` is returned in the register of `b`
+    builder.int_op(BinaryIntOp::Mul, q, b, b);
+    builder.int_op(BinaryIntOp::Sub, a, b, b);
+    // The quotient is returned in the register of `a`
+    builder.mov(a, q);
+    builder.stop();
+
+    builder.finish()
+}
+
+/// The type of the values held in a [`DirectiveRegister`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DirectiveType {
+    Field,
+    Integer(u32),
+}
+
+impl DirectiveType {
+    /// The bit size used for integer operations on values of this type.
+    fn bit_size(self) -> u32 {
+        match self {
+            DirectiveType::Field => FieldElement::max_num_bits(),
+            DirectiveType::Integer(bit_size) => bit_size,
+        }
+    }
+}
+
+/// A register of a directive, along with the type of the value it holds.
+#[derive(Debug, Clone, Copy)]
+struct DirectiveRegister {
+    index: RegisterIndex,
+    typ: DirectiveType,
+}
+
+/// A location in a directive's bytecode which can be jumped to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct DirectiveLabel(usize);
+
+/// A small builder for the bytecode of directives.
+///
+/// Registers are allocated in order: the inputs of the directive are declared first using
+/// [`DirectiveBuilder::input`] so that they are placed in the registers the VM is initialized with.
+/// Outputs are read back from the same leading registers once the directive stops.
+///
+/// Jumps target labels, which are resolved to opcode locations when the directive is finished.
+#[derive(Default)]
+struct DirectiveBuilder {
+    byte_code: Vec<BrilligOpcode>,
+    num_registers: usize,
+    has_scratch_registers: bool,
+    /// The opcode location of each label, once it has been set.
+    labels: Vec<Option<usize>>,
+    /// Jump opcodes which target a label, along with the label they target.
+    unresolved_jumps: Vec<(usize, DirectiveLabel)>,
+}
+
+impl DirectiveBuilder {
+    /// Declares the next input of the directive.
+    fn input(&mut self, typ: DirectiveType) -> DirectiveRegister {
+        assert!(
+            !self.has_scratch_registers,
+            "ICE: directive inputs must be declared before any other register"
+        );
+        self.allocate_register(typ)
+    }
+
+    /// Allocates a new scratch register.
+    fn register(&mut self, typ: DirectiveType) -> DirectiveRegister {
+        self.has_scratch_registers = true;
+        self.allocate_register(typ)
+    }
+
+    fn allocate_register(&mut self, typ: DirectiveType) -> DirectiveRegister {
+        let index = RegisterIndex::from(self.num_registers);
+        self.num_registers += 1;
+        DirectiveRegister { index, typ }
+    }
+
+    /// Allocates a new register holding the constant `value`.
+    fn constant(&mut self, typ: DirectiveType, value: impl Into<Value>) -> DirectiveRegister {
+        let destination = self.register(typ);
+        self.byte_code
+            .push(BrilligOpcode::Const { destination: destination.index, value: value.into() });
+        destination
+    }
+
+    fn mov(&mut self, destination: DirectiveRegister, source: DirectiveRegister) {
+        self.byte_code
+            .push(BrilligOpcode::Mov { destination: destination.index, source: source.index });
+    }
+
+    fn field_op(
+        &mut self,
+        op: BinaryFieldOp,
+        lhs: DirectiveRegister,
+        rhs: DirectiveRegister,
+        destination: DirectiveRegister,
+    ) {
+        self.byte_code.push(BrilligOpcode::BinaryFieldOp {
+            op,
+            lhs: lhs.index,
+            rhs: rhs.index,
+            destination: destination.index,
+        });
+    }
+
+    /// Pushes an integer operation on the operands, performed with the bit size of their type.
+    fn int_op(
+        &mut self,
+        op: BinaryIntOp,
+        lhs: DirectiveRegister,
+        rhs: DirectiveRegister,
+        destination: DirectiveRegister,
+    ) {
+        assert_eq!(lhs.typ, rhs.typ, "ICE: integer operation on operands of different types");
+        self.byte_code.push(BrilligOpcode::BinaryIntOp {
+            op,
+            lhs: lhs.index,
+            rhs: rhs.index,
+            destination: destination.index,
+            bit_size: lhs.typ.bit_size(),
+        });
+    }
+
+    /// Creates a new label, which must be set before the directive is finished.
+    fn new_label(&mut self) -> DirectiveLabel {
+        self.labels.push(None);
+        DirectiveLabel(self.labels.len() - 1)
+    }
+
+    /// Sets the label to point to the next opcode.
+    fn set_label(&mut self, label: DirectiveLabel) {
+        let location = &mut self.labels[label.0];
+        assert!(location.is_none(), "ICE: directive label set twice");
+        *location = Some(self.byte_code.len());
+    }
+
+    fn jump_if_not(&mut self, condition: DirectiveRegister, label: DirectiveLabel) {
+        self.push_jump(BrilligOpcode::JumpIfNot { condition: condition.index, location: 0 }, label);
+    }
+
+    fn push_jump(&mut self, jump: BrilligOpcode, label: DirectiveLabel) {
+        self.unresolved_jumps.push((self.byte_code.len(), label));
+        self.byte_code.push(jump);
+    }
+
+    fn stop(&mut self) {
+        self.byte_code.push(BrilligOpcode::Stop);
+    }
+
+    /// Resolves the targets of all jumps and returns the generated bytecode.
+    fn finish(mut self) -> GeneratedBrillig {
+        for (jump_location, label) in self.unresolved_jumps {
+            let label_location =
+                self.labels[label.0].expect("ICE: jump to a directive label which was never set");
+            match &mut self.byte_code[jump_location] {
+                BrilligOpcode::Jump { location }
+                | BrilligOpcode::JumpIf { location, .. }
+                | BrilligOpcode::JumpIfNot { location, .. } => *location = label_location,
+                _ => unreachable!("ICE: only jumps can target a directive label"),
+            }
+        }
+
+        GeneratedBrillig {
+            byte_code: self.byte_code,
+            assert_messages: Default::default(),
+            locations: Default::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::{acir::brillig::Value, FieldElement};
+
+    use crate::brillig::brillig_ir::tests::create_and_run_vm;
+
+    use super::{directive_invert, directive_quotient};
+
+    #[test]
+    fn invert() {
+        let bytecode = directive_invert().byte_code;
+        let vm = create_and_run_vm(vec![], vec![Value::from(4_u128)], &bytecode);
+        let inverse = vm.get_registers().get(0_usize.into()).to_field();
+        assert_eq!(inverse, FieldElement::from(4_u128).inverse());
+
+        let vm = create_and_run_vm(vec![], vec![Value::from(0_u128)], &bytecode);
+        assert_eq!(vm.get_registers().get(0_usize.into()).to_field(), FieldElement::zero());
+    }
+
+    #[test]
+    fn quotient() {
+        let bytecode = directive_quotient(32).byte_code;
+        let vm =
+            create_and_run_vm(vec![], vec![Value::from(17_u128), Value::from(5_u128)], &bytecode);
+        let registers = vm.get_registers();
+        assert_eq!(registers.get(0_usize.into()).to_field(), FieldElement::from(3_u128));
+        assert_eq!(registers.get(1_usize.into()).to_field(), FieldElement::from(2_u128));
     }
 }
@@ -16,7 +16,10 @@ use crate::{
 };
 use fxhash::{FxHashMap as HashMap, FxHashSet as HashSet};
 
-use super::{brillig_block_variables::allocate_value, variable_liveness::VariableLiveness};
+use super::{
+    brillig_block_variables::allocate_value, variable_liveness::VariableLiveness,
+    vector_lifetimes::find_vectors_freed_at_last_use,
+};
 
 pub(crate) struct FunctionContext {
     pub(crate) function_id: FunctionId,
@@ -28,6 +31,11 @@ pub(crate) struct FunctionContext {
     pub(crate) blocks: Vec<BasicBlockId>,
     /// Liveness information for each variable in the function.
     pub(crate) liveness: VariableLiveness,
+    /// The vectors whose memory is freed after their last use, as no other reference to it remains.
+    pub(crate) vectors_freed_at_last_use: HashSet<ValueId>,
+    /// Whether none of the memory allocated by the function can be accessed once it returns, in
+    /// which case it is freed on return.
+    pub(crate) local_memory: bool,
 }
 
 impl FunctionContext {
@@ -52,12 +60,17 @@ impl FunctionContext {
             block_parameters.insert(block_id, parameters);
         }
 
+        let liveness = VariableLiveness::from_function(function);
+        let vectors_freed_at_last_use = find_vectors_freed_at_last_use(function, &liveness);
+
         Self {
             function_id: id,
             ssa_value_allocations: ssa_variable_to_register_or_memory,
             block_parameters,
             blocks: reverse_post_order,
-            liveness: VariableLiveness::from_function(function),
+            liveness,
+            vectors_freed_at_last_use,
+            local_memory: false,
         }
     }
 
@@ -70,7 +83,7 @@ impl FunctionContext {
         function_id.to_string()
     }
 
-    fn ssa_type_to_parameter(typ: &Type) -> BrilligParameter {
+    pub(crate) fn ssa_type_to_parameter(typ: &Type) -> BrilligParameter {
         match typ {
             Type::Numeric(_) | Type::Reference(_) => BrilligParameter::Simple,
             Type::Array(item_type, size) => BrilligParameter::Array(
@@ -18,7 +18,8 @@ impl<'block> BrilligBlock<'block> {
             BinaryIntOp::Add,
             variables_to_insert.len(),
         );
-        self.brillig_context.allocate_array_instruction(target_vector.pointer, target_vector.size);
+        self.brillig_context
+            .allocate_reusable_array_instruction(target_vector.pointer, target_vector.size);
         // We initialize the RC of the target vector to 1
         self.brillig_context.const_instruction(target_vector.rc, 1_usize.into());
 
@@ -55,7 +56,8 @@ impl<'block> BrilligBlock<'block> {
             BinaryIntOp::Add,
             variables_to_insert.len(),
         );
-        self.brillig_context.allocate_array_instruction(target_vector.pointer, target_vector.size);
+        self.brillig_context
+            .allocate_reusable_array_instruction(target_vector.pointer, target_vector.size);
         // We initialize the RC of the target vector to 1
         self.brillig_context.const_instruction(target_vector.rc, 1_usize.into());
 
@@ -98,7 +100,8 @@ impl<'block> BrilligBlock<'block> {
             BinaryIntOp::Sub,
             removed_items.len(),
         );
-        self.brillig_context.allocate_array_instruction(target_vector.pointer, target_vector.size);
+        self.brillig_context
+            .allocate_reusable_array_instruction(target_vector.pointer, target_vector.size);
         // We initialize the RC of the target vector to 1
         self.brillig_context.const_instruction(target_vector.rc, 1_usize.into());
 
@@ -140,7 +143,8 @@ impl<'block> BrilligBlock<'block> {
             BinaryIntOp::Sub,
             removed_items.len(),
         );
-        self.brillig_context.allocate_array_instruction(target_vector.pointer, target_vector.size);
+        self.brillig_context
+            .allocate_reusable_array_instruction(target_vector.pointer, target_vector.size);
         // We initialize the RC of the target vector to 1
         self.brillig_context.const_instruction(target_vector.rc, 1_usize.into());
 
@@ -178,7 +182,8 @@ impl<'block> BrilligBlock<'block> {
             BinaryIntOp::Add,
             items.len(),
         );
-        self.brillig_context.allocate_array_instruction(target_vector.pointer, target_vector.size);
+        self.brillig_context
+            .allocate_reusable_array_instruction(target_vector.pointer, target_vector.size);
         // We initialize the RC of the target vector to 1
         self.brillig_context.const_instruction(target_vector.rc, 1_usize.into());
 
@@ -250,7 +255,8 @@ impl<'block> BrilligBlock<'block> {
             BinaryIntOp::Sub,
             removed_items.len(),
         );
-        self.brillig_context.allocate_array_instruction(target_vector.pointer, target_vector.size);
+        self.brillig_context
+            .allocate_reusable_array_instruction(target_vector.pointer, target_vector.size);
         // We initialize the RC of the target vector to 1
         self.brillig_context.const_instruction(target_vector.rc, 1_usize.into());
 
@@ -0,0 +1,152 @@
+//! This module finds the vectors of a function whose memory can be freed after their last use.
+//!
+//! The slice intrinsics always allocate a new vector for their result, which at first is only
+//! referred to by its own value. As long as the vector is only read from, its memory can't be
+//! accessed once the value is no longer live. Writing to a vector in its last use may reuse its
+//! memory for the result, which then takes over the memory of the vector.
+//!
+//! A vector is freed after its last use if it is the result of a slice intrinsic, or of writing to
+//! such a vector in its last use, and it is only:
+//! - read from,
+//! - the source of a slice intrinsic, which copies it, or
+//! - written to in its last use, in which case its memory is freed with the result instead.
+//!
+//! Any other use, such as passing the vector to a function or a block, storing it, writing it into
+//! an array or changing its reference count, may keep another reference to its memory.
+use crate::ssa::ir::{
+    dfg::DataFlowGraph,
+    function::Function,
+    instruction::{Instruction, Intrinsic},
+    value::{Value, ValueId},
+};
+
+use fxhash::{FxHashMap as HashMap, FxHashSet as HashSet};
+
+use super::variable_liveness::VariableLiveness;
+
+/// Returns the vectors of `func` whose memory can be freed after their last use.
+pub(crate) fn find_vectors_freed_at_last_use(
+    func: &Function,
+    liveness: &VariableLiveness,
+) -> HashSet<ValueId> {
+    let lifetimes = VectorLifetimes::from_function(func, liveness);
+
+    let mut owned: HashSet<_> = lifetimes.created.difference(&lifetimes.shared).copied().collect();
+    loop {
+        let owned_count = owned.len();
+        for (result, vector) in &lifetimes.written {
+            if owned.contains(vector) && !lifetimes.shared.contains(result) {
+                owned.insert(*result);
+            }
+        }
+        if owned.len() == owned_count {
+            break;
+        }
+    }
+
+    // The memory of a vector written to in its last use is freed along with the result.
+    for vector in lifetimes.written.values() {
+        owned.remove(vector);
+    }
+    owned
+}
+
+#[derive(Default)]
+struct VectorLifetimes {
+    /// The vectors allocated by slice intrinsics
+    created: HashSet<ValueId>,
+
+    /// The vector written to in its last use by each `array_set`, keyed by its result
+    written: HashMap<ValueId, ValueId>,
+
+    /// Values used in a way which may keep another reference to their memory
+    shared: HashSet<ValueId>,
+}
+
+impl VectorLifetimes {
+    fn from_function(func: &Function, liveness: &VariableLiveness) -> Self {
+        let dfg = &func.dfg;
+        let mut lifetimes = Self::default();
+
+        for block_id in func.reachable_blocks() {
+            let block = &dfg[block_id];
+            let last_uses = liveness.get_last_uses(&block_id);
+
+            for instruction_id in block.instructions() {
+                let results = dfg.instruction_results(*instruction_id);
+                match &dfg[*instruction_id] {
+                    Instruction::ArrayGet { array, index } => {
+                        lifetimes.read(dfg, *array);
+                        lifetimes.share(dfg, *index);
+                    }
+                    Instruction::ArraySet { array, index, value } => {
+                        let array = dfg.resolve(*array);
+                        if last_uses[instruction_id].contains(&array) {
+                            lifetimes.read(dfg, array);
+                            lifetimes.written.insert(results[0], array);
+                        } else {
+                            lifetimes.share(dfg, array);
+                        }
+                        lifetimes.share(dfg, *index);
+                        lifetimes.share(dfg, *value);
+                    }
+                    Instruction::Call { func, arguments } => match &dfg[*func] {
+                        Value::Intrinsic(intrinsic) if is_slice_intrinsic(intrinsic) => {
+                            // The source slice follows its length, and is copied into the result.
+                            for (position, argument) in arguments.iter().enumerate() {
+                                if position == 1 {
+                                    lifetimes.read(dfg, *argument);
+                                } else {
+                                    lifetimes.share(dfg, *argument);
+                                }
+                            }
+                            let result = match intrinsic {
+                                Intrinsic::SlicePopFront => results[results.len() - 1],
+                                _ => results[1],
+                            };
+                            lifetimes.created.insert(result);
+                        }
+                        Value::Intrinsic(Intrinsic::ArrayLen) => {}
+                        _ => arguments.iter().for_each(|argument| lifetimes.share(dfg, *argument)),
+                    },
+                    instruction => {
+                        instruction.for_each_value(|value| lifetimes.share(dfg, value));
+                    }
+                }
+            }
+
+            if let Some(terminator) = block.terminator() {
+                terminator.for_each_value(|value| lifetimes.share(dfg, value));
+            }
+        }
+
+        lifetimes
+    }
+
+    /// Records a use of `value` which only reads from it.
+    fn read(&mut self, dfg: &DataFlowGraph, value: ValueId) {
+        // The elements of an array literal are copied into it.
+        if let Value::Array { array, .. } = &dfg[dfg.resolve(value)] {
+            array.iter().for_each(|element| self.share(dfg, *element));
+        }
+    }
+
+    /// Records a use of `value` which may keep another reference to it.
+    fn share(&mut self, dfg: &DataFlowGraph, value: ValueId) {
+        let value = dfg.resolve(value);
+        self.shared.insert(value);
+        self.read(dfg, value);
+    }
+}
+
+fn is_slice_intrinsic(intrinsic: &Intrinsic) -> bool {
+    matches!(
+        intrinsic,
+        Intrinsic::SlicePushBack
+            | Intrinsic::SlicePushFront
+            | Intrinsic::SlicePopBack
+            | Intrinsic::SlicePopFront
+            | Intrinsic::SliceInsert
+            | Intrinsic::SliceRemove
+    )
+}
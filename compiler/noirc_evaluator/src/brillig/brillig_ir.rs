@@ -10,6 +10,9 @@ pub(crate) mod debug_show;
 pub(crate) mod registers;
 
 mod entry_point;
+mod free_list;
+mod peephole;
+mod register_allocator;
 
 use crate::ssa::ir::dfg::CallStack;
 
@@ -19,15 +22,19 @@ use self::{
     registers::BrilligRegistersContext,
 };
 use acvm::{
-    acir::brillig::{
-        BinaryFieldOp, BinaryIntOp, BlackBoxOp, Opcode as BrilligOpcode, RegisterIndex,
-        RegisterOrMemory, Value,
+    acir::{
+        brillig::{
+            BinaryFieldOp, BinaryIntOp, BlackBoxOp, Opcode as BrilligOpcode, RegisterIndex,
+            RegisterOrMemory, Value,
+        },
+        circuit::{AssertionPayload, ASSERT_MESSAGE_VALUES_ORACLE},
     },
     FieldElement,
 };
 use debug_show::DebugShow;
+use iter_extended::vecmap;
 
-/// Integer arithmetic in Brillig is limited to 127 bit
+/// Integer arithmetic in Brillig is limited to 128 bit
 /// integers.
 ///
 /// We could lift this in the future and have Brillig
@@ -37,7 +44,7 @@ use debug_show::DebugShow;
 /// Since constrained functions do not have this property, it
 /// would mean that unconstrained functions will differ from
 /// constrained functions in terms of syntax compatibility.
-pub(crate) const BRILLIG_INTEGER_ARITHMETIC_BIT_SIZE: u32 = 127;
+pub(crate) const BRILLIG_INTEGER_ARITHMETIC_BIT_SIZE: u32 = 128;
 /// The Brillig VM does not apply a limit to the memory address space,
 /// As a convention, we take use 64 bits. This means that we assume that
 /// memory has 2^64 memory slots.
@@ -49,6 +56,8 @@ pub(crate) enum ReservedRegisters {
     StackPointer = 0,
     /// This register stores the previous stack pointer. The registers of the caller are stored here.
     PreviousStackPointer = 1,
+    /// This register stores the pointer to the memory freed last, from which arrays can be allocated again.
+    FreeListPointer = 2,
 }
 
 impl ReservedRegisters {
@@ -56,7 +65,7 @@ impl ReservedRegisters {
     ///
     /// This is used to offset the general registers
     /// which should not overwrite the special register
-    const NUM_RESERVED_REGISTERS: usize = 2;
+    const NUM_RESERVED_REGISTERS: usize = 3;
 
     /// Returns the length of the reserved registers
     pub(crate) fn len() -> usize {
@@ -73,6 +82,11 @@ impl ReservedRegisters {
         RegisterIndex::from(ReservedRegisters::PreviousStackPointer as usize)
     }
 
+    /// Returns the free list register. This will be used to reuse the memory of arrays no longer in use.
+    pub(crate) fn free_list_pointer() -> RegisterIndex {
+        RegisterIndex::from(ReservedRegisters::FreeListPointer as usize)
+    }
+
     /// Returns a user defined (non-reserved) register index.
     fn user_register_index(index: usize) -> RegisterIndex {
         RegisterIndex::from(index + ReservedRegisters::len())
@@ -94,6 +108,9 @@ pub(crate) struct BrilligContext {
     next_section: usize,
     /// IR printer
     debug_show: DebugShow,
+    /// The number of registers after the reserved ones which are used to pass arguments to and
+    /// return values from functions. These keep their index during register allocation.
+    calling_convention_registers: usize,
 }
 
 impl BrilligContext {
@@ -106,6 +123,7 @@ impl BrilligContext {
             section_label: 0,
             next_section: 1,
             debug_show: DebugShow::new(enable_debug_trace),
+            calling_convention_registers: 0,
         }
     }
 
@@ -113,6 +131,12 @@ impl BrilligContext {
         self.registers = BrilligRegistersContext::from_preallocated_registers(allocated_registers);
     }
 
+    /// Marks the first `count` registers after the reserved ones as being used to pass
+    /// arguments or return values.
+    pub(crate) fn reserve_calling_convention_registers(&mut self, count: usize) {
+        self.calling_convention_registers = self.calling_convention_registers.max(count);
+    }
+
     /// Adds a brillig instruction to the brillig byte code
     pub(crate) fn push_opcode(&mut self, opcode: BrilligOpcode) {
         self.obj.push_opcode(opcode);
@@ -433,10 +457,27 @@ impl BrilligContext {
         &mut self,
         condition: RegisterIndex,
         assert_message: Option<String>,
+    ) {
+        let assert_message = assert_message.map(AssertionPayload::from);
+        self.constrain_with_message_values_instruction(condition, assert_message, &[]);
+    }
+
+    /// Emits a constraint in the same way as [`Self::constrain_instruction`]. The `message_values`
+    /// formatted into the assertion message are passed to the [`ASSERT_MESSAGE_VALUES_ORACLE`]
+    /// right before trapping, so that the failure can be reported along with them.
+    pub(crate) fn constrain_with_message_values_instruction(
+        &mut self,
+        condition: RegisterIndex,
+        assert_message: Option<AssertionPayload>,
+        message_values: &[RegisterIndex],
     ) {
         self.debug_show.constrain_instruction(condition);
         let (next_section, next_label) = self.reserve_next_section_label();
         self.add_unresolved_jump(BrilligOpcode::JumpIf { condition, location: 0 }, next_label);
+        if !message_values.is_empty() {
+            let inputs = vecmap(message_values, |value| RegisterOrMemory::RegisterIndex(*value));
+            self.foreign_call_instruction(ASSERT_MESSAGE_VALUES_ORACLE.to_owned(), &inputs, &[]);
+        }
         self.push_opcode(BrilligOpcode::Trap);
         if let Some(assert_message) = assert_message {
             self.obj.add_assert_message_to_last_opcode(assert_message);
@@ -444,6 +485,19 @@ impl BrilligContext {
         self.enter_section(next_section);
     }
 
+    /// Frees all of the memory allocated since the current function was called, by moving the
+    /// stack pointer back to where it was when the function was called.
+    ///
+    /// This is only valid if none of the memory can be accessed once the function returns.
+    pub(crate) fn free_function_memory_instruction(&mut self) {
+        // The caller sets the previous stack pointer to the stack pointer right before calling,
+        // and each call made by the function restores it afterwards.
+        self.mov_instruction(
+            ReservedRegisters::stack_pointer(),
+            ReservedRegisters::previous_stack_pointer(),
+        );
+    }
+
     /// Processes a return instruction.
     ///
     /// For Brillig, the return is implicit, since there is no explicit return instruction.
@@ -455,6 +509,7 @@ impl BrilligContext {
     /// the VM.
     pub(crate) fn return_instruction(&mut self, return_registers: &[RegisterIndex]) {
         self.debug_show.return_instruction(return_registers);
+        self.reserve_calling_convention_registers(return_registers.len());
         let mut sources = Vec::with_capacity(return_registers.len());
         let mut destinations = Vec::with_capacity(return_registers.len());
 
@@ -805,7 +860,8 @@ impl BrilligContext {
         let mut used_registers: Vec<_> =
             vars.iter().flat_map(|var| var.extract_registers()).collect();
 
-        // Also dump the previous stack pointer
+        // Also dump the free list and the previous stack pointer
+        used_registers.push(ReservedRegisters::free_list_pointer());
         used_registers.push(ReservedRegisters::previous_stack_pointer());
         for register in used_registers.iter() {
             self.store_instruction(ReservedRegisters::stack_pointer(), *register);
@@ -813,6 +869,9 @@ impl BrilligContext {
             self.usize_op_in_place(ReservedRegisters::stack_pointer(), BinaryIntOp::Add, 1);
         }
 
+        // The callee starts with an empty free list, so that it can't reuse memory of ours
+        self.const_instruction(ReservedRegisters::free_list_pointer(), 0_usize.into());
+
         // Store the location of our registers in the previous stack pointer
         self.mov_instruction(
             ReservedRegisters::previous_stack_pointer(),
@@ -822,7 +881,21 @@ impl BrilligContext {
     }
 
     /// Loads all of the registers that have been save by save_all_used_registers.
+    ///
+    /// The memory the registers were saved to is freed again if the callee has not allocated any
+    /// memory which is still in use, so that recursive calls only use memory proportional to the
+    /// depth of the recursion rather than to the total number of calls made.
     fn load_all_saved_registers(&mut self, used_registers: &[RegisterIndex]) {
+        // The callee has freed all of its memory if the stack pointer is still at the end of the
+        // saved registers, which the previous stack pointer is restored to after each call.
+        let frame_is_on_top = self.allocate_register();
+        self.memory_op(
+            ReservedRegisters::stack_pointer(),
+            ReservedRegisters::previous_stack_pointer(),
+            frame_is_on_top,
+            BinaryIntOp::Equals,
+        );
+
         // Load all of the used registers that we saved.
         // We do all the reverse operations of save_all_used_registers.
         // Iterate our registers in reverse
@@ -834,6 +907,28 @@ impl BrilligContext {
             self.usize_op_in_place(iterator_register, BinaryIntOp::Sub, 1);
             self.load_instruction(*register, iterator_register);
         }
+
+        // The iterator is now at the start of the saved registers, so we move the stack pointer
+        // back there if the saved registers are on top of the stack:
+        // stack_pointer -= (stack_pointer - iterator) * frame_is_on_top
+        let frame_size = self.allocate_register();
+        self.memory_op(
+            ReservedRegisters::stack_pointer(),
+            iterator_register,
+            frame_size,
+            BinaryIntOp::Sub,
+        );
+        self.memory_op(frame_size, frame_is_on_top, frame_size, BinaryIntOp::Mul);
+        self.memory_op(
+            ReservedRegisters::stack_pointer(),
+            frame_size,
+            ReservedRegisters::stack_pointer(),
+            BinaryIntOp::Sub,
+        );
+
+        self.deallocate_register(frame_is_on_top);
+        self.deallocate_register(iterator_register);
+        self.deallocate_register(frame_size);
     }
 
     /// Utility method to perform a binary instruction with a constant value in place
@@ -884,6 +979,8 @@ impl BrilligContext {
         arguments: &[RegisterIndex],
         variables_to_save: &[BrilligVariable],
     ) -> Vec<RegisterIndex> {
+        self.reserve_calling_convention_registers(arguments.len());
+
         // Save all the registers we have used to the stack.
         let saved_registers = self.save_registers_of_vars(variables_to_save);
 
@@ -907,6 +1004,7 @@ impl BrilligContext {
     ) {
         // Allocate our result registers and write into them
         // We assume the return values of our call are held in 0..num results register indices
+        self.reserve_calling_convention_registers(result_registers.len());
         let (sources, destinations) = result_registers
             .iter()
             .enumerate()
@@ -935,16 +1033,14 @@ impl BrilligContext {
     }
 
     /// Issues a to_radix instruction. This instruction will write the modulus of the source register
-    /// And the radix register limb_count times to the target vector.
+    /// And the radix register to each limb of the target vector.
     pub(crate) fn radix_instruction(
         &mut self,
         source: RegisterIndex,
         target_vector: BrilligVector,
         radix: RegisterIndex,
-        limb_count: RegisterIndex,
         big_endian: bool,
     ) {
-        self.mov_instruction(target_vector.size, limb_count);
         self.const_instruction(target_vector.rc, 1_usize.into());
         self.allocate_array_instruction(target_vector.pointer, target_vector.size);
 
@@ -985,6 +1081,210 @@ impl BrilligContext {
         }
     }
 
+    /// Sets `result` to 1 if the two byte arrays are equal and to 0 otherwise.
+    pub(crate) fn bytes_equal_instruction(
+        &mut self,
+        lhs: BrilligArray,
+        rhs: BrilligArray,
+        result: RegisterIndex,
+    ) {
+        assert_eq!(lhs.size, rhs.size, "ICE: Expected two byte arrays of equal length");
+        self.const_instruction(result, 1_usize.into());
+
+        let length = self.make_constant(lhs.size.into());
+        let lhs_byte = self.allocate_register();
+        let rhs_byte = self.allocate_register();
+
+        self.loop_instruction(length, |ctx, iterator_register| {
+            ctx.array_get(lhs.pointer, iterator_register, lhs_byte);
+            ctx.array_get(rhs.pointer, iterator_register, rhs_byte);
+            ctx.binary_instruction(
+                lhs_byte,
+                rhs_byte,
+                lhs_byte,
+                BrilligBinaryOp::Integer { op: BinaryIntOp::Equals, bit_size: 8 },
+            );
+            ctx.binary_instruction(
+                result,
+                lhs_byte,
+                result,
+                BrilligBinaryOp::Integer { op: BinaryIntOp::And, bit_size: 1 },
+            );
+        });
+
+        self.deallocate_register(length);
+        self.deallocate_register(lhs_byte);
+        self.deallocate_register(rhs_byte);
+    }
+
+    /// Copies the bytes of `source` starting at index `start` into `destination`.
+    /// Fails if there are fewer than `destination.size` bytes left in `source` after `start`.
+    pub(crate) fn substring_instruction(
+        &mut self,
+        source: BrilligArray,
+        start: RegisterIndex,
+        destination: BrilligArray,
+    ) {
+        // The bounds are checked against the largest valid start to avoid overflowing the index.
+        let in_bounds = self.allocate_register();
+        match source.size.checked_sub(destination.size) {
+            Some(max_start) => {
+                self.usize_op(start, in_bounds, BinaryIntOp::LessThanEquals, max_start);
+            }
+            None => self.const_instruction(in_bounds, 0_usize.into()),
+        }
+        self.constrain_instruction(in_bounds, Some("Substring out of bounds".to_owned()));
+        self.deallocate_register(in_bounds);
+
+        let source_pointer = self.allocate_register();
+        self.memory_op(source.pointer, start, source_pointer, BinaryIntOp::Add);
+        let length = self.make_constant(destination.size.into());
+        self.copy_array_instruction(source_pointer, destination.pointer, length);
+
+        self.deallocate_register(source_pointer);
+        self.deallocate_register(length);
+    }
+
+    /// Stores the number of ones in the binary representation of `source`, an integer of
+    /// `bit_size` bits, in `result`.
+    pub(crate) fn count_ones_instruction(
+        &mut self,
+        source: RegisterIndex,
+        bit_size: u32,
+        result: RegisterIndex,
+    ) {
+        self.const_instruction(result, 0_usize.into());
+
+        let value = self.allocate_register();
+        self.mov_instruction(value, source);
+        let one = self.make_constant(1_usize.into());
+        let bit = self.allocate_register();
+        let iteration_count = self.make_constant((bit_size as usize).into());
+
+        self.loop_instruction(iteration_count, |ctx, _| {
+            ctx.binary_instruction(
+                value,
+                one,
+                bit,
+                BrilligBinaryOp::Integer { op: BinaryIntOp::And, bit_size },
+            );
+            ctx.binary_instruction(
+                result,
+                bit,
+                result,
+                BrilligBinaryOp::Integer { op: BinaryIntOp::Add, bit_size: 32 },
+            );
+            ctx.binary_instruction(
+                value,
+                one,
+                value,
+                BrilligBinaryOp::Integer { op: BinaryIntOp::Shr, bit_size },
+            );
+        });
+
+        self.deallocate_register(value);
+        self.deallocate_register(one);
+        self.deallocate_register(bit);
+        self.deallocate_register(iteration_count);
+    }
+
+    /// Stores the number of leading zeros in the binary representation of `source`, an integer
+    /// of `bit_size` bits, in `result`.
+    pub(crate) fn leading_zeros_instruction(
+        &mut self,
+        source: RegisterIndex,
+        bit_size: u32,
+        result: RegisterIndex,
+    ) {
+        // Each shift to the right which leaves a nonzero value is a significant bit.
+        self.const_instruction(result, (bit_size as usize).into());
+
+        let value = self.allocate_register();
+        self.mov_instruction(value, source);
+        let zero = self.make_constant(0_usize.into());
+        let one = self.make_constant(1_usize.into());
+        let is_nonzero = self.allocate_register();
+        let iteration_count = self.make_constant((bit_size as usize).into());
+
+        self.loop_instruction(iteration_count, |ctx, _| {
+            ctx.binary_instruction(
+                value,
+                zero,
+                is_nonzero,
+                BrilligBinaryOp::Integer { op: BinaryIntOp::Equals, bit_size },
+            );
+            ctx.not_instruction(is_nonzero, 1, is_nonzero);
+            ctx.binary_instruction(
+                result,
+                is_nonzero,
+                result,
+                BrilligBinaryOp::Integer { op: BinaryIntOp::Sub, bit_size: 32 },
+            );
+            ctx.binary_instruction(
+                value,
+                one,
+                value,
+                BrilligBinaryOp::Integer { op: BinaryIntOp::Shr, bit_size },
+            );
+        });
+
+        self.deallocate_register(value);
+        self.deallocate_register(zero);
+        self.deallocate_register(one);
+        self.deallocate_register(is_nonzero);
+        self.deallocate_register(iteration_count);
+    }
+
+    /// Stores `source`, an integer of `bit_size` bits, with its bits rotated to the left by
+    /// `amount` modulo `bit_size`, or to the right if `left` is false, in `result`.
+    pub(crate) fn rotate_instruction(
+        &mut self,
+        source: RegisterIndex,
+        amount: RegisterIndex,
+        bit_size: u32,
+        left: bool,
+        result: RegisterIndex,
+    ) {
+        let bit_size_register = self.make_constant((bit_size as usize).into());
+        let shift = self.allocate_register();
+        self.modulo_instruction(shift, amount, bit_size_register, 32, false);
+        let complement = self.allocate_register();
+        self.binary_instruction(
+            bit_size_register,
+            shift,
+            complement,
+            BrilligBinaryOp::Integer { op: BinaryIntOp::Sub, bit_size: 32 },
+        );
+
+        // Shifting by `bit_size` bits clears the value, so a rotation by zero bits keeps the source.
+        let (left_shift, right_shift) =
+            if left { (shift, complement) } else { (complement, shift) };
+        let high = self.allocate_register();
+        self.binary_instruction(
+            source,
+            left_shift,
+            high,
+            BrilligBinaryOp::Integer { op: BinaryIntOp::Shl, bit_size },
+        );
+        self.binary_instruction(
+            source,
+            right_shift,
+            result,
+            BrilligBinaryOp::Integer { op: BinaryIntOp::Shr, bit_size },
+        );
+        self.binary_instruction(
+            high,
+            result,
+            result,
+            BrilligBinaryOp::Integer { op: BinaryIntOp::Or, bit_size },
+        );
+
+        self.deallocate_register(bit_size_register);
+        self.deallocate_register(shift);
+        self.deallocate_register(complement);
+        self.deallocate_register(high);
+    }
+
     /// This instruction will reverse the order of the elements in a vector.
     pub(crate) fn reverse_vector_in_place_instruction(&mut self, vector: BrilligVector) {
         let iteration_count = self.allocate_register();
@@ -1104,6 +1404,14 @@ pub(crate) mod tests {
         ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
             panic!("Path not trodden by this test")
         }
+
+        fn poseidon2_permutation(
+            &self,
+            _inputs: &[FieldElement],
+            _len: u32,
+        ) -> Result<Vec<FieldElement>, BlackBoxResolutionError> {
+            panic!("Path not trodden by this test")
+        }
     }
 
     pub(crate) fn create_context() -> BrilligContext {
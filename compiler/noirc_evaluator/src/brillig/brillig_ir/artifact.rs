@@ -1,4 +1,5 @@
 use acvm::acir::brillig::Opcode as BrilligOpcode;
+use acvm::acir::circuit::AssertionPayload;
 use std::collections::{BTreeMap, HashMap};
 
 use crate::ssa::ir::dfg::CallStack;
@@ -17,7 +18,7 @@ pub(crate) enum BrilligParameter {
 pub(crate) struct GeneratedBrillig {
     pub(crate) byte_code: Vec<BrilligOpcode>,
     pub(crate) locations: BTreeMap<OpcodeLocation, CallStack>,
-    pub(crate) assert_messages: BTreeMap<OpcodeLocation, String>,
+    pub(crate) assert_messages: BTreeMap<OpcodeLocation, AssertionPayload>,
 }
 
 #[derive(Default, Debug, Clone)]
@@ -26,7 +27,7 @@ pub(crate) struct GeneratedBrillig {
 pub(crate) struct BrilligArtifact {
     pub(crate) byte_code: Vec<BrilligOpcode>,
     /// A map of bytecode positions to assertion messages
-    pub(crate) assert_messages: BTreeMap<OpcodeLocation, String>,
+    pub(crate) assert_messages: BTreeMap<OpcodeLocation, AssertionPayload>,
     /// The set of jumps that need to have their locations
     /// resolved.
     unresolved_jumps: Vec<(JumpInstructionPosition, UnresolvedJumpLocation)>,
@@ -250,11 +251,67 @@ impl BrilligArtifact {
         }
     }
 
+    /// Returns the position of each unresolved jump along with the position of the opcode it jumps to.
+    ///
+    /// All the labels of these jumps must be defined in this artifact.
+    pub(crate) fn jump_targets(&self) -> Vec<(OpcodeLocation, OpcodeLocation)> {
+        self.unresolved_jumps
+            .iter()
+            .map(|(location_of_jump, label)| (*location_of_jump, self.labels[label]))
+            .collect()
+    }
+
+    /// Replaces each opcode with the sequence of opcodes returned by `rewrite`, along with the
+    /// index within that sequence of the opcode taking the place of the original one.
+    ///
+    /// Labels point to the start of the sequence replacing the opcode they were attached to,
+    /// while jumps, calls and assert messages are moved to the opcode taking its place.
+    pub(crate) fn rewrite_opcodes(
+        &mut self,
+        mut rewrite: impl FnMut(OpcodeLocation, BrilligOpcode) -> (Vec<BrilligOpcode>, usize),
+    ) {
+        let old_byte_code = std::mem::take(&mut self.byte_code);
+        let mut sequence_starts = Vec::with_capacity(old_byte_code.len() + 1);
+        let mut new_locations = Vec::with_capacity(old_byte_code.len());
+
+        for (location, opcode) in old_byte_code.into_iter().enumerate() {
+            let (opcodes, index) = rewrite(location, opcode);
+            assert!(index < opcodes.len(), "ICE: rewritten opcode is outside of its sequence");
+
+            sequence_starts.push(self.byte_code.len());
+            new_locations.push(self.byte_code.len() + index);
+            self.byte_code.extend(opcodes);
+        }
+        // Labels can also point to the end of the bytecode.
+        sequence_starts.push(self.byte_code.len());
+
+        for location in self.labels.values_mut() {
+            *location = sequence_starts[*location];
+        }
+        for (location, _) in
+            self.unresolved_jumps.iter_mut().chain(self.unresolved_external_call_labels.iter_mut())
+        {
+            *location = new_locations[*location];
+        }
+        self.assert_messages = std::mem::take(&mut self.assert_messages)
+            .into_iter()
+            .map(|(location, message)| (new_locations[location], message))
+            .collect();
+        // Every opcode generated for an opcode shares its call stack.
+        self.locations = std::mem::take(&mut self.locations)
+            .into_iter()
+            .flat_map(|(location, call_stack)| {
+                (sequence_starts[location]..sequence_starts[location + 1])
+                    .map(move |new_location| (new_location, call_stack.clone()))
+            })
+            .collect();
+    }
+
     pub(crate) fn set_call_stack(&mut self, call_stack: CallStack) {
         self.call_stack = call_stack;
     }
 
-    pub(crate) fn add_assert_message_to_last_opcode(&mut self, message: String) {
+    pub(crate) fn add_assert_message_to_last_opcode(&mut self, message: AssertionPayload) {
         let position = self.index_of_next_opcode() - 1;
         self.assert_messages.insert(position, message);
     }
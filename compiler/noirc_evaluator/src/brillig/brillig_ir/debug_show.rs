@@ -30,6 +30,8 @@ impl DebugToString for RegisterIndex {
             "Stack".into()
         } else if *self == ReservedRegisters::previous_stack_pointer() {
             "PrevStack".into()
+        } else if *self == ReservedRegisters::free_list_pointer() {
+            "FreeList".into()
         } else {
             format!("R{}", self.to_usize())
         }
@@ -251,6 +253,34 @@ impl DebugShow {
         );
     }
 
+    /// Debug function for allocate_reusable_array_instruction
+    pub(crate) fn allocate_reusable_array_instruction(
+        &self,
+        pointer_register: RegisterIndex,
+        size_register: RegisterIndex,
+    ) {
+        debug_println!(
+            self.enable_debug_trace,
+            "  ALLOCATE_REUSABLE_ARRAY {} SIZE {}",
+            pointer_register,
+            size_register
+        );
+    }
+
+    /// Debug function for free_array_instruction
+    pub(crate) fn free_array_instruction(
+        &self,
+        pointer_register: RegisterIndex,
+        size_register: RegisterIndex,
+    ) {
+        debug_println!(
+            self.enable_debug_trace,
+            "  FREE_ARRAY {} SIZE {}",
+            pointer_register,
+            size_register
+        );
+    }
+
     /// Debug function for allocate_instruction
     pub(crate) fn allocate_instruction(&self, pointer_register: RegisterIndex) {
         debug_println!(self.enable_debug_trace, "  ALLOCATE {} ", pointer_register);
@@ -457,6 +487,15 @@ impl DebugShow {
                     result
                 );
             }
+            BlackBoxOp::Poseidon2Permutation { message, output, len } => {
+                debug_println!(
+                    self.enable_debug_trace,
+                    "  POSEIDON2_PERMUTATION {} {} -> {}",
+                    message,
+                    len,
+                    output
+                );
+            }
         }
     }
 
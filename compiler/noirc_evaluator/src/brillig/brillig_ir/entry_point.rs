@@ -23,6 +23,7 @@ impl BrilligContext {
             section_label: 0,
             next_section: 1,
             debug_show: DebugShow::new(false),
+            calling_convention_registers: 0,
         };
 
         context.entry_point_instruction(arguments);
@@ -82,12 +83,11 @@ impl BrilligContext {
             destination: ReservedRegisters::stack_pointer(),
             value: size_arguments_memory.into(),
         });
-        // Set the initial value of the previous stack pointer register
+        // Start with an empty free list
         self.push_opcode(BrilligOpcode::Const {
-            destination: ReservedRegisters::previous_stack_pointer(),
+            destination: ReservedRegisters::free_list_pointer(),
             value: 0_usize.into(),
         });
-
         // Deflatten the arrays
         for (parameter, assigned_variable) in arguments.iter().zip(&argument_variables) {
             if let BrilligParameter::Array(item_type, item_count) = parameter {
@@ -100,6 +100,13 @@ impl BrilligContext {
             }
         }
 
+        // Set the initial value of the previous stack pointer register to the stack pointer, as
+        // for any other call, so that a function freeing its memory keeps the arguments.
+        self.mov_instruction(
+            ReservedRegisters::previous_stack_pointer(),
+            ReservedRegisters::stack_pointer(),
+        );
+
         // Move the parameters to the first user defined registers, to follow function call convention.
         for (i, register) in
             argument_variables.into_iter().flat_map(|arg| arg.extract_registers()).enumerate()
@@ -109,7 +116,7 @@ impl BrilligContext {
     }
 
     /// Computes the size of a parameter if it was flattened
-    fn flattened_size(param: &BrilligParameter) -> usize {
+    pub(crate) fn flattened_size(param: &BrilligParameter) -> usize {
         match param {
             BrilligParameter::Simple => 1,
             BrilligParameter::Array(item_types, item_count) => {
@@ -267,7 +274,7 @@ impl BrilligContext {
     }
 
     // Flattens an array by recursively copying nested arrays and regular items.
-    fn flatten_array(
+    pub(crate) fn flatten_array(
         &mut self,
         item_type: &[BrilligParameter],
         item_count: usize,
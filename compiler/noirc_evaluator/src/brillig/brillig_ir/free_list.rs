@@ -0,0 +1,137 @@
+//! A free list of the memory of arrays which are no longer used, so that later arrays can reuse it.
+//!
+//! Memory is otherwise only allocated by moving the stack pointer past it, so a function building
+//! many temporary arrays, such as a hint sorting a large array, keeps all of them allocated until
+//! it returns. Arrays which are known to be unused after a certain point are freed instead, and
+//! the next array allocated with [BrilligContext::allocate_reusable_array_instruction] takes the
+//! memory freed last if it is large enough.
+//!
+//! The free list register points to the memory freed last, or is zero if the list is empty. The
+//! first two slots of each freed region hold its size and the pointer to the region freed before
+//! it. Regions too small to hold these two slots stay allocated, and so does a region starting at
+//! address zero, as it can't be told apart from the end of the list.
+//!
+//! Each function call starts with an empty free list and the list of the caller is restored once
+//! the call returns, so that the memory freed by one function is never reused by another one
+//! which might still access it.
+use acvm::acir::brillig::{BinaryIntOp, RegisterIndex};
+
+use super::{BrilligContext, ReservedRegisters};
+
+impl BrilligContext {
+    /// Allocates an array of `size_register` slots and stores the pointer to it in
+    /// `pointer_register`, reusing the memory freed last if it holds at least as many slots.
+    pub(crate) fn allocate_reusable_array_instruction(
+        &mut self,
+        pointer_register: RegisterIndex,
+        size_register: RegisterIndex,
+    ) {
+        self.debug_show.allocate_reusable_array_instruction(pointer_register, size_register);
+        let free_list = ReservedRegisters::free_list_pointer();
+
+        let list_is_empty = self.allocate_register();
+        let zero = self.make_constant(0_usize.into());
+        self.memory_op(free_list, zero, list_is_empty, BinaryIntOp::Equals);
+        self.deallocate_register(zero);
+
+        // The size of the freed region can only be read once the list is known to be non-empty.
+        self.branch_instruction(list_is_empty, |ctx, list_is_empty| {
+            if list_is_empty {
+                ctx.allocate_array_instruction(pointer_register, size_register);
+                return;
+            }
+
+            let freed_size = ctx.allocate_register();
+            ctx.load_instruction(freed_size, free_list);
+            let too_small = ctx.allocate_register();
+            ctx.memory_op(freed_size, size_register, too_small, BinaryIntOp::LessThan);
+
+            ctx.branch_instruction(too_small, |ctx, too_small| {
+                if too_small {
+                    ctx.allocate_array_instruction(pointer_register, size_register);
+                } else {
+                    // Pop the region off the list
+                    ctx.mov_instruction(pointer_register, free_list);
+                    let next_pointer = ctx.allocate_register();
+                    ctx.usize_op(pointer_register, next_pointer, BinaryIntOp::Add, 1);
+                    ctx.load_instruction(free_list, next_pointer);
+                    ctx.deallocate_register(next_pointer);
+                }
+            });
+
+            ctx.deallocate_register(freed_size);
+            ctx.deallocate_register(too_small);
+        });
+
+        self.deallocate_register(list_is_empty);
+    }
+
+    /// Adds the memory of the array of `size_register` slots at `pointer_register` to the free
+    /// list. The array must not be accessed anymore, through this or any other pointer to it.
+    pub(crate) fn free_array_instruction(
+        &mut self,
+        pointer_register: RegisterIndex,
+        size_register: RegisterIndex,
+    ) {
+        self.debug_show.free_array_instruction(pointer_register, size_register);
+        let free_list = ReservedRegisters::free_list_pointer();
+
+        let too_small = self.allocate_register();
+        self.usize_op(size_register, too_small, BinaryIntOp::LessThan, 2);
+
+        self.branch_instruction(too_small, |ctx, too_small| {
+            if !too_small {
+                // Push the region onto the list
+                ctx.store_instruction(pointer_register, size_register);
+                let next_pointer = ctx.allocate_register();
+                ctx.usize_op(pointer_register, next_pointer, BinaryIntOp::Add, 1);
+                ctx.store_instruction(next_pointer, free_list);
+                ctx.deallocate_register(next_pointer);
+                ctx.mov_instruction(free_list, pointer_register);
+            }
+        });
+
+        self.deallocate_register(too_small);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::brillig_vm::brillig::{RegisterIndex, Value};
+
+    use crate::brillig::brillig_ir::{
+        artifact::BrilligParameter,
+        tests::{create_and_run_vm, create_context, create_entry_point_bytecode},
+    };
+
+    #[test]
+    fn reuses_freed_arrays() {
+        let mut context = create_context();
+
+        let first = context.allocate_register();
+        let second = context.allocate_register();
+        let third = context.allocate_register();
+        let scratch = context.allocate_register();
+        let three = context.make_constant(3_usize.into());
+        let two = context.make_constant(2_usize.into());
+
+        // The array at address zero can't be freed, so the arrays being tested come after it.
+        context.allocate_reusable_array_instruction(scratch, three);
+        context.allocate_reusable_array_instruction(first, three);
+        context.free_array_instruction(first, three);
+        // The freed array is large enough to hold the second one
+        context.allocate_reusable_array_instruction(second, two);
+        // The free list is now empty again
+        context.allocate_reusable_array_instruction(third, two);
+
+        context.return_instruction(&[first, second, third]);
+
+        let returns = vec![BrilligParameter::Simple; 3];
+        let bytecode = create_entry_point_bytecode(context, vec![], returns).byte_code;
+        let vm = create_and_run_vm(vec![], vec![], &bytecode);
+
+        assert_eq!(vm.get_registers().get(RegisterIndex(0)), Value::from(3_usize));
+        assert_eq!(vm.get_registers().get(RegisterIndex(1)), Value::from(3_usize));
+        assert_eq!(vm.get_registers().get(RegisterIndex(2)), Value::from(6_usize));
+    }
+}
@@ -0,0 +1,356 @@
+//! Peephole optimizations over linked Brillig bytecode.
+//!
+//! Code generation favours simplicity over the quality of the bytecode it produces, so the linked
+//! bytecode contains many opcodes which can be simplified by looking at them in isolation or
+//! alongside their neighbours:
+//! - Jumps to another jump are redirected to the final destination of the chain.
+//! - Binary operations whose operands are both known constants are replaced by a constant.
+//!   Constants are only tracked within a straight-line sequence of opcodes, i.e. until the next
+//!   jump target or opcode which doesn't continue to the next one.
+//! - Moves from a register to itself, moves undoing the previous move and jumps to the next
+//!   opcode are removed.
+//!
+//! Removing opcodes shifts the location of the following ones, so all jump locations, call stacks
+//! and assert messages are updated accordingly.
+use std::collections::BTreeMap;
+
+use acvm::{
+    acir::brillig::{BinaryIntOp, Opcode as BrilligOpcode, RegisterIndex, Value},
+    brillig_vm::{evaluate_binary_bigint_op, evaluate_binary_field_op},
+    FieldElement,
+};
+use fxhash::{FxHashMap as HashMap, FxHashSet as HashSet};
+use num_bigint::BigUint;
+
+use super::{
+    artifact::{GeneratedBrillig, OpcodeLocation},
+    register_allocator::{for_each_register, Access},
+};
+
+impl GeneratedBrillig {
+    /// Applies peephole optimizations to the bytecode.
+    ///
+    /// See the [`peephole`][self] module for more information.
+    pub(crate) fn optimize(mut self) -> GeneratedBrillig {
+        collapse_jump_chains(&mut self.byte_code);
+        fold_constant_operations(&mut self.byte_code);
+        let redundant_opcodes = find_redundant_opcodes(&self.byte_code);
+        self.remove_opcodes(&redundant_opcodes);
+        self
+    }
+
+    /// Removes the opcodes flagged in `removed`, updating every location pointing into the bytecode.
+    fn remove_opcodes(&mut self, removed: &[bool]) {
+        if !removed.contains(&true) {
+            return;
+        }
+
+        // The new location of each opcode. A removed opcode is mapped to the location of the next
+        // opcode which is kept, which is where execution would have continued.
+        let mut new_locations = Vec::with_capacity(removed.len() + 1);
+        let mut kept_opcodes = 0;
+        for is_removed in removed {
+            new_locations.push(kept_opcodes);
+            if !is_removed {
+                kept_opcodes += 1;
+            }
+        }
+        new_locations.push(kept_opcodes);
+
+        let old_byte_code = std::mem::take(&mut self.byte_code);
+        for (mut opcode, is_removed) in old_byte_code.into_iter().zip(removed) {
+            if *is_removed {
+                continue;
+            }
+            if let Some(location) = jump_location_mut(&mut opcode) {
+                *location = new_locations[*location];
+            }
+            self.byte_code.push(opcode);
+        }
+
+        self.locations = relocate(std::mem::take(&mut self.locations), removed, &new_locations);
+        self.assert_messages =
+            relocate(std::mem::take(&mut self.assert_messages), removed, &new_locations);
+    }
+}
+
+/// Moves the entries of a map keyed by opcode location to the new locations of their opcodes.
+fn relocate<T>(
+    map: BTreeMap<OpcodeLocation, T>,
+    removed: &[bool],
+    new_locations: &[OpcodeLocation],
+) -> BTreeMap<OpcodeLocation, T> {
+    map.into_iter()
+        .filter(|(location, _)| !removed[*location])
+        .map(|(location, value)| (new_locations[location], value))
+        .collect()
+}
+
+/// Returns the location an opcode jumps or calls to, if any.
+fn jump_location_mut(opcode: &mut BrilligOpcode) -> Option<&mut usize> {
+    match opcode {
+        BrilligOpcode::Jump { location }
+        | BrilligOpcode::JumpIf { location, .. }
+        | BrilligOpcode::JumpIfNot { location, .. }
+        | BrilligOpcode::Call { location } => Some(location),
+        _ => None,
+    }
+}
+
+/// Returns every location which can be reached other than by continuing from the previous opcode.
+fn jump_targets(byte_code: &[BrilligOpcode]) -> HashSet<OpcodeLocation> {
+    byte_code
+        .iter()
+        .filter_map(|opcode| match opcode {
+            BrilligOpcode::Jump { location }
+            | BrilligOpcode::JumpIf { location, .. }
+            | BrilligOpcode::JumpIfNot { location, .. }
+            | BrilligOpcode::Call { location } => Some(*location),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Redirects every jump to a `Jump` opcode to the location that jump leads to.
+fn collapse_jump_chains(byte_code: &mut [BrilligOpcode]) {
+    for index in 0..byte_code.len() {
+        let destination = match &byte_code[index] {
+            BrilligOpcode::Jump { location }
+            | BrilligOpcode::JumpIf { location, .. }
+            | BrilligOpcode::JumpIfNot { location, .. } => final_destination(byte_code, *location),
+            _ => continue,
+        };
+        if let Some(location) = jump_location_mut(&mut byte_code[index]) {
+            *location = destination;
+        }
+    }
+}
+
+/// Follows the chain of `Jump` opcodes starting at `location`.
+fn final_destination(byte_code: &[BrilligOpcode], mut location: OpcodeLocation) -> OpcodeLocation {
+    // A chain can't be longer than the bytecode unless it loops forever, in which case
+    // jumping to any location within the loop is equivalent.
+    for _ in 0..byte_code.len() {
+        match byte_code.get(location) {
+            Some(BrilligOpcode::Jump { location: next }) if *next != location => location = *next,
+            _ => break,
+        }
+    }
+    location
+}
+
+/// Replaces binary operations on registers holding known constants by the constant result.
+fn fold_constant_operations(byte_code: &mut [BrilligOpcode]) {
+    let jump_targets = jump_targets(byte_code);
+    let mut constants: HashMap<RegisterIndex, Value> = HashMap::default();
+
+    for (location, opcode) in byte_code.iter_mut().enumerate() {
+        if jump_targets.contains(&location) {
+            constants.clear();
+        }
+
+        if let Some(folded) = fold_opcode(opcode, &constants) {
+            *opcode = folded;
+        }
+
+        match &*opcode {
+            BrilligOpcode::Const { destination, value } => {
+                constants.insert(*destination, *value);
+            }
+            BrilligOpcode::Mov { destination, source } => match constants.get(source).copied() {
+                Some(value) => {
+                    constants.insert(*destination, value);
+                }
+                None => {
+                    constants.remove(destination);
+                }
+            },
+            // Calls may overwrite any register, and the opcodes which don't continue to the
+            // next one end the sequence of opcodes.
+            BrilligOpcode::Call { .. }
+            | BrilligOpcode::Jump { .. }
+            | BrilligOpcode::Return
+            | BrilligOpcode::Stop
+            | BrilligOpcode::Trap => {
+                constants.clear();
+            }
+            _ => {
+                for_each_register(&mut opcode.clone(), &mut |register, access| {
+                    if access == Access::Write {
+                        constants.remove(&*register);
+                    }
+                });
+            }
+        }
+    }
+}
+
+/// Returns the constant equivalent to a binary operation on known constants.
+fn fold_opcode(
+    opcode: &BrilligOpcode,
+    constants: &HashMap<RegisterIndex, Value>,
+) -> Option<BrilligOpcode> {
+    match opcode {
+        BrilligOpcode::BinaryFieldOp { destination, op, lhs, rhs } => {
+            let lhs = constants.get(lhs)?.to_field();
+            let rhs = constants.get(rhs)?.to_field();
+            let value = evaluate_binary_field_op(op, lhs, rhs).into();
+            Some(BrilligOpcode::Const { destination: *destination, value })
+        }
+        BrilligOpcode::BinaryIntOp { destination, op, bit_size, lhs, rhs } => {
+            let lhs = BigUint::from_bytes_be(&constants.get(lhs)?.to_field().to_be_bytes());
+            let rhs = BigUint::from_bytes_be(&constants.get(rhs)?.to_field().to_be_bytes());
+
+            // Leave any shift the VM couldn't evaluate to fail at runtime.
+            if matches!(op, BinaryIntOp::Shl | BinaryIntOp::Shr)
+                && (*bit_size > 128 || rhs.bits() > 128)
+            {
+                return None;
+            }
+            // Divisions by zero are also left to fail at runtime.
+            let result = evaluate_binary_bigint_op(op, lhs, rhs, *bit_size).ok()?;
+            let value = FieldElement::from_be_bytes_reduce(&result.to_bytes_be()).into();
+            Some(BrilligOpcode::Const { destination: *destination, value })
+        }
+        _ => None,
+    }
+}
+
+/// Flags the opcodes which have no effect on the execution of the bytecode.
+fn find_redundant_opcodes(byte_code: &[BrilligOpcode]) -> Vec<bool> {
+    let jump_targets = jump_targets(byte_code);
+    let mut removed = vec![false; byte_code.len()];
+
+    for (location, opcode) in byte_code.iter().enumerate() {
+        let is_redundant = match opcode {
+            BrilligOpcode::Mov { destination, source } if destination == source => true,
+            // The previous opcode already made both registers equal, unless we jumped here.
+            BrilligOpcode::Mov { destination, source }
+                if location > 0 && !removed[location - 1] && !jump_targets.contains(&location) =>
+            {
+                byte_code[location - 1]
+                    == BrilligOpcode::Mov { destination: *source, source: *destination }
+            }
+            BrilligOpcode::Jump { location: target } => *target == location + 1,
+            _ => false,
+        };
+        removed[location] = is_redundant;
+    }
+
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use acvm::acir::brillig::{BinaryFieldOp, BinaryIntOp, Opcode as BrilligOpcode, RegisterIndex};
+
+    use crate::{brillig::brillig_ir::artifact::GeneratedBrillig, ssa::ir::dfg::CallStack};
+
+    fn generated_brillig(byte_code: Vec<BrilligOpcode>) -> GeneratedBrillig {
+        GeneratedBrillig { byte_code, locations: BTreeMap::new(), assert_messages: BTreeMap::new() }
+    }
+
+    #[test]
+    fn remove_redundant_opcodes_and_relocate_jumps() {
+        let r0 = RegisterIndex::from(0);
+        let r1 = RegisterIndex::from(1);
+        let byte_code = vec![
+            /* 0 */ BrilligOpcode::Mov { destination: r0, source: r0 },
+            /* 1 */ BrilligOpcode::Jump { location: 2 },
+            /* 2 */ BrilligOpcode::Mov { destination: r1, source: r0 },
+            /* 3 */ BrilligOpcode::Mov { destination: r0, source: r1 },
+            /* 4 */ BrilligOpcode::JumpIf { condition: r0, location: 6 },
+            /* 5 */ BrilligOpcode::Trap,
+            /* 6 */ BrilligOpcode::Stop,
+        ];
+        let mut brillig = generated_brillig(byte_code);
+        brillig.assert_messages.insert(5, "assertion".to_string().into());
+        brillig.locations.insert(4, CallStack::new());
+
+        let brillig = brillig.optimize();
+
+        assert_eq!(
+            brillig.byte_code,
+            vec![
+                BrilligOpcode::Mov { destination: r1, source: r0 },
+                BrilligOpcode::JumpIf { condition: r0, location: 3 },
+                BrilligOpcode::Trap,
+                BrilligOpcode::Stop,
+            ]
+        );
+        assert_eq!(brillig.assert_messages.keys().collect::<Vec<_>>(), vec![&2]);
+        assert_eq!(brillig.locations.keys().collect::<Vec<_>>(), vec![&1]);
+    }
+
+    #[test]
+    fn fold_operations_on_constants() {
+        let r0 = RegisterIndex::from(0);
+        let r1 = RegisterIndex::from(1);
+        let r2 = RegisterIndex::from(2);
+        let byte_code = vec![
+            BrilligOpcode::Const { destination: r0, value: 6_u128.into() },
+            BrilligOpcode::Mov { destination: r1, source: r0 },
+            BrilligOpcode::BinaryIntOp {
+                destination: r2,
+                op: BinaryIntOp::Mul,
+                bit_size: 32,
+                lhs: r0,
+                rhs: r1,
+            },
+            // Division by zero must be left to fail at runtime.
+            BrilligOpcode::Const { destination: r1, value: 0_u128.into() },
+            BrilligOpcode::BinaryIntOp {
+                destination: r2,
+                op: BinaryIntOp::UnsignedDiv,
+                bit_size: 32,
+                lhs: r0,
+                rhs: r1,
+            },
+            // r0 is unknown after the jump target.
+            BrilligOpcode::BinaryFieldOp {
+                destination: r2,
+                op: BinaryFieldOp::Add,
+                lhs: r0,
+                rhs: r1,
+            },
+            BrilligOpcode::JumpIf { condition: r2, location: 5 },
+            BrilligOpcode::Stop,
+        ];
+
+        let brillig = generated_brillig(byte_code.clone()).optimize();
+
+        let mut expected = byte_code;
+        expected[2] = BrilligOpcode::Const { destination: r2, value: 36_u128.into() };
+        assert_eq!(brillig.byte_code, expected);
+    }
+
+    #[test]
+    fn collapse_jump_chains() {
+        let r0 = RegisterIndex::from(0);
+        let byte_code = vec![
+            BrilligOpcode::JumpIf { condition: r0, location: 2 },
+            BrilligOpcode::Trap,
+            BrilligOpcode::Jump { location: 3 },
+            BrilligOpcode::Jump { location: 5 },
+            BrilligOpcode::Trap,
+            BrilligOpcode::Stop,
+        ];
+
+        let brillig = generated_brillig(byte_code).optimize();
+
+        // The jumps in the chain are kept as they may be reached from elsewhere.
+        assert_eq!(
+            brillig.byte_code,
+            vec![
+                BrilligOpcode::JumpIf { condition: r0, location: 5 },
+                BrilligOpcode::Trap,
+                BrilligOpcode::Jump { location: 5 },
+                BrilligOpcode::Jump { location: 5 },
+                BrilligOpcode::Trap,
+                BrilligOpcode::Stop,
+            ]
+        );
+    }
+}
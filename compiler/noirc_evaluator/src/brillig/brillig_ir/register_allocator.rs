@@ -0,0 +1,711 @@
+//! Linear scan register allocation for Brillig functions.
+//!
+//! While generating the bytecode of a function, registers are handed out on demand and are only
+//! reused once explicitly deallocated, so large functions can end up using a very large number
+//! of registers. Once the bytecode of a function has been generated, this pass reassigns all of
+//! its registers into a bounded set of registers, following the approach described in
+//! "Linear Scan Register Allocation" by Poletto and Sarkar:
+//! - Split the bytecode into blocks and compute the registers live at the start of each block.
+//!   The interval of each register spans from the first to the last opcode where it is live.
+//! - Walk the intervals in order of their start, assigning a free register to each of them.
+//!   When no register is free, whichever active interval ends last is spilled to memory.
+//! - Rewrite the bytecode with the assigned registers. Spilled registers are loaded into scratch
+//!   registers before each opcode reading them and stored back after each opcode writing them.
+//!
+//! The reserved registers and the registers used to pass arguments and return values keep their
+//! index. A function call may overwrite any other register, so registers live across a call are
+//! always spilled.
+//!
+//! Spilled registers are stored in a spill area, which is allocated on the stack when entering
+//! the function. The pointer to this area is pushed to the stack before each call and loaded back
+//! from the previous stack pointer once the call returns.
+use std::collections::BTreeSet;
+
+use acvm::acir::brillig::{
+    BinaryIntOp, BlackBoxOp, HeapArray, HeapVector, Opcode as BrilligOpcode, RegisterIndex,
+    RegisterOrMemory, Value,
+};
+use fxhash::{FxHashMap as HashMap, FxHashSet as HashSet};
+use iter_extended::vecmap;
+
+use super::{
+    artifact::BrilligArtifact, BrilligContext, ReservedRegisters,
+    BRILLIG_MEMORY_ADDRESSING_BIT_SIZE,
+};
+
+/// The maximum number of registers assigned to the values of a function.
+/// Any register which doesn't fit is spilled to memory.
+const MAX_ALLOCATED_REGISTERS: usize = 1024;
+
+impl BrilligContext {
+    /// Reassigns the registers used by the bytecode generated so far, so that at most
+    /// [MAX_ALLOCATED_REGISTERS] registers hold its values and the rest are spilled to memory.
+    ///
+    /// This must only be called once all of the bytecode of the function has been generated.
+    pub(crate) fn allocate_registers(&mut self) {
+        self.allocate_registers_with_limit(MAX_ALLOCATED_REGISTERS);
+    }
+
+    fn allocate_registers_with_limit(&mut self, max_registers: usize) {
+        let fixed_registers = ReservedRegisters::len() + self.calling_convention_registers;
+        allocate_registers(&mut self.obj, fixed_registers, max_registers);
+    }
+}
+
+/// Whether an opcode reads or writes a register.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Access {
+    Read,
+    Write,
+}
+
+/// The registers accessed by an opcode which are subject to allocation.
+#[derive(Debug, Default)]
+struct OpcodeRegisters {
+    reads: Vec<RegisterIndex>,
+    writes: Vec<RegisterIndex>,
+}
+
+/// A sequence of opcodes which can only be entered through its first opcode
+/// and only be exited through its last opcode.
+#[derive(Debug)]
+struct Block {
+    start: usize,
+    end: usize,
+    successors: Vec<usize>,
+}
+
+/// The range of opcodes in which a register is live.
+#[derive(Debug)]
+struct LiveInterval {
+    register: RegisterIndex,
+    start: usize,
+    end: usize,
+    crosses_call: bool,
+}
+
+#[derive(Debug, Default)]
+struct Allocation {
+    /// The index within the allocated registers assigned to each register.
+    registers: HashMap<RegisterIndex, usize>,
+    /// The slot within the spill area assigned to each spilled register.
+    spill_slots: HashMap<RegisterIndex, usize>,
+}
+
+impl Allocation {
+    fn spill(&mut self, register: RegisterIndex) {
+        let slot = self.spill_slots.len();
+        self.spill_slots.insert(register, slot);
+    }
+}
+
+/// Reassigns every register with an index of at least `fixed_registers` within the artifact.
+fn allocate_registers(
+    artifact: &mut BrilligArtifact,
+    fixed_registers: usize,
+    max_registers: usize,
+) {
+    let opcode_registers = vecmap(&artifact.byte_code, |opcode| {
+        let mut registers = OpcodeRegisters::default();
+        for_each_register(&mut opcode.clone(), &mut |register, access| {
+            if register.to_usize() >= fixed_registers {
+                match access {
+                    Access::Read => registers.reads.push(*register),
+                    Access::Write => registers.writes.push(*register),
+                }
+            }
+        });
+        registers
+    });
+
+    let jump_targets: HashMap<usize, usize> = artifact.jump_targets().into_iter().collect();
+    let blocks = split_into_blocks(&artifact.byte_code, &jump_targets);
+    let intervals = compute_live_intervals(&artifact.byte_code, &blocks, &opcode_registers);
+    let allocation = linear_scan(intervals, max_registers);
+
+    if allocation.spill_slots.is_empty() {
+        for opcode in artifact.byte_code.iter_mut() {
+            for_each_register(opcode, &mut |register, _| {
+                if let Some(index) = allocation.registers.get(&*register) {
+                    *register = RegisterIndex::from(fixed_registers + index);
+                }
+            });
+        }
+        return;
+    }
+
+    // The prologue allocating the spill area must only run once when entering the function.
+    assert!(
+        jump_targets.values().all(|target| *target != 0),
+        "ICE: the start of a function with spilled registers must not be jumped to"
+    );
+
+    // The spill pointer and the scratch registers are placed between the fixed registers and
+    // the allocated ones.
+    let spill_pointer = RegisterIndex::from(fixed_registers);
+    let address_register = RegisterIndex::from(fixed_registers + 1);
+    let first_scratch_register = fixed_registers + 2;
+    let scratch_registers_count = opcode_registers
+        .iter()
+        .map(|registers| spilled_registers(registers, &allocation).len())
+        .max()
+        .unwrap_or_default();
+    let first_allocated_register = first_scratch_register + scratch_registers_count;
+    let spill_area_size = allocation.spill_slots.len();
+
+    artifact.rewrite_opcodes(|location, mut opcode| {
+        let mut before = Vec::new();
+        let mut after = Vec::new();
+
+        if location == 0 {
+            before.extend(allocate_spill_area(spill_pointer, address_register, spill_area_size));
+        }
+
+        let registers = &opcode_registers[location];
+        let spilled = spilled_registers(registers, &allocation);
+        for (index, spilled_register) in spilled.iter().enumerate() {
+            let scratch_register = RegisterIndex::from(first_scratch_register + index);
+            let slot = allocation.spill_slots[spilled_register];
+            if registers.reads.contains(spilled_register) {
+                before.extend(load_spilled_register(scratch_register, slot, spill_pointer));
+            }
+            if registers.writes.contains(spilled_register) {
+                after.extend(store_spilled_register(
+                    scratch_register,
+                    slot,
+                    spill_pointer,
+                    address_register,
+                ));
+            }
+        }
+
+        for_each_register(&mut opcode, &mut |register, _| {
+            if let Some(index) = allocation.registers.get(&*register) {
+                *register = RegisterIndex::from(first_allocated_register + index);
+            } else if let Some(index) = spilled.iter().position(|candidate| candidate == &*register)
+            {
+                *register = RegisterIndex::from(first_scratch_register + index);
+            }
+        });
+
+        if matches!(opcode, BrilligOpcode::Call { .. }) {
+            before.extend(save_spill_pointer(spill_pointer, address_register));
+            // The caller sets the previous stack pointer to the stack pointer right before calling,
+            // which is where the spill pointer was saved.
+            after.insert(
+                0,
+                BrilligOpcode::Load {
+                    destination: spill_pointer,
+                    source_pointer: ReservedRegisters::previous_stack_pointer(),
+                },
+            );
+        }
+
+        let index = before.len();
+        before.push(opcode);
+        before.extend(after);
+        (before, index)
+    });
+}
+
+/// Returns the registers of an opcode which have been spilled, without duplicates.
+fn spilled_registers(registers: &OpcodeRegisters, allocation: &Allocation) -> Vec<RegisterIndex> {
+    let mut spilled = Vec::new();
+    for register in registers.reads.iter().chain(&registers.writes) {
+        if allocation.spill_slots.contains_key(register) && !spilled.contains(register) {
+            spilled.push(*register);
+        }
+    }
+    spilled
+}
+
+/// Splits the bytecode into blocks, starting new blocks at jump targets and after
+/// any opcode that doesn't continue to the next one.
+fn split_into_blocks(
+    byte_code: &[BrilligOpcode],
+    jump_targets: &HashMap<usize, usize>,
+) -> Vec<Block> {
+    let mut block_starts = BTreeSet::from([0]);
+    for (location, opcode) in byte_code.iter().enumerate() {
+        if let Some(target) = jump_targets.get(&location) {
+            block_starts.insert(*target);
+        }
+        if ends_block(opcode) {
+            block_starts.insert(location + 1);
+        }
+    }
+    let block_starts: Vec<usize> =
+        block_starts.into_iter().filter(|start| *start < byte_code.len()).collect();
+
+    let block_of = |location: usize| {
+        block_starts.binary_search(&location).expect("ICE: jump target is not a block start")
+    };
+    let jump_target_block =
+        |location: usize| block_of(*jump_targets.get(&location).expect("ICE: jump has no label"));
+
+    vecmap(block_starts.iter().enumerate(), |(index, start)| {
+        let end = block_starts.get(index + 1).copied().unwrap_or(byte_code.len());
+        let last = end - 1;
+        let next_block = (end < byte_code.len()).then_some(index + 1);
+
+        let successors = match &byte_code[last] {
+            BrilligOpcode::Jump { .. } => vec![jump_target_block(last)],
+            BrilligOpcode::JumpIf { .. } | BrilligOpcode::JumpIfNot { .. } => {
+                let mut successors = vec![jump_target_block(last)];
+                successors.extend(next_block);
+                successors
+            }
+            BrilligOpcode::Stop | BrilligOpcode::Trap | BrilligOpcode::Return => Vec::new(),
+            _ => next_block.into_iter().collect(),
+        };
+
+        Block { start: *start, end, successors }
+    })
+}
+
+fn ends_block(opcode: &BrilligOpcode) -> bool {
+    matches!(
+        opcode,
+        BrilligOpcode::Jump { .. }
+            | BrilligOpcode::JumpIf { .. }
+            | BrilligOpcode::JumpIfNot { .. }
+            | BrilligOpcode::Stop
+            | BrilligOpcode::Trap
+            | BrilligOpcode::Return
+    )
+}
+
+/// Computes the live interval of every register, sorted by the start of the interval.
+fn compute_live_intervals(
+    byte_code: &[BrilligOpcode],
+    blocks: &[Block],
+    opcode_registers: &[OpcodeRegisters],
+) -> Vec<LiveInterval> {
+    // The registers read before being written and the registers written in each block.
+    let (block_reads, block_writes): (Vec<_>, Vec<_>) = blocks
+        .iter()
+        .map(|block| {
+            let mut reads: HashSet<RegisterIndex> = HashSet::default();
+            let mut writes: HashSet<RegisterIndex> = HashSet::default();
+            for registers in &opcode_registers[block.start..block.end] {
+                for register in &registers.reads {
+                    if !writes.contains(register) {
+                        reads.insert(*register);
+                    }
+                }
+                writes.extend(registers.writes.iter().copied());
+            }
+            (reads, writes)
+        })
+        .unzip();
+
+    let mut live_in: Vec<HashSet<RegisterIndex>> = vec![HashSet::default(); blocks.len()];
+    let live_out = |live_in: &[HashSet<RegisterIndex>], block: &Block| {
+        let mut live: HashSet<RegisterIndex> = HashSet::default();
+        for successor in &block.successors {
+            live.extend(live_in[*successor].iter().copied());
+        }
+        live
+    };
+
+    // Blocks are mostly laid out in the order they are executed, so iterating backwards
+    // converges quickly. Loops require further iterations.
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (index, block) in blocks.iter().enumerate().rev() {
+            let mut block_live_in = live_out(&live_in, block);
+            block_live_in.retain(|register| !block_writes[index].contains(register));
+            block_live_in.extend(block_reads[index].iter().copied());
+
+            if block_live_in != live_in[index] {
+                live_in[index] = block_live_in;
+                changed = true;
+            }
+        }
+    }
+
+    let mut intervals: HashMap<RegisterIndex, LiveInterval> = HashMap::default();
+
+    for (index, block) in blocks.iter().enumerate() {
+        for register in &live_in[index] {
+            extend_interval(&mut intervals, *register, block.start);
+        }
+
+        let mut live = live_out(&live_in, block);
+        for register in &live {
+            extend_interval(&mut intervals, *register, block.end - 1);
+        }
+
+        for location in (block.start..block.end).rev() {
+            let registers = &opcode_registers[location];
+            for register in &registers.writes {
+                extend_interval(&mut intervals, *register, location);
+                live.remove(register);
+            }
+            if matches!(byte_code[location], BrilligOpcode::Call { .. }) {
+                for register in &live {
+                    extend_interval(&mut intervals, *register, location).crosses_call = true;
+                }
+            }
+            for register in &registers.reads {
+                extend_interval(&mut intervals, *register, location);
+                live.insert(*register);
+            }
+        }
+    }
+
+    let mut intervals: Vec<_> = intervals.into_values().collect();
+    intervals.sort_by_key(|interval| (interval.start, interval.register));
+    intervals
+}
+
+/// Extends the interval of the register so that it includes the given location.
+fn extend_interval(
+    intervals: &mut HashMap<RegisterIndex, LiveInterval>,
+    register: RegisterIndex,
+    location: usize,
+) -> &mut LiveInterval {
+    let interval = intervals.entry(register).or_insert(LiveInterval {
+        register,
+        start: location,
+        end: location,
+        crosses_call: false,
+    });
+    interval.start = interval.start.min(location);
+    interval.end = interval.end.max(location);
+    interval
+}
+
+/// Assigns up to `max_registers` registers to the intervals, spilling the rest.
+fn linear_scan(intervals: Vec<LiveInterval>, max_registers: usize) -> Allocation {
+    let mut allocation = Allocation::default();
+    let mut free_registers: BTreeSet<usize> = (0..max_registers).collect();
+    // The intervals currently holding a register, ordered by their end.
+    let mut active: BTreeSet<(usize, RegisterIndex)> = BTreeSet::new();
+
+    for interval in intervals {
+        while let Some(&(end, register)) = active.first() {
+            // An opcode may write a register before it finishes reading the others,
+            // so intervals ending where the current one starts are still considered active.
+            if end >= interval.start {
+                break;
+            }
+            active.pop_first();
+            free_registers.insert(allocation.registers[&register]);
+        }
+
+        if interval.crosses_call {
+            allocation.spill(interval.register);
+        } else if let Some(index) = free_registers.pop_first() {
+            allocation.registers.insert(interval.register, index);
+            active.insert((interval.end, interval.register));
+        } else {
+            match active.last().copied() {
+                Some((end, register)) if end > interval.end => {
+                    // Take the register of the interval which is live for the longest.
+                    active.remove(&(end, register));
+                    let index = allocation.registers.remove(&register).expect("ICE: not active");
+                    allocation.spill(register);
+                    allocation.registers.insert(interval.register, index);
+                    active.insert((interval.end, interval.register));
+                }
+                _ => allocation.spill(interval.register),
+            }
+        }
+    }
+
+    allocation
+}
+
+/// Allocates the spill area on the stack and points the spill pointer to its start.
+fn allocate_spill_area(
+    spill_pointer: RegisterIndex,
+    address_register: RegisterIndex,
+    size: usize,
+) -> Vec<BrilligOpcode> {
+    vec![
+        BrilligOpcode::Mov {
+            destination: spill_pointer,
+            source: ReservedRegisters::stack_pointer(),
+        },
+        BrilligOpcode::Const { destination: address_register, value: Value::from(size) },
+        memory_op(
+            ReservedRegisters::stack_pointer(),
+            address_register,
+            ReservedRegisters::stack_pointer(),
+            BinaryIntOp::Add,
+        ),
+        // Writing to the last slot grows the memory to cover the whole area, so reading a slot
+        // which hasn't been written yet doesn't read out of bounds.
+        BrilligOpcode::Const { destination: address_register, value: Value::from(1_usize) },
+        memory_op(
+            ReservedRegisters::stack_pointer(),
+            address_register,
+            address_register,
+            BinaryIntOp::Sub,
+        ),
+        BrilligOpcode::Store { destination_pointer: address_register, source: address_register },
+    ]
+}
+
+/// Pushes the spill pointer to the stack so that it can be restored after a call.
+fn save_spill_pointer(
+    spill_pointer: RegisterIndex,
+    address_register: RegisterIndex,
+) -> Vec<BrilligOpcode> {
+    vec![
+        BrilligOpcode::Store {
+            destination_pointer: ReservedRegisters::stack_pointer(),
+            source: spill_pointer,
+        },
+        BrilligOpcode::Const { destination: address_register, value: Value::from(1_usize) },
+        memory_op(
+            ReservedRegisters::stack_pointer(),
+            address_register,
+            ReservedRegisters::stack_pointer(),
+            BinaryIntOp::Add,
+        ),
+    ]
+}
+
+/// Loads the value in the given slot of the spill area into `register`.
+fn load_spilled_register(
+    register: RegisterIndex,
+    slot: usize,
+    spill_pointer: RegisterIndex,
+) -> Vec<BrilligOpcode> {
+    vec![
+        BrilligOpcode::Const { destination: register, value: Value::from(slot) },
+        memory_op(spill_pointer, register, register, BinaryIntOp::Add),
+        BrilligOpcode::Load { destination: register, source_pointer: register },
+    ]
+}
+
+/// Stores the value of `register` into the given slot of the spill area.
+fn store_spilled_register(
+    register: RegisterIndex,
+    slot: usize,
+    spill_pointer: RegisterIndex,
+    address_register: RegisterIndex,
+) -> Vec<BrilligOpcode> {
+    vec![
+        BrilligOpcode::Const { destination: address_register, value: Value::from(slot) },
+        memory_op(spill_pointer, address_register, address_register, BinaryIntOp::Add),
+        BrilligOpcode::Store { destination_pointer: address_register, source: register },
+    ]
+}
+
+fn memory_op(
+    lhs: RegisterIndex,
+    rhs: RegisterIndex,
+    destination: RegisterIndex,
+    op: BinaryIntOp,
+) -> BrilligOpcode {
+    BrilligOpcode::BinaryIntOp {
+        destination,
+        op,
+        bit_size: BRILLIG_MEMORY_ADDRESSING_BIT_SIZE,
+        lhs,
+        rhs,
+    }
+}
+
+/// Calls `f` on each register of the opcode along with how the opcode accesses it.
+pub(super) fn for_each_register(
+    opcode: &mut BrilligOpcode,
+    f: &mut impl FnMut(&mut RegisterIndex, Access),
+) {
+    match opcode {
+        BrilligOpcode::BinaryFieldOp { destination, lhs, rhs, .. }
+        | BrilligOpcode::BinaryIntOp { destination, lhs, rhs, .. } => {
+            f(lhs, Access::Read);
+            f(rhs, Access::Read);
+            f(destination, Access::Write);
+        }
+        BrilligOpcode::JumpIfNot { condition, .. } | BrilligOpcode::JumpIf { condition, .. } => {
+            f(condition, Access::Read);
+        }
+        BrilligOpcode::Const { destination, .. } => f(destination, Access::Write),
+        BrilligOpcode::ForeignCall { destinations, inputs, .. } => {
+            for input in inputs {
+                match input {
+                    RegisterOrMemory::RegisterIndex(register) => f(register, Access::Read),
+                    RegisterOrMemory::HeapArray(array) => read_heap_array(array, f),
+                    RegisterOrMemory::HeapVector(vector) => read_heap_vector(vector, f),
+                }
+            }
+            for destination in destinations {
+                match destination {
+                    RegisterOrMemory::RegisterIndex(register) => f(register, Access::Write),
+                    RegisterOrMemory::HeapArray(array) => read_heap_array(array, f),
+                    // The size of a returned vector is written to its size register.
+                    RegisterOrMemory::HeapVector(HeapVector { pointer, size }) => {
+                        f(pointer, Access::Read);
+                        f(size, Access::Write);
+                    }
+                }
+            }
+        }
+        BrilligOpcode::Mov { destination, source } => {
+            f(source, Access::Read);
+            f(destination, Access::Write);
+        }
+        BrilligOpcode::Load { destination, source_pointer } => {
+            f(source_pointer, Access::Read);
+            f(destination, Access::Write);
+        }
+        BrilligOpcode::Store { destination_pointer, source } => {
+            f(destination_pointer, Access::Read);
+            f(source, Access::Read);
+        }
+        BrilligOpcode::BlackBox(op) => for_each_black_box_register(op, f),
+        BrilligOpcode::Jump { .. }
+        | BrilligOpcode::Call { .. }
+        | BrilligOpcode::Return
+        | BrilligOpcode::Trap
+        | BrilligOpcode::Stop => (),
+    }
+}
+
+fn for_each_black_box_register(
+    op: &mut BlackBoxOp,
+    f: &mut impl FnMut(&mut RegisterIndex, Access),
+) {
+    match op {
+        BlackBoxOp::Sha256 { message, output }
+        | BlackBoxOp::Blake2s { message, output }
+        | BlackBoxOp::Blake3 { message, output }
+        | BlackBoxOp::Keccak256 { message, output }
+        | BlackBoxOp::Keccakf1600 { message, output } => {
+            read_heap_vector(message, f);
+            read_heap_array(output, f);
+        }
+        BlackBoxOp::EcdsaSecp256k1 {
+            hashed_msg,
+            public_key_x,
+            public_key_y,
+            signature,
+            result,
+        }
+        | BlackBoxOp::EcdsaSecp256r1 {
+            hashed_msg,
+            public_key_x,
+            public_key_y,
+            signature,
+            result,
+        } => {
+            read_heap_vector(hashed_msg, f);
+            read_heap_array(public_key_x, f);
+            read_heap_array(public_key_y, f);
+            read_heap_array(signature, f);
+            f(result, Access::Write);
+        }
+        BlackBoxOp::SchnorrVerify { public_key_x, public_key_y, message, signature, result } => {
+            f(public_key_x, Access::Read);
+            f(public_key_y, Access::Read);
+            read_heap_vector(message, f);
+            read_heap_vector(signature, f);
+            f(result, Access::Write);
+        }
+        BlackBoxOp::PedersenCommitment { inputs, domain_separator, output } => {
+            read_heap_vector(inputs, f);
+            f(domain_separator, Access::Read);
+            read_heap_array(output, f);
+        }
+        BlackBoxOp::PedersenHash { inputs, domain_separator, output } => {
+            read_heap_vector(inputs, f);
+            f(domain_separator, Access::Read);
+            f(output, Access::Write);
+        }
+        BlackBoxOp::FixedBaseScalarMul { low, high, result } => {
+            f(low, Access::Read);
+            f(high, Access::Read);
+            read_heap_array(result, f);
+        }
+        BlackBoxOp::EmbeddedCurveAdd { input1_x, input1_y, input2_x, input2_y, result } => {
+            f(input1_x, Access::Read);
+            f(input1_y, Access::Read);
+            f(input2_x, Access::Read);
+            f(input2_y, Access::Read);
+            read_heap_array(result, f);
+        }
+        BlackBoxOp::EmbeddedCurveDouble { input1_x, input1_y, result } => {
+            f(input1_x, Access::Read);
+            f(input1_y, Access::Read);
+            read_heap_array(result, f);
+        }
+        BlackBoxOp::Poseidon2Permutation { message, output, len } => {
+            read_heap_vector(message, f);
+            f(len, Access::Read);
+            read_heap_array(output, f);
+        }
+    }
+}
+
+/// The pointer of an array is read, regardless of whether the opcode reads or writes its items.
+fn read_heap_array(array: &mut HeapArray, f: &mut impl FnMut(&mut RegisterIndex, Access)) {
+    f(&mut array.pointer, Access::Read);
+}
+
+fn read_heap_vector(vector: &mut HeapVector, f: &mut impl FnMut(&mut RegisterIndex, Access)) {
+    f(&mut vector.pointer, Access::Read);
+    f(&mut vector.size, Access::Read);
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::acir::brillig::{BinaryIntOp, RegisterIndex, Value};
+
+    use crate::brillig::brillig_ir::{
+        artifact::BrilligParameter,
+        tests::{create_and_run_vm, create_context, create_entry_point_bytecode},
+        BrilligBinaryOp,
+    };
+
+    #[test]
+    fn spill_registers_past_the_limit() {
+        // unconstrained fn main() -> u32 {
+        //   let (a, b, c, ...) = (1, 2, 3, ...);
+        //   a + b + c + ...
+        // }
+        let mut context = create_context();
+
+        // Keep more values live at the same time than there are registers available.
+        let values: Vec<RegisterIndex> =
+            (1..=10_usize).map(|value| context.make_constant(Value::from(value))).collect();
+        let sum = context.make_constant(Value::from(0_usize));
+        for value in values {
+            context.binary_instruction(
+                sum,
+                value,
+                sum,
+                BrilligBinaryOp::Integer { op: BinaryIntOp::Add, bit_size: 32 },
+            );
+        }
+        context.return_instruction(&[sum]);
+
+        context.allocate_registers_with_limit(2);
+
+        // The reserved registers, the return register, the spill pointer, the address register,
+        // at most two scratch registers and the two allocated registers.
+        let max_register = context
+            .obj
+            .byte_code
+            .iter()
+            .flat_map(|opcode| {
+                let mut registers = Vec::new();
+                super::for_each_register(&mut opcode.clone(), &mut |register, _| {
+                    registers.push(register.to_usize());
+                });
+                registers
+            })
+            .max();
+        assert_eq!(max_register, Some(8));
+
+        let bytecode =
+            create_entry_point_bytecode(context, vec![], vec![BrilligParameter::Simple]).byte_code;
+        let vm = create_and_run_vm(vec![], vec![], &bytecode);
+        assert_eq!(vm.get_registers().get(RegisterIndex(0)), Value::from(55_usize));
+    }
+}
@@ -9,20 +9,27 @@ use crate::ssa::{
     ir::function::{Function, FunctionId, RuntimeType},
     ssa_gen::Ssa,
 };
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet};
 
 /// Context structure for the brillig pass.
 /// It stores brillig-related data required for brillig generation.
 #[derive(Default)]
 pub struct Brillig {
     /// Maps SSA function labels to their brillig artifact
-    ssa_function_to_brillig: HashMap<FunctionId, BrilligArtifact>,
+    ssa_function_to_brillig: BTreeMap<FunctionId, BrilligArtifact>,
 }
 
 impl Brillig {
     /// Compiles a function into brillig and store the compilation artifacts
-    pub(crate) fn compile(&mut self, func: &Function, enable_debug_trace: bool) {
-        let obj = convert_ssa_function(func, enable_debug_trace);
+    ///
+    /// Functions with `local_memory` free all of their memory when they return.
+    pub(crate) fn compile(
+        &mut self,
+        func: &Function,
+        local_memory: bool,
+        enable_debug_trace: bool,
+    ) {
+        let obj = convert_ssa_function(func, local_memory, enable_debug_trace);
         self.ssa_function_to_brillig.insert(func.id(), obj);
     }
 
@@ -56,10 +63,13 @@ impl Ssa {
             .filter_map(|(id, func)| (func.runtime() == RuntimeType::Brillig).then_some(*id))
             .collect::<BTreeSet<_>>();
 
+        let functions_with_local_memory = self.find_functions_with_local_memory();
+
         let mut brillig = Brillig::default();
         for brillig_function_id in brillig_reachable_function_ids {
             let func = &self.functions[&brillig_function_id];
-            brillig.compile(func, enable_debug_trace);
+            let local_memory = functions_with_local_memory.contains(&brillig_function_id);
+            brillig.compile(func, local_memory, enable_debug_trace);
         }
 
         brillig
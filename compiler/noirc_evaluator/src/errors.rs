@@ -10,6 +10,7 @@
 use acvm::{acir::native_types::Expression, FieldElement};
 use iter_extended::vecmap;
 use noirc_errors::{CustomDiagnostic as Diagnostic, FileDiagnostic};
+use noirc_frontend::monomorphization::MonomorphizationError;
 use thiserror::Error;
 
 use crate::ssa::ir::{dfg::CallStack, types::NumericType};
@@ -42,8 +43,41 @@ pub enum RuntimeError {
     UnknownLoopBound { call_stack: CallStack },
     #[error("Argument is not constant")]
     AssertConstantFailed { call_stack: CallStack },
+    #[error("{}", format_failed_static_assert(.message))]
+    StaticAssertFailed { message: Option<String>, call_stack: CallStack },
+    #[error("The condition of a static assertion must be known at compile-time")]
+    StaticAssertDynamicPredicate { call_stack: CallStack },
     #[error("Nested slices are not supported")]
     NestedSlice { call_stack: CallStack },
+    #[error("Slices of references are not supported in constrained functions")]
+    SliceOfReferences { call_stack: CallStack },
+    #[error("Unconstrained code branches on a secret value within a constant time function")]
+    SecretDependentBranch { call_stack: CallStack },
+    #[error(
+        "Unconstrained code indexes an array with a secret value within a constant time function"
+    )]
+    SecretDependentIndex { call_stack: CallStack },
+    #[error(
+        "Function `{}` is recursive, which is only supported in unconstrained functions",
+        .cycle[0]
+    )]
+    RecursiveFunction {
+        /// The names of each function in the cycle, starting from the first one called.
+        cycle: Vec<String>,
+        /// The location of each call in the cycle.
+        call_stack: CallStack,
+    },
+    #[error("Numeric bound `{bound}` of `{function}` is not satisfied")]
+    UnsatisfiedNumericBound { function: String, bound: String, call_stack: CallStack },
+    #[error("Constant `{value}` given to a generic of `{function}` is not a `{expected}`")]
+    InvalidValueGenericArgument {
+        function: String,
+        value: String,
+        expected: String,
+        call_stack: CallStack,
+    },
+    #[error("Array length `{length}` does not evaluate to a valid length")]
+    InvalidArrayLength { length: String, call_stack: CallStack },
 }
 
 // We avoid showing the actual lhs and rhs since most of the time they are just 0
@@ -56,6 +90,13 @@ fn format_failed_constraint(message: &Option<String>) -> String {
     }
 }
 
+fn format_failed_static_assert(message: &Option<String>) -> String {
+    match message {
+        Some(message) => format!("Failed static assertion: '{message}'"),
+        None => "Failed static assertion".to_owned(),
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SsaReport {
     Warning(InternalWarning),
@@ -73,6 +114,9 @@ impl From<SsaReport> for FileDiagnostic {
                     InternalWarning::VerifyProof { call_stack } => {
                         ("verify_proof(...) aggregates data for the verifier, the actual verification will be done when the full proof is verified using nargo verify. nargo prove may generate an invalid proof if bad data is used as input to verify_proof".to_string(), call_stack)
                     },
+                    InternalWarning::UnverifiedHint { call_stack } => {
+                        ("The prover can replace the result of this unconstrained call with any value. Consider asserting that the result is correct".to_string(), call_stack)
+                    },
                 };
                 let call_stack = vecmap(call_stack, |location| location);
                 let file_id = call_stack.last().map(|location| location.file).unwrap_or_default();
@@ -91,6 +135,10 @@ pub enum InternalWarning {
     ReturnConstant { call_stack: CallStack },
     #[error("Calling std::verify_proof(...) does not verify a proof")]
     VerifyProof { call_stack: CallStack },
+    #[error(
+        "Return value depends on the result of an unconstrained call which is never constrained"
+    )]
+    UnverifiedHint { call_stack: CallStack },
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Error)]
@@ -130,9 +178,46 @@ impl RuntimeError {
             | RuntimeError::UnInitialized { call_stack, .. }
             | RuntimeError::UnknownLoopBound { call_stack }
             | RuntimeError::AssertConstantFailed { call_stack }
+            | RuntimeError::StaticAssertFailed { call_stack, .. }
+            | RuntimeError::StaticAssertDynamicPredicate { call_stack }
             | RuntimeError::IntegerOutOfBounds { call_stack, .. }
             | RuntimeError::UnsupportedIntegerSize { call_stack, .. }
-            | RuntimeError::NestedSlice { call_stack, .. } => call_stack,
+            | RuntimeError::NestedSlice { call_stack, .. }
+            | RuntimeError::SliceOfReferences { call_stack }
+            | RuntimeError::SecretDependentBranch { call_stack }
+            | RuntimeError::SecretDependentIndex { call_stack }
+            | RuntimeError::RecursiveFunction { call_stack, .. }
+            | RuntimeError::UnsatisfiedNumericBound { call_stack, .. }
+            | RuntimeError::InvalidValueGenericArgument { call_stack, .. }
+            | RuntimeError::InvalidArrayLength { call_stack, .. } => call_stack,
+        }
+    }
+}
+
+impl From<MonomorphizationError> for RuntimeError {
+    fn from(error: MonomorphizationError) -> RuntimeError {
+        match error {
+            MonomorphizationError::UnsatisfiedNumericBound { function, bound, location } => {
+                RuntimeError::UnsatisfiedNumericBound {
+                    function,
+                    bound,
+                    call_stack: CallStack::unit(location),
+                }
+            }
+            MonomorphizationError::InvalidValueGenericArgument {
+                function,
+                value,
+                expected,
+                location,
+            } => RuntimeError::InvalidValueGenericArgument {
+                function,
+                value,
+                expected,
+                call_stack: CallStack::unit(location),
+            },
+            MonomorphizationError::InvalidArrayLength { length, location } => {
+                RuntimeError::InvalidArrayLength { length, call_stack: CallStack::unit(location) }
+            }
         }
     }
 }
@@ -157,6 +242,57 @@ impl RuntimeError {
                     noirc_errors::Span::inclusive(0, 0)
                 )
             }
+            RuntimeError::RecursiveFunction { ref cycle, ref call_stack } => {
+                let message = self.to_string();
+                let location = call_stack.back().expect("Expected RuntimeError to have a location");
+
+                let mut diagnostic = Diagnostic::simple_error(
+                    message,
+                    format!("`{}` is called recursively here", cycle[0]),
+                    location.span,
+                );
+
+                let calls = vecmap(cycle.iter().skip(1).chain(std::iter::once(&cycle[0])), |name| {
+                    format!("calls `{name}`")
+                });
+                diagnostic.add_note(format!("`{}` {}", cycle[0], calls.join(", which ")));
+                diagnostic.add_note(format!(
+                    "Constrained functions are inlined into their callers, so they can't call \
+                    themselves. Consider marking `{}` as `unconstrained`",
+                    cycle[0]
+                ));
+                diagnostic
+            }
+            RuntimeError::UnsatisfiedNumericBound { ref function, ref call_stack, .. } => {
+                let message = self.to_string();
+                let location = call_stack.back().expect("Expected RuntimeError to have a location");
+
+                Diagnostic::simple_error(
+                    message,
+                    format!("`{function}` is called here with generics which violate the bound"),
+                    location.span,
+                )
+            }
+            RuntimeError::InvalidValueGenericArgument { ref function, ref call_stack, .. } => {
+                let message = self.to_string();
+                let location = call_stack.back().expect("Expected RuntimeError to have a location");
+
+                Diagnostic::simple_error(
+                    message,
+                    format!("`{function}` is called here with a constant which doesn't fit the generic"),
+                    location.span,
+                )
+            }
+            RuntimeError::InvalidArrayLength { ref call_stack, .. } => {
+                let message = self.to_string();
+                let location = call_stack.back().expect("Expected RuntimeError to have a location");
+
+                Diagnostic::simple_error(
+                    message,
+                    "The generics used here make the length overflow or divide by zero".to_owned(),
+                    location.span,
+                )
+            }
             _ => {
                 let message = self.to_string();
                 let location =
@@ -11,4 +11,7 @@ pub mod ssa;
 
 pub mod brillig;
 
-pub use ssa::create_circuit;
+pub use ssa::{
+    check_deterministic_compilation, create_circuit, OptimizationLevel, SsaEvaluatorOptions,
+    SsaPrintOptions, DEFAULT_ARRAY_MUX_THRESHOLD,
+};
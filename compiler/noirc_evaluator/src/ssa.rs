@@ -7,17 +7,24 @@
 //! This module heavily borrows from Cranelift
 #![allow(dead_code)]
 
-use std::collections::BTreeSet;
+use std::{
+    collections::BTreeSet,
+    fmt::Write,
+    path::PathBuf,
+    str::FromStr,
+    time::{Duration, Instant},
+};
 
 use crate::{
     brillig::Brillig,
-    errors::{RuntimeError, SsaReport},
+    errors::{InternalError, RuntimeError, SsaReport},
 };
 use acvm::acir::{
     circuit::{Circuit, PublicInputs},
     native_types::Witness,
 };
 
+use iter_extended::vecmap;
 use noirc_errors::debug_info::DebugInfo;
 
 use noirc_frontend::{
@@ -25,81 +32,280 @@ use noirc_frontend::{
 };
 use tracing::{span, Level};
 
-use self::{acir_gen::GeneratedAcir, ssa_gen::Ssa};
+use self::{acir_gen::GeneratedAcir, ir::dfg::CallStack, ssa_gen::Ssa};
 
 mod acir_gen;
 pub(super) mod function_builder;
 pub mod ir;
 mod opt;
+mod parser;
 pub mod ssa_gen;
+mod verify;
+
+/// Selects which of the optional SSA passes are run when compiling a program.
+///
+/// The passes required to produce valid ACIR, such as inlining, loop unrolling and flattening,
+/// are always run regardless of the optimization level.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OptimizationLevel {
+    /// `0`: Only run the passes required to produce a valid program.
+    None,
+    /// `1`: Additionally fold constants. This is the default, as it runs the same passes
+    /// as before optimization levels could be selected.
+    #[default]
+    Basic,
+    /// `2`: Run every SSA pass. Small loops within Brillig functions are also unrolled
+    /// and small Brillig functions are inlined into their Brillig callers.
+    Full,
+    /// `s`: Run every SSA pass but never duplicate code within Brillig functions.
+    Size,
+}
+
+impl OptimizationLevel {
+    fn folds_constants(self) -> bool {
+        self != OptimizationLevel::None
+    }
+
+    fn runs_all_passes(self) -> bool {
+        matches!(self, OptimizationLevel::Full | OptimizationLevel::Size)
+    }
+
+    fn expands_brillig_code(self) -> bool {
+        self == OptimizationLevel::Full
+    }
+}
+
+impl FromStr for OptimizationLevel {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "0" => Ok(OptimizationLevel::None),
+            "1" => Ok(OptimizationLevel::Basic),
+            "2" => Ok(OptimizationLevel::Full),
+            "s" => Ok(OptimizationLevel::Size),
+            _ => Err(format!("Unknown optimization level '{input}', expected one of 0, 1, 2 or s")),
+        }
+    }
+}
+
+/// The default size of the largest arrays whose accesses at dynamic indices are muxed, see
+/// [`SsaEvaluatorOptions::array_mux_threshold`].
+pub const DEFAULT_ARRAY_MUX_THRESHOLD: usize = 4;
+
+/// Selects which passes have their SSA printed while a program is being optimized, in addition
+/// to those printed by [`SsaEvaluatorOptions::enable_ssa_logging`].
+#[derive(Debug, Default, Clone)]
+pub struct SsaPrintOptions {
+    /// Print the SSA before and after each run of these passes.
+    pub trace: Vec<String>,
+    /// Write the SSA printed because of `trace` to this file rather than to stdout.
+    pub output: Option<PathBuf>,
+}
+
+/// Options controlling how a program is compiled from SSA into ACIR and what is reported while
+/// doing so.
+#[derive(Debug, Clone)]
+pub struct SsaEvaluatorOptions {
+    /// Print the SSA after each pass.
+    pub enable_ssa_logging: bool,
+    /// Print the Brillig bytecode generated for unconstrained functions.
+    pub enable_brillig_logging: bool,
+    /// Print the number of instructions and the time taken after each pass.
+    pub show_pass_stats: bool,
+    /// Print the SSA before and after the selected passes.
+    pub print_options: SsaPrintOptions,
+    /// Check that the SSA is well formed after each pass.
+    pub verify_ssa: bool,
+    /// Selects which of the optional SSA passes are run.
+    pub optimization_level: OptimizationLevel,
+    /// Accesses at dynamic indices of arrays with at most this many values are compiled to
+    /// arithmetic over each value of the array rather than to a memory block.
+    pub array_mux_threshold: usize,
+    /// Compile constrained functions marked `#[inline(never)]` to separate ACIR functions rather
+    /// than inlining them, which requires a backend supporting call opcodes.
+    pub acir_calls: bool,
+    /// Outline sequences of instructions repeated many times within a constrained function into
+    /// separate ACIR functions, which requires a backend supporting call opcodes.
+    pub outline_repeated_code: bool,
+}
+
+impl SsaEvaluatorOptions {
+    /// Returns the options which affect the compiled program, without printing or checking
+    /// anything along the way.
+    fn without_reporting(&self) -> SsaEvaluatorOptions {
+        SsaEvaluatorOptions {
+            enable_ssa_logging: false,
+            enable_brillig_logging: false,
+            show_pass_stats: false,
+            print_options: SsaPrintOptions::default(),
+            verify_ssa: false,
+            ..self.clone()
+        }
+    }
+}
 
 /// Optimize the given program by converting it into SSA
 /// form and performing optimizations there. When finished,
 /// convert the final SSA into ACIR and return it.
 pub(crate) fn optimize_into_acir(
     program: Program,
-    print_ssa_passes: bool,
-    print_brillig_trace: bool,
-) -> Result<GeneratedAcir, RuntimeError> {
+    options: &SsaEvaluatorOptions,
+) -> Result<Vec<GeneratedAcir>, RuntimeError> {
     let abi_distinctness = program.return_distinctness;
+    let optimization_level = options.optimization_level;
+    let acir_calls = options.acir_calls;
+
+    let inline_functions = |ssa: Ssa| {
+        if optimization_level.expands_brillig_code() {
+            ssa.inline_functions_and_small_brillig_calls(acir_calls)
+        } else {
+            ssa.inline_functions(acir_calls)
+        }
+    };
 
     let ssa_gen_span = span!(Level::TRACE, "ssa_generation");
     let ssa_gen_span_guard = ssa_gen_span.enter();
-    let ssa = SsaBuilder::new(program, print_ssa_passes)?
+    let builder = SsaBuilder::new(program, options)?;
+    let ssa = builder
         .run_pass(Ssa::defunctionalize, "After Defunctionalization:")
-        .run_pass(Ssa::inline_functions, "After Inlining:")
+        .try_run_pass(Ssa::check_constant_time, "After Constant Time Check:")?
+        .run_pass(inline_functions, "After Inlining:")
         // Run mem2reg with the CFG separated into blocks
         .run_pass(Ssa::mem2reg, "After Mem2Reg:")
+        .run_pass_if(
+            optimization_level.runs_all_passes(),
+            Ssa::loop_invariant_code_motion,
+            "After Loop Invariant Code Motion:",
+        )
         .try_run_pass(Ssa::evaluate_assert_constant, "After Assert Constant:")?
         .try_run_pass(Ssa::unroll_loops, "After Unrolling:")?
+        .run_pass_if(
+            optimization_level.expands_brillig_code(),
+            Ssa::unroll_small_brillig_loops,
+            "After Unrolling Brillig Loops:",
+        )
         .run_pass(Ssa::simplify_cfg, "After Simplifying:")
         // Run mem2reg before flattening to handle any promotion
         // of values that can be accessed after loop unrolling.
         // If there are slice mergers uncovered by loop unrolling
         // and this pass is missed, slice merging will fail inside of flattening.
         .run_pass(Ssa::mem2reg, "After Mem2Reg:")
+        .try_run_pass(Ssa::evaluate_static_asserts, "After Static Assert:")?
+        .run_pass_if(
+            optimization_level.runs_all_passes(),
+            Ssa::promote_constant_slices,
+            "After Slice Promotion:",
+        )
+        .run_pass_if(
+            optimization_level.runs_all_passes(),
+            Ssa::global_value_numbering,
+            "After Global Value Numbering:",
+        )
         .run_pass(Ssa::flatten_cfg, "After Flattening:")
         // Run mem2reg once more with the flattened CFG to catch any remaining loads/stores
         .run_pass(Ssa::mem2reg, "After Mem2Reg:")
-        .run_pass(Ssa::fold_constants, "After Constant Folding:")
+        .run_pass_if(
+            optimization_level.runs_all_passes(),
+            Ssa::dead_store_elimination,
+            "After Dead Store Elimination:",
+        )
+        .run_pass_if(
+            optimization_level.folds_constants(),
+            Ssa::fold_constants,
+            "After Constant Folding:",
+        )
+        .run_pass_if(
+            optimization_level.runs_all_passes(),
+            Ssa::merge_branch_constraints,
+            "After Merging Branch Constraints:",
+        )
+        .run_pass_if(
+            optimization_level.runs_all_passes(),
+            |ssa| ssa.mux_small_arrays(options.array_mux_threshold),
+            "After Muxing Small Arrays:",
+        )
+        .run_pass_if(
+            optimization_level.runs_all_passes(),
+            Ssa::shrink_integer_ranges,
+            "After Range Analysis:",
+        )
         .run_pass(Ssa::dead_instruction_elimination, "After Dead Instruction Elimination:")
-        .finish();
+        .run_pass_if(options.outline_repeated_code, Ssa::outline_repeated_code, "After Outlining:")
+        .finish()?;
 
-    let brillig = ssa.to_brillig(print_brillig_trace);
+    let brillig = ssa.to_brillig(options.enable_brillig_logging);
 
     drop(ssa_gen_span_guard);
 
     let last_array_uses = ssa.find_last_array_uses();
+    let shared_arrays = ssa.find_shared_read_only_arrays();
+    let unverified_hints = ssa.check_unverified_hints();
 
-    ssa.into_acir(brillig, abi_distinctness, &last_array_uses)
+    let mut generated_acirs =
+        ssa.into_acir(brillig, abi_distinctness, &last_array_uses, shared_arrays)?;
+    generated_acirs[0].warnings.extend(unverified_hints);
+    Ok(generated_acirs)
 }
 
 /// Compiles the [`Program`] into [`ACIR`][acvm::acir::circuit::Circuit].
 ///
 /// The output ACIR is is backend-agnostic and so must go through a transformation pass before usage in proof generation.
+///
+/// Along with the circuit of `main`, the circuits of the constrained functions it calls through
+/// [call opcodes][acvm::acir::circuit::Opcode::Call] are returned, indexed by the id of the calls.
 #[allow(clippy::type_complexity)]
 #[tracing::instrument(level = "trace", skip_all)]
 pub fn create_circuit(
     program: Program,
-    enable_ssa_logging: bool,
-    enable_brillig_logging: bool,
-) -> Result<(Circuit, DebugInfo, Vec<Witness>, Vec<Witness>, Vec<SsaReport>), RuntimeError> {
+    options: &SsaEvaluatorOptions,
+) -> Result<
+    (Circuit, Vec<Circuit>, DebugInfo, Vec<Witness>, Vec<Witness>, Vec<SsaReport>),
+    RuntimeError,
+> {
     let func_sig = program.main_function_signature.clone();
-    let mut generated_acir =
-        optimize_into_acir(program, enable_ssa_logging, enable_brillig_logging)?;
-    let opcodes = generated_acir.take_opcodes();
-    let current_witness_index = generated_acir.current_witness_index().0;
-    let GeneratedAcir {
-        return_witnesses,
-        locations,
-        input_witnesses,
-        assert_messages,
-        warnings,
-        ..
-    } = generated_acir;
+    let mut generated_acirs = optimize_into_acir(program, options)?.into_iter();
+    let generated_acir = generated_acirs.next().expect("ICE: main should always be converted");
+
+    // The parameters of a called function are all private, and it returns its values to its
+    // caller rather than making them public.
+    let mut warnings = Vec::new();
+    let functions = vecmap(generated_acirs, |mut generated_acir| {
+        warnings.append(&mut generated_acir.warnings);
+        let private_parameters = generated_acir.input_witnesses.iter().copied().collect();
+        let (circuit, ..) = convert_generated_acir_into_circuit(
+            generated_acir,
+            private_parameters,
+            BTreeSet::new(),
+        );
+        circuit
+    });
 
+    let input_witnesses = generated_acir.input_witnesses.clone();
     let (public_parameter_witnesses, private_parameters) =
         split_public_and_private_inputs(&func_sig, &input_witnesses);
+    let (circuit, debug_info, return_witnesses, mut main_warnings) =
+        convert_generated_acir_into_circuit(
+            generated_acir,
+            private_parameters,
+            public_parameter_witnesses,
+        );
+    main_warnings.extend(warnings);
+
+    Ok((circuit, functions, debug_info, input_witnesses, return_witnesses, main_warnings))
+}
+
+/// Builds the optimized [`Circuit`] for the ACIR generated for a function with the given
+/// parameters, along with its debug information, return witnesses and warnings.
+fn convert_generated_acir_into_circuit(
+    mut generated_acir: GeneratedAcir,
+    private_parameters: BTreeSet<Witness>,
+    public_parameter_witnesses: BTreeSet<Witness>,
+) -> (Circuit, DebugInfo, Vec<Witness>, Vec<SsaReport>) {
+    let opcodes = generated_acir.take_opcodes();
+    let current_witness_index = generated_acir.current_witness_index().0;
+    let GeneratedAcir { return_witnesses, locations, assert_messages, warnings, .. } =
+        generated_acir;
 
     let public_parameters = PublicInputs(public_parameter_witnesses);
     let return_values = PublicInputs(return_witnesses.iter().copied().collect());
@@ -125,7 +331,52 @@ pub fn create_circuit(
     let (optimized_circuit, transformation_map) = acvm::compiler::optimize(circuit);
     debug_info.update_acir(transformation_map);
 
-    Ok((optimized_circuit, debug_info, input_witnesses, return_witnesses, warnings))
+    (optimized_circuit, debug_info, return_witnesses, warnings)
+}
+
+/// Compiles `program` again with the same `options` and checks that doing so reproduces the
+/// `circuit`, `functions` and `debug` information of an earlier compilation of it.
+///
+/// Compilation must depend only on the program being compiled, and never on state which varies
+/// between runs such as the iteration order of hash maps, so that builds are reproducible.
+pub fn check_deterministic_compilation(
+    program: Program,
+    options: &SsaEvaluatorOptions,
+    circuit: &Circuit,
+    functions: &[Circuit],
+    debug: &DebugInfo,
+) -> Result<(), RuntimeError> {
+    let (recompiled_circuit, recompiled_functions, recompiled_debug, ..) =
+        create_circuit(program, &options.without_reporting())?;
+
+    let difference = if recompiled_circuit.opcodes.len() != circuit.opcodes.len() {
+        Some(format!(
+            "{} opcodes rather than {}",
+            recompiled_circuit.opcodes.len(),
+            circuit.opcodes.len()
+        ))
+    } else if let Some(index) =
+        circuit.opcodes.iter().zip(&recompiled_circuit.opcodes).position(|(a, b)| a != b)
+    {
+        Some(format!("a different opcode at index {index}"))
+    } else if &recompiled_circuit != circuit {
+        Some("a different circuit".to_owned())
+    } else if recompiled_functions != functions {
+        Some("different ACIR functions".to_owned())
+    } else if recompiled_debug.locations != debug.locations {
+        Some("different debug information".to_owned())
+    } else {
+        None
+    };
+
+    match difference {
+        Some(difference) => Err(InternalError::General {
+            message: format!("Compiling the program a second time produced {difference}"),
+            call_stack: CallStack::new(),
+        }
+        .into()),
+        None => Ok(()),
+    }
 }
 
 // Takes each function argument and partitions the circuit's inputs witnesses according to its visibility.
@@ -166,22 +417,93 @@ fn split_public_and_private_inputs(
 struct SsaBuilder {
     ssa: Ssa,
     print_ssa_passes: bool,
+    /// The statistics recorded after each pass, if `show_pass_stats` is set.
+    pass_stats: Option<Vec<PassStats>>,
+    /// The passes named by `--ssa-trace`, along with whether any pass run so far matched them.
+    traced_passes: Vec<(String, bool)>,
+    /// The file to which the SSA before and after each of `traced_passes` is written, along with
+    /// the SSA to be written to it.
+    ssa_output: Option<(PathBuf, String)>,
+    /// Whether the SSA is verified after each pass, as requested by `--verify-ssa`.
+    verify_ssa: bool,
+    /// The error found by the first verification of the SSA to fail, after which no more passes
+    /// are run.
+    verification_error: Option<RuntimeError>,
+}
+
+/// The size of the SSA after running a pass, along with the time taken to run it.
+struct PassStats {
+    name: String,
+    instructions: usize,
+    duration: Duration,
 }
 
 impl SsaBuilder {
-    fn new(program: Program, print_ssa_passes: bool) -> Result<SsaBuilder, RuntimeError> {
+    fn new(program: Program, options: &SsaEvaluatorOptions) -> Result<SsaBuilder, RuntimeError> {
+        let start = Instant::now();
         let ssa = ssa_gen::generate_ssa(program)?;
-        Ok(SsaBuilder { print_ssa_passes, ssa }.print("Initial SSA:"))
+        let pass_stats = options.show_pass_stats.then(Vec::new);
+        let print_options = &options.print_options;
+        let unmatched = |names: &[String]| -> Vec<_> {
+            names.iter().map(|name| (name.clone(), false)).collect()
+        };
+        let builder = SsaBuilder {
+            print_ssa_passes: options.enable_ssa_logging,
+            ssa,
+            pass_stats,
+            traced_passes: unmatched(&print_options.trace),
+            ssa_output: print_options.output.clone().map(|path| (path, String::new())),
+            verify_ssa: options.verify_ssa,
+            verification_error: None,
+        };
+        Ok(builder
+            .record_stats("SSA Generation", start.elapsed())
+            .print("Initial SSA:")
+            .verify("SSA Generation"))
     }
 
-    fn finish(self) -> Ssa {
-        self.ssa
+    fn finish(self) -> Result<Ssa, RuntimeError> {
+        if let Some(pass_stats) = &self.pass_stats {
+            print_pass_stats(pass_stats);
+        }
+        for (name, matched) in &self.traced_passes {
+            // Passes aren't run after the SSA fails verification, so may not have been matched.
+            if !matched && self.verification_error.is_none() {
+                eprintln!("warning: `--ssa-trace {name}` did not match any SSA pass which was run");
+            }
+        }
+        if let Some((path, output)) = &self.ssa_output {
+            if let Err(err) = std::fs::write(path, output) {
+                eprintln!("warning: could not write SSA to {}: {err}", path.display());
+            }
+        }
+        match self.verification_error {
+            Some(error) => Err(error),
+            None => Ok(self.ssa),
+        }
     }
 
     /// Runs the given SSA pass and prints the SSA afterward if `print_ssa_passes` is true.
-    fn run_pass(mut self, pass: fn(Ssa) -> Ssa, msg: &str) -> Self {
+    fn run_pass(mut self, pass: impl FnOnce(Ssa) -> Ssa, msg: &str) -> Self {
+        if self.verification_error.is_some() {
+            return self;
+        }
+        let _span = span!(Level::TRACE, "ssa_pass", name = pass_name(msg)).entered();
+        let traced = self.trace_before(msg);
+        let start = Instant::now();
         self.ssa = pass(self.ssa);
-        self.print(msg)
+        self.record_stats(pass_name(msg), start.elapsed())
+            .trace_after(msg, traced)
+            .verify(pass_name(msg))
+    }
+
+    /// Runs the given SSA pass only if `condition` is true.
+    fn run_pass_if(self, condition: bool, pass: impl FnOnce(Ssa) -> Ssa, msg: &str) -> Self {
+        if condition {
+            self.run_pass(pass, msg)
+        } else {
+            self
+        }
     }
 
     /// The same as `run_pass` but for passes that may fail
@@ -190,8 +512,17 @@ impl SsaBuilder {
         pass: fn(Ssa) -> Result<Ssa, RuntimeError>,
         msg: &str,
     ) -> Result<Self, RuntimeError> {
+        if self.verification_error.is_some() {
+            return Ok(self);
+        }
+        let _span = span!(Level::TRACE, "ssa_pass", name = pass_name(msg)).entered();
+        let traced = self.trace_before(msg);
+        let start = Instant::now();
         self.ssa = pass(self.ssa)?;
-        Ok(self.print(msg))
+        Ok(self
+            .record_stats(pass_name(msg), start.elapsed())
+            .trace_after(msg, traced)
+            .verify(pass_name(msg)))
     }
 
     fn to_brillig(&self, print_brillig_trace: bool) -> Brillig {
@@ -204,4 +535,93 @@ impl SsaBuilder {
         }
         self
     }
+
+    /// Prints the SSA before the pass described by `msg` is run if it was named by `--ssa-trace`,
+    /// returning whether it was.
+    fn trace_before(&mut self, msg: &str) -> bool {
+        let name = pass_name(msg);
+        let traced = match_passes(&mut self.traced_passes, name);
+        if traced {
+            self.write_trace(&format!("Before {name}:"), !self.print_ssa_passes);
+        }
+        traced
+    }
+
+    /// Prints the SSA after a pass if it is traced.
+    fn trace_after(mut self, msg: &str, traced: bool) -> Self {
+        if traced {
+            // The SSA after each pass is already printed to stdout by `print_ssa_passes`.
+            self.write_trace(msg, !self.print_ssa_passes);
+        }
+        self.print(msg)
+    }
+
+    /// Writes the SSA under the heading `msg` to `ssa_output` if there is one, or else prints it
+    /// if `print` is true.
+    fn write_trace(&mut self, msg: &str, print: bool) {
+        match &mut self.ssa_output {
+            Some((_, output)) => {
+                writeln!(output, "{msg}\n{}", self.ssa).expect("writing to a String cannot fail");
+            }
+            None if print => println!("{msg}\n{}", self.ssa),
+            None => (),
+        }
+    }
+
+    /// Checks the SSA produced by the pass called `name` if `verify_ssa` is set, printing the SSA
+    /// if it is invalid.
+    fn verify(mut self, name: &str) -> Self {
+        if self.verify_ssa {
+            if let Err(error) = verify::verify(&self.ssa) {
+                eprintln!("The SSA is invalid after {name}:\n{}", self.ssa);
+                self.verification_error = Some(error);
+            }
+        }
+        self
+    }
+
+    fn record_stats(mut self, name: &str, duration: Duration) -> Self {
+        if let Some(pass_stats) = &mut self.pass_stats {
+            let instructions = self.ssa.functions.values().map(|f| f.num_instructions()).sum();
+            pass_stats.push(PassStats { name: name.to_owned(), instructions, duration });
+        }
+        self
+    }
+}
+
+/// Turns a message such as "After Inlining:" into the name of the pass.
+fn pass_name(msg: &str) -> &str {
+    msg.trim_start_matches("After ").trim_end_matches(':')
+}
+
+/// Returns true if `trace`, as given to `--ssa-trace`, names the pass called `name`.
+///
+/// Case is ignored and underscores stand for spaces, so `constant_folding` names the
+/// "Constant Folding" pass.
+fn is_pass_named(name: &str, trace: &str) -> bool {
+    trace.replace('_', " ").eq_ignore_ascii_case(name)
+}
+
+/// Marks each of `passes` which names the pass called `name` as matched, returning whether any
+/// of them did.
+fn match_passes(passes: &mut [(String, bool)], name: &str) -> bool {
+    let mut any_matched = false;
+    for (pass, matched) in passes {
+        if is_pass_named(name, pass) {
+            *matched = true;
+            any_matched = true;
+        }
+    }
+    any_matched
+}
+
+fn print_pass_stats(pass_stats: &[PassStats]) {
+    println!("{:<40} {:>14} {:>12}", "SSA Pass", "Instructions", "Time (ms)");
+    for stats in pass_stats {
+        let milliseconds = stats.duration.as_secs_f64() * 1000.0;
+        println!("{:<40} {:>14} {:>12.3}", stats.name, stats.instructions, milliseconds);
+    }
+
+    let total: Duration = pass_stats.iter().map(|stats| stats.duration).sum();
+    println!("{:<40} {:>14} {:>12.3}", "Total", "", total.as_secs_f64() * 1000.0);
 }
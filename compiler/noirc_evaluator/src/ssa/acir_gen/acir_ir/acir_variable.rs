@@ -8,7 +8,7 @@ use crate::ssa::ir::types::Type as SsaType;
 use crate::ssa::ir::{instruction::Endian, types::NumericType};
 use acvm::acir::circuit::brillig::{BrilligInputs, BrilligOutputs};
 use acvm::acir::circuit::opcodes::{BlockId, MemOp};
-use acvm::acir::circuit::Opcode;
+use acvm::acir::circuit::{AssertionPayload, Opcode};
 use acvm::blackbox_solver;
 use acvm::brillig_vm::{brillig::Value, Registers, VMStatus, VM};
 use acvm::{
@@ -107,6 +107,24 @@ pub(crate) struct AcirContext {
     /// then the `acir_ir` will be populated to assert this
     /// addition.
     acir_ir: GeneratedAcir,
+
+    /// The state of each memory block as known at compile time.
+    memory_blocks: HashMap<BlockId, MemoryBlock>,
+}
+
+/// The compile-time state of a memory block.
+///
+/// While the elements of a block are known, reads at a constant index use the element directly
+/// and writes at a constant index only update it. The `MemoryInit` opcode for a block is only
+/// emitted once the block is accessed at an index which isn't known, so blocks which are only
+/// ever accessed at constant indices are never added to the circuit.
+#[derive(Debug)]
+struct MemoryBlock {
+    /// The value of each element of the block, or None once the block has been written to at
+    /// an index which isn't known.
+    elements: Option<Vec<AcirVar>>,
+    /// Whether the `MemoryInit` opcode for the block has been emitted.
+    initialized: bool,
 }
 
 impl AcirContext {
@@ -234,7 +252,7 @@ impl AcirContext {
     }
 
     /// Converts an [`AcirVar`] to a [`Witness`]
-    fn var_to_witness(&mut self, var: AcirVar) -> Result<Witness, InternalError> {
+    pub(crate) fn var_to_witness(&mut self, var: AcirVar) -> Result<Witness, InternalError> {
         let expression = self.var_to_expression(var)?;
         let witness = if let Some(constant) = expression.to_const() {
             // Check if a witness has been assigned this value already, if so reuse it.
@@ -475,7 +493,7 @@ impl AcirContext {
         &mut self,
         lhs: AcirVar,
         rhs: AcirVar,
-        assert_message: Option<String>,
+        assert_message: Option<AssertionPayload>,
     ) -> Result<(), RuntimeError> {
         let lhs_expr = self.var_to_expression(lhs)?;
         let rhs_expr = self.var_to_expression(rhs)?;
@@ -526,7 +544,7 @@ impl AcirContext {
             }
             NumericType::Signed { bit_size } => {
                 let (quotient_var, _remainder_var) =
-                    self.signed_division_var(lhs, rhs, bit_size)?;
+                    self.signed_division_var(lhs, rhs, bit_size, predicate)?;
                 Ok(quotient_var)
             }
         }
@@ -615,7 +633,10 @@ impl AcirContext {
     pub(crate) fn not_var(&mut self, x: AcirVar, typ: AcirType) -> Result<AcirVar, RuntimeError> {
         let bit_size = typ.bit_size();
         // Subtracting from max flips the bits
-        let max = self.add_constant((1_u128 << bit_size) - 1);
+        let max = self.add_constant(
+            FieldElement::from(2_i128).pow(&FieldElement::from(bit_size as i128))
+                - FieldElement::one(),
+        );
         self.sub_var(max, x)
     }
 
@@ -697,13 +718,14 @@ impl AcirContext {
 
         // Avoids overflow: 'q*b+r < 2^max_q_bits*2^max_rhs_bits'
         let mut avoid_overflow = false;
+        let mut bound_product = false;
         if max_q_bits + max_rhs_bits >= FieldElement::max_num_bits() - 1 {
             // q*b+r can overflow; we avoid this when b is constant
             if self.var_to_expression(rhs)?.is_const() {
                 avoid_overflow = true;
             } else {
-                // we do not support unbounded division
-                unreachable!("overflow in unbounded division");
+                // otherwise we bound q*b below
+                bound_product = true;
             }
         }
 
@@ -730,6 +752,20 @@ impl AcirContext {
             None,
         )?;
 
+        if bound_product {
+            // If both q and b have their upper half of bits set then q*b >= 2^bit_size > a, so
+            // one of them must fit in half of the bits. This ensures that q*b+r fits in the field.
+            let half_bit_size = (bit_size + 1) / 2;
+            let pow_half = self.add_constant(
+                FieldElement::from(2_i128).pow(&FieldElement::from(half_bit_size as i128)),
+            );
+            let (q_high, _) = self.euclidean_division_var(quotient_var, pow_half, bit_size, one)?;
+            let (rhs_high, _) = self.euclidean_division_var(rhs, pow_half, bit_size, one)?;
+            let high_product = self.mul_var(q_high, rhs_high)?;
+            let high_product = self.mul_var(high_product, predicate)?;
+            self.assert_eq_var(high_product, zero, None)?;
+        }
+
         // Constrain `r < 2^{max_rhs_bits}`.
         //
         // If `rhs` is a power of 2, then is just a looser version of the following bound constraint.
@@ -835,7 +871,7 @@ impl AcirContext {
 
             let bit_size = bit_size_u128(rhs_offset);
             // r = 2^bit_size - rhs_offset -1, is of bit size  'bit_size' by construction
-            let r = (1_u128 << bit_size) - rhs_offset - 1;
+            let r = u128::MAX.checked_shr(128 - bit_size).unwrap_or(0) - rhs_offset;
             // however, since it is a constant, we can compute it's actual bit size
             let r_bit_size = bit_size_u128(r);
             // witness = lhs_offset + r
@@ -882,6 +918,7 @@ impl AcirContext {
         lhs: AcirVar,
         rhs: AcirVar,
         bit_size: u32,
+        predicate: AcirVar,
     ) -> Result<(AcirVar, AcirVar), RuntimeError> {
         // We derive the signed division from the unsigned euclidean division.
         // note that this is not euclidean division!
@@ -909,20 +946,33 @@ impl AcirContext {
         let unsigned_lhs = self.two_complement(lhs, lhs_leading, bit_size)?;
         let unsigned_rhs = self.two_complement(rhs, rhs_leading, bit_size)?;
 
-        // Performs the division using the unsigned values of lhs and rhs
+        // Performs the division using the unsigned values of lhs and rhs.
+        // The absolute value of the smallest integer, 2^{bit_size-1}, requires all `bit_size` bits.
         let (q1, r1) =
-            self.euclidean_division_var(unsigned_lhs, unsigned_rhs, bit_size - 1, one)?;
+            self.euclidean_division_var(unsigned_lhs, unsigned_rhs, bit_size, predicate)?;
 
         // Unsigned to signed: derive q and r from q1,r1 and the signs of lhs and rhs
         // Quotient sign is lhs sign * rhs sign, whose resulting sign bit is the XOR of the sign bits
         let q_sign = self.xor_var(lhs_leading, rhs_leading, AcirType::unsigned(1))?;
+        // Zero is never negative, its 2-complement 2^bit_size would not fit in the integer type
+        let q_sign = self.nonzero_sign(q1, q_sign)?;
+        let r_sign = self.nonzero_sign(r1, lhs_leading)?;
 
         let quotient = self.two_complement(q1, q_sign, bit_size)?;
-        let remainder = self.two_complement(r1, lhs_leading, bit_size)?;
+        let remainder = self.two_complement(r1, r_sign, bit_size)?;
 
         Ok((quotient, remainder))
     }
 
+    /// Returns the sign bit `sign` of the integer whose absolute value is `abs`, which is cleared
+    /// when `abs` is zero.
+    fn nonzero_sign(&mut self, abs: AcirVar, sign: AcirVar) -> Result<AcirVar, RuntimeError> {
+        let zero = self.add_constant(FieldElement::zero());
+        let is_zero = self.eq_var(abs, zero)?;
+        let is_nonzero = self.not_var(is_zero, AcirType::unsigned(1))?;
+        self.mul_var(sign, is_nonzero)
+    }
+
     /// Returns a variable which is constrained to be `lhs mod rhs`
     pub(crate) fn modulo_var(
         &mut self,
@@ -935,6 +985,19 @@ impl AcirContext {
         Ok(remainder)
     }
 
+    /// Returns a variable which is constrained to be the remainder of the division of `lhs` by
+    /// `rhs`, signed integers of `bit_size` bits, which has the sign of `lhs`.
+    pub(crate) fn signed_modulo_var(
+        &mut self,
+        lhs: AcirVar,
+        rhs: AcirVar,
+        bit_size: u32,
+        predicate: AcirVar,
+    ) -> Result<AcirVar, RuntimeError> {
+        let (_, remainder) = self.signed_division_var(lhs, rhs, bit_size, predicate)?;
+        Ok(remainder)
+    }
+
     /// Converts the `AcirVar` to a `Witness` if it hasn't been already, and appends it to the
     /// `GeneratedAcir`'s return witnesses.
     pub(crate) fn return_var(&mut self, acir_var: AcirVar) -> Result<(), InternalError> {
@@ -967,7 +1030,7 @@ impl AcirContext {
                 if let Some(message) = message {
                     self.acir_ir
                         .assert_messages
-                        .insert(self.acir_ir.last_acir_opcode_location(), message);
+                        .insert(self.acir_ir.last_acir_opcode_location(), message.into());
                 }
             }
             NumericType::NativeField => {
@@ -1009,8 +1072,11 @@ impl AcirContext {
         rhs: AcirVar,
         bit_count: u32,
     ) -> Result<AcirVar, RuntimeError> {
-        let pow_last = self.add_constant(FieldElement::from(1_u128 << (bit_count - 1)));
-        let pow = self.add_constant(FieldElement::from(1_u128 << (bit_count)));
+        let pow_last = self.add_constant(
+            FieldElement::from(2_i128).pow(&FieldElement::from(bit_count as i128 - 1)),
+        );
+        let pow = self
+            .add_constant(FieldElement::from(2_i128).pow(&FieldElement::from(bit_count as i128)));
 
         // We check whether the inputs have same sign or not by computing the XOR of their bit sign
 
@@ -1169,6 +1235,31 @@ impl AcirContext {
 
                 vec![domain_constant]
             }
+            BlackBoxFunc::Poseidon2Permutation => {
+                // The last argument is the length of the state, which must be a constant
+                let state_len = match inputs.pop() {
+                    Some(state_len) => state_len.into_var()?,
+                    None => {
+                        return Err(RuntimeError::InternalError(InternalError::MissingArg {
+                            name: "poseidon2 permutation call".to_string(),
+                            arg: "state length".to_string(),
+                            call_stack: self.get_call_stack(),
+                        }))
+                    }
+                };
+
+                let state_len = match self.vars[&state_len].as_constant() {
+                    Some(state_len) => state_len,
+                    None => {
+                        return Err(RuntimeError::InternalError(InternalError::NotAConstant {
+                            name: "state length".to_string(),
+                            call_stack: self.get_call_stack(),
+                        }))
+                    }
+                };
+
+                vec![state_len]
+            }
             _ => vec![],
         };
 
@@ -1210,10 +1301,10 @@ impl AcirContext {
         Ok(witnesses)
     }
 
-    /// Returns a vector of `AcirVar`s constrained to be the decomposition of the given input
-    /// over given radix.
+    /// Returns an array of `limb_count` `AcirVar`s constrained to be the decomposition of the
+    /// given input over given radix.
     ///
-    /// The `AcirVar`s for the `radix_var` and `limb_count_var` must be a constant
+    /// The `AcirVar` for the `radix_var` must be a constant
     ///
     /// TODO: support radix larger than field modulus
     pub(crate) fn radix_decompose(
@@ -1221,9 +1312,9 @@ impl AcirContext {
         endian: Endian,
         input_var: AcirVar,
         radix_var: AcirVar,
-        limb_count_var: AcirVar,
+        limb_count: u32,
         result_element_type: AcirType,
-    ) -> Result<Vec<AcirValue>, RuntimeError> {
+    ) -> Result<AcirValue, RuntimeError> {
         let radix = match self.vars[&radix_var].as_constant() {
             Some(radix) => radix.to_u128() as u32,
             None => {
@@ -1234,16 +1325,6 @@ impl AcirContext {
             }
         };
 
-        let limb_count = match self.vars[&limb_count_var].as_constant() {
-            Some(limb_count) => limb_count.to_u128() as u32,
-            None => {
-                return Err(RuntimeError::InternalError(InternalError::NotAConstant {
-                    name: "limb_size".to_string(),
-                    call_stack: self.get_call_stack(),
-                }));
-            }
-        };
-
         let input_expr = self.var_to_expression(input_var)?;
 
         let bit_size = u32::BITS - (radix - 1).leading_zeros();
@@ -1258,24 +1339,119 @@ impl AcirContext {
             limb_vars.reverse();
         }
 
-        // `Intrinsic::ToRadix` returns slices which are represented
-        // by tuples with the structure (length, slice contents)
-        Ok(vec![
-            AcirValue::Var(self.add_constant(limb_vars.len()), AcirType::field()),
-            AcirValue::Array(limb_vars.into()),
-        ])
+        Ok(AcirValue::Array(limb_vars.into()))
     }
 
-    /// Returns `AcirVar`s constrained to be the bit decomposition of the provided input
+    /// Returns an array of `AcirVar`s constrained to be the bit decomposition of the provided
+    /// input
     pub(crate) fn bit_decompose(
         &mut self,
         endian: Endian,
         input_var: AcirVar,
-        limb_count_var: AcirVar,
+        limb_count: u32,
         result_element_type: AcirType,
-    ) -> Result<Vec<AcirValue>, RuntimeError> {
+    ) -> Result<AcirValue, RuntimeError> {
         let two_var = self.add_constant(2_u128);
-        self.radix_decompose(endian, input_var, two_var, limb_count_var, result_element_type)
+        self.radix_decompose(endian, input_var, two_var, limb_count, result_element_type)
+    }
+
+    /// Returns the `bit_size` bits of `input`, least significant first.
+    fn bits_var(&mut self, input: AcirVar, bit_size: u32) -> Result<Vec<AcirVar>, RuntimeError> {
+        let input_expr = self.var_to_expression(input)?;
+        let bits = self.acir_ir.radix_le_decompose(&input_expr, 2, bit_size, 1)?;
+        Ok(vecmap(bits, |bit| self.add_data(AcirVarData::Witness(bit))))
+    }
+
+    /// Returns the integer whose bits are `bits`, least significant first.
+    fn compose_bits(&mut self, bits: &[AcirVar]) -> Result<AcirVar, RuntimeError> {
+        let mut result = self.add_constant(FieldElement::zero());
+        let mut power = FieldElement::one();
+        for bit in bits {
+            let power_var = self.add_constant(power);
+            let term = self.mul_var(*bit, power_var)?;
+            result = self.add_var(result, term)?;
+            power = power + power;
+        }
+        Ok(result)
+    }
+
+    /// Returns a variable constrained to be the number of ones in the binary representation of
+    /// `input`, an integer of `bit_size` bits.
+    pub(crate) fn count_ones_var(
+        &mut self,
+        input: AcirVar,
+        bit_size: u32,
+    ) -> Result<AcirVar, RuntimeError> {
+        let bits = self.bits_var(input, bit_size)?;
+        let mut count = self.add_constant(FieldElement::zero());
+        for bit in bits {
+            count = self.add_var(count, bit)?;
+        }
+        Ok(count)
+    }
+
+    /// Returns a variable constrained to be the number of leading zeros in the binary
+    /// representation of `input`, an integer of `bit_size` bits.
+    pub(crate) fn leading_zeros_var(
+        &mut self,
+        input: AcirVar,
+        bit_size: u32,
+    ) -> Result<AcirVar, RuntimeError> {
+        let bits = self.bits_var(input, bit_size)?;
+        let one = self.add_constant(FieldElement::one());
+        let mut count = self.add_constant(FieldElement::zero());
+        // Whether all of the bits seen so far, starting from the most significant one, are zero
+        let mut all_zero = one;
+        for bit in bits.into_iter().rev() {
+            let is_zero = self.sub_var(one, bit)?;
+            all_zero = self.mul_var(all_zero, is_zero)?;
+            count = self.add_var(count, all_zero)?;
+        }
+        Ok(count)
+    }
+
+    /// Returns a variable constrained to be `input`, an integer of `bit_size` bits, with its bits
+    /// rotated to the left by `amount` modulo `bit_size`, or to the right if `left` is false.
+    ///
+    /// A rotation by an amount which is not known at compile-time is done in a step for each bit
+    /// of the amount, each of which rotates by the value of the bit if it is set.
+    pub(crate) fn rotate_var(
+        &mut self,
+        input: AcirVar,
+        amount: AcirVar,
+        bit_size: u32,
+        left: bool,
+    ) -> Result<AcirVar, RuntimeError> {
+        let mut bits = self.bits_var(input, bit_size)?;
+        // Rotating the integer to the left moves each bit to a more significant position.
+        let rotate = |bits: &mut Vec<AcirVar>, amount: u128| {
+            let amount = (amount % bit_size as u128) as usize;
+            if left {
+                bits.rotate_right(amount);
+            } else {
+                bits.rotate_left(amount);
+            }
+        };
+
+        if let Some(amount) = self.vars[&amount].as_constant() {
+            rotate(&mut bits, amount.to_u128());
+            return self.compose_bits(&bits);
+        }
+
+        let amount_bits = self.bits_var(amount, u32::BITS)?;
+        for (index, amount_bit) in amount_bits.into_iter().enumerate() {
+            if (1_u128 << index) % bit_size as u128 == 0 {
+                continue;
+            }
+            let mut rotated = bits.clone();
+            rotate(&mut rotated, 1 << index);
+            bits = try_vecmap(bits.into_iter().zip(rotated), |(bit, rotated_bit)| {
+                let difference = self.sub_var(rotated_bit, bit)?;
+                let difference = self.mul_var(amount_bit, difference)?;
+                self.add_var(bit, difference)
+            })?;
+        }
+        self.compose_bits(&bits)
     }
 
     /// Recursive helper for flatten_values to flatten a single AcirValue into the result vector.
@@ -1371,6 +1547,31 @@ impl AcirContext {
         Ok(outputs_var)
     }
 
+    /// Calls the ACIR function `id` with the witnesses of `inputs`, returning a new variable for
+    /// each of its `output_count` outputs. The outputs are zero if `predicate` is false.
+    pub(crate) fn call_acir_function(
+        &mut self,
+        id: u32,
+        inputs: Vec<AcirVar>,
+        output_count: usize,
+        predicate: AcirVar,
+    ) -> Result<Vec<AcirVar>, InternalError> {
+        let inputs = try_vecmap(inputs, |input| {
+            let input = self.get_or_create_witness_var(input)?;
+            self.var_to_witness(input)
+        })?;
+        let outputs = vecmap(0..output_count, |_| self.add_variable());
+        let output_witnesses = try_vecmap(&outputs, |output| self.var_to_witness(*output))?;
+        let predicate = self.var_to_expression(predicate)?;
+        self.acir_ir.push_opcode(Opcode::Call {
+            id,
+            inputs,
+            outputs: output_witnesses,
+            predicate: Some(predicate),
+        });
+        Ok(outputs)
+    }
+
     fn brillig_array_input(
         &mut self,
         var_expressions: &mut Vec<Expression>,
@@ -1541,6 +1742,11 @@ impl AcirContext {
         block_id: BlockId,
         index: &AcirVar,
     ) -> Result<AcirVar, InternalError> {
+        if let Some(element) = self.known_memory_element(block_id, index) {
+            return Ok(element);
+        }
+        self.initialize_memory_block(block_id)?;
+
         // Fetch the witness corresponding to the index
         let index_var = self.get_or_create_witness_var(*index)?;
         let index_witness = self.var_to_witness(index_var)?;
@@ -1563,6 +1769,26 @@ impl AcirContext {
         index: &AcirVar,
         value: &AcirVar,
     ) -> Result<(), InternalError> {
+        let constant_index = self.constant_index(index);
+        if let Some(block) = self.memory_blocks.get_mut(&block_id) {
+            if let (Some(elements), Some(index)) = (&mut block.elements, constant_index) {
+                if index < elements.len() {
+                    elements[index] = *value;
+                    // Until the block is initialized, writes only need to update its elements
+                    if !block.initialized {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+
+        self.initialize_memory_block(block_id)?;
+        if constant_index.is_none() {
+            if let Some(block) = self.memory_blocks.get_mut(&block_id) {
+                block.elements = None;
+            }
+        }
+
         // Fetch the witness corresponding to the index
         let index_var = self.get_or_create_witness_var(*index)?;
         let index_witness = self.var_to_witness(index_var)?;
@@ -1580,51 +1806,62 @@ impl AcirContext {
 
     /// Initializes an array in memory with the given values `optional_values`.
     /// If `optional_values` is empty, then the array is initialized with zeros.
+    ///
+    /// The `MemoryInit` opcode for the array is only emitted once the array is accessed at an
+    /// index which isn't known at compile time.
     pub(crate) fn initialize_array(
         &mut self,
         block_id: BlockId,
         len: usize,
         optional_value: Option<AcirValue>,
     ) -> Result<(), InternalError> {
-        let initialized_values = match optional_value {
-            None => {
-                let zero = self.add_constant(FieldElement::zero());
-                let zero_witness = self.var_to_witness(zero)?;
-                vec![zero_witness; len]
-            }
+        let elements = match optional_value {
+            None => vec![self.add_constant(FieldElement::zero()); len],
             Some(optional_value) => {
-                let mut values = Vec::new();
-                self.initialize_array_inner(&mut values, optional_value)?;
-                values
+                let mut elements = Vec::new();
+                Self::flatten_value(&mut elements, optional_value);
+                elements
             }
         };
 
-        self.acir_ir.push_opcode(Opcode::MemoryInit { block_id, init: initialized_values });
-
+        let block = MemoryBlock { elements: Some(elements), initialized: false };
+        self.memory_blocks.insert(block_id, block);
         Ok(())
     }
 
-    fn initialize_array_inner(
-        &mut self,
-        witnesses: &mut Vec<Witness>,
-        input: AcirValue,
-    ) -> Result<(), InternalError> {
-        match input {
-            AcirValue::Var(var, _) => {
-                let var = self.get_or_create_witness_var(var)?;
-                witnesses.push(self.var_to_witness(var)?);
-            }
-            AcirValue::Array(values) => {
-                for value in values {
-                    self.initialize_array_inner(witnesses, value)?;
-                }
+    /// Emits the `MemoryInit` opcode for `block_id` with its current elements, if it hasn't been
+    /// emitted already.
+    fn initialize_memory_block(&mut self, block_id: BlockId) -> Result<(), InternalError> {
+        let elements = match self.memory_blocks.get_mut(&block_id) {
+            Some(block) if !block.initialized => {
+                block.initialized = true;
+                block
+                    .elements
+                    .clone()
+                    .expect("ICE: uninitialized memory should have known elements")
             }
-            AcirValue::DynamicArray(_) => {
-                unreachable!("Dynamic array should already be initialized");
-            }
-        }
+            _ => return Ok(()),
+        };
+
+        let init = try_vecmap(elements, |element| {
+            let element = self.get_or_create_witness_var(element)?;
+            self.var_to_witness(element)
+        })?;
+        self.acir_ir.push_opcode(Opcode::MemoryInit { block_id, init });
         Ok(())
     }
+
+    /// Returns the element of `block_id` at `index` if both the index and the element are known.
+    fn known_memory_element(&self, block_id: BlockId, index: &AcirVar) -> Option<AcirVar> {
+        let index = self.constant_index(index)?;
+        let elements = self.memory_blocks.get(&block_id)?.elements.as_ref()?;
+        elements.get(index).copied()
+    }
+
+    fn constant_index(&self, index: &AcirVar) -> Option<usize> {
+        let index = self.vars[index].as_constant()?.try_to_u64()?;
+        usize::try_from(index).ok()
+    }
 }
 
 /// Enum representing the possible values that a
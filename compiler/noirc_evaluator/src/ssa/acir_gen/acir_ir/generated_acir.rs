@@ -12,7 +12,7 @@ use acvm::acir::{
     circuit::{
         brillig::{Brillig as AcvmBrillig, BrilligInputs, BrilligOutputs},
         opcodes::{BlackBoxFuncCall, FunctionInput, Opcode as AcirOpcode},
-        OpcodeLocation,
+        AssertionPayload, OpcodeLocation,
     },
     native_types::Witness,
     BlackBoxFunc,
@@ -55,7 +55,7 @@ pub(crate) struct GeneratedAcir {
     pub(crate) call_stack: CallStack,
 
     /// Correspondence between an opcode index and the error message associated with it.
-    pub(crate) assert_messages: BTreeMap<OpcodeLocation, String>,
+    pub(crate) assert_messages: BTreeMap<OpcodeLocation, AssertionPayload>,
 
     pub(crate) warnings: Vec<SsaReport>,
 }
@@ -251,6 +251,11 @@ impl GeneratedAcir {
                 public_inputs: inputs[2].clone(),
                 key_hash: inputs[3][0],
             },
+            BlackBoxFunc::Poseidon2Permutation => BlackBoxFuncCall::Poseidon2Permutation {
+                inputs: inputs[0].clone(),
+                outputs,
+                len: constants[0].to_u128() as u32,
+            },
         };
 
         self.push_opcode(AcirOpcode::BlackBoxFuncCall(black_box_func_call));
@@ -583,6 +588,8 @@ fn black_box_func_expected_input_size(name: BlackBoxFunc) -> Option<usize> {
         | BlackBoxFunc::PedersenHash => None,
 
         BlackBoxFunc::Keccakf1600 => Some(25),
+        // The permutation takes in the whole state, whose width varies between backends.
+        BlackBoxFunc::Poseidon2Permutation => None,
 
         // Can only apply a range constraint to one
         // witness at a time.
@@ -618,6 +625,8 @@ fn black_box_expected_output_size(name: BlackBoxFunc) -> Option<usize> {
         | BlackBoxFunc::Blake2s
         | BlackBoxFunc::Blake3 => Some(32),
         BlackBoxFunc::Keccakf1600 => Some(25),
+        // The permutation returns a state of the same width as its input.
+        BlackBoxFunc::Poseidon2Permutation => None,
         // Pedersen commitment returns a point
         BlackBoxFunc::PedersenCommitment => Some(2),
         // Pedersen hash returns a field
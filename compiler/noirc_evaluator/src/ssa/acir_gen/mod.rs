@@ -10,9 +10,10 @@ use super::ir::dfg::CallStack;
 use super::{
     ir::{
         dfg::DataFlowGraph,
-        function::{Function, RuntimeType},
+        function::{Function, FunctionId, RuntimeType},
         instruction::{
-            Binary, BinaryOp, Instruction, InstructionId, Intrinsic, TerminatorInstruction,
+            AssertMessage, Binary, BinaryOp, Instruction, InstructionId, Intrinsic,
+            TerminatorInstruction,
         },
         map::Id,
         types::{NumericType, Type},
@@ -29,13 +30,16 @@ pub(crate) use acir_ir::generated_acir::GeneratedAcir;
 use acvm::acir::native_types::Witness;
 use acvm::acir::BlackBoxFunc;
 use acvm::{
-    acir::{circuit::opcodes::BlockId, native_types::Expression},
+    acir::{
+        circuit::{opcodes::BlockId, AssertionPayload},
+        native_types::Expression,
+    },
     FieldElement,
 };
 use fxhash::FxHashMap as HashMap;
 use im::Vector;
 use iter_extended::{try_vecmap, vecmap};
-use noirc_frontend::Distinctness;
+use noirc_frontend::{token::MAX_INTEGER_BIT_SIZE, Distinctness};
 
 /// Context struct for the acir generation pass.
 /// May be similar to the Evaluator struct in the current SSA IR.
@@ -84,6 +88,16 @@ struct Context {
     /// takes place thus we track it separate here in this map.
     internal_mem_block_lengths: HashMap<BlockId, usize>,
 
+    /// Maps read-only arrays to an earlier array with the same contents.
+    ///
+    /// These arrays are never written to, so they use the memory block of the earlier array
+    /// rather than initializing a copy of it.
+    shared_arrays: HashMap<ValueId, ValueId>,
+
+    /// Maps each constrained function compiled to a separate ACIR function to the id it is
+    /// called with.
+    acir_function_ids: HashMap<FunctionId, u32>,
+
     /// Number of the next BlockId, it is used to construct
     /// a new BlockId
     max_block_id: u32,
@@ -152,40 +166,99 @@ impl AcirValue {
 }
 
 impl Ssa {
+    /// Converts the SSA into the ACIR of `main`, followed by the ACIR of each function called
+    /// from it through an ACIR call opcode, in the order of the ids the functions are called with.
     #[tracing::instrument(level = "trace", skip_all)]
     pub(crate) fn into_acir(
         self,
         brillig: Brillig,
         abi_distinctness: Distinctness,
-        last_array_uses: &HashMap<ValueId, InstructionId>,
-    ) -> Result<GeneratedAcir, RuntimeError> {
-        let context = Context::new();
-        let mut generated_acir = context.convert_ssa(self, brillig, last_array_uses)?;
-
-        match abi_distinctness {
-            Distinctness::Distinct => {
-                // Create a witness for each return witness we have
-                // to guarantee that the return witnesses are distinct
-                let distinct_return_witness: Vec<_> = generated_acir
-                    .return_witnesses
-                    .clone()
-                    .into_iter()
-                    .map(|return_witness| {
-                        generated_acir
-                            .create_witness_for_expression(&Expression::from(return_witness))
-                    })
-                    .collect();
+        last_array_uses: &HashMap<FunctionId, HashMap<ValueId, InstructionId>>,
+        mut shared_arrays: HashMap<FunctionId, HashMap<ValueId, ValueId>>,
+    ) -> Result<Vec<GeneratedAcir>, RuntimeError> {
+        let called_functions = self.find_called_acir_functions();
+        let acir_function_ids: HashMap<_, _> = called_functions
+            .iter()
+            .enumerate()
+            .map(|(id, function)| (*function, id as u32))
+            .collect();
+
+        let mut generated_acirs = Vec::with_capacity(called_functions.len() + 1);
+        for function_id in std::iter::once(self.main_id).chain(called_functions) {
+            let function = &self.functions[&function_id];
+            let context = Context::new(
+                shared_arrays.remove(&function_id).unwrap_or_default(),
+                acir_function_ids.clone(),
+            );
+            let mut generated_acir = context.convert_ssa_function(
+                function,
+                &self,
+                &brillig,
+                &last_array_uses[&function_id],
+            )?;
 
-                generated_acir.return_witnesses = distinct_return_witness;
-                Ok(generated_acir)
+            // The return values of a called function are read in increasing witness order, so
+            // they are always made distinct.
+            let distinct =
+                function_id != self.main_id || matches!(abi_distinctness, Distinctness::Distinct);
+            if distinct {
+                make_return_witnesses_distinct(&mut generated_acir);
             }
-            Distinctness::DuplicationAllowed => Ok(generated_acir),
+            generated_acirs.push(generated_acir);
         }
+        Ok(generated_acirs)
+    }
+
+    /// Returns the constrained functions called from `main`, directly or through each other,
+    /// which are compiled to separate ACIR functions, in the order they are first called.
+    fn find_called_acir_functions(&self) -> Vec<FunctionId> {
+        let mut called_functions = Vec::new();
+        let mut visited = HashSet::new();
+        let mut stack = vec![self.main_id];
+        while let Some(function_id) = stack.pop() {
+            let function = &self.functions[&function_id];
+            if function.runtime() == RuntimeType::Brillig {
+                continue;
+            }
+            for block in function.reachable_blocks() {
+                for instruction in function.dfg[block].instructions() {
+                    let Instruction::Call { func, .. } = &function.dfg[*instruction] else {
+                        continue;
+                    };
+                    if let Value::Function(callee) = function.dfg[*func] {
+                        let is_acir = self.functions[&callee].runtime() == RuntimeType::Acir;
+                        if is_acir && visited.insert(callee) {
+                            called_functions.push(callee);
+                            stack.push(callee);
+                        }
+                    }
+                }
+            }
+        }
+        called_functions
     }
 }
 
+/// Creates a witness for each return witness we have
+/// to guarantee that the return witnesses are distinct
+fn make_return_witnesses_distinct(generated_acir: &mut GeneratedAcir) {
+    let distinct_return_witness: Vec<_> = generated_acir
+        .return_witnesses
+        .clone()
+        .into_iter()
+        .map(|return_witness| {
+            generated_acir.create_witness_for_expression(&Expression::from(return_witness))
+        })
+        .collect();
+
+    generated_acir.return_witnesses = distinct_return_witness;
+}
+
 impl Context {
-    fn new() -> Context {
+    fn new(
+        shared_arrays: HashMap<ValueId, ValueId>,
+        acir_function_ids: HashMap<FunctionId, u32>,
+    ) -> Context {
         let mut acir_context = AcirContext::default();
         let current_side_effects_enabled_var = acir_context.add_constant(FieldElement::one());
 
@@ -197,34 +270,38 @@ impl Context {
             memory_blocks: HashMap::default(),
             internal_memory_blocks: HashMap::default(),
             internal_mem_block_lengths: HashMap::default(),
+            shared_arrays,
+            acir_function_ids,
             max_block_id: 0,
             data_bus: DataBus::default(),
         }
     }
 
-    /// Converts SSA into ACIR
-    fn convert_ssa(
+    /// Converts an SSA function, either `main` or a function it calls, into ACIR
+    fn convert_ssa_function(
         self,
-        ssa: Ssa,
-        brillig: Brillig,
+        function: &Function,
+        ssa: &Ssa,
+        brillig: &Brillig,
         last_array_uses: &HashMap<ValueId, InstructionId>,
     ) -> Result<GeneratedAcir, RuntimeError> {
-        let main_func = ssa.main();
-        match main_func.runtime() {
-            RuntimeType::Acir => self.convert_acir_main(main_func, &ssa, brillig, last_array_uses),
-            RuntimeType::Brillig => self.convert_brillig_main(main_func, brillig),
+        match function.runtime() {
+            RuntimeType::Acir => {
+                self.convert_acir_function(function, ssa, brillig, last_array_uses)
+            }
+            RuntimeType::Brillig => self.convert_brillig_main(function, brillig),
         }
     }
 
-    fn convert_acir_main(
+    fn convert_acir_function(
         mut self,
-        main_func: &Function,
+        function: &Function,
         ssa: &Ssa,
-        brillig: Brillig,
+        brillig: &Brillig,
         last_array_uses: &HashMap<ValueId, InstructionId>,
     ) -> Result<GeneratedAcir, RuntimeError> {
-        let dfg = &main_func.dfg;
-        let entry_block = &dfg[main_func.entry_block()];
+        let dfg = &function.dfg;
+        let entry_block = &dfg[function.entry_block()];
         let input_witness = self.convert_ssa_block_params(entry_block.parameters(), dfg)?;
 
         self.data_bus = dfg.data_bus.to_owned();
@@ -234,7 +311,7 @@ impl Context {
                 *instruction_id,
                 dfg,
                 ssa,
-                &brillig,
+                brillig,
                 last_array_uses,
             )?);
         }
@@ -246,7 +323,7 @@ impl Context {
     fn convert_brillig_main(
         mut self,
         main_func: &Function,
-        brillig: Brillig,
+        brillig: &Brillig,
     ) -> Result<GeneratedAcir, RuntimeError> {
         let dfg = &main_func.dfg;
 
@@ -259,7 +336,7 @@ impl Context {
         let outputs: Vec<AcirType> =
             vecmap(main_func.returns(), |result_id| dfg.type_of_value(*result_id).into());
 
-        let code = self.gen_brillig_for(main_func, &brillig)?;
+        let code = self.gen_brillig_for(main_func, brillig)?;
 
         // We specifically do not attempt execution of the brillig code being generated as this can result in it being
         // replaced with constraints on witnesses to the program outputs.
@@ -412,8 +489,12 @@ impl Context {
             Instruction::Constrain(lhs, rhs, assert_message) => {
                 let lhs = self.convert_numeric_value(*lhs, dfg)?;
                 let rhs = self.convert_numeric_value(*rhs, dfg)?;
+                let assert_payload = match assert_message {
+                    Some(assert_message) => Some(self.convert_assert_message(assert_message, dfg)?),
+                    None => None,
+                };
 
-                self.acir_context.assert_eq_var(lhs, rhs, assert_message.clone())?;
+                self.acir_context.assert_eq_var(lhs, rhs, assert_payload)?;
             }
             Instruction::Cast(value_id, _) => {
                 let acir_var = self.convert_numeric_value(*value_id, dfg)?;
@@ -425,17 +506,25 @@ impl Context {
                     Value::Function(id) => {
                         let func = &ssa.functions[id];
                         match func.runtime() {
-                            RuntimeType::Acir => unimplemented!(
-                                "expected an intrinsic/brillig call, but found {func:?}. All ACIR methods should be inlined"
-                            ),
+                            RuntimeType::Acir => {
+                                self.convert_acir_call(*id, arguments, result_ids, dfg)?;
+                            }
                             RuntimeType::Brillig => {
                                 let inputs = vecmap(arguments, |arg| self.convert_value(*arg, dfg));
 
                                 let code = self.gen_brillig_for(func, brillig)?;
 
-                                let outputs: Vec<AcirType> = vecmap(result_ids, |result_id| dfg.type_of_value(*result_id).into());
+                                let outputs: Vec<AcirType> = vecmap(result_ids, |result_id| {
+                                    dfg.type_of_value(*result_id).into()
+                                });
 
-                                let output_values = self.acir_context.brillig(self.current_side_effects_enabled_var, code, inputs, outputs, true)?;
+                                let output_values = self.acir_context.brillig(
+                                    self.current_side_effects_enabled_var,
+                                    code,
+                                    inputs,
+                                    outputs,
+                                    true,
+                                )?;
 
                                 // Compiler sanity check
                                 assert_eq!(result_ids.len(), output_values.len(), "ICE: The number of Brillig output values should match the result ids in SSA");
@@ -445,13 +534,20 @@ impl Context {
                                         let array_id = dfg.resolve(*result.0);
                                         let block_id = self.block_id(&array_id);
                                         let array_typ = dfg.type_of_value(array_id);
-                                        self.initialize_array(block_id, array_typ.flattened_size(), Some(result.1.clone()))?;
+                                        self.initialize_array(
+                                            block_id,
+                                            array_typ.flattened_size(),
+                                            Some(result.1.clone()),
+                                        )?;
                                     }
                                     self.ssa_values.insert(*result.0, result.1);
                                 }
                             }
                         }
                     }
+                    Value::Intrinsic(Intrinsic::ArraySwap) => {
+                        self.array_swap(instruction_id, arguments, dfg, last_array_uses)?;
+                    }
                     Value::Intrinsic(intrinsic) => {
                         if matches!(
                             intrinsic,
@@ -543,6 +639,58 @@ impl Context {
         Ok(warnings)
     }
 
+    /// Calls the constrained function `function`, which is compiled to a separate ACIR function,
+    /// defining the results of the call from its outputs.
+    ///
+    /// Arguments and results are passed as the witnesses of their flattened values. The call is
+    /// skipped, with all of its outputs being zero, when side effects are disabled.
+    fn convert_acir_call(
+        &mut self,
+        function: FunctionId,
+        arguments: &[ValueId],
+        result_ids: &[ValueId],
+        dfg: &DataFlowGraph,
+    ) -> Result<(), RuntimeError> {
+        let Some(id) = self.acir_function_ids.get(&function).copied() else {
+            return Err(InternalError::General {
+                message: format!("Calls to the ACIR function {function} should have been inlined"),
+                call_stack: self.acir_context.get_call_stack(),
+            }
+            .into());
+        };
+
+        let mut inputs = Vector::new();
+        for argument in arguments {
+            let argument = self.convert_value(*argument, dfg);
+            self.slice_intrinsic_input(&mut inputs, argument)?;
+        }
+        let inputs = try_vecmap(inputs, AcirValue::into_var)?;
+
+        let result_types = vecmap(result_ids, |result_id| dfg.type_of_value(*result_id));
+        let output_count = result_types.iter().map(Type::flattened_size).sum();
+        let outputs = self.acir_context.call_acir_function(
+            id,
+            inputs,
+            output_count,
+            self.current_side_effects_enabled_var,
+        )?;
+
+        let mut outputs = outputs.into_iter();
+        for (result_id, result_type) in result_ids.iter().zip(result_types) {
+            let result = Self::convert_var_type_to_values(&result_type, &mut outputs);
+            if let AcirValue::Array(_) = &result {
+                let block_id = self.block_id(result_id);
+                self.initialize_array(
+                    block_id,
+                    result_type.flattened_size(),
+                    Some(result.clone()),
+                )?;
+            }
+            self.ssa_values.insert(*result_id, result);
+        }
+        Ok(())
+    }
+
     fn gen_brillig_for(
         &self,
         func: &Function,
@@ -569,7 +717,7 @@ impl Context {
             entry_point.link_with(artifact);
         }
         // Generate the final bytecode
-        Ok(entry_point.finish())
+        Ok(entry_point.finish().optimize())
     }
 
     /// Handles an ArrayGet or ArraySet instruction.
@@ -990,6 +1138,103 @@ impl Context {
         Ok(())
     }
 
+    /// Swaps two elements of an array with two memory writes for each of the element's fields.
+    ///
+    /// The swap is made directly on the array's memory when this is the array's last use,
+    /// otherwise the array is copied first as for an array set.
+    fn array_swap(
+        &mut self,
+        instruction: InstructionId,
+        arguments: &[ValueId],
+        dfg: &DataFlowGraph,
+        last_array_uses: &HashMap<ValueId, InstructionId>,
+    ) -> Result<(), RuntimeError> {
+        let (array, first_index, second_index) = (arguments[0], arguments[1], arguments[2]);
+        let element_types = match dfg.type_of_value(array) {
+            Type::Array(element_types, _) => element_types,
+            _ => unreachable!("ICE: array_swap on non-array"),
+        };
+        let element_size = element_types.len();
+
+        let first_const =
+            dfg.get_numeric_constant(first_index).and_then(|index| index.try_to_u64());
+        let second_const =
+            dfg.get_numeric_constant(second_index).and_then(|index| index.try_to_u64());
+        if let (Some(first), Some(second), AcirValue::Array(mut values)) =
+            (first_const, second_const, self.convert_value(array, dfg))
+        {
+            let array_size = values.len() / element_size.max(1);
+            let (first, second) = (first as usize, second as usize);
+            if first < array_size && second < array_size {
+                for offset in 0..element_size {
+                    values.swap(first * element_size + offset, second * element_size + offset);
+                }
+                self.define_result(dfg, instruction, AcirValue::Array(values));
+                return Ok(());
+            } else if self.acir_context.is_constant_one(&self.current_side_effects_enabled_var) {
+                // Report the error if side effects are enabled.
+                let call_stack = self.acir_context.get_call_stack();
+                return Err(RuntimeError::IndexOutOfBounds {
+                    index: first.max(second),
+                    array_size,
+                    call_stack,
+                });
+            }
+        }
+
+        let (array_id, array_typ, block_id) = self.check_array_is_initialized(array, dfg)?;
+        let array_len = array_typ.flattened_size();
+
+        let result_id = dfg
+            .instruction_results(instruction)
+            .first()
+            .expect("Array swap does not have one result");
+        let result_block_id = if last_array_uses.get(&array_id) == Some(&instruction) {
+            self.memory_blocks.insert(*result_id, block_id);
+            block_id
+        } else {
+            let result_block_id = self.block_id(result_id);
+            self.copy_dynamic_array(block_id, result_block_id, array_len)?;
+            result_block_id
+        };
+
+        // The indices of inactive swaps have already been zeroed out during flattening, and
+        // reading from memory constrains both indices to lie within the array.
+        let element_size_var = self.acir_context.add_constant(element_size);
+        let first_index = self.convert_numeric_value(first_index, dfg)?;
+        let first_index = self.acir_context.mul_var(first_index, element_size_var)?;
+        let second_index = self.convert_numeric_value(second_index, dfg)?;
+        let second_index = self.acir_context.mul_var(second_index, element_size_var)?;
+
+        for (offset, element_type) in element_types.iter().enumerate() {
+            let offset = self.acir_context.add_constant(offset);
+            let first = self.acir_context.add_var(first_index, offset)?;
+            let mut first = self.get_flattened_index(&array_typ, array_id, first, dfg)?;
+            let second = self.acir_context.add_var(second_index, offset)?;
+            let mut second = self.get_flattened_index(&array_typ, array_id, second, dfg)?;
+
+            // Reading a value moves the index past it, so the reads are made with copies.
+            let (mut first_read, mut second_read) = (first, second);
+            let first_value = self.array_get_value(element_type, block_id, &mut first_read)?;
+            let second_value = self.array_get_value(element_type, block_id, &mut second_read)?;
+            self.array_set_value(&second_value, result_block_id, &mut first)?;
+            self.array_set_value(&first_value, result_block_id, &mut second)?;
+        }
+
+        let element_type_sizes = if !can_omit_element_sizes_array(&array_typ) {
+            Some(self.init_element_type_sizes_array(&array_typ, array_id, None, dfg)?)
+        } else {
+            None
+        };
+        let result_value = AcirValue::DynamicArray(AcirDynamicArray {
+            block_id: result_block_id,
+            len: array_len,
+            element_type_sizes,
+        });
+        self.define_result(dfg, instruction, result_value);
+        Ok(())
+    }
+
     fn check_array_is_initialized(
         &mut self,
         array: ValueId,
@@ -997,6 +1242,7 @@ impl Context {
     ) -> Result<(ValueId, Type, BlockId), RuntimeError> {
         // Fetch the internal SSA ID for the array
         let array_id = dfg.resolve(array);
+        let array_id = self.shared_arrays.get(&array_id).copied().unwrap_or(array_id);
 
         let array_typ = dfg.type_of_value(array_id);
 
@@ -1340,6 +1586,24 @@ impl Context {
         acir_value
     }
 
+    /// Converts the message of a constrain instruction into the payload attached to its opcode,
+    /// assigning a witness to each value formatted into the message so that it can be reported.
+    fn convert_assert_message(
+        &mut self,
+        assert_message: &AssertMessage,
+        dfg: &DataFlowGraph,
+    ) -> Result<AssertionPayload, InternalError> {
+        let witnesses = try_vecmap(&assert_message.values, |value| {
+            let var = self.convert_numeric_value(*value, dfg)?;
+            self.acir_context.var_to_witness(var)
+        })?;
+        Ok(AssertionPayload {
+            message: assert_message.message.clone(),
+            witnesses,
+            printable_type: assert_message.printable_type.clone(),
+        })
+    }
+
     fn convert_numeric_value(
         &mut self,
         value_id: ValueId,
@@ -1373,10 +1637,10 @@ impl Context {
         match &binary_type {
             Type::Numeric(NumericType::Unsigned { bit_size })
             | Type::Numeric(NumericType::Signed { bit_size }) => {
-                // Conservative max bit size that is small enough such that two operands can be
-                // multiplied and still fit within the field modulus. This is necessary for the
-                // truncation technique: result % 2^bit_size to be valid.
-                let max_integer_bit_size = FieldElement::max_num_bits() / 2;
+                // Operations on integers larger than this can't be checked for overflow within the
+                // field modulus, which is necessary for the truncation technique:
+                // result % 2^bit_size to be valid.
+                let max_integer_bit_size = MAX_INTEGER_BIT_SIZE;
                 if *bit_size > max_integer_bit_size {
                     return Err(RuntimeError::UnsupportedIntegerSize {
                         num_bits: *bit_size,
@@ -1413,12 +1677,17 @@ impl Context {
             BinaryOp::Xor => self.acir_context.xor_var(lhs, rhs, binary_type),
             BinaryOp::And => self.acir_context.and_var(lhs, rhs, binary_type),
             BinaryOp::Or => self.acir_context.or_var(lhs, rhs, binary_type),
-            BinaryOp::Mod => self.acir_context.modulo_var(
-                lhs,
-                rhs,
-                bit_count,
-                self.current_side_effects_enabled_var,
-            ),
+            BinaryOp::Mod => match binary_type {
+                AcirType::NumericType(NumericType::Signed { .. }) => self
+                    .acir_context
+                    .signed_modulo_var(lhs, rhs, bit_count, self.current_side_effects_enabled_var),
+                _ => self.acir_context.modulo_var(
+                    lhs,
+                    rhs,
+                    bit_count,
+                    self.current_side_effects_enabled_var,
+                ),
+            },
         }
     }
 
@@ -1484,7 +1753,9 @@ impl Context {
                 ) {
                     // Subtractions must first have the integer modulus added before truncation can be
                     // applied. This is done in order to prevent underflow.
-                    let integer_modulus = self.acir_context.add_constant(2_u128.pow(bit_size));
+                    let integer_modulus = self.acir_context.add_constant(
+                        FieldElement::from(2_i128).pow(&FieldElement::from(bit_size as i128)),
+                    );
                     var = self.acir_context.add_var(var, integer_modulus)?;
                 }
             }
@@ -1533,7 +1804,13 @@ impl Context {
                     }
                 }
 
-                let inputs = vecmap(&arguments_no_slice_len, |arg| self.convert_value(*arg, dfg));
+                let mut inputs =
+                    vecmap(&arguments_no_slice_len, |arg| self.convert_value(*arg, dfg));
+                if black_box == BlackBoxFunc::RecursiveAggregation {
+                    // The verification key, proof and public inputs may be held in memory, e.g.
+                    // a key chosen at runtime, in which case they are read into witnesses first.
+                    inputs = try_vecmap(inputs, |input| self.read_array_from_memory(input))?;
+                }
 
                 let output_count = result_ids.iter().fold(0usize, |sum, result_id| {
                     sum + dfg.try_get_array_length(*result_id).unwrap_or(1)
@@ -1549,19 +1826,56 @@ impl Context {
             Intrinsic::ToRadix(endian) => {
                 let field = self.convert_value(arguments[0], dfg).into_var()?;
                 let radix = self.convert_value(arguments[1], dfg).into_var()?;
-                let limb_size = self.convert_value(arguments[2], dfg).into_var()?;
-
-                let result_type = Self::array_element_type(dfg, result_ids[1]);
 
-                self.acir_context.radix_decompose(endian, field, radix, limb_size, result_type)
+                let limb_count = dfg
+                    .try_get_array_length(result_ids[0])
+                    .expect("ICE: radix decompositions must return an array")
+                    as u32;
+                let result_type = Self::array_element_type(dfg, result_ids[0]);
+
+                let limbs = self.acir_context.radix_decompose(
+                    endian,
+                    field,
+                    radix,
+                    limb_count,
+                    result_type,
+                )?;
+                Ok(vec![limbs])
             }
             Intrinsic::ToBits(endian) => {
                 let field = self.convert_value(arguments[0], dfg).into_var()?;
-                let bit_size = self.convert_value(arguments[1], dfg).into_var()?;
 
-                let result_type = Self::array_element_type(dfg, result_ids[1]);
+                let limb_count = dfg
+                    .try_get_array_length(result_ids[0])
+                    .expect("ICE: radix decompositions must return an array")
+                    as u32;
+                let result_type = Self::array_element_type(dfg, result_ids[0]);
 
-                self.acir_context.bit_decompose(endian, field, bit_size, result_type)
+                let bits =
+                    self.acir_context.bit_decompose(endian, field, limb_count, result_type)?;
+                Ok(vec![bits])
+            }
+            Intrinsic::CountOnes | Intrinsic::LeadingZeros => {
+                let input = self.convert_numeric_value(arguments[0], dfg)?;
+                let bit_size = dfg.type_of_value(arguments[0]).bit_size();
+
+                let result = if intrinsic == Intrinsic::CountOnes {
+                    self.acir_context.count_ones_var(input, bit_size)?
+                } else {
+                    self.acir_context.leading_zeros_var(input, bit_size)?
+                };
+
+                Ok(Self::convert_vars_to_values(vec![result], dfg, result_ids))
+            }
+            Intrinsic::RotateLeft | Intrinsic::RotateRight => {
+                let input = self.convert_numeric_value(arguments[0], dfg)?;
+                let amount = self.convert_numeric_value(arguments[1], dfg)?;
+                let bit_size = dfg.type_of_value(arguments[0]).bit_size();
+
+                let left = intrinsic == Intrinsic::RotateLeft;
+                let result = self.acir_context.rotate_var(input, amount, bit_size, left)?;
+
+                Ok(Self::convert_vars_to_values(vec![result], dfg, result_ids))
             }
             Intrinsic::Sort => {
                 let inputs = vecmap(arguments, |arg| self.convert_value(*arg, dfg));
@@ -1590,6 +1904,71 @@ impl Context {
 
                 Ok(Self::convert_vars_to_values(out_vars, dfg, result_ids))
             }
+            Intrinsic::StrEq => {
+                let lhs = self.convert_value(arguments[0], dfg);
+                let rhs = self.convert_value(arguments[1], dfg);
+                let lhs = self.flatten_bytes(lhs)?;
+                let rhs = self.flatten_bytes(rhs)?;
+                assert_eq!(lhs.len(), rhs.len(), "ICE: Expected two strings of equal length");
+
+                // Up to 31 bytes can be packed into a single field element without overflowing,
+                // so we compare the strings in chunks of 31 bytes rather than byte by byte.
+                // This is only sound if every byte is range constrained, see `pack_bytes`.
+                let mut result = self.acir_context.add_constant(FieldElement::one());
+                for (lhs_chunk, rhs_chunk) in lhs.chunks(31).zip(rhs.chunks(31)) {
+                    let lhs_chunk = self.pack_bytes(lhs_chunk)?;
+                    let rhs_chunk = self.pack_bytes(rhs_chunk)?;
+                    let chunk_equal = self.acir_context.eq_var(lhs_chunk, rhs_chunk)?;
+                    result = self.acir_context.mul_var(result, chunk_equal)?;
+                }
+
+                Ok(Self::convert_vars_to_values(vec![result], dfg, result_ids))
+            }
+            Intrinsic::StrSubstring => {
+                let substring_length = match dfg.type_of_value(result_ids[0]) {
+                    Type::Array(_, length) => length,
+                    _ => unreachable!("ICE: str_substring must return a string"),
+                };
+                let start_const =
+                    dfg.get_numeric_constant(arguments[1]).and_then(|start| start.try_to_u64());
+
+                if let (Some(start), AcirValue::Array(mut string)) =
+                    (start_const, self.convert_value(arguments[0], dfg))
+                {
+                    let start = start as usize;
+                    let end = start + substring_length;
+                    if end <= string.len() {
+                        return Ok(vec![AcirValue::Array(string.slice(start..end))]);
+                    } else if self
+                        .acir_context
+                        .is_constant_one(&self.current_side_effects_enabled_var)
+                    {
+                        // Report the error if side effects are enabled.
+                        let call_stack = self.acir_context.get_call_stack();
+                        return Err(RuntimeError::IndexOutOfBounds {
+                            index: end - 1,
+                            array_size: string.len(),
+                            call_stack,
+                        });
+                    }
+                }
+
+                // The substring is read from memory, which constrains each index to lie within
+                // the string. The start of an inactive substring has already been zeroed out
+                // during flattening.
+                let (_, _, block_id) = self.check_array_is_initialized(arguments[0], dfg)?;
+                let start = self.convert_numeric_value(arguments[1], dfg)?;
+
+                let bytes = try_vecmap(0..substring_length, |i| {
+                    let offset = self.acir_context.add_constant(i);
+                    let index = self.acir_context.add_var(start, offset)?;
+                    Ok::<AcirVar, RuntimeError>(
+                        self.acir_context.read_from_memory(block_id, &index)?,
+                    )
+                })?;
+
+                Ok(Self::convert_vars_to_values(bytes, dfg, result_ids))
+            }
             Intrinsic::ArrayLen => {
                 let len = match self.convert_value(arguments[0], dfg) {
                     AcirValue::Var(_, _) => unreachable!("Non-array passed to array.len() method"),
@@ -1828,6 +2207,10 @@ impl Context {
                 let max_flat_user_index =
                     self.acir_context.add_var(flat_user_index, inner_elem_size)?;
 
+                // The inserted elements are written past the end of the original slice when
+                // the slice's length is equal to its capacity, so the capacity must grow to fit them.
+                let new_slice_size = slice_size + inner_elem_size_usize;
+
                 // Go through the entire slice argument and determine what value should be written to the new slice.
                 // 1. If we are below the starting insertion index we should insert the value that was already
                 //    in the original slice.
@@ -1836,56 +2219,71 @@ impl Context {
                 // 3. If we are above the max insertion index we should insert the previous value from the original slice,
                 //    as during an insertion we want to shift all elements after the insertion up an index.
                 let result_block_id = self.block_id(&result_ids[1]);
-                self.initialize_array(result_block_id, slice_size, None)?;
+                self.initialize_array(result_block_id, new_slice_size, None)?;
                 let mut current_insert_index = 0;
-                for i in 0..slice_size {
+                for i in 0..new_slice_size {
                     let current_index = self.acir_context.add_constant(i);
 
-                    // Check that we are above the lower bound of the insertion index
-                    let greater_eq_than_idx =
-                        self.acir_context.more_than_eq_var(current_index, flat_user_index, 64)?;
-                    // Check that we are below the upper bound of the insertion index
-                    let less_than_idx =
-                        self.acir_context.less_than_var(current_index, max_flat_user_index, 64)?;
-
-                    // Read from the original slice the value we want to insert into our new slice.
-                    // We need to make sure that we read the previous element when our current index is greater than insertion index.
-                    // If the index for the previous element is out of the array bounds we can avoid the check for whether
-                    // the current index is over the insertion index.
-                    let shifted_index = if i < inner_elem_size_usize {
-                        current_index
+                    // Past the end of the original slice there is nothing to shift up, so only
+                    // the elements being inserted can be written there.
+                    let new_value = if i >= slice_size && i < inner_elem_size_usize {
+                        flattened_elements[current_insert_index]
                     } else {
-                        let index_minus_elem_size =
-                            self.acir_context.add_constant(i - inner_elem_size_usize);
+                        // Check that we are above the lower bound of the insertion index
+                        let greater_eq_than_idx = self.acir_context.more_than_eq_var(
+                            current_index,
+                            flat_user_index,
+                            64,
+                        )?;
+                        // Check that we are below the upper bound of the insertion index
+                        let less_than_idx = self.acir_context.less_than_var(
+                            current_index,
+                            max_flat_user_index,
+                            64,
+                        )?;
 
-                        let use_shifted_index_pred = self
-                            .acir_context
-                            .mul_var(index_minus_elem_size, greater_eq_than_idx)?;
+                        // Read from the original slice the value we want to insert into our new slice.
+                        // We need to make sure that we read the previous element when our current index is greater than insertion index.
+                        // If the index for the previous element is out of the array bounds we can avoid the check for whether
+                        // the current index is over the insertion index.
+                        let shifted_index = if i < inner_elem_size_usize {
+                            current_index
+                        } else if i >= slice_size {
+                            // Past the end of the original slice the value is always shifted up
+                            self.acir_context.add_constant(i - inner_elem_size_usize)
+                        } else {
+                            let index_minus_elem_size =
+                                self.acir_context.add_constant(i - inner_elem_size_usize);
 
-                        let not_pred = self.acir_context.sub_var(one, greater_eq_than_idx)?;
-                        let use_current_index_pred =
-                            self.acir_context.mul_var(not_pred, current_index)?;
+                            let use_shifted_index_pred = self
+                                .acir_context
+                                .mul_var(index_minus_elem_size, greater_eq_than_idx)?;
 
-                        self.acir_context.add_var(use_shifted_index_pred, use_current_index_pred)?
-                    };
+                            let not_pred = self.acir_context.sub_var(one, greater_eq_than_idx)?;
+                            let use_current_index_pred =
+                                self.acir_context.mul_var(not_pred, current_index)?;
 
-                    let value_shifted_index =
-                        self.acir_context.read_from_memory(block_id, &shifted_index)?;
+                            self.acir_context
+                                .add_var(use_shifted_index_pred, use_current_index_pred)?
+                        };
 
-                    // Final predicate to determine whether we are within the insertion bounds
-                    let should_insert_value_pred =
-                        self.acir_context.mul_var(greater_eq_than_idx, less_than_idx)?;
-                    let insert_value_pred = self.acir_context.mul_var(
-                        flattened_elements[current_insert_index],
-                        should_insert_value_pred,
-                    )?;
+                        let value_shifted_index =
+                            self.acir_context.read_from_memory(block_id, &shifted_index)?;
+
+                        // Final predicate to determine whether we are within the insertion bounds
+                        let should_insert_value_pred =
+                            self.acir_context.mul_var(greater_eq_than_idx, less_than_idx)?;
+                        let insert_value_pred = self.acir_context.mul_var(
+                            flattened_elements[current_insert_index],
+                            should_insert_value_pred,
+                        )?;
 
-                    let not_pred = self.acir_context.sub_var(one, should_insert_value_pred)?;
-                    let shifted_value_pred =
-                        self.acir_context.mul_var(not_pred, value_shifted_index)?;
+                        let not_pred = self.acir_context.sub_var(one, should_insert_value_pred)?;
+                        let shifted_value_pred =
+                            self.acir_context.mul_var(not_pred, value_shifted_index)?;
 
-                    let new_value =
-                        self.acir_context.add_var(insert_value_pred, shifted_value_pred)?;
+                        self.acir_context.add_var(insert_value_pred, shifted_value_pred)?
+                    };
 
                     self.acir_context.write_to_memory(
                         result_block_id,
@@ -1911,7 +2309,7 @@ impl Context {
                 };
                 let result = AcirValue::DynamicArray(AcirDynamicArray {
                     block_id: result_block_id,
-                    len: slice_size,
+                    len: new_slice_size,
                     element_type_sizes,
                 });
 
@@ -2070,6 +2468,44 @@ impl Context {
         Ok(())
     }
 
+    /// Returns an array holding the values of `value` if it is an array held in memory, or `value`
+    /// itself otherwise. The values are read as fields.
+    fn read_array_from_memory(&mut self, value: AcirValue) -> Result<AcirValue, RuntimeError> {
+        match value {
+            AcirValue::Var(..) => Ok(value),
+            AcirValue::Array(_) | AcirValue::DynamicArray(_) => {
+                let mut values = Vector::new();
+                self.slice_intrinsic_input(&mut values, value)?;
+                Ok(AcirValue::Array(values))
+            }
+        }
+    }
+
+    /// Flattens a string or byte array into the variables for each of its bytes.
+    fn flatten_bytes(&mut self, value: AcirValue) -> Result<Vec<AcirVar>, RuntimeError> {
+        let mut bytes = Vector::new();
+        self.slice_intrinsic_input(&mut bytes, value)?;
+        Ok(try_vecmap(bytes, AcirValue::into_var)?)
+    }
+
+    /// Packs bytes into a single variable, with the first byte being the most significant.
+    ///
+    /// Each byte is range constrained to 8 bits first, as otherwise a prover could supply
+    /// out-of-range witnesses whose packings collide with those of different byte strings.
+    /// Constraints on bytes which are already known to be in range are removed again by the
+    /// range constraint optimization pass.
+    fn pack_bytes(&mut self, bytes: &[AcirVar]) -> Result<AcirVar, RuntimeError> {
+        let byte_type = NumericType::Unsigned { bit_size: 8 };
+        let byte_base = self.acir_context.add_constant(256_u128);
+        let mut packed = self.acir_context.add_constant(FieldElement::zero());
+        for byte in bytes {
+            let byte = self.acir_context.range_constrain_var(*byte, &byte_type, None)?;
+            packed = self.acir_context.mul_var(packed, byte_base)?;
+            packed = self.acir_context.add_var(packed, byte)?;
+        }
+        Ok(packed)
+    }
+
     /// Given an array value, return the numerical type of its element.
     /// Panics if the given value is not an array or has a non-numeric element type.
     fn array_element_type(dfg: &DataFlowGraph, value: ValueId) -> AcirType {
@@ -41,6 +41,19 @@ impl DataBusBuilder {
         }
         params_is_databus
     }
+
+    /// Generates a boolean vector telling which (ssa) parameter from the given function signature
+    /// are public inputs of the program
+    pub(crate) fn is_public(main_signature: &FunctionSignature) -> Vec<bool> {
+        let mut params_is_public = Vec::new();
+
+        for param in &main_signature.0 {
+            let is_public = matches!(param.2, noirc_frontend::Visibility::Public);
+            let len = param.1.field_count() as usize;
+            params_is_public.extend(vec![is_public; len]);
+        }
+        params_is_public
+    }
 }
 
 #[derive(Clone, Default, Debug)]
@@ -8,7 +8,7 @@ use noirc_errors::Location;
 use crate::ssa::ir::{
     basic_block::BasicBlockId,
     function::{Function, FunctionId},
-    instruction::{Binary, BinaryOp, Instruction, TerminatorInstruction},
+    instruction::{AssertMessage, Binary, BinaryOp, Instruction, TerminatorInstruction},
     types::Type,
     value::{Value, ValueId},
 };
@@ -250,7 +250,7 @@ impl FunctionBuilder {
         &mut self,
         lhs: ValueId,
         rhs: ValueId,
-        assert_message: Option<String>,
+        assert_message: Option<AssertMessage>,
     ) {
         self.insert_instruction(Instruction::Constrain(lhs, rhs, assert_message), None);
     }
@@ -283,43 +283,52 @@ impl FunctionBuilder {
     /// and truncate the result to bit_size
     pub(crate) fn insert_wrapping_shift_left(
         &mut self,
-        lhs: ValueId,
+        mut lhs: ValueId,
         rhs: ValueId,
         bit_size: u32,
     ) -> ValueId {
-        let base = self.field_constant(FieldElement::from(2_u128));
         let typ = self.current_function.dfg.type_of_value(lhs);
-        let (max_bit, pow) =
-            if let Some(rhs_constant) = self.current_function.dfg.get_numeric_constant(rhs) {
-                // Happy case is that we know precisely by how many bits the the integer will
-                // increase: lhs_bit_size + rhs
-                let bit_shift_size = rhs_constant.to_u128() as u32;
-
-                let (rhs_bit_size_pow_2, overflows) = 2_u128.overflowing_pow(bit_shift_size);
-                if overflows {
-                    assert!(bit_size < 128, "ICE - shift left with big integers are not supported");
-                    if bit_size < 128 {
-                        let zero = self.numeric_constant(FieldElement::zero(), typ);
-                        return InsertInstructionResult::SimplifiedTo(zero).first();
-                    }
-                }
-                let pow = self.numeric_constant(FieldElement::from(rhs_bit_size_pow_2), typ);
-
-                let max_lhs_bits = self.current_function.dfg.get_value_max_num_bits(lhs);
-
-                (max_lhs_bits + bit_shift_size, pow)
-            } else {
-                // we use a predicate to nullify the result in case of overflow
-                let bit_size_var =
-                    self.numeric_constant(FieldElement::from(bit_size as u128), typ.clone());
-                let overflow = self.insert_binary(rhs, BinaryOp::Lt, bit_size_var);
-                let predicate = self.insert_cast(overflow, typ.clone());
-                // we can safely cast to unsigned because overflow_checks prevent bit-shift with a negative value
-                let rhs_unsigned = self.insert_cast(rhs, Type::unsigned(bit_size));
-                let pow = self.pow(base, rhs_unsigned);
-                let pow = self.insert_cast(pow, typ);
-                (FieldElement::max_num_bits(), self.insert_binary(predicate, BinaryOp::Mul, pow))
-            };
+        let (max_bit, pow) = if let Some(rhs_constant) =
+            self.current_function.dfg.get_numeric_constant(rhs)
+        {
+            // Happy case is that we know precisely by how many bits the the integer will
+            // increase: lhs_bit_size + rhs
+            let bit_shift_size = rhs_constant.to_u128() as u32;
+
+            let (rhs_bit_size_pow_2, overflows) = 2_u128.overflowing_pow(bit_shift_size);
+            if overflows {
+                // Shifting by at least 128 bits overflows any integer type, so this is only
+                // reached when the overflow check of the shift fails.
+                let zero = self.numeric_constant(FieldElement::zero(), typ);
+                return InsertInstructionResult::SimplifiedTo(zero).first();
+            }
+
+            let mut max_lhs_bits = self.current_function.dfg.get_value_max_num_bits(lhs);
+            if max_lhs_bits + bit_shift_size >= FieldElement::max_num_bits() {
+                // The shifted value may not fit in the field, so the bits of `lhs` which would
+                // be shifted out are truncated beforehand.
+                let kept_bits = bit_size.saturating_sub(bit_shift_size);
+                lhs = self.insert_truncate(lhs, kept_bits, max_lhs_bits);
+                max_lhs_bits = kept_bits;
+            }
+            let pow = self.numeric_constant(FieldElement::from(rhs_bit_size_pow_2), typ);
+
+            (max_lhs_bits + bit_shift_size, pow)
+        } else if 2 * bit_size > FieldElement::max_num_bits() {
+            // `lhs * 2^rhs` may not fit in the field, so `lhs` is shifted by half of `rhs`
+            // first and the truncated result is then shifted by the other half.
+            let rhs = self.insert_cast(rhs, Type::unsigned(bit_size));
+            let two = self.numeric_constant(FieldElement::from(2_u128), Type::unsigned(bit_size));
+            let first_shift = self.insert_binary(rhs, BinaryOp::Div, two);
+            let second_shift = self.insert_binary(rhs, BinaryOp::Sub, first_shift);
+
+            let pow = self.shift_left_power(first_shift, bit_size, typ.clone());
+            let shifted = self.insert_binary(lhs, BinaryOp::Mul, pow);
+            lhs = self.insert_truncate(shifted, bit_size, FieldElement::max_num_bits());
+            (FieldElement::max_num_bits(), self.shift_left_power(second_shift, bit_size, typ))
+        } else {
+            (FieldElement::max_num_bits(), self.shift_left_power(rhs, bit_size, typ))
+        };
 
         if max_bit <= bit_size {
             self.insert_binary(lhs, BinaryOp::Mul, pow)
@@ -329,6 +338,21 @@ impl FunctionBuilder {
         }
     }
 
+    /// Insert ssa instructions which compute `2^rhs` as a value of type `typ`, or zero if shifting
+    /// an integer of `bit_size` bits by `rhs` would overflow.
+    fn shift_left_power(&mut self, rhs: ValueId, bit_size: u32, typ: Type) -> ValueId {
+        let base = self.field_constant(FieldElement::from(2_u128));
+        // we use a predicate to nullify the result in case of overflow
+        let bit_size_var = self.numeric_constant(FieldElement::from(bit_size as u128), typ.clone());
+        let overflow = self.insert_binary(rhs, BinaryOp::Lt, bit_size_var);
+        let predicate = self.insert_cast(overflow, typ.clone());
+        // we can safely cast to unsigned because overflow_checks prevent bit-shift with a negative value
+        let rhs_unsigned = self.insert_cast(rhs, Type::unsigned(bit_size));
+        let pow = self.pow(base, rhs_unsigned);
+        let pow = self.insert_cast(pow, typ);
+        self.insert_binary(predicate, BinaryOp::Mul, pow)
+    }
+
     /// Insert ssa instructions which computes lhs >> rhs by doing lhs/2^rhs
     pub(crate) fn insert_shift_right(
         &mut self,
@@ -356,11 +380,8 @@ impl FunctionBuilder {
         let typ = self.current_function.dfg.type_of_value(rhs);
         if let Type::Numeric(NumericType::Unsigned { bit_size }) = typ {
             let to_bits = self.import_intrinsic_id(Intrinsic::ToBits(Endian::Little));
-            let length = self.field_constant(FieldElement::from(bit_size as i128));
-            let result_types =
-                vec![Type::field(), Type::Array(Rc::new(vec![Type::bool()]), bit_size as usize)];
-            let rhs_bits = self.insert_call(to_bits, vec![rhs, length], result_types);
-            let rhs_bits = rhs_bits[1];
+            let result_types = vec![Type::Array(Rc::new(vec![Type::bool()]), bit_size as usize)];
+            let rhs_bits = self.insert_call(to_bits, vec![rhs], result_types)[0];
             let one = self.field_constant(FieldElement::one());
             let mut r = one;
             for i in 1..bit_size + 1 {
@@ -539,7 +560,7 @@ mod tests {
     fn insert_constant_call() {
         // `bits` should be an array of constants [1, 1, 1, 0...] of length 8:
         // let x = 7;
-        // let bits = x.to_le_bits(8);
+        // let bits: [u1; 8] = x.to_le_bits();
         let func_id = Id::test_new(0);
         let mut builder = FunctionBuilder::new("func".into(), func_id, RuntimeType::Acir);
         let one = builder.numeric_constant(FieldElement::one(), Type::bool());
@@ -547,24 +568,17 @@ mod tests {
 
         let to_bits_id = builder.import_intrinsic_id(Intrinsic::ToBits(Endian::Little));
         let input = builder.numeric_constant(FieldElement::from(7_u128), Type::field());
-        let length = builder.numeric_constant(FieldElement::from(8_u128), Type::field());
         let result_types = vec![Type::Array(Rc::new(vec![Type::bool()]), 8)];
-        let call_results =
-            builder.insert_call(to_bits_id, vec![input, length], result_types).into_owned();
-
-        let slice_len = match &builder.current_function.dfg[call_results[0]] {
-            Value::NumericConstant { constant, .. } => *constant,
-            _ => panic!(),
-        };
-        assert_eq!(slice_len, FieldElement::from(8_u128));
+        let call_results = builder.insert_call(to_bits_id, vec![input], result_types).into_owned();
 
-        let slice = match &builder.current_function.dfg[call_results[1]] {
+        let array = match &builder.current_function.dfg[call_results[0]] {
             Value::Array { array, .. } => array,
             _ => panic!(),
         };
-        assert_eq!(slice[0], one);
-        assert_eq!(slice[1], one);
-        assert_eq!(slice[2], one);
-        assert_eq!(slice[3], zero);
+        assert_eq!(array.len(), 8);
+        assert_eq!(array[0], one);
+        assert_eq!(array[1], one);
+        assert_eq!(array[2], one);
+        assert_eq!(array[3], zero);
     }
 }
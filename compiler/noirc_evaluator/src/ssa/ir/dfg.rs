@@ -221,7 +221,8 @@ impl DataFlowGraph {
         match value {
             Value::Instruction { typ, .. }
             | Value::Param { typ, .. }
-            | Value::NumericConstant { typ, .. } => {
+            | Value::NumericConstant { typ, .. }
+            | Value::Array { typ, .. } => {
                 *typ = target_type;
             }
             _ => {
@@ -1,6 +1,7 @@
 use std::collections::BTreeSet;
 
 use iter_extended::vecmap;
+use noirc_frontend::token::InlineType;
 
 use super::basic_block::BasicBlockId;
 use super::dfg::DataFlowGraph;
@@ -35,6 +36,15 @@ pub(crate) struct Function {
 
     runtime: RuntimeType,
 
+    /// True if the function was annotated with `#[constant_time]`
+    constant_time: bool,
+
+    /// The parameters of `main` which are public inputs of the program
+    public_parameters: BTreeSet<ValueId>,
+
+    /// How calls to the function are inlined, as requested by its `#[inline(...)]` attribute
+    inline_type: InlineType,
+
     /// The DataFlowGraph holds the majority of data pertaining to the function
     /// including its blocks, instructions, and values.
     pub(crate) dfg: DataFlowGraph,
@@ -47,7 +57,16 @@ impl Function {
     pub(crate) fn new(name: String, id: FunctionId) -> Self {
         let mut dfg = DataFlowGraph::default();
         let entry_block = dfg.make_block();
-        Self { name, id, entry_block, dfg, runtime: RuntimeType::Acir }
+        Self {
+            name,
+            id,
+            entry_block,
+            dfg,
+            runtime: RuntimeType::Acir,
+            constant_time: false,
+            public_parameters: BTreeSet::new(),
+            inline_type: InlineType::Default,
+        }
     }
 
     /// The name of the function.
@@ -71,6 +90,37 @@ impl Function {
         self.runtime = runtime;
     }
 
+    /// True if the function must be checked to not branch on or index memory using its inputs.
+    pub(crate) fn is_constant_time(&self) -> bool {
+        self.constant_time
+    }
+
+    /// Mark the function as requiring the constant time checks.
+    pub(crate) fn set_constant_time(&mut self, constant_time: bool) {
+        self.constant_time = constant_time;
+    }
+
+    /// The parameters of the function which are public inputs of the program. Only `main` has
+    /// public parameters.
+    pub(crate) fn public_parameters(&self) -> &BTreeSet<ValueId> {
+        &self.public_parameters
+    }
+
+    /// Mark the given parameters as public inputs of the program.
+    pub(crate) fn set_public_parameters(&mut self, public_parameters: BTreeSet<ValueId>) {
+        self.public_parameters = public_parameters;
+    }
+
+    /// How calls to the function are inlined into its callers.
+    pub(crate) fn inline_type(&self) -> InlineType {
+        self.inline_type
+    }
+
+    /// Set how calls to the function are inlined into its callers.
+    pub(crate) fn set_inline_type(&mut self, inline_type: InlineType) {
+        self.inline_type = inline_type;
+    }
+
     /// Retrieves the entry block of a function.
     ///
     /// A function's entry block contains the instructions
@@ -119,6 +169,14 @@ impl Function {
         blocks
     }
 
+    /// Returns the number of instructions within the reachable blocks of this function.
+    ///
+    /// Unlike [`DataFlowGraph::num_instructions`] this does not count any
+    /// instructions which have been removed from their block.
+    pub(crate) fn num_instructions(&self) -> usize {
+        self.reachable_blocks().iter().map(|block| self.dfg[*block].instructions().len()).sum()
+    }
+
     pub(crate) fn signature(&self) -> Signature {
         let params = vecmap(self.parameters(), |param| self.dfg.type_of_value(*param));
         let returns = vecmap(self.returns(), |ret| self.dfg.type_of_value(*ret));
@@ -1,5 +1,6 @@
 use acvm::{acir::BlackBoxFunc, FieldElement};
 use iter_extended::vecmap;
+use num_bigint::BigUint;
 
 use super::{
     basic_block::BasicBlockId,
@@ -9,11 +10,13 @@ use super::{
     value::{Value, ValueId},
 };
 
+mod array;
 mod binary;
 mod call;
 mod cast;
 mod constrain;
 
+use array::simplify_array_get;
 pub(crate) use binary::{Binary, BinaryOp};
 use call::simplify_call;
 use cast::simplify_cast;
@@ -39,19 +42,29 @@ pub(crate) enum Intrinsic {
     Sort,
     ArrayLen,
     AssertConstant,
+    StaticAssert,
     SlicePushBack,
     SlicePushFront,
     SlicePopBack,
     SlicePopFront,
     SliceInsert,
     SliceRemove,
+    ArraySwap,
     ApplyRangeConstraint,
     StrAsBytes,
+    StrEq,
+    StrSubstring,
     ToBits(Endian),
     ToRadix(Endian),
     BlackBox(BlackBoxFunc),
     FromField,
     AsField,
+    /// Integer addition, subtraction or multiplication with the given overflow behavior
+    Arithmetic(BinaryOp, OverflowBehavior),
+    CountOnes,
+    LeadingZeros,
+    RotateLeft,
+    RotateRight,
 }
 
 impl std::fmt::Display for Intrinsic {
@@ -60,13 +73,17 @@ impl std::fmt::Display for Intrinsic {
             Intrinsic::Sort => write!(f, "arraysort"),
             Intrinsic::ArrayLen => write!(f, "array_len"),
             Intrinsic::AssertConstant => write!(f, "assert_constant"),
+            Intrinsic::StaticAssert => write!(f, "static_assert"),
             Intrinsic::SlicePushBack => write!(f, "slice_push_back"),
             Intrinsic::SlicePushFront => write!(f, "slice_push_front"),
             Intrinsic::SlicePopBack => write!(f, "slice_pop_back"),
             Intrinsic::SlicePopFront => write!(f, "slice_pop_front"),
             Intrinsic::SliceInsert => write!(f, "slice_insert"),
             Intrinsic::SliceRemove => write!(f, "slice_remove"),
+            Intrinsic::ArraySwap => write!(f, "array_swap"),
             Intrinsic::StrAsBytes => write!(f, "str_as_bytes"),
+            Intrinsic::StrEq => write!(f, "str_eq"),
+            Intrinsic::StrSubstring => write!(f, "str_substring"),
             Intrinsic::ApplyRangeConstraint => write!(f, "apply_range_constraint"),
             Intrinsic::ToBits(Endian::Big) => write!(f, "to_be_bits"),
             Intrinsic::ToBits(Endian::Little) => write!(f, "to_le_bits"),
@@ -75,6 +92,11 @@ impl std::fmt::Display for Intrinsic {
             Intrinsic::BlackBox(function) => write!(f, "{function}"),
             Intrinsic::FromField => write!(f, "from_field"),
             Intrinsic::AsField => write!(f, "as_field"),
+            Intrinsic::Arithmetic(operator, behavior) => write!(f, "{behavior}_{operator}"),
+            Intrinsic::CountOnes => write!(f, "count_ones"),
+            Intrinsic::LeadingZeros => write!(f, "leading_zeros"),
+            Intrinsic::RotateLeft => write!(f, "rotate_left"),
+            Intrinsic::RotateRight => write!(f, "rotate_right"),
         }
     }
 }
@@ -85,11 +107,19 @@ impl Intrinsic {
     /// If there are no side effects then the `Intrinsic` can be removed if the result is unused.
     pub(crate) fn has_side_effects(&self) -> bool {
         match self {
-            Intrinsic::AssertConstant | Intrinsic::ApplyRangeConstraint => true,
+            Intrinsic::AssertConstant
+            | Intrinsic::StaticAssert
+            | Intrinsic::ApplyRangeConstraint => true,
 
             // These apply a constraint that the input must fit into a specified number of limbs.
             Intrinsic::ToBits(_) | Intrinsic::ToRadix(_) => true,
 
+            // This applies a constraint that the substring is within the bounds of the string.
+            Intrinsic::StrSubstring => true,
+
+            // This applies a constraint that both indices are within the bounds of the array.
+            Intrinsic::ArraySwap => true,
+
             Intrinsic::Sort
             | Intrinsic::ArrayLen
             | Intrinsic::SlicePushBack
@@ -99,8 +129,14 @@ impl Intrinsic {
             | Intrinsic::SliceInsert
             | Intrinsic::SliceRemove
             | Intrinsic::StrAsBytes
+            | Intrinsic::StrEq
             | Intrinsic::FromField
-            | Intrinsic::AsField => false,
+            | Intrinsic::AsField
+            | Intrinsic::Arithmetic(..)
+            | Intrinsic::CountOnes
+            | Intrinsic::LeadingZeros
+            | Intrinsic::RotateLeft
+            | Intrinsic::RotateRight => false,
 
             // Some black box functions have side-effects
             Intrinsic::BlackBox(func) => matches!(func, BlackBoxFunc::RecursiveAggregation),
@@ -114,6 +150,7 @@ impl Intrinsic {
             "arraysort" => Some(Intrinsic::Sort),
             "array_len" => Some(Intrinsic::ArrayLen),
             "assert_constant" => Some(Intrinsic::AssertConstant),
+            "static_assert" => Some(Intrinsic::StaticAssert),
             "apply_range_constraint" => Some(Intrinsic::ApplyRangeConstraint),
             "slice_push_back" => Some(Intrinsic::SlicePushBack),
             "slice_push_front" => Some(Intrinsic::SlicePushFront),
@@ -121,13 +158,41 @@ impl Intrinsic {
             "slice_pop_front" => Some(Intrinsic::SlicePopFront),
             "slice_insert" => Some(Intrinsic::SliceInsert),
             "slice_remove" => Some(Intrinsic::SliceRemove),
+            "array_swap" => Some(Intrinsic::ArraySwap),
             "str_as_bytes" => Some(Intrinsic::StrAsBytes),
+            "str_eq" => Some(Intrinsic::StrEq),
+            "str_substring" => Some(Intrinsic::StrSubstring),
             "to_le_radix" => Some(Intrinsic::ToRadix(Endian::Little)),
             "to_be_radix" => Some(Intrinsic::ToRadix(Endian::Big)),
             "to_le_bits" => Some(Intrinsic::ToBits(Endian::Little)),
             "to_be_bits" => Some(Intrinsic::ToBits(Endian::Big)),
             "from_field" => Some(Intrinsic::FromField),
             "as_field" => Some(Intrinsic::AsField),
+            "wrapping_add" => {
+                Some(Intrinsic::Arithmetic(BinaryOp::Add, OverflowBehavior::Wrapping))
+            }
+            "wrapping_sub" => {
+                Some(Intrinsic::Arithmetic(BinaryOp::Sub, OverflowBehavior::Wrapping))
+            }
+            "wrapping_mul" => {
+                Some(Intrinsic::Arithmetic(BinaryOp::Mul, OverflowBehavior::Wrapping))
+            }
+            "checked_add" => Some(Intrinsic::Arithmetic(BinaryOp::Add, OverflowBehavior::Checked)),
+            "checked_sub" => Some(Intrinsic::Arithmetic(BinaryOp::Sub, OverflowBehavior::Checked)),
+            "checked_mul" => Some(Intrinsic::Arithmetic(BinaryOp::Mul, OverflowBehavior::Checked)),
+            "saturating_add" => {
+                Some(Intrinsic::Arithmetic(BinaryOp::Add, OverflowBehavior::Saturating))
+            }
+            "saturating_sub" => {
+                Some(Intrinsic::Arithmetic(BinaryOp::Sub, OverflowBehavior::Saturating))
+            }
+            "saturating_mul" => {
+                Some(Intrinsic::Arithmetic(BinaryOp::Mul, OverflowBehavior::Saturating))
+            }
+            "count_ones" => Some(Intrinsic::CountOnes),
+            "leading_zeros" => Some(Intrinsic::LeadingZeros),
+            "rotate_left" => Some(Intrinsic::RotateLeft),
+            "rotate_right" => Some(Intrinsic::RotateRight),
             other => BlackBoxFunc::lookup(other).map(Intrinsic::BlackBox),
         }
     }
@@ -140,6 +205,46 @@ pub(crate) enum Endian {
     Little,
 }
 
+/// How the result of an `Intrinsic::Arithmetic` operation which doesn't fit in the integer type
+/// is handled.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub(crate) enum OverflowBehavior {
+    /// The result is truncated to the bits of the integer type
+    Wrapping,
+    /// The wrapped result is returned along with whether the operation overflowed
+    Checked,
+    /// The result is clamped to the smallest or largest value of the integer type
+    Saturating,
+}
+
+impl std::fmt::Display for OverflowBehavior {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OverflowBehavior::Wrapping => write!(f, "wrapping"),
+            OverflowBehavior::Checked => write!(f, "checked"),
+            OverflowBehavior::Saturating => write!(f, "saturating"),
+        }
+    }
+}
+
+/// The message of a constrain instruction which is reported when the constraint fails.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub(crate) struct AssertMessage {
+    /// The message, in which each `{name}` placeholder is replaced by the value at the same
+    /// position in `values`.
+    pub(crate) message: String,
+    pub(crate) values: Vec<ValueId>,
+    /// The serialized `PrintableType` of the message if it is a format string, which describes
+    /// how its values are printed.
+    pub(crate) printable_type: Option<String>,
+}
+
+impl From<String> for AssertMessage {
+    fn from(message: String) -> Self {
+        AssertMessage { message, values: Vec::new(), printable_type: None }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 /// Instructions are used to perform tasks.
 /// The instructions that the IR is able to specify are listed below.
@@ -157,7 +262,7 @@ pub(crate) enum Instruction {
     Truncate { value: ValueId, bit_size: u32, max_bit_size: u32 },
 
     /// Constrains two values to be equal to one another.
-    Constrain(ValueId, ValueId, Option<String>),
+    Constrain(ValueId, ValueId, Option<AssertMessage>),
 
     /// Range constrain `value` to `max_bit_size`
     RangeCheck { value: ValueId, max_bit_size: u32, assert_message: Option<String> },
@@ -326,7 +431,12 @@ impl Instruction {
                 max_bit_size: *max_bit_size,
             },
             Instruction::Constrain(lhs, rhs, assert_message) => {
-                Instruction::Constrain(f(*lhs), f(*rhs), assert_message.clone())
+                let assert_message = assert_message.as_ref().map(|assert_message| AssertMessage {
+                    message: assert_message.message.clone(),
+                    values: vecmap(assert_message.values.iter().copied(), &mut f),
+                    printable_type: assert_message.printable_type.clone(),
+                });
+                Instruction::Constrain(f(*lhs), f(*rhs), assert_message)
             }
             Instruction::Call { func, arguments } => Instruction::Call {
                 func: f(*func),
@@ -376,9 +486,14 @@ impl Instruction {
             | Instruction::Load { address: value } => {
                 f(*value);
             }
-            Instruction::Constrain(lhs, rhs, _) => {
+            Instruction::Constrain(lhs, rhs, assert_message) => {
                 f(*lhs);
                 f(*rhs);
+                if let Some(assert_message) = assert_message {
+                    for value in &assert_message.values {
+                        f(*value);
+                    }
+                }
             }
 
             Instruction::Store { address, value } => {
@@ -448,18 +563,7 @@ impl Instruction {
                     SimplifiedToInstructionMultiple(constraints)
                 }
             }
-            Instruction::ArrayGet { array, index } => {
-                let array = dfg.get_array_constant(*array);
-                let index = dfg.get_numeric_constant(*index);
-                if let (Some((array, _)), Some(index)) = (array, index) {
-                    let index =
-                        index.try_to_u64().expect("Expected array index to fit in u64") as usize;
-                    if index < array.len() {
-                        return SimplifiedTo(array[index]);
-                    }
-                }
-                None
-            }
+            Instruction::ArrayGet { array, index } => simplify_array_get(*array, *index, dfg),
             Instruction::ArraySet { array, index, value, .. } => {
                 let array = dfg.get_array_constant(*array);
                 let index = dfg.get_numeric_constant(*index);
@@ -476,9 +580,11 @@ impl Instruction {
             }
             Instruction::Truncate { value, bit_size, max_bit_size } => {
                 if let Some((numeric_constant, typ)) = dfg.get_numeric_constant_with_type(*value) {
-                    let integer_modulus = 2_u128.pow(*bit_size);
-                    let truncated = numeric_constant.to_u128() % integer_modulus;
-                    SimplifiedTo(dfg.make_constant(truncated.into(), typ))
+                    let integer_modulus = BigUint::from(2u128).pow(*bit_size);
+                    let constant = BigUint::from_bytes_be(&numeric_constant.to_be_bytes());
+                    let truncated = constant % integer_modulus;
+                    let truncated = FieldElement::from_be_bytes_reduce(&truncated.to_bytes_be());
+                    SimplifiedTo(dfg.make_constant(truncated, typ))
                 } else if let Value::Instruction { instruction, .. } = &dfg[dfg.resolve(*value)] {
                     match &dfg[*instruction] {
                         Instruction::Truncate { bit_size: src_bit_size, .. } => {
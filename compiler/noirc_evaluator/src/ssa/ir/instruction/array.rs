@@ -0,0 +1,59 @@
+use super::{DataFlowGraph, Instruction, SimplifyResult, Value, ValueId};
+
+/// Try to simplify this array_get instruction. If the element at `index` is known, it is
+/// returned. Otherwise None is returned.
+///
+/// The element is looked up through the chain of `array_set` instructions which `array` was built
+/// from. For example in the following SSA, `v3` is simplified to `v0` and `v4` to `Field 2`:
+///
+/// ```text
+/// v1 = array_set [Field 1, Field 2], index v5, value Field 3
+/// v2 = array_set v1, index Field 0, value v0
+/// v3 = array_get v2, index Field 0
+/// v4 = array_get [Field 1, Field 2], index Field 1
+/// ```
+///
+/// The search stops at the first `array_set` with a non-constant index, as the element it sets
+/// may or may not be the one at `index`.
+pub(super) fn simplify_array_get(
+    array: ValueId,
+    index: ValueId,
+    dfg: &DataFlowGraph,
+) -> SimplifyResult {
+    let Some(index) = dfg.get_numeric_constant(index) else {
+        return SimplifyResult::None;
+    };
+    let index = index.try_to_u64().expect("Expected array index to fit in u64") as usize;
+
+    // Reading out of bounds must fail at runtime so the array_get can't be removed.
+    if let Some(length) = dfg.try_get_array_length(array) {
+        if index >= length * dfg.type_of_value(array).element_size() {
+            return SimplifyResult::None;
+        }
+    }
+
+    let mut array = dfg.resolve(array);
+    loop {
+        match &dfg[array] {
+            Value::Array { array, .. } => {
+                return match array.get(index) {
+                    Some(element) => SimplifyResult::SimplifiedTo(*element),
+                    None => SimplifyResult::None,
+                };
+            }
+            Value::Instruction { instruction, .. } => match &dfg[*instruction] {
+                Instruction::ArraySet { array: previous_array, index: set_index, value } => {
+                    let Some(set_index) = dfg.get_numeric_constant(*set_index) else {
+                        return SimplifyResult::None;
+                    };
+                    if set_index.try_to_u64() == Some(index as u64) {
+                        return SimplifyResult::SimplifiedTo(*value);
+                    }
+                    array = dfg.resolve(*previous_array);
+                }
+                _ => return SimplifyResult::None,
+            },
+            _ => return SimplifyResult::None,
+        }
+    }
+}
@@ -98,6 +98,12 @@ impl Binary {
 
         let lhs_is_one = lhs.map_or(false, |lhs| lhs.is_one());
         let rhs_is_one = rhs.map_or(false, |rhs| rhs.is_one());
+        let rhs_is_minus_one = match (&operand_type, rhs) {
+            (Type::Numeric(NumericType::Signed { bit_size }), Some(rhs)) => {
+                rhs.try_into_u128().map_or(false, |rhs| to_signed(rhs, *bit_size) == -1)
+            }
+            _ => false,
+        };
 
         match self.operator {
             BinaryOp::Add => {
@@ -131,7 +137,9 @@ impl Binary {
                 }
             }
             BinaryOp::Mod => {
-                if rhs_is_one {
+                // Any integer is a multiple of 1 and -1, including the smallest signed integer
+                // which can't be divided by -1 without overflowing.
+                if rhs_is_one || rhs_is_minus_one {
                     let zero = dfg.make_constant(FieldElement::zero(), operand_type);
                     return SimplifyResult::SimplifiedTo(zero);
                 }
@@ -253,20 +261,16 @@ fn eval_constant_binary_op(
             }
             let result = function(lhs, rhs)?;
             // Check for overflow
-            if result >= 2u128.pow(*bit_size) {
+            if result > max_unsigned(*bit_size) {
                 return None;
             }
-            result.into()
+            integer_to_field(result)?
         }
         Type::Numeric(NumericType::Signed { bit_size }) => {
             let function = operator.get_i128_function();
 
-            let lhs = truncate(lhs.try_into_u128()?, *bit_size);
-            let rhs = truncate(rhs.try_into_u128()?, *bit_size);
-            let l_pos = lhs < 2u128.pow(bit_size - 1);
-            let r_pos = rhs < 2u128.pow(bit_size - 1);
-            let lhs = if l_pos { lhs as i128 } else { -((2u128.pow(*bit_size) - lhs) as i128) };
-            let rhs = if r_pos { rhs as i128 } else { -((2u128.pow(*bit_size) - rhs) as i128) };
+            let lhs = to_signed(lhs.try_into_u128()?, *bit_size);
+            let rhs = to_signed(rhs.try_into_u128()?, *bit_size);
             // The divisor is being truncated into the type of the operand, which can potentially
             // lead to the rhs being zero.
             // If the rhs of a division is zero, attempting to evaluate the division will cause a compiler panic.
@@ -278,12 +282,10 @@ fn eval_constant_binary_op(
 
             let result = function(lhs, rhs)?;
             // Check for overflow
-            if result >= 2i128.pow(*bit_size - 1) || result < -(2i128.pow(*bit_size - 1)) {
+            if to_signed(result as u128, *bit_size) != result {
                 return None;
             }
-            let result =
-                if result >= 0 { result as u128 } else { (2i128.pow(*bit_size) + result) as u128 };
-            result.into()
+            integer_to_field(truncate(result as u128, *bit_size))?
         }
         _ => return None,
     };
@@ -295,9 +297,28 @@ fn eval_constant_binary_op(
     Some((value, operand_type))
 }
 
+/// Returns the field element holding integer `value`, or `None` if `value` doesn't fit in the
+/// native field, in which case it would be reduced modulo the field's order and the result of the
+/// integer operation lost.
+fn integer_to_field(value: u128) -> Option<FieldElement> {
+    let field = FieldElement::from(value);
+    (field.to_u128() == value).then_some(field)
+}
+
+/// Returns the largest value of an unsigned integer of `bit_size` bits.
+fn max_unsigned(bit_size: u32) -> u128 {
+    u128::MAX.checked_shr(128 - bit_size).unwrap_or(0)
+}
+
 fn truncate(int: u128, bit_size: u32) -> u128 {
-    let max = 2u128.pow(bit_size);
-    int % max
+    int & max_unsigned(bit_size)
+}
+
+/// Returns the signed integer of `bit_size` bits whose two's complement representation is held in
+/// the lowest `bit_size` bits of `int`.
+fn to_signed(int: u128, bit_size: u32) -> i128 {
+    let unused_bits = 128 - bit_size;
+    ((int << unused_bits) as i128) >> unused_bits
 }
 
 impl BinaryOp {
@@ -338,7 +359,9 @@ impl BinaryOp {
             BinaryOp::Sub => i128::checked_sub,
             BinaryOp::Mul => i128::checked_mul,
             BinaryOp::Div => i128::checked_div,
-            BinaryOp::Mod => i128::checked_rem,
+            // The remainder of the division of the smallest integer by -1 is zero, even though
+            // the quotient overflows.
+            BinaryOp::Mod => |x, y| Some(x.wrapping_rem(y)),
             BinaryOp::And => |x, y| Some(x & y),
             BinaryOp::Or => |x, y| Some(x | y),
             BinaryOp::Xor => |x, y| Some(x ^ y),
@@ -347,3 +370,63 @@ impl BinaryOp {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use acvm::FieldElement;
+
+    use super::{eval_constant_binary_op, BinaryOp};
+    use crate::ssa::ir::types::Type;
+
+    #[test]
+    fn folds_128_bit_integers() {
+        let max = FieldElement::from(u128::MAX);
+        let one = FieldElement::one();
+
+        let unsigned = Type::unsigned(128);
+        assert_eq!(eval_constant_binary_op(max, one, BinaryOp::Add, unsigned.clone()), None);
+        assert_eq!(
+            eval_constant_binary_op(max, one, BinaryOp::Sub, unsigned.clone()),
+            Some((FieldElement::from(u128::MAX - 1), unsigned))
+        );
+
+        // `-1 * -1 == 1` while `i128::MIN * -1` overflows
+        let signed = Type::signed(128);
+        let min = FieldElement::from(1u128 << 127);
+        assert_eq!(
+            eval_constant_binary_op(max, max, BinaryOp::Mul, signed.clone()),
+            Some((one, signed.clone()))
+        );
+        assert_eq!(eval_constant_binary_op(min, max, BinaryOp::Mul, signed.clone()), None);
+        assert_eq!(
+            eval_constant_binary_op(min, one, BinaryOp::Add, signed.clone()),
+            Some((FieldElement::from((1u128 << 127) + 1), signed))
+        );
+    }
+
+    #[test]
+    fn folds_signed_division() {
+        let signed = Type::signed(32);
+        let minus_seven = FieldElement::from(u32::MAX as u128 - 6);
+        let two = FieldElement::from(2u128);
+        let minus_one = FieldElement::from(u32::MAX as u128);
+        let min = FieldElement::from(1u128 << 31);
+
+        // Division truncates towards zero and the remainder has the sign of the dividend
+        assert_eq!(
+            eval_constant_binary_op(minus_seven, two, BinaryOp::Div, signed.clone()),
+            Some((FieldElement::from(u32::MAX as u128 - 2), signed.clone()))
+        );
+        assert_eq!(
+            eval_constant_binary_op(minus_seven, two, BinaryOp::Mod, signed.clone()),
+            Some((minus_one, signed.clone()))
+        );
+
+        // `i32::MIN / -1` overflows while `i32::MIN % -1` is zero
+        assert_eq!(eval_constant_binary_op(min, minus_one, BinaryOp::Div, signed.clone()), None);
+        assert_eq!(
+            eval_constant_binary_op(min, minus_one, BinaryOp::Mod, signed.clone()),
+            Some((FieldElement::zero(), signed))
+        );
+    }
+}
@@ -10,14 +10,17 @@ use crate::ssa::{
         dfg::{CallStack, DataFlowGraph},
         instruction::Intrinsic,
         map::Id,
-        types::Type,
+        types::{NumericType, Type},
         value::{Value, ValueId},
     },
-    opt::flatten_cfg::value_merger::ValueMerger,
+    opt::flatten_cfg::{capacity_tracker::SliceCapacities, value_merger::ValueMerger},
 };
 
 use super::{Binary, BinaryOp, Endian, Instruction, SimplifyResult};
 
+mod arithmetic;
+mod embedded_curve;
+
 /// Try to simplify this call instruction. If the instruction can be simplified to a known value,
 /// that value is returned. Otherwise None is returned.
 ///
@@ -45,14 +48,10 @@ pub(super) fn simplify_call(
         Intrinsic::ToBits(endian) => {
             if let Some(constant_args) = constant_args {
                 let field = constant_args[0];
-                let limb_count = constant_args[1].to_u128() as u32;
-
-                let (len_value, result_slice) =
-                    constant_to_radix(endian, field, 2, limb_count, dfg);
+                let limb_count = limb_count(ctrl_typevars);
 
-                // `Intrinsic::ToBits` returns slices which are represented
-                // by tuples with the structure (length, slice contents)
-                SimplifyResult::SimplifiedToMultiple(vec![len_value, result_slice])
+                let result_array = constant_to_radix(endian, field, 2, limb_count, dfg);
+                SimplifyResult::SimplifiedTo(result_array)
             } else {
                 SimplifyResult::None
             }
@@ -61,14 +60,10 @@ pub(super) fn simplify_call(
             if let Some(constant_args) = constant_args {
                 let field = constant_args[0];
                 let radix = constant_args[1].to_u128() as u32;
-                let limb_count = constant_args[2].to_u128() as u32;
-
-                let (len_value, result_slice) =
-                    constant_to_radix(endian, field, radix, limb_count, dfg);
+                let limb_count = limb_count(ctrl_typevars);
 
-                // `Intrinsic::ToRadix` returns slices which are represented
-                // by tuples with the structure (length, slice contents)
-                SimplifyResult::SimplifiedToMultiple(vec![len_value, result_slice])
+                let result_array = constant_to_radix(endian, field, radix, limb_count, dfg);
+                SimplifyResult::SimplifiedTo(result_array)
             } else {
                 SimplifyResult::None
             }
@@ -101,6 +96,24 @@ pub(super) fn simplify_call(
                     return SimplifyResult::SimplifiedToMultiple(vec![new_slice_length, new_slice]);
                 }
 
+                // When the length of the slice is known, there's no need to merge the slice with
+                // the element written over its capacity.
+                if let Some(length) = dfg.get_numeric_constant(arguments[0]) {
+                    let length = length.to_u128() as usize;
+                    if length <= slice.len() {
+                        slice.push_back(arguments[2]);
+                        slice.set(length, arguments[2]);
+
+                        let new_slice_length =
+                            update_slice_length(arguments[0], dfg, BinaryOp::Add, block);
+                        let new_slice = dfg.make_array(slice, element_type);
+                        return SimplifyResult::SimplifiedToMultiple(vec![
+                            new_slice_length,
+                            new_slice,
+                        ]);
+                    }
+                }
+
                 simplify_slice_push_back(slice, element_type, arguments, dfg, block)
             } else {
                 SimplifyResult::None
@@ -212,10 +225,56 @@ pub(super) fn simplify_call(
                 SimplifyResult::None
             }
         }
+        Intrinsic::ArraySwap => {
+            let first = dfg.get_numeric_constant(arguments[1]).and_then(|index| index.try_to_u64());
+            let second =
+                dfg.get_numeric_constant(arguments[2]).and_then(|index| index.try_to_u64());
+            match (dfg.get_array_constant(arguments[0]), first, second) {
+                (Some((mut array, typ)), Some(first), Some(second)) if typ.element_size() > 0 => {
+                    let element_size = typ.element_size();
+                    let length = (array.len() / element_size) as u64;
+                    if first >= length || second >= length {
+                        // Leave it to the backends to report the out of bounds access
+                        return SimplifyResult::None;
+                    }
+
+                    let first = first as usize * element_size;
+                    let second = second as usize * element_size;
+                    for offset in 0..element_size {
+                        array.swap(first + offset, second + offset);
+                    }
+                    SimplifyResult::SimplifiedTo(dfg.make_array(array, typ))
+                }
+                _ => SimplifyResult::None,
+            }
+        }
         Intrinsic::StrAsBytes => {
             // Strings are already represented as bytes internally
             SimplifyResult::SimplifiedTo(arguments[0])
         }
+        Intrinsic::StrEq => simplify_str_eq(dfg, arguments),
+        Intrinsic::StrSubstring => {
+            let start = dfg.get_numeric_constant(arguments[1]).and_then(|start| start.try_to_u64());
+            match (dfg.get_array_constant(arguments[0]), start) {
+                (Some((mut string, _)), Some(start)) => {
+                    let result_type = ctrl_typevars.unwrap().remove(0);
+                    let length = match &result_type {
+                        Type::Array(_, length) => *length,
+                        _ => unreachable!("ICE: str_substring must return a string"),
+                    };
+
+                    let start = start as usize;
+                    if start + length <= string.len() {
+                        let substring = string.slice(start..start + length);
+                        SimplifyResult::SimplifiedTo(dfg.make_array(substring, result_type))
+                    } else {
+                        // Leave it to the backends to report the out of bounds access
+                        SimplifyResult::None
+                    }
+                }
+                _ => SimplifyResult::None,
+            }
+        }
         Intrinsic::AssertConstant => {
             if arguments.iter().all(|argument| dfg.is_constant(*argument)) {
                 SimplifyResult::Remove
@@ -223,6 +282,13 @@ pub(super) fn simplify_call(
                 SimplifyResult::None
             }
         }
+        Intrinsic::StaticAssert => {
+            // Failing assertions are kept for `Ssa::evaluate_static_asserts` to report
+            match dfg.get_numeric_constant(arguments[0]) {
+                Some(predicate) if predicate.is_one() => SimplifyResult::Remove,
+                _ => SimplifyResult::None,
+            }
+        }
         Intrinsic::ApplyRangeConstraint => {
             let value = arguments[0];
             let max_bit_size = dfg.get_numeric_constant(arguments[1]);
@@ -246,6 +312,22 @@ pub(super) fn simplify_call(
             );
             SimplifyResult::SimplifiedToInstruction(instruction)
         }
+        Intrinsic::Arithmetic(operator, behavior) => {
+            let results = arithmetic::lower_arithmetic(
+                operator,
+                behavior,
+                arguments[0],
+                arguments[1],
+                dfg,
+                block,
+                call_stack,
+            );
+            SimplifyResult::SimplifiedToMultiple(results)
+        }
+        Intrinsic::CountOnes
+        | Intrinsic::LeadingZeros
+        | Intrinsic::RotateLeft
+        | Intrinsic::RotateRight => simplify_bit_operation(intrinsic, dfg, arguments),
         Intrinsic::FromField => {
             let incoming_type = Type::field();
             let target_type = ctrl_typevars.unwrap().remove(0);
@@ -327,7 +409,8 @@ fn simplify_slice_push_back(
         .insert_instruction_and_results(set_last_slice_value_instr, block, None, call_stack)
         .first();
 
-    let mut value_merger = ValueMerger::new(dfg, block, None, None);
+    let mut capacities = SliceCapacities::default();
+    let mut value_merger = ValueMerger::new(dfg, block, &mut capacities);
     let new_slice = value_merger.merge_values(
         len_not_equals_capacity,
         len_equals_capacity,
@@ -425,12 +508,14 @@ fn simplify_black_box_func(
             simplify_signature(dfg, arguments, acvm::blackbox_solver::ecdsa_secp256r1_verify)
         }
 
-        BlackBoxFunc::FixedBaseScalarMul
-        | BlackBoxFunc::SchnorrVerify
+        BlackBoxFunc::FixedBaseScalarMul => simplify_fixed_base_scalar_mul(dfg, arguments),
+        BlackBoxFunc::EmbeddedCurveAdd => simplify_embedded_curve_add(dfg, arguments),
+        BlackBoxFunc::EmbeddedCurveDouble => simplify_embedded_curve_double(dfg, arguments),
+
+        BlackBoxFunc::SchnorrVerify
         | BlackBoxFunc::PedersenCommitment
         | BlackBoxFunc::PedersenHash
-        | BlackBoxFunc::EmbeddedCurveAdd
-        | BlackBoxFunc::EmbeddedCurveDouble => {
+        | BlackBoxFunc::Poseidon2Permutation => {
             // Currently unsolvable here as we rely on an implementation in the backend.
             SimplifyResult::None
         }
@@ -458,26 +543,100 @@ fn make_constant_array(dfg: &mut DataFlowGraph, results: Vec<FieldElement>, typ:
     dfg.make_array(result_constants.into(), typ)
 }
 
-fn make_constant_slice(
+/// Returns the point with coordinates `x` and `y` if both are constants.
+fn constant_point(dfg: &DataFlowGraph, x: ValueId, y: ValueId) -> Option<embedded_curve::Point> {
+    Some((dfg.get_numeric_constant(x)?, dfg.get_numeric_constant(y)?))
+}
+
+fn make_point(dfg: &mut DataFlowGraph, x: ValueId, y: ValueId) -> ValueId {
+    let typ = Type::Array(Rc::new(vec![Type::field()]), 2);
+    dfg.make_array(vec![x, y].into(), typ)
+}
+
+fn make_constant_point(dfg: &mut DataFlowGraph, point: embedded_curve::Point) -> ValueId {
+    make_constant_array(dfg, vec![point.0, point.1], Type::field())
+}
+
+fn simplify_fixed_base_scalar_mul(
     dfg: &mut DataFlowGraph,
-    results: Vec<FieldElement>,
-    typ: Type,
-) -> (ValueId, ValueId) {
-    let result_constants = vecmap(results, |element| dfg.make_constant(element, typ.clone()));
+    arguments: &[ValueId],
+) -> SimplifyResult {
+    let (Some(low), Some(high)) =
+        (dfg.get_numeric_constant(arguments[0]), dfg.get_numeric_constant(arguments[1]))
+    else {
+        return SimplifyResult::None;
+    };
+
+    // Multiplying by zero gives the point at infinity whatever the embedded curve.
+    let product = if low.is_zero() && high.is_zero() {
+        Some(embedded_curve::infinity())
+    } else if embedded_curve::is_grumpkin() {
+        embedded_curve::fixed_base_scalar_mul(low, high)
+    } else {
+        None
+    };
+    // Invalid scalars are left for the solver to report.
+    match product {
+        Some(product) => SimplifyResult::SimplifiedTo(make_constant_point(dfg, product)),
+        None => SimplifyResult::None,
+    }
+}
 
-    let typ = Type::Slice(Rc::new(vec![typ]));
-    let length = FieldElement::from(result_constants.len() as u128);
-    (dfg.make_constant(length, Type::field()), dfg.make_array(result_constants.into(), typ))
+fn simplify_embedded_curve_add(dfg: &mut DataFlowGraph, arguments: &[ValueId]) -> SimplifyResult {
+    let lhs = constant_point(dfg, arguments[0], arguments[1]);
+    let rhs = constant_point(dfg, arguments[2], arguments[3]);
+
+    match (lhs, rhs) {
+        // The point at infinity is the identity of the addition.
+        (Some(lhs), _) if embedded_curve::is_infinity(lhs) => {
+            SimplifyResult::SimplifiedTo(make_point(dfg, arguments[2], arguments[3]))
+        }
+        (_, Some(rhs)) if embedded_curve::is_infinity(rhs) => {
+            SimplifyResult::SimplifiedTo(make_point(dfg, arguments[0], arguments[1]))
+        }
+        (Some(lhs), Some(rhs)) if embedded_curve::is_grumpkin() => {
+            match embedded_curve::add(lhs, rhs) {
+                Some(sum) => SimplifyResult::SimplifiedTo(make_constant_point(dfg, sum)),
+                // Points which aren't on the curve are left for the solver to report.
+                None => SimplifyResult::None,
+            }
+        }
+        _ => SimplifyResult::None,
+    }
+}
+
+fn simplify_embedded_curve_double(
+    dfg: &mut DataFlowGraph,
+    arguments: &[ValueId],
+) -> SimplifyResult {
+    let point = match constant_point(dfg, arguments[0], arguments[1]) {
+        Some(point) if embedded_curve::is_infinity(point) => Some(point),
+        Some(point) if embedded_curve::is_grumpkin() => embedded_curve::double(point),
+        _ => None,
+    };
+    match point {
+        Some(point) => SimplifyResult::SimplifiedTo(make_constant_point(dfg, point)),
+        None => SimplifyResult::None,
+    }
+}
+
+/// Returns the number of limbs of a radix decomposition, given by the length of the array it
+/// returns.
+fn limb_count(ctrl_typevars: Option<Vec<Type>>) -> u32 {
+    match ctrl_typevars.as_deref() {
+        Some([Type::Array(_, length)]) => *length as u32,
+        _ => unreachable!("ICE: radix decompositions must return an array"),
+    }
 }
 
-/// Returns a slice (represented by a tuple (len, slice)) of constants corresponding to the limbs of the radix decomposition.
+/// Returns an array of constants corresponding to the limbs of the radix decomposition.
 fn constant_to_radix(
     endian: Endian,
     field: FieldElement,
     radix: u32,
     limb_count: u32,
     dfg: &mut DataFlowGraph,
-) -> (ValueId, ValueId) {
+) -> ValueId {
     let bit_size = u32::BITS - (radix - 1).leading_zeros();
     let radix_big = BigUint::from(radix);
     assert_eq!(BigUint::from(2u128).pow(bit_size), radix_big, "ICE: Radix must be a power of 2");
@@ -492,7 +651,7 @@ fn constant_to_radix(
     if endian == Endian::Big {
         limbs.reverse();
     }
-    make_constant_slice(dfg, limbs, Type::unsigned(bit_size))
+    make_constant_array(dfg, limbs, Type::unsigned(bit_size))
 }
 
 fn to_u8_vec(dfg: &DataFlowGraph, values: im::Vector<Id<Value>>) -> Vec<u8> {
@@ -596,3 +755,62 @@ fn simplify_sort(dfg: &mut DataFlowGraph, arguments: &[ValueId]) -> SimplifyResu
         _ => SimplifyResult::None,
     }
 }
+
+/// Folds a bit manipulation intrinsic applied to a constant integer.
+fn simplify_bit_operation(
+    intrinsic: Intrinsic,
+    dfg: &mut DataFlowGraph,
+    arguments: &[ValueId],
+) -> SimplifyResult {
+    let Some((value, typ)) = dfg.get_numeric_constant_with_type(arguments[0]) else {
+        return SimplifyResult::None;
+    };
+    let bit_size = match typ {
+        Type::Numeric(NumericType::Signed { bit_size } | NumericType::Unsigned { bit_size }) => {
+            bit_size
+        }
+        _ => return SimplifyResult::None,
+    };
+    let mask = u128::MAX >> (128 - bit_size);
+    let value = value.to_u128() & mask;
+
+    let (result, result_type) = match intrinsic {
+        Intrinsic::CountOnes => (value.count_ones() as u128, Type::unsigned(32)),
+        Intrinsic::LeadingZeros => {
+            let leading_zeros = value.leading_zeros() - (128 - bit_size);
+            (leading_zeros as u128, Type::unsigned(32))
+        }
+        Intrinsic::RotateLeft | Intrinsic::RotateRight => {
+            let Some(amount) = dfg.get_numeric_constant(arguments[1]) else {
+                return SimplifyResult::None;
+            };
+            let mut amount = (amount.to_u128() % bit_size as u128) as u32;
+            if intrinsic == Intrinsic::RotateRight {
+                amount = (bit_size - amount) % bit_size;
+            }
+            let high = value.checked_shr(bit_size - amount).unwrap_or(0);
+            (((value << amount) | high) & mask, typ)
+        }
+        _ => unreachable!("ICE: {intrinsic} is not a bit manipulation intrinsic"),
+    };
+    SimplifyResult::SimplifiedTo(dfg.make_constant(result.into(), result_type))
+}
+
+fn simplify_str_eq(dfg: &mut DataFlowGraph, arguments: &[ValueId]) -> SimplifyResult {
+    let lhs = dfg.resolve(arguments[0]);
+    let rhs = dfg.resolve(arguments[1]);
+    if lhs == rhs {
+        return SimplifyResult::SimplifiedTo(dfg.make_constant(FieldElement::one(), Type::bool()));
+    }
+
+    match (dfg.get_array_constant(lhs), dfg.get_array_constant(rhs)) {
+        (Some((lhs, _)), Some((rhs, _)))
+            if array_is_constant(dfg, &lhs) && array_is_constant(dfg, &rhs) =>
+        {
+            let equal = to_u8_vec(dfg, lhs) == to_u8_vec(dfg, rhs);
+            let equal = if equal { FieldElement::one() } else { FieldElement::zero() };
+            SimplifyResult::SimplifiedTo(dfg.make_constant(equal, Type::bool()))
+        }
+        _ => SimplifyResult::None,
+    }
+}
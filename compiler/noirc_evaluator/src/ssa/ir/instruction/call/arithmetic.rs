@@ -0,0 +1,319 @@
+//! Lowering of the intrinsics for integer addition, subtraction and multiplication which wrap,
+//! report or saturate on overflow.
+//!
+//! The operation is done on the operands cast to fields, in which it can't overflow. Its result is
+//! then split by a truncation into the bits which fit in the integer type and the overflowing bits,
+//! so that both ACIR and Brillig reuse their lowering of truncations rather than checking the
+//! operands against each other.
+use acvm::FieldElement;
+
+use crate::ssa::ir::{
+    basic_block::BasicBlockId,
+    dfg::{CallStack, DataFlowGraph},
+    instruction::{BinaryOp, Instruction, OverflowBehavior},
+    types::{power_of_two, NumericType, Type},
+    value::ValueId,
+};
+
+/// Inserts the instructions computing `lhs operator rhs` with the given overflow behavior, and
+/// returns its result followed, for checked operations, by whether it overflowed.
+pub(super) fn lower_arithmetic(
+    operator: BinaryOp,
+    behavior: OverflowBehavior,
+    lhs: ValueId,
+    rhs: ValueId,
+    dfg: &mut DataFlowGraph,
+    block: BasicBlockId,
+    call_stack: &CallStack,
+) -> Vec<ValueId> {
+    let typ = dfg.type_of_value(lhs);
+    let mut inserter = Inserter { dfg, block, call_stack: call_stack.clone() };
+
+    let (bit_size, is_signed) = match typ {
+        Type::Numeric(NumericType::Unsigned { bit_size }) => (bit_size, false),
+        Type::Numeric(NumericType::Signed { bit_size }) => (bit_size, true),
+        _ => {
+            // Field arithmetic never overflows
+            let result = inserter.binary(lhs, operator, rhs);
+            return match behavior {
+                OverflowBehavior::Checked => {
+                    vec![result, inserter.dfg.make_constant(FieldElement::zero(), Type::bool())]
+                }
+                OverflowBehavior::Wrapping | OverflowBehavior::Saturating => vec![result],
+            };
+        }
+    };
+
+    let lhs_field = inserter.cast(lhs, Type::field());
+    let rhs_field = inserter.cast(rhs, Type::field());
+    let operation = match operator {
+        BinaryOp::Add => inserter.add_integers(lhs_field, rhs_field, bit_size),
+        BinaryOp::Sub => inserter.sub_integers(lhs_field, rhs_field, bit_size),
+        BinaryOp::Mul => inserter.mul_integers(lhs_field, rhs_field, bit_size),
+        _ => unreachable!("ICE: no arithmetic intrinsic for {operator}"),
+    };
+
+    if behavior == OverflowBehavior::Wrapping {
+        return vec![inserter.cast(operation.wrapped, typ)];
+    }
+
+    let (overflow, saturated) = if is_signed {
+        let (overflow, negative) = match operator {
+            BinaryOp::Add | BinaryOp::Sub => inserter.signed_sum_overflow(
+                lhs_field,
+                rhs_field,
+                operation.wrapped,
+                operator,
+                bit_size,
+            ),
+            _ => inserter.signed_product_overflow(lhs_field, rhs_field, bit_size),
+        };
+        // The largest integer `2^{bit_size-1} - 1` if the result is positive, the smallest one
+        // `-2^{bit_size-1}` otherwise
+        let max = inserter.constant(power_of_two(bit_size - 1) - FieldElement::one());
+        (overflow, inserter.add(max, negative))
+    } else {
+        let saturated = match operator {
+            BinaryOp::Sub => FieldElement::zero(),
+            _ => power_of_two(bit_size) - FieldElement::one(),
+        };
+        (operation.unsigned_overflow, inserter.constant(saturated))
+    };
+
+    match behavior {
+        OverflowBehavior::Checked => {
+            let result = inserter.cast(operation.wrapped, typ);
+            vec![result, inserter.cast(overflow, Type::bool())]
+        }
+        _ => {
+            let result = inserter.select(overflow, saturated, operation.wrapped);
+            vec![inserter.cast(result, typ)]
+        }
+    }
+}
+
+/// The result of an operation on integers of a given bit size, as fields.
+struct Operation {
+    /// The result truncated to the bit size
+    wrapped: ValueId,
+    /// One if the operation on the integers as unsigned integers overflows, zero otherwise
+    unsigned_overflow: ValueId,
+}
+
+struct Inserter<'dfg> {
+    dfg: &'dfg mut DataFlowGraph,
+    block: BasicBlockId,
+    call_stack: CallStack,
+}
+
+impl Inserter<'_> {
+    fn add_integers(&mut self, lhs: ValueId, rhs: ValueId, bit_size: u32) -> Operation {
+        let sum = self.add(lhs, rhs);
+        let (wrapped, carry) = self.split(sum, bit_size, bit_size + 1);
+        Operation { wrapped, unsigned_overflow: carry }
+    }
+
+    fn sub_integers(&mut self, lhs: ValueId, rhs: ValueId, bit_size: u32) -> Operation {
+        // `2^bit_size` is added so that the difference isn't negative, in which case it's the
+        // only bit of the difference above the bit size.
+        let difference = self.binary(lhs, BinaryOp::Sub, rhs);
+        let modulus = self.constant(power_of_two(bit_size));
+        let difference = self.add(difference, modulus);
+        let (wrapped, no_borrow) = self.split(difference, bit_size, bit_size + 1);
+        let one = self.constant(FieldElement::one());
+        Operation { wrapped, unsigned_overflow: self.binary(one, BinaryOp::Sub, no_borrow) }
+    }
+
+    fn mul_integers(&mut self, lhs: ValueId, rhs: ValueId, bit_size: u32) -> Operation {
+        let (product, max_bit_size, high_product) = self.wide_product(lhs, rhs, bit_size);
+        let (wrapped, high) = self.split(product, bit_size, max_bit_size);
+        let mut unsigned_overflow = self.is_nonzero(high);
+        if let Some(high_product) = high_product {
+            let high_product_overflow = self.is_nonzero(high_product);
+            unsigned_overflow = self.or(unsigned_overflow, high_product_overflow);
+        }
+        Operation { wrapped, unsigned_overflow }
+    }
+
+    /// Returns the product of integers `lhs` and `rhs` of `bit_size` bits along with its maximum
+    /// bit size.
+    ///
+    /// If the product may not fit in the field, `lhs` and `rhs` are split in halves of at least
+    /// `bit_size / 2` bits, and the product of their upper halves is left out of the product and
+    /// returned separately. The product then has the same lowest `bit_size` bits, and is the actual
+    /// product if the product of the upper halves is zero.
+    fn wide_product(
+        &mut self,
+        lhs: ValueId,
+        rhs: ValueId,
+        bit_size: u32,
+    ) -> (ValueId, u32, Option<ValueId>) {
+        if 2 * bit_size < FieldElement::max_num_bits() {
+            return (self.mul(lhs, rhs), 2 * bit_size, None);
+        }
+
+        let half = (bit_size + 1) / 2;
+        let (lhs_low, lhs_high) = self.split(lhs, half, bit_size);
+        let (rhs_low, rhs_high) = self.split(rhs, half, bit_size);
+
+        let low_product = self.mul(lhs_low, rhs_low);
+        let cross_product = self.mul(lhs_high, rhs_low);
+        let other_cross_product = self.mul(lhs_low, rhs_high);
+        let cross_product = self.add(cross_product, other_cross_product);
+        let shift = self.constant(power_of_two(half));
+        let cross_product = self.mul(cross_product, shift);
+        let product = self.add(low_product, cross_product);
+
+        let high_product = self.mul(lhs_high, rhs_high);
+        (product, bit_size + half + 2, Some(high_product))
+    }
+
+    /// Returns whether the sum or difference of signed integers `lhs` and `rhs`, whose wrapped
+    /// result is `wrapped`, overflows, along with whether its actual result is negative if so.
+    ///
+    /// The operation overflows if the sign of the wrapped result differs from the sign of `lhs`
+    /// while the actual result has the sign of `lhs`: when both operands have the same sign for
+    /// a sum, or when they have different signs for a difference.
+    fn signed_sum_overflow(
+        &mut self,
+        lhs: ValueId,
+        rhs: ValueId,
+        wrapped: ValueId,
+        operator: BinaryOp,
+        bit_size: u32,
+    ) -> (ValueId, ValueId) {
+        let lhs_sign = self.sign(lhs, bit_size);
+        let rhs_sign = self.sign(rhs, bit_size);
+        let wrapped_sign = self.sign(wrapped, bit_size);
+
+        let different_signs = self.xor(lhs_sign, rhs_sign);
+        let result_has_lhs_sign = if operator == BinaryOp::Add {
+            let one = self.constant(FieldElement::one());
+            self.binary(one, BinaryOp::Sub, different_signs)
+        } else {
+            different_signs
+        };
+        let sign_changed = self.xor(lhs_sign, wrapped_sign);
+        (self.mul(result_has_lhs_sign, sign_changed), lhs_sign)
+    }
+
+    /// Returns whether the product of signed integers `lhs` and `rhs` overflows, along with
+    /// whether it is negative.
+    ///
+    /// The product of their absolute values must be at most `2^{bit_size-1}` when negative, and
+    /// less than that otherwise.
+    fn signed_product_overflow(
+        &mut self,
+        lhs: ValueId,
+        rhs: ValueId,
+        bit_size: u32,
+    ) -> (ValueId, ValueId) {
+        let lhs_sign = self.sign(lhs, bit_size);
+        let rhs_sign = self.sign(rhs, bit_size);
+        let lhs_abs = self.absolute_value(lhs, lhs_sign, bit_size);
+        let rhs_abs = self.absolute_value(rhs, rhs_sign, bit_size);
+        let (product, max_bit_size, high_product) = self.wide_product(lhs_abs, rhs_abs, bit_size);
+
+        let high_product_is_nonzero =
+            high_product.map(|high_product| self.is_nonzero(high_product));
+
+        // A zero product is never negative
+        let different_signs = self.xor(lhs_sign, rhs_sign);
+        let mut product_is_nonzero = self.is_nonzero(product);
+        if let Some(high_product_is_nonzero) = high_product_is_nonzero {
+            product_is_nonzero = self.or(product_is_nonzero, high_product_is_nonzero);
+        }
+        let negative = self.mul(different_signs, product_is_nonzero);
+
+        // `product - negative` is nonnegative unless the product of the upper halves is nonzero,
+        // in which case the product overflows anyway.
+        let bound = self.binary(product, BinaryOp::Sub, negative);
+        let (_, high) = self.split(bound, bit_size - 1, max_bit_size);
+        let mut overflow = self.is_nonzero(high);
+        if let Some(high_product_is_nonzero) = high_product_is_nonzero {
+            overflow = self.or(overflow, high_product_is_nonzero);
+        }
+        (overflow, negative)
+    }
+
+    /// Returns the sign bit of the signed integer `value` of `bit_size` bits.
+    fn sign(&mut self, value: ValueId, bit_size: u32) -> ValueId {
+        self.split(value, bit_size - 1, bit_size).1
+    }
+
+    /// Returns the absolute value of the signed integer `value` of `bit_size` bits with sign bit
+    /// `sign`, i.e. its two's complement `2^bit_size - value` if `sign` is one.
+    fn absolute_value(&mut self, value: ValueId, sign: ValueId, bit_size: u32) -> ValueId {
+        let modulus = self.constant(power_of_two(bit_size));
+        let twice_value = self.add(value, value);
+        let difference = self.binary(modulus, BinaryOp::Sub, twice_value);
+        let difference = self.mul(sign, difference);
+        self.add(value, difference)
+    }
+
+    /// Returns the lowest `bit_size` bits of `value`, a field of at most `max_bit_size` bits,
+    /// along with its remaining bits.
+    fn split(&mut self, value: ValueId, bit_size: u32, max_bit_size: u32) -> (ValueId, ValueId) {
+        let low = self.insert(Instruction::Truncate { value, bit_size, max_bit_size });
+        let high = self.binary(value, BinaryOp::Sub, low);
+        let inverse = self.constant(power_of_two(bit_size).inverse());
+        (low, self.mul(high, inverse))
+    }
+
+    /// Returns one if `value` is nonzero, zero otherwise.
+    fn is_nonzero(&mut self, value: ValueId) -> ValueId {
+        let zero = self.constant(FieldElement::zero());
+        let is_zero = self.binary(value, BinaryOp::Eq, zero);
+        let is_zero = self.cast(is_zero, Type::field());
+        let one = self.constant(FieldElement::one());
+        self.binary(one, BinaryOp::Sub, is_zero)
+    }
+
+    /// Returns `if_true` if `condition` is one, `if_false` if it is zero.
+    fn select(&mut self, condition: ValueId, if_true: ValueId, if_false: ValueId) -> ValueId {
+        let difference = self.binary(if_true, BinaryOp::Sub, if_false);
+        let difference = self.mul(condition, difference);
+        self.add(if_false, difference)
+    }
+
+    /// The exclusive or of bits `lhs` and `rhs`: `lhs + rhs - 2 * lhs * rhs`
+    fn xor(&mut self, lhs: ValueId, rhs: ValueId) -> ValueId {
+        let sum = self.add(lhs, rhs);
+        let product = self.mul(lhs, rhs);
+        let twice_product = self.add(product, product);
+        self.binary(sum, BinaryOp::Sub, twice_product)
+    }
+
+    /// The inclusive or of bits `lhs` and `rhs`: `lhs + rhs - lhs * rhs`
+    fn or(&mut self, lhs: ValueId, rhs: ValueId) -> ValueId {
+        let sum = self.add(lhs, rhs);
+        let product = self.mul(lhs, rhs);
+        self.binary(sum, BinaryOp::Sub, product)
+    }
+
+    fn add(&mut self, lhs: ValueId, rhs: ValueId) -> ValueId {
+        self.binary(lhs, BinaryOp::Add, rhs)
+    }
+
+    fn mul(&mut self, lhs: ValueId, rhs: ValueId) -> ValueId {
+        self.binary(lhs, BinaryOp::Mul, rhs)
+    }
+
+    fn binary(&mut self, lhs: ValueId, operator: BinaryOp, rhs: ValueId) -> ValueId {
+        self.insert(Instruction::binary(operator, lhs, rhs))
+    }
+
+    fn cast(&mut self, value: ValueId, typ: Type) -> ValueId {
+        self.insert(Instruction::Cast(value, typ))
+    }
+
+    fn constant(&mut self, value: FieldElement) -> ValueId {
+        self.dfg.make_constant(value, Type::field())
+    }
+
+    fn insert(&mut self, instruction: Instruction) -> ValueId {
+        self.dfg
+            .insert_instruction_and_results(instruction, self.block, None, self.call_stack.clone())
+            .first()
+    }
+}
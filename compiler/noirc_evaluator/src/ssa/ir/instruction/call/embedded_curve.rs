@@ -0,0 +1,149 @@
+//! Arithmetic over Grumpkin, the embedded curve of the bn254 field, with which calls to the
+//! embedded curve black box functions on constants are evaluated at compile time.
+//!
+//! Grumpkin is the curve `y^2 = x^3 - 17` over the bn254 scalar field. As in the black box
+//! functions, its point at infinity is represented as `(0, 0)`, which isn't on the curve.
+use acvm::{
+    acir::acir_field::{FieldOptions, CHOSEN_FIELD},
+    FieldElement,
+};
+
+pub(super) type Point = (FieldElement, FieldElement);
+
+/// The y coordinate of the generator of Grumpkin, whose x coordinate is 1.
+const GENERATOR_Y: &str = "0x0000000000000002cf135e7506a45d632d270d45f1181294833fc48d823f272c";
+
+/// The order of Grumpkin's scalar field, split into limbs of 128 bits.
+const SCALAR_MODULUS_HIGH: u128 = 0x30644e72e131a029b85045b68181585d;
+const SCALAR_MODULUS_LOW: u128 = 0x97816a916871ca8d3c208c16d87cfd47;
+
+/// Whether the embedded curve of the field being compiled for is Grumpkin. Calls on constant
+/// points are only evaluated at compile time if so.
+pub(super) fn is_grumpkin() -> bool {
+    CHOSEN_FIELD == FieldOptions::BN254
+}
+
+pub(super) fn infinity() -> Point {
+    (FieldElement::zero(), FieldElement::zero())
+}
+
+pub(super) fn is_infinity(point: Point) -> bool {
+    point.0.is_zero() && point.1.is_zero()
+}
+
+fn is_on_curve((x, y): Point) -> bool {
+    y * y == x * x * x - FieldElement::from(17u128)
+}
+
+/// Returns the sum of two points, or `None` if either isn't on the curve.
+pub(super) fn add(lhs: Point, rhs: Point) -> Option<Point> {
+    if is_infinity(lhs) || is_infinity(rhs) {
+        let other = if is_infinity(lhs) { rhs } else { lhs };
+        return (is_infinity(other) || is_on_curve(other)).then_some(other);
+    }
+    if !is_on_curve(lhs) || !is_on_curve(rhs) {
+        return None;
+    }
+    if lhs.0 == rhs.0 {
+        // The points are either equal or each other's inverse
+        return if lhs.1 == rhs.1 { double(lhs) } else { Some(infinity()) };
+    }
+
+    let slope = (rhs.1 - lhs.1) / (rhs.0 - lhs.0);
+    Some(line_intersection(slope, lhs, rhs.0))
+}
+
+/// Returns twice `point`, or `None` if it isn't on the curve.
+pub(super) fn double(point: Point) -> Option<Point> {
+    if is_infinity(point) {
+        return Some(infinity());
+    }
+    if !is_on_curve(point) {
+        return None;
+    }
+    // Grumpkin has no point of order 2, so `y` is never zero on the curve.
+    let (x, y) = point;
+    let slope = (FieldElement::from(3u128) * x * x) / (y + y);
+    Some(line_intersection(slope, point, x))
+}
+
+/// Returns the inverse of the third intersection with the curve of the line through `point` with
+/// slope `slope`, whose second intersection has x coordinate `other_x`.
+fn line_intersection(slope: FieldElement, point: Point, other_x: FieldElement) -> Point {
+    let x = slope * slope - point.0 - other_x;
+    let y = slope * (point.0 - x) - point.1;
+    (x, y)
+}
+
+/// Returns the generator multiplied by the scalar whose low and high 128 bits are `low` and
+/// `high`, or `None` if these aren't a valid scalar.
+pub(super) fn fixed_base_scalar_mul(low: FieldElement, high: FieldElement) -> Option<Point> {
+    let low = low.try_into_u128()?;
+    let high = high.try_into_u128()?;
+    if (high, low) >= (SCALAR_MODULUS_HIGH, SCALAR_MODULUS_LOW) {
+        return None;
+    }
+
+    let generator = (FieldElement::one(), FieldElement::from_hex(GENERATOR_Y)?);
+    let mut result = infinity();
+    for limb in [high, low] {
+        for bit in (0..u128::BITS).rev() {
+            result = double(result)?;
+            if (limb >> bit) & 1 == 1 {
+                result = add(result, generator)?;
+            }
+        }
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::FieldElement;
+
+    use super::{add, double, fixed_base_scalar_mul, infinity};
+
+    fn field(hex: &str) -> FieldElement {
+        FieldElement::from_hex(hex).unwrap()
+    }
+
+    #[test]
+    fn matches_fixed_base_scalar_mul_solver() {
+        // The scalar 2^129 + 1, as computed by the bn254 black box solver
+        let point = fixed_base_scalar_mul(FieldElement::one(), FieldElement::from(2u128));
+        let expected = (
+            field("0x0702ab9c7038eeecc179b4f209991bcb68c7cb05bf4c532d804ccac36199c9a9"),
+            field("0x23f10e9e43a3ae8d75d24154e796aae12ae7af546716e8f81a2564f1b5814130"),
+        );
+        assert_eq!(point, Some(expected));
+    }
+
+    #[test]
+    fn adds_and_doubles_points() {
+        let generator = fixed_base_scalar_mul(FieldElement::one(), FieldElement::zero()).unwrap();
+        let two = fixed_base_scalar_mul(FieldElement::from(2u128), FieldElement::zero()).unwrap();
+        let three = fixed_base_scalar_mul(FieldElement::from(3u128), FieldElement::zero()).unwrap();
+
+        assert_eq!(double(generator), Some(two));
+        assert_eq!(add(generator, generator), Some(two));
+        assert_eq!(add(two, generator), Some(three));
+        assert_eq!(add(generator, infinity()), Some(generator));
+        assert_eq!(add(infinity(), generator), Some(generator));
+        assert_eq!(add(generator, (generator.0, -generator.1)), Some(infinity()));
+        assert_eq!(
+            fixed_base_scalar_mul(FieldElement::zero(), FieldElement::zero()),
+            Some(infinity())
+        );
+    }
+
+    #[test]
+    fn rejects_points_off_the_curve() {
+        let point = (FieldElement::one(), FieldElement::one());
+        assert_eq!(add(point, infinity()), None);
+        assert_eq!(double(point), None);
+        assert_eq!(
+            fixed_base_scalar_mul(FieldElement::zero(), FieldElement::from(u128::MAX)),
+            None
+        );
+    }
+}
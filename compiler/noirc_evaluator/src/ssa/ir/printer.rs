@@ -1,4 +1,5 @@
 //! This file is for pretty-printing the SSA IR in a human-readable form for debugging.
+//! The printed form can be read back in with the SSA parser in `ssa/parser.rs`.
 use std::{
     collections::HashSet,
     fmt::{Formatter, Result},
@@ -9,7 +10,7 @@ use iter_extended::vecmap;
 use super::{
     basic_block::BasicBlockId,
     function::Function,
-    instruction::{Instruction, InstructionId, TerminatorInstruction},
+    instruction::{Instruction, InstructionId, InstructionResultType, TerminatorInstruction},
     value::ValueId,
 };
 
@@ -68,13 +69,12 @@ fn value(function: &Function, id: ValueId) -> String {
         }
         Value::Function(id) => id.to_string(),
         Value::Intrinsic(intrinsic) => intrinsic.to_string(),
-        Value::Array { array, .. } => {
+        Value::ForeignFunction(name) => name.clone(),
+        Value::Array { array, typ } => {
             let elements = vecmap(array, |element| value(function, *element));
-            format!("[{}]", elements.join(", "))
-        }
-        Value::Param { .. } | Value::Instruction { .. } | Value::ForeignFunction(_) => {
-            id.to_string()
+            format!("[{}]: {typ}", elements.join(", "))
         }
+        Value::Param { .. } | Value::Instruction { .. } => id.to_string(),
     }
 }
 
@@ -133,50 +133,77 @@ pub(crate) fn display_instruction(
         write!(f, "{} = ", value_list(function, results))?;
     }
 
+    let instruction = &function.dfg[instruction];
+    display_instruction_inner(function, instruction, f)?;
+
+    // The types of these results can't be derived from the instruction itself so they are
+    // printed as well, which lets the parser recreate them.
+    if matches!(instruction.result_type(), InstructionResultType::Unknown) && !results.is_empty() {
+        let types = vecmap(results, |result| function.dfg.type_of_value(*result).to_string());
+        write!(f, " -> {}", types.join(", "))?;
+    }
+    writeln!(f)
+}
+
+/// Display an instruction without its results
+fn display_instruction_inner(
+    function: &Function,
+    instruction: &Instruction,
+    f: &mut Formatter,
+) -> Result {
     let show = |id| value(function, id);
 
-    match &function.dfg[instruction] {
+    match instruction {
         Instruction::Binary(binary) => {
-            writeln!(f, "{} {}, {}", binary.operator, show(binary.lhs), show(binary.rhs))
+            write!(f, "{} {}, {}", binary.operator, show(binary.lhs), show(binary.rhs))
         }
-        Instruction::Cast(lhs, typ) => writeln!(f, "cast {} as {typ}", show(*lhs)),
-        Instruction::Not(rhs) => writeln!(f, "not {}", show(*rhs)),
+        Instruction::Cast(lhs, typ) => write!(f, "cast {} as {typ}", show(*lhs)),
+        Instruction::Not(rhs) => write!(f, "not {}", show(*rhs)),
         Instruction::Truncate { value, bit_size, max_bit_size } => {
             let value = show(*value);
-            writeln!(f, "truncate {value} to {bit_size} bits, max_bit_size: {max_bit_size}",)
+            write!(f, "truncate {value} to {bit_size} bits, max_bit_size: {max_bit_size}",)
+        }
+        Instruction::Constrain(lhs, rhs, message) => {
+            write!(f, "constrain {} == {}", show(*lhs), show(*rhs))?;
+            match message {
+                Some(message) => match &message.printable_type {
+                    Some(printable_type) => write!(
+                        f,
+                        " '{}' ({}) '{printable_type}'",
+                        message.message,
+                        value_list(function, &message.values)
+                    ),
+                    None => write!(f, " '{}'", message.message),
+                },
+                None => Ok(()),
+            }
         }
-        Instruction::Constrain(lhs, rhs, message) => match message {
-            Some(message) => writeln!(f, "constrain {} == {} '{message}'", show(*lhs), show(*rhs)),
-            None => writeln!(f, "constrain {} == {}", show(*lhs), show(*rhs)),
-        },
         Instruction::Call { func, arguments } => {
-            writeln!(f, "call {}({})", show(*func), value_list(function, arguments))
+            write!(f, "call {}({})", show(*func), value_list(function, arguments))
         }
-        Instruction::Allocate => writeln!(f, "allocate"),
-        Instruction::Load { address } => writeln!(f, "load {}", show(*address)),
+        Instruction::Allocate => write!(f, "allocate"),
+        Instruction::Load { address } => write!(f, "load {}", show(*address)),
         Instruction::Store { address, value } => {
-            writeln!(f, "store {} at {}", show(*value), show(*address))
+            write!(f, "store {} at {}", show(*value), show(*address))
         }
         Instruction::EnableSideEffects { condition } => {
-            writeln!(f, "enable_side_effects {}", show(*condition))
+            write!(f, "enable_side_effects {}", show(*condition))
         }
         Instruction::ArrayGet { array, index } => {
-            writeln!(f, "array_get {}, index {}", show(*array), show(*index))
+            write!(f, "array_get {}, index {}", show(*array), show(*index))
         }
         Instruction::ArraySet { array, index, value } => {
-            writeln!(
-                f,
-                "array_set {}, index {}, value {}",
-                show(*array),
-                show(*index),
-                show(*value)
-            )
+            write!(f, "array_set {}, index {}, value {}", show(*array), show(*index), show(*value))
         }
         Instruction::IncrementRc { value } => {
-            writeln!(f, "inc_rc {}", show(*value))
-        }
-        Instruction::RangeCheck { value, max_bit_size, .. } => {
-            writeln!(f, "range_check {} to {} bits", show(*value), *max_bit_size,)
+            write!(f, "inc_rc {}", show(*value))
+        }
+        Instruction::RangeCheck { value, max_bit_size, assert_message } => {
+            write!(f, "range_check {} to {} bits", show(*value), *max_bit_size)?;
+            match assert_message {
+                Some(message) => write!(f, " '{message}'"),
+                None => Ok(()),
+            }
         }
     }
 }
@@ -32,14 +32,19 @@ impl NumericType {
     pub(crate) fn value_is_within_limits(self, field: FieldElement) -> bool {
         match self {
             NumericType::Signed { bit_size } | NumericType::Unsigned { bit_size } => {
-                let max = 2u128.pow(bit_size) - 1;
-                field <= max.into()
+                field.num_bits() <= bit_size
             }
             NumericType::NativeField => true,
         }
     }
 }
 
+/// Returns `2^{exponent}` as a field element, e.g. the modulus of an integer type of
+/// `exponent` bits.
+pub(crate) fn power_of_two(exponent: u32) -> FieldElement {
+    FieldElement::from(2_u128).pow(&FieldElement::from(u128::from(exponent)))
+}
+
 /// All types representable in the IR.
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Ord, PartialOrd)]
 pub(crate) enum Type {
@@ -146,6 +151,17 @@ impl Type {
         }
     }
 
+    /// True if this type is a reference or internally contains a reference
+    pub(crate) fn contains_reference(&self) -> bool {
+        match self {
+            Type::Numeric(_) | Type::Function => false,
+            Type::Array(elements, _) | Type::Slice(elements) => {
+                elements.iter().any(|element| element.contains_reference())
+            }
+            Type::Reference(_) => true,
+        }
+    }
+
     /// True if this type is an array (or slice) or internally contains an array (or slice)
     pub(crate) fn contains_an_array(&self) -> bool {
         match self {
@@ -0,0 +1,306 @@
+//! This module defines an SSA pass which replaces reads and writes of small arrays at dynamic
+//! indices with arithmetic selecting between each of the values of the array.
+//!
+//! ACIR gen implements an access at a dynamic index with a memory block holding the array, which
+//! must first be initialized with every value of the array. For arrays of only a few values it is
+//! cheaper to compare the index against each position of the array instead:
+//!
+//! - `v2 = array_get v0, index v1` becomes the sum of `(v1 == i) * v0[i]` over each position `i`.
+//! - `v3 = array_set v0, index v1, value v2` becomes a new array whose value at each position `i`
+//!   is `(v1 == i) * v2 + (v1 != i) * v0[i]`.
+//!
+//! Exactly one of these comparisons must be true, which constrains the index to be within the
+//! bounds of the array.
+//!
+//! Note that this pass must run after flattening as it only considers the entry block of each
+//! function. Accesses made while side effects are disabled are moved to the first position of
+//! the array, and writes made while they are disabled leave the array unchanged, in the same way
+//! as ACIR gen does for memory blocks.
+use acvm::FieldElement;
+use iter_extended::vecmap;
+
+use crate::ssa::{
+    ir::{
+        basic_block::BasicBlockId,
+        dfg::{CallStack, DataFlowGraph},
+        function::{Function, RuntimeType},
+        instruction::{BinaryOp, Instruction},
+        types::Type,
+        value::ValueId,
+    },
+    ssa_gen::Ssa,
+};
+
+impl Ssa {
+    /// Replaces each read and write at a dynamic index of an array with at most `max_array_size`
+    /// values by arithmetic over each of the values of the array.
+    #[tracing::instrument(level = "trace", skip(self))]
+    pub(crate) fn mux_small_arrays(mut self, max_array_size: usize) -> Ssa {
+        for function in self.functions.values_mut() {
+            // Brillig indexes into arrays natively, which is always cheaper.
+            if function.runtime() == RuntimeType::Acir {
+                mux_small_arrays(function, max_array_size);
+            }
+        }
+        self
+    }
+}
+
+fn mux_small_arrays(function: &mut Function, max_array_size: usize) {
+    let block = function.entry_block();
+    let dfg = &mut function.dfg;
+
+    // The condition of the last `enable_side_effects` instruction, unless it is always true
+    let mut side_effects_condition = None;
+
+    for instruction_id in dfg[block].take_instructions() {
+        let call_stack = dfg.get_call_stack(instruction_id);
+        let instruction = dfg[instruction_id].clone();
+
+        let new_value = match instruction {
+            Instruction::EnableSideEffects { condition } => {
+                let always_enabled =
+                    dfg.get_numeric_constant(condition).map_or(false, |value| value.is_one());
+                side_effects_condition = (!always_enabled).then_some(condition);
+                None
+            }
+            Instruction::ArrayGet { array, index } => {
+                muxed_element_type(dfg, array, index, max_array_size).map(|(len, element_type)| {
+                    let dfg = &mut *dfg;
+                    let mut muxer = ArrayMuxer { dfg, block, call_stack, side_effects_condition };
+                    let positions = muxer.index_positions(index, len);
+                    muxer.array_get(array, element_type, positions)
+                })
+            }
+            Instruction::ArraySet { array, index, value } => {
+                muxed_element_type(dfg, array, index, max_array_size).map(|(len, element_type)| {
+                    let dfg = &mut *dfg;
+                    let mut muxer = ArrayMuxer { dfg, block, call_stack, side_effects_condition };
+                    let positions = muxer.index_positions(index, len);
+                    muxer.array_set(array, element_type, value, positions)
+                })
+            }
+            _ => None,
+        };
+
+        match new_value {
+            Some(new_value) => {
+                let result = dfg.instruction_results(instruction_id)[0];
+                dfg.set_value_from_id(result, new_value);
+            }
+            None => dfg[block].insert_instruction(instruction_id),
+        }
+    }
+}
+
+/// Returns the number of values of `array`, along with the type of each of them, if an access to
+/// it at `index` should be muxed.
+///
+/// Only arrays whose values all have the same numeric type are muxed, as a dynamic index into an
+/// array of tuples could select a value of any of their field types.
+fn muxed_element_type(
+    dfg: &DataFlowGraph,
+    array: ValueId,
+    index: ValueId,
+    max_array_size: usize,
+) -> Option<(usize, Type)> {
+    if dfg.get_numeric_constant(index).is_some() {
+        return None;
+    }
+
+    let Type::Array(element_types, len) = dfg.type_of_value(array) else {
+        return None;
+    };
+    let element_type = element_types.first()?.clone();
+    let array_size = element_types.len() * len;
+
+    // An access to an empty array always fails so is left to ACIR gen, which only fails it
+    // while side effects are enabled.
+    let is_muxed = matches!(element_type, Type::Numeric(_))
+        && element_types.iter().all(|typ| *typ == element_type)
+        && array_size > 0
+        && array_size <= max_array_size;
+    is_muxed.then_some((array_size, element_type))
+}
+
+/// Inserts the instructions replacing a single array access.
+struct ArrayMuxer<'dfg> {
+    dfg: &'dfg mut DataFlowGraph,
+    block: BasicBlockId,
+    call_stack: CallStack,
+    side_effects_condition: Option<ValueId>,
+}
+
+impl ArrayMuxer<'_> {
+    fn insert(&mut self, instruction: Instruction) -> ValueId {
+        self.dfg
+            .insert_instruction_and_results(instruction, self.block, None, self.call_stack.clone())
+            .first()
+    }
+
+    /// Returns whether `index` is equal to each position of an array with `len` values, after
+    /// constraining it to be equal to exactly one of them.
+    fn index_positions(&mut self, index: ValueId, len: usize) -> Vec<ValueId> {
+        let index_type = self.dfg.type_of_value(index);
+        let index = match self.side_effects_condition {
+            Some(condition) => {
+                let condition = self.insert(Instruction::Cast(condition, index_type.clone()));
+                self.insert(Instruction::binary(BinaryOp::Mul, index, condition))
+            }
+            None => index,
+        };
+
+        let positions = vecmap(0..len, |position| {
+            let position = self.dfg.make_constant((position as u128).into(), index_type.clone());
+            self.insert(Instruction::binary(BinaryOp::Eq, index, position))
+        });
+
+        let mut matches = self.dfg.make_constant(FieldElement::zero(), Type::field());
+        for position in &positions {
+            let position = self.insert(Instruction::Cast(*position, Type::field()));
+            matches = self.insert(Instruction::binary(BinaryOp::Add, matches, position));
+        }
+        let one = self.dfg.make_constant(FieldElement::one(), Type::field());
+        let message = Some("Index out of bounds".to_owned().into());
+        self.dfg.insert_instruction_and_results(
+            Instruction::Constrain(matches, one, message),
+            self.block,
+            None,
+            self.call_stack.clone(),
+        );
+
+        positions
+    }
+
+    fn array_get(
+        &mut self,
+        array: ValueId,
+        element_type: Type,
+        positions: Vec<ValueId>,
+    ) -> ValueId {
+        let mut result = self.dfg.make_constant(FieldElement::zero(), element_type.clone());
+        for (position, is_index) in positions.into_iter().enumerate() {
+            let element = self.array_element(array, position, element_type.clone());
+            let is_index = self.insert(Instruction::Cast(is_index, element_type.clone()));
+            let selected = self.insert(Instruction::binary(BinaryOp::Mul, is_index, element));
+            result = self.insert(Instruction::binary(BinaryOp::Add, result, selected));
+        }
+        result
+    }
+
+    fn array_set(
+        &mut self,
+        array: ValueId,
+        element_type: Type,
+        value: ValueId,
+        positions: Vec<ValueId>,
+    ) -> ValueId {
+        let mut elements = im::Vector::new();
+        for (position, is_index) in positions.into_iter().enumerate() {
+            let element = self.array_element(array, position, element_type.clone());
+            let is_index = match self.side_effects_condition {
+                Some(condition) => {
+                    self.insert(Instruction::binary(BinaryOp::And, is_index, condition))
+                }
+                None => is_index,
+            };
+            elements.push_back(self.select(is_index, value, element, element_type.clone()));
+        }
+
+        let array_type = self.dfg.type_of_value(array);
+        self.dfg.make_array(elements, array_type)
+    }
+
+    fn array_element(&mut self, array: ValueId, position: usize, element_type: Type) -> ValueId {
+        let index = self.dfg.make_constant((position as u128).into(), Type::field());
+        self.dfg
+            .insert_instruction_and_results(
+                Instruction::ArrayGet { array, index },
+                self.block,
+                Some(vec![element_type]),
+                self.call_stack.clone(),
+            )
+            .first()
+    }
+
+    /// Returns `condition * then_value + !condition * else_value`.
+    fn select(
+        &mut self,
+        condition: ValueId,
+        then_value: ValueId,
+        else_value: ValueId,
+        typ: Type,
+    ) -> ValueId {
+        let else_condition = self.insert(Instruction::Not(condition));
+        let then_condition = self.insert(Instruction::Cast(condition, typ.clone()));
+        let else_condition = self.insert(Instruction::Cast(else_condition, typ));
+
+        let then_value =
+            self.insert(Instruction::binary(BinaryOp::Mul, then_condition, then_value));
+        let else_value =
+            self.insert(Instruction::binary(BinaryOp::Mul, else_condition, else_value));
+        self.insert(Instruction::binary(BinaryOp::Add, then_value, else_value))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ssa::{
+        function_builder::FunctionBuilder,
+        ir::{function::RuntimeType, instruction::Instruction, map::Id, types::Type},
+        ssa_gen::Ssa,
+    };
+
+    // fn main f0 {
+    //   b0(v0: u32, v1: [Field; 3]):
+    //     v2 = array_get v1, index v0
+    //     v3 = array_set v1, index v0, value Field 5
+    //     return v2, v3
+    // }
+    fn dynamic_accesses() -> Ssa {
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::unsigned(32));
+        let v1 = builder.add_parameter(Type::Array(vec![Type::field()].into(), 3));
+
+        let five = builder.field_constant(5u128);
+        let v2 = builder.insert_array_get(v1, v0, Type::field());
+        let v3 = builder.insert_array_set(v1, v0, five);
+        builder.terminate_with_return(vec![v2, v3]);
+        builder.finish()
+    }
+
+    fn count_dynamic_accesses(ssa: &Ssa) -> usize {
+        let main = ssa.main();
+        let instructions = main.dfg[main.entry_block()].instructions();
+        instructions
+            .iter()
+            .filter(|instruction| match &main.dfg[**instruction] {
+                Instruction::ArrayGet { index, .. } | Instruction::ArraySet { index, .. } => {
+                    main.dfg.get_numeric_constant(*index).is_none()
+                }
+                _ => false,
+            })
+            .count()
+    }
+
+    #[test]
+    fn muxes_small_arrays() {
+        let ssa = dynamic_accesses().mux_small_arrays(3);
+        assert_eq!(count_dynamic_accesses(&ssa), 0);
+
+        let main = ssa.main();
+        let constraints = main.dfg[main.entry_block()]
+            .instructions()
+            .iter()
+            .filter(|instruction| matches!(main.dfg[**instruction], Instruction::Constrain(..)))
+            .count();
+        assert_eq!(constraints, 2);
+    }
+
+    #[test]
+    fn keeps_large_arrays() {
+        let ssa = dynamic_accesses().mux_small_arrays(2);
+        assert_eq!(count_dynamic_accesses(&ssa), 2);
+    }
+}
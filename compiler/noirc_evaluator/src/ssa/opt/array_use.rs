@@ -2,7 +2,8 @@ use crate::ssa::{
     ir::{
         basic_block::BasicBlockId,
         dfg::DataFlowGraph,
-        instruction::{Instruction, InstructionId},
+        function::FunctionId,
+        instruction::{Instruction, InstructionId, Intrinsic},
         post_order::PostOrder,
         value::{Value, ValueId},
     },
@@ -11,20 +12,24 @@ use crate::ssa::{
 use fxhash::FxHashMap as HashMap;
 
 impl Ssa {
-    /// Map arrays with the last instruction that uses it
+    /// Map arrays with the last instruction that uses it, for each function
     /// For this we simply process all the instructions in execution order
     /// and update the map whenever there is a match
     #[tracing::instrument(level = "trace", skip(self))]
-    pub(crate) fn find_last_array_uses(&self) -> HashMap<ValueId, InstructionId> {
-        let mut array_use = HashMap::default();
-        for func in self.functions.values() {
+    pub(crate) fn find_last_array_uses(
+        &self,
+    ) -> HashMap<FunctionId, HashMap<ValueId, InstructionId>> {
+        let mut last_array_uses = HashMap::default();
+        for (id, func) in &self.functions {
+            let mut array_use = HashMap::default();
             let mut reverse_post_order = PostOrder::with_function(func).into_vec();
             reverse_post_order.reverse();
             for block in reverse_post_order {
                 last_use(block, &func.dfg, &mut array_use);
             }
+            last_array_uses.insert(*id, array_use);
         }
-        array_use
+        last_array_uses
     }
 }
 
@@ -41,13 +46,17 @@ fn last_use(
                 let array = dfg.resolve(*array);
                 array_def.insert(array, *instruction_id);
             }
-            Instruction::Call { arguments, .. } => {
+            Instruction::Call { func, arguments } => {
                 for argument in arguments {
                     let resolved_arg = dfg.resolve(*argument);
                     if matches!(dfg[resolved_arg], Value::Array { .. }) {
                         array_def.insert(resolved_arg, *instruction_id);
                     }
                 }
+                // Swaps can be made in place on the last use of an array, as for an array set
+                if matches!(dfg[*func], Value::Intrinsic(Intrinsic::ArraySwap)) {
+                    array_def.insert(dfg.resolve(arguments[0]), *instruction_id);
+                }
             }
             _ => {
                 // Nothing to do
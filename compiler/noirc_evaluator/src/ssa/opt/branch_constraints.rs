@@ -0,0 +1,398 @@
+//! This module defines an SSA pass which merges constraints which are duplicated across both
+//! branches of a flattened `if` statement.
+//!
+//! When flattening the CFG, a constraint `x == y` within a branch with condition `c` is replaced
+//! with `x * c == y * c` (see the flatten_cfg module). If the same constraint is made in both
+//! branches then the program contains both `x * c == y * c` and `x * !c == y * !c`, which together
+//! are equivalent to the unconditional constraint `x == y`. This pass replaces such pairs with the
+//! single unconditional constraint.
+//!
+//! For nested branches the conditions take the form `o & c` and `o & !c` where `o` is the
+//! condition of the outer branch, in which case the pair is replaced with `x * o == y * o`.
+use acvm::FieldElement;
+use fxhash::FxHashMap as HashMap;
+
+use crate::ssa::{
+    ir::{
+        dfg::DataFlowGraph,
+        function::{Function, RuntimeType},
+        instruction::{AssertMessage, Binary, BinaryOp, Instruction, InstructionId},
+        types::Type,
+        value::{Value, ValueId},
+    },
+    ssa_gen::Ssa,
+};
+
+impl Ssa {
+    /// Replaces pairs of constraints made under complementary conditions with a single constraint.
+    ///
+    /// This pass must run after flattening as it only considers the entry block of each function.
+    #[tracing::instrument(level = "trace", skip(self))]
+    pub(crate) fn merge_branch_constraints(mut self) -> Ssa {
+        for function in self.functions.values_mut() {
+            // Constraints in brillig functions are never multiplied by their branch conditions.
+            if function.runtime() == RuntimeType::Acir {
+                merge_branch_constraints(function);
+            }
+        }
+        self
+    }
+}
+
+/// A constraint of the form `lhs * condition == rhs * condition`
+struct ConditionalConstraint {
+    instruction: InstructionId,
+    condition: ValueId,
+}
+
+fn merge_branch_constraints(function: &mut Function) {
+    let block = function.entry_block();
+
+    // Group the conditional constraints by the values they constrain, and their message,
+    // in the order in which they appear.
+    let mut constraints: HashMap<
+        (ValueId, ValueId, Option<AssertMessage>),
+        Vec<ConditionalConstraint>,
+    > = HashMap::default();
+    let mut keys = Vec::new();
+    for instruction in function.dfg[block].instructions().to_vec() {
+        let Instruction::Constrain(lhs, rhs, message) = function.dfg[instruction].clone() else {
+            continue;
+        };
+        if let Some((lhs, rhs, condition)) =
+            split_conditional_constraint(&mut function.dfg, lhs, rhs)
+        {
+            let key = (lhs, rhs, message);
+            let entry = constraints.entry(key.clone()).or_default();
+            if entry.is_empty() {
+                keys.push(key);
+            }
+            entry.push(ConditionalConstraint { instruction, condition });
+        }
+    }
+
+    // Maps the first constraint of each complementary pair to the merged constraint, which is
+    // conditional on the outer condition of the pair if there is one. The second constraint of
+    // each pair is mapped to `None` as it is removed.
+    let dfg = &function.dfg;
+    let mut replacements: HashMap<InstructionId, Option<(ValueId, ValueId, Option<ValueId>)>> =
+        HashMap::default();
+    for key in keys {
+        let constraints = &constraints[&key];
+        for (index, first) in constraints.iter().enumerate() {
+            if replacements.contains_key(&first.instruction) {
+                continue;
+            }
+            let complement = constraints[index + 1..].iter().find_map(|second| {
+                if replacements.contains_key(&second.instruction) {
+                    return None;
+                }
+                merged_condition(dfg, first.condition, second.condition)
+                    .map(|condition| (second.instruction, condition))
+            });
+            if let Some((second, condition)) = complement {
+                replacements.insert(first.instruction, Some((key.0, key.1, condition)));
+                replacements.insert(second, None);
+            }
+        }
+    }
+
+    if replacements.is_empty() {
+        return;
+    }
+
+    let instructions = function.dfg[block].take_instructions();
+    for instruction_id in instructions {
+        let (lhs, rhs, condition) = match replacements.get(&instruction_id) {
+            None => {
+                function.dfg[block].insert_instruction(instruction_id);
+                continue;
+            }
+            // This constraint has been merged into an earlier one.
+            Some(None) => continue,
+            Some(Some(replacement)) => *replacement,
+        };
+
+        let (lhs, rhs) = match condition {
+            None => (lhs, rhs),
+            Some(condition) => {
+                let call_stack = function.dfg.get_call_stack(instruction_id);
+                let typ = function.dfg.type_of_value(lhs);
+                let mut insert = |instruction| {
+                    function
+                        .dfg
+                        .insert_instruction_and_results(
+                            instruction,
+                            block,
+                            None,
+                            call_stack.clone(),
+                        )
+                        .first()
+                };
+                let condition = insert(Instruction::Cast(condition, typ));
+                let lhs = insert(Instruction::binary(BinaryOp::Mul, lhs, condition));
+                let rhs = insert(Instruction::binary(BinaryOp::Mul, rhs, condition));
+                (lhs, rhs)
+            }
+        };
+        let Instruction::Constrain(_, _, message) = &function.dfg[instruction_id] else {
+            unreachable!("Only constraints are merged");
+        };
+        function.dfg[instruction_id] = Instruction::Constrain(lhs, rhs, message.clone());
+        function.dfg[block].insert_instruction(instruction_id);
+    }
+}
+
+/// If the constraint `lhs == rhs` is of the form `x * c == y * c` for a boolean `c`,
+/// returns `(x, y, c)`.
+///
+/// As multiplications by constants are simplified when flattening, either side may also be
+/// `c` itself (where `x` is one) or zero.
+fn split_conditional_constraint(
+    dfg: &mut DataFlowGraph,
+    lhs: ValueId,
+    rhs: ValueId,
+) -> Option<(ValueId, ValueId, ValueId)> {
+    let typ = dfg.type_of_value(lhs);
+    let one = dfg.make_constant(FieldElement::one(), typ.clone());
+    let zero = dfg.make_constant(FieldElement::zero(), typ);
+    let dfg = &*dfg;
+
+    let lhs_terms = conditional_terms(dfg, lhs, one);
+    let rhs_terms = conditional_terms(dfg, rhs, one);
+    let is_zero = |value| dfg.resolve(value) == zero;
+
+    let mut candidates = Vec::new();
+    for (x, lhs_factor) in &lhs_terms {
+        for (y, rhs_factor) in &rhs_terms {
+            if lhs_factor == rhs_factor {
+                candidates.push((*x, *y, *lhs_factor));
+            }
+        }
+        if is_zero(rhs) {
+            candidates.push((*x, zero, *lhs_factor));
+        }
+    }
+    if is_zero(lhs) {
+        candidates.extend(rhs_terms.iter().map(|(y, rhs_factor)| (zero, *y, *rhs_factor)));
+    }
+
+    candidates.into_iter().find_map(|(x, y, factor)| {
+        boolean_condition(dfg, factor).map(|condition| (x, y, condition))
+    })
+}
+
+/// Returns the ways of writing `value` as `x * factor`, as pairs of `(x, factor)`.
+fn conditional_terms(dfg: &DataFlowGraph, value: ValueId, one: ValueId) -> Vec<(ValueId, ValueId)> {
+    let value = dfg.resolve(value);
+    let mut terms = vec![(one, value)];
+    if let Some(Instruction::Binary(Binary { lhs, rhs, operator: BinaryOp::Mul })) =
+        instruction_of(dfg, value)
+    {
+        let (lhs, rhs) = (dfg.resolve(*lhs), dfg.resolve(*rhs));
+        terms.push((lhs, rhs));
+        terms.push((rhs, lhs));
+    }
+    terms
+}
+
+/// Returns the boolean value `factor` is derived from, if it can only be zero or one.
+fn boolean_condition(dfg: &DataFlowGraph, factor: ValueId) -> Option<ValueId> {
+    if dfg.type_of_value(factor) == Type::bool() {
+        return Some(factor);
+    }
+    match instruction_of(dfg, factor)? {
+        Instruction::Cast(value, _) if dfg.type_of_value(*value) == Type::bool() => {
+            Some(dfg.resolve(*value))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the condition under which both of the constraints made under `first` and `second`
+/// apply, or `None` if the conditions are not of the form `c` and `!c`, optionally combined with
+/// the same outer condition.
+///
+/// The outer `Option` is `None` if the conditions can't be merged, while the inner `Option` is
+/// `None` if the merged constraint is unconditional.
+fn merged_condition(
+    dfg: &DataFlowGraph,
+    first: ValueId,
+    second: ValueId,
+) -> Option<Option<ValueId>> {
+    if is_negation(dfg, first, second) {
+        return Some(None);
+    }
+
+    let first_operands = and_operands(dfg, first)?;
+    let second_operands = and_operands(dfg, second)?;
+    for (first_outer, first_inner) in [first_operands, (first_operands.1, first_operands.0)] {
+        for (second_outer, second_inner) in
+            [second_operands, (second_operands.1, second_operands.0)]
+        {
+            if first_outer == second_outer && is_negation(dfg, first_inner, second_inner) {
+                return Some(Some(first_outer));
+            }
+        }
+    }
+    None
+}
+
+fn and_operands(dfg: &DataFlowGraph, value: ValueId) -> Option<(ValueId, ValueId)> {
+    match instruction_of(dfg, value)? {
+        Instruction::Binary(Binary { lhs, rhs, operator: BinaryOp::And }) => {
+            Some((dfg.resolve(*lhs), dfg.resolve(*rhs)))
+        }
+        _ => None,
+    }
+}
+
+/// Returns true if either of `a` and `b` is the negation of the other.
+fn is_negation(dfg: &DataFlowGraph, a: ValueId, b: ValueId) -> bool {
+    let negates = |value, negated| match instruction_of(dfg, negated) {
+        Some(Instruction::Not(operand)) => dfg.resolve(*operand) == value,
+        _ => false,
+    };
+    negates(a, b) || negates(b, a)
+}
+
+fn instruction_of(dfg: &DataFlowGraph, value: ValueId) -> Option<&Instruction> {
+    match &dfg[dfg.resolve(value)] {
+        Value::Instruction { instruction, .. } => Some(&dfg[*instruction]),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ssa::{
+        function_builder::FunctionBuilder,
+        ir::{
+            function::RuntimeType,
+            instruction::{Binary, BinaryOp, Instruction},
+            map::Id,
+            types::Type,
+            value::Value,
+        },
+    };
+
+    #[test]
+    fn merge_constraints_of_both_branches() {
+        // fn main f0 {
+        //   b0(v0: u1, v1: Field, v2: Field):
+        //     v3 = cast v0 as Field
+        //     v4 = mul v1, v3
+        //     v5 = mul v2, v3
+        //     constrain v4 == v5
+        //     v6 = not v0
+        //     v7 = cast v6 as Field
+        //     v8 = mul v1, v7
+        //     v9 = mul v2, v7
+        //     constrain v8 == v9
+        //     return
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::bool());
+        let v1 = builder.add_parameter(Type::field());
+        let v2 = builder.add_parameter(Type::field());
+
+        let v3 = builder.insert_cast(v0, Type::field());
+        let v4 = builder.insert_binary(v1, BinaryOp::Mul, v3);
+        let v5 = builder.insert_binary(v2, BinaryOp::Mul, v3);
+        builder.insert_constrain(v4, v5, None);
+
+        let v6 = builder.insert_not(v0);
+        let v7 = builder.insert_cast(v6, Type::field());
+        let v8 = builder.insert_binary(v7, BinaryOp::Mul, v1);
+        let v9 = builder.insert_binary(v7, BinaryOp::Mul, v2);
+        builder.insert_constrain(v8, v9, None);
+        builder.terminate_with_return(vec![]);
+
+        let ssa = builder.finish().merge_branch_constraints();
+        let main = ssa.main();
+        let constraints: Vec<_> = main.dfg[main.entry_block()]
+            .instructions()
+            .iter()
+            .filter_map(|instruction| match &main.dfg[*instruction] {
+                Instruction::Constrain(lhs, rhs, _) => Some((*lhs, *rhs)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(constraints, vec![(v1, v2)]);
+    }
+
+    #[test]
+    fn merge_constraints_of_nested_branches() {
+        // fn main f0 {
+        //   b0(v0: u1, v1: u1, v2: Field):
+        //     v3 = and v0, v1
+        //     v4 = cast v3 as Field
+        //     v5 = mul v2, v4
+        //     constrain v5 == v4
+        //     v6 = not v1
+        //     v7 = and v0, v6
+        //     v8 = cast v7 as Field
+        //     v9 = mul v2, v8
+        //     constrain v9 == v8
+        //     v10 = cast v1 as Field
+        //     v11 = mul v2, v10
+        //     constrain v11 == v10
+        //     return
+        // }
+        //
+        // The first two constraints are merged into a single constraint conditional on `v0`
+        // while the last one is left as is since it has no complementary constraint.
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::bool());
+        let v1 = builder.add_parameter(Type::bool());
+        let v2 = builder.add_parameter(Type::field());
+
+        let v3 = builder.insert_binary(v0, BinaryOp::And, v1);
+        let v4 = builder.insert_cast(v3, Type::field());
+        let v5 = builder.insert_binary(v2, BinaryOp::Mul, v4);
+        builder.insert_constrain(v5, v4, None);
+
+        let v6 = builder.insert_not(v1);
+        let v7 = builder.insert_binary(v0, BinaryOp::And, v6);
+        let v8 = builder.insert_cast(v7, Type::field());
+        let v9 = builder.insert_binary(v2, BinaryOp::Mul, v8);
+        builder.insert_constrain(v9, v8, None);
+
+        let v10 = builder.insert_cast(v1, Type::field());
+        let v11 = builder.insert_binary(v2, BinaryOp::Mul, v10);
+        builder.insert_constrain(v11, v10, None);
+        builder.terminate_with_return(vec![]);
+
+        let ssa = builder.finish().merge_branch_constraints();
+        let main = ssa.main();
+        let constraints: Vec<_> = main.dfg[main.entry_block()]
+            .instructions()
+            .iter()
+            .filter_map(|instruction| match &main.dfg[*instruction] {
+                Instruction::Constrain(lhs, rhs, _) => Some((*lhs, *rhs)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(constraints.len(), 2);
+        assert_eq!(constraints[1], (v11, v10));
+
+        // The merged constraint is `v2 * cast(v0) == cast(v0)`.
+        let (lhs, rhs) = constraints[0];
+        let Value::Instruction { instruction, .. } = &main.dfg[lhs] else {
+            panic!("Expected the merged constraint to be conditional");
+        };
+        let Instruction::Binary(Binary { lhs: x, rhs: condition, operator: BinaryOp::Mul }) =
+            &main.dfg[*instruction]
+        else {
+            panic!("Expected the merged constraint to be conditional");
+        };
+        assert_eq!(*x, v2);
+        assert_eq!(*condition, rhs);
+        let Value::Instruction { instruction, .. } = &main.dfg[rhs] else {
+            panic!("Expected the condition to be cast");
+        };
+        assert_eq!(main.dfg[*instruction], Instruction::Cast(v0, Type::field()));
+    }
+}
@@ -508,4 +508,61 @@ mod test {
         assert_eq!(main.dfg[instructions[4]], Instruction::Constrain(v1, v_true, None));
         assert_eq!(main.dfg[instructions[5]], Instruction::Constrain(v2, v_false, None));
     }
+
+    #[test]
+    fn array_get_through_previous_array_sets() {
+        // fn main f0 {
+        //   b0(v0: [Field; 2], v1: Field, v2: Field):
+        //     v3 = array_set v0, index v2, value v1
+        //     v4 = array_get v3, index Field 0
+        //     v5 = array_get v3, index Field 1
+        //     return v4, v5
+        // }
+        //
+        // After constructing this IR, we set the value of v2 to 0 so that v4 can be read from the
+        // array_set which created v3. The value of v5 is still unknown as v0 isn't constant.
+        let main_id = Id::test_new(0);
+
+        // Compiling main
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let array_type = Type::Array(Rc::new(vec![Type::field()]), 2);
+        let v0 = builder.add_parameter(array_type);
+        let v1 = builder.add_parameter(Type::field());
+        let v2 = builder.add_parameter(Type::field());
+
+        let zero = builder.field_constant(0u128);
+        let one = builder.field_constant(1u128);
+
+        let v3 = builder.insert_array_set(v0, v2, v1);
+        let v4 = builder.insert_array_get(v3, zero, Type::field());
+        let v5 = builder.insert_array_get(v3, one, Type::field());
+        builder.terminate_with_return(vec![v4, v5]);
+
+        let mut ssa = builder.finish();
+        let main = ssa.main_mut();
+        assert_eq!(main.dfg[main.entry_block()].instructions().len(), 3);
+
+        // Expected output:
+        //
+        // fn main f0 {
+        //   b0(v0: [Field; 2], v1: Field, Field 0: Field):
+        //     v3 = array_set v0, index Field 0, value v1
+        //     v5 = array_get v3, index Field 1
+        //     return v1, v5
+        // }
+        main.dfg.set_value_from_id(v2, zero);
+
+        let ssa = ssa.fold_constants();
+        let main = ssa.main();
+        let block = &main.dfg[main.entry_block()];
+        assert_eq!(block.instructions().len(), 2);
+
+        match block.terminator() {
+            Some(TerminatorInstruction::Return { return_values, .. }) => {
+                assert_eq!(main.dfg.resolve(return_values[0]), v1);
+                assert!(matches!(main.dfg[return_values[1]], Value::Instruction { .. }));
+            }
+            _ => unreachable!("b0 should have a return terminator"),
+        }
+    }
 }
@@ -0,0 +1,414 @@
+//! The constant time check verifies that functions annotated with `#[constant_time]` do not
+//! leak their inputs through the execution of unconstrained code.
+//!
+//! Once flattened, constrained code always executes every instruction, whichever values it is
+//! given. Unconstrained code however is executed by the Brillig VM which only runs the blocks
+//! it jumps to and only accesses the array elements it indexes. If these branches or indices
+//! depend on the inputs of a function then the time taken to solve the program can reveal
+//! information about these inputs.
+//!
+//! All parameters of a constant time function are considered secret, except for the public inputs
+//! of the program which the verifier knows anyway. The check then follows
+//! these secret values through each instruction, block argument, memory store and function call
+//! and reports an error for any `jmpif` on a secret condition or any array access with a secret
+//! index found within unconstrained code.
+//!
+//! Only explicit data flow is tracked: a value computed in a branch depending on a secret is not
+//! itself considered secret unless it is derived from a secret.
+use std::collections::{BTreeSet, HashSet};
+
+use iter_extended::vecmap;
+
+use crate::{
+    errors::RuntimeError,
+    ssa::{
+        ir::{
+            basic_block::BasicBlockId,
+            function::{Function, FunctionId, RuntimeType},
+            instruction::{Instruction, Intrinsic, TerminatorInstruction},
+            value::{Value, ValueId},
+        },
+        ssa_gen::Ssa,
+    },
+};
+
+impl Ssa {
+    /// Checks that no function annotated with `#[constant_time]` branches on or indexes arrays
+    /// with its inputs within unconstrained code, including in any function it calls.
+    ///
+    /// This pass must run before inlining so that the annotated functions still exist.
+    #[tracing::instrument(level = "trace", skip(self))]
+    pub(crate) fn check_constant_time(self) -> Result<Ssa, RuntimeError> {
+        let mut checker = ConstantTimeChecker::default();
+        for function in self.functions.values() {
+            if function.is_constant_time() {
+                let public_parameters = function.public_parameters();
+                let secret_parameters = vecmap(function.parameters(), |parameter| {
+                    !public_parameters.contains(parameter)
+                });
+                let unconstrained = function.runtime() == RuntimeType::Brillig;
+                checker.queue.push((function.id(), unconstrained, secret_parameters));
+            }
+        }
+        checker.check_queued_functions(&self)?;
+        Ok(self)
+    }
+}
+
+/// A function to check, along with whether it is executed as unconstrained code
+/// and which of its parameters hold secret values.
+type CheckedFunction = (FunctionId, bool, Vec<bool>);
+
+#[derive(Default)]
+struct ConstantTimeChecker {
+    queue: Vec<CheckedFunction>,
+    checked: HashSet<CheckedFunction>,
+}
+
+impl ConstantTimeChecker {
+    fn check_queued_functions(&mut self, ssa: &Ssa) -> Result<(), RuntimeError> {
+        while let Some(context) = self.queue.pop() {
+            if !self.checked.insert(context.clone()) {
+                continue;
+            }
+            let (function_id, unconstrained, secret_parameters) = context;
+            let function = &ssa.functions[&function_id];
+
+            let secrets = SecretValues::new(function, &secret_parameters);
+            if unconstrained {
+                secrets.check_unconstrained_code()?;
+            }
+
+            for (callee, secret_arguments) in secrets.calls() {
+                // Constrained functions called from unconstrained code are compiled to Brillig too
+                let callee_unconstrained =
+                    unconstrained || ssa.functions[&callee].runtime() == RuntimeType::Brillig;
+                self.queue.push((callee, callee_unconstrained, secret_arguments));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The values of a function which may depend on a secret input.
+struct SecretValues<'f> {
+    function: &'f Function,
+    values: HashSet<ValueId>,
+    /// References to memory which may hold a secret value
+    references: HashSet<ValueId>,
+}
+
+impl<'f> SecretValues<'f> {
+    fn new(function: &'f Function, secret_parameters: &[bool]) -> Self {
+        let values = function
+            .parameters()
+            .iter()
+            .zip(secret_parameters)
+            .filter_map(|(parameter, is_secret)| is_secret.then_some(*parameter))
+            .collect();
+
+        let mut secrets = Self { function, values, references: HashSet::new() };
+
+        // Loops can carry secret values back to earlier blocks so we need to iterate
+        // until we reach a fixed point.
+        let blocks = function.reachable_blocks();
+        while secrets.propagate(&blocks) {}
+        secrets
+    }
+
+    fn is_secret(&self, value: ValueId) -> bool {
+        let value = self.function.dfg.resolve(value);
+        if self.values.contains(&value) || self.references.contains(&value) {
+            return true;
+        }
+        match &self.function.dfg[value] {
+            Value::Array { array, .. } => array.iter().any(|element| self.is_secret(*element)),
+            _ => false,
+        }
+    }
+
+    fn mark_secret(&mut self, value: ValueId) -> bool {
+        let value = self.function.dfg.resolve(value);
+        self.values.insert(value)
+    }
+
+    /// Marks each value derived from a secret value as secret.
+    /// Returns true if any new secret value was found.
+    fn propagate(&mut self, blocks: &BTreeSet<BasicBlockId>) -> bool {
+        let function = self.function;
+        let dfg = &function.dfg;
+        let mut changed = false;
+
+        for block in blocks {
+            for instruction_id in dfg[*block].instructions() {
+                let results = dfg.instruction_results(*instruction_id);
+                let secret_result = match &dfg[*instruction_id] {
+                    Instruction::Allocate => false,
+                    Instruction::Store { address, value } => {
+                        if self.is_secret(*value) {
+                            let address = dfg.resolve(*address);
+                            changed |= self.references.insert(address);
+                        }
+                        false
+                    }
+                    Instruction::Call { arguments, .. } => {
+                        let secret_arguments = arguments.iter().any(|arg| self.is_secret(*arg));
+                        if secret_arguments {
+                            // The callee may store secrets behind any references passed to it
+                            for argument in arguments {
+                                if dfg.type_of_value(*argument).contains_reference() {
+                                    let argument = dfg.resolve(*argument);
+                                    changed |= self.references.insert(argument);
+                                }
+                            }
+                        }
+                        secret_arguments
+                    }
+                    instruction => {
+                        let mut secret_operand = false;
+                        instruction.for_each_value(|value| secret_operand |= self.is_secret(value));
+                        secret_operand
+                    }
+                };
+
+                if secret_result {
+                    for result in results {
+                        changed |= self.mark_secret(*result);
+                    }
+                }
+            }
+
+            if let Some(TerminatorInstruction::Jmp { destination, arguments, .. }) =
+                dfg[*block].terminator()
+            {
+                let parameters = dfg.block_parameters(*destination);
+                for (parameter, argument) in parameters.iter().zip(arguments) {
+                    if self.is_secret(*argument) {
+                        changed |= self.mark_secret(*parameter);
+                    }
+                }
+            }
+        }
+        changed
+    }
+
+    /// Returns an error on the first branch or array access depending on a secret value.
+    fn check_unconstrained_code(&self) -> Result<(), RuntimeError> {
+        let dfg = &self.function.dfg;
+        for block in self.function.reachable_blocks() {
+            for instruction_id in dfg[block].instructions() {
+                match &dfg[*instruction_id] {
+                    Instruction::ArrayGet { index, .. } | Instruction::ArraySet { index, .. }
+                        if self.is_secret(*index) =>
+                    {
+                        let call_stack = dfg.get_call_stack(*instruction_id);
+                        return Err(RuntimeError::SecretDependentIndex { call_stack });
+                    }
+                    Instruction::Call { func, arguments }
+                        if matches!(dfg[*func], Value::Intrinsic(Intrinsic::ArraySwap))
+                            && arguments[1..].iter().any(|index| self.is_secret(*index)) =>
+                    {
+                        let call_stack = dfg.get_call_stack(*instruction_id);
+                        return Err(RuntimeError::SecretDependentIndex { call_stack });
+                    }
+                    _ => (),
+                }
+            }
+
+            if let Some(TerminatorInstruction::JmpIf { condition, then_destination, .. }) =
+                dfg[block].terminator()
+            {
+                if self.is_secret(*condition) {
+                    // Conditions which are parameters have no location of their own so we fall
+                    // back to the location of the code surrounding the branch.
+                    let mut call_stack = dfg.get_value_call_stack(*condition);
+                    let surrounding_instructions = dfg[block]
+                        .instructions()
+                        .iter()
+                        .rev()
+                        .chain(dfg[*then_destination].instructions());
+                    for instruction in surrounding_instructions {
+                        if !call_stack.is_empty() {
+                            break;
+                        }
+                        call_stack = dfg.get_call_stack(*instruction);
+                    }
+                    return Err(RuntimeError::SecretDependentBranch { call_stack });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns each function called along with which of the arguments passed to it are secret.
+    fn calls(&self) -> Vec<(FunctionId, Vec<bool>)> {
+        let dfg = &self.function.dfg;
+        let mut calls = Vec::new();
+        for block in self.function.reachable_blocks() {
+            for instruction_id in dfg[block].instructions() {
+                if let Instruction::Call { func, arguments } = &dfg[*instruction_id] {
+                    if let Value::Function(callee) = &dfg[dfg.resolve(*func)] {
+                        let secret_arguments = arguments.iter().map(|arg| self.is_secret(*arg));
+                        calls.push((*callee, secret_arguments.collect()));
+                    }
+                }
+            }
+        }
+        calls
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        errors::RuntimeError,
+        ssa::{
+            function_builder::FunctionBuilder,
+            ir::{
+                function::RuntimeType,
+                instruction::BinaryOp,
+                map::Id,
+                types::{NumericType, Type},
+            },
+            ssa_gen::Ssa,
+        },
+    };
+
+    // unconstrained fn main f0 {
+    //   b0(v0: u32):
+    //     v1 = eq v0, u32 <branch_on>
+    //     jmpif v1 then: b1, else: b2
+    //   b1():
+    //     jmp b2()
+    //   b2():
+    //     return
+    // }
+    fn brillig_branch(branch_on_parameter: bool, constant_time: bool) -> Ssa {
+        brillig_branch_on_input(branch_on_parameter, constant_time, false)
+    }
+
+    fn brillig_branch_on_input(
+        branch_on_parameter: bool,
+        constant_time: bool,
+        public_parameter: bool,
+    ) -> Ssa {
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Brillig);
+        builder.current_function.set_constant_time(constant_time);
+
+        let v0 = builder.add_parameter(Type::unsigned(32));
+        if public_parameter {
+            builder.current_function.set_public_parameters([v0].into());
+        }
+        let zero = builder.numeric_constant(0u128, Type::unsigned(32));
+        let lhs = if branch_on_parameter { v0 } else { zero };
+        let v1 = builder.insert_binary(lhs, BinaryOp::Eq, zero);
+
+        let b1 = builder.insert_block();
+        let b2 = builder.insert_block();
+        builder.terminate_with_jmpif(v1, b1, b2);
+        builder.switch_to_block(b1);
+        builder.terminate_with_jmp(b2, vec![]);
+        builder.switch_to_block(b2);
+        builder.terminate_with_return(vec![]);
+
+        builder.finish()
+    }
+
+    #[test]
+    fn branch_on_secret() {
+        let ssa = brillig_branch(true, true);
+        assert!(matches!(
+            ssa.check_constant_time(),
+            Err(RuntimeError::SecretDependentBranch { .. })
+        ));
+    }
+
+    #[test]
+    fn branch_on_constant() {
+        assert!(brillig_branch(false, true).check_constant_time().is_ok());
+    }
+
+    #[test]
+    fn branch_on_public_input() {
+        assert!(brillig_branch_on_input(true, true, true).check_constant_time().is_ok());
+    }
+
+    #[test]
+    fn branch_without_attribute() {
+        assert!(brillig_branch(true, false).check_constant_time().is_ok());
+    }
+
+    #[test]
+    fn secret_index_in_brillig_callee() {
+        // fn main f0 {
+        //   b0(v0: u32):
+        //     v1 = allocate
+        //     store v0 at v1
+        //     v2 = load v1
+        //     v3 = call f1(v2)
+        //     return v3
+        // }
+        // unconstrained fn foo f1 {
+        //   b0(v0: u32):
+        //     v1 = array_get [Field 1, Field 2], index v0
+        //     return v1
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        builder.current_function.set_constant_time(true);
+
+        let v0 = builder.add_parameter(Type::unsigned(32));
+        let v1 = builder.insert_allocate(Type::unsigned(32));
+        builder.insert_store(v1, v0);
+        let v2 = builder.insert_load(v1, Type::unsigned(32));
+
+        let foo_id = Id::test_new(1);
+        let foo = builder.import_function(foo_id);
+        let v3 = builder.insert_call(foo, vec![v2], vec![Type::field()])[0];
+        builder.terminate_with_return(vec![v3]);
+
+        builder.new_brillig_function("foo".into(), foo_id);
+        let v0 = builder.add_parameter(Type::unsigned(32));
+        let one = builder.field_constant(1u128);
+        let two = builder.field_constant(2u128);
+        let array_type = Type::Array(vec![Type::field()].into(), 2);
+        let array = builder.array_constant(vec![one, two].into(), array_type);
+        let v1 = builder.insert_array_get(array, v0, Type::field());
+        builder.terminate_with_return(vec![v1]);
+
+        let ssa = builder.finish();
+        assert!(matches!(
+            ssa.check_constant_time(),
+            Err(RuntimeError::SecretDependentIndex { .. })
+        ));
+    }
+
+    #[test]
+    fn branch_in_constrained_function() {
+        // Branches within constrained code are flattened so do not leak their condition.
+        //
+        // fn main f0 {
+        //   b0(v0: bool):
+        //     jmpif v0 then: b1, else: b2
+        //   b1():
+        //     jmp b2()
+        //   b2():
+        //     return
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        builder.current_function.set_constant_time(true);
+
+        let v0 = builder.add_parameter(Type::Numeric(NumericType::Unsigned { bit_size: 1 }));
+        let b1 = builder.insert_block();
+        let b2 = builder.insert_block();
+        builder.terminate_with_jmpif(v0, b1, b2);
+        builder.switch_to_block(b1);
+        builder.terminate_with_jmp(b2, vec![]);
+        builder.switch_to_block(b2);
+        builder.terminate_with_return(vec![]);
+
+        assert!(builder.finish().check_constant_time().is_ok());
+    }
+}
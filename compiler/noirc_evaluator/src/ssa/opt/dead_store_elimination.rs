@@ -0,0 +1,349 @@
+//! Dead Store Elimination (DSE) pass: Removes any `Store` instruction to a local allocation
+//! whose stored value can never be loaded afterwards.
+//!
+//! mem2reg can only remove a store if it is followed by another store to the same address
+//! within the same block, or if the function consists of a single block. This pass instead
+//! performs a liveness analysis over the whole function.
+//!
+//! The pass works as follows:
+//! - Find each allocation which does not escape the function. An allocation is considered to
+//!   escape if its reference is used for anything other than the address of a `Load` or `Store`,
+//!   e.g. passed to a call, stored in memory, placed in an array, or passed to another block.
+//!   We cannot know when the memory of an escaping allocation is read so it is left untouched.
+//! - Compute, for each block, the set of allocations which may be loaded from before being
+//!   overwritten on some path starting at the beginning of that block. This is a backwards
+//!   dataflow analysis which is iterated until a fixed point is reached to handle loops.
+//! - Walk each block backwards starting from the allocations live at the end of the block.
+//!   A store to an allocation which is not live at that point is removed.
+//!
+//! Any allocations which are left without any uses are later removed by the
+//! [dead instruction elimination][super::die] pass.
+use std::collections::HashSet;
+
+use crate::ssa::{
+    ir::{
+        basic_block::BasicBlockId,
+        dfg::DataFlowGraph,
+        function::Function,
+        instruction::{Instruction, InstructionId},
+        post_order::PostOrder,
+        value::{Value, ValueId},
+    },
+    ssa_gen::Ssa,
+};
+use fxhash::FxHashMap as HashMap;
+
+impl Ssa {
+    /// Performs Dead Store Elimination (DSE) to remove any stores to local allocations
+    /// which are never subsequently loaded.
+    #[tracing::instrument(level = "trace", skip(self))]
+    pub(crate) fn dead_store_elimination(mut self) -> Ssa {
+        for function in self.functions.values_mut() {
+            dead_store_elimination(function);
+        }
+        self
+    }
+}
+
+fn dead_store_elimination(function: &mut Function) {
+    let allocations = find_local_allocations(function);
+    if allocations.is_empty() {
+        return;
+    }
+
+    let post_order = PostOrder::with_function(function);
+    let live_in = compute_live_allocations(&function.dfg, &post_order, &allocations);
+
+    for block in post_order.as_slice() {
+        let live_out = live_at_end_of_block(&function.dfg, *block, &live_in);
+        let (_, dead_stores) = analyze_block(&function.dfg, *block, &allocations, live_out);
+
+        if !dead_stores.is_empty() {
+            function.dfg[*block]
+                .instructions_mut()
+                .retain(|instruction| !dead_stores.contains(instruction));
+        }
+    }
+}
+
+/// Returns each allocation in the function whose reference is only ever used
+/// as the address of a `Load` or `Store` instruction.
+fn find_local_allocations(function: &Function) -> HashSet<ValueId> {
+    let dfg = &function.dfg;
+    let mut allocations = HashSet::new();
+    let mut escaped = HashSet::new();
+
+    for block in function.reachable_blocks() {
+        for instruction in dfg[block].instructions() {
+            match &dfg[*instruction] {
+                Instruction::Allocate => {
+                    allocations.insert(dfg.instruction_results(*instruction)[0]);
+                }
+                Instruction::Load { .. } => (),
+                Instruction::Store { value, .. } => mark_escaped(dfg, *value, &mut escaped),
+                other => other.for_each_value(|value| mark_escaped(dfg, value, &mut escaped)),
+            }
+        }
+
+        dfg[block].unwrap_terminator().for_each_value(|value| {
+            mark_escaped(dfg, value, &mut escaped);
+        });
+    }
+
+    allocations.retain(|allocation| !escaped.contains(allocation));
+    allocations
+}
+
+/// Inspects a value recursively (as it could be an array) and marks all comprised values as escaped.
+fn mark_escaped(dfg: &DataFlowGraph, value: ValueId, escaped: &mut HashSet<ValueId>) {
+    let value = dfg.resolve(value);
+    match &dfg[value] {
+        Value::Array { array, .. } => {
+            for element in array {
+                mark_escaped(dfg, *element, escaped);
+            }
+        }
+        _ => {
+            escaped.insert(value);
+        }
+    }
+}
+
+/// Computes the set of allocations which are live at the start of each block.
+fn compute_live_allocations(
+    dfg: &DataFlowGraph,
+    post_order: &PostOrder,
+    allocations: &HashSet<ValueId>,
+) -> HashMap<BasicBlockId, HashSet<ValueId>> {
+    let mut live_in: HashMap<BasicBlockId, HashSet<ValueId>> = HashMap::default();
+
+    // Visiting blocks in post-order means that successors are generally visited before their
+    // predecessors, so this converges quickly. Loops require extra iterations to propagate the
+    // liveness of the loop header back into the loop body.
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for block in post_order.as_slice() {
+            let live_out = live_at_end_of_block(dfg, *block, &live_in);
+            let (block_live_in, _) = analyze_block(dfg, *block, allocations, live_out);
+
+            if live_in.get(block) != Some(&block_live_in) {
+                live_in.insert(*block, block_live_in);
+                changed = true;
+            }
+        }
+    }
+
+    live_in
+}
+
+/// The allocations live at the end of a block are those live at the start of any of its successors.
+fn live_at_end_of_block(
+    dfg: &DataFlowGraph,
+    block: BasicBlockId,
+    live_in: &HashMap<BasicBlockId, HashSet<ValueId>>,
+) -> HashSet<ValueId> {
+    let mut live = HashSet::new();
+    for successor in dfg[block].successors() {
+        if let Some(successor_live_in) = live_in.get(&successor) {
+            live.extend(successor_live_in.iter().copied());
+        }
+    }
+    live
+}
+
+/// Steps backwards through the instructions of the given block, starting with the set of
+/// allocations live at the end of the block.
+///
+/// Returns the set of allocations live at the start of the block, along with any stores in
+/// the block to allocations which are not live immediately after the store.
+fn analyze_block(
+    dfg: &DataFlowGraph,
+    block: BasicBlockId,
+    allocations: &HashSet<ValueId>,
+    mut live: HashSet<ValueId>,
+) -> (HashSet<ValueId>, HashSet<InstructionId>) {
+    let mut dead_stores = HashSet::new();
+
+    for instruction in dfg[block].instructions().iter().rev() {
+        match &dfg[*instruction] {
+            Instruction::Load { address } => {
+                let address = dfg.resolve(*address);
+                if allocations.contains(&address) {
+                    live.insert(address);
+                }
+            }
+            Instruction::Store { address, .. } => {
+                let address = dfg.resolve(*address);
+                if allocations.contains(&address) && !live.remove(&address) {
+                    dead_stores.insert(*instruction);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    (live, dead_stores)
+}
+
+#[cfg(test)]
+mod test {
+    use acvm::FieldElement;
+
+    use crate::ssa::{
+        function_builder::FunctionBuilder,
+        ir::{
+            basic_block::BasicBlockId,
+            dfg::DataFlowGraph,
+            function::RuntimeType,
+            instruction::{BinaryOp, Instruction, Intrinsic},
+            map::Id,
+            types::Type,
+        },
+    };
+
+    fn count_stores(block: BasicBlockId, dfg: &DataFlowGraph) -> usize {
+        dfg[block]
+            .instructions()
+            .iter()
+            .filter(|instruction_id| matches!(dfg[**instruction_id], Instruction::Store { .. }))
+            .count()
+    }
+
+    #[test]
+    fn remove_stores_which_are_never_loaded() {
+        // fn main f0 {
+        //   b0(v0: u1):
+        //     v1 = allocate
+        //     store Field 1 at v1
+        //     jmpif v0, then: b1, else: b2
+        //   b1():
+        //     v2 = load v1
+        //     store Field 2 at v1
+        //     jmp b3()
+        //   b2():
+        //     store Field 3 at v1
+        //     jmp b3()
+        //   b3():
+        //     return
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+
+        let b1 = builder.insert_block();
+        let b2 = builder.insert_block();
+        let b3 = builder.insert_block();
+
+        let v0 = builder.add_parameter(Type::bool());
+        let v1 = builder.insert_allocate(Type::field());
+        let one = builder.field_constant(FieldElement::one());
+        builder.insert_store(v1, one);
+        builder.terminate_with_jmpif(v0, b1, b2);
+
+        builder.switch_to_block(b1);
+        builder.insert_load(v1, Type::field());
+        let two = builder.field_constant(2u128);
+        builder.insert_store(v1, two);
+        builder.terminate_with_jmp(b3, vec![]);
+
+        builder.switch_to_block(b2);
+        let three = builder.field_constant(3u128);
+        builder.insert_store(v1, three);
+        builder.terminate_with_jmp(b3, vec![]);
+
+        builder.switch_to_block(b3);
+        builder.terminate_with_return(vec![]);
+
+        // The store in b0 must be kept as it is loaded in b1, while the other two are never loaded.
+        let ssa = builder.finish().dead_store_elimination();
+        let main = ssa.main();
+
+        assert_eq!(count_stores(main.entry_block(), &main.dfg), 1);
+        assert_eq!(count_stores(b1, &main.dfg), 0);
+        assert_eq!(main.dfg[b1].instructions().len(), 1);
+        assert_eq!(count_stores(b2, &main.dfg), 0);
+    }
+
+    #[test]
+    fn keep_stores_to_loaded_allocation_in_loop() {
+        // fn main f0 {
+        //   b0():
+        //     v0 = allocate
+        //     store Field 0 at v0
+        //     jmp b1(u32 0)
+        //   b1(v1: u32):
+        //     v2 = lt v1, u32 4
+        //     jmpif v2, then: b2, else: b3
+        //   b2():
+        //     v3 = load v0
+        //     v4 = add v3, Field 1
+        //     store v4 at v0
+        //     v5 = add v1, u32 1
+        //     jmp b1(v5)
+        //   b3():
+        //     return
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Brillig);
+
+        let b1 = builder.insert_block();
+        let b2 = builder.insert_block();
+        let b3 = builder.insert_block();
+
+        let v1 = builder.add_block_parameter(b1, Type::unsigned(32));
+
+        let v0 = builder.insert_allocate(Type::field());
+        let zero = builder.field_constant(0u128);
+        builder.insert_store(v0, zero);
+        let zero_u32 = builder.numeric_constant(0u128, Type::unsigned(32));
+        builder.terminate_with_jmp(b1, vec![zero_u32]);
+
+        builder.switch_to_block(b1);
+        let four = builder.numeric_constant(4u128, Type::unsigned(32));
+        let v2 = builder.insert_binary(v1, BinaryOp::Lt, four);
+        builder.terminate_with_jmpif(v2, b2, b3);
+
+        builder.switch_to_block(b2);
+        let v3 = builder.insert_load(v0, Type::field());
+        let one = builder.field_constant(1u128);
+        let v4 = builder.insert_binary(v3, BinaryOp::Add, one);
+        builder.insert_store(v0, v4);
+        let one_u32 = builder.numeric_constant(1u128, Type::unsigned(32));
+        let v5 = builder.insert_binary(v1, BinaryOp::Add, one_u32);
+        builder.terminate_with_jmp(b1, vec![v5]);
+
+        builder.switch_to_block(b3);
+        builder.terminate_with_return(vec![]);
+
+        // The store in the loop body is loaded by the next iteration, so no store can be removed.
+        let ssa = builder.finish().dead_store_elimination();
+        let main = ssa.main();
+
+        assert_eq!(count_stores(main.entry_block(), &main.dfg), 1);
+        assert_eq!(count_stores(b2, &main.dfg), 1);
+    }
+
+    #[test]
+    fn keep_stores_to_escaping_allocation() {
+        // fn main f0 {
+        //   b0():
+        //     v0 = allocate
+        //     store Field 1 at v0
+        //     call assert_constant(v0)
+        //     return
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+
+        let v0 = builder.insert_allocate(Type::field());
+        let one = builder.field_constant(FieldElement::one());
+        builder.insert_store(v0, one);
+        let f0 = builder.import_intrinsic_id(Intrinsic::AssertConstant);
+        builder.insert_call(f0, vec![v0], vec![]);
+        builder.terminate_with_return(vec![]);
+
+        let ssa = builder.finish().dead_store_elimination();
+        let main = ssa.main();
+        assert_eq!(count_stores(main.entry_block(), &main.dfg), 1);
+    }
+}
@@ -115,8 +115,16 @@ impl DefunctionalizationContext {
 
                         replacement_instruction = Some(Instruction::Call { func, arguments });
                     }
-                    Value::Function(..) => {
-                        call_target_values.insert(target_func_id);
+                    Value::Function(id) => {
+                        // The same function value may also be passed to another function, as
+                        // happens when calling a function pointer stored in a variable. Each
+                        // call is given its own target so that the original value can still be
+                        // replaced by its function id below, which unconstrained functions
+                        // receiving it as an argument require.
+                        let func = func.dfg.import_function(id);
+                        call_target_values.insert(func);
+
+                        replacement_instruction = Some(Instruction::Call { func, arguments });
                     }
                     _ => {}
                 }
@@ -0,0 +1,256 @@
+//! Finds the unconstrained functions whose memory can be freed when they return.
+//!
+//! Brillig allocates arrays, vectors and references by moving the stack pointer past them. After a
+//! call, the memory of the call is only freed again if the callee has not allocated any, so arrays
+//! which are only used while a function runs otherwise stay allocated for the rest of the
+//! execution, growing the memory with each call.
+//!
+//! All of the memory allocated during a call, including by the functions it calls in turn, lies
+//! past the stack pointer at the time of the call. If none of it can be reached once the function
+//! returns, the function moves the stack pointer back when returning, freeing all of it at once.
+//! The memory of a function escapes when it:
+//! - returns an array, vector or reference other than one of its parameters,
+//! - stores an array, vector or reference at an address it has not allocated itself,
+//! - writes an array, vector or reference into an array it has not created itself, which may be
+//!   an array of its caller written to in place, or
+//! - passes a reference to a function whose memory escapes, which may store into it.
+use fxhash::FxHashSet as HashSet;
+
+use crate::ssa::{
+    ir::{
+        function::{Function, FunctionId, RuntimeType},
+        instruction::{Instruction, TerminatorInstruction},
+        post_order::PostOrder,
+        value::{Value, ValueId},
+    },
+    ssa_gen::Ssa,
+};
+
+impl Ssa {
+    /// Returns the unconstrained functions none of whose memory can be accessed once they return.
+    #[tracing::instrument(level = "trace", skip(self))]
+    pub(crate) fn find_functions_with_local_memory(&self) -> HashSet<FunctionId> {
+        let functions: Vec<_> = self
+            .functions
+            .values()
+            .filter(|function| function.runtime() == RuntimeType::Brillig)
+            .collect();
+
+        // Functions are assumed to keep their memory local until shown otherwise, so that
+        // recursive functions can free their memory as well.
+        let mut local_memory: HashSet<_> = functions.iter().map(|function| function.id()).collect();
+        loop {
+            let escaping: Vec<_> = functions
+                .iter()
+                .filter(|function| {
+                    local_memory.contains(&function.id()) && memory_escapes(function, &local_memory)
+                })
+                .map(|function| function.id())
+                .collect();
+            if escaping.is_empty() {
+                return local_memory;
+            }
+            for function in escaping {
+                local_memory.remove(&function);
+            }
+        }
+    }
+}
+
+/// Returns true if memory allocated by a call to `function` could be accessed after it returns,
+/// given the functions in `local_memory` whose memory can't.
+fn memory_escapes(function: &Function, local_memory: &HashSet<FunctionId>) -> bool {
+    let dfg = &function.dfg;
+    let holds_memory = |value: ValueId| {
+        let typ = dfg.type_of_value(value);
+        typ.contains_an_array() || typ.contains_reference()
+    };
+
+    // The arrays and references created by the function, which its caller can't access
+    let mut created = HashSet::default();
+    let is_created = |created: &HashSet<ValueId>, value: ValueId| {
+        let value = dfg.resolve(value);
+        matches!(dfg[value], Value::Array { .. }) || created.contains(&value)
+    };
+
+    let mut blocks = PostOrder::with_function(function).into_vec();
+    blocks.reverse();
+    for block in blocks {
+        for instruction_id in dfg[block].instructions() {
+            let results = dfg.instruction_results(*instruction_id);
+            match &dfg[*instruction_id] {
+                Instruction::Allocate => {
+                    created.insert(results[0]);
+                }
+                Instruction::ArraySet { array, value, .. } => {
+                    if is_created(&created, *array) {
+                        created.insert(results[0]);
+                    } else if holds_memory(*value) {
+                        return true;
+                    }
+                }
+                Instruction::Store { address, value } => {
+                    if holds_memory(*value) && !is_created(&created, *address) {
+                        return true;
+                    }
+                }
+                Instruction::Call { func, arguments } => {
+                    if let Value::Function(callee) = dfg[*func] {
+                        let passes_reference = arguments
+                            .iter()
+                            .any(|argument| dfg.type_of_value(*argument).contains_reference());
+                        if passes_reference && !local_memory.contains(&callee) {
+                            return true;
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        if let Some(TerminatorInstruction::Return { return_values, .. }) = dfg[block].terminator() {
+            let returns_memory = return_values.iter().any(|value| {
+                holds_memory(*value) && !function.parameters().contains(&dfg.resolve(*value))
+            });
+            if returns_memory {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use acvm::brillig_vm::brillig::Value;
+
+    use crate::brillig::{
+        brillig_gen::brillig_fn::FunctionContext,
+        brillig_ir::{tests::create_and_run_vm, BrilligContext},
+    };
+    use crate::ssa::{
+        function_builder::FunctionBuilder,
+        ir::{function::RuntimeType, map::Id, types::Type},
+        ssa_gen::Ssa,
+    };
+
+    // brillig fn main f0 {
+    //   b0(v0: Field):
+    //     v1 = call f1(v0)
+    //     v2 = call f2(v0)
+    //     return v1
+    // }
+    // brillig fn sum f1 {
+    //   b0(v0: Field):
+    //     v1 = array_set [Field 0, Field 0], index u32 0, value v0
+    //     v2 = array_get v1, index u32 1
+    //     return v2
+    // }
+    // brillig fn make f2 {
+    //   b0(v0: Field):
+    //     return [v0, v0]
+    // }
+    fn local_and_returned_arrays() -> Ssa {
+        let array_type = Type::Array(Rc::new(vec![Type::field()]), 2);
+
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Brillig);
+        let v0 = builder.add_parameter(Type::field());
+        let sum = builder.import_function(Id::test_new(1));
+        let make = builder.import_function(Id::test_new(2));
+        let v1 = builder.insert_call(sum, vec![v0], vec![Type::field()]).to_vec();
+        builder.insert_call(make, vec![v0], vec![array_type.clone()]);
+        builder.terminate_with_return(v1);
+
+        builder.new_brillig_function("sum".into(), Id::test_new(1));
+        let v0 = builder.add_parameter(Type::field());
+        let zero = builder.field_constant(0u128);
+        let array = builder.array_constant(vec![zero, zero].into(), array_type.clone());
+        let index = builder.numeric_constant(0u128, Type::unsigned(32));
+        let v1 = builder.insert_array_set(array, index, v0);
+        let index = builder.numeric_constant(1u128, Type::unsigned(32));
+        let v2 = builder.insert_array_get(v1, index, Type::field());
+        builder.terminate_with_return(vec![v2]);
+
+        builder.new_brillig_function("make".into(), Id::test_new(2));
+        let v0 = builder.add_parameter(Type::field());
+        let array = builder.array_constant(vec![v0, v0].into(), array_type);
+        builder.terminate_with_return(vec![array]);
+
+        builder.finish()
+    }
+
+    #[test]
+    fn finds_functions_with_local_memory() {
+        let ssa = local_and_returned_arrays();
+        let local_memory = ssa.find_functions_with_local_memory();
+
+        assert!(local_memory.contains(&Id::test_new(0)));
+        assert!(local_memory.contains(&Id::test_new(1)));
+        assert!(!local_memory.contains(&Id::test_new(2)));
+    }
+
+    // brillig fn main f0 {
+    //   b0(v0: Field):
+    //     v1 = call f1(v0)
+    //     ... repeated `calls` times
+    //     return
+    // }
+    // with f1 being either `sum` or `make` above
+    fn repeated_calls(calls: usize, local: bool) -> Ssa {
+        let array_type = Type::Array(Rc::new(vec![Type::field()]), 2);
+
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Brillig);
+        let v0 = builder.add_parameter(Type::field());
+        let callee = builder.import_function(Id::test_new(1));
+        let return_type = if local { Type::field() } else { array_type.clone() };
+        for _ in 0..calls {
+            builder.insert_call(callee, vec![v0], vec![return_type.clone()]);
+        }
+        builder.terminate_with_return(vec![]);
+
+        builder.new_brillig_function("callee".into(), Id::test_new(1));
+        let v0 = builder.add_parameter(Type::field());
+        let result = if local {
+            let zero = builder.field_constant(0u128);
+            let array = builder.array_constant(vec![zero, zero].into(), array_type.clone());
+            let index = builder.numeric_constant(0u128, Type::unsigned(32));
+            let v1 = builder.insert_array_set(array, index, v0);
+            let index = builder.numeric_constant(1u128, Type::unsigned(32));
+            builder.insert_array_get(v1, index, Type::field())
+        } else {
+            builder.array_constant(vec![v0, v0].into(), array_type)
+        };
+        builder.terminate_with_return(vec![result]);
+
+        builder.finish()
+    }
+
+    /// Runs the Brillig bytecode of `ssa`, returning the size of the memory it used.
+    fn memory_used(ssa: &Ssa) -> usize {
+        let brillig = ssa.to_brillig(false);
+        let main = ssa.main();
+        let mut entry_point = BrilligContext::new_entry_point_artifact(
+            FunctionContext::parameters(main),
+            FunctionContext::return_values(main),
+            FunctionContext::function_id_to_function_label(main.id()),
+        );
+        while let Some(label) = entry_point.first_unresolved_function_call() {
+            let artifact = brillig.find_by_function_label(label).expect("function should exist");
+            entry_point.link_with(artifact);
+        }
+        let bytecode = entry_point.finish().byte_code;
+
+        let vm = create_and_run_vm(vec![], vec![Value::from(1_usize)], &bytecode);
+        vm.get_memory().len()
+    }
+
+    #[test]
+    fn reuses_freed_memory_in_later_calls() {
+        assert_eq!(memory_used(&repeated_calls(10, true)), memory_used(&repeated_calls(1, true)));
+        assert!(memory_used(&repeated_calls(10, false)) > memory_used(&repeated_calls(1, false)));
+    }
+}
@@ -152,12 +152,15 @@ use crate::ssa::{
 };
 
 mod branch_analysis;
+pub(crate) mod capacity_tracker;
 pub(crate) mod value_merger;
 
+use capacity_tracker::SliceCapacities;
 use value_merger::ValueMerger;
 
 impl Ssa {
-    /// Flattens the control flow graph of main such that the function is left with a
+    /// Flattens the control flow graph of each constrained function, which is main and any
+    /// function compiled to a separate ACIR function, such that the function is left with a
     /// single block containing all instructions and no more control-flow.
     ///
     /// This pass will modify any instructions with side effects in particular, often multiplying
@@ -165,7 +168,9 @@ impl Ssa {
     /// For more information, see the module-level comment at the top of this file.
     #[tracing::instrument(level = "trace", skip(self))]
     pub(crate) fn flatten_cfg(mut self) -> Ssa {
-        flatten_function_cfg(self.main_mut());
+        for function in self.functions.values_mut() {
+            flatten_function_cfg(function);
+        }
         self
     }
 }
@@ -182,18 +187,12 @@ struct Context<'f> {
     /// Maps an address to the old and new value of the element at that address
     /// These only hold stores for one block at a time and is cleared
     /// between inlining of branches.
-    store_values: HashMap<ValueId, Store>,
+    /// This is ordered as stores are inserted while iterating over it.
+    store_values: BTreeMap<ValueId, Store>,
 
-    /// Maps an address to the old and new value of the element at that address
-    /// The difference between this map and store_values is that this stores
-    /// the old and new value of an element from the outer block whose jmpif
-    /// terminator is being flattened.
-    ///
-    /// This map persists throughout the flattening process, where addresses
-    /// are overwritten as new stores are found. This overwriting is the desired behavior,
-    /// as we want the most update to date value to be stored at a given address as
-    /// we walk through blocks to flatten.
-    outer_block_stores: HashMap<ValueId, ValueId>,
+    /// The capacity of each slice which may be merged, including slices loaded from
+    /// addresses stored to in an outer block.
+    slice_capacities: SliceCapacities,
 
     /// Stores all allocations local to the current branch.
     /// Since these branches are local to the current branch (ie. only defined within one branch of
@@ -211,7 +210,7 @@ struct Context<'f> {
     conditions: Vec<(BasicBlockId, ValueId)>,
 }
 
-pub(crate) struct Store {
+struct Store {
     old_value: ValueId,
     new_value: ValueId,
 }
@@ -219,7 +218,7 @@ pub(crate) struct Store {
 struct Branch {
     condition: ValueId,
     last_block: BasicBlockId,
-    store_values: HashMap<ValueId, Store>,
+    store_values: BTreeMap<ValueId, Store>,
 }
 
 fn flatten_function_cfg(function: &mut Function) {
@@ -235,20 +234,37 @@ fn flatten_function_cfg(function: &mut Function) {
     let mut context = Context {
         inserter: FunctionInserter::new(function),
         cfg,
-        store_values: HashMap::default(),
+        store_values: BTreeMap::new(),
         local_allocations: HashSet::new(),
         branch_ends,
         conditions: Vec::new(),
-        outer_block_stores: HashMap::default(),
+        slice_capacities: SliceCapacities::default(),
     };
     context.flatten();
 }
 
 impl<'f> Context<'f> {
     fn flatten(&mut self) {
+        // The entry block is never inlined, so the slices it stores and loads are recorded here
+        // for any slices from it which are merged later.
+        let entry = self.inserter.function.entry_block();
+        let dfg = &self.inserter.function.dfg;
+        for instruction in dfg[entry].instructions() {
+            match &dfg[*instruction] {
+                Instruction::Store { address, value } => {
+                    self.slice_capacities.record_store(*address, *value);
+                }
+                Instruction::Load { address } => {
+                    let result = dfg.instruction_results(*instruction)[0];
+                    self.slice_capacities.record_load(dfg, *address, result);
+                }
+                _ => (),
+            }
+        }
+
         // Start with following the terminator of the entry block since we don't
         // need to flatten the entry block into itself.
-        self.handle_terminator(self.inserter.function.entry_block());
+        self.handle_terminator(entry);
     }
 
     /// Check the terminator of the given block and recursively inline any blocks reachable from
@@ -262,23 +278,6 @@ impl<'f> Context<'f> {
     /// Returns the last block to be inlined. This is either the return block of the function or,
     /// if self.conditions is not empty, the end block of the most recent condition.
     fn handle_terminator(&mut self, block: BasicBlockId) -> BasicBlockId {
-        if let TerminatorInstruction::JmpIf { .. } =
-            self.inserter.function.dfg[block].unwrap_terminator()
-        {
-            // Find stores in the outer block and insert into the `outer_block_stores` map.
-            // Not using this map can lead to issues when attempting to merge slices.
-            // When inlining a branch end, only the then branch and the else branch are checked for stores.
-            // However, there are cases where we want to load a value that comes from the outer block
-            // that we are handling the terminator for here.
-            let instructions = self.inserter.function.dfg[block].instructions().to_vec();
-            for instruction in instructions {
-                let (instruction, _) = self.inserter.map_instruction(instruction);
-                if let Instruction::Store { address, value } = instruction {
-                    self.outer_block_stores.insert(address, value);
-                }
-            }
-        }
-
         match self.inserter.function.dfg[block].unwrap_terminator() {
             TerminatorInstruction::JmpIf { condition, then_destination, else_destination } => {
                 let old_condition = *condition;
@@ -387,6 +386,12 @@ impl<'f> Context<'f> {
         )
     }
 
+    /// Inserts a store of `value` at `address` into the function's entry block.
+    fn insert_store(&mut self, address: ValueId, value: ValueId) {
+        self.slice_capacities.record_store(address, value);
+        self.insert_instruction_with_typevars(Instruction::Store { address, value }, None);
+    }
+
     /// Checks the branch condition on the top of the stack and uses it to build and insert an
     /// `EnableSideEffects` instruction into the entry block.
     ///
@@ -429,7 +434,7 @@ impl<'f> Context<'f> {
                 // args that will be merged by inline_branch_end. Since jmpifs don't have
                 // block arguments, it is safe to use the jmpif block here.
                 last_block: jmpif_block,
-                store_values: HashMap::default(),
+                store_values: BTreeMap::new(),
             }
         } else {
             self.push_condition(jmpif_block, new_condition);
@@ -494,12 +499,8 @@ impl<'f> Context<'f> {
         });
 
         let block = self.inserter.function.entry_block();
-        let mut value_merger = ValueMerger::new(
-            &mut self.inserter.function.dfg,
-            block,
-            Some(&self.store_values),
-            Some(&self.outer_block_stores),
-        );
+        let mut value_merger =
+            ValueMerger::new(&mut self.inserter.function.dfg, block, &mut self.slice_capacities);
 
         // Cannot include this in the previous vecmap since it requires exclusive access to self
         let args = vecmap(args, |(then_arg, else_arg)| {
@@ -543,12 +544,8 @@ impl<'f> Context<'f> {
 
         let block = self.inserter.function.entry_block();
 
-        let mut value_merger = ValueMerger::new(
-            &mut self.inserter.function.dfg,
-            block,
-            Some(&self.store_values),
-            Some(&self.outer_block_stores),
-        );
+        let mut value_merger =
+            ValueMerger::new(&mut self.inserter.function.dfg, block, &mut self.slice_capacities);
 
         // Merging must occur in a separate loop as we cannot borrow `self` as mutable while `value_merger` does
         let mut new_values = HashMap::default();
@@ -562,7 +559,7 @@ impl<'f> Context<'f> {
         for (address, (_, _, old_value)) in &new_map {
             let value = new_values[address];
             let address = *address;
-            self.insert_instruction_with_typevars(Instruction::Store { address, value }, None);
+            self.insert_store(address, value);
 
             if let Some(store) = self.store_values.get_mut(&address) {
                 store.new_value = value;
@@ -581,6 +578,8 @@ impl<'f> Context<'f> {
                 let load = Instruction::Load { address };
                 let load_type = Some(vec![self.inserter.function.dfg.type_of_value(new_value)]);
                 let old_value = self.insert_instruction_with_typevars(load, load_type).first();
+                let dfg = &self.inserter.function.dfg;
+                self.slice_capacities.record_load(dfg, address, old_value);
 
                 self.store_values.insert(address, Store { old_value, new_value });
             }
@@ -620,6 +619,15 @@ impl<'f> Context<'f> {
         let instruction = self.handle_instruction_side_effects(instruction, call_stack.clone());
         let is_allocate = matches!(instruction, Instruction::Allocate);
 
+        let loaded_address = match instruction {
+            Instruction::Store { address, value } => {
+                self.slice_capacities.record_store(address, value);
+                None
+            }
+            Instruction::Load { address } => Some(address),
+            _ => None,
+        };
+
         let entry = self.inserter.function.entry_block();
         let results = self.inserter.push_instruction_value(instruction, id, entry, call_stack);
 
@@ -628,6 +636,12 @@ impl<'f> Context<'f> {
         if is_allocate {
             self.local_allocations.insert(results.first());
         }
+
+        if let Some(address) = loaded_address {
+            let result = results.first();
+            let dfg = &self.inserter.function.dfg;
+            self.slice_capacities.record_load(dfg, address, result);
+        }
     }
 
     /// If we are currently in a branch, we need to modify constrain instructions
@@ -702,6 +716,43 @@ impl<'f> Context<'f> {
 
                         Instruction::Call { func, arguments }
                     }
+                    Value::Intrinsic(Intrinsic::StrSubstring) => {
+                        // Replace the start of the substring with `start * predicate` so that an
+                        // inactive substring is taken from the start of the string.
+                        let start = arguments[1];
+                        let argument_type = self.inserter.function.dfg.type_of_value(start);
+
+                        let casted_condition = self.insert_instruction(
+                            Instruction::Cast(condition, argument_type),
+                            call_stack.clone(),
+                        );
+                        let start = self.insert_instruction(
+                            Instruction::binary(BinaryOp::Mul, start, casted_condition),
+                            call_stack.clone(),
+                        );
+
+                        arguments[1] = start;
+
+                        Instruction::Call { func, arguments }
+                    }
+                    Value::Intrinsic(Intrinsic::ArraySwap) => {
+                        // Replace both indices with `index * predicate` so that an inactive swap
+                        // swaps the first element with itself.
+                        for argument in &mut arguments[1..] {
+                            let argument_type = self.inserter.function.dfg.type_of_value(*argument);
+
+                            let casted_condition = self.insert_instruction(
+                                Instruction::Cast(condition, argument_type),
+                                call_stack.clone(),
+                            );
+                            *argument = self.insert_instruction(
+                                Instruction::binary(BinaryOp::Mul, *argument, casted_condition),
+                                call_stack.clone(),
+                            );
+                        }
+
+                        Instruction::Call { func, arguments }
+                    }
 
                     _ => Instruction::Call { func, arguments },
                 },
@@ -716,7 +767,7 @@ impl<'f> Context<'f> {
         for (address, store) in &then_branch.store_values {
             let address = *address;
             let value = store.old_value;
-            self.insert_instruction_with_typevars(Instruction::Store { address, value }, None);
+            self.insert_store(address, value);
         }
     }
 }
@@ -1378,6 +1429,73 @@ mod test {
         assert_eq!(constrain_count, 1);
     }
 
+    #[test]
+    fn merge_slice_of_tuples_popped_in_branch() {
+        // fn main f0 {
+        //   b0(v0: u1):
+        //     v1 = allocate
+        //     store [Field 1, Field 2, Field 3, Field 4] at v1
+        //     v2 = load v1
+        //     jmpif v0 then: b1, else: b2
+        //   b1():
+        //     v3, v4, v5, v6 = call slice_pop_front(Field 2, v2)
+        //     jmp b3(v6)
+        //   b2():
+        //     jmp b3(v2)
+        //   b3(v7: [(Field, Field)]):
+        //     return v7
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+
+        let b1 = builder.insert_block();
+        let b2 = builder.insert_block();
+        let b3 = builder.insert_block();
+
+        let slice_type = Type::Slice(Rc::new(vec![Type::field(), Type::field()]));
+        let v0 = builder.add_parameter(Type::bool());
+        let v7 = builder.add_block_parameter(b3, slice_type.clone());
+
+        let elements = im::vector![
+            builder.field_constant(1u128),
+            builder.field_constant(2u128),
+            builder.field_constant(3u128),
+            builder.field_constant(4u128)
+        ];
+        let array = builder.array_constant(elements, slice_type.clone());
+
+        let v1 = builder.insert_allocate(slice_type.clone());
+        builder.insert_store(v1, array);
+        let v2 = builder.insert_load(v1, slice_type.clone());
+        builder.terminate_with_jmpif(v0, b1, b2);
+
+        builder.switch_to_block(b1);
+        let pop_front = builder.import_intrinsic_id(Intrinsic::SlicePopFront);
+        let length = builder.field_constant(2u128);
+        let result_types = vec![Type::field(), Type::field(), Type::field(), slice_type];
+        let results = builder.insert_call(pop_front, vec![length, v2], result_types).to_vec();
+        builder.terminate_with_jmp(b3, vec![results[3]]);
+
+        builder.switch_to_block(b2);
+        builder.terminate_with_jmp(b3, vec![v2]);
+
+        builder.switch_to_block(b3);
+        builder.terminate_with_return(vec![v7]);
+
+        let ssa = builder.finish().flatten_cfg();
+        let main = ssa.main();
+
+        // The popped slice holds a single tuple, so the merged slice holds two tuples
+        let return_value = match main.dfg[main.entry_block()].terminator() {
+            Some(TerminatorInstruction::Return { return_values, .. }) => return_values[0],
+            _ => unreachable!("Should have terminator instruction"),
+        };
+        match &main.dfg[return_value] {
+            Value::Array { array, .. } => assert_eq!(array.len(), 4),
+            other => unreachable!("Expected the merged slice to be an array, found {other:?}"),
+        }
+    }
+
     #[test]
     fn undo_stores() {
         // Regression test for #1826. Ensures the `else` branch does not see the stores of the
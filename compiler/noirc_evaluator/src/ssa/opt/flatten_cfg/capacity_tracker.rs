@@ -0,0 +1,111 @@
+//! Tracks the capacity of each slice merged while flattening.
+//!
+//! A slice is a pair of its length and the array holding its contents. The length is the
+//! user-facing `.len()` of the slice, which may only be known at runtime. Merging two slices
+//! however must know how many values the contents of each slice hold at compile-time, so that
+//! the merged contents can hold the values of both. This capacity can be larger than the length
+//! of the slice, with any values past its length being placeholder data.
+use fxhash::FxHashMap as HashMap;
+
+use crate::ssa::ir::{
+    dfg::DataFlowGraph,
+    instruction::{Instruction, Intrinsic},
+    types::Type,
+    value::{Value, ValueId},
+};
+
+#[derive(Default)]
+pub(crate) struct SliceCapacities {
+    /// The number of values in the contents of each slice.
+    capacities: HashMap<ValueId, usize>,
+
+    /// The value last stored at each address, whose capacity is that of a slice loaded
+    /// from the address.
+    stored_values: HashMap<ValueId, ValueId>,
+}
+
+impl SliceCapacities {
+    /// Remembers `value` as the last value stored at `address`.
+    ///
+    /// This must be called on each store in the order they are inserted, so that each load
+    /// finds the capacity of the last slice stored at its address.
+    pub(crate) fn record_store(&mut self, address: ValueId, value: ValueId) {
+        self.stored_values.insert(address, value);
+    }
+
+    /// Records the capacity of `result` as that of the last slice stored at `address`.
+    pub(crate) fn record_load(&mut self, dfg: &DataFlowGraph, address: ValueId, result: ValueId) {
+        if !matches!(dfg.type_of_value(result), Type::Slice(_)) {
+            return;
+        }
+
+        let stored = self.stored_values.get(&address).copied();
+        if let Some(capacity) = stored.and_then(|stored| self.try_capacity(dfg, stored)) {
+            self.capacities.insert(result, capacity);
+        }
+    }
+
+    /// Returns the number of values held by the contents of `slice`.
+    pub(crate) fn capacity(&mut self, dfg: &DataFlowGraph, slice: ValueId) -> usize {
+        self.try_capacity(dfg, slice)
+            .unwrap_or_else(|| unreachable!("ICE: Could not find the capacity of slice {slice}"))
+    }
+
+    fn try_capacity(&mut self, dfg: &DataFlowGraph, slice: ValueId) -> Option<usize> {
+        let slice = dfg.resolve(slice);
+        if let Some(capacity) = self.capacities.get(&slice) {
+            return Some(*capacity);
+        }
+
+        let capacity = match &dfg[slice] {
+            Value::Array { array, .. } => array.len(),
+            Value::Instruction { instruction, .. } => match &dfg[*instruction] {
+                Instruction::ArraySet { array, .. } => self.try_capacity(dfg, *array)?,
+                Instruction::Call { func, arguments } => match &dfg[*func] {
+                    Value::Intrinsic(intrinsic) => {
+                        self.intrinsic_capacity(dfg, *intrinsic, arguments)?
+                    }
+                    _ => return None,
+                },
+                // TODO(#3188): A slice loaded from a slice of slices or a struct field
+                // needs nested slice support in flattening.
+                _ => return None,
+            },
+            _ => return None,
+        };
+
+        self.capacities.insert(slice, capacity);
+        Some(capacity)
+    }
+
+    /// Returns the capacity of the slice returned by a call to `intrinsic`.
+    ///
+    /// Each of these must match the capacity of the slice created by ACIR gen for the call.
+    fn intrinsic_capacity(
+        &mut self,
+        dfg: &DataFlowGraph,
+        intrinsic: Intrinsic,
+        arguments: &[ValueId],
+    ) -> Option<usize> {
+        match intrinsic {
+            // arguments = [slice_length, slice_contents, ...elements]
+            Intrinsic::SlicePushBack | Intrinsic::SlicePushFront => {
+                Some(self.try_capacity(dfg, arguments[1])? + arguments.len() - 2)
+            }
+            // arguments = [slice_length, slice_contents, index, ...elements]
+            Intrinsic::SliceInsert => {
+                Some(self.try_capacity(dfg, arguments[1])? + arguments.len() - 3)
+            }
+            // The popped element is only removed from the contents of the slice when it is
+            // taken from the front. Otherwise it remains past the length of the slice.
+            Intrinsic::SlicePopFront => {
+                let element_size = dfg.type_of_value(arguments[1]).element_size();
+                Some(self.try_capacity(dfg, arguments[1])?.saturating_sub(element_size))
+            }
+            Intrinsic::SlicePopBack | Intrinsic::SliceRemove => {
+                self.try_capacity(dfg, arguments[1])
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,209 @@
+//! The global value numbering (GVN) pass deduplicates [pure][Instruction::is_pure()] instructions
+//! across blocks.
+//!
+//! Constant folding only reuses the results of an identical instruction found earlier in the same
+//! block. This pass generalizes this to any earlier instruction whose block dominates the block of
+//! the duplicate instruction, as the results of a dominating instruction are guaranteed to be
+//! available on every path which reaches the duplicate.
+//!
+//! The pass works as follows:
+//! - Visit each reachable block in reverse post-order, which guarantees that a block's
+//!   dominators are visited before the block itself.
+//! - Resolve the inputs of each pure instruction and look up identical instructions seen so far.
+//!   If one of them was defined in a block dominating the current block, the current instruction
+//!   is removed and its results are replaced with those of the dominating instruction.
+//! - Otherwise the instruction is kept and recorded as available for later blocks.
+//!
+//! Instructions which create new arrays are never deduplicated since Brillig may mutate an array
+//! in-place when it holds the only reference to it.
+use crate::ssa::{
+    ir::{
+        basic_block::BasicBlockId,
+        dfg::DataFlowGraph,
+        dom::DominatorTree,
+        function::Function,
+        instruction::{Instruction, InstructionId},
+        post_order::PostOrder,
+        value::ValueId,
+    },
+    ssa_gen::Ssa,
+};
+use fxhash::FxHashMap as HashMap;
+
+impl Ssa {
+    /// Replaces pure instructions with the results of an identical instruction
+    /// in a dominating block.
+    ///
+    /// See [`global_value_numbering`][self] module for more information.
+    #[tracing::instrument(level = "trace", skip(self))]
+    pub(crate) fn global_value_numbering(mut self) -> Ssa {
+        for function in self.functions.values_mut() {
+            global_value_numbering(function);
+        }
+        self
+    }
+}
+
+fn global_value_numbering(function: &mut Function) {
+    let mut dom_tree = DominatorTree::with_function(function);
+
+    let mut reverse_post_order = PostOrder::with_function(function).into_vec();
+    reverse_post_order.reverse();
+
+    // Each pure instruction seen so far, along with all of the blocks it was found in and the
+    // respective results of the instruction in that block.
+    let mut available_instructions: HashMap<Instruction, Vec<(BasicBlockId, Vec<ValueId>)>> =
+        HashMap::default();
+
+    for block in reverse_post_order {
+        let instructions = function.dfg[block].take_instructions();
+        let mut remaining_instructions = Vec::with_capacity(instructions.len());
+
+        for instruction_id in instructions {
+            if !can_be_numbered(&function.dfg, instruction_id) {
+                remaining_instructions.push(instruction_id);
+                continue;
+            }
+
+            // Resolve any inputs to ensure that we're comparing like-for-like instructions.
+            let instruction =
+                function.dfg[instruction_id].map_values(|value| function.dfg.resolve(value));
+
+            let dominating_results = available_instructions.get(&instruction).and_then(|found| {
+                found
+                    .iter()
+                    .find(|(defining_block, _)| dom_tree.dominates(*defining_block, block))
+                    .map(|(_, results)| results.clone())
+            });
+
+            let old_results = function.dfg.instruction_results(instruction_id).to_vec();
+            match dominating_results {
+                Some(new_results) => {
+                    for (old_result, new_result) in old_results.iter().zip(new_results) {
+                        function.dfg.set_value_from_id(*old_result, new_result);
+                    }
+                }
+                None => {
+                    available_instructions
+                        .entry(instruction)
+                        .or_default()
+                        .push((block, old_results));
+                    remaining_instructions.push(instruction_id);
+                }
+            }
+        }
+
+        *function.dfg[block].instructions_mut() = remaining_instructions;
+    }
+}
+
+/// True if the instruction's results may be replaced with those of an identical instruction.
+fn can_be_numbered(dfg: &DataFlowGraph, instruction_id: InstructionId) -> bool {
+    let instruction = &dfg[instruction_id];
+    if !instruction.is_pure(dfg) {
+        return false;
+    }
+
+    match instruction {
+        Instruction::ArraySet { .. } => false,
+        Instruction::ArrayGet { .. } => true,
+        _ => dfg
+            .instruction_results(instruction_id)
+            .iter()
+            .all(|result| !dfg.type_of_value(*result).contains_an_array()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ssa::{
+        function_builder::FunctionBuilder,
+        ir::{
+            function::RuntimeType,
+            instruction::{BinaryOp, Instruction, TerminatorInstruction},
+            map::Id,
+            types::Type,
+        },
+    };
+
+    #[test]
+    fn deduplicate_across_dominated_blocks() {
+        // fn main f0 {
+        //   b0(v0: u32, v1: u32, v2: u1):
+        //     v3 = mul v0, v1
+        //     jmpif v2, then: b1, else: b2
+        //   b1():
+        //     v4 = mul v0, v1
+        //     v5 = cast v0 as Field
+        //     jmp b3(v4)
+        //   b2():
+        //     v6 = cast v0 as Field
+        //     jmp b3(v3)
+        //   b3(v7: u32):
+        //     v8 = mul v0, v1
+        //     return v7, v8
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Brillig);
+
+        let b1 = builder.insert_block();
+        let b2 = builder.insert_block();
+        let b3 = builder.insert_block();
+
+        let v0 = builder.add_parameter(Type::unsigned(32));
+        let v1 = builder.add_parameter(Type::unsigned(32));
+        let v2 = builder.add_parameter(Type::bool());
+        let v7 = builder.add_block_parameter(b3, Type::unsigned(32));
+
+        let v3 = builder.insert_binary(v0, BinaryOp::Mul, v1);
+        builder.terminate_with_jmpif(v2, b1, b2);
+
+        builder.switch_to_block(b1);
+        let v4 = builder.insert_binary(v0, BinaryOp::Mul, v1);
+        builder.insert_cast(v0, Type::field());
+        builder.terminate_with_jmp(b3, vec![v4]);
+
+        builder.switch_to_block(b2);
+        builder.insert_cast(v0, Type::field());
+        builder.terminate_with_jmp(b3, vec![v3]);
+
+        builder.switch_to_block(b3);
+        let v8 = builder.insert_binary(v0, BinaryOp::Mul, v1);
+        builder.terminate_with_return(vec![v7, v8]);
+
+        // Expected output:
+        //
+        // fn main f0 {
+        //   b0(v0: u32, v1: u32, v2: u1):
+        //     v3 = mul v0, v1
+        //     jmpif v2, then: b1, else: b2
+        //   b1():
+        //     v5 = cast v0 as Field
+        //     jmp b3(v3)
+        //   b2():
+        //     v6 = cast v0 as Field
+        //     jmp b3(v3)
+        //   b3(v7: u32):
+        //     return v7, v3
+        // }
+        let ssa = builder.finish().global_value_numbering();
+        let main = ssa.main();
+
+        assert_eq!(main.dfg[main.entry_block()].instructions().len(), 1);
+        assert_eq!(main.dfg[b3].instructions().len(), 0);
+
+        // Neither b1 nor b2 dominates the other so both casts are kept.
+        let b1_instructions = main.dfg[b1].instructions();
+        assert_eq!(b1_instructions.len(), 1);
+        assert_eq!(main.dfg[b1_instructions[0]], Instruction::Cast(v0, Type::field()));
+        assert_eq!(main.dfg[b2].instructions().len(), 1);
+
+        assert_eq!(main.dfg.resolve(v4), v3);
+        match main.dfg[b3].unwrap_terminator() {
+            TerminatorInstruction::Return { return_values, .. } => {
+                assert_eq!(main.dfg.resolve(return_values[1]), v3);
+            }
+            _ => unreachable!("b3 should have a return terminator"),
+        }
+    }
+}
@@ -2,6 +2,12 @@
 //! The purpose of this pass is to inline the instructions of each function call
 //! within the function caller. If all function calls are known, there will only
 //! be a single function remaining when the pass finishes.
+//!
+//! Calls between Brillig functions are kept, unless the callee is marked `#[inline(always)]` or
+//! is small enough once the calls it makes are inlined in turn. Constrained functions are inlined
+//! unless the backend supports call opcodes and they are either marked `#[inline(never)]` or large
+//! and called from several places, in which case they are compiled to a separate ACIR function
+//! called from the circuits of their callers.
 use std::collections::{BTreeSet, HashSet};
 
 use iter_extended::{btree_map, vecmap};
@@ -13,16 +19,31 @@ use crate::ssa::{
         dfg::{CallStack, InsertInstructionResult},
         function::{Function, FunctionId, RuntimeType},
         instruction::{Instruction, InstructionId, TerminatorInstruction},
+        types::Type,
         value::{Value, ValueId},
     },
     ssa_gen::Ssa,
 };
 use fxhash::FxHashMap as HashMap;
+use noirc_frontend::token::InlineType;
 
 /// An arbitrary limit to the maximum number of recursive call
 /// frames at any point in time.
 const RECURSION_LIMIT: u32 = 1000;
 
+/// The maximum number of instructions a Brillig function may contain, once the calls it makes
+/// are inlined, to be inlined into its Brillig callers by
+/// [`Ssa::inline_functions_and_small_brillig_calls`].
+const MAX_INLINED_BRILLIG_INSTRUCTIONS: usize = 32;
+
+/// The maximum number of instructions a constrained function without an `#[inline(...)]`
+/// attribute may contain, once the calls it makes are inlined, to be inlined into each of its
+/// callers when the backend supports call opcodes. Larger functions called from several places
+/// are compiled to a separate ACIR function instead, rather than being copied into every caller.
+///
+/// Loops are only unrolled after inlining, so their bodies are only counted once.
+const MAX_INLINED_ACIR_INSTRUCTIONS: usize = 500;
+
 impl Ssa {
     /// Inline all functions within the IR.
     ///
@@ -36,10 +57,35 @@ impl Ssa {
     /// changes. This is because if the function's id later becomes known by a later
     /// pass, we would need to re-run all of inlining anyway to inline it, so we might
     /// as well save the work for later instead of performing it twice.
+    ///
+    /// Calls to Brillig functions are only inlined into Brillig callers if the callee is marked
+    /// `#[inline(always)]`. If `acir_calls` is set, calls to constrained functions marked
+    /// `#[inline(never)]` or too large to be copied into each of their callers are kept as well,
+    /// see [`InlineCosts::is_separate_acir_function`].
     #[tracing::instrument(level = "trace", skip(self))]
-    pub(crate) fn inline_functions(mut self) -> Ssa {
-        self.functions = btree_map(get_entry_point_functions(&self), |entry_point| {
-            let new_function = InlineContext::new(&self, entry_point).inline_all(&self);
+    pub(crate) fn inline_functions(self, acir_calls: bool) -> Ssa {
+        self.inline_functions_with(false, acir_calls)
+    }
+
+    /// Inline all functions within the IR as [`Ssa::inline_functions`] does, additionally
+    /// inlining calls from one Brillig function to another if the callee is small and is
+    /// not already being inlined further up the call chain. Callees marked `#[inline(never)]`
+    /// are never inlined.
+    ///
+    /// The callee is still kept as an entry point as it may also be called from ACIR.
+    #[tracing::instrument(level = "trace", skip(self))]
+    pub(crate) fn inline_functions_and_small_brillig_calls(self, acir_calls: bool) -> Ssa {
+        self.inline_functions_with(true, acir_calls)
+    }
+
+    fn inline_functions_with(mut self, inline_small_brillig_calls: bool, acir_calls: bool) -> Ssa {
+        let inlined_functions =
+            InlineCosts::new(&self, inline_small_brillig_calls, acir_calls).inlined_functions();
+
+        let entry_points = get_entry_point_functions(&self, &inlined_functions);
+        self.functions = btree_map(entry_points, |entry_point| {
+            let context = InlineContext::new(&self, entry_point, inlined_functions.clone());
+            let new_function = context.inline_all(&self);
             (entry_point, new_function)
         });
 
@@ -60,6 +106,13 @@ struct InlineContext {
 
     // The FunctionId of the entry point function we're inlining into in the old, unmodified Ssa.
     entry_point: FunctionId,
+
+    /// The Brillig functions which are inlined into their Brillig callers, and the constrained
+    /// functions which are inlined rather than compiled to separate ACIR functions.
+    inlined_functions: HashSet<FunctionId>,
+
+    /// The functions currently being inlined, from the outermost to the innermost call.
+    functions_being_inlined: Vec<FunctionId>,
 }
 
 /// The per-function inlining context contains information that is only valid for one function.
@@ -95,11 +148,16 @@ struct PerFunctionContext<'function> {
 
 /// The entry point functions are each function we should inline into - and each function that
 /// should be left in the final program. This is usually just `main` but also includes any
-/// brillig functions used.
-fn get_entry_point_functions(ssa: &Ssa) -> BTreeSet<FunctionId> {
+/// brillig functions used and any constrained functions which are not inlined.
+fn get_entry_point_functions(
+    ssa: &Ssa,
+    inlined_functions: &HashSet<FunctionId>,
+) -> BTreeSet<FunctionId> {
     let functions = ssa.functions.iter();
     let mut entry_points = functions
-        .filter(|(_, function)| function.runtime() == RuntimeType::Brillig)
+        .filter(|(id, function)| {
+            function.runtime() == RuntimeType::Brillig || !inlined_functions.contains(id)
+        })
         .map(|(id, _)| *id)
         .collect::<BTreeSet<_>>();
 
@@ -107,16 +165,36 @@ fn get_entry_point_functions(ssa: &Ssa) -> BTreeSet<FunctionId> {
     entry_points
 }
 
+/// Returns true if values of type `typ` are made of a fixed number of witnesses.
+fn is_witness_type(typ: &Type) -> bool {
+    match typ {
+        Type::Numeric(_) => true,
+        Type::Array(elements, _) => elements.iter().all(is_witness_type),
+        Type::Slice(_) | Type::Reference(_) | Type::Function => false,
+    }
+}
+
 impl InlineContext {
     /// Create a new context object for the function inlining pass.
     /// This starts off with an empty mapping of instructions for main's parameters.
     /// The function being inlined into will always be the main function, although it is
     /// actually a copy that is created in case the original main is still needed from a function
     /// that could not be inlined calling it.
-    fn new(ssa: &Ssa, entry_point: FunctionId) -> InlineContext {
+    fn new(
+        ssa: &Ssa,
+        entry_point: FunctionId,
+        inlined_functions: HashSet<FunctionId>,
+    ) -> InlineContext {
         let source = &ssa.functions[&entry_point];
         let builder = FunctionBuilder::new(source.name().to_owned(), entry_point, source.runtime());
-        Self { builder, recursion_level: 0, entry_point, call_stack: CallStack::new() }
+        Self {
+            builder,
+            recursion_level: 0,
+            entry_point,
+            call_stack: CallStack::new(),
+            inlined_functions,
+            functions_being_inlined: Vec::new(),
+        }
     }
 
     /// Start inlining the entry point function and all functions reachable from it.
@@ -177,10 +255,160 @@ impl InlineContext {
         let current_block = context.context.builder.current_block();
         context.blocks.insert(source_function.entry_block(), current_block);
 
+        self.functions_being_inlined.push(id);
         let return_values = context.inline_blocks(ssa);
+        self.functions_being_inlined.pop();
         self.recursion_level -= 1;
         return_values
     }
+
+    /// Calls to Brillig functions are only inlined into Brillig entry points, and never into
+    /// themselves to avoid endlessly inlining recursive functions.
+    fn should_inline_brillig_call(&self, function: FunctionId) -> bool {
+        self.builder.current_function.runtime() == RuntimeType::Brillig
+            && function != self.entry_point
+            && !self.functions_being_inlined.contains(&function)
+            && self.inlined_functions.contains(&function)
+    }
+}
+
+/// Decides which functions are inlined into their callers of the same runtime, from their
+/// `#[inline(...)]` attribute or otherwise from their number of instructions once the calls
+/// they make are inlined in turn.
+struct InlineCosts<'ssa> {
+    ssa: &'ssa Ssa,
+
+    /// True if Brillig functions without an attribute are inlined when they are small enough
+    inline_small_brillig_calls: bool,
+
+    /// True if constrained functions may be compiled to separate ACIR functions
+    acir_calls: bool,
+
+    /// The number of places each function is called from
+    call_counts: HashMap<FunctionId, usize>,
+
+    /// The number of instructions of each function once its calls are inlined
+    costs: HashMap<FunctionId, usize>,
+
+    /// The functions whose cost is being computed, from the outermost to the innermost call
+    functions_being_costed: Vec<FunctionId>,
+
+    inlined: HashSet<FunctionId>,
+}
+
+impl<'ssa> InlineCosts<'ssa> {
+    fn new(ssa: &'ssa Ssa, inline_small_brillig_calls: bool, acir_calls: bool) -> Self {
+        let mut call_counts: HashMap<FunctionId, usize> = HashMap::default();
+        for function in ssa.functions.values() {
+            for block in function.reachable_blocks() {
+                for instruction in function.dfg[block].instructions() {
+                    if let Instruction::Call { func, .. } = &function.dfg[*instruction] {
+                        if let Value::Function(callee) = function.dfg[*func] {
+                            *call_counts.entry(callee).or_default() += 1;
+                        }
+                    }
+                }
+            }
+        }
+
+        Self {
+            ssa,
+            inline_small_brillig_calls,
+            acir_calls,
+            call_counts,
+            costs: HashMap::default(),
+            functions_being_costed: Vec::new(),
+            inlined: HashSet::new(),
+        }
+    }
+
+    fn inlined_functions(mut self) -> HashSet<FunctionId> {
+        for id in self.ssa.functions.keys() {
+            self.cost(*id);
+        }
+        self.inlined
+    }
+
+    /// Returns the number of instructions of the function `id` once the calls it makes to
+    /// inlined functions are replaced by their instructions, deciding whether it is inlined
+    /// itself.
+    fn cost(&mut self, id: FunctionId) -> usize {
+        if let Some(cost) = self.costs.get(&id) {
+            return *cost;
+        }
+        // Recursive calls are kept after the first call has been inlined.
+        if self.functions_being_costed.contains(&id) {
+            return 1;
+        }
+
+        self.functions_being_costed.push(id);
+        let ssa = self.ssa;
+        let function = &ssa.functions[&id];
+        let mut cost = 0;
+        for block in function.reachable_blocks() {
+            for instruction in function.dfg[block].instructions() {
+                cost += match &function.dfg[*instruction] {
+                    Instruction::Call { func, .. } => match function.dfg[*func] {
+                        Value::Function(callee)
+                            if ssa.functions[&callee].runtime() == function.runtime() =>
+                        {
+                            let callee_cost = self.cost(callee);
+                            if self.inlined.contains(&callee) {
+                                callee_cost
+                            } else {
+                                1
+                            }
+                        }
+                        _ => 1,
+                    },
+                    _ => 1,
+                };
+            }
+        }
+        self.functions_being_costed.pop();
+
+        let inlined = match function.runtime() {
+            RuntimeType::Acir => !self.is_separate_acir_function(id, cost),
+            RuntimeType::Brillig => match function.inline_type() {
+                InlineType::Always => true,
+                InlineType::Never => false,
+                InlineType::Default => {
+                    self.inline_small_brillig_calls && cost <= MAX_INLINED_BRILLIG_INSTRUCTIONS
+                }
+            },
+        };
+        if inlined {
+            self.inlined.insert(id);
+        }
+        self.costs.insert(id, cost);
+        cost
+    }
+
+    /// Returns true if the constrained function `id`, made of `cost` instructions once its calls
+    /// are inlined, is compiled to a separate ACIR function rather than being inlined into its
+    /// callers. This requires the backend to support call opcodes.
+    ///
+    /// Functions marked `#[inline(never)]` are kept, as are functions without an attribute which
+    /// are larger than [`MAX_INLINED_ACIR_INSTRUCTIONS`] and called from several places, if their
+    /// parameters and return values can be passed as witnesses. Slices and references are only
+    /// known to ACIR gen within a function, so functions taking or returning them are inlined
+    /// regardless.
+    fn is_separate_acir_function(&self, id: FunctionId, cost: usize) -> bool {
+        if !self.acir_calls || id == self.ssa.main_id {
+            return false;
+        }
+        let function = &self.ssa.functions[&id];
+        let separate = match function.inline_type() {
+            InlineType::Always => false,
+            InlineType::Never => true,
+            InlineType::Default => {
+                cost > MAX_INLINED_ACIR_INSTRUCTIONS
+                    && self.call_counts.get(&id).copied().unwrap_or_default() > 1
+            }
+        };
+        let signature = function.signature();
+        separate && signature.params.iter().chain(&signature.returns).all(is_witness_type)
+    }
 }
 
 impl<'function> PerFunctionContext<'function> {
@@ -350,7 +578,17 @@ impl<'function> PerFunctionContext<'function> {
             match &self.source_function.dfg[*id] {
                 Instruction::Call { func, arguments } => match self.get_function(*func) {
                     Some(function) => match ssa.functions[&function].runtime() {
+                        RuntimeType::Acir
+                            if !self.context.inlined_functions.contains(&function) =>
+                        {
+                            self.push_instruction(*id);
+                        }
                         RuntimeType::Acir => self.inline_function(ssa, *id, function, arguments),
+                        RuntimeType::Brillig
+                            if self.context.should_inline_brillig_call(function) =>
+                        {
+                            self.inline_function(ssa, *id, function, arguments);
+                        }
                         RuntimeType::Brillig => self.push_instruction(*id),
                     },
                     None => self.push_instruction(*id),
@@ -509,18 +747,23 @@ impl<'function> PerFunctionContext<'function> {
 #[cfg(test)]
 mod test {
     use acvm::FieldElement;
+    use noirc_frontend::token::InlineType;
 
     use crate::ssa::{
         function_builder::FunctionBuilder,
         ir::{
             basic_block::BasicBlockId,
             function::RuntimeType,
-            instruction::{BinaryOp, Intrinsic, TerminatorInstruction},
+            instruction::{BinaryOp, Instruction, Intrinsic, TerminatorInstruction},
             map::Id,
             types::Type,
+            value::Value,
         },
+        ssa_gen::Ssa,
     };
 
+    use super::MAX_INLINED_ACIR_INSTRUCTIONS;
+
     #[test]
     fn basic_inlining() {
         // fn foo {
@@ -548,7 +791,7 @@ mod test {
         let ssa = builder.finish();
         assert_eq!(ssa.functions.len(), 2);
 
-        let inlined = ssa.inline_functions();
+        let inlined = ssa.inline_functions(false);
         assert_eq!(inlined.functions.len(), 1);
     }
 
@@ -614,7 +857,7 @@ mod test {
         let ssa = builder.finish();
         assert_eq!(ssa.functions.len(), 4);
 
-        let inlined = ssa.inline_functions();
+        let inlined = ssa.inline_functions(false);
         assert_eq!(inlined.functions.len(), 1);
     }
 
@@ -688,7 +931,7 @@ mod test {
         //   b6():
         //     return Field 120
         // }
-        let inlined = ssa.inline_functions();
+        let inlined = ssa.inline_functions(false);
         assert_eq!(inlined.functions.len(), 1);
 
         let main = inlined.main();
@@ -771,7 +1014,7 @@ mod test {
         builder.switch_to_block(join_block);
         builder.terminate_with_return(vec![join_param]);
 
-        let ssa = builder.finish().inline_functions();
+        let ssa = builder.finish().inline_functions(false);
         // Expected result:
         // fn main f3 {
         //   b0(v0: u1):
@@ -787,4 +1030,228 @@ mod test {
         let main = ssa.main();
         assert_eq!(main.reachable_blocks().len(), 4);
     }
+
+    #[test]
+    fn inline_small_brillig_calls() {
+        // brillig fn main f0 {
+        //   b0():
+        //     v0 = call f1()
+        //     return v0
+        // }
+        // brillig fn bar f1 {
+        //   b0():
+        //     v0 = call f1()
+        //     return Field 72
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Brillig);
+
+        let bar_id = Id::test_new(1);
+        let bar = builder.import_function(bar_id);
+        let results = builder.insert_call(bar, Vec::new(), vec![Type::field()]).to_vec();
+        builder.terminate_with_return(results);
+
+        // bar is recursive so the inner call must be kept after it has been inlined once
+        builder.new_brillig_function("bar".into(), bar_id);
+        let bar = builder.import_function(bar_id);
+        builder.insert_call(bar, Vec::new(), vec![Type::field()]);
+        let seventy_two = builder.field_constant(72u128);
+        builder.terminate_with_return(vec![seventy_two]);
+
+        let ssa = builder.finish();
+        let count_calls = |ssa: &Ssa| {
+            let main = ssa.main();
+            main.reachable_blocks()
+                .iter()
+                .flat_map(|block| main.dfg[*block].instructions())
+                .filter(|instruction| matches!(main.dfg[**instruction], Instruction::Call { .. }))
+                .count()
+        };
+
+        // Both functions are Brillig entry points so the call is kept by default.
+        let inlined = ssa.inline_functions(false);
+        assert_eq!(inlined.functions.len(), 2);
+        assert_eq!(count_calls(&inlined), 1);
+
+        // Otherwise bar is inlined into main once, leaving only bar's recursive call to itself.
+        let inlined = inlined.inline_functions_and_small_brillig_calls(false);
+        assert_eq!(inlined.functions.len(), 2);
+        assert_eq!(count_calls(&inlined), 1);
+
+        let main = inlined.main();
+        match main.dfg[main.entry_block()].unwrap_terminator() {
+            TerminatorInstruction::Return { return_values, .. } => {
+                let value = main.dfg.get_numeric_constant(return_values[0]);
+                assert_eq!(value, Some(FieldElement::from(72u128)));
+            }
+            other => unreachable!("Unexpected terminator {other:?}"),
+        }
+    }
+
+    #[test]
+    fn inline_attributes() {
+        // brillig fn main f0 {
+        //   b0(v0: Field):
+        //     v1 = call f1(v0)
+        //     v2 = call f2(v1)
+        //     return v2
+        // }
+        // #[inline(never)]
+        // brillig fn small f1 {
+        //   b0(v0: Field):
+        //     v1 = add v0, Field 1
+        //     return v1
+        // }
+        // #[inline(always)]
+        // brillig fn large f2 {
+        //   b0(v0: Field):
+        //     v1 = mul v0, v0
+        //     ... 40 multiplications in total
+        //     return v40
+        // }
+        let build = || {
+            let main_id = Id::test_new(0);
+            let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Brillig);
+            let v0 = builder.add_parameter(Type::field());
+            let small = builder.import_function(Id::test_new(1));
+            let large = builder.import_function(Id::test_new(2));
+            let v1 = builder.insert_call(small, vec![v0], vec![Type::field()])[0];
+            let v2 = builder.insert_call(large, vec![v1], vec![Type::field()]).to_vec();
+            builder.terminate_with_return(v2);
+
+            builder.new_brillig_function("small".into(), Id::test_new(1));
+            builder.current_function.set_inline_type(InlineType::Never);
+            let v0 = builder.add_parameter(Type::field());
+            let one = builder.field_constant(1u128);
+            let v1 = builder.insert_binary(v0, BinaryOp::Add, one);
+            builder.terminate_with_return(vec![v1]);
+
+            builder.new_brillig_function("large".into(), Id::test_new(2));
+            builder.current_function.set_inline_type(InlineType::Always);
+            let mut value = builder.add_parameter(Type::field());
+            for _ in 0..40 {
+                value = builder.insert_binary(value, BinaryOp::Mul, value);
+            }
+            builder.terminate_with_return(vec![value]);
+            builder.finish()
+        };
+
+        let called_functions = |ssa: &Ssa| {
+            let main = ssa.main();
+            let mut called = Vec::new();
+            for instruction in main.dfg[main.entry_block()].instructions() {
+                if let Instruction::Call { func, .. } = &main.dfg[*instruction] {
+                    called.push(main.dfg[*func].clone());
+                }
+            }
+            called
+        };
+
+        for inlined in [
+            build().inline_functions(false),
+            build().inline_functions_and_small_brillig_calls(false),
+        ] {
+            let called = called_functions(&inlined);
+            assert!(matches!(called[..], [Value::Function(id)] if id == Id::test_new(1)));
+        }
+    }
+
+    #[test]
+    fn keeps_separate_acir_functions() {
+        // acir fn main f0 {
+        //   b0(v0: Field):
+        //     v1 = call f1(v0)
+        //     v2 = call f2(v1)
+        //     return v2
+        // }
+        // #[inline(never)]
+        // acir fn separate f1 {
+        //   b0(v0: Field):
+        //     v1 = add v0, Field 1
+        //     return v1
+        // }
+        // acir fn inlined f2 {
+        //   b0(v0: Field):
+        //     v1 = mul v0, v0
+        //     return v1
+        // }
+        let build = || {
+            let main_id = Id::test_new(0);
+            let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+            let v0 = builder.add_parameter(Type::field());
+            let separate = builder.import_function(Id::test_new(1));
+            let inlined = builder.import_function(Id::test_new(2));
+            let v1 = builder.insert_call(separate, vec![v0], vec![Type::field()])[0];
+            let v2 = builder.insert_call(inlined, vec![v1], vec![Type::field()]).to_vec();
+            builder.terminate_with_return(v2);
+
+            builder.new_function("separate".into(), Id::test_new(1));
+            builder.current_function.set_inline_type(InlineType::Never);
+            let v0 = builder.add_parameter(Type::field());
+            let one = builder.field_constant(1u128);
+            let v1 = builder.insert_binary(v0, BinaryOp::Add, one);
+            builder.terminate_with_return(vec![v1]);
+
+            builder.new_function("inlined".into(), Id::test_new(2));
+            let v0 = builder.add_parameter(Type::field());
+            let v1 = builder.insert_binary(v0, BinaryOp::Mul, v0);
+            builder.terminate_with_return(vec![v1]);
+            builder.finish()
+        };
+
+        let inlined = build().inline_functions(true);
+        assert_eq!(inlined.functions.len(), 2);
+
+        let main = inlined.main();
+        let instructions = main.dfg[main.entry_block()].instructions();
+        assert_eq!(instructions.len(), 2);
+        assert!(matches!(
+            &main.dfg[instructions[0]],
+            Instruction::Call { func, .. } if main.dfg[*func] == Value::Function(Id::test_new(1))
+        ));
+
+        // Without call opcodes every constrained function is inlined.
+        let inlined = build().inline_functions(false);
+        assert_eq!(inlined.functions.len(), 1);
+    }
+
+    #[test]
+    fn keeps_large_acir_functions_called_several_times() {
+        // acir fn main f0 {
+        //   b0(v0: Field):
+        //     v1 = call f1(v0)
+        //     ... `calls` calls in total
+        //     return v1
+        // }
+        // acir fn large f1 {
+        //   b0(v0: Field):
+        //     v1 = mul v0, v0
+        //     ... MAX_INLINED_ACIR_INSTRUCTIONS + 1 multiplications in total
+        //     return v501
+        // }
+        let build = |calls: usize| {
+            let main_id = Id::test_new(0);
+            let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+            let mut value = builder.add_parameter(Type::field());
+            let large = builder.import_function(Id::test_new(1));
+            for _ in 0..calls {
+                value = builder.insert_call(large, vec![value], vec![Type::field()])[0];
+            }
+            builder.terminate_with_return(vec![value]);
+
+            builder.new_function("large".into(), Id::test_new(1));
+            let mut value = builder.add_parameter(Type::field());
+            for _ in 0..=MAX_INLINED_ACIR_INSTRUCTIONS {
+                value = builder.insert_binary(value, BinaryOp::Mul, value);
+            }
+            builder.terminate_with_return(vec![value]);
+            builder.finish()
+        };
+
+        assert_eq!(build(2).inline_functions(true).functions.len(), 2);
+        // Inlining a function called once doesn't copy it, and without call opcodes every
+        // constrained function is inlined.
+        assert_eq!(build(1).inline_functions(true).functions.len(), 1);
+        assert_eq!(build(2).inline_functions(false).functions.len(), 1);
+    }
 }
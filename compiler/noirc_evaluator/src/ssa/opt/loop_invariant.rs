@@ -0,0 +1,336 @@
+//! The loop invariant code motion pass moves instructions which do not depend on any value
+//! defined inside of a loop out of that loop and into its pre-header block.
+//!
+//! The pass works as follows:
+//! - Find all loops in the function (see [`find_all_loops`][super::unrolling::find_all_loops]).
+//!   Loops are visited from innermost to outermost so that instructions hoisted out of an inner
+//!   loop can then be hoisted further out of any enclosing loop.
+//! - For each loop, find its pre-header: the single block outside of the loop which jumps to the
+//!   loop header. Loops without a unique pre-header are skipped.
+//! - Walk the loop's blocks in reverse post-order. Any instruction which is safe to execute
+//!   speculatively and whose inputs are all defined outside of the loop is moved to the end
+//!   of the pre-header.
+//!
+//! Only instructions which cannot fail or cause side-effects are hoisted since the loop body
+//! may never be executed. This excludes e.g. array accesses (which may be out of bounds) as well
+//! as divisions by non-constant values.
+//!
+//! This is most beneficial for Brillig functions, where loops are not unrolled. For ACIR functions
+//! this reduces the number of instructions which are copied during loop unrolling.
+use std::collections::HashSet;
+
+use crate::ssa::{
+    ir::{
+        basic_block::BasicBlockId,
+        cfg::ControlFlowGraph,
+        dfg::DataFlowGraph,
+        function::Function,
+        instruction::{Instruction, InstructionId},
+        post_order::PostOrder,
+        value::{Value, ValueId},
+    },
+    ssa_gen::Ssa,
+};
+
+use super::unrolling::{find_all_loops, Loop};
+
+impl Ssa {
+    /// Hoists loop invariant instructions out of each loop and into the loop's pre-header.
+    ///
+    /// See [`loop_invariant`][self] module for more information.
+    #[tracing::instrument(level = "trace", skip(self))]
+    pub(crate) fn loop_invariant_code_motion(mut self) -> Ssa {
+        for function in self.functions.values_mut() {
+            hoist_loop_invariants(function);
+        }
+        self
+    }
+}
+
+fn hoist_loop_invariants(function: &mut Function) {
+    let loops = find_all_loops(function);
+    if loops.yet_to_unroll.is_empty() {
+        return;
+    }
+
+    // Iterating the blocks in reverse post-order ensures that the instructions within a loop
+    // are visited after the instructions defining their inputs.
+    let mut reverse_post_order = PostOrder::with_function(function).into_vec();
+    reverse_post_order.reverse();
+
+    // Loops are sorted by their number of blocks, so inner loops are visited first.
+    for loop_ in &loops.yet_to_unroll {
+        if let Some(pre_header) = get_pre_header(&loops.cfg, loop_) {
+            hoist_loop_invariants_in_loop(function, loop_, pre_header, &reverse_post_order);
+        }
+    }
+}
+
+/// Returns the single block outside of the loop which jumps into the loop header, if it exists.
+fn get_pre_header(cfg: &ControlFlowGraph, loop_: &Loop) -> Option<BasicBlockId> {
+    let mut predecessors =
+        cfg.predecessors(loop_.header).filter(|predecessor| !loop_.blocks.contains(predecessor));
+
+    let pre_header = predecessors.next()?;
+    predecessors.next().is_none().then_some(pre_header)
+}
+
+fn hoist_loop_invariants_in_loop(
+    function: &mut Function,
+    loop_: &Loop,
+    pre_header: BasicBlockId,
+    reverse_post_order: &[BasicBlockId],
+) {
+    let mut defined_in_loop = HashSet::new();
+    for block in &loop_.blocks {
+        let block = &function.dfg[*block];
+        defined_in_loop.extend(block.parameters().iter().copied());
+        for instruction in block.instructions() {
+            defined_in_loop.extend(function.dfg.instruction_results(*instruction).iter().copied());
+        }
+    }
+
+    let mut hoisted_instructions = Vec::new();
+
+    for block in reverse_post_order.iter().filter(|block| loop_.blocks.contains(block)) {
+        let instructions = function.dfg[*block].take_instructions();
+        let mut remaining_instructions = Vec::with_capacity(instructions.len());
+
+        for instruction_id in instructions {
+            if is_loop_invariant(&function.dfg, instruction_id, &defined_in_loop) {
+                for result in function.dfg.instruction_results(instruction_id) {
+                    defined_in_loop.remove(result);
+                }
+                hoisted_instructions.push(instruction_id);
+            } else {
+                remaining_instructions.push(instruction_id);
+            }
+        }
+
+        *function.dfg[*block].instructions_mut() = remaining_instructions;
+    }
+
+    function.dfg[pre_header].instructions_mut().append(&mut hoisted_instructions);
+}
+
+/// Returns true if the given instruction may be moved out of its loop.
+fn is_loop_invariant(
+    dfg: &DataFlowGraph,
+    instruction_id: InstructionId,
+    defined_in_loop: &HashSet<ValueId>,
+) -> bool {
+    let instruction = &dfg[instruction_id];
+    if !can_be_hoisted(instruction, dfg) {
+        return false;
+    }
+
+    let mut is_invariant = true;
+    instruction.for_each_value(|value| {
+        is_invariant &= !is_defined_in_loop(dfg, value, defined_in_loop);
+    });
+    is_invariant
+}
+
+/// Instructions which may be executed speculatively, i.e. they will produce the same result
+/// wherever they are executed and can never fail.
+fn can_be_hoisted(instruction: &Instruction, dfg: &DataFlowGraph) -> bool {
+    use Instruction::*;
+
+    match instruction {
+        Binary(_) | Cast(..) | Not(_) | Truncate { .. } => {
+            instruction.is_pure(dfg) && !instruction.has_side_effects(dfg)
+        }
+        _ => false,
+    }
+}
+
+/// Checks whether the given value or, in the case of an array literal, any of its elements
+/// are defined within the loop.
+fn is_defined_in_loop(
+    dfg: &DataFlowGraph,
+    value: ValueId,
+    defined_in_loop: &HashSet<ValueId>,
+) -> bool {
+    let value = dfg.resolve(value);
+    match &dfg[value] {
+        Value::Array { array, .. } => {
+            array.iter().any(|element| is_defined_in_loop(dfg, *element, defined_in_loop))
+        }
+        _ => defined_in_loop.contains(&value),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ssa::{
+        function_builder::FunctionBuilder,
+        ir::{
+            function::RuntimeType,
+            instruction::{BinaryOp, Instruction},
+            map::Id,
+            types::Type,
+        },
+    };
+
+    #[test]
+    fn hoist_invariant_with_nested_loops() {
+        // fn main f0 {
+        //   b0(v0: u32, v1: u32):
+        //     jmp b1(u32 0)
+        //   b1(v2: u32):
+        //     v3 = lt v2, u32 4
+        //     jmpif v3, then: b2, else: b3
+        //   b2():
+        //     jmp b4(u32 0)
+        //   b4(v4: u32):
+        //     v5 = lt v4, u32 4
+        //     jmpif v5, then: b5, else: b6
+        //   b5():
+        //     v6 = mul v0, v1
+        //     v7 = add v6, v2
+        //     constrain v7 == u32 12
+        //     v8 = add v4, u32 1
+        //     jmp b4(v8)
+        //   b6():
+        //     v9 = add v2, u32 1
+        //     jmp b1(v9)
+        //   b3():
+        //     return
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Brillig);
+
+        let b1 = builder.insert_block();
+        let b2 = builder.insert_block();
+        let b3 = builder.insert_block();
+        let b4 = builder.insert_block();
+        let b5 = builder.insert_block();
+        let b6 = builder.insert_block();
+
+        let v0 = builder.add_parameter(Type::unsigned(32));
+        let v1 = builder.add_parameter(Type::unsigned(32));
+        let v2 = builder.add_block_parameter(b1, Type::unsigned(32));
+        let v4 = builder.add_block_parameter(b4, Type::unsigned(32));
+
+        let zero = builder.numeric_constant(0u128, Type::unsigned(32));
+        let one = builder.numeric_constant(1u128, Type::unsigned(32));
+        let four = builder.numeric_constant(4u128, Type::unsigned(32));
+        let twelve = builder.numeric_constant(12u128, Type::unsigned(32));
+
+        builder.terminate_with_jmp(b1, vec![zero]);
+
+        builder.switch_to_block(b1);
+        let v3 = builder.insert_binary(v2, BinaryOp::Lt, four);
+        builder.terminate_with_jmpif(v3, b2, b3);
+
+        builder.switch_to_block(b2);
+        builder.terminate_with_jmp(b4, vec![zero]);
+
+        builder.switch_to_block(b4);
+        let v5 = builder.insert_binary(v4, BinaryOp::Lt, four);
+        builder.terminate_with_jmpif(v5, b5, b6);
+
+        builder.switch_to_block(b5);
+        let v6 = builder.insert_binary(v0, BinaryOp::Mul, v1);
+        let v7 = builder.insert_binary(v6, BinaryOp::Add, v2);
+        builder.insert_constrain(v7, twelve, None);
+        let v8 = builder.insert_binary(v4, BinaryOp::Add, one);
+        builder.terminate_with_jmp(b4, vec![v8]);
+
+        builder.switch_to_block(b6);
+        let v9 = builder.insert_binary(v2, BinaryOp::Add, one);
+        builder.terminate_with_jmp(b1, vec![v9]);
+
+        builder.switch_to_block(b3);
+        builder.terminate_with_return(vec![]);
+
+        let ssa = builder.finish();
+
+        // Expected output:
+        //
+        // fn main f0 {
+        //   b0(v0: u32, v1: u32):
+        //     v6 = mul v0, v1
+        //     jmp b1(u32 0)
+        //   b1(v2: u32):
+        //     v3 = lt v2, u32 4
+        //     jmpif v3, then: b2, else: b3
+        //   b2():
+        //     v7 = add v6, v2
+        //     jmp b4(u32 0)
+        //   b4(v4: u32):
+        //     v5 = lt v4, u32 4
+        //     jmpif v5, then: b5, else: b6
+        //   b5():
+        //     constrain v7 == u32 12
+        //     v8 = add v4, u32 1
+        //     jmp b4(v8)
+        //   ...
+        // }
+        let ssa = ssa.loop_invariant_code_motion();
+        let main = ssa.main();
+
+        let entry_instructions = main.dfg[main.entry_block()].instructions();
+        assert_eq!(entry_instructions.len(), 1);
+        assert_eq!(main.dfg[entry_instructions[0]], Instruction::binary(BinaryOp::Mul, v0, v1));
+
+        let inner_pre_header = main.dfg[b2].instructions();
+        assert_eq!(inner_pre_header.len(), 1);
+        assert_eq!(main.dfg[inner_pre_header[0]], Instruction::binary(BinaryOp::Add, v6, v2));
+
+        // The constrain and the increment of the induction variable must stay within the loop.
+        assert_eq!(main.dfg[b5].instructions().len(), 2);
+        assert_eq!(main.dfg[b6].instructions().len(), 1);
+    }
+
+    #[test]
+    fn does_not_hoist_division_by_non_constant() {
+        // fn main f0 {
+        //   b0(v0: u32, v1: u32):
+        //     jmp b1(u32 0)
+        //   b1(v2: u32):
+        //     v3 = lt v2, u32 4
+        //     jmpif v3, then: b2, else: b3
+        //   b2():
+        //     v4 = div v0, v1
+        //     v5 = add v2, v4
+        //     jmp b1(v5)
+        //   b3():
+        //     return
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Brillig);
+
+        let b1 = builder.insert_block();
+        let b2 = builder.insert_block();
+        let b3 = builder.insert_block();
+
+        let v0 = builder.add_parameter(Type::unsigned(32));
+        let v1 = builder.add_parameter(Type::unsigned(32));
+        let v2 = builder.add_block_parameter(b1, Type::unsigned(32));
+
+        let zero = builder.numeric_constant(0u128, Type::unsigned(32));
+        let four = builder.numeric_constant(4u128, Type::unsigned(32));
+
+        builder.terminate_with_jmp(b1, vec![zero]);
+
+        builder.switch_to_block(b1);
+        let v3 = builder.insert_binary(v2, BinaryOp::Lt, four);
+        builder.terminate_with_jmpif(v3, b2, b3);
+
+        builder.switch_to_block(b2);
+        let v4 = builder.insert_binary(v0, BinaryOp::Div, v1);
+        let v5 = builder.insert_binary(v2, BinaryOp::Add, v4);
+        builder.terminate_with_jmp(b1, vec![v5]);
+
+        builder.switch_to_block(b3);
+        builder.terminate_with_return(vec![]);
+
+        // The division may fail if `v1` is zero but the loop body is never executed,
+        // so it must not be moved into the pre-header.
+        let ssa = builder.finish().loop_invariant_code_motion();
+        let main = ssa.main();
+        assert_eq!(main.dfg[main.entry_block()].instructions().len(), 0);
+        assert_eq!(main.dfg[b2].instructions().len(), 2);
+    }
+}
@@ -3,14 +3,27 @@
 //! Each pass is generally expected to mutate the SSA IR into a gradually
 //! simpler form until the IR only has a single function remaining with 1 block within it.
 //! Generally, these passes are also expected to minimize the final amount of instructions.
+mod array_mux;
 mod array_use;
 mod assert_constant;
+mod branch_constraints;
 mod bubble_up_constrains;
 mod constant_folding;
+mod constant_time;
+mod dead_store_elimination;
 mod defunctionalize;
 mod die;
+mod escape_analysis;
 pub(crate) mod flatten_cfg;
+mod global_value_numbering;
 mod inlining;
+mod loop_invariant;
 mod mem2reg;
+mod outlining;
+mod range_analysis;
+mod read_only_arrays;
 mod simplify_cfg;
+mod slice_promotion;
+mod static_assert;
 mod unrolling;
+mod unverified_hints;
@@ -0,0 +1,610 @@
+//! This module defines an SSA pass which outlines sequences of instructions repeated many times
+//! within an ACIR function into a shared function, each occurrence becoming a call to it.
+//!
+//! Unrolling copies the body of a loop once per iteration, so the rounds of a hash function end up
+//! as long runs of near identical instructions, each of which is compiled to its own opcodes. A
+//! constrained function marked `#[inline(never)]` is instead compiled once to a separate ACIR
+//! function, which its callers run through call opcodes. Outlining the repeated sequences into such
+//! functions trades a small overhead for each call for a much smaller circuit, which only pays off
+//! for backends which can prove call opcodes, so the pass only runs when it is asked for.
+//!
+//! Sequences of instructions are outlined together when they are structurally identical:
+//! - their instructions only differ in their operands, and have results of the same types, and
+//! - each operand refers to the same instruction of the sequence, to the same one of the values
+//!   defined before the sequence, or to the same constant. Constants which are added, subtracted
+//!   or multiplied may differ between the sequences, in which case they are passed to the outlined
+//!   function as well.
+//!
+//! Note that this pass must run after flattening as it only considers the entry block of each
+//! function. Only sequences run while side effects are enabled are outlined, as a call is skipped
+//! along with all of the instructions of the function when they are disabled, and the values the
+//! sequences take from and pass on to the rest of the function must all be numeric.
+use std::collections::{BTreeMap, BTreeSet};
+
+use acvm::FieldElement;
+use fxhash::FxHashMap as HashMap;
+use iter_extended::vecmap;
+use noirc_frontend::token::InlineType;
+
+use crate::ssa::{
+    function_builder::FunctionBuilder,
+    ir::{
+        dfg::DataFlowGraph,
+        function::{Function, FunctionId, RuntimeType},
+        instruction::{BinaryOp, Instruction, InstructionId, Intrinsic},
+        types::Type,
+        value::{Value, ValueId},
+    },
+    ssa_gen::Ssa,
+};
+
+/// The fewest instructions a sequence must hold to be outlined.
+const MIN_OUTLINED_INSTRUCTIONS: usize = 10;
+
+/// The fewest times a sequence must occur within a function to be outlined.
+const MIN_OUTLINED_REPETITIONS: usize = 3;
+
+impl Ssa {
+    /// Outlines the sequences of instructions repeated within each ACIR function into shared
+    /// functions, which are compiled to separate ACIR functions.
+    #[tracing::instrument(level = "trace", skip(self))]
+    pub(crate) fn outline_repeated_code(mut self) -> Ssa {
+        let acir_functions: Vec<_> = self
+            .functions
+            .values()
+            .filter(|function| function.runtime() == RuntimeType::Acir)
+            .map(|function| function.id())
+            .collect();
+
+        for function_id in acir_functions {
+            let mut function =
+                self.functions.remove(&function_id).expect("ICE: Function should exist");
+            let regions = RepeatedRegion::find_all(&mut function);
+            if !regions.is_empty() {
+                let outlined_functions = vecmap(regions.iter().enumerate(), |(index, region)| {
+                    let name = format!("{}_outlined_{index}", function.name());
+                    self.add_fn(|id| region.build_function(&function, name, id))
+                });
+                replace_regions(&mut function, &regions, &outlined_functions);
+            }
+            self.functions.insert(function_id, function);
+        }
+        self
+    }
+}
+
+/// An operand of an instruction within a sequence of instructions.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Operand {
+    /// The result at `index` of the instruction at `offset` within the sequence
+    Local {
+        offset: usize,
+        index: usize,
+    },
+    /// A value defined before the sequence, numbered in order of its first use
+    Input(usize),
+    /// A numeric constant, whose value is left out if it may differ between sequences
+    Constant(Type, Option<FieldElement>),
+    Intrinsic(Intrinsic),
+    Function(FunctionId),
+}
+
+/// An instruction up to the values it refers to, which is the same for instructions which may be
+/// at the same offset of identical sequences.
+#[derive(PartialEq, Eq, Hash)]
+struct Shape {
+    /// The instruction with each of its operands replaced by the same placeholder value
+    instruction: Instruction,
+    /// The constants and functions among the operands, `None` for each other value
+    operands: Vec<Option<Operand>>,
+    result_types: Vec<Type>,
+}
+
+/// One occurrence of a sequence of instructions.
+struct Occurrence {
+    /// The position of the first instruction of the occurrence within its block
+    start: usize,
+    /// The values defined before the occurrence which it uses, in order of their first use
+    inputs: Vec<ValueId>,
+    /// The constants which may differ between occurrences, in the order they are used
+    constants: Vec<ValueId>,
+}
+
+/// A sequence of instructions along with each of its non-overlapping occurrences in a function.
+struct RepeatedRegion {
+    occurrences: Vec<Occurrence>,
+    length: usize,
+    /// Whether each of the constants which may differ between the occurrences does, in which
+    /// case it is passed to the outlined function
+    lifted_constants: Vec<bool>,
+    /// The results of the sequence which are used after one of its occurrences, as the offset of
+    /// their instruction and their index among its results
+    outputs: Vec<(usize, usize)>,
+}
+
+impl RepeatedRegion {
+    /// Finds the sequences of instructions worth outlining in `function`, none of which overlap.
+    fn find_all(function: &mut Function) -> Vec<RepeatedRegion> {
+        if function.reachable_blocks().len() != 1 {
+            return Vec::new();
+        }
+        let placeholder = function.dfg.make_constant(FieldElement::zero(), Type::field());
+        let dfg = &function.dfg;
+        let instructions = dfg[function.entry_block()].instructions();
+
+        let mut side_effects_enabled = true;
+        let shapes = vecmap(instructions, |instruction| {
+            if let Instruction::EnableSideEffects { condition } = &dfg[*instruction] {
+                side_effects_enabled =
+                    dfg.get_numeric_constant(*condition).map_or(false, |c| c.is_one());
+            }
+            side_effects_enabled
+                .then(|| instruction_shape(dfg, *instruction, placeholder))
+                .flatten()
+        });
+        let tokens = vecmap(&shapes, |shape| shape.as_ref().map(fxhash::hash64));
+        let last_uses = find_last_uses(function, instructions);
+
+        // Group the starts of the runs of the fewest instructions which could be outlined by the
+        // shapes of their instructions.
+        let mut runs: BTreeMap<u64, Vec<usize>> = BTreeMap::new();
+        for start in 0..instructions.len().saturating_sub(MIN_OUTLINED_INSTRUCTIONS - 1) {
+            let run = &tokens[start..start + MIN_OUTLINED_INSTRUCTIONS];
+            if run.iter().all(Option::is_some) {
+                runs.entry(fxhash::hash64(&run)).or_default().push(start);
+            }
+        }
+
+        let mut candidates = Vec::new();
+        for starts in runs.into_values() {
+            let mut occurrence_starts: Vec<usize> = Vec::new();
+            for start in starts {
+                if occurrence_starts
+                    .last()
+                    .map_or(true, |last| start >= last + MIN_OUTLINED_INSTRUCTIONS)
+                {
+                    occurrence_starts.push(start);
+                }
+            }
+            if occurrence_starts.len() < MIN_OUTLINED_REPETITIONS {
+                continue;
+            }
+
+            // Extend the occurrences for as long as they keep matching without overlapping.
+            let first = occurrence_starts[0];
+            let mut length = 0;
+            while occurrence_starts.iter().enumerate().all(|(index, start)| {
+                let end = occurrence_starts.get(index + 1).copied().unwrap_or(instructions.len());
+                start + length < end
+                    && tokens[start + length].is_some()
+                    && tokens[start + length] == tokens[first + length]
+            }) {
+                length += 1;
+            }
+            if length < MIN_OUTLINED_INSTRUCTIONS {
+                continue;
+            }
+
+            let mut sequences: HashMap<_, Vec<Occurrence>> = HashMap::default();
+            for start in occurrence_starts {
+                let (operands, occurrence) = Occurrence::new(dfg, instructions, start, length);
+                let input_types = vecmap(&occurrence.inputs, |input| dfg.type_of_value(*input));
+                let shapes: Vec<_> = shapes[start..start + length].iter().collect();
+                sequences.entry((shapes, operands, input_types)).or_default().push(occurrence);
+            }
+            candidates.extend(sequences.into_values().filter_map(|occurrences| {
+                RepeatedRegion::new(dfg, instructions, &last_uses, occurrences, length)
+            }));
+        }
+
+        // Outline the sequences saving the most instructions first.
+        candidates.sort_by_key(|region| {
+            (std::cmp::Reverse(region.savings()), region.occurrences[0].start)
+        });
+        let mut outlined = vec![false; instructions.len()];
+        let mut regions = Vec::new();
+        for mut region in candidates {
+            region.occurrences.retain(|occurrence| {
+                !outlined[occurrence.start..occurrence.start + region.length].contains(&true)
+            });
+            if region.occurrences.len() < MIN_OUTLINED_REPETITIONS || region.savings() <= 0 {
+                continue;
+            }
+            for occurrence in &region.occurrences {
+                outlined[occurrence.start..occurrence.start + region.length].fill(true);
+            }
+            regions.push(region);
+        }
+        regions
+    }
+
+    /// Returns the region of the `occurrences` of a sequence of `length` instructions, if it can be
+    /// outlined and doing so saves instructions.
+    fn new(
+        dfg: &DataFlowGraph,
+        instructions: &[InstructionId],
+        last_uses: &HashMap<ValueId, usize>,
+        occurrences: Vec<Occurrence>,
+        length: usize,
+    ) -> Option<RepeatedRegion> {
+        if occurrences.len() < MIN_OUTLINED_REPETITIONS {
+            return None;
+        }
+
+        let lifted_constants = vecmap(0..occurrences[0].constants.len(), |index| {
+            let value = dfg.get_numeric_constant(occurrences[0].constants[index]);
+            occurrences
+                .iter()
+                .any(|occurrence| dfg.get_numeric_constant(occurrence.constants[index]) != value)
+        });
+
+        let mut outputs = BTreeSet::new();
+        for occurrence in &occurrences {
+            let end = occurrence.start + length;
+            for offset in 0..length {
+                let results = dfg.instruction_results(instructions[occurrence.start + offset]);
+                for (index, result) in results.iter().enumerate() {
+                    if last_uses.get(result).map_or(false, |last_use| *last_use >= end) {
+                        outputs.insert((offset, index));
+                    }
+                }
+            }
+        }
+
+        let template = &occurrences[0];
+        let is_numeric = |value: &ValueId| matches!(dfg.type_of_value(*value), Type::Numeric(_));
+        let outputs_are_numeric = outputs.iter().all(|(offset, index)| {
+            is_numeric(&dfg.instruction_results(instructions[template.start + offset])[*index])
+        });
+        if !outputs_are_numeric || !template.inputs.iter().all(is_numeric) {
+            return None;
+        }
+
+        let region = RepeatedRegion {
+            occurrences,
+            length,
+            lifted_constants,
+            outputs: outputs.into_iter().collect(),
+        };
+        (region.savings() > 0).then_some(region)
+    }
+
+    /// The number of values passed to the outlined function.
+    fn parameter_count(&self) -> usize {
+        let lifted_count = self.lifted_constants.iter().filter(|lifted| **lifted).count();
+        self.occurrences[0].inputs.len() + lifted_count
+    }
+
+    /// Estimates the number of instructions saved by outlining the region, counting each call as
+    /// one instruction along with one for each of its arguments, which must all be witnesses.
+    fn savings(&self) -> isize {
+        let count = self.occurrences.len() as isize;
+        let call_cost = 1 + self.parameter_count() as isize;
+        (count - 1) * self.length as isize - count * call_cost
+    }
+
+    /// Builds the function each occurrence of the region is replaced by a call to, out of the
+    /// first occurrence of the region in `caller`.
+    fn build_function(&self, caller: &Function, name: String, id: FunctionId) -> Function {
+        let dfg = &caller.dfg;
+        let instructions = dfg[caller.entry_block()].instructions();
+        let template = &self.occurrences[0];
+
+        let mut builder = FunctionBuilder::new(name, id, RuntimeType::Acir);
+        builder.current_function.set_inline_type(InlineType::Never);
+
+        // The values of the caller used by the outlined instructions, along with their results
+        let mut values: HashMap<ValueId, ValueId> = HashMap::default();
+        for input in &template.inputs {
+            values.insert(*input, builder.add_parameter(dfg.type_of_value(*input)));
+        }
+        let lifted_parameters =
+            vecmap(template.constants.iter().zip(&self.lifted_constants), |(constant, lifted)| {
+                lifted.then(|| builder.add_parameter(dfg.type_of_value(*constant)))
+            });
+
+        let mut constant_index = 0;
+        for instruction_id in &instructions[template.start..template.start + self.length] {
+            let instruction = &dfg[*instruction_id];
+            let has_liftable_constants = has_liftable_constants(instruction);
+            let new_instruction = instruction.map_values(|value| {
+                let value = dfg.resolve(value);
+                if let Some(new_value) = values.get(&value) {
+                    return *new_value;
+                }
+                match &dfg[value] {
+                    Value::NumericConstant { constant, typ } => {
+                        let lifted_parameter = if has_liftable_constants {
+                            constant_index += 1;
+                            lifted_parameters[constant_index - 1]
+                        } else {
+                            None
+                        };
+                        lifted_parameter
+                            .unwrap_or_else(|| builder.numeric_constant(*constant, typ.clone()))
+                    }
+                    Value::Intrinsic(intrinsic) => builder.import_intrinsic_id(*intrinsic),
+                    Value::Function(function) => builder.import_function(*function),
+                    _ => unreachable!("ICE: Outlined instructions only use inputs and constants"),
+                }
+            });
+
+            let results = dfg.instruction_results(*instruction_id);
+            let result_types = vecmap(results, |result| dfg.type_of_value(*result));
+            builder.set_call_stack(dfg.get_call_stack(*instruction_id));
+            let new_results = builder
+                .insert_instruction(new_instruction, Some(result_types))
+                .results()
+                .into_owned();
+            for (result, new_result) in results.iter().zip(new_results) {
+                values.insert(*result, new_result);
+            }
+        }
+
+        let return_values = vecmap(&self.outputs, |(offset, index)| {
+            values[&dfg.instruction_results(instructions[template.start + offset])[*index]]
+        });
+        builder.terminate_with_return(return_values);
+        builder.current_function
+    }
+}
+
+impl Occurrence {
+    /// Describes the operands of the `length` instructions at `start` relative to the sequence
+    /// they form, returning them along with the occurrence of the sequence.
+    fn new(
+        dfg: &DataFlowGraph,
+        instructions: &[InstructionId],
+        start: usize,
+        length: usize,
+    ) -> (Vec<Operand>, Occurrence) {
+        let mut locals = HashMap::default();
+        let mut input_numbers = HashMap::default();
+        let mut occurrence = Occurrence { start, inputs: Vec::new(), constants: Vec::new() };
+        let mut operands = Vec::new();
+
+        for offset in 0..length {
+            let instruction_id = instructions[start + offset];
+            let instruction = &dfg[instruction_id];
+            let has_liftable_constants = has_liftable_constants(instruction);
+            instruction.for_each_value(|value| {
+                let value = dfg.resolve(value);
+                let operand = match (locals.get(&value), &dfg[value]) {
+                    (Some(local), _) => Operand::clone(local),
+                    (None, Value::NumericConstant { typ, .. }) if has_liftable_constants => {
+                        occurrence.constants.push(value);
+                        Operand::Constant(typ.clone(), None)
+                    }
+                    (None, Value::NumericConstant { constant, typ }) => {
+                        Operand::Constant(typ.clone(), Some(*constant))
+                    }
+                    (None, Value::Intrinsic(intrinsic)) => Operand::Intrinsic(*intrinsic),
+                    (None, Value::Function(function)) => Operand::Function(*function),
+                    (None, _) => {
+                        let next_number = occurrence.inputs.len();
+                        let number = *input_numbers.entry(value).or_insert(next_number);
+                        if number == next_number {
+                            occurrence.inputs.push(value);
+                        }
+                        Operand::Input(number)
+                    }
+                };
+                operands.push(operand);
+            });
+
+            for (index, result) in dfg.instruction_results(instruction_id).iter().enumerate() {
+                locals.insert(*result, Operand::Local { offset, index });
+            }
+        }
+        (operands, occurrence)
+    }
+}
+
+/// Returns the shape of the instruction, or `None` if it can't be outlined.
+fn instruction_shape(
+    dfg: &DataFlowGraph,
+    instruction_id: InstructionId,
+    placeholder: ValueId,
+) -> Option<Shape> {
+    let instruction = &dfg[instruction_id];
+    match instruction {
+        Instruction::Allocate
+        | Instruction::Load { .. }
+        | Instruction::Store { .. }
+        | Instruction::EnableSideEffects { .. }
+        | Instruction::IncrementRc { .. } => return None,
+        Instruction::Call { func, .. }
+            if !matches!(dfg[*func], Value::Intrinsic(_) | Value::Function(_)) =>
+        {
+            return None;
+        }
+        _ => (),
+    }
+
+    let has_liftable_constants = has_liftable_constants(instruction);
+    let mut operands = Vec::new();
+    let mut can_be_outlined = true;
+    let instruction = instruction.map_values(|value| {
+        let operand = match &dfg[dfg.resolve(value)] {
+            Value::NumericConstant { typ, .. } if has_liftable_constants => {
+                Some(Operand::Constant(typ.clone(), None))
+            }
+            Value::NumericConstant { constant, typ } => {
+                Some(Operand::Constant(typ.clone(), Some(*constant)))
+            }
+            Value::Intrinsic(intrinsic) => Some(Operand::Intrinsic(*intrinsic)),
+            Value::Function(function) => Some(Operand::Function(*function)),
+            Value::Instruction { .. } | Value::Param { .. } => None,
+            Value::Array { .. } | Value::ForeignFunction(_) => {
+                can_be_outlined = false;
+                None
+            }
+        };
+        operands.push(operand);
+        placeholder
+    });
+
+    let results = dfg.instruction_results(instruction_id);
+    let result_types = vecmap(results, |result| dfg.type_of_value(*result));
+    can_be_outlined.then_some(Shape { instruction, operands, result_types })
+}
+
+/// Returns true if the constants `instruction` uses may differ between identical sequences, as it
+/// computes the same whether they are constants or witnesses.
+fn has_liftable_constants(instruction: &Instruction) -> bool {
+    matches!(
+        instruction,
+        Instruction::Binary(binary)
+            if matches!(binary.operator, BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul)
+    )
+}
+
+/// Returns the position of the last instruction using each value, values used by the terminator
+/// being last used after all of the instructions.
+fn find_last_uses(function: &Function, instructions: &[InstructionId]) -> HashMap<ValueId, usize> {
+    let dfg = &function.dfg;
+    let mut last_uses = HashMap::default();
+    for (position, instruction) in instructions.iter().enumerate() {
+        dfg[*instruction].for_each_value(|value| {
+            last_uses.insert(dfg.resolve(value), position);
+        });
+    }
+    if let Some(terminator) = dfg[function.entry_block()].terminator() {
+        terminator.for_each_value(|value| {
+            last_uses.insert(dfg.resolve(value), instructions.len());
+        });
+    }
+    last_uses
+}
+
+/// Replaces each occurrence of the `regions` in `function` with a call to its outlined function.
+fn replace_regions(
+    function: &mut Function,
+    regions: &[RepeatedRegion],
+    outlined_functions: &[FunctionId],
+) {
+    let block = function.entry_block();
+    let dfg = &mut function.dfg;
+    let instructions = dfg[block].take_instructions();
+
+    let mut occurrences = BTreeMap::new();
+    for (region, outlined_function) in regions.iter().zip(outlined_functions) {
+        for occurrence in &region.occurrences {
+            occurrences.insert(occurrence.start, (region, occurrence, *outlined_function));
+        }
+    }
+
+    let mut position = 0;
+    while position < instructions.len() {
+        let Some((region, occurrence, outlined_function)) = occurrences.get(&position) else {
+            dfg[block].insert_instruction(instructions[position]);
+            position += 1;
+            continue;
+        };
+
+        let lifted_constants = occurrence
+            .constants
+            .iter()
+            .zip(&region.lifted_constants)
+            .filter_map(|(constant, lifted)| lifted.then_some(*constant));
+        let arguments = occurrence.inputs.iter().copied().chain(lifted_constants);
+        let arguments = arguments.map(|argument| dfg.resolve(argument)).collect();
+
+        let outputs = vecmap(&region.outputs, |(offset, index)| {
+            dfg.instruction_results(instructions[position + offset])[*index]
+        });
+        let output_types = vecmap(&outputs, |output| dfg.type_of_value(*output));
+        let func = dfg.import_function(*outlined_function);
+        let call_stack = dfg.get_call_stack(instructions[position]);
+        let results = dfg
+            .insert_instruction_and_results(
+                Instruction::Call { func, arguments },
+                block,
+                Some(output_types),
+                call_stack,
+            )
+            .results()
+            .into_owned();
+        for (output, result) in outputs.into_iter().zip(results) {
+            dfg.set_value_from_id(output, result);
+        }
+        position += region.length;
+    }
+
+    // Refer to the results of the calls directly rather than to the outlined results they replace.
+    for instruction in dfg[block].instructions().to_vec() {
+        let resolved = dfg[instruction].map_values(|value| dfg.resolve(value));
+        dfg[instruction] = resolved;
+    }
+    let terminator = dfg[block].unwrap_terminator().map_values(|value| dfg.resolve(value));
+    dfg[block].set_terminator(terminator);
+}
+
+#[cfg(test)]
+mod test {
+    use noirc_frontend::token::InlineType;
+
+    use crate::ssa::{
+        function_builder::FunctionBuilder,
+        ir::{
+            function::RuntimeType,
+            instruction::{BinaryOp, Instruction},
+            map::Id,
+            types::Type,
+        },
+        ssa_gen::Ssa,
+    };
+
+    // fn main f0 {
+    //   b0(v0: Field):
+    //     v1 = mul v0, v0
+    //     v2 = mul v1, v1
+    //     v3 = add v2, Field c0
+    //     ... repeated four times for each constant c of `round_constants`
+    //     return v36
+    // }
+    fn repeated_rounds(round_constants: &[u128]) -> Ssa {
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let mut value = builder.add_parameter(Type::field());
+        for round_constant in round_constants {
+            for _ in 0..4 {
+                value = builder.insert_binary(value, BinaryOp::Mul, value);
+                value = builder.insert_binary(value, BinaryOp::Mul, value);
+                let constant = builder.field_constant(*round_constant);
+                value = builder.insert_binary(value, BinaryOp::Add, constant);
+            }
+        }
+        builder.terminate_with_return(vec![value]);
+        builder.finish()
+    }
+
+    fn assert_rounds_outlined(ssa: &Ssa, parameter_count: usize) {
+        assert_eq!(ssa.functions.len(), 2);
+
+        let main = ssa.main();
+        let instructions = main.dfg[main.entry_block()].instructions();
+        assert_eq!(instructions.len(), 3);
+        assert!(instructions
+            .iter()
+            .all(|instruction| matches!(main.dfg[*instruction], Instruction::Call { .. })));
+
+        let outlined = ssa.functions.values().find(|function| function.id() != main.id()).unwrap();
+        assert_eq!(outlined.inline_type(), InlineType::Never);
+        assert_eq!(outlined.parameters().len(), parameter_count);
+        assert_eq!(outlined.dfg[outlined.entry_block()].instructions().len(), 12);
+    }
+
+    #[test]
+    fn outlines_repeated_rounds() {
+        let ssa = repeated_rounds(&[1, 1, 1]).outline_repeated_code();
+        assert_rounds_outlined(&ssa, 1);
+    }
+
+    #[test]
+    fn passes_differing_constants_to_outlined_function() {
+        let ssa = repeated_rounds(&[1, 2, 3]).outline_repeated_code();
+        // The value of the previous round along with the constant of each of the four additions
+        assert_rounds_outlined(&ssa, 5);
+    }
+}
@@ -0,0 +1,345 @@
+//! This module defines an SSA pass which infers an upper bound on the number of bits of each
+//! integer value within ACIR functions and uses it to shrink or remove truncations and range checks.
+//!
+//! The bounds are computed from the definition of each value, e.g. the sum of two values
+//! casted up from `u8` fits in 9 bits whichever type it is computed in. A `truncate` of a value
+//! which already fits in the target bit size, or a `range_check` of a value which already fits in
+//! the checked bit size, is then removed. The `max_bit_size` of any remaining truncation is reduced
+//! to the inferred bound, which reduces the size of the constraints generated for it.
+//!
+//! Parameters are assumed to fit within their type as ACIR gen constrains each input to its type.
+//! Values which are loaded, read from arrays or returned from calls are not bounded by their type
+//! however: unconstrained calls in particular return witnesses which the prover is free to choose.
+//! These values are only bounded once a range check or truncation has been applied to them.
+//!
+//! Note that this pass must run after flattening: the bounds of block parameters are never
+//! inferred so loops would be pessimized by this pass.
+use fxhash::FxHashMap as HashMap;
+
+use acvm::FieldElement;
+
+use crate::ssa::{
+    ir::{
+        dfg::DataFlowGraph,
+        function::{Function, RuntimeType},
+        instruction::{Binary, BinaryOp, Instruction, InstructionId},
+        post_order::PostOrder,
+        types::{NumericType, Type},
+        value::{Value, ValueId},
+    },
+    ssa_gen::Ssa,
+};
+
+impl Ssa {
+    /// Removes truncations and range checks of integer values which are known to fit within
+    /// the required number of bits, and tightens the `max_bit_size` of the remaining truncations.
+    #[tracing::instrument(level = "trace", skip(self))]
+    pub(crate) fn shrink_integer_ranges(mut self) -> Ssa {
+        for function in self.functions.values_mut() {
+            // Brillig performs these operations natively so gains nothing from this pass.
+            if function.runtime() == RuntimeType::Acir {
+                shrink_integer_ranges(function);
+            }
+        }
+        self
+    }
+}
+
+fn shrink_integer_ranges(function: &mut Function) {
+    let mut ranges = RangeAnalysis::default();
+
+    // Visit blocks in reverse post order so that values are visited before their uses.
+    let mut blocks = PostOrder::with_function(function).into_vec();
+    blocks.reverse();
+
+    for block in blocks {
+        let instructions = function.dfg[block].take_instructions();
+        let mut filtered_instructions = Vec::with_capacity(instructions.len());
+
+        for instruction_id in instructions {
+            let keep_instruction = match function.dfg[instruction_id] {
+                Instruction::Truncate { value, bit_size, max_bit_size } => {
+                    let value_bits = ranges.max_num_bits(&function.dfg, value);
+                    if value_bits <= bit_size {
+                        let result = function.dfg.instruction_results(instruction_id)[0];
+                        function.dfg.set_value_from_id(result, value);
+                        false
+                    } else {
+                        let max_bit_size = max_bit_size.min(value_bits);
+                        function.dfg[instruction_id] =
+                            Instruction::Truncate { value, bit_size, max_bit_size };
+                        true
+                    }
+                }
+                Instruction::RangeCheck { value, max_bit_size, .. } => {
+                    let keep_range_check = ranges.max_num_bits(&function.dfg, value) > max_bit_size;
+                    if keep_range_check {
+                        // Any later use of the value can rely on the range check
+                        ranges.record_range_check(&function.dfg, value, max_bit_size);
+                    }
+                    keep_range_check
+                }
+                _ => true,
+            };
+
+            if keep_instruction {
+                ranges.record_results(&function.dfg, instruction_id);
+                filtered_instructions.push(instruction_id);
+            }
+        }
+
+        *function.dfg[block].instructions_mut() = filtered_instructions;
+    }
+}
+
+#[derive(Default)]
+struct RangeAnalysis {
+    /// Maps the results of each visited instruction to the maximum number of bits they occupy
+    max_num_bits: HashMap<ValueId, u32>,
+}
+
+impl RangeAnalysis {
+    /// Returns an upper bound on the number of bits of `value`.
+    ///
+    /// Values which cannot be bounded are assumed to take the full size of a field element.
+    fn max_num_bits(&self, dfg: &DataFlowGraph, value: ValueId) -> u32 {
+        let value = dfg.resolve(value);
+        if let Some(num_bits) = self.max_num_bits.get(&value) {
+            return *num_bits;
+        }
+        match &dfg[value] {
+            Value::NumericConstant { constant, .. } => constant.num_bits(),
+            Value::Param { typ, .. } => type_max_num_bits(typ),
+            _ => FieldElement::max_num_bits(),
+        }
+    }
+
+    fn record_results(&mut self, dfg: &DataFlowGraph, instruction_id: InstructionId) {
+        let instruction = &dfg[instruction_id];
+        for result in dfg.instruction_results(instruction_id) {
+            let typ = dfg.type_of_value(*result);
+            if matches!(typ, Type::Numeric(_)) {
+                let num_bits = match instruction {
+                    Instruction::Binary(binary) => self.binary_max_num_bits(dfg, binary, &typ),
+                    Instruction::Cast(value, _) => self.max_num_bits(dfg, *value),
+                    Instruction::Not(value) => {
+                        if self.max_num_bits(dfg, *value) <= typ.bit_size() {
+                            typ.bit_size()
+                        } else {
+                            FieldElement::max_num_bits()
+                        }
+                    }
+                    Instruction::Truncate { value, bit_size, .. } => {
+                        self.max_num_bits(dfg, *value).min(*bit_size)
+                    }
+                    // The results of any other instruction, such as the outputs of a Brillig call,
+                    // are not constrained to fit within their type.
+                    _ => FieldElement::max_num_bits(),
+                };
+                self.max_num_bits.insert(*result, num_bits.min(FieldElement::max_num_bits()));
+            }
+        }
+    }
+
+    /// Bounds `value` to `max_bit_size` bits once it has been range checked.
+    fn record_range_check(&mut self, dfg: &DataFlowGraph, value: ValueId, max_bit_size: u32) {
+        let value = dfg.resolve(value);
+        let num_bits = self.max_num_bits(dfg, value).min(max_bit_size);
+        self.max_num_bits.insert(value, num_bits);
+    }
+
+    fn binary_max_num_bits(&self, dfg: &DataFlowGraph, binary: &Binary, typ: &Type) -> u32 {
+        let lhs_bits = self.max_num_bits(dfg, binary.lhs);
+        let rhs_bits = self.max_num_bits(dfg, binary.rhs);
+        let unknown = FieldElement::max_num_bits();
+
+        match binary.operator {
+            BinaryOp::Add => lhs_bits.max(rhs_bits) + 1,
+            BinaryOp::Sub => match dfg.get_numeric_constant(binary.lhs) {
+                // The result cannot underflow so is at most `lhs`
+                Some(lhs) if is_at_least_max_value(lhs, rhs_bits) => lhs_bits,
+                _ => unknown,
+            },
+            BinaryOp::Mul if lhs_bits == 1 || rhs_bits == 1 => lhs_bits.max(rhs_bits),
+            BinaryOp::Mul => lhs_bits + rhs_bits,
+            BinaryOp::Div | BinaryOp::Mod => match typ {
+                Type::Numeric(NumericType::Unsigned { .. }) if binary.operator == BinaryOp::Div => {
+                    lhs_bits
+                }
+                Type::Numeric(NumericType::Unsigned { .. }) => lhs_bits.min(rhs_bits),
+                Type::Numeric(NumericType::Signed { bit_size }) => *bit_size,
+                _ => unknown,
+            },
+            BinaryOp::Eq | BinaryOp::Lt => 1,
+            BinaryOp::And => lhs_bits.min(rhs_bits),
+            BinaryOp::Or | BinaryOp::Xor => lhs_bits.max(rhs_bits),
+        }
+    }
+}
+
+/// The maximum number of bits of a value which is constrained to fit within its type.
+fn type_max_num_bits(typ: &Type) -> u32 {
+    match typ {
+        Type::Numeric(numeric_type) => numeric_type.bit_size(),
+        _ => FieldElement::max_num_bits(),
+    }
+}
+
+/// Returns true if `constant` is at least the maximum value which fits in `num_bits` bits
+fn is_at_least_max_value(constant: FieldElement, num_bits: u32) -> bool {
+    let constant_bits = constant.num_bits();
+    constant_bits > num_bits
+        || (constant_bits == num_bits && (constant + FieldElement::one()).num_bits() > num_bits)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ssa::{
+        function_builder::FunctionBuilder,
+        ir::{
+            function::RuntimeType,
+            instruction::{BinaryOp, Instruction},
+            map::Id,
+            types::Type,
+        },
+    };
+
+    #[test]
+    fn remove_range_check_of_small_sum() {
+        // fn main f0 {
+        //   b0(v0: u8, v1: u8):
+        //     v2 = cast v0 as u32
+        //     v3 = cast v1 as u32
+        //     v4 = add v2, v3
+        //     v5 = add v4, v2
+        //     range_check v5 to 32 bits
+        //     v6 = mul v4, v2
+        //     range_check v6 to 16 bits
+        //     return v5
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::unsigned(8));
+        let v1 = builder.add_parameter(Type::unsigned(8));
+        let v2 = builder.insert_cast(v0, Type::unsigned(32));
+        let v3 = builder.insert_cast(v1, Type::unsigned(32));
+        let v4 = builder.insert_binary(v2, BinaryOp::Add, v3);
+        let v5 = builder.insert_binary(v4, BinaryOp::Add, v2);
+        builder.insert_range_check(v5, 32, None);
+        let v6 = builder.insert_binary(v4, BinaryOp::Mul, v2);
+        builder.insert_range_check(v6, 16, None);
+        builder.terminate_with_return(vec![v5]);
+
+        let ssa = builder.finish().shrink_integer_ranges();
+        let main = ssa.main();
+        let instructions = main.dfg[main.entry_block()].instructions();
+
+        // `v6` takes up to 17 bits so only its range check remains
+        let range_checks: Vec<_> = instructions
+            .iter()
+            .filter_map(|instruction| match &main.dfg[*instruction] {
+                Instruction::RangeCheck { value, .. } => Some(*value),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(range_checks, vec![v6]);
+    }
+
+    #[test]
+    fn shrink_truncations() {
+        // fn main f0 {
+        //   b0(v0: u8, v1: u8):
+        //     v2 = cast v0 as u32
+        //     v3 = cast v1 as u32
+        //     v4 = mul v2, v3
+        //     v5 = truncate v4 to 32 bits, max_bit_size: 64
+        //     v6 = truncate v4 to 8 bits, max_bit_size: 64
+        //     v7 = sub u32 255, v0
+        //     v8 = truncate v7 to 8 bits, max_bit_size: 33
+        //     return v5, v6, v8
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::unsigned(8));
+        let v1 = builder.add_parameter(Type::unsigned(8));
+        let v2 = builder.insert_cast(v0, Type::unsigned(32));
+        let v3 = builder.insert_cast(v1, Type::unsigned(32));
+        let v4 = builder.insert_binary(v2, BinaryOp::Mul, v3);
+        let v5 = builder.insert_truncate(v4, 32, 64);
+        let v6 = builder.insert_truncate(v4, 8, 64);
+        let max = builder.numeric_constant(255u128, Type::unsigned(32));
+        let v7 = builder.insert_binary(max, BinaryOp::Sub, v2);
+        let v8 = builder.insert_truncate(v7, 8, 33);
+        builder.terminate_with_return(vec![v5, v6, v8]);
+
+        let ssa = builder.finish().shrink_integer_ranges();
+        let main = ssa.main();
+        let instructions = main.dfg[main.entry_block()].instructions();
+
+        let truncations: Vec<_> = instructions
+            .iter()
+            .filter_map(|instruction| match &main.dfg[*instruction] {
+                Instruction::Truncate { bit_size, max_bit_size, .. } => {
+                    Some((*bit_size, *max_bit_size))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(truncations, vec![(8, 16)]);
+        assert_eq!(main.dfg.resolve(v5), v4);
+        assert_eq!(main.dfg.resolve(v8), v7);
+    }
+
+    #[test]
+    fn keep_range_check_of_brillig_output() {
+        // fn main f0 {
+        //   b0():
+        //     v0 = call f1()
+        //     v1 = cast v0 as u32
+        //     v2 = add v1, u32 1
+        //     range_check v2 to 16 bits
+        //     range_check v0 to 8 bits
+        //     v3 = cast v0 as u32
+        //     v4 = add v3, u32 1
+        //     range_check v4 to 16 bits
+        //     return v2, v4
+        // }
+        // unconstrained fn foo f1 {
+        //   b0():
+        //     return u8 0
+        // }
+        let main_id = Id::test_new(0);
+        let foo_id = Id::test_new(1);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let foo = builder.import_function(foo_id);
+        let v0 = builder.insert_call(foo, Vec::new(), vec![Type::unsigned(8)])[0];
+        let v1 = builder.insert_cast(v0, Type::unsigned(32));
+        let one = builder.numeric_constant(1u128, Type::unsigned(32));
+        let v2 = builder.insert_binary(v1, BinaryOp::Add, one);
+        builder.insert_range_check(v2, 16, None);
+        builder.insert_range_check(v0, 8, None);
+        let v3 = builder.insert_cast(v0, Type::unsigned(32));
+        let v4 = builder.insert_binary(v3, BinaryOp::Add, one);
+        builder.insert_range_check(v4, 16, None);
+        builder.terminate_with_return(vec![v2, v4]);
+
+        builder.new_brillig_function("foo".into(), foo_id);
+        let zero = builder.numeric_constant(0u128, Type::unsigned(8));
+        builder.terminate_with_return(vec![zero]);
+
+        let ssa = builder.finish().shrink_integer_ranges();
+        let main = ssa.main();
+        let instructions = main.dfg[main.entry_block()].instructions();
+
+        // The output of the Brillig call is not constrained to fit in a `u8` until it has been
+        // range checked, so only the range check of `v4` can be removed.
+        let range_checks: Vec<_> = instructions
+            .iter()
+            .filter_map(|instruction| match &main.dfg[*instruction] {
+                Instruction::RangeCheck { value, .. } => Some(*value),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(range_checks, vec![v2, v0]);
+    }
+}
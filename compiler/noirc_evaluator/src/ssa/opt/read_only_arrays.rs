@@ -0,0 +1,181 @@
+//! Finds arrays which are only ever read from so that each copy of the same array can share
+//! a single ACIR memory block.
+//!
+//! Arrays are values in SSA, so each use of a constant array, such as a lookup table, creates a
+//! new array with the same contents. ACIR gen initializes a separate memory block for each array
+//! which is indexed dynamically, repeating the initialization of the whole array for each copy.
+//! An array which is never the input of an array set or swap is never written to however, so all
+//! copies of it can read from the memory block of the first one.
+use fxhash::{FxHashMap as HashMap, FxHashSet as HashSet};
+
+use crate::ssa::{
+    ir::{
+        dfg::DataFlowGraph,
+        function::{Function, FunctionId},
+        instruction::{Instruction, Intrinsic},
+        types::Type,
+        value::{Value, ValueId},
+    },
+    ssa_gen::Ssa,
+};
+
+impl Ssa {
+    /// Maps each read-only array to the first read-only array with the same elements and type,
+    /// whose memory block the array can share.
+    ///
+    /// Arrays which are the first of their contents are not included in the map. The arrays of
+    /// each function are mapped separately, as values are only unique within a function.
+    #[tracing::instrument(level = "trace", skip(self))]
+    pub(crate) fn find_shared_read_only_arrays(
+        &self,
+    ) -> HashMap<FunctionId, HashMap<ValueId, ValueId>> {
+        let mut shared_arrays = HashMap::default();
+        for (id, function) in &self.functions {
+            let mut function_shared_arrays = HashMap::default();
+            find_shared_arrays(function, &mut function_shared_arrays);
+            shared_arrays.insert(*id, function_shared_arrays);
+        }
+        shared_arrays
+    }
+}
+
+fn find_shared_arrays(function: &Function, shared_arrays: &mut HashMap<ValueId, ValueId>) {
+    let dfg = &function.dfg;
+    let blocks = function.reachable_blocks();
+
+    let mut written_arrays = HashSet::default();
+    for block in &blocks {
+        for instruction in dfg[*block].instructions() {
+            match &dfg[*instruction] {
+                Instruction::ArraySet { array, .. } => {
+                    written_arrays.insert(dfg.resolve(*array));
+                }
+                Instruction::Call { func, arguments }
+                    if matches!(dfg[*func], Value::Intrinsic(Intrinsic::ArraySwap)) =>
+                {
+                    written_arrays.insert(dfg.resolve(arguments[0]));
+                }
+                _ => (),
+            }
+        }
+    }
+
+    let mut first_arrays: HashMap<(im::Vector<ValueId>, Type), ValueId> = HashMap::default();
+    for block in &blocks {
+        for instruction in dfg[*block].instructions() {
+            if let Instruction::ArrayGet { array, .. } = &dfg[*instruction] {
+                let array = dfg.resolve(*array);
+                if written_arrays.contains(&array) {
+                    continue;
+                }
+
+                if let Some(contents) = array_contents(dfg, array) {
+                    let first_array = *first_arrays.entry(contents).or_insert(array);
+                    if first_array != array {
+                        shared_arrays.insert(array, first_array);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn array_contents(dfg: &DataFlowGraph, array: ValueId) -> Option<(im::Vector<ValueId>, Type)> {
+    match &dfg[array] {
+        Value::Array { array, typ } => Some((array.clone(), typ.clone())),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ssa::{
+        function_builder::FunctionBuilder,
+        ir::{function::RuntimeType, map::Id, types::Type},
+    };
+
+    #[test]
+    fn shares_copies_of_read_only_arrays() {
+        // fn main f0 {
+        //   b0(v0: u64):
+        //     v1 = array_get [Field 1, Field 2], index v0
+        //     v2 = array_get [Field 1, Field 2], index v0
+        //     v3 = array_set [Field 1, Field 2], index v0, value Field 3
+        //     v4 = array_get [Field 1, Field 2], index v0
+        //     return v1, v2, v4
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::unsigned(64));
+
+        let one = builder.field_constant(1u128);
+        let two = builder.field_constant(2u128);
+        let three = builder.field_constant(3u128);
+        let array_type = Type::Array(vec![Type::field()].into(), 2);
+
+        let first = builder.array_constant(im::vector![one, two], array_type.clone());
+        let v1 = builder.insert_array_get(first, v0, Type::field());
+        let second = builder.array_constant(im::vector![one, two], array_type.clone());
+        let v2 = builder.insert_array_get(second, v0, Type::field());
+
+        let written = builder.array_constant(im::vector![one, two], array_type);
+        builder.insert_array_set(written, v0, three);
+        let v4 = builder.insert_array_get(written, v0, Type::field());
+        builder.terminate_with_return(vec![v1, v2, v4]);
+
+        let shared_arrays = builder.finish().find_shared_read_only_arrays();
+        let shared_arrays = &shared_arrays[&main_id];
+
+        assert_eq!(shared_arrays.len(), 1);
+        assert_eq!(shared_arrays[&second], first);
+    }
+
+    #[test]
+    fn does_not_share_arrays_across_functions() {
+        // fn main f0 {
+        //   b0(v0: u64):
+        //     v1 = array_get [Field 1, Field 2], index v0
+        //     v2 = array_get [Field 2, Field 1], index v0
+        //     return v1, v2
+        // }
+        // fn foo f1 {
+        //   b0(v0: u64):
+        //     v1 = array_get [Field 3, Field 4], index v0
+        //     v2 = array_get [Field 3, Field 4], index v0
+        //     return v1, v2
+        // }
+        //
+        // The arrays of foo have the same ids as the arrays of main, so sharing the second array
+        // of foo must not make main read its second array from the block of its first one.
+        let main_id = Id::test_new(0);
+        let foo_id = Id::test_new(1);
+        let array_type = Type::Array(vec![Type::field()].into(), 2);
+
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::unsigned(64));
+        let one = builder.field_constant(1u128);
+        let two = builder.field_constant(2u128);
+        let first = builder.array_constant(im::vector![one, two], array_type.clone());
+        let v1 = builder.insert_array_get(first, v0, Type::field());
+        let main_second = builder.array_constant(im::vector![two, one], array_type.clone());
+        let v2 = builder.insert_array_get(main_second, v0, Type::field());
+        builder.terminate_with_return(vec![v1, v2]);
+
+        builder.new_function("foo".into(), foo_id);
+        let v0 = builder.add_parameter(Type::unsigned(64));
+        let three = builder.field_constant(3u128);
+        let four = builder.field_constant(4u128);
+        let first = builder.array_constant(im::vector![three, four], array_type.clone());
+        let v1 = builder.insert_array_get(first, v0, Type::field());
+        let second = builder.array_constant(im::vector![three, four], array_type);
+        let v2 = builder.insert_array_get(second, v0, Type::field());
+        builder.terminate_with_return(vec![v1, v2]);
+        assert_eq!(main_second, second);
+
+        let shared_arrays = builder.finish().find_shared_read_only_arrays();
+
+        assert!(shared_arrays[&main_id].is_empty());
+        assert_eq!(shared_arrays[&foo_id].len(), 1);
+        assert_eq!(shared_arrays[&foo_id][&second], first);
+    }
+}
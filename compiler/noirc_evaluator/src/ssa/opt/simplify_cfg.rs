@@ -159,6 +159,8 @@ mod test {
             map::Id,
             types::Type,
         },
+        parser::assert_normalized_ssa_equals,
+        ssa_gen::Ssa,
     };
 
     #[test]
@@ -216,6 +218,29 @@ mod test {
         }
     }
 
+    #[test]
+    fn inline_blocks_from_text() {
+        let src = "
+            acir fn main f0 {
+              b0():
+                jmp b1(Field 7)
+              b1(v0: Field):
+                jmp b2(v0)
+              b2(v1: Field):
+                return v1
+            }
+            ";
+        let ssa: Ssa = src.parse().unwrap();
+
+        let expected = "
+            acir fn main f0 {
+              b0():
+                return Field 7
+            }
+            ";
+        assert_normalized_ssa_equals(ssa.simplify_cfg(), expected);
+    }
+
     #[test]
     fn remove_known_jmpif() {
         // fn main {
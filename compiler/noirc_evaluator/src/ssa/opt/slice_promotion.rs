@@ -0,0 +1,341 @@
+//! This module defines an SSA pass which promotes the contents of slices whose length is known at
+//! compile-time to arrays.
+//!
+//! A slice is a pair of its length and the array holding its contents, which may hold more values
+//! than the length of the slice. Merging two slices while flattening must pad the smaller contents
+//! with placeholder values, and ACIR gen allocates memory blocks for slices large enough for any
+//! values pushed onto them. After unrolling however, most slices in ACIR functions are built from
+//! constant arrays and never change length, such as slice literals or slices written to at an
+//! index. The contents of these slices are retyped to arrays, which are merged value by value
+//! like any other array.
+//!
+//! The contents of a slice are promoted when:
+//! - they are a constant array, the result of writing to promoted contents, or a block parameter
+//!   whose arguments along all paths are promoted contents of the same size passed along with a
+//!   constant length matching that size, and
+//! - they are only read from, written to or passed to such a block parameter. Contents which are
+//!   passed to a call, stored in a reference or returned keep their slice type.
+//!
+//! Each access to a slice is still checked against its length during SSA gen, so promoting its
+//! contents does not change the values which can be read from it.
+use fxhash::{FxHashMap as HashMap, FxHashSet as HashSet};
+
+use crate::ssa::{
+    ir::{
+        dfg::DataFlowGraph,
+        function::{Function, RuntimeType},
+        instruction::{Instruction, TerminatorInstruction},
+        types::Type,
+        value::{Value, ValueId},
+    },
+    ssa_gen::Ssa,
+};
+
+impl Ssa {
+    /// Retypes the contents of each slice in ACIR functions whose length is the same along all
+    /// paths to an array of that length.
+    #[tracing::instrument(level = "trace", skip(self))]
+    pub(crate) fn promote_constant_slices(mut self) -> Ssa {
+        for function in self.functions.values_mut() {
+            // Brillig holds the length of a vector along with its contents, which are allocated
+            // at runtime.
+            if function.runtime() == RuntimeType::Acir {
+                promote_constant_slices(function);
+            }
+        }
+        self
+    }
+}
+
+fn promote_constant_slices(function: &mut Function) {
+    let contents = SliceContents::find(function);
+    let promoted = contents.promoted_types(&function.dfg);
+    if promoted.is_empty() {
+        return;
+    }
+
+    // Values replaced by promoted contents hold a copy of them which must be retyped as well.
+    let dfg = &mut function.dfg;
+    let values: Vec<_> = dfg.values_iter().map(|(value, _)| value).collect();
+    for value in values {
+        if let Some(typ) = promoted.get(&dfg.resolve(value)) {
+            dfg.set_type_of_value(value, typ.clone());
+        }
+    }
+}
+
+/// The contents of each slice in a function which could be promoted to an array.
+#[derive(Default)]
+struct SliceContents {
+    /// The number of values held by each constant array of slice type
+    constants: HashMap<ValueId, usize>,
+
+    /// The contents written to by each `array_set` on a slice, keyed by its result
+    writes: HashMap<ValueId, ValueId>,
+
+    /// The contents passed to each block parameter of slice type from each predecessor, along
+    /// with the length passed to the parameter before it
+    parameters: HashMap<ValueId, Vec<(ValueId, Option<ValueId>)>>,
+
+    /// Values used other than by being read from, written to or passed to a block, which must
+    /// keep their type
+    escaped: HashSet<ValueId>,
+}
+
+impl SliceContents {
+    fn find(function: &Function) -> Self {
+        let dfg = &function.dfg;
+        let mut contents = Self::default();
+
+        for block in function.reachable_blocks() {
+            for instruction in dfg[block].instructions() {
+                match &dfg[*instruction] {
+                    Instruction::ArrayGet { array, index } => {
+                        contents.access(dfg, *array);
+                        contents.escape(dfg, *index);
+                    }
+                    Instruction::ArraySet { array, index, value } => {
+                        contents.access(dfg, *array);
+                        if is_promotable_slice(dfg, *array) {
+                            let result = dfg.instruction_results(*instruction)[0];
+                            contents.writes.insert(result, dfg.resolve(*array));
+                        }
+                        contents.escape(dfg, *index);
+                        contents.escape(dfg, *value);
+                    }
+                    other => other.for_each_value(|value| contents.escape(dfg, value)),
+                }
+            }
+
+            match dfg[block].unwrap_terminator() {
+                TerminatorInstruction::Jmp { destination, arguments, .. } => {
+                    let parameters = dfg.block_parameters(*destination);
+                    for (position, (parameter, argument)) in
+                        parameters.iter().zip(arguments).enumerate()
+                    {
+                        if !is_promotable_slice(dfg, *parameter) {
+                            contents.escape(dfg, *argument);
+                            continue;
+                        }
+
+                        contents.access(dfg, *argument);
+                        let length = position.checked_sub(1).map(|position| arguments[position]);
+                        let incoming = (dfg.resolve(*argument), length);
+                        contents.parameters.entry(*parameter).or_default().push(incoming);
+                    }
+                }
+                terminator => terminator.for_each_value(|value| contents.escape(dfg, value)),
+            }
+        }
+
+        contents
+    }
+
+    /// Records a use of `value` as the contents of a slice being read from, written to or passed
+    /// to a block.
+    fn access(&mut self, dfg: &DataFlowGraph, value: ValueId) {
+        let value = dfg.resolve(value);
+        if let Value::Array { array, typ } = &dfg[value] {
+            if is_promotable_slice(dfg, value) {
+                // The contents of a slice always hold whole elements, unless they are malformed.
+                if array.len() % typ.element_size() == 0 {
+                    self.constants.insert(value, array.len());
+                }
+            } else if typ.is_nested_slice() {
+                array.iter().for_each(|element| self.escape(dfg, *element));
+            }
+        }
+    }
+
+    /// Records a use of `value` which requires it to keep its type.
+    fn escape(&mut self, dfg: &DataFlowGraph, value: ValueId) {
+        let value = dfg.resolve(value);
+        self.escaped.insert(value);
+
+        // Slices held by an array keep their type as part of the type of the array.
+        if let Value::Array { array, typ } = &dfg[value] {
+            if typ.is_nested_slice() {
+                array.iter().for_each(|element| self.escape(dfg, *element));
+            }
+        }
+    }
+
+    /// Returns the array type each promoted value is retyped to.
+    fn promoted_types(&self, dfg: &DataFlowGraph) -> HashMap<ValueId, Type> {
+        let capacities = self.capacities(dfg);
+        let mut promoted: HashSet<ValueId> =
+            capacities.keys().filter(|value| !self.escaped.contains(value)).copied().collect();
+
+        // Contents are only promoted along with all of the contents they are written to or passed
+        // from and to, so that each instruction and jump keeps values of the same type.
+        loop {
+            let promoted_count = promoted.len();
+            for (result, array) in &self.writes {
+                if !promoted.contains(result) || !promoted.contains(array) {
+                    promoted.remove(result);
+                    promoted.remove(array);
+                }
+            }
+            for (parameter, incoming) in &self.parameters {
+                let all_promoted = promoted.contains(parameter)
+                    && incoming.iter().all(|(argument, _)| promoted.contains(argument));
+                if !all_promoted {
+                    promoted.remove(parameter);
+                    incoming.iter().for_each(|(argument, _)| {
+                        promoted.remove(argument);
+                    });
+                }
+            }
+            if promoted.len() == promoted_count {
+                break;
+            }
+        }
+
+        promoted
+            .into_iter()
+            .map(|value| {
+                let Type::Slice(element_types) = dfg.type_of_value(value) else {
+                    unreachable!("ICE: Only slices are promoted to arrays");
+                };
+                let length = capacities[&value] / element_types.len();
+                (value, Type::Array(element_types, length))
+            })
+            .collect()
+    }
+
+    /// Returns the number of values held by each of the contents whose size is known.
+    fn capacities(&self, dfg: &DataFlowGraph) -> HashMap<ValueId, usize> {
+        let mut capacities = self.constants.clone();
+        loop {
+            let known_count = capacities.len();
+            for (result, array) in &self.writes {
+                if let Some(capacity) = capacities.get(array).copied() {
+                    capacities.entry(*result).or_insert(capacity);
+                }
+            }
+            for (parameter, incoming) in &self.parameters {
+                if !capacities.contains_key(parameter) {
+                    if let Some(capacity) =
+                        parameter_capacity(dfg, *parameter, incoming, &capacities)
+                    {
+                        capacities.insert(*parameter, capacity);
+                    }
+                }
+            }
+            if capacities.len() == known_count {
+                return capacities;
+            }
+        }
+    }
+}
+
+/// Returns the number of values held by the contents passed to `parameter` if it is the same for
+/// each of the `incoming` contents, each of which is passed along with a constant length of as
+/// many elements.
+fn parameter_capacity(
+    dfg: &DataFlowGraph,
+    parameter: ValueId,
+    incoming: &[(ValueId, Option<ValueId>)],
+    capacities: &HashMap<ValueId, usize>,
+) -> Option<usize> {
+    let element_size = dfg.type_of_value(parameter).element_size();
+    let mut parameter_capacity = None;
+    for (argument, length) in incoming {
+        let capacity = *capacities.get(argument)?;
+        let length = dfg.get_numeric_constant((*length)?)?;
+        if length.to_u128() != (capacity / element_size) as u128 {
+            return None;
+        }
+        if *parameter_capacity.get_or_insert(capacity) != capacity {
+            return None;
+        }
+    }
+    parameter_capacity
+}
+
+/// Returns true if `value` is a slice which could be promoted to an array.
+///
+/// Nested slices are left to the checks of ACIR gen, and slices of empty tuples have no length
+/// which can be deduced from the size of their contents.
+fn is_promotable_slice(dfg: &DataFlowGraph, value: ValueId) -> bool {
+    let typ = dfg.type_of_value(value);
+    matches!(&typ, Type::Slice(element_types) if !element_types.is_empty() && !typ.is_nested_slice())
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use crate::ssa::{
+        function_builder::FunctionBuilder,
+        ir::{function::RuntimeType, map::Id, types::Type},
+        ssa_gen::Ssa,
+    };
+
+    // fn main f0 {
+    //   b0(v0: u1, v1: u32, v2: u32):
+    //     jmpif v0 then: b1, else: b2
+    //   b1():
+    //     jmp b3(u32 2, [Field 1, Field 2])
+    //   b2():
+    //     jmp b3(v2, [Field 3, Field 4])
+    //   b3(v3: u32, v4: [Field]):
+    //     v5 = array_get v4, index v1
+    //     return v5
+    // }
+    fn merged_slices(else_length: Option<u128>) -> Ssa {
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::bool());
+        let v1 = builder.add_parameter(Type::unsigned(32));
+        let v2 = builder.add_parameter(Type::unsigned(32));
+
+        let b1 = builder.insert_block();
+        let b2 = builder.insert_block();
+        let b3 = builder.insert_block();
+        builder.terminate_with_jmpif(v0, b1, b2);
+
+        let slice_type = Type::Slice(Rc::new(vec![Type::field()]));
+        let then_length = builder.numeric_constant(2u128, Type::unsigned(32));
+        let else_length = match else_length {
+            Some(length) => builder.numeric_constant(length, Type::unsigned(32)),
+            None => v2,
+        };
+
+        builder.switch_to_block(b1);
+        let elements = vec![builder.field_constant(1u128), builder.field_constant(2u128)];
+        let then_slice = builder.array_constant(elements.into(), slice_type.clone());
+        builder.terminate_with_jmp(b3, vec![then_length, then_slice]);
+
+        builder.switch_to_block(b2);
+        let elements = vec![builder.field_constant(3u128), builder.field_constant(4u128)];
+        let else_slice = builder.array_constant(elements.into(), slice_type.clone());
+        builder.terminate_with_jmp(b3, vec![else_length, else_slice]);
+
+        builder.switch_to_block(b3);
+        builder.add_block_parameter(b3, Type::unsigned(32));
+        let v4 = builder.add_block_parameter(b3, slice_type);
+        let v5 = builder.insert_array_get(v4, v1, Type::field());
+        builder.terminate_with_return(vec![v5]);
+
+        builder.finish()
+    }
+
+    fn merged_contents_type(ssa: &Ssa) -> Type {
+        let main = ssa.main();
+        let merge_block = main.reachable_blocks().into_iter().last().unwrap();
+        main.dfg.type_of_value(main.dfg.block_parameters(merge_block)[1])
+    }
+
+    #[test]
+    fn promotes_slices_of_constant_length() {
+        let ssa = merged_slices(Some(2)).promote_constant_slices();
+        assert_eq!(merged_contents_type(&ssa), Type::Array(Rc::new(vec![Type::field()]), 2));
+    }
+
+    #[test]
+    fn keeps_slices_of_unknown_length() {
+        let ssa = merged_slices(None).promote_constant_slices();
+        assert!(matches!(merged_contents_type(&ssa), Type::Slice(_)));
+    }
+}
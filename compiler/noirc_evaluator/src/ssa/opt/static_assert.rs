@@ -0,0 +1,138 @@
+use crate::{
+    errors::RuntimeError,
+    ssa::{
+        ir::{
+            dfg::DataFlowGraph,
+            function::Function,
+            instruction::{Instruction, InstructionId, Intrinsic},
+            value::ValueId,
+        },
+        ssa_gen::Ssa,
+    },
+};
+
+impl Ssa {
+    /// Evaluates each call to `static_assert`, issuing an error if its condition is false or is
+    /// not known at compile-time.
+    ///
+    /// This pass is placed after loop unrolling and the removal of the branches it leaves
+    /// unreachable so that conditions depending on the index of a loop, or on the numeric
+    /// generics of a function, are constants by the time they are checked.
+    #[tracing::instrument(level = "trace", skip(self))]
+    pub(crate) fn evaluate_static_asserts(mut self) -> Result<Ssa, RuntimeError> {
+        for function in self.functions.values_mut() {
+            for block in function.reachable_blocks() {
+                let instructions = function.dfg[block].take_instructions();
+                let mut filtered_instructions = Vec::with_capacity(instructions.len());
+
+                for instruction in instructions {
+                    if !is_static_assert(function, instruction)? {
+                        filtered_instructions.push(instruction);
+                    }
+                }
+
+                *function.dfg[block].instructions_mut() = filtered_instructions;
+            }
+        }
+        Ok(self)
+    }
+}
+
+/// Returns whether `instruction` is a call to `static_assert` which holds, in which case it is
+/// removed from its block.
+fn is_static_assert(
+    function: &mut Function,
+    instruction: InstructionId,
+) -> Result<bool, RuntimeError> {
+    let static_assert_id = function.dfg.import_intrinsic(Intrinsic::StaticAssert);
+    let Instruction::Call { func, arguments } = &function.dfg[instruction] else {
+        return Ok(false);
+    };
+    if *func != static_assert_id {
+        return Ok(false);
+    }
+
+    let call_stack = function.dfg.get_call_stack(instruction);
+    match function.dfg.get_numeric_constant(arguments[0]) {
+        Some(predicate) if predicate.is_one() => Ok(true),
+        Some(_) => {
+            let message = constant_string(&function.dfg, arguments[1]);
+            Err(RuntimeError::StaticAssertFailed { message, call_stack })
+        }
+        None => Err(RuntimeError::StaticAssertDynamicPredicate { call_stack }),
+    }
+}
+
+/// Returns the contents of a string if each of its bytes is a constant.
+fn constant_string(dfg: &DataFlowGraph, string: ValueId) -> Option<String> {
+    let (bytes, _) = dfg.get_array_constant(string)?;
+    let bytes = bytes
+        .iter()
+        .map(|byte| dfg.get_numeric_constant(*byte).map(|byte| byte.to_u128() as u8))
+        .collect::<Option<Vec<_>>>()?;
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        errors::RuntimeError,
+        ssa::{parser::assert_normalized_ssa_equals, ssa_gen::Ssa},
+    };
+
+    #[test]
+    fn removes_static_asserts_which_hold() {
+        let src = "
+            acir fn main f0 {
+              b0(v0: Field):
+                call static_assert(u1 1, [u8 111, u8 107]: [u8; 2])
+                return v0
+            }
+            ";
+        let ssa: Ssa = src.parse().unwrap();
+
+        let expected = "
+            acir fn main f0 {
+              b0(v0: Field):
+                return v0
+            }
+            ";
+        assert_normalized_ssa_equals(ssa.evaluate_static_asserts().unwrap(), expected);
+    }
+
+    #[test]
+    fn reports_the_message_of_a_failed_static_assert() {
+        let src = "
+            acir fn main f0 {
+              b0():
+                call static_assert(u1 0, [u8 116, u8 111, u8 111, u8 32, u8 98, u8 105, u8 103]: [u8; 7])
+                return
+            }
+            ";
+        let ssa: Ssa = src.parse().unwrap();
+
+        match ssa.evaluate_static_asserts() {
+            Err(RuntimeError::StaticAssertFailed { message, .. }) => {
+                assert_eq!(message.as_deref(), Some("too big"));
+            }
+            _ => panic!("Expected the static assertion to fail"),
+        }
+    }
+
+    #[test]
+    fn rejects_static_asserts_on_runtime_values() {
+        let src = "
+            acir fn main f0 {
+              b0(v0: u1):
+                call static_assert(v0, [u8 110, u8 111]: [u8; 2])
+                return
+            }
+            ";
+        let ssa: Ssa = src.parse().unwrap();
+
+        assert!(matches!(
+            ssa.evaluate_static_asserts(),
+            Err(RuntimeError::StaticAssertDynamicPredicate { .. })
+        ));
+    }
+}
@@ -24,9 +24,9 @@ use crate::{
             dom::DominatorTree,
             function::{Function, RuntimeType},
             function_inserter::FunctionInserter,
-            instruction::TerminatorInstruction,
+            instruction::{Binary, BinaryOp, Instruction, TerminatorInstruction},
             post_order::PostOrder,
-            value::ValueId,
+            value::{Value, ValueId},
         },
         ssa_gen::Ssa,
     },
@@ -55,32 +55,131 @@ impl Ssa {
     }
 }
 
-struct Loop {
+/// The maximum number of instructions a loop in a Brillig function may expand to when
+/// it is unrolled by [`Ssa::unroll_small_brillig_loops`].
+const MAX_UNROLLED_BRILLIG_INSTRUCTIONS: u64 = 256;
+
+impl Ssa {
+    /// Unroll the loops of each Brillig function which have constant bounds and which only
+    /// expand to a small number of instructions, trading code size for fewer jumps at runtime.
+    ///
+    /// Only innermost loops are unrolled, so an outer loop is only considered once all of the
+    /// loops nested within it have been unrolled.
+    #[tracing::instrument(level = "trace", skip(self))]
+    pub(crate) fn unroll_small_brillig_loops(mut self) -> Ssa {
+        for function in self.functions.values_mut() {
+            if function.runtime() == RuntimeType::Brillig {
+                unroll_small_loops(function);
+            }
+        }
+        self
+    }
+}
+
+fn unroll_small_loops(function: &mut Function) {
+    let mut failed_to_unroll = HashSet::new();
+
+    loop {
+        let loops = find_all_loops(function);
+        let next_loop = loops.yet_to_unroll.iter().find(|loop_| {
+            !failed_to_unroll.contains(&loop_.header)
+                && is_innermost_loop(loop_, &loops.yet_to_unroll)
+                && is_small_loop(function, &loops.cfg, loop_)
+        });
+
+        // Unrolling an innermost loop never creates any new loops, so each iteration
+        // either removes a loop from the function or marks one as failed.
+        let Some(next_loop) = next_loop else { break };
+        if unroll_loop(function, &loops.cfg, next_loop).is_err() {
+            failed_to_unroll.insert(next_loop.header);
+        }
+    }
+}
+
+fn is_innermost_loop(loop_: &Loop, loops: &[Loop]) -> bool {
+    loops.iter().all(|other| other.header == loop_.header || !loop_.blocks.contains(&other.header))
+}
+
+/// True if the loop has constant bounds and its unrolled body stays within
+/// [`MAX_UNROLLED_BRILLIG_INSTRUCTIONS`].
+fn is_small_loop(function: &Function, cfg: &ControlFlowGraph, loop_: &Loop) -> bool {
+    let Some(iterations) = get_constant_iteration_count(function, cfg, loop_) else {
+        return false;
+    };
+
+    let instructions: usize =
+        loop_.blocks.iter().map(|block| function.dfg[*block].instructions().len()).sum();
+
+    iterations.saturating_mul(instructions as u64) <= MAX_UNROLLED_BRILLIG_INSTRUCTIONS
+}
+
+/// Returns the number of iterations of a loop of the form generated for `for i in start..end`
+/// if both `start` and `end` are constants.
+fn get_constant_iteration_count(
+    function: &Function,
+    cfg: &ControlFlowGraph,
+    loop_: &Loop,
+) -> Option<u64> {
+    // Loops without a range, from `loop` and `while` in unconstrained code, have no induction
+    // variable.
+    let header = &function.dfg[loop_.header];
+    if header.parameters().len() != 1 {
+        return None;
+    }
+
+    let pre_header = get_pre_header(cfg, loop_);
+    let start = get_induction_variable(function, pre_header).ok()?;
+    let start = function.dfg.get_numeric_constant(start)?.try_to_u64()?;
+
+    let induction_variable = header.parameters()[0];
+    let condition = match header.unwrap_terminator() {
+        TerminatorInstruction::JmpIf { condition, .. } => *condition,
+        _ => return None,
+    };
+
+    let instruction = match &function.dfg[condition] {
+        Value::Instruction { instruction, .. } => *instruction,
+        _ => return None,
+    };
+
+    match &function.dfg[instruction] {
+        Instruction::Binary(Binary { lhs, operator: BinaryOp::Lt, rhs })
+            if *lhs == induction_variable =>
+        {
+            let end = function.dfg.get_numeric_constant(*rhs)?.try_to_u64()?;
+            Some(end.saturating_sub(start))
+        }
+        _ => None,
+    }
+}
+
+pub(super) struct Loop {
     /// The header block of a loop is the block which dominates all the
     /// other blocks in the loop.
-    header: BasicBlockId,
+    pub(super) header: BasicBlockId,
 
     /// The start of the back_edge n -> d is the block n at the end of
     /// the loop that jumps back to the header block d which restarts the loop.
-    back_edge_start: BasicBlockId,
+    pub(super) back_edge_start: BasicBlockId,
 
     /// All the blocks contained within the loop, including `header` and `back_edge_start`.
     pub(crate) blocks: HashSet<BasicBlockId>,
 }
 
-struct Loops {
+pub(super) struct Loops {
     /// The loops that failed to be unrolled so that we do not try to unroll them again.
     /// Each loop is identified by its header block id.
     failed_to_unroll: HashSet<BasicBlockId>,
 
-    yet_to_unroll: Vec<Loop>,
+    /// The loops of the function, sorted such that inner loops come before the loops containing them.
+    pub(super) yet_to_unroll: Vec<Loop>,
     modified_blocks: HashSet<BasicBlockId>,
-    cfg: ControlFlowGraph,
+    pub(super) cfg: ControlFlowGraph,
 }
 
 /// Find a loop in the program by finding a node that dominates any predecessor node.
 /// The edge where this happens will be the back-edge of the loop.
-fn find_all_loops(function: &Function) -> Loops {
+pub(super) fn find_all_loops(function: &Function) -> Loops {
     let cfg = ControlFlowGraph::with_function(function);
     let post_order = PostOrder::with_function(function);
     let mut dom_tree = DominatorTree::with_cfg_and_post_order(&cfg, &post_order);
@@ -465,8 +564,11 @@ mod tests {
     use crate::ssa::{
         function_builder::FunctionBuilder,
         ir::{function::RuntimeType, instruction::BinaryOp, map::Id, types::Type},
+        ssa_gen::Ssa,
     };
 
+    use super::find_all_loops;
+
     #[test]
     fn unroll_nested_loops() {
         // fn main() {
@@ -636,4 +738,100 @@ mod tests {
         // Expected that we failed to unroll the loop
         assert!(ssa.unroll_loops().is_err());
     }
+
+    /// Builds a Brillig function containing `for i in 0..end { constrain i != end }`.
+    fn brillig_loop(end: u128) -> Ssa {
+        // brillig fn main f0 {
+        //   b0():
+        //     jmp b1(u32 0)
+        //   b1(v0: u32):
+        //     v1 = lt v0, u32 end
+        //     jmpif v1, then: b2, else: b3
+        //   b2():
+        //     v2 = eq v0, u32 end
+        //     constrain v2 == u1 0
+        //     v3 = add v0, u32 1
+        //     jmp b1(v3)
+        //   b3():
+        //     return
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Brillig);
+
+        let b1 = builder.insert_block();
+        let b2 = builder.insert_block();
+        let b3 = builder.insert_block();
+
+        let v0 = builder.add_block_parameter(b1, Type::unsigned(32));
+
+        let zero = builder.numeric_constant(0u128, Type::unsigned(32));
+        builder.terminate_with_jmp(b1, vec![zero]);
+
+        builder.switch_to_block(b1);
+        let end = builder.numeric_constant(end, Type::unsigned(32));
+        let v1 = builder.insert_binary(v0, BinaryOp::Lt, end);
+        builder.terminate_with_jmpif(v1, b2, b3);
+
+        builder.switch_to_block(b2);
+        let v2 = builder.insert_binary(v0, BinaryOp::Eq, end);
+        let false_const = builder.numeric_constant(0u128, Type::bool());
+        builder.insert_constrain(v2, false_const, None);
+        let one = builder.numeric_constant(1u128, Type::unsigned(32));
+        let v3 = builder.insert_binary(v0, BinaryOp::Add, one);
+        builder.terminate_with_jmp(b1, vec![v3]);
+
+        builder.switch_to_block(b3);
+        builder.terminate_with_return(vec![]);
+
+        builder.finish()
+    }
+
+    #[test]
+    fn unroll_small_brillig_loops() {
+        let ssa = brillig_loop(4).unroll_small_brillig_loops();
+        assert!(find_all_loops(ssa.main()).yet_to_unroll.is_empty());
+    }
+
+    #[test]
+    fn keep_large_brillig_loops() {
+        let ssa = brillig_loop(1000).unroll_small_brillig_loops();
+        assert_eq!(find_all_loops(ssa.main()).yet_to_unroll.len(), 1);
+    }
+
+    #[test]
+    fn keep_brillig_loops_when_unrolling_all_loops() {
+        let ssa = brillig_loop(4).unroll_loops().expect("Brillig loops are never unrolled");
+        assert_eq!(find_all_loops(ssa.main()).yet_to_unroll.len(), 1);
+    }
+
+    #[test]
+    fn keep_brillig_loops_without_a_range() {
+        // brillig fn main f0 {
+        //   b0(v0: u32):
+        //     jmp b1()
+        //   b1():
+        //     v1 = eq v0, u32 0
+        //     jmpif v1, then: b2, else: b1
+        //   b2():
+        //     return
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Brillig);
+        let v0 = builder.add_parameter(Type::unsigned(32));
+
+        let b1 = builder.insert_block();
+        let b2 = builder.insert_block();
+        builder.terminate_with_jmp(b1, vec![]);
+
+        builder.switch_to_block(b1);
+        let zero = builder.numeric_constant(0u128, Type::unsigned(32));
+        let v1 = builder.insert_binary(v0, BinaryOp::Eq, zero);
+        builder.terminate_with_jmpif(v1, b2, b1);
+
+        builder.switch_to_block(b2);
+        builder.terminate_with_return(vec![]);
+
+        let ssa = builder.finish().unroll_small_brillig_loops();
+        assert_eq!(find_all_loops(ssa.main()).yet_to_unroll.len(), 1);
+    }
 }
@@ -0,0 +1,231 @@
+//! The unverified hints check warns about values returned from unconstrained functions which
+//! reach a public output of the program without ever being constrained.
+//!
+//! Unconstrained functions are executed by the prover alone, so a malicious prover is free to
+//! replace their results with any value. Constrained code calling them is expected to check
+//! these results, e.g. asserting that the square of a claimed square root equals its input.
+//!
+//! Each result of a call to an unconstrained function is considered a hint. The check follows
+//! hints through each instruction, memory store and array of the final SSA of `main`, and
+//! considers a hint verified once a value derived from it is used in a `constrain` or
+//! `range_check` instruction. A warning is then reported for each hint which flows into a
+//! return value of `main` without being verified.
+//!
+//! Any constraint on a value derived from a hint is accepted as verifying it, even if the
+//! constraint doesn't pin the hint down to a single value, so this check only catches hints
+//! which are never checked at all.
+use std::collections::{BTreeSet, HashMap};
+
+use crate::{
+    errors::{InternalWarning, SsaReport},
+    ssa::{
+        ir::{
+            basic_block::BasicBlockId,
+            function::{Function, RuntimeType},
+            instruction::{Instruction, InstructionId, TerminatorInstruction},
+            value::{Value, ValueId},
+        },
+        ssa_gen::Ssa,
+    },
+};
+
+impl Ssa {
+    /// Returns a warning for each result of an unconstrained call which reaches a return value
+    /// of `main` without being constrained.
+    ///
+    /// This check is run on the final SSA, once `main` has been flattened and everything but
+    /// calls to unconstrained functions has been inlined into it.
+    #[tracing::instrument(level = "trace", skip(self))]
+    pub(crate) fn check_unverified_hints(&self) -> Vec<SsaReport> {
+        let main = self.main();
+        if main.runtime() == RuntimeType::Brillig {
+            // Unconstrained programs don't generate any constraints to verify hints with.
+            return Vec::new();
+        }
+
+        let mut hints = HintValues::new(self, main);
+        let blocks = main.reachable_blocks();
+        while hints.propagate(&blocks) {}
+
+        let mut unverified = BTreeSet::new();
+        for block in &blocks {
+            if let Some(TerminatorInstruction::Return { return_values, .. }) =
+                main.dfg[*block].terminator()
+            {
+                for value in return_values {
+                    unverified.extend(hints.hints_of(*value).difference(&hints.verified));
+                }
+            }
+        }
+
+        unverified
+            .into_iter()
+            .map(|hint| {
+                let call_stack = main.dfg.get_call_stack(hint);
+                SsaReport::Warning(InternalWarning::UnverifiedHint { call_stack })
+            })
+            .collect()
+    }
+}
+
+/// The calls to unconstrained functions which each value of a function is derived from.
+struct HintValues<'f> {
+    ssa: &'f Ssa,
+    function: &'f Function,
+    values: HashMap<ValueId, BTreeSet<InstructionId>>,
+    /// The hints stored behind each reference.
+    references: HashMap<ValueId, BTreeSet<InstructionId>>,
+    /// The hints which a constraint has been applied to.
+    verified: BTreeSet<InstructionId>,
+}
+
+impl<'f> HintValues<'f> {
+    fn new(ssa: &'f Ssa, function: &'f Function) -> Self {
+        Self {
+            ssa,
+            function,
+            values: HashMap::new(),
+            references: HashMap::new(),
+            verified: BTreeSet::new(),
+        }
+    }
+
+    fn hints_of(&self, value: ValueId) -> BTreeSet<InstructionId> {
+        let value = self.function.dfg.resolve(value);
+        let mut hints = BTreeSet::new();
+        hints.extend(self.values.get(&value).into_iter().flatten());
+        hints.extend(self.references.get(&value).into_iter().flatten());
+        if let Value::Array { array, .. } = &self.function.dfg[value] {
+            for element in array {
+                hints.extend(self.hints_of(*element));
+            }
+        }
+        hints
+    }
+
+    fn add_hints(
+        map: &mut HashMap<ValueId, BTreeSet<InstructionId>>,
+        value: ValueId,
+        hints: &BTreeSet<InstructionId>,
+    ) -> bool {
+        let entry = map.entry(value).or_default();
+        let previous_len = entry.len();
+        entry.extend(hints);
+        entry.len() != previous_len
+    }
+
+    fn is_unconstrained_call(&self, func: ValueId) -> bool {
+        let dfg = &self.function.dfg;
+        match &dfg[dfg.resolve(func)] {
+            Value::Function(callee) => self.ssa.functions[callee].runtime() == RuntimeType::Brillig,
+            _ => false,
+        }
+    }
+
+    /// Follows hints through each instruction, marking those reaching a constraint as verified.
+    /// Returns true if anything new was found.
+    fn propagate(&mut self, blocks: &BTreeSet<BasicBlockId>) -> bool {
+        let function = self.function;
+        let dfg = &function.dfg;
+        let mut changed = false;
+
+        for block in blocks {
+            for instruction_id in dfg[*block].instructions() {
+                let mut hints = BTreeSet::new();
+                match &dfg[*instruction_id] {
+                    Instruction::Constrain(..) | Instruction::RangeCheck { .. } => {
+                        let mut constrained = BTreeSet::new();
+                        dfg[*instruction_id]
+                            .for_each_value(|value| constrained.extend(self.hints_of(value)));
+                        let previous_len = self.verified.len();
+                        self.verified.extend(constrained);
+                        changed |= self.verified.len() != previous_len;
+                        continue;
+                    }
+                    Instruction::Store { address, value } => {
+                        let hints = self.hints_of(*value);
+                        let address = dfg.resolve(*address);
+                        changed |= Self::add_hints(&mut self.references, address, &hints);
+                        continue;
+                    }
+                    Instruction::Call { func, .. } if self.is_unconstrained_call(*func) => {
+                        hints.insert(*instruction_id);
+                    }
+                    _ => (),
+                }
+
+                dfg[*instruction_id].for_each_value(|value| hints.extend(self.hints_of(value)));
+                if !hints.is_empty() {
+                    for result in dfg.instruction_results(*instruction_id) {
+                        changed |= Self::add_hints(&mut self.values, *result, &hints);
+                    }
+                }
+            }
+
+            if let Some(TerminatorInstruction::Jmp { destination, arguments, .. }) =
+                dfg[*block].terminator()
+            {
+                let parameters = dfg.block_parameters(*destination);
+                for (parameter, argument) in parameters.iter().zip(arguments) {
+                    let hints = self.hints_of(*argument);
+                    changed |= Self::add_hints(&mut self.values, *parameter, &hints);
+                }
+            }
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        errors::{InternalWarning, SsaReport},
+        ssa::{
+            function_builder::FunctionBuilder,
+            ir::{function::RuntimeType, map::Id, types::Type},
+            ssa_gen::Ssa,
+        },
+    };
+
+    // fn main f0 {
+    //   b0(v0: Field):
+    //     v1 = call f1(v0)
+    //     (constrain v1 == v0)
+    //     return v1
+    // }
+    // unconstrained fn hint f1 {
+    //   b0(v0: Field):
+    //     return v0
+    // }
+    fn returned_hint(constrain_hint: bool) -> Ssa {
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::field());
+
+        let hint_id = Id::test_new(1);
+        let hint = builder.import_function(hint_id);
+        let v1 = builder.insert_call(hint, vec![v0], vec![Type::field()])[0];
+        if constrain_hint {
+            builder.insert_constrain(v1, v0, None);
+        }
+        builder.terminate_with_return(vec![v1]);
+
+        builder.new_brillig_function("hint".into(), hint_id);
+        let v0 = builder.add_parameter(Type::field());
+        builder.terminate_with_return(vec![v0]);
+
+        builder.finish()
+    }
+
+    #[test]
+    fn warns_on_unverified_hint() {
+        let warnings = returned_hint(false).check_unverified_hints();
+        assert_eq!(warnings.len(), 1);
+        assert!(matches!(warnings[0], SsaReport::Warning(InternalWarning::UnverifiedHint { .. })));
+    }
+
+    #[test]
+    fn accepts_constrained_hint() {
+        assert!(returned_hint(true).check_unverified_hints().is_empty());
+    }
+}
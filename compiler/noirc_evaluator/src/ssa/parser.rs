@@ -0,0 +1,848 @@
+//! A parser for the textual form of the SSA produced by the printer in `ir/printer.rs`.
+//!
+//! This lets the tests of a pass be written as the SSA before and after the pass rather than
+//! building the IR programmatically with a `FunctionBuilder`:
+//!
+//! ```text
+//! acir fn main f0 {
+//!   b0(v0: Field):
+//!     v1 = add v0, Field 1
+//!     return v1
+//! }
+//! ```
+//!
+//! Instructions are inserted exactly as they are written and are never simplified. Values,
+//! blocks and functions may use any ids in the source text; they are renumbered in the order
+//! in which they are created.
+use std::{
+    collections::{HashMap, HashSet},
+    rc::Rc,
+    str::FromStr,
+};
+
+use acvm::FieldElement;
+
+use super::{
+    ir::{
+        basic_block::BasicBlockId,
+        dfg::CallStack,
+        function::{Function, FunctionId, RuntimeType},
+        instruction::{AssertMessage, BinaryOp, Instruction, Intrinsic, TerminatorInstruction},
+        map::AtomicCounter,
+        types::Type,
+        value::ValueId,
+    },
+    ssa_gen::Ssa,
+};
+
+/// An error encountered while parsing the textual form of the SSA.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SsaParseError {
+    /// The 1-based line on which the error occurred.
+    pub(crate) line: usize,
+    pub(crate) message: String,
+}
+
+impl std::fmt::Display for SsaParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl FromStr for Ssa {
+    type Err = SsaParseError;
+
+    /// Parses a program in the format printed by `Ssa`'s `Display` implementation.
+    /// The first function in the source becomes the program's main function.
+    fn from_str(source: &str) -> Result<Ssa, SsaParseError> {
+        let mut parser = Parser::default();
+        let mut line_count = 0;
+        for (index, line) in source.lines().enumerate() {
+            line_count = index + 1;
+            parser
+                .parse_line(line)
+                .map_err(|message| SsaParseError { line: line_count, message })?;
+        }
+
+        let error = |message: &str| SsaParseError { line: line_count, message: message.to_owned() };
+        if parser.current.is_some() {
+            return Err(error("expected '}' to close the last function"));
+        }
+        if parser.functions.is_empty() {
+            return Err(error("expected at least one function"));
+        }
+        Ok(Ssa::new(parser.functions))
+    }
+}
+
+/// Parses `ssa` back from its printed form and asserts that it matches `expected`.
+///
+/// Both sides are printed and parsed again before being compared so that the ids used in
+/// `expected`, and the order its blocks are written in, don't need to match those of `ssa`.
+#[cfg(test)]
+pub(crate) fn assert_normalized_ssa_equals(ssa: Ssa, expected: &str) {
+    fn normalize(ssa: &Ssa) -> String {
+        let ssa: Ssa = ssa.to_string().parse().expect("Printed SSA should parse");
+        ssa.to_string()
+    }
+
+    let expected: Ssa = match expected.parse() {
+        Ok(ssa) => ssa,
+        Err(error) => panic!("Could not parse the expected SSA: {error}"),
+    };
+    assert_eq!(normalize(&ssa), normalize(&expected));
+}
+
+#[derive(Default)]
+struct Parser {
+    functions: Vec<Function>,
+    function_ids: FunctionIds,
+    /// The function currently being parsed, if the parser is between its `{` and `}`.
+    current: Option<FunctionContext>,
+}
+
+impl Parser {
+    fn parse_line(&mut self, line: &str) -> Result<(), String> {
+        if line.trim().is_empty() {
+            return Ok(());
+        }
+
+        let Some(context) = self.current.as_mut() else {
+            let function = self.parse_function_header(line)?;
+            self.current = Some(FunctionContext::new(function));
+            return Ok(());
+        };
+
+        let mut tokens = Tokens::new(line)?;
+        if tokens.eat_symbol("}") {
+            tokens.expect_end()?;
+            let context = self.current.take().expect("Checked to be some above");
+            self.functions.push(context.finish()?);
+        } else if tokens.starts_block() {
+            context.parse_block_header(&mut tokens)?;
+        } else {
+            context.parse_instruction(&mut self.function_ids, &mut tokens)?;
+        }
+        Ok(())
+    }
+
+    /// Parses e.g. `acir fn main f0 {`
+    fn parse_function_header(&mut self, line: &str) -> Result<Function, String> {
+        let words: Vec<_> = line.split_whitespace().collect();
+        let [runtime, "fn", name, id, "{"] = words[..] else {
+            return Err(format!("expected a function header, found '{}'", line.trim()));
+        };
+
+        let runtime = match runtime {
+            "acir" => RuntimeType::Acir,
+            "brillig" => RuntimeType::Brillig,
+            other => return Err(format!("unknown runtime '{other}'")),
+        };
+        if !is_id(id, 'f') {
+            return Err(format!("expected a function id, found '{id}'"));
+        }
+
+        let mut function = Function::new(name.to_owned(), self.function_ids.get(id));
+        function.set_runtime(runtime);
+        Ok(function)
+    }
+}
+
+/// Assigns a fresh FunctionId to each function id in the source text, in order of appearance.
+#[derive(Default)]
+struct FunctionIds {
+    ids: HashMap<String, FunctionId>,
+    next_id: AtomicCounter<Function>,
+}
+
+impl FunctionIds {
+    fn get(&mut self, name: &str) -> FunctionId {
+        let next_id = &self.next_id;
+        *self.ids.entry(name.to_owned()).or_insert_with(|| next_id.next())
+    }
+}
+
+/// The state needed while parsing the body of a single function.
+struct FunctionContext {
+    function: Function,
+    values: HashMap<String, ValueId>,
+    blocks: HashMap<String, BasicBlockId>,
+    /// The blocks whose header has been parsed, used to catch jumps to blocks which don't exist.
+    defined_blocks: HashSet<BasicBlockId>,
+    /// The block new instructions are added to. This is `None` once its terminator is parsed.
+    current_block: Option<BasicBlockId>,
+}
+
+impl FunctionContext {
+    fn new(function: Function) -> Self {
+        Self {
+            function,
+            values: HashMap::default(),
+            blocks: HashMap::default(),
+            defined_blocks: HashSet::default(),
+            current_block: None,
+        }
+    }
+
+    fn finish(self) -> Result<Function, String> {
+        if self.defined_blocks.is_empty() {
+            return Err(format!("function {} has no blocks", self.function.name()));
+        }
+        if self.current_block.is_some() {
+            return Err("expected a terminator before the end of the function".to_owned());
+        }
+        let mut undefined: Vec<_> = self
+            .blocks
+            .iter()
+            .filter_map(|(name, block)| (!self.defined_blocks.contains(block)).then_some(name))
+            .collect();
+        undefined.sort();
+        match undefined.first() {
+            Some(name) => Err(format!("block {name} is used but never defined")),
+            None => Ok(self.function),
+        }
+    }
+
+    /// Returns the block with the given name, creating it if this is its first use.
+    fn block(&mut self, name: &str) -> Result<BasicBlockId, String> {
+        if !is_id(name, 'b') {
+            return Err(format!("expected a block id, found '{name}'"));
+        }
+        if let Some(block) = self.blocks.get(name) {
+            return Ok(*block);
+        }
+        // The first block of each function is its entry block
+        let block = if self.blocks.is_empty() {
+            self.function.entry_block()
+        } else {
+            self.function.dfg.make_block()
+        };
+        self.blocks.insert(name.to_owned(), block);
+        Ok(block)
+    }
+
+    fn define_value(&mut self, name: String, value: ValueId) -> Result<(), String> {
+        if !is_id(&name, 'v') {
+            return Err(format!("expected a value id, found '{name}'"));
+        }
+        if self.values.contains_key(&name) {
+            return Err(format!("value {name} is defined more than once"));
+        }
+        self.values.insert(name, value);
+        Ok(())
+    }
+
+    /// Parses e.g. `b1(v0: Field, v1: u32):`
+    fn parse_block_header(&mut self, tokens: &mut Tokens) -> Result<(), String> {
+        if self.current_block.is_some() {
+            return Err("expected a terminator before the next block".to_owned());
+        }
+
+        let name = tokens.expect_ident()?;
+        let block = self.block(&name)?;
+        if !self.defined_blocks.insert(block) {
+            return Err(format!("block {name} is defined more than once"));
+        }
+
+        tokens.expect_symbol("(")?;
+        if !tokens.eat_symbol(")") {
+            loop {
+                let name = tokens.expect_ident()?;
+                tokens.expect_symbol(":")?;
+                let typ = tokens.parse_type()?;
+                let parameter = self.function.dfg.add_block_parameter(block, typ);
+                self.define_value(name, parameter)?;
+                if !tokens.eat_symbol(",") {
+                    tokens.expect_symbol(")")?;
+                    break;
+                }
+            }
+        }
+        tokens.expect_symbol(":")?;
+        tokens.expect_end()?;
+
+        self.current_block = Some(block);
+        Ok(())
+    }
+
+    /// Parses an instruction or terminator, along with any values it defines, and appends it
+    /// to the current block.
+    fn parse_instruction(
+        &mut self,
+        function_ids: &mut FunctionIds,
+        tokens: &mut Tokens,
+    ) -> Result<(), String> {
+        let Some(block) = self.current_block else {
+            return Err("expected a block header before this instruction".to_owned());
+        };
+
+        let mut results = Vec::new();
+        if matches!(tokens.peek_nth(1), Some(Token::Symbol("," | "="))) {
+            loop {
+                results.push(tokens.expect_ident()?);
+                if !tokens.eat_symbol(",") {
+                    break;
+                }
+            }
+            tokens.expect_symbol("=")?;
+        }
+
+        let keyword = tokens.expect_ident()?;
+        if results.is_empty() {
+            if let Some(terminator) = self.parse_terminator(function_ids, &keyword, tokens)? {
+                tokens.expect_end()?;
+                self.function.dfg.set_block_terminator(block, terminator);
+                self.current_block = None;
+                return Ok(());
+            }
+        }
+
+        let mut value = |tokens: &mut Tokens| self.parse_value(function_ids, tokens);
+        let instruction = match keyword.as_str() {
+            "add" | "sub" | "mul" | "div" | "mod" | "eq" | "lt" | "and" | "or" | "xor" => {
+                let lhs = value(tokens)?;
+                tokens.expect_symbol(",")?;
+                let rhs = value(tokens)?;
+                Instruction::binary(binary_operator(&keyword), lhs, rhs)
+            }
+            "cast" => {
+                let lhs = value(tokens)?;
+                tokens.expect_keyword("as")?;
+                Instruction::Cast(lhs, tokens.parse_type()?)
+            }
+            "not" => Instruction::Not(value(tokens)?),
+            "truncate" => {
+                let value = value(tokens)?;
+                tokens.expect_keyword("to")?;
+                let bit_size = tokens.expect_number()?;
+                tokens.expect_keyword("bits")?;
+                tokens.expect_symbol(",")?;
+                tokens.expect_keyword("max_bit_size")?;
+                tokens.expect_symbol(":")?;
+                let max_bit_size = tokens.expect_number()?;
+                Instruction::Truncate { value, bit_size, max_bit_size }
+            }
+            "constrain" => {
+                let lhs = value(tokens)?;
+                tokens.expect_symbol("==")?;
+                let rhs = value(tokens)?;
+                let assert_message = match tokens.eat_string() {
+                    Some(message) => {
+                        // A format string is followed by its values and its type
+                        let (values, printable_type) = if tokens.eat_symbol("(") {
+                            let values = self.parse_values_until(function_ids, tokens, ")")?;
+                            let printable_type = tokens.eat_string().ok_or_else(|| {
+                                format!(
+                                    "expected the type of the format string, found {}",
+                                    describe(tokens.peek_nth(0).cloned())
+                                )
+                            })?;
+                            (values, Some(printable_type))
+                        } else {
+                            (Vec::new(), None)
+                        };
+                        Some(AssertMessage { message, values, printable_type })
+                    }
+                    None => None,
+                };
+                Instruction::Constrain(lhs, rhs, assert_message)
+            }
+            "range_check" => {
+                let value = value(tokens)?;
+                tokens.expect_keyword("to")?;
+                let max_bit_size = tokens.expect_number()?;
+                tokens.expect_keyword("bits")?;
+                Instruction::RangeCheck { value, max_bit_size, assert_message: tokens.eat_string() }
+            }
+            "call" => {
+                let func = value(tokens)?;
+                tokens.expect_symbol("(")?;
+                let arguments = self.parse_values_until(function_ids, tokens, ")")?;
+                Instruction::Call { func, arguments }
+            }
+            "allocate" => Instruction::Allocate,
+            "load" => Instruction::Load { address: value(tokens)? },
+            "store" => {
+                let value = value(tokens)?;
+                tokens.expect_keyword("at")?;
+                Instruction::Store { address: self.parse_value(function_ids, tokens)?, value }
+            }
+            "enable_side_effects" => Instruction::EnableSideEffects { condition: value(tokens)? },
+            "array_get" => {
+                let array = value(tokens)?;
+                tokens.expect_symbol(",")?;
+                tokens.expect_keyword("index")?;
+                Instruction::ArrayGet { array, index: value(tokens)? }
+            }
+            "array_set" => {
+                let array = value(tokens)?;
+                tokens.expect_symbol(",")?;
+                tokens.expect_keyword("index")?;
+                let index = value(tokens)?;
+                tokens.expect_symbol(",")?;
+                tokens.expect_keyword("value")?;
+                Instruction::ArraySet { array, index, value: value(tokens)? }
+            }
+            "inc_rc" => Instruction::IncrementRc { value: value(tokens)? },
+            other => return Err(format!("unknown instruction '{other}'")),
+        };
+
+        let mut result_types = Vec::new();
+        if tokens.eat_symbol("->") {
+            loop {
+                result_types.push(tokens.parse_type()?);
+                if !tokens.eat_symbol(",") {
+                    break;
+                }
+            }
+        }
+        tokens.expect_end()?;
+
+        let dfg = &mut self.function.dfg;
+        let instruction = dfg.make_instruction(instruction, Some(result_types));
+        dfg[block].insert_instruction(instruction);
+
+        let result_values = dfg.instruction_results(instruction).to_vec();
+        if result_values.len() != results.len() {
+            return Err(format!(
+                "{keyword} has {} results but {} were given, \
+                 result types may need to be given with `-> type`",
+                result_values.len(),
+                results.len()
+            ));
+        }
+        for (name, value) in results.into_iter().zip(result_values) {
+            self.define_value(name, value)?;
+        }
+        Ok(())
+    }
+
+    /// Parses the rest of a terminator if `keyword` starts one.
+    fn parse_terminator(
+        &mut self,
+        function_ids: &mut FunctionIds,
+        keyword: &str,
+        tokens: &mut Tokens,
+    ) -> Result<Option<TerminatorInstruction>, String> {
+        let terminator = match keyword {
+            "jmp" => {
+                let destination = self.block(&tokens.expect_ident()?)?;
+                tokens.expect_symbol("(")?;
+                let arguments = self.parse_values_until(function_ids, tokens, ")")?;
+                TerminatorInstruction::Jmp { destination, arguments, call_stack: CallStack::new() }
+            }
+            "jmpif" => {
+                let condition = self.parse_value(function_ids, tokens)?;
+                tokens.expect_keyword("then")?;
+                tokens.expect_symbol(":")?;
+                let then_destination = self.block(&tokens.expect_ident()?)?;
+                tokens.expect_symbol(",")?;
+                tokens.expect_keyword("else")?;
+                tokens.expect_symbol(":")?;
+                let else_destination = self.block(&tokens.expect_ident()?)?;
+                TerminatorInstruction::JmpIf { condition, then_destination, else_destination }
+            }
+            "return" => {
+                let mut return_values = Vec::new();
+                if !tokens.is_at_end() {
+                    loop {
+                        return_values.push(self.parse_value(function_ids, tokens)?);
+                        if !tokens.eat_symbol(",") {
+                            break;
+                        }
+                    }
+                }
+                TerminatorInstruction::Return { return_values, call_stack: CallStack::new() }
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(terminator))
+    }
+
+    /// Parses a comma separated list of values followed by the `end` symbol.
+    fn parse_values_until(
+        &mut self,
+        function_ids: &mut FunctionIds,
+        tokens: &mut Tokens,
+        end: &'static str,
+    ) -> Result<Vec<ValueId>, String> {
+        let mut values = Vec::new();
+        if tokens.eat_symbol(end) {
+            return Ok(values);
+        }
+        loop {
+            values.push(self.parse_value(function_ids, tokens)?);
+            if !tokens.eat_symbol(",") {
+                tokens.expect_symbol(end)?;
+                return Ok(values);
+            }
+        }
+    }
+
+    /// Parses a value: a previously defined value such as `v3`, a constant such as `u32 7` or
+    /// `[Field 1, v2]: [Field; 2]`, or a function such as `f1`, an intrinsic or a foreign
+    /// function.
+    fn parse_value(
+        &mut self,
+        function_ids: &mut FunctionIds,
+        tokens: &mut Tokens,
+    ) -> Result<ValueId, String> {
+        let dfg = &mut self.function.dfg;
+        match tokens.next() {
+            Some(Token::Symbol("[")) => {
+                let elements = self.parse_values_until(function_ids, tokens, "]")?;
+                tokens.expect_symbol(":")?;
+                let typ = tokens.parse_type()?;
+                if !matches!(typ, Type::Array(..) | Type::Slice(_)) {
+                    return Err(format!("expected an array or slice type, found {typ}"));
+                }
+                Ok(self.function.dfg.make_array(elements.into(), typ))
+            }
+            Some(Token::Ident(name)) => {
+                if let Some(typ) = numeric_type(&name) {
+                    let constant = match tokens.next() {
+                        Some(Token::Number(number)) => parse_field_element(&number)
+                            .ok_or_else(|| format!("invalid constant {number}"))?,
+                        other => {
+                            return Err(format!("expected a constant, found {}", describe(other)))
+                        }
+                    };
+                    Ok(dfg.make_constant(constant, typ))
+                } else if is_id(&name, 'v') {
+                    self.values.get(&name).copied().ok_or_else(|| format!("undefined value {name}"))
+                } else if is_id(&name, 'f') {
+                    Ok(dfg.import_function(function_ids.get(&name)))
+                } else if let Some(intrinsic) = Intrinsic::lookup(&name) {
+                    Ok(dfg.import_intrinsic(intrinsic))
+                } else {
+                    Ok(dfg.import_foreign_function(&name))
+                }
+            }
+            other => Err(format!("expected a value, found {}", describe(other))),
+        }
+    }
+}
+
+/// Returns true if `name` is `prefix` followed by a number, e.g. `v12` for the prefix `v`.
+fn is_id(name: &str, prefix: char) -> bool {
+    name.strip_prefix(prefix)
+        .map_or(false, |index| !index.is_empty() && index.chars().all(|c| c.is_ascii_digit()))
+}
+
+fn binary_operator(name: &str) -> BinaryOp {
+    match name {
+        "add" => BinaryOp::Add,
+        "sub" => BinaryOp::Sub,
+        "mul" => BinaryOp::Mul,
+        "div" => BinaryOp::Div,
+        "mod" => BinaryOp::Mod,
+        "eq" => BinaryOp::Eq,
+        "lt" => BinaryOp::Lt,
+        "and" => BinaryOp::And,
+        "or" => BinaryOp::Or,
+        "xor" => BinaryOp::Xor,
+        other => unreachable!("{other} is not a binary operator"),
+    }
+}
+
+/// Returns the numeric type with the given name, e.g. `Field`, `u32` or `i8`.
+fn numeric_type(name: &str) -> Option<Type> {
+    if name == "Field" {
+        return Some(Type::field());
+    }
+    let bit_size = |prefix: char| name.strip_prefix(prefix)?.parse().ok();
+    bit_size('u').map(Type::unsigned).or_else(|| bit_size('i').map(Type::signed))
+}
+
+const SUPERSCRIPT_DIGITS: [char; 10] = ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+
+/// Parses a field element in any of the forms it is displayed in, e.g. `7`, `-1`, `2⁶⁴` or
+/// `2³²×5`.
+fn parse_field_element(text: &str) -> Option<FieldElement> {
+    if let Some(positive) = text.strip_prefix('-') {
+        return parse_field_element(positive).map(|field| -field);
+    }
+    if let Some((power, factor)) = text.split_once('×') {
+        return Some(parse_field_element(power)? * FieldElement::try_from_str(factor)?);
+    }
+    match text.strip_prefix('2') {
+        Some(exponent) if !exponent.is_empty() && exponent.chars().all(is_superscript) => {
+            let exponent: String = exponent
+                .chars()
+                .map(|c| {
+                    let digit = SUPERSCRIPT_DIGITS.iter().position(|digit| *digit == c);
+                    char::from(b'0' + digit.expect("Checked to be a superscript") as u8)
+                })
+                .collect();
+            let exponent = FieldElement::try_from_str(&exponent)?;
+            Some(FieldElement::from(2u128).pow(&exponent))
+        }
+        _ => FieldElement::try_from_str(text),
+    }
+}
+
+fn is_superscript(c: char) -> bool {
+    SUPERSCRIPT_DIGITS.contains(&c)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    Number(String),
+    /// A string between single quotes, e.g. the message of a `constrain`
+    Str(String),
+    Symbol(&'static str),
+}
+
+/// Multi-character symbols come first so that they take precedence over their prefixes.
+const SYMBOLS: [&str; 13] = ["->", "==", "(", ")", "[", "]", ",", ":", ";", "=", "&", "{", "}"];
+
+fn describe(token: Option<Token>) -> String {
+    match token {
+        Some(Token::Ident(text) | Token::Number(text) | Token::Str(text)) => format!("'{text}'"),
+        Some(Token::Symbol(symbol)) => format!("'{symbol}'"),
+        None => "the end of the line".to_owned(),
+    }
+}
+
+/// The tokens of a single line of the source text.
+struct Tokens {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Tokens {
+    fn new(line: &str) -> Result<Self, String> {
+        let mut tokens = Vec::new();
+        let mut rest = line.trim_start();
+        while let Some(c) = rest.chars().next() {
+            let is_number_start = c.is_ascii_digit()
+                || c == '-' && rest[1..].starts_with(|c: char| c.is_ascii_digit());
+
+            let (token, length) = if c.is_ascii_alphabetic() || c == '_' {
+                let length = rest
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(rest.len());
+                (Token::Ident(rest[..length].to_owned()), length)
+            } else if is_number_start {
+                let length = rest[1..]
+                    .find(|c: char| !(c.is_ascii_digit() || c == '×' || is_superscript(c)))
+                    .map_or(rest.len(), |length| length + 1);
+                (Token::Number(rest[..length].to_owned()), length)
+            } else if c == '\'' {
+                let length = rest[1..].find('\'').ok_or("unterminated string")?;
+                (Token::Str(rest[1..length + 1].to_owned()), length + 2)
+            } else if let Some(symbol) = SYMBOLS.iter().find(|symbol| rest.starts_with(**symbol)) {
+                (Token::Symbol(*symbol), symbol.len())
+            } else {
+                return Err(format!("unexpected character '{c}'"));
+            };
+
+            tokens.push(token);
+            rest = rest[length..].trim_start();
+        }
+        Ok(Self { tokens, position: 0 })
+    }
+
+    fn peek_nth(&self, n: usize) -> Option<&Token> {
+        self.tokens.get(self.position + n)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn is_at_end(&self) -> bool {
+        self.position >= self.tokens.len()
+    }
+
+    /// Returns true if the line is a block header such as `b0():`
+    fn starts_block(&self) -> bool {
+        matches!(
+            (self.peek_nth(0), self.peek_nth(1)),
+            (Some(Token::Ident(name)), Some(Token::Symbol("("))) if is_id(name, 'b')
+        )
+    }
+
+    fn eat_symbol(&mut self, symbol: &str) -> bool {
+        let matches = matches!(self.peek_nth(0), Some(Token::Symbol(next)) if *next == symbol);
+        if matches {
+            self.position += 1;
+        }
+        matches
+    }
+
+    fn eat_string(&mut self) -> Option<String> {
+        match self.peek_nth(0) {
+            Some(Token::Str(text)) => {
+                let text = text.clone();
+                self.position += 1;
+                Some(text)
+            }
+            _ => None,
+        }
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> Result<(), String> {
+        if self.eat_symbol(symbol) {
+            Ok(())
+        } else {
+            Err(format!("expected '{symbol}', found {}", describe(self.peek_nth(0).cloned())))
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), String> {
+        match self.next() {
+            Some(Token::Ident(name)) if name == keyword => Ok(()),
+            other => Err(format!("expected '{keyword}', found {}", describe(other))),
+        }
+    }
+
+    fn expect_ident(&mut self) -> Result<String, String> {
+        match self.next() {
+            Some(Token::Ident(name)) => Ok(name),
+            other => Err(format!("expected an identifier, found {}", describe(other))),
+        }
+    }
+
+    fn expect_number<T: FromStr>(&mut self) -> Result<T, String> {
+        match self.next() {
+            Some(Token::Number(number)) => {
+                number.parse().map_err(|_| format!("invalid number {number}"))
+            }
+            other => Err(format!("expected a number, found {}", describe(other))),
+        }
+    }
+
+    fn expect_end(&self) -> Result<(), String> {
+        match self.peek_nth(0) {
+            None => Ok(()),
+            Some(token) => Err(format!("unexpected {}", describe(Some(token.clone())))),
+        }
+    }
+
+    /// Parses a type such as `Field`, `&mut u8`, `[Field, u32; 2]` or `[u8]`
+    fn parse_type(&mut self) -> Result<Type, String> {
+        match self.next() {
+            Some(Token::Symbol("&")) => {
+                self.expect_keyword("mut")?;
+                Ok(Type::Reference(Rc::new(self.parse_type()?)))
+            }
+            Some(Token::Symbol("[")) => {
+                let mut elements = vec![self.parse_type()?];
+                while self.eat_symbol(",") {
+                    elements.push(self.parse_type()?);
+                }
+                if self.eat_symbol(";") {
+                    let length = self.expect_number()?;
+                    self.expect_symbol("]")?;
+                    Ok(Type::Array(Rc::new(elements), length))
+                } else {
+                    self.expect_symbol("]")?;
+                    Ok(Type::Slice(Rc::new(elements)))
+                }
+            }
+            Some(Token::Ident(name)) if name == "function" => Ok(Type::Function),
+            Some(Token::Ident(name)) => {
+                numeric_type(&name).ok_or_else(|| format!("unknown type '{name}'"))
+            }
+            other => Err(format!("expected a type, found {}", describe(other))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ssa::ssa_gen::Ssa;
+
+    use super::assert_normalized_ssa_equals;
+
+    #[test]
+    fn round_trips_printed_ssa() {
+        let src = "
+            acir fn main f0 {
+              b0(v0: Field, v1: u32, v2: [Field; 2]):
+                v3 = allocate -> &mut Field
+                store v0 at v3
+                v4 = load v3 -> Field
+                v5 = add v4, Field -1
+                v6 = cast v1 as Field
+                v7 = lt v1, u32 2
+                v8 = array_get v2, index u32 0 -> Field
+                v9 = array_set v2, index u32 1, value v5
+                v10 = call f1(v9) -> Field
+                v11 = call to_le_bits(v0, u32 8) -> [u1; 8]
+                constrain v10 == Field 2⁶⁴×3 'not equal'
+                constrain v4 == v0 'expected {x}, got {y}' (v0, v4) '{\"kind\":\"fmtstring\",\"length\":22,\"type\":{\"kind\":\"tuple\",\"types\":[{\"kind\":\"field\"},{\"kind\":\"field\"}]}}'
+                range_check v1 to 16 bits
+                enable_side_effects u1 1
+                inc_rc v9
+                jmpif v7 then: b1, else: b2
+              b1():
+                v12 = truncate v1 to 8 bits, max_bit_size: 32
+                jmp b3(v12)
+              b3(v13: u32):
+                return v13, [Field 1, v0]: [Field; 2]
+              b2():
+                jmp b3(u32 0)
+            }
+            brillig fn foo f1 {
+              b0(v0: [Field; 2]):
+                call print(v0)
+                return Field 2¹²⁸
+            }
+            ";
+        let ssa: Ssa = src.parse().unwrap();
+        let main = ssa.main();
+        assert_eq!(main.reachable_blocks().len(), 4);
+        assert_eq!(main.dfg[main.entry_block()].instructions().len(), 15);
+
+        // The blocks above are written in the order they are printed in, so printing the
+        // program and parsing it again shouldn't change it
+        let printed = ssa.to_string();
+        let reparsed: Ssa = printed.parse().unwrap();
+        assert_eq!(reparsed.to_string(), printed);
+        assert_normalized_ssa_equals(ssa, src);
+    }
+
+    #[test]
+    fn does_not_simplify_instructions() {
+        let src = "
+            acir fn main f0 {
+              b0():
+                v0 = add Field 1, Field 2
+                return v0
+            }
+            ";
+        let ssa: Ssa = src.parse().unwrap();
+        let main = ssa.main();
+        assert_eq!(main.dfg[main.entry_block()].instructions().len(), 1);
+    }
+
+    #[test]
+    fn reports_the_line_of_an_error() {
+        let src = "
+            acir fn main f0 {
+              b0():
+                return v0
+            }
+            ";
+        let error = src.parse::<Ssa>().err().expect("v0 is undefined");
+        assert_eq!(error.line, 4);
+        assert_eq!(error.message, "undefined value v0");
+    }
+
+    #[test]
+    fn rejects_missing_result_types() {
+        let src = "
+            acir fn main f0 {
+              b0(v0: &mut Field):
+                v1 = load v0
+                return v1
+            }
+            ";
+        assert!(src.parse::<Ssa>().is_err());
+    }
+}
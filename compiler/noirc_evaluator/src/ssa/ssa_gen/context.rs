@@ -10,13 +10,15 @@ use noirc_frontend::{BinaryOpKind, Signedness};
 
 use crate::errors::RuntimeError;
 use crate::ssa::function_builder::FunctionBuilder;
+use crate::ssa::ir::basic_block::BasicBlockId;
 use crate::ssa::ir::dfg::DataFlowGraph;
 use crate::ssa::ir::function::FunctionId as IrFunctionId;
 use crate::ssa::ir::function::{Function, RuntimeType};
 use crate::ssa::ir::instruction::BinaryOp;
 use crate::ssa::ir::instruction::Instruction;
+use crate::ssa::ir::instruction::Intrinsic;
 use crate::ssa::ir::map::AtomicCounter;
-use crate::ssa::ir::types::{NumericType, Type};
+use crate::ssa::ir::types::{power_of_two, NumericType, Type};
 use crate::ssa::ir::value::ValueId;
 
 use super::value::{Tree, Value, Values};
@@ -38,6 +40,21 @@ pub(super) struct FunctionContext<'a> {
 
     pub(super) builder: FunctionBuilder,
     shared_context: &'a SharedContext,
+
+    /// The loops surrounding the expression currently being compiled, innermost last.
+    loops: Vec<Loop>,
+
+    /// The `#[max_iterations(N)]` bound of the current function, used to unroll constrained
+    /// loops whose range isn't known at compile-time.
+    pub(super) max_iterations: Option<u64>,
+}
+
+/// The blocks a `break` or `continue` within a loop jump to.
+#[derive(Debug, Copy, Clone)]
+pub(super) struct Loop {
+    /// Jumps back to the start of the loop, incrementing the index of a for loop first.
+    pub(super) loop_continue: BasicBlockId,
+    pub(super) loop_end: BasicBlockId,
 }
 
 /// Shared context for all functions during ssa codegen. This is the only
@@ -96,7 +113,13 @@ impl<'a> FunctionContext<'a> {
             .1;
 
         let builder = FunctionBuilder::new(function_name, function_id, runtime);
-        let mut this = Self { definitions: HashMap::default(), builder, shared_context };
+        let mut this = Self {
+            definitions: HashMap::default(),
+            builder,
+            shared_context,
+            loops: Vec::new(),
+            max_iterations: None,
+        };
         this.add_parameters_to_scope(parameters);
         this
     }
@@ -113,6 +136,9 @@ impl<'a> FunctionContext<'a> {
         } else {
             self.builder.new_function(func.name.clone(), id);
         }
+        self.builder.current_function.set_constant_time(func.constant_time);
+        self.builder.current_function.set_inline_type(func.inline_type);
+        self.max_iterations = func.max_iterations;
         self.add_parameters_to_scope(&func.parameters);
     }
 
@@ -149,6 +175,18 @@ impl<'a> FunctionContext<'a> {
         self.definitions.insert(parameter_id, parameter_value);
     }
 
+    pub(super) fn enter_loop(&mut self, loop_continue: BasicBlockId, loop_end: BasicBlockId) {
+        self.loops.push(Loop { loop_continue, loop_end });
+    }
+
+    pub(super) fn exit_loop(&mut self) {
+        self.loops.pop();
+    }
+
+    pub(super) fn current_loop(&self) -> Loop {
+        *self.loops.last().expect("Expected `break` or `continue` to be within a loop")
+    }
+
     /// Allocate a single slot of memory and store into it the given initial value of the variable.
     /// Always returns a Value::Mutable wrapping the allocate instruction.
     pub(super) fn new_mutable_variable(&mut self, value_to_store: ValueId) -> Value {
@@ -273,8 +311,7 @@ impl<'a> FunctionContext<'a> {
         assert_eq!(self.builder.type_of_value(sign), Type::bool());
 
         // We compute the absolute value of lhs
-        let bit_width =
-            self.builder.numeric_constant(FieldElement::from(2_i128.pow(bit_size)), Type::field());
+        let bit_width = self.builder.numeric_constant(power_of_two(bit_size), Type::field());
         let sign_not = self.builder.insert_not(sign);
 
         // We use unsafe casts here, this is fine as we're casting to a `field` type.
@@ -321,17 +358,19 @@ impl<'a> FunctionContext<'a> {
                         self.insert_safe_cast(result, result_type, location)
                     }
                     BinaryOpKind::Multiply => {
-                        // Result is computed modulo the bit size
-                        let mut result =
-                            self.builder.insert_cast(result, Type::unsigned(2 * bit_size));
-                        result = self.builder.insert_truncate(result, bit_size, 2 * bit_size);
-
-                        self.check_signed_overflow(result, lhs, rhs, operator, bit_size, location);
+                        // The product of the operands may not fit in the field, so the result is
+                        // rebuilt from the product of their absolute values
+                        let result = self
+                            .check_signed_overflow(result, lhs, rhs, operator, bit_size, location);
                         self.insert_safe_cast(result, result_type, location)
                     }
                     BinaryOpKind::ShiftLeft | BinaryOpKind::ShiftRight => {
                         self.check_shift_overflow(result, rhs, bit_size, location, true)
                     }
+                    BinaryOpKind::Divide => {
+                        self.check_signed_division_overflow(lhs, rhs, bit_size, location);
+                        result
+                    }
                     _ => unreachable!("operator {} should not overflow", operator),
                 }
             }
@@ -376,6 +415,7 @@ impl<'a> FunctionContext<'a> {
                             return result;
                         }
 
+                        self.check_product_fits_in_field(lhs, rhs, bit_size, location);
                         let message = "attempt to multiply with overflow".to_string();
                         self.builder.set_location(location).insert_range_check(
                             result,
@@ -396,6 +436,8 @@ impl<'a> FunctionContext<'a> {
 
                         self.check_shift_overflow(result, rhs, bit_size, location, false);
                     }
+                    // Unsigned division cannot overflow
+                    BinaryOpKind::Divide => (),
 
                     _ => unreachable!("operator {} should not overflow", operator),
                 }
@@ -406,6 +448,31 @@ impl<'a> FunctionContext<'a> {
         }
     }
 
+    /// Insert constraints ensuring that the division of `lhs` by `rhs`, signed integers of
+    /// `bit_size` bits, doesn't overflow. The only overflowing division is that of the smallest
+    /// integer by -1, whose result `2^{bit_size-1}` is one more than the largest integer.
+    fn check_signed_division_overflow(
+        &mut self,
+        lhs: ValueId,
+        rhs: ValueId,
+        bit_size: u32,
+        location: Location,
+    ) {
+        let typ = Type::signed(bit_size);
+        let min = self.builder.numeric_constant(power_of_two(bit_size - 1), typ.clone());
+        let minus_one =
+            self.builder.numeric_constant(power_of_two(bit_size) - FieldElement::one(), typ);
+        let lhs_is_min = self.builder.insert_binary(lhs, BinaryOp::Eq, min);
+        let rhs_is_minus_one = self.builder.insert_binary(rhs, BinaryOp::Eq, minus_one);
+        let overflow = self.builder.insert_binary(lhs_is_min, BinaryOp::Mul, rhs_is_minus_one);
+        let zero = self.builder.numeric_constant(FieldElement::zero(), Type::bool());
+        self.builder.set_location(location).insert_constrain(
+            overflow,
+            zero,
+            Some("attempt to divide with overflow".to_owned().into()),
+        );
+    }
+
     /// Overflow checks for bit-shift
     /// We use Rust behavior for bit-shift:
     /// If rhs is more or equal than the bit size, then we overflow
@@ -427,15 +494,13 @@ impl<'a> FunctionContext<'a> {
         // Bit-shift with a negative number is an overflow
         if is_signed {
             // We compute the sign of rhs.
-            let half_width = self.builder.numeric_constant(
-                FieldElement::from(2_i128.pow(bit_size - 1)),
-                Type::unsigned(bit_size),
-            );
+            let half_width =
+                self.builder.numeric_constant(power_of_two(bit_size - 1), Type::unsigned(bit_size));
             let sign = self.builder.insert_binary(rhs, BinaryOp::Lt, half_width);
             self.builder.set_location(location).insert_constrain(
                 sign,
                 one,
-                Some("attempt to bit-shift with overflow".to_string()),
+                Some("attempt to bit-shift with overflow".to_string().into()),
             );
         }
 
@@ -446,11 +511,49 @@ impl<'a> FunctionContext<'a> {
         self.builder.set_location(location).insert_constrain(
             overflow,
             one,
-            Some("attempt to bit-shift with overflow".to_owned()),
+            Some("attempt to bit-shift with overflow".to_owned().into()),
         );
         self.builder.insert_truncate(result, bit_size, bit_size + 1)
     }
 
+    /// Insert constraints ensuring that the product of `lhs` and `rhs`, unsigned integers of at most
+    /// `bit_size` bits, doesn't wrap around the field modulus, so that range checking it against
+    /// `bit_size` detects any overflow.
+    ///
+    /// If both operands are at least `2^{half_bit_size}` then their product overflows `bit_size`
+    /// bits, otherwise it is less than `2^{bit_size + half_bit_size}` which fits in the field.
+    fn check_product_fits_in_field(
+        &mut self,
+        lhs: ValueId,
+        rhs: ValueId,
+        bit_size: u32,
+        location: Location,
+    ) {
+        let dfg = &self.builder.current_function.dfg;
+        let max_lhs_bits = std::cmp::min(dfg.get_value_max_num_bits(lhs), bit_size);
+        let max_rhs_bits = std::cmp::min(dfg.get_value_max_num_bits(rhs), bit_size);
+        if max_lhs_bits + max_rhs_bits < FieldElement::max_num_bits() {
+            return;
+        }
+
+        let typ = Type::unsigned(bit_size);
+        let half_bit_size = (bit_size + 1) / 2;
+        let half_width = self.builder.numeric_constant(power_of_two(half_bit_size), typ.clone());
+        // The operands may be fields holding integers of `bit_size` bits
+        let lhs = self.builder.insert_cast(lhs, typ.clone());
+        let rhs = self.builder.insert_cast(rhs, typ.clone());
+        let lhs_high = self.builder.insert_binary(lhs, BinaryOp::Div, half_width);
+        let rhs_high = self.builder.insert_binary(rhs, BinaryOp::Div, half_width);
+        // Both high halves are less than `2^{half_bit_size}` so their product can't overflow
+        let high_product = self.builder.insert_binary(lhs_high, BinaryOp::Mul, rhs_high);
+        let zero = self.builder.numeric_constant(FieldElement::zero(), typ);
+        self.builder.set_location(location).insert_constrain(
+            high_product,
+            zero,
+            Some("attempt to multiply with overflow".to_string().into()),
+        );
+    }
+
     /// Insert constraints ensuring that the operation does not overflow the bit size of the result
     /// We assume that:
     /// lhs and rhs are signed integers of bit size bit_size
@@ -463,6 +566,10 @@ impl<'a> FunctionContext<'a> {
     ///             different sign => no overflow
     /// multiplication:     we check that the product of the operands' absolute values does not overflow the bit size
     ///                     then we check that the result has the proper sign, using the rule of signs
+    ///
+    /// Returns `result` for additions and subtractions. For multiplications, `result` is not used
+    /// and the unsigned integer representing the product is returned instead, as it is computed
+    /// from the absolute values of the operands.
     fn check_signed_overflow(
         &mut self,
         result: ValueId,
@@ -471,12 +578,10 @@ impl<'a> FunctionContext<'a> {
         operator: BinaryOpKind,
         bit_size: u32,
         location: Location,
-    ) {
+    ) -> ValueId {
         let is_sub = operator == BinaryOpKind::Subtract;
-        let half_width = self.builder.numeric_constant(
-            FieldElement::from(2_i128.pow(bit_size - 1)),
-            Type::unsigned(bit_size),
-        );
+        let half_width =
+            self.builder.numeric_constant(power_of_two(bit_size - 1), Type::unsigned(bit_size));
         // We compute the sign of the operands. The overflow checks for signed integers depends on these signs
         let lhs_as_unsigned = self.insert_safe_cast(lhs, Type::unsigned(bit_size), location);
         let rhs_as_unsigned = self.insert_safe_cast(rhs, Type::unsigned(bit_size), location);
@@ -498,15 +603,20 @@ impl<'a> FunctionContext<'a> {
                 let sign_diff = self.builder.insert_binary(result_sign, BinaryOp::Eq, lhs_sign);
                 let sign_diff_with_predicate =
                     self.builder.insert_binary(sign_diff, BinaryOp::Mul, same_sign);
-                let overflow_check =
-                    Instruction::Constrain(sign_diff_with_predicate, same_sign, Some(message));
+                let overflow_check = Instruction::Constrain(
+                    sign_diff_with_predicate,
+                    same_sign,
+                    Some(message.into()),
+                );
                 self.builder.set_location(location).insert_instruction(overflow_check, None);
+                result
             }
             BinaryOpKind::Multiply => {
                 // Overflow check for the multiplication:
                 // First we compute the absolute value of operands, and their product
                 let lhs_abs = self.absolute_value_helper(lhs, lhs_sign, bit_size);
                 let rhs_abs = self.absolute_value_helper(rhs, rhs_sign, bit_size);
+                self.check_product_fits_in_field(lhs_abs, rhs_abs, bit_size, location);
                 let product_field = self.builder.insert_binary(lhs_abs, BinaryOp::Mul, rhs_abs);
                 // It must not already overflow the bit_size
                 let message = "attempt to multiply with overflow".to_string();
@@ -530,8 +640,20 @@ impl<'a> FunctionContext<'a> {
                 self.builder.set_location(location).insert_constrain(
                     product_overflow_check,
                     one,
-                    Some(message),
+                    Some(message.into()),
                 );
+
+                // The result is the product if the operands have the same sign, and its two's
+                // complement otherwise, which is truncated for a zero product.
+                let bit_width =
+                    self.builder.numeric_constant(power_of_two(bit_size), Type::field());
+                let negated = self.builder.insert_binary(bit_width, BinaryOp::Sub, product_field);
+                let difference = self.builder.insert_binary(negated, BinaryOp::Sub, product_field);
+                let not_same_field = self.builder.insert_cast(not_same, Type::field());
+                let offset = self.builder.insert_binary(not_same_field, BinaryOp::Mul, difference);
+                let result = self.builder.insert_binary(product_field, BinaryOp::Add, offset);
+                let result = self.builder.insert_truncate(result, bit_size, bit_size + 1);
+                self.builder.insert_cast(result, Type::unsigned(bit_size))
             }
             _ => unreachable!("operator {} should not overflow", operator),
         }
@@ -587,6 +709,7 @@ impl<'a> FunctionContext<'a> {
             BinaryOpKind::Add
                 | BinaryOpKind::Subtract
                 | BinaryOpKind::Multiply
+                | BinaryOpKind::Divide
                 | BinaryOpKind::ShiftLeft
         ) {
             result = self.check_overflow(result, lhs, rhs, operator, location);
@@ -620,6 +743,9 @@ impl<'a> FunctionContext<'a> {
     ///   jmp loop_start(v6)
     /// loop_end():
     ///   result = load result_alloc
+    ///
+    /// Arrays of bytes, which includes strings, are instead compared with a single call to the
+    /// `str_eq` intrinsic so that the backends can compare several bytes at once.
     fn insert_array_equality(
         &mut self,
         lhs: ValueId,
@@ -646,6 +772,20 @@ impl<'a> FunctionContext<'a> {
             _ => unreachable!("Expected two array values"),
         };
 
+        if element_type == Type::unsigned(8) {
+            let str_eq = self.builder.import_intrinsic_id(Intrinsic::StrEq);
+            let mut result = self.builder.set_location(location).insert_call(
+                str_eq,
+                vec![lhs, rhs],
+                vec![Type::bool()],
+            )[0];
+
+            if operator_requires_not(operator) {
+                result = self.builder.insert_not(result);
+            }
+            return result.into();
+        }
+
         let loop_start = self.builder.insert_block();
         let loop_body = self.builder.insert_block();
         let loop_end = self.builder.insert_block();
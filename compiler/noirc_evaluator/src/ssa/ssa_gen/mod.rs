@@ -29,7 +29,7 @@ use super::{
     function_builder::data_bus::DataBus,
     ir::{
         function::RuntimeType,
-        instruction::{BinaryOp, TerminatorInstruction},
+        instruction::{AssertMessage, BinaryOp, TerminatorInstruction},
         types::Type,
         value::ValueId,
     },
@@ -41,6 +41,7 @@ use super::{
 pub(crate) fn generate_ssa(program: Program) -> Result<Ssa, RuntimeError> {
     // see which parameter has call_data/return_data attribute
     let is_databus = DataBusBuilder::is_databus(&program.main_function_signature);
+    let is_public = DataBusBuilder::is_public(&program.main_function_signature);
 
     let is_return_data = matches!(program.return_visibility, Visibility::DataBus);
 
@@ -59,6 +60,18 @@ pub(crate) fn generate_ssa(program: Program) -> Result<Ssa, RuntimeError> {
         if main.unconstrained { RuntimeType::Brillig } else { RuntimeType::Acir },
         &context,
     );
+    function_context.builder.current_function.set_constant_time(main.constant_time);
+
+    let entry_block = function_context.builder.current_function.entry_block();
+    let parameters = function_context.builder.current_function.dfg[entry_block].parameters();
+    let public_parameters = parameters
+        .iter()
+        .zip(is_public)
+        .filter_map(|(parameter, is_public)| is_public.then_some(*parameter))
+        .collect();
+    function_context.builder.current_function.set_public_parameters(public_parameters);
+    function_context.builder.current_function.set_inline_type(main.inline_type);
+    function_context.max_iterations = main.max_iterations;
 
     // Generate the call_data bus from the relevant parameters. We create it *before* processing the function body
     let call_data = function_context.builder.call_data_bus(is_databus);
@@ -133,6 +146,7 @@ impl<'a> FunctionContext<'a> {
             Expression::Index(index) => self.codegen_index(index),
             Expression::Cast(cast) => self.codegen_cast(cast),
             Expression::For(for_expr) => self.codegen_for(for_expr),
+            Expression::Loop(block) => self.codegen_loop(block),
             Expression::If(if_expr) => self.codegen_if(if_expr),
             Expression::Tuple(tuple) => self.codegen_tuple(tuple),
             Expression::ExtractTupleField(tuple, index) => {
@@ -141,10 +155,12 @@ impl<'a> FunctionContext<'a> {
             Expression::Call(call) => self.codegen_call(call),
             Expression::Let(let_expr) => self.codegen_let(let_expr),
             Expression::Constrain(expr, location, assert_message) => {
-                self.codegen_constrain(expr, *location, assert_message.clone())
+                self.codegen_constrain(expr, *location, assert_message)
             }
             Expression::Assign(assign) => self.codegen_assign(assign),
             Expression::Semi(semi) => self.codegen_semi(semi),
+            Expression::Break => Ok(self.codegen_break()),
+            Expression::Continue => Ok(self.codegen_continue()),
         }
     }
 
@@ -233,7 +249,8 @@ impl<'a> FunctionContext<'a> {
         self.codegen_array(elements, typ)
     }
 
-    // Codegen an array but make sure that we do not have a nested slice
+    // Codegen an array but make sure that we do not have a nested slice, or a slice of
+    // references within a constrained function as these cannot be merged when flattening.
     fn codegen_array_checked(
         &mut self,
         elements: Vec<Values>,
@@ -242,6 +259,13 @@ impl<'a> FunctionContext<'a> {
         if typ.is_nested_slice() {
             return Err(RuntimeError::NestedSlice { call_stack: self.builder.get_call_stack() });
         }
+        if matches!(typ, Type::Slice(_))
+            && typ.contains_reference()
+            && self.builder.current_function.runtime() == RuntimeType::Acir
+        {
+            let call_stack = self.builder.get_call_stack();
+            return Err(RuntimeError::SliceOfReferences { call_stack });
+        }
         Ok(self.codegen_array(elements, typ))
     }
 
@@ -441,7 +465,7 @@ impl<'a> FunctionContext<'a> {
         self.builder.insert_constrain(
             is_offset_out_of_bounds,
             true_const,
-            Some("Index out of bounds".to_owned()),
+            Some("Index out of bounds".to_owned().into()),
         );
     }
 
@@ -465,18 +489,35 @@ impl<'a> FunctionContext<'a> {
     ///   brif v2, then: loop_body, else: loop_end
     /// loop_body():
     ///   v3 = ... codegen body ...
+    ///   br loop_continue()
+    /// loop_continue():
     ///   v4 = add 1, i
     ///   br loop_entry(v4)
     /// loop_end():
     ///   ... This is the current insert point after codegen_for finishes ...
+    ///
+    /// If the range isn't known at compile-time in a constrained function with a
+    /// `#[max_iterations(N)]` attribute, the loop instead runs from 0 to N so that it can
+    /// still be unrolled. Each iteration checks it is still within the range:
+    ///
+    /// loop_body():
+    ///   v3 = add v0, k
+    ///   v4 = lt v3, v1
+    ///   brif v4, then: loop_in_range, else: loop_continue
+    /// loop_in_range():
+    ///   v5 = ... codegen body using v3 as the index ...
+    ///   br loop_continue()
+    ///
+    /// and after the loop finishes, `v0 + N < v1` is constrained to be false.
     fn codegen_for(&mut self, for_expr: &ast::For) -> Result<Values, RuntimeError> {
         let loop_entry = self.builder.insert_block();
         let loop_body = self.builder.insert_block();
+        let loop_continue = self.builder.insert_block();
         let loop_end = self.builder.insert_block();
 
         // this is the 'i' in `for i in start .. end { block }`
         let index_type = Self::convert_non_tuple_type(&for_expr.index_type);
-        let loop_index = self.builder.add_block_parameter(loop_entry, index_type);
+        let loop_index = self.builder.add_block_parameter(loop_entry, index_type.clone());
 
         self.builder.set_location(for_expr.start_range_location);
         let start_index = self.codegen_non_tuple_expression(&for_expr.start_range)?;
@@ -484,10 +525,21 @@ impl<'a> FunctionContext<'a> {
         self.builder.set_location(for_expr.end_range_location);
         let end_index = self.codegen_non_tuple_expression(&for_expr.end_range)?;
 
+        // A loop with a bounded number of iterations instead iterates over `0..max_iterations`,
+        // offsetting the loop index by the start of the range in each iteration.
+        let max_iterations = self.max_loop_iterations(start_index, end_index);
+        let (entry_start, entry_end) = match max_iterations {
+            Some(max_iterations) => (
+                self.builder.numeric_constant(0u128, index_type.clone()),
+                self.builder.numeric_constant(max_iterations as u128, index_type.clone()),
+            ),
+            None => (start_index, end_index),
+        };
+
         // Set the location of the initial jmp instruction to the start range. This is the location
         // used to issue an error if the start range cannot be determined at compile-time.
         self.builder.set_location(for_expr.start_range_location);
-        self.builder.terminate_with_jmp(loop_entry, vec![start_index]);
+        self.builder.terminate_with_jmp(loop_entry, vec![entry_start]);
 
         // Compile the loop entry block
         self.builder.switch_to_block(loop_entry);
@@ -496,17 +548,92 @@ impl<'a> FunctionContext<'a> {
         // end range. These are the instructions used to issue an error if the end of the range
         // cannot be determined at compile-time.
         self.builder.set_location(for_expr.end_range_location);
-        let jump_condition = self.builder.insert_binary(loop_index, BinaryOp::Lt, end_index);
+        let jump_condition = self.builder.insert_binary(loop_index, BinaryOp::Lt, entry_end);
         self.builder.terminate_with_jmpif(jump_condition, loop_body, loop_end);
 
         // Compile the loop body
         self.builder.switch_to_block(loop_body);
-        self.define(for_expr.index_variable, loop_index.into());
+        let index = match max_iterations {
+            Some(_) => {
+                // Only the iterations within the range execute the body
+                let index = self.builder.insert_binary(start_index, BinaryOp::Add, loop_index);
+                let in_range = self.builder.insert_binary(index, BinaryOp::Lt, end_index);
+                let loop_in_range = self.builder.insert_block();
+                self.builder.terminate_with_jmpif(in_range, loop_in_range, loop_continue);
+                self.builder.switch_to_block(loop_in_range);
+                index
+            }
+            None => loop_index,
+        };
+        self.define(for_expr.index_variable, index.into());
+
+        self.enter_loop(loop_continue, loop_end);
         self.codegen_expression(&for_expr.block)?;
+        self.exit_loop();
+        self.builder.terminate_with_jmp(loop_continue, vec![]);
+
+        // Each `continue` jumps to the same block so that the loop has a single back-edge
+        self.builder.switch_to_block(loop_continue);
         let new_loop_index = self.make_offset(loop_index, 1);
         self.builder.terminate_with_jmp(loop_entry, vec![new_loop_index]);
 
         // Finish by switching back to the end of the loop
+        self.builder.switch_to_block(loop_end);
+
+        if let Some(max_iterations) = max_iterations {
+            // The iterations past the bound were never executed, so the range must fit within it
+            self.builder.set_location(for_expr.end_range_location);
+            let bound = self.builder.numeric_constant(max_iterations as u128, index_type);
+            let last_index = self.builder.insert_binary(start_index, BinaryOp::Add, bound);
+            let exceeded = self.builder.insert_binary(last_index, BinaryOp::Lt, end_index);
+            let false_value = self.builder.numeric_constant(0u128, Type::bool());
+            let message = format!("Loop exceeded its maximum of {max_iterations} iterations");
+            self.builder.insert_constrain(exceeded, false_value, Some(message.into()));
+        }
+
+        Ok(Self::unit_value())
+    }
+
+    /// Returns the bound given by `#[max_iterations(N)]` if the current function is
+    /// constrained and the range of a loop from `start` to `end` isn't known yet.
+    ///
+    /// Such loops are unrolled for N iterations with only the iterations in range
+    /// executing the loop body.
+    fn max_loop_iterations(&self, start: ValueId, end: ValueId) -> Option<u64> {
+        let function = &self.builder.current_function;
+        let is_constant = |value| function.dfg.get_numeric_constant(value).is_some();
+        let is_known_range = is_constant(start) && is_constant(end);
+        self.max_iterations.filter(|_| function.runtime() == RuntimeType::Acir && !is_known_range)
+    }
+
+    /// Codegens a loop without a range, which only exits through a `break`:
+    ///
+    ///   br loop_body()
+    /// loop_body():
+    ///   v0 = ... codegen body ...
+    ///   br loop_continue()
+    /// loop_continue():
+    ///   br loop_body()
+    /// loop_end():
+    ///   ... This is the current insert point after codegen_loop finishes ...
+    fn codegen_loop(&mut self, block: &Expression) -> Result<Values, RuntimeError> {
+        let loop_body = self.builder.insert_block();
+        let loop_continue = self.builder.insert_block();
+        let loop_end = self.builder.insert_block();
+
+        self.builder.terminate_with_jmp(loop_body, vec![]);
+        self.builder.switch_to_block(loop_body);
+
+        self.enter_loop(loop_continue, loop_end);
+        self.codegen_expression(block)?;
+        self.exit_loop();
+        self.builder.terminate_with_jmp(loop_continue, vec![]);
+
+        // As with for loops, `continue` jumps to a separate block so that the loop only has a
+        // single back-edge.
+        self.builder.switch_to_block(loop_continue);
+        self.builder.terminate_with_jmp(loop_body, vec![]);
+
         self.builder.switch_to_block(loop_end);
         Ok(Self::unit_value())
     }
@@ -599,9 +726,14 @@ impl<'a> FunctionContext<'a> {
             arguments.append(&mut values);
         }
 
-        // If an array is passed as an argument we increase its reference count
-        for argument in &arguments {
-            self.builder.increment_array_reference_count(*argument);
+        // If an array is passed as an argument we increase its reference count.
+        // A swap replaces its array in the same way as an array set, which already copies the
+        // array when the array is shared, so its array is left as is.
+        let is_swap = self.builder.get_intrinsic_from_value(function) == Some(Intrinsic::ArraySwap);
+        for (index, argument) in arguments.iter().enumerate() {
+            if !(is_swap && index == 0) {
+                self.builder.increment_array_reference_count(*argument);
+            }
         }
 
         self.codegen_intrinsic_call_checks(function, &arguments, call.location);
@@ -665,15 +797,39 @@ impl<'a> FunctionContext<'a> {
         &mut self,
         expr: &Expression,
         location: Location,
-        assert_message: Option<String>,
+        assert_message: &Option<Box<(Expression, Option<String>)>>,
     ) -> Result<Values, RuntimeError> {
         let expr = self.codegen_non_tuple_expression(expr)?;
         let true_literal = self.builder.numeric_constant(true, Type::bool());
+        let assert_message = match assert_message.as_deref() {
+            Some((assert_message, printable_type)) => {
+                Some(self.codegen_assert_message(assert_message, printable_type)?)
+            }
+            None => None,
+        };
         self.builder.set_location(location).insert_constrain(expr, true_literal, assert_message);
 
         Ok(Self::unit_value())
     }
 
+    /// The message of an assertion is either a string, or a format string whose fields are
+    /// formatted into the message when the assertion fails, as described by its `printable_type`.
+    fn codegen_assert_message(
+        &mut self,
+        assert_message: &Expression,
+        printable_type: &Option<String>,
+    ) -> Result<AssertMessage, RuntimeError> {
+        match assert_message {
+            Expression::Literal(ast::Literal::Str(message)) => Ok(message.clone().into()),
+            Expression::Literal(ast::Literal::FmtStr(message, _, fields)) => {
+                let values = self.codegen_expression(fields)?.into_value_list(self);
+                let printable_type = printable_type.clone();
+                Ok(AssertMessage { message: message.clone(), values, printable_type })
+            }
+            _ => unreachable!("ICE: assertion messages are always string literals"),
+        }
+    }
+
     fn codegen_assign(&mut self, assign: &ast::Assign) -> Result<Values, RuntimeError> {
         let lhs = self.extract_current_value(&assign.lvalue)?;
         let rhs = self.codegen_expression(&assign.expression)?;
@@ -686,4 +842,25 @@ impl<'a> FunctionContext<'a> {
         self.codegen_expression(expr)?;
         Ok(Self::unit_value())
     }
+
+    /// Only unconstrained functions contain a `break` or `continue`, these are removed from
+    /// constrained functions during monomorphization. Any code following one is compiled into
+    /// a fresh block which is never reached.
+    fn codegen_break(&mut self) -> Values {
+        let loop_end = self.current_loop().loop_end;
+        self.builder.terminate_with_jmp(loop_end, vec![]);
+
+        let unreachable_block = self.builder.insert_block();
+        self.builder.switch_to_block(unreachable_block);
+        Self::unit_value()
+    }
+
+    fn codegen_continue(&mut self) -> Values {
+        let loop_continue = self.current_loop().loop_continue;
+        self.builder.terminate_with_jmp(loop_continue, vec![]);
+
+        let unreachable_block = self.builder.insert_block();
+        self.builder.switch_to_block(unreachable_block);
+        Self::unit_value()
+    }
 }
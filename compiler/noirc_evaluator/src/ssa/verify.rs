@@ -0,0 +1,658 @@
+//! The SSA verifier checks that a program satisfies the invariants which the optimization passes,
+//! ACIR generation and Brillig generation rely upon.
+//!
+//! A pass which breaks one of these invariants usually only causes a panic much later on, far
+//! away from the pass responsible. Compiling with `--verify-ssa` runs the verifier after every
+//! pass so that the first pass to produce invalid SSA is reported instead.
+//!
+//! The verifier checks that:
+//! - every value is defined before it is used, that is that its definition dominates its uses,
+//! - the operands of instructions have the types expected of them,
+//! - every reachable block ends in a terminator which passes the right values to its successors,
+//! - array and slice values agree with the size of their element types.
+use fxhash::FxHashMap as HashMap;
+
+use crate::errors::{InternalError, RuntimeError};
+
+use super::{
+    ir::{
+        basic_block::BasicBlockId,
+        dfg::{CallStack, DataFlowGraph},
+        dom::DominatorTree,
+        function::Function,
+        instruction::{Instruction, InstructionId, Intrinsic, TerminatorInstruction},
+        types::Type,
+        value::{Value, ValueId},
+    },
+    ssa_gen::Ssa,
+};
+
+/// Checks that each function of `ssa` is well-formed, returning an error describing the first
+/// problem found otherwise.
+pub(crate) fn verify(ssa: &Ssa) -> Result<(), RuntimeError> {
+    for function in ssa.functions.values() {
+        let mut verifier = FunctionVerifier::new(ssa, function);
+        if let Err(message) = verifier.verify() {
+            let message =
+                format!("Invalid SSA in function {} {}: {message}", function.name(), function.id());
+            return Err(InternalError::General { message, call_stack: verifier.call_stack }.into());
+        }
+    }
+    Ok(())
+}
+
+struct FunctionVerifier<'f> {
+    ssa: &'f Ssa,
+    function: &'f Function,
+    dom: DominatorTree,
+    /// The block containing each instruction of the function, along with its index in the block.
+    instruction_positions: HashMap<InstructionId, (BasicBlockId, usize)>,
+    /// The number of values returned by the first return terminator found in the function.
+    return_count: Option<usize>,
+    /// The call stack of the instruction being checked, used to locate any problem found.
+    call_stack: CallStack,
+}
+
+impl<'f> FunctionVerifier<'f> {
+    fn new(ssa: &'f Ssa, function: &'f Function) -> Self {
+        FunctionVerifier {
+            ssa,
+            function,
+            dom: DominatorTree::with_function(function),
+            instruction_positions: HashMap::default(),
+            return_count: None,
+            call_stack: CallStack::new(),
+        }
+    }
+
+    fn verify(&mut self) -> Result<(), String> {
+        let function = self.function;
+        let blocks = function.reachable_blocks();
+        for block in &blocks {
+            for (index, instruction) in function.dfg[*block].instructions().iter().enumerate() {
+                if self.instruction_positions.insert(*instruction, (*block, index)).is_some() {
+                    return Err(format!("an instruction of block {block} appears more than once"));
+                }
+            }
+        }
+
+        for block in blocks {
+            self.verify_block(block).map_err(|message| format!("in block {block}, {message}"))?;
+        }
+        Ok(())
+    }
+
+    fn verify_block(&mut self, block: BasicBlockId) -> Result<(), String> {
+        let function = self.function;
+        let dfg = &function.dfg;
+        let instructions = dfg[block].instructions();
+        for (index, instruction) in instructions.iter().enumerate() {
+            self.call_stack = dfg.get_call_stack(*instruction);
+            self.verify_instruction(*instruction, block, index)?;
+        }
+
+        self.call_stack = CallStack::new();
+        let terminator = dfg[block].terminator().ok_or("the block has no terminator")?;
+        self.call_stack = match terminator {
+            TerminatorInstruction::Jmp { call_stack, .. }
+            | TerminatorInstruction::Return { call_stack, .. } => call_stack.clone(),
+            TerminatorInstruction::JmpIf { .. } => CallStack::new(),
+        };
+        let mut operands = Vec::new();
+        terminator.for_each_value(|value| operands.push(value));
+        for operand in operands {
+            self.verify_use(operand, block, instructions.len())?;
+        }
+        self.verify_terminator(terminator)
+    }
+
+    fn verify_instruction(
+        &mut self,
+        id: InstructionId,
+        block: BasicBlockId,
+        index: usize,
+    ) -> Result<(), String> {
+        let function = self.function;
+        let dfg = &function.dfg;
+        let instruction = &dfg[id];
+
+        let mut operands = Vec::new();
+        instruction.for_each_value(|value| operands.push(value));
+        for operand in operands {
+            self.verify_use(operand, block, index)?;
+        }
+
+        let results = dfg.instruction_results(id);
+        for (position, result) in results.iter().enumerate() {
+            match &dfg[*result] {
+                Value::Instruction { instruction, position: result_position, .. }
+                    if *instruction == id && *result_position == position => {}
+                _ => {
+                    return Err(format!(
+                        "{result} is listed as a result of an instruction not defining it"
+                    ))
+                }
+            }
+        }
+
+        self.verify_types(instruction, results)
+    }
+
+    /// Checks that the definition of `value` dominates its use by the instruction at `index` of
+    /// `block`, where an index past the last instruction stands for the block's terminator.
+    fn verify_use(
+        &mut self,
+        value: ValueId,
+        block: BasicBlockId,
+        index: usize,
+    ) -> Result<(), String> {
+        let function = self.function;
+        let dfg = &function.dfg;
+        let value = dfg.resolve(value);
+        match &dfg[value] {
+            Value::Instruction { instruction, .. } => {
+                let Some(&(definition_block, definition_index)) =
+                    self.instruction_positions.get(instruction)
+                else {
+                    return Err(format!(
+                        "{value} is defined by an instruction which is no longer in the function"
+                    ));
+                };
+                let dominates = if definition_block == block {
+                    definition_index < index
+                } else {
+                    self.dom.dominates(definition_block, block)
+                };
+                if !dominates {
+                    return Err(format!("{value} is used before it is defined"));
+                }
+            }
+            Value::Param { block: definition_block, .. } => {
+                if !dfg[*definition_block].parameters().contains(&value) {
+                    return Err(format!(
+                        "{value} is no longer a parameter of block {definition_block}"
+                    ));
+                }
+                if !self.dom.is_reachable(*definition_block)
+                    || !self.dom.dominates(*definition_block, block)
+                {
+                    return Err(format!(
+                        "{value} is a parameter of block {definition_block}, \
+                        which does not dominate its use"
+                    ));
+                }
+            }
+            Value::Array { array, typ } => {
+                verify_array_elements(value, array.len(), typ)?;
+                let element_types = element_types(typ);
+                for (index_in_array, element) in array.iter().enumerate() {
+                    let expected = &element_types[index_in_array % element_types.len()];
+                    if !agrees_with(dfg, *element, expected) {
+                        return Err(format!(
+                            "element {index_in_array} of array {value} should have type {expected}"
+                        ));
+                    }
+                    self.verify_use(*element, block, index)?;
+                }
+            }
+            Value::Function(function) => {
+                if !self.ssa.functions.contains_key(function) {
+                    return Err(format!(
+                        "{value} refers to function {function} which doesn't exist"
+                    ));
+                }
+            }
+            Value::NumericConstant { .. } | Value::Intrinsic(_) | Value::ForeignFunction(_) => (),
+        }
+        Ok(())
+    }
+
+    /// Checks that the operands and results of `instruction` have the types it expects.
+    fn verify_types(&self, instruction: &Instruction, results: &[ValueId]) -> Result<(), String> {
+        let dfg = &self.function.dfg;
+        let type_of = |value: ValueId| dfg.type_of_value(dfg.resolve(value));
+        let expect_numeric = |value: ValueId| {
+            if matches!(type_of(value), Type::Numeric(_)) {
+                Ok(())
+            } else {
+                Err(format!("{value} should be numeric but has type {}", type_of(value)))
+            }
+        };
+
+        match instruction {
+            Instruction::Binary(binary) => {
+                expect_numeric(binary.lhs)?;
+                expect_numeric(binary.rhs)?;
+                if !agrees_with(dfg, binary.rhs, &type_of(binary.lhs))
+                    && !agrees_with(dfg, binary.lhs, &type_of(binary.rhs))
+                {
+                    return Err(format!(
+                        "the operands of `{}` have different types {} and {}",
+                        binary.operator,
+                        type_of(binary.lhs),
+                        type_of(binary.rhs)
+                    ));
+                }
+            }
+            Instruction::Cast(value, typ) => {
+                expect_numeric(*value)?;
+                if !matches!(typ, Type::Numeric(_)) {
+                    return Err(format!("{value} is cast to non-numeric type {typ}"));
+                }
+            }
+            Instruction::Not(value)
+            | Instruction::Truncate { value, .. }
+            | Instruction::RangeCheck { value, .. } => expect_numeric(*value)?,
+            Instruction::Constrain(lhs, rhs, _) => {
+                if !agrees_with(dfg, *rhs, &type_of(*lhs))
+                    && !agrees_with(dfg, *lhs, &type_of(*rhs))
+                {
+                    return Err(format!(
+                        "{lhs} and {rhs} are constrained to be equal but have different types"
+                    ));
+                }
+            }
+            Instruction::EnableSideEffects { condition } => {
+                if !agrees_with(dfg, *condition, &Type::bool()) {
+                    return Err(format!("side effects are enabled by non-boolean {condition}"));
+                }
+            }
+            Instruction::Allocate => {
+                if let Some(result) = results.first() {
+                    if !matches!(type_of(*result), Type::Reference(_)) {
+                        return Err(format!("allocation {result} is not a reference"));
+                    }
+                }
+            }
+            Instruction::Load { address } | Instruction::Store { address, .. } => {
+                if !matches!(type_of(*address), Type::Reference(_)) {
+                    return Err(format!("{address} is accessed as memory but isn't a reference"));
+                }
+            }
+            Instruction::ArrayGet { array, index } => {
+                let element = results.first().copied();
+                self.verify_array_access(*array, *index, element)?;
+            }
+            Instruction::ArraySet { array, index, value } => {
+                self.verify_array_access(*array, *index, Some(*value))?;
+            }
+            Instruction::Call { func, arguments } => {
+                self.verify_call(*func, arguments, results)?;
+            }
+            Instruction::IncrementRc { .. } => (),
+        }
+        Ok(())
+    }
+
+    /// Checks that `array` is indexed by a numeric `index`, and that the `element` read from or
+    /// written to it has the type of the array's elements.
+    fn verify_array_access(
+        &self,
+        array: ValueId,
+        index: ValueId,
+        element: Option<ValueId>,
+    ) -> Result<(), String> {
+        let dfg = &self.function.dfg;
+        let array_type = dfg.type_of_value(dfg.resolve(array));
+        if !matches!(array_type, Type::Array(..) | Type::Slice(_)) {
+            return Err(format!("{array} is indexed but has type {array_type}"));
+        }
+        if !matches!(dfg.type_of_value(dfg.resolve(index)), Type::Numeric(_)) {
+            return Err(format!("{array} is indexed by non-numeric {index}"));
+        }
+
+        let element_types = element_types(&array_type);
+        let Some(element) = element else { return Ok(()) };
+        if element_types.is_empty() {
+            return Err(format!("{array} is indexed but has no elements"));
+        }
+
+        // Arrays of composite types are flattened, so a constant index identifies which of the
+        // element types is accessed.
+        let agrees = match dfg.get_numeric_constant(index) {
+            Some(index) => {
+                let index = index.to_u128() as usize % element_types.len();
+                agrees_with(dfg, element, &element_types[index])
+            }
+            None => element_types.iter().any(|typ| agrees_with(dfg, element, typ)),
+        };
+        if agrees {
+            Ok(())
+        } else {
+            let element_type = dfg.type_of_value(dfg.resolve(element));
+            Err(format!("{element} of type {element_type} is not an element of {array_type}"))
+        }
+    }
+
+    fn verify_call(
+        &self,
+        func: ValueId,
+        arguments: &[ValueId],
+        results: &[ValueId],
+    ) -> Result<(), String> {
+        let dfg = &self.function.dfg;
+        match &dfg[dfg.resolve(func)] {
+            Value::Function(id) => {
+                // Calls to missing functions are reported when checking the uses of `func`.
+                let Some(callee) = self.ssa.functions.get(id) else { return Ok(()) };
+                let parameters = callee.parameters();
+                if arguments.len() != parameters.len() {
+                    return Err(format!(
+                        "{} is called with {} arguments but takes {}",
+                        callee.name(),
+                        arguments.len(),
+                        parameters.len()
+                    ));
+                }
+                for (argument, parameter) in arguments.iter().zip(parameters) {
+                    let parameter_type = callee.dfg.type_of_value(*parameter);
+                    if !agrees_with(dfg, *argument, &parameter_type) {
+                        return Err(format!(
+                            "{argument} is passed to {} where a {parameter_type} is expected",
+                            callee.name()
+                        ));
+                    }
+                }
+                if let Some(return_count) = return_count(callee) {
+                    if results.len() != return_count {
+                        return Err(format!(
+                            "a call to {} has {} results but it returns {return_count} values",
+                            callee.name(),
+                            results.len()
+                        ));
+                    }
+                }
+                Ok(())
+            }
+            Value::Intrinsic(intrinsic) => {
+                verify_slice_intrinsic(dfg, *intrinsic, arguments, results)
+            }
+            Value::ForeignFunction(_) => Ok(()),
+            _ => {
+                let func_type = dfg.type_of_value(dfg.resolve(func));
+                if func_type == Type::Function {
+                    Ok(())
+                } else {
+                    Err(format!("{func} is called but has type {func_type}"))
+                }
+            }
+        }
+    }
+
+    fn verify_terminator(&mut self, terminator: &TerminatorInstruction) -> Result<(), String> {
+        let function = self.function;
+        let dfg = &function.dfg;
+        match terminator {
+            TerminatorInstruction::JmpIf { condition, then_destination, else_destination } => {
+                if !agrees_with(dfg, *condition, &Type::bool()) {
+                    return Err(format!("the condition {condition} of a jmpif is not a boolean"));
+                }
+                for destination in [then_destination, else_destination] {
+                    if !dfg[*destination].parameters().is_empty() {
+                        return Err(format!(
+                            "a jmpif passes no arguments to block {destination} \
+                            which has parameters"
+                        ));
+                    }
+                }
+            }
+            TerminatorInstruction::Jmp { destination, arguments, .. } => {
+                let parameters = dfg[*destination].parameters();
+                if arguments.len() != parameters.len() {
+                    return Err(format!(
+                        "a jmp passes {} arguments to block {destination} which has {} parameters",
+                        arguments.len(),
+                        parameters.len()
+                    ));
+                }
+                for (argument, parameter) in arguments.iter().zip(parameters) {
+                    let parameter_type = dfg.type_of_value(*parameter);
+                    if !agrees_with(dfg, *argument, &parameter_type) {
+                        return Err(format!(
+                            "{argument} is passed to parameter {parameter} of type {parameter_type}"
+                        ));
+                    }
+                }
+            }
+            TerminatorInstruction::Return { return_values, .. } => {
+                let return_count = *self.return_count.get_or_insert(return_values.len());
+                if return_values.len() != return_count {
+                    return Err(format!(
+                        "the function returns {} values here but {return_count} elsewhere",
+                        return_values.len()
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Checks that the number of values in array `value` is a multiple of the size of its elements,
+/// and is the array's length times that size if it isn't a slice.
+fn verify_array_elements(value: ValueId, len: usize, typ: &Type) -> Result<(), String> {
+    let element_size = element_types(typ).len();
+    let expected_len = match typ {
+        Type::Array(_, length) => Some(length * element_size),
+        _ => None,
+    };
+    if element_size == 0 && len != 0 {
+        Err(format!("array {value} of type {typ} should be empty but has {len} values"))
+    } else if expected_len.map_or(false, |expected_len| expected_len != len) {
+        Err(format!("array {value} of type {typ} has {len} values"))
+    } else if element_size != 0 && len % element_size != 0 {
+        Err(format!(
+            "slice {value} of type {typ} has {len} values, not a multiple of {element_size}"
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Checks that the arguments and results of a call to a slice intrinsic agree with the size of
+/// the elements of the slice, which is always passed as the second argument.
+fn verify_slice_intrinsic(
+    dfg: &DataFlowGraph,
+    intrinsic: Intrinsic,
+    arguments: &[ValueId],
+    results: &[ValueId],
+) -> Result<(), String> {
+    // The number of arguments and results other than the slice's elements.
+    let (other_arguments, other_results, elements_are_arguments) = match intrinsic {
+        Intrinsic::SlicePushBack | Intrinsic::SlicePushFront => (2, 2, true),
+        Intrinsic::SliceInsert => (3, 2, true),
+        Intrinsic::SlicePopBack | Intrinsic::SlicePopFront => (2, 2, false),
+        Intrinsic::SliceRemove => (3, 2, false),
+        _ => return Ok(()),
+    };
+
+    let Some(slice) = arguments.get(1) else {
+        return Err(format!("{intrinsic} is called without a slice"));
+    };
+    let slice_type = dfg.type_of_value(dfg.resolve(*slice));
+    if !matches!(slice_type, Type::Array(..) | Type::Slice(_)) {
+        return Err(format!("{intrinsic} is called on {slice} of type {slice_type}"));
+    }
+    let element_size = slice_type.element_size();
+
+    let (expected_arguments, expected_results) = if elements_are_arguments {
+        (other_arguments + element_size, other_results)
+    } else {
+        (other_arguments, other_results + element_size)
+    };
+    if arguments.len() != expected_arguments || results.len() != expected_results {
+        return Err(format!(
+            "{intrinsic} on {slice} of type {slice_type} has {} arguments and {} results \
+            rather than {expected_arguments} and {expected_results}",
+            arguments.len(),
+            results.len()
+        ));
+    }
+    Ok(())
+}
+
+/// Returns true if `value` may be used where a value of type `expected` is expected.
+///
+/// Numeric constants are frequently created as fields regardless of the type of the values they
+/// are combined with, so they only need to be numeric when a numeric type is expected.
+fn agrees_with(dfg: &DataFlowGraph, value: ValueId, expected: &Type) -> bool {
+    let value = dfg.resolve(value);
+    let typ = dfg.type_of_value(value);
+    typ == *expected
+        || (matches!(dfg[value], Value::NumericConstant { .. })
+            && matches!(expected, Type::Numeric(_)))
+}
+
+/// Returns the types of the fields of each element of an array or slice type.
+fn element_types(typ: &Type) -> &[Type] {
+    match typ {
+        Type::Array(elements, _) | Type::Slice(elements) => elements,
+        _ => &[],
+    }
+}
+
+/// Returns the number of values returned by `function`, if it returns at all.
+fn return_count(function: &Function) -> Option<usize> {
+    function.reachable_blocks().into_iter().find_map(|block| {
+        match function.dfg[block].terminator() {
+            Some(TerminatorInstruction::Return { return_values, .. }) => Some(return_values.len()),
+            _ => None,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ssa::{
+        function_builder::FunctionBuilder,
+        ir::{
+            function::RuntimeType,
+            instruction::{BinaryOp, TerminatorInstruction},
+            map::Id,
+            types::Type,
+        },
+    };
+
+    use super::verify;
+
+    #[test]
+    fn accepts_valid_ssa() {
+        // fn main f0 {
+        //   b0(v0: u32, v1: u1):
+        //     jmpif v1 then: b1, else: b2
+        //   b1():
+        //     v2 = add v0, u32 1
+        //     jmp b3(v2)
+        //   b2():
+        //     jmp b3(v0)
+        //   b3(v3: u32):
+        //     return v3
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::unsigned(32));
+        let v1 = builder.add_parameter(Type::bool());
+
+        let b1 = builder.insert_block();
+        let b2 = builder.insert_block();
+        let b3 = builder.insert_block();
+        let v3 = builder.add_block_parameter(b3, Type::unsigned(32));
+        builder.terminate_with_jmpif(v1, b1, b2);
+
+        builder.switch_to_block(b1);
+        let one = builder.numeric_constant(1u128, Type::unsigned(32));
+        let v2 = builder.insert_binary(v0, BinaryOp::Add, one);
+        builder.terminate_with_jmp(b3, vec![v2]);
+
+        builder.switch_to_block(b2);
+        builder.terminate_with_jmp(b3, vec![v0]);
+
+        builder.switch_to_block(b3);
+        builder.terminate_with_return(vec![v3]);
+
+        assert!(verify(&builder.finish()).is_ok());
+    }
+
+    #[test]
+    fn rejects_use_before_definition() {
+        // fn main f0 {
+        //   b0(v0: u1, v1: Field):
+        //     jmpif v0 then: b1, else: b2
+        //   b1():
+        //     v2 = mul v1, v1
+        //     jmp b2()
+        //   b2():
+        //     return v2
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::bool());
+        let v1 = builder.add_parameter(Type::field());
+
+        let b1 = builder.insert_block();
+        let b2 = builder.insert_block();
+        builder.terminate_with_jmpif(v0, b1, b2);
+
+        builder.switch_to_block(b1);
+        let v2 = builder.insert_binary(v1, BinaryOp::Mul, v1);
+        builder.terminate_with_jmp(b2, vec![]);
+
+        builder.switch_to_block(b2);
+        builder.terminate_with_return(vec![v2]);
+
+        let error = verify(&builder.finish()).expect_err("b1 does not dominate b2");
+        assert!(error.to_string().contains("used before it is defined"));
+    }
+
+    #[test]
+    fn rejects_jumps_with_the_wrong_arguments() {
+        // fn main f0 {
+        //   b0(v0: Field):
+        //     jmp b1()
+        //   b1(v1: Field):
+        //     return v1
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::field());
+
+        let b1 = builder.insert_block();
+        let v1 = builder.add_block_parameter(b1, Type::field());
+        builder.terminate_with_jmp(b1, vec![v0]);
+
+        builder.switch_to_block(b1);
+        builder.terminate_with_return(vec![v1]);
+
+        let mut ssa = builder.finish();
+        let main = ssa.main_mut();
+        let entry = main.entry_block();
+        let TerminatorInstruction::Jmp { arguments, .. } = main.dfg[entry].unwrap_terminator_mut()
+        else {
+            panic!("Expected a jmp");
+        };
+        arguments.clear();
+
+        let error = verify(&ssa).expect_err("b1 takes a parameter");
+        assert!(error.to_string().contains("passes 0 arguments to block b1"));
+    }
+
+    #[test]
+    fn rejects_arrays_with_the_wrong_number_of_elements() {
+        // fn main f0 {
+        //   b0(v0: Field):
+        //     return [v0, v0, v0]
+        // }
+        // where the array has type [(Field, Field); 2]
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::field());
+
+        let element_type = std::rc::Rc::new(vec![Type::field(), Type::field()]);
+        let array_type = Type::Array(element_type, 2);
+        let array = builder.array_constant(im::vector![v0, v0, v0], array_type);
+        builder.terminate_with_return(vec![array]);
+
+        let error = verify(&builder.finish()).expect_err("the array should have 4 values");
+        assert!(error.to_string().contains("has 3 values"));
+    }
+}
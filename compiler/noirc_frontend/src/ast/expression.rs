@@ -4,7 +4,8 @@ use std::fmt::Display;
 use crate::token::{Attributes, Token};
 use crate::{
     Distinctness, FunctionVisibility, Ident, Path, Pattern, Recoverable, Statement, StatementKind,
-    UnresolvedTraitConstraint, UnresolvedType, UnresolvedTypeData, Visibility,
+    UnresolvedNumericBound, UnresolvedTraitConstraint, UnresolvedType, UnresolvedTypeData,
+    Visibility,
 };
 use acvm::FieldElement;
 use iter_extended::vecmap;
@@ -23,6 +24,7 @@ pub enum ExpressionKind {
     Cast(Box<CastExpression>),
     Infix(Box<InfixExpression>),
     If(Box<IfExpression>),
+    Match(Box<MatchExpression>),
     Variable(Path),
     Tuple(Vec<Expression>),
     Lambda(Box<Lambda>),
@@ -30,9 +32,41 @@ pub enum ExpressionKind {
     Error,
 }
 
-/// A Vec of unresolved names for type variables.
-/// For `fn foo<A, B>(...)` this corresponds to vec!["A", "B"].
-pub type UnresolvedGenerics = Vec<Ident>;
+/// A Vec of unresolved generics.
+/// For `fn foo<A, let B: Field>(...)` this corresponds to vec!["A", "let B: Field"].
+pub type UnresolvedGenerics = Vec<UnresolvedGeneric>;
+
+/// A generic is either the name of a type variable, or a constant declared with `let` and its
+/// type, such as the `SEPARATOR` in `struct Hasher<let SEPARATOR: Field>`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum UnresolvedGeneric {
+    Variable(Ident),
+    Value { ident: Ident, typ: UnresolvedType, span: Span },
+}
+
+impl UnresolvedGeneric {
+    pub fn ident(&self) -> &Ident {
+        match self {
+            UnresolvedGeneric::Variable(ident) | UnresolvedGeneric::Value { ident, .. } => ident,
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            UnresolvedGeneric::Variable(ident) => ident.span(),
+            UnresolvedGeneric::Value { span, .. } => *span,
+        }
+    }
+}
+
+impl Display for UnresolvedGeneric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnresolvedGeneric::Variable(ident) => write!(f, "{ident}"),
+            UnresolvedGeneric::Value { ident, typ, .. } => write!(f, "let {ident}: {typ}"),
+        }
+    }
+}
 
 impl ExpressionKind {
     pub fn into_path(self) -> Option<Path> {
@@ -176,10 +210,10 @@ impl Expression {
     }
 
     pub fn call(lhs: Expression, arguments: Vec<Expression>, span: Span) -> Expression {
-        // Need to check if lhs is an if expression since users can sequence if expressions
+        // Need to check if lhs is an if or match expression since users can sequence these
         // with tuples without calling them. E.g. `if c { t } else { e }(a, b)` is interpreted
         // as a sequence of { if, tuple } rather than a function call. This behavior matches rust.
-        let kind = if matches!(&lhs.kind, ExpressionKind::If(..)) {
+        let kind = if matches!(&lhs.kind, ExpressionKind::If(..) | ExpressionKind::Match(..)) {
             ExpressionKind::Block(BlockExpression(vec![
                 Statement { kind: StatementKind::Expression(lhs), span },
                 Statement {
@@ -354,6 +388,41 @@ pub struct IfExpression {
     pub alternative: Option<Expression>,
 }
 
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct MatchExpression {
+    pub expression: Expression,
+    pub rules: Vec<(MatchPattern, Expression)>,
+}
+
+/// The pattern of a single arm of a `match` expression.
+///
+/// Unlike a [Pattern] this may be refutable: literals only match a single value.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum MatchPattern {
+    /// `_`, which matches any value without binding it
+    Wildcard(Span),
+    Identifier(Ident),
+    /// An integer or boolean literal
+    Literal(Literal, Span),
+    Tuple(Vec<MatchPattern>, Span),
+    Struct(Path, Vec<(Ident, MatchPattern)>, Span),
+    /// An enum variant such as `Option::Some(x)` along with the patterns for its payload
+    Variant(Path, Vec<MatchPattern>, Span),
+}
+
+impl MatchPattern {
+    pub fn span(&self) -> Span {
+        match self {
+            MatchPattern::Identifier(ident) => ident.span(),
+            MatchPattern::Wildcard(span)
+            | MatchPattern::Literal(_, span)
+            | MatchPattern::Tuple(_, span)
+            | MatchPattern::Struct(_, _, span)
+            | MatchPattern::Variant(_, _, span) => *span,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Lambda {
     pub parameters: Vec<(Pattern, UnresolvedType)>,
@@ -385,6 +454,8 @@ pub struct FunctionDefinition {
     pub body: BlockExpression,
     pub span: Span,
     pub where_clause: Vec<UnresolvedTraitConstraint>,
+    /// The bounds on numeric generics in the where clause, such as `N <= 32`
+    pub numeric_bounds: Vec<UnresolvedNumericBound>,
     pub return_type: FunctionReturnType,
     pub return_visibility: Visibility,
     pub return_distinctness: Distinctness,
@@ -491,6 +562,7 @@ impl Display for ExpressionKind {
             Cast(cast) => cast.fmt(f),
             Infix(infix) => infix.fmt(f),
             If(if_expr) => if_expr.fmt(f),
+            Match(match_expr) => match_expr.fmt(f),
             Variable(path) => path.fmt(f),
             Constructor(constructor) => constructor.fmt(f),
             MemberAccess(access) => access.fmt(f),
@@ -645,6 +717,36 @@ impl Display for IfExpression {
     }
 }
 
+impl Display for MatchExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rules = vecmap(&self.rules, |(pattern, body)| format!("{pattern} => {body}"));
+        write!(f, "match {} {{ {} }}", self.expression, rules.join(", "))
+    }
+}
+
+impl Display for MatchPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MatchPattern::Wildcard(_) => write!(f, "_"),
+            MatchPattern::Identifier(name) => name.fmt(f),
+            MatchPattern::Literal(literal, _) => literal.fmt(f),
+            MatchPattern::Tuple(fields, _) => {
+                let fields = vecmap(fields, ToString::to_string);
+                write!(f, "({})", fields.join(", "))
+            }
+            MatchPattern::Struct(typename, fields, _) => {
+                let fields = vecmap(fields, |(name, pattern)| format!("{name}: {pattern}"));
+                write!(f, "{} {{ {} }}", typename, fields.join(", "))
+            }
+            MatchPattern::Variant(variant, fields, _) if fields.is_empty() => variant.fmt(f),
+            MatchPattern::Variant(variant, fields, _) => {
+                let fields = vecmap(fields, ToString::to_string);
+                write!(f, "{}({})", variant, fields.join(", "))
+            }
+        }
+    }
+}
+
 impl Display for Lambda {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let parameters = vecmap(&self.parameters, |(name, r#type)| format!("{name}: {type}"));
@@ -683,6 +785,7 @@ impl FunctionDefinition {
             body: body.clone(),
             span: name.span(),
             where_clause: where_clause.to_vec(),
+            numeric_bounds: Vec::new(),
             return_type: return_type.clone(),
             return_visibility: Visibility::Private,
             return_distinctness: Distinctness::DuplicationAllowed,
@@ -698,7 +801,8 @@ impl Display for FunctionDefinition {
             format!("{pattern}: {visibility} {typ}")
         });
 
-        let where_clause = vecmap(&self.where_clause, ToString::to_string);
+        let mut where_clause = vecmap(&self.where_clause, ToString::to_string);
+        where_clause.extend(self.numeric_bounds.iter().map(ToString::to_string));
         let where_clause_str = if !where_clause.is_empty() {
             format!("where {}", where_clause.join(", "))
         } else {
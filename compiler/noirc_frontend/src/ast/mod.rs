@@ -24,7 +24,7 @@ pub use type_alias::*;
 use crate::{
     parser::{ParserError, ParserErrorReason},
     token::IntType,
-    BinaryTypeOperator,
+    BinaryTypeOperator, TypeLevelValue,
 };
 use iter_extended::vecmap;
 
@@ -38,6 +38,11 @@ pub enum UnresolvedTypeData {
     Integer(Signedness, u32),                                     // u32 = Integer(unsigned, 32)
     Bool,
     Expression(UnresolvedTypeExpression),
+
+    /// A constant given to a generic declared with `let`, which isn't an array length
+    /// such as `Cipher<[1, 2, 3]>`.
+    Value(TypeLevelValue),
+
     String(Option<UnresolvedTypeExpression>),
     FormatString(UnresolvedTypeExpression, Box<UnresolvedType>),
     Unit,
@@ -131,6 +136,7 @@ impl std::fmt::Display for UnresolvedTypeData {
                 write!(f, "({})", elements.join(", "))
             }
             Expression(expression) => expression.fmt(f),
+            Value(value) => value.fmt(f),
             Bool => write!(f, "bool"),
             String(len) => match len {
                 None => write!(f, "str<_>"),
@@ -5,8 +5,8 @@ use crate::lexer::token::SpannedToken;
 use crate::parser::{ParserError, ParserErrorReason};
 use crate::token::Token;
 use crate::{
-    BlockExpression, Expression, ExpressionKind, IndexExpression, MemberAccessExpression,
-    MethodCallExpression, UnresolvedType,
+    BlockExpression, Expression, ExpressionKind, IfExpression, IndexExpression,
+    MemberAccessExpression, MethodCallExpression, UnresolvedType,
 };
 use acvm::FieldElement;
 use iter_extended::vecmap;
@@ -35,6 +35,11 @@ pub enum StatementKind {
     Expression(Expression),
     Assign(AssignStatement),
     For(ForLoopStatement),
+    While(WhileStatement),
+    /// `loop { block }`, only allowed in unconstrained functions
+    Loop(Expression),
+    Break,
+    Continue,
     // This is an expression with a trailing semi-colon
     Semi(Expression),
     // This statement is the result of a recovered parse error.
@@ -59,6 +64,8 @@ impl Statement {
             | StatementKind::Constrain(_)
             | StatementKind::Assign(_)
             | StatementKind::Semi(_)
+            | StatementKind::Break
+            | StatementKind::Continue
             | StatementKind::Error => {
                 // To match rust, statements always require a semicolon, even at the end of a block
                 if semi.is_none() {
@@ -66,13 +73,15 @@ impl Statement {
                 }
                 self.kind
             }
-            // A semicolon on a for loop is optional and does nothing
-            StatementKind::For(_) => self.kind,
+            // A semicolon on a loop is optional and does nothing
+            StatementKind::For(_) | StatementKind::While(_) | StatementKind::Loop(_) => self.kind,
 
             StatementKind::Expression(expr) => {
                 match (&expr.kind, semi, last_statement_in_block) {
                     // Semicolons are optional for these expressions
-                    (ExpressionKind::Block(_), semi, _) | (ExpressionKind::If(_), semi, _) => {
+                    (ExpressionKind::Block(_), semi, _)
+                    | (ExpressionKind::If(_), semi, _)
+                    | (ExpressionKind::Match(_), semi, _) => {
                         if semi.is_some() {
                             StatementKind::Semi(expr)
                         } else {
@@ -416,7 +425,7 @@ pub enum LValue {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
-pub struct ConstrainStatement(pub Expression, pub Option<String>, pub ConstrainKind);
+pub struct ConstrainStatement(pub Expression, pub Option<Expression>, pub ConstrainKind);
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum ConstrainKind {
@@ -614,6 +623,38 @@ pub struct ForLoopStatement {
     pub span: Span,
 }
 
+/// `while condition { block }`, only allowed in unconstrained functions
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct WhileStatement {
+    pub condition: Expression,
+    pub block: Expression,
+    pub span: Span,
+}
+
+impl WhileStatement {
+    /// Desugars the while loop into `loop { if condition { block } else { break; } }`
+    pub fn into_loop(self) -> StatementKind {
+        let span = self.span;
+        let condition_span = self.condition.span;
+
+        let break_statement = Statement { kind: StatementKind::Break, span: condition_span };
+        let alternative = ExpressionKind::Block(BlockExpression(vec![break_statement]));
+
+        let if_expression = ExpressionKind::If(Box::new(IfExpression {
+            condition: self.condition,
+            consequence: self.block,
+            alternative: Some(Expression::new(alternative, condition_span)),
+        }));
+
+        let statement = Statement {
+            kind: StatementKind::Expression(Expression::new(if_expression, span)),
+            span,
+        };
+        let block = ExpressionKind::Block(BlockExpression(vec![statement]));
+        StatementKind::Loop(Expression::new(block, span))
+    }
+}
+
 impl Display for StatementKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -622,7 +663,13 @@ impl Display for StatementKind {
             StatementKind::Expression(expression) => expression.fmt(f),
             StatementKind::Assign(assign) => assign.fmt(f),
             StatementKind::For(for_loop) => for_loop.fmt(f),
+            StatementKind::While(while_loop) => {
+                write!(f, "while {} {}", while_loop.condition, while_loop.block)
+            }
+            StatementKind::Loop(block) => write!(f, "loop {block}"),
             StatementKind::Semi(semi) => write!(f, "{semi};"),
+            StatementKind::Break => write!(f, "break"),
+            StatementKind::Continue => write!(f, "continue"),
             StatementKind::Error => write!(f, "Error"),
         }
     }
@@ -4,13 +4,16 @@ use crate::{token::SecondaryAttribute, Ident, UnresolvedGenerics, UnresolvedType
 use iter_extended::vecmap;
 use noirc_errors::Span;
 
-/// Ast node for a struct
+/// Ast node for a struct or an enum
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct NoirStruct {
     pub name: Ident,
     pub attributes: Vec<SecondaryAttribute>,
     pub generics: UnresolvedGenerics,
     pub fields: Vec<(Ident, UnresolvedType)>,
+    /// The variants of an enum along with the types of their payloads, or None for a struct.
+    /// Enums have no fields of their own.
+    pub variants: Option<Vec<(Ident, Vec<UnresolvedType>)>>,
     pub span: Span,
 }
 
@@ -18,11 +21,11 @@ impl NoirStruct {
     pub fn new(
         name: Ident,
         attributes: Vec<SecondaryAttribute>,
-        generics: Vec<Ident>,
+        generics: UnresolvedGenerics,
         fields: Vec<(Ident, UnresolvedType)>,
         span: Span,
     ) -> NoirStruct {
-        NoirStruct { name, attributes, generics, fields, span }
+        NoirStruct { name, attributes, generics, fields, variants: None, span }
     }
 }
 
@@ -31,6 +34,20 @@ impl Display for NoirStruct {
         let generics = vecmap(&self.generics, |generic| generic.to_string());
         let generics = if generics.is_empty() { "".into() } else { generics.join(", ") };
 
+        if let Some(variants) = &self.variants {
+            writeln!(f, "enum {}{} {{", self.name, generics)?;
+
+            for (name, payload) in variants {
+                if payload.is_empty() {
+                    writeln!(f, "    {name},")?;
+                } else {
+                    let payload = vecmap(payload, ToString::to_string);
+                    writeln!(f, "    {name}({}),", payload.join(", "))?;
+                }
+            }
+            return write!(f, "}}");
+        }
+
         writeln!(f, "struct {}{} {{", self.name, generics)?;
 
         for (name, typ) in self.fields.iter() {
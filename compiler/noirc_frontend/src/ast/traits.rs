@@ -4,8 +4,8 @@ use iter_extended::vecmap;
 use noirc_errors::Span;
 
 use crate::{
-    node_interner::TraitId, BlockExpression, Expression, FunctionReturnType, Ident, NoirFunction,
-    Path, UnresolvedGenerics, UnresolvedType,
+    node_interner::TraitId, BinaryOp, BlockExpression, Expression, FunctionReturnType, Ident,
+    NoirFunction, Path, UnresolvedGenerics, UnresolvedType, UnresolvedTypeExpression,
 };
 
 /// AST node for trait definitions:
@@ -13,7 +13,7 @@ use crate::{
 #[derive(Clone, Debug)]
 pub struct NoirTrait {
     pub name: Ident,
-    pub generics: Vec<Ident>,
+    pub generics: UnresolvedGenerics,
     pub where_clause: Vec<UnresolvedTraitConstraint>,
     pub span: Span,
     pub items: Vec<TraitItem>,
@@ -25,7 +25,7 @@ pub struct NoirTrait {
 pub enum TraitItem {
     Function {
         name: Ident,
-        generics: Vec<Ident>,
+        generics: UnresolvedGenerics,
         parameters: Vec<(Ident, UnresolvedType)>,
         return_type: FunctionReturnType,
         where_clause: Vec<UnresolvedTraitConstraint>,
@@ -79,6 +79,16 @@ pub struct UnresolvedTraitConstraint {
     pub trait_bound: TraitBound,
 }
 
+/// Represents a bound on numeric generics such as `where N <= 32`, where each side of the
+/// comparison is a type expression like those in the length position of an array type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnresolvedNumericBound {
+    pub lhs: UnresolvedTypeExpression,
+    pub operator: BinaryOp,
+    pub rhs: UnresolvedTypeExpression,
+    pub span: Span,
+}
+
 /// Represents a single trait bound, such as `TraitX` or `TraitY<U, V>`
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct TraitBound {
@@ -173,6 +183,12 @@ impl Display for UnresolvedTraitConstraint {
     }
 }
 
+impl Display for UnresolvedNumericBound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} {}", self.lhs, self.operator.contents, self.rhs)
+    }
+}
+
 impl Display for TraitBound {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let generics = vecmap(&self.trait_generics, |generic| generic.to_string());
@@ -4,7 +4,7 @@
 // This version is also simpler due to not having macro_defs or proc_macros
 // XXX: Edition may be reintroduced or some sort of versioning
 
-use std::{fmt::Display, str::FromStr};
+use std::{collections::BTreeSet, fmt::Display, str::FromStr};
 
 use fm::FileId;
 use rustc_hash::{FxHashMap, FxHashSet};
@@ -101,6 +101,9 @@ mod crate_name {
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct CrateGraph {
     arena: FxHashMap<CrateId, CrateData>,
+    /// The name of the backend the crates are compiled for, which enables the items marked with
+    /// `#[cfg(backend = "...")]`.
+    pub backend: Option<String>,
 }
 
 /// List of characters that are not allowed in a crate name
@@ -112,6 +115,9 @@ pub const CHARACTER_BLACK_LIST: [char; 1] = ['-'];
 pub struct CrateData {
     pub root_file_id: FileId,
     pub dependencies: Vec<Dependency>,
+    /// The features the crate is compiled with, which enable the items marked with
+    /// `#[cfg(feature = "...")]`.
+    pub features: BTreeSet<String>,
 }
 
 /// A dependency is a crate name and a crate_id
@@ -154,7 +160,11 @@ impl CrateGraph {
             }
         }
 
-        let data = CrateData { root_file_id: file_id, dependencies: Vec::new() };
+        let data = CrateData {
+            root_file_id: file_id,
+            dependencies: Vec::new(),
+            features: BTreeSet::new(),
+        };
         let crate_id = CrateId::Root(self.arena.len());
         let prev = self.arena.insert(crate_id, data);
         assert!(prev.is_none());
@@ -185,7 +195,11 @@ impl CrateGraph {
                 panic!("ICE: A dummy CrateId should not exist in the CrateGraph")
             }
             None => {
-                let data = CrateData { root_file_id: file_id, dependencies: Vec::new() };
+                let data = CrateData {
+                    root_file_id: file_id,
+                    dependencies: Vec::new(),
+                    features: BTreeSet::new(),
+                };
                 let crate_id = CrateId::Crate(self.arena.len());
                 let prev = self.arena.insert(crate_id, data);
                 assert!(prev.is_none());
@@ -205,7 +219,11 @@ impl CrateGraph {
             }
         }
 
-        let data = CrateData { root_file_id: file_id, dependencies: Vec::new() };
+        let data = CrateData {
+            root_file_id: file_id,
+            dependencies: Vec::new(),
+            features: BTreeSet::new(),
+        };
         let crate_id = CrateId::Stdlib(self.arena.len());
         let prev = self.arena.insert(crate_id, data);
         assert!(prev.is_none());
@@ -273,6 +291,20 @@ impl CrateGraph {
         false
     }
 
+    /// Enables `features` on top of those already enabled for the crate, as a crate which is a
+    /// dependency of several packages is compiled once with all the features they enable.
+    pub fn enable_features(
+        &mut self,
+        crate_id: CrateId,
+        features: impl IntoIterator<Item = String>,
+    ) {
+        self.arena
+            .get_mut(&crate_id)
+            .expect("crate should be in the graph")
+            .features
+            .extend(features);
+    }
+
     pub fn number_of_crates(&self) -> usize {
         self.arena.len()
     }
@@ -0,0 +1,182 @@
+use std::collections::HashSet;
+
+use crate::{
+    hir_def::{
+        expr::{HirArrayLiteral, HirExpression, HirLiteral, HirMatchPattern, ImplKind},
+        stmt::{HirLValue, HirStatement},
+    },
+    node_interner::{
+        DefinitionId, DefinitionKind, ExprId, FuncId, NodeInterner, StmtId, TraitImplKind,
+    },
+};
+
+/// Returns the globals used by `expression`, either directly or within any function it may call.
+pub(crate) fn global_dependencies(
+    interner: &NodeInterner,
+    expression: ExprId,
+) -> Vec<DefinitionId> {
+    let mut collector = DependencyCollector {
+        interner,
+        globals: Vec::new(),
+        found_globals: HashSet::new(),
+        visited_functions: HashSet::new(),
+    };
+    collector.expression(expression);
+    collector.globals
+}
+
+struct DependencyCollector<'interner> {
+    interner: &'interner NodeInterner,
+    /// The globals found so far, in the order they were first found in.
+    globals: Vec<DefinitionId>,
+    found_globals: HashSet<DefinitionId>,
+    visited_functions: HashSet<FuncId>,
+}
+
+impl<'interner> DependencyCollector<'interner> {
+    fn expression(&mut self, expression: ExprId) {
+        match self.interner.expression(&expression) {
+            HirExpression::Ident(ident) => {
+                if let ImplKind::TraitMethod(method, _, _) = ident.impl_kind {
+                    // Only impls which are already known can be followed. Those selected during
+                    // monomorphization depend on the generics a function is called with.
+                    if let Some(TraitImplKind::Normal(impl_id)) =
+                        self.interner.get_selected_impl_for_expression(expression)
+                    {
+                        let trait_impl = self.interner.get_trait_implementation(impl_id);
+                        let function = trait_impl.borrow().methods[method.method_index];
+                        self.function(function);
+                    }
+                    return;
+                }
+
+                match &self.interner.definition(ident.id).kind {
+                    DefinitionKind::Function(function) => self.function(*function),
+                    DefinitionKind::Global(_) => {
+                        if self.found_globals.insert(ident.id) {
+                            self.globals.push(ident.id);
+                        }
+                    }
+                    DefinitionKind::Local(_) | DefinitionKind::GenericType(_) => (),
+                }
+            }
+            HirExpression::Literal(literal) => match literal {
+                HirLiteral::Array(HirArrayLiteral::Standard(elements)) => {
+                    self.expressions(elements)
+                }
+                HirLiteral::Array(HirArrayLiteral::Repeated { repeated_element, .. }) => {
+                    self.expression(repeated_element);
+                }
+                HirLiteral::FmtStr(_, arguments) => self.expressions(arguments),
+                HirLiteral::Bool(_)
+                | HirLiteral::Integer(..)
+                | HirLiteral::Str(_)
+                | HirLiteral::Unit => (),
+            },
+            HirExpression::Block(block) => {
+                block.statements().iter().for_each(|statement| self.statement(*statement));
+            }
+            HirExpression::Prefix(prefix) => self.expression(prefix.rhs),
+            HirExpression::Infix(infix) => {
+                self.expression(infix.lhs);
+                self.expression(infix.rhs);
+            }
+            HirExpression::Index(index) => {
+                self.expression(index.collection);
+                self.expression(index.index);
+            }
+            HirExpression::Constructor(constructor) => {
+                constructor.fields.into_iter().for_each(|(_, field)| self.expression(field));
+            }
+            HirExpression::EnumConstructor(constructor) => self.expressions(constructor.arguments),
+            HirExpression::MemberAccess(access) => self.expression(access.lhs),
+            HirExpression::Call(call) => {
+                self.expression(call.func);
+                self.expressions(call.arguments);
+            }
+            HirExpression::MethodCall(call) => {
+                self.expression(call.object);
+                self.expressions(call.arguments);
+            }
+            HirExpression::Cast(cast) => self.expression(cast.lhs),
+            HirExpression::If(if_expr) => {
+                self.expression(if_expr.condition);
+                self.expression(if_expr.consequence);
+                if let Some(alternative) = if_expr.alternative {
+                    self.expression(alternative);
+                }
+            }
+            HirExpression::Match(match_expr) => {
+                self.expression(match_expr.expression);
+                for (pattern, body) in match_expr.rules {
+                    self.match_pattern(&pattern);
+                    self.expression(body);
+                }
+            }
+            HirExpression::Tuple(fields) => self.expressions(fields),
+            HirExpression::Lambda(lambda) => self.expression(lambda.body),
+            HirExpression::Error => (),
+        }
+    }
+
+    fn expressions(&mut self, expressions: Vec<ExprId>) {
+        expressions.into_iter().for_each(|expression| self.expression(expression));
+    }
+
+    fn statement(&mut self, statement: StmtId) {
+        match self.interner.statement(&statement) {
+            HirStatement::Let(let_statement) => self.expression(let_statement.expression),
+            HirStatement::Constrain(constrain) => {
+                self.expression(constrain.0);
+                if let Some(message) = constrain.2 {
+                    self.expression(message);
+                }
+            }
+            HirStatement::Assign(assign) => {
+                self.lvalue(&assign.lvalue);
+                self.expression(assign.expression);
+            }
+            HirStatement::For(for_loop) => {
+                self.expression(for_loop.start_range);
+                self.expression(for_loop.end_range);
+                self.expression(for_loop.block);
+            }
+            HirStatement::Loop(block) => self.expression(block),
+            HirStatement::Expression(expression) | HirStatement::Semi(expression) => {
+                self.expression(expression);
+            }
+            HirStatement::Break | HirStatement::Continue | HirStatement::Error => (),
+        }
+    }
+
+    fn lvalue(&mut self, lvalue: &HirLValue) {
+        match lvalue {
+            HirLValue::Ident(..) => (),
+            HirLValue::MemberAccess { object, .. } => self.lvalue(object),
+            HirLValue::Index { array, index, .. } => {
+                self.lvalue(array);
+                self.expression(*index);
+            }
+            HirLValue::Dereference { lvalue, .. } => self.lvalue(lvalue),
+        }
+    }
+
+    fn match_pattern(&mut self, pattern: &HirMatchPattern) {
+        match pattern {
+            HirMatchPattern::Wildcard(_) | HirMatchPattern::Identifier(_) => (),
+            HirMatchPattern::Literal(literal, _) => self.expression(*literal),
+            HirMatchPattern::Tuple(patterns, _) | HirMatchPattern::Variant(_, _, patterns, _) => {
+                patterns.iter().for_each(|pattern| self.match_pattern(pattern));
+            }
+            HirMatchPattern::Struct(_, patterns, _) => {
+                patterns.iter().for_each(|(_, pattern)| self.match_pattern(pattern));
+            }
+        }
+    }
+
+    fn function(&mut self, function: FuncId) {
+        if self.visited_functions.insert(function) {
+            self.expression(*self.interner.function(&function).as_expr());
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use noirc_errors::{CustomDiagnostic as Diagnostic, Location};
+use thiserror::Error;
+
+/// The result of evaluating an expression at compile-time.
+pub(crate) type IResult<T> = Result<T, InterpreterError>;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum InterpreterError {
+    #[error("Failed assertion")]
+    FailedConstraint { message: Option<String>, location: Location },
+    #[error("Integer overflow")]
+    IntegerOverflow { operation: &'static str, location: Location },
+    #[error("Division by zero")]
+    DivisionByZero { location: Location },
+    #[error("Index out of bounds")]
+    IndexOutOfBounds { index: u128, length: usize, location: Location },
+    #[error("Recursion limit reached")]
+    CallDepthExceeded { location: Location },
+    #[error("Loop limit reached")]
+    LoopLimitExceeded { location: Location },
+
+    /// The expression uses a feature which can't be evaluated at compile-time, such as a call to
+    /// an oracle or a black box function. It is then left to be evaluated at runtime instead.
+    #[error("Unsupported at compile-time")]
+    Unsupported,
+
+    /// Raised by `break` and `continue` to leave the current iteration of the innermost loop.
+    /// These never escape the loop.
+    #[error("Break")]
+    Break,
+    #[error("Continue")]
+    Continue,
+}
+
+impl InterpreterError {
+    /// The location of an error which is reported to the user.
+    pub fn location(&self) -> Location {
+        match self {
+            InterpreterError::FailedConstraint { location, .. }
+            | InterpreterError::IntegerOverflow { location, .. }
+            | InterpreterError::DivisionByZero { location }
+            | InterpreterError::IndexOutOfBounds { location, .. }
+            | InterpreterError::CallDepthExceeded { location }
+            | InterpreterError::LoopLimitExceeded { location } => *location,
+            InterpreterError::Unsupported
+            | InterpreterError::Break
+            | InterpreterError::Continue => {
+                unreachable!("ICE: {self} is never reported to the user")
+            }
+        }
+    }
+}
+
+impl From<InterpreterError> for Diagnostic {
+    fn from(error: InterpreterError) -> Diagnostic {
+        let span = error.location().span;
+        let secondary = "while evaluating a global at compile-time".to_string();
+        match error {
+            InterpreterError::FailedConstraint { message, .. } => {
+                let primary = match message {
+                    Some(message) => format!("Failed assertion: '{message}'"),
+                    None => "Failed assertion".to_string(),
+                };
+                Diagnostic::simple_error(primary, secondary, span)
+            }
+            InterpreterError::IntegerOverflow { operation, .. } => Diagnostic::simple_error(
+                format!("Attempt to {operation} with overflow"),
+                secondary,
+                span,
+            ),
+            InterpreterError::DivisionByZero { .. } => {
+                Diagnostic::simple_error("Attempt to divide by zero".to_string(), secondary, span)
+            }
+            InterpreterError::IndexOutOfBounds { index, length, .. } => Diagnostic::simple_error(
+                format!("Index out of bounds, array has size {length}, but index was {index}"),
+                secondary,
+                span,
+            ),
+            InterpreterError::CallDepthExceeded { .. } => Diagnostic::simple_error(
+                "Recursion limit reached while evaluating a global".to_string(),
+                "the calls made from here are nested too deeply".to_string(),
+                span,
+            ),
+            InterpreterError::LoopLimitExceeded { .. } => Diagnostic::simple_error(
+                "Loop limit reached while evaluating a global".to_string(),
+                "this loop ran for too many iterations".to_string(),
+                span,
+            ),
+            InterpreterError::Unsupported
+            | InterpreterError::Break
+            | InterpreterError::Continue => unreachable!(),
+        }
+    }
+}
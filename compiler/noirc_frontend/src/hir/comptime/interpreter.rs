@@ -0,0 +1,851 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use acvm::FieldElement;
+use iter_extended::{try_vecmap, vecmap};
+use noirc_errors::Location;
+use regex::Regex;
+
+use crate::{
+    hir_def::{
+        expr::{
+            HirArrayLiteral, HirCallExpression, HirCastExpression, HirConstructorExpression,
+            HirEnumConstructorExpression, HirExpression, HirIdent, HirIfExpression,
+            HirIndexExpression, HirInfixExpression, HirLambda, HirLiteral, HirMatchExpression,
+            HirMatchPattern, HirMemberAccess, HirPrefixExpression, ImplKind,
+        },
+        stmt::{HirForStatement, HirLValue, HirPattern, HirStatement},
+    },
+    monomorphization::impl_bindings,
+    node_interner::{
+        DefinitionId, DefinitionKind, ExprId, FuncId, NodeInterner, StmtId, TraitImplKind,
+        TraitMethodId,
+    },
+    FunctionKind, Signedness, Type, TypeBinding, TypeBindings, TypeVariable, UnaryOp,
+};
+
+use super::{
+    errors::{IResult, InterpreterError},
+    value::Value,
+};
+
+/// The deepest calls can be nested while evaluating a global.
+const MAX_CALL_DEPTH: usize = 64;
+
+/// The most iterations all of the loops run while evaluating a global can take in total.
+const MAX_LOOP_ITERATIONS: usize = 1_000_000;
+
+/// Evaluates HIR expressions, calling into any functions they use.
+pub(crate) struct Interpreter<'interner> {
+    interner: &'interner NodeInterner,
+
+    /// The variables of the function currently being evaluated. Each is held in a slot of its
+    /// own, which mutable references to the variable share.
+    locals: HashMap<DefinitionId, Rc<RefCell<Value>>>,
+
+    call_depth: usize,
+    loop_iterations: usize,
+}
+
+/// A field or element within a value which is assigned to.
+enum Projection {
+    Field(usize),
+    Index(u128, Location),
+}
+
+impl<'interner> Interpreter<'interner> {
+    pub(crate) fn new(interner: &'interner NodeInterner) -> Self {
+        Self { interner, locals: HashMap::new(), call_depth: 0, loop_iterations: 0 }
+    }
+
+    /// Returns the type of an expression, with any integer literal whose type was never
+    /// constrained defaulting to a Field as it does during monomorphization.
+    pub(crate) fn type_of(&self, expression: ExprId) -> Type {
+        concrete_type(self.interner.id_type(expression))
+    }
+
+    pub(crate) fn evaluate(&mut self, expression: ExprId) -> IResult<Value> {
+        match self.interner.expression(&expression) {
+            HirExpression::Ident(ident) => self.evaluate_ident(ident, expression),
+            HirExpression::Literal(literal) => self.evaluate_literal(literal, expression),
+            HirExpression::Block(block) => self.evaluate_block(block.statements()),
+            HirExpression::Prefix(prefix) => self.evaluate_prefix(prefix, expression),
+            HirExpression::Infix(infix) => self.evaluate_infix(infix, expression),
+            HirExpression::Index(index) => self.evaluate_index(index, expression),
+            HirExpression::Constructor(constructor) => self.evaluate_constructor(constructor),
+            HirExpression::EnumConstructor(constructor) => {
+                self.evaluate_enum_constructor(constructor)
+            }
+            HirExpression::MemberAccess(access) => self.evaluate_member_access(access, expression),
+            HirExpression::Call(call) => self.evaluate_call(call, expression),
+            HirExpression::Cast(cast) => self.evaluate_cast(cast),
+            HirExpression::If(if_expr) => self.evaluate_if(if_expr),
+            HirExpression::Match(match_expr) => self.evaluate_match(match_expr),
+            HirExpression::Tuple(fields) => {
+                Ok(Value::Tuple(try_vecmap(fields, |field| self.evaluate(field))?))
+            }
+            HirExpression::Lambda(lambda) => self.evaluate_lambda(lambda),
+            HirExpression::MethodCall(_) | HirExpression::Error => {
+                Err(InterpreterError::Unsupported)
+            }
+        }
+    }
+
+    fn evaluate_ident(&mut self, ident: HirIdent, id: ExprId) -> IResult<Value> {
+        if let ImplKind::TraitMethod(method, _, _) = ident.impl_kind {
+            let function = self.resolve_trait_method(id, method)?;
+            return Ok(Value::Function(function, self.instantiation_bindings(id), Some(method)));
+        }
+
+        match &self.interner.definition(ident.id).kind {
+            DefinitionKind::Function(function) => {
+                Ok(Value::Function(*function, self.instantiation_bindings(id), None))
+            }
+            DefinitionKind::Global(expression) => match self.interner.global_value(ident.id) {
+                Some(value) => Ok(value.clone()),
+                // Globals are evaluated on their own as they can't refer to any local variables.
+                None => self.call_body(&[], Vec::new(), Vec::new(), *expression, ident.location),
+            },
+            DefinitionKind::Local(_) => match self.locals.get(&ident.id) {
+                Some(slot) => Ok(slot.borrow().clone()),
+                None => Err(InterpreterError::Unsupported),
+            },
+            DefinitionKind::GenericType(type_variable) => {
+                let binding = match &*type_variable.borrow() {
+                    TypeBinding::Bound(binding) => binding.follow_bindings(),
+                    TypeBinding::Unbound(_) => return Err(InterpreterError::Unsupported),
+                };
+                if let Type::Value(value) = &binding {
+                    return Value::from_type_level_value(value, &self.type_of(id));
+                }
+                let value = binding.evaluate_to_u64().ok_or(InterpreterError::Unsupported)?;
+                Value::from_u128(value.into(), &self.type_of(id))
+            }
+        }
+    }
+
+    fn evaluate_literal(&mut self, literal: HirLiteral, id: ExprId) -> IResult<Value> {
+        match literal {
+            HirLiteral::Unit => Ok(Value::Unit),
+            HirLiteral::Bool(value) => Ok(Value::Bool(value)),
+            HirLiteral::Integer(value, negative) => {
+                Value::integer_literal(value, negative, &self.type_of(id))
+            }
+            HirLiteral::Str(value) => Ok(Value::String(Rc::new(value))),
+            HirLiteral::Array(HirArrayLiteral::Standard(elements)) => {
+                Ok(Value::Array(try_vecmap(elements, |element| self.evaluate(element))?))
+            }
+            HirLiteral::Array(HirArrayLiteral::Repeated { repeated_element, length }) => {
+                let length = length.evaluate_to_u64().ok_or(InterpreterError::Unsupported)?;
+                let elements = try_vecmap(0..length, |_| self.evaluate(repeated_element))?;
+                Ok(Value::Array(elements))
+            }
+            HirLiteral::FmtStr(..) => Err(InterpreterError::Unsupported),
+        }
+    }
+
+    /// Formats the values referred to by the message of a failed assertion into it, in the same
+    /// way as they are reported when the assertion fails at runtime.
+    fn evaluate_assert_message(&mut self, message: ExprId) -> IResult<String> {
+        let (message, arguments) = match self.interner.expression(&message) {
+            HirExpression::Literal(HirLiteral::Str(message)) => return Ok(message),
+            HirExpression::Literal(HirLiteral::FmtStr(message, arguments)) => (message, arguments),
+            _ => return Err(InterpreterError::Unsupported),
+        };
+
+        let mut arguments = try_vecmap(arguments, |argument| match self.evaluate(argument)? {
+            Value::Bool(value) => Ok((value as u8).to_string()),
+            Value::Integer(value, ..) => Ok(value.to_string()),
+            Value::Field(value) => Ok(match value.try_into_u128() {
+                Some(value) => value.to_string(),
+                None => format!("0x{}", value.to_hex()),
+            }),
+            _ => Err(InterpreterError::Unsupported),
+        })?
+        .into_iter();
+
+        let placeholder = Regex::new(r"\{([a-zA-Z0-9_]+)\}")
+            .expect("ICE: an invalid regex pattern was used for assertion messages");
+        let message = placeholder.replace_all(&message, |captures: &regex::Captures| {
+            arguments.next().unwrap_or_else(|| captures[0].to_string())
+        });
+        Ok(message.into_owned())
+    }
+
+    fn evaluate_block(&mut self, statements: &[StmtId]) -> IResult<Value> {
+        let mut result = Value::Unit;
+        for statement in statements {
+            result = self.evaluate_statement(*statement)?;
+        }
+        Ok(result)
+    }
+
+    fn evaluate_statement(&mut self, statement: StmtId) -> IResult<Value> {
+        match self.interner.statement(&statement) {
+            HirStatement::Let(let_statement) => {
+                let value = self.evaluate(let_statement.expression)?;
+                self.define_pattern(&let_statement.pattern, value)?;
+                Ok(Value::Unit)
+            }
+            HirStatement::Constrain(constrain) => {
+                let location = self.interner.expr_location(&constrain.0);
+                match self.evaluate(constrain.0)? {
+                    Value::Bool(true) => Ok(Value::Unit),
+                    Value::Bool(false) => {
+                        let message = match constrain.2 {
+                            Some(message) => Some(self.evaluate_assert_message(message)?),
+                            None => None,
+                        };
+                        Err(InterpreterError::FailedConstraint { message, location })
+                    }
+                    _ => Err(InterpreterError::Unsupported),
+                }
+            }
+            HirStatement::Assign(assign) => {
+                let value = self.evaluate(assign.expression)?;
+                self.store(assign.lvalue, value)?;
+                Ok(Value::Unit)
+            }
+            HirStatement::For(for_loop) => self.evaluate_for(for_loop),
+            HirStatement::Loop(block) => {
+                let location = self.interner.expr_location(&block);
+                loop {
+                    self.count_iteration(location)?;
+                    match self.evaluate(block) {
+                        Ok(_) | Err(InterpreterError::Continue) => (),
+                        Err(InterpreterError::Break) => return Ok(Value::Unit),
+                        Err(error) => return Err(error),
+                    }
+                }
+            }
+            HirStatement::Break => Err(InterpreterError::Break),
+            HirStatement::Continue => Err(InterpreterError::Continue),
+            HirStatement::Expression(expression) => self.evaluate(expression),
+            HirStatement::Semi(expression) => {
+                self.evaluate(expression)?;
+                Ok(Value::Unit)
+            }
+            HirStatement::Error => Err(InterpreterError::Unsupported),
+        }
+    }
+
+    fn evaluate_for(&mut self, for_loop: HirForStatement) -> IResult<Value> {
+        let start = self.evaluate(for_loop.start_range)?;
+        let end = self.evaluate(for_loop.end_range)?;
+        let location = self.interner.expr_location(&for_loop.start_range);
+        let typ = self.type_of(for_loop.start_range);
+
+        let range = start.as_u128().zip(end.as_u128()).ok_or(InterpreterError::Unsupported)?;
+        for index in range.0..range.1 {
+            self.count_iteration(location)?;
+            self.define(for_loop.identifier.id, Value::from_u128(index, &typ)?);
+            match self.evaluate(for_loop.block) {
+                Ok(_) | Err(InterpreterError::Continue) => (),
+                Err(InterpreterError::Break) => break,
+                Err(error) => return Err(error),
+            }
+        }
+        Ok(Value::Unit)
+    }
+
+    fn count_iteration(&mut self, location: Location) -> IResult<()> {
+        self.loop_iterations += 1;
+        if self.loop_iterations > MAX_LOOP_ITERATIONS {
+            return Err(InterpreterError::LoopLimitExceeded { location });
+        }
+        Ok(())
+    }
+
+    fn evaluate_prefix(&mut self, prefix: HirPrefixExpression, id: ExprId) -> IResult<Value> {
+        // Operators on types other than primitives call their trait impl, which is left to runtime.
+        if self.interner.get_selected_impl_for_expression(id).is_some() {
+            return Err(InterpreterError::Unsupported);
+        }
+
+        match prefix.operator {
+            UnaryOp::MutableReference => self.evaluate_reference(prefix.rhs),
+            UnaryOp::Dereference { .. } => match self.evaluate(prefix.rhs)? {
+                Value::Pointer(slot) => Ok(slot.borrow().clone()),
+                _ => Err(InterpreterError::Unsupported),
+            },
+            UnaryOp::Minus => {
+                let location = self.interner.expr_location(&id);
+                self.evaluate(prefix.rhs)?.negate(location)
+            }
+            UnaryOp::Not => self.evaluate(prefix.rhs)?.not(),
+        }
+    }
+
+    /// A reference to a variable shares the slot of the variable, while a reference to any
+    /// other expression refers to a copy of its value.
+    fn evaluate_reference(&mut self, rhs: ExprId) -> IResult<Value> {
+        match self.interner.expression(&rhs) {
+            HirExpression::Ident(ident) => {
+                if let Some(slot) = self.locals.get(&ident.id) {
+                    return Ok(Value::Pointer(slot.clone()));
+                }
+            }
+            HirExpression::MemberAccess(access) if access.is_offset => {
+                return Err(InterpreterError::Unsupported);
+            }
+            _ => (),
+        }
+        let value = self.evaluate(rhs)?;
+        Ok(Value::Pointer(Rc::new(RefCell::new(value))))
+    }
+
+    fn evaluate_infix(&mut self, infix: HirInfixExpression, id: ExprId) -> IResult<Value> {
+        if self.interner.get_selected_impl_for_expression(id).is_some() {
+            return Err(InterpreterError::Unsupported);
+        }
+
+        let lhs = self.evaluate(infix.lhs)?;
+        let rhs = self.evaluate(infix.rhs)?;
+        lhs.binary(infix.operator.kind, rhs, infix.operator.location)
+    }
+
+    fn evaluate_index(&mut self, index: HirIndexExpression, id: ExprId) -> IResult<Value> {
+        if self.interner.get_selected_impl_for_expression(id).is_some() {
+            return Err(InterpreterError::Unsupported);
+        }
+
+        let collection = self.evaluate(index.collection)?;
+        let position = self.evaluate(index.index)?;
+        let location = self.interner.expr_location(&id);
+        match collection {
+            Value::Array(mut elements) => {
+                let position = position.as_u128().ok_or(InterpreterError::Unsupported)?;
+                get_element(&mut elements, position, location).cloned()
+            }
+            _ => Err(InterpreterError::Unsupported),
+        }
+    }
+
+    fn evaluate_constructor(&mut self, constructor: HirConstructorExpression) -> IResult<Value> {
+        // Fields are evaluated in the order they are written in, and then reordered.
+        let mut fields = try_vecmap(constructor.fields, |(name, field)| {
+            self.evaluate(field).map(|value| (name.0.contents, Some(value)))
+        })?;
+
+        let definition = constructor.r#type.borrow();
+        let field_types = definition.get_fields(&constructor.struct_generics);
+        let fields = try_vecmap(field_types, |(name, _)| {
+            fields
+                .iter_mut()
+                .find(|(field_name, _)| *field_name == name)
+                .and_then(|(_, value)| value.take())
+                .ok_or(InterpreterError::Unsupported)
+        })?;
+        Ok(Value::Struct(fields))
+    }
+
+    /// Enums are represented as structs holding a tag followed by the payload of each variant,
+    /// where all but the payload of the constructed variant are zeroed.
+    fn evaluate_enum_constructor(
+        &mut self,
+        constructor: HirEnumConstructorExpression,
+    ) -> IResult<Value> {
+        let arguments = try_vecmap(constructor.arguments, |argument| self.evaluate(argument))?;
+
+        let definition = constructor.r#type.borrow();
+        let field_types = definition.get_fields(&constructor.enum_generics);
+        let offset = definition.variant_field_offset(constructor.variant);
+        let payload_fields = offset..offset + arguments.len();
+        let mut arguments = arguments.into_iter();
+
+        let mut fields = vec![enum_tag(constructor.variant)];
+        for (i, (_, typ)) in field_types.into_iter().enumerate().skip(1) {
+            fields.push(if payload_fields.contains(&i) {
+                arguments.next().ok_or(InterpreterError::Unsupported)?
+            } else {
+                Value::zeroed(&concrete_type(typ))?
+            });
+        }
+        Ok(Value::Struct(fields))
+    }
+
+    fn evaluate_member_access(&mut self, access: HirMemberAccess, id: ExprId) -> IResult<Value> {
+        if access.is_offset {
+            return Err(InterpreterError::Unsupported);
+        }
+
+        let field_index = self.interner.get_field_index(id);
+        match self.evaluate(access.lhs)? {
+            Value::Tuple(mut fields) | Value::Struct(mut fields) if field_index < fields.len() => {
+                Ok(fields.swap_remove(field_index))
+            }
+            _ => Err(InterpreterError::Unsupported),
+        }
+    }
+
+    fn evaluate_call(&mut self, call: HirCallExpression, id: ExprId) -> IResult<Value> {
+        let function = self.evaluate(call.func)?;
+        let arguments = try_vecmap(call.arguments, |argument| self.evaluate(argument))?;
+
+        match function {
+            Value::Function(function, bindings, trait_method) => {
+                self.call_function(function, &bindings, trait_method, arguments, id, call.location)
+            }
+            Value::Closure(lambda, environment) => {
+                let parameters = vecmap(&lambda.parameters, |(pattern, _)| pattern.clone());
+                let captures = lambda.captures.iter().map(|capture| capture.ident.id);
+                let captures = captures.zip(environment).collect();
+                self.call_body(&parameters, arguments, captures, lambda.body, call.location)
+            }
+            _ => Err(InterpreterError::Unsupported),
+        }
+    }
+
+    fn call_function(
+        &mut self,
+        function: FuncId,
+        bindings: &TypeBindings,
+        trait_method: Option<TraitMethodId>,
+        arguments: Vec<Value>,
+        id: ExprId,
+        location: Location,
+    ) -> IResult<Value> {
+        let meta = self.interner.function_meta(&function);
+        match meta.kind {
+            FunctionKind::Normal => (),
+            FunctionKind::Builtin => return self.call_builtin(function, arguments, id, location),
+            // Black box functions and oracles are left to be executed at runtime.
+            FunctionKind::LowLevel | FunctionKind::Oracle => {
+                return Err(InterpreterError::Unsupported)
+            }
+        }
+
+        let parameters = vecmap(&meta.parameters.0, |(pattern, _, _)| pattern.clone());
+        let body = *self.interner.function(&function).as_expr();
+
+        let mut previous_bindings = bind_type_variables(bindings);
+        let impl_bindings = impl_bindings(self.interner, trait_method, function);
+        previous_bindings.extend(bind_type_variables(&impl_bindings));
+        if let Some((self_type, trait_id)) = self.interner.get_function_trait(&function) {
+            let self_type_variable = &self.interner.get_trait(trait_id).self_type_typevar;
+            previous_bindings.push(bind_type_variable(self_type_variable, self_type));
+        }
+
+        let result = self.call_body(&parameters, arguments, Vec::new(), body, location);
+        restore_type_variables(previous_bindings);
+        result
+    }
+
+    /// Evaluates `body` in a scope of its own, holding only its parameters and the variables
+    /// it captures.
+    fn call_body(
+        &mut self,
+        parameters: &[HirPattern],
+        arguments: Vec<Value>,
+        captures: Vec<(DefinitionId, Value)>,
+        body: ExprId,
+        location: Location,
+    ) -> IResult<Value> {
+        if self.call_depth == MAX_CALL_DEPTH {
+            return Err(InterpreterError::CallDepthExceeded { location });
+        }
+
+        let caller_locals = std::mem::take(&mut self.locals);
+        self.call_depth += 1;
+
+        for (variable, value) in captures {
+            self.define(variable, value);
+        }
+        let result = parameters
+            .iter()
+            .zip(arguments)
+            .try_for_each(|(pattern, argument)| self.define_pattern(pattern, argument))
+            .and_then(|()| self.evaluate(body));
+
+        self.call_depth -= 1;
+        self.locals = caller_locals;
+        result
+    }
+
+    fn call_builtin(
+        &mut self,
+        function: FuncId,
+        arguments: Vec<Value>,
+        id: ExprId,
+        location: Location,
+    ) -> IResult<Value> {
+        let attribute = self.interner.function_attributes(&function).function.clone();
+        let builtin = attribute.and_then(|attribute| attribute.builtin());
+        let return_type = self.type_of(id);
+
+        let mut arguments = arguments.into_iter();
+        let mut argument = || arguments.next().ok_or(InterpreterError::Unsupported);
+        match builtin.as_deref() {
+            Some("array_len") => match argument()? {
+                Value::Array(elements) => Value::from_u128(elements.len() as u128, &return_type),
+                _ => Err(InterpreterError::Unsupported),
+            },
+            Some("assert_constant") => Ok(Value::Unit),
+            Some("static_assert") => match (argument()?, argument()?) {
+                (Value::Bool(true), _) => Ok(Value::Unit),
+                (Value::Bool(false), Value::String(message)) => {
+                    let message = Some(message.as_ref().clone());
+                    Err(InterpreterError::FailedConstraint { message, location })
+                }
+                _ => Err(InterpreterError::Unsupported),
+            },
+            Some("modulus_num_bits") => {
+                Value::from_u128(FieldElement::max_num_bits().into(), &return_type)
+            }
+            Some("zeroed") => Value::zeroed(&return_type),
+            Some("as_field") => argument()?.cast(&Type::FieldElement),
+            Some("from_field") => argument()?.cast(&return_type),
+            Some("str_as_bytes") => match argument()? {
+                Value::String(string) => {
+                    let bytes = string
+                        .bytes()
+                        .map(|byte| Value::Integer(byte.into(), Signedness::Unsigned, 8));
+                    Ok(Value::Array(bytes.collect()))
+                }
+                _ => Err(InterpreterError::Unsupported),
+            },
+            Some(builtin @ ("to_le_bits" | "to_be_bits" | "to_le_radix" | "to_be_radix")) => {
+                let value = argument()?.as_u128().ok_or(InterpreterError::Unsupported)?;
+                let radix = match builtin {
+                    "to_le_bits" | "to_be_bits" => 2,
+                    _ => argument()?.as_u128().ok_or(InterpreterError::Unsupported)?,
+                };
+                let Type::Array(length, element_type) = &return_type else {
+                    return Err(InterpreterError::Unsupported);
+                };
+                let length = length.evaluate_to_u64().ok_or(InterpreterError::Unsupported)?;
+                let mut digits = to_radix(value, radix, length)?;
+                if builtin.ends_with("be_bits") || builtin.ends_with("be_radix") {
+                    digits.reverse();
+                }
+                let digits = try_vecmap(digits, |digit| Value::from_u128(digit, element_type))?;
+                Ok(Value::Array(digits))
+            }
+            Some("slice_push_back") => {
+                let mut elements = slice_elements(argument()?)?;
+                elements.push(argument()?);
+                Ok(Value::Array(elements))
+            }
+            Some("slice_push_front") => {
+                let mut elements = slice_elements(argument()?)?;
+                elements.insert(0, argument()?);
+                Ok(Value::Array(elements))
+            }
+            Some("slice_pop_back") => {
+                let mut elements = slice_elements(argument()?)?;
+                get_element(&mut elements, 0, location)?;
+                let last = elements.pop().expect("The slice was checked to be non-empty");
+                Ok(Value::Tuple(vec![Value::Array(elements), last]))
+            }
+            Some("slice_pop_front") => {
+                let mut elements = slice_elements(argument()?)?;
+                get_element(&mut elements, 0, location)?;
+                let first = elements.remove(0);
+                Ok(Value::Tuple(vec![first, Value::Array(elements)]))
+            }
+            Some("slice_insert") => {
+                let mut elements = slice_elements(argument()?)?;
+                let index = argument()?.as_u128().ok_or(InterpreterError::Unsupported)?;
+                let element = argument()?;
+                if index > elements.len() as u128 {
+                    let length = elements.len();
+                    return Err(InterpreterError::IndexOutOfBounds { index, length, location });
+                }
+                elements.insert(index as usize, element);
+                Ok(Value::Array(elements))
+            }
+            Some("slice_remove") => {
+                let mut elements = slice_elements(argument()?)?;
+                let index = argument()?.as_u128().ok_or(InterpreterError::Unsupported)?;
+                get_element(&mut elements, index, location)?;
+                let element = elements.remove(index as usize);
+                Ok(Value::Tuple(vec![Value::Array(elements), element]))
+            }
+            _ => Err(InterpreterError::Unsupported),
+        }
+    }
+
+    fn evaluate_cast(&mut self, cast: HirCastExpression) -> IResult<Value> {
+        let typ = concrete_type(cast.r#type);
+        self.evaluate(cast.lhs)?.cast(&typ)
+    }
+
+    fn evaluate_if(&mut self, if_expr: HirIfExpression) -> IResult<Value> {
+        match self.evaluate(if_expr.condition)? {
+            Value::Bool(true) => self.evaluate(if_expr.consequence),
+            Value::Bool(false) => match if_expr.alternative {
+                Some(alternative) => self.evaluate(alternative),
+                None => Ok(Value::Unit),
+            },
+            _ => Err(InterpreterError::Unsupported),
+        }
+    }
+
+    fn evaluate_match(&mut self, match_expr: HirMatchExpression) -> IResult<Value> {
+        let value = self.evaluate(match_expr.expression)?;
+        for (pattern, body) in match_expr.rules {
+            if self.match_pattern(&pattern, &value)? {
+                return self.evaluate(body);
+            }
+        }
+        Err(InterpreterError::Unsupported)
+    }
+
+    /// Returns true if `value` matches `pattern`, defining the variables it binds.
+    fn match_pattern(&mut self, pattern: &HirMatchPattern, value: &Value) -> IResult<bool> {
+        match pattern {
+            HirMatchPattern::Wildcard(_) => Ok(true),
+            HirMatchPattern::Identifier(ident) => {
+                self.define(ident.id, value.clone());
+                Ok(true)
+            }
+            HirMatchPattern::Literal(literal, _) => self.evaluate(*literal)?.equals(value),
+            HirMatchPattern::Tuple(patterns, _) => match value {
+                Value::Tuple(fields) => self.match_fields(patterns.iter().zip(fields)),
+                _ => Err(InterpreterError::Unsupported),
+            },
+            HirMatchPattern::Struct(typ, patterns, _) => {
+                let Value::Struct(fields) = value else {
+                    return Err(InterpreterError::Unsupported);
+                };
+                let names = struct_field_names(typ)?;
+                let fields = try_vecmap(patterns, |(name, pattern)| {
+                    field_position(&names, &name.0.contents).map(|index| (pattern, &fields[index]))
+                })?;
+                self.match_fields(fields.into_iter())
+            }
+            HirMatchPattern::Variant(typ, variant, patterns, _) => {
+                let (Value::Struct(fields), Type::Struct(definition, _)) =
+                    (value, typ.follow_bindings())
+                else {
+                    return Err(InterpreterError::Unsupported);
+                };
+                let tag = fields.first().ok_or(InterpreterError::Unsupported)?;
+                if !enum_tag(*variant).equals(tag)? {
+                    return Ok(false);
+                }
+
+                let offset = definition.borrow().variant_field_offset(*variant);
+                self.match_fields(patterns.iter().zip(&fields[offset..]))
+            }
+        }
+    }
+
+    fn match_fields<'a>(
+        &mut self,
+        fields: impl Iterator<Item = (&'a HirMatchPattern, &'a Value)>,
+    ) -> IResult<bool> {
+        for (pattern, field) in fields {
+            if !self.match_pattern(pattern, field)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    fn evaluate_lambda(&mut self, lambda: HirLambda) -> IResult<Value> {
+        let environment =
+            try_vecmap(&lambda.captures, |capture| match self.locals.get(&capture.ident.id) {
+                Some(slot) => Ok(slot.borrow().clone()),
+                None => Err(InterpreterError::Unsupported),
+            })?;
+        Ok(Value::Closure(Rc::new(lambda), environment))
+    }
+
+    fn define(&mut self, variable: DefinitionId, value: Value) {
+        self.locals.insert(variable, Rc::new(RefCell::new(value)));
+    }
+
+    fn define_pattern(&mut self, pattern: &HirPattern, value: Value) -> IResult<()> {
+        match (pattern, value) {
+            (HirPattern::Identifier(ident), value) => {
+                self.define(ident.id, value);
+                Ok(())
+            }
+            (HirPattern::Mutable(pattern, _), value) => self.define_pattern(pattern, value),
+            (HirPattern::Tuple(patterns, _), Value::Tuple(fields)) => patterns
+                .iter()
+                .zip(fields)
+                .try_for_each(|(pattern, field)| self.define_pattern(pattern, field)),
+            (HirPattern::Struct(typ, patterns, _), Value::Struct(mut fields)) => {
+                let names = struct_field_names(typ)?;
+                patterns.iter().try_for_each(|(name, pattern)| {
+                    let index = field_position(&names, &name.0.contents)?;
+                    let field = std::mem::replace(&mut fields[index], Value::Unit);
+                    self.define_pattern(pattern, field)
+                })
+            }
+            _ => Err(InterpreterError::Unsupported),
+        }
+    }
+
+    /// Assigns `value` to `lvalue`.
+    fn store(&mut self, lvalue: HirLValue, value: Value) -> IResult<()> {
+        let (slot, projections) = self.resolve_lvalue(lvalue)?;
+        let mut slot = slot.borrow_mut();
+        *project(&mut slot, &projections)? = value;
+        Ok(())
+    }
+
+    /// Returns the slot of the variable or reference `lvalue` assigns to, along with the
+    /// fields and elements to follow within its value. Any index is evaluated before the slot
+    /// is accessed, as it may read from the same variable.
+    fn resolve_lvalue(
+        &mut self,
+        lvalue: HirLValue,
+    ) -> IResult<(Rc<RefCell<Value>>, Vec<Projection>)> {
+        match lvalue {
+            HirLValue::Ident(ident, _) => match self.locals.get(&ident.id) {
+                Some(slot) => Ok((slot.clone(), Vec::new())),
+                None => Err(InterpreterError::Unsupported),
+            },
+            HirLValue::MemberAccess { object, field_index, .. } => {
+                let (slot, mut projections) = self.resolve_lvalue(*object)?;
+                let field_index = field_index.ok_or(InterpreterError::Unsupported)?;
+                projections.push(Projection::Field(field_index));
+                Ok((slot, projections))
+            }
+            HirLValue::Index { array, index, .. } => {
+                let (slot, mut projections) = self.resolve_lvalue(*array)?;
+                let location = self.interner.expr_location(&index);
+                let index = self.evaluate(index)?.as_u128().ok_or(InterpreterError::Unsupported)?;
+                projections.push(Projection::Index(index, location));
+                Ok((slot, projections))
+            }
+            HirLValue::Dereference { lvalue, .. } => {
+                let (slot, projections) = self.resolve_lvalue(*lvalue)?;
+                let mut value = slot.borrow_mut();
+                let pointer = match project(&mut value, &projections)? {
+                    Value::Pointer(pointer) => pointer.clone(),
+                    _ => return Err(InterpreterError::Unsupported),
+                };
+                Ok((pointer, Vec::new()))
+            }
+        }
+    }
+
+    fn resolve_trait_method(&self, id: ExprId, method: TraitMethodId) -> IResult<FuncId> {
+        let impl_id = match self.interner.get_selected_impl_for_expression(id) {
+            Some(TraitImplKind::Normal(impl_id)) => impl_id,
+            Some(TraitImplKind::Assumed { object_type, trait_generics }) => {
+                match self.interner.lookup_trait_implementation(
+                    &object_type,
+                    method.trait_id,
+                    &trait_generics,
+                ) {
+                    Ok(TraitImplKind::Normal(impl_id)) => impl_id,
+                    _ => return Err(InterpreterError::Unsupported),
+                }
+            }
+            None => return Err(InterpreterError::Unsupported),
+        };
+        Ok(self.interner.get_trait_implementation(impl_id).borrow().methods[method.method_index])
+    }
+
+    /// The bindings of the generics of the function referred to by `id`, followed so that they
+    /// still hold once the bindings of the current function are undone.
+    fn instantiation_bindings(&self, id: ExprId) -> Rc<TypeBindings> {
+        let bindings = self.interner.get_instantiation_bindings(id);
+        let bindings = bindings
+            .iter()
+            .map(|(id, (variable, binding))| (*id, (variable.clone(), binding.follow_bindings())))
+            .collect();
+        Rc::new(bindings)
+    }
+}
+
+/// Follows the bindings of `typ`, defaulting any integer literal type which was never
+/// constrained to a Field.
+pub(crate) fn concrete_type(typ: Type) -> Type {
+    match typ.follow_bindings() {
+        Type::TypeVariable(variable, kind) if variable.borrow().is_unbound() => kind.default_type(),
+        typ => typ,
+    }
+}
+
+/// Binds each type variable in `bindings`, returning their previous bindings. Unlike during
+/// monomorphization, a generic function may be called again while it is still being evaluated,
+/// so the bindings of the outer call must be restored rather than unbound once a call returns.
+fn bind_type_variables(bindings: &TypeBindings) -> Vec<(TypeVariable, TypeBinding)> {
+    vecmap(bindings.values(), |(variable, binding)| bind_type_variable(variable, binding.clone()))
+}
+
+fn bind_type_variable(variable: &TypeVariable, binding: Type) -> (TypeVariable, TypeBinding) {
+    let previous = variable.borrow().clone();
+    variable.force_bind(binding);
+    (variable.clone(), previous)
+}
+
+fn restore_type_variables(previous_bindings: Vec<(TypeVariable, TypeBinding)>) {
+    for (variable, binding) in previous_bindings.into_iter().rev() {
+        match binding {
+            TypeBinding::Bound(binding) => variable.force_bind(binding),
+            TypeBinding::Unbound(id) => variable.unbind(id),
+        }
+    }
+}
+
+/// Follows `projections` into `value`, returning the field or element they lead to.
+fn project<'a>(mut value: &'a mut Value, projections: &[Projection]) -> IResult<&'a mut Value> {
+    for projection in projections {
+        value = match (projection, value) {
+            (Projection::Field(index), Value::Tuple(fields) | Value::Struct(fields)) => {
+                fields.get_mut(*index).ok_or(InterpreterError::Unsupported)?
+            }
+            (Projection::Index(index, location), Value::Array(elements)) => {
+                get_element(elements, *index, *location)?
+            }
+            _ => return Err(InterpreterError::Unsupported),
+        };
+    }
+    Ok(value)
+}
+
+fn get_element(elements: &mut [Value], index: u128, location: Location) -> IResult<&mut Value> {
+    let length = elements.len();
+    usize::try_from(index)
+        .ok()
+        .and_then(|index| elements.get_mut(index))
+        .ok_or(InterpreterError::IndexOutOfBounds { index, length, location })
+}
+
+fn slice_elements(slice: Value) -> IResult<Vec<Value>> {
+    match slice {
+        Value::Array(elements) => Ok(elements),
+        _ => Err(InterpreterError::Unsupported),
+    }
+}
+
+/// Decomposes `value` into `length` digits of the given radix, from least to most significant.
+/// Values which don't fit are left to fail at runtime.
+fn to_radix(mut value: u128, radix: u128, length: u64) -> IResult<Vec<u128>> {
+    if !(2..=256).contains(&radix) {
+        return Err(InterpreterError::Unsupported);
+    }
+    let digits = vecmap(0..length, |_| {
+        let digit = value % radix;
+        value /= radix;
+        digit
+    });
+    if value != 0 {
+        return Err(InterpreterError::Unsupported);
+    }
+    Ok(digits)
+}
+
+/// The tag identifying the given variant of an enum
+fn enum_tag(variant: usize) -> Value {
+    Value::Field(FieldElement::from(variant as u128))
+}
+
+fn struct_field_names(typ: &Type) -> IResult<Vec<String>> {
+    match typ.follow_bindings() {
+        Type::Struct(definition, generics) => {
+            Ok(vecmap(definition.borrow().get_fields(&generics), |(name, _)| name))
+        }
+        _ => Err(InterpreterError::Unsupported),
+    }
+}
+
+fn field_position(names: &[String], name: &str) -> IResult<usize> {
+    names.iter().position(|field_name| field_name == name).ok_or(InterpreterError::Unsupported)
+}
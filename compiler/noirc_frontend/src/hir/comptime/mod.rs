@@ -0,0 +1,50 @@
+//! This module evaluates the initializers of globals at compile-time.
+//!
+//! Once type checking is finished, each global which isn't already a literal is interpreted
+//! directly on the HIR, calling into any functions it uses. If this succeeds, the initializer is
+//! replaced with a literal of the resulting value, so that the functions are no longer called
+//! at each use of the global. Failed assertions, overflows and out of bounds accesses found
+//! along the way are reported as errors.
+//!
+//! Anything which can't be evaluated at compile-time, such as a call to an oracle or a black box
+//! function, leaves the global as it is to be evaluated at runtime instead. The depth of calls and
+//! the number of loop iterations are limited so that evaluating a global always terminates.
+//!
+//! Globals are evaluated after the globals they depend on, and the value of each is kept in the
+//! [NodeInterner] so that it is only evaluated once across the crate graph.
+mod dependencies;
+mod errors;
+mod interpreter;
+mod value;
+
+pub(crate) use dependencies::global_dependencies;
+pub use errors::InterpreterError;
+pub(crate) use value::Value;
+
+use crate::node_interner::{DefinitionId, ExprId, NodeInterner};
+
+use self::interpreter::Interpreter;
+
+/// Evaluates the initializer of a global, replacing it with a literal of its value.
+pub(crate) fn evaluate_global(
+    interner: &mut NodeInterner,
+    global: DefinitionId,
+    expression: ExprId,
+) -> Result<(), InterpreterError> {
+    let mut interpreter = Interpreter::new(interner);
+    let typ = interpreter.type_of(expression);
+    let value = match interpreter.evaluate(expression) {
+        Ok(value) => value,
+        Err(InterpreterError::Unsupported) => return Ok(()),
+        Err(error) => return Err(error),
+    };
+
+    let location = interner.expr_location(&expression);
+    // References and functions can't be written as a literal, nor shared between each use of
+    // the global, so these are evaluated again at each use.
+    if let Some(literal) = value.clone().into_hir_expression(interner, &typ, location) {
+        interner.replace_expr(&expression, literal);
+        interner.push_global_value(global, value);
+    }
+    Ok(())
+}
@@ -0,0 +1,437 @@
+use std::{cell::RefCell, rc::Rc};
+
+use acvm::FieldElement;
+use iter_extended::try_vecmap;
+use noirc_errors::Location;
+
+use crate::{
+    hir_def::expr::{
+        HirArrayLiteral, HirConstructorExpression, HirExpression, HirLambda, HirLiteral,
+    },
+    node_interner::{ExprId, FuncId, NodeInterner, TraitMethodId},
+    BinaryOpKind, Ident, Signedness, Type, TypeBindings, TypeLevelValue,
+};
+
+use super::errors::{IResult, InterpreterError};
+
+/// A value computed at compile-time by the [Interpreter][super::interpreter::Interpreter].
+#[derive(Debug, Clone)]
+pub(crate) enum Value {
+    Unit,
+    Bool(bool),
+    Field(FieldElement),
+    /// An integer of the given signedness and bit size, held in two's complement
+    Integer(u128, Signedness, u32),
+    String(Rc<String>),
+    /// The elements of an array or slice
+    Array(Vec<Value>),
+    Tuple(Vec<Value>),
+    /// The fields of a struct, in the order they are defined in
+    Struct(Vec<Value>),
+    /// A mutable reference, sharing the value it refers to
+    Pointer(Rc<RefCell<Value>>),
+    /// A function along with the bindings of its generics where it is referred to, and the
+    /// trait method it implements if it is referred to through its trait
+    Function(FuncId, Rc<TypeBindings>, Option<TraitMethodId>),
+    /// A closure along with the values of the variables it captures
+    Closure(Rc<HirLambda>, Vec<Value>),
+}
+
+impl Value {
+    /// Returns the value of an integer literal of type `typ`.
+    pub(crate) fn integer_literal(
+        value: FieldElement,
+        negative: bool,
+        typ: &Type,
+    ) -> IResult<Value> {
+        match typ {
+            Type::FieldElement => Ok(Value::Field(if negative { -value } else { value })),
+            Type::Integer(signedness, bit_size) => {
+                check_integer_type(*signedness, *bit_size)?;
+                let magnitude = value.try_into_u128().ok_or(InterpreterError::Unsupported)?;
+                let value = match (signedness, negative) {
+                    (Signedness::Signed, true) => {
+                        let magnitude =
+                            i128::try_from(magnitude).map_err(|_| InterpreterError::Unsupported)?;
+                        from_signed(-magnitude, *bit_size)
+                    }
+                    (Signedness::Signed, false) => i128::try_from(magnitude)
+                        .ok()
+                        .and_then(|value| from_signed(value, *bit_size)),
+                    (Signedness::Unsigned, true) => None,
+                    (Signedness::Unsigned, false) => {
+                        (magnitude <= max_unsigned(*bit_size)).then_some(magnitude)
+                    }
+                };
+                // Literals which don't fit their type are reported where they are used instead.
+                let value = value.ok_or(InterpreterError::Unsupported)?;
+                Ok(Value::Integer(value, *signedness, *bit_size))
+            }
+            _ => Err(InterpreterError::Unsupported),
+        }
+    }
+
+    /// Returns `value` as a field element or integer of type `typ`.
+    pub(crate) fn from_u128(value: u128, typ: &Type) -> IResult<Value> {
+        match typ {
+            Type::FieldElement => Ok(Value::Field(value.into())),
+            Type::Integer(signedness, bit_size) => {
+                Value::integer_literal(value.into(), false, &Type::Integer(*signedness, *bit_size))
+            }
+            _ => Err(InterpreterError::Unsupported),
+        }
+    }
+
+    /// Returns the constant given to a generic declared with `let` as a value of type `typ`.
+    pub(crate) fn from_type_level_value(value: &TypeLevelValue, typ: &Type) -> IResult<Value> {
+        match (value, typ) {
+            (TypeLevelValue::Field(value), _) => Value::integer_literal(*value, false, typ),
+            (TypeLevelValue::Array(elements), Type::Array(_, element_type)) => {
+                let elements = try_vecmap(elements, |element| {
+                    Value::from_type_level_value(element, element_type)
+                })?;
+                Ok(Value::Array(elements))
+            }
+            _ => Err(InterpreterError::Unsupported),
+        }
+    }
+
+    /// Returns the value of a field element or integer, as long as it is not negative and fits
+    /// in a u128.
+    pub(crate) fn as_u128(&self) -> Option<u128> {
+        match self {
+            Value::Field(value) => value.try_into_u128(),
+            Value::Integer(value, Signedness::Unsigned, _) => Some(*value),
+            Value::Integer(value, Signedness::Signed, bit_size) => {
+                (to_signed(*value, *bit_size) >= 0).then_some(*value)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the value of the given type whose fields and elements are all zero.
+    pub(crate) fn zeroed(typ: &Type) -> IResult<Value> {
+        Ok(match typ {
+            Type::FieldElement => Value::Field(FieldElement::zero()),
+            Type::Integer(signedness, bit_size) => Value::Integer(0, *signedness, *bit_size),
+            Type::Bool => Value::Bool(false),
+            Type::Unit => Value::Unit,
+            Type::Array(length, element) => match length.evaluate_to_u64() {
+                Some(length) => Value::Array(try_vecmap(0..length, |_| Value::zeroed(element))?),
+                // Slices are zeroed to an empty slice
+                None => Value::Array(Vec::new()),
+            },
+            Type::String(length) => {
+                let length = length.evaluate_to_u64().ok_or(InterpreterError::Unsupported)?;
+                Value::String(Rc::new("\0".repeat(length as usize)))
+            }
+            Type::Tuple(fields) => Value::Tuple(try_vecmap(fields, Value::zeroed)?),
+            Type::Struct(definition, generics) => {
+                let fields = definition.borrow().get_fields(generics);
+                Value::Struct(try_vecmap(fields, |(_, typ)| Value::zeroed(&typ))?)
+            }
+            _ => return Err(InterpreterError::Unsupported),
+        })
+    }
+
+    /// Returns true if this value is equal to `other`. Only primitive values are compared
+    /// directly, the others are compared through their `Eq` impl.
+    pub(crate) fn equals(&self, other: &Value) -> IResult<bool> {
+        match (self, other) {
+            (Value::Unit, Value::Unit) => Ok(true),
+            (Value::Bool(lhs), Value::Bool(rhs)) => Ok(lhs == rhs),
+            (Value::Field(lhs), Value::Field(rhs)) => Ok(lhs == rhs),
+            (Value::Integer(lhs, ..), Value::Integer(rhs, ..)) => Ok(lhs == rhs),
+            _ => Err(InterpreterError::Unsupported),
+        }
+    }
+
+    pub(crate) fn binary(
+        self,
+        operator: BinaryOpKind,
+        rhs: Value,
+        location: Location,
+    ) -> IResult<Value> {
+        match (self, rhs) {
+            (Value::Field(lhs), Value::Field(rhs)) => field_binary(lhs, operator, rhs, location),
+            (Value::Integer(lhs, signedness, bit_size), Value::Integer(rhs, ..)) => {
+                integer_binary(lhs, operator, rhs, signedness, bit_size, location)
+            }
+            (Value::Bool(lhs), Value::Bool(rhs)) => bool_binary(lhs, operator, rhs),
+            _ => Err(InterpreterError::Unsupported),
+        }
+    }
+
+    pub(crate) fn negate(self, location: Location) -> IResult<Value> {
+        match self {
+            Value::Field(value) => Ok(Value::Field(-value)),
+            Value::Integer(value, Signedness::Signed, bit_size) => {
+                from_signed(-to_signed(value, bit_size), bit_size)
+                    .map(|value| Value::Integer(value, Signedness::Signed, bit_size))
+                    .ok_or(InterpreterError::IntegerOverflow { operation: "negate", location })
+            }
+            _ => Err(InterpreterError::Unsupported),
+        }
+    }
+
+    pub(crate) fn not(self) -> IResult<Value> {
+        match self {
+            Value::Bool(value) => Ok(Value::Bool(!value)),
+            Value::Integer(value, signedness, bit_size) => {
+                Ok(Value::Integer(!value & max_unsigned(bit_size), signedness, bit_size))
+            }
+            _ => Err(InterpreterError::Unsupported),
+        }
+    }
+
+    /// Casts this value to `typ`, keeping only the lowest bits of the value when casting to an
+    /// integer type.
+    pub(crate) fn cast(self, typ: &Type) -> IResult<Value> {
+        let value = match self {
+            Value::Field(value) => value,
+            Value::Bool(value) => value.into(),
+            Value::Integer(value, signedness, bit_size) => {
+                // The representation of negative integers differs between types.
+                if signedness == Signedness::Signed && to_signed(value, bit_size) < 0 {
+                    return Err(InterpreterError::Unsupported);
+                }
+                value.into()
+            }
+            _ => return Err(InterpreterError::Unsupported),
+        };
+
+        match typ {
+            Type::FieldElement => Ok(Value::Field(value)),
+            Type::Integer(signedness, bit_size) => {
+                check_integer_type(*signedness, *bit_size)?;
+                let bytes = value.to_be_bytes();
+                let low_bytes = bytes[bytes.len() - 16..]
+                    .try_into()
+                    .expect("Field elements hold at least 16 bytes");
+                let value = u128::from_be_bytes(low_bytes) & max_unsigned(*bit_size);
+                Ok(Value::Integer(value, *signedness, *bit_size))
+            }
+            _ => Err(InterpreterError::Unsupported),
+        }
+    }
+
+    /// Converts this value into an expression of type `typ`, or returns None if it holds a
+    /// reference or a function, which can't be written as an expression.
+    pub(crate) fn into_expression(
+        self,
+        interner: &mut NodeInterner,
+        typ: &Type,
+        location: Location,
+    ) -> Option<ExprId> {
+        let expression = self.into_hir_expression(interner, typ, location)?;
+        let id = interner.push_expr(expression);
+        interner.push_expr_location(id, location.span, location.file);
+        interner.push_expr_type(&id, typ.clone());
+        Some(id)
+    }
+
+    pub(crate) fn into_hir_expression(
+        self,
+        interner: &mut NodeInterner,
+        typ: &Type,
+        location: Location,
+    ) -> Option<HirExpression> {
+        let literal = match self {
+            Value::Unit => HirLiteral::Unit,
+            Value::Bool(value) => HirLiteral::Bool(value),
+            Value::Field(value) => HirLiteral::Integer(value, false),
+            Value::Integer(value, Signedness::Signed, bit_size) => {
+                let value = to_signed(value, bit_size);
+                HirLiteral::Integer(value.unsigned_abs().into(), value < 0)
+            }
+            Value::Integer(value, Signedness::Unsigned, _) => {
+                HirLiteral::Integer(value.into(), false)
+            }
+            Value::String(value) => HirLiteral::Str(value.as_ref().clone()),
+            Value::Array(elements) => {
+                let Type::Array(_, element_type) = typ else { return None };
+                let elements = elements
+                    .into_iter()
+                    .map(|element| element.into_expression(interner, element_type, location))
+                    .collect::<Option<_>>()?;
+                HirLiteral::Array(HirArrayLiteral::Standard(elements))
+            }
+            Value::Tuple(fields) => {
+                let Type::Tuple(field_types) = typ else { return None };
+                let fields = fields
+                    .into_iter()
+                    .zip(field_types)
+                    .map(|(field, typ)| field.into_expression(interner, typ, location))
+                    .collect::<Option<_>>()?;
+                return Some(HirExpression::Tuple(fields));
+            }
+            Value::Struct(fields) => {
+                let Type::Struct(definition, generics) = typ else { return None };
+                let field_types = definition.borrow().get_fields(generics);
+                let fields = fields
+                    .into_iter()
+                    .zip(field_types)
+                    .map(|(field, (name, typ))| {
+                        let field = field.into_expression(interner, &typ, location)?;
+                        Some((Ident::new(name, location.span), field))
+                    })
+                    .collect::<Option<_>>()?;
+                return Some(HirExpression::Constructor(HirConstructorExpression {
+                    r#type: definition.clone(),
+                    struct_generics: generics.clone(),
+                    fields,
+                }));
+            }
+            Value::Pointer(_) | Value::Function(..) | Value::Closure(..) => return None,
+        };
+        Some(HirExpression::Literal(literal))
+    }
+}
+
+fn field_binary(
+    lhs: FieldElement,
+    operator: BinaryOpKind,
+    rhs: FieldElement,
+    location: Location,
+) -> IResult<Value> {
+    Ok(match operator {
+        BinaryOpKind::Add => Value::Field(lhs + rhs),
+        BinaryOpKind::Subtract => Value::Field(lhs - rhs),
+        BinaryOpKind::Multiply => Value::Field(lhs * rhs),
+        BinaryOpKind::Divide if rhs.is_zero() => {
+            return Err(InterpreterError::DivisionByZero { location })
+        }
+        BinaryOpKind::Divide => Value::Field(lhs / rhs),
+        BinaryOpKind::Equal => Value::Bool(lhs == rhs),
+        BinaryOpKind::NotEqual => Value::Bool(lhs != rhs),
+        BinaryOpKind::Less => Value::Bool(lhs < rhs),
+        BinaryOpKind::LessEqual => Value::Bool(lhs <= rhs),
+        BinaryOpKind::Greater => Value::Bool(lhs > rhs),
+        BinaryOpKind::GreaterEqual => Value::Bool(lhs >= rhs),
+        _ => return Err(InterpreterError::Unsupported),
+    })
+}
+
+fn integer_binary(
+    lhs: u128,
+    operator: BinaryOpKind,
+    rhs: u128,
+    signedness: Signedness,
+    bit_size: u32,
+    location: Location,
+) -> IResult<Value> {
+    use BinaryOpKind::*;
+    let integer = |value| Ok(Value::Integer(value, signedness, bit_size));
+
+    match operator {
+        Equal => return Ok(Value::Bool(lhs == rhs)),
+        NotEqual => return Ok(Value::Bool(lhs != rhs)),
+        And => return integer(lhs & rhs),
+        Or => return integer(lhs | rhs),
+        Xor => return integer(lhs ^ rhs),
+        Divide | Modulo if rhs == 0 => return Err(InterpreterError::DivisionByZero { location }),
+        _ => (),
+    }
+
+    let overflow = |operation| InterpreterError::IntegerOverflow { operation, location };
+    match signedness {
+        Signedness::Unsigned => {
+            let max = max_unsigned(bit_size);
+            let checked = |value: Option<u128>, operation| match value {
+                Some(value) if value <= max => integer(value),
+                _ => Err(overflow(operation)),
+            };
+            match operator {
+                Add => checked(lhs.checked_add(rhs), "add"),
+                Subtract => checked(lhs.checked_sub(rhs), "subtract"),
+                Multiply => checked(lhs.checked_mul(rhs), "multiply"),
+                Divide => integer(lhs / rhs),
+                Modulo => integer(lhs % rhs),
+                Less => Ok(Value::Bool(lhs < rhs)),
+                LessEqual => Ok(Value::Bool(lhs <= rhs)),
+                Greater => Ok(Value::Bool(lhs > rhs)),
+                GreaterEqual => Ok(Value::Bool(lhs >= rhs)),
+                ShiftRight => {
+                    integer(lhs.checked_shr(rhs.try_into().unwrap_or(u32::MAX)).unwrap_or(0))
+                }
+                // Shifting bits out of the integer is left to be checked at runtime.
+                ShiftLeft => match u32::try_from(rhs).ok().and_then(|rhs| lhs.checked_shl(rhs)) {
+                    Some(value) if value <= max && value >> rhs == lhs => integer(value),
+                    _ => Err(InterpreterError::Unsupported),
+                },
+                Equal | NotEqual | And | Or | Xor => unreachable!(),
+            }
+        }
+        Signedness::Signed => {
+            let (lhs, rhs) = (to_signed(lhs, bit_size), to_signed(rhs, bit_size));
+            let checked = |value: Option<i128>, operation| match value
+                .and_then(|value| from_signed(value, bit_size))
+            {
+                Some(value) => integer(value),
+                None => Err(overflow(operation)),
+            };
+            match operator {
+                Add => checked(lhs.checked_add(rhs), "add"),
+                Subtract => checked(lhs.checked_sub(rhs), "subtract"),
+                Multiply => checked(lhs.checked_mul(rhs), "multiply"),
+                Divide => checked(lhs.checked_div(rhs), "divide"),
+                Modulo => checked(lhs.checked_rem(rhs), "calculate the remainder"),
+                Less => Ok(Value::Bool(lhs < rhs)),
+                LessEqual => Ok(Value::Bool(lhs <= rhs)),
+                Greater => Ok(Value::Bool(lhs > rhs)),
+                GreaterEqual => Ok(Value::Bool(lhs >= rhs)),
+                ShiftLeft | ShiftRight => Err(InterpreterError::Unsupported),
+                Equal | NotEqual | And | Or | Xor => unreachable!(),
+            }
+        }
+    }
+}
+
+fn bool_binary(lhs: bool, operator: BinaryOpKind, rhs: bool) -> IResult<Value> {
+    Ok(Value::Bool(match operator {
+        BinaryOpKind::Equal => lhs == rhs,
+        BinaryOpKind::NotEqual => lhs != rhs,
+        BinaryOpKind::And => lhs & rhs,
+        BinaryOpKind::Or => lhs | rhs,
+        BinaryOpKind::Xor => lhs ^ rhs,
+        _ => return Err(InterpreterError::Unsupported),
+    }))
+}
+
+/// Integers are held in a u128, so signed integers can have at most 127 bits.
+fn check_integer_type(signedness: Signedness, bit_size: u32) -> IResult<()> {
+    let max_bit_size = match signedness {
+        Signedness::Unsigned => 128,
+        Signedness::Signed => 127,
+    };
+    if bit_size == 0 || bit_size > max_bit_size {
+        return Err(InterpreterError::Unsupported);
+    }
+    Ok(())
+}
+
+/// The largest unsigned integer of `bit_size` bits.
+fn max_unsigned(bit_size: u32) -> u128 {
+    if bit_size >= 128 {
+        u128::MAX
+    } else {
+        (1 << bit_size) - 1
+    }
+}
+
+/// Interprets `value` as the two's complement of a signed integer of `bit_size` bits.
+fn to_signed(value: u128, bit_size: u32) -> i128 {
+    if (value >> (bit_size - 1)) & 1 == 1 {
+        value as i128 - (1 << bit_size)
+    } else {
+        value as i128
+    }
+}
+
+/// Returns the two's complement of `value` in `bit_size` bits, if it is in the range of a
+/// signed integer of that size.
+fn from_signed(value: i128, bit_size: u32) -> Option<u128> {
+    let max = (1i128 << (bit_size - 1)) - 1;
+    let min = -max - 1;
+    (min..=max).contains(&value).then(|| value as u128 & max_unsigned(bit_size))
+}
@@ -1,6 +1,7 @@
 use super::dc_mod::collect_defs;
 use super::errors::{DefCollectorErrorKind, DuplicateType};
 use crate::graph::CrateId;
+use crate::hir::comptime::{self, InterpreterError};
 use crate::hir::def_map::{CrateDefMap, LocalModuleId, ModuleId};
 use crate::hir::resolution::errors::ResolverError;
 
@@ -8,14 +9,18 @@ use crate::hir::resolution::import::{resolve_import, ImportDirective};
 use crate::hir::resolution::resolver::Resolver;
 use crate::hir::resolution::{
     collect_impls, collect_trait_impls, path_resolver, resolve_free_functions, resolve_globals,
-    resolve_impls, resolve_structs, resolve_trait_by_path, resolve_trait_impls, resolve_traits,
-    resolve_type_aliases,
+    resolve_impls, resolve_struct_generics, resolve_structs, resolve_trait_by_path,
+    resolve_trait_impls, resolve_traits, resolve_type_aliases,
 };
 use crate::hir::type_check::{type_check_func, TypeCheckError, TypeChecker};
 use crate::hir::Context;
+use crate::hir_def::expr::{HirExpression, HirLiteral};
+use crate::hir_def::stmt::HirPattern;
 
 use crate::macros_api::MacroProcessor;
-use crate::node_interner::{FuncId, NodeInterner, StmtId, StructId, TraitId, TypeAliasId};
+use crate::node_interner::{
+    DefinitionId, ExprId, FuncId, NodeInterner, StmtId, StructId, TraitId, TypeAliasId,
+};
 
 use crate::parser::{ParserError, SortedModule};
 use crate::{
@@ -26,7 +31,7 @@ use crate::{
 use fm::FileId;
 use iter_extended::vecmap;
 use noirc_errors::{CustomDiagnostic, Span};
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use std::vec;
 
@@ -94,6 +99,9 @@ pub struct UnresolvedTraitImpl {
     pub trait_path: Path,
     pub object_type: UnresolvedType,
     pub methods: UnresolvedFunctions,
+    /// Copies of the trait's default methods for each method this impl does not override.
+    /// These are kept separate from `methods` since they are resolved in the trait's module.
+    pub default_methods: UnresolvedFunctions,
     pub generics: UnresolvedGenerics,
     pub where_clause: Vec<UnresolvedTraitConstraint>,
 }
@@ -142,6 +150,7 @@ pub enum CompilationError {
     DefinitionError(DefCollectorErrorKind),
     ResolverError(ResolverError),
     TypeError(TypeCheckError),
+    InterpreterError(InterpreterError),
 }
 
 impl From<CompilationError> for CustomDiagnostic {
@@ -151,6 +160,7 @@ impl From<CompilationError> for CustomDiagnostic {
             CompilationError::DefinitionError(error) => error.into(),
             CompilationError::ResolverError(error) => error.into(),
             CompilationError::TypeError(error) => error.into(),
+            CompilationError::InterpreterError(error) => error.into(),
         }
     }
 }
@@ -178,6 +188,12 @@ impl From<TypeCheckError> for CompilationError {
     }
 }
 
+impl From<InterpreterError> for CompilationError {
+    fn from(value: InterpreterError) -> Self {
+        CompilationError::InterpreterError(value)
+    }
+}
+
 impl DefCollector {
     fn new(def_map: CrateDefMap) -> DefCollector {
         DefCollector {
@@ -301,6 +317,10 @@ impl DefCollector {
 
         let mut resolved_globals = resolve_globals(context, literal_globals, crate_id);
 
+        // Resolve the generics of structs before any types which may refer to them, so that the
+        // constants given to their generics declared with `let` can be checked.
+        errors.extend(resolve_struct_generics(context, &def_collector.collected_types, crate_id));
+
         errors.extend(resolve_type_aliases(
             context,
             def_collector.collected_type_aliases,
@@ -361,12 +381,24 @@ impl DefCollector {
         for macro_processor in macro_processors {
             macro_processor.process_typed_ast(&crate_id, context);
         }
+        let global_ids = resolved_globals.globals.clone();
         errors.extend(type_check_globals(&mut context.def_interner, resolved_globals.globals));
 
         // Type check all of the functions in the crate
         errors.extend(type_check_functions(&mut context.def_interner, file_func_ids));
         errors.extend(type_check_functions(&mut context.def_interner, file_method_ids));
         errors.extend(type_check_functions(&mut context.def_interner, file_trait_impls_ids));
+
+        let mut global_order = GlobalOrder::new(&context.def_interner, &global_ids);
+        let globals = global_order.sort();
+        errors.extend(global_order.errors);
+
+        // Globals can only be evaluated once the functions they call are known to be well-typed.
+        let has_errors =
+            errors.iter().any(|(error, _)| CustomDiagnostic::from(error.clone()).is_error());
+        if !has_errors {
+            errors.extend(evaluate_globals(&mut context.def_interner, &globals));
+        }
         errors
     }
 }
@@ -440,6 +472,115 @@ fn type_check_globals(
         .collect()
 }
 
+/// Orders the globals of a crate so that each comes after the globals it depends on, either
+/// directly or through the functions it calls. A global which depends on itself is reported as
+/// an error, as it could never be evaluated.
+struct GlobalOrder<'interner> {
+    interner: &'interner NodeInterner,
+
+    /// The initializer of each global in the crate, along with the globals it depends on
+    globals: HashMap<DefinitionId, (ExprId, Vec<DefinitionId>)>,
+
+    /// The globals in the crate, in the order they were declared in
+    declared: Vec<DefinitionId>,
+
+    /// The globals whose dependencies are currently being visited
+    stack: Vec<DefinitionId>,
+    visited: HashSet<DefinitionId>,
+    sorted: Vec<(DefinitionId, ExprId)>,
+    errors: Vec<(CompilationError, FileId)>,
+}
+
+impl<'interner> GlobalOrder<'interner> {
+    fn new(interner: &'interner NodeInterner, global_ids: &[(FileId, StmtId)]) -> Self {
+        let mut globals = HashMap::new();
+        let declared = vecmap(global_ids, |(_, stmt_id)| {
+            let let_statement = interner.let_statement(stmt_id);
+            let HirPattern::Identifier(ident) = let_statement.pattern else {
+                unreachable!("ICE: globals are always bound to an identifier")
+            };
+            let dependencies = comptime::global_dependencies(interner, let_statement.expression);
+            globals.insert(ident.id, (let_statement.expression, dependencies));
+            ident.id
+        });
+
+        GlobalOrder {
+            interner,
+            globals,
+            declared,
+            stack: Vec::new(),
+            visited: HashSet::new(),
+            sorted: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Returns the globals of the crate along with their initializers, sorted by their
+    /// dependencies.
+    fn sort(&mut self) -> Vec<(DefinitionId, ExprId)> {
+        for global in std::mem::take(&mut self.declared) {
+            self.visit(global);
+        }
+        std::mem::take(&mut self.sorted)
+    }
+
+    fn visit(&mut self, global: DefinitionId) {
+        if let Some(position) = self.stack.iter().position(|other| *other == global) {
+            self.report_cycle(position);
+            return;
+        }
+
+        // Globals from other crates were already evaluated
+        let Some((expression, dependencies)) = self.globals.get(&global) else { return };
+        if !self.visited.insert(global) {
+            return;
+        }
+
+        let expression = *expression;
+        self.stack.push(global);
+        for dependency in dependencies.clone() {
+            self.visit(dependency);
+        }
+        self.stack.pop();
+        self.sorted.push((global, expression));
+    }
+
+    fn report_cycle(&mut self, start: usize) {
+        let cycle = &self.stack[start..];
+        let names = vecmap(cycle.iter().chain(std::iter::once(&cycle[0])), |global| {
+            self.interner.definition_name(*global)
+        });
+        let location = self.interner.definition(cycle[0]).location;
+        let error = DefCollectorErrorKind::DependencyCycle {
+            name: names[0].to_string(),
+            cycle: names.join(" -> "),
+            span: location.span,
+        };
+        self.errors.push((error.into(), location.file));
+    }
+}
+
+/// Evaluates the globals which call functions or use other globals at compile-time, replacing
+/// each with a literal of its value where possible.
+fn evaluate_globals(
+    interner: &mut NodeInterner,
+    globals: &[(DefinitionId, ExprId)],
+) -> Vec<(CompilationError, fm::FileId)> {
+    globals
+        .iter()
+        .filter_map(|(global, expression)| {
+            if let HirExpression::Literal(literal) = interner.expression(expression) {
+                if !matches!(literal, HirLiteral::Array(_)) {
+                    return None;
+                }
+            }
+            let error = comptime::evaluate_global(interner, *global, *expression).err()?;
+            let file = error.location().file;
+            Some((error.into(), file))
+        })
+        .collect()
+}
+
 fn type_check_functions(
     interner: &mut NodeInterner,
     file_func_ids: Vec<(FileId, FuncId)>,
@@ -5,10 +5,11 @@ use fm::{FileId, FileManager, FILE_EXTENSION};
 use noirc_errors::Location;
 
 use crate::{
-    graph::CrateId,
+    graph::{CrateGraph, CrateId},
     hir::def_collector::dc_crate::{UnresolvedStruct, UnresolvedTrait},
     node_interner::{FunctionModifiers, TraitId, TypeAliasId},
     parser::{SortedModule, SortedSubModule},
+    token::SecondaryAttribute,
     FunctionDefinition, Ident, LetStatement, NoirFunction, NoirStruct, NoirTrait, NoirTraitImpl,
     NoirTypeAlias, TraitImplItem, TraitItem, TypeImpl,
 };
@@ -36,12 +37,14 @@ struct ModCollector<'a> {
 /// This performs the entirety of the definition collection phase of the name resolution pass.
 pub fn collect_defs(
     def_collector: &mut DefCollector,
-    ast: SortedModule,
+    mut ast: SortedModule,
     file_id: FileId,
     module_id: LocalModuleId,
     crate_id: CrateId,
     context: &mut Context,
 ) -> Vec<(CompilationError, FileId)> {
+    remove_disabled_items(&mut ast, &context.crate_graph, crate_id);
+
     let mut collector = ModCollector { def_collector, file_id, module_id };
     let mut errors: Vec<(CompilationError, FileId)> = vec![];
 
@@ -79,6 +82,38 @@ pub fn collect_defs(
     errors
 }
 
+/// Removes the functions, structs and methods of `ast` marked with a `cfg` attribute which isn't
+/// satisfied by the features crate `crate_id` is compiled with, the native field or the backend.
+fn remove_disabled_items(ast: &mut SortedModule, crate_graph: &CrateGraph, crate_id: CrateId) {
+    let features = &crate_graph[crate_id].features;
+    let is_enabled = |attributes: &[SecondaryAttribute]| {
+        attributes.iter().all(|attribute| match attribute {
+            SecondaryAttribute::Cfg { name, value, negated } => {
+                let satisfied = match name.as_str() {
+                    "feature" => features.contains(value),
+                    "field" => FieldOptions::is_native_field(&value.to_lowercase()),
+                    "backend" => crate_graph.backend.as_ref() == Some(value),
+                    _ => unreachable!("cfg options are checked by the lexer"),
+                };
+                satisfied != *negated
+            }
+            _ => true,
+        })
+    };
+
+    ast.functions.retain(|function| is_enabled(function.secondary_attributes()));
+    ast.types.retain(|typ| is_enabled(&typ.attributes));
+    for r#impl in &mut ast.impls {
+        r#impl.methods.retain(|method| is_enabled(method.secondary_attributes()));
+    }
+    for trait_impl in &mut ast.trait_impls {
+        trait_impl.items.retain(|item| match item {
+            TraitImplItem::Function(function) => is_enabled(function.secondary_attributes()),
+            TraitImplItem::Constant(..) | TraitImplItem::Type { .. } => true,
+        });
+    }
+}
+
 impl<'a> ModCollector<'a> {
     fn collect_globals(
         &mut self,
@@ -164,6 +199,11 @@ impl<'a> ModCollector<'a> {
                 module_id: self.module_id,
                 trait_path: trait_name,
                 methods: unresolved_functions,
+                default_methods: UnresolvedFunctions {
+                    file_id: self.file_id,
+                    functions: Vec::new(),
+                    trait_id: None,
+                },
                 object_type: trait_impl.object_type,
                 generics: trait_impl.impl_generics,
                 where_clause: trait_impl.where_clause,
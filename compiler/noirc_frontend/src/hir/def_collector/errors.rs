@@ -75,6 +75,8 @@ pub enum DefCollectorErrorKind {
     TraitImplOrphaned { span: Span },
     #[error("macro error : {0:?}")]
     MacroError(MacroError),
+    #[error("Dependency cycle found")]
+    DependencyCycle { name: String, cycle: String, span: Span },
 }
 
 /// An error struct that macro processors can return.
@@ -249,6 +251,13 @@ impl From<DefCollectorErrorKind> for Diagnostic {
             DefCollectorErrorKind::MacroError(macro_error) => {
                 Diagnostic::simple_error(macro_error.primary_message, macro_error.secondary_message.unwrap_or_default(), macro_error.span.unwrap_or_default())
             },
+            DefCollectorErrorKind::DependencyCycle { name, cycle, span } => {
+                Diagnostic::simple_error(
+                    "Dependency cycle found".into(),
+                    format!("'{name}' recursively depends on itself: {cycle}"),
+                    span,
+                )
+            }
         }
     }
 }
@@ -3,9 +3,9 @@ use crate::{
     node_interner::{FuncId, TraitId},
     Ident,
 };
-use std::collections::{hash_map::Entry, HashMap};
+use std::collections::{btree_map::Entry, BTreeMap};
 
-type Scope = HashMap<Option<TraitId>, (ModuleDefId, Visibility, bool /*is_prelude*/)>;
+type Scope = BTreeMap<Option<TraitId>, (ModuleDefId, Visibility, bool /*is_prelude*/)>;
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
 pub enum Visibility {
@@ -14,8 +14,10 @@ pub enum Visibility {
 
 #[derive(Default, Debug, PartialEq, Eq)]
 pub struct ItemScope {
-    types: HashMap<Ident, Scope>,
-    values: HashMap<Ident, Scope>,
+    // Ordered maps so that the definitions of a module, and so the functions of contracts or the
+    // tests of a crate, are always listed in the same order.
+    types: BTreeMap<Ident, Scope>,
+    values: BTreeMap<Ident, Scope>,
 
     defs: Vec<ModuleDefId>,
 }
@@ -42,7 +44,7 @@ impl ItemScope {
         trait_id: Option<TraitId>,
         is_prelude: bool,
     ) -> Result<(), (Ident, Ident)> {
-        let add_item = |map: &mut HashMap<Ident, Scope>| {
+        let add_item = |map: &mut BTreeMap<Ident, Scope>| {
             if let Entry::Occupied(mut o) = map.entry(name.clone()) {
                 let trait_hashmap = o.get_mut();
                 if let Entry::Occupied(mut n) = trait_hashmap.entry(trait_id) {
@@ -59,7 +61,7 @@ impl ItemScope {
                     Ok(())
                 }
             } else {
-                let mut trait_hashmap = HashMap::new();
+                let mut trait_hashmap = BTreeMap::new();
                 trait_hashmap.insert(trait_id, (mod_def, Visibility::Public, is_prelude));
                 map.insert(name, trait_hashmap);
                 Ok(())
@@ -123,7 +125,7 @@ impl ItemScope {
     pub fn find_name(&self, name: &Ident) -> PerNs {
         // Names, not associated with traits are searched first. If not found, we search for name, coming from a trait.
         // If we find only one name from trait, we return it. If there are multiple traits, providing the same name, we return None.
-        let find_name_in = |a: &HashMap<Ident, Scope>| {
+        let find_name_in = |a: &BTreeMap<Ident, Scope>| {
             if let Some(t) = a.get(name) {
                 if let Some(tt) = t.get(&None) {
                     Some(*tt)
@@ -159,11 +161,11 @@ impl ItemScope {
         self.defs.clone()
     }
 
-    pub fn types(&self) -> &HashMap<Ident, Scope> {
+    pub fn types(&self) -> &BTreeMap<Ident, Scope> {
         &self.types
     }
 
-    pub fn values(&self) -> &HashMap<Ident, Scope> {
+    pub fn values(&self) -> &BTreeMap<Ident, Scope> {
         &self.values
     }
 
@@ -23,6 +23,10 @@ use super::def_collector::errors::DefCollectorErrorKind;
 /// The name that is used for a non-contract program's entry-point function.
 pub const MAIN_FUNCTION: &str = "main";
 
+/// The name of the module of the stdlib holding the implementations in Noir of black box functions,
+/// which are used in place of the black box functions the backend doesn't support.
+pub const FALLBACK_MODULE: &str = "fallback";
+
 // XXX: Ultimately, we want to constrain an index to be of a certain type just like in RA
 /// Lets first check if this is offered by any external crate
 /// XXX: RA has made this a crate on crates.io
@@ -148,6 +152,23 @@ impl CrateDefMap {
         root_module.find_func_with_name(&MAIN_FUNCTION.into())
     }
 
+    /// Find the functions of the fallback module of this crate, along with the name of the black
+    /// box function each of them implements
+    pub fn black_box_fallbacks<'a>(
+        &'a self,
+        interner: &'a NodeInterner,
+    ) -> impl Iterator<Item = (&'a str, FuncId)> + 'a {
+        let root_module = &self.modules()[self.root.0];
+        let fallback_module = root_module.children.get(&FALLBACK_MODULE.into());
+
+        fallback_module.into_iter().flat_map(move |module| {
+            self.modules[module.0].value_definitions().filter_map(|id| {
+                let func_id = id.as_function()?;
+                Some((interner.function_name(&func_id), func_id))
+            })
+        })
+    }
+
     pub fn file_id(&self, module_id: LocalModuleId) -> FileId {
         self.modules[module_id.0].location.file
     }
@@ -311,6 +332,7 @@ impl std::ops::IndexMut<LocalModuleId> for CrateDefMap {
     }
 }
 
+#[derive(Clone)]
 pub struct TestFunction {
     id: FuncId,
     scope: TestScope,
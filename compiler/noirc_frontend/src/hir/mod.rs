@@ -1,7 +1,11 @@
+pub mod comptime;
 pub mod def_collector;
 pub mod def_map;
+pub mod rename;
 pub mod resolution;
 pub mod scope;
+pub mod semantic_tokens;
+pub mod symbols;
 pub mod type_check;
 
 use crate::graph::{CrateGraph, CrateId};
@@ -46,6 +50,53 @@ pub enum FunctionNameMatch<'a> {
     Anything,
     Exact(&'a str),
     Contains(&'a str),
+    /// Matches names against a pattern where `*` matches any sequence of characters
+    /// and `?` matches any single character.
+    Glob(&'a str),
+}
+
+impl FunctionNameMatch<'_> {
+    pub fn matches(&self, function_name: &str) -> bool {
+        match self {
+            FunctionNameMatch::Anything => true,
+            FunctionNameMatch::Exact(pattern) => function_name == *pattern,
+            FunctionNameMatch::Contains(pattern) => function_name.contains(pattern),
+            FunctionNameMatch::Glob(pattern) => glob_matches(pattern, function_name),
+        }
+    }
+}
+
+fn glob_matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    let (mut pattern_index, mut name_index) = (0, 0);
+    // The position of the last `*` seen in the pattern and the position in `name` it was
+    // last tried against, which lets us backtrack if the rest of the pattern fails to match.
+    let mut last_wildcard: Option<(usize, usize)> = None;
+
+    while name_index < name.len() {
+        match pattern.get(pattern_index) {
+            Some('*') => {
+                last_wildcard = Some((pattern_index, name_index));
+                pattern_index += 1;
+            }
+            Some(&c) if c == '?' || c == name[name_index] => {
+                pattern_index += 1;
+                name_index += 1;
+            }
+            _ => match last_wildcard {
+                Some((wildcard_index, matched_up_to)) => {
+                    last_wildcard = Some((wildcard_index, matched_up_to + 1));
+                    pattern_index = wildcard_index + 1;
+                    name_index = matched_up_to + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[pattern_index..].iter().all(|c| *c == '*')
 }
 
 impl Context<'_, '_> {
@@ -200,14 +251,9 @@ impl Context<'_, '_> {
             .filter_map(|test_function| {
                 let fully_qualified_name =
                     self.fully_qualified_function_name(crate_id, &test_function.get_id());
-                match &pattern {
-                    FunctionNameMatch::Anything => Some((fully_qualified_name, test_function)),
-                    FunctionNameMatch::Exact(pattern) => (&fully_qualified_name == pattern)
-                        .then_some((fully_qualified_name, test_function)),
-                    FunctionNameMatch::Contains(pattern) => fully_qualified_name
-                        .contains(pattern)
-                        .then_some((fully_qualified_name, test_function)),
-                }
+                pattern
+                    .matches(&fully_qualified_name)
+                    .then_some((fully_qualified_name, test_function))
             })
             .collect()
     }
@@ -236,3 +282,23 @@ impl Context<'_, '_> {
         module_id.module(&self.def_maps)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::FunctionNameMatch;
+
+    #[test]
+    fn glob_patterns() {
+        let glob = |pattern, name| FunctionNameMatch::Glob(pattern).matches(name);
+
+        assert!(glob("foo::*", "foo::test_bar"));
+        assert!(glob("*bar", "foo::test_bar"));
+        assert!(glob("foo::*_b?r", "foo::test_bar"));
+        assert!(glob("*::*::*", "a::b::c"));
+        assert!(glob("*", ""));
+
+        assert!(!glob("foo::*", "bar::test_foo"));
+        assert!(!glob("foo::test?", "foo::test"));
+        assert!(!glob("*bar", "foo::bar_baz"));
+    }
+}
@@ -0,0 +1,481 @@
+//! Renaming of items along with every place which refers to them by name.
+//!
+//! References are gathered from two sources:
+//! - identifiers and types which name resolution resolved to the item.
+//! - paths written in `use` statements and expressions, such as `foo::bar()`, each prefix of
+//!   which is resolved again from the module the path is written in. This is what finds the
+//!   references to modules and traits, which aren't recorded during name resolution.
+use fm::FileId;
+use noirc_errors::{Location, Span};
+use thiserror::Error;
+
+use crate::hir::def_map::{LocalModuleId, ModuleDefId, ModuleId};
+use crate::hir::resolution::import::{resolve_path_to_ns, ImportDirective};
+use crate::hir::Context;
+use crate::hir_def::expr::HirExpression;
+use crate::lexer::token::Keyword;
+use crate::node_interner::{DefinitionKind, Node};
+use crate::parser::{ItemKind, ParsedModule};
+use crate::{Ident, Path, PathKind};
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum RenameError {
+    #[error("There is no function, struct, trait, type alias, global or module to rename here")]
+    NoItem,
+    #[error("`{0}` is not a valid identifier")]
+    InvalidName(String),
+    #[error("`{name}` is already defined in the module")]
+    Conflict { name: String, location: Location },
+    #[error("`{name}` would refer to a local variable of the same name")]
+    Shadowed { name: String, location: Location },
+}
+
+/// The edits which rename an item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rename {
+    /// The current name of the item.
+    pub old_name: String,
+    /// Each occurrence of the old name which needs to be replaced, including the item's own
+    /// declaration, in the order they appear.
+    pub locations: Vec<Location>,
+    /// The file of a module declared with `mod foo;`, which has to be renamed along with it.
+    pub module_file: Option<FileId>,
+}
+
+/// An item which can be renamed, along with where it's declared.
+struct Item {
+    id: ModuleDefId,
+    /// The module the item is declared in.
+    module: ModuleId,
+    /// The name of the item in its declaration.
+    name: Location,
+    /// The location which references to the item resolve to.
+    definition: Location,
+}
+
+/// A path written in the source code.
+struct PathOccurrence {
+    /// The module the path is resolved from.
+    module: ModuleId,
+    file: FileId,
+    path: Path,
+    /// True for paths of `use` statements which import an item under its own name.
+    imports_name: bool,
+}
+
+impl Context<'_, '_> {
+    /// Returns the edits which rename the item named at `location` to `new_name`.
+    ///
+    /// Renames are rejected if another item of the same namespace is already named `new_name`
+    /// in a module which declares or imports the item, or if a local variable named `new_name`
+    /// would shadow one of its references.
+    pub fn rename(&self, location: Location, new_name: &str) -> Result<Rename, RenameError> {
+        if !is_valid_identifier(new_name) {
+            return Err(RenameError::InvalidName(new_name.to_string()));
+        }
+
+        let items = self.renameable_items();
+        let paths = self.path_occurrences();
+        let item = self.find_item_at(location, &items, &paths).ok_or(RenameError::NoItem)?;
+        let old_name = self.source_text(item.name).ok_or(RenameError::NoItem)?.to_string();
+
+        let mut references = Vec::new();
+        for reference in self.def_interner.references_to(item.definition) {
+            references.extend(self.name_within(reference, &old_name));
+        }
+        match item.id {
+            ModuleDefId::TraitId(trait_id) => {
+                for trait_impl in &self.def_interner.trait_implementations {
+                    let trait_impl = trait_impl.borrow();
+                    if trait_impl.trait_id == trait_id {
+                        let location = Location::new(trait_impl.ident.span(), trait_impl.file);
+                        references.extend(self.name_within(location, &old_name));
+                    }
+                }
+            }
+            ModuleDefId::TypeAliasId(alias_id) => {
+                for (id, reference) in &self.def_interner.type_alias_ref {
+                    if *id == alias_id {
+                        references.extend(self.name_within(*reference, &old_name));
+                    }
+                }
+            }
+            _ => (),
+        }
+
+        let mut importing_modules = Vec::new();
+        for occurrence in &paths {
+            let segments = &occurrence.path.segments;
+            for (index, segment) in segments.iter().enumerate() {
+                if segment.0.contents != old_name || !self.resolves_to(occurrence, index, item.id) {
+                    continue;
+                }
+                references.push(Location::new(segment.span(), occurrence.file));
+                if occurrence.imports_name && index + 1 == segments.len() {
+                    importing_modules.push(occurrence.module);
+                }
+            }
+        }
+
+        self.check_conflicts(&item, new_name, &importing_modules)?;
+        self.check_shadowing(new_name, &references)?;
+
+        let mut locations = references;
+        locations.push(item.name);
+        locations.sort_by_key(|location| (location.file, location.span.start()));
+        locations.dedup();
+
+        let module_file = match item.id {
+            ModuleDefId::ModuleId(module_id) => {
+                let file = module_id.module(&self.def_maps).location.file;
+                (file != item.name.file).then_some(file)
+            }
+            _ => None,
+        };
+
+        Ok(Rename { old_name, locations, module_file })
+    }
+
+    /// Returns every item declared in a module of any crate.
+    fn renameable_items(&self) -> Vec<Item> {
+        let interner = &self.def_interner;
+        let mut items = Vec::new();
+
+        for (krate, def_map) in &self.def_maps {
+            for (index, module_data) in def_map.modules().iter() {
+                let module = ModuleId { krate: *krate, local_id: LocalModuleId(index) };
+                let file = module_data.location.file;
+
+                for (name, child) in &module_data.children {
+                    let child = ModuleId { krate: *krate, local_id: *child };
+                    let name = Location::new(name.span(), file);
+                    items.push(Item {
+                        id: ModuleDefId::ModuleId(child),
+                        module,
+                        name,
+                        definition: name,
+                    });
+                }
+
+                for id in module_data.type_definitions().chain(module_data.value_definitions()) {
+                    let (name, definition) = match id {
+                        ModuleDefId::ModuleId(_) => continue,
+                        ModuleDefId::FunctionId(func_id) => {
+                            let Some(func_meta) = interner.try_function_meta(&func_id) else {
+                                continue;
+                            };
+                            (func_meta.name.location, func_meta.location)
+                        }
+                        ModuleDefId::TypeId(struct_id) => {
+                            let struct_type = interner.get_struct(struct_id);
+                            let struct_type = struct_type.borrow();
+                            let location = struct_type.location;
+                            (Location::new(struct_type.name.span(), location.file), location)
+                        }
+                        ModuleDefId::TypeAliasId(alias_id) => {
+                            let alias = interner.get_type_alias(alias_id);
+                            (Location::new(alias.name.span(), alias.location.file), alias.location)
+                        }
+                        ModuleDefId::TraitId(trait_id) => {
+                            let trait_ = interner.get_trait(trait_id);
+                            (
+                                Location::new(trait_.name.span(), trait_.location.file),
+                                trait_.location,
+                            )
+                        }
+                        ModuleDefId::GlobalId(stmt_id) => {
+                            let location = interner.let_statement(&stmt_id).ident().location;
+                            (location, location)
+                        }
+                    };
+                    items.push(Item { id, module, name, definition });
+                }
+            }
+        }
+        items
+    }
+
+    /// Returns the paths of every `use` statement, and of every identifier in a function body
+    /// which is written as a path.
+    fn path_occurrences(&self) -> Vec<PathOccurrence> {
+        let mut occurrences = Vec::new();
+
+        for (krate, def_map) in &self.def_maps {
+            let root = ModuleId { krate: *krate, local_id: def_map.root() };
+            let root_file = def_map.file_id(def_map.root());
+            if let Some((parsed_module, _)) = self.parsed_files.get(&root_file) {
+                self.collect_imports(parsed_module, root, root_file, &mut occurrences);
+            }
+        }
+
+        let interner = &self.def_interner;
+        let function_bodies = self.function_bodies();
+
+        for (index, location) in &interner.id_to_location {
+            if !matches!(
+                interner.nodes.get(*index),
+                Some(Node::Expression(HirExpression::Ident(_)))
+            ) {
+                continue;
+            }
+            let Some(path) = self.source_text(*location).and_then(|text| parse_path(text, *location))
+            else {
+                continue;
+            };
+            // Paths are resolved from the module of the function they're written in.
+            let module = innermost_body(&function_bodies, location).map(|(_, module)| *module);
+            if let Some(module) = module {
+                occurrences.push(PathOccurrence {
+                    module,
+                    file: location.file,
+                    path,
+                    imports_name: false,
+                });
+            }
+        }
+
+        occurrences
+    }
+
+    fn collect_imports(
+        &self,
+        parsed_module: &ParsedModule,
+        module: ModuleId,
+        file: FileId,
+        occurrences: &mut Vec<PathOccurrence>,
+    ) {
+        let module_data = module.module(&self.def_maps);
+        for item in &parsed_module.items {
+            match &item.kind {
+                ItemKind::Import(use_tree) => {
+                    for import in use_tree.clone().desugar(None) {
+                        occurrences.push(PathOccurrence {
+                            module,
+                            file,
+                            path: import.path,
+                            imports_name: import.alias.is_none(),
+                        });
+                    }
+                }
+                ItemKind::Submodules(submodule) => {
+                    if let Some(child) = module_data.children.get(&submodule.name) {
+                        let child = ModuleId { krate: module.krate, local_id: *child };
+                        self.collect_imports(&submodule.contents, child, file, occurrences);
+                    }
+                }
+                ItemKind::ModuleDecl(name) => {
+                    if let Some(child) = module_data.children.get(name) {
+                        let child = ModuleId { krate: module.krate, local_id: *child };
+                        let child_file = child.module(&self.def_maps).location.file;
+                        if let Some((parsed_module, _)) = self.parsed_files.get(&child_file) {
+                            self.collect_imports(parsed_module, child, child_file, occurrences);
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+
+    /// Returns the item to rename, which is either declared at `location` or referred to there.
+    fn find_item_at<'items>(
+        &self,
+        location: Location,
+        items: &'items [Item],
+        paths: &[PathOccurrence],
+    ) -> Option<&'items Item> {
+        if let Some(item) = items.iter().find(|item| item.name.contains(&location)) {
+            return Some(item);
+        }
+
+        for occurrence in paths {
+            for (index, segment) in occurrence.path.segments.iter().enumerate() {
+                if Location::new(segment.span(), occurrence.file).contains(&location) {
+                    return items.iter().find(|item| self.resolves_to(occurrence, index, item.id));
+                }
+            }
+        }
+
+        let definition = self.def_interner.get_definition_location_from(location, false)?;
+        items.iter().find(|item| item.definition == definition)
+    }
+
+    /// True if the path made of the first `index + 1` segments of `occurrence` resolves to `id`.
+    fn resolves_to(&self, occurrence: &PathOccurrence, index: usize, id: ModuleDefId) -> bool {
+        let path = &occurrence.path;
+        let prefix =
+            Path { segments: path.segments[..=index].to_vec(), kind: path.kind, span: path.span };
+        let import = ImportDirective {
+            module_id: occurrence.module.local_id,
+            path: prefix,
+            alias: None,
+            is_prelude: false,
+        };
+        let def_map = &self.def_maps[&occurrence.module.krate];
+        match resolve_path_to_ns(&import, def_map, &self.def_maps, true) {
+            Ok(ns) => [ns.types, ns.values].into_iter().flatten().any(|(found, ..)| found == id),
+            Err(_) => false,
+        }
+    }
+
+    fn check_conflicts(
+        &self,
+        item: &Item,
+        new_name: &str,
+        importing_modules: &[ModuleId],
+    ) -> Result<(), RenameError> {
+        let name = Ident::from(new_name);
+        for module_id in std::iter::once(&item.module).chain(importing_modules) {
+            let module = module_id.module(&self.def_maps);
+            let existing = module.find_name(&name);
+            let existing = match item.id {
+                ModuleDefId::FunctionId(_) | ModuleDefId::GlobalId(_) => existing.values,
+                ModuleDefId::ModuleId(_)
+                | ModuleDefId::TypeId(_)
+                | ModuleDefId::TypeAliasId(_)
+                | ModuleDefId::TraitId(_) => existing.types,
+            };
+            if existing.is_some_and(|(existing, ..)| existing != item.id) {
+                return Err(RenameError::Conflict {
+                    name: new_name.to_string(),
+                    location: module.location,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that no reference to the item is written after a local variable named `new_name`
+    /// in the same function, as the reference would then resolve to the variable instead.
+    fn check_shadowing(&self, new_name: &str, references: &[Location]) -> Result<(), RenameError> {
+        let interner = &self.def_interner;
+        let locals: Vec<Location> = interner
+            .definitions
+            .iter()
+            .filter(|definition| {
+                definition.name == new_name && matches!(definition.kind, DefinitionKind::Local(_))
+            })
+            .map(|definition| definition.location)
+            .collect();
+        if locals.is_empty() {
+            return Ok(());
+        }
+
+        let function_bodies = self.function_bodies();
+
+        for reference in references {
+            // Only references written as a single name can be shadowed by a local.
+            let is_path = reference.span.start() >= 2
+                && self
+                    .source_text(Location::new(
+                        Span::from(reference.span.start() - 2..reference.span.start()),
+                        reference.file,
+                    ))
+                    .is_some_and(|separator| separator == "::");
+            if is_path {
+                continue;
+            }
+
+            let Some((body, _)) = innermost_body(&function_bodies, reference) else {
+                continue;
+            };
+            if let Some(local) = locals
+                .iter()
+                .find(|local| body.contains(local) && local.span.start() < reference.span.start())
+            {
+                return Err(RenameError::Shadowed { name: new_name.to_string(), location: *local });
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the location of the body of each function along with the module it's declared in.
+    fn function_bodies(&self) -> Vec<(Location, ModuleId)> {
+        let interner = &self.def_interner;
+        interner
+            .func_meta
+            .iter()
+            .filter(|(_, func_meta)| func_meta.has_body)
+            .map(|(func_id, _)| {
+                let body = interner.expr_location(interner.function(func_id).as_expr());
+                (body, interner.function_module(*func_id))
+            })
+            .collect()
+    }
+
+    /// Returns the location of the last occurrence of `name` in the path at `location`, ignoring
+    /// any generics or arguments which follow it.
+    pub(super) fn name_within(&self, location: Location, name: &str) -> Option<Location> {
+        let text = self.source_text(location)?;
+        let path_end = text.find(['{', '(', '<', '[']).unwrap_or(text.len());
+        let offset = text[..path_end]
+            .match_indices(name)
+            .map(|(offset, _)| offset)
+            .filter(|offset| {
+                let before = text[..*offset].chars().next_back();
+                let after = text[offset + name.len()..].chars().next();
+                !before.is_some_and(is_identifier_char) && !after.is_some_and(is_identifier_char)
+            })
+            .last()?;
+        let start = location.span.start() + offset as u32;
+        Some(Location::new(Span::from(start..start + name.len() as u32), location.file))
+    }
+
+    pub(super) fn source_text(&self, location: Location) -> Option<&str> {
+        let source = self.file_manager.fetch_file(location.file)?;
+        source.get(location.span.start() as usize..location.span.end() as usize)
+    }
+}
+
+/// Returns the body, and its module, of the innermost function which `location` is within.
+fn innermost_body<'bodies>(
+    function_bodies: &'bodies [(Location, ModuleId)],
+    location: &Location,
+) -> Option<&'bodies (Location, ModuleId)> {
+    function_bodies
+        .iter()
+        .filter(|(body, _)| body.contains(location))
+        .min_by_key(|(body, _)| body.span.end() - body.span.start())
+}
+
+/// Parses the text of an identifier which is written as a path such as `foo::bar`, or returns
+/// `None` if it's a single name.
+fn parse_path(text: &str, location: Location) -> Option<Path> {
+    if !text.contains("::") {
+        return None;
+    }
+
+    let mut segments = Vec::new();
+    let mut offset = 0;
+    for segment in text.split("::") {
+        let trimmed = segment.trim();
+        if !is_valid_identifier(trimmed) && trimmed != "crate" && trimmed != "dep" {
+            return None;
+        }
+        let start = location.span.start() + (offset + segment.find(trimmed)?) as u32;
+        segments
+            .push(Ident::new(trimmed.to_string(), Span::from(start..start + trimmed.len() as u32)));
+        offset += segment.len() + 2;
+    }
+
+    let kind = match segments.first().map(|segment| segment.0.contents.as_str()) {
+        Some("crate") => PathKind::Crate,
+        Some("dep") => PathKind::Dep,
+        _ => PathKind::Plain,
+    };
+    if kind != PathKind::Plain {
+        segments.remove(0);
+    }
+    Some(Path { segments, kind, span: location.span })
+}
+
+fn is_identifier_char(char: char) -> bool {
+    char.is_ascii_alphanumeric() || char == '_'
+}
+
+fn is_valid_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && chars.all(is_identifier_char)
+        && Keyword::lookup_keyword(name).is_none()
+}
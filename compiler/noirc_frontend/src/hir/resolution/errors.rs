@@ -2,7 +2,7 @@ pub use noirc_errors::Span;
 use noirc_errors::{CustomDiagnostic as Diagnostic, FileDiagnostic};
 use thiserror::Error;
 
-use crate::{parser::ParserError, Ident, Type};
+use crate::{parser::ParserError, Ident, Path, Type};
 
 use super::import::PathResolutionError;
 
@@ -56,6 +56,8 @@ pub enum ResolverError {
     TestFunctionHasParameters { span: Span },
     #[error("Only struct types can be used in constructor expressions")]
     NonStructUsedInConstructor { typ: Type, span: Span },
+    #[error("{path} is not a variant of an enum")]
+    NotAnEnumVariant { path: Path, span: Span },
     #[error("Only struct types can have generics")]
     NonStructWithGenerics { span: Span },
     #[error("Cannot apply generics on Self type")]
@@ -84,6 +86,14 @@ pub enum ResolverError {
     InvalidTypeForEntryPoint { span: Span },
     #[error("Nested slices are not supported")]
     NestedSlices { span: Span },
+    #[error("`{keyword}` is only allowed within loops")]
+    JumpOutsideLoop { keyword: &'static str, span: Span },
+    #[error("`while` and `loop` are only allowed in unconstrained functions")]
+    UnboundedLoopInConstrainedFunction { span: Span },
+    #[error("Generics declared with `let` must have a constant type")]
+    InvalidValueGenericType { typ: Type, span: Span },
+    #[error("Invalid argument for a generic declared with `let`")]
+    InvalidValueGenericArgument { argument: Type, expected: Option<Type>, span: Span },
 }
 
 impl ResolverError {
@@ -223,7 +233,7 @@ impl From<ResolverError> for Diagnostic {
             ),
             ResolverError::InvalidArrayLengthExpr { span } => Diagnostic::simple_error(
                 "Expression invalid in an array-length context".into(),
-                "Array-length expressions can only have simple integer operations and any variables used must be global constants".into(),
+                "Array-length expressions can only have simple integer operations and any variables used must be global constants or numeric generics".into(),
                 span,
             ),
             ResolverError::IntegerTooLarge { span } => Diagnostic::simple_error(
@@ -251,6 +261,11 @@ impl From<ResolverError> for Diagnostic {
                 format!("{typ} has no fields to construct it with"),
                 span,
             ),
+            ResolverError::NotAnEnumVariant { path, span } => Diagnostic::simple_error(
+                format!("{path} is not a variant of an enum"),
+                String::new(),
+                span,
+            ),
             ResolverError::NonStructWithGenerics { span } => Diagnostic::simple_error(
                 "Only struct types can have generic arguments".into(),
                 "Try removing the generic arguments".into(),
@@ -311,6 +326,35 @@ impl From<ResolverError> for Diagnostic {
                 "Try to use a constant sized array instead".into(),
                 span,
             ),
+            ResolverError::JumpOutsideLoop { keyword, span } => Diagnostic::simple_error(
+                format!("`{keyword}` is only allowed within loops"),
+                String::new(),
+                span,
+            ),
+            ResolverError::UnboundedLoopInConstrainedFunction { span } => Diagnostic::simple_error(
+                "`while` and `loop` are only allowed in unconstrained functions".into(),
+                "Constrained loops must have a range known at compile-time, try using a `for` loop instead".into(),
+                span,
+            ),
+            ResolverError::InvalidValueGenericType { typ, span } => Diagnostic::simple_error(
+                format!("`{typ}` cannot be the type of a generic"),
+                "Generics declared with `let` may only be fields, integers, or arrays of these".into(),
+                span,
+            ),
+            ResolverError::InvalidValueGenericArgument { argument, expected, span } => {
+                match expected {
+                    Some(expected) => Diagnostic::simple_error(
+                        format!("Expected a constant of type `{expected}` but found `{argument}`"),
+                        "This generic is declared with `let`".into(),
+                        span,
+                    ),
+                    None => Diagnostic::simple_error(
+                        format!("The constant `{argument}` cannot be used as a type"),
+                        "Only generics declared with `let` accept constants other than integers".into(),
+                        span,
+                    ),
+                }
+            }
         }
     }
 }
@@ -13,13 +13,15 @@
 // XXX: Resolver does not check for unused functions
 use crate::hir_def::expr::{
     HirArrayLiteral, HirBinaryOp, HirBlockExpression, HirCallExpression, HirCapturedVar,
-    HirCastExpression, HirConstructorExpression, HirExpression, HirIdent, HirIfExpression,
-    HirIndexExpression, HirInfixExpression, HirLambda, HirLiteral, HirMemberAccess,
-    HirMethodCallExpression, HirPrefixExpression, ImplKind,
+    HirCastExpression, HirConstructorExpression, HirEnumConstructorExpression, HirExpression,
+    HirIdent, HirIfExpression, HirIndexExpression, HirInfixExpression, HirLambda, HirLiteral,
+    HirMatchExpression, HirMatchPattern, HirMemberAccess, HirMethodCallExpression,
+    HirPrefixExpression, ImplKind,
 };
 
 use crate::hir_def::traits::{Trait, TraitConstraint};
 use crate::token::{Attributes, FunctionAttribute};
+use acvm::FieldElement;
 use regex::Regex;
 use std::collections::{BTreeMap, HashSet};
 use std::rc::Rc;
@@ -33,15 +35,16 @@ use crate::node_interner::{
 };
 use crate::{
     hir::{def_map::CrateDefMap, resolution::path_resolver::PathResolver},
-    BlockExpression, Expression, ExpressionKind, FunctionKind, Ident, Literal, NoirFunction,
-    StatementKind,
+    BlockExpression, Expression, ExpressionKind, FunctionKind, Ident, Literal, MatchPattern,
+    NoirFunction, Statement, StatementKind,
 };
 use crate::{
     ArrayLiteral, ContractFunctionType, Distinctness, ForRange, FunctionDefinition,
     FunctionReturnType, FunctionVisibility, Generics, LValue, NoirStruct, NoirTypeAlias, Param,
-    Path, PathKind, Pattern, Shared, StructType, Type, TypeAliasType, TypeVariable,
-    TypeVariableKind, UnaryOp, UnresolvedGenerics, UnresolvedTraitConstraint, UnresolvedType,
-    UnresolvedTypeData, UnresolvedTypeExpression, Visibility, ERROR_IDENT,
+    Path, PathKind, Pattern, Shared, StructType, Type, TypeAliasType, TypeLevelValue, TypeVariable,
+    TypeVariableKind, UnaryOp, UnresolvedGeneric, UnresolvedGenerics, UnresolvedNumericBound,
+    UnresolvedTraitConstraint, UnresolvedType, UnresolvedTypeData, UnresolvedTypeExpression,
+    Visibility, ERROR_IDENT,
 };
 use fm::FileId;
 use iter_extended::vecmap;
@@ -51,7 +54,7 @@ use crate::hir::scope::{
     Scope as GenericScope, ScopeForest as GenericScopeForest, ScopeTree as GenericScopeTree,
 };
 use crate::hir_def::{
-    function::{FuncMeta, HirFunction},
+    function::{FuncMeta, HirFunction, NumericBound},
     stmt::{HirConstrainStatement, HirLetStatement, HirStatement},
 };
 
@@ -111,6 +114,15 @@ pub struct Resolver<'a> {
     /// that are captured. We do this in order to create the hidden environment
     /// parameter for the lambda function.
     lambda_stack: Vec<LambdaContext>,
+
+    /// The number of loops surrounding the statement currently being resolved, used to check
+    /// that `break` and `continue` are only used within a loop. Lambdas start again from zero
+    /// since they cannot break out of a loop in the function which defines them.
+    loop_depth: usize,
+
+    /// True if the current function is compiled to Brillig, which allows loops whose number of
+    /// iterations isn't known at compile-time. This is always false within lambdas.
+    in_unconstrained_fn: bool,
 }
 
 /// ResolverMetas are tagged onto each definition to track how many times they are used
@@ -147,6 +159,8 @@ impl<'a> Resolver<'a> {
             generics: Vec::new(),
             errors: Vec::new(),
             lambda_stack: Vec::new(),
+            loop_depth: 0,
+            in_unconstrained_fn: false,
             current_trait_impl: None,
             file,
             in_contract,
@@ -233,6 +247,7 @@ impl<'a> Resolver<'a> {
             body: BlockExpression(Vec::new()),
             span: name.span(),
             where_clause: where_clause.to_vec(),
+            numeric_bounds: Vec::new(),
             return_type: return_type.clone(),
             return_visibility: Visibility::Private,
             return_distinctness: Distinctness::DuplicationAllowed,
@@ -407,6 +422,7 @@ impl<'a> Resolver<'a> {
                 HirFunction::empty()
             }
             FunctionKind::Normal => {
+                self.in_unconstrained_fn = func.def.is_unconstrained || func.def.is_open;
                 let expr_id = self.intern_block(func.def.body);
                 self.interner.push_expr_location(expr_id, func.def.span, self.file);
                 HirFunction::unchecked_from_expr(expr_id)
@@ -461,6 +477,7 @@ impl<'a> Resolver<'a> {
                 Type::Array(Box::new(size), elem)
             }
             Expression(expr) => self.convert_expression_type(expr),
+            Value(value) => Type::Value(value),
             Integer(sign, bits) => Type::Integer(sign, bits),
             Bool => Type::Bool,
             String(size) => {
@@ -512,7 +529,12 @@ impl<'a> Resolver<'a> {
             Parenthesized(typ) => self.resolve_type_inner(*typ, new_variables),
         };
 
-        if let Type::Struct(_, _) = resolved_type {
+        let is_named = match &resolved_type {
+            Type::Struct(..) | Type::TraitAsType(..) => true,
+            Type::NamedGeneric(_, name) => !name.is_empty(),
+            _ => false,
+        };
+        if is_named {
             if let Some(unresolved_span) = typ.span {
                 // Record the location of the type reference
                 self.interner.push_type_ref_location(
@@ -556,16 +578,19 @@ impl<'a> Resolver<'a> {
         }
 
         let span = path.span();
+        let arg_spans = vecmap(&args, |arg| arg.span.unwrap_or(span));
         let mut args = vecmap(args, |arg| self.resolve_type_inner(arg, new_variables));
 
         if let Some(type_alias_type) = self.lookup_type_alias(path.clone()) {
             let expected_generic_count = type_alias_type.generics.len();
             let type_alias_string = type_alias_type.to_string();
             let id = type_alias_type.id;
+            let generics = type_alias_type.generics.clone();
 
             self.verify_generics_count(expected_generic_count, &mut args, span, || {
                 type_alias_string
             });
+            self.verify_value_generic_arguments(&generics, &mut args, &arg_spans);
 
             let result = self.interner.get_type_alias(id).get_type(&args);
 
@@ -590,6 +615,8 @@ impl<'a> Resolver<'a> {
                 self.verify_generics_count(expected_generic_count, &mut args, span, || {
                     struct_type.borrow().to_string()
                 });
+                let generics = struct_type.borrow().generics.clone();
+                self.verify_value_generic_arguments(&generics, &mut args, &arg_spans);
 
                 Type::Struct(struct_type, args)
             }
@@ -632,6 +659,46 @@ impl<'a> Resolver<'a> {
         }
     }
 
+    /// Checks that the arguments of generics declared with `let` are constants of the declared
+    /// type, and that other generics aren't given constants which aren't integers.
+    fn verify_value_generic_arguments(
+        &mut self,
+        generics: &Generics,
+        args: &mut [Type],
+        spans: &[Span],
+    ) {
+        for ((generic, arg), span) in generics.iter().zip(args.iter_mut()).zip(spans) {
+            let expected = self.interner.value_generic_type(generic.id()).cloned();
+            let is_valid = match (&expected, &*arg) {
+                (_, Type::Error) | (Some(Type::Error), _) => true,
+                (Some(expected), Type::Constant(value)) => {
+                    TypeLevelValue::Field((*value as u128).into()).has_type(expected)
+                }
+                (Some(expected), Type::Value(value)) => value.has_type(expected),
+                (Some(expected), Type::InfixExpr(..)) => {
+                    matches!(expected, Type::FieldElement | Type::Integer(..))
+                }
+                // Generics which weren't declared with `let` may still be numeric generics
+                (Some(expected), Type::NamedGeneric(argument, _)) => self
+                    .interner
+                    .value_generic_type(argument.id())
+                    .map_or(true, |argument_type| argument_type == expected),
+                (Some(_), _) | (None, Type::Value(_)) => false,
+                (None, _) => true,
+            };
+
+            if !is_valid {
+                let argument = std::mem::replace(arg, Type::Error);
+                let span = *span;
+                self.push_err(ResolverError::InvalidValueGenericArgument {
+                    argument,
+                    expected,
+                    span,
+                });
+            }
+        }
+    }
+
     fn lookup_generic_or_global_type(&mut self, path: &Path) -> Option<Type> {
         if path.segments.len() == 1 {
             let name = &path.last_segment().0.contents;
@@ -687,9 +754,12 @@ impl<'a> Resolver<'a> {
                     (Type::Constant(lhs), Type::Constant(rhs)) => {
                         Type::Constant(op.function()(lhs, rhs))
                     }
+                    // Expressions over numeric generics are evaluated once the generics are known
+                    (lhs, rhs) if is_type_level_integer(&lhs) && is_type_level_integer(&rhs) => {
+                        Type::InfixExpr(Box::new(lhs), op, Box::new(rhs))
+                    }
                     (lhs, _) => {
-                        let span =
-                            if !matches!(lhs, Type::Constant(_)) { lhs_span } else { rhs_span };
+                        let span = if !is_type_level_integer(&lhs) { lhs_span } else { rhs_span };
                         self.push_err(ResolverError::InvalidArrayLengthExpr { span });
                         Type::Constant(0)
                     }
@@ -774,14 +844,15 @@ impl<'a> Resolver<'a> {
             // Map the generic to a fresh type variable
             let id = self.interner.next_type_variable_id();
             let typevar = TypeVariable::unbound(id);
-            let span = generic.0.span();
+            let ident = generic.ident();
+            let span = ident.span();
 
             // Check for name collisions of this generic
-            let name = Rc::new(generic.0.contents.clone());
+            let name = Rc::new(ident.0.contents.clone());
 
             if let Some((_, _, first_span)) = self.find_generic(&name) {
                 self.errors.push(ResolverError::DuplicateDefinition {
-                    name: generic.0.contents.clone(),
+                    name: ident.0.contents.clone(),
                     first_span: *first_span,
                     second_span: span,
                 });
@@ -789,6 +860,7 @@ impl<'a> Resolver<'a> {
                 self.generics.push((name, typevar.clone(), span));
             }
 
+            self.add_value_generic_type(generic, &typevar);
             typevar
         })
     }
@@ -799,8 +871,29 @@ impl<'a> Resolver<'a> {
     pub fn add_existing_generics(&mut self, names: &UnresolvedGenerics, generics: &Generics) {
         assert_eq!(names.len(), generics.len());
 
-        for (name, typevar) in names.iter().zip(generics) {
-            self.add_existing_generic(&name.0.contents, name.0.span(), typevar.clone());
+        for (generic, typevar) in names.iter().zip(generics) {
+            let ident = generic.ident();
+            self.add_existing_generic(&ident.0.contents, ident.span(), typevar.clone());
+            self.add_value_generic_type(generic, typevar);
+        }
+    }
+
+    /// Remember the declared type of a generic declared with `let` so that it can be used
+    /// as a value of that type.
+    fn add_value_generic_type(&mut self, generic: &UnresolvedGeneric, typevar: &TypeVariable) {
+        // Existing generics may have been given their type when they were first added
+        if self.interner.value_generic_type(typevar.id()).is_some() {
+            return;
+        }
+
+        if let UnresolvedGeneric::Value { typ, .. } = generic {
+            let mut typ = self.resolve_type(typ.clone());
+            if !is_valid_value_generic_type(&typ) {
+                let span = generic.span();
+                self.push_err(ResolverError::InvalidValueGenericType { typ, span });
+                typ = Type::Error;
+            }
+            self.interner.push_value_generic(typevar.id(), typ);
         }
     }
 
@@ -819,18 +912,62 @@ impl<'a> Resolver<'a> {
         }
     }
 
-    pub fn resolve_struct_fields(
+    pub fn resolve_struct_generics(
         mut self,
-        unresolved: NoirStruct,
-    ) -> (Generics, Vec<(Ident, Type)>, Vec<ResolverError>) {
+        unresolved: &NoirStruct,
+    ) -> (Generics, Vec<ResolverError>) {
+        // The types of generics declared with `let` may use globals in their array lengths
+        self.resolve_local_globals();
+
         let generics = self.add_generics(&unresolved.generics);
+        (generics, self.errors)
+    }
 
-        // Check whether the struct definition has globals in the local module and add them to the scope
-        self.resolve_local_globals();
+    pub fn resolve_struct_fields(
+        mut self,
+        unresolved: NoirStruct,
+        generics: &Generics,
+    ) -> (Vec<(Ident, Type)>, Vec<ResolverError>) {
+        self.enter_struct_definition(&unresolved, generics);
 
         let fields = vecmap(unresolved.fields, |(ident, typ)| (ident, self.resolve_type(typ)));
 
-        (generics, fields, self.errors)
+        (fields, self.errors)
+    }
+
+    pub fn resolve_enum_variants(
+        mut self,
+        unresolved: NoirStruct,
+        generics: &Generics,
+    ) -> (Vec<(Ident, Vec<Type>)>, Vec<ResolverError>) {
+        self.enter_struct_definition(&unresolved, generics);
+
+        let mut variants: Vec<(Ident, Vec<Type>)> = Vec::new();
+        for (name, payload) in unresolved.variants.unwrap_or_default() {
+            if let Some((first, _)) = variants.iter().find(|(first, _)| *first == name) {
+                self.errors.push(ResolverError::DuplicateDefinition {
+                    name: name.0.contents.clone(),
+                    first_span: first.span(),
+                    second_span: name.span(),
+                });
+            }
+            let payload = vecmap(payload, |typ| self.resolve_type(typ));
+            variants.push((name, payload));
+        }
+
+        (variants, self.errors)
+    }
+
+    /// Brings the generics of a struct or enum and the globals of its module into scope.
+    fn enter_struct_definition(&mut self, unresolved: &NoirStruct, generics: &Generics) {
+        // These generics were already checked for name collisions by resolve_struct_generics
+        self.generics = vecmap(unresolved.generics.iter().zip(generics), |(generic, typevar)| {
+            let ident = generic.ident();
+            (Rc::new(ident.0.contents.clone()), typevar.clone(), ident.span())
+        });
+
+        // Check whether the struct definition has globals in the local module and add them to the scope
+        self.resolve_local_globals();
     }
 
     fn resolve_local_globals(&mut self) {
@@ -946,9 +1083,19 @@ impl<'a> Resolver<'a> {
             return_distinctness: func.def.return_distinctness,
             has_body: !func.def.body.is_empty(),
             trait_constraints: self.resolve_trait_constraints(&func.def.where_clause),
+            numeric_bounds: vecmap(&func.def.numeric_bounds, |bound| {
+                self.resolve_numeric_bound(bound.clone())
+            }),
         }
     }
 
+    fn resolve_numeric_bound(&mut self, bound: UnresolvedNumericBound) -> NumericBound {
+        let location = Location::new(bound.span, self.file);
+        let lhs = self.convert_expression_type(bound.lhs);
+        let rhs = self.convert_expression_type(bound.rhs);
+        NumericBound { lhs, operator: bound.operator.contents, rhs, location }
+    }
+
     /// Override whether this name resolver is within a contract or not.
     /// This will affect which types are allowed as parameters to methods as well
     /// as which modifiers are allowed on a function.
@@ -1007,6 +1154,11 @@ impl<'a> Resolver<'a> {
         }
 
         for (name_to_find, type_variable) in Self::find_numeric_generics(params, return_type) {
+            // Generics declared with `let` are declared below with the type they were given
+            if self.interner.value_generic_type(type_variable.id()).is_some() {
+                continue;
+            }
+
             // Declare any generics to let users use numeric generics in scope.
             // Don't issue a warning if these are unused
             //
@@ -1021,6 +1173,17 @@ impl<'a> Resolver<'a> {
                 self.add_variable_decl_inner(ident, false, false, false, definition);
             }
         }
+
+        // Generics declared with `let` are always in scope, whether or not they're used in an
+        // array length, and have the type they were declared with.
+        for (name, type_variable, span) in self.generics.clone() {
+            if let Some(typ) = self.interner.value_generic_type(type_variable.id()).cloned() {
+                let ident = Ident::new(name.to_string(), span);
+                let definition = DefinitionKind::GenericType(type_variable);
+                let ident = self.add_variable_decl_inner(ident, false, false, false, definition);
+                self.interner.push_definition_type(ident.id, typ);
+            }
+        }
     }
 
     fn find_numeric_generics(
@@ -1044,15 +1207,15 @@ impl<'a> Resolver<'a> {
             | Type::Error
             | Type::TypeVariable(_, _)
             | Type::Constant(_)
+            | Type::InfixExpr(..)
+            | Type::Value(_)
             | Type::NamedGeneric(_, _)
             | Type::NotConstant
             | Type::TraitAsType(..)
             | Type::Forall(_, _) => (),
 
             Type::Array(length, element_type) => {
-                if let Type::NamedGeneric(type_variable, name) = length.as_ref() {
-                    found.insert(name.to_string(), type_variable.clone());
-                }
+                Self::find_numeric_generics_in_length(length, found);
                 Self::find_numeric_generics_in_type(element_type, found);
             }
 
@@ -1081,20 +1244,29 @@ impl<'a> Resolver<'a> {
                 }
             }
             Type::MutableReference(element) => Self::find_numeric_generics_in_type(element, found),
-            Type::String(length) => {
-                if let Type::NamedGeneric(type_variable, name) = length.as_ref() {
-                    found.insert(name.to_string(), type_variable.clone());
-                }
-            }
+            Type::String(length) => Self::find_numeric_generics_in_length(length, found),
             Type::FmtString(length, fields) => {
-                if let Type::NamedGeneric(type_variable, name) = length.as_ref() {
-                    found.insert(name.to_string(), type_variable.clone());
-                }
+                Self::find_numeric_generics_in_length(length, found);
                 Self::find_numeric_generics_in_type(fields, found);
             }
         }
     }
 
+    /// Finds the generics used in the length of an array or string, e.g. both `N` and `M`
+    /// in `[Field; N + M]`.
+    fn find_numeric_generics_in_length(length: &Type, found: &mut BTreeMap<String, TypeVariable>) {
+        match length {
+            Type::NamedGeneric(type_variable, name) => {
+                found.insert(name.to_string(), type_variable.clone());
+            }
+            Type::InfixExpr(lhs, _, rhs) => {
+                Self::find_numeric_generics_in_length(lhs, found);
+                Self::find_numeric_generics_in_length(rhs, found);
+            }
+            _ => (),
+        }
+    }
+
     pub fn resolve_global_let(&mut self, let_stmt: crate::LetStatement) -> HirStatement {
         let expression = self.resolve_expression(let_stmt.expression);
         let definition = DefinitionKind::Global(expression);
@@ -1106,7 +1278,7 @@ impl<'a> Resolver<'a> {
         })
     }
 
-    pub fn resolve_stmt(&mut self, stmt: StatementKind) -> HirStatement {
+    pub fn resolve_stmt(&mut self, stmt: StatementKind, span: Span) -> HirStatement {
         match stmt {
             StatementKind::Let(let_stmt) => {
                 let expression = self.resolve_expression(let_stmt.expression);
@@ -1119,7 +1291,8 @@ impl<'a> Resolver<'a> {
             }
             StatementKind::Constrain(constrain_stmt) => {
                 let expr_id = self.resolve_expression(constrain_stmt.0);
-                let assert_message = constrain_stmt.1;
+                let assert_message =
+                    constrain_stmt.1.map(|message| self.resolve_expression(message));
                 HirStatement::Constrain(HirConstrainStatement(expr_id, self.file, assert_message))
             }
             StatementKind::Expression(expr) => {
@@ -1148,7 +1321,10 @@ impl<'a> Resolver<'a> {
                                 true,
                                 DefinitionKind::Local(None),
                             );
-                            (decl, this.resolve_expression(block))
+                            this.loop_depth += 1;
+                            let block = this.resolve_expression(block);
+                            this.loop_depth -= 1;
+                            (decl, block)
                         });
 
                         HirStatement::For(HirForStatement {
@@ -1161,16 +1337,44 @@ impl<'a> Resolver<'a> {
                     range @ ForRange::Array(_) => {
                         let for_stmt =
                             range.into_for(for_loop.identifier, for_loop.block, for_loop.span);
-                        self.resolve_stmt(for_stmt)
+                        self.resolve_stmt(for_stmt, span)
                     }
                 }
             }
+            StatementKind::While(while_loop) => {
+                let loop_statement = while_loop.into_loop();
+                self.resolve_stmt(loop_statement, span)
+            }
+            StatementKind::Loop(block) => {
+                if !self.in_unconstrained_fn {
+                    self.push_err(ResolverError::UnboundedLoopInConstrainedFunction { span });
+                }
+
+                self.loop_depth += 1;
+                let block = self.resolve_expression(block);
+                self.loop_depth -= 1;
+                HirStatement::Loop(block)
+            }
+            StatementKind::Break => {
+                self.check_in_loop("break", span);
+                HirStatement::Break
+            }
+            StatementKind::Continue => {
+                self.check_in_loop("continue", span);
+                HirStatement::Continue
+            }
             StatementKind::Error => HirStatement::Error,
         }
     }
 
-    pub fn intern_stmt(&mut self, stmt: StatementKind) -> StmtId {
-        let hir_stmt = self.resolve_stmt(stmt);
+    fn check_in_loop(&mut self, keyword: &'static str, span: Span) {
+        if self.loop_depth == 0 {
+            self.push_err(ResolverError::JumpOutsideLoop { keyword, span });
+        }
+    }
+
+    pub fn intern_stmt(&mut self, stmt: Statement) -> StmtId {
+        let hir_stmt = self.resolve_stmt(stmt.kind, stmt.span);
         self.interner.push_stmt(hir_stmt)
     }
 
@@ -1314,7 +1518,10 @@ impl<'a> Resolver<'a> {
                 Literal::Unit => HirLiteral::Unit,
             }),
             ExpressionKind::Variable(path) => {
-                if let Some((method, constraint, assumed)) = self.resolve_trait_generic_path(&path)
+                if let Some((enum_type, variant)) = self.lookup_enum_variant(&path) {
+                    self.enum_constructor(enum_type, variant, Vec::new())
+                } else if let Some((method, constraint, assumed)) =
+                    self.resolve_trait_generic_path(&path)
                 {
                     HirExpression::Ident(HirIdent {
                         location: Location::new(expr.span, self.file),
@@ -1373,7 +1580,12 @@ impl<'a> Resolver<'a> {
                     }
                 }
 
-                HirExpression::Prefix(HirPrefixExpression { operator, rhs })
+                let trait_method_id = match operator {
+                    UnaryOp::Minus => self.interner.get_neg_trait_method(),
+                    _ => None,
+                };
+
+                HirExpression::Prefix(HirPrefixExpression { operator, rhs, trait_method_id })
             }
             ExpressionKind::Infix(infix) => {
                 let lhs = self.resolve_expression(infix.lhs);
@@ -1388,12 +1600,22 @@ impl<'a> Resolver<'a> {
                 })
             }
             ExpressionKind::Call(call_expr) => {
-                // Get the span and name of path for error reporting
-                let func = self.resolve_expression(*call_expr.func);
+                let variant = match &call_expr.func.kind {
+                    ExpressionKind::Variable(path) => self.lookup_enum_variant(path),
+                    _ => None,
+                };
 
-                let arguments = vecmap(call_expr.arguments, |arg| self.resolve_expression(arg));
-                let location = Location::new(expr.span, self.file);
-                HirExpression::Call(HirCallExpression { func, arguments, location })
+                if let Some((enum_type, variant)) = variant {
+                    let arguments = vecmap(call_expr.arguments, |arg| self.resolve_expression(arg));
+                    self.enum_constructor(enum_type, variant, arguments)
+                } else {
+                    // Get the span and name of path for error reporting
+                    let func = self.resolve_expression(*call_expr.func);
+
+                    let arguments = vecmap(call_expr.arguments, |arg| self.resolve_expression(arg));
+                    let location = Location::new(expr.span, self.file);
+                    HirExpression::Call(HirCallExpression { func, arguments, location })
+                }
             }
             ExpressionKind::MethodCall(call_expr) => {
                 let method = call_expr.method_name;
@@ -1416,16 +1638,29 @@ impl<'a> Resolver<'a> {
                 consequence: self.resolve_expression(if_expr.consequence),
                 alternative: if_expr.alternative.map(|e| self.resolve_expression(e)),
             }),
+            ExpressionKind::Match(match_expr) => {
+                let expression = self.resolve_expression(match_expr.expression);
+                // Each arm gets its own scope so the variables bound by its pattern are only
+                // visible within its body.
+                let rules = vecmap(match_expr.rules, |(pattern, body)| {
+                    self.in_new_scope(|this| {
+                        let pattern = this.resolve_match_pattern(pattern);
+                        (pattern, this.resolve_expression(body))
+                    })
+                });
+                HirExpression::Match(HirMatchExpression { expression, rules })
+            }
             ExpressionKind::Index(indexed_expr) => HirExpression::Index(HirIndexExpression {
                 collection: self.resolve_expression(indexed_expr.collection),
                 index: self.resolve_expression(indexed_expr.index),
+                trait_method_id: self.interner.get_index_trait_method(),
             }),
             ExpressionKind::Block(block_expr) => self.resolve_block(block_expr),
             ExpressionKind::Constructor(constructor) => {
                 let span = constructor.type_name.span();
 
                 match self.lookup_type_or_error(constructor.type_name) {
-                    Some(Type::Struct(r#type, struct_generics)) => {
+                    Some(Type::Struct(r#type, struct_generics)) if !r#type.borrow().is_enum() => {
                         let typ = r#type.clone();
                         let fields = constructor.fields;
                         let resolve_expr = Resolver::resolve_expression;
@@ -1472,7 +1707,11 @@ impl<'a> Resolver<'a> {
                 });
 
                 let return_type = this.resolve_inferred_type(lambda.return_type);
+                let loop_depth = std::mem::take(&mut this.loop_depth);
+                let in_unconstrained_fn = std::mem::take(&mut this.in_unconstrained_fn);
                 let body = this.resolve_expression(lambda.body);
+                this.loop_depth = loop_depth;
+                this.in_unconstrained_fn = in_unconstrained_fn;
 
                 let lambda_context = this.lambda_stack.pop().unwrap();
 
@@ -1539,7 +1778,11 @@ impl<'a> Resolver<'a> {
                 };
 
                 let (struct_type, generics) = match self.lookup_type_or_error(name) {
-                    Some(Type::Struct(struct_type, generics)) => (struct_type, generics),
+                    Some(Type::Struct(struct_type, generics))
+                        if !struct_type.borrow().is_enum() =>
+                    {
+                        (struct_type, generics)
+                    }
                     None => return error_identifier(self),
                     Some(typ) => {
                         self.push_err(ResolverError::NonStructUsedInConstructor { typ, span });
@@ -1560,6 +1803,98 @@ impl<'a> Resolver<'a> {
         }
     }
 
+    fn resolve_match_pattern(&mut self, pattern: MatchPattern) -> HirMatchPattern {
+        match pattern {
+            MatchPattern::Wildcard(span) => HirMatchPattern::Wildcard(span),
+            MatchPattern::Identifier(name) => {
+                let definition = DefinitionKind::Local(None);
+                let id = self.add_variable_decl(name, false, true, definition);
+                HirMatchPattern::Identifier(id)
+            }
+            MatchPattern::Literal(literal, span) => {
+                let literal = Expression::new(ExpressionKind::Literal(literal), span);
+                HirMatchPattern::Literal(self.resolve_expression(literal), span)
+            }
+            MatchPattern::Tuple(fields, span) => {
+                let fields = vecmap(fields, |field| self.resolve_match_pattern(field));
+                HirMatchPattern::Tuple(fields, span)
+            }
+            MatchPattern::Struct(name, fields, span) => {
+                let (struct_type, generics) = match self.lookup_type_or_error(name) {
+                    Some(Type::Struct(struct_type, generics))
+                        if !struct_type.borrow().is_enum() =>
+                    {
+                        (struct_type, generics)
+                    }
+                    None => return HirMatchPattern::Wildcard(span),
+                    Some(typ) => {
+                        self.push_err(ResolverError::NonStructUsedInConstructor { typ, span });
+                        return HirMatchPattern::Wildcard(span);
+                    }
+                };
+
+                let typ = struct_type.clone();
+                let fields =
+                    self.resolve_constructor_fields(typ, fields, span, Self::resolve_match_pattern);
+
+                let typ = Type::Struct(struct_type, generics);
+                HirMatchPattern::Struct(typ, fields, span)
+            }
+            MatchPattern::Variant(path, fields, span) => {
+                let fields = vecmap(fields, |field| self.resolve_match_pattern(field));
+
+                match self.lookup_enum_variant(&path) {
+                    Some((enum_type, variant)) => {
+                        let generics = enum_type.borrow().instantiate(self.interner);
+                        let typ = Type::Struct(enum_type, generics);
+                        HirMatchPattern::Variant(typ, variant, fields, span)
+                    }
+                    None => {
+                        self.push_err(ResolverError::NotAnEnumVariant { path, span });
+                        HirMatchPattern::Wildcard(span)
+                    }
+                }
+            }
+        }
+    }
+
+    /// Looks up the enum variant named by a path such as `Shape::Circle`, returning the enum
+    /// along with the index of the variant within it.
+    fn lookup_enum_variant(&mut self, path: &Path) -> Option<(Shared<StructType>, usize)> {
+        let mut enum_path = path.clone();
+        let variant = enum_path.segments.pop()?;
+        if enum_path.segments.is_empty() {
+            return None;
+        }
+
+        let is_self = enum_path.as_ident().map_or(false, |ident| ident == SELF_TYPE_NAME);
+        let enum_type = match &self.self_type {
+            Some(Type::Struct(self_type, _)) if is_self => self_type.clone(),
+            _ => {
+                let enum_id = self.lookup(enum_path).ok()?;
+                self.get_struct(enum_id)
+            }
+        };
+
+        let variant = enum_type.borrow().variant_index(&variant.0.contents)?;
+        Some((enum_type, variant))
+    }
+
+    fn enum_constructor(
+        &mut self,
+        enum_type: Shared<StructType>,
+        variant: usize,
+        arguments: Vec<ExprId>,
+    ) -> HirExpression {
+        let enum_generics = enum_type.borrow().instantiate(self.interner);
+        HirExpression::EnumConstructor(HirEnumConstructorExpression {
+            r#type: enum_type,
+            enum_generics,
+            variant,
+            arguments,
+        })
+    }
+
     /// Resolve all the fields of a struct constructor expression.
     /// Ensures all fields are present, none are repeated, and all
     /// are part of the struct.
@@ -1809,7 +2144,7 @@ impl<'a> Resolver<'a> {
 
     fn resolve_block(&mut self, block_expr: BlockExpression) -> HirExpression {
         let statements =
-            self.in_new_scope(|this| vecmap(block_expr.0, |stmt| this.intern_stmt(stmt.kind)));
+            self.in_new_scope(|this| vecmap(block_expr.0, |stmt| this.intern_stmt(stmt)));
         HirExpression::Block(HirBlockExpression(statements))
     }
 
@@ -1846,10 +2181,29 @@ impl<'a> Resolver<'a> {
             HirExpression::Literal(HirLiteral::Integer(int, false)) => {
                 int.try_into_u128().ok_or(Some(ResolverError::IntegerTooLarge { span }))
             }
+            // The number of bits of the field's modulus is known as soon as the field is, so that
+            // the standard library can give it as the length of the arrays of a field's bits.
+            HirExpression::Call(call) if self.is_builtin(call.func, "modulus_num_bits") => {
+                Ok(FieldElement::max_num_bits().into())
+            }
             _other => Err(Some(ResolverError::InvalidArrayLengthExpr { span })),
         }
     }
 
+    /// Returns whether `function` is the builtin function `name`.
+    fn is_builtin(&self, function: ExprId, name: &str) -> bool {
+        let HirExpression::Ident(ident) = self.interner.expression(&function) else {
+            return false;
+        };
+        match self.interner.try_definition(ident.id).map(|definition| &definition.kind) {
+            Some(DefinitionKind::Function(func_id)) => {
+                let attribute = self.interner.function_attributes(func_id).function.clone();
+                attribute.and_then(|attribute| attribute.builtin()).as_deref() == Some(name)
+            }
+            _ => false,
+        }
+    }
+
     fn resolve_fmt_str_literal(&mut self, str: String, call_expr_span: Span) -> HirLiteral {
         let re = Regex::new(r"\{([a-zA-Z0-9_]+)\}")
             .expect("ICE: an invalid regex pattern was used for checking format strings");
@@ -1890,6 +2244,7 @@ impl<'a> Resolver<'a> {
             | UnresolvedTypeData::Integer(_, _)
             | UnresolvedTypeData::Bool
             | UnresolvedTypeData::Unit
+            | UnresolvedTypeData::Value(_)
             | UnresolvedTypeData::Error => (),
 
             UnresolvedTypeData::MutableReference(_)
@@ -1990,3 +2345,20 @@ pub fn verify_mutable_reference(interner: &NodeInterner, rhs: ExprId) -> Result<
         _ => Ok(()),
     }
 }
+
+/// True if `typ` can be declared as the type of a generic with `let`, which are the types a
+/// TypeLevelValue or a Type::Constant can have.
+fn is_valid_value_generic_type(typ: &Type) -> bool {
+    match typ {
+        Type::FieldElement | Type::Integer(..) | Type::Error => true,
+        Type::Array(length, element) => {
+            length.evaluate_to_u64().is_some() && is_valid_value_generic_type(element)
+        }
+        _ => false,
+    }
+}
+
+/// True if `typ` can be an operand of an arithmetic expression in an array length.
+fn is_type_level_integer(typ: &Type) -> bool {
+    matches!(typ, Type::Constant(_) | Type::NamedGeneric(..) | Type::InfixExpr(..))
+}
@@ -16,6 +16,31 @@ use crate::{
 
 use super::{errors::ResolverError, path_resolver::StandardPathResolver, resolver::Resolver};
 
+/// Resolve the generics of each struct before any types which may refer to them, so that the
+/// arguments given to generics declared with `let` can be checked against their declared types.
+pub(crate) fn resolve_struct_generics(
+    context: &mut Context,
+    structs: &BTreeMap<StructId, UnresolvedStruct>,
+    crate_id: CrateId,
+) -> Vec<(CompilationError, FileId)> {
+    let mut errors: Vec<(CompilationError, FileId)> = vec![];
+
+    for (type_id, typ) in structs {
+        let file_id = typ.file_id;
+        let path_resolver =
+            StandardPathResolver::new(ModuleId { local_id: typ.module_id, krate: crate_id });
+        let (generics, resolver_errors) =
+            Resolver::new(&mut context.def_interner, &path_resolver, &context.def_maps, file_id)
+                .resolve_struct_generics(&typ.struct_def);
+        errors.extend(vecmap(resolver_errors, |err| (err.into(), file_id)));
+        context.def_interner.update_struct(*type_id, |struct_def| {
+            struct_def.generics = generics;
+        });
+    }
+
+    errors
+}
+
 /// Create the mappings from TypeId -> StructType
 /// so that expressions can access the fields of structs
 pub(crate) fn resolve_structs(
@@ -32,12 +57,23 @@ pub(crate) fn resolve_structs(
     // Each struct should already be present in the NodeInterner after def collection.
     for (type_id, typ) in structs {
         let file_id = typ.file_id;
-        let (generics, fields, resolver_errors) = resolve_struct_fields(context, crate_id, typ);
-        errors.extend(vecmap(resolver_errors, |err| (err.into(), file_id)));
-        context.def_interner.update_struct(type_id, |struct_def| {
-            struct_def.set_fields(fields);
-            struct_def.generics = generics;
-        });
+        let generics = context.def_interner.get_struct(type_id).borrow().generics.clone();
+
+        if typ.struct_def.variants.is_some() {
+            let (variants, resolver_errors) =
+                resolve_enum_variants(context, crate_id, typ, &generics);
+            errors.extend(vecmap(resolver_errors, |err| (err.into(), file_id)));
+            context.def_interner.update_struct(type_id, |struct_def| {
+                struct_def.set_variants(variants);
+            });
+        } else {
+            let (fields, resolver_errors) =
+                resolve_struct_fields(context, crate_id, typ, &generics);
+            errors.extend(vecmap(resolver_errors, |err| (err.into(), file_id)));
+            context.def_interner.update_struct(type_id, |struct_def| {
+                struct_def.set_fields(fields);
+            });
+        }
     }
 
     // Check whether the struct fields have nested slices
@@ -68,13 +104,24 @@ fn resolve_struct_fields(
     context: &mut Context,
     krate: CrateId,
     unresolved: UnresolvedStruct,
-) -> (Generics, Vec<(Ident, Type)>, Vec<ResolverError>) {
+    generics: &Generics,
+) -> (Vec<(Ident, Type)>, Vec<ResolverError>) {
     let path_resolver =
         StandardPathResolver::new(ModuleId { local_id: unresolved.module_id, krate });
     let file_id = unresolved.file_id;
-    let (generics, fields, errors) =
-        Resolver::new(&mut context.def_interner, &path_resolver, &context.def_maps, file_id)
-            .resolve_struct_fields(unresolved.struct_def);
+    Resolver::new(&mut context.def_interner, &path_resolver, &context.def_maps, file_id)
+        .resolve_struct_fields(unresolved.struct_def, generics)
+}
 
-    (generics, fields, errors)
+fn resolve_enum_variants(
+    context: &mut Context,
+    krate: CrateId,
+    unresolved: UnresolvedStruct,
+    generics: &Generics,
+) -> (Vec<(Ident, Vec<Type>)>, Vec<ResolverError>) {
+    let path_resolver =
+        StandardPathResolver::new(ModuleId { local_id: unresolved.module_id, krate });
+    let file_id = unresolved.file_id;
+    Resolver::new(&mut context.def_interner, &path_resolver, &context.def_maps, file_id)
+        .resolve_enum_variants(unresolved.struct_def, generics)
 }
@@ -9,7 +9,8 @@ use crate::{
     hir::{
         def_collector::{
             dc_crate::{
-                check_methods_signatures, CompilationError, UnresolvedTrait, UnresolvedTraitImpl,
+                check_methods_signatures, CompilationError, UnresolvedFunctions, UnresolvedTrait,
+                UnresolvedTraitImpl,
             },
             errors::{DefCollectorErrorKind, DuplicateType},
         },
@@ -195,6 +196,9 @@ fn collect_trait_impl_methods(
     // while also mutating the interner
     let the_trait = interner.get_trait_mut(trait_id);
     let methods = std::mem::take(&mut the_trait.methods);
+    let trait_crate = the_trait.crate_id;
+    let trait_file = the_trait.location.file;
+    let mut default_methods = Vec::new();
 
     for method in &methods {
         let overrides: Vec<_> = trait_impl
@@ -206,12 +210,15 @@ fn collect_trait_impl_methods(
 
         if overrides.is_empty() {
             if let Some(default_impl) = &method.default_impl {
+                // The default method body is written in the trait, so it is resolved within the
+                // trait's module, which may be in a different file or crate than this impl.
                 let func_id = interner.push_empty_fn();
-                let module = ModuleId { local_id: trait_impl.module_id, krate: crate_id };
-                let location = Location::new(default_impl.def.span, trait_impl.file_id);
+                let module =
+                    ModuleId { local_id: method.default_impl_module_id, krate: trait_crate };
+                let location = Location::new(default_impl.def.span, trait_file);
                 interner.push_function(func_id, &default_impl.def, module, location);
                 func_ids_in_trait.insert(func_id);
-                ordered_methods.push((
+                default_methods.push((
                     method.default_impl_module_id,
                     func_id,
                     *default_impl.clone(),
@@ -260,6 +267,11 @@ fn collect_trait_impl_methods(
 
     trait_impl.methods.functions = ordered_methods;
     trait_impl.methods.trait_id = Some(trait_id);
+    trait_impl.default_methods = UnresolvedFunctions {
+        file_id: trait_file,
+        functions: default_methods,
+        trait_id: Some(trait_id),
+    };
     errors
 }
 
@@ -297,7 +309,8 @@ fn collect_trait_impl(
             let struct_type = struct_type.borrow();
             let module = get_module_mut(def_maps, struct_type.id.module_id());
 
-            for (_, method_id, method) in &trait_impl.methods.functions {
+            let methods = trait_impl.methods.functions.iter();
+            for (_, method_id, method) in methods.chain(&trait_impl.default_methods.functions) {
                 // If this method was already declared, remove it from the module so it cannot
                 // be accessed with the `TypeName::method` syntax. We'll check later whether the
                 // object types in each method overlap or not. If they do, we issue an error.
@@ -365,6 +378,20 @@ pub(crate) fn resolve_trait_by_path(
         Err(_) => Err(DefCollectorErrorKind::TraitNotFound { trait_path: path }),
     }
 }
+/// Orders the methods of a trait impl to match the order of the methods in the trait, so that
+/// `TraitImpl::methods[i]` is the implementation of `Trait::methods[i]`.
+fn sort_by_trait_method_index(
+    interner: &NodeInterner,
+    trait_id: TraitId,
+    methods: &mut [(FileId, FuncId)],
+) {
+    let the_trait = interner.get_trait(trait_id);
+    methods.sort_by_key(|(_, func_id)| {
+        let name = interner.function_name(func_id);
+        the_trait.find_method(name).map_or(usize::MAX, |method| method.method_index)
+    });
+}
+
 pub(crate) fn resolve_trait_impls(
     context: &mut Context,
     traits: Vec<UnresolvedTraitImpl>,
@@ -404,9 +431,23 @@ pub(crate) fn resolve_trait_impls(
             errors,
         );
 
+        // Default methods are resolved in the trait's crate, but with the impl's Self type and
+        // generics so that any calls to other trait methods resolve to this impl's methods.
+        let default_methods_crate = trait_impl.trait_id.map_or(crate_id, |id| id.0.krate);
+        let mut default_methods = functions::resolve_function_set(
+            interner,
+            default_methods_crate,
+            &context.def_maps,
+            trait_impl.default_methods.clone(),
+            Some(self_type.clone()),
+            Some(impl_id),
+            impl_generics.clone(),
+            errors,
+        );
+
         let maybe_trait_id = trait_impl.trait_id;
         if let Some(trait_id) = maybe_trait_id {
-            for (_, func) in &impl_methods {
+            for (_, func) in impl_methods.iter().chain(&default_methods) {
                 interner.set_function_trait(*func, self_type.clone(), trait_id);
             }
         }
@@ -435,7 +476,7 @@ pub(crate) fn resolve_trait_impls(
                 errors,
             );
 
-            let where_clause = trait_impl
+            let where_clause: Vec<_> = trait_impl
                 .where_clause
                 .into_iter()
                 .flat_map(|item| new_resolver.resolve_trait_constraint(item))
@@ -444,6 +485,16 @@ pub(crate) fn resolve_trait_impls(
             let resolver_errors = new_resolver.take_errors().into_iter();
             errors.extend(resolver_errors.map(|error| (error.into(), trait_impl.file_id)));
 
+            // The default methods did not see the impl's where clause when they were resolved,
+            // but they may call methods of this impl which rely on it.
+            for (_, func_id) in &default_methods {
+                let meta = interner.function_meta_mut(func_id);
+                meta.trait_constraints.extend(where_clause.iter().cloned());
+            }
+
+            impl_methods.append(&mut default_methods);
+            sort_by_trait_method_index(interner, trait_id, &mut impl_methods);
+
             let resolved_trait_impl = Shared::new(TraitImpl {
                 ident: trait_impl.trait_path.last_segment().clone(),
                 typ: self_type.clone(),
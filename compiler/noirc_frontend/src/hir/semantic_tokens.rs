@@ -0,0 +1,133 @@
+//! Classification of the names in the source code by the item they resolved to, for editors to
+//! highlight e.g. calls to unconstrained functions differently from calls to constrained ones.
+use fm::FileId;
+use noirc_errors::Location;
+
+use crate::hir::Context;
+use crate::hir_def::expr::HirExpression;
+use crate::node_interner::{DefinitionKind, FuncId, Node};
+use crate::{FunctionKind, Type};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SemanticTokenKind {
+    Struct,
+    TypeAlias,
+    Trait,
+    /// A generic type or numeric generic of a function, struct or trait.
+    Generic,
+    Global,
+    Function,
+    UnconstrainedFunction,
+    OracleFunction,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SemanticToken {
+    /// The location of the name, without any path leading to it.
+    pub location: Location,
+    pub kind: SemanticTokenKind,
+    /// True if this is the name of the item in its own declaration.
+    pub is_declaration: bool,
+}
+
+impl Context<'_, '_> {
+    /// Returns the names in `file` which resolved to an item, in the order they appear.
+    ///
+    /// Local variables aren't included as they're already known to an editor from the syntax.
+    pub fn semantic_tokens(&self, file: FileId) -> Vec<SemanticToken> {
+        let interner = &self.def_interner;
+        let mut tokens = Vec::new();
+        let mut push = |location: Location, name: &str, kind, is_declaration| {
+            if location.file == file {
+                if let Some(location) = self.name_within(location, name) {
+                    tokens.push(SemanticToken { location, kind, is_declaration });
+                }
+            }
+        };
+
+        for (index, location) in &interner.id_to_location {
+            let Some(Node::Expression(HirExpression::Ident(ident))) = interner.nodes.get(*index)
+            else {
+                continue;
+            };
+            let Some(definition) = interner.try_definition(ident.id) else {
+                continue;
+            };
+            let kind = match &definition.kind {
+                DefinitionKind::Function(func_id) => self.function_token_kind(*func_id),
+                DefinitionKind::Global(_) => SemanticTokenKind::Global,
+                DefinitionKind::GenericType(_) => SemanticTokenKind::Generic,
+                DefinitionKind::Local(_) => continue,
+            };
+            push(*location, &definition.name, kind, false);
+        }
+
+        for (typ, location) in &interner.type_ref_locations {
+            match typ {
+                Type::Struct(struct_type, _) => {
+                    let name = struct_type.borrow().name.to_string();
+                    push(*location, &name, SemanticTokenKind::Struct, false);
+                }
+                Type::TraitAsType(_, name, _) => {
+                    push(*location, name, SemanticTokenKind::Trait, false);
+                }
+                Type::NamedGeneric(_, name) => {
+                    push(*location, name, SemanticTokenKind::Generic, false);
+                }
+                _ => (),
+            }
+        }
+
+        for (alias_id, location) in &interner.type_alias_ref {
+            let name = interner.get_type_alias(*alias_id).name.to_string();
+            push(*location, &name, SemanticTokenKind::TypeAlias, false);
+        }
+
+        for trait_impl in &interner.trait_implementations {
+            let trait_impl = trait_impl.borrow();
+            let name = interner.get_trait(trait_impl.trait_id).name.to_string();
+            let location = Location::new(trait_impl.ident.span(), trait_impl.file);
+            push(location, &name, SemanticTokenKind::Trait, false);
+        }
+
+        // Declarations
+        for (func_id, func_meta) in &interner.func_meta {
+            let name = interner.function_name(func_id);
+            push(func_meta.name.location, name, self.function_token_kind(*func_id), true);
+        }
+        for struct_type in interner.structs.values() {
+            let struct_type = struct_type.borrow();
+            let location = Location::new(struct_type.name.span(), struct_type.location.file);
+            push(location, &struct_type.name.to_string(), SemanticTokenKind::Struct, true);
+        }
+        for trait_ in interner.traits.values() {
+            let location = Location::new(trait_.name.span(), trait_.location.file);
+            push(location, &trait_.name.to_string(), SemanticTokenKind::Trait, true);
+        }
+        for alias in &interner.type_aliases {
+            let location = Location::new(alias.name.span(), alias.location.file);
+            push(location, &alias.name.to_string(), SemanticTokenKind::TypeAlias, true);
+        }
+        for definition in &interner.definitions {
+            if matches!(definition.kind, DefinitionKind::Global(_)) {
+                push(definition.location, &definition.name, SemanticTokenKind::Global, true);
+            }
+        }
+
+        tokens.sort_by_key(|token| (token.location.span.start(), !token.is_declaration));
+        // The same name may be recorded more than once, e.g. a call to a method of a trait impl.
+        tokens.dedup_by_key(|token| token.location.span.start());
+        tokens
+    }
+
+    fn function_token_kind(&self, func_id: FuncId) -> SemanticTokenKind {
+        let interner = &self.def_interner;
+        match interner.try_function_meta(&func_id).map(|func_meta| func_meta.kind) {
+            Some(FunctionKind::Oracle) => SemanticTokenKind::OracleFunction,
+            _ if interner.function_modifiers(&func_id).is_unconstrained => {
+                SemanticTokenKind::UnconstrainedFunction
+            }
+            _ => SemanticTokenKind::Function,
+        }
+    }
+}
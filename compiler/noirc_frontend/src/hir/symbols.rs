@@ -0,0 +1,275 @@
+//! The outline of the items declared in each file, as found by the def collector.
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use fm::FileId;
+use noirc_errors::Location;
+
+use crate::graph::CrateId;
+use crate::hir::def_map::{LocalModuleId, ModuleDefId, ModuleId};
+use crate::hir::Context;
+use crate::node_interner::{FuncId, StructId};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Module,
+    Struct,
+    Trait,
+    Impl,
+    Function,
+    /// A function declared in a trait or an `impl`.
+    Method,
+    Global,
+    TypeAlias,
+}
+
+/// An item declared in the source code, along with the items nested within it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// The location of the whole item, e.g. a function along with its body.
+    pub location: Location,
+    /// The location of the name of the item.
+    pub name_location: Location,
+    pub children: Vec<Symbol>,
+}
+
+impl Context<'_, '_> {
+    /// Returns the items declared in `file`, each nested within the module, trait or `impl`
+    /// which declares it, in the order they appear.
+    pub fn document_symbols(&self, file: FileId) -> Vec<Symbol> {
+        let mut symbols = Vec::new();
+        for (krate, def_map) in &self.def_maps {
+            for (index, module) in def_map.modules().iter() {
+                // Modules declared inline are nested within the module containing them.
+                let is_nested = module.parent.is_some_and(|parent| def_map.file_id(parent) == file);
+                if module.location.file == file && !is_nested {
+                    let module_id = ModuleId { krate: *krate, local_id: LocalModuleId(index) };
+                    symbols.extend(self.module_symbols(module_id, file));
+                }
+            }
+        }
+        sort_symbols(&mut symbols);
+        symbols
+    }
+
+    /// Returns the items declared in `crate_id` whose name matches `query`, along with the name
+    /// of the item they're nested within.
+    ///
+    /// Names match if they contain each character of the query in order, ignoring case, so that
+    /// e.g. `fbar` matches `foo_bar`. An empty query matches every item.
+    pub fn workspace_symbols(
+        &self,
+        crate_id: CrateId,
+        query: &str,
+    ) -> Vec<(Symbol, Option<String>)> {
+        let Some(def_map) = self.def_maps.get(&crate_id) else {
+            return Vec::new();
+        };
+        let files: HashSet<FileId> =
+            def_map.modules().iter().map(|(_, module)| module.location.file).collect();
+
+        let mut matches = Vec::new();
+        let mut pending: Vec<_> = files
+            .into_iter()
+            .flat_map(|file| self.document_symbols(file))
+            .map(|symbol| (symbol, None))
+            .collect();
+        while let Some((mut symbol, container)) = pending.pop() {
+            let children = std::mem::take(&mut symbol.children);
+            pending.extend(children.into_iter().map(|child| (child, Some(symbol.name.clone()))));
+            if fuzzy_matches(query, &symbol.name) {
+                matches.push((symbol, container));
+            }
+        }
+        matches.sort_by_key(|(symbol, _)| (symbol.location.file, symbol.location.span.start()));
+        matches
+    }
+
+    fn module_symbols(&self, module_id: ModuleId, file: FileId) -> Vec<Symbol> {
+        let interner = &self.def_interner;
+        let module = module_id.module(&self.def_maps);
+        let mut symbols = Vec::new();
+
+        for (name, child) in &module.children {
+            let child_id = ModuleId { krate: module_id.krate, local_id: *child };
+            let name_location = Location::new(name.span(), file);
+            // The items of modules declared with `mod foo;` are shown in their own file.
+            let children = if child_id.module(&self.def_maps).location.file == file {
+                self.module_symbols(child_id, file)
+            } else {
+                Vec::new()
+            };
+            symbols.push(container_symbol(
+                name.to_string(),
+                SymbolKind::Module,
+                name_location,
+                children,
+            ));
+        }
+
+        // Functions which aren't declared directly in the module belong to a trait or an `impl`.
+        let mut module_functions = HashSet::new();
+        for id in module.type_definitions().chain(module.value_definitions()) {
+            let symbol = match id {
+                ModuleDefId::ModuleId(_) => continue,
+                ModuleDefId::FunctionId(func_id) => {
+                    module_functions.insert(func_id);
+                    self.function_symbol(func_id, SymbolKind::Function)
+                }
+                ModuleDefId::TypeId(struct_id) => {
+                    let struct_type = interner.get_struct(struct_id);
+                    let struct_type = struct_type.borrow();
+                    let location = struct_type.location;
+                    Some(Symbol {
+                        name: struct_type.name.to_string(),
+                        kind: SymbolKind::Struct,
+                        location,
+                        name_location: Location::new(struct_type.name.span(), location.file),
+                        children: Vec::new(),
+                    })
+                }
+                ModuleDefId::TraitId(trait_id) => {
+                    let trait_ = interner.get_trait(trait_id);
+                    module_functions.extend(trait_.method_ids.values().copied());
+                    let children = trait_
+                        .methods
+                        .iter()
+                        .map(|method| Symbol {
+                            name: method.name.to_string(),
+                            kind: SymbolKind::Method,
+                            location: method.location,
+                            name_location: Location::new(method.name.span(), method.location.file),
+                            children: Vec::new(),
+                        })
+                        .collect();
+                    Some(Symbol {
+                        name: trait_.name.to_string(),
+                        kind: SymbolKind::Trait,
+                        location: trait_.location,
+                        name_location: Location::new(trait_.name.span(), trait_.location.file),
+                        children,
+                    })
+                }
+                ModuleDefId::TypeAliasId(alias_id) => {
+                    let alias = interner.get_type_alias(alias_id);
+                    Some(Symbol {
+                        name: alias.name.to_string(),
+                        kind: SymbolKind::TypeAlias,
+                        location: alias.location,
+                        name_location: Location::new(alias.name.span(), alias.location.file),
+                        children: Vec::new(),
+                    })
+                }
+                ModuleDefId::GlobalId(stmt_id) => {
+                    let ident = interner.let_statement(&stmt_id).ident();
+                    Some(Symbol {
+                        name: interner.definition_name(ident.id).to_string(),
+                        kind: SymbolKind::Global,
+                        location: ident.location,
+                        name_location: ident.location,
+                        children: Vec::new(),
+                    })
+                }
+            };
+            symbols.extend(symbol.filter(|symbol| symbol.location.file == file));
+        }
+
+        for trait_impl in &interner.trait_implementations {
+            let trait_impl = trait_impl.borrow();
+            if trait_impl.file != file
+                || trait_impl.methods.first().map(|method| interner.function_module(*method))
+                    != Some(module_id)
+            {
+                continue;
+            }
+            module_functions.extend(trait_impl.methods.iter().copied());
+            let name = format!("impl {} for {}", trait_impl.ident, trait_impl.typ);
+            let name_location = Location::new(trait_impl.ident.span(), file);
+            let methods = trait_impl
+                .methods
+                .iter()
+                .filter_map(|method| self.function_symbol(*method, SymbolKind::Method))
+                .collect();
+            symbols.push(container_symbol(name, SymbolKind::Impl, name_location, methods));
+        }
+
+        // The remaining functions of the module are declared in `impl`s of a type, which are
+        // grouped by the struct they're declared on.
+        let struct_methods: HashMap<FuncId, StructId> = interner
+            .struct_inherent_methods()
+            .map(|(struct_id, method)| (method, struct_id))
+            .collect();
+        let mut impls: BTreeMap<Option<StructId>, Vec<Symbol>> = BTreeMap::new();
+        for (func_id, func_meta) in &interner.func_meta {
+            if func_meta.location.file != file
+                || func_meta.trait_impl.is_some()
+                || module_functions.contains(func_id)
+                || interner.function_module(*func_id) != module_id
+            {
+                continue;
+            }
+            if let Some(symbol) = self.function_symbol(*func_id, SymbolKind::Method) {
+                impls.entry(struct_methods.get(func_id).copied()).or_default().push(symbol);
+            }
+        }
+        for (struct_id, mut methods) in impls {
+            sort_symbols(&mut methods);
+            let name = match struct_id {
+                Some(struct_id) => format!("impl {}", interner.get_struct(struct_id).borrow().name),
+                None => "impl".to_string(),
+            };
+            let name_location = methods[0].name_location;
+            symbols.push(container_symbol(name, SymbolKind::Impl, name_location, methods));
+        }
+
+        sort_symbols(&mut symbols);
+        symbols
+    }
+
+    fn function_symbol(&self, func_id: FuncId, kind: SymbolKind) -> Option<Symbol> {
+        let interner = &self.def_interner;
+        let func_meta = interner.try_function_meta(&func_id)?;
+        let name_location = func_meta.name.location;
+        let location = if func_meta.has_body {
+            let body = interner.expr_location(interner.function(&func_id).as_expr());
+            Location::new(name_location.span.merge(body.span), name_location.file)
+        } else {
+            name_location
+        };
+        Some(Symbol {
+            name: interner.function_name(&func_id).to_string(),
+            kind,
+            location,
+            name_location,
+            children: Vec::new(),
+        })
+    }
+}
+
+/// Returns a symbol which spans its name along with each of its `children`.
+fn container_symbol(
+    name: String,
+    kind: SymbolKind,
+    name_location: Location,
+    children: Vec<Symbol>,
+) -> Symbol {
+    let span = children
+        .iter()
+        .filter(|child| child.location.file == name_location.file)
+        .fold(name_location.span, |span, child| span.merge(child.location.span));
+    let location = Location::new(span, name_location.file);
+    Symbol { name, kind, location, name_location, children }
+}
+
+fn sort_symbols(symbols: &mut [Symbol]) {
+    symbols.sort_by_key(|symbol| symbol.location.span.start());
+}
+
+fn fuzzy_matches(query: &str, name: &str) -> bool {
+    let mut name = name.chars().flat_map(char::to_lowercase);
+    query
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|query_char| name.any(|name_char| name_char == query_char))
+}
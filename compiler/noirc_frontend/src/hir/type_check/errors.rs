@@ -115,6 +115,12 @@ pub enum TypeCheckError {
     NoMatchingImplFound { constraints: Vec<(Type, String)>, span: Span },
     #[error("Constraint for `{typ}: {trait_name}` is not needed, another matching impl is already in scope")]
     UnneededTraitConstraint { trait_name: String, typ: Type, span: Span },
+    #[error("Match is not exhaustive, `{missing}` is not covered")]
+    NonExhaustiveMatch { missing: String, span: Span },
+    #[error("Unreachable match arm")]
+    UnreachableMatchArm { span: Span },
+    #[error("Only fields, integers and booleans can be formatted into an assertion message, but found {typ}")]
+    InvalidAssertMessageArgument { typ: Type, span: Span },
 }
 
 impl TypeCheckError {
@@ -202,6 +208,7 @@ impl From<TypeCheckError> for Diagnostic {
             | TypeCheckError::AmbiguousBitWidth { span, .. }
             | TypeCheckError::IntegerAndFieldBinaryOperation { span }
             | TypeCheckError::OverflowingAssignment { span, .. }
+            | TypeCheckError::InvalidAssertMessageArgument { span, .. }
             | TypeCheckError::FieldModulo { span } => {
                 Diagnostic::simple_error(error.to_string(), String::new(), span)
             }
@@ -275,6 +282,13 @@ impl From<TypeCheckError> for Diagnostic {
                 let msg = format!("Constraint for `{typ}: {trait_name}` is not needed, another matching impl is already in scope");
                 Diagnostic::simple_warning(msg, "Unnecessary trait constraint in where clause".into(), span)
             }
+            TypeCheckError::NonExhaustiveMatch { missing, span } => {
+                let msg = format!("Pattern `{missing}` is not covered by this match");
+                Diagnostic::simple_error(msg, "Add an arm for this pattern or a `_` wildcard arm".into(), span)
+            }
+            TypeCheckError::UnreachableMatchArm { span } => {
+                Diagnostic::simple_warning(error.to_string(), "This pattern is already covered by the previous arms".into(), span)
+            }
         }
     }
 }
@@ -0,0 +1,300 @@
+//! Exhaustiveness and reachability checking for `match` expressions.
+//!
+//! This follows the usefulness algorithm from "Warnings for pattern matching" (Maranget, 2007).
+//! A pattern is useful with respect to a list of earlier patterns if it matches some value which
+//! none of the earlier patterns match. A match arm is unreachable if its pattern is not useful,
+//! and a match is exhaustive if a wildcard pattern would not be useful after all of its arms.
+use acvm::FieldElement;
+use iter_extended::vecmap;
+use noirc_errors::Span;
+
+use crate::{
+    hir_def::expr::{HirExpression, HirLiteral, HirMatchExpression, HirMatchPattern},
+    Type,
+};
+
+use super::{errors::TypeCheckError, TypeChecker};
+
+/// A match pattern reduced to the parts which matter for exhaustiveness.
+///
+/// Tuples and structs only have a single constructor each so both are represented as a product
+/// of their fields, with struct fields in the order they're declared in the struct.
+#[derive(Debug, Clone)]
+enum Pattern {
+    Wildcard,
+    Literal(Constant),
+    Product(Vec<Pattern>),
+    /// The index of an enum variant along with the patterns for its payload
+    Variant(usize, Vec<Pattern>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Constant {
+    Bool(bool),
+    Integer(FieldElement, /*sign*/ bool),
+}
+
+impl std::fmt::Display for Constant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Constant::Bool(value) => value.fmt(f),
+            Constant::Integer(value, false) => value.fmt(f),
+            Constant::Integer(value, true) => write!(f, "-{value}"),
+        }
+    }
+}
+
+/// The set of values a column of patterns is matched against.
+enum Shape {
+    Bool,
+    Tuple(Vec<Type>),
+    /// A struct with the given name and fields
+    Struct(String, Vec<(String, Type)>),
+    /// An enum with the given name and variants, each with the types of its payload
+    Enum(String, Vec<(String, Vec<Type>)>),
+    /// Types such as integers which have too many values to list. These can only be fully
+    /// covered by a wildcard.
+    Infinite,
+}
+
+impl Shape {
+    fn of(typ: &Type) -> Shape {
+        match typ.follow_bindings() {
+            Type::Bool => Shape::Bool,
+            Type::Unit => Shape::Tuple(Vec::new()),
+            Type::Tuple(fields) => Shape::Tuple(fields),
+            Type::Struct(struct_type, generics) => {
+                let struct_type = struct_type.borrow();
+                let name = struct_type.name.to_string();
+                if struct_type.is_enum() {
+                    Shape::Enum(name, struct_type.get_variants(&generics))
+                } else {
+                    Shape::Struct(name, struct_type.get_fields(&generics))
+                }
+            }
+            _ => Shape::Infinite,
+        }
+    }
+
+    /// Writes out a value of this shape given examples of each of its fields.
+    fn format_product(&self, fields: Vec<String>) -> String {
+        match self {
+            Shape::Struct(name, field_types) => {
+                let fields = vecmap(field_types.iter().zip(fields), |((name, _), field)| {
+                    format!("{name}: {field}")
+                });
+                format!("{name} {{ {} }}", fields.join(", "))
+            }
+            _ => format!("({})", fields.join(", ")),
+        }
+    }
+}
+
+impl<'interner> TypeChecker<'interner> {
+    /// Warns about each arm of the match which can never be reached, and issues an error
+    /// if there are values the match has no arm for.
+    pub(super) fn check_match_patterns(
+        &mut self,
+        match_expr: &HirMatchExpression,
+        scrutinee_type: &Type,
+        span: Span,
+    ) {
+        if scrutinee_type.follow_bindings() == Type::Error {
+            return;
+        }
+
+        let types = [scrutinee_type.clone()];
+        let mut rows = Vec::with_capacity(match_expr.rules.len());
+
+        for (pattern, _) in &match_expr.rules {
+            let row = vec![self.lower_match_pattern(pattern)];
+            if self.useful(&rows, &row, &types).is_none() {
+                self.errors.push(TypeCheckError::UnreachableMatchArm { span: pattern.span() });
+            }
+            rows.push(row);
+        }
+
+        if let Some(mut missing) = self.useful(&rows, &[Pattern::Wildcard], &types) {
+            let missing = missing.remove(0);
+            self.errors.push(TypeCheckError::NonExhaustiveMatch { missing, span });
+        }
+    }
+
+    fn lower_match_pattern(&self, pattern: &HirMatchPattern) -> Pattern {
+        match pattern {
+            HirMatchPattern::Wildcard(_) | HirMatchPattern::Identifier(_) => Pattern::Wildcard,
+            HirMatchPattern::Literal(literal, _) => match self.interner.expression(literal) {
+                HirExpression::Literal(HirLiteral::Bool(value)) => {
+                    Pattern::Literal(Constant::Bool(value))
+                }
+                HirExpression::Literal(HirLiteral::Integer(value, sign)) => {
+                    Pattern::Literal(Constant::Integer(value, sign))
+                }
+                _ => Pattern::Wildcard,
+            },
+            HirMatchPattern::Tuple(fields, _) => {
+                Pattern::Product(vecmap(fields, |field| self.lower_match_pattern(field)))
+            }
+            HirMatchPattern::Struct(Type::Struct(struct_type, generics), fields, _) => {
+                let declared_fields = struct_type.borrow().get_fields(generics);
+                Pattern::Product(vecmap(declared_fields, |(name, _)| {
+                    fields
+                        .iter()
+                        .find(|(field, _)| field.0.contents == name)
+                        .map_or(Pattern::Wildcard, |(_, field)| self.lower_match_pattern(field))
+                }))
+            }
+            HirMatchPattern::Struct(..) => Pattern::Wildcard,
+            HirMatchPattern::Variant(_, variant, fields, _) => {
+                Pattern::Variant(*variant, vecmap(fields, |field| self.lower_match_pattern(field)))
+            }
+        }
+    }
+
+    /// Returns an example of a value matched by `row` but by none of the rows of `matrix`,
+    /// or None if every value `row` matches is already matched by `matrix`.
+    ///
+    /// Each row has one pattern for each of `types`, and the example has one value for each.
+    fn useful(
+        &self,
+        matrix: &[Vec<Pattern>],
+        row: &[Pattern],
+        types: &[Type],
+    ) -> Option<Vec<String>> {
+        let (head, rest) = match row.split_first() {
+            Some(split) => split,
+            None => return matrix.is_empty().then(Vec::new),
+        };
+        let (head_type, rest_types) = types.split_first().expect("Expected a type per column");
+        let shape = Shape::of(head_type);
+
+        if let Shape::Enum(name, variants) = &shape {
+            let variant_indices = match head {
+                Pattern::Variant(variant, _) => *variant..*variant + 1,
+                Pattern::Wildcard => 0..variants.len(),
+                // A literal or product can't match an enum, the type error for it was already issued.
+                _ => return None,
+            };
+
+            // A wildcard is only useful if it matches some variant no previous row covers
+            return variant_indices.into_iter().find_map(|variant| {
+                let (variant_name, payload) = variants.get(variant)?;
+                let arity = payload.len();
+                let matrix = specialize_variant(matrix, variant, arity);
+                let row = specialize_variant(&[row.to_vec()], variant, arity).pop()?;
+                let types: Vec<_> = payload.iter().chain(rest_types).cloned().collect();
+
+                let mut example = self.useful(&matrix, &row, &types)?;
+                let rest_example = example.split_off(arity);
+                let variant = if arity == 0 {
+                    format!("{name}::{variant_name}")
+                } else {
+                    format!("{name}::{variant_name}({})", example.join(", "))
+                };
+                Some(prepend(variant, rest_example))
+            });
+        }
+
+        let field_types = match (&shape, head) {
+            (Shape::Tuple(fields), _) => Some(fields.clone()),
+            (Shape::Struct(_, fields), _) => Some(vecmap(fields, |(_, typ)| typ.clone())),
+            // This is only reachable after a type error was already issued.
+            (_, Pattern::Product(fields)) => Some(vecmap(fields, |_| Type::Error)),
+            _ => None,
+        };
+
+        if let Some(field_types) = field_types {
+            // A literal can't match a tuple or struct, the type error for it was already issued.
+            if matches!(head, Pattern::Literal(_)) {
+                return None;
+            }
+
+            let arity = field_types.len();
+            let matrix = specialize_product(matrix, arity);
+            let row = &specialize_product(&[row.to_vec()], arity)[0];
+            let types: Vec<_> = field_types.into_iter().chain(rest_types.iter().cloned()).collect();
+
+            let mut example = self.useful(&matrix, row, &types)?;
+            let rest_example = example.split_off(arity);
+            return Some(prepend(shape.format_product(example), rest_example));
+        }
+
+        match (head, shape) {
+            (Pattern::Literal(constant), _) => {
+                let matrix = specialize_constant(matrix, constant);
+                let example = self.useful(&matrix, rest, rest_types)?;
+                Some(prepend(constant.to_string(), example))
+            }
+            (_, Shape::Bool) => [true, false].into_iter().find_map(|value| {
+                let constant = Constant::Bool(value);
+                let matrix = specialize_constant(matrix, &constant);
+                let example = self.useful(&matrix, rest, rest_types)?;
+                Some(prepend(constant.to_string(), example))
+            }),
+            _ => {
+                // The literals of a type with too many values to list can never cover all of
+                // them, so only the rows starting with a wildcard can cover this one.
+                let matrix = vecmap(
+                    matrix.iter().filter(|row| matches!(row[0], Pattern::Wildcard)),
+                    |row| row[1..].to_vec(),
+                );
+                let example = self.useful(&matrix, rest, rest_types)?;
+                Some(prepend("_".to_string(), example))
+            }
+        }
+    }
+}
+
+/// Replaces the first pattern of each row with the patterns for each of its `arity` fields.
+fn specialize_product(matrix: &[Vec<Pattern>], arity: usize) -> Vec<Vec<Pattern>> {
+    matrix
+        .iter()
+        .filter_map(|row| {
+            let fields = match &row[0] {
+                Pattern::Product(fields) if fields.len() == arity => fields.clone(),
+                Pattern::Literal(_) | Pattern::Variant(..) => return None,
+                _ => vec![Pattern::Wildcard; arity],
+            };
+            Some(fields.into_iter().chain(row[1..].iter().cloned()).collect())
+        })
+        .collect()
+}
+
+/// Keeps only the rows whose first pattern matches the given enum variant, replacing that
+/// pattern with the patterns for each of the `arity` fields of its payload.
+fn specialize_variant(matrix: &[Vec<Pattern>], variant: usize, arity: usize) -> Vec<Vec<Pattern>> {
+    matrix
+        .iter()
+        .filter_map(|row| {
+            let fields = match &row[0] {
+                Pattern::Variant(other, fields) if *other == variant => {
+                    // A payload with the wrong number of patterns was already reported as an error
+                    if fields.len() != arity {
+                        return None;
+                    }
+                    fields.clone()
+                }
+                Pattern::Wildcard => vec![Pattern::Wildcard; arity],
+                _ => return None,
+            };
+            Some(fields.into_iter().chain(row[1..].iter().cloned()).collect())
+        })
+        .collect()
+}
+
+/// Keeps only the rows whose first pattern matches `constant`, removing that pattern.
+fn specialize_constant(matrix: &[Vec<Pattern>], constant: &Constant) -> Vec<Vec<Pattern>> {
+    matrix
+        .iter()
+        .filter(|row| match &row[0] {
+            Pattern::Literal(other) => other == constant,
+            _ => true,
+        })
+        .map(|row| row[1..].to_vec())
+        .collect()
+}
+
+fn prepend(first: String, mut rest: Vec<String>) -> Vec<String> {
+    rest.insert(0, first);
+    rest
+}
@@ -6,7 +6,8 @@ use crate::{
     hir_def::{
         expr::{
             self, HirArrayLiteral, HirBinaryOp, HirExpression, HirIdent, HirLiteral,
-            HirMethodCallExpression, HirMethodReference, HirPrefixExpression, ImplKind,
+            HirMatchExpression, HirMatchPattern, HirMethodCallExpression, HirMethodReference,
+            HirPrefixExpression, ImplKind,
         },
         types::Type,
     },
@@ -148,7 +149,9 @@ impl<'interner> TypeChecker<'interner> {
                     (typ, *arg, self.interner.expr_span(arg))
                 });
                 let span = self.interner.expr_span(expr_id);
-                self.bind_function_type(function, args, span)
+                let return_type = self.bind_function_type(function, args, span);
+                self.check_returned_array_length(&return_type, span);
+                return_type
             }
             HirExpression::MethodCall(mut method_call) => {
                 let mut object_type = self.check_expression(&method_call.object).follow_bindings();
@@ -228,10 +231,19 @@ impl<'interner> TypeChecker<'interner> {
             HirExpression::Prefix(prefix_expr) => {
                 let rhs_type = self.check_expression(&prefix_expr.rhs);
                 let span = self.interner.expr_span(&prefix_expr.rhs);
-                self.type_check_prefix_operand(&prefix_expr.operator, &rhs_type, span)
+
+                match prefix_expr.trait_method_id {
+                    Some(trait_method_id) if uses_operator_impl(&rhs_type) => self
+                        .check_operator_impl_call(*expr_id, trait_method_id, &rhs_type, &[], span),
+                    _ => self.type_check_prefix_operand(&prefix_expr.operator, &rhs_type, span),
+                }
             }
             HirExpression::If(if_expr) => self.check_if_expr(&if_expr, expr_id),
+            HirExpression::Match(match_expr) => self.check_match_expr(&match_expr, expr_id),
             HirExpression::Constructor(constructor) => self.check_constructor(constructor, expr_id),
+            HirExpression::EnumConstructor(constructor) => {
+                self.check_enum_constructor(constructor, expr_id)
+            }
             HirExpression::MemberAccess(access) => self.check_member_access(access, *expr_id),
             HirExpression::Error => Type::Error,
             HirExpression::Tuple(elements) => {
@@ -412,6 +424,7 @@ impl<'interner> TypeChecker<'interner> {
                             self.interner.push_expr(HirExpression::Prefix(HirPrefixExpression {
                                 operator: UnaryOp::MutableReference,
                                 rhs: method_call.object,
+                                trait_method_id: None,
                             }));
                         self.interner.push_expr_type(&new_object, new_type);
                         self.interner.push_expr_location(new_object, location.span, location.file);
@@ -438,6 +451,7 @@ impl<'interner> TypeChecker<'interner> {
             let object = self.interner.push_expr(HirExpression::Prefix(HirPrefixExpression {
                 operator: UnaryOp::Dereference { implicitly_added: true },
                 rhs: object,
+                trait_method_id: None,
             }));
             self.interner.push_expr_type(&object, element.as_ref().clone());
             self.interner.push_expr_location(object, location.span, location.file);
@@ -482,6 +496,29 @@ impl<'interner> TypeChecker<'interner> {
         let index_type = self.check_expression(&index_expr.index);
         let span = self.interner.expr_span(&index_expr.index);
 
+        // When writing `a[i]`, if `a : &mut ...` then automatically dereference `a` as many
+        // times as needed to get the underlying array.
+        let lhs_type = self.check_expression(&index_expr.collection);
+        let (new_lhs, lhs_type) = self.insert_auto_dereferences(index_expr.collection, lhs_type);
+        index_expr.collection = new_lhs;
+        let trait_method_id = index_expr.trait_method_id;
+        self.interner.replace_expr(id, HirExpression::Index(index_expr));
+
+        // Anything other than an array is indexed with a call to its `Index` impl
+        if let Some(trait_method_id) = trait_method_id {
+            if uses_operator_impl(&lhs_type) {
+                let index = [(index_type, span)];
+                let span = self.interner.expr_span(id);
+                return self.check_operator_impl_call(
+                    *id,
+                    trait_method_id,
+                    &lhs_type,
+                    &index,
+                    span,
+                );
+            }
+        }
+
         index_type.unify(&Type::polymorphic_integer(self.interner), &mut self.errors, || {
             TypeCheckError::TypeMismatch {
                 expected_typ: "an integer".to_owned(),
@@ -490,13 +527,6 @@ impl<'interner> TypeChecker<'interner> {
             }
         });
 
-        // When writing `a[i]`, if `a : &mut ...` then automatically dereference `a` as many
-        // times as needed to get the underlying array.
-        let lhs_type = self.check_expression(&index_expr.collection);
-        let (new_lhs, lhs_type) = self.insert_auto_dereferences(index_expr.collection, lhs_type);
-        index_expr.collection = new_lhs;
-        self.interner.replace_expr(id, HirExpression::Index(index_expr));
-
         match lhs_type.follow_bindings() {
             // XXX: We can check the array bounds here also, but it may be better to constant fold first
             // and have ConstId instead of ExprId for constants
@@ -544,6 +574,108 @@ impl<'interner> TypeChecker<'interner> {
         }
     }
 
+    fn check_match_expr(&mut self, match_expr: &HirMatchExpression, expr_id: &ExprId) -> Type {
+        let scrutinee_type = self.check_expression(&match_expr.expression);
+
+        let arm_types = vecmap(&match_expr.rules, |(pattern, body)| {
+            self.bind_match_pattern(pattern, scrutinee_type.clone());
+            (self.check_expression(body), *body)
+        });
+
+        if let Some(((first_type, _), other_arms)) = arm_types.split_first() {
+            for (arm_type, body) in other_arms {
+                let expr_span = self.interner.expr_span(body);
+                self.unify(arm_type, first_type, || {
+                    TypeCheckError::TypeMismatch {
+                        expected_typ: first_type.to_string(),
+                        expr_typ: arm_type.to_string(),
+                        expr_span,
+                    }
+                    .add_context("Expected the types of all match arms to be equal")
+                });
+            }
+        }
+
+        let span = self.interner.expr_span(expr_id);
+        self.check_match_patterns(match_expr, &scrutinee_type, span);
+
+        arm_types.into_iter().next().map_or(Type::Unit, |(first_type, _)| first_type)
+    }
+
+    /// Associate the variables bound by a match arm's pattern with the types of the
+    /// parts of the matched value they refer to.
+    fn bind_match_pattern(&mut self, pattern: &HirMatchPattern, typ: Type) {
+        match pattern {
+            HirMatchPattern::Wildcard(_) => (),
+            HirMatchPattern::Identifier(ident) => self.interner.push_definition_type(ident.id, typ),
+            HirMatchPattern::Literal(literal, span) => {
+                let literal_type = self.check_expression(literal);
+                self.unify(&literal_type, &typ, || TypeCheckError::TypeMismatch {
+                    expected_typ: typ.to_string(),
+                    expr_typ: literal_type.to_string(),
+                    expr_span: *span,
+                });
+            }
+            HirMatchPattern::Tuple(fields, span) => {
+                let field_types = vecmap(fields, |_| self.interner.next_type_variable());
+                let expected = Type::Tuple(field_types.clone());
+
+                self.unify(&expected, &typ, || TypeCheckError::TypeMismatch {
+                    expected_typ: typ.to_string(),
+                    expr_typ: expected.to_string(),
+                    expr_span: *span,
+                });
+
+                for (field, field_type) in fields.iter().zip(field_types) {
+                    self.bind_match_pattern(field, field_type);
+                }
+            }
+            HirMatchPattern::Struct(struct_type, fields, span) => {
+                self.unify(struct_type, &typ, || TypeCheckError::TypeMismatch {
+                    expected_typ: typ.to_string(),
+                    expr_typ: struct_type.to_string(),
+                    expr_span: *span,
+                });
+
+                if let Type::Struct(struct_type, generics) = struct_type {
+                    let struct_type = struct_type.borrow();
+
+                    for (field_name, field_pattern) in fields {
+                        if let Some((field_type, _)) =
+                            struct_type.get_field(&field_name.0.contents, generics)
+                        {
+                            self.bind_match_pattern(field_pattern, field_type);
+                        }
+                    }
+                }
+            }
+            HirMatchPattern::Variant(enum_type, variant, fields, span) => {
+                self.unify(enum_type, &typ, || TypeCheckError::TypeMismatch {
+                    expected_typ: typ.to_string(),
+                    expr_typ: enum_type.to_string(),
+                    expr_span: *span,
+                });
+
+                if let Type::Struct(enum_type, generics) = enum_type {
+                    let payload = enum_type.borrow().get_variant_payload(*variant, generics);
+                    self.check_variant_arity(payload.len(), fields.len(), *span);
+
+                    for (field, field_type) in fields.iter().zip(payload) {
+                        self.bind_match_pattern(field, field_type);
+                    }
+                }
+            }
+        }
+    }
+
+    fn check_variant_arity(&mut self, expected: usize, found: usize, span: Span) {
+        if expected != found {
+            let expected = expected as u16;
+            let found = found as u16;
+            self.errors.push(TypeCheckError::ArityMisMatch { expected, found, span });
+        }
+    }
+
     fn check_if_expr(&mut self, if_expr: &expr::HirIfExpression, expr_id: &ExprId) -> Type {
         let cond_type = self.check_expression(&if_expr.condition);
         let then_type = self.check_expression(&if_expr.consequence);
@@ -624,6 +756,36 @@ impl<'interner> TypeChecker<'interner> {
         Type::Struct(typ, generics)
     }
 
+    fn check_enum_constructor(
+        &mut self,
+        constructor: expr::HirEnumConstructorExpression,
+        expr_id: &ExprId,
+    ) -> Type {
+        let typ = constructor.r#type;
+        let generics = constructor.enum_generics;
+
+        let payload = typ.borrow().get_variant_payload(constructor.variant, &generics);
+        let span = self.interner.expr_span(expr_id);
+        self.check_variant_arity(payload.len(), constructor.arguments.len(), span);
+
+        for (i, arg) in constructor.arguments.into_iter().enumerate() {
+            let arg_type = self.check_expression(&arg);
+
+            if let Some(param_type) = payload.get(i) {
+                let span = self.interner.expr_span(&arg);
+                self.unify_with_coercions(&arg_type, param_type, arg, || {
+                    TypeCheckError::TypeMismatch {
+                        expected_typ: param_type.to_string(),
+                        expr_typ: arg_type.to_string(),
+                        expr_span: span,
+                    }
+                });
+            }
+        }
+
+        Type::Struct(typ, generics)
+    }
+
     fn check_member_access(&mut self, mut access: expr::HirMemberAccess, expr_id: ExprId) -> Type {
         let lhs_type = self.check_expression(&access.lhs).follow_bindings();
         let span = self.interner.expr_span(&expr_id);
@@ -634,6 +796,7 @@ impl<'interner> TypeChecker<'interner> {
             *access_lhs = this.interner.push_expr(HirExpression::Prefix(HirPrefixExpression {
                 operator: crate::UnaryOp::Dereference { implicitly_added: true },
                 rhs: old_lhs,
+                trait_method_id: None,
             }));
             this.interner.push_expr_type(&old_lhs, lhs_type);
             this.interner.push_expr_type(access_lhs, element);
@@ -977,6 +1140,22 @@ impl<'interner> TypeChecker<'interner> {
         fn_ret.clone()
     }
 
+    /// Checks that the length of an array returned by a call is known by the end of the function.
+    /// Lengths which are only given by how the array is used, such as the number of bits returned
+    /// by `Field::to_le_bits`, are otherwise left unbound.
+    fn check_returned_array_length(&mut self, return_type: &Type, span: Span) {
+        if !matches!(return_type.follow_bindings(), Type::Array(..)) {
+            return;
+        }
+        let return_type = return_type.clone();
+        self.push_delayed_type_check(Box::new(move || match return_type.follow_bindings() {
+            Type::Array(length, _) if matches!(*length, Type::TypeVariable(..)) => {
+                Err(TypeCheckError::TypeAnnotationsNeeded { span })
+            }
+            _ => Ok(()),
+        }));
+    }
+
     fn bind_function_type(
         &mut self,
         function: Type,
@@ -1173,6 +1352,76 @@ impl<'interner> TypeChecker<'interner> {
         }
     }
 
+    /// Type checks an overloaded prefix or index operator on `object_type` as a call to the
+    /// operator's trait method, returning the type of the call. `arguments` are the types and
+    /// spans of any arguments passed to the method after the object itself.
+    ///
+    /// Unlike binary operators these may use the trait's generics, e.g. `Index<T>` returns a `T`,
+    /// so the whole method type is unified rather than just the object type.
+    fn check_operator_impl_call(
+        &mut self,
+        expr_id: ExprId,
+        trait_method_id: TraitMethodId,
+        object_type: &Type,
+        arguments: &[(Type, Span)],
+        span: Span,
+    ) -> Type {
+        let the_trait = self.interner.get_trait(trait_method_id.trait_id);
+        let method = &the_trait.methods[trait_method_id.method_index];
+        let trait_generics = Type::from_generics(&the_trait.generics);
+        let (method_type, mut bindings) = method.typ.instantiate(self.interner);
+        let trait_generics = vecmap(trait_generics, |generic| generic.substitute(&bindings));
+
+        let return_type = match method_type {
+            Type::Function(parameters, return_type, _) => {
+                let expected_object_type = &parameters[0];
+                self.unify(object_type, expected_object_type, || TypeCheckError::TypeMismatch {
+                    expected_typ: expected_object_type.to_string(),
+                    expr_typ: object_type.to_string(),
+                    expr_span: span,
+                });
+
+                if parameters.len() != arguments.len() + 1 {
+                    unreachable!("Operator method does not take {} arguments", arguments.len())
+                }
+
+                for (parameter, (argument, argument_span)) in parameters[1..].iter().zip(arguments)
+                {
+                    self.unify(argument, parameter, || TypeCheckError::TypeMismatch {
+                        expected_typ: parameter.to_string(),
+                        expr_typ: argument.to_string(),
+                        expr_span: *argument_span,
+                    });
+                }
+                *return_type
+            }
+            other => {
+                unreachable!("Expected operator method to have a function type, but found {other}")
+            }
+        };
+
+        let trait_id = trait_method_id.trait_id;
+        self.verify_trait_constraint(object_type, trait_id, &trait_generics, expr_id, span);
+
+        // As with binary operators, any Assumed impl needs these substitutions applied as well.
+        let impl_kind = self.interner.get_selected_impl_for_expression(expr_id);
+        if let Some(TraitImplKind::Assumed { object_type, trait_generics }) = impl_kind {
+            let the_trait = self.interner.get_trait(trait_id);
+            let object_type = object_type.substitute(&bindings);
+            bindings.insert(
+                the_trait.self_type_typevar_id,
+                (the_trait.self_type_typevar.clone(), object_type.clone()),
+            );
+            self.interner.select_impl_for_expression(
+                expr_id,
+                TraitImplKind::Assumed { object_type, trait_generics },
+            );
+        }
+
+        self.interner.store_instantiation_bindings(expr_id, bindings);
+        return_type
+    }
+
     /// Prerequisite: verify_trait_constraint of the operator's trait constraint.
     ///
     /// Although by this point the operator is expected to already have a trait impl,
@@ -1227,6 +1476,20 @@ impl<'interner> TypeChecker<'interner> {
     }
 }
 
+/// Returns true if an overloadable unary or index operator on a value of this type should be
+/// resolved to a call to the operator's trait impl rather than to the builtin operator.
+fn uses_operator_impl(typ: &Type) -> bool {
+    !matches!(
+        typ.follow_bindings(),
+        Type::FieldElement
+            | Type::Integer(..)
+            | Type::Bool
+            | Type::TypeVariable(..)
+            | Type::Array(..)
+            | Type::Error
+    )
+}
+
 /// Taken from: https://stackoverflow.com/a/47127500
 fn sort_by_key_ref<T, F, K>(xs: &mut [T], key: F)
 where
@@ -8,6 +8,7 @@
 //! as all functions are required to give their full signatures. Closures are inferred but are
 //! never generalized and thus cannot be used polymorphically.
 mod errors;
+mod exhaustiveness;
 mod expr;
 mod stmt;
 
@@ -348,6 +349,7 @@ mod test {
             trait_impl: None,
             return_type: FunctionReturnType::Default(Span::default()),
             trait_constraints: Vec::new(),
+            numeric_bounds: Vec::new(),
         };
         interner.push_fn_meta(func_meta, func_id);
 
@@ -6,7 +6,7 @@ use crate::hir_def::stmt::{
     HirAssignStatement, HirConstrainStatement, HirForStatement, HirLValue, HirLetStatement,
     HirPattern, HirStatement,
 };
-use crate::hir_def::types::Type;
+use crate::hir_def::types::{Type, TypeVariableKind};
 use crate::node_interner::{DefinitionId, ExprId, StmtId};
 
 use super::errors::{Source, TypeCheckError};
@@ -50,7 +50,10 @@ impl<'interner> TypeChecker<'interner> {
             HirStatement::Constrain(constrain_stmt) => self.check_constrain_stmt(constrain_stmt),
             HirStatement::Assign(assign_stmt) => self.check_assign_stmt(assign_stmt, stmt_id),
             HirStatement::For(for_loop) => self.check_for_loop(for_loop),
-            HirStatement::Error => (),
+            HirStatement::Loop(block) => {
+                self.check_expression(&block);
+            }
+            HirStatement::Break | HirStatement::Continue | HirStatement::Error => (),
         }
         Type::Unit
     }
@@ -308,6 +311,35 @@ impl<'interner> TypeChecker<'interner> {
             expected_typ: Type::Bool.to_string(),
             expr_span,
         });
+
+        if let Some(assert_message) = stmt.2 {
+            self.check_assert_message(assert_message);
+        }
+    }
+
+    /// Each value formatted into an assertion message is reported from the single witness it is
+    /// assigned to, so these must be fields, integers or booleans. As their types may not be
+    /// known yet, this is checked once the rest of the function has been type checked.
+    fn check_assert_message(&mut self, assert_message: ExprId) {
+        self.check_expression(&assert_message);
+
+        let HirExpression::Literal(HirLiteral::FmtStr(_, arguments)) =
+            self.interner.expression(&assert_message)
+        else {
+            return;
+        };
+        for argument in arguments {
+            let typ = self.interner.id_type(argument);
+            let span = self.interner.expr_span(&argument);
+            self.push_delayed_type_check(Box::new(move || match typ.follow_bindings() {
+                Type::FieldElement
+                | Type::Integer(..)
+                | Type::Bool
+                | Type::TypeVariable(_, TypeVariableKind::IntegerOrField)
+                | Type::Error => Ok(()),
+                typ => Err(TypeCheckError::InvalidAssertMessageArgument { typ, span }),
+            }));
+        }
     }
 
     /// All declaration statements check that the user specified type(UST) is equal to the
@@ -345,14 +377,13 @@ impl<'interner> TypeChecker<'interner> {
         let span = self.interner.expr_span(rhs_expr);
         match expr {
             HirExpression::Literal(HirLiteral::Integer(value, false)) => {
-                let v = value.to_u128();
                 if let Type::Integer(_, bit_count) = annotated_type {
-                    let max = 1 << bit_count;
-                    if v >= max {
+                    if value.num_bits() > *bit_count {
+                        let max = u128::MAX.checked_shr(128 - bit_count).unwrap_or(0);
                         self.errors.push(TypeCheckError::OverflowingAssignment {
                             expr: value,
                             ty: annotated_type.clone(),
-                            range: format!("0..={}", max - 1),
+                            range: format!("0..={max}"),
                             span,
                         });
                     };
@@ -1,6 +1,6 @@
 use acvm::FieldElement;
 use fm::FileId;
-use noirc_errors::Location;
+use noirc_errors::{Location, Span};
 
 use crate::node_interner::{DefinitionId, ExprId, FuncId, NodeInterner, StmtId, TraitMethodId};
 use crate::{BinaryOp, BinaryOpKind, Ident, Shared, UnaryOp};
@@ -23,11 +23,13 @@ pub enum HirExpression {
     Infix(HirInfixExpression),
     Index(HirIndexExpression),
     Constructor(HirConstructorExpression),
+    EnumConstructor(HirEnumConstructorExpression),
     MemberAccess(HirMemberAccess),
     Call(HirCallExpression),
     MethodCall(HirMethodCallExpression),
     Cast(HirCastExpression),
     If(HirIfExpression),
+    Match(HirMatchExpression),
     Tuple(Vec<ExprId>),
     Lambda(HirLambda),
     Error,
@@ -129,6 +131,10 @@ pub enum HirArrayLiteral {
 pub struct HirPrefixExpression {
     pub operator: UnaryOp,
     pub rhs: ExprId,
+
+    /// The trait method id for the operator trait method that corresponds to this operator,
+    /// if the operator can be overloaded. Currently this is only `Neg::neg` for `-`.
+    pub trait_method_id: Option<TraitMethodId>,
 }
 
 #[derive(Debug, Clone)]
@@ -167,6 +173,37 @@ pub struct HirIfExpression {
     pub alternative: Option<ExprId>,
 }
 
+#[derive(Debug, Clone)]
+pub struct HirMatchExpression {
+    pub expression: ExprId,
+    pub rules: Vec<(HirMatchPattern, ExprId)>,
+}
+
+#[derive(Debug, Clone)]
+pub enum HirMatchPattern {
+    Wildcard(Span),
+    Identifier(HirIdent),
+    /// An integer or boolean literal expression this pattern compares against
+    Literal(ExprId, Span),
+    Tuple(Vec<HirMatchPattern>, Span),
+    Struct(Type, Vec<(Ident, HirMatchPattern)>, Span),
+    /// The type of the enum, the index of the variant within it and the patterns for its payload
+    Variant(Type, usize, Vec<HirMatchPattern>, Span),
+}
+
+impl HirMatchPattern {
+    pub fn span(&self) -> Span {
+        match self {
+            HirMatchPattern::Identifier(ident) => ident.location.span,
+            HirMatchPattern::Wildcard(span)
+            | HirMatchPattern::Literal(_, span)
+            | HirMatchPattern::Tuple(_, span)
+            | HirMatchPattern::Struct(_, _, span)
+            | HirMatchPattern::Variant(_, _, _, span) => *span,
+        }
+    }
+}
+
 // `lhs as type` in the source code
 #[derive(Debug, Clone)]
 pub struct HirCastExpression {
@@ -252,11 +289,26 @@ pub struct HirConstructorExpression {
     pub fields: Vec<(Ident, ExprId)>,
 }
 
+/// Constructs a variant of an enum, e.g. `Option::Some(x)` or `Option::None`
+#[derive(Debug, Clone)]
+pub struct HirEnumConstructorExpression {
+    pub r#type: Shared<StructType>,
+    pub enum_generics: Vec<Type>,
+
+    /// The index of the variant within the enum
+    pub variant: usize,
+    pub arguments: Vec<ExprId>,
+}
+
 /// Indexing, as in `array[index]`
 #[derive(Debug, Clone)]
 pub struct HirIndexExpression {
     pub collection: ExprId,
     pub index: ExprId,
+
+    /// The `Index::index` trait method, used when the collection is not an array or slice.
+    /// This is `None` if the `Index` trait is not available, e.g. when compiling without the stdlib.
+    pub trait_method_id: Option<TraitMethodId>,
 }
 
 #[derive(Debug, Clone)]
@@ -6,7 +6,7 @@ use super::stmt::HirPattern;
 use super::traits::TraitConstraint;
 use crate::node_interner::{ExprId, NodeInterner, TraitImplId};
 use crate::FunctionKind;
-use crate::{Distinctness, FunctionReturnType, Type, Visibility};
+use crate::{BinaryOpKind, Distinctness, FunctionReturnType, Type, Visibility};
 
 /// A Hir function is a block expression
 /// with a list of statements
@@ -115,6 +115,10 @@ pub struct FuncMeta {
 
     pub trait_constraints: Vec<TraitConstraint>,
 
+    /// The bounds on the numeric generics of this function, checked each time the function is
+    /// monomorphized.
+    pub numeric_bounds: Vec<NumericBound>,
+
     /// The trait impl this function belongs to, if any
     pub trait_impl: Option<TraitImplId>,
 }
@@ -151,3 +155,41 @@ impl FuncMeta {
         }
     }
 }
+
+/// A bound on the numeric generics of a function such as `where N <= 32`.
+#[derive(Debug, Clone)]
+pub struct NumericBound {
+    pub lhs: Type,
+    pub operator: BinaryOpKind,
+    pub rhs: Type,
+    pub location: Location,
+}
+
+impl NumericBound {
+    /// Returns whether the bound holds once the generics it uses are bound to constants.
+    ///
+    /// A bound whose sides can't be evaluated, for example because a subtraction in them
+    /// underflows, never holds.
+    pub fn is_satisfied(&self) -> bool {
+        let (Some(lhs), Some(rhs)) = (self.lhs.evaluate_to_u64(), self.rhs.evaluate_to_u64())
+        else {
+            return false;
+        };
+
+        match self.operator {
+            BinaryOpKind::Equal => lhs == rhs,
+            BinaryOpKind::NotEqual => lhs != rhs,
+            BinaryOpKind::Less => lhs < rhs,
+            BinaryOpKind::LessEqual => lhs <= rhs,
+            BinaryOpKind::Greater => lhs > rhs,
+            BinaryOpKind::GreaterEqual => lhs >= rhs,
+            operator => unreachable!("Unexpected operator `{operator}` in a numeric bound"),
+        }
+    }
+}
+
+impl std::fmt::Display for NumericBound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} {}", self.lhs, self.operator, self.rhs)
+    }
+}
@@ -5,6 +5,8 @@ use std::{
     rc::Rc,
 };
 
+use acvm::FieldElement;
+
 use crate::{
     hir::type_check::TypeCheckError,
     node_interner::{ExprId, NodeInterner, TraitId, TypeAliasId},
@@ -92,6 +94,17 @@ pub enum Type {
     /// bind to an integer without special checks to bind it to a non-type.
     Constant(u64),
 
+    /// An arithmetic expression over type-level integers, e.g. the `N + M` in `[Field; N + M]`.
+    /// Expressions whose operands are all known are folded into a Type::Constant, so this
+    /// only remains while one of its operands is a generic. It is evaluated once those are
+    /// bound, at the latest during monomorphization.
+    InfixExpr(Box<Type>, BinaryTypeOperator, Box<Type>),
+
+    /// A type-level constant which isn't an array length, such as a field element or an array
+    /// of them. These are only used as the arguments of generics declared with `let`, e.g. the
+    /// `[1, 2, 3]` in `Cipher<[1, 2, 3]>`.
+    Value(TypeLevelValue),
+
     /// The type of a slice is an array of size NotConstant.
     /// The size of an array literal is resolved to this if it ever uses operations
     /// involving slices.
@@ -139,6 +152,8 @@ impl Type {
             | Type::MutableReference(_)
             | Type::Forall(_, _)
             | Type::Constant(_)
+            | Type::InfixExpr(..)
+            | Type::Value(_)
             | Type::NotConstant
             | Type::Error => unreachable!("This type cannot exist as a parameter to main"),
         }
@@ -187,6 +202,10 @@ impl Type {
 /// the binding to later be undone if needed.
 pub type TypeBindings = HashMap<TypeVariableId, (TypeVariable, Type)>;
 
+/// The name of the field holding the index of an enum's variant. This is not a valid
+/// identifier so it can't be accessed from source code.
+pub const ENUM_TAG_FIELD: &str = "$tag";
+
 /// Represents a struct type in the type system. Each instance of this
 /// rust struct will be shared across all Type::Struct variants that represent
 /// the same struct type.
@@ -203,6 +222,13 @@ pub struct StructType {
     /// since these will handle applying generic arguments to fields as well.
     fields: Vec<(Ident, Type)>,
 
+    /// If this is an enum, its variants along with the types of their payloads.
+    ///
+    /// An enum is stored as a struct whose first field is a tag holding the index of the
+    /// variant, followed by the payload fields of each variant in turn. These fields are
+    /// named such that they can't be accessed from source code.
+    variants: Option<Vec<(Ident, Vec<Type>)>>,
+
     pub generics: Generics,
     pub location: Location,
 }
@@ -231,7 +257,7 @@ impl StructType {
         fields: Vec<(Ident, Type)>,
         generics: Generics,
     ) -> StructType {
-        StructType { id, fields, name, location, generics }
+        StructType { id, fields, variants: None, name, location, generics }
     }
 
     /// To account for cyclic references between structs, a struct's
@@ -243,38 +269,88 @@ impl StructType {
         self.fields = fields;
     }
 
+    /// Like `set_fields`, this sets the variants of an enum once they become known,
+    /// along with the fields they are stored in.
+    pub fn set_variants(&mut self, variants: Vec<(Ident, Vec<Type>)>) {
+        assert!(self.fields.is_empty());
+
+        let tag = Ident::new(ENUM_TAG_FIELD.to_string(), self.name.span());
+        self.fields.push((tag, Type::FieldElement));
+
+        for (name, payload) in &variants {
+            for (i, typ) in payload.iter().enumerate() {
+                let field = Ident::new(format!("{name}.{i}"), name.span());
+                self.fields.push((field, typ.clone()));
+            }
+        }
+
+        self.variants = Some(variants);
+    }
+
+    pub fn is_enum(&self) -> bool {
+        self.variants.is_some()
+    }
+
     pub fn num_fields(&self) -> usize {
         self.fields.len()
     }
 
-    /// Returns the field matching the given field name, as well as its field index.
-    pub fn get_field(&self, field_name: &str, generic_args: &[Type]) -> Option<(Type, usize)> {
-        assert_eq!(self.generics.len(), generic_args.len());
+    /// Returns the index of the variant matching the given name.
+    /// This is always None for structs.
+    pub fn variant_index(&self, variant_name: &str) -> Option<usize> {
+        self.variants.as_ref()?.iter().position(|(name, _)| name.0.contents == variant_name)
+    }
 
-        self.fields.iter().enumerate().find(|(_, (name, _))| name.0.contents == field_name).map(
-            |(i, (_, typ))| {
-                let substitutions = self
-                    .generics
-                    .iter()
-                    .zip(generic_args)
-                    .map(|(old, new)| (old.id(), (old.clone(), new.clone())))
-                    .collect();
+    /// Returns the types of the payload of the variant with the given index, after being
+    /// applied to the given generic arguments.
+    pub fn get_variant_payload(&self, variant: usize, generic_args: &[Type]) -> Vec<Type> {
+        let substitutions = self.substitutions(generic_args);
+        let variants = self.variants.as_ref().expect("Expected an enum");
+        vecmap(&variants[variant].1, |typ| typ.substitute(&substitutions))
+    }
 
-                (typ.substitute(&substitutions), i)
-            },
-        )
+    /// Returns all the variants of this enum after being applied to the given generic
+    /// arguments, or an empty Vec for structs.
+    pub fn get_variants(&self, generic_args: &[Type]) -> Vec<(String, Vec<Type>)> {
+        let substitutions = self.substitutions(generic_args);
+
+        vecmap(self.variants.iter().flatten(), |(name, payload)| {
+            let payload = vecmap(payload, |typ| typ.substitute(&substitutions));
+            (name.0.contents.clone(), payload)
+        })
     }
 
-    /// Returns all the fields of this type, after being applied to the given generic arguments.
-    pub fn get_fields(&self, generic_args: &[Type]) -> Vec<(String, Type)> {
+    /// Returns the index of the first field holding the payload of the given variant.
+    pub fn variant_field_offset(&self, variant: usize) -> usize {
+        let variants = self.variants.as_ref().expect("Expected an enum");
+        // The tag comes before every payload
+        1 + variants[..variant].iter().map(|(_, payload)| payload.len()).sum::<usize>()
+    }
+
+    fn substitutions(&self, generic_args: &[Type]) -> TypeBindings {
         assert_eq!(self.generics.len(), generic_args.len());
 
-        let substitutions = self
-            .generics
+        self.generics
             .iter()
             .zip(generic_args)
             .map(|(old, new)| (old.id(), (old.clone(), new.clone())))
-            .collect();
+            .collect()
+    }
+
+    /// Returns the field matching the given field name, as well as its field index.
+    pub fn get_field(&self, field_name: &str, generic_args: &[Type]) -> Option<(Type, usize)> {
+        let substitutions = self.substitutions(generic_args);
+
+        self.fields
+            .iter()
+            .enumerate()
+            .find(|(_, (name, _))| name.0.contents == field_name)
+            .map(|(i, (_, typ))| (typ.substitute(&substitutions), i))
+    }
+
+    /// Returns all the fields of this type, after being applied to the given generic arguments.
+    pub fn get_fields(&self, generic_args: &[Type]) -> Vec<(String, Type)> {
+        let substitutions = self.substitutions(generic_args);
 
         vecmap(&self.fields, |(name, typ)| {
             let name = name.0.contents.clone();
@@ -429,6 +505,48 @@ pub enum BinaryTypeOperator {
     Modulo,
 }
 
+/// A constant used as the argument of a generic declared with `let`, e.g. `let SEPARATOR: Field`.
+/// Integers which fit in a u64 are represented by a Type::Constant instead so that they unify
+/// with array lengths, leaving this for larger field elements and arrays of constants.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub enum TypeLevelValue {
+    Field(FieldElement),
+    Array(Vec<TypeLevelValue>),
+}
+
+impl TypeLevelValue {
+    /// True if this value can be given to a generic declared with the given type.
+    pub fn has_type(&self, typ: &Type) -> bool {
+        match (self, typ.follow_bindings()) {
+            (TypeLevelValue::Field(_), Type::FieldElement) => true,
+            // Constants are never negative, so they leave the sign bit of signed integers unset
+            (TypeLevelValue::Field(value), Type::Integer(Signedness::Signed, bit_size)) => {
+                value.num_bits() < bit_size
+            }
+            (TypeLevelValue::Field(value), Type::Integer(Signedness::Unsigned, bit_size)) => {
+                value.num_bits() <= bit_size
+            }
+            (TypeLevelValue::Array(elements), Type::Array(length, element_type)) => {
+                length.evaluate_to_u64() == Some(elements.len() as u64)
+                    && elements.iter().all(|element| element.has_type(&element_type))
+            }
+            _ => false,
+        }
+    }
+}
+
+impl std::fmt::Display for TypeLevelValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TypeLevelValue::Field(value) => write!(f, "{value}"),
+            TypeLevelValue::Array(elements) => {
+                let elements = vecmap(elements, ToString::to_string);
+                write!(f, "[{}]", elements.join(", "))
+            }
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
 pub enum TypeVariableKind {
     /// Can bind to any type
@@ -611,13 +729,22 @@ impl Type {
             | Type::Error
             | Type::TypeVariable(_, _)
             | Type::Constant(_)
+            | Type::Value(_)
             | Type::NamedGeneric(_, _)
             | Type::NotConstant
             | Type::Forall(_, _)
             | Type::TraitAsType(..) => false,
 
             Type::Array(length, elem) => {
-                elem.contains_numeric_typevar(target_id) || named_generic_id_matches_target(length)
+                elem.contains_numeric_typevar(target_id)
+                    || named_generic_id_matches_target(length)
+                    || length.contains_numeric_typevar(target_id)
+            }
+            Type::InfixExpr(lhs, _, rhs) => {
+                named_generic_id_matches_target(lhs)
+                    || named_generic_id_matches_target(rhs)
+                    || lhs.contains_numeric_typevar(target_id)
+                    || rhs.contains_numeric_typevar(target_id)
             }
 
             Type::Tuple(fields) => {
@@ -638,10 +765,14 @@ impl Type {
                 })
             }
             Type::MutableReference(element) => element.contains_numeric_typevar(target_id),
-            Type::String(length) => named_generic_id_matches_target(length),
+            Type::String(length) => {
+                named_generic_id_matches_target(length)
+                    || length.contains_numeric_typevar(target_id)
+            }
             Type::FmtString(length, elements) => {
                 elements.contains_numeric_typevar(target_id)
                     || named_generic_id_matches_target(length)
+                    || length.contains_numeric_typevar(target_id)
             }
         }
     }
@@ -671,6 +802,8 @@ impl Type {
             | Type::MutableReference(_)
             | Type::Forall(_, _)
             | Type::TraitAsType(..)
+            | Type::InfixExpr(..)
+            | Type::Value(_)
             | Type::NotConstant => false,
 
             Type::Array(length, element) => {
@@ -797,6 +930,8 @@ impl std::fmt::Display for Type {
                 TypeBinding::Unbound(_) => write!(f, "{name}"),
             },
             Type::Constant(x) => x.fmt(f),
+            Type::InfixExpr(lhs, op, rhs) => write!(f, "({lhs} {op} {rhs})"),
+            Type::Value(value) => value.fmt(f),
             Type::Forall(typevars, typ) => {
                 let typevars = vecmap(typevars, |var| var.id().to_string());
                 write!(f, "forall {}. {}", typevars.join(" "), typ)
@@ -874,6 +1009,13 @@ impl Type {
                 bindings.insert(target_id, (var.clone(), Type::NotConstant));
                 Ok(())
             }
+            Type::InfixExpr(..) => match this.evaluate_to_u64() {
+                Some(length) if length == target_length => {
+                    bindings.insert(target_id, (var.clone(), Type::Constant(length)));
+                    Ok(())
+                }
+                _ => Err(UnificationError),
+            },
             // A TypeVariable is less specific than a MaybeConstant, so we bind
             // to the other type variable instead.
             Type::TypeVariable(new_var, kind) => {
@@ -1142,6 +1284,26 @@ impl Type {
                 elem_a.try_unify(elem_b, bindings)
             }
 
+            (InfixExpr(..), _) | (_, InfixExpr(..)) => {
+                // Compare the values of the two sides if they are both known by now. Otherwise
+                // the expressions must match exactly, e.g. `N + M` only unifies with `N + M`.
+                let this = self.substitute(bindings);
+                let other = other.substitute(bindings);
+                match (this.evaluate_to_u64(), other.evaluate_to_u64()) {
+                    (Some(a), Some(b)) if a == b => Ok(()),
+                    (Some(_), Some(_)) => Err(UnificationError),
+                    _ => match (self, other) {
+                        (InfixExpr(lhs_a, op_a, rhs_a), InfixExpr(lhs_b, op_b, rhs_b))
+                            if op_a == op_b =>
+                        {
+                            lhs_a.try_unify(lhs_b, bindings)?;
+                            rhs_a.try_unify(rhs_b, bindings)
+                        }
+                        _ => Err(UnificationError),
+                    },
+                }
+            }
+
             (other_a, other_b) => {
                 if other_a == other_b {
                     Ok(())
@@ -1255,6 +1417,9 @@ impl Type {
             Type::TypeVariable(_, TypeVariableKind::Constant(size)) => Some(*size),
             Type::Array(len, _elem) => len.evaluate_to_u64(),
             Type::Constant(x) => Some(*x),
+            Type::InfixExpr(lhs, op, rhs) => {
+                op.evaluate(lhs.evaluate_to_u64()?, rhs.evaluate_to_u64()?)
+            }
             _ => None,
         }
     }
@@ -1442,11 +1607,17 @@ impl Type {
             Type::MutableReference(element) => Type::MutableReference(Box::new(
                 element.substitute_helper(type_bindings, substitute_bound_typevars),
             )),
+            Type::InfixExpr(lhs, op, rhs) => {
+                let lhs = lhs.substitute_helper(type_bindings, substitute_bound_typevars);
+                let rhs = rhs.substitute_helper(type_bindings, substitute_bound_typevars);
+                Type::InfixExpr(Box::new(lhs), *op, Box::new(rhs))
+            }
 
             Type::FieldElement
             | Type::Integer(_, _)
             | Type::Bool
             | Type::Constant(_)
+            | Type::Value(_)
             | Type::TraitAsType(..)
             | Type::Error
             | Type::NotConstant
@@ -1481,11 +1652,13 @@ impl Type {
                     || env.occurs(target_id)
             }
             Type::MutableReference(element) => element.occurs(target_id),
+            Type::InfixExpr(lhs, _, rhs) => lhs.occurs(target_id) || rhs.occurs(target_id),
 
             Type::FieldElement
             | Type::Integer(_, _)
             | Type::Bool
             | Type::Constant(_)
+            | Type::Value(_)
             | Type::TraitAsType(..)
             | Type::Error
             | Type::NotConstant
@@ -1532,6 +1705,17 @@ impl Type {
 
             MutableReference(element) => MutableReference(Box::new(element.follow_bindings())),
 
+            InfixExpr(lhs, op, rhs) => {
+                let lhs = lhs.follow_bindings();
+                let rhs = rhs.follow_bindings();
+                if let (Constant(lhs), Constant(rhs)) = (&lhs, &rhs) {
+                    if let Some(value) = op.evaluate(*lhs, *rhs) {
+                        return Constant(value);
+                    }
+                }
+                InfixExpr(Box::new(lhs), *op, Box::new(rhs))
+            }
+
             // Expect that this function should only be called on instantiated types
             Forall(..) => unreachable!(),
             TraitAsType(..)
@@ -1539,6 +1723,7 @@ impl Type {
             | Integer(_, _)
             | Bool
             | Constant(_)
+            | Value(_)
             | Unit
             | Error
             | NotConstant => self.clone(),
@@ -1591,6 +1776,18 @@ impl BinaryTypeOperator {
             BinaryTypeOperator::Modulo => |a, b| a.wrapping_rem(b), // % b,
         }
     }
+
+    /// Apply this operator to the two operands, returning None if the result would overflow
+    /// or be a division by zero.
+    pub fn evaluate(self, lhs: u64, rhs: u64) -> Option<u64> {
+        match self {
+            BinaryTypeOperator::Addition => lhs.checked_add(rhs),
+            BinaryTypeOperator::Subtraction => lhs.checked_sub(rhs),
+            BinaryTypeOperator::Multiplication => lhs.checked_mul(rhs),
+            BinaryTypeOperator::Division => lhs.checked_div(rhs),
+            BinaryTypeOperator::Modulo => lhs.checked_rem(rhs),
+        }
+    }
 }
 
 impl TypeVariableKind {
@@ -1636,10 +1833,13 @@ impl From<&Type> for PrintableType {
                 let size = size.evaluate_to_u64().expect("Cannot print variable sized strings");
                 PrintableType::String { length: size }
             }
-            Type::FmtString(_, _) => unreachable!("format strings cannot be printed"),
+            Type::FmtString(size, fields) => {
+                let size = size.evaluate_to_u64().expect("Cannot print variable sized strings");
+                PrintableType::FmtString { length: size, typ: Box::new(fields.as_ref().into()) }
+            }
             Type::Error => unreachable!(),
             Type::Unit => PrintableType::Unit,
-            Type::Constant(_) => unreachable!(),
+            Type::Constant(_) | Type::InfixExpr(..) | Type::Value(_) => unreachable!(),
             Type::Struct(def, ref args) => {
                 let struct_type = def.borrow();
                 let fields = struct_type.get_fields(args);
@@ -1715,6 +1915,8 @@ impl std::fmt::Debug for Type {
             Type::Error => write!(f, "error"),
             Type::NamedGeneric(binding, name) => write!(f, "{}{:?}", name, binding),
             Type::Constant(x) => x.fmt(f),
+            Type::InfixExpr(lhs, op, rhs) => write!(f, "({lhs:?} {op} {rhs:?})"),
+            Type::Value(value) => value.fmt(f),
             Type::Forall(typevars, typ) => {
                 let typevars = vecmap(typevars, |var| format!("{:?}", var));
                 write!(f, "forall {}. {:?}", typevars.join(" "), typ)
@@ -17,7 +17,7 @@ pub enum LexerErrorKind {
     InvalidIntegerLiteral { span: Span, found: String },
     #[error("{:?} is not a valid attribute", found)]
     MalformedFuncAttribute { span: Span, found: String },
-    #[error("Integer type is larger than the maximum supported size of u127")]
+    #[error("Integer type is larger than the maximum supported size of u{max}")]
     TooManyBits { span: Span, max: u32, got: u32 },
     #[error("Logical and used instead of bitwise and")]
     LogicalAnd { span: Span },
@@ -86,9 +86,9 @@ impl LexerErrorKind {
                 *span,
             ),
             LexerErrorKind::TooManyBits { span, max, got } => (
-                "Integer literal too large".to_string(),
+                "Integer type too large".to_string(),
                 format!(
-                    "The maximum number of bits needed to represent a field is {max}, This integer type needs {got} bits"
+                    "Integer types can have at most {max} bits, this integer type has {got} bits"
                 ),
                 *span,
             ),
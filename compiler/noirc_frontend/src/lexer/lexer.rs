@@ -193,7 +193,18 @@ impl<'a> Lexer<'a> {
                 }
             }
             Token::Bang => self.single_double_peek_token('=', prev_token, Token::NotEqual),
-            Token::Assign => self.single_double_peek_token('=', prev_token, Token::Equal),
+            Token::Assign => {
+                let start = self.position;
+                if self.peek_char_is('=') {
+                    self.next_char();
+                    Ok(Token::Equal.into_span(start, start + 1))
+                } else if self.peek_char_is('>') {
+                    self.next_char();
+                    Ok(Token::FatArrow.into_span(start, start + 1))
+                } else {
+                    Ok(prev_token.into_single_span(start))
+                }
+            }
             Token::Minus => self.single_double_peek_token('>', prev_token, Token::Arrow),
             Token::Colon => self.single_double_peek_token(':', prev_token, Token::DoubleColon),
             Token::Slash => {
@@ -581,10 +592,10 @@ impl<'a> Iterator for Lexer<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::token::{FunctionAttribute, SecondaryAttribute, TestScope};
+    use crate::token::{FunctionAttribute, InlineType, SecondaryAttribute, TestScope};
     #[test]
     fn test_single_double_char() {
-        let input = "! != + ( ) { } [ ] | , ; : :: < <= > >= & - -> . .. % / * = == << >>";
+        let input = "! != + ( ) { } [ ] | , ; : :: < <= > >= & - -> . .. % / * = == => << >>";
 
         let expected = vec![
             Token::Bang,
@@ -615,6 +626,7 @@ mod tests {
             Token::Star,
             Token::Assign,
             Token::Equal,
+            Token::FatArrow,
             Token::ShiftLeft,
             Token::Greater,
             Token::Greater,
@@ -629,6 +641,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn integer_types_are_limited_by_the_field() {
+        let max_bits = crate::token::max_integer_bit_size();
+        let field_bits = FieldElement::max_num_bits();
+        // The product of two integers of the largest size still fits in the field
+        assert!(max_bits + (max_bits + 1) / 2 < field_bits);
+        if field_bits == 254 {
+            assert_eq!(max_bits, 128);
+        }
+
+        let input = format!("u{max_bits} i{max_bits} u{}", max_bits + 1);
+        let mut lexer = Lexer::new(&input);
+        assert_eq!(
+            lexer.next_token().unwrap().token(),
+            &Token::IntType(IntType::Unsigned(max_bits))
+        );
+        assert_eq!(lexer.next_token().unwrap().token(), &Token::IntType(IntType::Signed(max_bits)));
+        assert!(
+            matches!(lexer.next_token(), Err(LexerErrorKind::TooManyBits { max, .. }) if max == max_bits)
+        );
+    }
+
     #[test]
     fn invalid_attribute() {
         let input = "#";
@@ -681,6 +715,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn cfg_attribute() {
+        let input = r#"#[cfg(feature = "fast")] #[cfg(not(field = "bn254"))] #[cfg(backend = "acvm")]
+            #[cfg(fast)] #[cfg(os = "linux")]"#;
+        let mut lexer = Lexer::new(input);
+
+        let expected =
+            [("feature", "fast", false), ("field", "bn254", true), ("backend", "acvm", false)];
+        for (name, value, negated) in expected {
+            let token = lexer.next_token().unwrap();
+            assert_eq!(
+                token.token(),
+                &Token::Attribute(Attribute::Secondary(SecondaryAttribute::Cfg {
+                    name: name.to_string(),
+                    value: value.to_string(),
+                    negated
+                }))
+            );
+        }
+        assert!(lexer.next_token().is_err());
+        assert!(lexer.next_token().is_err());
+    }
+
     #[test]
     fn test_custom_gate_syntax() {
         let input = "#[foreign(sha256)]#[foreign(blake2s)]#[builtin(sum)]";
@@ -738,6 +795,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn constant_time_attribute() {
+        let input = r#"#[constant_time]"#;
+        let mut lexer = Lexer::new(input);
+
+        let token = lexer.next_token().unwrap();
+        assert_eq!(
+            token.token(),
+            &Token::Attribute(Attribute::Secondary(SecondaryAttribute::ConstantTime))
+        );
+    }
+
+    #[test]
+    fn max_iterations_attribute() {
+        let input = r#"#[max_iterations(16)]"#;
+        let mut lexer = Lexer::new(input);
+
+        let token = lexer.next_token().unwrap();
+        assert_eq!(
+            token.token(),
+            &Token::Attribute(Attribute::Secondary(SecondaryAttribute::MaxIterations(16)))
+        );
+
+        let mut lexer = Lexer::new(r#"#[max_iterations(N)]"#);
+        assert!(lexer.next_token().is_err());
+    }
+
+    #[test]
+    fn inline_attribute() {
+        let input = r#"#[inline(never)]"#;
+        let mut lexer = Lexer::new(input);
+
+        let token = lexer.next_token().unwrap();
+        assert_eq!(
+            token.token(),
+            &Token::Attribute(Attribute::Secondary(SecondaryAttribute::Inline(InlineType::Never)))
+        );
+
+        let mut lexer = Lexer::new(r#"#[inline(sometimes)]"#);
+        assert!(lexer.next_token().is_err());
+    }
+
     #[test]
     fn test_attribute_with_valid_scope() {
         let input = r#"#[test(should_fail)]"#;
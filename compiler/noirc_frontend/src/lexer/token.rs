@@ -70,6 +70,8 @@ pub enum Token {
     RightBracket,
     /// ->
     Arrow,
+    /// =>
+    FatArrow,
     /// |
     Pipe,
     /// #
@@ -191,6 +193,7 @@ impl fmt::Display for Token {
             Token::LeftBracket => write!(f, "["),
             Token::RightBracket => write!(f, "]"),
             Token::Arrow => write!(f, "->"),
+            Token::FatArrow => write!(f, "=>"),
             Token::Pipe => write!(f, "|"),
             Token::Pound => write!(f, "#"),
             Token::Comma => write!(f, ","),
@@ -287,6 +290,25 @@ impl Token {
     }
 }
 
+/// The largest number of bits of an integer type. Integers are held in the native field, and are
+/// limited to 128 bits so that each of them is also represented by a `u128` in the compiler.
+/// Smaller fields lower this limit further, see [max_integer_bit_size].
+pub const MAX_INTEGER_BIT_SIZE: u32 = 128;
+
+/// Returns the largest number of bits of an integer type in the native field, which is
+/// [MAX_INTEGER_BIT_SIZE] unless the field is too small for it.
+///
+/// Integer arithmetic must not wrap around the field modulus. The product of two integers of
+/// `bit_size` bits is only known to be less than `2^{bit_size + ceil(bit_size / 2)}` before it is
+/// range checked for overflow, so this is the largest bit size for which that bound fits in the field.
+pub fn max_integer_bit_size() -> u32 {
+    let field_bits = FieldElement::max_num_bits();
+    (1..=MAX_INTEGER_BIT_SIZE)
+        .rev()
+        .find(|bit_size| bit_size + (bit_size + 1) / 2 < field_bits)
+        .unwrap_or(1)
+}
+
 #[derive(PartialEq, Eq, Hash, Debug, Clone, PartialOrd, Ord)]
 pub enum IntType {
     Unsigned(u32), // u32 = Unsigned(32)
@@ -324,8 +346,7 @@ impl IntType {
             Err(_) => return Ok(None),
         };
 
-        let max_bits = FieldElement::max_num_bits() / 2;
-
+        let max_bits = max_integer_bit_size();
         if str_as_u32 > max_bits {
             return Err(LexerErrorKind::TooManyBits { span, max: max_bits, got: str_as_u32 });
         }
@@ -406,6 +427,31 @@ impl Attributes {
             .any(|attribute| attribute == &SecondaryAttribute::ContractLibraryMethod)
     }
 
+    /// Returns true if one of the secondary attributes is `constant_time`
+    pub fn is_constant_time(&self) -> bool {
+        self.secondary.iter().any(|attribute| attribute == &SecondaryAttribute::ConstantTime)
+    }
+
+    /// Returns the maximum number of iterations of the function's loops given by the
+    /// `max_iterations` attribute, if it has one
+    pub fn max_iterations(&self) -> Option<u64> {
+        self.secondary.iter().find_map(|attribute| match attribute {
+            SecondaryAttribute::MaxIterations(count) => Some(*count),
+            _ => None,
+        })
+    }
+
+    /// Returns how the function should be inlined, as given by the `inline` attribute
+    pub fn inline_type(&self) -> InlineType {
+        self.secondary
+            .iter()
+            .find_map(|attribute| match attribute {
+                SecondaryAttribute::Inline(inline_type) => Some(*inline_type),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
     pub fn is_test_function(&self) -> bool {
         matches!(self.function, Some(FunctionAttribute::Test(_)))
     }
@@ -504,11 +550,42 @@ impl Attribute {
                 validate(name)?;
                 Attribute::Secondary(SecondaryAttribute::Field(name.to_string()))
             }
+            ["max_iterations", count] => match count.trim().parse() {
+                Ok(count) => Attribute::Secondary(SecondaryAttribute::MaxIterations(count)),
+                Err(_) => {
+                    return Err(LexerErrorKind::MalformedFuncAttribute {
+                        span,
+                        found: word.to_owned(),
+                    })
+                }
+            },
+            ["inline", inline_type] => match InlineType::lookup_str(inline_type) {
+                Some(inline_type) => Attribute::Secondary(SecondaryAttribute::Inline(inline_type)),
+                None => {
+                    return Err(LexerErrorKind::MalformedFuncAttribute {
+                        span,
+                        found: word.to_owned(),
+                    })
+                }
+            },
             // Secondary attributes
+            ["cfg", condition] => {
+                let (name, value) = cfg_condition(condition).ok_or_else(|| {
+                    LexerErrorKind::MalformedFuncAttribute { span, found: word.to_owned() }
+                })?;
+                Attribute::Secondary(SecondaryAttribute::Cfg { name, value, negated: false })
+            }
+            ["cfg", "not", condition] => {
+                let (name, value) = cfg_condition(condition).ok_or_else(|| {
+                    LexerErrorKind::MalformedFuncAttribute { span, found: word.to_owned() }
+                })?;
+                Attribute::Secondary(SecondaryAttribute::Cfg { name, value, negated: true })
+            }
             ["deprecated"] => Attribute::Secondary(SecondaryAttribute::Deprecated(None)),
             ["contract_library_method"] => {
                 Attribute::Secondary(SecondaryAttribute::ContractLibraryMethod)
             }
+            ["constant_time"] => Attribute::Secondary(SecondaryAttribute::ConstantTime),
             ["event"] => Attribute::Secondary(SecondaryAttribute::Event),
             ["export"] => Attribute::Secondary(SecondaryAttribute::Export),
             ["deprecated", name] => {
@@ -533,6 +610,18 @@ impl Attribute {
     }
 }
 
+/// The options which the condition of a `cfg` attribute can test.
+const CFG_OPTIONS: [&str; 3] = ["feature", "field", "backend"];
+
+/// Splits the condition `option = "value"` of a `cfg` attribute into its option and value.
+fn cfg_condition(condition: &str) -> Option<(String, String)> {
+    let (name, value) = condition.split_once('=')?;
+    let name = name.trim();
+    let value = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+    (CFG_OPTIONS.contains(&name) && !value.is_empty())
+        .then(|| (name.to_string(), value.to_string()))
+}
+
 /// Primary Attributes are those which a function can only have one of.
 /// They change the FunctionKind and thus have direct impact on the IR output
 #[derive(PartialEq, Eq, Hash, Debug, Clone, PartialOrd, Ord)]
@@ -578,6 +667,38 @@ impl fmt::Display for FunctionAttribute {
     }
 }
 
+/// How calls to a function are inlined into its callers.
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy, PartialOrd, Ord, Default)]
+pub enum InlineType {
+    /// Calls are inlined if the compiler deems the function small enough.
+    #[default]
+    Default,
+    /// `#[inline(always)]`: Calls are inlined wherever possible.
+    Always,
+    /// `#[inline(never)]`: Calls are never inlined where the function can be called instead.
+    Never,
+}
+
+impl InlineType {
+    fn lookup_str(string: &str) -> Option<InlineType> {
+        match string.trim() {
+            "always" => Some(InlineType::Always),
+            "never" => Some(InlineType::Never),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for InlineType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InlineType::Default => write!(f, "default"),
+            InlineType::Always => write!(f, "always"),
+            InlineType::Never => write!(f, "never"),
+        }
+    }
+}
+
 /// Secondary attributes are those which a function can have many of.
 /// They are not able to change the `FunctionKind` and thus do not have direct impact on the IR output
 /// They are often consumed by libraries or used as notices for the developer
@@ -588,6 +709,19 @@ pub enum SecondaryAttribute {
     // is a helper method for a contract and should not be seen as
     // the entry point.
     ContractLibraryMethod,
+    // This attribute requests that the compiler checks that the
+    // control flow and memory accesses of unconstrained code do not
+    // depend on the function's inputs.
+    ConstantTime,
+    // This attribute bounds the number of iterations of the function's loops
+    // whose range isn't known at compile-time.
+    MaxIterations(u64),
+    // This attribute requests that calls to the function are always or never inlined.
+    Inline(InlineType),
+    // This attribute removes the item it's attached to unless the crate is compiled with the
+    // feature enabled, for the field or for the backend given by its value, or the opposite if
+    // negated.
+    Cfg { name: String, value: String, negated: bool },
     Event,
     Export,
     Field(String),
@@ -603,6 +737,15 @@ impl fmt::Display for SecondaryAttribute {
             }
             SecondaryAttribute::Custom(ref k) => write!(f, "#[{k}]"),
             SecondaryAttribute::ContractLibraryMethod => write!(f, "#[contract_library_method]"),
+            SecondaryAttribute::ConstantTime => write!(f, "#[constant_time]"),
+            SecondaryAttribute::MaxIterations(count) => write!(f, "#[max_iterations({count})]"),
+            SecondaryAttribute::Inline(inline_type) => write!(f, "#[inline({inline_type})]"),
+            SecondaryAttribute::Cfg { name, value, negated: false } => {
+                write!(f, r#"#[cfg({name} = "{value}")]"#)
+            }
+            SecondaryAttribute::Cfg { name, value, negated: true } => {
+                write!(f, r#"#[cfg(not({name} = "{value}"))]"#)
+            }
             SecondaryAttribute::Event => write!(f, "#[event]"),
             SecondaryAttribute::Export => write!(f, "#[export]"),
             SecondaryAttribute::Field(ref k) => write!(f, "#[field({k})]"),
@@ -627,7 +770,9 @@ impl AsRef<str> for SecondaryAttribute {
             SecondaryAttribute::Deprecated(Some(string)) => string,
             SecondaryAttribute::Deprecated(None) => "",
             SecondaryAttribute::Custom(string) | SecondaryAttribute::Field(string) => string,
-            SecondaryAttribute::ContractLibraryMethod => "",
+            SecondaryAttribute::Cfg { value, .. } => value,
+            SecondaryAttribute::ContractLibraryMethod | SecondaryAttribute::ConstantTime => "",
+            SecondaryAttribute::MaxIterations(_) | SecondaryAttribute::Inline(_) => "",
             SecondaryAttribute::Event | SecondaryAttribute::Export => "",
         }
     }
@@ -642,15 +787,18 @@ pub enum Keyword {
     Assert,
     AssertEq,
     Bool,
+    Break,
     CallData,
     Char,
     CompTime,
     Constrain,
+    Continue,
     Contract,
     Crate,
     Dep,
     Distinct,
     Else,
+    Enum,
     Field,
     Fn,
     For,
@@ -661,6 +809,8 @@ pub enum Keyword {
     In,
     Internal,
     Let,
+    Loop,
+    Match,
     Mod,
     Mut,
     Open,
@@ -684,15 +834,18 @@ impl fmt::Display for Keyword {
             Keyword::Assert => write!(f, "assert"),
             Keyword::AssertEq => write!(f, "assert_eq"),
             Keyword::Bool => write!(f, "bool"),
+            Keyword::Break => write!(f, "break"),
             Keyword::Char => write!(f, "char"),
             Keyword::CallData => write!(f, "call_data"),
             Keyword::CompTime => write!(f, "comptime"),
             Keyword::Constrain => write!(f, "constrain"),
+            Keyword::Continue => write!(f, "continue"),
             Keyword::Contract => write!(f, "contract"),
             Keyword::Crate => write!(f, "crate"),
             Keyword::Dep => write!(f, "dep"),
             Keyword::Distinct => write!(f, "distinct"),
             Keyword::Else => write!(f, "else"),
+            Keyword::Enum => write!(f, "enum"),
             Keyword::Field => write!(f, "Field"),
             Keyword::Fn => write!(f, "fn"),
             Keyword::For => write!(f, "for"),
@@ -703,6 +856,8 @@ impl fmt::Display for Keyword {
             Keyword::In => write!(f, "in"),
             Keyword::Internal => write!(f, "internal"),
             Keyword::Let => write!(f, "let"),
+            Keyword::Loop => write!(f, "loop"),
+            Keyword::Match => write!(f, "match"),
             Keyword::Mod => write!(f, "mod"),
             Keyword::Mut => write!(f, "mut"),
             Keyword::Open => write!(f, "open"),
@@ -729,15 +884,18 @@ impl Keyword {
             "assert" => Keyword::Assert,
             "assert_eq" => Keyword::AssertEq,
             "bool" => Keyword::Bool,
+            "break" => Keyword::Break,
             "call_data" => Keyword::CallData,
             "char" => Keyword::Char,
             "comptime" => Keyword::CompTime,
             "constrain" => Keyword::Constrain,
+            "continue" => Keyword::Continue,
             "contract" => Keyword::Contract,
             "crate" => Keyword::Crate,
             "dep" => Keyword::Dep,
             "distinct" => Keyword::Distinct,
             "else" => Keyword::Else,
+            "enum" => Keyword::Enum,
             "Field" => Keyword::Field,
             "fn" => Keyword::Fn,
             "for" => Keyword::For,
@@ -748,6 +906,8 @@ impl Keyword {
             "in" => Keyword::In,
             "internal" => Keyword::Internal,
             "let" => Keyword::Let,
+            "loop" => Keyword::Loop,
+            "match" => Keyword::Match,
             "mod" => Keyword::Mod,
             "mut" => Keyword::Mut,
             "open" => Keyword::Open,
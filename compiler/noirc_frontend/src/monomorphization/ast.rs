@@ -3,7 +3,8 @@ use iter_extended::vecmap;
 use noirc_errors::Location;
 
 use crate::{
-    hir_def::function::FunctionSignature, BinaryOpKind, Distinctness, Signedness, Visibility,
+    hir_def::function::FunctionSignature, token::InlineType, BinaryOpKind, Distinctness,
+    Signedness, Visibility,
 };
 
 /// The monomorphized AST is expression-based, all statements are also
@@ -26,14 +27,22 @@ pub enum Expression {
     Index(Index),
     Cast(Cast),
     For(For),
+    /// A loop without a range, only used in unconstrained functions
+    Loop(Box<Expression>),
     If(If),
     Tuple(Vec<Expression>),
     ExtractTupleField(Box<Expression>, usize),
     Call(Call),
     Let(Let),
-    Constrain(Box<Expression>, Location, Option<String>),
+    /// A constraint along with its message, which is a string or format string literal. A format
+    /// string is accompanied by its serialized `PrintableType`.
+    Constrain(Box<Expression>, Location, Option<Box<(Expression, Option<String>)>>),
     Assign(Assign),
     Semi(Box<Expression>),
+    /// Only used in unconstrained functions. Loops in constrained functions are lowered
+    /// to track whether they have broken out of the loop instead.
+    Break,
+    Continue,
 }
 
 /// A definition is either a local (variable), function, or is a built-in
@@ -204,6 +213,13 @@ pub struct Function {
 
     pub return_type: Type,
     pub unconstrained: bool,
+    /// True if the function is annotated with `#[constant_time]`
+    pub constant_time: bool,
+    /// The bound on the iterations of loops whose range isn't known at compile-time,
+    /// from the `#[max_iterations(N)]` attribute
+    pub max_iterations: Option<u64>,
+    /// How calls to the function are inlined, from the `#[inline(...)]` attribute
+    pub inline_type: InlineType,
 }
 
 /// Compared to hir_def::types::Type, this monomorphized Type has:
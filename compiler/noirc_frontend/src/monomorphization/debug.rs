@@ -0,0 +1,146 @@
+//! Instrumentation of programs which are compiled to be debugged.
+//!
+//! Variables are compiled away to witnesses and Brillig memory, so in order for the debugger to
+//! show them each assignment to a variable is followed by a call passing its new value to the
+//! [`DEBUG_VAR_ASSIGN_ORACLE`]. Only the functions of the crates being debugged are instrumented.
+use iter_extended::vecmap;
+use noirc_errors::Location;
+use noirc_printable_type::PrintableType;
+
+use crate::{hir_def::expr::HirIdent, token::InlineType, Type, TypeBinding, TypeVariableKind};
+
+use super::{
+    ast::{self, Definition},
+    HirType, Monomorphizer,
+};
+
+/// The oracle which is called with the name of a variable, its new value and the serialized
+/// [`PrintableType`] of the value each time a variable is assigned to. The value and its type are
+/// followed by `false`, in the same way as the arguments to the `print` oracle.
+pub const DEBUG_VAR_ASSIGN_ORACLE: &str = "__debug_var_assign";
+
+impl<'interner> Monomorphizer<'interner> {
+    /// Returns a call reporting the value of the local variable `ident` to the debugger, or `None`
+    /// if the current function isn't instrumented or the variable's value can't be reported.
+    pub(super) fn debug_var_assign(&mut self, ident: &HirIdent) -> Option<ast::Expression> {
+        if !self.in_instrumented_function {
+            return None;
+        }
+
+        let typ = self.interner.id_type(ident.id).follow_bindings();
+        if !is_reportable(&typ) {
+            return None;
+        }
+        let variable = self.local_ident(ident)?;
+
+        let printable_type: PrintableType = (&typ).into();
+        let printable_type = serde_json::to_string(&printable_type)
+            .expect("ICE: expected PrintableType to serialize");
+
+        let parameter_types = vec![
+            ast::Type::String(variable.name.len() as u64),
+            variable.typ.clone(),
+            ast::Type::String(printable_type.len() as u64),
+        ];
+        let function = self.debug_var_assign_function(&parameter_types, ident.location);
+        let func = Box::new(ast::Expression::Ident(ast::Ident {
+            location: None,
+            mutable: false,
+            definition: Definition::Function(function),
+            name: DEBUG_VAR_ASSIGN_ORACLE.to_owned(),
+            typ: function_type(parameter_types),
+        }));
+
+        let arguments = vec![
+            ast::Expression::Literal(ast::Literal::Str(variable.name.clone())),
+            ast::Expression::Ident(variable),
+            ast::Expression::Literal(ast::Literal::Str(printable_type)),
+        ];
+        let return_type = ast::Type::Unit;
+        Some(ast::Expression::Call(ast::Call {
+            func,
+            arguments,
+            return_type,
+            location: ident.location,
+        }))
+    }
+
+    /// Creates an unconstrained function passing its arguments on to the oracle, since oracles
+    /// can't be called from constrained code. A function is created for each assignment rather
+    /// than for each type of variable so that the oracle call is located at the assignment.
+    fn debug_var_assign_function(
+        &mut self,
+        parameter_types: &[ast::Type],
+        location: Location,
+    ) -> ast::FuncId {
+        let names = ["name", "value", "printable_type"];
+        let parameters = vecmap(names.iter().zip(parameter_types), |(name, typ)| {
+            (self.next_local_id(), false, name.to_string(), typ.clone())
+        });
+
+        let mut arguments = vecmap(&parameters, |(id, mutable, name, typ)| {
+            ast::Expression::Ident(ast::Ident {
+                location: None,
+                mutable: *mutable,
+                definition: Definition::Local(*id),
+                name: name.clone(),
+                typ: typ.clone(),
+            })
+        });
+        arguments.push(ast::Expression::Literal(ast::Literal::Bool(false)));
+
+        let mut oracle_parameter_types = parameter_types.to_vec();
+        oracle_parameter_types.push(ast::Type::Bool);
+        let oracle = ast::Expression::Ident(ast::Ident {
+            location: None,
+            mutable: false,
+            definition: Definition::Oracle(DEBUG_VAR_ASSIGN_ORACLE.to_owned()),
+            name: DEBUG_VAR_ASSIGN_ORACLE.to_owned(),
+            typ: function_type(oracle_parameter_types),
+        });
+        let body = ast::Expression::Call(ast::Call {
+            func: Box::new(oracle),
+            arguments,
+            return_type: ast::Type::Unit,
+            location,
+        });
+
+        let id = self.next_function_id();
+        let function = ast::Function {
+            id,
+            name: DEBUG_VAR_ASSIGN_ORACLE.to_owned(),
+            parameters,
+            body,
+            return_type: ast::Type::Unit,
+            unconstrained: true,
+            constant_time: false,
+            max_iterations: None,
+            inline_type: InlineType::Default,
+        };
+        self.push_function(id, function);
+        id
+    }
+}
+
+fn function_type(parameter_types: Vec<ast::Type>) -> ast::Type {
+    ast::Type::Function(parameter_types, Box::new(ast::Type::Unit), Box::new(ast::Type::Unit))
+}
+
+/// Values can only be reported if they can be passed from ACIR into Brillig, which excludes
+/// slices, references and functions along with any value containing one of them.
+fn is_reportable(typ: &HirType) -> bool {
+    match typ {
+        Type::FieldElement | Type::Integer(..) | Type::Bool => true,
+        Type::String(size) => size.evaluate_to_u64().is_some(),
+        Type::Array(size, element) => size.evaluate_to_u64().is_some() && is_reportable(element),
+        Type::Tuple(types) => types.iter().all(is_reportable),
+        Type::Struct(definition, generics) => {
+            definition.borrow().get_fields(generics).iter().all(|(_, typ)| is_reportable(typ))
+        }
+        Type::TypeVariable(binding, TypeVariableKind::IntegerOrField) => match &*binding.borrow() {
+            TypeBinding::Bound(typ) => is_reportable(typ),
+            TypeBinding::Unbound(_) => true,
+        },
+        _ => false,
+    }
+}
@@ -0,0 +1,34 @@
+use noirc_errors::Location;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum MonomorphizationError {
+    #[error("Numeric bound `{bound}` of `{function}` is not satisfied")]
+    UnsatisfiedNumericBound {
+        /// The name of the function declaring the bound.
+        function: String,
+        /// The bound with each of the generics it uses replaced by their value.
+        bound: String,
+        /// Where the function is instantiated with the generics which violate the bound.
+        location: Location,
+    },
+    #[error("Constant `{value}` given to a generic of `{function}` is not a `{expected}`")]
+    InvalidValueGenericArgument {
+        /// The name of the function declaring the generic.
+        function: String,
+        /// The constant inferred for the generic.
+        value: String,
+        /// The type the generic is declared with.
+        expected: String,
+        /// Where the function is instantiated with the constant.
+        location: Location,
+    },
+    #[error("Array length `{length}` does not evaluate to a valid length")]
+    InvalidArrayLength {
+        /// The length with each of the generics it uses replaced by their value.
+        length: String,
+        /// The expression whose type uses the length, or else where the function declaring it is
+        /// instantiated with the generics which make it overflow or divide by zero.
+        location: Location,
+    },
+}
@@ -13,6 +13,7 @@ use iter_extended::{btree_map, vecmap};
 use noirc_errors::Location;
 use noirc_printable_type::PrintableType;
 use std::{
+    cell::RefCell,
     collections::{BTreeMap, HashMap, VecDeque},
     unreachable,
 };
@@ -25,21 +26,39 @@ use crate::{
         types,
     },
     node_interner::{self, DefinitionKind, NodeInterner, StmtId, TraitImplKind, TraitMethodId},
-    token::FunctionAttribute,
-    ContractFunctionType, FunctionKind, Type, TypeBinding, TypeBindings, TypeVariable,
-    TypeVariableKind, UnaryOp, Visibility,
+    token::{FunctionAttribute, InlineType},
+    BinaryOpKind, ContractFunctionType, FunctionKind, Type, TypeBinding, TypeBindings,
+    TypeLevelValue, TypeVariable, TypeVariableKind, UnaryOp, Visibility,
 };
 
 use self::ast::{Definition, FuncId, Function, LocalId, Program};
 
 pub mod ast;
+mod debug;
+mod errors;
 pub mod printer;
 
+pub use debug::DEBUG_VAR_ASSIGN_ORACLE;
+pub use errors::MonomorphizationError;
+
 struct LambdaContext {
     env_ident: ast::Ident,
     captures: Vec<HirCapturedVar>,
 }
 
+/// Constrained functions cannot jump out of a loop since loops are always unrolled, so a loop
+/// containing a `break` or `continue` instead keeps track of which of its iterations are still
+/// executing with these flags. See [`Monomorphizer::predicated_loop`].
+struct LoopFlags {
+    /// Set once the loop executes a `break`, skipping each of the iterations after it.
+    broken: LocalId,
+    /// Cleared once the current iteration executes a `break` or `continue`, skipping the rest
+    /// of the iteration.
+    live: LocalId,
+    /// The number of `break` and `continue` statements found within the loop so far.
+    exits: usize,
+}
+
 /// The context struct for the monomorphization pass.
 ///
 /// This struct holds the FIFO queue of functions to monomorphize, which is added to
@@ -59,8 +78,9 @@ struct Monomorphizer<'interner> {
     locals: HashMap<node_interner::DefinitionId, LocalId>,
 
     /// Queue of functions to monomorphize next each item in the queue is a tuple of:
-    /// (old_id, new_monomorphized_id, any type bindings to apply, the trait method if old_id is from a trait impl)
-    queue: VecDeque<(node_interner::FuncId, FuncId, TypeBindings, Option<TraitMethodId>)>,
+    /// (old_id, new_monomorphized_id, any type bindings to apply, the trait method if old_id is from a trait impl,
+    /// the location the function is instantiated at)
+    queue: VecDeque<(node_interner::FuncId, FuncId, TypeBindings, Option<TraitMethodId>, Location)>,
 
     /// When a function finishes being monomorphized, the monomorphized ast::Function is
     /// stored here along with its FuncId.
@@ -77,6 +97,30 @@ struct Monomorphizer<'interner> {
     is_range_loop: bool,
 
     return_location: Option<Location>,
+
+    /// True while monomorphizing the body of an unconstrained function, whose loops can use
+    /// `break` and `continue` directly.
+    in_unconstrained_function: bool,
+
+    /// One entry for each loop surrounding the current expression, innermost last. Loops in
+    /// unconstrained functions don't need any flags.
+    loops: Vec<Option<LoopFlags>>,
+
+    /// The implementation in Noir of each black box function which the backend doesn't support,
+    /// keyed by the name of the black box function.
+    black_box_fallbacks: HashMap<String, node_interner::FuncId>,
+
+    /// The first array length of the current function which failed to evaluate, along with the
+    /// location of the innermost expression using it. It is reported once the function is
+    /// monomorphized since types are converted without a location.
+    invalid_array_length: RefCell<Option<(String, Option<Location>)>>,
+
+    /// True if the functions of the program's own crates report the values assigned to their
+    /// variables to the debugger, see the `debug` module.
+    instrument_debug: bool,
+
+    /// True while monomorphizing the body of a function which reports its variables.
+    in_instrumented_function: bool,
 }
 
 type HirType = crate::Type;
@@ -92,36 +136,66 @@ type HirType = crate::Type;
 /// Note that there is no requirement on the `main` function that can be passed into
 /// this function. Typically, this is the function named "main" in the source project,
 /// but it can also be, for example, an arbitrary test function for running `nargo test`.
+///
+/// Returns an error if a function is instantiated with numeric generics which don't satisfy
+/// the numeric bounds of its where clause.
 #[tracing::instrument(level = "trace", skip(main, interner))]
-pub fn monomorphize(main: node_interner::FuncId, interner: &NodeInterner) -> Program {
-    let mut monomorphizer = Monomorphizer::new(interner);
-    let function_sig = monomorphizer.compile_main(main);
+pub fn monomorphize(
+    main: node_interner::FuncId,
+    interner: &NodeInterner,
+) -> Result<Program, MonomorphizationError> {
+    monomorphize_with_fallbacks(main, interner, HashMap::new(), false)
+}
+
+/// Monomorphizes the program like [monomorphize], except that calls to the black box functions
+/// in `black_box_fallbacks` from constrained code are replaced with calls to the given
+/// implementation in Noir of the black box function.
+///
+/// If `instrument_debug` is set, the functions of every crate other than the standard library
+/// pass the values assigned to their variables to the [DEBUG_VAR_ASSIGN_ORACLE].
+#[tracing::instrument(level = "trace", skip(main, interner, black_box_fallbacks))]
+pub fn monomorphize_with_fallbacks(
+    main: node_interner::FuncId,
+    interner: &NodeInterner,
+    black_box_fallbacks: HashMap<String, node_interner::FuncId>,
+    instrument_debug: bool,
+) -> Result<Program, MonomorphizationError> {
+    let mut monomorphizer = Monomorphizer::new(interner, black_box_fallbacks, instrument_debug);
+    let function_sig = monomorphizer.compile_main(main)?;
 
     while !monomorphizer.queue.is_empty() {
-        let (next_fn_id, new_id, bindings, trait_method) = monomorphizer.queue.pop_front().unwrap();
+        let (next_fn_id, new_id, bindings, trait_method, location) =
+            monomorphizer.queue.pop_front().unwrap();
         monomorphizer.locals.clear();
 
         perform_instantiation_bindings(&bindings);
         let impl_bindings = monomorphizer.perform_impl_bindings(trait_method, next_fn_id);
-        monomorphizer.function(next_fn_id, new_id);
+        let result = monomorphizer.function(next_fn_id, new_id, location);
+        // The type variables are shared with the interner, which may be used to compile other
+        // functions afterwards, so they're unbound before returning any error.
         undo_instantiation_bindings(impl_bindings);
         undo_instantiation_bindings(bindings);
+        result?;
     }
 
     let functions = vecmap(monomorphizer.finished_functions, |(_, f)| f);
     let meta = interner.function_meta(&main);
 
-    Program::new(
+    Ok(Program::new(
         functions,
         function_sig,
         meta.return_distinctness,
         monomorphizer.return_location,
         meta.return_visibility,
-    )
+    ))
 }
 
 impl<'interner> Monomorphizer<'interner> {
-    fn new(interner: &'interner NodeInterner) -> Self {
+    fn new(
+        interner: &'interner NodeInterner,
+        black_box_fallbacks: HashMap<String, node_interner::FuncId>,
+        instrument_debug: bool,
+    ) -> Self {
         Monomorphizer {
             globals: HashMap::new(),
             locals: HashMap::new(),
@@ -133,6 +207,12 @@ impl<'interner> Monomorphizer<'interner> {
             lambda_envs_stack: Vec::new(),
             is_range_loop: false,
             return_location: None,
+            in_unconstrained_function: false,
+            loops: Vec::new(),
+            black_box_fallbacks,
+            invalid_array_length: RefCell::new(None),
+            instrument_debug,
+            in_instrumented_function: false,
         }
     }
 
@@ -171,7 +251,15 @@ impl<'interner> Monomorphizer<'interner> {
                         let opcode = attribute.foreign().expect(
                             "ice: function marked as foreign, but attribute kind does not match this",
                         );
-                        Definition::LowLevel(opcode)
+                        // Black box functions called from unconstrained code are executed by the
+                        // ACVM rather than proven by the backend, so they are always available.
+                        match self.black_box_fallbacks.get(&opcode) {
+                            Some(fallback) if !self.in_unconstrained_function => {
+                                let location = self.interner.expr_location(&expr_id);
+                                Definition::Function(self.queue_fallback(*fallback, typ, location))
+                            }
+                            _ => Definition::LowLevel(opcode),
+                        }
                     }
                     FunctionKind::Builtin => {
                         let attribute = attributes.function.clone().expect("all low level functions must contain a function  attribute which contains the opcode which it links to");
@@ -209,10 +297,14 @@ impl<'interner> Monomorphizer<'interner> {
         self.globals.entry(id).or_default().insert(typ, new_id);
     }
 
-    fn compile_main(&mut self, main_id: node_interner::FuncId) -> FunctionSignature {
+    fn compile_main(
+        &mut self,
+        main_id: node_interner::FuncId,
+    ) -> Result<FunctionSignature, MonomorphizationError> {
         let new_main_id = self.next_function_id();
         assert_eq!(new_main_id, Program::main_id());
-        self.function(main_id, new_main_id);
+        let location = self.interner.function_meta(&main_id).location;
+        self.function(main_id, new_main_id, location)?;
         self.return_location =
             self.interner.function(&main_id).block(self.interner).statements().last().and_then(
                 |x| match self.interner.statement(x) {
@@ -221,10 +313,17 @@ impl<'interner> Monomorphizer<'interner> {
                 },
             );
         let main_meta = self.interner.function_meta(&main_id);
-        main_meta.function_signature()
+        Ok(main_meta.function_signature())
     }
 
-    fn function(&mut self, f: node_interner::FuncId, id: FuncId) {
+    /// Monomorphizes `f` with the type bindings currently applied, after checking that they
+    /// satisfy its numeric bounds. `location` is where `f` is instantiated with these bindings.
+    fn function(
+        &mut self,
+        f: node_interner::FuncId,
+        id: FuncId,
+        location: Location,
+    ) -> Result<(), MonomorphizationError> {
         if let Some((self_type, trait_id)) = self.interner.get_function_trait(&f) {
             let the_trait = self.interner.get_trait(trait_id);
             the_trait.self_type_typevar.force_bind(self_type);
@@ -234,6 +333,23 @@ impl<'interner> Monomorphizer<'interner> {
         let modifiers = self.interner.function_modifiers(&f);
         let name = self.interner.function_name(&f).to_owned();
 
+        if let Some(bound) = meta.numeric_bounds.iter().find(|bound| !bound.is_satisfied()) {
+            return Err(MonomorphizationError::UnsatisfiedNumericBound {
+                function: name,
+                bound: bound.to_string(),
+                location,
+            });
+        }
+
+        if let Some((value, expected)) = self.invalid_value_generic(&meta.typ) {
+            return Err(MonomorphizationError::InvalidValueGenericArgument {
+                function: name,
+                value: value.to_string(),
+                expected: expected.to_string(),
+                location,
+            });
+        }
+
         let body_expr_id = *self.interner.function(&f).as_expr();
         let body_return_type = self.interner.id_type(body_expr_id);
         let return_type = self.convert_type(match meta.return_type() {
@@ -243,12 +359,60 @@ impl<'interner> Monomorphizer<'interner> {
 
         let parameters = self.parameters(&meta.parameters);
 
-        let body = self.expr(body_expr_id);
         let unconstrained = modifiers.is_unconstrained
             || matches!(modifiers.contract_function_type, Some(ContractFunctionType::Open));
 
-        let function = ast::Function { id, name, parameters, body, return_type, unconstrained };
+        self.in_unconstrained_function = unconstrained;
+        self.in_instrumented_function =
+            self.instrument_debug && !self.interner.function_module(f).krate.is_stdlib();
+        let body = self.expr(body_expr_id);
+
+        // The parameters are reported before the body so that they can be watched from the start
+        // of the function. Parameters which are destructured by a pattern aren't reported.
+        let mut parameter_reports: Vec<_> = meta
+            .parameters
+            .0
+            .iter()
+            .filter_map(|(pattern, _, _)| match pattern {
+                HirPattern::Identifier(ident) => self.debug_var_assign(ident),
+                HirPattern::Mutable(pattern, _) => match pattern.as_ref() {
+                    HirPattern::Identifier(ident) => self.debug_var_assign(ident),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect();
+        let body = if parameter_reports.is_empty() {
+            body
+        } else {
+            parameter_reports.push(body);
+            ast::Expression::Block(parameter_reports)
+        };
+
+        // A length outside of any expression, such as in the parameters of the function, is
+        // invalid because of the generics it is instantiated with.
+        if let Some((length, length_location)) = self.invalid_array_length.take() {
+            let location = length_location.unwrap_or(location);
+            return Err(MonomorphizationError::InvalidArrayLength { length, location });
+        }
+
+        let constant_time = modifiers.attributes.is_constant_time();
+        let max_iterations = modifiers.attributes.max_iterations();
+        let inline_type = modifiers.attributes.inline_type();
+
+        let function = ast::Function {
+            id,
+            name,
+            parameters,
+            body,
+            return_type,
+            unconstrained,
+            constant_time,
+            max_iterations,
+            inline_type,
+        };
         self.push_function(id, function);
+        Ok(())
     }
 
     fn push_function(&mut self, id: FuncId, function: ast::Function) {
@@ -309,6 +473,14 @@ impl<'interner> Monomorphizer<'interner> {
     }
 
     fn expr(&mut self, expr: node_interner::ExprId) -> ast::Expression {
+        let expression = self.convert_expr(expr);
+        if let Some((_, location @ None)) = self.invalid_array_length.get_mut() {
+            *location = Some(self.interner.expr_location(&expr));
+        }
+        expression
+    }
+
+    fn convert_expr(&mut self, expr: node_interner::ExprId) -> ast::Expression {
         use ast::Expression::Literal;
         use ast::Literal::*;
 
@@ -331,8 +503,9 @@ impl<'interner> Monomorphizer<'interner> {
                     match typ {
                         ast::Type::Field => Literal(Integer(-value, typ, location)),
                         ast::Type::Integer(_, bit_size) => {
-                            let base = 1_u128 << bit_size;
-                            Literal(Integer(FieldElement::from(base) - value, typ, location))
+                            let base = FieldElement::from(2_u128)
+                                .pow(&FieldElement::from(u128::from(bit_size)));
+                            Literal(Integer(base - value, typ, location))
                         }
                         _ => unreachable!("Integer literal must be numeric"),
                     }
@@ -352,13 +525,24 @@ impl<'interner> Monomorphizer<'interner> {
             HirExpression::Block(block) => self.block(block.0),
 
             HirExpression::Prefix(prefix) => {
+                let rhs = self.expr(prefix.rhs);
                 let location = self.interner.expr_location(&expr);
-                ast::Expression::Unary(ast::Unary {
-                    operator: prefix.operator,
-                    rhs: Box::new(self.expr(prefix.rhs)),
-                    result_type: self.convert_type(&self.interner.id_type(expr)),
-                    location,
-                })
+
+                if self.interner.get_selected_impl_for_expression(expr).is_some() {
+                    // If an impl was selected for this prefix operator, replace it
+                    // with a method call to the appropriate trait impl method.
+                    let method =
+                        prefix.trait_method_id.expect("ICE: missing operator trait method");
+                    let args = vec![self.interner.id_type(prefix.rhs)];
+                    self.operator_impl_call(expr, method, args, vec![rhs], location)
+                } else {
+                    ast::Expression::Unary(ast::Unary {
+                        operator: prefix.operator,
+                        rhs: Box::new(rhs),
+                        result_type: self.convert_type(&self.interner.id_type(expr)),
+                        location,
+                    })
+                }
             }
 
             HirExpression::Infix(infix) => {
@@ -423,11 +607,14 @@ impl<'interner> Monomorphizer<'interner> {
                 })
             }
 
+            HirExpression::Match(match_expr) => self.match_expr(match_expr, expr),
+
             HirExpression::Tuple(fields) => {
                 let fields = vecmap(fields, |id| self.expr(id));
                 ast::Expression::Tuple(fields)
             }
             HirExpression::Constructor(constructor) => self.constructor(constructor, expr),
+            HirExpression::EnumConstructor(constructor) => self.enum_constructor(constructor, expr),
 
             HirExpression::Lambda(lambda) => self.lambda(lambda, expr),
 
@@ -456,8 +643,8 @@ impl<'interner> Monomorphizer<'interner> {
     ) -> ast::Expression {
         let typ = self.convert_type(&self.interner.id_type(array));
 
-        let length = length
-            .evaluate_to_u64()
+        let length = self
+            .evaluate_array_length(&length)
             .expect("Length of array is unknown when evaluating numeric generic");
 
         let contents = vecmap(0..length, |_| self.expr(repeated_element));
@@ -467,19 +654,58 @@ impl<'interner> Monomorphizer<'interner> {
     fn index(&mut self, id: node_interner::ExprId, index: HirIndexExpression) -> ast::Expression {
         let element_type = self.convert_type(&self.interner.id_type(id));
 
-        let collection = Box::new(self.expr(index.collection));
-        let index = Box::new(self.expr(index.index));
+        let collection = self.expr(index.collection);
+        let index_expr = self.expr(index.index);
         let location = self.interner.expr_location(&id);
+
+        if self.interner.get_selected_impl_for_expression(id).is_some() {
+            // The collection is not an array, so this indexes with a call to its `Index` impl
+            let method = index.trait_method_id.expect("ICE: missing operator trait method");
+            let args =
+                vec![self.interner.id_type(index.collection), self.interner.id_type(index.index)];
+            return self.operator_impl_call(
+                id,
+                method,
+                args,
+                vec![collection, index_expr],
+                location,
+            );
+        }
+
+        let collection = Box::new(collection);
+        let index = Box::new(index_expr);
         ast::Expression::Index(ast::Index { collection, index, element_type, location })
     }
 
+    /// Lowers an overloaded prefix or index operator to a call to the selected impl's method.
+    fn operator_impl_call(
+        &mut self,
+        expr: node_interner::ExprId,
+        method: TraitMethodId,
+        argument_types: Vec<Type>,
+        arguments: Vec<ast::Expression>,
+        location: Location,
+    ) -> ast::Expression {
+        let return_type = self.interner.id_type(expr);
+        let env = Box::new(Type::Unit);
+        let function_type = Type::Function(argument_types, Box::new(return_type.clone()), env);
+
+        let func = Box::new(self.resolve_trait_method_reference(expr, function_type, method));
+        let return_type = self.convert_type(&return_type);
+        ast::Expression::Call(ast::Call { func, arguments, return_type, location })
+    }
+
     fn statement(&mut self, id: StmtId) -> ast::Expression {
         match self.interner.statement(&id) {
             HirStatement::Let(let_statement) => self.let_statement(let_statement),
             HirStatement::Constrain(constrain) => {
                 let expr = self.expr(constrain.0);
                 let location = self.interner.expr_location(&constrain.0);
-                ast::Expression::Constrain(Box::new(expr), location, constrain.2)
+                let assert_message = constrain.2.map(|message| {
+                    let printable_type = self.assert_message_printable_type(message);
+                    Box::new((self.expr(message), printable_type))
+                });
+                ast::Expression::Constrain(Box::new(expr), location, assert_message)
             }
             HirStatement::Assign(assign) => self.assign(assign),
             HirStatement::For(for_loop) => {
@@ -490,9 +716,16 @@ impl<'interner> Monomorphizer<'interner> {
                 let index_variable = self.next_local_id();
                 self.define_local(for_loop.identifier.id, index_variable);
 
+                let flags = (!self.in_unconstrained_function).then(|| LoopFlags {
+                    broken: self.next_local_id(),
+                    live: self.next_local_id(),
+                    exits: 0,
+                });
+                self.loops.push(flags);
                 let block = Box::new(self.expr(for_loop.block));
+                let flags = self.loops.pop().expect("Expected the flags of the current loop");
 
-                ast::Expression::For(ast::For {
+                let for_loop = ast::For {
                     index_variable,
                     index_name: self.interner.definition_name(for_loop.identifier.id).to_owned(),
                     index_type: self.convert_type(&self.interner.id_type(for_loop.start_range)),
@@ -501,14 +734,62 @@ impl<'interner> Monomorphizer<'interner> {
                     start_range_location: self.interner.expr_location(&for_loop.start_range),
                     end_range_location: self.interner.expr_location(&for_loop.end_range),
                     block,
-                })
+                };
+
+                match flags {
+                    Some(flags) if flags.exits > 0 => self.predicated_loop(for_loop, &flags),
+                    _ => ast::Expression::For(for_loop),
+                }
             }
+            HirStatement::Loop(block) => {
+                // Loops without a range are only allowed in unconstrained functions, which can
+                // break out of them directly.
+                self.loops.push(None);
+                let block = Box::new(self.expr(block));
+                self.loops.pop();
+                ast::Expression::Loop(block)
+            }
+            HirStatement::Break => self.loop_exit(true),
+            HirStatement::Continue => self.loop_exit(false),
             HirStatement::Expression(expr) => self.expr(expr),
             HirStatement::Semi(expr) => ast::Expression::Semi(Box::new(self.expr(expr))),
             HirStatement::Error => unreachable!(),
         }
     }
 
+    /// Lowers the construction of an enum variant to a tuple of the tag of the variant
+    /// followed by the payload fields of every variant. Only the fields of this variant's
+    /// payload are given, the fields of the other variants are zeroed.
+    fn enum_constructor(
+        &mut self,
+        constructor: HirEnumConstructorExpression,
+        id: node_interner::ExprId,
+    ) -> ast::Expression {
+        let typ = self.interner.id_type(id);
+        let location = self.interner.expr_location(&id);
+        let field_types = unwrap_struct_type(&typ);
+
+        let offset = constructor.r#type.borrow().variant_field_offset(constructor.variant);
+        let payload_fields = offset..offset + constructor.arguments.len();
+        let mut arguments = constructor.arguments.into_iter();
+
+        let mut fields = Vec::with_capacity(field_types.len());
+        fields.push(enum_tag(constructor.variant, location));
+
+        for (i, (_, field_type)) in field_types.iter().enumerate().skip(1) {
+            let field = if payload_fields.contains(&i) {
+                let argument = arguments.next().expect("Expected an argument for each field");
+                self.expr(argument)
+            } else {
+                let field_type = self.convert_type(field_type);
+                self.zeroed_value_of_type(&field_type, location)
+            };
+            fields.push(field);
+        }
+
+        ast::Expression::Tuple(fields)
+    }
+
     fn let_statement(&mut self, let_statement: HirLetStatement) -> ast::Expression {
         let expr = self.expr(let_statement.expression);
         let expected_type = self.interner.id_type(let_statement.expression);
@@ -565,7 +846,144 @@ impl<'interner> Monomorphizer<'interner> {
     }
 
     fn block(&mut self, statement_ids: Vec<StmtId>) -> ast::Expression {
-        ast::Expression::Block(vecmap(statement_ids, |id| self.statement(id)))
+        let mut statements = Vec::with_capacity(statement_ids.len());
+
+        for (i, id) in statement_ids.iter().enumerate() {
+            let exits = self.loop_exits();
+            statements.push(self.statement(*id));
+
+            // Once a statement may have left the current iteration of a constrained loop,
+            // the rest of the block only executes if the iteration is still live.
+            let rest = &statement_ids[i + 1..];
+            if self.loop_exits() != exits && !rest.is_empty() {
+                statements.push(self.predicated_block(rest.to_vec()));
+                break;
+            }
+        }
+
+        ast::Expression::Block(statements)
+    }
+
+    /// The number of `break` and `continue` statements found so far within the innermost loop
+    /// if it is in a constrained function.
+    fn loop_exits(&self) -> usize {
+        match self.loops.last() {
+            Some(Some(flags)) => flags.exits,
+            _ => 0,
+        }
+    }
+
+    fn loop_flag(id: LocalId, name: &str) -> ast::Ident {
+        let definition = Definition::Local(id);
+        let name = name.to_owned();
+        ast::Ident { location: None, definition, mutable: true, name, typ: ast::Type::Bool }
+    }
+
+    fn set_loop_flag(id: LocalId, name: &str, value: bool) -> ast::Expression {
+        ast::Expression::Assign(ast::Assign {
+            lvalue: ast::LValue::Ident(Self::loop_flag(id, name)),
+            expression: Box::new(ast::Expression::Literal(ast::Literal::Bool(value))),
+        })
+    }
+
+    /// Lowers a `break` or `continue`. In constrained functions these only update the flags of
+    /// the innermost loop, which prevent the rest of the loop from executing.
+    fn loop_exit(&mut self, is_break: bool) -> ast::Expression {
+        let flags = match self.loops.last_mut() {
+            Some(Some(flags)) => flags,
+            _ if is_break => return ast::Expression::Break,
+            _ => return ast::Expression::Continue,
+        };
+
+        flags.exits += 1;
+        let mut statements = vec![Self::set_loop_flag(flags.live, "live", false)];
+        if is_break {
+            statements.insert(0, Self::set_loop_flag(flags.broken, "broken", true));
+        }
+        ast::Expression::Block(statements)
+    }
+
+    /// Monomorphizes the given statements, the rest of a block following a `break` or
+    /// `continue`, so that they only execute if the current iteration is still live.
+    fn predicated_block(&mut self, statement_ids: Vec<StmtId>) -> ast::Expression {
+        let live = match self.loops.last() {
+            Some(Some(flags)) => Self::loop_flag(flags.live, "live"),
+            _ => unreachable!("Only blocks within constrained loops are predicated"),
+        };
+
+        let last_expression =
+            statement_ids.last().and_then(|id| match self.interner.statement(id) {
+                HirStatement::Expression(expr) => Some(expr),
+                _ => None,
+            });
+
+        let consequence = Box::new(self.block(statement_ids));
+
+        // The value of the block is discarded when the iteration is no longer live, but both
+        // branches still need to have the same type.
+        let (typ, alternative) = match last_expression {
+            Some(expr) => {
+                let typ = self.convert_type(&self.interner.id_type(expr));
+                let location = self.interner.expr_location(&expr);
+                let alternative = (typ != ast::Type::Unit)
+                    .then(|| Box::new(self.zeroed_value_of_type(&typ, location)));
+                (typ, alternative)
+            }
+            None => (ast::Type::Unit, None),
+        };
+
+        let condition = Box::new(ast::Expression::Ident(live));
+        ast::Expression::If(ast::If { condition, consequence, alternative, typ })
+    }
+
+    /// Lowers a loop in a constrained function which contains a `break` or `continue` to:
+    ///
+    /// ```text
+    /// let mut broken = false;
+    /// for i in start..end {
+    ///     if !broken {
+    ///         let mut live = true;
+    ///         body
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// The loop is still unrolled for each iteration of its range, but once the loop has
+    /// executed a `break` each of the remaining iterations is skipped. When the conditions of
+    /// the `break` statements are known at compile-time, the skipped iterations are removed
+    /// entirely. Otherwise, they are executed under a predicate like any other `if`.
+    fn predicated_loop(&mut self, mut for_loop: ast::For, flags: &LoopFlags) -> ast::Expression {
+        let location = for_loop.start_range_location;
+        let not_broken = ast::Expression::Unary(ast::Unary {
+            operator: UnaryOp::Not,
+            rhs: Box::new(ast::Expression::Ident(Self::loop_flag(flags.broken, "broken"))),
+            result_type: ast::Type::Bool,
+            location,
+        });
+
+        let live = ast::Expression::Let(ast::Let {
+            id: flags.live,
+            mutable: true,
+            name: "live".to_owned(),
+            expression: Box::new(ast::Expression::Literal(ast::Literal::Bool(true))),
+        });
+
+        let body = std::mem::replace(&mut for_loop.block, Box::new(ast::Expression::Block(vec![])));
+        for_loop.block = Box::new(ast::Expression::If(ast::If {
+            condition: Box::new(not_broken),
+            consequence: Box::new(ast::Expression::Block(vec![live, *body])),
+            alternative: None,
+            typ: ast::Type::Unit,
+        }));
+
+        let broken = ast::Expression::Let(ast::Let {
+            id: flags.broken,
+            mutable: true,
+            name: "broken".to_owned(),
+            expression: Box::new(ast::Expression::Literal(ast::Literal::Bool(false))),
+        });
+
+        ast::Expression::Block(vec![broken, ast::Expression::For(for_loop)])
     }
 
     fn unpack_pattern(
@@ -580,12 +998,16 @@ impl<'interner> Monomorphizer<'interner> {
                 self.define_local(ident.id, new_id);
                 let definition = self.interner.definition(ident.id);
 
-                ast::Expression::Let(ast::Let {
+                let let_expr = ast::Expression::Let(ast::Let {
                     id: new_id,
                     mutable: definition.mutable,
                     name: definition.name.clone(),
                     expression: Box::new(value),
-                })
+                });
+                match self.debug_var_assign(&ident) {
+                    Some(report) => ast::Expression::Block(vec![let_expr, report]),
+                    None => let_expr,
+                }
             }
             HirPattern::Mutable(pattern, _) => self.unpack_pattern(*pattern, value, typ),
             HirPattern::Tuple(patterns, _) => {
@@ -642,6 +1064,173 @@ impl<'interner> Monomorphizer<'interner> {
         ast::Expression::Block(definitions)
     }
 
+    /// Lowers a match expression into a chain of if expressions which check the matched
+    /// value against the pattern of each arm in turn.
+    fn match_expr(
+        &mut self,
+        match_expr: HirMatchExpression,
+        expr: node_interner::ExprId,
+    ) -> ast::Expression {
+        let typ = self.convert_type(&self.interner.id_type(expr));
+        let location = self.interner.expr_location(&expr);
+        let scrutinee_type = self.interner.id_type(match_expr.expression);
+
+        let fresh_id = self.next_local_id();
+        let scrutinee = ast::Expression::Let(ast::Let {
+            id: fresh_id,
+            mutable: false,
+            name: "_".into(),
+            expression: Box::new(self.expr(match_expr.expression)),
+        });
+        let value = ast::Expression::Ident(ast::Ident {
+            location: None,
+            mutable: false,
+            definition: Definition::Local(fresh_id),
+            name: "_".into(),
+            typ: self.convert_type(&scrutinee_type),
+        });
+
+        // The arms are built from last to first so each can fall through to the arms after it.
+        // Type checking ensures the match is exhaustive, so the last arm to be tried never needs
+        // to check its pattern.
+        let mut result = None;
+        for (pattern, body) in match_expr.rules.into_iter().rev() {
+            let mut arm = Vec::new();
+            let condition =
+                self.match_pattern(pattern, value.clone(), &scrutinee_type, &mut arm, location);
+            arm.push(self.expr(body));
+            let arm = ast::Expression::Block(arm);
+
+            result = Some(match (condition, result) {
+                (Some(condition), Some(alternative)) => ast::Expression::If(ast::If {
+                    condition: Box::new(condition),
+                    consequence: Box::new(arm),
+                    alternative: Some(Box::new(alternative)),
+                    typ: typ.clone(),
+                }),
+                _ => arm,
+            });
+        }
+
+        // Type checking ensures a match has at least one arm unless it matches an enum without
+        // variants, which can't be constructed. Such a match has the unit type.
+        let mut block = vec![scrutinee];
+        block.extend(result);
+        ast::Expression::Block(block)
+    }
+
+    /// Returns the condition for `value` to match `pattern`, or None if it always matches.
+    /// A `let` is pushed onto `bindings` for each variable bound by the pattern.
+    fn match_pattern(
+        &mut self,
+        pattern: HirMatchPattern,
+        value: ast::Expression,
+        typ: &HirType,
+        bindings: &mut Vec<ast::Expression>,
+        location: Location,
+    ) -> Option<ast::Expression> {
+        match pattern {
+            HirMatchPattern::Wildcard(_) => None,
+            HirMatchPattern::Identifier(ident) => {
+                let new_id = self.next_local_id();
+                self.define_local(ident.id, new_id);
+
+                bindings.push(ast::Expression::Let(ast::Let {
+                    id: new_id,
+                    mutable: false,
+                    name: self.interner.definition_name(ident.id).to_owned(),
+                    expression: Box::new(value),
+                }));
+                None
+            }
+            HirMatchPattern::Literal(literal, _) => Some(ast::Expression::Binary(ast::Binary {
+                lhs: Box::new(value),
+                operator: BinaryOpKind::Equal,
+                rhs: Box::new(self.expr(literal)),
+                location,
+            })),
+            HirMatchPattern::Tuple(patterns, _) => {
+                let fields = unwrap_tuple_type(typ);
+                let fields = patterns.into_iter().zip(fields).enumerate();
+                self.match_fields(value, fields, bindings, location)
+            }
+            HirMatchPattern::Struct(_, patterns, _) => {
+                let fields = unwrap_struct_type(typ);
+                let mut patterns =
+                    btree_map(patterns, |(name, pattern)| (name.0.contents, pattern));
+
+                // We iterate through the type's fields to match the order defined in the struct type
+                let patterns = fields.into_iter().map(|(field_name, field_type)| {
+                    (patterns.remove(&field_name).unwrap(), field_type)
+                });
+
+                self.match_fields(value, patterns.enumerate(), bindings, location)
+            }
+            HirMatchPattern::Variant(_, variant, patterns, _) => {
+                let (offset, payload) = match typ.follow_bindings() {
+                    HirType::Struct(enum_type, generics) => {
+                        let enum_type = enum_type.borrow();
+                        let offset = enum_type.variant_field_offset(variant);
+                        (offset, enum_type.get_variant_payload(variant, &generics))
+                    }
+                    other => unreachable!("Expected an enum type, found {other}"),
+                };
+
+                // The tag of an enum is its first field
+                let tag = ast::Expression::ExtractTupleField(Box::new(value.clone()), 0);
+                let tag_condition = ast::Expression::Binary(ast::Binary {
+                    lhs: Box::new(tag),
+                    operator: BinaryOpKind::Equal,
+                    rhs: Box::new(enum_tag(variant, location)),
+                    location,
+                });
+
+                let fields = patterns.into_iter().zip(payload).enumerate();
+                let fields = fields.map(|(i, field)| (offset + i, field));
+                let payload_condition = self.match_fields(value, fields, bindings, location);
+
+                Some(match payload_condition {
+                    Some(payload_condition) => ast::Expression::Binary(ast::Binary {
+                        lhs: Box::new(tag_condition),
+                        operator: BinaryOpKind::And,
+                        rhs: Box::new(payload_condition),
+                        location,
+                    }),
+                    None => tag_condition,
+                })
+            }
+        }
+    }
+
+    /// Returns the condition for the given fields of `value` to match their patterns.
+    fn match_fields(
+        &mut self,
+        value: ast::Expression,
+        fields: impl Iterator<Item = (usize, (HirMatchPattern, HirType))>,
+        bindings: &mut Vec<ast::Expression>,
+        location: Location,
+    ) -> Option<ast::Expression> {
+        let mut condition = None;
+
+        for (i, (field_pattern, field_type)) in fields {
+            let field = ast::Expression::ExtractTupleField(Box::new(value.clone()), i);
+            let field_condition =
+                self.match_pattern(field_pattern, field, &field_type, bindings, location);
+
+            condition = match (condition, field_condition) {
+                (Some(lhs), Some(rhs)) => Some(ast::Expression::Binary(ast::Binary {
+                    lhs: Box::new(lhs),
+                    operator: BinaryOpKind::And,
+                    rhs: Box::new(rhs),
+                    location,
+                })),
+                (lhs, rhs) => lhs.or(rhs),
+            };
+        }
+
+        condition
+    }
+
     /// Find a captured variable in the innermost closure, and construct an expression
     fn lookup_captured_expr(&mut self, id: node_interner::DefinitionId) -> Option<ast::Expression> {
         let ctx = self.lambda_envs_stack.last()?;
@@ -712,22 +1301,108 @@ impl<'interner> Monomorphizer<'interner> {
                 ast::Expression::Ident(ident)
             }),
             DefinitionKind::GenericType(type_variable) => {
-                let value = match &*type_variable.borrow() {
+                let binding = match &*type_variable.borrow() {
                     TypeBinding::Unbound(_) => {
                         unreachable!("Unbound type variable used in expression")
                     }
-                    TypeBinding::Bound(binding) => binding.evaluate_to_u64().unwrap_or_else(|| {
-                        panic!("Non-numeric type variable used in expression expecting a value")
-                    }),
+                    TypeBinding::Bound(binding) => binding.follow_bindings(),
                 };
-
-                let value = FieldElement::from(value as u128);
                 let location = self.interner.id_location(expr_id);
+
+                // Generics declared with `let` keep the type they were declared with
+                if self.interner.value_generic_type(type_variable.id()).is_some() {
+                    let value = Self::type_level_value(&binding).unwrap_or_else(|| {
+                        panic!("Non-numeric type variable used in expression expecting a value")
+                    });
+                    let typ = self.convert_type(&typ);
+                    return Self::type_level_value_literal(&value, &typ, location);
+                }
+
+                let value = FieldElement::from(Self::evaluate_generic(&binding) as u128);
                 ast::Expression::Literal(ast::Literal::Integer(value, ast::Type::Field, location))
             }
         }
     }
 
+    fn evaluate_generic(binding: &HirType) -> u64 {
+        binding.evaluate_to_u64().unwrap_or_else(|| {
+            panic!("Non-numeric type variable used in expression expecting a value")
+        })
+    }
+
+    /// Returns the constant a generic declared with `let` is bound to, if it is bound to one.
+    fn type_level_value(binding: &HirType) -> Option<TypeLevelValue> {
+        match binding {
+            HirType::Value(value) => Some(value.clone()),
+            other => {
+                let value = other.evaluate_to_u64()? as u128;
+                Some(TypeLevelValue::Field(FieldElement::from(value)))
+            }
+        }
+    }
+
+    /// Returns a generic of a function of type `typ` which is declared with `let` but bound to a
+    /// constant that isn't of the declared type, as the constant and the declared type.
+    ///
+    /// The constants given explicitly to such generics are checked when they are resolved, but
+    /// those inferred from the arguments of a call are only known once the function is
+    /// instantiated.
+    fn invalid_value_generic(&self, typ: &HirType) -> Option<(TypeLevelValue, HirType)> {
+        let HirType::Forall(generics, _) = typ else {
+            return None;
+        };
+        generics.iter().find_map(|generic| {
+            let expected = self.interner.value_generic_type(generic.id())?;
+            let binding = match &*generic.borrow() {
+                TypeBinding::Bound(binding) => binding.follow_bindings(),
+                TypeBinding::Unbound(_) => return None,
+            };
+            let value = Self::type_level_value(&binding)?;
+            (!value.has_type(expected)).then(|| (value, expected.clone()))
+        })
+    }
+
+    /// Converts the constant given to a generic declared with `let` into a literal of its type.
+    fn type_level_value_literal(
+        value: &TypeLevelValue,
+        typ: &ast::Type,
+        location: Location,
+    ) -> ast::Expression {
+        match (value, typ) {
+            (TypeLevelValue::Field(value), _) => {
+                ast::Expression::Literal(ast::Literal::Integer(*value, typ.clone(), location))
+            }
+            (TypeLevelValue::Array(elements), ast::Type::Array(_, element_type)) => {
+                let contents = vecmap(elements, |element| {
+                    Self::type_level_value_literal(element, element_type, location)
+                });
+                let typ = typ.clone();
+                ast::Expression::Literal(ast::Literal::Array(ast::ArrayLiteral { contents, typ }))
+            }
+            (TypeLevelValue::Array(_), _) => {
+                unreachable!("Array constant given to a generic of type {typ}")
+            }
+        }
+    }
+
+    /// Evaluates the length of an array type, returning None if the length is unknown so the
+    /// type is a slice. A length which overflows or divides by zero is recorded to be reported
+    /// once the current function is monomorphized, and evaluates to zero until then.
+    fn evaluate_array_length(&self, length: &HirType) -> Option<u64> {
+        if let Some(length) = length.evaluate_to_u64() {
+            Some(length)
+        } else if let HirType::InfixExpr(..) = length.follow_bindings() {
+            // All generics are bound by now so the expression can only fail to
+            // evaluate if it overflowed or divided by zero.
+            self.invalid_array_length
+                .borrow_mut()
+                .get_or_insert_with(|| (length.to_string(), None));
+            Some(0)
+        } else {
+            None
+        }
+    }
+
     /// Convert a non-tuple/struct type to a monomorphized type
     fn convert_type(&self, typ: &HirType) -> ast::Type {
         match typ {
@@ -744,10 +1419,9 @@ impl<'interner> Monomorphizer<'interner> {
             HirType::Array(length, element) => {
                 let element = Box::new(self.convert_type(element.as_ref()));
 
-                if let Some(length) = length.evaluate_to_u64() {
-                    ast::Type::Array(length, element)
-                } else {
-                    ast::Type::Slice(element)
+                match self.evaluate_array_length(length) {
+                    Some(length) => ast::Type::Array(length, element),
+                    None => ast::Type::Slice(element),
                 }
             }
             HirType::TraitAsType(..) => {
@@ -821,6 +1495,8 @@ impl<'interner> Monomorphizer<'interner> {
 
             HirType::Forall(_, _)
             | HirType::Constant(_)
+            | HirType::InfixExpr(..)
+            | HirType::Value(_)
             | HirType::NotConstant
             | HirType::Error => {
                 unreachable!("Unexpected type {} found", typ)
@@ -924,9 +1600,10 @@ impl<'interner> Monomorphizer<'interner> {
 
         if let ast::Expression::Ident(ident) = original_func.as_ref() {
             if let Definition::Oracle(name) = &ident.definition {
-                if name.as_str() == "print" {
+                if name.as_str() == "print" || name.as_str() == "log" {
                     // Oracle calls are required to be wrapped in an unconstrained function
                     // The first argument to the `print` oracle is a bool, indicating a newline to be inserted at the end of the input
+                    // The first argument to the `log` oracle is the level the input is logged at
                     // The second argument is expected to always be an ident
                     self.append_printable_type_info(&hir_arguments[1], &mut arguments);
                 }
@@ -989,6 +1666,8 @@ impl<'interner> Monomorphizer<'interner> {
     /// The Noir compiler has a `PrintableType` that handles encoding/decoding a list
     /// of field elements to/from JSON. The type metadata attached in this method
     /// is the serialized `PrintableType` for the argument passed to the function.
+    /// A format string is described by a single `PrintableType` holding the types of each of the
+    /// values interpolated into it, so that it can be decoded along with its template.
     /// The caller that is running a Noir program should then deserialize the `PrintableType`,
     /// and accurately decode the list of field elements passed to the foreign call.
     fn append_printable_type_info(
@@ -1000,15 +1679,13 @@ impl<'interner> Monomorphizer<'interner> {
             HirExpression::Ident(ident) => {
                 let typ = self.interner.id_type(ident.id);
                 let typ: Type = typ.follow_bindings();
-                let is_fmt_str = match typ {
+                let is_fmt_str = match &typ {
                     // A format string has many different possible types that need to be handled.
-                    // Loop over each element in the format string to fetch each type's relevant metadata
+                    // Check each element in the format string can be printed.
                     Type::FmtString(_, elements) => {
-                        match *elements {
+                        match elements.as_ref() {
                             Type::Tuple(element_types) => {
-                                for typ in element_types {
-                                    Self::append_printable_type_info_inner(&typ, arguments);
-                                }
+                                element_types.iter().for_each(Self::check_printable_type);
                             }
                             _ => unreachable!(
                                 "ICE: format string type should be a tuple but got a {elements}"
@@ -1017,10 +1694,11 @@ impl<'interner> Monomorphizer<'interner> {
                         true
                     }
                     _ => {
-                        Self::append_printable_type_info_inner(&typ, arguments);
+                        Self::check_printable_type(&typ);
                         false
                     }
                 };
+                Self::append_printable_type_info_inner(&typ, arguments);
                 // The caller needs information as to whether it is handling a format string or a single type
                 arguments.push(ast::Expression::Literal(ast::Literal::Bool(is_fmt_str)));
             }
@@ -1028,7 +1706,7 @@ impl<'interner> Monomorphizer<'interner> {
         }
     }
 
-    fn append_printable_type_info_inner(typ: &Type, arguments: &mut Vec<ast::Expression>) {
+    fn check_printable_type(typ: &Type) {
         // Disallow printing slices and mutable references for consistency,
         // since they cannot be passed from ACIR into Brillig
         if let HirType::Array(size, _) = typ {
@@ -1038,7 +1716,9 @@ impl<'interner> Monomorphizer<'interner> {
         } else if matches!(typ, HirType::MutableReference(_)) {
             unreachable!("println does not support mutable references.");
         }
+    }
 
+    fn append_printable_type_info_inner(typ: &Type, arguments: &mut Vec<ast::Expression>) {
         let printable_type: PrintableType = typ.into();
         let abi_as_string = serde_json::to_string(&printable_type)
             .expect("ICE: expected PrintableType to serialize");
@@ -1046,6 +1726,19 @@ impl<'interner> Monomorphizer<'interner> {
         arguments.push(ast::Expression::Literal(ast::Literal::Str(abi_as_string)));
     }
 
+    /// Returns the serialized `PrintableType` of an assertion message if it is a format string,
+    /// which tells how the values formatted into it are printed when the assertion fails.
+    fn assert_message_printable_type(&self, message: node_interner::ExprId) -> Option<String> {
+        let typ = self.interner.id_type(message).follow_bindings();
+        if !matches!(typ, Type::FmtString(..)) {
+            return None;
+        }
+        let printable_type: PrintableType = (&typ).into();
+        let printable_type = serde_json::to_string(&printable_type)
+            .expect("ICE: expected PrintableType to serialize");
+        Some(printable_type)
+    }
+
     /// Try to evaluate certain builtin functions (currently only 'array_len' and field modulus methods)
     /// at their call site.
     /// NOTE: Evaluating at the call site means we cannot track aliased functions.
@@ -1126,7 +1819,44 @@ impl<'interner> Monomorphizer<'interner> {
 
         let bindings = self.interner.get_instantiation_bindings(expr_id);
         let bindings = self.follow_bindings(bindings);
-        self.queue.push_back((id, new_id, bindings, trait_method));
+        let location = self.interner.expr_location(&expr_id);
+        self.queue.push_back((id, new_id, bindings, trait_method, location));
+        new_id
+    }
+
+    /// Queues the monomorphization of `fallback`, the implementation in Noir of a black box
+    /// function, at `function_type`, the type of the black box function where it is called at
+    /// `location`.
+    fn queue_fallback(
+        &mut self,
+        fallback: node_interner::FuncId,
+        function_type: HirType,
+        location: Location,
+    ) -> FuncId {
+        if let Some(id) =
+            self.globals.get(&fallback).and_then(|inner_map| inner_map.get(&function_type))
+        {
+            return *id;
+        }
+
+        // The generics of the fallback are bound by unifying its type with the type of the black
+        // box function, as the bindings of the call only bind the generics of the latter.
+        let (generics, fallback_type) = self.interner.function_meta(&fallback).typ.unwrap_forall();
+        let replace_type_variable = |var: &TypeVariable| {
+            (var.id(), (var.clone(), Type::TypeVariable(var.clone(), TypeVariableKind::Normal)))
+        };
+        let type_bindings = generics.iter().map(replace_type_variable).collect();
+        let fallback_type = fallback_type.force_substitute(&type_bindings);
+
+        let mut bindings = TypeBindings::new();
+        fallback_type.try_unify(&function_type, &mut bindings).unwrap_or_else(|_| {
+            unreachable!("Fallback of type {} does not unify with black box function type {} during monomorphization", fallback_type, function_type)
+        });
+
+        let new_id = self.next_function_id();
+        self.define_global(fallback, function_type, new_id);
+        let bindings = self.follow_bindings(&bindings);
+        self.queue.push_back((fallback, new_id, bindings, None, location));
         new_id
     }
 
@@ -1149,8 +1879,29 @@ impl<'interner> Monomorphizer<'interner> {
 
     fn assign(&mut self, assign: HirAssignStatement) -> ast::Expression {
         let expression = Box::new(self.expr(assign.expression));
+        let report = self.debug_lvalue_assign(&assign.lvalue);
         let lvalue = self.lvalue(assign.lvalue);
-        ast::Expression::Assign(ast::Assign { expression, lvalue })
+        let assign = ast::Expression::Assign(ast::Assign { expression, lvalue });
+        match report {
+            Some(report) => ast::Expression::Block(vec![assign, report]),
+            None => assign,
+        }
+    }
+
+    /// Returns a call reporting the whole variable which is assigned to by `lvalue`, unless it is
+    /// assigned through a reference or is captured by the lambda being monomorphized.
+    fn debug_lvalue_assign(&mut self, lvalue: &HirLValue) -> Option<ast::Expression> {
+        match lvalue {
+            HirLValue::Ident(ident, _) => {
+                if self.lookup_captured_lvalue(ident.id).is_some() {
+                    return None;
+                }
+                self.debug_var_assign(ident)
+            }
+            HirLValue::MemberAccess { object, .. } => self.debug_lvalue_assign(object),
+            HirLValue::Index { array, .. } => self.debug_lvalue_assign(array),
+            HirLValue::Dereference { .. } => None,
+        }
     }
 
     fn lvalue(&mut self, lvalue: HirLValue) -> ast::LValue {
@@ -1187,6 +1938,17 @@ impl<'interner> Monomorphizer<'interner> {
         }
     }
 
+    /// Lambdas are always monomorphized as constrained functions and cannot refer to the
+    /// loops of the function they are defined in.
+    fn lambda_body(&mut self, body: node_interner::ExprId) -> ast::Expression {
+        let in_unconstrained_function = std::mem::take(&mut self.in_unconstrained_function);
+        let loops = std::mem::take(&mut self.loops);
+        let body = self.expr(body);
+        self.in_unconstrained_function = in_unconstrained_function;
+        self.loops = loops;
+        body
+    }
+
     fn lambda_no_capture(&mut self, lambda: HirLambda) -> ast::Expression {
         let ret_type = self.convert_type(&lambda.return_type);
         let lambda_name = "lambda";
@@ -1197,14 +1959,27 @@ impl<'interner> Monomorphizer<'interner> {
             vecmap(lambda.parameters, |(pattern, typ)| (pattern, typ, Visibility::Private)).into();
 
         let parameters = self.parameters(&parameters);
-        let body = self.expr(lambda.body);
+        let body = self.lambda_body(lambda.body);
 
         let id = self.next_function_id();
         let return_type = ret_type.clone();
         let name = lambda_name.to_owned();
         let unconstrained = false;
+        let constant_time = false;
+        let max_iterations = None;
+        let inline_type = InlineType::Default;
 
-        let function = ast::Function { id, name, parameters, body, return_type, unconstrained };
+        let function = ast::Function {
+            id,
+            name,
+            parameters,
+            body,
+            return_type,
+            unconstrained,
+            constant_time,
+            max_iterations,
+            inline_type,
+        };
         self.push_function(id, function);
 
         let typ =
@@ -1300,7 +2075,7 @@ impl<'interner> Monomorphizer<'interner> {
 
         self.lambda_envs_stack
             .push(LambdaContext { env_ident: env_ident.clone(), captures: lambda.captures });
-        let body = self.expr(lambda.body);
+        let body = self.lambda_body(lambda.body);
         self.lambda_envs_stack.pop();
 
         let lambda_fn_typ: ast::Type =
@@ -1318,7 +2093,20 @@ impl<'interner> Monomorphizer<'interner> {
         parameters.append(&mut converted_parameters);
 
         let unconstrained = false;
-        let function = ast::Function { id, name, parameters, body, return_type, unconstrained };
+        let constant_time = false;
+        let max_iterations = None;
+        let inline_type = InlineType::Default;
+        let function = ast::Function {
+            id,
+            name,
+            parameters,
+            body,
+            return_type,
+            unconstrained,
+            constant_time,
+            max_iterations,
+            inline_type,
+        };
         self.push_function(id, function);
 
         let lambda_value =
@@ -1436,7 +2224,20 @@ impl<'interner> Monomorphizer<'interner> {
         let name = lambda_name.to_owned();
 
         let unconstrained = false;
-        let function = ast::Function { id, name, parameters, body, return_type, unconstrained };
+        let constant_time = false;
+        let max_iterations = None;
+        let inline_type = InlineType::Default;
+        let function = ast::Function {
+            id,
+            name,
+            parameters,
+            body,
+            return_type,
+            unconstrained,
+            constant_time,
+            max_iterations,
+            inline_type,
+        };
         self.push_function(id, function);
 
         ast::Expression::Ident(ast::Ident {
@@ -1516,45 +2317,54 @@ impl<'interner> Monomorphizer<'interner> {
         result
     }
 
-    /// Call sites are instantiated against the trait method, but when an impl is later selected,
-    /// the corresponding method in the impl will have a different set of generics. `perform_impl_bindings`
-    /// is needed to apply the generics from the trait method to the impl method. Without this,
-    /// static method references to generic impls (e.g. `Eq::eq` for `[T; N]`) will fail to re-apply
-    /// the correct type bindings during monomorphization.
+    /// Binds the generics of `impl_method` to those of the trait method it implements.
+    /// See [impl_bindings].
     fn perform_impl_bindings(
         &self,
         trait_method: Option<TraitMethodId>,
         impl_method: node_interner::FuncId,
     ) -> TypeBindings {
-        let mut bindings = TypeBindings::new();
-
-        if let Some(trait_method) = trait_method {
-            let the_trait = self.interner.get_trait(trait_method.trait_id);
-
-            let trait_method_type = the_trait.methods[trait_method.method_index].typ.as_monotype();
-
-            // Make each NamedGeneric in this type bindable by replacing it with a TypeVariable
-            // with the same internal id and binding.
-            let (generics, impl_method_type) =
-                self.interner.function_meta(&impl_method).typ.unwrap_forall();
-
-            let replace_type_variable = |var: &TypeVariable| {
-                (var.id(), (var.clone(), Type::TypeVariable(var.clone(), TypeVariableKind::Normal)))
-            };
-
-            // Replace each NamedGeneric with a TypeVariable containing the same internal type variable
-            let type_bindings = generics.iter().map(replace_type_variable).collect();
-            let impl_method_type = impl_method_type.force_substitute(&type_bindings);
+        let bindings = impl_bindings(self.interner, trait_method, impl_method);
+        perform_instantiation_bindings(&bindings);
+        bindings
+    }
+}
 
-            trait_method_type.try_unify(&impl_method_type, &mut bindings).unwrap_or_else(|_| {
-                unreachable!("Impl method type {} does not unify with trait method type {} during monomorphization", impl_method_type, trait_method_type)
-            });
+/// Call sites are instantiated against the trait method, but when an impl is later selected,
+/// the corresponding method in the impl will have a different set of generics. `impl_bindings`
+/// is needed to apply the generics from the trait method to the impl method. Without this,
+/// static method references to generic impls (e.g. `Eq::eq` for `[T; N]`) will fail to re-apply
+/// the correct type bindings during monomorphization.
+pub(crate) fn impl_bindings(
+    interner: &NodeInterner,
+    trait_method: Option<TraitMethodId>,
+    impl_method: node_interner::FuncId,
+) -> TypeBindings {
+    let mut bindings = TypeBindings::new();
+
+    if let Some(trait_method) = trait_method {
+        let the_trait = interner.get_trait(trait_method.trait_id);
+
+        let trait_method_type = the_trait.methods[trait_method.method_index].typ.as_monotype();
+
+        // Make each NamedGeneric in this type bindable by replacing it with a TypeVariable
+        // with the same internal id and binding.
+        let (generics, impl_method_type) = interner.function_meta(&impl_method).typ.unwrap_forall();
+
+        let replace_type_variable = |var: &TypeVariable| {
+            (var.id(), (var.clone(), Type::TypeVariable(var.clone(), TypeVariableKind::Normal)))
+        };
 
-            perform_instantiation_bindings(&bindings);
-        }
+        // Replace each NamedGeneric with a TypeVariable containing the same internal type variable
+        let type_bindings = generics.iter().map(replace_type_variable).collect();
+        let impl_method_type = impl_method_type.force_substitute(&type_bindings);
 
-        bindings
+        trait_method_type.try_unify(&impl_method_type, &mut bindings).unwrap_or_else(|_| {
+            unreachable!("Impl method type {} does not unify with trait method type {} during monomorphization", impl_method_type, trait_method_type)
+        });
     }
+
+    bindings
 }
 
 fn unwrap_tuple_type(typ: &HirType) -> Vec<HirType> {
@@ -1568,6 +2378,12 @@ fn unwrap_tuple_type(typ: &HirType) -> Vec<HirType> {
     }
 }
 
+/// The tag identifying the given variant of an enum
+fn enum_tag(variant: usize, location: Location) -> ast::Expression {
+    let tag = FieldElement::from(variant as u128);
+    ast::Expression::Literal(ast::Literal::Integer(tag, ast::Type::Field, location))
+}
+
 fn unwrap_struct_type(typ: &HirType) -> Vec<(String, HirType)> {
     match typ {
         HirType::Struct(def, args) => def.borrow().get_fields(args),
@@ -49,6 +49,14 @@ impl AstPrinter {
                 write!(f, " as {})", cast.r#type)
             }
             Expression::For(for_expr) => self.print_for(for_expr, f),
+            Expression::Loop(block) => {
+                write!(f, "loop {{")?;
+                self.indent_level += 1;
+                self.print_expr_expect_block(block, f)?;
+                self.indent_level -= 1;
+                self.next_line(f)?;
+                write!(f, "}}")
+            }
             Expression::If(if_expr) => self.print_if(if_expr, f),
             Expression::Tuple(tuple) => self.print_tuple(tuple, f),
             Expression::ExtractTupleField(expr, index) => {
@@ -73,6 +81,8 @@ impl AstPrinter {
                 self.print_expr(expr, f)?;
                 write!(f, ";")
             }
+            Expression::Break => write!(f, "break"),
+            Expression::Continue => write!(f, "continue"),
         }
     }
 
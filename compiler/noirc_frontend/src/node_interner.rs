@@ -7,6 +7,7 @@ use noirc_errors::{Location, Span, Spanned};
 
 use crate::ast::Ident;
 use crate::graph::CrateId;
+use crate::hir::comptime::Value;
 use crate::hir::def_collector::dc_crate::{UnresolvedStruct, UnresolvedTrait, UnresolvedTypeAlias};
 use crate::hir::def_map::{LocalModuleId, ModuleId};
 
@@ -55,7 +56,7 @@ pub struct NodeInterner {
     pub(crate) id_to_location: HashMap<Index, Location>,
 
     // Maps each DefinitionId to a DefinitionInfo.
-    definitions: Vec<DefinitionInfo>,
+    pub(crate) definitions: Vec<DefinitionInfo>,
 
     // Type checking map
     //
@@ -71,7 +72,7 @@ pub struct NodeInterner {
     // Each struct definition is possibly shared across multiple type nodes.
     // It is also mutated through the RefCell during name resolution to append
     // methods from impls to the type.
-    structs: HashMap<StructId, Shared<StructType>>,
+    pub(crate) structs: HashMap<StructId, Shared<StructType>>,
 
     struct_attributes: HashMap<StructId, StructAttributes>,
     // Type Aliases map.
@@ -113,13 +114,19 @@ pub struct NodeInterner {
     /// Holds the trait ids of the traits used for operator overloading
     operator_traits: HashMap<BinaryOpKind, TraitId>,
 
+    /// The `Neg` trait used to overload the prefix `-` operator, if declared by the stdlib.
+    neg_trait: Option<TraitId>,
+
+    /// The `Index` trait used to overload `collection[index]`, if declared by the stdlib.
+    index_trait: Option<TraitId>,
+
     /// The `Ordering` type is a semi-builtin type that is the result of the comparison traits.
     ordering_type: Option<Type>,
 
     /// Map from ExprId (referring to a Function/Method call) to its corresponding TypeBindings,
     /// filled out during type checking from instantiated variables. Used during monomorphization
     /// to map call site types back onto function parameter types, and undo this binding as needed.
-    instantiation_bindings: HashMap<ExprId, TypeBindings>,
+    pub(crate) instantiation_bindings: HashMap<ExprId, TypeBindings>,
 
     /// Remembers the field index a given HirMemberAccess expression was resolved to during type
     /// checking.
@@ -127,6 +134,14 @@ pub struct NodeInterner {
 
     globals: HashMap<StmtId, GlobalInfo>, // NOTE: currently only used for checking repeat globals and restricting their scope to a module
 
+    /// The values of the globals which were evaluated at compile-time, so that the globals
+    /// using them, even from other crates, don't need to evaluate them again.
+    global_values: HashMap<DefinitionId, Value>,
+
+    /// The declared types of generics declared with `let`, e.g. the `Field` in
+    /// `struct Hasher<let SEPARATOR: Field>`, which can be used as values of that type.
+    value_generics: HashMap<TypeVariableId, Type>,
+
     next_type_variable_id: std::cell::Cell<usize>,
 
     /// A map from a struct type and method name to a function id for the method.
@@ -450,11 +465,15 @@ impl Default for NodeInterner {
             trait_implementation_map: HashMap::new(),
             selected_trait_implementations: HashMap::new(),
             operator_traits: HashMap::new(),
+            neg_trait: None,
+            index_trait: None,
             ordering_type: None,
             instantiation_bindings: HashMap::new(),
             field_indices: HashMap::new(),
             next_type_variable_id: std::cell::Cell::new(0),
             globals: HashMap::new(),
+            global_values: HashMap::new(),
+            value_generics: HashMap::new(),
             struct_methods: HashMap::new(),
             primitive_methods: HashMap::new(),
             type_alias_ref: Vec::new(),
@@ -774,6 +793,10 @@ impl NodeInterner {
         self.func_meta.get(func_id).expect("ice: all function ids should have metadata")
     }
 
+    pub fn function_meta_mut(&mut self, func_id: &FuncId) -> &mut FuncMeta {
+        self.func_meta.get_mut(func_id).expect("ice: all function ids should have metadata")
+    }
+
     pub fn try_function_meta(&self, func_id: &FuncId) -> Option<&FuncMeta> {
         self.func_meta.get(func_id)
     }
@@ -901,6 +924,24 @@ impl NodeInterner {
         self.globals.clone()
     }
 
+    pub(crate) fn push_global_value(&mut self, global: DefinitionId, value: Value) {
+        self.global_values.insert(global, value);
+    }
+
+    /// Returns the value of a global if it was evaluated at compile-time.
+    pub(crate) fn global_value(&self, global: DefinitionId) -> Option<&Value> {
+        self.global_values.get(&global)
+    }
+
+    pub(crate) fn push_value_generic(&mut self, generic: TypeVariableId, typ: Type) {
+        self.value_generics.insert(generic, typ);
+    }
+
+    /// Returns the declared type of a generic if it was declared with `let`.
+    pub(crate) fn value_generic_type(&self, generic: TypeVariableId) -> Option<&Type> {
+        self.value_generics.get(&generic)
+    }
+
     /// Returns the type of an item stored in the Interner or Error if it was not found.
     pub fn id_type(&self, index: impl Into<Index>) -> Type {
         self.id_to_type.get(&index.into()).cloned().unwrap_or(Type::Error)
@@ -978,6 +1019,13 @@ impl NodeInterner {
         self.function_definition_id(function_id)
     }
 
+    /// Returns each method declared in an `impl` of a struct, along with the struct's id.
+    pub(crate) fn struct_inherent_methods(&self) -> impl Iterator<Item = (StructId, FuncId)> + '_ {
+        self.struct_methods
+            .iter()
+            .flat_map(|((id, _), methods)| methods.direct.iter().map(move |method| (*id, *method)))
+    }
+
     /// Adds a non-trait method to a type.
     ///
     /// Returns `Some(duplicate)` if a matching method was already defined.
@@ -1346,12 +1394,30 @@ impl NodeInterner {
         TraitMethodId { trait_id, method_index: 0 }
     }
 
+    /// Returns the `Neg::neg` method, if the `Neg` trait has been declared.
+    pub fn get_neg_trait_method(&self) -> Option<TraitMethodId> {
+        self.neg_trait.map(|trait_id| TraitMethodId { trait_id, method_index: 0 })
+    }
+
+    /// Returns the `Index::index` method, if the `Index` trait has been declared.
+    pub fn get_index_trait_method(&self) -> Option<TraitMethodId> {
+        self.index_trait.map(|trait_id| TraitMethodId { trait_id, method_index: 0 })
+    }
+
     /// Add the given trait as an operator trait if its name matches one of the
     /// operator trait names (Add, Sub, ...).
     pub fn try_add_operator_trait(&mut self, trait_id: TraitId) {
         let the_trait = self.get_trait(trait_id);
 
         let operator = match the_trait.name.0.contents.as_str() {
+            "Neg" => {
+                self.neg_trait = Some(trait_id);
+                return;
+            }
+            "Index" => {
+                self.index_trait = Some(trait_id);
+                return;
+            }
             "Add" => BinaryOpKind::Add,
             "Sub" => BinaryOpKind::Subtract,
             "Mul" => BinaryOpKind::Multiply,
@@ -1508,6 +1574,8 @@ fn get_type_method_key(typ: &Type) -> Option<TypeMethodKey> {
         Type::TypeVariable(_, _)
         | Type::Forall(_, _)
         | Type::Constant(_)
+        | Type::InfixExpr(..)
+        | Type::Value(_)
         | Type::Error
         | Type::NotConstant
         | Type::Struct(_, _)
@@ -39,10 +39,12 @@ use crate::token::{Attribute, Attributes, Keyword, SecondaryAttribute, Token, To
 use crate::{
     BinaryOp, BinaryOpKind, BlockExpression, ConstrainKind, ConstrainStatement, Distinctness,
     ForLoopStatement, ForRange, FunctionDefinition, FunctionReturnType, FunctionVisibility, Ident,
-    IfExpression, InfixExpression, LValue, Lambda, Literal, NoirFunction, NoirStruct, NoirTrait,
-    NoirTraitImpl, NoirTypeAlias, Param, Path, PathKind, Pattern, Recoverable, Statement,
-    TraitBound, TraitImplItem, TraitItem, TypeImpl, UnaryOp, UnresolvedTraitConstraint,
-    UnresolvedTypeExpression, UseTree, UseTreeKind, Visibility,
+    IfExpression, InfixExpression, LValue, Lambda, Literal, MatchExpression, MatchPattern,
+    NoirFunction, NoirStruct, NoirTrait, NoirTraitImpl, NoirTypeAlias, Param, Path, PathKind,
+    Pattern, Recoverable, Statement, TraitBound, TraitImplItem, TraitItem, TypeImpl,
+    TypeLevelValue, UnaryOp, UnresolvedGeneric, UnresolvedGenerics, UnresolvedNumericBound,
+    UnresolvedTraitConstraint, UnresolvedTypeExpression, UseTree, UseTreeKind, Visibility,
+    WhileStatement,
 };
 
 use chumsky::prelude::*;
@@ -99,6 +101,7 @@ fn module() -> impl NoirParser<ParsedModule> {
 
 /// top_level_statement: function_definition
 ///                    | struct_definition
+///                    | enum_definition
 ///                    | trait_definition
 ///                    | implementation
 ///                    | submodule
@@ -111,6 +114,7 @@ fn top_level_statement(
     choice((
         function_definition(false).map(TopLevelStatement::Function),
         struct_definition(),
+        enum_definition(),
         trait_definition(),
         trait_implementation(),
         implementation(),
@@ -170,10 +174,11 @@ fn function_definition(allow_self: bool) -> impl NoirParser<NoirFunction> {
         .then(generics())
         .then(parenthesized(function_parameters(allow_self)))
         .then(function_return_type())
-        .then(where_clause())
+        .then(function_where_clause())
         .then(spanned(block(fresh_statement())))
         .validate(|(((args, ret), where_clause), (body, body_span)), span, emit| {
             let ((((attributes, modifiers), name), generics), parameters) = args;
+            let (where_clause, numeric_bounds) = where_clause;
 
             // Validate collected attributes, filtering them into function and secondary variants
             let attributes = validate_attributes(attributes, span, emit);
@@ -195,6 +200,7 @@ fn function_definition(allow_self: bool) -> impl NoirParser<NoirFunction> {
                 parameters,
                 body,
                 where_clause,
+                numeric_bounds,
                 return_type: ret.1,
                 return_visibility: ret.0 .1,
                 return_distinctness: ret.0 .0,
@@ -233,13 +239,13 @@ fn is_pub_crate() -> impl NoirParser<bool> {
     .map(|a| a.is_some())
 }
 
-/// non_empty_ident_list: ident ',' non_empty_ident_list
-///                     | ident
+/// non_empty_generic_list: generic ',' non_empty_generic_list
+///                       | generic
 ///
-/// generics: '<' non_empty_ident_list '>'
+/// generics: '<' non_empty_generic_list '>'
 ///         | %empty
-fn generics() -> impl NoirParser<Vec<Ident>> {
-    ident()
+fn generics() -> impl NoirParser<UnresolvedGenerics> {
+    generic()
         .separated_by(just(Token::Comma))
         .allow_trailing()
         .at_least(1)
@@ -248,6 +254,18 @@ fn generics() -> impl NoirParser<Vec<Ident>> {
         .map(|opt| opt.unwrap_or_default())
 }
 
+/// generic: ident
+///        | 'let' ident ':' type
+fn generic() -> impl NoirParser<UnresolvedGeneric> {
+    let value_generic = keyword(Keyword::Let)
+        .ignore_then(ident())
+        .then_ignore(just(Token::Colon))
+        .then(parse_type())
+        .map_with_span(|(ident, typ), span| UnresolvedGeneric::Value { ident, typ, span });
+
+    value_generic.or(ident().map(UnresolvedGeneric::Variable))
+}
+
 fn struct_definition() -> impl NoirParser<TopLevelStatement> {
     use self::Keyword::Struct;
     use Token::*;
@@ -265,7 +283,54 @@ fn struct_definition() -> impl NoirParser<TopLevelStatement> {
     attributes().then_ignore(keyword(Struct)).then(ident()).then(generics()).then(fields).validate(
         |(((raw_attributes, name), generics), fields), span, emit| {
             let attributes = validate_struct_attributes(raw_attributes, span, emit);
-            TopLevelStatement::Struct(NoirStruct { name, attributes, generics, fields, span })
+            let variants = None;
+            TopLevelStatement::Struct(NoirStruct {
+                name,
+                attributes,
+                generics,
+                fields,
+                variants,
+                span,
+            })
+        },
+    )
+}
+
+/// enum_definition: attributes 'enum' ident generics '{' enum_variants '}'
+fn enum_definition() -> impl NoirParser<TopLevelStatement> {
+    use self::Keyword::Enum;
+    use Token::*;
+
+    let payload = parse_type()
+        .separated_by(just(Comma))
+        .allow_trailing()
+        .delimited_by(just(LeftParen), just(RightParen));
+
+    let variants = ident()
+        .then(payload.or_not().map(Option::unwrap_or_default))
+        .separated_by(just(Comma))
+        .allow_trailing()
+        .delimited_by(just(LeftBrace), just(RightBrace))
+        .recover_with(nested_delimiters(
+            LeftBrace,
+            RightBrace,
+            [(LeftParen, RightParen), (LeftBracket, RightBracket)],
+            |_| vec![],
+        ));
+
+    attributes().then_ignore(keyword(Enum)).then(ident()).then(generics()).then(variants).validate(
+        |(((raw_attributes, name), generics), variants), span, emit| {
+            let attributes = validate_struct_attributes(raw_attributes, span, emit);
+            let variants = Some(variants);
+            let fields = Vec::new();
+            TopLevelStatement::Struct(NoirStruct {
+                name,
+                attributes,
+                generics,
+                fields,
+                variants,
+                span,
+            })
         },
     )
 }
@@ -620,32 +685,61 @@ fn trait_implementation_body() -> impl NoirParser<Vec<TraitImplItem>> {
 }
 
 fn where_clause() -> impl NoirParser<Vec<UnresolvedTraitConstraint>> {
-    struct MultiTraitConstraint {
-        typ: UnresolvedType,
-        trait_bounds: Vec<TraitBound>,
-    }
-
-    let constraints = parse_type()
-        .then_ignore(just(Token::Colon))
-        .then(trait_bounds())
-        .map(|(typ, trait_bounds)| MultiTraitConstraint { typ, trait_bounds });
-
     keyword(Keyword::Where)
-        .ignore_then(constraints.separated_by(just(Token::Comma)))
+        .ignore_then(trait_constraints().separated_by(just(Token::Comma)))
         .or_not()
-        .map(|option| option.unwrap_or_default())
-        .map(|x: Vec<MultiTraitConstraint>| {
-            let mut result: Vec<UnresolvedTraitConstraint> = Vec::new();
-            for constraint in x {
-                for bound in constraint.trait_bounds {
-                    result.push(UnresolvedTraitConstraint {
-                        typ: constraint.typ.clone(),
-                        trait_bound: bound,
-                    });
+        .map(|option| option.unwrap_or_default().into_iter().flatten().collect())
+}
+
+/// function_where_clause: 'where' (trait_constraints | numeric_bound) (',' (trait_constraints | numeric_bound))*
+///
+/// Unlike the where clauses of traits and impls, those of functions may also bound the numeric
+/// generics of the function.
+fn function_where_clause(
+) -> impl NoirParser<(Vec<UnresolvedTraitConstraint>, Vec<UnresolvedNumericBound>)> {
+    enum WhereClauseItem {
+        Traits(Vec<UnresolvedTraitConstraint>),
+        Numeric(UnresolvedNumericBound),
+    }
+
+    // Trait constraints are tried first as `Foo<T>: Eq` would otherwise start a numeric bound.
+    let item = trait_constraints()
+        .map(WhereClauseItem::Traits)
+        .or(numeric_bound().map(WhereClauseItem::Numeric));
+
+    keyword(Keyword::Where).ignore_then(item.separated_by(just(Token::Comma))).or_not().map(
+        |option| {
+            let mut trait_constraints = Vec::new();
+            let mut numeric_bounds = Vec::new();
+            for item in option.unwrap_or_default() {
+                match item {
+                    WhereClauseItem::Traits(constraints) => trait_constraints.extend(constraints),
+                    WhereClauseItem::Numeric(bound) => numeric_bounds.push(bound),
                 }
             }
-            result
+            (trait_constraints, numeric_bounds)
+        },
+    )
+}
+
+/// Parses a constraint such as `T: Eq + Default`, splitting it into a constraint per trait bound.
+fn trait_constraints() -> impl NoirParser<Vec<UnresolvedTraitConstraint>> {
+    parse_type().then_ignore(just(Token::Colon)).then(trait_bounds()).map(|(typ, trait_bounds)| {
+        vecmap(trait_bounds, |trait_bound| UnresolvedTraitConstraint {
+            typ: typ.clone(),
+            trait_bound,
         })
+    })
+}
+
+/// numeric_bound: type_expression comparison_operator type_expression
+fn numeric_bound() -> impl NoirParser<UnresolvedNumericBound> {
+    let comparison = operator_with_precedence(Precedence::LessGreater)
+        .or(operator_with_precedence(Precedence::Lowest));
+
+    type_expression().then(comparison).then(type_expression()).map_with_span(
+        |((lhs, operator), rhs), span| UnresolvedNumericBound { lhs, operator, rhs, span },
+    )
 }
 
 fn trait_bounds() -> impl NoirParser<Vec<TraitBound>> {
@@ -797,7 +891,11 @@ where
             assertion_eq(expr_parser.clone()),
             declaration(expr_parser.clone()),
             assignment(expr_parser.clone()),
-            for_loop(expr_no_constructors, statement),
+            for_loop(expr_no_constructors.clone(), statement.clone()),
+            while_loop(expr_no_constructors, statement.clone()),
+            loop_statement(statement),
+            break_statement(),
+            continue_statement(),
             return_statement(expr_parser.clone()),
             expr_parser.map(StatementKind::Expression),
         ))
@@ -834,21 +932,9 @@ where
         .labelled(ParsingRuleLabel::Statement)
         .validate(|expressions, span, emit| {
             let condition = expressions.get(0).unwrap_or(&Expression::error(span)).clone();
-            let mut message_str = None;
-
-            if let Some(message) = expressions.get(1) {
-                if let ExpressionKind::Literal(Literal::Str(message)) = &message.kind {
-                    message_str = Some(message.clone());
-                } else {
-                    emit(ParserError::with_reason(ParserErrorReason::AssertMessageNotString, span));
-                }
-            }
+            let message = assert_message(expressions.get(1), span, emit);
 
-            StatementKind::Constrain(ConstrainStatement(
-                condition,
-                message_str,
-                ConstrainKind::Assert,
-            ))
+            StatementKind::Constrain(ConstrainStatement(condition, message, ConstrainKind::Assert))
         })
 }
 
@@ -870,23 +956,33 @@ where
                 })),
                 span,
             );
-            let mut message_str = None;
+            let message = assert_message(exprs.get(2), span, emit);
 
-            if let Some(message) = exprs.get(2) {
-                if let ExpressionKind::Literal(Literal::Str(message)) = &message.kind {
-                    message_str = Some(message.clone());
-                } else {
-                    emit(ParserError::with_reason(ParserErrorReason::AssertMessageNotString, span));
-                }
-            }
             StatementKind::Constrain(ConstrainStatement(
                 predicate,
-                message_str,
+                message,
                 ConstrainKind::AssertEq,
             ))
         })
 }
 
+/// The message of an assertion must be a string literal or a format string, which may refer to
+/// the values it reports.
+fn assert_message(
+    message: Option<&Expression>,
+    span: Span,
+    emit: &mut dyn FnMut(ParserError),
+) -> Option<Expression> {
+    let message = message?;
+    match &message.kind {
+        ExpressionKind::Literal(Literal::Str(_) | Literal::FmtStr(_)) => Some(message.clone()),
+        _ => {
+            emit(ParserError::with_reason(ParserErrorReason::AssertMessageNotString, span));
+            None
+        }
+    }
+}
+
 fn declaration<'a, P>(expr_parser: P) -> impl NoirParser<StatementKind> + 'a
 where
     P: ExprParser + 'a,
@@ -1139,6 +1235,8 @@ fn generic_type_args(
         .then_ignore(one_of([Token::Comma, Token::Greater]).rewind())
         .or(type_expression()
             .map_with_span(|expr, span| UnresolvedTypeData::Expression(expr).with_span(span)))
+        .or(type_level_value()
+            .map_with_span(|value, span| UnresolvedTypeData::Value(value).with_span(span)))
         .separated_by(just(Token::Comma))
         .allow_trailing()
         .at_least(1)
@@ -1147,6 +1245,31 @@ fn generic_type_args(
         .map(Option::unwrap_or_default)
 }
 
+/// The constants given to generics declared with `let` which `type_expression` can't parse,
+/// such as integers larger than a u64 or arrays of constants.
+///
+/// type_level_value: int
+///                 | '[' type_level_value (',' type_level_value)* ','? ']'
+fn type_level_value() -> impl NoirParser<TypeLevelValue> {
+    recursive(|value| {
+        let field = filter_map(|span, token: Token| match token {
+            Token::Int(x) => Ok(TypeLevelValue::Field(x)),
+            unexpected => {
+                Err(ParserError::expected_label(ParsingRuleLabel::TypeExpression, unexpected, span))
+            }
+        });
+
+        let array = value
+            .separated_by(just(Token::Comma))
+            .allow_trailing()
+            .at_least(1)
+            .delimited_by(just(Token::LeftBracket), just(Token::RightBracket))
+            .map(TypeLevelValue::Array);
+
+        field.or(array)
+    })
+}
+
 fn array_type(type_parser: impl NoirParser<UnresolvedType>) -> impl NoirParser<UnresolvedType> {
     just(Token::LeftBracket)
         .ignore_then(type_parser)
@@ -1253,6 +1376,14 @@ where
     .labelled(ParsingRuleLabel::Expression)
 }
 
+fn break_statement() -> impl NoirParser<StatementKind> {
+    keyword(Keyword::Break).to(StatementKind::Break).labelled(ParsingRuleLabel::Statement)
+}
+
+fn continue_statement() -> impl NoirParser<StatementKind> {
+    keyword(Keyword::Continue).to(StatementKind::Continue).labelled(ParsingRuleLabel::Statement)
+}
+
 fn return_statement<'a, P>(expr_parser: P) -> impl NoirParser<StatementKind> + 'a
 where
     P: ExprParser + 'a,
@@ -1468,6 +1599,86 @@ where
     })
 }
 
+fn match_expr<'a, P, P2>(
+    expr_parser: P,
+    expr_no_constructors: P2,
+) -> impl NoirParser<ExpressionKind> + 'a
+where
+    P: ExprParser + 'a,
+    P2: ExprParser + 'a,
+{
+    let rule = match_pattern().then_ignore(just(Token::FatArrow)).then(expr_parser);
+
+    let rules = rule
+        .separated_by(just(Token::Comma))
+        .allow_trailing()
+        .delimited_by(just(Token::LeftBrace), just(Token::RightBrace));
+
+    keyword(Keyword::Match).ignore_then(expr_no_constructors).then(rules).map(
+        |(expression, rules)| {
+            ExpressionKind::Match(Box::new(MatchExpression { expression, rules }))
+        },
+    )
+}
+
+fn match_pattern() -> impl NoirParser<MatchPattern> {
+    recursive(|pattern| {
+        let literal = filter_map(|span, token: Token| match token {
+            Token::Int(value) => Ok(Literal::Integer(value, false)),
+            Token::Bool(value) => Ok(Literal::Bool(value)),
+            unexpected => {
+                Err(ParserError::expected_label(ParsingRuleLabel::Pattern, unexpected, span))
+            }
+        });
+        let negative_integer =
+            just(Token::Minus).ignore_then(filter_map(|span, token: Token| match token {
+                Token::Int(value) => Ok(Literal::Integer(value, true)),
+                unexpected => {
+                    Err(ParserError::expected_label(ParsingRuleLabel::Pattern, unexpected, span))
+                }
+            }));
+        let literal_pattern = literal.or(negative_integer).map_with_span(MatchPattern::Literal);
+
+        let short_field = ident().map(|name| (name.clone(), MatchPattern::Identifier(name)));
+        let long_field = ident().then_ignore(just(Token::Colon)).then(pattern.clone());
+
+        let struct_pattern_fields = long_field
+            .or(short_field)
+            .separated_by(just(Token::Comma))
+            .allow_trailing()
+            .delimited_by(just(Token::LeftBrace), just(Token::RightBrace));
+
+        let struct_pattern = path()
+            .then(struct_pattern_fields)
+            .map_with_span(|(typename, fields), span| MatchPattern::Struct(typename, fields, span));
+
+        let variant_payload = pattern
+            .clone()
+            .separated_by(just(Token::Comma))
+            .allow_trailing()
+            .delimited_by(just(Token::LeftParen), just(Token::RightParen));
+
+        // A lone identifier binds a variable, so variants without a payload must be written
+        // with the enum they belong to, e.g. `Option::None`
+        let variant_or_ident_pattern =
+            path().then(variant_payload.or_not()).map_with_span(|(path, payload), span| {
+                match (path.as_ident(), payload) {
+                    (Some(name), None) if name.0.contents == "_" => MatchPattern::Wildcard(span),
+                    (Some(name), None) => MatchPattern::Identifier(name.clone()),
+                    (_, payload) => MatchPattern::Variant(path, payload.unwrap_or_default(), span),
+                }
+            });
+
+        let tuple_pattern = pattern
+            .separated_by(just(Token::Comma))
+            .delimited_by(just(Token::LeftParen), just(Token::RightParen))
+            .map_with_span(MatchPattern::Tuple);
+
+        choice((literal_pattern, tuple_pattern, struct_pattern, variant_or_ident_pattern))
+    })
+    .labelled(ParsingRuleLabel::Pattern)
+}
+
 fn lambda<'a>(
     expr_parser: impl NoirParser<Expression> + 'a,
 ) -> impl NoirParser<ExpressionKind> + 'a {
@@ -1495,6 +1706,29 @@ where
         })
 }
 
+fn while_loop<'a, P, S>(
+    expr_no_constructors: P,
+    statement: S,
+) -> impl NoirParser<StatementKind> + 'a
+where
+    P: ExprParser + 'a,
+    S: NoirParser<StatementKind> + 'a,
+{
+    keyword(Keyword::While)
+        .ignore_then(expr_no_constructors)
+        .then(block_expr(statement))
+        .map_with_span(|(condition, block), span| {
+            StatementKind::While(WhileStatement { condition, block, span })
+        })
+}
+
+fn loop_statement<'a, S>(statement: S) -> impl NoirParser<StatementKind> + 'a
+where
+    S: NoirParser<StatementKind> + 'a,
+{
+    keyword(Keyword::Loop).ignore_then(block_expr(statement)).map(StatementKind::Loop)
+}
+
 /// The 'range' of a for loop. Either an actual range `start .. end` or an array expression.
 fn for_range<P>(expr_no_constructors: P) -> impl NoirParser<ForRange>
 where
@@ -1594,7 +1828,8 @@ where
     S: NoirParser<StatementKind> + 'a,
 {
     choice((
-        if_expr(expr_no_constructors, statement.clone()),
+        if_expr(expr_no_constructors.clone(), statement.clone()),
+        match_expr(expr_parser.clone(), expr_no_constructors),
         array_expr(expr_parser.clone()),
         if allow_constructors {
             constructor(expr_parser.clone()).boxed()
@@ -2092,10 +2327,27 @@ mod test {
         match parse_with(assertion(expression()), "assert(x == y, \"assertion message\")").unwrap()
         {
             StatementKind::Constrain(ConstrainStatement(_, message, _)) => {
-                assert_eq!(message, Some("assertion message".to_owned()));
+                let message = message.unwrap().kind;
+                assert_eq!(
+                    message,
+                    ExpressionKind::Literal(Literal::Str("assertion message".to_owned()))
+                );
+            }
+            _ => unreachable!(),
+        }
+
+        match parse_with(assertion(expression()), "assert(x == y, f\"{x} is not {y}\")").unwrap() {
+            StatementKind::Constrain(ConstrainStatement(_, message, _)) => {
+                let message = message.unwrap().kind;
+                assert_eq!(
+                    message,
+                    ExpressionKind::Literal(Literal::FmtStr("{x} is not {y}".to_owned()))
+                );
             }
             _ => unreachable!(),
         }
+
+        parse_all_failing(assertion(expression()), vec!["assert(x == y, x)"]);
     }
 
     /// This is the standard way to assert that two expressions are equivalent
@@ -2116,7 +2368,11 @@ mod test {
             .unwrap()
         {
             StatementKind::Constrain(ConstrainStatement(_, message, _)) => {
-                assert_eq!(message, Some("assertion message".to_owned()));
+                let message = message.unwrap().kind;
+                assert_eq!(
+                    message,
+                    ExpressionKind::Literal(Literal::Str("assertion message".to_owned()))
+                );
             }
             _ => unreachable!(),
         }
@@ -2155,6 +2411,34 @@ mod test {
         );
     }
 
+    #[test]
+    fn parse_while_and_loop() {
+        parse_all(
+            fresh_statement(),
+            vec![
+                "while x < 10 { x += 1; }",
+                "while (a != b) & !done {}",
+                "loop { if x == 0 { break; } x -= 1; }",
+            ],
+        );
+
+        parse_all_failing(fresh_statement(), vec!["while {}", "while x", "loop x"]);
+    }
+
+    #[test]
+    fn parse_break_and_continue() {
+        parse_all(
+            for_loop(expression_no_constructors(expression()), fresh_statement()),
+            vec![
+                "for i in 0..10 { break; }",
+                "for i in 0..10 { if i == 2 { continue; } foo(i); }",
+                "for i in 0..10 { for j in 0..i { if j == 3 { break; } } continue; }",
+            ],
+        );
+
+        parse_all_failing(fresh_statement(), vec!["break 1", "continue x"]);
+    }
+
     #[test]
     fn parse_function() {
         parse_all(
@@ -2186,6 +2470,10 @@ mod test {
                 "fn func_name<T>(f: Field, y : T) where T: SomeTrait + {}",
                 // The following should produce compile error on later stage. From the parser's perspective it's fine
                 "fn func_name<A>(f: Field, y : Field, z : Field) where T: SomeTrait {}",
+                "fn func_name<N>(x: [Field; N]) where N <= 32 {}",
+                "fn func_name<N>(x: [Field; N]) where N * 2 == 64 {}",
+                "fn func_name<T, N, M>(x: [T; N], y: [T; M]) where N < M + 1, T: SomeTrait {}",
+                "fn func_name<N, M>(x: [Field; N], y: [Field; M]) where N < 32, M > 2 {}",
             ],
         );
 
@@ -2202,6 +2490,9 @@ mod test {
                 // A leading plus is not allowed.
                 "fn func_name<T>(f: Field, y : T) where T: + SomeTrait {}",
                 "fn func_name<T>(f: Field, y : T) where T: TraitX + <Y> {}",
+                // Numeric bounds may only compare type expressions.
+                "fn func_name<N>(x: [Field; N]) where N & 1 == 0 {}",
+                "fn func_name<N>(x: [Field; N]) where N {}",
             ],
         );
     }
@@ -2263,6 +2554,27 @@ mod test {
         );
     }
 
+    #[test]
+    fn parse_match_expr() {
+        parse_all(
+            match_expr(expression(), expression_no_constructors(expression())),
+            vec![
+                "match x {}",
+                "match x { _ => 1 }",
+                "match x { 1 => a, -2 => b, y => y + 1, }",
+                "match (a, b) { (true, _) => 1, (false, x) => x }",
+                "match p { Point { x: 0, y } => y, Point { x, y: _ } => { x } }",
+                "match o { Option::Some((1, x)) => x, Option::None => 0, _ => 1 }",
+                "match e { crate::Shape::Square(side,) => side, Shape::Rect(w, _) => w }",
+            ],
+        );
+
+        parse_all_failing(
+            match_expr(expression(), expression_no_constructors(expression())),
+            vec!["match x { 1 => a 2 => b }", "match x { \"a\" => 1 }", "match x { 1 }"],
+        );
+    }
+
     fn expr_to_lit(expr: ExpressionKind) -> Literal {
         match expr {
             ExpressionKind::Literal(literal) => literal,
@@ -2400,6 +2712,8 @@ mod test {
             "struct Bar { ident: Field, }",
             "struct Baz { ident: Field, other: Field }",
             "#[attribute] struct Baz { ident: Field, other: Field }",
+            "struct Hasher<let SEPARATOR: Field> { }",
+            "struct Cipher<T, let KEY: [u8; 2]> { value: T }",
         ];
         parse_all(struct_definition(), cases);
 
@@ -2408,13 +2722,35 @@ mod test {
             "struct Foo { bar: pub Field }",
             "struct Foo { bar: pub Field }",
             "#[oracle(some)] struct Foo { bar: Field }",
+            "struct Foo<let N> { }",
         ];
         parse_all_failing(struct_definition(), failing);
     }
 
+    #[test]
+    fn parse_enums() {
+        let cases = vec![
+            "enum Foo { }",
+            "enum Bar { A, B, }",
+            "enum Baz<T> { Leaf(T), Pair(T, Field,), Empty }",
+            "#[attribute] enum Qux { A(u8) }",
+        ];
+        parse_all(enum_definition(), cases);
+
+        let failing = vec!["enum {  }", "enum Foo;", "enum Foo { A: Field }", "enum Foo { A() B }"];
+        parse_all_failing(enum_definition(), failing);
+    }
+
     #[test]
     fn parse_type_aliases() {
-        let cases = vec!["type foo = u8", "type bar = String", "type baz<T> = Vec<T>"];
+        let cases = vec![
+            "type foo = u8",
+            "type bar = String",
+            "type baz<T> = Vec<T>",
+            "type qux = Hasher<0x1000000000000000000>",
+            "type quux = Cipher<u8, [1, 2]>",
+            "type corge<let KEY: [u8; 2]> = Cipher<u8, KEY>",
+        ];
         parse_all(type_alias_definition(), cases);
 
         let failing = vec!["type = u8", "type foo", "type foo = 1"];
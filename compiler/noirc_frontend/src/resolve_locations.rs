@@ -4,7 +4,7 @@ use noirc_errors::Location;
 use crate::hir_def::expr::HirExpression;
 use crate::hir_def::types::Type;
 
-use crate::node_interner::{DefinitionKind, Node, NodeInterner};
+use crate::node_interner::{DefinitionKind, FuncId, Node, NodeInterner};
 
 impl NodeInterner {
     /// Scans the interner for the item which is located at that [Location]
@@ -56,6 +56,67 @@ impl NodeInterner {
         })
     }
 
+    /// Returns the [FuncId] of the function whose name is found at the given [Location].
+    pub fn find_function_at(&self, location: Location) -> Option<FuncId> {
+        self.func_meta
+            .iter()
+            .find(|(_, func_meta)| func_meta.name.location.contains(&location))
+            .map(|(func_id, _)| *func_id)
+    }
+
+    /// Returns the [Location]s of every reference to the item found at the given [Location],
+    /// which may either be a reference to the item or its definition.
+    ///
+    /// References are searched for across every crate known to the interner, so this includes
+    /// those from dependencies and the crates depending on them.
+    pub fn find_references(&self, location: Location, include_definition: bool) -> Vec<Location> {
+        let Some(definition) = self
+            .get_definition_location_from(location, false)
+            .or_else(|| self.find_definition_at(location))
+        else {
+            return Vec::new();
+        };
+
+        let mut references = self.references_to(definition);
+        if include_definition {
+            references.push(definition);
+        }
+        references.sort_by_key(|location| (location.file, location.span.start()));
+        references.dedup();
+        references
+    }
+
+    /// Returns the [Location]s of the identifiers and types which resolve to `definition`.
+    pub(crate) fn references_to(&self, definition: Location) -> Vec<Location> {
+        let identifiers = self.id_to_location.iter().filter(|(index, _)| {
+            matches!(self.nodes.get(**index), Some(Node::Expression(HirExpression::Ident(_))))
+        });
+
+        identifiers
+            .filter(|(index, _)| self.resolve_location(**index, false) == Some(definition))
+            .map(|(_, location)| *location)
+            .chain(
+                self.type_ref_locations
+                    .iter()
+                    .filter(|(typ, _)| match typ {
+                        Type::Struct(struct_type, _) => struct_type.borrow().location == definition,
+                        _ => false,
+                    })
+                    .map(|(_, location)| *location),
+            )
+            .collect()
+    }
+
+    /// Returns the [Location] of the definition which is named at the given [Location].
+    fn find_definition_at(&self, location: Location) -> Option<Location> {
+        self.definitions
+            .iter()
+            .map(|definition| definition.location)
+            .chain(self.func_meta.values().map(|func_meta| func_meta.location))
+            .chain(self.structs.values().map(|struct_type| struct_type.borrow().location))
+            .find(|definition| definition.contains(&location))
+    }
+
     /// For a given [Index] we return [Location] to which we resolved to
     /// We currently return None for features not yet implemented
     /// TODO(#3659): LSP goto def should error when Ident at Location could not resolve
@@ -112,6 +173,10 @@ impl NodeInterner {
                 let struct_type = &expr.r#type.borrow();
                 Some(struct_type.location)
             }
+            HirExpression::EnumConstructor(expr) => {
+                let enum_type = &expr.r#type.borrow();
+                Some(enum_type.location)
+            }
             HirExpression::MemberAccess(expr_member_access) => {
                 self.resolve_struct_member_access(expr_member_access)
             }
@@ -5,13 +5,14 @@
 mod test {
 
     use core::panic;
-    use std::collections::BTreeMap;
+    use std::collections::{BTreeMap, HashMap};
 
     use fm::FileId;
 
     use iter_extended::vecmap;
     use noirc_errors::Location;
 
+    use crate::hir::comptime::InterpreterError;
     use crate::hir::def_collector::dc_crate::CompilationError;
     use crate::hir::def_collector::errors::{DefCollectorErrorKind, DuplicateType};
     use crate::hir::def_map::ModuleData;
@@ -24,12 +25,15 @@ mod test {
     use crate::hir::def_collector::dc_crate::DefCollector;
     use crate::hir_def::expr::HirExpression;
     use crate::hir_def::stmt::HirStatement;
-    use crate::monomorphization::monomorphize;
+    use crate::monomorphization::{
+        monomorphize, monomorphize_with_fallbacks, MonomorphizationError,
+    };
     use crate::parser::ParserErrorReason;
+    use crate::type_hints::TypeHintKind;
     use crate::ParsedModule;
     use crate::{
         hir::def_map::{CrateDefMap, LocalModuleId},
-        parse_program,
+        parse_program, Type,
     };
     use arena::Arena;
     use fm::FileManager;
@@ -139,6 +143,299 @@ mod test {
         assert!(errors.is_empty());
     }
 
+    #[test]
+    fn trait_default_method_calls_methods_of_each_impl() {
+        let src = "
+        trait Value {
+            fn value(self) -> Field;
+
+            fn double(self) -> Field {
+                self.value() * 2
+            }
+
+            fn one_more(self) -> Field {
+                Self::zero() + 1
+            }
+
+            fn zero() -> Field {
+                0
+            }
+        }
+
+        struct Foo {
+            x: Field,
+        }
+
+        impl Value for Foo {
+            fn value(self) -> Field { self.x }
+        }
+
+        struct Wrapper<T> {
+            inner: T,
+        }
+
+        impl<T> Value for Wrapper<T> where T: Value {
+            fn value(self) -> Field { self.inner.value() + 1 }
+        }
+
+        impl Value for Field {
+            fn value(self) -> Field { self }
+            fn zero() -> Field { 1 }
+        }
+
+        fn main(a: Field) {
+            let foo = Foo { x: a };
+            let wrapper = Wrapper { inner: foo };
+            assert(foo.double() + wrapper.double() + a.double() != 0);
+            assert(foo.one_more() + a.one_more() == 3);
+        }";
+
+        let (_program, context, errors) = get_program(src);
+        errors.iter().for_each(|err| println!("{:?}", err));
+        assert!(errors.is_empty());
+
+        // Each impl gets its own copy of the default methods
+        let main_func_id = context.def_interner.find_function("main").unwrap();
+        let program = monomorphize(main_func_id, &context.def_interner).unwrap().to_string();
+        assert_eq!(program.matches("fn double$").count(), 3);
+        assert_eq!(program.matches("fn one_more$").count(), 2);
+    }
+
+    #[test]
+    fn black_box_calls_in_constrained_code_use_fallbacks() {
+        let src = "
+        #[foreign(keccak256)]
+        fn black_box<N>(_input: [u8; N]) -> u8 {}
+
+        fn fallback<N>(input: [u8; N]) -> u8 {
+            input[N - 1]
+        }
+
+        unconstrained fn black_box_unconstrained(input: [u8; 2]) -> u8 {
+            black_box(input)
+        }
+
+        fn main(x: u8) {
+            assert(black_box([x]) == black_box([1, x]));
+            assert(black_box_unconstrained([1, x]) == x);
+        }";
+
+        let (_program, context, errors) = get_program(src);
+        assert!(errors.is_empty(), "{errors:?}");
+
+        let main_func_id = context.def_interner.find_function("main").unwrap();
+        let fallback_id = context.def_interner.find_function("fallback").unwrap();
+        let fallbacks = HashMap::from([("keccak256".to_string(), fallback_id)]);
+        let program =
+            monomorphize_with_fallbacks(main_func_id, &context.def_interner, fallbacks, false)
+                .unwrap()
+                .to_string();
+
+        // The fallback is monomorphized for each length of the input, while unconstrained code
+        // still calls the black box function
+        assert_eq!(program.matches("fn fallback$").count(), 2);
+        assert_eq!(program.matches("keccak256(").count(), 1);
+    }
+
+    #[test]
+    fn variables_are_reported_to_the_debugger_when_instrumented() {
+        let src = r#"
+        struct Counter {
+            count: u8,
+        }
+
+        fn main(x: u8) {
+            let mut counter = Counter { count: x };
+            counter.count += 1;
+            let double = |y: u8| y * 2;
+            assert(double(counter.count) != x);
+        }"#;
+
+        let (_program, context, errors) = get_program(src);
+        assert!(errors.is_empty(), "{errors:?}");
+
+        let main_func_id = context.def_interner.find_function("main").unwrap();
+        let program =
+            monomorphize_with_fallbacks(main_func_id, &context.def_interner, HashMap::new(), true)
+                .unwrap()
+                .to_string();
+
+        // `x`, then `counter` when it is declared and assigned to. The lambda can't be reported.
+        assert_eq!(program.matches("fn __debug_var_assign$").count(), 3, "{program}");
+
+        let program = monomorphize(main_func_id, &context.def_interner).unwrap().to_string();
+        assert!(!program.contains("__debug_var_assign"), "{program}");
+    }
+
+    #[test]
+    fn numeric_bounds_are_checked_when_monomorphizing() {
+        let src = "
+        fn first<N>(values: [Field; N]) -> Field where N >= 1, N * 2 <= 4 {
+            values[0]
+        }
+
+        fn main(x: Field) {
+            assert(first([x]) == first([x, x]));
+            assert(first([x, x, x]) == x);
+        }";
+
+        let (_program, context, errors) = get_program(src);
+        assert!(errors.is_empty(), "{errors:?}");
+
+        let main_func_id = context.def_interner.find_function("main").unwrap();
+        match monomorphize(main_func_id, &context.def_interner) {
+            Err(MonomorphizationError::UnsatisfiedNumericBound { function, bound, location }) => {
+                assert_eq!(function, "first");
+                assert_eq!(bound, "(3 * 2) <= 4");
+                // The error points at the function in the call which violates the bound
+                let call = src.find("first([x, x, x])").unwrap();
+                assert_eq!(location.span.start() as usize, call);
+            }
+            other => panic!("Expected the numeric bound to be violated, found {other:?}"),
+        }
+    }
+
+    #[test]
+    fn invalid_array_lengths_are_reported_when_monomorphizing() {
+        let src = "
+        fn drop<N, M>(values: [Field; N], _dropped: [Field; M]) -> [Field; N - M] {
+            let mut result = [0; N - M];
+            for i in 0..N - M {
+                result[i] = values[i + M];
+            }
+            result
+        }
+
+        fn main(x: Field) {
+            assert(drop([x, x], [x])[0] == x);
+            let dropped = drop([x], [x, x]);
+            assert(dropped.len() == 0);
+        }";
+
+        let (_program, context, errors) = get_program(src);
+        assert!(errors.is_empty(), "{errors:?}");
+
+        let main_func_id = context.def_interner.find_function("main").unwrap();
+        match monomorphize(main_func_id, &context.def_interner) {
+            Err(MonomorphizationError::InvalidArrayLength { location, .. }) => {
+                // The error points at the function in the call whose generics underflow
+                let call = src.find("drop([x], [x, x])").unwrap();
+                assert_eq!(location.span.start() as usize, call);
+            }
+            other => panic!("Expected the array length to be invalid, found {other:?}"),
+        }
+    }
+
+    #[test]
+    fn value_generics_are_monomorphized_per_constant() {
+        let src = "
+        struct Hasher<let SEPARATOR: Field> {}
+
+        impl<let SEPARATOR: Field> Hasher<SEPARATOR> {
+            fn hash(_self: Self, x: Field) -> Field {
+                x + SEPARATOR
+            }
+        }
+
+        struct Cipher<let KEY: [u8; 2]> {}
+
+        impl<let KEY: [u8; 2]> Cipher<KEY> {
+            fn encrypt(_self: Self, x: u8) -> u8 {
+                x ^ KEY[0] ^ KEY[1]
+            }
+        }
+
+        fn main(x: Field, y: u8) {
+            let large: Hasher<0x1000000000000000000> = Hasher {};
+            let small: Hasher<1> = Hasher {};
+            let same: Hasher<1> = Hasher {};
+            assert(large.hash(x) != small.hash(x));
+            assert(small.hash(x) == same.hash(x));
+
+            let first: Cipher<[1, 2]> = Cipher {};
+            let second: Cipher<[2, 1]> = Cipher {};
+            assert(first.encrypt(y) == second.encrypt(y));
+        }";
+
+        let (_program, context, errors) = get_program(src);
+        assert!(errors.is_empty(), "{errors:?}");
+
+        let main_func_id = context.def_interner.find_function("main").unwrap();
+        let program = monomorphize(main_func_id, &context.def_interner).unwrap().to_string();
+
+        assert_eq!(program.matches("fn hash$").count(), 2);
+        assert_eq!(program.matches("fn encrypt$").count(), 2);
+        assert!(program.contains("[1, 2]"), "{program}");
+    }
+
+    #[test]
+    fn value_generic_arguments_are_checked() {
+        let src = "
+        struct Cipher<let KEY: [u8; 2]> {}
+        struct Wrapper<T> { value: T }
+        struct Named<let NAME: str<4>> {}
+        struct Offset<let DELTA: i8> {}
+
+        fn main() {
+            let _wrong_length: Cipher<[1, 2, 3]> = Cipher {};
+            let _too_large: Cipher<[1, 256]> = Cipher {};
+            let _too_large_signed: Offset<200> = Offset {};
+            let _not_a_constant: Cipher<Field> = Cipher {};
+            let _not_a_value_generic: Wrapper<[1, 2]> = Wrapper { value: 1 };
+        }";
+
+        let errors = get_program_errors(src);
+        let invalid_arguments = errors
+            .iter()
+            .filter(|(error, _)| {
+                matches!(
+                    error,
+                    CompilationError::ResolverError(
+                        ResolverError::InvalidValueGenericArgument { .. }
+                    )
+                )
+            })
+            .count();
+        assert_eq!(invalid_arguments, 5, "{errors:?}");
+        assert!(errors.iter().any(|(error, _)| matches!(
+            error,
+            CompilationError::ResolverError(ResolverError::InvalidValueGenericType { .. })
+        )));
+    }
+
+    #[test]
+    fn inferred_value_generic_arguments_are_checked_when_monomorphizing() {
+        let src = "
+        fn first<let N: u8>(values: [Field; N]) -> Field {
+            values[0]
+        }
+
+        fn main(x: Field) {
+            assert(first([x; 255]) == x);
+            assert(first([x; 300]) == x);
+        }";
+
+        let (_program, context, errors) = get_program(src);
+        assert!(errors.is_empty(), "{errors:?}");
+
+        let main_func_id = context.def_interner.find_function("main").unwrap();
+        match monomorphize(main_func_id, &context.def_interner) {
+            Err(MonomorphizationError::InvalidValueGenericArgument {
+                function,
+                value,
+                expected,
+                location,
+            }) => {
+                assert_eq!(function, "first");
+                assert_eq!(value, "300");
+                assert_eq!(expected, "u8");
+                let call = src.find("first([x; 300])").unwrap();
+                assert_eq!(location.span.start() as usize, call);
+            }
+            other => panic!("Expected the generic's constant to be rejected, found {other:?}"),
+        }
+    }
+
     #[test]
     fn check_trait_implementation_duplicate_method() {
         let src = "
@@ -777,6 +1074,8 @@ mod test {
                 HirStatement::Constrain(constr_stmt) => constr_stmt.0,
                 HirStatement::Semi(semi_expr) => semi_expr,
                 HirStatement::For(for_loop) => for_loop.block,
+                HirStatement::Loop(block) => block,
+                HirStatement::Break | HirStatement::Continue => continue,
                 HirStatement::Error => panic!("Invalid HirStatement!"),
             };
             let expr = interner.expression(&expr_id);
@@ -1128,7 +1427,7 @@ mod test {
     fn check_rewrite(src: &str, expected: &str) {
         let (_program, context, _errors) = get_program(src);
         let main_func_id = context.def_interner.find_function("main").unwrap();
-        let program = monomorphize(main_func_id, &context.def_interner);
+        let program = monomorphize(main_func_id, &context.def_interner).unwrap();
         assert!(format!("{}", program) == expected);
     }
 
@@ -1162,4 +1461,632 @@ fn lambda$f1(mut env$l1: (Field)) -> Field {
 "#;
         check_rewrite(src, expected_rewrite);
     }
+
+    #[test]
+    fn arithmetic_on_numeric_generics_in_array_lengths() {
+        let src = r#"
+        fn concat<N, M>(a: [Field; N], b: [Field; M]) -> [Field; N + M] {
+            let mut result = [0; N + M];
+            for i in 0..N {
+                result[i] = a[i];
+            }
+            for i in 0..M {
+                result[N + i] = b[i];
+            }
+            result
+        }
+
+        fn main() {
+            let result: [Field; 3] = concat([1, 2], [3]);
+            assert(result[2] == 3);
+        }
+        "#;
+        assert_eq!(get_program_errors(src).len(), 0);
+    }
+
+    #[test]
+    fn arithmetic_on_numeric_generics_checks_the_resulting_length() {
+        let src = r#"
+        fn concat<N, M>(a: [Field; N], b: [Field; M]) -> [Field; N + M] {
+            let mut result = [0; N + M];
+            for i in 0..N {
+                result[i] = a[i];
+            }
+            for i in 0..M {
+                result[N + i] = b[i];
+            }
+            result
+        }
+
+        fn main() {
+            let result: [Field; 4] = concat([1, 2], [3]);
+            assert(result[2] == 3);
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            errors[0].0,
+            CompilationError::TypeError(TypeCheckError::TypeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn exhaustive_match_expressions() {
+        let src = r#"
+        struct Point {
+            x: Field,
+            y: Field,
+        }
+
+        fn main(a: bool, b: bool, p: Point) -> pub Field {
+            let first = match (a, b) {
+                (true, true) => 1,
+                (true, false) => 2,
+                (false, _) => 3,
+            };
+            let second = match p {
+                Point { x: 0, y } => y,
+                Point { x, y: 0 } => x,
+                Point { x, y } => x + y,
+            };
+            first + second
+        }
+        "#;
+        assert_eq!(get_program_errors(src).len(), 0);
+    }
+
+    #[test]
+    fn non_exhaustive_match_reports_a_missing_pattern() {
+        let src = r#"
+        fn main(a: bool, b: bool) -> pub Field {
+            match (a, b) {
+                (true, _) => 1,
+                (false, true) => 2,
+            }
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 1);
+        match &errors[0].0 {
+            CompilationError::TypeError(TypeCheckError::NonExhaustiveMatch { missing, .. }) => {
+                assert_eq!(missing, "(false, false)");
+            }
+            other => panic!("Expected a non-exhaustive match error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn match_on_integers_requires_a_wildcard() {
+        let src = r#"
+        fn main(x: u8) -> pub u8 {
+            match x {
+                0 => 1,
+                1 => 2,
+            }
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            errors[0].0,
+            CompilationError::TypeError(TypeCheckError::NonExhaustiveMatch { .. })
+        ));
+    }
+
+    #[test]
+    fn unreachable_match_arm() {
+        let src = r#"
+        fn main(x: u8) -> pub u8 {
+            match x {
+                0 => 1,
+                y => y,
+                1 => 2,
+            }
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 1);
+        match &errors[0].0 {
+            CompilationError::TypeError(TypeCheckError::UnreachableMatchArm { span }) => {
+                assert_eq!(&src[span.start() as usize..span.end() as usize], "1");
+            }
+            other => panic!("Expected an unreachable match arm warning, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn enums_are_constructed_and_matched() {
+        let src = r#"
+        enum Shape<T> {
+            Circle(T),
+            Rectangle(T, T),
+            Empty,
+        }
+
+        impl<T> Shape<T> {
+            fn is_empty(self) -> bool {
+                match self {
+                    Self::Empty => true,
+                    _ => false,
+                }
+            }
+        }
+
+        fn area(shape: Shape<Field>) -> Field {
+            match shape {
+                Shape::Circle(radius) => 3 * radius * radius,
+                Shape::Rectangle(width, 0) => width,
+                Shape::Rectangle(width, height) => width * height,
+                Shape::Empty => 0,
+            }
+        }
+
+        fn main(x: Field) -> pub Field {
+            let empty: Shape<u8> = Shape::Empty;
+            assert(empty.is_empty());
+            area(Shape::Circle(x)) + area(Shape::Rectangle(x, 2))
+        }
+        "#;
+        let (_program, context, errors) = get_program(src);
+        assert!(errors.is_empty(), "{errors:?}");
+
+        let main_func_id = context.def_interner.find_function("main").unwrap();
+        monomorphize(main_func_id, &context.def_interner).unwrap();
+    }
+
+    #[test]
+    fn non_exhaustive_enum_match_reports_a_missing_variant() {
+        let src = r#"
+        enum Shape {
+            Circle(Field),
+            Rectangle(Field, bool),
+        }
+
+        fn main(shape: Shape) -> pub Field {
+            match shape {
+                Shape::Circle(radius) => radius,
+                Shape::Rectangle(width, true) => width,
+            }
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 1);
+        match &errors[0].0 {
+            CompilationError::TypeError(TypeCheckError::NonExhaustiveMatch { missing, .. }) => {
+                assert_eq!(missing, "Shape::Rectangle(_, false)");
+            }
+            other => panic!("Expected a non-exhaustive match error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn enum_variants_must_be_given_their_payload() {
+        let src = r#"
+        enum Shape {
+            Circle(Field),
+            Empty,
+        }
+
+        fn main(x: Field) {
+            let _ = Shape::Circle(x, x);
+            let _ = Shape::Empty(x);
+            let _ = Shape::Circle;
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 3);
+        for (error, _) in errors {
+            assert!(
+                matches!(error, CompilationError::TypeError(TypeCheckError::ArityMisMatch { .. })),
+                "Expected an arity mismatch, got {error:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn enums_cannot_be_used_as_structs() {
+        let src = r#"
+        enum Shape {
+            Empty,
+        }
+
+        fn main() {
+            let _ = Shape {};
+            let _ = Shape::Square;
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(
+            errors[0].0,
+            CompilationError::ResolverError(ResolverError::NonStructUsedInConstructor { .. })
+        ));
+        assert!(matches!(
+            errors[1].0,
+            CompilationError::ResolverError(ResolverError::PathResolutionError(..))
+        ));
+    }
+
+    #[test]
+    fn break_and_continue_outside_loop() {
+        let src = r#"
+        fn main(x: Field) {
+            for i in 0..3 {
+                let f = |y: Field| {
+                    if y == i { continue; }
+                };
+                f(x);
+            }
+            break;
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 2, "Expected 2 errors, got: {errors:?}");
+        for (error, _) in errors {
+            match error {
+                CompilationError::ResolverError(ResolverError::JumpOutsideLoop {
+                    keyword,
+                    span,
+                }) => {
+                    assert_eq!(&src[span.start() as usize..span.end() as usize], keyword);
+                }
+                other => panic!("Expected a jump outside of a loop, got {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn break_and_continue_in_loop() {
+        let src = r#"
+        fn main(x: u32) {
+            for i in 0..10 {
+                if i == x {
+                    break;
+                }
+                for j in 0..i {
+                    if j == 3 { continue; }
+                    assert(j != x);
+                }
+            }
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert!(errors.is_empty(), "Expected no errors, got: {errors:?}");
+    }
+
+    #[test]
+    fn unbounded_loops_only_in_unconstrained_functions() {
+        let src = r#"
+        fn main(x: u32) {
+            while x > 10 {}
+            assert(gcd(x, 6) == 1);
+        }
+
+        unconstrained fn gcd(mut a: u32, mut b: u32) -> u32 {
+            while b != 0 {
+                let remainder = a % b;
+                a = b;
+                b = remainder;
+            }
+            let f = || { loop {} };
+            f();
+            a
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 2, "Expected 2 errors, got: {errors:?}");
+        for (error, _) in errors {
+            match error {
+                CompilationError::ResolverError(
+                    ResolverError::UnboundedLoopInConstrainedFunction { span },
+                ) => {
+                    let source = &src[span.start() as usize..span.end() as usize];
+                    assert!(source == "while x > 10 {}" || source == "loop {}");
+                }
+                other => panic!("Expected an unbounded loop error, got {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn type_hints_for_inferred_types() {
+        let src = r#"
+        fn main(x: Field, arr: [Field; 3]) {
+            let y = x + 1;
+            let annotated: Field = y;
+            let f = |z| z + annotated;
+            assert(f(y) == sum(arr));
+        }
+
+        fn sum<N>(arr: [Field; N]) -> Field {
+            let mut total = 0;
+            for i in 0..N {
+                total += arr[i];
+            }
+            total
+        }
+        "#;
+        let (_, context, errors) = get_program(src);
+        assert!(errors.is_empty(), "Expected no errors, got: {errors:?}");
+
+        let hints = context.def_interner.type_hints(FileId::dummy());
+        let hints = vecmap(hints, |hint| {
+            let span = hint.location.span;
+            (hint.kind, src[span.start() as usize..span.end() as usize].to_string(), hint.label)
+        });
+        assert!(hints.contains(&(TypeHintKind::LetBinding, "y".into(), ": Field".into())));
+        assert!(hints.contains(&(TypeHintKind::LambdaParameter, "z".into(), ": Field".into())));
+        assert!(hints.contains(&(TypeHintKind::NumericGenerics, "sum".into(), "<N = 3>".into())));
+        assert!(!hints.iter().any(|(_, source, _)| source == "annotated"));
+    }
+
+    #[test]
+    fn cfg_attribute_removes_disabled_items() {
+        let src = r#"
+        #[cfg(feature = "fast")]
+        fn hash(x: Field) -> Field {
+            x
+        }
+
+        #[cfg(not(feature = "fast"))]
+        fn hash(x: Field) -> Field {
+            x + 1
+        }
+
+        struct Hasher {}
+
+        impl Hasher {
+            #[cfg(feature = "fast")]
+            fn fast(self) {}
+        }
+
+        fn main(x: Field) -> pub Field {
+            hash(x)
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert!(errors.is_empty(), "Expected no errors, got: {errors:?}");
+
+        // The method doesn't exist without the feature
+        let src = src.replace("hash(x)", "Hasher {}.fast(); hash(x)");
+        assert!(!get_program_errors(&src).is_empty());
+    }
+
+    #[test]
+    fn cfg_attribute_on_field_and_backend() {
+        let src = r#"
+        #[cfg(field = "bn254")]
+        fn modulus_bits() -> u64 {
+            254
+        }
+
+        #[cfg(not(field = "bn254"))]
+        fn modulus_bits() -> u64 {
+            255
+        }
+
+        #[cfg(backend = "acvm")]
+        fn backend_specific() {}
+
+        fn main() -> pub u64 {
+            modulus_bits()
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert!(errors.is_empty(), "Expected no errors, got: {errors:?}");
+
+        // No backend is given when compiling these tests
+        let src = src.replace("modulus_bits()\n", "backend_specific(); modulus_bits()\n");
+        assert!(!get_program_errors(&src).is_empty());
+    }
+
+    #[test]
+    fn evaluates_global_function_calls_at_compile_time() {
+        let src = r#"
+        fn square(x: Field) -> Field {
+            x * x
+        }
+
+        fn squares<N>() -> [Field; N] {
+            let mut result = [0; N];
+            for i in 0..N {
+                result[i] = square(i as Field);
+            }
+            result
+        }
+
+        global SQUARES: [Field; 4] = squares();
+
+        fn main() -> pub Field {
+            SQUARES[3]
+        }
+        "#;
+        let (_program, context, errors) = get_program(src);
+        assert!(errors.is_empty(), "Expected no errors, got: {errors:?}");
+
+        // The global is replaced by its value, so the functions are no longer called at runtime
+        let main_func_id = context.def_interner.find_function("main").unwrap();
+        let program = monomorphize(main_func_id, &context.def_interner).unwrap().to_string();
+        assert!(program.contains("[0, 1, 4, 9]"), "{program}");
+        assert!(!program.contains("fn squares$"), "{program}");
+    }
+
+    #[test]
+    fn failed_assertion_in_global_is_reported() {
+        let src = r#"
+        fn checked_half(x: u32) -> u32 {
+            assert(x % 2 == 0, "odd value");
+            x / 2
+        }
+
+        global HALF = checked_half(3);
+
+        fn main() -> pub u32 {
+            HALF
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 1, "Expected one error, got: {errors:?}");
+        assert!(matches!(
+            &errors[0].0,
+            CompilationError::InterpreterError(InterpreterError::FailedConstraint {
+                message: Some(message),
+                ..
+            }) if message == "odd value"
+        ));
+    }
+
+    #[test]
+    fn failed_assertion_in_global_formats_its_message() {
+        let src = r#"
+        fn checked_half(x: u32) -> u32 {
+            let is_even = x % 2 == 0;
+            assert(is_even, f"{x} is odd, is_even: {is_even}");
+            x / 2
+        }
+
+        global HALF = checked_half(3);
+
+        fn main() -> pub u32 {
+            HALF
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 1, "Expected one error, got: {errors:?}");
+        assert!(matches!(
+            &errors[0].0,
+            CompilationError::InterpreterError(InterpreterError::FailedConstraint {
+                message: Some(message),
+                ..
+            }) if message == "3 is odd, is_even: 0"
+        ));
+    }
+
+    #[test]
+    fn only_primitive_values_can_be_formatted_into_assertion_messages() {
+        let src = r#"
+        fn main(x: [Field; 2], y: u8) {
+            let z = 3;
+            assert(y == 1, f"{x} {y} {z}");
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 1, "Expected one error, got: {errors:?}");
+        assert!(matches!(
+            &errors[0].0,
+            CompilationError::TypeError(TypeCheckError::InvalidAssertMessageArgument {
+                typ: Type::Array(..),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn unbounded_recursion_in_global_is_reported() {
+        let src = r#"
+        fn count(x: u32) -> u32 {
+            if x == 0 { 0 } else { count(x - 1) + 1 }
+        }
+
+        global COUNT = count(1000);
+
+        fn main() -> pub u32 {
+            COUNT
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 1, "Expected one error, got: {errors:?}");
+        assert!(matches!(
+            &errors[0].0,
+            CompilationError::InterpreterError(InterpreterError::CallDepthExceeded { .. })
+        ));
+    }
+
+    #[test]
+    fn evaluates_globals_after_their_dependencies() {
+        let src = r#"
+        global SUM: Field = sum(TABLE);
+        global TABLE: [Field; 256] = generate_table();
+
+        fn generate_table() -> [Field; 256] {
+            let mut table = [0; 256];
+            for i in 0..256 {
+                table[i] = i as Field * 2;
+            }
+            table
+        }
+
+        fn sum(values: [Field; 256]) -> Field {
+            let mut total = 0;
+            for i in 0..256 {
+                total += values[i];
+            }
+            total
+        }
+
+        fn main(x: u8) -> pub Field {
+            TABLE[x] + SUM
+        }
+        "#;
+        let (_program, context, errors) = get_program(src);
+        assert!(errors.is_empty(), "Expected no errors, got: {errors:?}");
+
+        let main_func_id = context.def_interner.find_function("main").unwrap();
+        let program = monomorphize(main_func_id, &context.def_interner).unwrap().to_string();
+        assert!(program.contains("65280"), "{program}");
+        assert!(!program.contains("fn generate_table$"), "{program}");
+        assert!(!program.contains("fn sum$"), "{program}");
+    }
+
+    #[test]
+    fn global_dependency_cycles_are_reported() {
+        let src = r#"
+        global A: Field = double(B);
+        global B: Field = A + 1;
+        global C: Field = c();
+
+        fn double(x: Field) -> Field {
+            x * 2
+        }
+
+        fn c() -> Field {
+            C
+        }
+
+        fn main() -> pub Field {
+            A + C
+        }
+        "#;
+        let errors = get_program_errors(src);
+        let cycles = vecmap(&errors, |(error, _)| match error {
+            CompilationError::DefinitionError(DefCollectorErrorKind::DependencyCycle {
+                cycle,
+                ..
+            }) => cycle.clone(),
+            _ => panic!("Expected a dependency cycle, got: {error:?}"),
+        });
+        assert_eq!(cycles, vec!["A -> B -> A".to_string(), "C -> C".to_string()]);
+    }
+
+    #[test]
+    fn unknown_length_of_returned_array_is_an_error() {
+        let src = r#"
+        fn zeroes<N>() -> [Field; N] {
+            [0; N]
+        }
+
+        fn main() -> pub Field {
+            let known: [Field; 2] = zeroes();
+            let unknown = zeroes();
+            known[0] + unknown[0]
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 1, "Expected one error, got: {errors:?}");
+        assert!(matches!(
+            errors[0].0,
+            CompilationError::TypeError(TypeCheckError::TypeAnnotationsNeeded { .. })
+        ));
+    }
 }
@@ -0,0 +1,199 @@
+use std::rc::Rc;
+
+use fm::FileId;
+use noirc_errors::Location;
+
+use crate::hir_def::expr::{HirExpression, HirIdent};
+use crate::hir_def::stmt::{HirPattern, HirStatement};
+use crate::hir_def::types::{Type, TypeBinding, TypeVariableId, TypeVariableKind};
+use crate::node_interner::{DefinitionKind, Node, NodeInterner};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeHintKind {
+    /// The inferred type of a variable bound by a `let` statement without a type annotation.
+    LetBinding,
+    /// The inferred type of a lambda parameter without a type annotation.
+    LambdaParameter,
+    /// The values of the numeric generics of a function at a call site.
+    NumericGenerics,
+}
+
+/// Type information which the type checker inferred at a [Location] rather than being written
+/// out in the source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeHint {
+    pub kind: TypeHintKind,
+    /// The location of the code the hint is about. Hints are shown right after it.
+    pub location: Location,
+    pub label: String,
+}
+
+impl NodeInterner {
+    /// Returns the type hints for the code of the given [FileId], in the order they appear.
+    ///
+    /// This should be called once type checking has finished, so that type variables have been
+    /// bound to the types they were inferred as.
+    pub fn type_hints(&self, file: FileId) -> Vec<TypeHint> {
+        let mut hints = Vec::new();
+
+        for (_, node) in self.nodes.iter() {
+            match node {
+                Node::Statement(HirStatement::Let(let_statement))
+                    if is_inferred(&let_statement.r#type) =>
+                {
+                    self.pattern_type_hints(
+                        &let_statement.pattern,
+                        file,
+                        TypeHintKind::LetBinding,
+                        &mut hints,
+                    );
+                }
+                Node::Expression(HirExpression::Lambda(lambda)) => {
+                    for (pattern, typ) in &lambda.parameters {
+                        if is_inferred(typ) {
+                            self.pattern_type_hints(
+                                pattern,
+                                file,
+                                TypeHintKind::LambdaParameter,
+                                &mut hints,
+                            );
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        for (expr_id, bindings) in &self.instantiation_bindings {
+            let location = self.expr_location(expr_id);
+            if location.file != file {
+                continue;
+            }
+            let HirExpression::Ident(ident) = self.expression(expr_id) else {
+                continue;
+            };
+            let Some(DefinitionKind::Function(func_id)) =
+                self.try_definition(ident.id).map(|definition| &definition.kind)
+            else {
+                continue;
+            };
+            let parameters = &self.function_meta(func_id).parameters;
+
+            let mut generics: Vec<(Rc<String>, u64)> = bindings
+                .iter()
+                .filter_map(|(id, (_, binding))| {
+                    let Type::Constant(value) = binding.follow_bindings() else {
+                        return None;
+                    };
+                    let name = parameters.iter().find_map(|(_, typ, _)| named_generic(typ, *id))?;
+                    Some((name, value))
+                })
+                .collect();
+            if generics.is_empty() {
+                continue;
+            }
+            generics.sort();
+
+            let generics = generics.iter().map(|(name, value)| format!("{name} = {value}"));
+            hints.push(TypeHint {
+                kind: TypeHintKind::NumericGenerics,
+                location,
+                label: format!("<{}>", generics.collect::<Vec<_>>().join(", ")),
+            });
+        }
+
+        hints.sort_by_key(|hint| hint.location.span.end());
+        hints
+    }
+
+    /// Adds a hint with the inferred type of each variable bound by `pattern`.
+    fn pattern_type_hints(
+        &self,
+        pattern: &HirPattern,
+        file: FileId,
+        kind: TypeHintKind,
+        hints: &mut Vec<TypeHint>,
+    ) {
+        match pattern {
+            HirPattern::Identifier(ident) => {
+                if let Some(hint) = self.ident_type_hint(ident, file, kind) {
+                    hints.push(hint);
+                }
+            }
+            HirPattern::Mutable(pattern, _) => {
+                self.pattern_type_hints(pattern, file, kind, hints);
+            }
+            HirPattern::Tuple(fields, _) => {
+                for field in fields {
+                    self.pattern_type_hints(field, file, kind, hints);
+                }
+            }
+            HirPattern::Struct(_, fields, _) => {
+                for (_, field) in fields {
+                    self.pattern_type_hints(field, file, kind, hints);
+                }
+            }
+        }
+    }
+
+    fn ident_type_hint(
+        &self,
+        ident: &HirIdent,
+        file: FileId,
+        kind: TypeHintKind,
+    ) -> Option<TypeHint> {
+        if ident.location.file != file {
+            return None;
+        }
+        let typ = self.id_type(ident.id).follow_bindings();
+        if matches!(typ, Type::Error | Type::TypeVariable(_, TypeVariableKind::Normal)) {
+            // The type couldn't be inferred, so there's nothing useful to show.
+            return None;
+        }
+        Some(TypeHint { kind, location: ident.location, label: format!(": {typ}") })
+    }
+}
+
+/// True if `typ` was left for the type checker to infer, rather than being annotated.
+fn is_inferred(typ: &Type) -> bool {
+    matches!(typ, Type::TypeVariable(..))
+}
+
+/// Returns the name of the generic with the given [TypeVariableId] if it occurs within `typ`.
+fn named_generic(typ: &Type, target_id: TypeVariableId) -> Option<Rc<String>> {
+    match typ {
+        Type::NamedGeneric(type_variable, name) => match &*type_variable.borrow() {
+            TypeBinding::Unbound(id) if *id == target_id => Some(name.clone()),
+            _ => None,
+        },
+        Type::Array(length, element) => {
+            named_generic(length, target_id).or_else(|| named_generic(element, target_id))
+        }
+        Type::String(length) => named_generic(length, target_id),
+        Type::FmtString(length, fields) => {
+            named_generic(length, target_id).or_else(|| named_generic(fields, target_id))
+        }
+        Type::InfixExpr(lhs, _, rhs) => {
+            named_generic(lhs, target_id).or_else(|| named_generic(rhs, target_id))
+        }
+        Type::Struct(_, generics) | Type::Tuple(generics) | Type::TraitAsType(_, _, generics) => {
+            generics.iter().find_map(|typ| named_generic(typ, target_id))
+        }
+        Type::Function(arguments, return_type, environment) => arguments
+            .iter()
+            .chain([return_type.as_ref(), environment.as_ref()])
+            .find_map(|typ| named_generic(typ, target_id)),
+        Type::MutableReference(element) | Type::Forall(_, element) => {
+            named_generic(element, target_id)
+        }
+        Type::FieldElement
+        | Type::Integer(..)
+        | Type::Bool
+        | Type::Unit
+        | Type::TypeVariable(..)
+        | Type::Constant(_)
+        | Type::Value(_)
+        | Type::NotConstant
+        | Type::Error => None,
+    }
+}
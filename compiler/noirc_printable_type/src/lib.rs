@@ -32,6 +32,12 @@ pub enum PrintableType {
     String {
         length: u64,
     },
+    /// A format string, along with the types of the values to be interpolated into it.
+    FmtString {
+        length: u64,
+        #[serde(rename = "type")]
+        typ: Box<PrintableType>,
+    },
     Function {
         env: Box<PrintableType>,
     },
@@ -64,9 +70,18 @@ pub enum ForeignCallError {
     #[error("Foreign call inputs needed for execution are missing")]
     MissingForeignCallInputs,
 
+    #[error("Expected the PrintableType of a format string but got {0:?}")]
+    ExpectedFmtString(PrintableType),
+
     #[error("Could not parse PrintableType argument. {0}")]
     ParsingError(#[from] serde_json::Error),
 
+    #[error("Log level passed to the log oracle is not one of debug, info, warn or error")]
+    InvalidLogLevel,
+
+    #[error("The parameters of mock {0} were requested but it has not been called")]
+    MockNotCalled(usize),
+
     #[error("Failed calling external resolver. {0}")]
     ExternalResolverError(#[from] jsonrpc::Error),
 }
@@ -107,31 +122,70 @@ fn convert_string_inputs(
 fn convert_fmt_string_inputs(
     foreign_call_inputs: &[ForeignCallParam],
 ) -> Result<PrintableValueDisplay, ForeignCallError> {
-    let (message, input_and_printable_types) =
-        foreign_call_inputs.split_first().ok_or(ForeignCallError::MissingForeignCallInputs)?;
+    // The format string is passed as a single value, described by the last input,
+    // which holds its template followed by the values to be interpolated into it.
+    let (printable_type_as_values, input_values) =
+        foreign_call_inputs.split_last().ok_or(ForeignCallError::MissingForeignCallInputs)?;
+    let printable_type = fetch_printable_type(printable_type_as_values)?;
+    let (length, types) = fmt_string_types(printable_type)?;
 
-    let message_as_fields = vecmap(message.values(), |value| value.to_field());
+    let mut input_values_as_fields =
+        input_values.iter().flat_map(|param| vecmap(param.values(), |value| value.to_field()));
+
+    let message_as_fields: Vec<FieldElement> =
+        input_values_as_fields.by_ref().take(length as usize).collect();
     let message_as_string = decode_string_value(&message_as_fields);
 
-    let (num_values, input_and_printable_types) = input_and_printable_types
-        .split_first()
-        .ok_or(ForeignCallError::MissingForeignCallInputs)?;
+    // Skip over the number of values in the format string as it is implied by its type.
+    input_values_as_fields.next().ok_or(ForeignCallError::MissingForeignCallInputs)?;
 
-    let mut output = Vec::new();
-    let num_values = num_values.unwrap_value().to_field().to_u128() as usize;
+    let output = decode_fmt_string_values(types, &mut input_values_as_fields);
 
-    let types_start_at = input_and_printable_types.len() - num_values;
-    let mut input_iter = input_and_printable_types[0..types_start_at]
-        .iter()
-        .flat_map(|param| vecmap(param.values(), |value| value.to_field()));
-    for printable_type in input_and_printable_types.iter().skip(types_start_at) {
-        let printable_type = fetch_printable_type(printable_type)?;
-        let value = decode_value(&mut input_iter, &printable_type);
+    Ok(PrintableValueDisplay::FmtString(message_as_string, output))
+}
 
-        output.push((value, printable_type));
+impl PrintableValueDisplay {
+    /// Decodes the values formatted into `template` from `values`, given the serialized
+    /// [`PrintableType`] of the format string.
+    ///
+    /// This is used for the messages of failed assertions, whose templates are known ahead of
+    /// execution so only the values interpolated into them are provided. There must be enough
+    /// `values` to decode each of them.
+    pub fn try_from_fmt_string_values(
+        template: String,
+        printable_type: &str,
+        values: impl IntoIterator<Item = FieldElement>,
+    ) -> Result<Self, ForeignCallError> {
+        let printable_type: PrintableType = serde_json::from_str(printable_type)?;
+        let (_, types) = fmt_string_types(printable_type)?;
+        let output = decode_fmt_string_values(types, &mut values.into_iter());
+
+        Ok(PrintableValueDisplay::FmtString(template, output))
     }
+}
 
-    Ok(PrintableValueDisplay::FmtString(message_as_string, output))
+/// Returns the length of the template of a format string of type `printable_type` along with the
+/// types of the values interpolated into it.
+fn fmt_string_types(
+    printable_type: PrintableType,
+) -> Result<(u64, Vec<PrintableType>), ForeignCallError> {
+    match printable_type {
+        PrintableType::FmtString { length, typ } => match *typ {
+            PrintableType::Tuple { types } => Ok((length, types)),
+            typ => Err(ForeignCallError::ExpectedFmtString(typ)),
+        },
+        typ => Err(ForeignCallError::ExpectedFmtString(typ)),
+    }
+}
+
+fn decode_fmt_string_values(
+    types: Vec<PrintableType>,
+    field_iterator: &mut impl Iterator<Item = FieldElement>,
+) -> Vec<(PrintableValue, PrintableType)> {
+    vecmap(types, |printable_type| {
+        let value = decode_value(field_iterator, &printable_type);
+        (value, printable_type)
+    })
 }
 
 fn fetch_printable_type(
@@ -151,7 +205,7 @@ fn to_string(value: &PrintableValue, typ: &PrintableType) -> Option<String> {
             output.push_str(&format_field_string(*f));
         }
         (PrintableValue::Field(f), PrintableType::UnsignedInteger { width }) => {
-            let uint_cast = f.to_u128() & ((1 << width) - 1); // Retain the lower 'width' bits
+            let uint_cast = f.to_u128() & width_mask(*width); // Retain the lower 'width' bits
             output.push_str(&uint_cast.to_string());
         }
         (PrintableValue::Field(f), PrintableType::SignedInteger { width }) => {
@@ -160,7 +214,7 @@ fn to_string(value: &PrintableValue, typ: &PrintableType) -> Option<String> {
             // Extract sign relative to width of input
             if (uint >> (width - 1)) == 1 {
                 output.push('-');
-                uint = (uint ^ ((1 << width) - 1)) + 1; // Two's complement relative to width of input
+                uint = (uint ^ width_mask(*width)) + 1; // Two's complement relative to width of input
             }
 
             output.push_str(&uint.to_string());
@@ -193,7 +247,10 @@ fn to_string(value: &PrintableValue, typ: &PrintableType) -> Option<String> {
             output.push(']');
         }
 
-        (PrintableValue::String(s), PrintableType::String { .. }) => {
+        (
+            PrintableValue::String(s),
+            PrintableType::String { .. } | PrintableType::FmtString { .. },
+        ) => {
             output.push_str(s);
         }
 
@@ -292,6 +349,11 @@ fn format_field_string(field: FieldElement) -> String {
     "0x".to_owned() + &trimmed_field
 }
 
+/// Returns the mask retaining the lower `width` bits of an integer.
+fn width_mask(width: u32) -> u128 {
+    u128::MAX.checked_shr(128 - width).unwrap_or(0)
+}
+
 /// Assumes that `field_iterator` contains enough [FieldElement] in order to decode the [PrintableType]
 fn decode_value(
     field_iterator: &mut impl Iterator<Item = FieldElement>,
@@ -335,6 +397,15 @@ fn decode_value(
 
             PrintableValue::String(decode_string_value(&field_elements))
         }
+        PrintableType::FmtString { length, typ } => {
+            // Format strings are only printed at the top level, in which case their template
+            // and contents are decoded by the caller. A nested format string prints as its template.
+            let field_elements: Vec<FieldElement> = field_iterator.take(*length as usize).collect();
+            field_iterator.next();
+            decode_value(field_iterator, typ);
+
+            PrintableValue::String(decode_string_value(&field_elements))
+        }
         PrintableType::Struct { fields, .. } => {
             let mut struct_map = BTreeMap::new();
 
@@ -41,6 +41,7 @@ export type ProgramArtifact = {
     hash: number;
     abi: any;
     bytecode: string;
+    functions?: string[];
     debug_symbols: any;
     file_map: Record<number, any>;
 }
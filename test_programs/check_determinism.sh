@@ -0,0 +1,19 @@
+#!/usr/bin/env bash
+set -e
+
+# These tests are not a single package so are compiled separately by the test suite.
+excluded_dirs=("workspace" "workspace_default_member")
+
+current_dir=$(pwd)
+base_path="$current_dir/execution_success"
+test_dirs=$(ls $base_path)
+
+# Each program is compiled twice, failing if the two compilations produce different circuits.
+for dir in $test_dirs; do
+    if [[ " ${excluded_dirs[@]} " =~ " ${dir} " ]]; then
+      continue
+    fi
+
+    echo "Checking $dir"
+    nargo --program-dir "$base_path/$dir" compile --force --check-determinism
+done
@@ -13,6 +13,8 @@ pub(crate) struct InfoCommand {
 #[derive(Deserialize)]
 struct InfoResponse {
     language: LanguageResponse,
+    #[serde(default)]
+    black_box_functions_supported: Option<Vec<String>>,
 }
 
 #[derive(Deserialize)]
@@ -21,8 +23,15 @@ struct LanguageResponse {
     width: Option<usize>,
 }
 
+/// The circuits which a backend can prove.
+pub(crate) struct BackendInfo {
+    pub(crate) expression_width: ExpressionWidth,
+    /// The names of the black box functions the backend supports, `None` if it doesn't list them.
+    pub(crate) black_box_functions: Option<Vec<String>>,
+}
+
 impl InfoCommand {
-    pub(crate) fn run(self, binary_path: &Path) -> Result<ExpressionWidth, BackendError> {
+    pub(crate) fn run(self, binary_path: &Path) -> Result<BackendInfo, BackendError> {
         let mut command = std::process::Command::new(binary_path);
 
         command.arg("info").arg("-c").arg(self.crs_path).arg("-o").arg("-");
@@ -44,7 +53,10 @@ impl InfoCommand {
             _ => panic!("Unknown Expression width configuration"),
         };
 
-        Ok(expression_width)
+        Ok(BackendInfo {
+            expression_width,
+            black_box_functions: backend_info.black_box_functions_supported,
+        })
     }
 }
 
@@ -53,9 +65,11 @@ fn info_command() -> Result<(), BackendError> {
     let backend = crate::get_mock_backend()?;
     let crs_path = backend.backend_directory();
 
-    let expression_width = InfoCommand { crs_path }.run(backend.binary_path())?;
+    let backend_info = InfoCommand { crs_path }.run(backend.binary_path())?;
 
-    assert!(matches!(expression_width, ExpressionWidth::Bounded { width: 3 }));
+    assert!(matches!(backend_info.expression_width, ExpressionWidth::Bounded { width: 3 }));
+    let black_box_functions = backend_info.black_box_functions.unwrap();
+    assert!(black_box_functions.contains(&"keccak256".to_string()));
 
     Ok(())
 }
@@ -36,6 +36,6 @@ fn no_command_provided_works() -> Result<(), crate::BackendError> {
 }
 
 // Converts a stderr byte array to a string (including invalid characters)
-fn string_from_stderr(stderr: &[u8]) -> String {
+pub(crate) fn string_from_stderr(stderr: &[u8]) -> String {
     String::from_utf8_lossy(stderr).to_string()
 }
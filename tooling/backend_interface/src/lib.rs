@@ -1,26 +1,58 @@
 #![warn(unused_crate_dependencies, unused_extern_crates)]
 #![warn(unreachable_pub)]
 
-use std::path::PathBuf;
+use std::{collections::BTreeMap, path::PathBuf, sync::OnceLock};
 
 mod cli;
 mod download;
 mod proof_system;
+mod protocol;
 mod smart_contract;
 
 pub use bb_abstraction_leaks::ACVM_BACKEND_BARRETENBERG;
 use bb_abstraction_leaks::BB_VERSION;
 use cli::VersionCommand;
 pub use download::download_backend;
+pub use protocol::Capability;
+use protocol::{Handshake, ProtocolBackend};
 use tracing::warn;
 
 const BACKENDS_DIR: &str = ".nargo/backends";
 
+/// The prefix of the executables on the `PATH` which are discovered as backends, e.g. the backend
+/// `foo` is run from `nargo-backend-foo`.
+pub const BACKEND_EXECUTABLE_PREFIX: &str = "nargo-backend-";
+
 pub fn backends_directory() -> PathBuf {
     let home_directory = dirs::home_dir().unwrap();
     home_directory.join(BACKENDS_DIR)
 }
 
+/// Returns the backends found on the `PATH` by the name of their executable, along with its path.
+/// The first executable of each name on the `PATH` is the one returned.
+pub fn discover_backends() -> BTreeMap<String, PathBuf> {
+    let mut backends = BTreeMap::new();
+    let Some(paths) = std::env::var_os("PATH") else {
+        return backends;
+    };
+    for dir in std::env::split_paths(&paths) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let file_name =
+                file_name.strip_suffix(std::env::consts::EXE_SUFFIX).unwrap_or(&file_name);
+            if let Some(name) = file_name.strip_prefix(BACKEND_EXECUTABLE_PREFIX) {
+                if !name.is_empty() && entry.path().is_file() {
+                    backends.entry(name.to_string()).or_insert_with(|| entry.path());
+                }
+            }
+        }
+    }
+    backends
+}
+
 #[cfg(test)]
 test_binary::build_test_binary_once!(mock_backend, "test-binaries");
 
@@ -52,24 +84,43 @@ pub enum BackendError {
 
     #[error("The backend encountered an error: {0:?}")]
     CommandFailed(String),
+
+    #[error("The backend responded with a malformed message: {0}")]
+    MalformedResponse(String),
+
+    #[error(
+        "The backend speaks version {0} of the backend protocol which is not supported by nargo"
+    )]
+    UnsupportedProtocolVersion(u32),
+
+    #[error("The backend does not support `{0}`")]
+    MissingCapability(Capability),
 }
 
 #[derive(Debug)]
 pub struct Backend {
     name: String,
     binary_path: PathBuf,
+    /// The handshake of the backend protocol, `None` for backends driven through the barretenberg
+    /// command line interface.
+    handshake: OnceLock<Option<Handshake>>,
 }
 
 impl Backend {
+    /// Creates the backend `name`, which is run from `NARGO_BACKEND_PATH` if set, otherwise from
+    /// its installation in the backends directory or else from an executable on the `PATH`.
     pub fn new(name: String) -> Backend {
+        const BINARY_NAME: &str = "backend_binary";
+
+        let installed_path = backends_directory().join(&name).join(BINARY_NAME);
         let binary_path = if let Some(binary_path) = std::env::var_os("NARGO_BACKEND_PATH") {
             PathBuf::from(binary_path)
+        } else if installed_path.is_file() {
+            installed_path
         } else {
-            const BINARY_NAME: &str = "backend_binary";
-
-            backends_directory().join(&name).join(BINARY_NAME)
+            discover_backends().remove(&name).unwrap_or(installed_path)
         };
-        Backend { name, binary_path }
+        Backend { name, binary_path, handshake: OnceLock::new() }
     }
 
     pub fn name(&self) -> &str {
@@ -107,6 +158,26 @@ impl Backend {
         self.backend_directory().join("crs")
     }
 
+    /// Returns the backend as driven through the backend protocol, or `None` if it doesn't speak
+    /// it. Barretenberg is always driven through its command line interface.
+    ///
+    /// Failed handshakes aren't remembered, so that they are reported by each command.
+    fn protocol(&self) -> Result<Option<ProtocolBackend<'_>>, BackendError> {
+        if self.name == ACVM_BACKEND_BARRETENBERG {
+            return Ok(None);
+        }
+        let handshake = match self.handshake.get() {
+            Some(handshake) => handshake,
+            None => {
+                let handshake = protocol::handshake(self.binary_path())?;
+                self.handshake.get_or_init(|| handshake)
+            }
+        };
+        Ok(handshake
+            .as_ref()
+            .map(|handshake| ProtocolBackend { binary_path: self.binary_path(), handshake }))
+    }
+
     fn assert_correct_version(&self) -> Result<&PathBuf, BackendError> {
         let binary_path = self.binary_path();
         if binary_path.to_string_lossy().contains(ACVM_BACKEND_BARRETENBERG) {
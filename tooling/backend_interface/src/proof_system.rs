@@ -17,6 +17,9 @@ use crate::{Backend, BackendError};
 impl Backend {
     pub fn get_exact_circuit_size(&self, circuit: &Circuit) -> Result<u32, BackendError> {
         let binary_path = self.assert_binary_exists()?;
+        if let Some(backend) = self.protocol()? {
+            return backend.gates(circuit);
+        }
         self.assert_correct_version()?;
 
         let temp_directory = tempdir().expect("could not create a temporary directory");
@@ -33,8 +36,24 @@ impl Backend {
 
     pub fn get_backend_info(&self) -> Result<ExpressionWidth, BackendError> {
         let binary_path = self.assert_binary_exists()?;
+        if let Some(backend) = self.protocol()? {
+            return backend.expression_width();
+        }
         self.assert_correct_version()?;
-        InfoCommand { crs_path: self.crs_directory() }.run(binary_path)
+        let backend_info = InfoCommand { crs_path: self.crs_directory() }.run(binary_path)?;
+        Ok(backend_info.expression_width)
+    }
+
+    /// Returns the names of the black box functions which the backend supports, or `None` if it
+    /// doesn't report which ones it supports.
+    pub fn get_supported_black_boxes(&self) -> Result<Option<Vec<String>>, BackendError> {
+        let binary_path = self.assert_binary_exists()?;
+        if let Some(backend) = self.protocol()? {
+            return backend.black_box_functions();
+        }
+        self.assert_correct_version()?;
+        let backend_info = InfoCommand { crs_path: self.crs_directory() }.run(binary_path)?;
+        Ok(backend_info.black_box_functions)
     }
 
     /// If we cannot get a valid backend, returns `ExpressionWidth::Bound { width: 3 }``
@@ -58,6 +77,9 @@ impl Backend {
         is_recursive: bool,
     ) -> Result<Vec<u8>, BackendError> {
         let binary_path = self.assert_binary_exists()?;
+        if let Some(backend) = self.protocol()? {
+            return backend.prove(circuit, witness_values, is_recursive);
+        }
         self.assert_correct_version()?;
 
         let temp_directory = tempdir().expect("could not create a temporary directory");
@@ -100,6 +122,9 @@ impl Backend {
         is_recursive: bool,
     ) -> Result<bool, BackendError> {
         let binary_path = self.assert_binary_exists()?;
+        if let Some(backend) = self.protocol()? {
+            return backend.verify(proof, public_inputs, circuit, is_recursive);
+        }
         self.assert_correct_version()?;
 
         let temp_directory = tempdir().expect("could not create a temporary directory");
@@ -131,6 +156,35 @@ impl Backend {
             .run(binary_path)
     }
 
+    /// Returns the verification key of `circuit`, which verifier contracts are generated from.
+    pub fn get_verification_key(&self, circuit: &Circuit) -> Result<Vec<u8>, BackendError> {
+        let binary_path = self.assert_binary_exists()?;
+        if let Some(backend) = self.protocol()? {
+            return backend.verification_key(circuit);
+        }
+        self.assert_correct_version()?;
+
+        let temp_directory = tempdir().expect("could not create a temporary directory");
+        let temp_directory = temp_directory.path().to_path_buf();
+
+        // Create a temporary file for the circuit
+        let bytecode_path = temp_directory.join("circuit").with_extension("bytecode");
+        let serialized_circuit = Circuit::serialize_circuit(circuit);
+        write_to_file(&serialized_circuit, &bytecode_path);
+
+        // Create the verification key and write it to the specified path
+        let vk_path = temp_directory.join("vk");
+
+        WriteVkCommand {
+            crs_path: self.crs_directory(),
+            bytecode_path,
+            vk_path_output: vk_path.clone(),
+        }
+        .run(binary_path)?;
+
+        Ok(std::fs::read(vk_path)?)
+    }
+
     pub fn get_intermediate_proof_artifacts(
         &self,
         circuit: &Circuit,
@@ -138,6 +192,11 @@ impl Backend {
         public_inputs: WitnessMap,
     ) -> Result<(Vec<FieldElement>, FieldElement, Vec<FieldElement>), BackendError> {
         let binary_path = self.assert_binary_exists()?;
+        if self.protocol()?.is_some() {
+            return Err(BackendError::CommandFailed(
+                "intermediate proof artifacts are only available from barretenberg".to_string(),
+            ));
+        }
         self.assert_correct_version()?;
 
         let temp_directory = tempdir().expect("could not create a temporary directory");
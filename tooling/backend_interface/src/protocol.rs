@@ -0,0 +1,350 @@
+//! The JSON protocol through which nargo drives proving backends other than barretenberg, so that
+//! they can be used without nargo knowing about their command line interface.
+//!
+//! Nargo runs the backend executable as `<backend> nargo-protocol`, writes a single request to its
+//! stdin and then reads a single response from its stdout. Binary data is hex encoded:
+//!
+//! ```json
+//! {"method": "prove", "params": {"bytecode": "1f8b...", "witness": "1f8b...", "recursive": false}}
+//! {"result": {"proof": "0a3c..."}}
+//! ```
+//!
+//! A backend reports a failure as `{"error": "<message>"}`.
+//!
+//! The first request is a `handshake` listing the versions of the protocol nargo supports, to which
+//! the backend replies with the version it speaks, the requests it supports, the width of the
+//! expressions it accepts and the black box functions it can prove:
+//!
+//! ```json
+//! {"method": "handshake", "params": {"versions": [1]}}
+//! {"result": {"version": 1, "capabilities": ["prove", "verify", "gates"], "expression_width": 3,
+//!     "black_box_functions": ["range", "and", "xor", "sha256"]}}
+//! ```
+//!
+//! A backend which doesn't answer the handshake with a response of the protocol, such as one which
+//! doesn't know the `nargo-protocol` command, is driven through the barretenberg command line
+//! interface. A backend which answers with an error speaks the protocol, so the error is reported.
+use std::{
+    collections::BTreeMap,
+    io::Write,
+    path::Path,
+    process::{Command, Output, Stdio},
+};
+
+use acvm::{
+    acir::{circuit::Circuit, native_types::WitnessMap},
+    ExpressionWidth,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{cli::string_from_stderr, BackendError};
+
+/// The argument given to a backend to make it read a request of the protocol.
+const PROTOCOL_COMMAND: &str = "nargo-protocol";
+
+/// The versions of the protocol which nargo speaks.
+const PROTOCOL_VERSIONS: [u32; 1] = [1];
+
+/// A request of the protocol which a backend may support.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Capability {
+    Prove,
+    Verify,
+    Gates,
+    Contract,
+    VerificationKey,
+}
+
+impl std::fmt::Display for Capability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Capability::Prove => write!(f, "prove"),
+            Capability::Verify => write!(f, "verify"),
+            Capability::Gates => write!(f, "gates"),
+            Capability::Contract => write!(f, "contract"),
+            Capability::VerificationKey => write!(f, "verification_key"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct Handshake {
+    version: u32,
+    capabilities: Vec<Capability>,
+    /// Unbounded if not given.
+    #[serde(default)]
+    expression_width: Option<usize>,
+    /// All black box functions are supported if not given.
+    #[serde(default)]
+    black_box_functions: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "method", content = "params", rename_all = "snake_case")]
+enum Request {
+    Handshake {
+        versions: Vec<u32>,
+    },
+    Prove {
+        bytecode: String,
+        witness: String,
+        recursive: bool,
+    },
+    Verify {
+        bytecode: String,
+        proof: String,
+        public_inputs: BTreeMap<u32, String>,
+        recursive: bool,
+    },
+    Gates {
+        bytecode: String,
+    },
+    Contract {
+        bytecode: String,
+    },
+    VerificationKey {
+        bytecode: String,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Response<T> {
+    Ok { result: T },
+    Err { error: String },
+}
+
+#[derive(Deserialize)]
+struct ProveResponse {
+    proof: String,
+}
+
+#[derive(Deserialize)]
+struct VerifyResponse {
+    valid: bool,
+}
+
+#[derive(Deserialize)]
+struct GatesResponse {
+    gates: u32,
+}
+
+#[derive(Deserialize)]
+struct ContractResponse {
+    contract: String,
+}
+
+#[derive(Deserialize)]
+struct VerificationKeyResponse {
+    verification_key: String,
+}
+
+/// Returns the handshake of the backend at `binary_path`, or `None` if it doesn't speak the
+/// protocol.
+pub(crate) fn handshake(binary_path: &Path) -> Result<Option<Handshake>, BackendError> {
+    let request = Request::Handshake { versions: PROTOCOL_VERSIONS.to_vec() };
+    let output = run(binary_path, &request)?;
+
+    // Backends which don't speak the protocol fail on the unknown command, or print something
+    // else than a response, regardless of their exit status.
+    match serde_json::from_slice(&output.stdout) {
+        Ok(Response::Ok { result }) => serde_json::from_value(result)
+            .map(Some)
+            .map_err(|error| BackendError::MalformedResponse(error.to_string())),
+        Ok(Response::Err { error }) => Err(BackendError::CommandFailed(error)),
+        Err(_) => Ok(None),
+    }
+}
+
+/// A backend which passed the handshake.
+pub(crate) struct ProtocolBackend<'a> {
+    pub(crate) binary_path: &'a Path,
+    pub(crate) handshake: &'a Handshake,
+}
+
+impl ProtocolBackend<'_> {
+    pub(crate) fn expression_width(&self) -> Result<ExpressionWidth, BackendError> {
+        self.check_version()?;
+        Ok(match self.handshake.expression_width {
+            Some(width) => ExpressionWidth::Bounded { width },
+            None => ExpressionWidth::Unbounded,
+        })
+    }
+
+    pub(crate) fn black_box_functions(&self) -> Result<Option<Vec<String>>, BackendError> {
+        self.check_version()?;
+        Ok(self.handshake.black_box_functions.clone())
+    }
+
+    pub(crate) fn gates(&self, circuit: &Circuit) -> Result<u32, BackendError> {
+        self.require(Capability::Gates)?;
+        let bytecode = hex::encode(Circuit::serialize_circuit(circuit));
+        let response: GatesResponse = send(self.binary_path, &Request::Gates { bytecode })?;
+        Ok(response.gates)
+    }
+
+    pub(crate) fn prove(
+        &self,
+        circuit: &Circuit,
+        witness_values: WitnessMap,
+        recursive: bool,
+    ) -> Result<Vec<u8>, BackendError> {
+        self.require(Capability::Prove)?;
+        let witness: Vec<u8> = witness_values.try_into().expect("could not serialize witness map");
+        let request = Request::Prove {
+            bytecode: hex::encode(Circuit::serialize_circuit(circuit)),
+            witness: hex::encode(witness),
+            recursive,
+        };
+        let response: ProveResponse = send(self.binary_path, &request)?;
+        decode_hex(&response.proof)
+    }
+
+    pub(crate) fn verify(
+        &self,
+        proof: &[u8],
+        public_inputs: WitnessMap,
+        circuit: &Circuit,
+        recursive: bool,
+    ) -> Result<bool, BackendError> {
+        self.require(Capability::Verify)?;
+        let request = Request::Verify {
+            bytecode: hex::encode(Circuit::serialize_circuit(circuit)),
+            proof: hex::encode(proof),
+            public_inputs: public_inputs
+                .into_iter()
+                .map(|(witness, value)| (witness.0, value.to_hex()))
+                .collect(),
+            recursive,
+        };
+        let response: VerifyResponse = send(self.binary_path, &request)?;
+        Ok(response.valid)
+    }
+
+    pub(crate) fn contract(&self, circuit: &Circuit) -> Result<String, BackendError> {
+        self.require(Capability::Contract)?;
+        let bytecode = hex::encode(Circuit::serialize_circuit(circuit));
+        let response: ContractResponse = send(self.binary_path, &Request::Contract { bytecode })?;
+        Ok(response.contract)
+    }
+
+    pub(crate) fn verification_key(&self, circuit: &Circuit) -> Result<Vec<u8>, BackendError> {
+        self.require(Capability::VerificationKey)?;
+        let bytecode = hex::encode(Circuit::serialize_circuit(circuit));
+        let response: VerificationKeyResponse =
+            send(self.binary_path, &Request::VerificationKey { bytecode })?;
+        decode_hex(&response.verification_key)
+    }
+
+    fn require(&self, capability: Capability) -> Result<(), BackendError> {
+        self.check_version()?;
+        if self.handshake.capabilities.contains(&capability) {
+            Ok(())
+        } else {
+            Err(BackendError::MissingCapability(capability))
+        }
+    }
+
+    fn check_version(&self) -> Result<(), BackendError> {
+        if PROTOCOL_VERSIONS.contains(&self.handshake.version) {
+            Ok(())
+        } else {
+            Err(BackendError::UnsupportedProtocolVersion(self.handshake.version))
+        }
+    }
+}
+
+/// Sends `request` to the backend at `binary_path` and returns its result.
+fn send<T: DeserializeOwned>(binary_path: &Path, request: &Request) -> Result<T, BackendError> {
+    let output = run(binary_path, request)?;
+    if !output.status.success() {
+        return Err(BackendError::CommandFailed(string_from_stderr(&output.stderr)));
+    }
+
+    match serde_json::from_slice(&output.stdout) {
+        Ok(Response::Ok { result }) => Ok(result),
+        Ok(Response::Err { error }) => Err(BackendError::CommandFailed(error)),
+        Err(error) => Err(BackendError::MalformedResponse(error.to_string())),
+    }
+}
+
+/// Runs the backend at `binary_path` on `request`, returning its output once it exits.
+fn run(binary_path: &Path, request: &Request) -> Result<Output, BackendError> {
+    let mut child = Command::new(binary_path)
+        .arg(PROTOCOL_COMMAND)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // Stdin is closed once the request is written, so that the backend knows it has all of it.
+    let request = serde_json::to_vec(request).expect("request should serialize to JSON");
+    child.stdin.take().expect("stdin should be piped").write_all(&request)?;
+
+    Ok(child.wait_with_output()?)
+}
+
+fn decode_hex(value: &str) -> Result<Vec<u8>, BackendError> {
+    hex::decode(value).map_err(|error| BackendError::MalformedResponse(error.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_requests() {
+        let request = Request::Gates { bytecode: "00".to_string() };
+        assert_eq!(
+            serde_json::to_string(&request).unwrap(),
+            r#"{"method":"gates","params":{"bytecode":"00"}}"#
+        );
+
+        let handshake: Response<Handshake> = serde_json::from_str(
+            r#"{"result": {"version": 1, "capabilities": ["prove", "gates"]}}"#,
+        )
+        .unwrap();
+        let Response::Ok { result: handshake } = handshake else {
+            panic!("Expected a successful handshake");
+        };
+        let backend = ProtocolBackend { binary_path: Path::new("backend"), handshake: &handshake };
+        assert!(matches!(backend.expression_width(), Ok(ExpressionWidth::Unbounded)));
+        assert!(matches!(
+            backend.require(Capability::Contract),
+            Err(BackendError::MissingCapability(Capability::Contract))
+        ));
+    }
+
+    #[test]
+    fn barretenberg_cli_fails_handshake() -> Result<(), BackendError> {
+        let backend = crate::get_mock_backend()?;
+        assert!(handshake(backend.binary_path())?.is_none());
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reports_handshake_errors() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_directory = tempfile::tempdir().unwrap();
+        let binary_path = temp_directory.path().join("backend");
+        let write_backend = |script: &str| {
+            std::fs::write(&binary_path, format!("#!/bin/sh\n{script}\n")).unwrap();
+            std::fs::set_permissions(&binary_path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        };
+
+        write_backend(r#"echo '{"error": "the license has expired"}'"#);
+        assert!(matches!(
+            handshake(&binary_path),
+            Err(BackendError::CommandFailed(error)) if error == "the license has expired"
+        ));
+
+        write_backend(r#"echo '{"result": {"version": "one"}}'"#);
+        assert!(matches!(handshake(&binary_path), Err(BackendError::MalformedResponse(_))));
+
+        write_backend("echo 'Unknown command' >&2; exit 1");
+        assert!(matches!(handshake(&binary_path), Ok(None)));
+    }
+}
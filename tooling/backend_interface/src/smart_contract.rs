@@ -9,6 +9,9 @@ use tempfile::tempdir;
 impl Backend {
     pub fn eth_contract(&self, circuit: &Circuit) -> Result<String, BackendError> {
         let binary_path = self.assert_binary_exists()?;
+        if let Some(backend) = self.protocol()? {
+            return backend.contract(circuit);
+        }
         self.assert_correct_version()?;
 
         let temp_directory = tempdir().expect("could not create a temporary directory");
@@ -22,6 +22,7 @@ const INFO_RESPONSE: &str = r#"{
         "ecdsa_secp256k1",
         "ecdsa_secp256r1",
         "fixed_base_scalar_mul",
+        "poseidon2_permutation",
         "recursive_aggregation"
     ]
 }"#;
@@ -1,8 +1,14 @@
-use acvm::acir::circuit::{Circuit, Opcode, OpcodeLocation};
-use acvm::acir::native_types::{Witness, WitnessMap};
-use acvm::brillig_vm::{brillig::Value, Registers};
+use acvm::acir::circuit::brillig::BrilligInputs;
+use acvm::acir::circuit::directives::Directive;
+use acvm::acir::circuit::{Circuit, Opcode, OpcodeLocation, ASSERT_MESSAGE_VALUES_ORACLE};
+use acvm::acir::native_types::{Expression, Witness, WitnessMap};
+use acvm::brillig_vm::{
+    brillig::{ForeignCallResult, Value},
+    Registers,
+};
 use acvm::pwg::{
-    ACVMStatus, BrilligSolver, BrilligSolverStatus, ForeignCallWaitInfo, StepResult, ACVM,
+    get_value, ACVMStatus, BrilligSolver, BrilligSolverStatus, ForeignCallWaitInfo, StepResult,
+    ACVM,
 };
 use acvm::{BlackBoxFunctionSolver, FieldElement};
 
@@ -10,23 +16,176 @@ use nargo::artifacts::debug::DebugArtifact;
 use nargo::errors::{ExecutionError, Location};
 use nargo::ops::ForeignCallExecutor;
 use nargo::NargoError;
+use noirc_frontend::monomorphization::DEBUG_VAR_ASSIGN_ORACLE;
+
+use crate::debug_vars::DebugVars;
 
-use std::collections::{hash_set::Iter, HashSet};
+use std::collections::{hash_set::Iter, BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
 
 #[derive(Debug)]
 pub(super) enum DebugCommandResult {
     Done,
     Ok,
     BreakpointReached(OpcodeLocation),
+    WatchpointTriggered(WatchpointHit),
     Error(NargoError),
 }
 
+/// A value which execution pauses on when it changes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(super) enum Watchpoint {
+    /// A witness of the circuit, written as `_<index>`
+    Witness(Witness),
+    /// A cell of the memory of the Brillig block being executed, written as `memory[<index>]`
+    BrilligMemory(usize),
+    /// A variable of the program, or one of the elements of an array variable, written as
+    /// `<name>` or `<name>[<index>]`. Variables can only be watched in programs compiled with
+    /// debug instrumentation, which reports each value assigned to them.
+    Variable { name: String, index: Option<usize> },
+}
+
+impl fmt::Display for Watchpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Watchpoint::Witness(witness) => write!(f, "_{}", witness.witness_index()),
+            Watchpoint::BrilligMemory(index) => write!(f, "memory[{index}]"),
+            Watchpoint::Variable { name, index: None } => write!(f, "{name}"),
+            Watchpoint::Variable { name, index: Some(index) } => write!(f, "{name}[{index}]"),
+        }
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error(
+    "Invalid watchpoint {0}, expected `_<index>`, `memory[<index>]`, `<variable>` or `<variable>[<index>]`"
+)]
+pub(super) struct WatchpointFromStrError(String);
+
+impl FromStr for Watchpoint {
+    type Err = WatchpointFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || WatchpointFromStrError(s.to_owned());
+        let (name, index) = match s.strip_suffix(']').and_then(|s| s.split_once('[')) {
+            Some((name, index)) => (name, Some(index.parse().map_err(|_| error())?)),
+            None => (s, None),
+        };
+
+        let is_identifier = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        // Witnesses and Brillig memory take precedence over variables of the same name.
+        match (name.strip_prefix('_').map(str::parse), index) {
+            (Some(Ok(witness_index)), None) => Ok(Watchpoint::Witness(Witness(witness_index))),
+            (_, Some(index)) if name == "memory" => Ok(Watchpoint::BrilligMemory(index)),
+            _ if is_identifier => Ok(Watchpoint::Variable { name: name.to_owned(), index }),
+            _ => Err(error()),
+        }
+    }
+}
+
+/// A change to the value of a [`Watchpoint`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct WatchpointHit {
+    pub(super) watchpoint: Watchpoint,
+    pub(super) old_value: Option<String>,
+    pub(super) new_value: Option<String>,
+    /// The location of the opcode which changed the value
+    pub(super) location: Option<OpcodeLocation>,
+    /// The source code which changed the value: the assignment for a variable, otherwise the
+    /// code which the opcode was compiled from
+    pub(super) source_location: Option<Location>,
+}
+
+impl fmt::Display for WatchpointHit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let render = |value: &Option<String>| value.clone().unwrap_or_else(|| "unset".to_owned());
+        write!(
+            f,
+            "{} changed from {} to {}",
+            self.watchpoint,
+            render(&self.old_value),
+            render(&self.new_value)
+        )?;
+        if let Some(location) = self.location {
+            write!(f, " by opcode {location}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the witnesses which the ACIR opcode reads in order to be solved.
+fn get_opcode_input_witnesses(opcode: &Opcode) -> BTreeSet<Witness> {
+    fn add_expression_witnesses(witnesses: &mut BTreeSet<Witness>, expr: &Expression) {
+        for (_, lhs, rhs) in &expr.mul_terms {
+            witnesses.insert(*lhs);
+            witnesses.insert(*rhs);
+        }
+        witnesses.extend(expr.linear_combinations.iter().map(|(_, witness)| *witness));
+    }
+
+    let mut witnesses = BTreeSet::new();
+    match opcode {
+        Opcode::AssertZero(expr) => add_expression_witnesses(&mut witnesses, expr),
+        Opcode::BlackBoxFuncCall(call) => {
+            witnesses.extend(call.get_inputs_vec().iter().map(|input| input.witness));
+        }
+        Opcode::Directive(Directive::ToLeRadix { a, .. }) => {
+            add_expression_witnesses(&mut witnesses, a);
+        }
+        Opcode::Directive(Directive::PermutationSort { inputs, .. }) => {
+            for expr in inputs.iter().flatten() {
+                add_expression_witnesses(&mut witnesses, expr);
+            }
+        }
+        Opcode::Brillig(brillig) => {
+            for input in &brillig.inputs {
+                match input {
+                    BrilligInputs::Single(expr) => add_expression_witnesses(&mut witnesses, expr),
+                    BrilligInputs::Array(exprs) => {
+                        for expr in exprs {
+                            add_expression_witnesses(&mut witnesses, expr);
+                        }
+                    }
+                }
+            }
+            if let Some(predicate) = &brillig.predicate {
+                add_expression_witnesses(&mut witnesses, predicate);
+            }
+        }
+        Opcode::MemoryOp { op, predicate, .. } => {
+            add_expression_witnesses(&mut witnesses, &op.index);
+            // the value of a read is written by the opcode rather than read from it
+            if op.operation.to_const() == Some(FieldElement::one()) {
+                add_expression_witnesses(&mut witnesses, &op.value);
+            }
+            if let Some(predicate) = predicate {
+                add_expression_witnesses(&mut witnesses, predicate);
+            }
+        }
+        Opcode::MemoryInit { init, .. } => witnesses.extend(init.iter().copied()),
+        Opcode::Call { inputs, predicate, .. } => {
+            witnesses.extend(inputs.iter().copied());
+            if let Some(predicate) = predicate {
+                add_expression_witnesses(&mut witnesses, predicate);
+            }
+        }
+    }
+    witnesses
+}
+
 pub(super) struct DebugContext<'a, B: BlackBoxFunctionSolver> {
     acvm: ACVM<'a, B>,
     brillig_solver: Option<BrilligSolver<'a, B>>,
     foreign_call_executor: Box<dyn ForeignCallExecutor + 'a>,
     debug_artifact: &'a DebugArtifact,
     breakpoints: HashSet<OpcodeLocation>,
+    /// The value each watchpoint had when execution last paused
+    watchpoints: HashMap<Watchpoint, Option<String>>,
+    /// The values assigned to the variables of the program so far
+    debug_vars: DebugVars,
 }
 
 impl<'a, B: BlackBoxFunctionSolver> DebugContext<'a, B> {
@@ -43,6 +202,8 @@ impl<'a, B: BlackBoxFunctionSolver> DebugContext<'a, B> {
             foreign_call_executor,
             debug_artifact,
             breakpoints: HashSet::new(),
+            watchpoints: HashMap::new(),
+            debug_vars: DebugVars::default(),
         }
     }
 
@@ -166,6 +327,28 @@ impl<'a, B: BlackBoxFunctionSolver> DebugContext<'a, B> {
         }
     }
 
+    /// Returns the witnesses read by the ACIR opcode at `acir_index` along with their current
+    /// values, which are `None` if the witness hasn't been assigned yet.
+    pub(super) fn get_opcode_input_values(
+        &self,
+        acir_index: usize,
+    ) -> Vec<(Witness, Option<FieldElement>)> {
+        let witness_map = self.get_witness_map();
+        get_opcode_input_witnesses(&self.get_opcodes()[acir_index])
+            .into_iter()
+            .map(|witness| (witness, witness_map.get(&witness).copied()))
+            .collect()
+    }
+
+    /// Evaluates the expression of the `AssertZero` opcode at `acir_index` using the current
+    /// witness values. Returns `None` for other opcodes or if not every witness is assigned.
+    pub(super) fn evaluate_assert_zero(&self, acir_index: usize) -> Option<FieldElement> {
+        let Opcode::AssertZero(expr) = &self.get_opcodes()[acir_index] else {
+            return None;
+        };
+        get_value(expr, self.get_witness_map()).ok()
+    }
+
     pub(super) fn render_opcode_at_location(&self, location: &Option<OpcodeLocation>) -> String {
         let opcodes = self.get_opcodes();
         match location {
@@ -221,7 +404,20 @@ impl<'a, B: BlackBoxFunctionSolver> DebugContext<'a, B> {
     }
 
     fn handle_foreign_call(&mut self, foreign_call: ForeignCallWaitInfo) -> DebugCommandResult {
-        let foreign_call_result = self.foreign_call_executor.execute(&foreign_call);
+        let foreign_call_result = if foreign_call.function == DEBUG_VAR_ASSIGN_ORACLE {
+            // The oracle call is located at the assignment it reports.
+            let location = self
+                .get_current_source_location()
+                .and_then(|call_stack| call_stack.last().copied());
+            self.debug_vars
+                .assign(&foreign_call.inputs, location)
+                .map(|()| ForeignCallResult { values: vec![] })
+        } else if foreign_call.function == ASSERT_MESSAGE_VALUES_ORACLE {
+            // The debugger reports a failed assertion with the message as written.
+            Ok(ForeignCallResult { values: vec![] })
+        } else {
+            self.foreign_call_executor.execute(&foreign_call)
+        };
         match foreign_call_result {
             Ok(foreign_call_result) => {
                 if let Some(mut solver) = self.brillig_solver.take() {
@@ -260,10 +456,21 @@ impl<'a, B: BlackBoxFunctionSolver> DebugContext<'a, B> {
             ACVMStatus::RequiresForeignCall(_) => {
                 unreachable!("Unexpected pending foreign call resolution");
             }
+            // The debugger only steps through the opcodes of `main`, so the functions it calls
+            // are not available to it.
+            ACVMStatus::RequiresAcirCall(acir_call) => DebugCommandResult::Error(
+                NargoError::ExecutionError(ExecutionError::MissingAcirFunction(acir_call.id)),
+            ),
         }
     }
 
     pub(super) fn step_into_opcode(&mut self) -> DebugCommandResult {
+        let location = self.get_current_opcode_location();
+        let result = self.step_into_opcode_unwatched();
+        self.check_watchpoints(location, result)
+    }
+
+    fn step_into_opcode_unwatched(&mut self) -> DebugCommandResult {
         if self.brillig_solver.is_some() {
             return self.step_brillig_opcode();
         }
@@ -318,8 +525,10 @@ impl<'a, B: BlackBoxFunctionSolver> DebugContext<'a, B> {
         if self.currently_executing_brillig() {
             self.step_out_of_brillig_opcode()
         } else {
+            let location = self.get_current_opcode_location();
             let status = self.acvm.solve_opcode();
-            self.handle_acvm_status(status)
+            let result = self.handle_acvm_status(status);
+            self.check_watchpoints(location, result)
         }
     }
 
@@ -418,6 +627,111 @@ impl<'a, B: BlackBoxFunctionSolver> DebugContext<'a, B> {
         self.breakpoints.clear();
     }
 
+    fn get_watched_value(&self, watchpoint: &Watchpoint) -> Option<String> {
+        match watchpoint {
+            Watchpoint::Witness(witness) => {
+                self.get_witness_map().get(witness).map(|value| value.to_string())
+            }
+            Watchpoint::BrilligMemory(index) => self
+                .get_brillig_memory()
+                .and_then(|memory| memory.get(*index))
+                .map(|value| value.to_field().to_string()),
+            Watchpoint::Variable { name, index } => self.debug_vars.render(name, *index),
+        }
+    }
+
+    /// Returns the source code which changed the value of `watchpoint` by executing the opcode at
+    /// `location`.
+    fn get_watchpoint_source_location(
+        &self,
+        watchpoint: &Watchpoint,
+        location: Option<OpcodeLocation>,
+    ) -> Option<Location> {
+        match watchpoint {
+            Watchpoint::Variable { name, .. } => self.debug_vars.location(name),
+            Watchpoint::Witness(_) | Watchpoint::BrilligMemory(_) => self
+                .debug_artifact
+                .debug_symbols
+                .first()?
+                .opcode_location(&location?)?
+                .last()
+                .copied(),
+        }
+    }
+
+    /// Describes `hit` along with the `<file>:<line>:<column>` of the code which triggered it.
+    pub(super) fn describe_watchpoint_hit(&self, hit: &WatchpointHit) -> String {
+        let Some(location) = hit.source_location else {
+            return hit.to_string();
+        };
+        let path = self
+            .debug_artifact
+            .file_map
+            .get(&location.file)
+            .map_or_else(|| String::from("<unknown>"), |file| file.path.display().to_string());
+        let line = self.debug_artifact.location_line_number(location).unwrap_or_default();
+        let column = self.debug_artifact.location_column_number(location).unwrap_or_default();
+        format!("{hit} at {path}:{line}:{column}")
+    }
+
+    /// Replaces `result` with [`DebugCommandResult::WatchpointTriggered`] if executing the
+    /// opcode at `location` changed the value of a watchpoint.
+    fn check_watchpoints(
+        &mut self,
+        location: Option<OpcodeLocation>,
+        result: DebugCommandResult,
+    ) -> DebugCommandResult {
+        if !matches!(result, DebugCommandResult::Ok | DebugCommandResult::BreakpointReached(_)) {
+            return result;
+        }
+
+        let mut hit = None;
+        let watchpoints: Vec<_> = self.watchpoints.keys().cloned().collect();
+        for watchpoint in watchpoints {
+            let new_value = self.get_watched_value(&watchpoint);
+            let old_value =
+                self.watchpoints.insert(watchpoint.clone(), new_value.clone()).flatten();
+            // Brillig memory only exists while a Brillig block is executing, so a cell
+            // disappearing when the block finishes isn't reported as a change.
+            if new_value.is_some() && new_value != old_value && hit.is_none() {
+                let source_location = self.get_watchpoint_source_location(&watchpoint, location);
+                hit = Some(WatchpointHit {
+                    watchpoint,
+                    old_value,
+                    new_value,
+                    location,
+                    source_location,
+                });
+            }
+        }
+
+        match hit {
+            Some(hit) => DebugCommandResult::WatchpointTriggered(hit),
+            None => result,
+        }
+    }
+
+    pub(super) fn add_watchpoint(&mut self, watchpoint: Watchpoint) -> bool {
+        if self.watchpoints.contains_key(&watchpoint) {
+            return false;
+        }
+        let value = self.get_watched_value(&watchpoint);
+        self.watchpoints.insert(watchpoint, value);
+        true
+    }
+
+    pub(super) fn delete_watchpoint(&mut self, watchpoint: &Watchpoint) -> bool {
+        self.watchpoints.remove(watchpoint).is_some()
+    }
+
+    pub(super) fn iterate_watchpoints(&self) -> impl Iterator<Item = &Watchpoint> {
+        self.watchpoints.keys()
+    }
+
+    pub(super) fn clear_watchpoints(&mut self) {
+        self.watchpoints.clear();
+    }
+
     pub(super) fn is_solved(&self) -> bool {
         matches!(self.acvm.get_status(), ACVMStatus::Solved)
     }
@@ -605,6 +919,145 @@ mod tests {
         assert_eq!(context.get_current_opcode_location(), None);
     }
 
+    #[test]
+    fn test_watchpoint_triggered_by_brillig_output() {
+        let fe_1 = FieldElement::one();
+        let w_x = Witness(1);
+        let w_y = Witness(2);
+        let w_z = Witness(3);
+
+        // This Brillig block is equivalent to: z = x + y
+        let brillig_opcodes = Brillig {
+            inputs: vec![
+                BrilligInputs::Single(Expression {
+                    linear_combinations: vec![(fe_1, w_x)],
+                    ..Expression::default()
+                }),
+                BrilligInputs::Single(Expression {
+                    linear_combinations: vec![(fe_1, w_y)],
+                    ..Expression::default()
+                }),
+            ],
+            outputs: vec![BrilligOutputs::Simple(w_z)],
+            bytecode: vec![
+                BrilligOpcode::BinaryFieldOp {
+                    destination: RegisterIndex::from(0),
+                    op: BinaryFieldOp::Add,
+                    lhs: RegisterIndex::from(0),
+                    rhs: RegisterIndex::from(1),
+                },
+                BrilligOpcode::Stop,
+            ],
+            predicate: None,
+        };
+        let opcodes = vec![
+            // z = x + y
+            Opcode::Brillig(brillig_opcodes),
+            // x + y - z = 0
+            Opcode::AssertZero(Expression {
+                mul_terms: vec![],
+                linear_combinations: vec![(fe_1, w_x), (fe_1, w_y), (-fe_1, w_z)],
+                q_c: FieldElement::zero(),
+            }),
+        ];
+        let current_witness_index = 3;
+        let circuit = &Circuit { current_witness_index, opcodes, ..Circuit::default() };
+
+        let debug_artifact =
+            &DebugArtifact { debug_symbols: vec![], file_map: BTreeMap::new(), warnings: vec![] };
+
+        let initial_witness = BTreeMap::from([(w_x, fe_1), (w_y, fe_1)]).into();
+
+        let mut context = DebugContext::new(
+            &StubbedBlackBoxSolver,
+            circuit,
+            debug_artifact,
+            initial_witness,
+            Box::new(DefaultForeignCallExecutor::new(true, None)),
+        );
+
+        assert!(context.add_watchpoint(Watchpoint::Witness(w_z)));
+        assert!(!context.add_watchpoint(Watchpoint::Witness(w_z)));
+
+        // the Brillig block only writes to z once it finishes, so execution pauses after its
+        // last opcode has been executed rather than at the start of the block
+        let result = context.step_acir_opcode();
+        let DebugCommandResult::WatchpointTriggered(hit) = result else {
+            panic!("Expected watchpoint to be triggered but got {result:?}");
+        };
+        assert_eq!(
+            hit,
+            WatchpointHit {
+                watchpoint: Watchpoint::Witness(w_z),
+                old_value: None,
+                new_value: Some(FieldElement::from(2u128).to_string()),
+                location: Some(OpcodeLocation::Brillig { acir_index: 0, brillig_index: 1 }),
+                source_location: None,
+            }
+        );
+
+        let result = context.step_acir_opcode();
+        assert!(matches!(result, DebugCommandResult::Done));
+    }
+
+    #[test]
+    fn test_parse_watchpoint() {
+        assert_eq!("_12".parse::<Watchpoint>(), Ok(Watchpoint::Witness(Witness(12))));
+        assert_eq!("memory[3]".parse::<Watchpoint>(), Ok(Watchpoint::BrilligMemory(3)));
+        assert!("memory[x]".parse::<Watchpoint>().is_err());
+        assert!("12".parse::<Watchpoint>().is_err());
+
+        let variable = |name: &str, index| Watchpoint::Variable { name: name.to_owned(), index };
+        assert_eq!("total".parse::<Watchpoint>(), Ok(variable("total", None)));
+        assert_eq!("_count".parse::<Watchpoint>(), Ok(variable("_count", None)));
+        assert_eq!("memory".parse::<Watchpoint>(), Ok(variable("memory", None)));
+        assert_eq!("values[2]".parse::<Watchpoint>(), Ok(variable("values", Some(2))));
+        assert!("values[i]".parse::<Watchpoint>().is_err());
+        assert!("a.b".parse::<Watchpoint>().is_err());
+    }
+
+    #[test]
+    fn test_inspect_assert_zero_opcode_inputs() {
+        let fe_1 = FieldElement::one();
+        let w_x = Witness(1);
+        let w_y = Witness(2);
+        let w_z = Witness(3);
+
+        let opcodes = vec![
+            // x * y - z = 0
+            Opcode::AssertZero(Expression {
+                mul_terms: vec![(fe_1, w_x, w_y)],
+                linear_combinations: vec![(-fe_1, w_z)],
+                q_c: FieldElement::zero(),
+            }),
+        ];
+        let circuit = &Circuit { current_witness_index: 3, opcodes, ..Circuit::default() };
+
+        let debug_artifact =
+            &DebugArtifact { debug_symbols: vec![], file_map: BTreeMap::new(), warnings: vec![] };
+
+        let fe_2 = FieldElement::from(2u128);
+        let fe_3 = FieldElement::from(3u128);
+        let initial_witness = BTreeMap::from([(w_x, fe_2), (w_y, fe_3)]).into();
+
+        let mut context = DebugContext::new(
+            &StubbedBlackBoxSolver,
+            circuit,
+            debug_artifact,
+            initial_witness,
+            Box::new(DefaultForeignCallExecutor::new(true, None)),
+        );
+
+        assert_eq!(
+            context.get_opcode_input_values(0),
+            vec![(w_x, Some(fe_2)), (w_y, Some(fe_3)), (w_z, None)]
+        );
+        assert_eq!(context.evaluate_assert_zero(0), None);
+
+        context.overwrite_witness(w_z, FieldElement::from(5u128));
+        assert_eq!(context.evaluate_assert_zero(0), Some(fe_1));
+    }
+
     #[test]
     fn test_offset_opcode_location() {
         let opcodes = vec![
@@ -9,13 +9,15 @@ use codespan_reporting::files::{Files, SimpleFile};
 
 use crate::context::DebugCommandResult;
 use crate::context::DebugContext;
+use crate::context::Watchpoint;
 
 use dap::errors::ServerError;
 use dap::events::StoppedEventBody;
 use dap::prelude::Event;
 use dap::requests::{Command, Request, SetBreakpointsArguments};
 use dap::responses::{
-    ContinueResponse, DisassembleResponse, ResponseBody, ScopesResponse, SetBreakpointsResponse,
+    ContinueResponse, DataBreakpointInfoResponse, DisassembleResponse, ResponseBody,
+    ScopesResponse, SetBreakpointsResponse, SetDataBreakpointsResponse,
     SetExceptionBreakpointsResponse, SetInstructionBreakpointsResponse, StackTraceResponse,
     ThreadsResponse,
 };
@@ -39,6 +41,7 @@ pub struct DapSession<'a, R: Read, W: Write, B: BlackBoxFunctionSolver> {
     next_breakpoint_id: i64,
     instruction_breakpoints: Vec<(OpcodeLocation, i64)>,
     source_breakpoints: BTreeMap<FileId, Vec<(OpcodeLocation, i64)>>,
+    data_breakpoints: Vec<(Watchpoint, i64)>,
 }
 
 // BTreeMap<FileId, Vec<(usize, OpcodeLocation)>
@@ -68,6 +71,7 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver> DapSession<'a, R, W, B> {
             next_breakpoint_id: 1,
             instruction_breakpoints: vec![],
             source_breakpoints: BTreeMap::new(),
+            data_breakpoints: vec![],
         }
     }
 
@@ -160,6 +164,12 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver> DapSession<'a, R, W, B> {
                 Command::SetInstructionBreakpoints(_) => {
                     self.handle_set_instruction_breakpoints(req)?;
                 }
+                Command::DataBreakpointInfo(_) => {
+                    self.handle_data_breakpoint_info(req)?;
+                }
+                Command::SetDataBreakpoints(_) => {
+                    self.handle_set_data_breakpoints(req)?;
+                }
                 Command::Threads => {
                     self.server.respond(req.success(ResponseBody::Threads(ThreadsResponse {
                         threads: vec![Thread { id: 0, name: "main".to_string() }],
@@ -376,6 +386,23 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver> DapSession<'a, R, W, B> {
                     hit_breakpoint_ids: Some(breakpoint_ids),
                 }))?;
             }
+            DebugCommandResult::WatchpointTriggered(hit) => {
+                let breakpoint_ids = self
+                    .data_breakpoints
+                    .iter()
+                    .filter(|(watchpoint, _)| *watchpoint == hit.watchpoint)
+                    .map(|(_, id)| *id)
+                    .collect();
+                self.server.send_event(Event::Stopped(StoppedEventBody {
+                    reason: StoppedEventReason::Data,
+                    description: Some(String::from("Paused at data breakpoint")),
+                    thread_id: Some(0),
+                    preserve_focus_hint: Some(false),
+                    text: Some(self.context.describe_watchpoint_hit(&hit)),
+                    all_threads_stopped: Some(false),
+                    hit_breakpoint_ids: Some(breakpoint_ids),
+                }))?;
+            }
             DebugCommandResult::Error(err) => {
                 self.server.send_event(Event::Stopped(StoppedEventBody {
                     reason: StoppedEventReason::Exception,
@@ -451,6 +478,67 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver> DapSession<'a, R, W, B> {
         Ok(())
     }
 
+    fn handle_data_breakpoint_info(&mut self, req: Request) -> Result<(), ServerError> {
+        let Command::DataBreakpointInfo(ref args) = req.command else {
+            unreachable!("handle_data_breakpoint_info called on a different request");
+        };
+
+        // Data breakpoints are identified by the name of the variable, witness or Brillig memory
+        // cell they watch, so the name is also used as the data breakpoint's id.
+        let response = match Watchpoint::from_str(&args.name) {
+            Ok(watchpoint) => DataBreakpointInfoResponse {
+                data_id: Some(watchpoint.to_string()),
+                description: format!("Pause when {watchpoint} changes"),
+                access_types: None,
+                can_persist: Some(true),
+            },
+            Err(err) => DataBreakpointInfoResponse {
+                data_id: None,
+                description: err.to_string(),
+                access_types: None,
+                can_persist: None,
+            },
+        };
+        self.server.respond(req.success(ResponseBody::DataBreakpointInfo(response)))?;
+        Ok(())
+    }
+
+    fn handle_set_data_breakpoints(&mut self, req: Request) -> Result<(), ServerError> {
+        let Command::SetDataBreakpoints(ref args) = req.command else {
+            unreachable!("handle_set_data_breakpoints called on a different request");
+        };
+
+        let mut breakpoints_to_set: Vec<(Watchpoint, i64)> = vec![];
+        let breakpoints: Vec<Breakpoint> = args
+            .breakpoints
+            .iter()
+            .map(|breakpoint| {
+                let Ok(watchpoint) = Watchpoint::from_str(&breakpoint.data_id) else {
+                    return Breakpoint {
+                        verified: false,
+                        message: Some(String::from("Invalid data breakpoint id")),
+                        ..Breakpoint::default()
+                    };
+                };
+                let id = self.get_next_breakpoint_id();
+                breakpoints_to_set.push((watchpoint, id));
+                Breakpoint { id: Some(id), verified: true, ..Breakpoint::default() }
+            })
+            .collect();
+
+        // data breakpoints replace all of the previously set ones
+        self.context.clear_watchpoints();
+        for (watchpoint, _) in &breakpoints_to_set {
+            self.context.add_watchpoint(watchpoint.clone());
+        }
+        self.data_breakpoints = breakpoints_to_set;
+
+        self.server.respond(req.success(ResponseBody::SetDataBreakpoints(
+            SetDataBreakpointsResponse { breakpoints },
+        )))?;
+        Ok(())
+    }
+
     fn find_file_id(&self, source_path: &str) -> Option<FileId> {
         let file_map = &self.debug_artifact.file_map;
         let found = file_map.iter().find(|(_, debug_file)| match debug_file.path.to_str() {
@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+
+use acvm::brillig_vm::brillig::ForeignCallParam;
+use nargo::errors::Location;
+use noirc_printable_type::{
+    decode_string_value, ForeignCallError, PrintableType, PrintableValue, PrintableValueDisplay,
+};
+
+/// The value last assigned to a variable of the program, along with where it was assigned.
+struct DebugVar {
+    value: PrintableValue,
+    typ: PrintableType,
+    location: Option<Location>,
+}
+
+/// The variables of the program being debugged, as reported to the debugger by the calls to the
+/// `__debug_var_assign` oracle which are compiled into the program.
+///
+/// Variables are only known by their name, so a variable shadowing another one or a variable of
+/// the same name in another function replaces it.
+#[derive(Default)]
+pub(super) struct DebugVars {
+    variables: HashMap<String, DebugVar>,
+}
+
+impl DebugVars {
+    /// Records the assignment reported by a call to the `__debug_var_assign` oracle with the
+    /// given `inputs`, which was made at `location`.
+    pub(super) fn assign(
+        &mut self,
+        inputs: &[ForeignCallParam],
+        location: Option<Location>,
+    ) -> Result<(), ForeignCallError> {
+        let (name, value_inputs) =
+            inputs.split_first().ok_or(ForeignCallError::MissingForeignCallInputs)?;
+        let name: Vec<_> = name.values().iter().map(|value| value.to_field()).collect();
+        let name = decode_string_value(&name);
+
+        let (value, typ) = match PrintableValueDisplay::try_from(value_inputs)? {
+            PrintableValueDisplay::Plain(value, typ) => (value, typ),
+            PrintableValueDisplay::FmtString(..) => {
+                unreachable!("variables are never reported as format strings")
+            }
+        };
+        self.variables.insert(name, DebugVar { value, typ, location });
+        Ok(())
+    }
+
+    /// Renders the value of the variable `name`, or of its element at `index` if it is an array.
+    /// Returns `None` if the variable hasn't been assigned yet or has no such element.
+    pub(super) fn render(&self, name: &str, index: Option<usize>) -> Option<String> {
+        let variable = self.variables.get(name)?;
+        let (value, typ) = match (index, &variable.value, &variable.typ) {
+            (None, value, typ) => (value.clone(), typ.clone()),
+            (Some(index), PrintableValue::Vec(elements), PrintableType::Array { typ, .. }) => {
+                (elements.get(index)?.clone(), typ.as_ref().clone())
+            }
+            (Some(_), _, _) => return None,
+        };
+        Some(PrintableValueDisplay::Plain(value, typ).to_string())
+    }
+
+    /// Returns where the variable `name` was last assigned.
+    pub(super) fn location(&self, name: &str) -> Option<Location> {
+        self.variables.get(name)?.location
+    }
+}
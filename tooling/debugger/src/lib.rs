@@ -1,5 +1,6 @@
 mod context;
 mod dap;
+mod debug_vars;
 mod repl;
 mod source_code_printer;
 
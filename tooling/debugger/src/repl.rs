@@ -1,4 +1,4 @@
-use crate::context::{DebugCommandResult, DebugContext};
+use crate::context::{DebugCommandResult, DebugContext, Watchpoint};
 
 use acvm::acir::circuit::{Circuit, Opcode, OpcodeLocation};
 use acvm::acir::native_types::{Witness, WitnessMap};
@@ -18,6 +18,8 @@ pub struct ReplDebugger<'a, B: BlackBoxFunctionSolver> {
     debug_artifact: &'a DebugArtifact,
     initial_witness: WitnessMap,
     last_result: DebugCommandResult,
+    /// Whether the inputs of each ACIR opcode are displayed when execution pauses at it
+    acir_mode: bool,
 }
 
 impl<'a, B: BlackBoxFunctionSolver> ReplDebugger<'a, B> {
@@ -41,6 +43,7 @@ impl<'a, B: BlackBoxFunctionSolver> ReplDebugger<'a, B> {
             debug_artifact,
             initial_witness,
             last_result: DebugCommandResult::Ok,
+            acir_mode: false,
         }
     }
 
@@ -62,6 +65,9 @@ impl<'a, B: BlackBoxFunctionSolver> ReplDebugger<'a, B> {
                             _ => format!("{}", opcodes[ip]),
                         };
                         println!("At opcode {}: {}", ip, opcode_summary);
+                        if self.acir_mode {
+                            self.show_opcode_inputs(ip);
+                        }
                     }
                     OpcodeLocation::Brillig { acir_index, brillig_index } => {
                         let Opcode::Brillig(ref brillig) = opcodes[acir_index] else {
@@ -79,6 +85,43 @@ impl<'a, B: BlackBoxFunctionSolver> ReplDebugger<'a, B> {
         }
     }
 
+    /// Shows the concrete values of the witnesses read by the ACIR opcode at `acir_index` and,
+    /// for arithmetic opcodes, the value the expression currently evaluates to.
+    fn show_opcode_inputs(&self, acir_index: usize) {
+        for (witness, value) in self.context.get_opcode_input_values(acir_index) {
+            match value {
+                Some(value) => println!("  _{} = {value}", witness.witness_index()),
+                None => println!("  _{} = (unassigned)", witness.witness_index()),
+            }
+        }
+        if let Some(value) = self.context.evaluate_assert_zero(acir_index) {
+            let status = if value.is_zero() { "satisfied" } else { "NOT satisfied" };
+            println!("  expression evaluates to {value} ({status})");
+        }
+    }
+
+    fn inspect_current_opcode(&self) {
+        match self.context.get_current_opcode_location() {
+            None => println!("Finished execution"),
+            Some(OpcodeLocation::Brillig { .. }) => {
+                println!("Executing a Brillig block, use `registers` and `memory` to inspect it")
+            }
+            Some(OpcodeLocation::Acir(acir_index)) => {
+                println!("At opcode {}: {}", acir_index, self.context.get_opcodes()[acir_index]);
+                self.show_opcode_inputs(acir_index);
+            }
+        }
+    }
+
+    fn toggle_acir_mode(&mut self) {
+        self.acir_mode = !self.acir_mode;
+        if self.acir_mode {
+            println!("ACIR mode enabled: showing the witness values read by each opcode");
+        } else {
+            println!("ACIR mode disabled");
+        }
+    }
+
     fn display_opcodes(&self) {
         let opcodes = self.context.get_opcodes();
         let current_opcode_location = self.context.get_current_opcode_location();
@@ -150,9 +193,27 @@ impl<'a, B: BlackBoxFunctionSolver> ReplDebugger<'a, B> {
         }
     }
 
+    fn add_watchpoint(&mut self, watchpoint: Watchpoint) {
+        if self.context.add_watchpoint(watchpoint.clone()) {
+            println!("Added watchpoint on {watchpoint}");
+        } else {
+            println!("Watchpoint on {watchpoint} already set");
+        }
+    }
+
+    fn delete_watchpoint(&mut self, watchpoint: Watchpoint) {
+        if self.context.delete_watchpoint(&watchpoint) {
+            println!("Watchpoint on {watchpoint} deleted");
+        } else {
+            println!("Watchpoint on {watchpoint} not set");
+        }
+    }
+
     fn validate_in_progress(&self) -> bool {
         match self.last_result {
-            DebugCommandResult::Ok | DebugCommandResult::BreakpointReached(..) => true,
+            DebugCommandResult::Ok
+            | DebugCommandResult::BreakpointReached(..)
+            | DebugCommandResult::WatchpointTriggered(..) => true,
             DebugCommandResult::Done => {
                 println!("Execution finished");
                 false
@@ -170,6 +231,9 @@ impl<'a, B: BlackBoxFunctionSolver> ReplDebugger<'a, B> {
             DebugCommandResult::BreakpointReached(location) => {
                 println!("Stopped at breakpoint in opcode {}", location);
             }
+            DebugCommandResult::WatchpointTriggered(hit) => {
+                println!("Stopped at watchpoint: {}", self.context.describe_watchpoint_hit(hit));
+            }
             DebugCommandResult::Error(error) => {
                 println!("ERROR: {}", error);
             }
@@ -211,6 +275,7 @@ impl<'a, B: BlackBoxFunctionSolver> ReplDebugger<'a, B> {
     fn restart_session(&mut self) {
         let breakpoints: Vec<OpcodeLocation> =
             self.context.iterate_breakpoints().copied().collect();
+        let watchpoints: Vec<Watchpoint> = self.context.iterate_watchpoints().cloned().collect();
         self.context = DebugContext::new(
             self.blackbox_solver,
             self.circuit,
@@ -221,6 +286,9 @@ impl<'a, B: BlackBoxFunctionSolver> ReplDebugger<'a, B> {
         for opcode_location in breakpoints {
             self.context.add_breakpoint(opcode_location);
         }
+        for watchpoint in watchpoints {
+            self.context.add_watchpoint(watchpoint);
+        }
         self.last_result = DebugCommandResult::Ok;
         println!("Restarted debugging session.");
         self.show_current_vm_status();
@@ -395,6 +463,26 @@ pub fn run<B: BlackBoxFunctionSolver>(
                 }
             },
         )
+        .add(
+            "acir",
+            command! {
+                "toggle showing the witness values read by the current ACIR opcode at every step",
+                () => || {
+                    ref_context.borrow_mut().toggle_acir_mode();
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "inspect",
+            command! {
+                "show the current ACIR opcode along with the values of the witnesses it reads",
+                () => || {
+                    ref_context.borrow().inspect_current_opcode();
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
         .add(
             "break",
             command! {
@@ -415,6 +503,26 @@ pub fn run<B: BlackBoxFunctionSolver>(
                 }
             },
         )
+        .add(
+            "watch",
+            command! {
+                "pause when a variable (<name> or <name>[<index>]), witness (_<index>) or Brillig memory cell (memory[<index>]) changes",
+                (WATCHPOINT:Watchpoint) => |watchpoint| {
+                    ref_context.borrow_mut().add_watchpoint(watchpoint);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "unwatch",
+            command! {
+                "delete the watchpoint on a variable, witness or Brillig memory cell",
+                (WATCHPOINT:Watchpoint) => |watchpoint| {
+                    ref_context.borrow_mut().delete_watchpoint(watchpoint);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
         .add(
             "witness",
             command! {
@@ -4,7 +4,7 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies, unused_extern_crates))]
 
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, BTreeSet, HashMap},
     future::Future,
     ops::{self, ControlFlow},
     path::{Path, PathBuf},
@@ -22,11 +22,13 @@ use fm::{codespan_files as files, FileManager};
 use fxhash::FxHashSet;
 use lsp_types::CodeLens;
 use nargo::{
-    package::{Package, PackageType},
+    package::{Dependency, Package, PackageType},
     parse_all,
     workspace::Workspace,
 };
-use nargo_toml::{find_file_manifest, resolve_workspace_from_toml, PackageSelection};
+use nargo_toml::{
+    find_file_manifest, find_package_manifest, resolve_workspace_from_toml, PackageSelection,
+};
 use noirc_driver::{file_manager_with_stdlib, prepare_crate, NOIR_ARTIFACT_VERSION_STRING};
 use noirc_frontend::{
     graph::{CrateId, CrateName},
@@ -42,15 +44,18 @@ use notifications::{
     on_did_open_text_document, on_did_save_text_document, on_exit, on_initialized,
 };
 use requests::{
-    on_code_lens_request, on_formatting, on_goto_declaration_request, on_goto_definition_request,
-    on_goto_type_definition_request, on_initialize, on_profile_run_request, on_shutdown,
-    on_test_run_request, on_tests_request,
+    on_code_lens_request, on_document_symbol_request, on_formatting, on_goto_declaration_request,
+    on_goto_definition_request, on_goto_type_definition_request, on_hover_request, on_initialize,
+    on_inlay_hint_request, on_profile_run_request, on_references_request, on_rename_request,
+    on_semantic_tokens_full_request, on_shutdown, on_test_run_request, on_tests_request,
+    on_workspace_symbol_request,
 };
 use serde_json::Value as JsonValue;
 use thiserror::Error;
 use tower::Service;
 
 mod notifications;
+mod opcode_estimate;
 mod requests;
 mod solver;
 mod types;
@@ -113,6 +118,13 @@ impl NargoLspService {
             .request::<request::GotoDefinition, _>(on_goto_definition_request)
             .request::<request::GotoDeclaration, _>(on_goto_declaration_request)
             .request::<request::GotoTypeDefinition, _>(on_goto_type_definition_request)
+            .request::<request::References, _>(on_references_request)
+            .request::<request::InlayHint, _>(on_inlay_hint_request)
+            .request::<request::Hover, _>(on_hover_request)
+            .request::<request::Rename, _>(on_rename_request)
+            .request::<request::DocumentSymbol, _>(on_document_symbol_request)
+            .request::<request::WorkspaceSymbol, _>(on_workspace_symbol_request)
+            .request::<request::SemanticTokensFull, _>(on_semantic_tokens_full_request)
             .notification::<notification::Initialized>(on_initialized)
             .notification::<notification::DidChangeConfiguration>(on_did_change_configuration)
             .notification::<notification::DidOpenTextDocument>(on_did_open_text_document)
@@ -237,6 +249,7 @@ pub(crate) fn resolve_workspace_for_source_path(file_path: &Path) -> Result<Work
             name: CrateName::from_str(parent_folder)
                 .map_err(|err| LspError::WorkspaceResolutionError(err.to_string()))?,
             dependencies: BTreeMap::new(),
+            features: BTreeSet::new(),
         };
         let workspace = Workspace {
             root_dir: PathBuf::from(parent_folder),
@@ -248,6 +261,62 @@ pub(crate) fn resolve_workspace_for_source_path(file_path: &Path) -> Result<Work
     }
 }
 
+/// Resolves the workspace and package which `file_path` should be compiled as part of.
+///
+/// Git and path dependencies have manifests of their own, so resolving the workspace of one of
+/// their files would compile the dependency on its own. If a package of the workspace opened in
+/// the editor has the file in its crate graph then that package is returned instead, so that
+/// queries on the dependency's sources also see the code which uses them.
+pub(crate) fn resolve_package_for_source_path(
+    root_path: Option<&Path>,
+    file_path: &Path,
+) -> Result<(Workspace, Package), LspError> {
+    if let Some(workspace) = resolve_root_workspace(root_path) {
+        let package =
+            workspace.members.iter().find(|package| package_includes_file(package, file_path));
+        if let Some(package) = package.cloned() {
+            return Ok((workspace, package));
+        }
+    }
+
+    let workspace = resolve_workspace_for_source_path(file_path)?;
+    let package = workspace
+        .members
+        .iter()
+        .find(|package| package_includes_file(package, file_path))
+        .or_else(|| workspace.members.first())
+        .cloned()
+        .ok_or_else(|| {
+            LspError::WorkspaceResolutionError(format!(
+                "Could not find a package for file: {:?}",
+                file_path
+            ))
+        })?;
+    Ok((workspace, package))
+}
+
+/// Resolves the workspace of the folder opened in the editor, if it contains one.
+pub(crate) fn resolve_root_workspace(root_path: Option<&Path>) -> Option<Workspace> {
+    let root_path = root_path?;
+    let toml_path = find_package_manifest(root_path, root_path).ok()?;
+    resolve_workspace_from_toml(
+        &toml_path,
+        PackageSelection::All,
+        Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
+    )
+    .ok()
+}
+
+/// Returns whether `file_path` belongs to `package` or to any of its dependencies.
+pub(crate) fn package_includes_file(package: &Package, file_path: &Path) -> bool {
+    file_path.starts_with(&package.root_dir)
+        || package.dependencies.values().any(|dependency| match dependency {
+            Dependency::Local { package } | Dependency::Remote { package } => {
+                package_includes_file(package, file_path)
+            }
+        })
+}
+
 /// Prepares a package from a source string
 /// This is useful for situations when we don't need dependencies
 /// and just need to operate on single file.
@@ -0,0 +1,215 @@
+//! A rough estimate of the number of ACIR opcodes a function compiles to.
+//!
+//! Compiling a program down to ACIR runs the whole SSA pipeline, which is too slow to do on
+//! every hover. Instead this walks the monomorphized AST and assigns each operation the
+//! number of opcodes it typically takes once lowered, e.g. an integer addition needs a range
+//! check on its result while a field addition is folded into other constraints for free.
+//!
+//! Calls to constrained functions are counted as if inlined, loops with constant bounds are
+//! counted once per iteration and both branches of an `if` are counted, as they are all
+//! flattened into a single circuit. Optimizations such as constant folding and deduplication
+//! aren't modelled, so the estimate tends to err on the high side.
+use std::collections::HashMap;
+
+use noirc_frontend::monomorphization::ast::{
+    Definition, Expression, FuncId, LValue, Literal, Program, Type,
+};
+use noirc_frontend::{BinaryOpKind, UnaryOp};
+
+/// Returns the estimated number of ACIR opcodes for calling `function` from a circuit.
+pub(crate) fn estimate_acir_opcodes(program: &Program, function: FuncId) -> usize {
+    OpcodeEstimator { program, function_costs: HashMap::new() }.function_cost(function)
+}
+
+struct OpcodeEstimator<'program> {
+    program: &'program Program,
+    /// The cost of each function which has been visited, or `None` while it's being visited.
+    function_costs: HashMap<FuncId, Option<usize>>,
+}
+
+impl OpcodeEstimator<'_> {
+    fn function_cost(&mut self, id: FuncId) -> usize {
+        match self.function_costs.get(&id) {
+            Some(Some(cost)) => return *cost,
+            // Recursive calls can't be inlined, so they can't be counted either.
+            Some(None) => return 0,
+            None => (),
+        }
+
+        let program = self.program;
+        let function = &program[id];
+        let cost = if function.unconstrained {
+            // The function is run by the prover, leaving a single opcode in the circuit.
+            1
+        } else {
+            self.function_costs.insert(id, None);
+            self.expression_cost(&function.body, function.max_iterations)
+        };
+        self.function_costs.insert(id, Some(cost));
+        cost
+    }
+
+    fn expression_cost(&mut self, expression: &Expression, max_iterations: Option<u64>) -> usize {
+        let mut cost = |expression: &Expression| self.expression_cost(expression, max_iterations);
+        match expression {
+            Expression::Ident(_) | Expression::Break | Expression::Continue => 0,
+            Expression::Literal(Literal::Array(array)) => {
+                array.contents.iter().map(&mut cost).sum()
+            }
+            Expression::Literal(Literal::FmtStr(_, _, fields)) => cost(fields),
+            Expression::Literal(_) => 0,
+            Expression::Block(expressions) | Expression::Tuple(expressions) => {
+                expressions.iter().map(cost).sum()
+            }
+            Expression::Unary(unary) => {
+                let operator_cost = match (unary.operator, &unary.result_type) {
+                    (UnaryOp::Not, Type::Integer(..)) => 1,
+                    _ => 0,
+                };
+                operator_cost + cost(&unary.rhs)
+            }
+            Expression::Binary(binary) => {
+                let typ = expression_type(&binary.lhs);
+                binary_operator_cost(binary.operator, typ) + cost(&binary.lhs) + cost(&binary.rhs)
+            }
+            Expression::Index(index) => {
+                // Indexing with a constant is resolved at compile-time, otherwise it needs a
+                // memory read.
+                let read_cost = usize::from(!matches!(*index.index, Expression::Literal(_)));
+                read_cost + cost(&index.collection) + cost(&index.index)
+            }
+            Expression::Cast(cast) => {
+                // Casts to a smaller integer type have to be range checked.
+                let range_check_cost = match (expression_type(&cast.lhs), &cast.r#type) {
+                    (Some(Type::Integer(_, from)), Type::Integer(_, to)) => usize::from(to < from),
+                    (_, Type::Integer(..)) => 1,
+                    _ => 0,
+                };
+                range_check_cost + cost(&cast.lhs)
+            }
+            Expression::For(for_expr) => {
+                let iterations = match (&*for_expr.start_range, &*for_expr.end_range) {
+                    (
+                        Expression::Literal(Literal::Integer(start, ..)),
+                        Expression::Literal(Literal::Integer(end, ..)),
+                    ) => (end.to_u128().saturating_sub(start.to_u128())) as usize,
+                    _ => max_iterations.unwrap_or(1) as usize,
+                };
+                cost(&for_expr.start_range)
+                    + cost(&for_expr.end_range)
+                    + iterations * cost(&for_expr.block)
+            }
+            Expression::Loop(block) => cost(block),
+            Expression::If(if_expr) => {
+                cost(&if_expr.condition)
+                    + cost(&if_expr.consequence)
+                    + if_expr.alternative.as_deref().map_or(0, cost)
+            }
+            Expression::ExtractTupleField(tuple, _) => cost(tuple),
+            Expression::Call(call) => {
+                let arguments_cost: usize = call.arguments.iter().map(&mut cost).sum();
+                let call_cost = match &*call.func {
+                    Expression::Ident(ident) => match &ident.definition {
+                        Definition::Function(id) => self.function_cost(*id),
+                        // Black box functions and oracles each take a single opcode.
+                        Definition::LowLevel(_) | Definition::Oracle(_) => 1,
+                        Definition::Builtin(_) | Definition::Local(_) => 0,
+                    },
+                    _ => 0,
+                };
+                arguments_cost + call_cost
+            }
+            Expression::Let(let_expr) => cost(&let_expr.expression),
+            Expression::Constrain(expression, ..) => 1 + cost(expression),
+            Expression::Assign(assign) => {
+                let mut lvalue_cost = 0;
+                let mut lvalue = &assign.lvalue;
+                loop {
+                    match lvalue {
+                        LValue::Ident(_) => break,
+                        LValue::Index { array, index, .. } => {
+                            // Writing to a dynamic index needs a memory write.
+                            lvalue_cost += usize::from(!matches!(**index, Expression::Literal(_)));
+                            lvalue_cost += cost(index);
+                            lvalue = array;
+                        }
+                        LValue::MemberAccess { object, .. } => lvalue = object,
+                        LValue::Dereference { reference, .. } => lvalue = reference,
+                    }
+                }
+                lvalue_cost + cost(&assign.expression)
+            }
+            Expression::Semi(expression) => cost(expression),
+        }
+    }
+}
+
+/// The number of opcodes needed for a binary operation on values of the given type.
+fn binary_operator_cost(operator: BinaryOpKind, typ: Option<&Type>) -> usize {
+    let is_integer = matches!(typ, Some(Type::Integer(..)));
+    match operator {
+        // Field additions are folded into other constraints, while integer results need a
+        // range check to detect overflows.
+        BinaryOpKind::Add | BinaryOpKind::Subtract => usize::from(is_integer),
+        BinaryOpKind::Multiply => 1 + usize::from(is_integer),
+        // Division is computed by the prover and then checked, integers need their quotient
+        // and remainder range checked as well.
+        BinaryOpKind::Divide | BinaryOpKind::Modulo => {
+            if is_integer {
+                5
+            } else {
+                2
+            }
+        }
+        // Checking whether a value is zero takes the inverse of the difference.
+        BinaryOpKind::Equal | BinaryOpKind::NotEqual => 2,
+        BinaryOpKind::Less
+        | BinaryOpKind::LessEqual
+        | BinaryOpKind::Greater
+        | BinaryOpKind::GreaterEqual => 3,
+        BinaryOpKind::And | BinaryOpKind::Or | BinaryOpKind::Xor => {
+            if matches!(typ, Some(Type::Bool)) {
+                1
+            } else {
+                2
+            }
+        }
+        BinaryOpKind::ShiftLeft | BinaryOpKind::ShiftRight => 3,
+    }
+}
+
+/// Returns the type of `expression` where it can be found without type checking it again.
+fn expression_type(expression: &Expression) -> Option<&Type> {
+    match expression {
+        Expression::Ident(ident) => Some(&ident.typ),
+        Expression::Literal(Literal::Integer(_, typ, _)) => Some(typ),
+        Expression::Binary(binary) => expression_type(&binary.lhs),
+        Expression::Unary(unary) => Some(&unary.result_type),
+        Expression::Cast(cast) => Some(&cast.r#type),
+        Expression::Index(index) => Some(&index.element_type),
+        Expression::Call(call) => Some(&call.return_type),
+        Expression::If(if_expr) => Some(&if_expr.typ),
+        Expression::Block(expressions) => expressions.last().and_then(expression_type),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::binary_operator_cost;
+    use noirc_frontend::monomorphization::ast::Type;
+    use noirc_frontend::{BinaryOpKind, Signedness};
+
+    #[test]
+    fn integer_arithmetic_costs_more_than_field_arithmetic() {
+        let field = Some(&Type::Field);
+        let integer = Type::Integer(Signedness::Unsigned, 32);
+        for operator in [BinaryOpKind::Add, BinaryOpKind::Multiply, BinaryOpKind::Divide] {
+            assert!(
+                binary_operator_cost(operator, field)
+                    < binary_operator_cost(operator, Some(&integer))
+            );
+        }
+        assert_eq!(binary_operator_cost(BinaryOpKind::Add, field), 0);
+    }
+}
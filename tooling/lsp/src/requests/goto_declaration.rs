@@ -2,7 +2,7 @@ use std::future::{self, Future};
 
 use crate::types::GotoDeclarationResult;
 use crate::LspState;
-use crate::{parse_diff, resolve_workspace_for_source_path};
+use crate::{parse_diff, resolve_package_for_source_path};
 use async_lsp::{ErrorCode, ResponseError};
 
 use lsp_types::request::{GotoDeclarationParams, GotoDeclarationResponse};
@@ -29,8 +29,9 @@ fn on_goto_definition_inner(
             ResponseError::new(ErrorCode::REQUEST_FAILED, "URI is not a valid file path")
         })?;
 
-    let workspace = resolve_workspace_for_source_path(file_path.as_path()).unwrap();
-    let package = workspace.members.first().unwrap();
+    let (workspace, package) =
+        resolve_package_for_source_path(state.root_path.as_deref(), file_path.as_path())
+            .map_err(|err| ResponseError::new(ErrorCode::REQUEST_FAILED, err.to_string()))?;
 
     let package_root_path: String = package.root_dir.as_os_str().to_string_lossy().into();
 
@@ -39,7 +40,7 @@ fn on_goto_definition_inner(
     let parsed_files = parse_diff(&workspace_file_manager, state);
 
     let (mut context, crate_id) =
-        nargo::prepare_package(&workspace_file_manager, &parsed_files, package);
+        nargo::prepare_package(&workspace_file_manager, &parsed_files, &package);
 
     let interner;
     if let Some(def_interner) = state.cached_definitions.get(&package_root_path) {
@@ -1,6 +1,6 @@
 use std::future::{self, Future};
 
-use crate::{parse_diff, resolve_workspace_for_source_path};
+use crate::{parse_diff, resolve_package_for_source_path};
 use crate::{types::GotoDefinitionResult, LspState};
 use async_lsp::{ErrorCode, ResponseError};
 
@@ -37,8 +37,9 @@ fn on_goto_definition_inner(
             ResponseError::new(ErrorCode::REQUEST_FAILED, "URI is not a valid file path")
         })?;
 
-    let workspace = resolve_workspace_for_source_path(file_path.as_path()).unwrap();
-    let package = workspace.members.first().unwrap();
+    let (workspace, package) =
+        resolve_package_for_source_path(state.root_path.as_deref(), file_path.as_path())
+            .map_err(|err| ResponseError::new(ErrorCode::REQUEST_FAILED, err.to_string()))?;
 
     let package_root_path: String = package.root_dir.as_os_str().to_string_lossy().into();
 
@@ -47,7 +48,7 @@ fn on_goto_definition_inner(
     let parsed_files = parse_diff(&workspace_file_manager, state);
 
     let (mut context, crate_id) =
-        nargo::prepare_package(&workspace_file_manager, &parsed_files, package);
+        nargo::prepare_package(&workspace_file_manager, &parsed_files, &package);
 
     let interner;
     if let Some(def_interner) = state.cached_definitions.get(&package_root_path) {
@@ -93,44 +94,15 @@ fn on_goto_definition_inner(
 #[cfg(test)]
 mod goto_definition_tests {
 
-    use acvm::blackbox_solver::StubbedBlackBoxSolver;
-    use async_lsp::ClientSocket;
-    use lsp_types::{Position, Url};
+    use lsp_types::Position;
     use tokio::test;
 
     use super::*;
+    use crate::requests::init_lsp_server;
 
     #[test]
     async fn test_on_goto_definition() {
-        let client = ClientSocket::new_closed();
-        let mut state = LspState::new(&client, StubbedBlackBoxSolver);
-
-        let root_path = std::env::current_dir()
-            .unwrap()
-            .join("../../test_programs/execution_success/7_function")
-            .canonicalize()
-            .expect("Could not resolve root path");
-        let noir_text_document = Url::from_file_path(root_path.join("src/main.nr").as_path())
-            .expect("Could not convert text document path to URI");
-        let root_uri = Some(
-            Url::from_file_path(root_path.as_path()).expect("Could not convert root path to URI"),
-        );
-
-        #[allow(deprecated)]
-        let initialize_params = lsp_types::InitializeParams {
-            process_id: Default::default(),
-            root_path: None,
-            root_uri,
-            initialization_options: None,
-            capabilities: Default::default(),
-            trace: Some(lsp_types::TraceValue::Verbose),
-            workspace_folders: None,
-            client_info: None,
-            locale: None,
-        };
-        let _initialize_response = crate::requests::on_initialize(&mut state, initialize_params)
-            .await
-            .expect("Could not initialize LSP server");
+        let (mut state, noir_text_document) = init_lsp_server("execution_success/7_function").await;
 
         let params = GotoDefinitionParams {
             text_document_position_params: lsp_types::TextDocumentPositionParams {
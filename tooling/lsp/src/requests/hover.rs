@@ -0,0 +1,145 @@
+use std::future::{self, Future};
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::opcode_estimate::estimate_acir_opcodes;
+use crate::types::HoverResult;
+use crate::LspState;
+use crate::{parse_diff, resolve_package_for_source_path};
+use async_lsp::{ErrorCode, ResponseError};
+
+use lsp_types::{Hover, HoverContents, HoverParams, MarkupContent, MarkupKind};
+use nargo::insert_all_files_for_workspace_into_file_manager;
+use noirc_driver::{check_crate, file_manager_with_stdlib};
+use noirc_frontend::monomorphization::{ast::Program, monomorphize};
+use noirc_frontend::Type;
+
+use super::{position_to_byte_index, to_lsp_location};
+
+pub(crate) fn on_hover_request(
+    state: &mut LspState,
+    params: HoverParams,
+) -> impl Future<Output = Result<HoverResult, ResponseError>> {
+    let result = on_hover_inner(state, params);
+    future::ready(result)
+}
+
+/// Shows an estimate of the number of ACIR opcodes of the function being hovered over.
+fn on_hover_inner(state: &mut LspState, params: HoverParams) -> Result<HoverResult, ResponseError> {
+    let file_path =
+        params.text_document_position_params.text_document.uri.to_file_path().map_err(|_| {
+            ResponseError::new(ErrorCode::REQUEST_FAILED, "URI is not a valid file path")
+        })?;
+
+    let (workspace, package) =
+        resolve_package_for_source_path(state.root_path.as_deref(), file_path.as_path())
+            .map_err(|err| ResponseError::new(ErrorCode::REQUEST_FAILED, err.to_string()))?;
+
+    let mut workspace_file_manager = file_manager_with_stdlib(&workspace.root_dir);
+    insert_all_files_for_workspace_into_file_manager(&workspace, &mut workspace_file_manager);
+    let parsed_files = parse_diff(&workspace_file_manager, state);
+
+    let (mut context, crate_id) =
+        nargo::prepare_package(&workspace_file_manager, &parsed_files, &package);
+
+    // Monomorphization expects a program which type checks, so nothing is estimated until the
+    // errors have been fixed.
+    if check_crate(&mut context, crate_id, false, false).is_err() {
+        return Ok(None);
+    }
+
+    let files = context.file_manager.as_file_map();
+    let file_id = context.file_manager.name_to_id(file_path.clone()).ok_or(ResponseError::new(
+        ErrorCode::REQUEST_FAILED,
+        format!("Could not find file in file manager. File path: {:?}", file_path),
+    ))?;
+    let byte_index =
+        position_to_byte_index(files, file_id, &params.text_document_position_params.position)
+            .map_err(|err| {
+                ResponseError::new(
+                    ErrorCode::REQUEST_FAILED,
+                    format!("Could not convert position to byte index. Error: {:?}", err),
+                )
+            })?;
+
+    let search_for_location = noirc_errors::Location {
+        file: file_id,
+        span: noirc_errors::Span::single_char(byte_index as u32),
+    };
+
+    let interner = &context.def_interner;
+    let Some(func_id) = interner.find_function_at(search_for_location) else {
+        return Ok(None);
+    };
+    let func_meta = interner.function_meta(&func_id);
+    if !func_meta.has_body || matches!(func_meta.typ, Type::Forall(..)) {
+        // Generic functions only have a size once they're called with concrete types.
+        return Ok(None);
+    }
+
+    // Monomorphization fails if the function calls a function with generics which violate its
+    // numeric bounds, and may still panic on programs it doesn't support. Neither of them should
+    // take down the language server, so no estimate is shown instead.
+    let estimate = panic::catch_unwind(AssertUnwindSafe(|| {
+        let program = monomorphize(func_id, interner).ok()?;
+        let estimate = if program.main().unconstrained {
+            "Unconstrained function, executed by the prover outside of the circuit".to_string()
+        } else {
+            let opcodes = estimate_acir_opcodes(&program, Program::main_id());
+            format!("Estimated ACIR opcodes: ~{opcodes}")
+        };
+        Some(estimate)
+    }));
+    let Ok(Some(estimate)) = estimate else {
+        return Ok(None);
+    };
+
+    let range = to_lsp_location(files, file_id, func_meta.name.location.span)
+        .map(|location| location.range);
+    Ok(Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: estimate,
+        }),
+        range,
+    }))
+}
+
+#[cfg(test)]
+mod hover_tests {
+
+    use lsp_types::Position;
+    use tokio::test;
+
+    use super::*;
+    use crate::requests::init_lsp_server;
+
+    #[test]
+    async fn test_on_hover() {
+        let (mut state, noir_text_document) = init_lsp_server("execution_success/7_function").await;
+
+        // Hovering over the name of `main`.
+        let source = std::fs::read_to_string(noir_text_document.to_file_path().unwrap())
+            .expect("Could not read the source of the program");
+        let line = source
+            .lines()
+            .position(|line| line.starts_with("fn main("))
+            .expect("Expected the program to have a `main` function");
+        let params = HoverParams {
+            text_document_position_params: lsp_types::TextDocumentPositionParams {
+                text_document: lsp_types::TextDocumentIdentifier { uri: noir_text_document },
+                position: Position { line: line as u32, character: "fn ".len() as u32 },
+            },
+            work_done_progress_params: Default::default(),
+        };
+
+        let response = on_hover_request(&mut state, params)
+            .await
+            .expect("Could execute on_hover_request")
+            .expect("Expected a hover for `main`");
+
+        let HoverContents::Markup(contents) = response.contents else {
+            panic!("Expected markup contents");
+        };
+        assert!(contents.value.starts_with("Estimated ACIR opcodes"));
+    }
+}
@@ -0,0 +1,116 @@
+use std::future::{self, Future};
+
+use crate::types::InlayHintResult;
+use crate::LspState;
+use crate::{parse_diff, resolve_package_for_source_path};
+use async_lsp::{ErrorCode, ResponseError};
+
+use lsp_types::{InlayHint, InlayHintKind, InlayHintLabel, InlayHintParams};
+use nargo::insert_all_files_for_workspace_into_file_manager;
+use noirc_driver::file_manager_with_stdlib;
+
+use super::to_lsp_location;
+
+pub(crate) fn on_inlay_hint_request(
+    state: &mut LspState,
+    params: InlayHintParams,
+) -> impl Future<Output = Result<InlayHintResult, ResponseError>> {
+    let result = on_inlay_hint_inner(state, params);
+    future::ready(result)
+}
+
+fn on_inlay_hint_inner(
+    state: &mut LspState,
+    params: InlayHintParams,
+) -> Result<InlayHintResult, ResponseError> {
+    let file_path = params.text_document.uri.to_file_path().map_err(|_| {
+        ResponseError::new(ErrorCode::REQUEST_FAILED, "URI is not a valid file path")
+    })?;
+
+    let (workspace, package) =
+        resolve_package_for_source_path(state.root_path.as_deref(), file_path.as_path())
+            .map_err(|err| ResponseError::new(ErrorCode::REQUEST_FAILED, err.to_string()))?;
+
+    let package_root_path: String = package.root_dir.as_os_str().to_string_lossy().into();
+
+    let mut workspace_file_manager = file_manager_with_stdlib(&workspace.root_dir);
+    insert_all_files_for_workspace_into_file_manager(&workspace, &mut workspace_file_manager);
+    let parsed_files = parse_diff(&workspace_file_manager, state);
+
+    let (mut context, crate_id) =
+        nargo::prepare_package(&workspace_file_manager, &parsed_files, &package);
+
+    let interner;
+    if let Some(def_interner) = state.cached_definitions.get(&package_root_path) {
+        interner = def_interner;
+    } else {
+        // We ignore the warnings and errors produced by compilation, hints are still given for
+        // the code which could be type checked.
+        let _ = noirc_driver::check_crate(&mut context, crate_id, false, false);
+        interner = &context.def_interner;
+    }
+
+    let files = context.file_manager.as_file_map();
+    let file_id = context.file_manager.name_to_id(file_path.clone()).ok_or(ResponseError::new(
+        ErrorCode::REQUEST_FAILED,
+        format!("Could not find file in file manager. File path: {:?}", file_path),
+    ))?;
+
+    let hints: Vec<_> = interner
+        .type_hints(file_id)
+        .into_iter()
+        .filter_map(|hint| {
+            let position = to_lsp_location(files, file_id, hint.location.span)?.range.end;
+            if position < params.range.start || position > params.range.end {
+                return None;
+            }
+
+            Some(InlayHint {
+                position,
+                label: InlayHintLabel::String(hint.label),
+                kind: Some(InlayHintKind::TYPE),
+                text_edits: None,
+                tooltip: None,
+                padding_left: None,
+                padding_right: None,
+                data: None,
+            })
+        })
+        .collect();
+
+    Ok(Some(hints))
+}
+
+#[cfg(test)]
+mod inlay_hint_tests {
+
+    use lsp_types::{Position, Range};
+    use tokio::test;
+
+    use super::*;
+    use crate::requests::init_lsp_server;
+
+    #[test]
+    async fn test_on_inlay_hint() {
+        let (mut state, noir_text_document) = init_lsp_server("execution_success/7_function").await;
+
+        let params = InlayHintParams {
+            text_document: lsp_types::TextDocumentIdentifier { uri: noir_text_document },
+            range: Range {
+                start: Position { line: 0, character: 0 },
+                end: Position { line: u32::MAX, character: 0 },
+            },
+            work_done_progress_params: Default::default(),
+        };
+
+        let response = on_inlay_hint_request(&mut state, params)
+            .await
+            .expect("Could execute on_inlay_hint_request");
+
+        // `let my = my2 { aa: ss, bb: ss };` in `main` is given the type of the struct.
+        let hints = response.expect("Expected inlay hints");
+        assert!(hints
+            .iter()
+            .any(|hint| matches!(&hint.label, InlayHintLabel::String(label) if label == ": my2")));
+    }
+}
@@ -28,15 +28,24 @@ use crate::{
 mod code_lens_request;
 mod goto_declaration;
 mod goto_definition;
+mod hover;
+mod inlay_hint;
 mod profile_run;
+mod references;
+mod rename;
+mod semantic_tokens;
+mod symbols;
 mod test_run;
 mod tests;
 
 pub(crate) use {
     code_lens_request::collect_lenses_for_package, code_lens_request::on_code_lens_request,
     goto_declaration::on_goto_declaration_request, goto_definition::on_goto_definition_request,
-    goto_definition::on_goto_type_definition_request, profile_run::on_profile_run_request,
-    test_run::on_test_run_request, tests::on_tests_request,
+    goto_definition::on_goto_type_definition_request, hover::on_hover_request,
+    inlay_hint::on_inlay_hint_request, profile_run::on_profile_run_request,
+    references::on_references_request, rename::on_rename_request,
+    semantic_tokens::on_semantic_tokens_full_request, symbols::on_document_symbol_request,
+    symbols::on_workspace_symbol_request, test_run::on_test_run_request, tests::on_tests_request,
 };
 
 /// LSP client will send initialization request after the server has started.
@@ -106,6 +115,20 @@ pub(crate) fn on_initialize(
                 definition_provider: Some(lsp_types::OneOf::Left(true)),
                 declaration_provider: Some(DeclarationCapability::Simple(true)),
                 type_definition_provider: Some(TypeDefinitionProviderCapability::Simple(true)),
+                references_provider: Some(lsp_types::OneOf::Left(true)),
+                inlay_hint_provider: Some(lsp_types::OneOf::Left(true)),
+                rename_provider: Some(lsp_types::OneOf::Left(true)),
+                document_symbol_provider: Some(lsp_types::OneOf::Left(true)),
+                workspace_symbol_provider: Some(lsp_types::OneOf::Left(true)),
+                semantic_tokens_provider: Some(
+                    lsp_types::SemanticTokensOptions {
+                        legend: semantic_tokens::semantic_tokens_legend(),
+                        full: Some(lsp_types::SemanticTokensFullOptions::Bool(true)),
+                        ..Default::default()
+                    }
+                    .into(),
+                ),
+                hover_provider: Some(lsp_types::HoverProviderCapability::Simple(true)),
             },
             server_info: None,
         })
@@ -219,6 +242,43 @@ pub(crate) fn on_shutdown(
     async { Ok(()) }
 }
 
+/// Initializes a language server for the project in `directory`, given relative to the
+/// `test_programs` directory, returning it along with the URI of the project's `src/main.nr`.
+#[cfg(test)]
+pub(crate) async fn init_lsp_server(directory: &str) -> (LspState, Url) {
+    let client = async_lsp::ClientSocket::new_closed();
+    let mut state = LspState::new(&client, acvm::blackbox_solver::StubbedBlackBoxSolver);
+
+    let root_path = std::env::current_dir()
+        .unwrap()
+        .join("../../test_programs")
+        .join(directory)
+        .canonicalize()
+        .expect("Could not resolve root path");
+    let noir_text_document = Url::from_file_path(root_path.join("src/main.nr").as_path())
+        .expect("Could not convert text document path to URI");
+    let root_uri =
+        Some(Url::from_file_path(root_path.as_path()).expect("Could not convert root path to URI"));
+
+    #[allow(deprecated)]
+    let initialize_params = InitializeParams {
+        process_id: Default::default(),
+        root_path: None,
+        root_uri,
+        initialization_options: None,
+        capabilities: Default::default(),
+        trace: Some(lsp_types::TraceValue::Verbose),
+        workspace_folders: None,
+        client_info: None,
+        locale: None,
+    };
+    let _initialize_response = on_initialize(&mut state, initialize_params)
+        .await
+        .expect("Could not initialize LSP server");
+
+    (state, noir_text_document)
+}
+
 #[cfg(test)]
 mod initialization {
     use acvm::blackbox_solver::StubbedBlackBoxSolver;
@@ -0,0 +1,112 @@
+use std::future::{self, Future};
+
+use crate::types::ReferencesResult;
+use crate::LspState;
+use crate::{parse_diff, resolve_package_for_source_path};
+use async_lsp::{ErrorCode, ResponseError};
+
+use lsp_types::ReferenceParams;
+use nargo::insert_all_files_for_workspace_into_file_manager;
+use noirc_driver::file_manager_with_stdlib;
+
+use super::{position_to_byte_index, to_lsp_location};
+
+pub(crate) fn on_references_request(
+    state: &mut LspState,
+    params: ReferenceParams,
+) -> impl Future<Output = Result<ReferencesResult, ResponseError>> {
+    let result = on_references_inner(state, params);
+    future::ready(result)
+}
+
+fn on_references_inner(
+    state: &mut LspState,
+    params: ReferenceParams,
+) -> Result<ReferencesResult, ResponseError> {
+    let file_path =
+        params.text_document_position.text_document.uri.to_file_path().map_err(|_| {
+            ResponseError::new(ErrorCode::REQUEST_FAILED, "URI is not a valid file path")
+        })?;
+
+    let (workspace, package) =
+        resolve_package_for_source_path(state.root_path.as_deref(), file_path.as_path())
+            .map_err(|err| ResponseError::new(ErrorCode::REQUEST_FAILED, err.to_string()))?;
+
+    let package_root_path: String = package.root_dir.as_os_str().to_string_lossy().into();
+
+    let mut workspace_file_manager = file_manager_with_stdlib(&workspace.root_dir);
+    insert_all_files_for_workspace_into_file_manager(&workspace, &mut workspace_file_manager);
+    let parsed_files = parse_diff(&workspace_file_manager, state);
+
+    let (mut context, crate_id) =
+        nargo::prepare_package(&workspace_file_manager, &parsed_files, &package);
+
+    let interner;
+    if let Some(def_interner) = state.cached_definitions.get(&package_root_path) {
+        interner = def_interner;
+    } else {
+        // We ignore the warnings and errors produced by compilation while resolving the references
+        let _ = noirc_driver::check_crate(&mut context, crate_id, false, false);
+        interner = &context.def_interner;
+    }
+
+    let files = context.file_manager.as_file_map();
+    let file_id = context.file_manager.name_to_id(file_path.clone()).ok_or(ResponseError::new(
+        ErrorCode::REQUEST_FAILED,
+        format!("Could not find file in file manager. File path: {:?}", file_path),
+    ))?;
+    let byte_index =
+        position_to_byte_index(files, file_id, &params.text_document_position.position).map_err(
+            |err| {
+                ResponseError::new(
+                    ErrorCode::REQUEST_FAILED,
+                    format!("Could not convert position to byte index. Error: {:?}", err),
+                )
+            },
+        )?;
+
+    let search_for_location = noirc_errors::Location {
+        file: file_id,
+        span: noirc_errors::Span::single_char(byte_index as u32),
+    };
+
+    let locations: Vec<_> = interner
+        .find_references(search_for_location, params.context.include_declaration)
+        .into_iter()
+        .filter_map(|location| to_lsp_location(files, location.file, location.span))
+        .collect();
+
+    Ok(if locations.is_empty() { None } else { Some(locations) })
+}
+
+#[cfg(test)]
+mod references_tests {
+
+    use lsp_types::Position;
+    use tokio::test;
+
+    use super::*;
+    use crate::requests::init_lsp_server;
+
+    #[test]
+    async fn test_on_references() {
+        let (mut state, noir_text_document) = init_lsp_server("execution_success/7_function").await;
+
+        let params = ReferenceParams {
+            text_document_position: lsp_types::TextDocumentPositionParams {
+                text_document: lsp_types::TextDocumentIdentifier { uri: noir_text_document },
+                position: Position { line: 95, character: 5 },
+            },
+            context: lsp_types::ReferenceContext { include_declaration: true },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+
+        let response = on_references_request(&mut state, params)
+            .await
+            .expect("Could execute on_references_request");
+
+        // The definition is included along with the reference the request was made on.
+        assert!(response.is_some_and(|locations| locations.len() >= 2));
+    }
+}
@@ -0,0 +1,314 @@
+use std::collections::{BTreeSet, HashMap};
+use std::future::{self, Future};
+
+use crate::types::RenameResult;
+use crate::LspState;
+use crate::{package_includes_file, parse_diff, resolve_package_for_source_path};
+use async_lsp::{ErrorCode, ResponseError};
+
+use lsp_types::{
+    DocumentChangeOperation, DocumentChanges, OneOf, OptionalVersionedTextDocumentIdentifier,
+    RenameFile, RenameParams, ResourceOp, TextDocumentEdit, TextEdit, Url, WorkspaceEdit,
+};
+use nargo::insert_all_files_for_workspace_into_file_manager;
+use noirc_driver::file_manager_with_stdlib;
+
+use super::{position_to_byte_index, to_lsp_location};
+
+pub(crate) fn on_rename_request(
+    state: &mut LspState,
+    params: RenameParams,
+) -> impl Future<Output = Result<RenameResult, ResponseError>> {
+    let result = on_rename_inner(state, params);
+    future::ready(result)
+}
+
+fn on_rename_inner(
+    state: &mut LspState,
+    params: RenameParams,
+) -> Result<RenameResult, ResponseError> {
+    let file_path =
+        params.text_document_position.text_document.uri.to_file_path().map_err(|_| {
+            ResponseError::new(ErrorCode::REQUEST_FAILED, "URI is not a valid file path")
+        })?;
+
+    let (workspace, package) =
+        resolve_package_for_source_path(state.root_path.as_deref(), file_path.as_path())
+            .map_err(|err| ResponseError::new(ErrorCode::REQUEST_FAILED, err.to_string()))?;
+
+    let mut workspace_file_manager = file_manager_with_stdlib(&workspace.root_dir);
+    insert_all_files_for_workspace_into_file_manager(&workspace, &mut workspace_file_manager);
+    let parsed_files = parse_diff(&workspace_file_manager, state);
+
+    let files = workspace_file_manager.as_file_map();
+    let file_id =
+        workspace_file_manager.name_to_id(file_path.clone()).ok_or(ResponseError::new(
+            ErrorCode::REQUEST_FAILED,
+            format!("Could not find file in file manager. File path: {:?}", file_path),
+        ))?;
+    let byte_index =
+        position_to_byte_index(files, file_id, &params.text_document_position.position).map_err(
+            |err| {
+                ResponseError::new(
+                    ErrorCode::REQUEST_FAILED,
+                    format!("Could not convert position to byte index. Error: {:?}", err),
+                )
+            },
+        )?;
+
+    let search_for_location = noirc_errors::Location {
+        file: file_id,
+        span: noirc_errors::Span::single_char(byte_index as u32),
+    };
+
+    // The other packages of the workspace which depend on the file refer to the item too, so it
+    // is renamed in each of them. Files are shared between their crate graphs, so the same
+    // locations are found in each package.
+    let dependents = workspace.members.iter().filter(|member| {
+        member.root_dir != package.root_dir && package_includes_file(member, file_path.as_path())
+    });
+    let mut locations = BTreeSet::new();
+    let mut module_file = None;
+    for package in std::iter::once(&package).chain(dependents) {
+        let (mut context, crate_id) =
+            nargo::prepare_package(&workspace_file_manager, &parsed_files, package);
+
+        // We ignore the warnings and errors produced by compilation while resolving the references
+        let _ = noirc_driver::check_crate(&mut context, crate_id, false, false);
+
+        let rename = context
+            .rename(search_for_location, &params.new_name)
+            .map_err(|err| ResponseError::new(ErrorCode::REQUEST_FAILED, err.to_string()))?;
+
+        // Only the sources of the workspace can be edited, dependencies are left untouched.
+        let is_in_workspace = |file_id| {
+            workspace_file_manager
+                .path(file_id)
+                .is_some_and(|path| path.starts_with(&workspace.root_dir))
+        };
+        if let Some(location) =
+            rename.locations.iter().find(|location| !is_in_workspace(location.file))
+        {
+            let path = workspace_file_manager.path(location.file);
+            return Err(ResponseError::new(
+                ErrorCode::REQUEST_FAILED,
+                format!(
+                    "`{}` is referred to outside of the workspace in {:?}",
+                    rename.old_name, path
+                ),
+            ));
+        }
+
+        locations
+            .extend(rename.locations.into_iter().map(|location| (location.file, location.span)));
+        module_file = module_file.or(rename.module_file);
+    }
+
+    let mut edits: HashMap<Url, Vec<TextEdit>> = HashMap::new();
+    for (file, span) in locations {
+        if let Some(location) = to_lsp_location(files, file, span) {
+            let edit = TextEdit { range: location.range, new_text: params.new_name.clone() };
+            edits.entry(location.uri).or_default().push(edit);
+        }
+    }
+
+    let mut operations: Vec<_> = edits
+        .into_iter()
+        .map(|(uri, edits)| {
+            DocumentChangeOperation::Edit(TextDocumentEdit {
+                text_document: OptionalVersionedTextDocumentIdentifier { uri, version: None },
+                edits: edits.into_iter().map(OneOf::Left).collect(),
+            })
+        })
+        .collect();
+
+    // Modules declared with `mod foo;` are found by their file name, so the file is renamed too.
+    // The submodules of `foo.nr` are found in the `foo` directory next to it, which is moved
+    // along with it.
+    if let Some(module_file) = module_file {
+        let old_path = workspace_file_manager.path(module_file).ok_or(ResponseError::new(
+            ErrorCode::REQUEST_FAILED,
+            "Could not find the file of the module",
+        ))?;
+        let mut renames = Vec::new();
+        if old_path.file_stem().is_some_and(|stem| stem == "mod") {
+            let old_path = old_path.parent().unwrap_or(old_path);
+            renames.push((old_path.to_path_buf(), old_path.with_file_name(&params.new_name)));
+        } else {
+            let new_path = old_path.with_file_name(format!("{}.nr", params.new_name));
+            renames.push((old_path.to_path_buf(), new_path));
+            let submodules = old_path.with_extension("");
+            if submodules.is_dir() {
+                let new_path = submodules.with_file_name(&params.new_name);
+                renames.push((submodules, new_path));
+            }
+        }
+        for (old_path, new_path) in renames {
+            if let (Ok(old_uri), Ok(new_uri)) =
+                (Url::from_file_path(old_path), Url::from_file_path(new_path))
+            {
+                operations.push(DocumentChangeOperation::Op(ResourceOp::Rename(RenameFile {
+                    old_uri,
+                    new_uri,
+                    options: None,
+                    annotation_id: None,
+                })));
+            }
+        }
+    }
+
+    Ok(Some(WorkspaceEdit {
+        document_changes: Some(DocumentChanges::Operations(operations)),
+        ..Default::default()
+    }))
+}
+
+#[cfg(test)]
+mod rename_tests {
+
+    use lsp_types::Position;
+    use tokio::test;
+
+    use super::*;
+    use crate::requests::init_lsp_server;
+
+    /// Renames the item at `position` of `file`, given relative to the project in `directory`.
+    async fn rename_at(
+        directory: &str,
+        file: &str,
+        position: Position,
+        new_name: &str,
+    ) -> Result<RenameResult, ResponseError> {
+        let (mut state, _) = init_lsp_server(directory).await;
+        let root_path = state.root_path.clone().expect("Expected the LSP server to have a root");
+        let noir_text_document = Url::from_file_path(root_path.join(file))
+            .expect("Could not convert text document path to URI");
+
+        let params = RenameParams {
+            text_document_position: lsp_types::TextDocumentPositionParams {
+                text_document: lsp_types::TextDocumentIdentifier { uri: noir_text_document },
+                position,
+            },
+            new_name: new_name.to_string(),
+            work_done_progress_params: Default::default(),
+        };
+
+        on_rename_request(&mut state, params).await
+    }
+
+    #[test]
+    async fn test_on_rename() {
+        let response = rename_at(
+            "execution_success/7_function",
+            "src/main.nr",
+            Position { line: 95, character: 5 },
+            "check_pair",
+        )
+        .await
+        .expect("Could execute on_rename_request");
+
+        let Some(DocumentChanges::Operations(operations)) =
+            response.and_then(|edit| edit.document_changes)
+        else {
+            panic!("Expected the rename to be returned as document changes");
+        };
+        // Both the declaration of `test_multiple5` and its call in `main` are renamed.
+        let edits: usize = operations
+            .iter()
+            .map(|operation| match operation {
+                DocumentChangeOperation::Edit(edit) => edit.edits.len(),
+                DocumentChangeOperation::Op(_) => 0,
+            })
+            .sum();
+        assert_eq!(edits, 2);
+    }
+
+    #[test]
+    async fn test_on_rename_rejects_conflicts() {
+        let conflict = rename_at(
+            "execution_success/7_function",
+            "src/main.nr",
+            Position { line: 95, character: 5 },
+            "test_multiple4",
+        )
+        .await;
+        assert!(conflict.is_err());
+
+        let invalid_name = rename_at(
+            "execution_success/7_function",
+            "src/main.nr",
+            Position { line: 95, character: 5 },
+            "fn",
+        )
+        .await;
+        assert!(invalid_name.is_err());
+    }
+
+    /// Returns the files edited by `response` and the files and directories it renames.
+    fn changed_paths(response: RenameResult) -> (Vec<String>, Vec<(String, String)>) {
+        let Some(DocumentChanges::Operations(operations)) =
+            response.and_then(|edit| edit.document_changes)
+        else {
+            panic!("Expected the rename to be returned as document changes");
+        };
+        let mut edited = Vec::new();
+        let mut renamed = Vec::new();
+        for operation in operations {
+            match operation {
+                DocumentChangeOperation::Edit(edit) => {
+                    edited.push(edit.text_document.uri.path().to_string());
+                }
+                DocumentChangeOperation::Op(ResourceOp::Rename(rename)) => {
+                    renamed.push((
+                        rename.old_uri.path().to_string(),
+                        rename.new_uri.path().to_string(),
+                    ));
+                }
+                DocumentChangeOperation::Op(_) => (),
+            }
+        }
+        (edited, renamed)
+    }
+
+    #[test]
+    async fn test_on_rename_in_dependent_packages() {
+        // `ReExportMeFromAnotherLib` is declared in `library2`, then re-exported by `library`
+        // which `binary` imports it from.
+        let response = rename_at(
+            "compile_success_empty/workspace_reexport_bug",
+            "library2/src/lib.nr",
+            Position { line: 2, character: 7 },
+            "Renamed",
+        )
+        .await
+        .expect("Could execute on_rename_request");
+
+        let (edited, _) = changed_paths(response);
+        assert_eq!(edited.len(), 3);
+        for file in ["library2/src/lib.nr", "library/src/lib.nr", "binary/src/main.nr"] {
+            assert!(edited.iter().any(|path| path.ends_with(file)), "{file} is not renamed");
+        }
+    }
+
+    #[test]
+    async fn test_on_rename_moves_submodules() {
+        // `foo.nr` declares `mod bar;`, which is found in `foo/bar.nr`.
+        let response = rename_at(
+            "execution_success/modules_more",
+            "src/main.nr",
+            Position { line: 0, character: 4 },
+            "baz",
+        )
+        .await
+        .expect("Could execute on_rename_request");
+
+        let (_, renamed) = changed_paths(response);
+        let is_renamed = |old: &str, new: &str| {
+            renamed
+                .iter()
+                .any(|(old_path, new_path)| old_path.ends_with(old) && new_path.ends_with(new))
+        };
+        assert!(is_renamed("src/foo.nr", "src/baz.nr"));
+        assert!(is_renamed("src/foo", "src/baz"));
+    }
+}
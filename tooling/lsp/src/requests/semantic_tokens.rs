@@ -0,0 +1,159 @@
+use std::future::{self, Future};
+
+use crate::types::SemanticTokensFullResult;
+use crate::LspState;
+use crate::{parse_diff, resolve_package_for_source_path};
+use async_lsp::{ErrorCode, ResponseError};
+
+use lsp_types::{
+    SemanticToken, SemanticTokenModifier, SemanticTokenType, SemanticTokens, SemanticTokensLegend,
+    SemanticTokensParams, SemanticTokensResult,
+};
+use nargo::insert_all_files_for_workspace_into_file_manager;
+use noirc_driver::file_manager_with_stdlib;
+use noirc_frontend::hir::semantic_tokens::SemanticTokenKind;
+
+use super::to_lsp_location;
+
+const TOKEN_TYPES: [SemanticTokenType; 6] = [
+    SemanticTokenType::STRUCT,
+    SemanticTokenType::TYPE,
+    SemanticTokenType::INTERFACE,
+    SemanticTokenType::TYPE_PARAMETER,
+    SemanticTokenType::VARIABLE,
+    SemanticTokenType::FUNCTION,
+];
+
+const DECLARATION: u32 = 1 << 0;
+const READONLY: u32 = 1 << 1;
+const UNCONSTRAINED: u32 = 1 << 2;
+const ORACLE: u32 = 1 << 3;
+
+/// The token types and modifiers the server classifies names with. Calls to unconstrained
+/// functions and oracles are functions with an `unconstrained` or `oracle` modifier.
+pub(crate) fn semantic_tokens_legend() -> SemanticTokensLegend {
+    SemanticTokensLegend {
+        token_types: TOKEN_TYPES.to_vec(),
+        token_modifiers: vec![
+            SemanticTokenModifier::DECLARATION,
+            SemanticTokenModifier::READONLY,
+            SemanticTokenModifier::new("unconstrained"),
+            SemanticTokenModifier::new("oracle"),
+        ],
+    }
+}
+
+pub(crate) fn on_semantic_tokens_full_request(
+    state: &mut LspState,
+    params: SemanticTokensParams,
+) -> impl Future<Output = Result<SemanticTokensFullResult, ResponseError>> {
+    let result = on_semantic_tokens_full_inner(state, params);
+    future::ready(result)
+}
+
+fn on_semantic_tokens_full_inner(
+    state: &mut LspState,
+    params: SemanticTokensParams,
+) -> Result<SemanticTokensFullResult, ResponseError> {
+    let file_path = params.text_document.uri.to_file_path().map_err(|_| {
+        ResponseError::new(ErrorCode::REQUEST_FAILED, "URI is not a valid file path")
+    })?;
+
+    let (workspace, package) =
+        resolve_package_for_source_path(state.root_path.as_deref(), file_path.as_path())
+            .map_err(|err| ResponseError::new(ErrorCode::REQUEST_FAILED, err.to_string()))?;
+
+    let mut workspace_file_manager = file_manager_with_stdlib(&workspace.root_dir);
+    insert_all_files_for_workspace_into_file_manager(&workspace, &mut workspace_file_manager);
+    let parsed_files = parse_diff(&workspace_file_manager, state);
+
+    let (mut context, crate_id) =
+        nargo::prepare_package(&workspace_file_manager, &parsed_files, &package);
+
+    // We ignore the warnings and errors produced by compilation, the names which resolved are
+    // still highlighted.
+    let _ = noirc_driver::check_crate(&mut context, crate_id, false, false);
+
+    let files = context.file_manager.as_file_map();
+    let file_id = context.file_manager.name_to_id(file_path.clone()).ok_or(ResponseError::new(
+        ErrorCode::REQUEST_FAILED,
+        format!("Could not find file in file manager. File path: {:?}", file_path),
+    ))?;
+
+    // Each token is encoded relative to the start of the previous one.
+    let mut data = Vec::new();
+    let (mut previous_line, mut previous_start) = (0, 0);
+    for token in context.semantic_tokens(file_id) {
+        let Some(location) = to_lsp_location(files, file_id, token.location.span) else {
+            continue;
+        };
+        let range = location.range;
+        if range.start.line != range.end.line {
+            continue;
+        }
+
+        let (token_type, mut modifiers) = match token.kind {
+            SemanticTokenKind::Struct => (SemanticTokenType::STRUCT, 0),
+            SemanticTokenKind::TypeAlias => (SemanticTokenType::TYPE, 0),
+            SemanticTokenKind::Trait => (SemanticTokenType::INTERFACE, 0),
+            SemanticTokenKind::Generic => (SemanticTokenType::TYPE_PARAMETER, 0),
+            SemanticTokenKind::Global => (SemanticTokenType::VARIABLE, READONLY),
+            SemanticTokenKind::Function => (SemanticTokenType::FUNCTION, 0),
+            SemanticTokenKind::UnconstrainedFunction => {
+                (SemanticTokenType::FUNCTION, UNCONSTRAINED)
+            }
+            SemanticTokenKind::OracleFunction => (SemanticTokenType::FUNCTION, ORACLE),
+        };
+        if token.is_declaration {
+            modifiers |= DECLARATION;
+        }
+
+        let delta_line = range.start.line - previous_line;
+        let delta_start = if delta_line == 0 {
+            range.start.character - previous_start
+        } else {
+            range.start.character
+        };
+        data.push(SemanticToken {
+            delta_line,
+            delta_start,
+            length: range.end.character - range.start.character,
+            token_type: TOKEN_TYPES.iter().position(|typ| *typ == token_type).unwrap_or(0) as u32,
+            token_modifiers_bitset: modifiers,
+        });
+        (previous_line, previous_start) = (range.start.line, range.start.character);
+    }
+
+    Ok(Some(SemanticTokensResult::Tokens(SemanticTokens { result_id: None, data })))
+}
+
+#[cfg(test)]
+mod semantic_tokens_tests {
+
+    use tokio::test;
+
+    use super::*;
+    use crate::requests::init_lsp_server;
+
+    #[test]
+    async fn test_on_semantic_tokens_full() {
+        let (mut state, noir_text_document) = init_lsp_server("execution_success/7_function").await;
+
+        let params = SemanticTokensParams {
+            text_document: lsp_types::TextDocumentIdentifier { uri: noir_text_document },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        let response = on_semantic_tokens_full_request(&mut state, params)
+            .await
+            .expect("Could execute on_semantic_tokens_full_request");
+
+        let Some(SemanticTokensResult::Tokens(tokens)) = response else {
+            panic!("Expected semantic tokens for the document");
+        };
+        let function = TOKEN_TYPES.iter().position(|typ| *typ == SemanticTokenType::FUNCTION);
+        let structs = TOKEN_TYPES.iter().position(|typ| *typ == SemanticTokenType::STRUCT);
+        assert!(tokens.data.iter().any(|token| Some(token.token_type as usize) == function));
+        assert!(tokens.data.iter().any(|token| Some(token.token_type as usize) == structs));
+    }
+}
@@ -0,0 +1,200 @@
+use std::future::{self, Future};
+
+use crate::types::{DocumentSymbolResult, WorkspaceSymbolResult};
+use crate::LspState;
+use crate::{parse_diff, resolve_package_for_source_path, resolve_root_workspace};
+use async_lsp::{ErrorCode, ResponseError};
+
+use fm::codespan_files::Files;
+use lsp_types::{
+    DocumentSymbol, DocumentSymbolParams, DocumentSymbolResponse, SymbolInformation,
+    WorkspaceSymbolParams, WorkspaceSymbolResponse,
+};
+use nargo::insert_all_files_for_workspace_into_file_manager;
+use noirc_driver::file_manager_with_stdlib;
+use noirc_frontend::hir::symbols::{Symbol, SymbolKind};
+
+use super::to_lsp_location;
+
+pub(crate) fn on_document_symbol_request(
+    state: &mut LspState,
+    params: DocumentSymbolParams,
+) -> impl Future<Output = Result<DocumentSymbolResult, ResponseError>> {
+    let result = on_document_symbol_inner(state, params);
+    future::ready(result)
+}
+
+pub(crate) fn on_workspace_symbol_request(
+    state: &mut LspState,
+    params: WorkspaceSymbolParams,
+) -> impl Future<Output = Result<WorkspaceSymbolResult, ResponseError>> {
+    let result = on_workspace_symbol_inner(state, params);
+    future::ready(result)
+}
+
+fn on_document_symbol_inner(
+    state: &mut LspState,
+    params: DocumentSymbolParams,
+) -> Result<DocumentSymbolResult, ResponseError> {
+    let file_path = params.text_document.uri.to_file_path().map_err(|_| {
+        ResponseError::new(ErrorCode::REQUEST_FAILED, "URI is not a valid file path")
+    })?;
+
+    let (workspace, package) =
+        resolve_package_for_source_path(state.root_path.as_deref(), file_path.as_path())
+            .map_err(|err| ResponseError::new(ErrorCode::REQUEST_FAILED, err.to_string()))?;
+
+    let mut workspace_file_manager = file_manager_with_stdlib(&workspace.root_dir);
+    insert_all_files_for_workspace_into_file_manager(&workspace, &mut workspace_file_manager);
+    let parsed_files = parse_diff(&workspace_file_manager, state);
+
+    let (mut context, crate_id) =
+        nargo::prepare_package(&workspace_file_manager, &parsed_files, &package);
+
+    // We ignore the warnings and errors produced by compilation, the outline only needs the
+    // items which were collected.
+    let _ = noirc_driver::check_crate(&mut context, crate_id, false, false);
+
+    let files = context.file_manager.as_file_map();
+    let file_id = context.file_manager.name_to_id(file_path.clone()).ok_or(ResponseError::new(
+        ErrorCode::REQUEST_FAILED,
+        format!("Could not find file in file manager. File path: {:?}", file_path),
+    ))?;
+
+    let symbols: Vec<_> = context
+        .document_symbols(file_id)
+        .into_iter()
+        .filter_map(|symbol| to_document_symbol(files, symbol))
+        .collect();
+
+    Ok(Some(DocumentSymbolResponse::Nested(symbols)))
+}
+
+/// Searches the packages of the workspace opened in the editor, dependencies aren't included.
+fn on_workspace_symbol_inner(
+    state: &mut LspState,
+    params: WorkspaceSymbolParams,
+) -> Result<WorkspaceSymbolResult, ResponseError> {
+    let Some(workspace) = resolve_root_workspace(state.root_path.as_deref()) else {
+        return Ok(None);
+    };
+
+    let mut workspace_file_manager = file_manager_with_stdlib(&workspace.root_dir);
+    insert_all_files_for_workspace_into_file_manager(&workspace, &mut workspace_file_manager);
+    let parsed_files = parse_diff(&workspace_file_manager, state);
+
+    let mut symbols = Vec::new();
+    for package in &workspace {
+        let (mut context, crate_id) =
+            nargo::prepare_package(&workspace_file_manager, &parsed_files, package);
+        let _ = noirc_driver::check_crate(&mut context, crate_id, false, false);
+
+        let files = context.file_manager.as_file_map();
+        for (symbol, container_name) in context.workspace_symbols(crate_id, &params.query) {
+            let Some(location) = to_lsp_location(files, symbol.location.file, symbol.location.span)
+            else {
+                continue;
+            };
+            #[allow(deprecated)]
+            symbols.push(SymbolInformation {
+                name: symbol.name,
+                kind: to_lsp_symbol_kind(symbol.kind),
+                tags: None,
+                deprecated: None,
+                location,
+                container_name,
+            });
+        }
+    }
+
+    Ok(Some(WorkspaceSymbolResponse::Flat(symbols)))
+}
+
+fn to_document_symbol<'a, F>(files: &'a F, symbol: Symbol) -> Option<DocumentSymbol>
+where
+    F: Files<'a, FileId = fm::FileId> + ?Sized,
+{
+    let range = to_lsp_location(files, symbol.location.file, symbol.location.span)?.range;
+    let selection_range =
+        to_lsp_location(files, symbol.name_location.file, symbol.name_location.span)?.range;
+    let children: Vec<_> =
+        symbol.children.into_iter().filter_map(|child| to_document_symbol(files, child)).collect();
+
+    #[allow(deprecated)]
+    Some(DocumentSymbol {
+        name: symbol.name,
+        detail: None,
+        kind: to_lsp_symbol_kind(symbol.kind),
+        tags: None,
+        deprecated: None,
+        range,
+        selection_range,
+        children: (!children.is_empty()).then_some(children),
+    })
+}
+
+fn to_lsp_symbol_kind(kind: SymbolKind) -> lsp_types::SymbolKind {
+    match kind {
+        SymbolKind::Module => lsp_types::SymbolKind::MODULE,
+        SymbolKind::Struct => lsp_types::SymbolKind::STRUCT,
+        SymbolKind::Trait => lsp_types::SymbolKind::INTERFACE,
+        SymbolKind::Impl => lsp_types::SymbolKind::OBJECT,
+        SymbolKind::Function => lsp_types::SymbolKind::FUNCTION,
+        SymbolKind::Method => lsp_types::SymbolKind::METHOD,
+        SymbolKind::Global => lsp_types::SymbolKind::CONSTANT,
+        SymbolKind::TypeAlias => lsp_types::SymbolKind::TYPE_PARAMETER,
+    }
+}
+
+#[cfg(test)]
+mod symbols_tests {
+
+    use tokio::test;
+
+    use super::*;
+    use crate::requests::init_lsp_server;
+
+    #[test]
+    async fn test_on_document_symbol() {
+        let (mut state, noir_text_document) = init_lsp_server("execution_success/7_function").await;
+
+        let params = DocumentSymbolParams {
+            text_document: lsp_types::TextDocumentIdentifier { uri: noir_text_document },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        let response = on_document_symbol_request(&mut state, params)
+            .await
+            .expect("Could execute on_document_symbol_request");
+
+        let Some(DocumentSymbolResponse::Nested(symbols)) = response else {
+            panic!("Expected a nested outline of the document");
+        };
+        assert!(symbols
+            .iter()
+            .any(|symbol| symbol.name == "main" && symbol.kind == lsp_types::SymbolKind::FUNCTION));
+        assert!(symbols.iter().any(
+            |symbol| symbol.name == "my_struct" && symbol.kind == lsp_types::SymbolKind::STRUCT
+        ));
+    }
+
+    #[test]
+    async fn test_on_workspace_symbol() {
+        let (mut state, _) = init_lsp_server("execution_success/7_function").await;
+
+        let params = WorkspaceSymbolParams {
+            query: "tmult6".to_string(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        let response = on_workspace_symbol_request(&mut state, params)
+            .await
+            .expect("Could execute on_workspace_symbol_request");
+
+        let Some(WorkspaceSymbolResponse::Flat(symbols)) = response else {
+            panic!("Expected a flat list of symbols");
+        };
+        let names: Vec<_> = symbols.iter().map(|symbol| symbol.name.as_str()).collect();
+        assert_eq!(names, vec!["test_multiple6"]);
+    }
+}
@@ -1,6 +1,8 @@
 use fm::FileId;
 use lsp_types::{
-    DeclarationCapability, DefinitionOptions, OneOf, TypeDefinitionProviderCapability,
+    DeclarationCapability, DefinitionOptions, DocumentSymbolOptions, HoverProviderCapability,
+    InlayHintServerCapabilities, OneOf, ReferencesOptions, RenameOptions,
+    SemanticTokensServerCapabilities, TypeDefinitionProviderCapability, WorkspaceSymbolOptions,
 };
 use noirc_driver::DebugFile;
 use noirc_errors::{debug_info::OpCodesCount, Location};
@@ -27,8 +29,11 @@ pub(crate) mod request {
 
     // Re-providing lsp_types that we don't need to override
     pub(crate) use lsp_types::request::{
-        CodeLensRequest as CodeLens, Formatting, GotoDeclaration, GotoDefinition,
-        GotoTypeDefinition, Shutdown,
+        CodeLensRequest as CodeLens, DocumentSymbolRequest as DocumentSymbol, Formatting,
+        GotoDeclaration, GotoDefinition, GotoTypeDefinition, HoverRequest as Hover,
+        InlayHintRequest as InlayHint, References, Rename,
+        SemanticTokensFullRequest as SemanticTokensFull, Shutdown,
+        WorkspaceSymbolRequest as WorkspaceSymbol,
     };
 
     #[derive(Debug)]
@@ -125,6 +130,34 @@ pub(crate) struct ServerCapabilities {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) type_definition_provider: Option<TypeDefinitionProviderCapability>,
 
+    /// The server provides find references support.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) references_provider: Option<OneOf<bool, ReferencesOptions>>,
+
+    /// The server provides inlay hints.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) inlay_hint_provider: Option<OneOf<bool, InlayHintServerCapabilities>>,
+
+    /// The server provides rename support.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) rename_provider: Option<OneOf<bool, RenameOptions>>,
+
+    /// The server provides document symbol support.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) document_symbol_provider: Option<OneOf<bool, DocumentSymbolOptions>>,
+
+    /// The server provides workspace symbol support.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) workspace_symbol_provider: Option<OneOf<bool, WorkspaceSymbolOptions>>,
+
+    /// The server provides semantic tokens.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) semantic_tokens_provider: Option<SemanticTokensServerCapabilities>,
+
+    /// The server provides hover support.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) hover_provider: Option<HoverProviderCapability>,
+
     /// The server provides code lens.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) code_lens_provider: Option<CodeLensOptions>,
@@ -234,3 +267,10 @@ pub(crate) struct NargoProfileRunResult {
 pub(crate) type CodeLensResult = Option<Vec<CodeLens>>;
 pub(crate) type GotoDefinitionResult = Option<lsp_types::GotoDefinitionResponse>;
 pub(crate) type GotoDeclarationResult = Option<lsp_types::request::GotoDeclarationResponse>;
+pub(crate) type ReferencesResult = Option<Vec<lsp_types::Location>>;
+pub(crate) type InlayHintResult = Option<Vec<lsp_types::InlayHint>>;
+pub(crate) type HoverResult = Option<lsp_types::Hover>;
+pub(crate) type RenameResult = Option<lsp_types::WorkspaceEdit>;
+pub(crate) type DocumentSymbolResult = Option<lsp_types::DocumentSymbolResponse>;
+pub(crate) type WorkspaceSymbolResult = Option<lsp_types::WorkspaceSymbolResponse>;
+pub(crate) type SemanticTokensFullResult = Option<lsp_types::SemanticTokensResult>;
@@ -55,6 +55,16 @@ pub struct ContractFunctionArtifact {
     )]
     pub bytecode: Circuit,
 
+    /// Saved so that the function can be executed from the artifact without recompiling the
+    /// contract. Left out of the artifact when `bytecode` makes no calls.
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "Circuit::serialize_circuits_base64",
+        deserialize_with = "Circuit::deserialize_circuits_base64"
+    )]
+    pub functions: Vec<Circuit>,
+
     #[serde(
         serialize_with = "DebugInfo::serialize_compressed_base64_json",
         deserialize_with = "DebugInfo::deserialize_compressed_base64_json"
@@ -70,6 +80,7 @@ impl From<ContractFunction> for ContractFunctionArtifact {
             is_internal: func.is_internal,
             abi: func.abi,
             bytecode: func.bytecode,
+            functions: func.functions,
             debug_symbols: func.debug,
         }
     }
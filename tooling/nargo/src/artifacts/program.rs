@@ -26,6 +26,16 @@ pub struct ProgramArtifact {
     )]
     pub bytecode: Circuit,
 
+    /// The circuits called by `bytecode`, left out of the artifact when there are none so that the
+    /// artifacts of programs without calls are unchanged.
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "Circuit::serialize_circuits_base64",
+        deserialize_with = "Circuit::deserialize_circuits_base64"
+    )]
+    pub functions: Vec<Circuit>,
+
     #[serde(
         serialize_with = "DebugInfo::serialize_compressed_base64_json",
         deserialize_with = "DebugInfo::deserialize_compressed_base64_json"
@@ -43,6 +53,7 @@ impl From<CompiledProgram> for ProgramArtifact {
             abi: program.abi,
             noir_version: program.noir_version,
             bytecode: program.circuit,
+            functions: program.functions,
             debug_symbols: program.debug,
             file_map: program.file_map,
         }
@@ -56,6 +67,7 @@ impl From<ProgramArtifact> for CompiledProgram {
             abi: program.abi,
             noir_version: program.noir_version,
             circuit: program.bytecode,
+            functions: program.functions,
             debug: program.debug_symbols,
             file_map: program.file_map,
             warnings: vec![],
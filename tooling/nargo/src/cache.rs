@@ -0,0 +1,231 @@
+//! Fingerprints of previously built packages, used to skip building packages which have not
+//! changed since they were last built.
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
+
+use fm::{FileManager, NormalizePath};
+use noirc_driver::{CompileOptions, NOIR_ARTIFACT_VERSION_STRING};
+use serde::{Deserialize, Serialize};
+
+use crate::package::{Dependency, Package};
+
+/// Records the fingerprint of each package when it was last built.
+///
+/// Packages are only recorded if they were built without any errors or warnings,
+/// so that skipping a package never hides a diagnostic from the user.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BuildCache {
+    pub noir_version: String,
+
+    /// Fingerprints of the packages which were last checked successfully.
+    pub checked: BTreeMap<String, u64>,
+
+    /// Fingerprints of the binary packages which were last compiled successfully.
+    pub compiled: BTreeMap<String, u64>,
+}
+
+impl Default for BuildCache {
+    fn default() -> Self {
+        BuildCache {
+            noir_version: NOIR_ARTIFACT_VERSION_STRING.to_owned(),
+            checked: BTreeMap::new(),
+            compiled: BTreeMap::new(),
+        }
+    }
+}
+
+impl BuildCache {
+    /// Returns true if the cache was written by the current version of the compiler.
+    pub fn is_current_version(&self) -> bool {
+        self.noir_version == NOIR_ARTIFACT_VERSION_STRING
+    }
+}
+
+/// Returns true if the given options allow skipping the build of unchanged packages.
+///
+/// Any option requesting debug output or checks of the compiler requires the package to actually
+/// be built.
+pub fn can_use_build_cache(options: &CompileOptions) -> bool {
+    !(options.force_compile
+        || options.show_ssa
+        || options.show_brillig
+        || options.show_pass_stats
+        || !options.ssa_trace.is_empty()
+        || options.show_monomorphized
+        || options.print_acir
+        || options.check_determinism
+        || options.verify_ssa)
+}
+
+/// Computes a fingerprint of the source files of `package` and of all of its dependencies,
+/// along with the compile options which affect the result of building it.
+///
+/// The standard library is not hashed as it only changes along with the compiler version.
+pub fn package_fingerprint(
+    file_manager: &FileManager,
+    package: &Package,
+    options: &CompileOptions,
+) -> u64 {
+    let mut source_dirs = Vec::new();
+    collect_source_dirs(package, &mut source_dirs);
+    let mut features = Vec::new();
+    collect_features(package, &mut features);
+
+    let mut sources: Vec<(&Path, &str)> = file_manager
+        .as_file_map()
+        .all_file_ids()
+        .filter_map(|&file_id| {
+            let path = file_manager.path(file_id)?;
+            if source_dirs.iter().any(|dir| path.starts_with(dir)) {
+                Some((path, file_manager.fetch_file(file_id)?))
+            } else {
+                None
+            }
+        })
+        .collect();
+    // Sort the files so that the fingerprint does not depend on the order they were loaded in.
+    sources.sort_unstable();
+
+    let options = (
+        format!("{:?}", options.expression_width),
+        options.optimization_level,
+        options.array_mux_threshold,
+        options.acir_calls,
+        options.outline_repeated_code,
+        options.disable_macros,
+        options.only_acir,
+        &options.target_backend,
+        &options.supported_black_boxes,
+        options.instrument_debug,
+    );
+    fxhash::hash64(&(NOIR_ARTIFACT_VERSION_STRING, sources, features, options))
+}
+
+/// Collects the directories containing the source files of `package` and its dependencies.
+fn collect_source_dirs(package: &Package, source_dirs: &mut Vec<PathBuf>) {
+    if let Some(source_dir) = package.entry_path.parent() {
+        source_dirs.push(source_dir.normalize());
+    }
+    for dependency in package.dependencies.values() {
+        match dependency {
+            Dependency::Local { package } | Dependency::Remote { package } => {
+                collect_source_dirs(package, source_dirs);
+            }
+        }
+    }
+}
+
+/// Collects the features enabled in `package` and its dependencies.
+fn collect_features<'a>(package: &'a Package, features: &mut Vec<(String, &'a BTreeSet<String>)>) {
+    features.push((package.name.to_string(), &package.features));
+    for dependency in package.dependencies.values() {
+        match dependency {
+            Dependency::Local { package } | Dependency::Remote { package } => {
+                collect_features(package, features);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{BTreeMap, BTreeSet},
+        path::{Path, PathBuf},
+    };
+
+    use fm::FileManager;
+    use noirc_driver::CompileOptions;
+
+    use super::package_fingerprint;
+    use crate::package::{Dependency, Package, PackageType};
+
+    fn package(name: &str, dependencies: Vec<Package>) -> Package {
+        let root_dir = PathBuf::from(format!("/workspace/{name}"));
+        Package {
+            version: None,
+            compiler_required_version: None,
+            entry_path: root_dir.join("src/main.nr"),
+            root_dir,
+            package_type: PackageType::Binary,
+            name: name.parse().unwrap(),
+            dependencies: dependencies
+                .into_iter()
+                .map(|package| (package.name.clone(), Dependency::Local { package }))
+                .collect::<BTreeMap<_, _>>(),
+            features: BTreeSet::new(),
+        }
+    }
+
+    /// Returns a file manager holding the entry files of `bin` and its dependency `dep`.
+    fn file_manager(bin_source: &str, dep_source: &str) -> FileManager {
+        let mut file_manager = FileManager::new(Path::new("/"));
+        file_manager
+            .add_file_with_source(Path::new("workspace/bin/src/main.nr"), bin_source.to_owned());
+        file_manager
+            .add_file_with_source(Path::new("workspace/dep/src/main.nr"), dep_source.to_owned());
+        file_manager.add_file_with_source(
+            Path::new("workspace/other/src/main.nr"),
+            "fn bar() {}".to_owned(),
+        );
+        file_manager
+    }
+
+    fn fingerprint(file_manager: &FileManager, options: &CompileOptions) -> u64 {
+        let bin = package("bin", vec![package("dep", Vec::new())]);
+        package_fingerprint(file_manager, &bin, options)
+    }
+
+    #[test]
+    fn unchanged_package_hits_the_cache() {
+        let options = CompileOptions::default();
+        let fingerprint_before =
+            fingerprint(&file_manager("fn main() {}", "fn foo() {}"), &options);
+        let fingerprint_after = fingerprint(&file_manager("fn main() {}", "fn foo() {}"), &options);
+        assert_eq!(fingerprint_before, fingerprint_after);
+
+        // Files outside of the package and its dependencies do not affect it
+        let mut file_manager = file_manager("fn main() {}", "fn foo() {}");
+        file_manager.add_file_with_source(
+            Path::new("workspace/other/src/lib.nr"),
+            "fn baz() {}".to_owned(),
+        );
+        assert_eq!(fingerprint_before, fingerprint(&file_manager, &options));
+    }
+
+    #[test]
+    fn changed_package_misses_the_cache() {
+        let options = CompileOptions::default();
+        let fingerprint_before =
+            fingerprint(&file_manager("fn main() {}", "fn foo() {}"), &options);
+        let fingerprint_after =
+            fingerprint(&file_manager("fn main() { foo() }", "fn foo() {}"), &options);
+        assert_ne!(fingerprint_before, fingerprint_after);
+
+        let file_manager = file_manager("fn main() {}", "fn foo() {}");
+        let options_after = CompileOptions { outline_repeated_code: true, ..options.clone() };
+        assert_ne!(
+            fingerprint(&file_manager, &options),
+            fingerprint(&file_manager, &options_after)
+        );
+    }
+
+    #[test]
+    fn changed_dependency_invalidates_the_cache() {
+        let options = CompileOptions::default();
+        let fingerprint_before =
+            fingerprint(&file_manager("fn main() {}", "fn foo() {}"), &options);
+        let fingerprint_after =
+            fingerprint(&file_manager("fn main() {}", "fn foo() { assert(false); }"), &options);
+        assert_ne!(fingerprint_before, fingerprint_after);
+
+        // Enabling a feature of a dependency also changes how the package is built
+        let file_manager = file_manager("fn main() {}", "fn foo() {}");
+        let mut dep = package("dep", Vec::new());
+        dep.features.insert("extra".to_owned());
+        let bin = package("bin", vec![dep]);
+        assert_ne!(fingerprint_before, package_fingerprint(&file_manager, &bin, &options));
+    }
+}
@@ -23,3 +23,5 @@ pub const PKG_FILE: &str = "Nargo.toml";
 pub const PROOF_EXT: &str = "proof";
 /// The extension for files containing proof witnesses.
 pub const WITNESS_EXT: &str = "gz";
+/// The extension for files containing proof witnesses as JSON.
+pub const WITNESS_JSON_EXT: &str = "witness.json";
@@ -1,6 +1,12 @@
+use std::{collections::BTreeMap, fmt};
+
 use acvm::{
-    acir::circuit::OpcodeLocation,
-    pwg::{ErrorLocation, OpcodeResolutionError},
+    acir::{
+        circuit::OpcodeLocation,
+        native_types::{Expression, Witness, WitnessMap},
+    },
+    pwg::{get_value, ErrorLocation, OpcodeResolutionError},
+    FieldElement,
 };
 use noirc_errors::{
     debug_info::DebugInfo, reporter::ReportedErrors, CustomDiagnostic, FileDiagnostic,
@@ -60,11 +66,14 @@ impl NargoError {
         };
 
         match execution_error {
-            ExecutionError::AssertionFailed(message, _) => Some(message),
+            ExecutionError::AssertionFailed(message, ..) => Some(message),
+            ExecutionError::UnsatisfiedConstraint(..) => None,
+            ExecutionError::MissingAcirFunction(_) => None,
             ExecutionError::SolvingError(error) => match error {
                 OpcodeResolutionError::IndexOutOfBounds { .. }
                 | OpcodeResolutionError::OpcodeNotSolvable(_)
-                | OpcodeResolutionError::UnsatisfiedConstrain { .. } => None,
+                | OpcodeResolutionError::UnsatisfiedConstrain { .. }
+                | OpcodeResolutionError::AcirCallOutputsMismatch { .. } => None,
                 OpcodeResolutionError::BrilligFunctionFailed { message, .. } => Some(message),
                 OpcodeResolutionError::BlackBoxFunctionFailed(_, reason) => Some(reason),
             },
@@ -72,13 +81,34 @@ impl NargoError {
     }
 }
 
+/// Errors encountered while checking a circuit's constraints with an SMT solver.
+#[derive(Debug, Error)]
+pub enum SmtError {
+    #[error("Opcode {0} can't be translated to SMT-LIB, memory and call opcodes are unsupported")]
+    UnsupportedOpcode(usize),
+
+    #[error("Could not run SMT solver `{0}`: {1}")]
+    Solver(String, std::io::Error),
+
+    #[error("Unexpected output from SMT solver: {0}")]
+    UnexpectedOutput(String),
+}
+
 #[derive(Debug, Error)]
 pub enum ExecutionError {
     #[error("Failed assertion: '{}'", .0)]
-    AssertionFailed(String, Vec<OpcodeLocation>),
+    AssertionFailed(String, Vec<OpcodeLocation>, Option<ConstraintFailure>),
+
+    /// An arithmetic constraint without an assertion message wasn't satisfied.
+    #[error("Failed constraint")]
+    UnsatisfiedConstraint(Vec<OpcodeLocation>, ConstraintFailure),
 
     #[error(transparent)]
     SolvingError(#[from] OpcodeResolutionError),
+
+    /// A call opcode called a function which isn't part of the program.
+    #[error("Called ACIR function {0} which the program does not define")]
+    MissingAcirFunction(u32),
 }
 
 /// Extracts the opcode locations from a nargo error.
@@ -91,7 +121,8 @@ fn extract_locations_from_error(
             call_stack,
             ..
         })
-        | ExecutionError::AssertionFailed(_, call_stack) => Some(call_stack.clone()),
+        | ExecutionError::AssertionFailed(_, call_stack, _)
+        | ExecutionError::UnsatisfiedConstraint(call_stack, _) => Some(call_stack.clone()),
         ExecutionError::SolvingError(OpcodeResolutionError::IndexOutOfBounds {
             opcode_location: error_location,
             ..
@@ -136,7 +167,7 @@ pub fn try_to_diagnose_runtime_error(
     let location = source_locations.last()?;
 
     let message = match nargo_err {
-        NargoError::ExecutionError(ExecutionError::AssertionFailed(message, _)) => {
+        NargoError::ExecutionError(ExecutionError::AssertionFailed(message, ..)) => {
             format!("Assertion failed: '{message}'")
         }
         NargoError::ExecutionError(ExecutionError::SolvingError(
@@ -150,9 +181,146 @@ pub fn try_to_diagnose_runtime_error(
         _ => nargo_err.to_string(),
     };
 
-    Some(
-        CustomDiagnostic::simple_error(message, String::new(), location.span)
-            .in_file(location.file)
-            .with_call_stack(source_locations),
-    )
+    let mut diagnostic = CustomDiagnostic::simple_error(message, String::new(), location.span);
+    if let ExecutionError::AssertionFailed(_, _, Some(failure))
+    | ExecutionError::UnsatisfiedConstraint(_, failure) = execution_error
+    {
+        failure.add_notes(&mut diagnostic);
+    }
+
+    Some(diagnostic.in_file(location.file).with_call_stack(source_locations))
+}
+
+/// The arithmetic constraint which an execution failed on along with the values it was evaluated
+/// with, so that users can see why it wasn't satisfied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstraintFailure {
+    /// The expression which was asserted to be zero
+    pub expression: Expression,
+    /// The values of the witnesses in `expression` when it was evaluated
+    pub operands: BTreeMap<Witness, FieldElement>,
+}
+
+impl ConstraintFailure {
+    pub fn new(expression: &Expression, witness_map: &WitnessMap) -> Self {
+        let witnesses = expression
+            .mul_terms
+            .iter()
+            .flat_map(|(_, lhs, rhs)| [*lhs, *rhs])
+            .chain(expression.linear_combinations.iter().map(|(_, witness)| *witness));
+        let operands = witnesses
+            .filter_map(|witness| witness_map.get(&witness).map(|value| (witness, *value)))
+            .collect();
+
+        ConstraintFailure { expression: expression.clone(), operands }
+    }
+
+    /// Returns the value of the expression, which is `None` if any of its witnesses is unknown.
+    pub fn evaluate(&self) -> Option<FieldElement> {
+        get_value(&self.expression, &WitnessMap::from(self.operands.clone())).ok()
+    }
+
+    fn add_notes(&self, diagnostic: &mut CustomDiagnostic) {
+        diagnostic.add_note(format!("Constraint: {self} == 0"));
+        if !self.operands.is_empty() {
+            let operands = self
+                .operands
+                .iter()
+                .map(|(witness, value)| format!("_{} = {value}", witness.witness_index()))
+                .collect::<Vec<_>>()
+                .join(", ");
+            diagnostic.add_note(format!("Evaluated with {operands}"));
+        }
+        if let Some(value) = self.evaluate() {
+            diagnostic.add_note(format!("Constraint evaluated to {value} instead of 0"));
+        }
+    }
+}
+
+impl fmt::Display for ConstraintFailure {
+    /// Writes the expression as a sum of its terms, e.g. `2 * _1 * _2 - _3 + 5`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut terms: Vec<(FieldElement, String)> = Vec::new();
+        for (coefficient, lhs, rhs) in &self.expression.mul_terms {
+            let witnesses = format!("_{} * _{}", lhs.witness_index(), rhs.witness_index());
+            terms.push((*coefficient, witnesses));
+        }
+        for (coefficient, witness) in &self.expression.linear_combinations {
+            terms.push((*coefficient, format!("_{}", witness.witness_index())));
+        }
+        if !self.expression.q_c.is_zero() || terms.is_empty() {
+            terms.push((self.expression.q_c, String::new()));
+        }
+
+        for (index, (coefficient, witnesses)) in terms.into_iter().enumerate() {
+            // Negative coefficients are written as a subtraction of their absolute value.
+            let is_negative = coefficient.to_string().starts_with('-');
+            let magnitude = if is_negative { -coefficient } else { coefficient };
+            match (index, is_negative) {
+                (0, true) => write!(f, "-")?,
+                (0, false) => (),
+                (_, true) => write!(f, " - ")?,
+                (_, false) => write!(f, " + ")?,
+            }
+
+            if witnesses.is_empty() {
+                write!(f, "{magnitude}")?;
+            } else if magnitude.is_one() {
+                write!(f, "{witnesses}")?;
+            } else {
+                write!(f, "{magnitude} * {witnesses}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use acvm::{
+        acir::native_types::{Expression, Witness, WitnessMap},
+        FieldElement,
+    };
+
+    use super::ConstraintFailure;
+
+    #[test]
+    fn renders_and_evaluates_failing_constraint() {
+        let expression = Expression {
+            mul_terms: vec![(FieldElement::from(2u128), Witness(1), Witness(2))],
+            linear_combinations: vec![(-FieldElement::one(), Witness(3))],
+            q_c: FieldElement::from(5u128),
+        };
+        let witness_map = WitnessMap::from(BTreeMap::from([
+            (Witness(1), FieldElement::from(2u128)),
+            (Witness(2), FieldElement::from(3u128)),
+            (Witness(3), FieldElement::from(6u128)),
+            (Witness(4), FieldElement::from(7u128)),
+        ]));
+
+        let failure = ConstraintFailure::new(&expression, &witness_map);
+
+        assert_eq!(failure.to_string(), "2 * _1 * _2 - _3 + 5");
+        assert_eq!(
+            failure.operands.keys().copied().collect::<Vec<_>>(),
+            vec![Witness(1), Witness(2), Witness(3)]
+        );
+        assert_eq!(failure.evaluate(), Some(FieldElement::from(11u128)));
+    }
+
+    #[test]
+    fn unknown_operands_are_not_evaluated() {
+        let expression = Expression {
+            mul_terms: vec![],
+            linear_combinations: vec![(FieldElement::one(), Witness(1))],
+            q_c: FieldElement::zero(),
+        };
+
+        let failure = ConstraintFailure::new(&expression, &WitnessMap::new());
+
+        assert_eq!(failure.to_string(), "_1");
+        assert_eq!(failure.evaluate(), None);
+    }
 }
@@ -8,6 +8,7 @@
 //! Noir Package Manager abbreviated is npm, which is already taken.
 
 pub mod artifacts;
+pub mod cache;
 pub mod constants;
 pub mod errors;
 pub mod ops;
@@ -36,6 +37,7 @@ pub fn prepare_dependencies(
         match dep {
             Dependency::Remote { package } | Dependency::Local { package } => {
                 let crate_id = prepare_dependency(context, &package.entry_path);
+                context.crate_graph.enable_features(crate_id, package.features.iter().cloned());
                 add_dep(context, parent_crate, crate_id, dep_name.clone());
                 prepare_dependencies(context, crate_id, &package.dependencies);
             }
@@ -119,6 +121,7 @@ pub fn prepare_package<'file_manager, 'parsed_files>(
     let mut context = Context::from_ref_file_manager(file_manager, parsed_files);
 
     let crate_id = prepare_crate(&mut context, &package.entry_path);
+    context.crate_graph.enable_features(crate_id, package.features.iter().cloned());
 
     prepare_dependencies(&mut context, crate_id, &package.dependencies);
 
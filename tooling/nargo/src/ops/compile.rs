@@ -26,14 +26,10 @@ pub fn compile_workspace(
         .partition(|package| package.is_binary());
 
     // Compile all of the packages in parallel.
-    let program_results: Vec<CompilationResult<CompiledProgram>> = binary_packages
-        .par_iter()
-        .map(|package| compile_program(file_manager, parsed_files, package, compile_options, None))
-        .collect();
-    let contract_results: Vec<CompilationResult<CompiledContract>> = contract_packages
-        .par_iter()
-        .map(|package| compile_contract(file_manager, parsed_files, package, compile_options))
-        .collect();
+    let (program_results, contract_results) = rayon::join(
+        || compile_programs(file_manager, parsed_files, &binary_packages, compile_options),
+        || compile_contracts(file_manager, parsed_files, &contract_packages, compile_options),
+    );
 
     // Report any warnings/errors which were encountered during compilation.
     let compiled_programs: Vec<CompiledProgram> = program_results
@@ -62,6 +58,38 @@ pub fn compile_workspace(
     Ok((compiled_programs, compiled_contracts))
 }
 
+/// Compiles each of `packages` as a program in parallel.
+///
+/// The results are returned in the same order as `packages` so that diagnostics can be reported
+/// deterministically.
+pub fn compile_programs(
+    file_manager: &FileManager,
+    parsed_files: &ParsedFiles,
+    packages: &[Package],
+    compile_options: &CompileOptions,
+) -> Vec<CompilationResult<CompiledProgram>> {
+    packages
+        .par_iter()
+        .map(|package| compile_program(file_manager, parsed_files, package, compile_options, None))
+        .collect()
+}
+
+/// Compiles each of `packages` as a contract in parallel.
+///
+/// The results are returned in the same order as `packages` so that diagnostics can be reported
+/// deterministically.
+pub fn compile_contracts(
+    file_manager: &FileManager,
+    parsed_files: &ParsedFiles,
+    packages: &[Package],
+    compile_options: &CompileOptions,
+) -> Vec<CompilationResult<CompiledContract>> {
+    packages
+        .par_iter()
+        .map(|package| compile_contract(file_manager, parsed_files, package, compile_options))
+        .collect()
+}
+
 pub fn compile_program(
     file_manager: &FileManager,
     parsed_files: &ParsedFiles,
@@ -70,7 +98,8 @@ pub fn compile_program(
     cached_program: Option<CompiledProgram>,
 ) -> CompilationResult<CompiledProgram> {
     let (mut context, crate_id) = prepare_package(file_manager, parsed_files, package);
-    noirc_driver::compile_main(&mut context, crate_id, compile_options, cached_program)
+    let compile_options = package_compile_options(package, compile_options);
+    noirc_driver::compile_main(&mut context, crate_id, &compile_options, cached_program)
 }
 
 pub fn compile_contract(
@@ -80,7 +109,18 @@ pub fn compile_contract(
     compile_options: &CompileOptions,
 ) -> CompilationResult<CompiledContract> {
     let (mut context, crate_id) = prepare_package(file_manager, parsed_files, package);
-    noirc_driver::compile_contract(&mut context, crate_id, compile_options)
+    let compile_options = package_compile_options(package, compile_options);
+    noirc_driver::compile_contract(&mut context, crate_id, &compile_options)
+}
+
+/// Gives each package its own subdirectory of the IR output directory, as otherwise the IR of
+/// the `main` functions of several packages would be written to the same files.
+fn package_compile_options(package: &Package, compile_options: &CompileOptions) -> CompileOptions {
+    let mut compile_options = compile_options.clone();
+    if let Some(ir_output_dir) = &mut compile_options.ir_output_dir {
+        *ir_output_dir = ir_output_dir.join(package.name.to_string());
+    }
+    compile_options
 }
 
 pub(crate) fn report_errors<T>(
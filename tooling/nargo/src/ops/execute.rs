@@ -1,20 +1,64 @@
-use acvm::pwg::{ACVMStatus, ErrorLocation, OpcodeResolutionError, ACVM};
-use acvm::BlackBoxFunctionSolver;
-use acvm::{acir::circuit::Circuit, acir::native_types::WitnessMap};
+use std::collections::BTreeMap;
 
-use crate::errors::ExecutionError;
+use acvm::acir::brillig::ForeignCallResult;
+use acvm::acir::circuit::{
+    AssertionPayload, Circuit, Opcode, OpcodeLocation, ASSERT_MESSAGE_VALUES_ORACLE,
+};
+use acvm::acir::native_types::{Witness, WitnessMap};
+use acvm::pwg::{ACVMStatus, AcirCallWaitInfo, ErrorLocation, OpcodeResolutionError, ACVM};
+use acvm::{BlackBoxFunctionSolver, FieldElement};
+use iter_extended::vecmap;
+use noirc_printable_type::PrintableValueDisplay;
+
+use crate::errors::{ConstraintFailure, ExecutionError};
 use crate::NargoError;
 
 use super::foreign_calls::ForeignCallExecutor;
 
+/// Executes `circuit`, running the ACIR `functions` it calls through call opcodes.
 #[tracing::instrument(level = "trace", skip_all)]
 pub fn execute_circuit<B: BlackBoxFunctionSolver, F: ForeignCallExecutor>(
     circuit: &Circuit,
+    functions: &[Circuit],
     initial_witness: WitnessMap,
     blackbox_solver: &B,
     foreign_call_executor: &mut F,
 ) -> Result<WitnessMap, NargoError> {
     let mut acvm = ACVM::new(blackbox_solver, &circuit.opcodes, initial_witness);
+    solve(&mut acvm, circuit, functions, blackbox_solver, foreign_call_executor)?;
+    Ok(acvm.finalize())
+}
+
+/// Executes `circuit` in the same way as [`execute_circuit`] while counting the number of times
+/// each Brillig opcode is executed.
+///
+/// The counts are returned for each Brillig call, keyed by the index of the call's ACIR opcode.
+#[tracing::instrument(level = "trace", skip_all)]
+pub fn execute_circuit_with_brillig_profiling<B: BlackBoxFunctionSolver, F: ForeignCallExecutor>(
+    circuit: &Circuit,
+    functions: &[Circuit],
+    initial_witness: WitnessMap,
+    blackbox_solver: &B,
+    foreign_call_executor: &mut F,
+) -> Result<(WitnessMap, BTreeMap<usize, Vec<usize>>), NargoError> {
+    let mut acvm = ACVM::new(blackbox_solver, &circuit.opcodes, initial_witness);
+    acvm.enable_brillig_profiling();
+    solve(&mut acvm, circuit, functions, blackbox_solver, foreign_call_executor)?;
+
+    let opcode_counts =
+        acvm.brillig_opcode_counts().cloned().expect("Brillig profiling should be enabled");
+    Ok((acvm.finalize(), opcode_counts))
+}
+
+fn solve<B: BlackBoxFunctionSolver, F: ForeignCallExecutor>(
+    acvm: &mut ACVM<B>,
+    circuit: &Circuit,
+    functions: &[Circuit],
+    blackbox_solver: &B,
+    foreign_call_executor: &mut F,
+) -> Result<(), NargoError> {
+    // The values of the message of an assertion which is failing in Brillig code
+    let mut brillig_assert_values: Option<Vec<FieldElement>> = None;
 
     loop {
         let solver_status = acvm.solve();
@@ -35,12 +79,39 @@ pub fn execute_circuit<B: BlackBoxFunctionSolver, F: ForeignCallExecutor>(
                     _ => None,
                 };
 
+                // Keep hold of the values the failing constraint was evaluated with so that the
+                // error can explain why it wasn't satisfied.
+                let constraint_failure = match &error {
+                    OpcodeResolutionError::UnsatisfiedConstrain {
+                        opcode_location: ErrorLocation::Resolved(OpcodeLocation::Acir(index)),
+                    } => match &circuit.opcodes[*index] {
+                        Opcode::AssertZero(expression) => {
+                            Some(ConstraintFailure::new(expression, acvm.witness_map()))
+                        }
+                        _ => None,
+                    },
+                    _ => None,
+                };
+
                 return Err(NargoError::ExecutionError(match call_stack {
                     Some(call_stack) => {
-                        if let Some(assert_message) = circuit.get_assert_message(
-                            *call_stack.last().expect("Call stacks should not be empty"),
-                        ) {
-                            ExecutionError::AssertionFailed(assert_message.to_owned(), call_stack)
+                        let location = *call_stack.last().expect("Call stacks should not be empty");
+                        if let Some(assert_payload) = circuit.get_assert_message(location) {
+                            let values = match location {
+                                OpcodeLocation::Acir(_) => assert_payload
+                                    .witnesses
+                                    .iter()
+                                    .map(|witness| acvm.witness_map().get(witness).copied())
+                                    .collect(),
+                                OpcodeLocation::Brillig { .. } => brillig_assert_values,
+                            };
+                            ExecutionError::AssertionFailed(
+                                format_assert_message(assert_payload, values),
+                                call_stack,
+                                constraint_failure,
+                            )
+                        } else if let Some(constraint_failure) = constraint_failure {
+                            ExecutionError::UnsatisfiedConstraint(call_stack, constraint_failure)
                         } else {
                             ExecutionError::SolvingError(error)
                         }
@@ -49,11 +120,122 @@ pub fn execute_circuit<B: BlackBoxFunctionSolver, F: ForeignCallExecutor>(
                 }));
             }
             ACVMStatus::RequiresForeignCall(foreign_call) => {
-                let foreign_call_result = foreign_call_executor.execute(&foreign_call)?;
+                let foreign_call_result = if foreign_call.function == ASSERT_MESSAGE_VALUES_ORACLE {
+                    brillig_assert_values = Some(
+                        foreign_call
+                            .inputs
+                            .iter()
+                            .flat_map(|input| input.values())
+                            .map(|value| value.to_field())
+                            .collect(),
+                    );
+                    ForeignCallResult { values: Vec::new() }
+                } else {
+                    foreign_call_executor.execute(&foreign_call)?
+                };
                 acvm.resolve_pending_foreign_call(foreign_call_result);
             }
+            ACVMStatus::RequiresAcirCall(acir_call) => {
+                let call_location = OpcodeLocation::Acir(acvm.instruction_pointer());
+                let call_results = execute_acir_call(
+                    &acir_call,
+                    functions,
+                    blackbox_solver,
+                    foreign_call_executor,
+                )
+                .map_err(|error| relocate_acir_call_error(error, call_location))?;
+                acvm.resolve_pending_acir_call(call_results);
+            }
         }
     }
 
-    Ok(acvm.finalize())
+    Ok(())
+}
+
+/// Formats the `values` of a failed assertion into its message. The message is reported as written
+/// if it isn't a format string or its values aren't known.
+fn format_assert_message(
+    assert_payload: &AssertionPayload,
+    values: Option<Vec<FieldElement>>,
+) -> String {
+    let formatted =
+        assert_payload.printable_type.as_ref().zip(values).and_then(|(printable_type, values)| {
+            PrintableValueDisplay::try_from_fmt_string_values(
+                assert_payload.message.clone(),
+                printable_type,
+                values,
+            )
+            .ok()
+        });
+    match formatted {
+        Some(formatted) => formatted.to_string(),
+        None => assert_payload.message.clone(),
+    }
+}
+
+/// Executes the function called by `acir_call`, returning the values it returns.
+fn execute_acir_call<B: BlackBoxFunctionSolver, F: ForeignCallExecutor>(
+    acir_call: &AcirCallWaitInfo,
+    functions: &[Circuit],
+    blackbox_solver: &B,
+    foreign_call_executor: &mut F,
+) -> Result<Vec<FieldElement>, NargoError> {
+    let function = functions
+        .get(acir_call.id as usize)
+        .ok_or(ExecutionError::MissingAcirFunction(acir_call.id))?;
+
+    // The parameters of a function are its private parameters, in ascending witness order.
+    let initial_witness: WitnessMap = function
+        .private_parameters
+        .iter()
+        .copied()
+        .zip(acir_call.inputs.iter().copied())
+        .collect::<BTreeMap<_, _>>()
+        .into();
+    let witness_map = execute_circuit(
+        function,
+        functions,
+        initial_witness,
+        blackbox_solver,
+        foreign_call_executor,
+    )?;
+
+    Ok(vecmap(function.return_values.indices(), |index| witness_map[&Witness(index)]))
+}
+
+/// Moves an error raised while executing a called function onto the opcode of the call, as the
+/// locations within the function don't map to the debug information of the caller.
+fn relocate_acir_call_error(error: NargoError, call_location: OpcodeLocation) -> NargoError {
+    let NargoError::ExecutionError(error) = error else {
+        return error;
+    };
+    let resolved_location = ErrorLocation::Resolved(call_location);
+    NargoError::ExecutionError(match error {
+        ExecutionError::AssertionFailed(message, ..) => {
+            ExecutionError::AssertionFailed(message, vec![call_location], None)
+        }
+        ExecutionError::UnsatisfiedConstraint(..)
+        | ExecutionError::SolvingError(OpcodeResolutionError::UnsatisfiedConstrain { .. }) => {
+            ExecutionError::SolvingError(OpcodeResolutionError::UnsatisfiedConstrain {
+                opcode_location: resolved_location,
+            })
+        }
+        ExecutionError::SolvingError(OpcodeResolutionError::IndexOutOfBounds {
+            index,
+            array_size,
+            ..
+        }) => ExecutionError::SolvingError(OpcodeResolutionError::IndexOutOfBounds {
+            opcode_location: resolved_location,
+            index,
+            array_size,
+        }),
+        ExecutionError::SolvingError(OpcodeResolutionError::BrilligFunctionFailed {
+            message,
+            ..
+        }) => ExecutionError::SolvingError(OpcodeResolutionError::BrilligFunctionFailed {
+            message,
+            call_stack: vec![call_location],
+        }),
+        error => error,
+    })
 }
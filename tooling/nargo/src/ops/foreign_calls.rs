@@ -1,10 +1,13 @@
 use acvm::{
     acir::brillig::{ForeignCallParam, ForeignCallResult, Value},
     pwg::ForeignCallWaitInfo,
+    FieldElement,
 };
 use jsonrpc::{arg as build_json_rpc_arg, minreq_http::Builder, Client};
 use noirc_printable_type::{decode_string_value, ForeignCallError, PrintableValueDisplay};
 
+mod transport;
+
 pub trait ForeignCallExecutor {
     fn execute(
         &mut self,
@@ -16,11 +19,14 @@ pub trait ForeignCallExecutor {
 /// After resolution of a foreign call, nargo will restart execution of the ACVM
 pub(crate) enum ForeignCall {
     Print,
+    Log,
     CreateMock,
     SetMockParams,
     SetMockReturns,
     SetMockTimes,
     ClearMock,
+    GetMockLastParams,
+    GetMockTimesCalled,
 }
 
 impl std::fmt::Display for ForeignCall {
@@ -33,27 +39,85 @@ impl ForeignCall {
     pub(crate) fn name(&self) -> &'static str {
         match self {
             ForeignCall::Print => "print",
+            ForeignCall::Log => "log",
             ForeignCall::CreateMock => "create_mock",
             ForeignCall::SetMockParams => "set_mock_params",
             ForeignCall::SetMockReturns => "set_mock_returns",
             ForeignCall::SetMockTimes => "set_mock_times",
             ForeignCall::ClearMock => "clear_mock",
+            ForeignCall::GetMockLastParams => "get_mock_last_params",
+            ForeignCall::GetMockTimesCalled => "get_mock_times_called",
         }
     }
 
     pub(crate) fn lookup(op_name: &str) -> Option<ForeignCall> {
         match op_name {
             "print" => Some(ForeignCall::Print),
+            "log" => Some(ForeignCall::Log),
             "create_mock" => Some(ForeignCall::CreateMock),
             "set_mock_params" => Some(ForeignCall::SetMockParams),
             "set_mock_returns" => Some(ForeignCall::SetMockReturns),
             "set_mock_times" => Some(ForeignCall::SetMockTimes),
             "clear_mock" => Some(ForeignCall::ClearMock),
+            "get_mock_last_params" => Some(ForeignCall::GetMockLastParams),
+            "get_mock_times_called" => Some(ForeignCall::GetMockTimesCalled),
+            _ => None,
+        }
+    }
+}
+
+/// The severity of a message logged through [`ForeignCall::Log`].
+///
+/// These match the values passed to the `log` oracle by `std::log`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Debug,
+    #[default]
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    fn from_field(value: FieldElement) -> Option<LogLevel> {
+        match value.try_to_u64()? {
+            0 => Some(LogLevel::Debug),
+            1 => Some(LogLevel::Info),
+            2 => Some(LogLevel::Warn),
+            3 => Some(LogLevel::Error),
             _ => None,
         }
     }
 }
 
+impl std::fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            LogLevel::Debug => "debug",
+            LogLevel::Info => "info",
+            LogLevel::Warn => "warn",
+            LogLevel::Error => "error",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl std::str::FromStr for LogLevel {
+    type Err = String;
+
+    fn from_str(level: &str) -> Result<Self, Self::Err> {
+        match level {
+            "debug" => Ok(LogLevel::Debug),
+            "info" => Ok(LogLevel::Info),
+            "warn" => Ok(LogLevel::Warn),
+            "error" => Ok(LogLevel::Error),
+            _ => Err(format!(
+                "unknown log level `{level}`, expected one of debug, info, warn or error"
+            )),
+        }
+    }
+}
+
 /// This struct represents an oracle mock. It can be used for testing programs that use oracles.
 #[derive(Debug, PartialEq, Eq, Clone)]
 struct MockedCall {
@@ -65,8 +129,12 @@ struct MockedCall {
     params: Option<Vec<ForeignCallParam>>,
     /// The result to return when this mock is called
     result: ForeignCallResult,
-    /// How many times should this mock be called before it is removed
+    /// How many more times this mock can be called before it stops matching
     times_left: Option<u64>,
+    /// How many times this mock has been called
+    times_called: u64,
+    /// The parameters this mock was last called with
+    last_called_params: Option<Vec<ForeignCallParam>>,
 }
 
 impl MockedCall {
@@ -77,13 +145,17 @@ impl MockedCall {
             params: None,
             result: ForeignCallResult { values: vec![] },
             times_left: None,
+            times_called: 0,
+            last_called_params: None,
         }
     }
 }
 
 impl MockedCall {
     fn matches(&self, name: &str, params: &[ForeignCallParam]) -> bool {
-        self.name == name && (self.params.is_none() || self.params.as_deref() == Some(params))
+        self.name == name
+            && self.times_left != Some(0)
+            && (self.params.is_none() || self.params.as_deref() == Some(params))
     }
 }
 
@@ -93,15 +165,20 @@ pub struct DefaultForeignCallExecutor {
     last_mock_id: usize,
     /// The registered mocks
     mocked_responses: Vec<MockedCall>,
-    /// Whether to print [`ForeignCall::Print`] output.
+    /// Whether to print [`ForeignCall::Print`] and [`ForeignCall::Log`] output.
     show_output: bool,
-    /// JSON RPC client to resolve foreign calls
+    /// The lowest level of [`ForeignCall::Log`] messages which are printed.
+    log_level: LogLevel,
+    /// JSON RPC client to resolve foreign calls over HTTP, WebSocket or a subprocess's stdio
     external_resolver: Option<Client>,
 }
 
 impl DefaultForeignCallExecutor {
     pub fn new(show_output: bool, resolver_url: Option<&str>) -> Self {
         let oracle_resolver = resolver_url.map(|resolver_url| {
+            if let Some(transport) = transport::connect(resolver_url) {
+                return Client::with_transport(transport);
+            }
             let transport_builder =
                 Builder::new().url(resolver_url).expect("Invalid oracle resolver URL");
             Client::with_transport(transport_builder.build())
@@ -112,6 +189,12 @@ impl DefaultForeignCallExecutor {
             ..DefaultForeignCallExecutor::default()
         }
     }
+
+    /// Only prints messages logged at `log_level` or above.
+    pub fn with_log_level(mut self, log_level: LogLevel) -> Self {
+        self.log_level = log_level;
+        self
+    }
 }
 
 impl DefaultForeignCallExecutor {
@@ -142,6 +225,23 @@ impl DefaultForeignCallExecutor {
         print!("{display_values}{}", if skip_newline { "" } else { "\n" });
         Ok(())
     }
+
+    fn execute_log(
+        foreign_call_inputs: &[ForeignCallParam],
+        log_level: LogLevel,
+    ) -> Result<(), ForeignCallError> {
+        let (level, message) =
+            foreign_call_inputs.split_first().ok_or(ForeignCallError::MissingForeignCallInputs)?;
+        let level = LogLevel::from_field(level.unwrap_value().to_field())
+            .ok_or(ForeignCallError::InvalidLogLevel)?;
+        if level < log_level {
+            return Ok(());
+        }
+
+        let display_values: PrintableValueDisplay = message.try_into()?;
+        eprintln!("[{}] {display_values}", level.to_string().to_uppercase());
+        Ok(())
+    }
 }
 
 impl ForeignCallExecutor for DefaultForeignCallExecutor {
@@ -157,6 +257,12 @@ impl ForeignCallExecutor for DefaultForeignCallExecutor {
                 }
                 Ok(ForeignCallResult { values: vec![] })
             }
+            Some(ForeignCall::Log) => {
+                if self.show_output {
+                    Self::execute_log(&foreign_call.inputs, self.log_level)?;
+                }
+                Ok(ForeignCallResult { values: vec![] })
+            }
             Some(ForeignCall::CreateMock) => {
                 let mock_oracle_name = Self::parse_string(&foreign_call.inputs[0]);
                 assert!(ForeignCall::lookup(&mock_oracle_name).is_none());
@@ -201,6 +307,26 @@ impl ForeignCallExecutor for DefaultForeignCallExecutor {
                 self.mocked_responses.retain(|response| response.id != id);
                 Ok(ForeignCallResult { values: vec![] })
             }
+            Some(ForeignCall::GetMockLastParams) => {
+                let (id, _) = Self::extract_mock_id(&foreign_call.inputs)?;
+                let last_called_params = self
+                    .find_mock_by_id(id)
+                    .unwrap_or_else(|| panic!("Unknown mock id {}", id))
+                    .last_called_params
+                    .clone()
+                    .ok_or(ForeignCallError::MockNotCalled(id))?;
+
+                Ok(ForeignCallResult { values: last_called_params })
+            }
+            Some(ForeignCall::GetMockTimesCalled) => {
+                let (id, _) = Self::extract_mock_id(&foreign_call.inputs)?;
+                let times_called = self
+                    .find_mock_by_id(id)
+                    .unwrap_or_else(|| panic!("Unknown mock id {}", id))
+                    .times_called;
+
+                Ok(ForeignCallResult { values: vec![Value::from(times_called as u128).into()] })
+            }
             None => {
                 let mock_response_position = self
                     .mocked_responses
@@ -215,12 +341,12 @@ impl ForeignCallExecutor for DefaultForeignCallExecutor {
                             .expect("Invalid position of mocked response");
                         let result = mock.result.values.clone();
 
+                        // Exhausted mocks are kept so that their calls can still be inspected.
                         if let Some(times_left) = &mut mock.times_left {
                             *times_left -= 1;
-                            if *times_left == 0 {
-                                self.mocked_responses.remove(response_position);
-                            }
                         }
+                        mock.times_called += 1;
+                        mock.last_called_params = Some(foreign_call.inputs.clone());
 
                         Ok(ForeignCallResult { values: result })
                     }
@@ -255,7 +381,10 @@ mod tests {
     use jsonrpc_core::Result as RpcResult;
     use jsonrpc_derive::rpc;
     use jsonrpc_http_server::{Server, ServerBuilder};
+    use noirc_printable_type::ForeignCallError;
     use serial_test::serial;
+    use std::net::TcpListener;
+    use tungstenite::Message;
 
     use crate::ops::{DefaultForeignCallExecutor, ForeignCallExecutor};
 
@@ -334,4 +463,54 @@ mod tests {
 
         server.close();
     }
+
+    #[test]
+    fn get_last_params_of_uncalled_mock_is_an_error() {
+        let mut executor = DefaultForeignCallExecutor::new(false, None);
+
+        let name = "foo".bytes().map(|byte| Value::from(byte as u128)).collect();
+        let create_mock = ForeignCallWaitInfo {
+            function: "create_mock".to_string(),
+            inputs: vec![ForeignCallParam::Array(name)],
+        };
+        let id = executor.execute(&create_mock).unwrap().values;
+
+        let get_last_params =
+            ForeignCallWaitInfo { function: "get_mock_last_params".to_string(), inputs: id };
+        let result = executor.execute(&get_last_params);
+        assert!(matches!(result, Err(ForeignCallError::MockNotCalled(0))));
+    }
+
+    #[test]
+    fn test_oracle_resolver_websocket_echo() {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Could not bind listener");
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+
+        // A server which answers each request by echoing back its parameters.
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut socket = tungstenite::accept(stream).unwrap();
+            while let Ok(Message::Text(message)) = socket.read() {
+                let request: serde_json::Value = serde_json::from_str(&message).unwrap();
+                let response = serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": request["id"],
+                    "result": { "values": request["params"] },
+                });
+                socket.send(Message::Text(response.to_string())).unwrap();
+            }
+        });
+
+        let mut executor = DefaultForeignCallExecutor::new(false, Some(&url));
+
+        for value in 0..3_u128 {
+            let foreign_call = ForeignCallWaitInfo {
+                function: "echo".to_string(),
+                inputs: vec![ForeignCallParam::Single(value.into())],
+            };
+
+            let result = executor.execute(&foreign_call);
+            assert_eq!(result.unwrap(), ForeignCallResult { values: foreign_call.inputs });
+        }
+    }
 }
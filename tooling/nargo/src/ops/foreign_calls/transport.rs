@@ -0,0 +1,309 @@
+//! Transports which allow foreign calls to be resolved by oracle servers which aren't HTTP services.
+//!
+//! These keep a single connection open for the lifetime of nargo, so that the resolver isn't
+//! reconnected to (or respawned) for each program which is executed. Requests are tagged with
+//! their own ids on the wire so that several of them can be awaiting a response at once, with
+//! each response being handed back to the request it answers.
+
+use std::{
+    collections::HashMap,
+    error::Error as StdError,
+    fmt,
+    io::{BufRead, BufReader, ErrorKind, Write},
+    net::TcpStream,
+    process::{ChildStdin, ChildStdout, Command, Stdio},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc, Arc, Mutex, OnceLock,
+    },
+    thread,
+    time::Duration,
+};
+
+use jsonrpc::{Error, Request, Response, Transport};
+use tungstenite::{Message, WebSocket};
+
+type BoxedError = Box<dyn StdError + Send + Sync>;
+
+/// The prefix of resolvers which are run as a subprocess speaking JSON-RPC over stdio.
+const STDIO_PREFIX: &str = "stdio:";
+
+/// Returns a transport for `resolver` if it isn't an HTTP url.
+///
+/// - `ws://<host>:<port>` connects to a WebSocket server.
+/// - `stdio:<command>` spawns `<command>` and exchanges one JSON message per line over its
+///   stdin and stdout. The command is split into its arguments as a shell would, so arguments
+///   containing spaces can be quoted.
+///
+/// Transports are shared between every executor which uses the same resolver.
+pub(super) fn connect(resolver: &str) -> Option<SharedTransport> {
+    if !resolver.starts_with("ws://") && !resolver.starts_with(STDIO_PREFIX) {
+        return None;
+    }
+    Some(SharedTransport { resolver: resolver.to_owned() })
+}
+
+/// The half of a connection which messages are written to.
+trait MessageWriter: Send {
+    fn write_message(&mut self, message: String) -> Result<(), BoxedError>;
+}
+
+/// The half of a connection which messages are read from.
+trait MessageReader: Send {
+    /// Returns the next message, or `None` once the connection has been closed.
+    fn read_message(&mut self) -> Result<Option<String>, BoxedError>;
+}
+
+impl MessageWriter for ChildStdin {
+    fn write_message(&mut self, message: String) -> Result<(), BoxedError> {
+        writeln!(self, "{message}")?;
+        self.flush()?;
+        Ok(())
+    }
+}
+
+impl MessageReader for BufReader<ChildStdout> {
+    fn read_message(&mut self) -> Result<Option<String>, BoxedError> {
+        let mut line = String::new();
+        if self.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        Ok(Some(line))
+    }
+}
+
+/// A WebSocket connection which is both read from and written to.
+///
+/// `tungstenite` writes to the socket while reading in order to answer pings, so the reader and
+/// the writer share a single [`WebSocket`] behind a lock rather than each writing frames onto
+/// their own handle of the stream. The stream has a short read timeout so that the reader
+/// regularly releases the lock for messages to be written.
+#[derive(Clone)]
+struct SharedWebSocket(Arc<Mutex<WebSocket<TcpStream>>>);
+
+/// How long the reader of a [`SharedWebSocket`] holds its lock while waiting for a message.
+const WEBSOCKET_READ_TIMEOUT: Duration = Duration::from_millis(1);
+
+impl MessageWriter for SharedWebSocket {
+    fn write_message(&mut self, message: String) -> Result<(), BoxedError> {
+        self.0.lock().unwrap().send(Message::Text(message))?;
+        Ok(())
+    }
+}
+
+impl MessageReader for SharedWebSocket {
+    fn read_message(&mut self) -> Result<Option<String>, BoxedError> {
+        loop {
+            let result = self.0.lock().unwrap().read();
+            match result {
+                Ok(Message::Text(message)) => return Ok(Some(message)),
+                Ok(Message::Binary(message)) => return Ok(Some(String::from_utf8(message)?)),
+                Ok(Message::Close(_))
+                | Err(tungstenite::Error::ConnectionClosed | tungstenite::Error::AlreadyClosed) => {
+                    return Ok(None)
+                }
+                // Pings are answered by `tungstenite` itself.
+                Ok(Message::Ping(_) | Message::Pong(_) | Message::Frame(_)) => continue,
+                Err(tungstenite::Error::Io(err))
+                    if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) =>
+                {
+                    thread::yield_now();
+                    continue;
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+    }
+}
+
+/// The requests which are waiting for a response, keyed by the id they were sent with.
+type PendingRequests = HashMap<u64, mpsc::Sender<Result<Response, String>>>;
+
+/// A JSON-RPC connection which can have several requests in flight at once.
+pub(super) struct MultiplexedTransport {
+    writer: Mutex<Box<dyn MessageWriter>>,
+    next_id: AtomicU64,
+    pending: Arc<Mutex<Option<PendingRequests>>>,
+}
+
+impl MultiplexedTransport {
+    fn new(writer: Box<dyn MessageWriter>, mut reader: Box<dyn MessageReader>) -> Self {
+        // `None` marks that the connection has been closed and no more responses will arrive.
+        let pending: Arc<Mutex<Option<PendingRequests>>> =
+            Arc::new(Mutex::new(Some(HashMap::new())));
+
+        let reader_pending = pending.clone();
+        thread::spawn(move || {
+            let error = loop {
+                let message = match reader.read_message() {
+                    Ok(Some(message)) => message,
+                    Ok(None) => break "connection closed by oracle resolver".to_owned(),
+                    Err(err) => break err.to_string(),
+                };
+                let response: Response = match serde_json::from_str(&message) {
+                    Ok(response) => response,
+                    Err(err) => break format!("invalid response {message:?}: {err}"),
+                };
+
+                let mut pending = reader_pending.lock().unwrap();
+                let sender = response.id.as_u64().and_then(|id| pending.as_mut()?.remove(&id));
+                if let Some(sender) = sender {
+                    // The request may have stopped waiting, in which case the response is dropped.
+                    let _ = sender.send(Ok(response));
+                }
+            };
+
+            // Let every request which is still waiting know that it won't receive a response.
+            let pending = reader_pending.lock().unwrap().take().unwrap_or_default();
+            for sender in pending.into_values() {
+                let _ = sender.send(Err(error.clone()));
+            }
+        });
+
+        MultiplexedTransport { writer: Mutex::new(writer), next_id: AtomicU64::new(0), pending }
+    }
+
+    fn spawn(command: &str) -> Result<Self, BoxedError> {
+        let words = shell_words::split(command)?;
+        let (program, args) = words.split_first().ok_or("missing command")?;
+
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let stdin = child.stdin.take().expect("stdin should be piped");
+        let stdout = child.stdout.take().expect("stdout should be piped");
+
+        // The child exits once its stdin is closed, which happens when nargo exits.
+        Ok(Self::new(Box::new(stdin), Box::new(BufReader::new(stdout))))
+    }
+
+    fn connect_websocket(url: &str) -> Result<Self, BoxedError> {
+        let address = url.trim_start_matches("ws://");
+        let address = address.split('/').next().unwrap_or(address);
+        let stream = TcpStream::connect(address)?;
+        stream.set_nodelay(true)?;
+
+        let (socket, _) = tungstenite::client(url, stream).map_err(|err| err.to_string())?;
+        socket.get_ref().set_read_timeout(Some(WEBSOCKET_READ_TIMEOUT))?;
+        let socket = SharedWebSocket(Arc::new(Mutex::new(socket)));
+
+        Ok(Self::new(Box::new(socket.clone()), Box::new(socket)))
+    }
+
+    fn send(&self, request: &Request) -> Result<Response, Error> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+
+        // Callers number their requests independently, so each one is given an id which is
+        // unique to this connection before being sent.
+        let mut message = serde_json::to_value(request)?;
+        message["id"] = id.into();
+
+        let (sender, receiver) = mpsc::channel();
+        self.pending
+            .lock()
+            .unwrap()
+            .as_mut()
+            .ok_or_else(|| transport_error("connection to oracle resolver is closed".into()))?
+            .insert(id, sender);
+
+        if let Err(err) = self.writer.lock().unwrap().write_message(message.to_string()) {
+            if let Some(pending) = self.pending.lock().unwrap().as_mut() {
+                pending.remove(&id);
+            }
+            return Err(transport_error(err));
+        }
+
+        let mut response = receiver
+            .recv()
+            .map_err(|err| transport_error(err.into()))?
+            .map_err(|err| transport_error(err.into()))?;
+        response.id = request.id.clone();
+
+        Ok(response)
+    }
+}
+
+fn transport_error(err: BoxedError) -> Error {
+    Error::Transport(err)
+}
+
+/// A handle onto the [`MultiplexedTransport`] of a resolver, which is shared between clients.
+///
+/// The connection is made when the first request is sent. If it can't be made then that request
+/// fails with the error, and the next request tries to connect again.
+pub(super) struct SharedTransport {
+    resolver: String,
+}
+
+impl SharedTransport {
+    fn transport(&self) -> Result<Arc<MultiplexedTransport>, Error> {
+        static CONNECTIONS: OnceLock<Mutex<HashMap<String, Arc<MultiplexedTransport>>>> =
+            OnceLock::new();
+        let mut connections = CONNECTIONS.get_or_init(Default::default).lock().unwrap();
+
+        if let Some(transport) = connections.get(&self.resolver) {
+            return Ok(transport.clone());
+        }
+
+        let transport = match self.resolver.strip_prefix(STDIO_PREFIX) {
+            Some(command) => MultiplexedTransport::spawn(command),
+            None => MultiplexedTransport::connect_websocket(&self.resolver),
+        };
+        let transport = Arc::new(transport.map_err(|err| {
+            let message = format!("could not connect to oracle resolver {}: {err}", self.resolver);
+            transport_error(message.into())
+        })?);
+        connections.insert(self.resolver.clone(), transport.clone());
+
+        Ok(transport)
+    }
+}
+
+impl Transport for SharedTransport {
+    fn send_request(&self, request: Request) -> Result<Response, Error> {
+        self.transport()?.send(&request)
+    }
+
+    fn send_batch(&self, requests: &[Request]) -> Result<Vec<Response>, Error> {
+        let transport = self.transport()?;
+        requests.iter().map(|request| transport.send(request)).collect()
+    }
+
+    fn fmt_target(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.resolver)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use jsonrpc::{arg, Client};
+
+    use super::connect;
+
+    #[test]
+    fn stdio_transport_matches_responses_to_requests() {
+        // `cat` echoes each request back, which reads as a response carrying the request's id.
+        // The quoted script checks that the command is split into arguments as a shell would.
+        let transport = connect(r#"stdio:sh -c "exec cat""#).expect("should be a stdio resolver");
+        let client = Client::with_transport(transport);
+
+        for value in 0..3 {
+            let params = [arg(value)];
+            let request = client.build_request("echo", &params);
+            assert!(client.send_request(request).is_ok());
+        }
+    }
+
+    #[test]
+    fn stdio_transport_reports_commands_which_fail_to_spawn() {
+        let transport =
+            connect("stdio:/nonexistent/oracle-resolver").expect("should be a stdio resolver");
+        let client = Client::with_transport(transport);
+
+        let request = client.build_request("echo", &[]);
+        let error = client.send_request(request).unwrap_err();
+        assert!(error.to_string().contains("could not connect to oracle resolver"));
+    }
+}
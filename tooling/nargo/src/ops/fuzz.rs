@@ -0,0 +1,260 @@
+//! Property testing of compiled programs by executing them on random inputs.
+//!
+//! Inputs are generated from the program's ABI, favouring values at the edges of each type's
+//! range (zero, one and the maximum value) as these are the most likely to expose bugs. Once a
+//! failing input is found it is shrunk towards smaller values which still cause the program to
+//! fail so that the reported input is easier to reason about.
+use acvm::{BlackBoxFunctionSolver, FieldElement};
+use noirc_abi::{input_parser::InputValue, AbiType, InputMap};
+use noirc_driver::CompiledProgram;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::NargoError;
+
+use super::{execute_circuit, DefaultForeignCallExecutor};
+
+/// The maximum number of executions spent on shrinking a failing input.
+const MAX_SHRINK_RUNS: usize = 1000;
+
+pub enum FuzzResult {
+    /// The program executed successfully on all generated inputs.
+    Pass { runs: usize },
+    /// The program failed on `inputs`, which were shrunk from the input found on run `run`.
+    Fail { run: usize, inputs: InputMap, error: NargoError },
+}
+
+/// Executes `program` on `runs` randomly generated inputs, stopping at the first input which
+/// causes it to fail.
+///
+/// The inputs are generated deterministically from `seed` so that failures can be reproduced.
+pub fn fuzz_program<B: BlackBoxFunctionSolver>(
+    blackbox_solver: &B,
+    program: &CompiledProgram,
+    runs: usize,
+    seed: u64,
+    foreign_call_resolver_url: Option<&str>,
+) -> FuzzResult {
+    let execute = |inputs: &InputMap| -> Result<(), NargoError> {
+        let initial_witness =
+            program.abi.encode(inputs, None).expect("Generated inputs should match the ABI");
+        execute_circuit(
+            &program.circuit,
+            &program.functions,
+            initial_witness,
+            blackbox_solver,
+            &mut DefaultForeignCallExecutor::new(false, foreign_call_resolver_url),
+        )?;
+        Ok(())
+    };
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    for run in 0..runs {
+        let inputs: InputMap = program
+            .abi
+            .parameters
+            .iter()
+            .map(|param| (param.name.clone(), random_input(&mut rng, &param.typ)))
+            .collect();
+
+        if execute(&inputs).is_err() {
+            let inputs = shrink_inputs(inputs, |inputs| execute(inputs).is_err());
+            let error = execute(&inputs).expect_err("Shrunk inputs should still fail");
+            return FuzzResult::Fail { run, inputs, error };
+        }
+    }
+
+    FuzzResult::Pass { runs }
+}
+
+fn random_input(rng: &mut StdRng, typ: &AbiType) -> InputValue {
+    match typ {
+        AbiType::Field => InputValue::Field(random_field(rng)),
+        AbiType::Integer { width, .. } => InputValue::Field(random_integer(rng, *width)),
+        AbiType::Boolean => InputValue::Field(rng.gen_bool(0.5).into()),
+        AbiType::String { length } => {
+            InputValue::String((0..*length).map(|_| rng.gen_range(' '..='~')).collect())
+        }
+        AbiType::Array { length, typ } => {
+            InputValue::Vec((0..*length).map(|_| random_input(rng, typ)).collect())
+        }
+        AbiType::Tuple { fields } => {
+            InputValue::Vec(fields.iter().map(|typ| random_input(rng, typ)).collect())
+        }
+        AbiType::Vec { max_length, typ } => {
+            let length = rng.gen_range(0..=*max_length);
+            InputValue::Vec((0..length).map(|_| random_input(rng, typ)).collect())
+        }
+        AbiType::Struct { fields, .. } => InputValue::Struct(
+            fields.iter().map(|(name, typ)| (name.clone(), random_input(rng, typ))).collect(),
+        ),
+    }
+}
+
+fn random_field(rng: &mut StdRng) -> FieldElement {
+    match rng.gen_range(0..8) {
+        0 => FieldElement::zero(),
+        1 => FieldElement::one(),
+        2 => -FieldElement::one(),
+        _ => FieldElement::from_be_bytes_reduce(&rng.gen::<[u8; 32]>()),
+    }
+}
+
+/// Returns a random value which fits in `width` bits.
+///
+/// Signed integers are encoded in two's complement so the same values cover their range too.
+fn random_integer(rng: &mut StdRng, width: u32) -> FieldElement {
+    match rng.gen_range(0..8) {
+        0 => FieldElement::zero(),
+        1 => FieldElement::one(),
+        2 => max_integer(width),
+        3 if width > 1 => max_integer(width - 1),
+        _ => {
+            let mut bytes = vec![0u8; (width as usize + 7) / 8];
+            rng.fill(&mut bytes[..]);
+            if width % 8 != 0 {
+                bytes[0] &= (1u8 << (width % 8)) - 1;
+            }
+            FieldElement::from_be_bytes_reduce(&bytes)
+        }
+    }
+}
+
+/// Returns `2^width - 1`
+fn max_integer(width: u32) -> FieldElement {
+    FieldElement::from(2u128).pow(&FieldElement::from(width as u128)) - FieldElement::one()
+}
+
+/// Repeatedly replaces values in `inputs` with smaller ones for as long as `still_fails` holds.
+fn shrink_inputs(mut inputs: InputMap, still_fails: impl Fn(&InputMap) -> bool) -> InputMap {
+    let mut shrink_runs = 0;
+    let mut shrunk = true;
+    while shrunk {
+        shrunk = false;
+        let names: Vec<String> = inputs.keys().cloned().collect();
+        for name in names {
+            for candidate in shrink_candidates(&inputs[&name]) {
+                if shrink_runs == MAX_SHRINK_RUNS {
+                    return inputs;
+                }
+                shrink_runs += 1;
+
+                let mut candidate_inputs = inputs.clone();
+                candidate_inputs.insert(name.clone(), candidate);
+                if still_fails(&candidate_inputs) {
+                    inputs = candidate_inputs;
+                    shrunk = true;
+                    break;
+                }
+            }
+        }
+    }
+    inputs
+}
+
+/// Returns the values which are smaller than `value` in exactly one position, simplest first.
+fn shrink_candidates(value: &InputValue) -> Vec<InputValue> {
+    match value {
+        InputValue::Field(field) => {
+            let mut candidates = Vec::new();
+            if !field.is_zero() {
+                candidates.push(FieldElement::zero());
+            }
+            if field.num_bits() <= 128 {
+                let value = field.to_u128();
+                if value > 2 {
+                    candidates.push(FieldElement::from(value / 2));
+                }
+                if value > 1 {
+                    candidates.push(FieldElement::from(value - 1));
+                }
+            } else {
+                candidates.push(FieldElement::one());
+            }
+            candidates.into_iter().map(InputValue::Field).collect()
+        }
+        // Strings have a fixed length and there is no meaningful order on their contents.
+        InputValue::String(_) => Vec::new(),
+        InputValue::Vec(elements) => {
+            let mut candidates = Vec::new();
+            for (index, element) in elements.iter().enumerate() {
+                for candidate in shrink_candidates(element) {
+                    let mut elements = elements.clone();
+                    elements[index] = candidate;
+                    candidates.push(InputValue::Vec(elements));
+                }
+            }
+            candidates
+        }
+        InputValue::Struct(fields) => {
+            let mut candidates = Vec::new();
+            for (name, field) in fields {
+                for candidate in shrink_candidates(field) {
+                    let mut fields = fields.clone();
+                    fields.insert(name.clone(), candidate);
+                    candidates.push(InputValue::Struct(fields));
+                }
+            }
+            candidates
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::FieldElement;
+    use noirc_abi::{input_parser::InputValue, AbiType, InputMap, Sign};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::{random_input, shrink_inputs};
+
+    #[test]
+    fn random_inputs_match_abi() {
+        let types = [
+            AbiType::Field,
+            AbiType::Boolean,
+            AbiType::Integer { sign: Sign::Unsigned, width: 1 },
+            AbiType::Integer { sign: Sign::Unsigned, width: 13 },
+            AbiType::Integer { sign: Sign::Signed, width: 64 },
+            AbiType::String { length: 5 },
+            AbiType::Array { length: 3, typ: Box::new(AbiType::Boolean) },
+            AbiType::Tuple { fields: vec![AbiType::Field, AbiType::String { length: 2 }] },
+            AbiType::Struct {
+                path: "Foo".to_owned(),
+                fields: vec![
+                    ("a".to_owned(), AbiType::Integer { sign: Sign::Unsigned, width: 8 }),
+                    ("b".to_owned(), AbiType::Field),
+                ],
+            },
+        ];
+
+        let mut rng = StdRng::seed_from_u64(0);
+        for typ in &types {
+            for _ in 0..100 {
+                assert!(random_input(&mut rng, typ).matches_abi(typ));
+            }
+        }
+    }
+
+    #[test]
+    fn shrinks_to_smallest_failing_input() {
+        let inputs = InputMap::from([
+            ("x".to_owned(), InputValue::Field(FieldElement::from(1000u128))),
+            (
+                "y".to_owned(),
+                InputValue::Vec(vec![
+                    InputValue::Field(FieldElement::from(77u128)),
+                    InputValue::Field(FieldElement::from(5u128)),
+                ]),
+            ),
+        ]);
+
+        // Fails whenever `x` is at least 10, regardless of `y`.
+        let fails =
+            |inputs: &InputMap| matches!(&inputs["x"], InputValue::Field(x) if x.to_u128() >= 10);
+        let shrunk = shrink_inputs(inputs, fails);
+
+        let zero = InputValue::Field(FieldElement::zero());
+        assert_eq!(shrunk["x"], InputValue::Field(FieldElement::from(10u128)));
+        assert_eq!(shrunk["y"], InputValue::Vec(vec![zero.clone(), zero]));
+    }
+}
@@ -1,7 +1,22 @@
-pub use self::compile::{compile_contract, compile_program, compile_workspace};
-pub use self::execute::execute_circuit;
-pub use self::foreign_calls::{DefaultForeignCallExecutor, ForeignCallExecutor};
+pub use self::compile::{
+    compile_contract, compile_contracts, compile_program, compile_programs, compile_workspace,
+};
+pub use self::execute::{execute_circuit, execute_circuit_with_brillig_profiling};
+pub use self::foreign_calls::{DefaultForeignCallExecutor, ForeignCallExecutor, LogLevel};
+pub use self::fuzz::{fuzz_program, FuzzResult};
+pub use self::mutate::{
+    apply_mutation, circuit_mutations, run_mutation_test, MutantOutcome, MutantResult, Mutation,
+    MutationKind, MutationTestStatus,
+};
 pub use self::optimize::{optimize_contract, optimize_program};
+pub use self::profile::{
+    profile_brillig_execution, profile_circuit, BrilligExecutionProfile, CallSiteProfile,
+    CircuitProfile, LineProfile, SourceLine, StackProfile, UNKNOWN_FRAME,
+};
+pub use self::redundancy::{find_redundant_constraints, RedundancyReason, RedundantConstraint};
+pub use self::smt::{
+    check_output_determination, circuit_to_smtlib, Determination, OutputDetermination,
+};
 pub use self::transform::{transform_contract, transform_program};
 
 pub use self::test::{run_test, TestStatus};
@@ -9,6 +24,11 @@ pub use self::test::{run_test, TestStatus};
 mod compile;
 mod execute;
 mod foreign_calls;
+mod fuzz;
+mod mutate;
 mod optimize;
+mod profile;
+mod redundancy;
+mod smt;
 mod test;
 mod transform;
@@ -0,0 +1,341 @@
+//! Mutation testing of compiled test functions.
+//!
+//! Each constraint in the circuit of a test is relaxed in turn and the test is executed again.
+//! If the test still passes then the test suite is unable to tell the relaxed circuit apart from
+//! the original one, which often means that the constraint is not covered by any test. As tests
+//! call into the functions making up a program, a surviving mutant is a hint that the program may
+//! be under-constrained.
+//!
+//! Some mutants cannot be executed at all, e.g. removing the only constraint from which the
+//! value of a witness is solved. These mutants are invalid: they are neither killed nor survive.
+use acvm::{
+    acir::{
+        circuit::{opcodes::BlackBoxFuncCall, Circuit, Opcode, OpcodeLocation},
+        native_types::{Expression, WitnessMap},
+    },
+    pwg::OpcodeResolutionError,
+    BlackBoxFunctionSolver, FieldElement,
+};
+use noirc_driver::{compile_no_check, CompileOptions};
+use noirc_errors::{FileDiagnostic, Location};
+use noirc_frontend::hir::{def_map::TestFunction, Context};
+
+use crate::errors::{ExecutionError, NargoError};
+
+use super::{
+    execute_circuit,
+    test::{test_status_program_compile_pass, TestStatus},
+    DefaultForeignCallExecutor,
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum MutationKind {
+    /// Removes an arithmetic assertion from the circuit.
+    DropAssertion,
+    /// Negates the coefficient of the first linear term of an arithmetic assertion,
+    /// e.g. turning `a + b = c` into `a - b = c`.
+    NegateTerm,
+    /// Replaces the first product of an arithmetic assertion with a sum,
+    /// e.g. turning `a * b = c` into `a + b = c`.
+    ProductToSum,
+    /// Adds one to the constant term of an arithmetic assertion,
+    /// e.g. turning `a = b` into `a + 1 = b`.
+    ShiftConstant,
+    /// Removes a range constraint from the circuit.
+    DropRange,
+    /// Allows a range constraint to accept values one bit wider.
+    WidenRange,
+}
+
+impl std::fmt::Display for MutationKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MutationKind::DropAssertion => write!(f, "removing this constraint"),
+            MutationKind::NegateTerm => write!(f, "negating a term of this constraint"),
+            MutationKind::ProductToSum => {
+                write!(f, "replacing a product with a sum in this constraint")
+            }
+            MutationKind::ShiftConstant => write!(f, "adding one to this constraint"),
+            MutationKind::DropRange => write!(f, "removing this range constraint"),
+            MutationKind::WidenRange => write!(f, "widening this range constraint by one bit"),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Mutation {
+    pub opcode_index: usize,
+    pub kind: MutationKind,
+}
+
+/// The outcome of executing a test against a mutant of its circuit.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MutantOutcome {
+    /// The test failed, so it detects the mutation.
+    Killed,
+    /// The test still passed, so it does not detect the mutation.
+    Survived,
+    /// The mutant could not be executed, so the test tells nothing about the mutation.
+    Invalid,
+}
+
+impl MutantOutcome {
+    /// Combines the outcomes of the same mutation in the circuits of two tests: a mutant is killed
+    /// if any test kills it, and only invalid if it is invalid in every test.
+    pub fn combine(self, other: MutantOutcome) -> MutantOutcome {
+        match (self, other) {
+            (MutantOutcome::Killed, _) | (_, MutantOutcome::Killed) => MutantOutcome::Killed,
+            (MutantOutcome::Survived, _) | (_, MutantOutcome::Survived) => MutantOutcome::Survived,
+            (MutantOutcome::Invalid, MutantOutcome::Invalid) => MutantOutcome::Invalid,
+        }
+    }
+}
+
+/// The result of executing a test against one of the mutants of its circuit.
+pub struct MutantResult {
+    pub mutation: Mutation,
+    /// The source location of the constraint which was mutated, if known.
+    pub location: Option<Location>,
+    pub outcome: MutantOutcome,
+}
+
+pub enum MutationTestStatus {
+    /// The test passed on its original circuit and was executed against each of its mutants.
+    Ran(Vec<MutantResult>),
+    /// The test doesn't pass on its original circuit, so it cannot be used to detect mutants.
+    Skipped(TestStatus),
+    CompileError(FileDiagnostic),
+}
+
+/// Returns the mutations which can be applied to `circuit`.
+pub fn circuit_mutations(circuit: &Circuit) -> Vec<Mutation> {
+    let mut mutations = Vec::new();
+    for (opcode_index, opcode) in circuit.opcodes.iter().enumerate() {
+        let mut push = |kind| mutations.push(Mutation { opcode_index, kind });
+        match opcode {
+            Opcode::AssertZero(expr) if !expr.is_const() => {
+                push(MutationKind::DropAssertion);
+                if expr.linear_combinations.len() > 1 {
+                    push(MutationKind::NegateTerm);
+                }
+                if !expr.mul_terms.is_empty() {
+                    push(MutationKind::ProductToSum);
+                }
+                push(MutationKind::ShiftConstant);
+            }
+            Opcode::BlackBoxFuncCall(BlackBoxFuncCall::RANGE { input }) => {
+                push(MutationKind::DropRange);
+                if input.num_bits < FieldElement::max_num_bits() {
+                    push(MutationKind::WidenRange);
+                }
+            }
+            _ => (),
+        }
+    }
+    mutations
+}
+
+/// Returns a copy of `circuit` with `mutation` applied to it.
+///
+/// Opcodes are replaced rather than removed so that the opcode locations in the debug
+/// information and assertion messages of the circuit remain valid.
+pub fn apply_mutation(circuit: &Circuit, mutation: Mutation) -> Circuit {
+    let mut circuit = circuit.clone();
+    let opcode = &mut circuit.opcodes[mutation.opcode_index];
+    match (mutation.kind, opcode) {
+        (MutationKind::DropAssertion, opcode @ Opcode::AssertZero(_)) => {
+            *opcode = Opcode::AssertZero(Expression::zero());
+        }
+        (MutationKind::NegateTerm, Opcode::AssertZero(expr)) => {
+            let (coefficient, _) = &mut expr.linear_combinations[0];
+            *coefficient = -*coefficient;
+        }
+        (MutationKind::ProductToSum, Opcode::AssertZero(expr)) => {
+            let (coefficient, lhs, rhs) = expr.mul_terms.remove(0);
+            expr.linear_combinations.push((coefficient, lhs));
+            expr.linear_combinations.push((coefficient, rhs));
+        }
+        (MutationKind::ShiftConstant, Opcode::AssertZero(expr)) => {
+            expr.q_c += FieldElement::one();
+        }
+        (
+            MutationKind::DropRange,
+            opcode @ Opcode::BlackBoxFuncCall(BlackBoxFuncCall::RANGE { .. }),
+        ) => {
+            *opcode = Opcode::AssertZero(Expression::zero());
+        }
+        (MutationKind::WidenRange, Opcode::BlackBoxFuncCall(BlackBoxFuncCall::RANGE { input })) => {
+            input.num_bits += 1;
+        }
+        (kind, opcode) => unreachable!("Cannot apply mutation {kind:?} to opcode {opcode}"),
+    }
+    circuit
+}
+
+/// Compiles `test_function` and executes it against each of the mutants of its circuit.
+pub fn run_mutation_test<B: BlackBoxFunctionSolver>(
+    blackbox_solver: &B,
+    context: &Context,
+    test_function: TestFunction,
+    foreign_call_resolver_url: Option<&str>,
+    config: &CompileOptions,
+) -> MutationTestStatus {
+    let program = match compile_no_check(context, config, test_function.get_id(), None, false) {
+        Ok(program) => program,
+        Err(err) => return MutationTestStatus::CompileError(err.into()),
+    };
+
+    let execute = |circuit: &Circuit| {
+        execute_circuit(
+            circuit,
+            &program.functions,
+            WitnessMap::new(),
+            blackbox_solver,
+            &mut DefaultForeignCallExecutor::new(false, foreign_call_resolver_url),
+        )
+    };
+    let test_status = |circuit_execution: Result<WitnessMap, NargoError>| {
+        test_status_program_compile_pass(
+            test_function.clone(),
+            program.debug.clone(),
+            circuit_execution,
+        )
+    };
+
+    let original_status = test_status(execute(&program.circuit));
+    if !matches!(original_status, TestStatus::Pass) {
+        return MutationTestStatus::Skipped(original_status);
+    }
+
+    let results = circuit_mutations(&program.circuit)
+        .into_iter()
+        .map(|mutation| {
+            let mutant = apply_mutation(&program.circuit, mutation);
+            let circuit_execution = execute(&mutant);
+            let outcome = if is_unsolvable(&circuit_execution) {
+                MutantOutcome::Invalid
+            } else if matches!(test_status(circuit_execution), TestStatus::Pass) {
+                MutantOutcome::Survived
+            } else {
+                MutantOutcome::Killed
+            };
+            let location = program
+                .debug
+                .locations
+                .get(&OpcodeLocation::Acir(mutation.opcode_index))
+                .and_then(|call_stack| call_stack.last())
+                .copied();
+            MutantResult { mutation, location, outcome }
+        })
+        .collect();
+    MutationTestStatus::Ran(results)
+}
+
+/// Returns true if the execution failed because the ACVM could not solve the circuit, rather than
+/// because a constraint was not satisfied.
+fn is_unsolvable(circuit_execution: &Result<WitnessMap, NargoError>) -> bool {
+    matches!(
+        circuit_execution,
+        Err(NargoError::ExecutionError(ExecutionError::SolvingError(
+            OpcodeResolutionError::OpcodeNotSolvable(_)
+        )))
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::{
+        acir::{
+            circuit::{
+                opcodes::{BlackBoxFuncCall, FunctionInput},
+                Circuit, Opcode,
+            },
+            native_types::{Expression, Witness},
+        },
+        FieldElement,
+    };
+
+    use super::{apply_mutation, circuit_mutations, MutantOutcome, Mutation, MutationKind};
+
+    #[test]
+    fn mutates_assertions_and_range_checks() {
+        // _0 * _1 + _0 + _1 - _2 = 0
+        let sum = Expression {
+            mul_terms: vec![(FieldElement::one(), Witness(0), Witness(1))],
+            linear_combinations: vec![
+                (FieldElement::one(), Witness(0)),
+                (FieldElement::one(), Witness(1)),
+                (-FieldElement::one(), Witness(2)),
+            ],
+            q_c: FieldElement::zero(),
+        };
+        let range = FunctionInput { witness: Witness(2), num_bits: 8 };
+        let circuit = Circuit {
+            current_witness_index: 2,
+            opcodes: vec![
+                Opcode::AssertZero(sum),
+                Opcode::AssertZero(Expression::from_field(FieldElement::zero())),
+                Opcode::BlackBoxFuncCall(BlackBoxFuncCall::RANGE { input: range }),
+            ],
+            ..Circuit::default()
+        };
+
+        let mutations = circuit_mutations(&circuit);
+        assert_eq!(
+            mutations,
+            vec![
+                Mutation { opcode_index: 0, kind: MutationKind::DropAssertion },
+                Mutation { opcode_index: 0, kind: MutationKind::NegateTerm },
+                Mutation { opcode_index: 0, kind: MutationKind::ProductToSum },
+                Mutation { opcode_index: 0, kind: MutationKind::ShiftConstant },
+                Mutation { opcode_index: 2, kind: MutationKind::DropRange },
+                Mutation { opcode_index: 2, kind: MutationKind::WidenRange },
+            ]
+        );
+
+        let dropped = apply_mutation(&circuit, mutations[0]);
+        assert_eq!(dropped.opcodes.len(), circuit.opcodes.len());
+        assert_eq!(dropped.opcodes[0], Opcode::AssertZero(Expression::zero()));
+
+        let negated = apply_mutation(&circuit, mutations[1]);
+        let Opcode::AssertZero(expr) = &negated.opcodes[0] else {
+            panic!("Expected an assertion");
+        };
+        assert_eq!(expr.linear_combinations[0], (-FieldElement::one(), Witness(0)));
+
+        let summed = apply_mutation(&circuit, mutations[2]);
+        let Opcode::AssertZero(expr) = &summed.opcodes[0] else {
+            panic!("Expected an assertion");
+        };
+        assert!(expr.mul_terms.is_empty());
+        assert_eq!(
+            &expr.linear_combinations[3..],
+            &[(FieldElement::one(), Witness(0)), (FieldElement::one(), Witness(1))]
+        );
+
+        let shifted = apply_mutation(&circuit, mutations[3]);
+        let Opcode::AssertZero(expr) = &shifted.opcodes[0] else {
+            panic!("Expected an assertion");
+        };
+        assert_eq!(expr.q_c, FieldElement::one());
+
+        let dropped_range = apply_mutation(&circuit, mutations[4]);
+        assert_eq!(dropped_range.opcodes[2], Opcode::AssertZero(Expression::zero()));
+
+        let widened = apply_mutation(&circuit, mutations[5]);
+        let widened_range = FunctionInput { witness: Witness(2), num_bits: 9 };
+        assert_eq!(
+            widened.opcodes[2],
+            Opcode::BlackBoxFuncCall(BlackBoxFuncCall::RANGE { input: widened_range })
+        );
+    }
+
+    #[test]
+    fn combines_outcomes_across_tests() {
+        use MutantOutcome::*;
+        assert_eq!(Survived.combine(Killed), Killed);
+        assert_eq!(Invalid.combine(Killed), Killed);
+        assert_eq!(Invalid.combine(Survived), Survived);
+        assert_eq!(Invalid.combine(Invalid), Invalid);
+    }
+}
@@ -5,6 +5,7 @@ pub fn optimize_program(mut program: CompiledProgram) -> CompiledProgram {
     let (optimized_circuit, location_map) = acvm::compiler::optimize(program.circuit);
     program.circuit = optimized_circuit;
     program.debug.update_acir(location_map);
+    program.functions = vecmap(program.functions, |function| acvm::compiler::optimize(function).0);
     program
 }
 
@@ -13,6 +14,7 @@ pub fn optimize_contract(contract: CompiledContract) -> CompiledContract {
         let (optimized_bytecode, location_map) = acvm::compiler::optimize(func.bytecode);
         func.bytecode = optimized_bytecode;
         func.debug.update_acir(location_map);
+        func.functions = vecmap(func.functions, |function| acvm::compiler::optimize(function).0);
         func
     });
 
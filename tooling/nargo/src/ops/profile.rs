@@ -0,0 +1,339 @@
+//! Attribution of the opcodes of compiled circuits to the source code which generated them.
+//!
+//! The debug information of a circuit records the call stack of source locations which generated
+//! each of its ACIR opcodes. Counting opcodes per call stack gives the data for a flamegraph of the
+//! circuit, while counting them per source line shows which lines contribute the most constraints.
+//!
+//! Brillig opcodes have call stacks too, so the opcodes executed by unconstrained calls while
+//! solving a circuit can be attributed in the same way to find the most expensive hints.
+use std::collections::{BTreeMap, BTreeSet};
+
+use acvm::acir::circuit::{Circuit, OpcodeLocation};
+use codespan_reporting::files::Files;
+use noirc_errors::{debug_info::DebugInfo, Location};
+use serde::Serialize;
+
+use crate::artifacts::debug::DebugArtifact;
+
+/// The frame which opcodes without a source location are attributed to.
+pub const UNKNOWN_FRAME: &str = "<unknown>";
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct SourceLine {
+    pub file: String,
+    /// The line number, starting at 1.
+    pub line: usize,
+}
+
+impl std::fmt::Display for SourceLine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.file, self.line)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct LineProfile {
+    #[serde(flatten)]
+    pub source_line: SourceLine,
+    /// The source code of the line, without surrounding whitespace.
+    pub source: String,
+    /// The number of opcodes generated by the line itself.
+    pub self_opcodes: usize,
+    /// The number of opcodes generated by the line and the functions it calls.
+    pub total_opcodes: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StackProfile {
+    /// The source lines of the call stack, outermost first.
+    pub frames: Vec<String>,
+    pub opcodes: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CircuitProfile {
+    pub total_opcodes: usize,
+    /// The source lines which generated opcodes, those generating the most opcodes first.
+    pub lines: Vec<LineProfile>,
+    /// The distinct call stacks which generated opcodes.
+    pub stacks: Vec<StackProfile>,
+}
+
+/// Attributes each opcode of `circuit` to the call stack of source lines which generated it.
+pub fn profile_circuit(
+    circuit: &Circuit,
+    debug: &DebugInfo,
+    debug_artifact: &DebugArtifact,
+) -> CircuitProfile {
+    let mut stacks: BTreeMap<Vec<String>, usize> = BTreeMap::new();
+    let mut lines = LineCounter::default();
+
+    for opcode_index in 0..circuit.opcodes.len() {
+        let call_stack = call_stack(debug, OpcodeLocation::Acir(opcode_index));
+        let source_lines = lines.count(debug_artifact, call_stack, 1);
+
+        let frames = if source_lines.is_empty() {
+            vec![UNKNOWN_FRAME.to_owned()]
+        } else {
+            source_lines.iter().map(ToString::to_string).collect()
+        };
+        *stacks.entry(frames).or_default() += 1;
+    }
+
+    let stacks =
+        stacks.into_iter().map(|(frames, opcodes)| StackProfile { frames, opcodes }).collect();
+
+    CircuitProfile { total_opcodes: circuit.opcodes.len(), lines: lines.into_profiles(), stacks }
+}
+
+#[derive(Debug, Serialize)]
+pub struct CallSiteProfile {
+    /// The source line of the unconstrained call, or [`UNKNOWN_FRAME`] if it has none.
+    pub call_site: String,
+    /// The source code of the line, without surrounding whitespace.
+    pub source: String,
+    /// The number of times the unconstrained function was called from this line.
+    pub calls: usize,
+    /// The number of Brillig opcodes executed by these calls.
+    pub executed_opcodes: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BrilligExecutionProfile {
+    pub executed_opcodes: usize,
+    /// The call sites of unconstrained functions, those executing the most opcodes first.
+    pub call_sites: Vec<CallSiteProfile>,
+    /// The source lines of unconstrained functions, those executing the most opcodes first.
+    pub lines: Vec<LineProfile>,
+}
+
+/// Attributes the Brillig opcodes executed by `circuit` to the unconstrained calls which executed
+/// them and to the source lines which generated them.
+///
+/// `opcode_counts` holds the number of times each Brillig opcode was executed, keyed by the index
+/// of the ACIR opcode of its call, as returned by [`execute_circuit_with_brillig_profiling`].
+///
+/// [`execute_circuit_with_brillig_profiling`]: super::execute_circuit_with_brillig_profiling
+pub fn profile_brillig_execution(
+    debug: &DebugInfo,
+    debug_artifact: &DebugArtifact,
+    opcode_counts: &BTreeMap<usize, Vec<usize>>,
+) -> BrilligExecutionProfile {
+    let mut executed_opcodes = 0;
+    let mut call_sites: BTreeMap<String, CallSiteProfile> = BTreeMap::new();
+    let mut lines = LineCounter::default();
+
+    for (acir_index, counts) in opcode_counts {
+        let call_executed_opcodes: usize = counts.iter().sum();
+        executed_opcodes += call_executed_opcodes;
+
+        let call_site_location =
+            call_stack(debug, OpcodeLocation::Acir(*acir_index)).last().copied();
+        let call_site = call_site_location
+            .and_then(|location| source_line(debug_artifact, location))
+            .map_or_else(|| UNKNOWN_FRAME.to_owned(), |source_line| source_line.to_string());
+        let call_site_profile =
+            call_sites.entry(call_site.clone()).or_insert_with(|| CallSiteProfile {
+                call_site,
+                source: call_site_location
+                    .and_then(|location| line_source(debug_artifact, location))
+                    .unwrap_or_default(),
+                calls: 0,
+                executed_opcodes: 0,
+            });
+        call_site_profile.calls += 1;
+        call_site_profile.executed_opcodes += call_executed_opcodes;
+
+        for (brillig_index, count) in counts.iter().enumerate() {
+            if *count > 0 {
+                let location = OpcodeLocation::Brillig { acir_index: *acir_index, brillig_index };
+                lines.count(debug_artifact, call_stack(debug, location), *count);
+            }
+        }
+    }
+
+    let mut call_sites: Vec<CallSiteProfile> = call_sites.into_values().collect();
+    call_sites.sort_by(|a, b| b.executed_opcodes.cmp(&a.executed_opcodes));
+
+    BrilligExecutionProfile { executed_opcodes, call_sites, lines: lines.into_profiles() }
+}
+
+/// Accumulates the number of opcodes attributed to each source line.
+#[derive(Default)]
+struct LineCounter {
+    lines: BTreeMap<SourceLine, LineProfile>,
+}
+
+impl LineCounter {
+    /// Attributes `opcodes` opcodes to the lines of `call_stack`, returning those lines.
+    fn count(
+        &mut self,
+        debug_artifact: &DebugArtifact,
+        call_stack: &[Location],
+        opcodes: usize,
+    ) -> Vec<SourceLine> {
+        let source_lines: Vec<SourceLine> = call_stack
+            .iter()
+            .filter_map(|location| {
+                let source_line = source_line(debug_artifact, *location)?;
+                self.lines.entry(source_line.clone()).or_insert_with(|| LineProfile {
+                    source: line_source(debug_artifact, *location).unwrap_or_default(),
+                    source_line: source_line.clone(),
+                    self_opcodes: 0,
+                    total_opcodes: 0,
+                });
+                Some(source_line)
+            })
+            .collect();
+
+        // A line may appear several times in the call stack of a recursive function
+        // but each opcode is only counted once towards its total.
+        let distinct_lines: BTreeSet<&SourceLine> = source_lines.iter().collect();
+        for source_line in distinct_lines {
+            self.lines.get_mut(source_line).expect("line was inserted above").total_opcodes +=
+                opcodes;
+        }
+        if let Some(innermost) = source_lines.last() {
+            self.lines.get_mut(innermost).expect("line was inserted above").self_opcodes += opcodes;
+        }
+        source_lines
+    }
+
+    /// Returns the profiles of all lines, those with the most opcodes first.
+    fn into_profiles(self) -> Vec<LineProfile> {
+        let mut lines: Vec<LineProfile> = self.lines.into_values().collect();
+        lines.sort_by(|a, b| {
+            (b.total_opcodes, b.self_opcodes).cmp(&(a.total_opcodes, a.self_opcodes))
+        });
+        lines
+    }
+}
+
+fn call_stack(debug: &DebugInfo, opcode_location: OpcodeLocation) -> &[Location] {
+    debug.locations.get(&opcode_location).map(Vec::as_slice).unwrap_or_default()
+}
+
+fn source_line(debug_artifact: &DebugArtifact, location: Location) -> Option<SourceLine> {
+    let file = debug_artifact.name(location.file).ok()?.to_string();
+    let line = debug_artifact.location_line_number(location).ok()?;
+    Some(SourceLine { file, line })
+}
+
+/// Returns the source code of the line which `location` starts at.
+fn line_source(debug_artifact: &DebugArtifact, location: Location) -> Option<String> {
+    let line_index = debug_artifact.location_line_index(location).ok()?;
+    let range = debug_artifact.line_range(location.file, line_index).ok()?;
+    let source = debug_artifact.location_source_code(location).ok()?;
+    Some(source[range].trim().to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, path::Path};
+
+    use acvm::acir::{
+        circuit::{Circuit, Opcode, OpcodeLocation},
+        native_types::Expression,
+    };
+    use fm::FileManager;
+    use noirc_errors::{debug_info::DebugInfo, Location, Span};
+    use tempfile::TempDir;
+
+    use crate::artifacts::debug::DebugArtifact;
+
+    use super::{profile_brillig_execution, profile_circuit, UNKNOWN_FRAME};
+
+    const SOURCE_CODE: &str = r#"fn main(x: Field) {
+    foo(x);
+}
+fn foo(x: Field) {
+    assert(x == 1);
+}"#;
+
+    /// Returns a file manager holding `SOURCE_CODE` along with the locations of
+    /// `foo(x)` on line 2 and `x == 1` on line 5.
+    fn source_locations() -> (TempDir, FileManager, Location, Location) {
+        let dir = tempfile::tempdir().unwrap();
+        let mut fm = FileManager::new(dir.path());
+        let file_id =
+            fm.add_file_with_source(Path::new("main.nr"), SOURCE_CODE.to_string()).unwrap();
+
+        let call = Location::new(Span::inclusive(24, 29), file_id);
+        let assertion = Location::new(Span::inclusive(64, 69), file_id);
+        (dir, fm, call, assertion)
+    }
+
+    #[test]
+    fn attributes_opcodes_to_call_stacks() {
+        let (_dir, fm, call, assertion) = source_locations();
+
+        let mut opcode_locations = BTreeMap::<OpcodeLocation, Vec<Location>>::new();
+        opcode_locations.insert(OpcodeLocation::Acir(0), vec![call, assertion]);
+        opcode_locations.insert(OpcodeLocation::Acir(1), vec![call, assertion]);
+        opcode_locations.insert(OpcodeLocation::Acir(2), vec![call]);
+        let debug = DebugInfo::new(opcode_locations);
+        let debug_artifact = DebugArtifact::new(vec![debug.clone()], &fm);
+
+        let circuit = Circuit {
+            opcodes: vec![Opcode::AssertZero(Expression::default()); 4],
+            ..Circuit::default()
+        };
+        let profile = profile_circuit(&circuit, &debug, &debug_artifact);
+        assert_eq!(profile.total_opcodes, 4);
+
+        let lines: Vec<_> = profile
+            .lines
+            .iter()
+            .map(|line| (line.source_line.line, line.self_opcodes, line.total_opcodes))
+            .collect();
+        assert_eq!(lines, vec![(2, 1, 3), (5, 2, 2)]);
+        assert_eq!(profile.lines[1].source, "assert(x == 1);");
+
+        let mut stacks: Vec<_> =
+            profile.stacks.iter().map(|stack| (stack.frames.len(), stack.opcodes)).collect();
+        stacks.sort();
+        assert_eq!(stacks, vec![(1, 1), (1, 1), (2, 2)]);
+
+        // The last opcode has no source location.
+        let unknown_stack =
+            profile.stacks.iter().find(|stack| stack.frames == [UNKNOWN_FRAME]).unwrap();
+        assert_eq!(unknown_stack.opcodes, 1);
+    }
+
+    #[test]
+    fn attributes_executed_brillig_opcodes_to_call_sites() {
+        let (_dir, fm, call, assertion) = source_locations();
+
+        // The first call is made from `foo(x)` and executes its opcodes in `x == 1`
+        // while the second has no source location.
+        let mut opcode_locations = BTreeMap::<OpcodeLocation, Vec<Location>>::new();
+        opcode_locations.insert(OpcodeLocation::Acir(0), vec![call]);
+        for brillig_index in 0..2 {
+            let location = OpcodeLocation::Brillig { acir_index: 0, brillig_index };
+            opcode_locations.insert(location, vec![assertion]);
+        }
+        let debug = DebugInfo::new(opcode_locations);
+        let debug_artifact = DebugArtifact::new(vec![debug.clone()], &fm);
+
+        let opcode_counts = BTreeMap::from([(0, vec![3, 2]), (1, vec![4])]);
+        let profile = profile_brillig_execution(&debug, &debug_artifact, &opcode_counts);
+        assert_eq!(profile.executed_opcodes, 9);
+
+        let call_sites: Vec<_> = profile
+            .call_sites
+            .iter()
+            .map(|call_site| {
+                (call_site.source.as_str(), call_site.calls, call_site.executed_opcodes)
+            })
+            .collect();
+        assert_eq!(call_sites, vec![("foo(x);", 1, 5), ("", 1, 4)]);
+        assert_eq!(profile.call_sites[1].call_site, UNKNOWN_FRAME);
+
+        let lines: Vec<_> = profile
+            .lines
+            .iter()
+            .map(|line| (line.source_line.line, line.self_opcodes, line.total_opcodes))
+            .collect();
+        assert_eq!(lines, vec![(5, 5, 5)]);
+    }
+}
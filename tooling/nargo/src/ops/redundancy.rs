@@ -0,0 +1,209 @@
+//! Detection of constraints which are implied by other constraints of a circuit.
+//!
+//! A constraint which is implied by others can be removed without changing which witnesses
+//! satisfy the circuit. The analysis only looks for the cases which are cheap to prove:
+//! - arithmetic constraints which assert the same expression as another one, up to a constant
+//!   factor, such as repeating `assert(x == y)` or asserting `y == x` as well.
+//! - range checks on a witness which is already range checked to the same or fewer bits.
+use std::collections::{hash_map::Entry, HashMap};
+
+use acvm::acir::circuit::opcodes::BlackBoxFuncCall;
+use acvm::acir::circuit::{Circuit, Opcode};
+use acvm::acir::native_types::{Expression, Witness};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedundancyReason {
+    /// The opcode asserts the same expression as another one, up to a constant factor.
+    DuplicateConstraint,
+    /// The witness is already range checked to `implied_by_bits` bits, which is no more than the
+    /// `num_bits` bits checked by the opcode.
+    ImpliedRangeCheck { num_bits: u32, implied_by_bits: u32 },
+}
+
+impl std::fmt::Display for RedundancyReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RedundancyReason::DuplicateConstraint => write!(f, "constraint is duplicated"),
+            RedundancyReason::ImpliedRangeCheck { num_bits, implied_by_bits } => write!(
+                f,
+                "{num_bits}-bit range check is implied by a {implied_by_bits}-bit range check"
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RedundantConstraint {
+    /// The index of the redundant opcode in the circuit.
+    pub opcode_index: usize,
+    /// The index of the opcode which implies the redundant one.
+    pub implied_by: usize,
+    pub reason: RedundancyReason,
+}
+
+/// Returns the opcodes of `circuit` which are implied by another of its opcodes, in the order
+/// they appear in the circuit.
+///
+/// When several opcodes imply each other only the first of them is kept.
+pub fn find_redundant_constraints(circuit: &Circuit) -> Vec<RedundantConstraint> {
+    let mut redundant_constraints = Vec::new();
+
+    let mut expressions: HashMap<Expression, usize> = HashMap::new();
+    // The narrowest range check of each witness, along with its number of bits.
+    let mut range_checks: HashMap<Witness, (usize, u32)> = HashMap::new();
+
+    for (opcode_index, opcode) in circuit.opcodes.iter().enumerate() {
+        match opcode {
+            Opcode::AssertZero(expr) => {
+                let Some(expr) = normalize(expr) else {
+                    continue;
+                };
+                match expressions.entry(expr) {
+                    Entry::Occupied(entry) => redundant_constraints.push(RedundantConstraint {
+                        opcode_index,
+                        implied_by: *entry.get(),
+                        reason: RedundancyReason::DuplicateConstraint,
+                    }),
+                    Entry::Vacant(entry) => {
+                        entry.insert(opcode_index);
+                    }
+                }
+            }
+            Opcode::BlackBoxFuncCall(BlackBoxFuncCall::RANGE { input }) => {
+                let num_bits = input.num_bits;
+                match range_checks.get(&input.witness).copied() {
+                    Some((implied_by, implied_by_bits)) if implied_by_bits <= num_bits => {
+                        redundant_constraints.push(RedundantConstraint {
+                            opcode_index,
+                            implied_by,
+                            reason: RedundancyReason::ImpliedRangeCheck {
+                                num_bits,
+                                implied_by_bits,
+                            },
+                        });
+                    }
+                    Some((narrower, narrower_bits)) => {
+                        // This check is narrower so it's the previous one which is redundant.
+                        redundant_constraints.push(RedundantConstraint {
+                            opcode_index: narrower,
+                            implied_by: opcode_index,
+                            reason: RedundancyReason::ImpliedRangeCheck {
+                                num_bits: narrower_bits,
+                                implied_by_bits: num_bits,
+                            },
+                        });
+                        range_checks.insert(input.witness, (opcode_index, num_bits));
+                    }
+                    None => {
+                        range_checks.insert(input.witness, (opcode_index, num_bits));
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    redundant_constraints.sort_by_key(|constraint| constraint.opcode_index);
+    redundant_constraints
+}
+
+/// Scales `expr` so that its first term has a coefficient of one, so that expressions which only
+/// differ by a constant factor are equal once normalized.
+///
+/// Returns `None` for expressions without any witnesses, as those aren't constraints on the
+/// witnesses at all.
+fn normalize(expr: &Expression) -> Option<Expression> {
+    let mut expr = expr.clone();
+    expr.sort();
+
+    let leading_coefficient = expr
+        .mul_terms
+        .iter()
+        .map(|(coefficient, ..)| *coefficient)
+        .chain(expr.linear_combinations.iter().map(|(coefficient, _)| *coefficient))
+        .find(|coefficient| !coefficient.is_zero())?;
+
+    Some(&expr * leading_coefficient.inverse())
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::{
+        acir::{
+            circuit::{
+                opcodes::{BlackBoxFuncCall, FunctionInput},
+                Circuit, Opcode,
+            },
+            native_types::{Expression, Witness},
+        },
+        FieldElement,
+    };
+
+    use super::{find_redundant_constraints, RedundancyReason, RedundantConstraint};
+
+    fn range_check(witness: u32, num_bits: u32) -> Opcode {
+        Opcode::BlackBoxFuncCall(BlackBoxFuncCall::RANGE {
+            input: FunctionInput { witness: Witness(witness), num_bits },
+        })
+    }
+
+    fn equality(lhs: u32, rhs: u32) -> Opcode {
+        Opcode::AssertZero(Expression {
+            mul_terms: vec![],
+            linear_combinations: vec![
+                (FieldElement::one(), Witness(lhs)),
+                (-FieldElement::one(), Witness(rhs)),
+            ],
+            q_c: FieldElement::zero(),
+        })
+    }
+
+    #[test]
+    fn finds_repeated_equalities() {
+        let circuit = Circuit {
+            opcodes: vec![equality(1, 2), equality(2, 3), equality(2, 1)],
+            ..Circuit::default()
+        };
+
+        assert_eq!(
+            find_redundant_constraints(&circuit),
+            vec![RedundantConstraint {
+                opcode_index: 2,
+                implied_by: 0,
+                reason: RedundancyReason::DuplicateConstraint,
+            }]
+        );
+    }
+
+    #[test]
+    fn finds_implied_range_checks() {
+        let circuit = Circuit {
+            opcodes: vec![
+                range_check(1, 32),
+                range_check(1, 8),
+                range_check(2, 8),
+                range_check(1, 8),
+            ],
+            ..Circuit::default()
+        };
+
+        assert_eq!(
+            find_redundant_constraints(&circuit),
+            vec![
+                RedundantConstraint {
+                    opcode_index: 0,
+                    implied_by: 1,
+                    reason: RedundancyReason::ImpliedRangeCheck {
+                        num_bits: 32,
+                        implied_by_bits: 8
+                    },
+                },
+                RedundantConstraint {
+                    opcode_index: 3,
+                    implied_by: 1,
+                    reason: RedundancyReason::ImpliedRangeCheck { num_bits: 8, implied_by_bits: 8 },
+                },
+            ]
+        );
+    }
+}
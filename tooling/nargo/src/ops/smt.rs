@@ -0,0 +1,377 @@
+//! Checking that the outputs of a circuit are uniquely determined by its inputs with an SMT solver.
+//!
+//! A circuit is under-constrained if a prover can satisfy it with the same inputs but different
+//! outputs, which usually means that the result of an unconstrained hint hasn't been checked.
+//! To find these, the circuit is translated into SMT-LIB twice, with a separate copy of every
+//! witness in each translation. The copies of the parameters are asserted to be equal and the
+//! solver is asked whether the copies of each return value can still differ.
+//!
+//! Field elements are represented as integers reduced modulo the field's modulus, which only
+//! scales to small circuits. The outputs of black box functions are assumed to be determined by
+//! their inputs, while the outputs of Brillig calls and directives are left unconstrained as
+//! they're only hints to the prover.
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::process::{Command, Stdio};
+
+use acvm::acir::circuit::brillig::{BrilligInputs, BrilligOutputs};
+use acvm::acir::circuit::directives::Directive;
+use acvm::acir::circuit::opcodes::BlackBoxFuncCall;
+use acvm::acir::circuit::{Circuit, Opcode, OpcodeLocation};
+use acvm::acir::native_types::{Expression, Witness};
+use acvm::FieldElement;
+
+use crate::errors::SmtError;
+
+/// The names given to the two copies of the circuit's witnesses.
+const COPIES: [&str; 2] = ["a", "b"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Determination {
+    /// The witness has the same value in every solution with the same inputs.
+    Unique,
+    /// The witness can take different values for the same inputs.
+    Free,
+    /// The solver couldn't decide whether the witness is uniquely determined.
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputDetermination {
+    pub witness: Witness,
+    pub determination: Determination,
+    /// The first opcode which refers to the witness, used to locate it in the source code.
+    pub opcode_location: Option<OpcodeLocation>,
+}
+
+/// Asks the SMT solver run by `solver_command` whether each return value of `circuit` is uniquely
+/// determined by its parameters.
+///
+/// The solver is given an SMT-LIB script on its stdin and is expected to answer each `check-sat`
+/// on its own line, e.g. `z3 -in`.
+pub fn check_output_determination(
+    circuit: &Circuit,
+    solver_command: &str,
+) -> Result<Vec<OutputDetermination>, SmtError> {
+    let outputs: Vec<Witness> = circuit.return_values.0.iter().copied().collect();
+    if outputs.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let script = circuit_to_smtlib(circuit, &outputs)?;
+    let answers = run_solver(solver_command, &script)?;
+    if answers.len() != outputs.len() {
+        return Err(SmtError::UnexpectedOutput(answers.join("\n")));
+    }
+
+    outputs
+        .into_iter()
+        .zip(answers)
+        .map(|(witness, answer)| {
+            let determination = match answer.as_str() {
+                // A model with differing outputs is a witness to the output being free.
+                "sat" => Determination::Free,
+                "unsat" => Determination::Unique,
+                "unknown" | "timeout" => Determination::Unknown,
+                _ => return Err(SmtError::UnexpectedOutput(answer)),
+            };
+            let opcode_location = circuit
+                .opcodes
+                .iter()
+                .position(|opcode| opcode_witnesses(opcode).contains(&witness))
+                .map(OpcodeLocation::Acir);
+            Ok(OutputDetermination { witness, determination, opcode_location })
+        })
+        .collect()
+}
+
+/// Translates `circuit` into an SMT-LIB script which checks, for each of `targets`, whether two
+/// solutions of the circuit with the same parameters can assign it different values.
+pub fn circuit_to_smtlib(circuit: &Circuit, targets: &[Witness]) -> Result<String, SmtError> {
+    let modulus = FieldElement::modulus().to_string();
+
+    let mut witnesses: BTreeSet<Witness> = circuit.private_parameters.clone();
+    witnesses.extend(circuit.public_parameters.0.iter().copied());
+    witnesses.extend(circuit.return_values.0.iter().copied());
+    for opcode in &circuit.opcodes {
+        witnesses.extend(opcode_witnesses(opcode));
+    }
+
+    let mut script = String::from("(set-logic QF_NIA)\n");
+    for copy in COPIES {
+        for witness in &witnesses {
+            let name = witness_name(copy, *witness);
+            writeln!(script, "(declare-const {name} Int)").unwrap();
+            writeln!(script, "(assert (and (<= 0 {name}) (< {name} {modulus})))").unwrap();
+        }
+    }
+
+    for parameter in circuit.private_parameters.iter().chain(&circuit.public_parameters.0) {
+        writeln!(script, "(assert {})", copies_equal(&[*parameter])).unwrap();
+    }
+
+    for (index, opcode) in circuit.opcodes.iter().enumerate() {
+        match opcode {
+            Opcode::AssertZero(expr) => {
+                for copy in COPIES {
+                    let expr = expression_to_smtlib(copy, expr);
+                    writeln!(script, "(assert (= (mod {expr} {modulus}) 0))").unwrap();
+                }
+            }
+            Opcode::BlackBoxFuncCall(BlackBoxFuncCall::RANGE { input }) => {
+                // Range checks of the full field size don't constrain the witness.
+                if input.num_bits < FieldElement::max_num_bits() {
+                    let bound = decimal_from_be_bytes(&power_of_two(input.num_bits));
+                    for copy in COPIES {
+                        let name = witness_name(copy, input.witness);
+                        writeln!(script, "(assert (< {name} {bound}))").unwrap();
+                    }
+                }
+            }
+            Opcode::BlackBoxFuncCall(call) => {
+                let inputs: Vec<Witness> =
+                    call.get_inputs_vec().iter().map(|input| input.witness).collect();
+                let outputs = call.get_outputs_vec();
+                writeln!(
+                    script,
+                    "(assert (=> {} {}))",
+                    copies_equal(&inputs),
+                    copies_equal(&outputs)
+                )
+                .unwrap();
+            }
+            // The outputs of hints aren't constrained by the opcodes which compute them.
+            Opcode::Brillig(_) | Opcode::Directive(_) => (),
+            Opcode::MemoryOp { .. } | Opcode::MemoryInit { .. } | Opcode::Call { .. } => {
+                return Err(SmtError::UnsupportedOpcode(index));
+            }
+        }
+    }
+
+    for target in targets {
+        writeln!(script, "(push 1)").unwrap();
+        writeln!(script, "(assert (not {}))", copies_equal(&[*target])).unwrap();
+        writeln!(script, "(check-sat)").unwrap();
+        writeln!(script, "(pop 1)").unwrap();
+    }
+
+    Ok(script)
+}
+
+fn run_solver(solver_command: &str, script: &str) -> Result<Vec<String>, SmtError> {
+    let solver_error = |err| SmtError::Solver(solver_command.to_owned(), err);
+
+    let mut words = solver_command.split_whitespace();
+    let program = words.next().unwrap_or_default();
+    let mut child = Command::new(program)
+        .args(words)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(solver_error)?;
+
+    // Dropping stdin once the script is written lets the solver know there's nothing more to do.
+    let mut stdin = child.stdin.take().expect("stdin should be piped");
+    stdin.write_all(script.as_bytes()).map_err(solver_error)?;
+    drop(stdin);
+
+    let output = child.wait_with_output().map_err(solver_error)?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout.lines().map(str::trim).filter(|line| !line.is_empty()).map(str::to_owned).collect())
+}
+
+/// Returns every witness which `opcode` reads or writes.
+fn opcode_witnesses(opcode: &Opcode) -> BTreeSet<Witness> {
+    fn expression_witnesses(expr: &Expression) -> impl Iterator<Item = Witness> + '_ {
+        let mul_witnesses = expr.mul_terms.iter().flat_map(|(_, lhs, rhs)| [*lhs, *rhs]);
+        mul_witnesses.chain(expr.linear_combinations.iter().map(|(_, witness)| *witness))
+    }
+
+    let mut witnesses = BTreeSet::new();
+    match opcode {
+        Opcode::AssertZero(expr) => witnesses.extend(expression_witnesses(expr)),
+        Opcode::BlackBoxFuncCall(call) => {
+            witnesses.extend(call.get_inputs_vec().iter().map(|input| input.witness));
+            witnesses.extend(call.get_outputs_vec());
+        }
+        Opcode::Directive(Directive::ToLeRadix { a, b, .. }) => {
+            witnesses.extend(expression_witnesses(a));
+            witnesses.extend(b.iter().copied());
+        }
+        Opcode::Directive(Directive::PermutationSort { inputs, bits, .. }) => {
+            witnesses.extend(inputs.iter().flatten().flat_map(expression_witnesses));
+            witnesses.extend(bits.iter().copied());
+        }
+        Opcode::Brillig(brillig) => {
+            for input in &brillig.inputs {
+                match input {
+                    BrilligInputs::Single(expr) => witnesses.extend(expression_witnesses(expr)),
+                    BrilligInputs::Array(exprs) => {
+                        witnesses.extend(exprs.iter().flat_map(expression_witnesses));
+                    }
+                }
+            }
+            for output in &brillig.outputs {
+                match output {
+                    BrilligOutputs::Simple(witness) => {
+                        witnesses.insert(*witness);
+                    }
+                    BrilligOutputs::Array(outputs) => witnesses.extend(outputs.iter().copied()),
+                }
+            }
+            if let Some(predicate) = &brillig.predicate {
+                witnesses.extend(expression_witnesses(predicate));
+            }
+        }
+        Opcode::MemoryOp { op, predicate, .. } => {
+            witnesses.extend(expression_witnesses(&op.operation));
+            witnesses.extend(expression_witnesses(&op.index));
+            witnesses.extend(expression_witnesses(&op.value));
+            if let Some(predicate) = predicate {
+                witnesses.extend(expression_witnesses(predicate));
+            }
+        }
+        Opcode::MemoryInit { init, .. } => witnesses.extend(init.iter().copied()),
+        Opcode::Call { inputs, outputs, predicate, .. } => {
+            witnesses.extend(inputs.iter().copied());
+            witnesses.extend(outputs.iter().copied());
+            if let Some(predicate) = predicate {
+                witnesses.extend(expression_witnesses(predicate));
+            }
+        }
+    }
+    witnesses
+}
+
+fn witness_name(copy: &str, witness: Witness) -> String {
+    format!("{copy}_{}", witness.witness_index())
+}
+
+/// Returns a term which is true if both copies of each of `witnesses` are equal.
+fn copies_equal(witnesses: &[Witness]) -> String {
+    let [a, b] = COPIES;
+    let equalities: Vec<String> = witnesses
+        .iter()
+        .map(|witness| format!("(= {} {})", witness_name(a, *witness), witness_name(b, *witness)))
+        .collect();
+    match equalities.len() {
+        0 => String::from("true"),
+        1 => equalities[0].clone(),
+        _ => format!("(and {})", equalities.join(" ")),
+    }
+}
+
+fn expression_to_smtlib(copy: &str, expr: &Expression) -> String {
+    let mut terms: Vec<String> = Vec::new();
+    for (coefficient, lhs, rhs) in &expr.mul_terms {
+        terms.push(format!(
+            "(* {} {} {})",
+            field_to_smtlib(*coefficient),
+            witness_name(copy, *lhs),
+            witness_name(copy, *rhs)
+        ));
+    }
+    for (coefficient, witness) in &expr.linear_combinations {
+        terms.push(format!(
+            "(* {} {})",
+            field_to_smtlib(*coefficient),
+            witness_name(copy, *witness)
+        ));
+    }
+    terms.push(field_to_smtlib(expr.q_c));
+
+    if terms.len() == 1 {
+        terms.remove(0)
+    } else {
+        format!("(+ {})", terms.join(" "))
+    }
+}
+
+fn field_to_smtlib(value: FieldElement) -> String {
+    decimal_from_be_bytes(&value.to_be_bytes())
+}
+
+/// Returns the big-endian bytes of `2^exponent`.
+fn power_of_two(exponent: u32) -> Vec<u8> {
+    let mut bytes = vec![0u8; exponent as usize / 8 + 1];
+    bytes[0] = 1 << (exponent % 8);
+    bytes
+}
+
+/// Formats a big-endian unsigned integer in decimal, as SMT-LIB has no hex literals for integers.
+fn decimal_from_be_bytes(bytes: &[u8]) -> String {
+    // Little-endian limbs of nine decimal digits each.
+    const LIMB: u64 = 1_000_000_000;
+    let mut limbs: Vec<u64> = vec![0];
+    for byte in bytes {
+        let mut carry = u64::from(*byte);
+        for limb in limbs.iter_mut() {
+            let value = *limb * 256 + carry;
+            *limb = value % LIMB;
+            carry = value / LIMB;
+        }
+        while carry > 0 {
+            limbs.push(carry % LIMB);
+            carry /= LIMB;
+        }
+    }
+
+    let mut limbs = limbs.into_iter().rev();
+    let mut decimal = limbs.next().unwrap_or_default().to_string();
+    for limb in limbs {
+        write!(decimal, "{limb:09}").unwrap();
+    }
+    decimal
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use acvm::{
+        acir::{
+            circuit::{Circuit, Opcode, PublicInputs},
+            native_types::{Expression, Witness},
+        },
+        FieldElement,
+    };
+
+    use super::{circuit_to_smtlib, decimal_from_be_bytes, power_of_two};
+
+    #[test]
+    fn formats_large_integers_in_decimal() {
+        assert_eq!(decimal_from_be_bytes(&[]), "0");
+        assert_eq!(decimal_from_be_bytes(&[1, 0]), "256");
+        assert_eq!(decimal_from_be_bytes(&power_of_two(64)), "18446744073709551616");
+        assert_eq!(
+            decimal_from_be_bytes(&(-FieldElement::one()).to_be_bytes()),
+            (FieldElement::modulus() - 1u32).to_string()
+        );
+    }
+
+    #[test]
+    fn translates_constraints_for_both_copies() {
+        // x * x - y = 0
+        let circuit = Circuit {
+            current_witness_index: 2,
+            opcodes: vec![Opcode::AssertZero(Expression {
+                mul_terms: vec![(FieldElement::one(), Witness(1), Witness(1))],
+                linear_combinations: vec![(-FieldElement::one(), Witness(2))],
+                q_c: FieldElement::zero(),
+            })],
+            private_parameters: BTreeSet::from([Witness(1)]),
+            return_values: PublicInputs(BTreeSet::from([Witness(2)])),
+            ..Circuit::default()
+        };
+
+        let script = circuit_to_smtlib(&circuit, &[Witness(2)]).unwrap();
+
+        assert!(script.contains("(assert (= a_1 b_1))"));
+        assert!(script.contains("(* 1 a_1 a_1)"));
+        assert!(script.contains("(* 1 b_1 b_1)"));
+        assert!(script.contains("(assert (not (= a_2 b_2)))"));
+        assert_eq!(script.matches("(check-sat)").count(), 1);
+    }
+}
@@ -6,7 +6,7 @@ use noirc_frontend::hir::{def_map::TestFunction, Context};
 
 use crate::{errors::try_to_diagnose_runtime_error, NargoError};
 
-use super::{execute_circuit, DefaultForeignCallExecutor};
+use super::{execute_circuit, DefaultForeignCallExecutor, LogLevel};
 
 pub enum TestStatus {
     Pass,
@@ -19,6 +19,7 @@ pub fn run_test<B: BlackBoxFunctionSolver>(
     context: &Context,
     test_function: TestFunction,
     show_output: bool,
+    log_level: LogLevel,
     foreign_call_resolver_url: Option<&str>,
     config: &CompileOptions,
 ) -> TestStatus {
@@ -29,9 +30,11 @@ pub fn run_test<B: BlackBoxFunctionSolver>(
             // otherwise constraints involving these expressions will not error.
             let circuit_execution = execute_circuit(
                 &program.circuit,
+                &program.functions,
                 WitnessMap::new(),
                 blackbox_solver,
-                &mut DefaultForeignCallExecutor::new(show_output, foreign_call_resolver_url),
+                &mut DefaultForeignCallExecutor::new(show_output, foreign_call_resolver_url)
+                    .with_log_level(log_level),
             );
             test_status_program_compile_pass(test_function, program.debug, circuit_execution)
         }
@@ -65,7 +68,7 @@ fn test_status_program_compile_fail(err: RuntimeError, test_function: TestFuncti
 ///
 /// We now check whether execution passed/failed and whether it should have
 /// passed/failed to determine the test status.
-fn test_status_program_compile_pass(
+pub(super) fn test_status_program_compile_pass(
     test_function: TestFunction,
     debug: DebugInfo,
     circuit_execution: Result<WitnessMap, NargoError>,
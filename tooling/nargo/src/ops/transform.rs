@@ -11,6 +11,8 @@ pub fn transform_program(
 
     program.circuit = optimized_circuit;
     program.debug.update_acir(location_map);
+    program.functions =
+        vecmap(program.functions, |function| acvm::compiler::compile(function, expression_width).0);
     program
 }
 
@@ -23,6 +25,9 @@ pub fn transform_contract(
             acvm::compiler::compile(func.bytecode, expression_width);
         func.bytecode = optimized_bytecode;
         func.debug.update_acir(location_map);
+        func.functions = vecmap(func.functions, |function| {
+            acvm::compiler::compile(function, expression_width).0
+        });
         func
     });
 
@@ -1,4 +1,8 @@
-use std::{collections::BTreeMap, fmt::Display, path::PathBuf};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+    path::PathBuf,
+};
 
 use noirc_frontend::graph::CrateName;
 
@@ -51,6 +55,8 @@ pub struct Package {
     pub entry_path: PathBuf,
     pub name: CrateName,
     pub dependencies: BTreeMap<CrateName, Dependency>,
+    /// The features of the package which are enabled, for `#[cfg(feature = "...")]` attributes.
+    pub features: BTreeSet<String>,
 }
 
 impl Package {
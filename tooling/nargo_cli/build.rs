@@ -75,7 +75,7 @@ fn execution_success_{test_name}() {{
     let mut cmd = Command::cargo_bin("nargo").unwrap();
     cmd.env("NARGO_BACKEND_PATH", path_to_mock_backend());
     cmd.arg("--program-dir").arg(test_program_dir);
-    cmd.arg("execute").arg("--force");
+    cmd.arg("execute").arg("--force").arg("--verify-ssa");
 
     cmd.assert().success();
 }}
@@ -0,0 +1,67 @@
+use clap::Args;
+use nargo_toml::{
+    add_dependency, get_package_manifest, resolve_workspace_from_toml, DependencySource,
+    PackageSelection,
+};
+use noirc_driver::NOIR_ARTIFACT_VERSION_STRING;
+use noirc_frontend::graph::CrateName;
+
+use crate::errors::CliError;
+
+use super::NargoConfig;
+
+/// Add a dependency to Nargo.toml
+#[derive(Debug, Clone, Args)]
+pub(crate) struct AddCommand {
+    /// The dependency to add, optionally followed by a version requirement, e.g. `foo@1.2`
+    dependency: String,
+
+    /// The name of the package to add the dependency to
+    #[clap(long)]
+    package: Option<CrateName>,
+
+    /// Depend on a git repository instead of a registry package
+    #[arg(long, requires = "tag", conflicts_with = "path")]
+    git: Option<String>,
+
+    /// The tag of the git repository to depend on
+    #[arg(long, requires = "git")]
+    tag: Option<String>,
+
+    /// Depend on a package in a local directory instead of a registry package
+    #[arg(long, conflicts_with = "git")]
+    path: Option<String>,
+}
+
+pub(crate) fn run(args: AddCommand, config: NargoConfig) -> Result<(), CliError> {
+    let (name, version) = match args.dependency.split_once('@') {
+        Some((name, version)) => (name.to_string(), Some(version.to_string())),
+        None => (args.dependency, None),
+    };
+    let source = match (args.git, args.tag, args.path) {
+        (Some(git), Some(tag), None) if version.is_none() => DependencySource::Git { git, tag },
+        (None, None, Some(path)) if version.is_none() => DependencySource::Path { path },
+        (None, None, None) => DependencySource::Registry { version },
+        _ => {
+            return Err(CliError::Generic(
+                "A version can only be given for a registry dependency".to_string(),
+            ))
+        }
+    };
+
+    let mut toml_path = get_package_manifest(&config.program_dir)?;
+    if let Some(package_name) = args.package {
+        let workspace = resolve_workspace_from_toml(
+            &toml_path,
+            PackageSelection::Selected(package_name),
+            Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
+        )?;
+        let package = workspace.into_iter().next().expect("selected package is in the workspace");
+        toml_path = get_package_manifest(&package.root_dir)?;
+    }
+
+    let value = add_dependency(&toml_path, &name, source)?;
+    println!("Added {name} = {value} to {}", toml_path.display());
+
+    Ok(())
+}
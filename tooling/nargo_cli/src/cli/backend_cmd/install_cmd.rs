@@ -1,30 +1,39 @@
+use std::path::Path;
+
 use clap::Args;
 
 use backend_interface::{backends_directory, download_backend};
 
 use crate::errors::{BackendError, CliError};
 
-use super::ls_cmd::get_available_backends;
+use super::ls_cmd::get_installed_backends;
 
-/// Install a new backend from a URL.
+/// Install a new backend from a URL or a local executable.
 #[derive(Debug, Clone, Args)]
 pub(crate) struct InstallCommand {
     /// The name of the backend to install.
     backend: String,
 
-    /// The URL from which to download the backend.
+    /// The URL from which to download the backend, or the path to a backend executable.
     url: String,
 }
 
 pub(crate) fn run(args: InstallCommand) -> Result<(), CliError> {
-    let installed_backends = get_available_backends();
+    let installed_backends = get_installed_backends();
 
     if installed_backends.contains(&args.backend) {
         return Err(BackendError::AlreadyInstalled(args.backend).into());
     }
 
-    download_backend(&args.url, &backends_directory().join(args.backend).join("backend_binary"))
-        .map_err(BackendError::from)?;
+    let binary_path = backends_directory().join(args.backend).join("backend_binary");
+    let local_binary = Path::new(&args.url);
+    if local_binary.is_file() {
+        std::fs::create_dir_all(binary_path.parent().expect("binary path has a parent"))
+            .and_then(|_| std::fs::copy(local_binary, &binary_path))
+            .map_err(BackendError::from)?;
+    } else {
+        download_backend(&args.url, &binary_path).map_err(BackendError::from)?;
+    }
 
     Ok(())
 }
@@ -1,25 +1,38 @@
-use backend_interface::backends_directory;
+use backend_interface::{backends_directory, discover_backends};
 use clap::Args;
 
-use crate::errors::CliError;
+use crate::{backends::get_active_backend, errors::CliError};
 
-/// Prints the list of currently installed backends
+/// Prints the list of currently installed backends, marking the active one
 #[derive(Debug, Clone, Args)]
 pub(crate) struct LsCommand;
 
 pub(crate) fn run(_args: LsCommand) -> Result<(), CliError> {
+    let active_backend = get_active_backend();
     for backend in get_available_backends() {
-        println!("{backend}");
+        let marker = if backend == active_backend { "*" } else { " " };
+        println!("{marker} {backend}");
     }
 
     Ok(())
 }
 
+/// Returns the installed backends along with the backends found on the `PATH`.
 pub(super) fn get_available_backends() -> Vec<String> {
+    let mut backends = get_installed_backends();
+    for backend in discover_backends().into_keys() {
+        if !backends.contains(&backend) {
+            backends.push(backend);
+        }
+    }
+    backends
+}
+
+/// Returns the backends installed in the backends directory.
+pub(super) fn get_installed_backends() -> Vec<String> {
     let backend_directory_contents = std::fs::read_dir(backends_directory())
         .expect("Could not read backends directory contents");
 
-    // TODO: Highlight the currently active backend.
     backend_directory_contents
         .into_iter()
         .filter_map(|entry| {
@@ -9,7 +9,7 @@ use crate::{
     errors::{BackendError, CliError},
 };
 
-use super::ls_cmd::get_available_backends;
+use super::ls_cmd::get_installed_backends;
 
 /// Uninstalls a backend
 #[derive(Debug, Clone, Args)]
@@ -19,7 +19,7 @@ pub(crate) struct UninstallCommand {
 }
 
 pub(crate) fn run(args: UninstallCommand) -> Result<(), CliError> {
-    let installed_backends = get_available_backends();
+    let installed_backends = get_installed_backends();
 
     if !installed_backends.contains(&args.backend) {
         return Err(BackendError::UnknownBackend(args.backend).into());
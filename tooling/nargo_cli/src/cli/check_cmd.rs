@@ -1,26 +1,35 @@
 use crate::backends::Backend;
 use crate::errors::CliError;
 
+use acvm::acir::circuit::OpcodeLocation;
 use clap::Args;
 use fm::FileManager;
 use iter_extended::btree_map;
 use nargo::{
-    errors::CompileError, insert_all_files_for_workspace_into_file_manager, package::Package,
+    cache::{can_use_build_cache, package_fingerprint},
+    errors::CompileError,
+    insert_all_files_for_workspace_into_file_manager,
+    ops::{compile_program, find_redundant_constraints},
+    package::Package,
     parse_all, prepare_package,
 };
-use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
+use nargo_toml::{get_package_manifest, resolve_workspace_with_features, PackageSelection};
 use noirc_abi::{AbiParameter, AbiType, MAIN_RETURN_NAME};
 use noirc_driver::{
     check_crate, compute_function_abi, file_manager_with_stdlib, CompileOptions,
     NOIR_ARTIFACT_VERSION_STRING,
 };
+use noirc_errors::CustomDiagnostic;
 use noirc_frontend::{
     graph::{CrateId, CrateName},
     hir::{Context, ParsedFiles},
 };
 
-use super::fs::write_to_file;
-use super::NargoConfig;
+use super::fs::{
+    build_cache::{read_build_cache, save_build_cache},
+    write_to_file,
+};
+use super::{feature_selection, fill_target_backend, NargoConfig};
 
 /// Checks the constraint system for errors
 #[derive(Debug, Clone, Args)]
@@ -33,22 +42,29 @@ pub(crate) struct CheckCommand {
     #[clap(long, conflicts_with = "package")]
     workspace: bool,
 
+    /// Report constraints which are implied by other constraints of the circuit
+    #[clap(long)]
+    overconstrained: bool,
+
     #[clap(flatten)]
     compile_options: CompileOptions,
 }
 
 pub(crate) fn run(
-    _backend: &Backend,
-    args: CheckCommand,
+    backend: &Backend,
+    mut args: CheckCommand,
     config: NargoConfig,
 ) -> Result<(), CliError> {
+    fill_target_backend(&mut args.compile_options, backend);
+
     let toml_path = get_package_manifest(&config.program_dir)?;
     let default_selection =
         if args.workspace { PackageSelection::All } else { PackageSelection::DefaultOrAll };
     let selection = args.package.map_or(default_selection, PackageSelection::Selected);
-    let workspace = resolve_workspace_from_toml(
+    let workspace = resolve_workspace_with_features(
         &toml_path,
         selection,
+        &feature_selection(&args.compile_options),
         Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
     )?;
 
@@ -56,31 +72,143 @@ pub(crate) fn run(
     insert_all_files_for_workspace_into_file_manager(&workspace, &mut workspace_file_manager);
     let parsed_files = parse_all(&workspace_file_manager);
 
+    let target_dir = workspace.target_directory_path();
+    let mut build_cache = read_build_cache(&target_dir);
+    let use_build_cache = can_use_build_cache(&args.compile_options);
+
     for package in &workspace {
-        check_package(&workspace_file_manager, &parsed_files, package, &args.compile_options)?;
+        let fingerprint =
+            package_fingerprint(&workspace_file_manager, package, &args.compile_options);
+        let package_name: String = package.name.clone().into();
+
+        // A package which was checked without warnings and hasn't changed since doesn't need
+        // to be checked again, as long as its input files haven't been deleted in the meantime.
+        let is_fresh = build_cache.checked.get(&package_name) == Some(&fingerprint);
+        let has_input_files = package.is_library()
+            || package.is_contract()
+            || (package.prover_input_path().exists() && package.verifier_input_path().exists());
+        if !(use_build_cache && is_fresh && has_input_files) {
+            let no_warnings = check_package(
+                &workspace_file_manager,
+                &parsed_files,
+                package,
+                &args.compile_options,
+            )?;
+            if no_warnings {
+                build_cache.checked.insert(package_name, fingerprint);
+            } else {
+                build_cache.checked.remove(&package_name);
+            }
+            save_build_cache(&build_cache, &target_dir);
+        }
         println!("[{}] Constraint system successfully built!", package.name);
+
+        if args.overconstrained && package.is_binary() {
+            report_redundant_constraints(
+                &workspace_file_manager,
+                &parsed_files,
+                package,
+                &args.compile_options,
+            )?;
+        }
     }
     Ok(())
 }
 
-fn check_package(
+/// Compiles `package` and reports the constraints of its circuit which are implied by others,
+/// as these can be removed without changing the semantics of the program.
+fn report_redundant_constraints(
     file_manager: &FileManager,
     parsed_files: &ParsedFiles,
     package: &Package,
     compile_options: &CompileOptions,
 ) -> Result<(), CompileError> {
+    let compiled_program = super::compile_cmd::report_errors(
+        compile_program(file_manager, parsed_files, package, compile_options, None),
+        file_manager,
+        compile_options.deny_warnings,
+        // Any warnings have already been reported when checking the package.
+        true,
+    )?;
+
+    let redundant_constraints = find_redundant_constraints(&compiled_program.circuit);
+    let source_location = |opcode_index| {
+        compiled_program
+            .debug
+            .opcode_location(&OpcodeLocation::Acir(opcode_index))
+            .and_then(|call_stack| call_stack.last().copied())
+    };
+
+    let diagnostics: Vec<_> = redundant_constraints
+        .iter()
+        .filter_map(|constraint| {
+            let location = source_location(constraint.opcode_index)?;
+            let mut diagnostic = CustomDiagnostic::simple_warning(
+                format!("Redundant constraint: {}", constraint.reason),
+                format!("opcode {} can be removed", constraint.opcode_index),
+                location.span,
+            );
+            if let Some(implied_by) = source_location(constraint.implied_by)
+                .filter(|implied_by| implied_by.file == location.file)
+            {
+                diagnostic.add_secondary(
+                    format!("implied by opcode {}", constraint.implied_by),
+                    implied_by.span,
+                );
+            }
+            Some(diagnostic.in_file(location.file))
+        })
+        .collect();
+
+    let reported_errors = noirc_errors::reporter::report_all(
+        file_manager.as_file_map(),
+        &diagnostics,
+        compile_options.deny_warnings,
+        false,
+    );
+    println!(
+        "[{}] Found {} redundant constraint{} out of {} ACIR opcodes",
+        package.name,
+        redundant_constraints.len(),
+        if redundant_constraints.len() == 1 { "" } else { "s" },
+        compiled_program.circuit.opcodes.len()
+    );
+
+    if reported_errors.error_count > 0 {
+        Err(reported_errors.into())
+    } else {
+        Ok(())
+    }
+}
+
+/// Checks `package` and generates its `Prover.toml` and `Verifier.toml` files.
+///
+/// Returns whether the package was checked without any warnings.
+fn check_package(
+    file_manager: &FileManager,
+    parsed_files: &ParsedFiles,
+    package: &Package,
+    compile_options: &CompileOptions,
+) -> Result<bool, CompileError> {
     let (mut context, crate_id) = prepare_package(file_manager, parsed_files, package);
-    check_crate_and_report_errors(
+    context.crate_graph.backend = compile_options.target_backend.clone();
+    let result = check_crate(
         &mut context,
         crate_id,
         compile_options.deny_warnings,
         compile_options.disable_macros,
+    );
+    let no_warnings = matches!(&result, Ok((_, warnings)) if warnings.is_empty());
+    super::compile_cmd::report_errors(
+        result,
+        &context.file_manager,
+        compile_options.deny_warnings,
         compile_options.silence_warnings,
     )?;
 
     if package.is_library() || package.is_contract() {
         // Libraries do not have ABIs while contracts have many, so we cannot generate a `Prover.toml` file.
-        Ok(())
+        Ok(no_warnings)
     } else {
         // XXX: We can have a --overwrite flag to determine if you want to overwrite the Prover/Verifier.toml files
         if let Some((parameters, return_type)) = compute_function_abi(&context, &crate_id) {
@@ -100,7 +228,7 @@ fn check_package(
                 write_to_file(verifier_toml.as_bytes(), &path_to_verifier_input);
             }
 
-            Ok(())
+            Ok(no_warnings)
         } else {
             Err(CompileError::MissingMainFunction(package.name.clone()))
         }
@@ -122,6 +250,7 @@ fn create_input_toml_template(
                     .collect();
                 toml::Value::Array(default_value_vec)
             }
+            AbiType::Vec { .. } => toml::Value::Array(Vec::new()),
             AbiType::Struct { fields, .. } => {
                 let default_value_map = toml::map::Map::from_iter(
                     fields.into_iter().map(|(name, typ)| (name, default_value(typ))),
@@ -195,15 +324,19 @@ d2 = ["", "", ""]
 pub(crate) fn check_crate_and_report_errors(
     context: &mut Context,
     crate_id: CrateId,
-    deny_warnings: bool,
-    disable_macros: bool,
-    silence_warnings: bool,
+    compile_options: &CompileOptions,
 ) -> Result<(), CompileError> {
-    let result = check_crate(context, crate_id, deny_warnings, disable_macros);
+    context.crate_graph.backend = compile_options.target_backend.clone();
+    let result = check_crate(
+        context,
+        crate_id,
+        compile_options.deny_warnings,
+        compile_options.disable_macros,
+    );
     super::compile_cmd::report_errors(
         result,
         &context.file_manager,
-        deny_warnings,
-        silence_warnings,
+        compile_options.deny_warnings,
+        compile_options.silence_warnings,
     )
 }
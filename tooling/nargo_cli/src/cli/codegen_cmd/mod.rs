@@ -0,0 +1,31 @@
+use clap::{Args, Subcommand};
+
+use crate::errors::CliError;
+
+use super::NargoConfig;
+
+mod ts_cmd;
+
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+/// Generate bindings for using the program from other languages.
+pub(crate) struct CodegenCommand {
+    #[command(subcommand)]
+    command: CodegenCommands,
+}
+
+#[non_exhaustive]
+#[derive(Subcommand, Clone, Debug)]
+pub(crate) enum CodegenCommands {
+    Ts(ts_cmd::TsCommand),
+}
+
+pub(crate) fn run(cmd: CodegenCommand, config: NargoConfig) -> Result<(), CliError> {
+    let CodegenCommand { command } = cmd;
+
+    match command {
+        CodegenCommands::Ts(args) => ts_cmd::run(args, config),
+    }?;
+
+    Ok(())
+}
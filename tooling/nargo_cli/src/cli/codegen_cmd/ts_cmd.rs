@@ -0,0 +1,79 @@
+use crate::cli::compile_cmd::report_errors;
+use crate::cli::fs::{create_named_dir, write_to_file};
+use crate::cli::{feature_selection, NargoConfig};
+use crate::errors::CliError;
+
+use clap::Args;
+use nargo::ops::compile_programs;
+use nargo::{insert_all_files_for_workspace_into_file_manager, parse_all};
+use nargo_toml::{get_package_manifest, resolve_workspace_with_features, PackageSelection};
+use noirc_driver::{file_manager_with_stdlib, CompileOptions, NOIR_ARTIFACT_VERSION_STRING};
+use noirc_frontend::graph::CrateName;
+
+/// Generates TypeScript types for the inputs of the program, along with helpers converting them
+/// to and from an `InputMap`
+#[derive(Debug, Clone, Args)]
+pub(crate) struct TsCommand {
+    /// The name of the package to codegen
+    #[clap(long, conflicts_with = "workspace")]
+    package: Option<CrateName>,
+
+    /// Codegen all packages in the workspace
+    #[clap(long, conflicts_with = "package")]
+    workspace: bool,
+
+    #[clap(flatten)]
+    compile_options: CompileOptions,
+}
+
+pub(crate) fn run(args: TsCommand, config: NargoConfig) -> Result<(), CliError> {
+    let toml_path = get_package_manifest(&config.program_dir)?;
+    let default_selection =
+        if args.workspace { PackageSelection::All } else { PackageSelection::DefaultOrAll };
+    let selection = args.package.map_or(default_selection, PackageSelection::Selected);
+    let workspace = resolve_workspace_with_features(
+        &toml_path,
+        selection,
+        &feature_selection(&args.compile_options),
+        Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
+    )?;
+
+    let mut workspace_file_manager = file_manager_with_stdlib(&workspace.root_dir);
+    insert_all_files_for_workspace_into_file_manager(&workspace, &mut workspace_file_manager);
+    let parsed_files = parse_all(&workspace_file_manager);
+
+    let binary_packages: Vec<_> =
+        workspace.into_iter().filter(|package| package.is_binary()).cloned().collect();
+
+    let compilation_results = compile_programs(
+        &workspace_file_manager,
+        &parsed_files,
+        &binary_packages,
+        &args.compile_options,
+    );
+    let target_dir = workspace.target_directory_path();
+    for (package, compilation_result) in binary_packages.iter().zip(compilation_results) {
+        let program = report_errors(
+            compilation_result,
+            &workspace_file_manager,
+            args.compile_options.deny_warnings,
+            args.compile_options.silence_warnings,
+        )?;
+
+        let bindings = program.abi.to_typescript_bindings();
+
+        let name: String = package.name.clone().into();
+        create_named_dir(&target_dir, "target");
+        let declarations_path = target_dir.join(format!("{name}.d.ts"));
+        write_to_file(bindings.declarations.as_bytes(), &declarations_path);
+        let helpers_path = target_dir.join(&name).with_extension("js");
+        let path = write_to_file(bindings.helpers.as_bytes(), &helpers_path);
+
+        println!(
+            "[{}] TypeScript bindings successfully created and located at {path}",
+            package.name
+        );
+    }
+
+    Ok(())
+}
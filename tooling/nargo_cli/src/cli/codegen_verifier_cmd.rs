@@ -1,19 +1,24 @@
 use super::fs::{create_named_dir, write_to_file};
-use super::NargoConfig;
+use super::{
+    check_no_acir_calls, compile_and_handle_programs, feature_selection,
+    fill_supported_black_boxes, fill_target_backend, NargoConfig,
+};
 use crate::backends::Backend;
-use crate::cli::compile_cmd::report_errors;
 use crate::errors::CliError;
 
-use clap::Args;
-use nargo::ops::compile_program;
+use clap::{Args, ValueEnum};
 use nargo::{insert_all_files_for_workspace_into_file_manager, parse_all};
-use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
+use nargo_toml::{get_package_manifest, resolve_workspace_with_features, PackageSelection};
 use noirc_driver::{file_manager_with_stdlib, CompileOptions, NOIR_ARTIFACT_VERSION_STRING};
 use noirc_frontend::graph::CrateName;
 
-/// Generates a Solidity verifier smart contract for the program
+/// Generates a verifier smart contract for the program
 #[derive(Debug, Clone, Args)]
 pub(crate) struct CodegenVerifierCommand {
+    /// The chain to generate the verifier contract for
+    #[clap(long, value_enum, default_value_t = VerifierTarget::Solidity)]
+    target: VerifierTarget,
+
     /// The name of the package to codegen
     #[clap(long, conflicts_with = "workspace")]
     package: Option<CrateName>,
@@ -26,18 +31,30 @@ pub(crate) struct CodegenVerifierCommand {
     compile_options: CompileOptions,
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum VerifierTarget {
+    /// A Solidity contract for EVM chains, written by the backend
+    Solidity,
+    /// A CosmWasm contract template for Cosmos chains
+    #[value(name = "cosmwasm")]
+    CosmWasm,
+    /// A Cairo contract template for Starknet
+    Cairo,
+}
+
 pub(crate) fn run(
     backend: &Backend,
-    args: CodegenVerifierCommand,
+    mut args: CodegenVerifierCommand,
     config: NargoConfig,
 ) -> Result<(), CliError> {
     let toml_path = get_package_manifest(&config.program_dir)?;
     let default_selection =
         if args.workspace { PackageSelection::All } else { PackageSelection::DefaultOrAll };
     let selection = args.package.map_or(default_selection, PackageSelection::Selected);
-    let workspace = resolve_workspace_from_toml(
+    let workspace = resolve_workspace_with_features(
         &toml_path,
         selection,
+        &feature_selection(&args.compile_options),
         Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
     )?;
 
@@ -46,34 +63,40 @@ pub(crate) fn run(
     let parsed_files = parse_all(&workspace_file_manager);
 
     let expression_width = backend.get_backend_info()?;
-    let binary_packages = workspace.into_iter().filter(|package| package.is_binary());
-    for package in binary_packages {
-        let compilation_result = compile_program(
-            &workspace_file_manager,
-            &parsed_files,
-            package,
-            &args.compile_options,
-            None,
-        );
-
-        let program = report_errors(
-            compilation_result,
-            &workspace_file_manager,
-            args.compile_options.deny_warnings,
-            args.compile_options.silence_warnings,
-        )?;
-
-        let program = nargo::ops::transform_program(program, expression_width);
-
-        let smart_contract_string = backend.eth_contract(&program.circuit)?;
+    fill_supported_black_boxes(&mut args.compile_options, backend);
+    fill_target_backend(&mut args.compile_options, backend);
+    let binary_packages: Vec<_> =
+        workspace.into_iter().filter(|package| package.is_binary()).cloned().collect();
 
-        let contract_dir = workspace.contracts_directory_path(package);
-        create_named_dir(&contract_dir, "contract");
-        let contract_path = contract_dir.join("plonk_vk").with_extension("sol");
+    compile_and_handle_programs(
+        &workspace_file_manager,
+        &parsed_files,
+        &binary_packages,
+        &args.compile_options,
+        expression_width,
+        |package, program| {
+            check_no_acir_calls(&package.name.to_string(), &program.functions)?;
+            let (smart_contract_string, contract_file) = match args.target {
+                VerifierTarget::Solidity => {
+                    (backend.eth_contract(&program.circuit)?, "plonk_vk.sol")
+                }
+                VerifierTarget::CosmWasm => {
+                    let verification_key = backend.get_verification_key(&program.circuit)?;
+                    (program.abi.to_cosmwasm_verifier(&verification_key), "verifier.rs")
+                }
+                VerifierTarget::Cairo => {
+                    let verification_key = backend.get_verification_key(&program.circuit)?;
+                    (program.abi.to_cairo_verifier(&verification_key), "verifier.cairo")
+                }
+            };
 
-        let path = write_to_file(smart_contract_string.as_bytes(), &contract_path);
-        println!("[{}] Contract successfully created and located at {path}", package.name);
-    }
+            let contract_dir = workspace.contracts_directory_path(package);
+            create_named_dir(&contract_dir, "contract");
+            let contract_path = contract_dir.join(contract_file);
 
-    Ok(())
+            let path = write_to_file(smart_contract_string.as_bytes(), &contract_path);
+            println!("[{}] Contract successfully created and located at {path}", package.name);
+            Ok(())
+        },
+    )
 }
@@ -2,12 +2,13 @@ use std::path::Path;
 
 use fm::FileManager;
 use nargo::artifacts::program::ProgramArtifact;
+use nargo::cache::{can_use_build_cache, package_fingerprint};
 use nargo::errors::CompileError;
-use nargo::ops::{compile_contract, compile_program};
+use nargo::ops::{compile_contracts, compile_program};
 use nargo::package::Package;
 use nargo::workspace::Workspace;
 use nargo::{insert_all_files_for_workspace_into_file_manager, parse_all};
-use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
+use nargo_toml::{get_package_manifest, resolve_workspace_and_write_lockfile, PackageSelection};
 use noirc_driver::file_manager_with_stdlib;
 use noirc_driver::NOIR_ARTIFACT_VERSION_STRING;
 use noirc_driver::{CompilationResult, CompileOptions, CompiledContract, CompiledProgram};
@@ -20,9 +21,10 @@ use noirc_frontend::hir::ParsedFiles;
 use crate::backends::Backend;
 use crate::errors::CliError;
 
+use super::fs::build_cache::{read_build_cache, save_build_cache};
 use super::fs::program::only_acir;
 use super::fs::program::{read_program_from_file, save_contract_to_file, save_program_to_file};
-use super::NargoConfig;
+use super::{feature_selection, fill_supported_black_boxes, fill_target_backend, NargoConfig};
 use rayon::prelude::*;
 
 /// Compile the program and its secret execution trace into ACIR format
@@ -42,7 +44,7 @@ pub(crate) struct CompileCommand {
 
 pub(crate) fn run(
     backend: &Backend,
-    args: CompileCommand,
+    mut args: CompileCommand,
     config: NargoConfig,
 ) -> Result<(), CliError> {
     let toml_path = get_package_manifest(&config.program_dir)?;
@@ -50,9 +52,10 @@ pub(crate) fn run(
         if args.workspace { PackageSelection::All } else { PackageSelection::DefaultOrAll };
     let selection = args.package.map_or(default_selection, PackageSelection::Selected);
 
-    let workspace = resolve_workspace_from_toml(
+    let workspace = resolve_workspace_and_write_lockfile(
         &toml_path,
         selection,
+        &feature_selection(&args.compile_options),
         Some(NOIR_ARTIFACT_VERSION_STRING.to_owned()),
     )?;
     let circuit_dir = workspace.target_directory_path();
@@ -65,6 +68,8 @@ pub(crate) fn run(
         .compile_options
         .expression_width
         .unwrap_or_else(|| backend.get_backend_info_or_default());
+    fill_supported_black_boxes(&mut args.compile_options, backend);
+    fill_target_backend(&mut args.compile_options, backend);
     let (compiled_program, compiled_contracts) = compile_workspace(
         &workspace_file_manager,
         &parsed_files,
@@ -104,29 +109,66 @@ pub(super) fn compile_workspace(
         .cloned()
         .partition(|package| package.is_binary());
 
-    // Compile all of the packages in parallel.
-    let program_results: Vec<CompilationResult<CompiledProgram>> = binary_packages
-        .par_iter()
-        .map(|package| {
-            let program_artifact_path = workspace.package_build_path(package);
-            let cached_program: Option<CompiledProgram> =
-                read_program_from_file(program_artifact_path)
-                    .ok()
-                    .filter(|p| p.noir_version == NOIR_ARTIFACT_VERSION_STRING)
-                    .map(|p| p.into());
-
-            compile_program(file_manager, parsed_files, package, compile_options, cached_program)
-        })
-        .collect();
-    let contract_results: Vec<CompilationResult<CompiledContract>> = contract_packages
-        .par_iter()
-        .map(|package| compile_contract(file_manager, parsed_files, package, compile_options))
-        .collect();
+    let target_dir = workspace.target_directory_path();
+    let mut build_cache = read_build_cache(&target_dir);
+    let use_build_cache = can_use_build_cache(compile_options);
+
+    // Compile all of the packages in parallel, programs alongside contracts. Results are collected
+    // in package order so that diagnostics and artifacts are reported deterministically.
+    let compile_binary_packages = || -> Vec<(u64, CompilationResult<CompiledProgram>)> {
+        binary_packages
+            .par_iter()
+            .map(|package| {
+                let program_artifact_path = workspace.package_build_path(package);
+                let cached_program: Option<CompiledProgram> =
+                    read_program_from_file(program_artifact_path)
+                        .ok()
+                        .filter(|p| p.noir_version == NOIR_ARTIFACT_VERSION_STRING)
+                        .map(|p| p.into());
+
+                // Packages which haven't changed since they were last compiled can reuse the
+                // previous artifact without being parsed or elaborated again.
+                let fingerprint = package_fingerprint(file_manager, package, compile_options);
+                let package_name: String = package.name.clone().into();
+                let is_fresh = build_cache.compiled.get(&package_name) == Some(&fingerprint);
+                if let Some(cached_program) =
+                    cached_program.clone().filter(|_| use_build_cache && is_fresh)
+                {
+                    return (fingerprint, Ok((cached_program, Vec::new())));
+                }
+
+                let result = compile_program(
+                    file_manager,
+                    parsed_files,
+                    package,
+                    compile_options,
+                    cached_program,
+                );
+                (fingerprint, result)
+            })
+            .collect()
+    };
+    let (program_results, contract_results) = rayon::join(compile_binary_packages, || {
+        compile_contracts(file_manager, parsed_files, &contract_packages, compile_options)
+    });
+
+    for (package, (fingerprint, result)) in binary_packages.iter().zip(&program_results) {
+        let package_name: String = package.name.clone().into();
+        match result {
+            Ok((_, warnings)) if warnings.is_empty() => {
+                build_cache.compiled.insert(package_name, *fingerprint);
+            }
+            _ => {
+                build_cache.compiled.remove(&package_name);
+            }
+        }
+    }
+    save_build_cache(&build_cache, &target_dir);
 
     // Report any warnings/errors which were encountered during compilation.
     let compiled_programs: Vec<CompiledProgram> = program_results
         .into_iter()
-        .map(|compilation_result| {
+        .map(|(_, compilation_result)| {
             report_errors(
                 compilation_result,
                 file_manager,
@@ -86,7 +86,7 @@ fn load_and_compile_project(
     insert_all_files_for_workspace_into_file_manager(&workspace, &mut workspace_file_manager);
     let parsed_files = parse_all(&workspace_file_manager);
 
-    let compile_options = CompileOptions::default();
+    let compile_options = CompileOptions { instrument_debug: true, ..CompileOptions::default() };
     let compilation_result =
         compile_program(&workspace_file_manager, &parsed_files, package, &compile_options, None);
 
@@ -127,6 +127,7 @@ fn loop_uninitialized_dap<R: Read, W: Write>(
                     supports_disassemble_request: Some(true),
                     supports_instruction_breakpoints: Some(true),
                     supports_stepping_granularity: Some(true),
+                    supports_data_breakpoints: Some(true),
                     ..Default::default()
                 }));
                 server.respond(rsp)?;
@@ -9,7 +9,7 @@ use nargo::constants::PROVER_INPUT_FILE;
 use nargo::ops::compile_program;
 use nargo::package::Package;
 use nargo::{insert_all_files_for_workspace_into_file_manager, parse_all};
-use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
+use nargo_toml::{get_package_manifest, resolve_workspace_with_features, PackageSelection};
 use noirc_abi::input_parser::{Format, InputValue};
 use noirc_abi::InputMap;
 use noirc_driver::{
@@ -18,8 +18,11 @@ use noirc_driver::{
 use noirc_frontend::graph::CrateName;
 
 use super::compile_cmd::report_errors;
-use super::fs::{inputs::read_inputs_from_file, witness::save_witness_to_dir};
-use super::NargoConfig;
+use super::fs::{
+    inputs::read_inputs_from_file,
+    witness::{save_witness_to_dir, WitnessFormat},
+};
+use super::{feature_selection, fill_target_backend, NargoConfig};
 use crate::backends::Backend;
 use crate::errors::CliError;
 
@@ -43,14 +46,18 @@ pub(crate) struct DebugCommand {
 
 pub(crate) fn run(
     backend: &Backend,
-    args: DebugCommand,
+    mut args: DebugCommand,
     config: NargoConfig,
 ) -> Result<(), CliError> {
+    args.compile_options.instrument_debug = true;
+    fill_target_backend(&mut args.compile_options, backend);
+
     let toml_path = get_package_manifest(&config.program_dir)?;
     let selection = args.package.map_or(PackageSelection::DefaultOrAll, PackageSelection::Selected);
-    let workspace = resolve_workspace_from_toml(
+    let workspace = resolve_workspace_with_features(
         &toml_path,
         selection,
+        &feature_selection(&args.compile_options),
         Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
     )?;
     let target_dir = &workspace.target_directory_path();
@@ -102,6 +109,7 @@ fn run_async(
 
     runtime.block_on(async {
         println!("[{}] Starting debugger", package.name);
+        let abi = program.abi.clone();
         let (return_value, solved_witness) =
             debug_program_and_decode(program, package, prover_name)?;
 
@@ -113,7 +121,13 @@ fn run_async(
             }
 
             if let Some(witness_name) = witness_name {
-                let witness_path = save_witness_to_dir(solved_witness, witness_name, target_dir)?;
+                let witness_path = save_witness_to_dir(
+                    solved_witness,
+                    &abi,
+                    witness_name,
+                    target_dir,
+                    WitnessFormat::Binary,
+                )?;
 
                 println!("[{}] Witness saved to {}", package.name, witness_path.display());
             }
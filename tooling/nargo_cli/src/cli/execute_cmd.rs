@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use acvm::acir::native_types::WitnessMap;
 use bn254_blackbox_solver::Bn254BlackBoxSolver;
 use clap::Args;
@@ -5,21 +7,27 @@ use clap::Args;
 use nargo::artifacts::debug::DebugArtifact;
 use nargo::constants::PROVER_INPUT_FILE;
 use nargo::errors::try_to_diagnose_runtime_error;
-use nargo::ops::{compile_program, DefaultForeignCallExecutor};
+use nargo::ops::{profile_brillig_execution, DefaultForeignCallExecutor, LogLevel};
 use nargo::package::Package;
-use nargo::{insert_all_files_for_workspace_into_file_manager, parse_all};
-use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
-use noirc_abi::input_parser::{Format, InputValue};
+use nargo::{insert_all_files_for_workspace_into_file_manager, parse_all, NargoError};
+use nargo_toml::{get_package_manifest, resolve_workspace_with_features, PackageSelection};
+use noirc_abi::input_parser::InputValue;
 use noirc_abi::InputMap;
 use noirc_driver::{
     file_manager_with_stdlib, CompileOptions, CompiledProgram, NOIR_ARTIFACT_VERSION_STRING,
 };
 use noirc_frontend::graph::CrateName;
 
-use super::fs::{inputs::read_inputs_from_file, witness::save_witness_to_dir};
-use super::NargoConfig;
+use super::fs::{
+    inputs::{read_inputs_from_file, InputFormat},
+    witness::{save_witness_to_dir, WitnessFormat},
+};
+use super::profile_cmd::print_brillig_profile;
+use super::{
+    compile_and_handle_programs, feature_selection, fill_supported_black_boxes,
+    fill_target_backend, NargoConfig,
+};
 use crate::backends::Backend;
-use crate::cli::compile_cmd::report_errors;
 use crate::errors::CliError;
 
 /// Executes a circuit to calculate its return value
@@ -28,10 +36,18 @@ pub(crate) struct ExecuteCommand {
     /// Write the execution witness to named file
     witness_name: Option<String>,
 
-    /// The name of the toml file which contains the inputs for the prover
+    /// The format in which the execution witness is written
+    #[clap(long, value_enum, default_value_t = WitnessFormat::Binary)]
+    witness_format: WitnessFormat,
+
+    /// The name of the file which contains the inputs for the prover
     #[clap(long, short, default_value = PROVER_INPUT_FILE)]
     prover_name: String,
 
+    /// The format of the file which contains the inputs for the prover
+    #[clap(long, value_enum, default_value_t = InputFormat::Toml)]
+    input_format: InputFormat,
+
     /// The name of the package to execute
     #[clap(long, conflicts_with = "workspace")]
     package: Option<CrateName>,
@@ -46,20 +62,29 @@ pub(crate) struct ExecuteCommand {
     /// JSON RPC url to solve oracle calls
     #[clap(long)]
     oracle_resolver: Option<String>,
+
+    /// The lowest level of `std::log` messages to print: debug, info, warn or error
+    #[clap(long, default_value_t = LogLevel::Info)]
+    log_level: LogLevel,
+
+    /// Count the Brillig opcodes executed by unconstrained calls and report the most expensive ones
+    #[clap(long)]
+    profile_brillig: bool,
 }
 
 pub(crate) fn run(
     backend: &Backend,
-    args: ExecuteCommand,
+    mut args: ExecuteCommand,
     config: NargoConfig,
 ) -> Result<(), CliError> {
     let toml_path = get_package_manifest(&config.program_dir)?;
     let default_selection =
         if args.workspace { PackageSelection::All } else { PackageSelection::DefaultOrAll };
     let selection = args.package.map_or(default_selection, PackageSelection::Selected);
-    let workspace = resolve_workspace_from_toml(
+    let workspace = resolve_workspace_with_features(
         &toml_path,
         selection,
+        &feature_selection(&args.compile_options),
         Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
     )?;
     let target_dir = &workspace.target_directory_path();
@@ -72,55 +97,76 @@ pub(crate) fn run(
         .compile_options
         .expression_width
         .unwrap_or_else(|| backend.get_backend_info_or_default());
-    let binary_packages = workspace.into_iter().filter(|package| package.is_binary());
-    for package in binary_packages {
-        let compilation_result = compile_program(
-            &workspace_file_manager,
-            &parsed_files,
-            package,
-            &args.compile_options,
-            None,
-        );
-
-        let compiled_program = report_errors(
-            compilation_result,
-            &workspace_file_manager,
-            args.compile_options.deny_warnings,
-            args.compile_options.silence_warnings,
-        )?;
-
-        let compiled_program = nargo::ops::transform_program(compiled_program, expression_width);
-
-        let (return_value, solved_witness) = execute_program_and_decode(
-            compiled_program,
-            package,
-            &args.prover_name,
-            args.oracle_resolver.as_deref(),
-        )?;
-
-        println!("[{}] Circuit witness successfully solved", package.name);
-        if let Some(return_value) = return_value {
-            println!("[{}] Circuit output: {return_value:?}", package.name);
-        }
-        if let Some(witness_name) = &args.witness_name {
-            let witness_path = save_witness_to_dir(solved_witness, witness_name, target_dir)?;
-
-            println!("[{}] Witness saved to {}", package.name, witness_path.display());
-        }
-    }
-    Ok(())
+    fill_supported_black_boxes(&mut args.compile_options, backend);
+    fill_target_backend(&mut args.compile_options, backend);
+    let binary_packages: Vec<_> =
+        workspace.into_iter().filter(|package| package.is_binary()).cloned().collect();
+
+    compile_and_handle_programs(
+        &workspace_file_manager,
+        &parsed_files,
+        &binary_packages,
+        &args.compile_options,
+        expression_width,
+        |package, compiled_program| {
+            let abi = compiled_program.abi.clone();
+
+            let (return_value, solved_witness) = execute_program_and_decode(
+                compiled_program,
+                package,
+                &args.prover_name,
+                args.input_format,
+                args.oracle_resolver.as_deref(),
+                args.log_level,
+                args.profile_brillig,
+            )?;
+
+            println!("[{}] Circuit witness successfully solved", package.name);
+            if let Some(return_value) = return_value {
+                println!("[{}] Circuit output: {return_value:?}", package.name);
+            }
+            if let Some(witness_name) = &args.witness_name {
+                let witness_path = save_witness_to_dir(
+                    solved_witness,
+                    &abi,
+                    witness_name,
+                    target_dir,
+                    args.witness_format,
+                )?;
+
+                println!("[{}] Witness saved to {}", package.name, witness_path.display());
+            }
+            Ok(())
+        },
+    )
 }
 
 fn execute_program_and_decode(
     program: CompiledProgram,
     package: &Package,
     prover_name: &str,
+    input_format: InputFormat,
     foreign_call_resolver_url: Option<&str>,
+    log_level: LogLevel,
+    profile_brillig: bool,
 ) -> Result<(Option<InputValue>, WitnessMap), CliError> {
-    // Parse the initial witness values from Prover.toml
+    // Parse the initial witness values from the prover's input file
     let (inputs_map, _) =
-        read_inputs_from_file(&package.root_dir, prover_name, Format::Toml, &program.abi)?;
-    let solved_witness = execute_program(&program, &inputs_map, foreign_call_resolver_url)?;
+        read_inputs_from_file(&package.root_dir, prover_name, input_format.into(), &program.abi)?;
+    let solved_witness = if profile_brillig {
+        let (solved_witness, opcode_counts) = execute_program_with_brillig_profiling(
+            &program,
+            &inputs_map,
+            foreign_call_resolver_url,
+            log_level,
+        )?;
+        let profile =
+            profile_brillig_execution(&program.debug, &debug_artifact(&program), &opcode_counts);
+        print_brillig_profile(package, &profile);
+        solved_witness
+    } else {
+        execute_program(&program, &inputs_map, foreign_call_resolver_url, log_level)?
+    };
     let public_abi = program.abi.public_abi();
     let (_, return_value) = public_abi.decode(&solved_witness)?;
 
@@ -131,31 +177,56 @@ pub(crate) fn execute_program(
     compiled_program: &CompiledProgram,
     inputs_map: &InputMap,
     foreign_call_resolver_url: Option<&str>,
+    log_level: LogLevel,
 ) -> Result<WitnessMap, CliError> {
     let blackbox_solver = Bn254BlackBoxSolver::new();
 
     let initial_witness = compiled_program.abi.encode(inputs_map, None)?;
 
-    let solved_witness_err = nargo::ops::execute_circuit(
+    nargo::ops::execute_circuit(
         &compiled_program.circuit,
+        &compiled_program.functions,
         initial_witness,
         &blackbox_solver,
-        &mut DefaultForeignCallExecutor::new(true, foreign_call_resolver_url),
-    );
-    match solved_witness_err {
-        Ok(solved_witness) => Ok(solved_witness),
-        Err(err) => {
-            let debug_artifact = DebugArtifact {
-                debug_symbols: vec![compiled_program.debug.clone()],
-                file_map: compiled_program.file_map.clone(),
-                warnings: compiled_program.warnings.clone(),
-            };
-
-            if let Some(diagnostic) = try_to_diagnose_runtime_error(&err, &compiled_program.debug) {
-                diagnostic.report(&debug_artifact, false);
-            }
+        &mut DefaultForeignCallExecutor::new(true, foreign_call_resolver_url)
+            .with_log_level(log_level),
+    )
+    .map_err(|err| report_execution_error(compiled_program, err))
+}
+
+fn execute_program_with_brillig_profiling(
+    compiled_program: &CompiledProgram,
+    inputs_map: &InputMap,
+    foreign_call_resolver_url: Option<&str>,
+    log_level: LogLevel,
+) -> Result<(WitnessMap, BTreeMap<usize, Vec<usize>>), CliError> {
+    let blackbox_solver = Bn254BlackBoxSolver::new();
+
+    let initial_witness = compiled_program.abi.encode(inputs_map, None)?;
+
+    nargo::ops::execute_circuit_with_brillig_profiling(
+        &compiled_program.circuit,
+        &compiled_program.functions,
+        initial_witness,
+        &blackbox_solver,
+        &mut DefaultForeignCallExecutor::new(true, foreign_call_resolver_url)
+            .with_log_level(log_level),
+    )
+    .map_err(|err| report_execution_error(compiled_program, err))
+}
+
+fn report_execution_error(compiled_program: &CompiledProgram, err: NargoError) -> CliError {
+    if let Some(diagnostic) = try_to_diagnose_runtime_error(&err, &compiled_program.debug) {
+        diagnostic.report(&debug_artifact(compiled_program), false);
+    }
+
+    CliError::NargoError(err)
+}
 
-            Err(crate::errors::CliError::NargoError(err))
-        }
+fn debug_artifact(compiled_program: &CompiledProgram) -> DebugArtifact {
+    DebugArtifact {
+        debug_symbols: vec![compiled_program.debug.clone()],
+        file_map: compiled_program.file_map.clone(),
+        warnings: compiled_program.warnings.clone(),
     }
 }
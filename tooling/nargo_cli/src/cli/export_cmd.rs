@@ -9,7 +9,7 @@ use nargo::package::Package;
 use nargo::prepare_package;
 use nargo::workspace::Workspace;
 use nargo::{insert_all_files_for_workspace_into_file_manager, parse_all};
-use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
+use nargo_toml::{get_package_manifest, resolve_workspace_with_features, PackageSelection};
 use noirc_driver::{
     compile_no_check, file_manager_with_stdlib, CompileOptions, CompiledProgram,
     NOIR_ARTIFACT_VERSION_STRING,
@@ -26,7 +26,8 @@ use super::check_cmd::check_crate_and_report_errors;
 
 use super::compile_cmd::report_errors;
 use super::fs::program::save_program_to_file;
-use super::NargoConfig;
+use super::fs::write_to_file;
+use super::{feature_selection, fill_target_backend, NargoConfig};
 
 /// Exports functions marked with #[export] attribute
 #[derive(Debug, Clone, Args)]
@@ -39,23 +40,31 @@ pub(crate) struct ExportCommand {
     #[clap(long, conflicts_with = "package")]
     workspace: bool,
 
+    /// Also generate a Solidity library for each exported function, which encodes its inputs
+    /// into the public inputs expected by its verifier contract
+    #[clap(long)]
+    solidity: bool,
+
     #[clap(flatten)]
     compile_options: CompileOptions,
 }
 
 pub(crate) fn run(
-    _backend: &Backend,
-    args: ExportCommand,
+    backend: &Backend,
+    mut args: ExportCommand,
     config: NargoConfig,
 ) -> Result<(), CliError> {
+    fill_target_backend(&mut args.compile_options, backend);
+
     let toml_path = get_package_manifest(&config.program_dir)?;
     let default_selection =
         if args.workspace { PackageSelection::All } else { PackageSelection::DefaultOrAll };
     let selection = args.package.map_or(default_selection, PackageSelection::Selected);
 
-    let workspace = resolve_workspace_from_toml(
+    let workspace = resolve_workspace_with_features(
         &toml_path,
         selection,
+        &feature_selection(&args.compile_options),
         Some(NOIR_ARTIFACT_VERSION_STRING.to_owned()),
     )?;
 
@@ -75,6 +84,7 @@ pub(crate) fn run(
                 &workspace,
                 package,
                 &args.compile_options,
+                args.solidity,
             )
         })
         .collect()
@@ -86,15 +96,10 @@ fn compile_exported_functions(
     workspace: &Workspace,
     package: &Package,
     compile_options: &CompileOptions,
+    solidity: bool,
 ) -> Result<(), CliError> {
     let (mut context, crate_id) = prepare_package(file_manager, parsed_files, package);
-    check_crate_and_report_errors(
-        &mut context,
-        crate_id,
-        compile_options.deny_warnings,
-        compile_options.disable_macros,
-        compile_options.silence_warnings,
-    )?;
+    check_crate_and_report_errors(&mut context, crate_id, compile_options)?;
 
     let exported_functions = context.get_all_exported_functions_in_crate(&crate_id);
 
@@ -118,7 +123,28 @@ fn compile_exported_functions(
 
     let export_dir = workspace.export_directory_path();
     for (function_name, program) in exported_programs {
+        let library = solidity
+            .then(|| program.abi.to_solidity_encoder(&solidity_library_name(&function_name)));
         save_program_to_file(&program.into(), &function_name.parse().unwrap(), &export_dir);
+
+        if let Some(library) = library {
+            let library_path = export_dir.join(&function_name).with_extension("sol");
+            write_to_file(library.as_bytes(), &library_path);
+        }
     }
     Ok(())
 }
+
+/// Converts the snake case name of a function into the name of its Solidity library,
+/// e.g. `verify_signature` becomes `VerifySignatureInputs`.
+fn solidity_library_name(function_name: &str) -> String {
+    let mut library_name: String = function_name
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map_or(String::new(), |first| first.to_uppercase().chain(chars).collect())
+        })
+        .collect();
+    library_name.push_str("Inputs");
+    library_name
+}
@@ -79,6 +79,7 @@ pub(crate) fn run(args: FormatCommand, config: NargoConfig) -> Result<(), CliErr
                         check_exit_code_one = true;
                     }
 
+                    println!("Diff in {}:", entry.path().display());
                     println!("{diff}");
                 }
 
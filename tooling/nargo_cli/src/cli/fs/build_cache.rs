@@ -0,0 +1,24 @@
+use std::path::Path;
+
+use nargo::cache::BuildCache;
+
+use super::{create_named_dir, write_to_file};
+
+const BUILD_CACHE_FILE: &str = "build_cache.json";
+
+/// Reads the build cache from the target directory.
+///
+/// An empty cache is returned if the cache is missing, unreadable or was written by another
+/// version of the compiler.
+pub(crate) fn read_build_cache(target_dir: &Path) -> BuildCache {
+    std::fs::read(target_dir.join(BUILD_CACHE_FILE))
+        .ok()
+        .and_then(|bytes| serde_json::from_slice::<BuildCache>(&bytes).ok())
+        .filter(BuildCache::is_current_version)
+        .unwrap_or_default()
+}
+
+pub(crate) fn save_build_cache(build_cache: &BuildCache, target_dir: &Path) {
+    create_named_dir(target_dir, "target");
+    write_to_file(&serde_json::to_vec(build_cache).unwrap(), &target_dir.join(BUILD_CACHE_FILE));
+}
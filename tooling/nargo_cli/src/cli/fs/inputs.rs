@@ -4,10 +4,30 @@ use noirc_abi::{
 };
 use std::{collections::BTreeMap, path::Path};
 
+use clap::ValueEnum;
+
 use crate::errors::FilesystemError;
 
 use super::write_to_file;
 
+/// The format of the file from which a program's inputs are read
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub(crate) enum InputFormat {
+    Json,
+    Toml,
+    Yaml,
+}
+
+impl From<InputFormat> for Format {
+    fn from(format: InputFormat) -> Self {
+        match format {
+            InputFormat::Json => Format::Json,
+            InputFormat::Toml => Format::Toml,
+            InputFormat::Yaml => Format::Yaml,
+        }
+    }
+}
+
 /// Returns the circuit's parameters and its return value, if one exists.
 /// # Examples
 ///
@@ -27,7 +47,11 @@ pub(crate) fn read_inputs_from_file<P: AsRef<Path>>(
 
     let file_path = path.as_ref().join(file_name).with_extension(format.ext());
     if !file_path.exists() {
-        return Err(FilesystemError::MissingTomlFile(file_name.to_owned(), file_path));
+        return Err(FilesystemError::MissingInputFile(
+            file_name.to_owned(),
+            format.ext(),
+            file_path,
+        ));
     }
 
     let input_string = std::fs::read_to_string(file_path).unwrap();
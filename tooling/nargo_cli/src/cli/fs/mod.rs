@@ -6,6 +6,7 @@ use std::{
 
 use crate::errors::FilesystemError;
 
+pub(super) mod build_cache;
 pub(super) mod inputs;
 pub(super) mod program;
 pub(super) mod proof;
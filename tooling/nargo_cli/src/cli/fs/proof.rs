@@ -1,6 +1,8 @@
 use std::path::{Path, PathBuf};
 
+use acvm::FieldElement;
 use nargo::constants::PROOF_EXT;
+use serde::Serialize;
 
 use crate::errors::FilesystemError;
 
@@ -18,3 +20,45 @@ pub(crate) fn save_proof_to_dir<P: AsRef<Path>>(
 
     Ok(proof_path)
 }
+
+/// The artifacts needed to verify a proof inside of another circuit, laid out as the inputs of the
+/// helpers of `std::recursion` so that they can be copied into the inputs of that circuit.
+#[derive(Debug, Serialize)]
+pub(crate) struct RecursionArtifacts {
+    pub(crate) public_inputs: Vec<String>,
+    pub(crate) key: RecursionKey,
+    pub(crate) proof: RecursionProof,
+}
+
+/// A `std::recursion::VerificationKey`
+#[derive(Debug, Serialize)]
+pub(crate) struct RecursionKey {
+    pub(crate) key: Vec<String>,
+    pub(crate) hash: String,
+}
+
+/// A `std::recursion::Proof`
+#[derive(Debug, Serialize)]
+pub(crate) struct RecursionProof {
+    pub(crate) fields: Vec<String>,
+}
+
+pub(crate) fn field_to_hex(value: FieldElement) -> String {
+    format!("0x{}", value.to_hex())
+}
+
+pub(crate) fn save_recursion_artifacts_to_dir<P: AsRef<Path>>(
+    artifacts: &RecursionArtifacts,
+    proof_name: &str,
+    proof_dir: P,
+) -> Result<PathBuf, FilesystemError> {
+    create_named_dir(proof_dir.as_ref(), "proof");
+    let artifacts_path =
+        proof_dir.as_ref().join(format!("{proof_name}_recursion")).with_extension("toml");
+
+    let contents =
+        toml::to_string(artifacts).expect("recursion artifacts should serialize to TOML");
+    write_to_file(contents.as_bytes(), &artifacts_path);
+
+    Ok(artifacts_path)
+}
@@ -1,22 +1,216 @@
-use std::path::{Path, PathBuf};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
 
-use acvm::acir::native_types::WitnessMap;
-use nargo::constants::WITNESS_EXT;
+use acvm::{
+    acir::native_types::{Witness, WitnessMap},
+    FieldElement,
+};
+use clap::ValueEnum;
+use nargo::constants::{WITNESS_EXT, WITNESS_JSON_EXT};
+use noirc_abi::{Abi, MAIN_RETURN_NAME};
+use serde::{Deserialize, Serialize};
 
 use super::{create_named_dir, write_to_file};
 use crate::errors::FilesystemError;
 
+/// The format in which a witness is written to or read from file
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub(crate) enum WitnessFormat {
+    /// The compressed bincode serialization used by backends
+    Binary,
+    /// A JSON object of hex-encoded values, grouped by the ABI name they belong to
+    Json,
+}
+
+impl WitnessFormat {
+    fn ext(&self) -> &'static str {
+        match self {
+            WitnessFormat::Binary => WITNESS_EXT,
+            WitnessFormat::Json => WITNESS_JSON_EXT,
+        }
+    }
+}
+
+/// A witness map in which each witness is listed under the ABI parameter or return value it is
+/// assigned to, keyed by its index in the witness map.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct JsonWitnessMap {
+    /// The witnesses of each of the program's parameters and its return value.
+    abi: BTreeMap<String, BTreeMap<u32, String>>,
+    /// The witnesses which are not part of the program's ABI.
+    intermediates: BTreeMap<u32, String>,
+}
+
+impl JsonWitnessMap {
+    fn from_witness_map(mut witnesses: BTreeMap<Witness, FieldElement>, abi: &Abi) -> Self {
+        let mut json_witness_map = JsonWitnessMap::default();
+
+        let mut abi_witnesses: Vec<(&str, Vec<Witness>)> = abi
+            .param_witnesses
+            .iter()
+            .map(|(param_name, ranges)| {
+                let indices = ranges.iter().flat_map(|range| range.start.0..range.end.0);
+                (param_name.as_str(), indices.map(Witness).collect())
+            })
+            .collect();
+        if !abi.return_witnesses.is_empty() {
+            abi_witnesses.push((MAIN_RETURN_NAME, abi.return_witnesses.clone()));
+        }
+
+        // The return value may be assigned to the same witnesses as a parameter so we can't
+        // remove witnesses from the map until every ABI name has been handled.
+        for (name, abi_witnesses) in &abi_witnesses {
+            let values = abi_witnesses
+                .iter()
+                .filter_map(|witness| {
+                    witnesses.get(witness).map(|value| (witness.0, format_field(*value)))
+                })
+                .collect();
+            json_witness_map.abi.insert(name.to_string(), values);
+        }
+        for (_, abi_witnesses) in abi_witnesses {
+            for witness in abi_witnesses {
+                witnesses.remove(&witness);
+            }
+        }
+
+        json_witness_map.intermediates = witnesses
+            .into_iter()
+            .map(|(witness, value)| (witness.0, format_field(value)))
+            .collect();
+
+        json_witness_map
+    }
+
+    fn into_witness_map(self) -> Result<WitnessMap, FilesystemError> {
+        let mut witness_map = WitnessMap::new();
+
+        let abi_values = self.abi.into_values().flatten();
+        for (index, value) in abi_values.chain(self.intermediates) {
+            let value = FieldElement::from_hex(&value)
+                .ok_or_else(|| FilesystemError::InvalidWitnessValue(index, value))?;
+
+            if let Some(previous) = witness_map.insert(Witness(index), value) {
+                if previous != value {
+                    return Err(FilesystemError::ConflictingWitnessValues(index));
+                }
+            }
+        }
+
+        Ok(witness_map)
+    }
+}
+
+fn format_field(value: FieldElement) -> String {
+    format!("0x{}", value.to_hex())
+}
+
 pub(crate) fn save_witness_to_dir<P: AsRef<Path>>(
     witnesses: WitnessMap,
+    abi: &Abi,
     witness_name: &str,
     witness_dir: P,
+    format: WitnessFormat,
 ) -> Result<PathBuf, FilesystemError> {
     create_named_dir(witness_dir.as_ref(), "witness");
-    let witness_path = witness_dir.as_ref().join(witness_name).with_extension(WITNESS_EXT);
+    let witness_path = witness_dir.as_ref().join(witness_name).with_extension(format.ext());
 
-    let buf: Vec<u8> = witnesses.try_into()?;
+    let buf: Vec<u8> = match format {
+        WitnessFormat::Binary => witnesses.try_into()?,
+        WitnessFormat::Json => {
+            let witnesses: BTreeMap<Witness, FieldElement> = witnesses.into_iter().collect();
+            let json_witness_map = JsonWitnessMap::from_witness_map(witnesses, abi);
+            serde_json::to_vec_pretty(&json_witness_map)
+                .map_err(|err| FilesystemError::WitnessJson(err.to_string()))?
+        }
+    };
 
     write_to_file(buf.as_slice(), &witness_path);
 
     Ok(witness_path)
 }
+
+pub(crate) fn read_witness_from_dir<P: AsRef<Path>>(
+    witness_name: &str,
+    witness_dir: P,
+    format: WitnessFormat,
+) -> Result<WitnessMap, FilesystemError> {
+    let witness_path = witness_dir.as_ref().join(witness_name).with_extension(format.ext());
+
+    let buf = std::fs::read(&witness_path)
+        .map_err(|_| FilesystemError::PathNotValid(witness_path.clone()))?;
+
+    match format {
+        WitnessFormat::Binary => Ok(WitnessMap::try_from(buf.as_slice())?),
+        WitnessFormat::Json => {
+            let json_witness_map: JsonWitnessMap = serde_json::from_slice(&buf)
+                .map_err(|err| FilesystemError::WitnessJson(err.to_string()))?;
+            json_witness_map.into_witness_map()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use acvm::{
+        acir::native_types::{Witness, WitnessMap},
+        FieldElement,
+    };
+    use noirc_abi::{Abi, AbiParameter, AbiReturnType, AbiType, AbiVisibility};
+    use tempfile::TempDir;
+
+    use super::{read_witness_from_dir, save_witness_to_dir, JsonWitnessMap, WitnessFormat};
+
+    fn abi() -> Abi {
+        Abi {
+            parameters: vec![AbiParameter {
+                name: "foo".into(),
+                typ: AbiType::Array { length: 2, typ: Box::new(AbiType::Field) },
+                visibility: AbiVisibility::Private,
+            }],
+            param_witnesses: BTreeMap::from([("foo".to_owned(), vec![Witness(0)..Witness(2)])]),
+            return_type: Some(AbiReturnType {
+                abi_type: AbiType::Field,
+                visibility: AbiVisibility::Public,
+            }),
+            return_witnesses: vec![Witness(3)],
+        }
+    }
+
+    fn witness_map() -> WitnessMap {
+        WitnessMap::from(BTreeMap::from([
+            (Witness(0), FieldElement::from(1u128)),
+            (Witness(1), FieldElement::from(2u128)),
+            (Witness(2), FieldElement::from(3u128)),
+            (Witness(3), FieldElement::from(6u128)),
+        ]))
+    }
+
+    #[test]
+    fn json_witness_is_grouped_by_abi_name() {
+        let witnesses = witness_map().into_iter().collect();
+        let json_witness_map = JsonWitnessMap::from_witness_map(witnesses, &abi());
+
+        let foo = &json_witness_map.abi["foo"];
+        assert_eq!(foo.keys().copied().collect::<Vec<_>>(), vec![0, 1]);
+        assert_eq!(json_witness_map.abi["return"].keys().copied().collect::<Vec<_>>(), vec![3]);
+        assert_eq!(json_witness_map.intermediates.keys().copied().collect::<Vec<_>>(), vec![2]);
+    }
+
+    #[test]
+    fn write_and_read_recovers_witness_map() {
+        let witness_dir = TempDir::new().unwrap().into_path();
+
+        for format in [WitnessFormat::Binary, WitnessFormat::Json] {
+            save_witness_to_dir(witness_map(), &abi(), "witness", &witness_dir, format).unwrap();
+            let loaded_witness_map =
+                read_witness_from_dir("witness", &witness_dir, format).unwrap();
+
+            assert_eq!(loaded_witness_map, witness_map());
+        }
+    }
+}
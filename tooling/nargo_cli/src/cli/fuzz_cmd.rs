@@ -0,0 +1,136 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bn254_blackbox_solver::Bn254BlackBoxSolver;
+use clap::Args;
+use nargo::artifacts::debug::DebugArtifact;
+use nargo::errors::try_to_diagnose_runtime_error;
+use nargo::ops::{compile_programs, fuzz_program, FuzzResult};
+use nargo::{insert_all_files_for_workspace_into_file_manager, parse_all};
+use nargo_toml::{get_package_manifest, resolve_workspace_with_features, PackageSelection};
+use noirc_abi::input_parser::Format;
+use noirc_driver::{file_manager_with_stdlib, CompileOptions, NOIR_ARTIFACT_VERSION_STRING};
+use noirc_frontend::graph::CrateName;
+
+use super::{feature_selection, fill_target_backend, NargoConfig};
+use crate::backends::Backend;
+use crate::cli::compile_cmd::report_errors;
+use crate::errors::CliError;
+
+/// Executes a circuit on random inputs to search for inputs which cause it to fail
+#[derive(Debug, Clone, Args)]
+pub(crate) struct FuzzCommand {
+    /// The number of random inputs to execute the circuit on
+    #[clap(long, default_value_t = 100)]
+    runs: usize,
+
+    /// The seed used to generate inputs. A random seed is used if none is given
+    #[clap(long)]
+    seed: Option<u64>,
+
+    /// The name of the package to fuzz
+    #[clap(long, conflicts_with = "workspace")]
+    package: Option<CrateName>,
+
+    /// Fuzz all packages in the workspace
+    #[clap(long, conflicts_with = "package")]
+    workspace: bool,
+
+    #[clap(flatten)]
+    compile_options: CompileOptions,
+
+    /// JSON RPC url to solve oracle calls
+    #[clap(long)]
+    oracle_resolver: Option<String>,
+}
+
+pub(crate) fn run(
+    backend: &Backend,
+    mut args: FuzzCommand,
+    config: NargoConfig,
+) -> Result<(), CliError> {
+    fill_target_backend(&mut args.compile_options, backend);
+
+    let toml_path = get_package_manifest(&config.program_dir)?;
+    let default_selection =
+        if args.workspace { PackageSelection::All } else { PackageSelection::DefaultOrAll };
+    let selection = args.package.map_or(default_selection, PackageSelection::Selected);
+    let workspace = resolve_workspace_with_features(
+        &toml_path,
+        selection,
+        &feature_selection(&args.compile_options),
+        Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
+    )?;
+
+    let mut workspace_file_manager = file_manager_with_stdlib(&workspace.root_dir);
+    insert_all_files_for_workspace_into_file_manager(&workspace, &mut workspace_file_manager);
+    let parsed_files = parse_all(&workspace_file_manager);
+
+    let expression_width = args
+        .compile_options
+        .expression_width
+        .unwrap_or_else(|| backend.get_backend_info_or_default());
+    let binary_packages: Vec<_> =
+        workspace.into_iter().filter(|package| package.is_binary()).cloned().collect();
+
+    let seed = args.seed.unwrap_or_else(|| {
+        SystemTime::now().duration_since(UNIX_EPOCH).expect("Time went backwards").as_secs()
+    });
+    let blackbox_solver = Bn254BlackBoxSolver::new();
+
+    let compilation_results = compile_programs(
+        &workspace_file_manager,
+        &parsed_files,
+        &binary_packages,
+        &args.compile_options,
+    );
+    for (package, compilation_result) in binary_packages.iter().zip(compilation_results) {
+        let compiled_program = report_errors(
+            compilation_result,
+            &workspace_file_manager,
+            args.compile_options.deny_warnings,
+            args.compile_options.silence_warnings,
+        )?;
+
+        let compiled_program = nargo::ops::transform_program(compiled_program, expression_width);
+
+        println!("[{}] Fuzzing {} inputs with seed {seed}", package.name, args.runs);
+        match fuzz_program(
+            &blackbox_solver,
+            &compiled_program,
+            args.runs,
+            seed,
+            args.oracle_resolver.as_deref(),
+        ) {
+            FuzzResult::Pass { runs } => {
+                println!("[{}] Circuit executed successfully on {runs} inputs", package.name);
+            }
+            FuzzResult::Fail { run, inputs, error } => {
+                let debug_artifact = DebugArtifact {
+                    debug_symbols: vec![compiled_program.debug.clone()],
+                    file_map: compiled_program.file_map.clone(),
+                    warnings: compiled_program.warnings.clone(),
+                };
+                if let Some(diagnostic) =
+                    try_to_diagnose_runtime_error(&error, &compiled_program.debug)
+                {
+                    diagnostic.report(&debug_artifact, false);
+                }
+
+                let inputs = Format::Toml
+                    .serialize(&inputs, &compiled_program.abi)
+                    .expect("Generated inputs should match the ABI");
+                println!(
+                    "[{}] Circuit failed on input {} of {}:",
+                    package.name,
+                    run + 1,
+                    args.runs
+                );
+                println!("{inputs}");
+
+                return Err(CliError::NargoError(error));
+            }
+        }
+    }
+
+    Ok(())
+}
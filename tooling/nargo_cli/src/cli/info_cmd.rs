@@ -1,14 +1,14 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
+use acvm::acir::circuit::{Circuit, Opcode};
 use acvm::ExpressionWidth;
-use backend_interface::BackendError;
 use clap::Args;
 use iter_extended::vecmap;
 use nargo::{
     artifacts::debug::DebugArtifact, insert_all_files_for_workspace_into_file_manager,
     package::Package, parse_all,
 };
-use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
+use nargo_toml::{get_package_manifest, resolve_workspace_with_features, PackageSelection};
 use noirc_driver::{
     file_manager_with_stdlib, CompileOptions, CompiledContract, CompiledProgram,
     NOIR_ARTIFACT_VERSION_STRING,
@@ -22,13 +22,19 @@ use serde::Serialize;
 use crate::backends::Backend;
 use crate::errors::CliError;
 
-use super::{compile_cmd::compile_workspace, NargoConfig};
+use super::{
+    check_no_acir_calls, compile_cmd::compile_workspace, feature_selection,
+    fill_supported_black_boxes, fill_target_backend, NargoConfig,
+};
 
 /// Provides detailed information on a circuit
 ///
 /// Current information provided:
 /// 1. The number of ACIR opcodes
 /// 2. Counts the final number gates in the circuit used by a backend
+///
+/// The JSON report additionally contains the number of witnesses, the number of opcodes of each
+/// kind and the total size of the bytecode of the unconstrained calls in each circuit.
 #[derive(Debug, Clone, Args)]
 pub(crate) struct InfoCommand {
     /// The name of the package to detail
@@ -40,7 +46,7 @@ pub(crate) struct InfoCommand {
     workspace: bool,
 
     /// Output a JSON formatted report. Changes to this format are not currently considered breaking.
-    #[clap(long, hide = true)]
+    #[clap(long)]
     json: bool,
 
     #[clap(long, hide = true)]
@@ -52,16 +58,17 @@ pub(crate) struct InfoCommand {
 
 pub(crate) fn run(
     backend: &Backend,
-    args: InfoCommand,
+    mut args: InfoCommand,
     config: NargoConfig,
 ) -> Result<(), CliError> {
     let toml_path = get_package_manifest(&config.program_dir)?;
     let default_selection =
         if args.workspace { PackageSelection::All } else { PackageSelection::DefaultOrAll };
     let selection = args.package.map_or(default_selection, PackageSelection::Selected);
-    let workspace = resolve_workspace_from_toml(
+    let workspace = resolve_workspace_with_features(
         &toml_path,
         selection,
+        &feature_selection(&args.compile_options),
         Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
     )?;
 
@@ -73,6 +80,8 @@ pub(crate) fn run(
         .compile_options
         .expression_width
         .unwrap_or_else(|| backend.get_backend_info_or_default());
+    fill_supported_black_boxes(&mut args.compile_options, backend);
+    fill_target_backend(&mut args.compile_options, backend);
     let (compiled_programs, compiled_contracts) = compile_workspace(
         &workspace_file_manager,
         &parsed_files,
@@ -217,6 +226,8 @@ struct ProgramInfo {
     expression_width: ExpressionWidth,
     acir_opcodes: usize,
     circuit_size: u32,
+    #[serde(flatten)]
+    stats: CircuitStats,
 }
 
 impl From<ProgramInfo> for Row {
@@ -243,6 +254,43 @@ struct FunctionInfo {
     name: String,
     acir_opcodes: usize,
     circuit_size: u32,
+    #[serde(flatten)]
+    stats: CircuitStats,
+}
+
+/// Statistics on a circuit which are only included in the JSON report.
+#[derive(Debug, Default, Serialize)]
+struct CircuitStats {
+    witnesses: u32,
+    /// The total number of Brillig opcodes in the bytecode of the circuit's Brillig calls. This is
+    /// the static size of the bytecode rather than the number of opcodes executed.
+    brillig_bytecode_size: usize,
+    /// The number of ACIR opcodes of each kind, with black box calls counted per function.
+    opcode_counts: BTreeMap<String, usize>,
+}
+
+impl From<&Circuit> for CircuitStats {
+    fn from(circuit: &Circuit) -> Self {
+        let mut stats = CircuitStats { witnesses: circuit.num_vars(), ..Default::default() };
+
+        for opcode in &circuit.opcodes {
+            let kind = match opcode {
+                Opcode::AssertZero(_) => "assert_zero".to_owned(),
+                Opcode::BlackBoxFuncCall(call) => format!("black_box::{}", call.name()),
+                Opcode::Directive(_) => "directive".to_owned(),
+                Opcode::Brillig(brillig) => {
+                    stats.brillig_bytecode_size += brillig.bytecode.len();
+                    "brillig".to_owned()
+                }
+                Opcode::MemoryOp { .. } => "memory_op".to_owned(),
+                Opcode::MemoryInit { .. } => "memory_init".to_owned(),
+                Opcode::Call { .. } => "call".to_owned(),
+            };
+            *stats.opcode_counts.entry(kind).or_default() += 1;
+        }
+
+        stats
+    }
 }
 
 impl From<ContractInfo> for Vec<Row> {
@@ -265,11 +313,13 @@ fn count_opcodes_and_gates_in_program(
     package: &Package,
     expression_width: ExpressionWidth,
 ) -> Result<ProgramInfo, CliError> {
+    check_no_acir_calls(&package.name.to_string(), &compiled_program.functions)?;
     Ok(ProgramInfo {
         name: package.name.to_string(),
         expression_width,
         acir_opcodes: compiled_program.circuit.opcodes.len(),
         circuit_size: backend.get_exact_circuit_size(&compiled_program.circuit)?,
+        stats: CircuitStats::from(&compiled_program.circuit),
     })
 }
 
@@ -281,14 +331,54 @@ fn count_opcodes_and_gates_in_contract(
     let functions = contract
         .functions
         .into_par_iter()
-        .map(|function| -> Result<_, BackendError> {
+        .map(|function| -> Result<_, CliError> {
+            let name = format!("{}::{}", contract.name, function.name);
+            check_no_acir_calls(&name, &function.functions)?;
             Ok(FunctionInfo {
                 name: function.name,
                 acir_opcodes: function.bytecode.opcodes.len(),
                 circuit_size: backend.get_exact_circuit_size(&function.bytecode)?,
+                stats: CircuitStats::from(&function.bytecode),
             })
         })
         .collect::<Result<_, _>>()?;
 
     Ok(ContractInfo { name: contract.name, expression_width, functions })
 }
+
+#[cfg(test)]
+mod tests {
+    use acvm::acir::{
+        brillig::Opcode as BrilligOpcode,
+        circuit::{brillig::Brillig, Circuit, Opcode},
+        native_types::{Expression, Witness},
+    };
+
+    use super::CircuitStats;
+
+    #[test]
+    fn counts_brillig_bytecode_of_each_call() {
+        let brillig = Brillig {
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            bytecode: vec![BrilligOpcode::Trap, BrilligOpcode::Stop],
+            predicate: None,
+        };
+        let circuit = Circuit {
+            current_witness_index: 2,
+            opcodes: vec![
+                Opcode::Brillig(brillig.clone()),
+                Opcode::AssertZero(Expression::from(Witness(1))),
+                Opcode::Brillig(brillig),
+            ],
+            ..Circuit::default()
+        };
+
+        let stats = CircuitStats::from(&circuit);
+
+        assert_eq!(stats.witnesses, 3);
+        assert_eq!(stats.brillig_bytecode_size, 4);
+        assert_eq!(stats.opcode_counts["brillig"], 2);
+        assert_eq!(stats.opcode_counts["assert_zero"], 1);
+    }
+}
@@ -1,17 +1,24 @@
+use acvm::{acir::circuit::Circuit, ExpressionWidth};
 use clap::{Args, Parser, Subcommand};
 use const_format::formatcp;
-use nargo_toml::find_package_root;
-use noirc_driver::NOIR_ARTIFACT_VERSION_STRING;
+use fm::FileManager;
+use nargo::{ops::compile_programs, package::Package};
+use nargo_toml::{find_package_root, FeatureSelection};
+use noirc_driver::{CompileOptions, CompiledProgram, NOIR_ARTIFACT_VERSION_STRING};
+use noirc_frontend::hir::ParsedFiles;
 use std::path::PathBuf;
 
 use color_eyre::eyre;
 
-use crate::backends::get_active_backend;
+use crate::backends::{get_active_backend, Backend};
+use crate::errors::CliError;
 
 mod fs;
 
+mod add_cmd;
 mod backend_cmd;
 mod check_cmd;
+mod codegen_cmd;
 mod codegen_verifier_cmd;
 mod compile_cmd;
 mod dap_cmd;
@@ -19,13 +26,19 @@ mod debug_cmd;
 mod execute_cmd;
 mod export_cmd;
 mod fmt_cmd;
+mod fuzz_cmd;
 mod info_cmd;
 mod init_cmd;
 mod lsp_cmd;
+mod mutate_cmd;
 mod new_cmd;
+mod profile_cmd;
 mod prove_cmd;
+mod publish_cmd;
 mod test_cmd;
+mod update_cmd;
 mod verify_cmd;
+mod verify_constraints_cmd;
 
 const GIT_HASH: &str = env!("GIT_COMMIT");
 const IS_DIRTY: &str = env!("GIT_DIRTY");
@@ -63,20 +76,28 @@ enum NargoCommand {
     Backend(backend_cmd::BackendCommand),
     Check(check_cmd::CheckCommand),
     Fmt(fmt_cmd::FormatCommand),
+    Codegen(codegen_cmd::CodegenCommand),
     CodegenVerifier(codegen_verifier_cmd::CodegenVerifierCommand),
     #[command(alias = "build")]
     Compile(compile_cmd::CompileCommand),
     New(new_cmd::NewCommand),
     Init(init_cmd::InitCommand),
     Execute(execute_cmd::ExecuteCommand),
+    Fuzz(fuzz_cmd::FuzzCommand),
     #[command(hide = true)] // Hidden while the feature is being built out
     Export(export_cmd::ExportCommand),
     #[command(hide = true)] // Hidden while the feature is being built out
     Debug(debug_cmd::DebugCommand),
+    Profile(profile_cmd::ProfileCommand),
     Prove(prove_cmd::ProveCommand),
     Verify(verify_cmd::VerifyCommand),
+    VerifyConstraints(verify_constraints_cmd::VerifyConstraintsCommand),
     Test(test_cmd::TestCommand),
+    Mutate(mutate_cmd::MutateCommand),
     Info(info_cmd::InfoCommand),
+    Add(add_cmd::AddCommand),
+    Update(update_cmd::UpdateCommand),
+    Publish(publish_cmd::PublishCommand),
     Lsp(lsp_cmd::LspCommand),
     #[command(hide = true)]
     Dap(dap_cmd::DapCommand),
@@ -112,17 +133,89 @@ pub(crate) fn start_cli() -> eyre::Result<()> {
         NargoCommand::Compile(args) => compile_cmd::run(&backend, args, config),
         NargoCommand::Debug(args) => debug_cmd::run(&backend, args, config),
         NargoCommand::Execute(args) => execute_cmd::run(&backend, args, config),
+        NargoCommand::Fuzz(args) => fuzz_cmd::run(&backend, args, config),
         NargoCommand::Export(args) => export_cmd::run(&backend, args, config),
+        NargoCommand::Profile(args) => profile_cmd::run(&backend, args, config),
         NargoCommand::Prove(args) => prove_cmd::run(&backend, args, config),
         NargoCommand::Verify(args) => verify_cmd::run(&backend, args, config),
+        NargoCommand::VerifyConstraints(args) => {
+            verify_constraints_cmd::run(&backend, args, config)
+        }
         NargoCommand::Test(args) => test_cmd::run(&backend, args, config),
+        NargoCommand::Mutate(args) => mutate_cmd::run(&backend, args, config),
         NargoCommand::Info(args) => info_cmd::run(&backend, args, config),
+        NargoCommand::Codegen(args) => codegen_cmd::run(args, config),
         NargoCommand::CodegenVerifier(args) => codegen_verifier_cmd::run(&backend, args, config),
         NargoCommand::Backend(args) => backend_cmd::run(args),
         NargoCommand::Lsp(args) => lsp_cmd::run(&backend, args, config),
         NargoCommand::Dap(args) => dap_cmd::run(&backend, args, config),
         NargoCommand::Fmt(args) => fmt_cmd::run(args, config),
+        NargoCommand::Add(args) => add_cmd::run(args, config),
+        NargoCommand::Update(args) => update_cmd::run(args, config),
+        NargoCommand::Publish(args) => publish_cmd::run(args, config),
     }?;
 
     Ok(())
 }
+
+/// Returns the features to enable in the selected packages given on the command line.
+pub(crate) fn feature_selection(compile_options: &CompileOptions) -> FeatureSelection {
+    FeatureSelection {
+        features: compile_options.features.clone(),
+        default_features: !compile_options.no_default_features,
+    }
+}
+
+/// Sets the black box functions supported by `backend` unless they were given on the command line,
+/// so that calls to any others are compiled to their fallback implementations.
+pub(crate) fn fill_supported_black_boxes(compile_options: &mut CompileOptions, backend: &Backend) {
+    if compile_options.supported_black_boxes.is_none() {
+        compile_options.supported_black_boxes = backend.get_supported_black_boxes().ok().flatten();
+    }
+}
+
+/// Sets the backend the program is compiled for to `backend` unless it was given on the command
+/// line, so that items marked with `#[cfg(backend = "...")]` match the backend in use.
+pub(crate) fn fill_target_backend(compile_options: &mut CompileOptions, backend: &Backend) {
+    if compile_options.target_backend.is_none() {
+        compile_options.target_backend = Some(backend.name().to_string());
+    }
+}
+
+/// Backends are only given the circuit of `main`, so the program `name` can't be proven, verified
+/// or measured by them if its circuit runs the ACIR `functions` through call opcodes.
+pub(crate) fn check_no_acir_calls(name: &str, functions: &[Circuit]) -> Result<(), CliError> {
+    if functions.is_empty() {
+        Ok(())
+    } else {
+        Err(CliError::UnsupportedAcirCalls(name.to_owned()))
+    }
+}
+
+/// Compiles all of the packages in parallel before handling each of them in order with
+/// `handle_program`, once its program is transformed for `expression_width`.
+///
+/// Errors are reported for each package when it is reached, so the packages before the first one
+/// which fails to compile are still handled.
+pub(crate) fn compile_and_handle_programs(
+    file_manager: &FileManager,
+    parsed_files: &ParsedFiles,
+    packages: &[Package],
+    compile_options: &CompileOptions,
+    expression_width: ExpressionWidth,
+    mut handle_program: impl FnMut(&Package, CompiledProgram) -> Result<(), CliError>,
+) -> Result<(), CliError> {
+    let compilation_results =
+        compile_programs(file_manager, parsed_files, packages, compile_options);
+    for (package, compilation_result) in packages.iter().zip(compilation_results) {
+        let program = compile_cmd::report_errors(
+            compilation_result,
+            file_manager,
+            compile_options.deny_warnings,
+            compile_options.silence_warnings,
+        )?;
+        let program = nargo::ops::transform_program(program, expression_width);
+        handle_program(package, program)?;
+    }
+    Ok(())
+}
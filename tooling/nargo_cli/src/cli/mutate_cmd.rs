@@ -0,0 +1,183 @@
+use std::collections::BTreeMap;
+
+use acvm::BlackBoxFunctionSolver;
+use bn254_blackbox_solver::Bn254BlackBoxSolver;
+use clap::Args;
+use fm::{FileId, FileManager};
+use nargo::{
+    insert_all_files_for_workspace_into_file_manager,
+    ops::{run_mutation_test, MutantOutcome, MutationKind, MutationTestStatus},
+    package::Package,
+    parse_all, prepare_package,
+};
+use nargo_toml::{get_package_manifest, resolve_workspace_with_features, PackageSelection};
+use noirc_driver::{file_manager_with_stdlib, CompileOptions, NOIR_ARTIFACT_VERSION_STRING};
+use noirc_errors::{CustomDiagnostic, Span};
+use noirc_frontend::{
+    graph::CrateName,
+    hir::{FunctionNameMatch, ParsedFiles},
+};
+
+use crate::{backends::Backend, cli::check_cmd::check_crate_and_report_errors, errors::CliError};
+
+use super::{feature_selection, fill_target_backend, NargoConfig};
+
+/// Relaxes each constraint of the tests' circuits in turn and reports those which no test detects
+#[derive(Debug, Clone, Args)]
+pub(crate) struct MutateCommand {
+    /// If given, only tests with names containing this string will be used
+    test_name: Option<String>,
+
+    /// The name of the package to mutate
+    #[clap(long, conflicts_with = "workspace")]
+    package: Option<CrateName>,
+
+    /// Mutate all packages in the workspace
+    #[clap(long, conflicts_with = "package")]
+    workspace: bool,
+
+    #[clap(flatten)]
+    compile_options: CompileOptions,
+
+    /// JSON RPC url to solve oracle calls
+    #[clap(long)]
+    oracle_resolver: Option<String>,
+}
+
+pub(crate) fn run(
+    backend: &Backend,
+    mut args: MutateCommand,
+    config: NargoConfig,
+) -> Result<(), CliError> {
+    fill_target_backend(&mut args.compile_options, backend);
+
+    let toml_path = get_package_manifest(&config.program_dir)?;
+    let default_selection =
+        if args.workspace { PackageSelection::All } else { PackageSelection::DefaultOrAll };
+    let selection = args.package.map_or(default_selection, PackageSelection::Selected);
+    let workspace = resolve_workspace_with_features(
+        &toml_path,
+        selection,
+        &feature_selection(&args.compile_options),
+        Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
+    )?;
+
+    let mut workspace_file_manager = file_manager_with_stdlib(&workspace.root_dir);
+    insert_all_files_for_workspace_into_file_manager(&workspace, &mut workspace_file_manager);
+    let parsed_files = parse_all(&workspace_file_manager);
+
+    let pattern = match &args.test_name {
+        Some(name) => FunctionNameMatch::Contains(name),
+        None => FunctionNameMatch::Anything,
+    };
+
+    let blackbox_solver = Bn254BlackBoxSolver::new();
+
+    let mut surviving_mutants = 0;
+    for package in &workspace {
+        surviving_mutants += mutate_package(
+            &workspace_file_manager,
+            &parsed_files,
+            &blackbox_solver,
+            package,
+            pattern,
+            args.oracle_resolver.as_deref(),
+            &args.compile_options,
+        )?;
+    }
+
+    if surviving_mutants == 0 {
+        Ok(())
+    } else {
+        let plural = if surviving_mutants == 1 { "" } else { "s" };
+        Err(CliError::Generic(format!("{surviving_mutants} mutant{plural} survived")))
+    }
+}
+
+/// Runs the matching tests of `package` against the mutants of their circuits,
+/// returning the number of mutants which survived.
+fn mutate_package<S: BlackBoxFunctionSolver>(
+    file_manager: &FileManager,
+    parsed_files: &ParsedFiles,
+    blackbox_solver: &S,
+    package: &Package,
+    fn_name: FunctionNameMatch,
+    foreign_call_resolver_url: Option<&str>,
+    compile_options: &CompileOptions,
+) -> Result<usize, CliError> {
+    let (mut context, crate_id) = prepare_package(file_manager, parsed_files, package);
+    check_crate_and_report_errors(&mut context, crate_id, compile_options)?;
+
+    // A constraint may be included in the circuits of several tests, in which case its mutant
+    // is killed if any of them kills it.
+    let mut mutants: BTreeMap<(FileId, Span, MutationKind), MutantOutcome> = BTreeMap::new();
+
+    let test_functions = context.get_all_test_functions_in_crate_matching(&crate_id, fn_name);
+    for (test_name, test_function) in test_functions {
+        let status = run_mutation_test(
+            blackbox_solver,
+            &context,
+            test_function,
+            foreign_call_resolver_url,
+            compile_options,
+        );
+        match status {
+            MutationTestStatus::Ran(results) => {
+                println!("[{}] Ran {test_name} against {} mutants", package.name, results.len());
+                // Constraints without a source location are generated by the compiler itself
+                // so can't be attributed to the user's code.
+                for result in results {
+                    if let Some(location) = result.location {
+                        let key = (location.file, location.span, result.mutation.kind);
+                        mutants
+                            .entry(key)
+                            .and_modify(|outcome| *outcome = outcome.combine(result.outcome))
+                            .or_insert(result.outcome);
+                    }
+                }
+            }
+            MutationTestStatus::Skipped(_) => {
+                println!("[{}] Skipping {test_name} as it does not pass", package.name);
+            }
+            MutationTestStatus::CompileError(err) => {
+                noirc_errors::reporter::report_all(
+                    context.file_manager.as_file_map(),
+                    &[err],
+                    compile_options.deny_warnings,
+                    compile_options.silence_warnings,
+                );
+            }
+        }
+    }
+
+    let survivors: Vec<_> = mutants
+        .iter()
+        .filter(|(_, outcome)| **outcome == MutantOutcome::Survived)
+        .map(|((file_id, span, kind), _)| {
+            CustomDiagnostic::simple_warning(
+                format!("Mutant survived: no test fails after {kind}"),
+                "This constraint may not be covered by any test".to_owned(),
+                *span,
+            )
+            .in_file(*file_id)
+        })
+        .collect();
+    noirc_errors::reporter::report_all(
+        context.file_manager.as_file_map(),
+        &survivors,
+        false,
+        false,
+    );
+
+    // Mutants which could not be executed by any test neither count as killed nor as survived
+    let total = mutants.len();
+    let survived = survivors.len();
+    let invalid = mutants.values().filter(|outcome| **outcome == MutantOutcome::Invalid).count();
+    println!(
+        "[{}] {total} mutants, {} killed, {survived} survived, {invalid} invalid",
+        package.name,
+        total - survived - invalid
+    );
+
+    Ok(survived)
+}
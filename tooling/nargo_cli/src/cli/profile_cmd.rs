@@ -0,0 +1,308 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use clap::Args;
+use nargo::artifacts::debug::DebugArtifact;
+use nargo::ops::{compile_programs, profile_circuit, BrilligExecutionProfile, CircuitProfile};
+use nargo::package::Package;
+use nargo::{insert_all_files_for_workspace_into_file_manager, parse_all};
+use nargo_toml::{get_package_manifest, resolve_workspace_with_features, PackageSelection};
+use noirc_driver::{file_manager_with_stdlib, CompileOptions, NOIR_ARTIFACT_VERSION_STRING};
+use noirc_frontend::graph::CrateName;
+use prettytable::{row, table};
+
+use super::fs::write_to_file;
+use super::{feature_selection, fill_supported_black_boxes, fill_target_backend, NargoConfig};
+use crate::backends::Backend;
+use crate::cli::compile_cmd::report_errors;
+use crate::errors::CliError;
+
+/// The number of source lines shown when printing a profile.
+const TOP_LINES: usize = 20;
+
+const FLAMEGRAPH_WIDTH: f64 = 1200.0;
+const FLAMEGRAPH_FRAME_HEIGHT: usize = 16;
+
+/// Attributes the ACIR opcodes of a circuit to the lines of source code which generated them
+///
+/// Without `--output` the lines contributing the most opcodes are printed. With `--output` the
+/// full profile is written to the given file, as JSON if its extension is `.json` and as a
+/// flamegraph SVG otherwise.
+#[derive(Debug, Clone, Args)]
+pub(crate) struct ProfileCommand {
+    /// Write the profile to this file, e.g. `flamegraph.svg` or `profile.json`
+    #[clap(long, short)]
+    output: Option<PathBuf>,
+
+    /// The name of the package to profile
+    #[clap(long, conflicts_with = "workspace")]
+    package: Option<CrateName>,
+
+    /// Profile all packages in the workspace
+    #[clap(long, conflicts_with = "package")]
+    workspace: bool,
+
+    #[clap(flatten)]
+    compile_options: CompileOptions,
+}
+
+pub(crate) fn run(
+    backend: &Backend,
+    mut args: ProfileCommand,
+    config: NargoConfig,
+) -> Result<(), CliError> {
+    let toml_path = get_package_manifest(&config.program_dir)?;
+    let default_selection =
+        if args.workspace { PackageSelection::All } else { PackageSelection::DefaultOrAll };
+    let selection = args.package.map_or(default_selection, PackageSelection::Selected);
+    let workspace = resolve_workspace_with_features(
+        &toml_path,
+        selection,
+        &feature_selection(&args.compile_options),
+        Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
+    )?;
+
+    let mut workspace_file_manager = file_manager_with_stdlib(&workspace.root_dir);
+    insert_all_files_for_workspace_into_file_manager(&workspace, &mut workspace_file_manager);
+    let parsed_files = parse_all(&workspace_file_manager);
+
+    let expression_width = args
+        .compile_options
+        .expression_width
+        .unwrap_or_else(|| backend.get_backend_info_or_default());
+    fill_supported_black_boxes(&mut args.compile_options, backend);
+    fill_target_backend(&mut args.compile_options, backend);
+    let binary_packages: Vec<_> =
+        workspace.into_iter().filter(|package| package.is_binary()).cloned().collect();
+
+    let compilation_results = compile_programs(
+        &workspace_file_manager,
+        &parsed_files,
+        &binary_packages,
+        &args.compile_options,
+    );
+    for (package, compilation_result) in binary_packages.iter().zip(compilation_results) {
+        let compiled_program = report_errors(
+            compilation_result,
+            &workspace_file_manager,
+            args.compile_options.deny_warnings,
+            args.compile_options.silence_warnings,
+        )?;
+
+        let compiled_program = nargo::ops::transform_program(compiled_program, expression_width);
+        let debug_artifact = DebugArtifact {
+            debug_symbols: vec![compiled_program.debug.clone()],
+            file_map: compiled_program.file_map.clone(),
+            warnings: compiled_program.warnings.clone(),
+        };
+        let profile =
+            profile_circuit(&compiled_program.circuit, &compiled_program.debug, &debug_artifact);
+
+        match &args.output {
+            Some(output) => {
+                // Each package is written to its own file when profiling several packages.
+                let output = if binary_packages.len() > 1 {
+                    package_output_path(output, package)
+                } else {
+                    output.clone()
+                };
+                let contents = if output.extension().map_or(false, |ext| ext == "json") {
+                    serde_json::to_string_pretty(&profile).expect("Profile should serialize")
+                } else {
+                    render_flamegraph(&package.name.to_string(), &profile)
+                };
+                let path = write_to_file(contents.as_bytes(), &output);
+                println!("[{}] Profile written to {path}", package.name);
+            }
+            None => print_profile(package, &profile),
+        }
+    }
+
+    Ok(())
+}
+
+/// Inserts the name of `package` before the extension of `output`.
+fn package_output_path(output: &Path, package: &Package) -> PathBuf {
+    let stem = output.file_stem().map(|stem| stem.to_string_lossy()).unwrap_or_default();
+    let mut file_name = format!("{stem}_{}", package.name);
+    if let Some(extension) = output.extension() {
+        file_name = format!("{file_name}.{}", extension.to_string_lossy());
+    }
+    output.with_file_name(file_name)
+}
+
+fn print_profile(package: &Package, profile: &CircuitProfile) {
+    println!("[{}] {} ACIR opcodes", package.name, profile.total_opcodes);
+
+    let mut table = table!([
+        Fm->"Line",
+        Fm->"Self Opcodes",
+        Fm->"Total Opcodes",
+        Fm->"% of Circuit",
+        Fm->"Source"
+    ]);
+    for line in profile.lines.iter().take(TOP_LINES) {
+        let percentage = 100.0 * line.total_opcodes as f64 / profile.total_opcodes.max(1) as f64;
+        table.add_row(row![
+            Fm->format!("{}", line.source_line),
+            Fc->format!("{}", line.self_opcodes),
+            Fc->format!("{}", line.total_opcodes),
+            Fc->format!("{percentage:.1}%"),
+            line.source,
+        ]);
+    }
+    table.printstd();
+}
+
+/// Prints the unconstrained call sites and lines which executed the most Brillig opcodes.
+pub(super) fn print_brillig_profile(package: &Package, profile: &BrilligExecutionProfile) {
+    println!("[{}] {} Brillig opcodes executed", package.name, profile.executed_opcodes);
+    if profile.executed_opcodes == 0 {
+        return;
+    }
+
+    let mut call_site_table = table!([
+        Fm->"Call Site",
+        Fm->"Calls",
+        Fm->"Executed Opcodes",
+        Fm->"% of Execution",
+        Fm->"Source"
+    ]);
+    for call_site in profile.call_sites.iter().take(TOP_LINES) {
+        let percentage =
+            100.0 * call_site.executed_opcodes as f64 / profile.executed_opcodes as f64;
+        call_site_table.add_row(row![
+            Fm->format!("{}", call_site.call_site),
+            Fc->format!("{}", call_site.calls),
+            Fc->format!("{}", call_site.executed_opcodes),
+            Fc->format!("{percentage:.1}%"),
+            call_site.source,
+        ]);
+    }
+    call_site_table.printstd();
+
+    let mut line_table = table!([
+        Fm->"Line",
+        Fm->"Self Opcodes",
+        Fm->"Total Opcodes",
+        Fm->"% of Execution",
+        Fm->"Source"
+    ]);
+    for line in profile.lines.iter().take(TOP_LINES) {
+        let percentage = 100.0 * line.total_opcodes as f64 / profile.executed_opcodes as f64;
+        line_table.add_row(row![
+            Fm->format!("{}", line.source_line),
+            Fc->format!("{}", line.self_opcodes),
+            Fc->format!("{}", line.total_opcodes),
+            Fc->format!("{percentage:.1}%"),
+            line.source,
+        ]);
+    }
+    line_table.printstd();
+}
+
+/// A frame of the flamegraph along with the frames called from it.
+#[derive(Default)]
+struct FlameNode {
+    opcodes: usize,
+    children: BTreeMap<String, FlameNode>,
+}
+
+impl FlameNode {
+    fn depth(&self) -> usize {
+        1 + self.children.values().map(FlameNode::depth).max().unwrap_or(0)
+    }
+}
+
+/// Renders the call stacks of `profile` as a flamegraph, with the root frame at the bottom.
+fn render_flamegraph(title: &str, profile: &CircuitProfile) -> String {
+    let mut root = FlameNode::default();
+    for stack in &profile.stacks {
+        root.opcodes += stack.opcodes;
+        let mut node = &mut root;
+        for frame in &stack.frames {
+            node = node.children.entry(frame.clone()).or_default();
+            node.opcodes += stack.opcodes;
+        }
+    }
+
+    let height = (root.depth() + 2) * FLAMEGRAPH_FRAME_HEIGHT;
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        concat!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" "#,
+            r#"font-family="monospace" font-size="12">"#
+        ),
+        FLAMEGRAPH_WIDTH, height
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        r#"<text x="{}" y="{FLAMEGRAPH_FRAME_HEIGHT}" text-anchor="middle">{}</text>"#,
+        FLAMEGRAPH_WIDTH / 2.0,
+        escape_xml(title)
+    )
+    .unwrap();
+
+    let scale = FLAMEGRAPH_WIDTH / root.opcodes.max(1) as f64;
+    let root_y = height - FLAMEGRAPH_FRAME_HEIGHT;
+    render_frame(&mut svg, "all", &root, 0.0, root_y, scale);
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn render_frame(svg: &mut String, name: &str, node: &FlameNode, x: f64, y: usize, scale: f64) {
+    let width = node.opcodes as f64 * scale;
+    let (red, green, blue) = frame_color(name);
+    let label = format!("{name} ({} opcodes)", node.opcodes);
+    writeln!(
+        svg,
+        concat!(
+            r#"<g><title>{}</title><rect x="{:.2}" y="{}" width="{:.2}" height="{}" "#,
+            r#"fill="rgb({},{},{})" stroke="white"/>"#
+        ),
+        escape_xml(&label),
+        x,
+        y,
+        width,
+        FLAMEGRAPH_FRAME_HEIGHT,
+        red,
+        green,
+        blue
+    )
+    .unwrap();
+    // Only label frames wide enough to fit a few characters.
+    let visible_chars = (width / 7.5) as usize;
+    if visible_chars > 3 {
+        let text: String = if name.chars().count() > visible_chars {
+            name.chars().take(visible_chars - 2).chain("..".chars()).collect()
+        } else {
+            name.to_owned()
+        };
+        writeln!(svg, r#"<text x="{:.2}" y="{}">{}</text>"#, x + 3.0, y + 12, escape_xml(&text))
+            .unwrap();
+    }
+    svg.push_str("</g>\n");
+
+    let mut child_x = x;
+    for (child_name, child) in &node.children {
+        if y >= FLAMEGRAPH_FRAME_HEIGHT {
+            render_frame(svg, child_name, child, child_x, y - FLAMEGRAPH_FRAME_HEIGHT, scale);
+        }
+        child_x += child.opcodes as f64 * scale;
+    }
+}
+
+/// Picks a warm colour for a frame, derived from its name so that it is stable across runs.
+fn frame_color(name: &str) -> (u8, u8, u8) {
+    let hash =
+        name.bytes().fold(0u32, |hash, byte| hash.wrapping_mul(31).wrapping_add(byte.into()));
+    (205 + (hash % 50) as u8, 80 + (hash / 50 % 120) as u8, 40 + (hash / 6000 % 40) as u8)
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
@@ -1,39 +1,62 @@
 use clap::Args;
 use nargo::constants::{PROVER_INPUT_FILE, VERIFIER_INPUT_FILE};
-use nargo::ops::compile_program;
+use nargo::ops::LogLevel;
 use nargo::package::Package;
 use nargo::workspace::Workspace;
 use nargo::{insert_all_files_for_workspace_into_file_manager, parse_all};
-use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
+use nargo_toml::{get_package_manifest, resolve_workspace_with_features, PackageSelection};
 use noirc_abi::input_parser::Format;
 use noirc_driver::{
     file_manager_with_stdlib, CompileOptions, CompiledProgram, NOIR_ARTIFACT_VERSION_STRING,
 };
 use noirc_frontend::graph::CrateName;
 
-use super::compile_cmd::report_errors;
 use super::fs::{
-    inputs::{read_inputs_from_file, write_inputs_to_file},
-    proof::save_proof_to_dir,
+    inputs::{read_inputs_from_file, write_inputs_to_file, InputFormat},
+    proof::{
+        field_to_hex, save_proof_to_dir, save_recursion_artifacts_to_dir, RecursionArtifacts,
+        RecursionKey, RecursionProof,
+    },
+    witness::{read_witness_from_dir, WitnessFormat},
+};
+use super::{
+    check_no_acir_calls, compile_and_handle_programs, feature_selection,
+    fill_supported_black_boxes, fill_target_backend, NargoConfig,
 };
-use super::NargoConfig;
 use crate::{backends::Backend, cli::execute_cmd::execute_program, errors::CliError};
 
 /// Create proof for this program. The proof is returned as a hex encoded string.
 #[derive(Debug, Clone, Args)]
 pub(crate) struct ProveCommand {
-    /// The name of the toml file which contains the inputs for the prover
+    /// The name of the file which contains the inputs for the prover
     #[clap(long, short, default_value = PROVER_INPUT_FILE)]
     prover_name: String,
 
+    /// The format of the file which contains the inputs for the prover
+    #[clap(long, value_enum, default_value_t = InputFormat::Toml)]
+    input_format: InputFormat,
+
     /// The name of the toml file which contains the inputs for the verifier
     #[clap(long, short, default_value = VERIFIER_INPUT_FILE)]
     verifier_name: String,
 
+    /// Read the witness from this file in the target directory instead of executing the program
+    #[clap(long)]
+    witness_name: Option<String>,
+
+    /// The format of the witness read with `--witness-name`
+    #[clap(long, value_enum, default_value_t = WitnessFormat::Binary)]
+    witness_format: WitnessFormat,
+
     /// Verify proof after proving
     #[arg(long)]
     verify: bool,
 
+    /// Create a proof which can be verified inside of another circuit, and write its verification
+    /// key and proof as field elements for the inputs of that circuit
+    #[arg(long)]
+    recursive: bool,
+
     /// The name of the package to prove
     #[clap(long, conflicts_with = "workspace")]
     package: Option<CrateName>,
@@ -52,16 +75,17 @@ pub(crate) struct ProveCommand {
 
 pub(crate) fn run(
     backend: &Backend,
-    args: ProveCommand,
+    mut args: ProveCommand,
     config: NargoConfig,
 ) -> Result<(), CliError> {
     let toml_path = get_package_manifest(&config.program_dir)?;
     let default_selection =
         if args.workspace { PackageSelection::All } else { PackageSelection::DefaultOrAll };
     let selection = args.package.map_or(default_selection, PackageSelection::Selected);
-    let workspace = resolve_workspace_from_toml(
+    let workspace = resolve_workspace_with_features(
         &toml_path,
         selection,
+        &feature_selection(&args.compile_options),
         Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
     )?;
 
@@ -73,38 +97,33 @@ pub(crate) fn run(
         .compile_options
         .expression_width
         .unwrap_or_else(|| backend.get_backend_info_or_default());
-    let binary_packages = workspace.into_iter().filter(|package| package.is_binary());
-    for package in binary_packages {
-        let compilation_result = compile_program(
-            &workspace_file_manager,
-            &parsed_files,
-            package,
-            &args.compile_options,
-            None,
-        );
-
-        let compiled_program = report_errors(
-            compilation_result,
-            &workspace_file_manager,
-            args.compile_options.deny_warnings,
-            args.compile_options.silence_warnings,
-        )?;
-
-        let compiled_program = nargo::ops::transform_program(compiled_program, expression_width);
-
-        prove_package(
-            backend,
-            &workspace,
-            package,
-            compiled_program,
-            &args.prover_name,
-            &args.verifier_name,
-            args.verify,
-            args.oracle_resolver.as_deref(),
-        )?;
-    }
-
-    Ok(())
+    fill_supported_black_boxes(&mut args.compile_options, backend);
+    fill_target_backend(&mut args.compile_options, backend);
+    let binary_packages: Vec<_> =
+        workspace.into_iter().filter(|package| package.is_binary()).cloned().collect();
+
+    compile_and_handle_programs(
+        &workspace_file_manager,
+        &parsed_files,
+        &binary_packages,
+        &args.compile_options,
+        expression_width,
+        |package, compiled_program| {
+            prove_package(
+                backend,
+                &workspace,
+                package,
+                compiled_program,
+                &args.prover_name,
+                args.input_format,
+                args.witness_name.as_deref().map(|name| (name, args.witness_format)),
+                &args.verifier_name,
+                args.verify,
+                args.recursive,
+                args.oracle_resolver.as_deref(),
+            )
+        },
+    )
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -114,16 +133,36 @@ pub(crate) fn prove_package(
     package: &Package,
     compiled_program: CompiledProgram,
     prover_name: &str,
+    input_format: InputFormat,
+    witness: Option<(&str, WitnessFormat)>,
     verifier_name: &str,
     check_proof: bool,
+    recursive: bool,
     foreign_call_resolver_url: Option<&str>,
 ) -> Result<(), CliError> {
-    // Parse the initial witness values from Prover.toml
-    let (inputs_map, _) =
-        read_inputs_from_file(&package.root_dir, prover_name, Format::Toml, &compiled_program.abi)?;
+    check_no_acir_calls(&package.name.to_string(), &compiled_program.functions)?;
 
-    let solved_witness =
-        execute_program(&compiled_program, &inputs_map, foreign_call_resolver_url)?;
+    let solved_witness = match witness {
+        Some((witness_name, witness_format)) => {
+            read_witness_from_dir(witness_name, workspace.target_directory_path(), witness_format)?
+        }
+        None => {
+            // Parse the initial witness values from the prover's input file
+            let (inputs_map, _) = read_inputs_from_file(
+                &package.root_dir,
+                prover_name,
+                input_format.into(),
+                &compiled_program.abi,
+            )?;
+
+            execute_program(
+                &compiled_program,
+                &inputs_map,
+                foreign_call_resolver_url,
+                LogLevel::default(),
+            )?
+        }
+    };
 
     // Write public inputs into Verifier.toml
     let public_abi = compiled_program.abi.public_abi();
@@ -138,19 +177,52 @@ pub(crate) fn prove_package(
         Format::Toml,
     )?;
 
-    let proof = backend.prove(&compiled_program.circuit, solved_witness, false)?;
+    let proof = backend.prove(&compiled_program.circuit, solved_witness, recursive)?;
+    let public_inputs = public_abi.encode(&public_inputs, return_value)?;
 
     if check_proof {
-        let public_inputs = public_abi.encode(&public_inputs, return_value)?;
         let valid_proof =
-            backend.verify(&proof, public_inputs, &compiled_program.circuit, false)?;
+            backend.verify(&proof, public_inputs.clone(), &compiled_program.circuit, recursive)?;
 
         if !valid_proof {
             return Err(CliError::InvalidProof("".into()));
         }
     }
 
-    save_proof_to_dir(&proof, &String::from(&package.name), workspace.proofs_directory_path())?;
+    let proof_name = String::from(&package.name);
+    save_proof_to_dir(&proof, &proof_name, workspace.proofs_directory_path())?;
+
+    if recursive {
+        let num_public_inputs = public_inputs.clone().into_iter().count();
+        let (proof_fields, key_hash, key_fields) = backend.get_intermediate_proof_artifacts(
+            &compiled_program.circuit,
+            &proof,
+            public_inputs.clone(),
+        )?;
+        let artifacts = RecursionArtifacts {
+            public_inputs: public_inputs
+                .into_iter()
+                .map(|(_, value)| field_to_hex(value))
+                .collect(),
+            key: RecursionKey {
+                key: key_fields.into_iter().map(field_to_hex).collect(),
+                hash: field_to_hex(key_hash),
+            },
+            // The fields of the proof start with its public inputs, which are given separately.
+            proof: RecursionProof {
+                fields: proof_fields
+                    .into_iter()
+                    .skip(num_public_inputs)
+                    .map(field_to_hex)
+                    .collect(),
+            },
+        };
+        save_recursion_artifacts_to_dir(
+            &artifacts,
+            &proof_name,
+            workspace.proofs_directory_path(),
+        )?;
+    }
 
     Ok(())
 }
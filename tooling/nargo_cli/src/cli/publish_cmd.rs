@@ -0,0 +1,55 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use nargo_toml::{
+    get_package_manifest, publish_package, resolve_workspace_from_toml, PackageSelection,
+};
+use noirc_driver::NOIR_ARTIFACT_VERSION_STRING;
+use noirc_frontend::graph::CrateName;
+
+use crate::errors::CliError;
+
+use super::NargoConfig;
+
+/// Add a library package to a registry index in a local directory, which is not uploaded anywhere
+#[derive(Debug, Clone, Args)]
+pub(crate) struct PublishCommand {
+    /// The name of the package to publish
+    #[clap(long, conflicts_with = "workspace")]
+    package: Option<CrateName>,
+
+    /// Publish all packages in the workspace
+    #[clap(long, conflicts_with = "package")]
+    workspace: bool,
+
+    /// The local directory of the registry index [default: NARGO_REGISTRY_INDEX]
+    #[arg(long)]
+    registry: Option<PathBuf>,
+
+    /// Build the tarball of the package without writing it to the registry
+    #[arg(long)]
+    dry_run: bool,
+}
+
+pub(crate) fn run(args: PublishCommand, config: NargoConfig) -> Result<(), CliError> {
+    let toml_path = get_package_manifest(&config.program_dir)?;
+    let default_selection =
+        if args.workspace { PackageSelection::All } else { PackageSelection::DefaultOrAll };
+    let selection = args.package.map_or(default_selection, PackageSelection::Selected);
+    let workspace = resolve_workspace_from_toml(
+        &toml_path,
+        selection,
+        Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
+    )?;
+
+    for package in &workspace {
+        let published = publish_package(package, args.registry.as_deref(), args.dry_run)?;
+        let action = if args.dry_run { "Packaged" } else { "Published" };
+        println!(
+            "{action} {} v{} ({}, sha256 {})",
+            published.name, published.version, published.archive, published.checksum
+        );
+    }
+
+    Ok(())
+}
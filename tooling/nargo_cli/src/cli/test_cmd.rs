@@ -1,31 +1,33 @@
-use std::io::Write;
+use std::{io::Write, time::Duration, time::Instant};
 
 use acvm::BlackBoxFunctionSolver;
 use bn254_blackbox_solver::Bn254BlackBoxSolver;
-use clap::Args;
+use clap::{Args, ValueEnum};
 use fm::FileManager;
 use nargo::{
     insert_all_files_for_workspace_into_file_manager,
-    ops::{run_test, TestStatus},
+    ops::{run_test, LogLevel, TestStatus},
     package::Package,
     parse_all, prepare_package,
 };
-use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
+use nargo_toml::{get_package_manifest, resolve_workspace_with_features, PackageSelection};
 use noirc_driver::{file_manager_with_stdlib, CompileOptions, NOIR_ARTIFACT_VERSION_STRING};
 use noirc_frontend::{
     graph::CrateName,
     hir::{FunctionNameMatch, ParsedFiles},
 };
+use serde::Serialize;
 use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
 
 use crate::{backends::Backend, cli::check_cmd::check_crate_and_report_errors, errors::CliError};
 
-use super::NargoConfig;
+use super::{feature_selection, fill_target_backend, NargoConfig};
 
 /// Run the tests for this program
 #[derive(Debug, Clone, Args)]
 pub(crate) struct TestCommand {
-    /// If given, only tests with names containing this string will be run
+    /// If given, only tests with names containing this string will be run.
+    /// Patterns containing `*` or `?` are matched as globs against the full test name.
     test_name: Option<String>,
 
     /// Display output of `println` statements
@@ -36,6 +38,14 @@ pub(crate) struct TestCommand {
     #[clap(long)]
     exact: bool,
 
+    /// List the matching tests without running them
+    #[clap(long)]
+    list: bool,
+
+    /// The format in which test results are reported
+    #[clap(long, value_enum, default_value_t = Format::Pretty)]
+    format: Format,
+
     /// The name of the package to test
     #[clap(long, conflicts_with = "workspace")]
     package: Option<CrateName>,
@@ -50,20 +60,35 @@ pub(crate) struct TestCommand {
     /// JSON RPC url to solve oracle calls
     #[clap(long)]
     oracle_resolver: Option<String>,
+
+    /// The lowest level of `std::log` messages to print with `--show-output`
+    #[clap(long, default_value_t = LogLevel::Info)]
+    log_level: LogLevel,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// Human readable output
+    Pretty,
+    /// A JSON object per line for each test and for the summary of each package
+    Json,
 }
 
 pub(crate) fn run(
-    _backend: &Backend,
-    args: TestCommand,
+    backend: &Backend,
+    mut args: TestCommand,
     config: NargoConfig,
 ) -> Result<(), CliError> {
+    fill_target_backend(&mut args.compile_options, backend);
+
     let toml_path = get_package_manifest(&config.program_dir)?;
     let default_selection =
         if args.workspace { PackageSelection::All } else { PackageSelection::DefaultOrAll };
     let selection = args.package.map_or(default_selection, PackageSelection::Selected);
-    let workspace = resolve_workspace_from_toml(
+    let workspace = resolve_workspace_with_features(
         &toml_path,
         selection,
+        &feature_selection(&args.compile_options),
         Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
     )?;
 
@@ -75,6 +100,8 @@ pub(crate) fn run(
         Some(name) => {
             if args.exact {
                 FunctionNameMatch::Exact(name)
+            } else if name.contains(['*', '?']) {
+                FunctionNameMatch::Glob(name)
             } else {
                 FunctionNameMatch::Contains(name)
             }
@@ -82,6 +109,23 @@ pub(crate) fn run(
         None => FunctionNameMatch::Anything,
     };
 
+    if args.list {
+        let test_names: Vec<Vec<String>> = workspace
+            .into_iter()
+            .map(|package| {
+                list_tests(
+                    &workspace_file_manager,
+                    &parsed_files,
+                    package,
+                    pattern,
+                    args.format,
+                    &args.compile_options,
+                )
+            })
+            .collect::<Result<_, _>>()?;
+        return check_tests_found(test_names.iter().flatten().count(), pattern);
+    }
+
     let blackbox_solver = Bn254BlackBoxSolver::new();
 
     let test_reports: Vec<Vec<(String, TestStatus)>> = workspace
@@ -94,6 +138,8 @@ pub(crate) fn run(
                 package,
                 pattern,
                 args.show_output,
+                args.log_level,
+                args.format,
                 args.oracle_resolver.as_deref(),
                 &args.compile_options,
             )
@@ -101,20 +147,7 @@ pub(crate) fn run(
         .collect::<Result<_, _>>()?;
     let test_report: Vec<(String, TestStatus)> = test_reports.into_iter().flatten().collect();
 
-    if test_report.is_empty() {
-        match &pattern {
-            FunctionNameMatch::Exact(pattern) => {
-                return Err(CliError::Generic(
-                    format!("Found 0 tests matching input '{pattern}'.",),
-                ))
-            }
-            FunctionNameMatch::Contains(pattern) => {
-                return Err(CliError::Generic(format!("Found 0 tests containing '{pattern}'.",)))
-            }
-            // If we are running all tests in a crate, having none is not an error
-            FunctionNameMatch::Anything => {}
-        };
-    }
+    check_tests_found(test_report.len(), pattern)?;
 
     if test_report.iter().any(|(_, status)| !matches!(status, TestStatus::Fail { .. })) {
         Ok(())
@@ -123,6 +156,107 @@ pub(crate) fn run(
     }
 }
 
+/// Returns an error if a test name was given but no tests match it.
+fn check_tests_found(count: usize, pattern: FunctionNameMatch) -> Result<(), CliError> {
+    if count != 0 {
+        return Ok(());
+    }
+    match pattern {
+        FunctionNameMatch::Exact(pattern) => {
+            Err(CliError::Generic(format!("Found 0 tests matching input '{pattern}'.",)))
+        }
+        FunctionNameMatch::Contains(pattern) => {
+            Err(CliError::Generic(format!("Found 0 tests containing '{pattern}'.",)))
+        }
+        FunctionNameMatch::Glob(pattern) => {
+            Err(CliError::Generic(format!("Found 0 tests matching pattern '{pattern}'.",)))
+        }
+        // If we are running all tests in a crate, having none is not an error
+        FunctionNameMatch::Anything => Ok(()),
+    }
+}
+
+fn list_tests(
+    file_manager: &FileManager,
+    parsed_files: &ParsedFiles,
+    package: &Package,
+    fn_name: FunctionNameMatch,
+    format: Format,
+    compile_options: &CompileOptions,
+) -> Result<Vec<String>, CliError> {
+    let (mut context, crate_id) = prepare_package(file_manager, parsed_files, package);
+    check_crate_and_report_errors(&mut context, crate_id, compile_options)?;
+
+    let test_names: Vec<String> = context
+        .get_all_test_functions_in_crate_matching(&crate_id, fn_name)
+        .into_iter()
+        .map(|(test_name, _)| test_name)
+        .collect();
+
+    for test_name in &test_names {
+        match format {
+            Format::Pretty => println!("[{}] {test_name}", package.name),
+            Format::Json => {
+                let event = JsonTestEvent::Test {
+                    package: package.name.to_string(),
+                    name: test_name,
+                    status: None,
+                    duration_ms: None,
+                    message: None,
+                };
+                println!("{}", serde_json::to_string(&event).expect("Failed to serialize test"));
+            }
+        }
+    }
+
+    Ok(test_names)
+}
+
+/// A line of the output of `nargo test --format json`.
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum JsonTestEvent<'a> {
+    Test {
+        package: String,
+        name: &'a str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        status: Option<&'static str>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        duration_ms: Option<u128>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        message: Option<String>,
+    },
+    Suite {
+        package: String,
+        passed: usize,
+        failed: usize,
+    },
+}
+
+impl<'a> JsonTestEvent<'a> {
+    fn from_test_status(
+        package: &Package,
+        name: &'a str,
+        test_status: &TestStatus,
+        duration: Duration,
+    ) -> Self {
+        let (status, message) = match test_status {
+            TestStatus::Pass => ("pass", None),
+            TestStatus::Fail { message, .. } => ("fail", Some(message.trim().to_owned())),
+            TestStatus::CompileError(err) => {
+                ("compile_error", Some(err.diagnostic.message.clone()))
+            }
+        };
+        JsonTestEvent::Test {
+            package: package.name.to_string(),
+            name,
+            status: Some(status),
+            duration_ms: Some(duration.as_millis()),
+            message,
+        }
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 fn run_tests<S: BlackBoxFunctionSolver>(
     file_manager: &FileManager,
@@ -131,19 +265,48 @@ fn run_tests<S: BlackBoxFunctionSolver>(
     package: &Package,
     fn_name: FunctionNameMatch,
     show_output: bool,
+    log_level: LogLevel,
+    format: Format,
     foreign_call_resolver_url: Option<&str>,
     compile_options: &CompileOptions,
 ) -> Result<Vec<(String, TestStatus)>, CliError> {
     let (mut context, crate_id) = prepare_package(file_manager, parsed_files, package);
-    check_crate_and_report_errors(
-        &mut context,
-        crate_id,
-        compile_options.deny_warnings,
-        compile_options.disable_macros,
-        compile_options.silence_warnings,
-    )?;
+    check_crate_and_report_errors(&mut context, crate_id, compile_options)?;
 
     let test_functions = context.get_all_test_functions_in_crate_matching(&crate_id, fn_name);
+
+    if format == Format::Json {
+        let mut test_report: Vec<(String, TestStatus)> = Vec::new();
+        for (test_name, test_function) in test_functions {
+            let start = Instant::now();
+            let test_status = run_test(
+                blackbox_solver,
+                &context,
+                test_function,
+                show_output,
+                log_level,
+                foreign_call_resolver_url,
+                compile_options,
+            );
+            let event =
+                JsonTestEvent::from_test_status(package, &test_name, &test_status, start.elapsed());
+            println!("{}", serde_json::to_string(&event).expect("Failed to serialize test result"));
+
+            test_report.push((test_name, test_status));
+        }
+
+        let failed =
+            test_report.iter().filter(|(_, status)| !matches!(status, TestStatus::Pass)).count();
+        let event = JsonTestEvent::Suite {
+            package: package.name.to_string(),
+            passed: test_report.len() - failed,
+            failed,
+        };
+        println!("{}", serde_json::to_string(&event).expect("Failed to serialize test summary"));
+
+        return Ok(test_report);
+    }
+
     let count_all = test_functions.len();
 
     let plural = if count_all == 1 { "" } else { "s" };
@@ -163,6 +326,7 @@ fn run_tests<S: BlackBoxFunctionSolver>(
             &context,
             test_function,
             show_output,
+            log_level,
             foreign_call_resolver_url,
             compile_options,
         );
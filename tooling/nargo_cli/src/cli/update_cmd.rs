@@ -0,0 +1,33 @@
+use clap::Args;
+use nargo_toml::{get_package_manifest, update_workspace_lockfile};
+
+use crate::errors::CliError;
+
+use super::NargoConfig;
+
+/// Update the versions of the registry dependencies in Nargo.lock
+#[derive(Debug, Clone, Args)]
+pub(crate) struct UpdateCommand {
+    /// The dependencies to update, all of them if none are given
+    packages: Vec<String>,
+}
+
+pub(crate) fn run(args: UpdateCommand, config: NargoConfig) -> Result<(), CliError> {
+    let toml_path = get_package_manifest(&config.program_dir)?;
+    let updates = update_workspace_lockfile(&toml_path, &args.packages)?;
+
+    for update in updates {
+        match (update.previous_version, update.version) {
+            (Some(previous_version), Some(version)) => {
+                println!("Updating {} v{previous_version} -> v{version}", update.name);
+            }
+            (None, Some(version)) => println!("Adding {} v{version}", update.name),
+            (Some(previous_version), None) => {
+                println!("Removing {} v{previous_version}", update.name);
+            }
+            (None, None) => unreachable!("unchanged dependencies are not reported"),
+        }
+    }
+
+    Ok(())
+}
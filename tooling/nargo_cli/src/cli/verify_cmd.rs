@@ -1,15 +1,16 @@
-use super::compile_cmd::report_errors;
 use super::fs::{inputs::read_inputs_from_file, load_hex_data};
-use super::NargoConfig;
+use super::{
+    check_no_acir_calls, compile_and_handle_programs, feature_selection,
+    fill_supported_black_boxes, fill_target_backend, NargoConfig,
+};
 use crate::{backends::Backend, errors::CliError};
 
 use clap::Args;
 use nargo::constants::{PROOF_EXT, VERIFIER_INPUT_FILE};
-use nargo::ops::compile_program;
 use nargo::package::Package;
 use nargo::workspace::Workspace;
 use nargo::{insert_all_files_for_workspace_into_file_manager, parse_all};
-use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
+use nargo_toml::{get_package_manifest, resolve_workspace_with_features, PackageSelection};
 use noirc_abi::input_parser::Format;
 use noirc_driver::{
     file_manager_with_stdlib, CompileOptions, CompiledProgram, NOIR_ARTIFACT_VERSION_STRING,
@@ -23,6 +24,10 @@ pub(crate) struct VerifyCommand {
     #[clap(long, short, default_value = VERIFIER_INPUT_FILE)]
     verifier_name: String,
 
+    /// Verify a proof created with `nargo prove --recursive`
+    #[arg(long)]
+    recursive: bool,
+
     /// The name of the package verify
     #[clap(long, conflicts_with = "workspace")]
     package: Option<CrateName>,
@@ -37,16 +42,17 @@ pub(crate) struct VerifyCommand {
 
 pub(crate) fn run(
     backend: &Backend,
-    args: VerifyCommand,
+    mut args: VerifyCommand,
     config: NargoConfig,
 ) -> Result<(), CliError> {
     let toml_path = get_package_manifest(&config.program_dir)?;
     let default_selection =
         if args.workspace { PackageSelection::All } else { PackageSelection::DefaultOrAll };
     let selection = args.package.map_or(default_selection, PackageSelection::Selected);
-    let workspace = resolve_workspace_from_toml(
+    let workspace = resolve_workspace_with_features(
         &toml_path,
         selection,
+        &feature_selection(&args.compile_options),
         Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
     )?;
 
@@ -58,29 +64,28 @@ pub(crate) fn run(
         .compile_options
         .expression_width
         .unwrap_or_else(|| backend.get_backend_info_or_default());
-    let binary_packages = workspace.into_iter().filter(|package| package.is_binary());
-    for package in binary_packages {
-        let compilation_result = compile_program(
-            &workspace_file_manager,
-            &parsed_files,
-            package,
-            &args.compile_options,
-            None,
-        );
-
-        let compiled_program = report_errors(
-            compilation_result,
-            &workspace_file_manager,
-            args.compile_options.deny_warnings,
-            args.compile_options.silence_warnings,
-        )?;
-
-        let compiled_program = nargo::ops::transform_program(compiled_program, expression_width);
-
-        verify_package(backend, &workspace, package, compiled_program, &args.verifier_name)?;
-    }
-
-    Ok(())
+    fill_supported_black_boxes(&mut args.compile_options, backend);
+    fill_target_backend(&mut args.compile_options, backend);
+    let binary_packages: Vec<_> =
+        workspace.into_iter().filter(|package| package.is_binary()).cloned().collect();
+
+    compile_and_handle_programs(
+        &workspace_file_manager,
+        &parsed_files,
+        &binary_packages,
+        &args.compile_options,
+        expression_width,
+        |package, compiled_program| {
+            verify_package(
+                backend,
+                &workspace,
+                package,
+                compiled_program,
+                &args.verifier_name,
+                args.recursive,
+            )
+        },
+    )
 }
 
 fn verify_package(
@@ -89,7 +94,10 @@ fn verify_package(
     package: &Package,
     compiled_program: CompiledProgram,
     verifier_name: &str,
+    recursive: bool,
 ) -> Result<(), CliError> {
+    check_no_acir_calls(&package.name.to_string(), &compiled_program.functions)?;
+
     // Load public inputs (if any) from `verifier_name`.
     let public_abi = compiled_program.abi.public_abi();
     let (public_inputs_map, return_value) =
@@ -102,7 +110,8 @@ fn verify_package(
 
     let proof = load_hex_data(&proof_path)?;
 
-    let valid_proof = backend.verify(&proof, public_inputs, &compiled_program.circuit, false)?;
+    let valid_proof =
+        backend.verify(&proof, public_inputs, &compiled_program.circuit, recursive)?;
 
     if valid_proof {
         Ok(())
@@ -0,0 +1,150 @@
+use clap::Args;
+use nargo::ops::{check_output_determination, compile_programs, Determination};
+use nargo::{insert_all_files_for_workspace_into_file_manager, parse_all};
+use nargo_toml::{get_package_manifest, resolve_workspace_with_features, PackageSelection};
+use noirc_driver::{file_manager_with_stdlib, CompileOptions, NOIR_ARTIFACT_VERSION_STRING};
+use noirc_errors::CustomDiagnostic;
+use noirc_frontend::graph::CrateName;
+
+use super::{feature_selection, fill_target_backend, NargoConfig};
+use crate::backends::Backend;
+use crate::cli::compile_cmd::report_errors;
+use crate::errors::CliError;
+
+/// Checks with an SMT solver that the return values of a circuit are determined by its inputs
+///
+/// Each return value which a prover could set to more than one value for the same inputs is
+/// reported along with the source code which first refers to it. This requires an SMT solver
+/// such as `z3` to be installed and is only practical for small circuits.
+#[derive(Debug, Clone, Args)]
+pub(crate) struct VerifyConstraintsCommand {
+    /// The command which runs the SMT solver, reading SMT-LIB from stdin
+    #[clap(long, default_value = "z3 -in")]
+    solver: String,
+
+    /// Skip circuits with more ACIR opcodes than this
+    #[clap(long, default_value_t = 1000)]
+    max_opcodes: usize,
+
+    /// The name of the package to check
+    #[clap(long, conflicts_with = "workspace")]
+    package: Option<CrateName>,
+
+    /// Check all packages in the workspace
+    #[clap(long, conflicts_with = "package")]
+    workspace: bool,
+
+    #[clap(flatten)]
+    compile_options: CompileOptions,
+}
+
+pub(crate) fn run(
+    backend: &Backend,
+    mut args: VerifyConstraintsCommand,
+    config: NargoConfig,
+) -> Result<(), CliError> {
+    fill_target_backend(&mut args.compile_options, backend);
+
+    let toml_path = get_package_manifest(&config.program_dir)?;
+    let default_selection =
+        if args.workspace { PackageSelection::All } else { PackageSelection::DefaultOrAll };
+    let selection = args.package.map_or(default_selection, PackageSelection::Selected);
+    let workspace = resolve_workspace_with_features(
+        &toml_path,
+        selection,
+        &feature_selection(&args.compile_options),
+        Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
+    )?;
+
+    let mut workspace_file_manager = file_manager_with_stdlib(&workspace.root_dir);
+    insert_all_files_for_workspace_into_file_manager(&workspace, &mut workspace_file_manager);
+    let parsed_files = parse_all(&workspace_file_manager);
+
+    let binary_packages: Vec<_> =
+        workspace.into_iter().filter(|package| package.is_binary()).cloned().collect();
+
+    let compilation_results = compile_programs(
+        &workspace_file_manager,
+        &parsed_files,
+        &binary_packages,
+        &args.compile_options,
+    );
+
+    let mut free_outputs = 0;
+    for (package, compilation_result) in binary_packages.iter().zip(compilation_results) {
+        let compiled_program = report_errors(
+            compilation_result,
+            &workspace_file_manager,
+            args.compile_options.deny_warnings,
+            args.compile_options.silence_warnings,
+        )?;
+
+        let circuit = &compiled_program.circuit;
+        if circuit.opcodes.len() > args.max_opcodes {
+            println!(
+                "[{}] Skipped: circuit has {} ACIR opcodes, more than --max-opcodes {}",
+                package.name,
+                circuit.opcodes.len(),
+                args.max_opcodes
+            );
+            continue;
+        }
+
+        let determinations = check_output_determination(circuit, &args.solver)?;
+        for output in &determinations {
+            let witness = output.witness.witness_index();
+            match output.determination {
+                Determination::Unique => (),
+                Determination::Unknown => {
+                    println!(
+                        "[{}] Solver could not decide whether _{witness} is determined",
+                        package.name
+                    );
+                }
+                Determination::Free => {
+                    free_outputs += 1;
+                    let location = output
+                        .opcode_location
+                        .and_then(|location| compiled_program.debug.opcode_location(&location))
+                        .and_then(|call_stack| call_stack.last().copied());
+                    match location {
+                        Some(location) => {
+                            let diagnostic = CustomDiagnostic::simple_warning(
+                                format!("Return value _{witness} is not determined by the inputs"),
+                                String::from("a prover can choose more than one value here"),
+                                location.span,
+                            );
+                            diagnostic
+                                .in_file(location.file)
+                                .report(workspace_file_manager.as_file_map(), false);
+                        }
+                        None => println!(
+                            "[{}] Return value _{witness} is not determined by the inputs",
+                            package.name
+                        ),
+                    }
+                }
+            }
+        }
+
+        let unique = determinations
+            .iter()
+            .filter(|output| output.determination == Determination::Unique)
+            .count();
+        println!(
+            "[{}] {unique} of {} return values are determined by the inputs",
+            package.name,
+            determinations.len()
+        );
+    }
+
+    if free_outputs > 0 {
+        Err(CliError::Generic(format!(
+            "Found {free_outputs} return value{} which {} not determined by the inputs",
+            if free_outputs == 1 { "" } else { "s" },
+            if free_outputs == 1 { "is" } else { "are" }
+        )))
+    } else {
+        Ok(())
+    }
+}
@@ -1,6 +1,9 @@
 use acvm::acir::native_types::WitnessMapError;
 use hex::FromHexError;
-use nargo::{errors::CompileError, NargoError};
+use nargo::{
+    errors::{CompileError, SmtError},
+    NargoError,
+};
 use nargo_toml::ManifestError;
 use noirc_abi::errors::{AbiError, InputParserError};
 use std::path::PathBuf;
@@ -13,9 +16,9 @@ pub(crate) enum FilesystemError {
     #[error("Error: could not parse hex build artifact (proof, proving and/or verification keys, ACIR checksum) ({0})")]
     HexArtifactNotValid(FromHexError),
     #[error(
-        " Error: cannot find {0}.toml file.\n Expected location: {1:?} \n Please generate this file at the expected location."
+        " Error: cannot find {0}.{1} file.\n Expected location: {2:?} \n Please generate this file at the expected location."
     )]
-    MissingTomlFile(String, PathBuf),
+    MissingInputFile(String, &'static str, PathBuf),
 
     /// Input parsing error
     #[error(transparent)]
@@ -25,6 +28,13 @@ pub(crate) enum FilesystemError {
     #[error(transparent)]
     WitnessMapSerialization(#[from] WitnessMapError),
 
+    #[error("Error: could not (de)serialize JSON witness: {0}")]
+    WitnessJson(String),
+    #[error("Error: value {1:?} of witness {0} is not a hex-encoded field element")]
+    InvalidWitnessValue(u32, String),
+    #[error("Error: witness {0} is assigned different values")]
+    ConflictingWitnessValues(u32),
+
     #[error("Error: could not deserialize build program: {0}")]
     ProgramSerializationError(String),
 }
@@ -42,6 +52,9 @@ pub(crate) enum CliError {
     #[error("Invalid package name {0}. Did you mean to use `--name`?")]
     InvalidPackageName(String),
 
+    #[error("{0} runs ACIR functions through call opcodes, which the backend is not given. Compile it without `--acir-calls` and `--outline-repeated-code`")]
+    UnsupportedAcirCalls(String),
+
     /// ABI encoding/decoding error
     #[error(transparent)]
     AbiError(#[from] AbiError),
@@ -60,6 +73,10 @@ pub(crate) enum CliError {
     #[error(transparent)]
     NargoError(#[from] NargoError),
 
+    /// Error while checking constraints with an SMT solver
+    #[error(transparent)]
+    SmtError(#[from] SmtError),
+
     /// Error from Manifest
     #[error(transparent)]
     ManifestError(#[from] ManifestError),
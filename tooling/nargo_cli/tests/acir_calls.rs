@@ -0,0 +1,41 @@
+//! Programs whose circuits run other ACIR functions through call opcodes can be executed, but are
+//! rejected by the commands which pass their circuit to the backend, as it is only given `main`.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::path::PathBuf;
+use std::process::Command;
+
+test_binary::build_test_binary_once!(mock_backend, "../backend_interface/test-binaries");
+
+fn nargo_with_acir_calls(command: &str) -> Command {
+    let program_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../../test_programs/execution_success/acir_calls");
+
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.env("NARGO_BACKEND_PATH", path_to_mock_backend());
+    cmd.arg("--program-dir").arg(program_dir);
+    cmd.arg(command).arg("--force").arg("--acir-calls");
+    cmd
+}
+
+#[test]
+fn executes_program_with_acir_calls() {
+    nargo_with_acir_calls("execute").assert().success();
+}
+
+#[test]
+fn rejects_proving_program_with_acir_calls() {
+    nargo_with_acir_calls("prove")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("runs ACIR functions through call opcodes"));
+}
+
+#[test]
+fn rejects_counting_gates_of_program_with_acir_calls() {
+    nargo_with_acir_calls("info")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("runs ACIR functions through call opcodes"));
+}
@@ -60,7 +60,7 @@ fn format_{test_name}() {{
 
     let (parsed_module, _errors) = noirc_frontend::parse_program(&input);
 
-    let config = nargo_fmt::Config::of("{config}").unwrap();
+    let config = nargo_fmt::Config::of(r#"{config}"#).unwrap();
     let fmt_text = nargo_fmt::format(&input, parsed_module, &config);
 
     if std::env::var("UPDATE_EXPECT").is_ok() {{
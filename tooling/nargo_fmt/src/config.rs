@@ -50,6 +50,18 @@ config! {
     array_width: usize, 100, "Maximum width of an array literal before falling back to vertical formatting";
     fn_call_width: usize, 60, "Maximum width of the args of a function call before falling back to vertical formatting";
     single_line_if_else_max_width: usize, 50, "Maximum line length for single line if-else expressions";
+    hard_tabs: bool, false, "Indent with tabs instead of spaces, each tab being `tab_spaces` wide";
+    reorder_imports: bool, false, "Sort consecutive `use` statements alphabetically";
+    group_imports: bool, false, "Separate consecutive `use` statements into `std`, dependency and crate imports";
+    trailing_comma: TrailingComma, TrailingComma::Never, "When to add a comma after the last item of a list of arguments, parameters or array elements";
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrailingComma {
+    Always,
+    Never,
+    /// Only when the items are each on their own line.
+    Vertical,
 }
 
 impl Config {
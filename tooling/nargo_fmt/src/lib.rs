@@ -27,7 +27,7 @@ mod visitor;
 use noirc_frontend::ParsedModule;
 use visitor::FmtVisitor;
 
-pub use config::Config;
+pub use config::{Config, TrailingComma};
 
 pub fn format(source: &str, parsed_module: ParsedModule, config: &Config) -> String {
     let mut fmt = FmtVisitor::new(source, config);
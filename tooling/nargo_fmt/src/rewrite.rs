@@ -3,9 +3,11 @@ mod expr;
 mod infix;
 mod parenthesized;
 mod typ;
+mod where_clause;
 
 pub(crate) use array::rewrite as array;
 pub(crate) use expr::{rewrite as expr, rewrite_sub_expr as sub_expr};
 pub(crate) use infix::rewrite as infix;
 pub(crate) use parenthesized::rewrite as parenthesized;
 pub(crate) use typ::rewrite as typ;
+pub(crate) use where_clause::rewrite as where_clause;
@@ -2,7 +2,11 @@ use noirc_frontend::{hir::resolution::errors::Span, token::Token, Expression};
 
 use crate::{
     utils::{Expr, FindToken},
-    visitor::{expr::NewlineMode, FmtVisitor},
+    visitor::{
+        expr::{is_vertical, NewlineMode},
+        FmtVisitor,
+    },
+    TrailingComma,
 };
 
 pub(crate) fn rewrite(mut visitor: FmtVisitor, array: Vec<Expression>, array_span: Span) -> String {
@@ -53,33 +57,53 @@ pub(crate) fn rewrite(mut visitor: FmtVisitor, array: Vec<Expression>, array_spa
 
     visitor.indent.block_unindent(visitor.config);
 
-    let mut items_str = String::new();
-    let mut items = result.into_iter().peekable();
-    while let Some(next) = items.next() {
-        items_str.push_str(&next.leading);
-        if next.leading.contains('\n') && !next.value.is_empty() {
-            items_str.push_str(&indent_str);
-        }
-        items_str.push_str(&next.value);
-        items_str.push_str(&next.trailing);
+    let join_items = |trailing_comma: bool| {
+        let mut items_str = String::new();
+        let mut items = result.iter().peekable();
+        while let Some(next) = items.next() {
+            items_str.push_str(&next.leading);
+            if next.leading.contains('\n') && !next.value.is_empty() {
+                items_str.push_str(&indent_str);
+            }
+            items_str.push_str(&next.value);
 
-        if let Some(item) = items.peek() {
-            if !item.value.is_empty() {
+            let is_last_item = items.peek().map_or(true, |item| item.value.is_empty());
+            if trailing_comma && is_last_item && !next.value.is_empty() {
                 items_str.push(',');
             }
+            items_str.push_str(&next.trailing);
 
-            if !item.leading.contains('\n') && !next.value.is_empty() {
-                items_str.push(' ');
+            if let Some(item) = items.peek() {
+                if !item.value.is_empty() {
+                    items_str.push(',');
+                }
+
+                if !item.leading.contains('\n') && !next.value.is_empty() {
+                    items_str.push(' ');
+                }
             }
         }
-    }
 
-    crate::visitor::expr::wrap_exprs(
-        "[",
-        "]",
-        items_str.trim().into(),
-        nested_indent,
-        visitor.shape(),
-        NewlineMode::IfContainsNewLineAndWidth,
-    )
+        crate::visitor::expr::wrap_exprs(
+            "[",
+            "]",
+            items_str.trim().into(),
+            nested_indent,
+            visitor.shape(),
+            NewlineMode::IfContainsNewLineAndWidth,
+        )
+    };
+
+    match visitor.config.trailing_comma {
+        TrailingComma::Always => join_items(true),
+        TrailingComma::Never => join_items(false),
+        TrailingComma::Vertical => {
+            let array = join_items(false);
+            if is_vertical(&array, "[") {
+                join_items(true)
+            } else {
+                array
+            }
+        }
+    }
 }
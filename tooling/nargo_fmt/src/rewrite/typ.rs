@@ -62,6 +62,7 @@ pub(crate) fn rewrite(visitor: &FmtVisitor, _shape: Shape, typ: UnresolvedType)
         | UnresolvedTypeData::Named(_, _)
         | UnresolvedTypeData::Unit
         | UnresolvedTypeData::Expression(_)
+        | UnresolvedTypeData::Value(_)
         | UnresolvedTypeData::String(_)
         | UnresolvedTypeData::FormatString(_, _)
         | UnresolvedTypeData::TraitAsType(_, _) => visitor.slice(typ.span.unwrap()).into(),
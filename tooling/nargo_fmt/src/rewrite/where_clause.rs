@@ -0,0 +1,163 @@
+use noirc_frontend::{hir::resolution::errors::Span, lexer::Lexer, token::Token};
+
+use crate::{
+    utils::{join_tokens, last_line_contains_single_line_comment},
+    visitor::FmtVisitor,
+};
+
+/// A constraint of a `where` clause along with the comments around it.
+///
+/// The parser splits `T: Eq + Default` into a constraint per trait bound and doesn't keep their
+/// spans, so the clause is laid out from its source instead.
+struct Constraint<'a> {
+    leading: Vec<&'a str>,
+    value: String,
+    trailing: Vec<&'a str>,
+}
+
+/// Formats the `where` clause starting at `span`, which ends before the `{` of the item's body or
+/// the `;` of a declaration, and returns whether the clause was split over multiple lines.
+///
+/// The clause stays on the same line as the signature if it's written that way, has no comments
+/// and fits within `max_width` after `used_width` columns. Otherwise each constraint goes on its
+/// own line:
+///
+/// ```noir
+/// fn foo<T, U>(x: T, y: U)
+/// where
+///     T: Eq + Default,
+///     U: Hash
+/// {
+/// ```
+pub(crate) fn rewrite(visitor: &FmtVisitor, span: Span, used_width: usize) -> (String, bool) {
+    let slice = visitor.slice(span);
+    let Some(constraints) = slice.strip_prefix("where").and_then(constraints) else {
+        let slice = slice.trim();
+        let is_vertical = slice.contains('\n') || last_line_contains_single_line_comment(slice);
+        return (format!(" {slice}"), is_vertical);
+    };
+
+    let has_comments = constraints
+        .iter()
+        .any(|constraint| !constraint.leading.is_empty() || !constraint.trailing.is_empty());
+    let starts_on_own_line = visitor.source[..span.start() as usize]
+        .chars()
+        .rev()
+        .take_while(|char| char.is_whitespace())
+        .any(|char| char == '\n');
+    if !has_comments && !starts_on_own_line && !slice.trim_end().contains('\n') {
+        let values: Vec<_> =
+            constraints.iter().map(|constraint| constraint.value.as_str()).collect();
+        let horizontal = format!(" where {}", values.join(", "));
+        // 2 = ` {`
+        if used_width + horizontal.chars().count() + 2 <= visitor.config.max_width {
+            return (horizontal, false);
+        }
+    }
+
+    let mut nested_indent = visitor.indent;
+    nested_indent.block_indent(visitor.config);
+    let nested_indent = nested_indent.to_string_with_newline();
+
+    let mut result = visitor.indent.to_string_with_newline();
+    result.push_str("where");
+
+    let last = constraints.len() - 1;
+    for (index, constraint) in constraints.into_iter().enumerate() {
+        for comment in constraint.leading {
+            result.push_str(&nested_indent);
+            result.push_str(comment);
+        }
+        result.push_str(&nested_indent);
+        result.push_str(&constraint.value);
+        if index != last {
+            result.push(',');
+        }
+        for comment in constraint.trailing {
+            result.push(' ');
+            result.push_str(comment);
+        }
+    }
+
+    (result, true)
+}
+
+/// Splits the constraints of a `where` clause, without the `where` keyword. Returns `None` if a
+/// comment is written in the middle of a constraint or if the clause bounds numeric generics.
+fn constraints(source: &str) -> Option<Vec<Constraint>> {
+    let mut constraints: Vec<Constraint> = Vec::new();
+    for (index, chunk) in split_constraints(source)?.into_iter().enumerate() {
+        let mut constraint =
+            Constraint { leading: Vec::new(), value: String::new(), trailing: Vec::new() };
+        let mut on_first_line = true;
+        let mut value_span: Option<(u32, u32)> = None;
+
+        for spanned in Lexer::new(chunk).skip_comments(false).skip_whitespaces(false) {
+            let spanned = spanned.ok()?;
+            let span = spanned.to_span();
+            match spanned.token() {
+                Token::EOF => break,
+                Token::Whitespace(whitespace) => {
+                    if whitespace.contains('\n') {
+                        on_first_line = false;
+                    }
+                }
+                Token::LineComment(_, _) | Token::BlockComment(_, _) => {
+                    let comment = &chunk[span.start() as usize..span.end() as usize];
+                    match (value_span, constraints.last_mut()) {
+                        // A comment following the comma on the same line is about the constraint
+                        // before it.
+                        (None, Some(previous)) if index > 0 && on_first_line => {
+                            previous.trailing.push(comment);
+                        }
+                        (None, _) => constraint.leading.push(comment),
+                        (Some(_), _) => constraint.trailing.push(comment),
+                    }
+                }
+                _ if !constraint.trailing.is_empty() => return None,
+                _ => {
+                    let start = value_span.map_or(span.start(), |(start, _)| start);
+                    value_span = Some((start, span.end()));
+                }
+            }
+        }
+
+        let (start, end) = value_span?;
+        constraint.value = join_tokens(&chunk[start as usize..end as usize])?;
+        constraints.push(constraint);
+    }
+
+    (!constraints.is_empty()).then_some(constraints)
+}
+
+/// Splits `source` at the commas between constraints, leaving those within generics alone.
+///
+/// Returns `None` if `source` contains a numeric bound such as `N <= 32`. A `<` or `>` written
+/// after a space is taken to be a comparison rather than the start or end of generics.
+fn split_constraints(source: &str) -> Option<Vec<&str>> {
+    let mut chunks = Vec::new();
+    let mut depth = 0;
+    let mut chunk_start = 0;
+    let mut previous_end = None;
+
+    for spanned in Lexer::new(source).flatten() {
+        let span = spanned.to_span();
+        let follows_space = previous_end.map_or(false, |end| end != span.start());
+        previous_end = Some(span.end());
+
+        match spanned.token() {
+            Token::LessEqual | Token::GreaterEqual | Token::Equal | Token::NotEqual => return None,
+            Token::Less | Token::Greater if follows_space => return None,
+            Token::Less | Token::LeftParen | Token::LeftBracket => depth += 1,
+            Token::Greater | Token::RightParen | Token::RightBracket => depth -= 1,
+            Token::Comma if depth == 0 => {
+                chunks.push(&source[chunk_start..span.start() as usize]);
+                chunk_start = span.end() as usize;
+            }
+            _ => {}
+        }
+    }
+    chunks.push(&source[chunk_start..]);
+
+    Some(chunks)
+}
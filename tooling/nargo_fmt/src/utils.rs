@@ -2,8 +2,8 @@ use crate::rewrite;
 use crate::visitor::{FmtVisitor, Shape};
 use noirc_frontend::hir::resolution::errors::Span;
 use noirc_frontend::lexer::Lexer;
-use noirc_frontend::token::Token;
-use noirc_frontend::{Expression, Ident, Param, Visibility};
+use noirc_frontend::token::{Keyword, Token};
+use noirc_frontend::{Expression, Ident, Param, UnresolvedGeneric, Visibility};
 
 pub(crate) fn changed_comment_content(original: &str, new: &str) -> bool {
     comments(original).ne(comments(new))
@@ -21,7 +21,7 @@ pub(crate) fn comments(source: &str) -> impl Iterator<Item = String> + '_ {
     })
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct Expr {
     pub(crate) leading: String,
     pub(crate) value: String,
@@ -251,6 +251,12 @@ impl Item for Param {
     }
 
     fn format(self, visitor: &FmtVisitor, shape: Shape) -> String {
+        // The span of `self` doesn't include the `&mut` or `mut` before it, which is kept as the
+        // text leading the parameter.
+        if visitor.slice(self.span) == "self" {
+            return "self".to_string();
+        }
+
         let visibility = match self.visibility {
             Visibility::Public => "pub ",
             Visibility::Private => "",
@@ -263,7 +269,7 @@ impl Item for Param {
     }
 }
 
-impl Item for Ident {
+impl Item for UnresolvedGeneric {
     fn span(&self) -> Span {
         self.span()
     }
@@ -300,3 +306,85 @@ pub(crate) fn last_line_used_width(s: &str, offset: usize) -> usize {
 pub(crate) fn span_is_empty(span: Span) -> bool {
     span.start() == span.end()
 }
+
+/// Joins the tokens of a type, signature or declaration, putting a single space between them
+/// where one is needed.
+///
+/// Returns `None` if `source` contains comments or tokens which can't be laid out this way without
+/// changing their meaning, in which case it's left as written.
+pub(crate) fn join_tokens(source: &str) -> Option<String> {
+    let mut result = String::new();
+    let mut previous: Option<(Token, u32)> = None;
+
+    for spanned in Lexer::new(source).skip_comments(false) {
+        let spanned = spanned.ok()?;
+        let span = spanned.to_span();
+        let token = spanned.into_token();
+
+        match token {
+            Token::EOF => break,
+            Token::Ident(_)
+            | Token::Keyword(_)
+            | Token::IntType(_)
+            | Token::Int(_)
+            | Token::Bool(_)
+            | Token::Str(_)
+            | Token::Less
+            | Token::Greater
+            | Token::LeftParen
+            | Token::RightParen
+            | Token::LeftBracket
+            | Token::RightBracket
+            | Token::Comma
+            | Token::Colon
+            | Token::DoubleColon
+            | Token::Semicolon
+            | Token::Ampersand
+            | Token::Plus
+            | Token::Arrow
+            | Token::Assign => {}
+            _ => return None,
+        }
+
+        if let Some((previous, previous_end)) = &previous {
+            let had_space = *previous_end != span.start();
+            if space_between(previous, &token, had_space) {
+                result.push(' ');
+            }
+        }
+
+        result.push_str(&source[span.start() as usize..span.end() as usize]);
+        previous = Some((token, span.end()));
+    }
+
+    Some(result)
+}
+
+fn space_between(previous: &Token, next: &Token, had_space: bool) -> bool {
+    match (previous, next) {
+        (
+            _,
+            Token::Comma
+            | Token::Semicolon
+            | Token::Colon
+            | Token::DoubleColon
+            | Token::RightParen
+            | Token::RightBracket
+            | Token::Less
+            | Token::Greater,
+        ) => false,
+        (
+            Token::LeftParen
+            | Token::LeftBracket
+            | Token::Less
+            | Token::DoubleColon
+            | Token::Ampersand,
+            _,
+        ) => false,
+        (Token::Ident(_) | Token::Greater | Token::Keyword(Keyword::Fn), Token::LeftParen) => false,
+        (Token::Keyword(Keyword::Fn), Token::LeftBracket) => false,
+        // `pub(crate)` as opposed to a public return type such as `pub (Field, Field)`
+        (Token::Keyword(Keyword::Pub), Token::LeftParen) => had_space,
+        _ => true,
+    }
+}
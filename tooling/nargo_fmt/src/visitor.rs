@@ -26,7 +26,7 @@ impl<'me> FmtVisitor<'me> {
             config,
             source,
             last_position: 0,
-            indent: Indent { block_indent: 0 },
+            indent: Indent::new(config),
         }
     }
 
@@ -250,9 +250,15 @@ impl<'me> FmtVisitor<'me> {
 #[derive(Clone, Copy, Debug, Default)]
 pub(crate) struct Indent {
     block_indent: usize,
+    /// The width of a tab when indenting with tabs, or `None` when indenting with spaces.
+    hard_tab_width: Option<usize>,
 }
 
 impl Indent {
+    pub(crate) fn new(config: &Config) -> Self {
+        Indent { block_indent: 0, hard_tab_width: config.hard_tabs.then_some(config.tab_spaces) }
+    }
+
     pub(crate) fn width(&self) -> usize {
         self.block_indent
     }
@@ -273,7 +279,13 @@ impl Indent {
 
     #[allow(clippy::inherent_to_string)]
     pub(crate) fn to_string(self) -> String {
-        " ".repeat(self.block_indent)
+        match self.hard_tab_width {
+            Some(tab_width) if tab_width > 0 => {
+                "\t".repeat(self.block_indent / tab_width)
+                    + &" ".repeat(self.block_indent % tab_width)
+            }
+            _ => " ".repeat(self.block_indent),
+        }
     }
 }
 
@@ -7,7 +7,7 @@ use super::{ExpressionType, FmtVisitor, Shape};
 use crate::{
     rewrite,
     utils::{self, first_line_width, Expr, FindToken, Item},
-    Config,
+    Config, TrailingComma,
 };
 
 impl FmtVisitor<'_> {
@@ -207,9 +207,31 @@ pub(crate) fn format_seq<T: Item>(
     nested_indent.indent.block_indent(visitor.config);
 
     let exprs: Vec<_> = utils::Exprs::new(&visitor, nested_indent, span, exprs).collect();
-    let exprs = format_exprs(visitor.config, tactic, trailing_comma, exprs, nested_indent, reduce);
+    let config = visitor.config;
+    let format = |trailing_comma| {
+        let exprs =
+            format_exprs(config, tactic, trailing_comma, exprs.clone(), nested_indent, reduce);
+        wrap_exprs(prefix, suffix, exprs, nested_indent, shape, mode)
+    };
+
+    match config.trailing_comma {
+        TrailingComma::Always if !exprs.is_empty() => format(true),
+        TrailingComma::Vertical if !trailing_comma => {
+            // Whether the items end up on their own lines is only known once they're wrapped.
+            let result = format(false);
+            if is_vertical(&result, prefix) {
+                format(true)
+            } else {
+                result
+            }
+        }
+        _ => format(trailing_comma),
+    }
+}
 
-    wrap_exprs(prefix, suffix, exprs, nested_indent, shape, mode)
+/// True if the items of a sequence wrapped by [wrap_exprs] start on a new line.
+pub(crate) fn is_vertical(wrapped: &str, prefix: &str) -> bool {
+    wrapped[prefix.len()..].starts_with('\n')
 }
 
 pub(crate) fn format_brackets(
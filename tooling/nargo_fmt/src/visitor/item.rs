@@ -1,13 +1,15 @@
 use noirc_frontend::{
     hir::resolution::errors::Span,
+    lexer::Lexer,
     parser::{Item, ItemKind},
     token::{Keyword, Token},
-    Distinctness, NoirFunction, ParsedModule, Visibility,
+    Distinctness, NoirFunction, NoirTrait, NoirTraitImpl, ParsedModule, TraitImplItem, TraitItem,
+    TypeImpl, Visibility,
 };
 
 use crate::{
     rewrite,
-    utils::{last_line_contains_single_line_comment, last_line_used_width, FindToken},
+    utils::{join_tokens, last_line_contains_single_line_comment, last_line_used_width, FindToken},
     visitor::expr::{format_seq, NewlineMode},
 };
 
@@ -21,7 +23,7 @@ impl super::FmtVisitor<'_> {
         let name_span = func.name_ident().span();
         let func_span = func.span();
 
-        let mut result = self.slice(start..name_span.end()).to_owned();
+        let mut result = self.format_fn_name(start, name_span.end());
 
         let params_open =
             self.span_before(name_span.end()..func_span.start(), Token::LeftParen).start();
@@ -35,8 +37,19 @@ impl super::FmtVisitor<'_> {
         let params_end = self.span_after(last_span, Token::RightParen).start();
 
         let params_span = params_open..params_end;
+        let where_clause_start =
+            if func.def.where_clause.is_empty() && func.def.numeric_bounds.is_empty() {
+                None
+            } else {
+                self.slice(params_end..func_span.start())
+                    .find_token(Token::Keyword(Keyword::Where))
+                    .map(|span| params_end + span.start())
+            };
+        let signature_end = where_clause_start.unwrap_or(func_span.start());
+
         let return_type_span = func.return_type().span;
-        let return_type = self.format_return_type(return_type_span, &func, func_span, params_end);
+        let return_type =
+            self.format_return_type(return_type_span, &func, signature_end, params_end);
         let parameters = func.def.parameters;
 
         if !func.def.generics.is_empty() {
@@ -72,7 +85,7 @@ impl super::FmtVisitor<'_> {
                 .unwrap()
                 .start();
 
-            let slice = self.slice(fn_start..result.len() as u32);
+            let slice = &result[fn_start as usize..];
             let indent = self.indent;
             let used_width = last_line_used_width(slice, indent.width());
             let overhead = if return_type.is_empty() { 2 } else { 3 }; // 2 = `()`, 3 = `() `
@@ -96,18 +109,70 @@ impl super::FmtVisitor<'_> {
         };
 
         result.push_str(&parameters);
-        result.push_str(&return_type);
 
-        let maybe_comment = self.slice(params_end..func_span.start());
+        let Some(where_clause_start) = where_clause_start else {
+            result.push_str(&return_type);
+            let maybe_comment = self.slice(params_end..func_span.start());
+            return (
+                result.trim_end().to_string(),
+                last_line_contains_single_line_comment(maybe_comment),
+            );
+        };
+
+        result.push_str(return_type.trim_end());
+        let used_width = last_line_used_width(&result, self.indent.width());
+        let (where_clause, is_vertical) =
+            rewrite::where_clause(self, (where_clause_start..func_span.start()).into(), used_width);
+        result.push_str(&where_clause);
 
-        (result.trim_end().to_string(), last_line_contains_single_line_comment(maybe_comment))
+        (result, is_vertical)
+    }
+
+    /// Formats the attributes, modifiers and name of a function, with each attribute on its own
+    /// line.
+    fn format_fn_name(&self, start: u32, end: u32) -> String {
+        let slice = self.slice(start..end);
+        let indent = self.indent.to_string_with_newline();
+        let mut result = String::new();
+
+        let tokens = Lexer::new(slice).skip_comments(false).skip_whitespaces(false).flatten();
+        for spanned in tokens {
+            let span = spanned.to_span();
+            let text = &slice[span.start() as usize..span.end() as usize];
+            match spanned.token() {
+                Token::Attribute(_) => {
+                    result.push_str(&format_attribute(text));
+                    result.push_str(&indent);
+                }
+                Token::LineComment(_, _) => {
+                    result.push_str(text);
+                    result.push_str(&indent);
+                }
+                Token::BlockComment(_, _) => {
+                    result.push_str(text);
+                    let is_on_own_line = slice[span.end() as usize..]
+                        .chars()
+                        .take_while(|char| char.is_whitespace())
+                        .any(|char| char == '\n');
+                    result.push_str(if is_on_own_line { indent.as_str() } else { " " });
+                }
+                Token::Whitespace(_) => {}
+                _ => {
+                    let rest = &slice[span.start() as usize..];
+                    result.push_str(&join_tokens(rest).unwrap_or_else(|| rest.to_string()));
+                    break;
+                }
+            }
+        }
+
+        result
     }
 
     fn format_return_type(
         &self,
         return_type_span: Option<Span>,
         func: &NoirFunction,
-        func_span: Span,
+        signature_end: u32,
         params_end: u32,
     ) -> String {
         let mut result = String::new();
@@ -126,12 +191,12 @@ impl super::FmtVisitor<'_> {
             let typ = rewrite::typ(self, self.shape(), func.return_type());
             result.push_str(&typ);
 
-            let slice = self.slice(span.end()..func_span.start());
+            let slice = self.slice(span.end()..signature_end);
             if !slice.trim().is_empty() {
                 result.push_str(slice);
             }
         } else {
-            result.push_str(self.slice(params_end..func_span.start()));
+            result.push_str(self.slice(params_end..signature_end));
         }
 
         result
@@ -143,25 +208,27 @@ impl super::FmtVisitor<'_> {
     }
 
     fn visit_module(&mut self, module: ParsedModule) {
-        for Item { kind, span } in module.items {
+        let mut items = module.items.into_iter().peekable();
+        while let Some(Item { kind, span }) = items.next() {
             match kind {
-                ItemKind::Function(func) => {
-                    self.format_missing_indent(span.start(), true);
-
-                    if std::mem::take(&mut self.ignore_next_node) {
-                        self.push_str(self.slice(span));
-                        self.last_position = span.end();
-                        continue;
+                ItemKind::Import(_) if self.config.reorder_imports || self.config.group_imports => {
+                    // Only `use` statements which directly follow each other are moved around, so
+                    // that comments stay with the imports they're about.
+                    let mut imports = vec![span];
+                    while let Some(Item { kind: ItemKind::Import(_), span: next }) = items.peek() {
+                        let previous_end = imports.last().unwrap().end();
+                        if !self.slice(previous_end..next.start()).trim().is_empty() {
+                            break;
+                        }
+                        imports.push(*next);
+                        items.next();
                     }
-
-                    let (fn_before_block, force_brace_newline) =
-                        self.format_fn_before_block(func.clone(), span.start());
-
-                    self.push_str(&fn_before_block);
-                    self.push_str(if force_brace_newline { "\n" } else { " " });
-
-                    self.visit_block(func.def.body, func.def.span);
+                    self.format_imports(&imports);
                 }
+                ItemKind::Function(func) => self.visit_function(span, func),
+                ItemKind::Impl(type_impl) => self.visit_impl(span, type_impl),
+                ItemKind::TraitImpl(trait_impl) => self.visit_trait_impl(span, trait_impl),
+                ItemKind::Trait(noir_trait) => self.visit_trait(span, noir_trait),
                 ItemKind::Submodules(module) => {
                     self.format_missing_indent(span.start(), true);
 
@@ -193,9 +260,6 @@ impl super::FmtVisitor<'_> {
                 }
                 ItemKind::Import(_)
                 | ItemKind::Struct(_)
-                | ItemKind::Trait(_)
-                | ItemKind::TraitImpl(_)
-                | ItemKind::Impl(_)
                 | ItemKind::TypeAlias(_)
                 | ItemKind::Global(_)
                 | ItemKind::ModuleDecl(_) => {
@@ -205,4 +269,307 @@ impl super::FmtVisitor<'_> {
             }
         }
     }
+    fn visit_function(&mut self, span: Span, func: NoirFunction) {
+        self.format_missing_indent(span.start(), true);
+
+        if std::mem::take(&mut self.ignore_next_node) {
+            self.push_str(self.slice(span));
+            self.last_position = span.end();
+            return;
+        }
+
+        let (fn_before_block, force_brace_newline) =
+            self.format_fn_before_block(func.clone(), span.start());
+
+        self.push_str(&fn_before_block);
+        self.push_brace_separator(force_brace_newline);
+
+        self.visit_block(func.def.body, func.def.span);
+    }
+
+    fn visit_impl(&mut self, span: Span, type_impl: TypeImpl) {
+        let Some(body_start) = self.visit_item_header(span, type_impl.type_span.end()) else {
+            return;
+        };
+
+        if type_impl.methods.is_empty() {
+            self.visit_empty_block((body_start..span.end()).into());
+            return;
+        }
+
+        self.push_str("{");
+        self.indent.block_indent(self.config);
+        for method in type_impl.methods {
+            let start = self.next_token_start(method.name_ident().span().start());
+            self.visit_function((start..method.span().end()).into(), method);
+        }
+        self.close_block((self.last_position..span.end() - 1).into());
+        self.last_position = span.end();
+    }
+
+    fn visit_trait_impl(&mut self, span: Span, trait_impl: NoirTraitImpl) {
+        let Some(body_start) = self.visit_item_header(span, span.start()) else {
+            return;
+        };
+
+        if trait_impl.items.is_empty() {
+            self.visit_empty_block((body_start..span.end()).into());
+            return;
+        }
+
+        self.push_str("{");
+        self.indent.block_indent(self.config);
+        for item in trait_impl.items {
+            match item {
+                TraitImplItem::Function(method) => {
+                    let start = self.next_token_start(method.name_ident().span().start());
+                    self.visit_function((start..method.span().end()).into(), method);
+                }
+                TraitImplItem::Constant(name, ..) | TraitImplItem::Type { name, .. } => {
+                    self.visit_declaration(name.span());
+                }
+            }
+        }
+        self.close_block((self.last_position..span.end() - 1).into());
+        self.last_position = span.end();
+    }
+
+    fn visit_trait(&mut self, span: Span, noir_trait: NoirTrait) {
+        let Some(body_start) = self.visit_item_header(span, noir_trait.name.span().end()) else {
+            return;
+        };
+
+        if noir_trait.items.is_empty() {
+            self.visit_empty_block((body_start..span.end()).into());
+            return;
+        }
+
+        self.push_str("{");
+        self.indent.block_indent(self.config);
+        for item in noir_trait.items {
+            match item {
+                TraitItem::Function { name, body: Some(body), .. } => {
+                    let start = self.next_token_start(name.span().start());
+                    self.format_missing_indent(start, true);
+
+                    let body_start =
+                        self.span_before(name.span().end()..span.end(), Token::LeftBrace).start();
+                    let body_span: Span = (body_start..self.block_end(body_start)).into();
+                    if std::mem::take(&mut self.ignore_next_node) {
+                        self.push_str(self.slice(start..body_span.end()));
+                        self.last_position = body_span.end();
+                        continue;
+                    }
+
+                    let (header, force_brace_newline) =
+                        self.format_header((start..body_start).into());
+                    self.push_str(&header);
+                    self.push_brace_separator(force_brace_newline);
+                    self.visit_block(body, body_span);
+                }
+                TraitItem::Function { name, .. }
+                | TraitItem::Constant { name, .. }
+                | TraitItem::Type { name } => self.visit_declaration(name.span()),
+            }
+        }
+        self.close_block((self.last_position..span.end() - 1).into());
+        self.last_position = span.end();
+    }
+
+    /// Formats everything before the `{` of the body of an `impl` or trait, which is searched for
+    /// after `header_end`. Returns the position of that `{`, or `None` if the whole item was left
+    /// as written.
+    fn visit_item_header(&mut self, span: Span, header_end: u32) -> Option<u32> {
+        self.format_missing_indent(span.start(), true);
+
+        if std::mem::take(&mut self.ignore_next_node) {
+            self.push_str(self.slice(span));
+            self.last_position = span.end();
+            return None;
+        }
+
+        let body_start = self.span_before(header_end..span.end(), Token::LeftBrace).start();
+        let (header, force_brace_newline) = self.format_header((span.start()..body_start).into());
+        self.push_str(&header);
+        self.push_brace_separator(force_brace_newline);
+        self.last_position = body_start + 1;
+
+        Some(body_start)
+    }
+
+    /// Formats a declaration ending with `;` in a trait or an `impl` of a trait, such as a function
+    /// without a body or an associated type.
+    fn visit_declaration(&mut self, name_span: Span) {
+        let start = self.next_token_start(name_span.start());
+        self.format_missing_indent(start, true);
+
+        let end = self.declaration_end(name_span.end());
+        if std::mem::take(&mut self.ignore_next_node) {
+            self.push_str(self.slice(start..end));
+            self.last_position = end;
+            return;
+        }
+
+        // The declaration is formatted without its `;`
+        let (declaration, _) = self.format_header((start..end - 1).into());
+        self.push_str(&declaration);
+        self.push_str(";");
+        self.last_position = end;
+    }
+
+    /// Formats a signature, or the header of an `impl` or trait, along with its `where` clause.
+    /// Returns whether the `{` following it has to go on its own line.
+    fn format_header(&self, span: Span) -> (String, bool) {
+        let slice = self.slice(span);
+        let where_clause_start = slice
+            .find_token(Token::Keyword(Keyword::Where))
+            .map(|where_span| span.start() + where_span.start());
+
+        let signature = self.slice(span.start()..where_clause_start.unwrap_or(span.end()));
+        let mut result = join_tokens(signature).unwrap_or_else(|| signature.trim().to_string());
+
+        match where_clause_start {
+            Some(where_clause_start) => {
+                let used_width = last_line_used_width(&result, self.indent.width());
+                let (where_clause, is_vertical) = rewrite::where_clause(
+                    self,
+                    (where_clause_start..span.end()).into(),
+                    used_width,
+                );
+                result.push_str(&where_clause);
+                (result, is_vertical)
+            }
+            None => {
+                let force_brace_newline = last_line_contains_single_line_comment(&result);
+                (result, force_brace_newline)
+            }
+        }
+    }
+
+    fn push_brace_separator(&mut self, force_brace_newline: bool) {
+        if force_brace_newline {
+            let indent = self.indent.to_string_with_newline();
+            self.push_str(&indent);
+        } else {
+            self.push_str(" ");
+        }
+    }
+
+    /// Returns the start of the first token after the last formatted position, skipping comments,
+    /// or `end` if there is none before it.
+    fn next_token_start(&self, end: u32) -> u32 {
+        self.slice(self.last_position..end)
+            .find_token_with(|token| {
+                !matches!(token, Token::LineComment(_, _) | Token::BlockComment(_, _))
+            })
+            .map_or(end, |span| self.last_position + span.start())
+    }
+
+    /// Returns the end of the `;` ending the declaration whose name ends at `start`.
+    fn declaration_end(&self, start: u32) -> u32 {
+        let mut depth = 0;
+        for spanned in Lexer::new(self.slice(start..self.source.len() as u32)).flatten() {
+            match spanned.token() {
+                Token::LeftParen | Token::LeftBracket | Token::LeftBrace => depth += 1,
+                Token::RightParen | Token::RightBracket | Token::RightBrace => depth -= 1,
+                Token::Semicolon if depth == 0 => return start + spanned.to_span().end(),
+                _ => {}
+            }
+        }
+        self.source.len() as u32
+    }
+
+    /// Returns the end of the `}` closing the block opened at `start`.
+    fn block_end(&self, start: u32) -> u32 {
+        let mut depth = 0;
+        for spanned in Lexer::new(self.slice(start..self.source.len() as u32)).flatten() {
+            match spanned.token() {
+                Token::LeftBrace => depth += 1,
+                Token::RightBrace => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return start + spanned.to_span().end();
+                    }
+                }
+                _ => {}
+            }
+        }
+        self.source.len() as u32
+    }
+
+    fn format_imports(&mut self, spans: &[Span]) {
+        let first = spans[0];
+        let last = spans[spans.len() - 1];
+        self.format_missing_indent(first.start(), true);
+        self.last_position = last.end();
+
+        if std::mem::take(&mut self.ignore_next_node) {
+            self.push_str(self.slice(first.start()..last.end()));
+            return;
+        }
+
+        let mut imports: Vec<_> = spans
+            .iter()
+            .map(|span| {
+                let import = self.slice(*span);
+                let group = if self.config.group_imports {
+                    ImportGroup::of(import)
+                } else {
+                    ImportGroup::Crate
+                };
+                (group, import)
+            })
+            .collect();
+        if self.config.reorder_imports {
+            imports.sort();
+        } else {
+            imports.sort_by_key(|(group, _)| *group);
+        }
+
+        let indent = self.indent.to_string_with_newline();
+        let mut previous_group = None;
+        for (group, import) in imports {
+            if let Some(previous_group) = previous_group {
+                if previous_group != group {
+                    self.push_str("\n");
+                }
+                self.push_str(&indent);
+            }
+            self.push_str(import);
+            previous_group = Some(group);
+        }
+    }
+}
+
+/// The groups `use` statements are separated into, in the order they're written in.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum ImportGroup {
+    Std,
+    Dependency,
+    Crate,
+}
+
+impl ImportGroup {
+    fn of(import: &str) -> Self {
+        let path = import.trim_start_matches("use").trim_start();
+        if path.starts_with("std::") {
+            ImportGroup::Std
+        } else if path.starts_with("dep::") {
+            ImportGroup::Dependency
+        } else {
+            ImportGroup::Crate
+        }
+    }
+}
+
+/// Puts spaces around the `=` of `#[test(should_fail_with = "reason")]`. The rest of an attribute is
+/// left as written, as its contents are given to the compiler as they are.
+fn format_attribute(attribute: &str) -> String {
+    let scope = attribute.strip_prefix("#[test(").and_then(|rest| rest.strip_suffix(")]"));
+    match scope.and_then(|scope| scope.split_once('=')) {
+        Some((key, reason)) if key.trim() == "should_fail_with" => {
+            format!("#[test(should_fail_with = {})]", reason.trim())
+        }
+        _ => attribute.to_string(),
+    }
 }
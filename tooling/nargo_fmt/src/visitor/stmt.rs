@@ -38,8 +38,9 @@ impl super::FmtVisitor<'_> {
 
                     nested_shape.indent.block_indent(self.config);
 
-                    let message =
-                        message.map_or(String::new(), |message| format!(", \"{message}\""));
+                    let message = message.map_or(String::new(), |message| {
+                        format!(", {}", rewrite::sub_expr(self, nested_shape, message))
+                    });
 
                     let (callee, args) = match kind {
                         ConstrainKind::Assert => {
@@ -95,9 +96,22 @@ impl super::FmtVisitor<'_> {
                     let result = format!("for {identifier} in {range} {block}");
                     self.push_rewrite(result, span);
                 }
+                StatementKind::While(while_stmt) => {
+                    let condition = rewrite::sub_expr(self, self.shape(), while_stmt.condition);
+                    let block = rewrite::sub_expr(self, self.shape(), while_stmt.block);
+
+                    let result = format!("while {condition} {block}");
+                    self.push_rewrite(result, span);
+                }
+                StatementKind::Loop(block) => {
+                    let block = rewrite::sub_expr(self, self.shape(), block);
+                    self.push_rewrite(format!("loop {block}"), span);
+                }
                 StatementKind::Assign(_) => {
                     self.push_rewrite(self.slice(span).to_string(), span);
                 }
+                StatementKind::Break => self.push_rewrite("break;".into(), span),
+                StatementKind::Continue => self.push_rewrite("continue;".into(), span),
                 StatementKind::Error => unreachable!(),
             }
 
@@ -0,0 +1,53 @@
+use std::path::{Path, PathBuf};
+
+/// Formatting a file which is already formatted must not change it, otherwise `nargo fmt --check`
+/// would fail right after running `nargo fmt`.
+#[test]
+fn formatting_is_idempotent() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("../..");
+    let mut files = Vec::new();
+    collect_noir_files(&root.join("noir_stdlib/src"), &mut files);
+    collect_noir_files(&root.join("test_programs/execution_success"), &mut files);
+    collect_noir_files(&root.join("test_programs/compile_success_empty"), &mut files);
+    assert!(!files.is_empty(), "Could not find any Noir files to format");
+
+    let config = nargo_fmt::Config::of("error_on_lost_comment = false").unwrap();
+    for file in files {
+        let source = std::fs::read_to_string(&file).unwrap();
+        let Some(formatted) = format(&source, &config) else {
+            continue;
+        };
+        let Some(reformatted) = format(&formatted, &config) else {
+            panic!("Formatting {} produced a program which doesn't parse", file.display());
+        };
+        similar_asserts::assert_eq!(
+            formatted,
+            reformatted,
+            "Formatting {} again changed it",
+            file.display()
+        );
+    }
+}
+
+/// Returns `None` if `source` has parse errors, as those are never formatted.
+fn format(source: &str, config: &nargo_fmt::Config) -> Option<String> {
+    let (parsed_module, errors) = noirc_frontend::parse_program(source);
+    if errors.iter().any(|error| !error.is_warning()) {
+        return None;
+    }
+    Some(nargo_fmt::format(source, parsed_module, config))
+}
+
+fn collect_noir_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_noir_files(&path, files);
+        } else if path.extension().is_some_and(|extension| extension == "nr") {
+            files.push(path);
+        }
+    }
+}
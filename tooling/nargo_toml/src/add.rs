@@ -0,0 +1,110 @@
+use std::path::Path;
+
+use noirc_frontend::graph::CrateName;
+
+use crate::{lockfile::Lockfile, registry::Registry, registry_index, Config, ManifestError};
+
+/// Where a dependency added by [add_dependency] comes from. A registry dependency without a
+/// version requirement is given its latest version in the registry index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DependencySource {
+    Registry { version: Option<String> },
+    Git { git: String, tag: String },
+    Path { path: String },
+}
+
+/// Adds dependency `name` to the package manifest at `toml_path`, replacing any dependency of the
+/// same name, and returns the value it was given in the manifest. The rest of the manifest is left
+/// as written.
+///
+/// The version of a registry dependency is looked up in the registry index.
+pub fn add_dependency(
+    toml_path: &Path,
+    name: &str,
+    source: DependencySource,
+) -> Result<String, ManifestError> {
+    if name.parse::<CrateName>().is_err() {
+        return Err(ManifestError::InvalidDependencyName {
+            toml: toml_path.to_path_buf(),
+            name: name.into(),
+        });
+    }
+
+    let contents = std::fs::read_to_string(toml_path)
+        .map_err(|_| ManifestError::ReadFailed(toml_path.to_path_buf()))?;
+    if let Config::Workspace { .. } = Config::try_from(contents.as_str())? {
+        return Err(ManifestError::UnexpectedWorkspace(toml_path.to_path_buf()));
+    }
+    let mut document: toml_edit::Document =
+        contents.parse().expect("manifest was parsed as valid TOML above");
+
+    let value = match source {
+        DependencySource::Registry { version } => {
+            let mut registry = Registry::new(registry_index(), Lockfile::default(), true);
+            let package = registry.latest_version(name, version.as_deref())?;
+            toml_edit::value(version.unwrap_or(package.version))
+        }
+        DependencySource::Git { git, tag } => {
+            let mut table = toml_edit::InlineTable::new();
+            table.insert("git", git.into());
+            table.insert("tag", tag.into());
+            toml_edit::value(table)
+        }
+        DependencySource::Path { path } => {
+            let mut table = toml_edit::InlineTable::new();
+            table.insert("path", path.into());
+            toml_edit::value(table)
+        }
+    };
+    let written = value.to_string().trim().to_string();
+
+    let dependencies = document.entry("dependencies").or_insert(toml_edit::table());
+    dependencies
+        .as_table_like_mut()
+        .expect("dependencies were parsed as a table above")
+        .insert(name, value);
+
+    std::fs::write(toml_path, document.to_string())
+        .map_err(|_| ManifestError::WriteFailed(toml_path.to_path_buf()))?;
+
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DependencyConfig, DependencySourceConfig};
+
+    #[test]
+    fn keeps_rest_of_manifest() {
+        let package_dir = tempfile::tempdir().unwrap();
+        let toml_path = package_dir.path().join("Nargo.toml");
+        let manifest = "[package]\nname = \"bar\" # the name\ntype = \"lib\"\n";
+        std::fs::write(&toml_path, manifest).unwrap();
+
+        let path = DependencySource::Path { path: "../foo".to_string() };
+        add_dependency(&toml_path, "foo", path).unwrap();
+        let git = DependencySource::Git {
+            git: "https://github.com/example/baz".to_string(),
+            tag: "v0.1.0".to_string(),
+        };
+        add_dependency(&toml_path, "baz", git).unwrap();
+
+        let contents = std::fs::read_to_string(&toml_path).unwrap();
+        assert!(contents.starts_with(manifest));
+        let Config::Package { package_config } = Config::try_from(contents).unwrap() else {
+            panic!("Expected a package manifest");
+        };
+        let dependencies = package_config.dependencies;
+        assert!(matches!(
+            &dependencies["foo"],
+            DependencyConfig::Detailed { source: DependencySourceConfig::Path { path }, .. }
+                if path == "../foo"
+        ));
+        assert!(matches!(
+            &dependencies["baz"],
+            DependencyConfig::Detailed { source: DependencySourceConfig::Github { tag, .. }, .. }
+                if tag == "v0.1.0"
+        ));
+    }
+}
@@ -0,0 +1,104 @@
+use std::path::{Path, PathBuf};
+
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use sha2::{Digest, Sha256};
+
+use crate::{git::nargo_crates, ManifestError};
+
+/// Builds the tarball of the package at `package_root` published by `nargo publish`, holding its
+/// `Nargo.toml`, its `src` directory and its readme and license files.
+///
+/// The files are sorted and their metadata left out so that the same sources always give the same
+/// checksum.
+pub(crate) fn pack_package(package_root: &Path) -> Result<Vec<u8>, ManifestError> {
+    let mut files = vec![PathBuf::from("Nargo.toml")];
+    let entries = std::fs::read_dir(package_root)
+        .map_err(|err| ManifestError::ArchiveFailed(package_root.to_path_buf(), err))?;
+    for entry in entries.flatten() {
+        let file_name = entry.file_name().to_string_lossy().to_uppercase();
+        if entry.path().is_file()
+            && (file_name.starts_with("README") || file_name.starts_with("LICENSE"))
+        {
+            files.push(PathBuf::from(entry.file_name()));
+        }
+    }
+    collect_files(package_root, Path::new("src"), &mut files)?;
+    files.sort();
+
+    let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+    for file in files {
+        let path = package_root.join(&file);
+        let contents = std::fs::read(&path).map_err(|_| ManifestError::ReadFailed(path.clone()))?;
+        let mut header = tar::Header::new_gnu();
+        header.set_size(contents.len() as u64);
+        header.set_mode(0o644);
+        builder
+            .append_data(&mut header, &file, contents.as_slice())
+            .map_err(|err| ManifestError::ArchiveFailed(path, err))?;
+    }
+
+    builder
+        .into_inner()
+        .and_then(GzEncoder::finish)
+        .map_err(|err| ManifestError::ArchiveFailed(package_root.to_path_buf(), err))
+}
+
+/// Collects the files in directory `dir` of the package at `package_root`, relative to it.
+fn collect_files(
+    package_root: &Path,
+    dir: &Path,
+    files: &mut Vec<PathBuf>,
+) -> Result<(), ManifestError> {
+    let entries = std::fs::read_dir(package_root.join(dir))
+        .map_err(|err| ManifestError::ArchiveFailed(package_root.join(dir), err))?;
+    for entry in entries.flatten() {
+        let path = dir.join(entry.file_name());
+        if entry.path().is_dir() {
+            collect_files(package_root, &path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Returns the hex encoded SHA-256 hash of a package tarball.
+pub(crate) fn checksum(archive: &[u8]) -> String {
+    hex::encode(Sha256::digest(archive))
+}
+
+/// Unpacks the tarball at `archive_path` of version `version` of package `name` into the nargo
+/// directory, unless it was already, and returns the directory of its sources.
+pub(crate) fn unpack_archive(
+    archive_path: &Path,
+    expected_checksum: &str,
+    name: &str,
+    version: &str,
+) -> Result<PathBuf, ManifestError> {
+    let short_checksum = &expected_checksum[..expected_checksum.len().min(16)];
+    let dir_name = format!("{name}-{version}-{short_checksum}");
+    let sources_dir = nargo_crates().join("registry").join("src").join(&dir_name);
+    if sources_dir.exists() {
+        return Ok(sources_dir);
+    }
+
+    let archive = std::fs::read(archive_path)
+        .map_err(|_| ManifestError::ReadFailed(archive_path.to_path_buf()))?;
+    if checksum(&archive) != expected_checksum {
+        return Err(ManifestError::ChecksumMismatch {
+            name: name.to_string(),
+            version: version.to_string(),
+        });
+    }
+
+    // Unpack next to the final directory first so that an interrupted unpack isn't used later on.
+    let partial_dir = sources_dir.with_file_name(format!("{dir_name}.partial"));
+    let _ = std::fs::remove_dir_all(&partial_dir);
+    tar::Archive::new(GzDecoder::new(archive.as_slice()))
+        .unpack(&partial_dir)
+        .map_err(|err| ManifestError::ArchiveFailed(archive_path.to_path_buf(), err))?;
+    std::fs::rename(&partial_dir, &sources_dir)
+        .map_err(|_| ManifestError::WriteFailed(sources_dir.clone()))?;
+
+    Ok(sources_dir)
+}
@@ -45,6 +45,12 @@ pub enum ManifestError {
     #[error("{} found in {toml}", if name.is_empty() { "Empty dependency name".into() } else { format!("Invalid dependency name `{name}`") })]
     InvalidDependencyName { toml: PathBuf, name: String },
 
+    #[error("Unknown feature `{feature}` in {toml}")]
+    UnknownFeature { toml: PathBuf, feature: String },
+
+    #[error("Feature `{feature}` in {toml} enables a feature of an unknown dependency")]
+    UnknownFeatureDependency { toml: PathBuf, feature: String },
+
     #[error("Invalid directory path {directory} in {toml}: It must point to a subdirectory")]
     InvalidDirectory { toml: PathBuf, directory: PathBuf },
 
@@ -72,6 +78,71 @@ pub enum ManifestError {
 
     #[error("Cyclic package dependency found when processing {cycle}")]
     CyclicDependency { cycle: String },
+
+    #[error(
+        "Invalid version requirement `{requirement}` for dependency `{name}` in {toml}: {error}"
+    )]
+    InvalidVersionRequirement { toml: PathBuf, name: String, requirement: String, error: String },
+
+    #[error("Dependency `{0}` is given by version but no registry index is set. Set the NARGO_REGISTRY_INDEX environment variable to the URL of a registry index or to a local directory")]
+    MissingRegistryIndex(String),
+
+    #[error("Package `{0}` was not found in the registry index")]
+    MissingRegistryPackage(String),
+
+    #[error("Entry for package `{name}` in the registry index is badly formed, could not parse.\n\n {error}")]
+    MalformedRegistryEntry { name: String, error: toml::de::Error },
+
+    #[error("No version of package `{name}` in the registry index matches `{requirement}`")]
+    NoMatchingVersion { name: String, requirement: String },
+
+    #[error("Cannot select a version of package `{name}`: `{dependent}` requires `{requirement}` but version {version} was already selected to satisfy `{previous_requirement}` required by `{previous_dependent}`")]
+    VersionConflict {
+        name: String,
+        version: String,
+        requirement: String,
+        dependent: String,
+        previous_requirement: String,
+        previous_dependent: String,
+    },
+
+    #[error("{0} is badly formed, could not parse.\n\n {1}")]
+    MalformedLockfile(PathBuf, toml::de::Error),
+
+    #[error("Cannot write file {0}")]
+    WriteFailed(PathBuf),
+
+    #[error("Package `{0}` is not in the lockfile")]
+    MissingLockedPackage(String),
+
+    #[error("Invalid archive path {archive} for package `{name}` in the registry index: It must point into the index")]
+    InvalidArchivePath { name: String, archive: PathBuf },
+
+    #[error("Checksum of the archive of package `{name}` version {version} doesn't match the registry index")]
+    ChecksumMismatch { name: String, version: String },
+
+    #[error("Could not read or write package archive {0}: {1}")]
+    ArchiveFailed(PathBuf, std::io::Error),
+
+    #[error("Package `{0}` has type `{1}` but only libraries can be published")]
+    PublishNonLibrary(CrateName, PackageType),
+
+    #[error("Missing `version` field in {toml}, which is required to publish a package")]
+    MissingVersionField { toml: PathBuf },
+
+    #[error(
+        "Cannot publish package `{package}` as its dependency `{dependency}` is given by path"
+    )]
+    PublishPathDependency { package: CrateName, dependency: String },
+
+    #[error("Version {version} of package `{name}` was already published")]
+    VersionAlreadyPublished { name: CrateName, version: String },
+
+    #[error("No registry to publish to. Pass `--registry` or set the NARGO_REGISTRY_INDEX environment variable to a local directory")]
+    MissingPublishRegistry,
+
+    #[error("Packages can only be published to a registry index in a local directory, not {0}")]
+    RemotePublishRegistry(String),
 }
 
 #[allow(clippy::enum_variant_names)]
@@ -9,7 +9,7 @@ fn resolve_folder_name(base: &url::Url, tag: &str) -> String {
     folder_name
 }
 
-fn nargo_crates() -> PathBuf {
+pub(crate) fn nargo_crates() -> PathBuf {
     dirs::home_dir().unwrap().join("nargo")
 }
 
@@ -53,3 +53,52 @@ pub(crate) fn clone_git_repo(url: &str, tag: &str) -> Result<PathBuf, String> {
 
     Ok(loc)
 }
+
+/// Clones the registry index at `url` into the nargo directory, or fetches its latest commit if
+/// it was cloned before and `update` is set.
+pub(crate) fn fetch_registry_index(url: &str, update: bool) -> Result<PathBuf, String> {
+    use std::process::Command;
+
+    let base = url::Url::parse(url).map_err(|err| err.to_string())?;
+    let loc = nargo_crates().join("registry").join(resolve_folder_name(&base, ""));
+
+    let status = if !loc.exists() {
+        Command::new("git")
+            .arg("clone")
+            .arg("--depth")
+            .arg("1")
+            .arg(base.as_str())
+            .arg(&loc)
+            .status()
+            .expect("git clone command failed to start")
+    } else if update {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(&loc)
+            .arg("fetch")
+            .arg("--depth")
+            .arg("1")
+            .arg("origin")
+            .status()
+            .expect("git fetch command failed to start");
+        if !status.success() {
+            return Err(format!("Could not fetch registry index {url}"));
+        }
+        Command::new("git")
+            .arg("-C")
+            .arg(&loc)
+            .arg("reset")
+            .arg("--hard")
+            .arg("FETCH_HEAD")
+            .status()
+            .expect("git reset command failed to start")
+    } else {
+        return Ok(loc);
+    };
+
+    if status.success() {
+        Ok(loc)
+    } else {
+        Err(format!("Could not fetch registry index {url}"))
+    }
+}
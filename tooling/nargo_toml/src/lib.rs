@@ -4,7 +4,7 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies, unused_extern_crates))]
 
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     path::{Component, Path, PathBuf},
 };
 
@@ -17,12 +17,22 @@ use nargo::{
 use noirc_frontend::graph::CrateName;
 use serde::Deserialize;
 
+mod add;
+mod archive;
 mod errors;
 mod git;
+mod lockfile;
+mod publish;
+mod registry;
 mod semver;
 
+pub use add::{add_dependency, DependencySource};
 pub use errors::ManifestError;
 use git::clone_git_repo;
+pub use lockfile::LockfileUpdate;
+use lockfile::{Lockfile, PackageSource, LOCKFILE_NAME};
+pub use publish::{publish_package, PublishedPackage};
+use registry::{Registry, REGISTRY_INDEX_ENV};
 
 /// Searches for a `Nargo.toml` file in the current directory and all parent directories.
 /// For example, if the current directory is `/workspace/package/src`, then this function
@@ -117,6 +127,25 @@ struct PackageConfig {
     package: PackageMetadata,
     #[serde(default)]
     dependencies: BTreeMap<String, DependencyConfig>,
+    /// The features of the package along with the features they enable, either of the package or
+    /// of a dependency as `dependency/feature`. The `default` feature is enabled unless the
+    /// dependent package opts out.
+    #[serde(default)]
+    features: BTreeMap<String, Vec<String>>,
+}
+
+/// The features to enable for a package, given on the command line or by a package depending on it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeatureSelection {
+    pub features: Vec<String>,
+    /// Whether to enable the `default` feature of the package.
+    pub default_features: bool,
+}
+
+impl Default for FeatureSelection {
+    fn default() -> Self {
+        Self { features: Vec::new(), default_features: true }
+    }
 }
 
 impl PackageConfig {
@@ -124,6 +153,8 @@ impl PackageConfig {
         &self,
         root_dir: &Path,
         processed: &mut Vec<String>,
+        registry: &mut Registry,
+        features: &FeatureSelection,
     ) -> Result<Package, ManifestError> {
         let name: CrateName = if let Some(name) = &self.package.name {
             name.parse().map_err(|_| ManifestError::InvalidPackageName {
@@ -134,15 +165,28 @@ impl PackageConfig {
             return Err(ManifestError::MissingNameField { toml: root_dir.join("Nargo.toml") });
         };
 
-        let mut dependencies: BTreeMap<CrateName, Dependency> = BTreeMap::new();
-        for (name, dep_config) in self.dependencies.iter() {
-            let name = name.parse().map_err(|_| ManifestError::InvalidDependencyName {
-                toml: root_dir.join("Nargo.toml"),
-                name: name.into(),
-            })?;
-            let resolved_dep = dep_config.resolve_to_dependency(root_dir, processed)?;
+        let (enabled_features, mut dependency_features) =
+            self.resolve_features(root_dir, features)?;
 
-            dependencies.insert(name, resolved_dep);
+        let mut dependencies: BTreeMap<CrateName, Dependency> = BTreeMap::new();
+        for (dep_name, dep_config) in self.dependencies.iter() {
+            let crate_name =
+                dep_name.parse().map_err(|_| ManifestError::InvalidDependencyName {
+                    toml: root_dir.join("Nargo.toml"),
+                    name: dep_name.into(),
+                })?;
+            let mut dep_features = dep_config.features();
+            dep_features.features.extend(dependency_features.remove(dep_name).unwrap_or_default());
+            let resolved_dep = dep_config.resolve_to_dependency(
+                dep_name,
+                &name,
+                root_dir,
+                processed,
+                registry,
+                &dep_features,
+            )?;
+
+            dependencies.insert(crate_name, resolved_dep);
         }
 
         let package_type = match self.package.package_type.as_deref() {
@@ -207,8 +251,50 @@ impl PackageConfig {
             package_type,
             name,
             dependencies,
+            features: enabled_features,
         })
     }
+
+    /// Returns the features of the package enabled by `selection` along with those they enable,
+    /// and the features they enable for each dependency.
+    fn resolve_features(
+        &self,
+        root_dir: &Path,
+        selection: &FeatureSelection,
+    ) -> Result<(BTreeSet<String>, BTreeMap<String, Vec<String>>), ManifestError> {
+        let mut enabled = BTreeSet::new();
+        let mut dependency_features: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+        let mut pending = selection.features.clone();
+        if selection.default_features && self.features.contains_key("default") {
+            pending.push("default".to_string());
+        }
+        while let Some(feature) = pending.pop() {
+            if let Some((dependency, dep_feature)) = feature.split_once('/') {
+                if !self.dependencies.contains_key(dependency) {
+                    return Err(ManifestError::UnknownFeatureDependency {
+                        toml: root_dir.join("Nargo.toml"),
+                        feature,
+                    });
+                }
+                dependency_features
+                    .entry(dependency.to_string())
+                    .or_default()
+                    .push(dep_feature.to_string());
+            } else if let Some(implied) = self.features.get(&feature) {
+                if enabled.insert(feature) {
+                    pending.extend(implied.iter().cloned());
+                }
+            } else {
+                return Err(ManifestError::UnknownFeature {
+                    toml: root_dir.join("Nargo.toml"),
+                    feature,
+                });
+            }
+        }
+
+        Ok((enabled, dependency_features))
+    }
 }
 
 /// Contains all the information about a package, as loaded from a `Nargo.toml`.
@@ -281,42 +367,105 @@ struct PackageMetadata {
 #[serde(untagged)]
 /// Enum representing the different types of ways to
 /// supply a source for the dependency
-enum DependencyConfig {
+enum DependencySourceConfig {
     Github { git: String, tag: String, directory: Option<String> },
     Path { path: String },
+    Registry { version: String },
+}
+
+/// A dependency along with the features it's compiled with.
+///
+/// A dependency can be given by its version alone, `foo = "1.2"` being short for
+/// `foo = { version = "1.2" }`.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(untagged)]
+enum DependencyConfig {
+    Version(String),
+    Detailed {
+        #[serde(flatten)]
+        source: DependencySourceConfig,
+        /// Features of the dependency to enable on top of its default ones.
+        #[serde(default)]
+        features: Vec<String>,
+        #[serde(default = "enabled", rename = "default-features")]
+        default_features: bool,
+    },
+}
+
+fn enabled() -> bool {
+    true
 }
 
 impl DependencyConfig {
+    fn features(&self) -> FeatureSelection {
+        match self {
+            Self::Version(_) => FeatureSelection::default(),
+            Self::Detailed { features, default_features, .. } => {
+                FeatureSelection { features: features.clone(), default_features: *default_features }
+            }
+        }
+    }
+
+    /// Resolves dependency `name` of package `dependent`, whose root is `pkg_root`, with
+    /// `features` enabled.
     fn resolve_to_dependency(
         &self,
+        name: &str,
+        dependent: &CrateName,
         pkg_root: &Path,
         processed: &mut Vec<String>,
+        registry: &mut Registry,
+        features: &FeatureSelection,
     ) -> Result<Dependency, ManifestError> {
-        let dep = match self {
-            Self::Github { git, tag, directory } => {
-                let dir_path = clone_git_repo(git, tag).map_err(ManifestError::GitError)?;
-                let project_path = if let Some(directory) = directory {
-                    let internal_path = dir_path.join(directory).normalize();
-                    if !internal_path.starts_with(&dir_path) {
-                        return Err(ManifestError::InvalidDirectory {
-                            toml: pkg_root.join("Nargo.toml"),
-                            directory: directory.into(),
-                        });
-                    }
-                    internal_path
-                } else {
-                    dir_path
-                };
-                let toml_path = project_path.join("Nargo.toml");
-                let package = resolve_package_from_toml(&toml_path, processed)?;
+        let source = match self {
+            Self::Version(version) => DependencySourceConfig::Registry { version: version.clone() },
+            Self::Detailed { source, .. } => source.clone(),
+        };
+
+        let dep = match source {
+            DependencySourceConfig::Github { git, tag, directory } => {
+                let package = resolve_git_dependency(
+                    &git,
+                    &tag,
+                    directory.as_deref(),
+                    pkg_root,
+                    processed,
+                    registry,
+                    features,
+                )?;
                 Dependency::Remote { package }
             }
-            Self::Path { path } => {
+            DependencySourceConfig::Path { path } => {
                 let dir_path = pkg_root.join(path);
                 let toml_path = dir_path.join("Nargo.toml");
-                let package = resolve_package_from_toml(&toml_path, processed)?;
+                let package = resolve_package_from_toml(&toml_path, processed, registry, features)?;
                 Dependency::Local { package }
             }
+            DependencySourceConfig::Registry { version } => {
+                let locked =
+                    registry.resolve(name, &version, dependent, &pkg_root.join("Nargo.toml"))?;
+                let package = match &locked.source {
+                    PackageSource::Git { git, tag, directory } => resolve_git_dependency(
+                        git,
+                        tag,
+                        directory.as_deref(),
+                        pkg_root,
+                        processed,
+                        registry,
+                        features,
+                    )?,
+                    PackageSource::Archive { archive, checksum } => {
+                        let dir_path = registry.unpack(&locked, archive, checksum)?;
+                        resolve_package_from_toml(
+                            &dir_path.join("Nargo.toml"),
+                            processed,
+                            registry,
+                            features,
+                        )?
+                    }
+                };
+                Dependency::Remote { package }
+            }
         };
 
         // Cannot depend on a binary
@@ -329,14 +478,47 @@ impl DependencyConfig {
     }
 }
 
+fn resolve_git_dependency(
+    git: &str,
+    tag: &str,
+    directory: Option<&str>,
+    pkg_root: &Path,
+    processed: &mut Vec<String>,
+    registry: &mut Registry,
+    features: &FeatureSelection,
+) -> Result<Package, ManifestError> {
+    let dir_path = clone_git_repo(git, tag).map_err(ManifestError::GitError)?;
+    let project_path = if let Some(directory) = directory {
+        let internal_path = dir_path.join(directory).normalize();
+        if !internal_path.starts_with(&dir_path) {
+            return Err(ManifestError::InvalidDirectory {
+                toml: pkg_root.join("Nargo.toml"),
+                directory: directory.into(),
+            });
+        }
+        internal_path
+    } else {
+        dir_path
+    };
+    let toml_path = project_path.join("Nargo.toml");
+    resolve_package_from_toml(&toml_path, processed, registry, features)
+}
+
 fn toml_to_workspace(
     nargo_toml: NargoToml,
     package_selection: PackageSelection,
+    features: &FeatureSelection,
+    registry: &mut Registry,
 ) -> Result<Workspace, ManifestError> {
     let mut resolved = Vec::new();
     let workspace = match nargo_toml.config {
         Config::Package { package_config } => {
-            let member = package_config.resolve_to_package(&nargo_toml.root_dir, &mut resolved)?;
+            let member = package_config.resolve_to_package(
+                &nargo_toml.root_dir,
+                &mut resolved,
+                registry,
+                features,
+            )?;
             match &package_selection {
                 PackageSelection::Selected(selected_name) if selected_name != &member.name => {
                     return Err(ManifestError::MissingSelectedPackage(member.name))
@@ -355,7 +537,12 @@ fn toml_to_workspace(
             for (index, member_path) in workspace_config.members.into_iter().enumerate() {
                 let package_root_dir = nargo_toml.root_dir.join(&member_path);
                 let package_toml_path = package_root_dir.join("Nargo.toml");
-                let member = resolve_package_from_toml(&package_toml_path, &mut resolved)?;
+                let member = resolve_package_from_toml(
+                    &package_toml_path,
+                    &mut resolved,
+                    registry,
+                    features,
+                )?;
 
                 match &package_selection {
                     PackageSelection::Selected(selected_name) => {
@@ -420,6 +607,8 @@ fn read_toml(toml_path: &Path) -> Result<NargoToml, ManifestError> {
 fn resolve_package_from_toml(
     toml_path: &Path,
     processed: &mut Vec<String>,
+    registry: &mut Registry,
+    features: &FeatureSelection,
 ) -> Result<Package, ManifestError> {
     // Checks for cyclic dependencies
     let str_path = toml_path.to_str().expect("ICE - path is empty");
@@ -444,7 +633,7 @@ fn resolve_package_from_toml(
 
     let result = match nargo_toml.config {
         Config::Package { package_config } => {
-            package_config.resolve_to_package(&nargo_toml.root_dir, processed)
+            package_config.resolve_to_package(&nargo_toml.root_dir, processed, registry, features)
         }
         Config::Workspace { .. } => {
             Err(ManifestError::UnexpectedWorkspace(toml_path.to_path_buf()))
@@ -468,15 +657,130 @@ pub fn resolve_workspace_from_toml(
     toml_path: &Path,
     package_selection: PackageSelection,
     current_compiler_version: Option<String>,
+) -> Result<Workspace, ManifestError> {
+    resolve_workspace_with_features(
+        toml_path,
+        package_selection,
+        &FeatureSelection::default(),
+        current_compiler_version,
+    )
+}
+
+/// Resolves a Nargo.toml file into a `Workspace` struct, enabling `features` in each of its
+/// selected packages.
+///
+/// Registry dependencies are resolved to the versions in the workspace's `Nargo.lock`, which is
+/// left untouched. See [resolve_workspace_and_write_lockfile] for recording newly selected versions.
+pub fn resolve_workspace_with_features(
+    toml_path: &Path,
+    package_selection: PackageSelection,
+    features: &FeatureSelection,
+    current_compiler_version: Option<String>,
+) -> Result<Workspace, ManifestError> {
+    resolve_workspace(toml_path, package_selection, features, current_compiler_version, false)
+}
+
+/// Resolves a Nargo.toml file into a `Workspace` struct in the same way as
+/// [resolve_workspace_with_features], then records the versions selected for its registry
+/// dependencies in the `Nargo.lock` at the root of the workspace.
+///
+/// This is meant for the commands which build the workspace, so that merely inspecting a
+/// project, for example from the language server, doesn't modify it.
+pub fn resolve_workspace_and_write_lockfile(
+    toml_path: &Path,
+    package_selection: PackageSelection,
+    features: &FeatureSelection,
+    current_compiler_version: Option<String>,
+) -> Result<Workspace, ManifestError> {
+    resolve_workspace(toml_path, package_selection, features, current_compiler_version, true)
+}
+
+fn resolve_workspace(
+    toml_path: &Path,
+    package_selection: PackageSelection,
+    features: &FeatureSelection,
+    current_compiler_version: Option<String>,
+    write_lockfile: bool,
 ) -> Result<Workspace, ManifestError> {
     let nargo_toml = read_toml(toml_path)?;
-    let workspace = toml_to_workspace(nargo_toml, package_selection)?;
+    let lockfile_path = lockfile_path(&nargo_toml.root_dir);
+    let lockfile = Lockfile::read(&lockfile_path)?;
+    let mut registry = Registry::new(registry_index(), lockfile.clone(), false);
+    let workspace = toml_to_workspace(nargo_toml, package_selection, features, &mut registry)?;
+    let new_lockfile = registry.into_lockfile();
+    if write_lockfile && new_lockfile != lockfile {
+        new_lockfile.write(&lockfile_path)?;
+    }
     if let Some(current_compiler_version) = current_compiler_version {
         semver::semver_check_workspace(&workspace, current_compiler_version)?;
     }
     Ok(workspace)
 }
 
+/// Returns the path of the `Nargo.lock` of the package or workspace in `root_dir`.
+///
+/// The members of a workspace share the lockfile at the root of the workspace, so that it is the
+/// same whether a member is built on its own or as part of the workspace.
+fn lockfile_path(root_dir: &Path) -> PathBuf {
+    let workspace_root = root_dir.ancestors().skip(1).find(|dir| {
+        let Ok(nargo_toml) = get_package_manifest(dir).and_then(|path| read_toml(&path)) else {
+            return false;
+        };
+        match nargo_toml.config {
+            Config::Workspace { workspace_config } => workspace_config
+                .members
+                .iter()
+                .any(|member| nargo_toml.root_dir.join(member).normalize() == root_dir),
+            Config::Package { .. } => false,
+        }
+    });
+    workspace_root.unwrap_or(root_dir).join(LOCKFILE_NAME)
+}
+
+/// Selects the latest versions of the registry dependencies of the workspace at `toml_path` which
+/// match their requirements, fetching the latest registry index, and writes them to its
+/// `Nargo.lock`. Only the versions of `packages` are updated if any are given.
+///
+/// Returns the dependencies whose version changed.
+pub fn update_workspace_lockfile(
+    toml_path: &Path,
+    packages: &[String],
+) -> Result<Vec<LockfileUpdate>, ManifestError> {
+    let nargo_toml = read_toml(toml_path)?;
+    let lockfile_path = lockfile_path(&nargo_toml.root_dir);
+    let lockfile = Lockfile::read(&lockfile_path)?;
+
+    let mut kept = lockfile.clone();
+    if packages.is_empty() {
+        kept.packages.clear();
+    } else {
+        for name in packages {
+            if !lockfile.packages.iter().any(|package| &package.name == name) {
+                return Err(ManifestError::MissingLockedPackage(name.clone()));
+            }
+        }
+        kept.packages.retain(|package| !packages.contains(&package.name));
+    }
+
+    let mut registry = Registry::new(registry_index(), kept, true);
+    toml_to_workspace(
+        nargo_toml,
+        PackageSelection::All,
+        &FeatureSelection::default(),
+        &mut registry,
+    )?;
+    let new_lockfile = registry.into_lockfile();
+    if new_lockfile != lockfile {
+        new_lockfile.write(&lockfile_path)?;
+    }
+
+    Ok(lockfile.changes(&new_lockfile))
+}
+
+fn registry_index() -> Option<String> {
+    std::env::var(REGISTRY_INDEX_ENV).ok()
+}
+
 #[test]
 fn parse_standard_toml() {
     let src = r#"
@@ -490,6 +794,8 @@ fn parse_standard_toml() {
         rand = { tag = "next", git = "https://github.com/rust-lang-nursery/rand"}
         cool = { tag = "next", git = "https://github.com/rust-lang-nursery/rand"}
         hello = {path = "./noir_driver"}
+        foo = "1.2"
+        bar = { version = "0.3.1" }
     "#;
 
     assert!(Config::try_from(String::from(src)).is_ok());
@@ -531,3 +837,64 @@ fn parse_workspace_default_member_toml() {
     assert!(Config::try_from(String::from(src)).is_ok());
     assert!(Config::try_from(src).is_ok());
 }
+
+#[test]
+fn resolve_package_features() {
+    let src = r#"
+        [package]
+        name = "test"
+        type = "lib"
+
+        [dependencies]
+        hello = { path = "./hello", features = ["fast"], default-features = false }
+
+        [features]
+        default = ["std"]
+        std = []
+        hash = ["hello/sha"]
+    "#;
+
+    let Config::Package { package_config } = Config::try_from(src).unwrap() else {
+        panic!("Expected a package manifest");
+    };
+    let resolve = |features: &[&str], default_features| {
+        let features = features.iter().map(|feature| feature.to_string()).collect();
+        package_config
+            .resolve_features(Path::new("."), &FeatureSelection { features, default_features })
+    };
+
+    let (enabled, dependency_features) = resolve(&["hash"], true).unwrap();
+    assert_eq!(enabled, BTreeSet::from(["default".to_string(), "hash".into(), "std".into()]));
+    assert_eq!(dependency_features["hello"], vec!["sha".to_string()]);
+
+    let (enabled, _) = resolve(&[], false).unwrap();
+    assert!(enabled.is_empty());
+
+    assert!(matches!(resolve(&["fast"], true), Err(ManifestError::UnknownFeature { .. })));
+    assert!(matches!(
+        resolve(&["other/fast"], true),
+        Err(ManifestError::UnknownFeatureDependency { .. })
+    ));
+
+    let hello = package_config.dependencies["hello"].features();
+    assert_eq!(hello, FeatureSelection { features: vec!["fast".into()], default_features: false });
+}
+
+#[test]
+fn members_share_the_lockfile_of_their_workspace() {
+    let workspace_dir = tempfile::tempdir().unwrap();
+    let workspace_dir = workspace_dir.path().canonicalize().unwrap();
+    let write_toml = |dir: &Path, contents: &str| {
+        std::fs::create_dir_all(dir).unwrap();
+        std::fs::write(dir.join("Nargo.toml"), contents).unwrap();
+    };
+    write_toml(&workspace_dir, "[workspace]\nmembers = [\"member\"]");
+    let member_dir = workspace_dir.join("member");
+    write_toml(&member_dir, "[package]\nname = \"member\"\ntype = \"bin\"");
+    let outside_dir = workspace_dir.join("outside");
+    write_toml(&outside_dir, "[package]\nname = \"outside\"\ntype = \"bin\"");
+
+    assert_eq!(lockfile_path(&workspace_dir), workspace_dir.join(LOCKFILE_NAME));
+    assert_eq!(lockfile_path(&member_dir), workspace_dir.join(LOCKFILE_NAME));
+    assert_eq!(lockfile_path(&outside_dir), outside_dir.join(LOCKFILE_NAME));
+}
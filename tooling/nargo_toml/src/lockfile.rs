@@ -0,0 +1,89 @@
+use std::{collections::BTreeMap, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::ManifestError;
+
+pub(crate) const LOCKFILE_NAME: &str = "Nargo.lock";
+
+const LOCKFILE_HEADER: &str =
+    "# This file is automatically generated by nargo.\n# It is not intended for manual editing.\n";
+
+/// The versions selected for the registry dependencies of a workspace, kept next to its
+/// `Nargo.toml` so that they only change when running `nargo update`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub(crate) struct Lockfile {
+    #[serde(default, rename = "package")]
+    pub(crate) packages: Vec<LockedPackage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub(crate) struct LockedPackage {
+    pub(crate) name: String,
+    pub(crate) version: String,
+    #[serde(flatten)]
+    pub(crate) source: PackageSource,
+}
+
+/// Where a version of a package published to a registry is fetched from.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub(crate) enum PackageSource {
+    Git {
+        git: String,
+        tag: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        directory: Option<String>,
+    },
+    /// A tarball published with `nargo publish`, whose path is relative to the root of the index.
+    Archive { archive: String, checksum: String },
+}
+
+/// A registry dependency whose version changed after running `nargo update`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockfileUpdate {
+    pub name: String,
+    /// The version in the lockfile before the update, `None` if the package wasn't locked.
+    pub previous_version: Option<String>,
+    /// The version in the lockfile after the update, `None` if the package is no longer used.
+    pub version: Option<String>,
+}
+
+impl Lockfile {
+    /// Reads the lockfile at `path`, which is empty if the file doesn't exist.
+    pub(crate) fn read(path: &Path) -> Result<Self, ManifestError> {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => toml::from_str(&contents)
+                .map_err(|err| ManifestError::MalformedLockfile(path.to_path_buf(), err)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(_) => Err(ManifestError::ReadFailed(path.to_path_buf())),
+        }
+    }
+
+    pub(crate) fn write(&self, path: &Path) -> Result<(), ManifestError> {
+        let contents = toml::to_string(self).expect("lockfile should serialize to TOML");
+        std::fs::write(path, format!("{LOCKFILE_HEADER}\n{contents}"))
+            .map_err(|_| ManifestError::WriteFailed(path.to_path_buf()))
+    }
+
+    /// Returns the packages whose version differs in `other`, sorted by name.
+    pub(crate) fn changes(&self, other: &Lockfile) -> Vec<LockfileUpdate> {
+        let mut versions: BTreeMap<&str, (Option<&str>, Option<&str>)> = BTreeMap::new();
+        for package in &self.packages {
+            versions.entry(&package.name).or_default().0 = Some(&package.version);
+        }
+        for package in &other.packages {
+            versions.entry(&package.name).or_default().1 = Some(&package.version);
+        }
+
+        versions
+            .into_iter()
+            .filter(|(_, (previous_version, version))| previous_version != version)
+            .map(|(name, (previous_version, version))| LockfileUpdate {
+                name: name.to_string(),
+                previous_version: previous_version.map(str::to_string),
+                version: version.map(str::to_string),
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,151 @@
+use std::path::{Path, PathBuf};
+
+use nargo::package::{Dependency, Package, PackageType};
+
+use crate::{
+    archive::{checksum, pack_package},
+    lockfile::PackageSource,
+    registry::{read_index_file, IndexEntry},
+    registry_index, ManifestError,
+};
+
+/// A version of a package written to a registry index by [publish_package].
+#[derive(Debug, Clone)]
+pub struct PublishedPackage {
+    pub name: String,
+    pub version: String,
+    /// The path of the tarball of the package, relative to the root of the index.
+    pub archive: String,
+    /// The hex encoded SHA-256 hash of the tarball.
+    pub checksum: String,
+}
+
+/// Publishes library `package` to the registry index in directory `registry`, or the one set in the
+/// environment if `None`: its tarball is written to the index and added to the versions of the
+/// package. Nothing is written if `dry_run` is set.
+///
+/// A registry index cloned from a git repository must be committed and pushed afterwards for the
+/// new version to be available to others.
+pub fn publish_package(
+    package: &Package,
+    registry: Option<&Path>,
+    dry_run: bool,
+) -> Result<PublishedPackage, ManifestError> {
+    if package.package_type != PackageType::Library {
+        return Err(ManifestError::PublishNonLibrary(package.name.clone(), package.package_type));
+    }
+    let Some(version) = package.version.clone() else {
+        return Err(ManifestError::MissingVersionField { toml: package.root_dir.join("Nargo.toml") });
+    };
+    // Path dependencies can't be fetched by the dependents of the package
+    for (dependency, dep) in &package.dependencies {
+        if let Dependency::Local { .. } = dep {
+            return Err(ManifestError::PublishPathDependency {
+                package: package.name.clone(),
+                dependency: dependency.to_string(),
+            });
+        }
+    }
+
+    let index_dir = match (registry, registry_index()) {
+        (Some(registry), _) => registry.to_path_buf(),
+        (None, Some(index)) if Path::new(&index).is_dir() => PathBuf::from(index),
+        (None, Some(index)) => return Err(ManifestError::RemotePublishRegistry(index)),
+        (None, None) => return Err(ManifestError::MissingPublishRegistry),
+    };
+
+    let name = package.name.to_string();
+    let mut index_file = read_index_file(&index_dir, &name)?.unwrap_or_default();
+    if index_file.versions.iter().any(|entry| entry.version == version) {
+        return Err(ManifestError::VersionAlreadyPublished { name: package.name.clone(), version });
+    }
+
+    let archive = pack_package(&package.root_dir)?;
+    let published = PublishedPackage {
+        archive: format!("archives/{name}/{name}-{version}.tar.gz"),
+        checksum: checksum(&archive),
+        name,
+        version,
+    };
+    if dry_run {
+        return Ok(published);
+    }
+
+    let archive_path = index_dir.join(&published.archive);
+    std::fs::create_dir_all(archive_path.parent().expect("archive path has a parent"))
+        .and_then(|_| std::fs::write(&archive_path, archive))
+        .map_err(|_| ManifestError::WriteFailed(archive_path))?;
+
+    index_file.versions.push(IndexEntry {
+        version: published.version.clone(),
+        source: PackageSource::Archive {
+            archive: published.archive.clone(),
+            checksum: published.checksum.clone(),
+        },
+        yanked: false,
+    });
+    let index_path = index_dir.join(&published.name).with_extension("toml");
+    let contents = toml::to_string(&index_file).expect("index entry should serialize to TOML");
+    std::fs::write(&index_path, contents).map_err(|_| ManifestError::WriteFailed(index_path))?;
+
+    Ok(published)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{BTreeMap, BTreeSet},
+        path::Path,
+        str::FromStr,
+    };
+
+    use noirc_frontend::graph::CrateName;
+
+    use super::*;
+    use crate::{lockfile::Lockfile, registry::Registry};
+
+    fn library(root_dir: &Path) -> Package {
+        std::fs::create_dir_all(root_dir.join("src")).unwrap();
+        std::fs::write(root_dir.join("Nargo.toml"), "[package]\nname = \"foo\"\ntype = \"lib\"")
+            .unwrap();
+        std::fs::write(root_dir.join("src/lib.nr"), "fn foo() {}").unwrap();
+        Package {
+            version: Some("1.0.0".to_string()),
+            compiler_required_version: None,
+            root_dir: root_dir.to_path_buf(),
+            package_type: PackageType::Library,
+            entry_path: root_dir.join("src/lib.nr"),
+            name: CrateName::from_str("foo").unwrap(),
+            dependencies: BTreeMap::new(),
+            features: BTreeSet::new(),
+        }
+    }
+
+    #[test]
+    fn publishes_to_local_index() {
+        let package_dir = tempfile::tempdir().unwrap();
+        let index_dir = tempfile::tempdir().unwrap();
+        let package = library(package_dir.path());
+
+        let dry_run = publish_package(&package, Some(index_dir.path()), true).unwrap();
+        assert!(!index_dir.path().join("foo.toml").exists());
+
+        let published = publish_package(&package, Some(index_dir.path()), false).unwrap();
+        assert_eq!(published.checksum, dry_run.checksum);
+        assert!(index_dir.path().join(&published.archive).exists());
+
+        let index = Some(index_dir.path().to_str().unwrap().to_string());
+        let mut registry = Registry::new(index, Lockfile::default(), false);
+        let locked = registry.latest_version("foo", Some("1")).unwrap();
+        assert_eq!(locked.version, "1.0.0");
+        assert_eq!(
+            locked.source,
+            PackageSource::Archive { archive: published.archive, checksum: published.checksum }
+        );
+
+        assert!(matches!(
+            publish_package(&package, Some(index_dir.path()), false),
+            Err(ManifestError::VersionAlreadyPublished { .. })
+        ));
+    }
+}
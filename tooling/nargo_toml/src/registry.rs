@@ -0,0 +1,337 @@
+//! Resolution of the dependencies given by a version requirement, e.g. `foo = "1.2"`, against a
+//! registry index.
+//!
+//! The index is a git repository, or a local directory, holding a `<package name>.toml` file for
+//! each package which lists its published versions and where to fetch each of them from, either a
+//! git tag or a tarball published to the index with `nargo publish`:
+//!
+//! ```toml
+//! [[versions]]
+//! version = "1.2.0"
+//! git = "https://github.com/example/foo"
+//! tag = "v1.2.0"
+//!
+//! [[versions]]
+//! version = "1.3.0"
+//! archive = "archives/foo/foo-1.3.0.tar.gz"
+//! checksum = "5c9f..."
+//! ```
+//!
+//! A single version of each package is used across the workspace. It is selected when the package
+//! is first required, preferring the version in the lockfile, so a later requirement which that
+//! version doesn't match is reported as a conflict.
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use fm::NormalizePath;
+use noirc_frontend::graph::CrateName;
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    archive::unpack_archive,
+    git::fetch_registry_index,
+    lockfile::{LockedPackage, Lockfile, PackageSource},
+    ManifestError,
+};
+
+/// The environment variable holding the URL of the registry index, or the path to a local one.
+pub(crate) const REGISTRY_INDEX_ENV: &str = "NARGO_REGISTRY_INDEX";
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub(crate) struct IndexFile {
+    #[serde(default)]
+    pub(crate) versions: Vec<IndexEntry>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct IndexEntry {
+    pub(crate) version: String,
+    #[serde(flatten)]
+    pub(crate) source: PackageSource,
+    /// Yanked versions are only used if they are already in the lockfile.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) yanked: bool,
+}
+
+/// The version selected for a package, along with the first requirement it was selected for.
+struct Selection {
+    package: LockedPackage,
+    requirement: String,
+    dependent: String,
+}
+
+pub(crate) struct Registry {
+    /// The URL of the index or the path to a local one.
+    index: Option<String>,
+    /// The local directory of the index, only fetched once a package needs it.
+    index_dir: Option<PathBuf>,
+    /// Whether to fetch the latest commit of an index which was already cloned.
+    update_index: bool,
+    locked: BTreeMap<String, LockedPackage>,
+    selected: BTreeMap<String, Selection>,
+}
+
+impl Registry {
+    pub(crate) fn new(index: Option<String>, lockfile: Lockfile, update_index: bool) -> Self {
+        let locked =
+            lockfile.packages.into_iter().map(|package| (package.name.clone(), package)).collect();
+        Self { index, index_dir: None, update_index, locked, selected: BTreeMap::new() }
+    }
+
+    /// Returns the version of package `name` to use for `dependent`, which requires `requirement`
+    /// in the manifest at `toml`.
+    pub(crate) fn resolve(
+        &mut self,
+        name: &str,
+        requirement: &str,
+        dependent: &CrateName,
+        toml: &Path,
+    ) -> Result<LockedPackage, ManifestError> {
+        let version_req = VersionReq::parse(requirement).map_err(|err| {
+            ManifestError::InvalidVersionRequirement {
+                toml: toml.to_path_buf(),
+                name: name.to_string(),
+                requirement: requirement.to_string(),
+                error: err.to_string(),
+            }
+        })?;
+
+        if let Some(selection) = self.selected.get(name) {
+            return if matches_version(&version_req, &selection.package.version) {
+                Ok(selection.package.clone())
+            } else {
+                Err(ManifestError::VersionConflict {
+                    name: name.to_string(),
+                    version: selection.package.version.clone(),
+                    requirement: requirement.to_string(),
+                    dependent: dependent.to_string(),
+                    previous_requirement: selection.requirement.clone(),
+                    previous_dependent: selection.dependent.clone(),
+                })
+            };
+        }
+
+        let package = match self.locked.get(name) {
+            Some(locked) if matches_version(&version_req, &locked.version) => locked.clone(),
+            _ => self.query_index(name, &version_req, requirement)?,
+        };
+        self.selected.insert(
+            name.to_string(),
+            Selection {
+                package: package.clone(),
+                requirement: requirement.to_string(),
+                dependent: dependent.to_string(),
+            },
+        );
+        Ok(package)
+    }
+
+    /// Returns the directory holding the sources of `package` once its tarball `archive` is
+    /// unpacked.
+    pub(crate) fn unpack(
+        &mut self,
+        package: &LockedPackage,
+        archive: &str,
+        checksum: &str,
+    ) -> Result<PathBuf, ManifestError> {
+        let index_dir = self.index_dir(&package.name)?.normalize();
+        let archive_path = index_dir.join(archive).normalize();
+        if !archive_path.starts_with(&index_dir) {
+            return Err(ManifestError::InvalidArchivePath {
+                name: package.name.clone(),
+                archive: archive.into(),
+            });
+        }
+        unpack_archive(&archive_path, checksum, &package.name, &package.version)
+    }
+
+    /// Returns the latest version of package `name` in the index which matches `requirement`, or
+    /// the latest version at all if there is no requirement.
+    pub(crate) fn latest_version(
+        &mut self,
+        name: &str,
+        requirement: Option<&str>,
+    ) -> Result<LockedPackage, ManifestError> {
+        let version_req = match requirement {
+            Some(requirement) => VersionReq::parse(requirement).map_err(|err| {
+                ManifestError::InvalidVersionRequirement {
+                    toml: PathBuf::from("Nargo.toml"),
+                    name: name.to_string(),
+                    requirement: requirement.to_string(),
+                    error: err.to_string(),
+                }
+            })?,
+            None => VersionReq::STAR,
+        };
+        self.query_index(name, &version_req, requirement.unwrap_or("*"))
+    }
+
+    /// Returns the lockfile holding the versions which were selected.
+    pub(crate) fn into_lockfile(self) -> Lockfile {
+        Lockfile {
+            packages: self.selected.into_values().map(|selection| selection.package).collect(),
+        }
+    }
+
+    /// Returns the latest version of package `name` in the index which matches `version_req`.
+    fn query_index(
+        &mut self,
+        name: &str,
+        version_req: &VersionReq,
+        requirement: &str,
+    ) -> Result<LockedPackage, ManifestError> {
+        let index_dir = self.index_dir(name)?;
+        let index_file = read_index_file(&index_dir, name)?
+            .ok_or_else(|| ManifestError::MissingRegistryPackage(name.to_string()))?;
+
+        index_file
+            .versions
+            .into_iter()
+            .filter(|entry| !entry.yanked)
+            .filter_map(|entry| Some((Version::parse(&entry.version).ok()?, entry)))
+            .filter(|(version, _)| version_req.matches(version))
+            .max_by(|(a, _), (b, _)| a.cmp(b))
+            .map(|(_, entry)| LockedPackage {
+                name: name.to_string(),
+                version: entry.version,
+                source: entry.source,
+            })
+            .ok_or_else(|| ManifestError::NoMatchingVersion {
+                name: name.to_string(),
+                requirement: requirement.to_string(),
+            })
+    }
+
+    /// Returns the local directory of the index, fetching it if needed. `name` is the package
+    /// which needs it, for error messages.
+    fn index_dir(&mut self, name: &str) -> Result<PathBuf, ManifestError> {
+        if let Some(index_dir) = &self.index_dir {
+            return Ok(index_dir.clone());
+        }
+
+        let Some(index) = &self.index else {
+            return Err(ManifestError::MissingRegistryIndex(name.to_string()));
+        };
+        let index_dir = if Path::new(index).is_dir() {
+            PathBuf::from(index)
+        } else {
+            fetch_registry_index(index, self.update_index).map_err(ManifestError::GitError)?
+        };
+        self.index_dir = Some(index_dir.clone());
+        Ok(index_dir)
+    }
+}
+
+/// Reads the entry of package `name` in the index at `index_dir`, `None` if there is none.
+pub(crate) fn read_index_file(
+    index_dir: &Path,
+    name: &str,
+) -> Result<Option<IndexFile>, ManifestError> {
+    let path = index_dir.join(name).with_extension("toml");
+    let index_file = match std::fs::read_to_string(&path) {
+        Ok(index_file) => index_file,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(_) => return Err(ManifestError::ReadFailed(path)),
+    };
+    toml::from_str(&index_file)
+        .map(Some)
+        .map_err(|error| ManifestError::MalformedRegistryEntry { name: name.to_string(), error })
+}
+
+fn matches_version(version_req: &VersionReq, version: &str) -> bool {
+    Version::parse(version).map_or(false, |version| version_req.matches(&version))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    const FOO_INDEX: &str = r#"
+        [[versions]]
+        version = "1.0.0"
+        git = "https://github.com/example/foo"
+        tag = "v1.0.0"
+
+        [[versions]]
+        version = "1.2.0"
+        git = "https://github.com/example/foo"
+        tag = "v1.2.0"
+
+        [[versions]]
+        version = "1.3.0"
+        git = "https://github.com/example/foo"
+        tag = "v1.3.0"
+        yanked = true
+
+        [[versions]]
+        version = "2.0.0"
+        git = "https://github.com/example/foo"
+        tag = "v2.0.0"
+    "#;
+
+    fn local_registry(index_dir: &Path, lockfile: Lockfile) -> Registry {
+        std::fs::write(index_dir.join("foo.toml"), FOO_INDEX).unwrap();
+        Registry::new(Some(index_dir.to_str().unwrap().to_string()), lockfile, false)
+    }
+
+    fn resolve(registry: &mut Registry, requirement: &str) -> Result<String, ManifestError> {
+        let dependent = CrateName::from_str("bar").unwrap();
+        let package = registry.resolve("foo", requirement, &dependent, Path::new("Nargo.toml"))?;
+        Ok(package.version)
+    }
+
+    #[test]
+    fn selects_latest_matching_version() {
+        let index_dir = tempfile::tempdir().unwrap();
+
+        let mut registry = local_registry(index_dir.path(), Lockfile::default());
+        assert_eq!(resolve(&mut registry, "1").unwrap(), "1.2.0");
+
+        let mut registry = local_registry(index_dir.path(), Lockfile::default());
+        assert_eq!(resolve(&mut registry, ">=1.0").unwrap(), "2.0.0");
+
+        let mut registry = local_registry(index_dir.path(), Lockfile::default());
+        assert!(matches!(
+            resolve(&mut registry, "3"),
+            Err(ManifestError::NoMatchingVersion { .. })
+        ));
+    }
+
+    #[test]
+    fn prefers_locked_version() {
+        let index_dir = tempfile::tempdir().unwrap();
+        let locked = LockedPackage {
+            name: "foo".to_string(),
+            version: "1.0.0".to_string(),
+            source: PackageSource::Git {
+                git: "https://github.com/example/foo".to_string(),
+                tag: "v1.0.0".to_string(),
+                directory: None,
+            },
+        };
+        let lockfile = Lockfile { packages: vec![locked] };
+
+        let mut registry = local_registry(index_dir.path(), lockfile.clone());
+        assert_eq!(resolve(&mut registry, "1").unwrap(), "1.0.0");
+        assert_eq!(registry.into_lockfile(), lockfile);
+
+        // The locked version is ignored if the requirement changed
+        let mut registry = local_registry(index_dir.path(), lockfile.clone());
+        assert_eq!(resolve(&mut registry, "2").unwrap(), "2.0.0");
+    }
+
+    #[test]
+    fn reports_conflicting_requirements() {
+        let index_dir = tempfile::tempdir().unwrap();
+
+        let mut registry = local_registry(index_dir.path(), Lockfile::default());
+        assert_eq!(resolve(&mut registry, "1").unwrap(), "1.2.0");
+        assert_eq!(resolve(&mut registry, "~1.2").unwrap(), "1.2.0");
+        assert!(matches!(resolve(&mut registry, "2"), Err(ManifestError::VersionConflict { .. })));
+    }
+}
@@ -70,7 +70,11 @@ fn strip_build_meta_data(version: &Version) -> String {
 
 #[cfg(test)]
 mod tests {
-    use std::{collections::BTreeMap, path::PathBuf, str::FromStr};
+    use std::{
+        collections::{BTreeMap, BTreeSet},
+        path::PathBuf,
+        str::FromStr,
+    };
 
     use nargo::package::PackageType;
     use noirc_frontend::graph::CrateName;
@@ -88,6 +92,7 @@ mod tests {
             entry_path: PathBuf::new(),
             name: CrateName::from_str("test").unwrap(),
             dependencies: BTreeMap::new(),
+            features: BTreeSet::new(),
             version: Some("1.0".to_string()),
         };
         if let Err(err) = semver_check_package(&package, &compiler_version) {
@@ -119,6 +124,7 @@ mod tests {
             entry_path: PathBuf::new(),
             name: CrateName::from_str("test").unwrap(),
             dependencies: BTreeMap::new(),
+            features: BTreeSet::new(),
             version: Some("1.0".to_string()),
         };
 
@@ -129,6 +135,7 @@ mod tests {
             entry_path: PathBuf::new(),
             name: CrateName::from_str("good_dependency").unwrap(),
             dependencies: BTreeMap::new(),
+            features: BTreeSet::new(),
             version: Some("1.0".to_string()),
         };
         let invalid_dependency = Package {
@@ -138,6 +145,7 @@ mod tests {
             entry_path: PathBuf::new(),
             name: CrateName::from_str("bad_dependency").unwrap(),
             dependencies: BTreeMap::new(),
+            features: BTreeSet::new(),
             version: Some("1.0".to_string()),
         };
 
@@ -178,6 +186,7 @@ mod tests {
             entry_path: PathBuf::new(),
             name: CrateName::from_str("test").unwrap(),
             dependencies: BTreeMap::new(),
+            features: BTreeSet::new(),
             version: Some("1.0".to_string()),
         };
 
@@ -197,6 +206,7 @@ mod tests {
             entry_path: PathBuf::new(),
             name: CrateName::from_str("test").unwrap(),
             dependencies: BTreeMap::new(),
+            features: BTreeSet::new(),
             version: Some("1.0".to_string()),
         };
 
@@ -64,6 +64,8 @@ function abiTypeToTs(type: AbiType, primitiveTypeMap: Map<string, PrimitiveTypes
       // The array would need to be `as const` to support this whereas that's unlikely to happen in user code.
       // return `FixedLengthArray<${abiTypeToTs(type.type, primitiveTypeMap)}, ${type.length}>`;
       return `${abiTypeToTs(type.type, primitiveTypeMap)}[]`;
+    case 'vec':
+      return `${abiTypeToTs(type.type, primitiveTypeMap)}[]`;
     case 'string':
       // We could enforce that literals are the correct length but not generally.
       // This would run into similar problems to above.
@@ -117,7 +119,7 @@ function generateStructInterfaces(
 ) {
   // Edge case to handle the array of structs case.
   if (
-    type.kind === 'array' &&
+    (type.kind === 'array' || type.kind === 'vec') &&
     type.type.kind === 'struct' &&
     !structsEncountered.has(getLastComponentOfPath(type.type.path))
   ) {
@@ -0,0 +1,263 @@
+//! Generates Starknet verifier contracts for a program, written in Cairo.
+//!
+//! The contract's `verify` function takes a proof and the program's inputs struct. As Cairo arrays
+//! can only be appended to, the public inputs are encoded in the order of their positions. The
+//! verification of the proof itself is a stub to be filled in with the backend's Cairo verifier.
+use std::fmt::Write;
+
+use crate::{
+    public_inputs::{
+        access_input, guard_conditions, power_of_two_integer_bits, write_inputs_struct, Collection,
+        Element, Guards, Language, PackedElement, GENERATED_HEADER,
+    },
+    Abi, AbiType, Sign,
+};
+
+struct Cairo;
+
+impl Language for Cairo {
+    const RETURN_VALUE_NAME: &'static str = "return_value";
+
+    fn index(access: &str, index: u64) -> String {
+        format!("{access}.at({index})")
+    }
+
+    fn length(access: &str) -> String {
+        format!("{access}.len()")
+    }
+
+    fn scalar_type(typ: &AbiType) -> String {
+        match typ {
+            AbiType::Boolean => "bool".to_string(),
+            AbiType::Integer { sign: Sign::Unsigned, width } => {
+                format!("u{}", power_of_two_integer_bits(*width))
+            }
+            AbiType::Integer { sign: Sign::Signed, width } => {
+                format!("i{}", power_of_two_integer_bits(*width))
+            }
+            _ => "u256".to_string(),
+        }
+    }
+
+    fn string_type(_length: u64) -> String {
+        "Array<u8>".to_string()
+    }
+
+    fn collection_type(element: String, _length: Option<u64>) -> String {
+        format!("Array<{element}>")
+    }
+
+    fn write_struct(source: &mut String, name: &str, fields: &[(String, String)]) {
+        writeln!(source, "#[derive(Drop, Serde)]").unwrap();
+        writeln!(source, "struct {name} {{").unwrap();
+        for (field_name, typ) in fields {
+            writeln!(source, "    {field_name}: {typ},").unwrap();
+        }
+        writeln!(source, "}}").unwrap();
+        writeln!(source).unwrap();
+    }
+}
+
+impl Abi {
+    /// Returns the source of a Starknet contract verifying proofs of the program against
+    /// `verification_key`, as written by the backend.
+    pub fn to_cairo_verifier(&self, verification_key: &[u8]) -> String {
+        let mut source = String::new();
+        writeln!(source, "{GENERATED_HEADER}").unwrap();
+        writeln!(source).unwrap();
+
+        let layout = write_inputs_struct::<Cairo>(self, &mut source);
+
+        source.push_str(CONTRACT);
+        writeln!(source).unwrap();
+
+        // Cairo has no byte array literals, so the key is written in words of 32 bytes.
+        writeln!(
+            source,
+            "/// The verification key of the program, as written by the backend, in big-endian words"
+        )
+        .unwrap();
+        writeln!(source, "/// of 32 bytes. The last word is padded with zeroes.").unwrap();
+        writeln!(source, "fn verification_key() -> Array<u256> {{").unwrap();
+        writeln!(source, "    array![").unwrap();
+        for chunk in verification_key.chunks(32) {
+            let mut word = [0; 32];
+            word[..chunk.len()].copy_from_slice(chunk);
+            let word: String = word.iter().map(|byte| format!("{byte:02x}")).collect();
+            writeln!(source, "        0x{word},").unwrap();
+        }
+        writeln!(source, "    ]").unwrap();
+        writeln!(source, "}}").unwrap();
+        writeln!(source).unwrap();
+        writeln!(source, "const VERIFICATION_KEY_LENGTH: u32 = {};", verification_key.len())
+            .unwrap();
+        writeln!(source).unwrap();
+
+        writeln!(source, "/// Encodes `inputs` into the public inputs of the verifier.").unwrap();
+        writeln!(source, "fn encode(inputs: @Inputs) -> Array<u256> {{").unwrap();
+        for check in &layout.checks {
+            let access = access_input::<Cairo>(&check.path);
+            let (condition, message) = match check.collection {
+                Collection::String | Collection::Array => (
+                    format!("{access}.len() == {}", check.length),
+                    format!("Expected {access} to have a length of {}", check.length),
+                ),
+                Collection::Vec => (
+                    format!("{access}.len() <= {}", check.length),
+                    format!("Expected {access} to have at most {} values", check.length),
+                ),
+            };
+            let statement = format!("assert!({condition}, \"{message}\");");
+            if check.guards.is_empty() {
+                writeln!(source, "    {statement}").unwrap();
+            } else {
+                writeln!(source, "    if {} {{", guard_condition(&check.guards)).unwrap();
+                writeln!(source, "        {statement}").unwrap();
+                writeln!(source, "    }}").unwrap();
+            }
+        }
+        writeln!(source, "    let mut public_inputs = ArrayTrait::new();").unwrap();
+        for element in layout.elements_by_position() {
+            let value = match element {
+                Some(element) if element.guards.is_empty() => encode_element(element),
+                Some(element) => format!(
+                    "if {} {{ {} }} else {{ 0 }}",
+                    guard_condition(&element.guards),
+                    encode_element(element)
+                ),
+                // The placeholder for the `empty_value` of a vector
+                None => "0".to_string(),
+            };
+            writeln!(source, "    public_inputs.append({value});").unwrap();
+        }
+        writeln!(source, "    public_inputs").unwrap();
+        writeln!(source, "}}").unwrap();
+
+        source.push_str(HELPERS);
+        source
+    }
+}
+
+const CONTRACT: &str = r#"#[starknet::interface]
+trait IVerifier<TContractState> {
+    fn verify(self: @TContractState, proof: Array<u256>, inputs: Inputs) -> bool;
+}
+
+#[starknet::contract]
+mod Verifier {
+    use super::{Inputs, encode, verification_key, verify_proof};
+
+    #[storage]
+    struct Storage {}
+
+    #[abi(embed_v0)]
+    impl VerifierImpl of super::IVerifier<ContractState> {
+        fn verify(self: @ContractState, proof: Array<u256>, inputs: Inputs) -> bool {
+            verify_proof(verification_key(), proof, encode(@inputs))
+        }
+    }
+}
+"#;
+
+const HELPERS: &str = r#"
+const FELT252_MODULUS: u256 = 0x800000000000011000000000000000000000000000000000000000000000001;
+
+/// Returns the two's complement of the signed integer `value` modulo `modulus`.
+fn signed_to_field(value: felt252, modulus: u256) -> u256 {
+    let value: u256 = value.into();
+    // Negative values are represented as the felt252 modulus minus their absolute value.
+    if value < FELT252_MODULUS / 2 {
+        value
+    } else {
+        modulus - (FELT252_MODULUS - value)
+    }
+}
+
+/// Verifies `proof` against `verification_key`, to be implemented with the verifier library of
+/// the backend.
+fn verify_proof(
+    _verification_key: Array<u256>, _proof: Array<u256>, _public_inputs: Array<u256>
+) -> bool {
+    panic!("proof verification is not implemented")
+}
+"#;
+
+/// Returns the condition that each vector in `guards` is long enough.
+fn guard_condition(guards: &Guards) -> String {
+    guard_conditions::<Cairo>(guards).join(" && ")
+}
+
+/// Returns the `u256` encoding of `element`.
+fn encode_element(element: &PackedElement) -> String {
+    let access = access_input::<Cairo>(&element.path);
+    match &element.element {
+        Element::Scalar(AbiType::Boolean) => format!("if *{access} {{ 1 }} else {{ 0 }}"),
+        Element::Scalar(AbiType::Integer { sign: Sign::Unsigned, .. }) => {
+            format!("(*{access}).into()")
+        }
+        Element::Scalar(AbiType::Integer { sign: Sign::Signed, width }) => {
+            // Signed integers are encoded as their two's complement in `width` bits.
+            let modulus = match 1u128.checked_shl(*width) {
+                Some(modulus) => format!("{modulus:#x}"),
+                None => format!("0x1{}", "0".repeat(32)),
+            };
+            format!("signed_to_field((*{access}).into(), {modulus})")
+        }
+        Element::Scalar(_) => format!("*{access}"),
+        Element::Byte(index) => format!("(*{access}.at({index})).into()"),
+        Element::Length => format!("{access}.len().into()"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use acvm::acir::native_types::Witness;
+
+    use crate::{Abi, AbiParameter, AbiType, AbiVisibility, Sign};
+
+    #[test]
+    fn appends_public_inputs_in_witness_order() {
+        let abi = Abi {
+            parameters: vec![
+                AbiParameter {
+                    name: "values".to_string(),
+                    typ: AbiType::Vec {
+                        max_length: 1,
+                        typ: Box::new(AbiType::Integer { sign: Sign::Signed, width: 8 }),
+                    },
+                    visibility: AbiVisibility::Public,
+                },
+                AbiParameter {
+                    name: "flag".to_string(),
+                    typ: AbiType::Boolean,
+                    visibility: AbiVisibility::Public,
+                },
+            ],
+            // The vector's value, length and `empty_value` follow the flag
+            param_witnesses: BTreeMap::from([
+                ("values".to_string(), vec![(Witness(2)..Witness(5))]),
+                ("flag".to_string(), vec![(Witness(1)..Witness(2))]),
+            ]),
+            return_type: None,
+            return_witnesses: Vec::new(),
+        };
+
+        let source = abi.to_cairo_verifier(&[0xab; 33]);
+
+        assert!(source.contains(&format!("        0x{},\n", "ab".repeat(32))));
+        assert!(source.contains(&format!("        0xab{},\n", "0".repeat(62))));
+        assert!(source.contains("    values: Array<i8>,\n    flag: bool,\n"));
+        assert!(source.contains(
+            "assert!(inputs.values.len() <= 1, \"Expected inputs.values to have at most 1 values\");"
+        ));
+        assert!(source.contains(
+            "    public_inputs.append(if *inputs.flag { 1 } else { 0 });\n    \
+             public_inputs.append(if inputs.values.len() > 0 { \
+             signed_to_field((*inputs.values.at(0)).into(), 0x100) } else { 0 });\n    \
+             public_inputs.append(inputs.values.len().into());\n    \
+             public_inputs.append(0);\n"
+        ));
+    }
+}
@@ -0,0 +1,258 @@
+//! Generates CosmWasm verifier contracts for a program.
+//!
+//! The contract answers a `verify` query holding a proof and the program's inputs struct, whose
+//! values are written into a vector of big-endian field elements at their positions in the public
+//! inputs. The verification of the proof itself is a stub returning an error until it is filled in
+//! with the backend's Rust verifier.
+use std::fmt::Write;
+
+use iter_extended::vecmap;
+
+use crate::{
+    public_inputs::{
+        access_input, guard_conditions, power_of_two_integer_bits, write_inputs_struct, Collection,
+        Element, Guards, Language, PackedElement, GENERATED_HEADER,
+    },
+    Abi, AbiType, Sign,
+};
+
+struct CosmWasm;
+
+impl Language for CosmWasm {
+    const RETURN_VALUE_NAME: &'static str = "return_value";
+
+    fn index(access: &str, index: u64) -> String {
+        format!("{access}[{index}]")
+    }
+
+    fn length(access: &str) -> String {
+        format!("{access}.len()")
+    }
+
+    fn scalar_type(typ: &AbiType) -> String {
+        match typ {
+            AbiType::Boolean => "bool".to_string(),
+            AbiType::Integer { sign: Sign::Unsigned, width } => {
+                format!("u{}", power_of_two_integer_bits(*width))
+            }
+            AbiType::Integer { sign: Sign::Signed, width } => {
+                format!("i{}", power_of_two_integer_bits(*width))
+            }
+            _ => "Uint256".to_string(),
+        }
+    }
+
+    fn string_type(_length: u64) -> String {
+        "String".to_string()
+    }
+
+    // Arrays are given as vectors as serde only supports arrays of up to 32 values.
+    fn collection_type(element: String, _length: Option<u64>) -> String {
+        format!("Vec<{element}>")
+    }
+
+    fn write_struct(source: &mut String, name: &str, fields: &[(String, String)]) {
+        writeln!(source, "#[cw_serde]").unwrap();
+        writeln!(source, "pub struct {name} {{").unwrap();
+        for (field_name, typ) in fields {
+            writeln!(source, "    pub {field_name}: {typ},").unwrap();
+        }
+        writeln!(source, "}}").unwrap();
+        writeln!(source).unwrap();
+    }
+}
+
+impl Abi {
+    /// Returns the source of a CosmWasm contract verifying proofs of the program against
+    /// `verification_key`, as written by the backend.
+    pub fn to_cosmwasm_verifier(&self, verification_key: &[u8]) -> String {
+        let mut source = String::new();
+        writeln!(source, "{GENERATED_HEADER}").unwrap();
+        writeln!(source, "use cosmwasm_schema::cw_serde;").unwrap();
+        writeln!(
+            source,
+            "use cosmwasm_std::{{entry_point, to_json_binary, Binary, Deps, Env, StdError, StdResult, Uint256}};"
+        )
+        .unwrap();
+        writeln!(source).unwrap();
+
+        writeln!(source, "/// The verification key of the program, as written by the backend.")
+            .unwrap();
+        writeln!(source, "pub const VERIFICATION_KEY: &[u8] = &[").unwrap();
+        for chunk in verification_key.chunks(16) {
+            let bytes = vecmap(chunk, |byte| format!("0x{byte:02x}"));
+            writeln!(source, "    {},", bytes.join(", ")).unwrap();
+        }
+        writeln!(source, "];").unwrap();
+        writeln!(source).unwrap();
+        // The structs follow the constants, but declaring them gives the number of public inputs.
+        let mut structs = String::new();
+        let layout = write_inputs_struct::<CosmWasm>(self, &mut structs);
+        writeln!(source, "pub const NUM_PUBLIC_INPUTS: usize = {};", layout.num_public_inputs)
+            .unwrap();
+        writeln!(source).unwrap();
+        source.push_str(&structs);
+
+        source.push_str(QUERY_ENTRY_POINT);
+        writeln!(source).unwrap();
+
+        writeln!(
+            source,
+            "/// Encodes `inputs` into the public inputs of the verifier, as big-endian field elements."
+        )
+        .unwrap();
+        writeln!(source, "pub fn encode(inputs: &Inputs) -> StdResult<Vec<[u8; 32]>> {{").unwrap();
+        for check in &layout.checks {
+            let access = access_input::<CosmWasm>(&check.path);
+            let (condition, message) = match check.collection {
+                Collection::String | Collection::Array => (
+                    format!("{access}.len() != {}", check.length),
+                    format!("Expected {access} to have a length of {}", check.length),
+                ),
+                Collection::Vec => (
+                    format!("{access}.len() > {}", check.length),
+                    format!("Expected {access} to have at most {} values", check.length),
+                ),
+            };
+            let condition = guarded(&check.guards, condition);
+            writeln!(source, "    if {condition} {{").unwrap();
+            writeln!(source, "        return Err(StdError::generic_err(\"{message}\"));").unwrap();
+            writeln!(source, "    }}").unwrap();
+        }
+        writeln!(source, "    let mut public_inputs = vec![[0; 32]; NUM_PUBLIC_INPUTS];").unwrap();
+        for element in &layout.elements {
+            let statement =
+                format!("public_inputs[{}] = {};", element.position, encode_element(element));
+            if element.guards.is_empty() {
+                writeln!(source, "    {statement}").unwrap();
+            } else {
+                let condition = guarded(&element.guards, String::new());
+                writeln!(source, "    if {condition} {{").unwrap();
+                writeln!(source, "        {statement}").unwrap();
+                writeln!(source, "    }}").unwrap();
+            }
+        }
+        writeln!(source, "    Ok(public_inputs)").unwrap();
+        writeln!(source, "}}").unwrap();
+
+        source.push_str(HELPERS);
+        source
+    }
+}
+
+const QUERY_ENTRY_POINT: &str = r#"#[cw_serde]
+pub enum QueryMsg {
+    Verify { proof: Binary, inputs: Inputs },
+}
+
+#[entry_point]
+pub fn query(_deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Verify { proof, inputs } => {
+            let public_inputs = encode(&inputs)?;
+            to_json_binary(&verify(VERIFICATION_KEY, &proof, &public_inputs)?)
+        }
+    }
+}
+"#;
+
+const HELPERS: &str = r#"
+/// Returns the field element encoding the integer `value`.
+fn field(value: u128) -> [u8; 32] {
+    Uint256::from(value).to_be_bytes()
+}
+
+/// Verifies `proof` against `verification_key`, to be implemented with the verifier library of
+/// the backend.
+fn verify(
+    _verification_key: &[u8],
+    _proof: &[u8],
+    _public_inputs: &[[u8; 32]],
+) -> StdResult<bool> {
+    Err(StdError::generic_err("proof verification is not implemented"))
+}
+"#;
+
+/// Returns `condition`, which is only checked if each vector in `guards` is long enough, or just
+/// the conditions of the guards if `condition` is empty.
+fn guarded(guards: &Guards, condition: String) -> String {
+    let mut conditions = guard_conditions::<CosmWasm>(guards);
+    if !condition.is_empty() {
+        conditions.push(condition);
+    }
+    conditions.join(" && ")
+}
+
+/// Returns the big-endian bytes of the field element encoding `element`.
+fn encode_element(element: &PackedElement) -> String {
+    let access = access_input::<CosmWasm>(&element.path);
+    match &element.element {
+        Element::Scalar(AbiType::Integer { sign: Sign::Signed, width }) => {
+            // Signed integers are encoded as their two's complement in `width` bits.
+            let mask = u128::MAX >> (128 - (*width).clamp(1, 128));
+            format!("field({access} as u128 & 0x{mask:x})")
+        }
+        Element::Scalar(AbiType::Boolean | AbiType::Integer { .. }) => {
+            format!("field({access} as u128)")
+        }
+        Element::Scalar(_) => format!("{access}.to_be_bytes()"),
+        Element::Byte(index) => format!("field({access}.as_bytes()[{index}] as u128)"),
+        Element::Length => format!("field({access}.len() as u128)"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use acvm::acir::native_types::Witness;
+
+    use crate::{Abi, AbiParameter, AbiReturnType, AbiType, AbiVisibility, Sign};
+
+    #[test]
+    fn encodes_public_inputs_in_witness_order() {
+        let abi = Abi {
+            parameters: vec![
+                AbiParameter {
+                    name: "values".to_string(),
+                    typ: AbiType::Vec {
+                        max_length: 2,
+                        typ: Box::new(AbiType::Integer { sign: Sign::Signed, width: 8 }),
+                    },
+                    visibility: AbiVisibility::Public,
+                },
+                AbiParameter {
+                    name: "name".to_string(),
+                    typ: AbiType::String { length: 2 },
+                    visibility: AbiVisibility::Public,
+                },
+            ],
+            // The vector's two values, length and `empty_value` follow the name
+            param_witnesses: BTreeMap::from([
+                ("values".to_string(), vec![(Witness(3)..Witness(7))]),
+                ("name".to_string(), vec![(Witness(1)..Witness(3))]),
+            ]),
+            return_type: Some(AbiReturnType {
+                abi_type: AbiType::Field,
+                visibility: AbiVisibility::Public,
+            }),
+            return_witnesses: vec![Witness(7)],
+        };
+
+        let source = abi.to_cosmwasm_verifier(&[1, 2, 255]);
+
+        assert!(
+            source.contains("pub const VERIFICATION_KEY: &[u8] = &[\n    0x01, 0x02, 0xff,\n];")
+        );
+        assert!(source.contains("pub const NUM_PUBLIC_INPUTS: usize = 7;"));
+        assert!(source.contains("    pub values: Vec<i8>,\n    pub name: String,\n"));
+        assert!(source.contains("if inputs.values.len() > 2 {"));
+        assert!(source.contains("if inputs.name.len() != 2 {"));
+        assert!(source.contains("public_inputs[0] = field(inputs.name.as_bytes()[0] as u128);"));
+        assert!(source.contains(
+            "    if inputs.values.len() > 1 {\n        public_inputs[3] = field(inputs.values[1] as u128 & 0xff);"
+        ));
+        assert!(source.contains("public_inputs[4] = field(inputs.values.len() as u128);"));
+        assert!(source.contains("public_inputs[6] = inputs.return_value.to_be_bytes();"));
+    }
+}
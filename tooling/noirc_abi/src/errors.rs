@@ -6,8 +6,8 @@ use thiserror::Error;
 pub enum InputParserError {
     #[error("input file is badly formed, could not parse, {0}")]
     ParseInputMap(String),
-    #[error("Expected witness values to be integers, provided value causes `{0}` error")]
-    ParseStr(String),
+    #[error("Could not parse the value `{value}` given for `{name}`: {reason}")]
+    ParseStr { name: String, value: String, reason: String },
     #[error("Could not parse hex value {0}")]
     ParseHexStr(String),
     #[error("cannot parse value into {0:?}")]
@@ -34,6 +34,12 @@ impl From<serde_json::Error> for InputParserError {
     }
 }
 
+impl From<serde_yaml::Error> for InputParserError {
+    fn from(err: serde_yaml::Error) -> Self {
+        Self::ParseInputMap(err.to_string())
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum AbiError {
     #[error("Received parameters not expected by ABI: {0:?}")]
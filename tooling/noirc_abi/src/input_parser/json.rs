@@ -1,4 +1,6 @@
-use super::{parse_str_to_field, InputValue};
+use super::{
+    integer_to_field, parse_str_to_field, parse_str_to_signed, parse_str_to_unsigned, InputValue,
+};
 use crate::{errors::InputParserError, Abi, AbiType, MAIN_RETURN_NAME};
 use acvm::FieldElement;
 use iter_extended::{try_btree_map, try_vecmap};
@@ -11,7 +13,16 @@ pub(crate) fn parse_json(
 ) -> Result<BTreeMap<String, InputValue>, InputParserError> {
     // Parse input.json into a BTreeMap.
     let data: BTreeMap<String, JsonTypes> = serde_json::from_str(input_string)?;
+    input_map_from_json_types(data, abi)
+}
 
+/// Converts the values of each argument in `data` into the types expected by the ABI.
+///
+/// This is shared by each format whose values deserialize into [`JsonTypes`].
+pub(super) fn input_map_from_json_types(
+    data: BTreeMap<String, JsonTypes>,
+    abi: &Abi,
+) -> Result<BTreeMap<String, InputValue>, InputParserError> {
     // Convert arguments to field elements.
     let mut parsed_inputs = try_btree_map(abi.to_btree_map(), |(arg_name, abi_type)| {
         // Check that json contains a value for each argument in the ABI.
@@ -43,6 +54,17 @@ pub(crate) fn serialize_to_json(
     input_map: &BTreeMap<String, InputValue>,
     abi: &Abi,
 ) -> Result<String, InputParserError> {
+    let json_map = json_types_from_input_map(input_map, abi)?;
+    let json_string = serde_json::to_string(&json_map)?;
+
+    Ok(json_string)
+}
+
+/// Converts each argument in `input_map` into the values they're written as in an input file.
+pub(super) fn json_types_from_input_map(
+    input_map: &BTreeMap<String, InputValue>,
+    abi: &Abi,
+) -> Result<BTreeMap<String, JsonTypes>, InputParserError> {
     let mut json_map = try_btree_map(abi.to_btree_map(), |(key, param_type)| {
         JsonTypes::try_from_input_value(&input_map[&key], &param_type)
             .map(|value| (key.to_owned(), value))
@@ -55,9 +77,7 @@ pub(crate) fn serialize_to_json(
         json_map.insert(MAIN_RETURN_NAME.to_owned(), return_value);
     }
 
-    let json_string = serde_json::to_string(&json_map)?;
-
-    Ok(json_string)
+    Ok(json_map)
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -72,6 +92,10 @@ pub enum JsonTypes {
     // however we restrict the allowable size. Values which do not fit in a u64 should be passed
     // as a string.
     Integer(u64),
+    // A negative integer, which only fits in 64 bits as a signed integer.
+    //
+    // This must come after `Integer` so that non-negative integers are deserialized as a `u64`.
+    NegativeInteger(i64),
     // Simple boolean flag
     Bool(bool),
     // Array of JsonTypes
@@ -91,7 +115,7 @@ impl JsonTypes {
             }
             (InputValue::Field(f), AbiType::Boolean) => JsonTypes::Bool(f.is_one()),
 
-            (InputValue::Vec(vector), AbiType::Array { typ, .. }) => {
+            (InputValue::Vec(vector), AbiType::Array { typ, .. } | AbiType::Vec { typ, .. }) => {
                 let array =
                     try_vecmap(vector, |value| JsonTypes::try_from_input_value(value, typ))?;
                 JsonTypes::Array(array)
@@ -143,10 +167,16 @@ impl InputValue {
     ) -> Result<InputValue, InputParserError> {
         let input_value = match (value, param_type) {
             (JsonTypes::String(string), AbiType::String { .. }) => InputValue::String(string),
+            (JsonTypes::String(string), AbiType::Field) => {
+                InputValue::Field(parse_str_to_field(&string, arg_name)?)
+            }
             (
                 JsonTypes::String(string),
-                AbiType::Field | AbiType::Integer { .. } | AbiType::Boolean,
-            ) => InputValue::Field(parse_str_to_field(&string)?),
+                AbiType::Integer { sign: crate::Sign::Unsigned, .. } | AbiType::Boolean,
+            ) => InputValue::Field(parse_str_to_unsigned(&string, arg_name)?),
+            (JsonTypes::String(string), AbiType::Integer { sign: crate::Sign::Signed, width }) => {
+                InputValue::Field(parse_str_to_signed(&string, *width, arg_name)?)
+            }
 
             (
                 JsonTypes::Integer(integer),
@@ -156,12 +186,18 @@ impl InputValue {
 
                 InputValue::Field(new_value)
             }
+            (
+                JsonTypes::NegativeInteger(integer),
+                AbiType::Field | AbiType::Integer { .. } | AbiType::Boolean,
+            ) => InputValue::Field(integer_to_field(integer, param_type, arg_name)?),
 
             (JsonTypes::Bool(boolean), AbiType::Boolean) => InputValue::Field(boolean.into()),
 
-            (JsonTypes::Array(array), AbiType::Array { typ, .. }) => {
+            (JsonTypes::Array(array), AbiType::Array { typ, .. } | AbiType::Vec { typ, .. }) => {
                 let array_elements =
-                    try_vecmap(array, |value| InputValue::try_from_json(value, typ, arg_name))?;
+                    try_vecmap(array.into_iter().enumerate(), |(index, value)| {
+                        InputValue::try_from_json(value, typ, &format!("{arg_name}[{index}]"))
+                    })?;
                 InputValue::Vec(array_elements)
             }
 
@@ -180,9 +216,12 @@ impl InputValue {
             }
 
             (JsonTypes::Array(array), AbiType::Tuple { fields }) => {
-                let tuple_fields = try_vecmap(array.into_iter().zip(fields), |(value, typ)| {
-                    InputValue::try_from_json(value, typ, arg_name)
-                })?;
+                let tuple_fields = try_vecmap(
+                    array.into_iter().zip(fields).enumerate(),
+                    |(index, (value, typ))| {
+                        InputValue::try_from_json(value, typ, &format!("{arg_name}.{index}"))
+                    },
+                )?;
                 InputValue::Vec(tuple_fields)
             }
 
@@ -1,15 +1,16 @@
 use num_bigint::{BigInt, BigUint};
-use num_traits::{Num, Zero};
+use num_traits::Num;
 use std::collections::BTreeMap;
 
 use acvm::FieldElement;
 use serde::Serialize;
 
 use crate::errors::InputParserError;
-use crate::{Abi, AbiType};
+use crate::{Abi, AbiType, Sign};
 
 pub mod json;
 mod toml;
+mod yaml;
 
 /// This is what all formats eventually transform into
 /// For example, a toml file will parse into TomlTypes
@@ -75,6 +76,11 @@ impl InputValue {
                     .all(|(input_value, abi_param)| input_value.matches_abi(abi_param))
             }
 
+            (InputValue::Vec(vec_elements), AbiType::Vec { max_length, typ }) => {
+                vec_elements.len() <= *max_length as usize
+                    && vec_elements.iter().all(|input_value| input_value.matches_abi(typ))
+            }
+
             // All other InputValue-AbiType combinations are fundamentally incompatible.
             _ => false,
         }
@@ -83,10 +89,12 @@ impl InputValue {
 
 /// The different formats that are supported when parsing
 /// the initial witness values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(test, derive(strum_macros::EnumIter))]
 pub enum Format {
     Json,
     Toml,
+    Yaml,
 }
 
 impl Format {
@@ -94,6 +102,7 @@ impl Format {
         match self {
             Format::Json => "json",
             Format::Toml => "toml",
+            Format::Yaml => "yaml",
         }
     }
 }
@@ -107,6 +116,7 @@ impl Format {
         match self {
             Format::Json => json::parse_json(input_string, abi),
             Format::Toml => toml::parse_toml(input_string, abi),
+            Format::Yaml => yaml::parse_yaml(input_string, abi),
         }
     }
 
@@ -118,6 +128,7 @@ impl Format {
         match self {
             Format::Json => json::serialize_to_json(input_map, abi),
             Format::Toml => toml::serialize_to_toml(input_map, abi),
+            Format::Yaml => yaml::serialize_to_yaml(input_map, abi),
         }
     }
 }
@@ -197,47 +208,119 @@ mod serialization_tests {
     }
 }
 
-fn parse_str_to_field(value: &str) -> Result<FieldElement, InputParserError> {
-    let big_num = if let Some(hex) = value.strip_prefix("0x") {
-        BigUint::from_str_radix(hex, 16)
-    } else {
-        BigUint::from_str_radix(value, 10)
+/// Parses an integer written in decimal or as a `0x`-prefixed hexadecimal string of any length.
+///
+/// The digits may be separated by underscores, and a leading `-` negates the integer.
+fn parse_str_to_big_int(value: &str, name: &str) -> Result<BigInt, InputParserError> {
+    let error = |reason: String| InputParserError::ParseStr {
+        name: name.to_owned(),
+        value: value.to_owned(),
+        reason,
+    };
+
+    let (is_negative, magnitude) = match value.strip_prefix('-') {
+        Some(magnitude) => (true, magnitude),
+        None => (false, value),
+    };
+    let (radix, digits) = match magnitude.strip_prefix("0x") {
+        Some(hex) => (16, hex),
+        None => (10, magnitude),
     };
-    big_num.map_err(|err_msg| InputParserError::ParseStr(err_msg.to_string())).and_then(|bigint| {
-        if bigint < FieldElement::modulus() {
-            Ok(field_from_big_uint(bigint))
-        } else {
-            Err(InputParserError::ParseStr(format!(
-                "Input exceeds field modulus. Values must fall within [0, {})",
+
+    if digits.starts_with('_') || digits.ends_with('_') || digits.contains("__") {
+        return Err(error("underscores are only allowed between digits".to_owned()));
+    }
+    // `from_str_radix` accepts a leading sign, which has already been handled.
+    if digits.starts_with(['+', '-']) {
+        return Err(error("expected a single leading `-` sign".to_owned()));
+    }
+
+    let magnitude = BigUint::from_str_radix(&digits.replace('_', ""), radix)
+        .map_err(|err| error(err.to_string()))?;
+    let bigint = BigInt::from(magnitude);
+    Ok(if is_negative { -bigint } else { bigint })
+}
+
+/// Parses a field element, reducing negative values modulo the field.
+fn parse_str_to_field(value: &str, name: &str) -> Result<FieldElement, InputParserError> {
+    let bigint = parse_str_to_big_int(value, name)?;
+    let modulus: BigInt = FieldElement::modulus().into();
+
+    if bigint.magnitude() >= modulus.magnitude() {
+        return Err(InputParserError::ParseStr {
+            name: name.to_owned(),
+            value: value.to_owned(),
+            reason: format!(
+                "input exceeds field modulus. Values must fall within (-{0}, {0})",
                 FieldElement::modulus(),
-            )))
-        }
-    })
+            ),
+        });
+    }
+
+    let field = field_from_big_uint(bigint.magnitude().clone());
+    Ok(if bigint.sign() == num_bigint::Sign::Minus { -field } else { field })
 }
 
-fn parse_str_to_signed(value: &str, width: u32) -> Result<FieldElement, InputParserError> {
-    let big_num = if let Some(hex) = value.strip_prefix("0x") {
-        BigInt::from_str_radix(hex, 16)
+/// Parses an unsigned integer or boolean, which can't be negative.
+fn parse_str_to_unsigned(value: &str, name: &str) -> Result<FieldElement, InputParserError> {
+    if value.starts_with('-') {
+        return Err(InputParserError::ParseStr {
+            name: name.to_owned(),
+            value: value.to_owned(),
+            reason: "negative values are only allowed for fields and signed integers".to_owned(),
+        });
+    }
+    parse_str_to_field(value, name)
+}
+
+/// Parses a signed integer of `width` bits, encoding negative values in two's complement.
+///
+/// Values must fall within `[-2^(width - 1), 2^(width - 1))`.
+fn parse_str_to_signed(
+    value: &str,
+    width: u32,
+    name: &str,
+) -> Result<FieldElement, InputParserError> {
+    let bigint = parse_str_to_big_int(value, name)?;
+
+    let max = BigInt::from(2).pow(width - 1);
+    let min = -max.clone();
+    if bigint < min || bigint >= max {
+        return Err(InputParserError::ParseStr {
+            name: name.to_owned(),
+            value: value.to_owned(),
+            reason: format!(
+                "input does not fit in a signed integer of {width} bits. Values must fall within [{min}, {max})"
+            ),
+        });
+    }
+
+    let bigint = if bigint.sign() == num_bigint::Sign::Minus {
+        BigInt::from(2).pow(width) + bigint
     } else {
-        BigInt::from_str_radix(value, 10)
+        bigint
     };
+    Ok(field_from_big_int(bigint))
+}
 
-    big_num.map_err(|err_msg| InputParserError::ParseStr(err_msg.to_string())).and_then(|bigint| {
-        let modulus: BigInt = FieldElement::modulus().into();
-        let bigint = if bigint.sign() == num_bigint::Sign::Minus {
-            BigInt::from(2).pow(width) + bigint
-        } else {
-            bigint
-        };
-        if bigint.is_zero() || (bigint.sign() == num_bigint::Sign::Plus && bigint < modulus) {
-            Ok(field_from_big_int(bigint))
-        } else {
-            Err(InputParserError::ParseStr(format!(
-                "Input exceeds field modulus. Values must fall within [0, {})",
-                FieldElement::modulus(),
-            )))
+/// Converts an integer from an input file into a field element of the given type.
+///
+/// Negative integers are reduced modulo the field for fields and encoded in two's complement
+/// for signed integers.
+fn integer_to_field(
+    integer: i64,
+    abi_type: &AbiType,
+    name: &str,
+) -> Result<FieldElement, InputParserError> {
+    match abi_type {
+        AbiType::Integer { sign: Sign::Signed, width } => {
+            parse_str_to_signed(&integer.to_string(), *width, name)
+        }
+        AbiType::Integer { sign: Sign::Unsigned, .. } | AbiType::Boolean if integer < 0 => {
+            parse_str_to_unsigned(&integer.to_string(), name)
         }
-    })
+        _ => Ok(FieldElement::from(i128::from(integer))),
+    }
 }
 
 fn field_from_big_uint(bigint: BigUint) -> FieldElement {
@@ -261,7 +344,7 @@ mod test {
     use acvm::FieldElement;
     use num_bigint::BigUint;
 
-    use super::parse_str_to_field;
+    use super::{parse_str_to_field, parse_str_to_signed, parse_str_to_unsigned};
 
     fn big_uint_from_field(field: FieldElement) -> BigUint {
         BigUint::from_bytes_be(&field.to_be_bytes())
@@ -270,7 +353,7 @@ mod test {
     #[test]
     fn parse_empty_str_fails() {
         // Check that this fails appropriately rather than being treated as 0, etc.
-        assert!(parse_str_to_field("").is_err());
+        assert!(parse_str_to_field("", "x").is_err());
     }
 
     #[test]
@@ -285,11 +368,11 @@ mod test {
 
         for field in fields {
             let hex_field = format!("0x{}", field.to_hex());
-            let field_from_hex = parse_str_to_field(&hex_field).unwrap();
+            let field_from_hex = parse_str_to_field(&hex_field, "x").unwrap();
             assert_eq!(field_from_hex, field);
 
             let dec_field = big_uint_from_field(field).to_string();
-            let field_from_dec = parse_str_to_field(&dec_field).unwrap();
+            let field_from_dec = parse_str_to_field(&dec_field, "x").unwrap();
             assert_eq!(field_from_dec, field);
         }
     }
@@ -297,6 +380,70 @@ mod test {
     #[test]
     fn rejects_noncanonical_fields() {
         let noncanonical_field = FieldElement::modulus().to_string();
-        assert!(parse_str_to_field(&noncanonical_field).is_err());
+        assert!(parse_str_to_field(&noncanonical_field, "x").is_err());
+    }
+
+    #[test]
+    fn parse_fields_with_underscores() {
+        assert_eq!(
+            parse_str_to_field("1_000_000", "x").unwrap(),
+            FieldElement::from(1_000_000u128)
+        );
+        assert_eq!(
+            parse_str_to_field("0xdead_beef", "x").unwrap(),
+            FieldElement::from(0xdead_beefu128)
+        );
+
+        assert!(parse_str_to_field("_1", "x").is_err());
+        assert!(parse_str_to_field("1_", "x").is_err());
+        assert!(parse_str_to_field("1__0", "x").is_err());
+        assert!(parse_str_to_field("0x_1", "x").is_err());
+    }
+
+    #[test]
+    fn parse_negative_fields_modulo_the_field() {
+        assert_eq!(parse_str_to_field("-1", "x").unwrap(), -FieldElement::one());
+        assert_eq!(parse_str_to_field("-0x10", "x").unwrap(), -FieldElement::from(16u128));
+        assert_eq!(parse_str_to_field("-0", "x").unwrap(), FieldElement::zero());
+
+        let noncanonical_field = format!("-{}", FieldElement::modulus());
+        assert!(parse_str_to_field(&noncanonical_field, "x").is_err());
+        assert!(parse_str_to_field("--1", "x").is_err());
+        assert!(parse_str_to_field("-+1", "x").is_err());
+    }
+
+    #[test]
+    fn parse_long_hex_strings() {
+        let hex_field = format!("0x{}1", "0".repeat(100));
+        assert_eq!(parse_str_to_field(&hex_field, "x").unwrap(), FieldElement::one());
+    }
+
+    #[test]
+    fn parse_negative_integers() {
+        assert_eq!(parse_str_to_signed("-1", 8, "x").unwrap(), FieldElement::from(255u128));
+        assert!(parse_str_to_unsigned("-1", "x").is_err());
+    }
+
+    #[test]
+    fn parse_signed_integers_within_their_range() {
+        assert_eq!(parse_str_to_signed("-128", 8, "x").unwrap(), FieldElement::from(128u128));
+        assert_eq!(parse_str_to_signed("127", 8, "x").unwrap(), FieldElement::from(127u128));
+        assert_eq!(parse_str_to_signed("0", 8, "x").unwrap(), FieldElement::zero());
+        assert_eq!(
+            parse_str_to_signed("-0x8000_0000", 32, "x").unwrap(),
+            FieldElement::from(0x8000_0000u128)
+        );
+
+        assert!(parse_str_to_signed("128", 8, "x").is_err());
+        assert!(parse_str_to_signed("200", 8, "x").is_err());
+        assert!(parse_str_to_signed("-129", 8, "x").is_err());
+        assert!(parse_str_to_signed("-256", 8, "x").is_err());
+        assert!(parse_str_to_signed("0x8000_0000", 32, "x").is_err());
+    }
+
+    #[test]
+    fn errors_name_the_key_path() {
+        let error = parse_str_to_field("0xzz", "foo.bar[1]").unwrap_err();
+        assert!(error.to_string().contains("`foo.bar[1]`"));
     }
 }
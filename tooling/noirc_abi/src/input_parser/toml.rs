@@ -1,6 +1,7 @@
-use super::{parse_str_to_field, parse_str_to_signed, InputValue};
+use super::{
+    integer_to_field, parse_str_to_field, parse_str_to_signed, parse_str_to_unsigned, InputValue,
+};
 use crate::{errors::InputParserError, Abi, AbiType, MAIN_RETURN_NAME};
-use acvm::FieldElement;
 use iter_extended::{try_btree_map, try_vecmap};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
@@ -68,7 +69,7 @@ enum TomlTypes {
     String(String),
     // Just a regular integer, that can fit in 64 bits
     // Note that the toml spec specifies that all numbers are represented as `i64`s.
-    Integer(u64),
+    Integer(i64),
     // Simple boolean flag
     Bool(bool),
     // Array of TomlTypes
@@ -89,7 +90,7 @@ impl TomlTypes {
             }
             (InputValue::Field(f), AbiType::Boolean) => TomlTypes::Bool(f.is_one()),
 
-            (InputValue::Vec(vector), AbiType::Array { typ, .. }) => {
+            (InputValue::Vec(vector), AbiType::Array { typ, .. } | AbiType::Vec { typ, .. }) => {
                 let array =
                     try_vecmap(vector, |value| TomlTypes::try_from_input_value(value, typ))?;
                 TomlTypes::Array(array)
@@ -126,29 +127,28 @@ impl InputValue {
     ) -> Result<InputValue, InputParserError> {
         let input_value = match (value, param_type) {
             (TomlTypes::String(string), AbiType::String { .. }) => InputValue::String(string),
+            (TomlTypes::String(string), AbiType::Field) => {
+                InputValue::Field(parse_str_to_field(&string, arg_name)?)
+            }
             (
                 TomlTypes::String(string),
-                AbiType::Field
-                | AbiType::Integer { sign: crate::Sign::Unsigned, .. }
-                | AbiType::Boolean,
-            ) => InputValue::Field(parse_str_to_field(&string)?),
+                AbiType::Integer { sign: crate::Sign::Unsigned, .. } | AbiType::Boolean,
+            ) => InputValue::Field(parse_str_to_unsigned(&string, arg_name)?),
             (TomlTypes::String(string), AbiType::Integer { sign: crate::Sign::Signed, width }) => {
-                InputValue::Field(parse_str_to_signed(&string, *width)?)
+                InputValue::Field(parse_str_to_signed(&string, *width, arg_name)?)
             }
             (
                 TomlTypes::Integer(integer),
                 AbiType::Field | AbiType::Integer { .. } | AbiType::Boolean,
-            ) => {
-                let new_value = FieldElement::from(i128::from(integer));
-
-                InputValue::Field(new_value)
-            }
+            ) => InputValue::Field(integer_to_field(integer, param_type, arg_name)?),
 
             (TomlTypes::Bool(boolean), AbiType::Boolean) => InputValue::Field(boolean.into()),
 
-            (TomlTypes::Array(array), AbiType::Array { typ, .. }) => {
+            (TomlTypes::Array(array), AbiType::Array { typ, .. } | AbiType::Vec { typ, .. }) => {
                 let array_elements =
-                    try_vecmap(array, |value| InputValue::try_from_toml(value, typ, arg_name))?;
+                    try_vecmap(array.into_iter().enumerate(), |(index, value)| {
+                        InputValue::try_from_toml(value, typ, &format!("{arg_name}[{index}]"))
+                    })?;
                 InputValue::Vec(array_elements)
             }
 
@@ -167,9 +167,12 @@ impl InputValue {
             }
 
             (TomlTypes::Array(array), AbiType::Tuple { fields }) => {
-                let tuple_fields = try_vecmap(array.into_iter().zip(fields), |(value, typ)| {
-                    InputValue::try_from_toml(value, typ, arg_name)
-                })?;
+                let tuple_fields = try_vecmap(
+                    array.into_iter().zip(fields).enumerate(),
+                    |(index, (value, typ))| {
+                        InputValue::try_from_toml(value, typ, &format!("{arg_name}.{index}"))
+                    },
+                )?;
                 InputValue::Vec(tuple_fields)
             }
 
@@ -0,0 +1,25 @@
+use super::json::{input_map_from_json_types, json_types_from_input_map, JsonTypes};
+use super::InputValue;
+use crate::{errors::InputParserError, Abi};
+use std::collections::BTreeMap;
+
+// YAML values deserialize into the same types as JSON values, so both formats share the
+// conversion between these and `InputValue`s.
+
+pub(crate) fn parse_yaml(
+    input_string: &str,
+    abi: &Abi,
+) -> Result<BTreeMap<String, InputValue>, InputParserError> {
+    let data: BTreeMap<String, JsonTypes> = serde_yaml::from_str(input_string)?;
+    input_map_from_json_types(data, abi)
+}
+
+pub(crate) fn serialize_to_yaml(
+    input_map: &BTreeMap<String, InputValue>,
+    abi: &Abi,
+) -> Result<String, InputParserError> {
+    let yaml_map = json_types_from_input_map(input_map, abi)?;
+    let yaml_string = serde_yaml::to_string(&yaml_map)?;
+
+    Ok(yaml_string)
+}
@@ -21,9 +21,16 @@ use std::{collections::BTreeMap, str};
 //
 // This ABI has nothing to do with ACVM or ACIR. Although they implicitly have a relationship
 
+mod cairo;
+mod cosmwasm;
 pub mod errors;
 pub mod input_parser;
+mod public_inputs;
 mod serialization;
+mod solidity;
+mod typescript;
+
+pub use typescript::TypeScriptBindings;
 
 /// A map from the fields in an TOML/JSON file which correspond to some ABI to their values
 pub type InputMap = BTreeMap<String, InputValue>;
@@ -68,6 +75,17 @@ pub enum AbiType {
     String {
         length: u64,
     },
+    /// A vector of up to `max_length` values, such as a `BoundedVec` from the standard library.
+    ///
+    /// This is written as an array of its values in input files, which is encoded with enough
+    /// placeholder values to fill `max_length` values, followed by the length of the vector and
+    /// by a single placeholder value for the vector's `empty_value`. This matches the layout of
+    /// the fields of a `BoundedVec`, and each placeholder value is encoded as zeroes.
+    Vec {
+        max_length: u64,
+        #[serde(rename = "type")]
+        typ: Box<AbiType>,
+    },
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -160,6 +178,14 @@ impl AbiType {
 
             Type::Struct(def, ref args) => {
                 let struct_type = def.borrow();
+                if is_bounded_vec(&struct_type) {
+                    let max_length = args[1]
+                        .evaluate_to_u64()
+                        .expect("Cannot have variable sized vectors as a parameter to main");
+                    let typ = Box::new(Self::from_type(context, &args[0]));
+                    return Self::Vec { max_length, typ };
+                }
+
                 let fields = struct_type.get_fields(args);
                 let fields = vecmap(fields, |(name, typ)| (name, Self::from_type(context, &typ)));
                 // For the ABI, we always want to resolve the struct paths from the root crate
@@ -174,6 +200,8 @@ impl AbiType {
             Type::Error
             | Type::Unit
             | Type::Constant(_)
+            | Type::InfixExpr(..)
+            | Type::Value(_)
             | Type::TraitAsType(..)
             | Type::TypeVariable(_, _)
             | Type::NamedGeneric(..)
@@ -197,10 +225,17 @@ impl AbiType {
                 fields.iter().fold(0, |acc, field_typ| acc + field_typ.field_count())
             }
             AbiType::String { length } => *length as u32,
+            AbiType::Vec { max_length, typ } => typ.field_count() * (*max_length as u32 + 1) + 1,
         }
     }
 }
 
+/// Returns whether `struct_type` is the `BoundedVec` of the standard library, which is given the
+/// `Vec` ABI type so that inputs don't need to include its placeholder values.
+fn is_bounded_vec(struct_type: &StructType) -> bool {
+    struct_type.id.krate().is_stdlib() && struct_type.name.0.contents == "BoundedVec"
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 /// An argument or return value of the circuit's `main` function.
 pub struct AbiParameter {
@@ -395,6 +430,22 @@ impl Abi {
                     encoded_value.extend(Self::encode_value(value, typ)?);
                 }
             }
+            (InputValue::Vec(vec_elements), AbiType::Vec { max_length, typ }) => {
+                let length = vec_elements.len();
+                for elem in vec_elements {
+                    encoded_value.extend(Self::encode_value(elem, typ)?);
+                }
+
+                let placeholder_count =
+                    (*max_length as usize - length) * typ.field_count() as usize;
+                encoded_value
+                    .extend(std::iter::repeat(FieldElement::zero()).take(placeholder_count));
+                encoded_value.push((length as u128).into());
+                // The placeholder for the vector's `empty_value`
+                encoded_value.extend(
+                    std::iter::repeat(FieldElement::zero()).take(typ.field_count() as usize),
+                );
+            }
             _ => unreachable!("value should have already been checked to match abi type"),
         }
         Ok(encoded_value)
@@ -495,6 +546,19 @@ fn decode_value(
 
             InputValue::Vec(tuple_elements)
         }
+        AbiType::Vec { max_length, typ } => {
+            let mut vec_elements = Vec::with_capacity(*max_length as usize);
+            for _ in 0..*max_length {
+                vec_elements.push(decode_value(field_iterator, typ)?);
+            }
+
+            let length = field_iterator.next().unwrap().to_u128() as usize;
+            // Skip over the vector's `empty_value`
+            decode_value(field_iterator, typ)?;
+
+            vec_elements.truncate(length);
+            InputValue::Vec(vec_elements)
+        }
     };
 
     Ok(value)
@@ -609,4 +673,38 @@ mod test {
         // We also decode the return value (we can do this immediately as we know it shares a witness with an input).
         assert_eq!(return_value.unwrap(), reconstructed_inputs["thing2"]);
     }
+
+    #[test]
+    fn vec_encoding_roundtrip() {
+        let abi = Abi {
+            parameters: vec![AbiParameter {
+                name: "values".to_string(),
+                typ: AbiType::Vec { max_length: 3, typ: Box::new(AbiType::Field) },
+                visibility: AbiVisibility::Public,
+            }],
+            param_witnesses: BTreeMap::from([(
+                "values".to_string(),
+                vec![(Witness(1)..Witness(6))],
+            )]),
+            return_type: None,
+            return_witnesses: Vec::new(),
+        };
+
+        let values = InputValue::Vec(vec![
+            InputValue::Field(FieldElement::from(7u128)),
+            InputValue::Field(FieldElement::from(8u128)),
+        ]);
+        let inputs: InputMap = BTreeMap::from([("values".to_string(), values.clone())]);
+
+        // The values are followed by a placeholder to fill the vector, its length and a
+        // placeholder for its `empty_value`.
+        let witness_map = abi.encode(&inputs, None).unwrap();
+        let encoded: Vec<_> = (1..6).map(|index| witness_map[&Witness(index)]).collect();
+        let expected: Vec<FieldElement> =
+            vec![7u128.into(), 8u128.into(), 0u128.into(), 2u128.into(), 0u128.into()];
+        assert_eq!(encoded, expected);
+
+        let (reconstructed_inputs, _) = abi.decode(&witness_map).unwrap();
+        assert_eq!(reconstructed_inputs["values"], values);
+    }
 }
@@ -0,0 +1,353 @@
+//! The layout of the public inputs of a program, shared by the generators of verifier contracts.
+//!
+//! The verifier takes the public inputs of the circuit as a flat array of field elements, ordered
+//! by the witness each of them is assigned to rather than by the order of the ABI's parameters.
+//! The layout gives the position in this array of each field element of the program's public
+//! parameters and return value, along with the path to the value it encodes from a struct which
+//! mirrors them.
+//!
+//! Each generator declares this inputs struct in its own [Language], and writes a function which
+//! checks the lengths of its strings, arrays and vectors before encoding it into the public inputs.
+use std::collections::BTreeMap;
+
+use acvm::acir::native_types::Witness;
+use iter_extended::vecmap;
+
+use crate::{range_to_vec, Abi, AbiType};
+
+/// A step from a value to one of its parts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Accessor {
+    Field(String),
+    Index(u64),
+}
+
+/// What a field element of the public inputs encodes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Element {
+    /// A field, boolean or integer value of this type
+    Scalar(AbiType),
+    /// The byte at this index of a string
+    Byte(u64),
+    /// The length of a vector
+    Length,
+}
+
+/// The kinds of values whose length is checked before they are encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Collection {
+    String,
+    Array,
+    Vec,
+}
+
+/// The vectors which must be longer than the given index for a value to be present. Values past
+/// the length of a vector are placeholders, which are encoded as zeroes.
+pub(crate) type Guards = Vec<(Vec<Accessor>, u64)>;
+
+#[derive(Debug, Clone)]
+pub(crate) struct PackedElement {
+    pub(crate) position: usize,
+    /// The path from the inputs struct to the value the element is taken from
+    pub(crate) path: Vec<Accessor>,
+    pub(crate) element: Element,
+    pub(crate) guards: Guards,
+}
+
+/// A check that a string or array has a length of exactly `length`, or a vector of at most
+/// `length`, which must pass for the inputs to be encoded.
+#[derive(Debug, Clone)]
+pub(crate) struct LengthCheck {
+    pub(crate) path: Vec<Accessor>,
+    pub(crate) collection: Collection,
+    pub(crate) length: u64,
+    pub(crate) guards: Guards,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct PublicInputLayout {
+    pub(crate) num_public_inputs: usize,
+    /// The fields of the inputs struct: the public parameters followed by the return value
+    pub(crate) inputs: Vec<(String, AbiType)>,
+    pub(crate) checks: Vec<LengthCheck>,
+    /// The field elements of the inputs in the order of the ABI. A return value may share
+    /// witnesses with the parameters, in which case several elements have the same position.
+    pub(crate) elements: Vec<PackedElement>,
+}
+
+impl PublicInputLayout {
+    /// Returns the element at each position of the public inputs, `None` for the placeholders of
+    /// the `empty_value` of vectors which are always zero.
+    pub(crate) fn elements_by_position(&self) -> Vec<Option<&PackedElement>> {
+        let mut by_position = vec![None; self.num_public_inputs];
+        for element in &self.elements {
+            by_position[element.position].get_or_insert(element);
+        }
+        by_position
+    }
+}
+
+impl Abi {
+    /// Returns the layout of the public parameters and return value of the program in the public
+    /// inputs of its verifier, with the return value named `return_value_name`.
+    pub(crate) fn public_input_layout(&self, return_value_name: &str) -> PublicInputLayout {
+        let mut public_witnesses: Vec<Witness> = self
+            .parameters
+            .iter()
+            .filter(|param| param.is_public())
+            .flat_map(|param| range_to_vec(&self.param_witnesses[&param.name]))
+            .chain(self.return_witnesses.iter().copied())
+            .collect();
+        // A return value may share witnesses with the parameters, each of which is only a
+        // single public input.
+        public_witnesses.sort();
+        public_witnesses.dedup();
+
+        let mut builder = LayoutBuilder {
+            positions: public_witnesses
+                .iter()
+                .enumerate()
+                .map(|(position, witness)| (*witness, position))
+                .collect(),
+            checks: Vec::new(),
+            elements: Vec::new(),
+        };
+
+        let mut inputs = Vec::new();
+        for param in self.parameters.iter().filter(|param| param.is_public()) {
+            let mut witnesses = range_to_vec(&self.param_witnesses[&param.name]).into_iter();
+            let path = vec![Accessor::Field(param.name.clone())];
+            builder.visit(&param.typ, path, &Vec::new(), &mut witnesses);
+            inputs.push((param.name.clone(), param.typ.clone()));
+        }
+        if let Some(return_type) = &self.return_type {
+            let mut witnesses = self.return_witnesses.clone().into_iter();
+            let path = vec![Accessor::Field(return_value_name.to_string())];
+            builder.visit(&return_type.abi_type, path, &Vec::new(), &mut witnesses);
+            inputs.push((return_value_name.to_string(), return_type.abi_type.clone()));
+        }
+
+        PublicInputLayout {
+            num_public_inputs: public_witnesses.len(),
+            inputs,
+            checks: builder.checks,
+            elements: builder.elements,
+        }
+    }
+}
+
+struct LayoutBuilder {
+    /// The position of each public witness in the verifier's public inputs
+    positions: BTreeMap<Witness, usize>,
+    checks: Vec<LengthCheck>,
+    elements: Vec<PackedElement>,
+}
+
+impl LayoutBuilder {
+    /// Adds each field element of the value at `path`, which has type `typ`, at the position of
+    /// the next witness in `witnesses`.
+    fn visit(
+        &mut self,
+        typ: &AbiType,
+        path: Vec<Accessor>,
+        guards: &Guards,
+        witnesses: &mut impl Iterator<Item = Witness>,
+    ) {
+        match typ {
+            AbiType::Field | AbiType::Boolean | AbiType::Integer { .. } => {
+                self.pack(witnesses, path, Element::Scalar(typ.clone()), guards);
+            }
+            AbiType::String { length } => {
+                self.check(&path, Collection::String, *length, guards);
+                for index in 0..*length {
+                    self.pack(witnesses, path.clone(), Element::Byte(index), guards);
+                }
+            }
+            AbiType::Array { length, typ } => {
+                self.check(&path, Collection::Array, *length, guards);
+                for index in 0..*length {
+                    self.visit(typ, child(&path, Accessor::Index(index)), guards, witnesses);
+                }
+            }
+            AbiType::Struct { fields, .. } => {
+                for (name, typ) in fields {
+                    self.visit(typ, child(&path, Accessor::Field(name.clone())), guards, witnesses);
+                }
+            }
+            AbiType::Tuple { fields } => {
+                for (index, typ) in fields.iter().enumerate() {
+                    let field = Accessor::Field(format!("_{index}"));
+                    self.visit(typ, child(&path, field), guards, witnesses);
+                }
+            }
+            AbiType::Vec { max_length, typ } => {
+                self.check(&path, Collection::Vec, *max_length, guards);
+                for index in 0..*max_length {
+                    let mut element_guards = guards.clone();
+                    element_guards.push((path.clone(), index));
+                    let element = child(&path, Accessor::Index(index));
+                    self.visit(typ, element, &element_guards, witnesses);
+                }
+                self.pack(witnesses, path, Element::Length, guards);
+                // The placeholder for the vector's `empty_value`
+                witnesses.by_ref().take(typ.field_count() as usize).for_each(drop);
+            }
+        }
+    }
+
+    fn pack(
+        &mut self,
+        witnesses: &mut impl Iterator<Item = Witness>,
+        path: Vec<Accessor>,
+        element: Element,
+        guards: &Guards,
+    ) {
+        let witness = witnesses.next().expect("ABI should have a witness for each field element");
+        let position = self.positions[&witness];
+        self.elements.push(PackedElement { position, path, element, guards: guards.clone() });
+    }
+
+    fn check(&mut self, path: &[Accessor], collection: Collection, length: u64, guards: &Guards) {
+        let path = path.to_vec();
+        self.checks.push(LengthCheck { path, collection, length, guards: guards.clone() });
+    }
+}
+
+fn child(path: &[Accessor], accessor: Accessor) -> Vec<Accessor> {
+    let mut path = path.to_vec();
+    path.push(accessor);
+    path
+}
+
+/// Returns the expression accessing the value at `path` from `root`, where `index` gives the
+/// expression accessing an element of an array.
+pub(crate) fn access(root: &str, path: &[Accessor], index: impl Fn(&str, u64) -> String) -> String {
+    path.iter().fold(root.to_string(), |access, accessor| match accessor {
+        Accessor::Field(name) => format!("{access}.{name}"),
+        Accessor::Index(position) => index(&access, *position),
+    })
+}
+
+/// The structs declared by a generated contract.
+#[derive(Default)]
+pub(crate) struct StructDeclarations {
+    /// The name of each struct along with the name and type of each of its fields
+    pub(crate) structs: Vec<(String, Vec<(String, String)>)>,
+}
+
+impl StructDeclarations {
+    /// Returns the name of the struct declared with `fields`, declaring a new struct if needed.
+    ///
+    /// Structs with the same name but different fields, such as generic structs used with
+    /// different types, are each declared as a separate struct with a numbered name.
+    pub(crate) fn declare(&mut self, name: &str, fields: Vec<(String, String)>) -> String {
+        let mut same_name = self.structs.iter().filter(|(existing, _)| {
+            existing == name
+                || existing
+                    .strip_prefix(name)
+                    .map_or(false, |suffix| suffix.chars().all(|char| char.is_ascii_digit()))
+        });
+        if let Some((existing, _)) = same_name.clone().find(|(_, existing)| *existing == fields) {
+            return existing.clone();
+        }
+
+        let name = match same_name.count() {
+            0 => name.to_string(),
+            count => format!("{name}{count}"),
+        };
+        self.structs.push((name.clone(), fields));
+        name
+    }
+}
+
+/// Returns the name of the struct at `path` without its module.
+pub(crate) fn struct_name(path: &str) -> &str {
+    path.rsplit("::").next().unwrap_or(path)
+}
+
+/// The first line of every generated source.
+pub(crate) const GENERATED_HEADER: &str =
+    "// This file was generated from the program's ABI. Do not edit.";
+
+/// The syntax of a language which the sources of verifiers are generated in.
+pub(crate) trait Language {
+    /// The name of the field holding the program's return value in the inputs struct.
+    const RETURN_VALUE_NAME: &'static str;
+
+    /// Returns the expression accessing the element at `index` of the array or vector `access`.
+    fn index(access: &str, index: u64) -> String;
+
+    /// Returns the expression for the length of the string, array or vector `access`.
+    fn length(access: &str) -> String;
+
+    /// Returns the type of a field, boolean or integer value of `typ`.
+    fn scalar_type(typ: &AbiType) -> String;
+
+    /// Returns the type of a string of `length` bytes.
+    fn string_type(length: u64) -> String;
+
+    /// Returns the type of an array of `length` values of type `element`, or of a vector of them
+    /// if `length` is `None`.
+    fn collection_type(element: String, length: Option<u64>) -> String;
+
+    /// Writes the declaration of the struct `name` with `fields`, given by name and type.
+    fn write_struct(source: &mut String, name: &str, fields: &[(String, String)]);
+}
+
+/// Returns the layout of the public inputs of `abi`, after writing the declarations of the inputs
+/// struct named `Inputs` and of the structs its fields require to `source`.
+pub(crate) fn write_inputs_struct<L: Language>(
+    abi: &Abi,
+    source: &mut String,
+) -> PublicInputLayout {
+    let layout = abi.public_input_layout(L::RETURN_VALUE_NAME);
+    let mut structs = StructDeclarations::default();
+    let inputs =
+        vecmap(&layout.inputs, |(name, typ)| (name.clone(), type_of::<L>(typ, &mut structs)));
+
+    for (name, fields) in &structs.structs {
+        L::write_struct(source, name, fields);
+    }
+    L::write_struct(source, "Inputs", &inputs);
+    layout
+}
+
+/// Returns the expression accessing the value at `path` from the inputs struct, named `inputs`.
+pub(crate) fn access_input<L: Language>(path: &[Accessor]) -> String {
+    access("inputs", path, L::index)
+}
+
+/// Returns the condition that each vector in `guards` is long enough for them to hold a value.
+pub(crate) fn guard_conditions<L: Language>(guards: &Guards) -> Vec<String> {
+    vecmap(guards, |(path, index)| format!("{} > {index}", L::length(&access_input::<L>(path))))
+}
+
+/// Returns the number of bits of the smallest integer type which can hold an integer of `width`
+/// bits, for languages whose integer types have a power of two bits starting from 8.
+pub(crate) fn power_of_two_integer_bits(width: u32) -> u32 {
+    width.max(8).next_power_of_two()
+}
+
+/// Returns the type of a value of `typ` in language `L`, declaring any structs it requires.
+fn type_of<L: Language>(typ: &AbiType, structs: &mut StructDeclarations) -> String {
+    match typ {
+        AbiType::Field | AbiType::Boolean | AbiType::Integer { .. } => L::scalar_type(typ),
+        AbiType::String { length } => L::string_type(*length),
+        AbiType::Array { length, typ } => {
+            L::collection_type(type_of::<L>(typ, structs), Some(*length))
+        }
+        AbiType::Vec { typ, .. } => L::collection_type(type_of::<L>(typ, structs), None),
+        AbiType::Struct { path, fields } => {
+            let fields = vecmap(fields, |(name, typ)| (name.clone(), type_of::<L>(typ, structs)));
+            structs.declare(struct_name(path), fields)
+        }
+        AbiType::Tuple { fields } => {
+            let fields = fields
+                .iter()
+                .enumerate()
+                .map(|(index, typ)| (format!("_{index}"), type_of::<L>(typ, structs)))
+                .collect();
+            structs.declare("Tuple", fields)
+        }
+    }
+}
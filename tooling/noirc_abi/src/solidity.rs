@@ -0,0 +1,215 @@
+//! Generates Solidity libraries which encode the inputs of a program into the public inputs
+//! expected by its verifier contract.
+//!
+//! The library declares the program's inputs struct, with fixed size arrays for the program's
+//! arrays so that only the lengths of strings and vectors need to be checked by `encode`. Proofs
+//! are verified by the verifier contract written by the backend rather than by the library.
+use std::fmt::Write;
+
+use crate::{
+    public_inputs::{
+        access_input, guard_conditions, write_inputs_struct, Collection, Element, Guards, Language,
+        PackedElement, GENERATED_HEADER,
+    },
+    Abi, AbiType, Sign,
+};
+
+struct Solidity;
+
+impl Language for Solidity {
+    const RETURN_VALUE_NAME: &'static str = "returnValue";
+
+    fn index(access: &str, index: u64) -> String {
+        format!("{access}[{index}]")
+    }
+
+    fn length(access: &str) -> String {
+        format!("{access}.length")
+    }
+
+    fn scalar_type(typ: &AbiType) -> String {
+        match typ {
+            AbiType::Boolean => "bool".to_string(),
+            AbiType::Integer { sign: Sign::Unsigned, width } => {
+                format!("uint{}", solidity_integer_bits(*width))
+            }
+            AbiType::Integer { sign: Sign::Signed, width } => {
+                format!("int{}", solidity_integer_bits(*width))
+            }
+            _ => "uint256".to_string(),
+        }
+    }
+
+    fn string_type(_length: u64) -> String {
+        "bytes".to_string()
+    }
+
+    fn collection_type(element: String, length: Option<u64>) -> String {
+        match length {
+            Some(length) => format!("{element}[{length}]"),
+            None => format!("{element}[]"),
+        }
+    }
+
+    // Structs are declared inside the library.
+    fn write_struct(source: &mut String, name: &str, fields: &[(String, String)]) {
+        writeln!(source, "    struct {name} {{").unwrap();
+        for (field_name, typ) in fields {
+            writeln!(source, "        {typ} {field_name};").unwrap();
+        }
+        writeln!(source, "    }}").unwrap();
+        writeln!(source).unwrap();
+    }
+}
+
+impl Abi {
+    /// Returns the source of a Solidity library named `library_name` which encodes the public
+    /// parameters and return value of the program into the public inputs of its verifier.
+    pub fn to_solidity_encoder(&self, library_name: &str) -> String {
+        let mut source = String::new();
+        writeln!(source, "// SPDX-License-Identifier: MIT").unwrap();
+        writeln!(source, "{GENERATED_HEADER}").unwrap();
+        writeln!(source, "pragma solidity >=0.8.4;").unwrap();
+        writeln!(source).unwrap();
+        writeln!(source, "library {library_name} {{").unwrap();
+        let layout = write_inputs_struct::<Solidity>(self, &mut source);
+
+        writeln!(
+            source,
+            "    function encode(Inputs memory inputs) internal pure returns (bytes32[] memory) {{"
+        )
+        .unwrap();
+        writeln!(
+            source,
+            "        bytes32[] memory publicInputs = new bytes32[]({});",
+            layout.num_public_inputs
+        )
+        .unwrap();
+        for check in &layout.checks {
+            let access = access_input::<Solidity>(&check.path);
+            let statement = match check.collection {
+                // Arrays are given a fixed size in the inputs struct.
+                Collection::Array => continue,
+                Collection::String => {
+                    let message = format!("Expected {access} to have a length of {}", check.length);
+                    format!("require({access}.length == {}, \"{message}\");", check.length)
+                }
+                Collection::Vec => {
+                    let message =
+                        format!("Expected {access} to have at most {} values", check.length);
+                    format!("require({access}.length <= {}, \"{message}\");", check.length)
+                }
+            };
+            writeln!(source, "        {}", guarded(&check.guards, statement)).unwrap();
+        }
+        for element in &layout.elements {
+            let statement =
+                format!("publicInputs[{}] = {};", element.position, encode_element(element));
+            writeln!(source, "        {}", guarded(&element.guards, statement)).unwrap();
+        }
+        writeln!(source, "        return publicInputs;").unwrap();
+        writeln!(source, "    }}").unwrap();
+        writeln!(source, "}}").unwrap();
+        source
+    }
+}
+
+/// Returns `statement`, only run if each vector in `guards` is long enough.
+fn guarded(guards: &Guards, statement: String) -> String {
+    if guards.is_empty() {
+        return statement;
+    }
+    format!("if ({}) {statement}", guard_conditions::<Solidity>(guards).join(" && "))
+}
+
+/// Returns the `bytes32` encoding of `element`.
+fn encode_element(element: &PackedElement) -> String {
+    let access = access_input::<Solidity>(&element.path);
+    match &element.element {
+        Element::Scalar(AbiType::Boolean) => format!("bytes32(uint256({access} ? 1 : 0))"),
+        Element::Scalar(AbiType::Integer { sign: Sign::Unsigned, .. }) => {
+            format!("bytes32(uint256({access}))")
+        }
+        Element::Scalar(AbiType::Integer { sign: Sign::Signed, width }) => {
+            // Signed integers are encoded as their two's complement in `width` bits.
+            let bits = solidity_integer_bits(*width);
+            let mut value = format!("uint256(uint{bits}({access}))");
+            if bits != *width {
+                value = format!("({value} & ((1 << {width}) - 1))");
+            }
+            format!("bytes32({value})")
+        }
+        Element::Scalar(_) => format!("bytes32({access})"),
+        Element::Byte(index) => format!("bytes32(uint256(uint8({access}[{index}])))"),
+        Element::Length => format!("bytes32({access}.length)"),
+    }
+}
+
+/// Returns the number of bits of the smallest Solidity integer type which can hold an integer
+/// of `width` bits.
+fn solidity_integer_bits(width: u32) -> u32 {
+    (width.max(1) + 7) / 8 * 8
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use acvm::acir::native_types::Witness;
+
+    use crate::{Abi, AbiParameter, AbiReturnType, AbiType, AbiVisibility, Sign};
+
+    #[test]
+    fn encodes_public_inputs_in_witness_order() {
+        let point = AbiType::Struct {
+            path: "foo::Point".to_string(),
+            fields: vec![
+                ("x".to_string(), AbiType::Field),
+                ("y".to_string(), AbiType::Integer { sign: Sign::Signed, width: 8 }),
+            ],
+        };
+        let abi = Abi {
+            parameters: vec![
+                AbiParameter {
+                    name: "secret".to_string(),
+                    typ: AbiType::Field,
+                    visibility: AbiVisibility::Private,
+                },
+                AbiParameter {
+                    name: "points".to_string(),
+                    typ: AbiType::Array { length: 2, typ: Box::new(point) },
+                    visibility: AbiVisibility::Public,
+                },
+                AbiParameter {
+                    name: "flag".to_string(),
+                    typ: AbiType::Boolean,
+                    visibility: AbiVisibility::Public,
+                },
+            ],
+            // Note that `flag` is assigned a witness before `points`
+            param_witnesses: BTreeMap::from([
+                ("secret".to_string(), vec![(Witness(1)..Witness(2))]),
+                ("points".to_string(), vec![(Witness(3)..Witness(7))]),
+                ("flag".to_string(), vec![(Witness(2)..Witness(3))]),
+            ]),
+            return_type: Some(AbiReturnType {
+                abi_type: AbiType::Field,
+                visibility: AbiVisibility::Public,
+            }),
+            // The return value shares a witness with `flag`
+            return_witnesses: vec![Witness(2)],
+        };
+
+        let source = abi.to_solidity_encoder("MainInputs");
+
+        assert!(source.contains("library MainInputs {"));
+        assert!(source.contains("        uint256 x;\n        int8 y;\n"));
+        assert!(source.contains("        Point[2] points;\n        bool flag;\n"));
+        assert!(source.contains("new bytes32[](5);"));
+        assert!(source.contains("publicInputs[0] = bytes32(uint256(inputs.flag ? 1 : 0));"));
+        assert!(source.contains("publicInputs[1] = bytes32(inputs.points[0].x);"));
+        assert!(source.contains("publicInputs[4] = bytes32(uint256(uint8(inputs.points[1].y)));"));
+        assert!(source.contains("publicInputs[0] = bytes32(inputs.returnValue);"));
+        assert!(!source.contains("secret"));
+    }
+}
@@ -0,0 +1,353 @@
+//! Generates TypeScript bindings for the inputs and return value of a program.
+//!
+//! The bindings are made up of type declarations describing the shape of each parameter and of
+//! the return value, along with a JavaScript module implementing helpers which convert between
+//! these types and the `InputMap` taken and returned by `@noir-lang/noirc_abi`.
+//!
+//! The helpers interpret the program's ABI types at runtime, so the module embeds the ABI types
+//! of each parameter. Integers are converted to a `number` when they can be represented exactly
+//! by one and to a `bigint` otherwise.
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::{Abi, AbiType, Sign, MAIN_RETURN_NAME};
+
+/// The widest integers which are converted to a `number` rather than a `bigint`.
+///
+/// `Number.MAX_SAFE_INTEGER == 2**53 - 1`, so 52 bits leaves room for the sign of a signed
+/// integer. This matches the cutoff used by `noir_codegen`.
+const MAX_NUMBER_WIDTH: u32 = 52;
+
+const PRELUDE: &str = "/* Autogenerated file, do not edit! */\n\n/* eslint-disable */\n";
+
+/// The TypeScript bindings of a program, see the [module documentation][self].
+pub struct TypeScriptBindings {
+    /// The contents of the `.d.ts` file declaring the types of the program's inputs
+    pub declarations: String,
+    /// The contents of the `.js` module implementing the helpers declared in `declarations`
+    pub helpers: String,
+}
+
+impl Abi {
+    /// Returns TypeScript bindings for the parameters and return value of the program.
+    pub fn to_typescript_bindings(&self) -> TypeScriptBindings {
+        let mut declarations = TypeDeclarations::default();
+        let inputs = self
+            .parameters
+            .iter()
+            .map(|param| (param.name.clone(), declarations.typescript_type(&param.typ)))
+            .collect::<Vec<_>>();
+        let return_type = self
+            .return_type
+            .as_ref()
+            .map(|return_type| declarations.typescript_type(&return_type.abi_type));
+
+        let mut source = String::from(PRELUDE);
+        writeln!(source).unwrap();
+        writeln!(source, "import {{ InputMap, InputValue }} from '@noir-lang/noirc_abi';").unwrap();
+        writeln!(source).unwrap();
+        for (alias, typ) in &declarations.aliases {
+            writeln!(source, "export type {alias} = {typ};").unwrap();
+        }
+        writeln!(source).unwrap();
+        for (name, fields) in &declarations.structs {
+            write_interface(&mut source, name, fields);
+        }
+        write_interface(&mut source, "Inputs", &inputs);
+
+        writeln!(source, "/** Converts the inputs of the program into an `InputMap`. */").unwrap();
+        writeln!(source, "export declare function encodeInputs(inputs: Inputs): InputMap;")
+            .unwrap();
+        writeln!(source, "/** Converts an `InputMap` into the inputs of the program. */").unwrap();
+        writeln!(source, "export declare function decodeInputs(inputMap: InputMap): Inputs;")
+            .unwrap();
+        if let Some(return_type) = return_type {
+            writeln!(source).unwrap();
+            writeln!(source, "export type ReturnValue = {return_type};").unwrap();
+            writeln!(source).unwrap();
+            writeln!(
+                source,
+                "/** Converts the return value of the program into an `InputValue`. */"
+            )
+            .unwrap();
+            writeln!(
+                source,
+                "export declare function encodeReturnValue(value: ReturnValue): InputValue;"
+            )
+            .unwrap();
+            writeln!(
+                source,
+                "/** Converts an `InputValue` into the return value of the program. */"
+            )
+            .unwrap();
+            writeln!(
+                source,
+                "export declare function decodeReturnValue(value: InputValue): ReturnValue;"
+            )
+            .unwrap();
+        }
+
+        TypeScriptBindings { declarations: source, helpers: self.typescript_helpers() }
+    }
+
+    fn typescript_helpers(&self) -> String {
+        let parameters: BTreeMap<&str, &AbiType> =
+            self.parameters.iter().map(|param| (param.name.as_str(), &param.typ)).collect();
+
+        let mut source = String::from(PRELUDE);
+        writeln!(source).unwrap();
+        writeln!(source, "const MAX_NUMBER_WIDTH = {MAX_NUMBER_WIDTH};").unwrap();
+        writeln!(
+            source,
+            "const PARAMETERS = {};",
+            serde_json::to_string(&parameters).expect("ABI types should serialize to JSON")
+        )
+        .unwrap();
+        if let Some(return_type) = &self.return_type {
+            writeln!(
+                source,
+                "const RETURN_TYPE = {};",
+                serde_json::to_string(&return_type.abi_type)
+                    .expect("ABI types should serialize to JSON")
+            )
+            .unwrap();
+        }
+        source.push_str(HELPERS);
+
+        if self.return_type.is_some() {
+            writeln!(source).unwrap();
+            writeln!(source, "export function encodeReturnValue(value) {{").unwrap();
+            writeln!(source, "  return encodeValue(RETURN_TYPE, value, '{MAIN_RETURN_NAME}');")
+                .unwrap();
+            writeln!(source, "}}").unwrap();
+            writeln!(source).unwrap();
+            writeln!(source, "export function decodeReturnValue(value) {{").unwrap();
+            writeln!(source, "  return decodeValue(RETURN_TYPE, value, '{MAIN_RETURN_NAME}');")
+                .unwrap();
+            writeln!(source, "}}").unwrap();
+        }
+        source
+    }
+}
+
+/// The conversions between values of each ABI type and `InputValue`s, following the same
+/// rules as the `InputMap` parser of `noirc_abi`.
+const HELPERS: &str = r#"
+function checkLength(value, length, name) {
+  if (value.length !== length) {
+    throw new Error(`Expected ${name} to have a length of ${length} but found ${value.length}`);
+  }
+}
+
+function encodeValue(type, value, name) {
+  switch (type.kind) {
+    case 'field':
+    case 'boolean':
+      return value;
+    case 'integer':
+      // Negative integers are written as decimal strings, which are parsed as two's complement.
+      return value.toString();
+    case 'string':
+      checkLength(value, type.length, name);
+      return value;
+    case 'array':
+      checkLength(value, type.length, name);
+      return value.map((element, index) => encodeValue(type.type, element, `${name}[${index}]`));
+    case 'vec':
+      if (value.length > type.max_length) {
+        throw new Error(`Expected ${name} to have at most ${type.max_length} values`);
+      }
+      return value.map((element, index) => encodeValue(type.type, element, `${name}[${index}]`));
+    case 'tuple':
+      checkLength(value, type.fields.length, name);
+      return type.fields.map((field, index) =>
+        encodeValue(field, value[index], `${name}.${index}`),
+      );
+    case 'struct':
+      return Object.fromEntries(
+        type.fields.map((field) => [
+          field.name,
+          encodeValue(field.type, value[field.name], `${name}.${field.name}`),
+        ]),
+      );
+    default:
+      throw new Error(`Unknown ABI type ${JSON.stringify(type)}`);
+  }
+}
+
+function decodeValue(type, value, name) {
+  switch (type.kind) {
+    case 'field':
+      return typeof value === 'string' ? value : `0x${BigInt(value).toString(16)}`;
+    case 'boolean':
+      return typeof value === 'boolean' ? value : BigInt(value) === 1n;
+    case 'integer': {
+      let integer = BigInt(value);
+      if (type.sign === 'signed' && integer >= 1n << BigInt(type.width - 1)) {
+        integer -= 1n << BigInt(type.width);
+      }
+      return type.width <= MAX_NUMBER_WIDTH ? Number(integer) : integer;
+    }
+    case 'string':
+      return value;
+    case 'array':
+      checkLength(value, type.length, name);
+      return value.map((element, index) => decodeValue(type.type, element, `${name}[${index}]`));
+    case 'vec':
+      return value.map((element, index) => decodeValue(type.type, element, `${name}[${index}]`));
+    case 'tuple':
+      checkLength(value, type.fields.length, name);
+      return type.fields.map((field, index) =>
+        decodeValue(field, value[index], `${name}.${index}`),
+      );
+    case 'struct':
+      return Object.fromEntries(
+        type.fields.map((field) => [
+          field.name,
+          decodeValue(field.type, value[field.name], `${name}.${field.name}`),
+        ]),
+      );
+    default:
+      throw new Error(`Unknown ABI type ${JSON.stringify(type)}`);
+  }
+}
+
+export function encodeInputs(inputs) {
+  return Object.fromEntries(
+    Object.entries(PARAMETERS).map(([name, type]) => [name, encodeValue(type, inputs[name], name)]),
+  );
+}
+
+export function decodeInputs(inputMap) {
+  return Object.fromEntries(
+    Object.entries(PARAMETERS).map(([name, type]) => [
+      name,
+      decodeValue(type, inputMap[name], name),
+    ]),
+  );
+}
+"#;
+
+#[derive(Default)]
+struct TypeDeclarations {
+    /// The TypeScript type of each Noir type with no direct equivalent in TypeScript
+    aliases: BTreeMap<String, &'static str>,
+    /// The name and fields of each struct, in the order they were found
+    structs: Vec<(String, Vec<(String, String)>)>,
+}
+
+impl TypeDeclarations {
+    /// Returns the TypeScript type of values of `typ`, declaring any types it requires.
+    fn typescript_type(&mut self, typ: &AbiType) -> String {
+        match typ {
+            AbiType::Field => {
+                self.aliases.insert("Field".to_string(), "string");
+                "Field".to_string()
+            }
+            AbiType::Integer { sign, width } => {
+                let alias = match sign {
+                    Sign::Unsigned => format!("u{width}"),
+                    Sign::Signed => format!("i{width}"),
+                };
+                let typ = if *width <= MAX_NUMBER_WIDTH { "number" } else { "bigint" };
+                self.aliases.insert(alias.clone(), typ);
+                alias
+            }
+            AbiType::Boolean => "boolean".to_string(),
+            AbiType::String { .. } => "string".to_string(),
+            AbiType::Array { typ, .. } | AbiType::Vec { typ, .. } => {
+                format!("{}[]", self.typescript_type(typ))
+            }
+            AbiType::Tuple { fields } => {
+                let fields: Vec<_> =
+                    fields.iter().map(|field| self.typescript_type(field)).collect();
+                format!("[{}]", fields.join(", "))
+            }
+            AbiType::Struct { path, fields } => {
+                // Structs are named after the last component of their path, as in `noir_codegen`.
+                let name = path.rsplit("::").next().unwrap_or(path).to_string();
+                if !self.structs.iter().any(|(existing, _)| *existing == name) {
+                    let fields = fields
+                        .iter()
+                        .map(|(field_name, typ)| (field_name.clone(), self.typescript_type(typ)))
+                        .collect();
+                    self.structs.push((name.clone(), fields));
+                }
+                name
+            }
+        }
+    }
+}
+
+fn write_interface(source: &mut String, name: &str, fields: &[(String, String)]) {
+    writeln!(source, "export interface {name} {{").unwrap();
+    for (field_name, typ) in fields {
+        writeln!(source, "  {field_name}: {typ};").unwrap();
+    }
+    writeln!(source, "}}").unwrap();
+    writeln!(source).unwrap();
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use acvm::acir::native_types::Witness;
+
+    use crate::{Abi, AbiParameter, AbiReturnType, AbiType, AbiVisibility, Sign};
+
+    #[test]
+    fn declares_types_of_inputs() {
+        let point = AbiType::Struct {
+            path: "foo::Point".to_string(),
+            fields: vec![
+                ("x".to_string(), AbiType::Field),
+                ("y".to_string(), AbiType::Integer { sign: Sign::Signed, width: 8 }),
+            ],
+        };
+        let abi = Abi {
+            parameters: vec![
+                AbiParameter {
+                    name: "points".to_string(),
+                    typ: AbiType::Array { length: 2, typ: Box::new(point) },
+                    visibility: AbiVisibility::Private,
+                },
+                AbiParameter {
+                    name: "pair".to_string(),
+                    typ: AbiType::Tuple {
+                        fields: vec![
+                            AbiType::Boolean,
+                            AbiType::Integer { sign: Sign::Unsigned, width: 64 },
+                        ],
+                    },
+                    visibility: AbiVisibility::Public,
+                },
+            ],
+            param_witnesses: BTreeMap::from([
+                ("points".to_string(), vec![(Witness(1)..Witness(5))]),
+                ("pair".to_string(), vec![(Witness(5)..Witness(7))]),
+            ]),
+            return_type: Some(AbiReturnType {
+                abi_type: AbiType::String { length: 5 },
+                visibility: AbiVisibility::Public,
+            }),
+            return_witnesses: vec![Witness(7)],
+        };
+
+        let bindings = abi.to_typescript_bindings();
+        let declarations = bindings.declarations;
+
+        assert!(declarations.contains("export type Field = string;"));
+        assert!(declarations.contains("export type i8 = number;"));
+        assert!(declarations.contains("export type u64 = bigint;"));
+        assert!(declarations.contains("export interface Point {\n  x: Field;\n  y: i8;\n}"));
+        assert!(declarations
+            .contains("export interface Inputs {\n  points: Point[];\n  pair: [boolean, u64];\n}"));
+        assert!(declarations.contains("export type ReturnValue = string;"));
+        assert!(declarations.contains("export declare function decodeReturnValue("));
+
+        assert!(bindings.helpers.contains(r#"const RETURN_TYPE = {"kind":"string","length":5};"#));
+        assert!(bindings.helpers.contains("export function encodeInputs(inputs)"));
+        assert!(bindings.helpers.contains("export function decodeReturnValue(value)"));
+    }
+}
@@ -55,6 +55,7 @@ export type AbiType =
     { kind: "string", length: number } |
     { kind: "integer", sign: Sign, width: number } |
     { kind: "array", length: number, type: AbiType } |
+    { kind: "vec", max_length: number, type: AbiType } |
     { kind: "tuple", fields: AbiType[] } |
     { kind: "struct", path: string, fields: { name: string, type: AbiType }[] };
 